@@ -0,0 +1,340 @@
+use crate::mp3_info::Mp3Info;
+use downcast_rs::{impl_downcast, Downcast};
+use generational_arena::{Arena, Index};
+use std::sync::Arc;
+
+pub mod swf {
+    pub use swf::{
+        read, AudioCompression, CharacterId, Sound, SoundEnvelope, SoundEnvelopePoint, SoundEvent,
+        SoundFormat, SoundInfo, SoundStreamHead,
+    };
+}
+
+pub type SoundHandle = Index;
+pub type SoundInstanceHandle = Index;
+pub type PreloadStreamHandle = u32;
+
+type Error = Box<dyn std::error::Error>;
+
+pub trait AudioBackend: Downcast {
+    fn play(&mut self);
+    fn pause(&mut self);
+    fn register_sound(&mut self, swf_sound: &swf::Sound) -> Result<SoundHandle, Error>;
+
+    /// Used by the web backend to pre-decode sound streams.
+    /// Returns the sound handle to be used to add data to the stream.
+    /// Other backends return `None`.
+    /// TODO: Get rid of the preload_* methods when web backend has a better way
+    /// of decoding audio on the fly.
+    fn preload_sound_stream_head(
+        &mut self,
+        _stream_info: &swf::SoundStreamHead,
+    ) -> Option<PreloadStreamHandle> {
+        None
+    }
+
+    /// Used by the web backend to add data to a currently preloading sound stream.
+    fn preload_sound_stream_block(
+        &mut self,
+        _stream: PreloadStreamHandle,
+        _clip_frame: u16,
+        _audio_data: &[u8],
+    ) {
+    }
+
+    /// Used by the web backend to finalize and decode a sound stream.
+    /// Returns true if this was a valid stream.
+    fn preload_sound_stream_end(&mut self, _stream: PreloadStreamHandle) -> Option<SoundHandle> {
+        None
+    }
+
+    /// Plays a sound.
+    fn start_sound(
+        &mut self,
+        sound: SoundHandle,
+        settings: &swf::SoundInfo,
+    ) -> Result<SoundInstanceHandle, Error>;
+
+    /// Starts playing a "stream" sound, which is an audio stream that is distributed
+    /// among the frames of a Flash MovieClip.
+    /// On the web backend, `stream_handle` should be the handle for the preloaded stream.
+    /// Other backends can pass `None`.
+    fn start_stream(
+        &mut self,
+        stream_handle: Option<SoundHandle>,
+        clip_frame: u16,
+        clip_data: StreamData,
+        handle: &swf::SoundStreamHead,
+    ) -> Result<SoundInstanceHandle, Error>;
+
+    /// Stops a playing sound instance.
+    /// No-op if the sound is not playing.
+    fn stop_sound(&mut self, sound: SoundInstanceHandle);
+
+    /// Good ol' stopAllSounds() :-)
+    fn stop_all_sounds(&mut self);
+
+    /// Get the position of a sound instance in milliseconds.
+    /// Returns `None` if ther sound is not/no longer playing
+    fn get_sound_position(&self, instance: SoundInstanceHandle) -> Option<u32>;
+
+    /// Get the duration of a sound in milliseconds.
+    /// Returns `None` if sound is not registered.
+    fn get_sound_duration(&self, sound: SoundHandle) -> Option<f64>;
+
+    /// Get the size of the data stored within a given sound.
+    ///
+    /// This is specifically measured in compressed bytes.
+    fn get_sound_size(&self, sound: SoundHandle) -> Option<u32>;
+
+    /// Get the sound format that a given sound was added with.
+    fn get_sound_format(&self, sound: SoundHandle) -> Option<&swf::SoundFormat>;
+
+    /// Set the volume transform for a sound instance.
+    fn set_sound_transform(&mut self, instance: SoundInstanceHandle, transform: SoundTransform);
+
+    // TODO: Eventually remove this/move it to library.
+    fn is_loading_complete(&self) -> bool {
+        true
+    }
+
+    /// Allows the audio backend to update.
+    ///
+    /// Runs once per event loop iteration.
+    fn tick(&mut self) {}
+
+    /// Inform the audio backend of the current stage frame rate.
+    ///
+    /// This is only necessary if your particular audio backend needs to know
+    /// what the stage frame rate is. Otherwise, you are free to avoid
+    /// implementing it.
+    fn set_frame_rate(&mut self, _frame_rate: f64) {}
+}
+
+impl_downcast!(AudioBackend);
+
+/// Information about a sound provided to `NullAudioBackend`.
+struct NullSound {
+    /// The duration of the sound in milliseconds.
+    duration: f64,
+
+    /// The compressed size of the sound data, excluding MP3 latency seek data.
+    size: u32,
+
+    /// The stated format of the sound data.
+    format: swf::SoundFormat,
+}
+
+/// Audio backend that ignores all audio.
+pub struct NullAudioBackend {
+    sounds: Arena<NullSound>,
+}
+
+impl NullAudioBackend {
+    pub fn new() -> NullAudioBackend {
+        NullAudioBackend {
+            sounds: Arena::new(),
+        }
+    }
+}
+
+impl AudioBackend for NullAudioBackend {
+    fn play(&mut self) {}
+    fn pause(&mut self) {}
+    fn register_sound(&mut self, sound: &swf::Sound) -> Result<SoundHandle, Error> {
+        // Slice off latency seek for MP3 data.
+        let data = if sound.format.compression == swf::AudioCompression::Mp3 {
+            &sound.data[2..]
+        } else {
+            sound.data
+        };
+
+        // AS duration does not subtract `skip_sample_frames`.
+        let mut num_sample_frames: f64 = sound.num_samples.into();
+        if sound.num_samples == 0 && sound.format.compression == swf::AudioCompression::Mp3 {
+            if let Some(n) = Mp3Info::parse(data).and_then(|i| i.num_sample_frames()) {
+                num_sample_frames = n.into();
+            }
+        }
+        let sample_rate: f64 = sound.format.sample_rate.into();
+        let duration = num_sample_frames * 1000.0 / sample_rate;
+
+        Ok(self.sounds.insert(NullSound {
+            duration,
+            size: data.len() as u32,
+            format: sound.format.clone(),
+        }))
+    }
+
+    fn start_sound(
+        &mut self,
+        _sound: SoundHandle,
+        _sound_info: &swf::SoundInfo,
+    ) -> Result<SoundInstanceHandle, Error> {
+        Ok(SoundInstanceHandle::from_raw_parts(0, 0))
+    }
+
+    fn start_stream(
+        &mut self,
+        _stream_handle: Option<SoundHandle>,
+        _clip_frame: u16,
+        _clip_data: StreamData,
+        _handle: &swf::SoundStreamHead,
+    ) -> Result<SoundInstanceHandle, Error> {
+        Ok(SoundInstanceHandle::from_raw_parts(0, 0))
+    }
+
+    fn stop_sound(&mut self, _sound: SoundInstanceHandle) {}
+
+    fn stop_all_sounds(&mut self) {}
+    fn get_sound_position(&self, _instance: SoundInstanceHandle) -> Option<u32> {
+        Some(0)
+    }
+    fn get_sound_duration(&self, sound: SoundHandle) -> Option<f64> {
+        if let Some(sound) = self.sounds.get(sound) {
+            Some(sound.duration)
+        } else {
+            None
+        }
+    }
+    fn get_sound_size(&self, sound: SoundHandle) -> Option<u32> {
+        if let Some(sound) = self.sounds.get(sound) {
+            Some(sound.size)
+        } else {
+            None
+        }
+    }
+
+    fn get_sound_format(&self, sound: SoundHandle) -> Option<&swf::SoundFormat> {
+        self.sounds.get(sound).map(|s| &s.format)
+    }
+
+    fn set_sound_transform(&mut self, _instance: SoundInstanceHandle, _transform: SoundTransform) {}
+}
+
+impl Default for NullAudioBackend {
+    fn default() -> Self {
+        NullAudioBackend::new()
+    }
+}
+
+/// A sound transform for a playing sound, for use by audio backends.
+/// This differs from `display_object::SoundTransform` by being
+/// already converted to `f32` and having `volume` baked in.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SoundTransform {
+    pub left_to_left: f32,
+    pub left_to_right: f32,
+    pub right_to_left: f32,
+    pub right_to_right: f32,
+}
+
+impl Default for SoundTransform {
+    fn default() -> Self {
+        Self {
+            left_to_left: 1.0,
+            left_to_right: 0.0,
+            right_to_left: 0.0,
+            right_to_right: 1.0,
+        }
+    }
+}
+
+/// The data of a SWF movie, as needed to decode its stream sounds.
+///
+/// `ruffle_core` implements this for `SwfMovie`.
+pub trait SwfData: Send + Sync {
+    /// The uncompressed SWF data, starting after the header.
+    fn data(&self) -> &[u8];
+
+    /// The SWF version of the movie.
+    fn version(&self) -> u8;
+}
+
+/// A shared-ownership reference to the tag data of a MovieClip that contains
+/// a stream sound.
+///
+/// Stream sounds are split across the `SoundStreamBlock` tags of the clip, so
+/// backends read through this data to find them as the clip plays.
+#[derive(Clone)]
+pub struct StreamData {
+    movie: Arc<dyn SwfData>,
+    start: usize,
+    end: usize,
+}
+
+impl StreamData {
+    /// Creates stream data for the given range of a movie's data.
+    pub fn new(movie: Arc<dyn SwfData>, start: usize, end: usize) -> Self {
+        Self { movie, start, end }
+    }
+
+    /// Creates an empty slice of the same movie.
+    pub fn to_empty(&self) -> Self {
+        Self {
+            movie: self.movie.clone(),
+            start: 0,
+            end: 0,
+        }
+    }
+
+    /// Construct new stream data from a regular slice.
+    ///
+    /// This function returns None if the given slice is not a subslice of the
+    /// current data.
+    pub fn to_subslice(&self, slice: &[u8]) -> Option<Self> {
+        let self_pval = self.movie.data().as_ptr() as usize;
+        let slice_pval = slice.as_ptr() as usize;
+
+        if (self_pval + self.start) <= slice_pval && slice_pval < (self_pval + self.end) {
+            Some(Self {
+                movie: self.movie.clone(),
+                start: slice_pval - self_pval,
+                end: (slice_pval - self_pval) + slice.len(),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// The SWF version of the movie this data belongs to.
+    pub fn version(&self) -> u8 {
+        self.movie.version()
+    }
+}
+
+impl AsRef<[u8]> for StreamData {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.movie.data()[self.start..self.end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestMovie(Vec<u8>);
+
+    impl SwfData for TestMovie {
+        fn data(&self) -> &[u8] {
+            &self.0
+        }
+
+        fn version(&self) -> u8 {
+            10
+        }
+    }
+
+    #[test]
+    fn stream_data_subslices() {
+        let data = StreamData::new(Arc::new(TestMovie(vec![1, 2, 3, 4, 5])), 1, 4);
+        assert_eq!(data.as_ref(), [2, 3, 4]);
+        assert_eq!(data.version(), 10);
+
+        let subslice = data.to_subslice(&data.as_ref()[1..]).unwrap();
+        assert_eq!(subslice.as_ref(), [3, 4]);
+        assert!(data.to_subslice(&[3, 4]).is_none());
+        assert!(data.to_empty().as_ref().is_empty());
+    }
+}
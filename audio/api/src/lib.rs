@@ -0,0 +1,11 @@
+//! The interface between Ruffle and its audio backends.
+//!
+//! This crate holds the `AudioBackend` trait along with the handle and stream
+//! types it uses, so that an audio backend can be written without depending
+//! on the rest of `ruffle_core`. `ruffle_core` re-exports all of it under
+//! `ruffle_core::backend::audio`.
+
+pub mod backend;
+pub mod mp3_info;
+
+pub use swf;
@@ -0,0 +1,423 @@
+//! MP3 stream metadata from frame headers and Xing/Info, LAME and VBRI tags.
+//!
+//! Encoders that write VBR streams (and LAME, for CBR streams) put an extra
+//! frame at the start of the stream describing the rest of it. That frame
+//! decodes to silence, so it has to be removed to get gapless loops. The LAME
+//! tag also records the encoder delay and padding, which tell us exactly
+//! which samples of the decoded stream are part of the original audio.
+
+/// The number of sample frames of delay added by the MP3 decoder itself, in
+/// addition to the encoder delay recorded in a LAME tag.
+const DECODER_DELAY: u32 = 529;
+
+/// The most main data that a frame can borrow from the frames before it.
+const MAX_BIT_RESERVOIR_BYTES: usize = 511;
+
+/// How many frames to search backwards for a frame to start decoding from
+/// when seeking.
+const MAX_SEEK_PREROLL_FRAMES: usize = 16;
+
+/// The header of a single MPEG-1/2/2.5 Layer III frame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameHeader {
+    pub sample_rate: u32,
+    pub num_channels: u8,
+    pub samples_per_frame: u32,
+
+    /// The length of the whole frame in bytes, including this header.
+    pub frame_len: usize,
+
+    /// Whether the header is followed by a 16-bit CRC.
+    has_crc: bool,
+
+    /// Whether this is an MPEG-1 frame, as opposed to MPEG-2 or MPEG-2.5.
+    is_mpeg1: bool,
+}
+
+impl FrameHeader {
+    /// Parse the frame header at the start of `data`.
+    ///
+    /// Returns `None` if `data` does not start with a valid Layer III frame
+    /// header.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        const MPEG1_BITRATES: [u32; 15] = [
+            0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+        ];
+        const MPEG2_BITRATES: [u32; 15] =
+            [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+        const SAMPLE_RATES: [u32; 3] = [44100, 48000, 32000];
+
+        let header = data.get(..4)?;
+        if header[0] != 0xFF || header[1] & 0xE0 != 0xE0 {
+            return None;
+        }
+
+        // Version: 0 = MPEG-2.5, 2 = MPEG-2, 3 = MPEG-1.
+        let version = (header[1] >> 3) & 0b11;
+        let layer = (header[1] >> 1) & 0b11;
+        if version == 1 || layer != 1 {
+            return None;
+        }
+        let is_mpeg1 = version == 3;
+
+        let bitrate_index = usize::from(header[2] >> 4);
+        let sample_rate_index = usize::from((header[2] >> 2) & 0b11);
+        if bitrate_index == 0 || bitrate_index == 15 || sample_rate_index == 3 {
+            // Free format streams can't be measured without decoding them.
+            return None;
+        }
+
+        let bitrate = if is_mpeg1 {
+            MPEG1_BITRATES[bitrate_index]
+        } else {
+            MPEG2_BITRATES[bitrate_index]
+        } * 1000;
+        let sample_rate = SAMPLE_RATES[sample_rate_index] >> (3 - version.max(1));
+        let samples_per_frame = if is_mpeg1 { 1152 } else { 576 };
+        let padding = u32::from((header[2] >> 1) & 1);
+        let frame_len = (samples_per_frame / 8 * bitrate / sample_rate + padding) as usize;
+
+        Some(Self {
+            sample_rate,
+            num_channels: if header[3] >> 6 == 0b11 { 1 } else { 2 },
+            samples_per_frame,
+            frame_len,
+            has_crc: header[1] & 1 == 0,
+            is_mpeg1,
+        })
+    }
+
+    /// The length of the side information that follows the header.
+    fn side_info_len(&self) -> usize {
+        match (self.is_mpeg1, self.num_channels) {
+            (true, 1) => 17,
+            (true, _) => 32,
+            (false, 1) => 9,
+            (false, _) => 17,
+        }
+    }
+
+    /// The offset of the side information from the start of the frame.
+    fn side_info_offset(&self) -> usize {
+        if self.has_crc {
+            6
+        } else {
+            4
+        }
+    }
+}
+
+/// The metadata in the Xing/Info or VBRI frame at the start of an MP3
+/// stream.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mp3Info {
+    /// The header of the frame that holds the metadata.
+    pub header: FrameHeader,
+
+    /// The number of audio frames in the stream, not counting the frame
+    /// holding the metadata.
+    pub num_frames: Option<u32>,
+
+    /// The number of bytes in the stream.
+    pub num_bytes: Option<u32>,
+
+    /// The number of sample frames of silence the encoder inserted at the
+    /// start of the stream.
+    pub encoder_delay: Option<u32>,
+
+    /// The number of sample frames of silence the encoder appended to fill
+    /// out the last frame.
+    pub encoder_padding: Option<u32>,
+}
+
+impl Mp3Info {
+    /// Parse the metadata frame at the start of `data`.
+    ///
+    /// Returns `None` if the stream doesn't start with one.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let header = FrameHeader::parse(data)?;
+        let frame = data.get(..header.frame_len)?;
+        let read_u32 = |pos: usize| -> Option<u32> {
+            let bytes = frame.get(pos..pos + 4)?;
+            Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        };
+
+        let xing_pos = header.side_info_offset() + header.side_info_len();
+        match frame.get(xing_pos..xing_pos + 4) {
+            Some(b"Xing") | Some(b"Info") => {
+                let flags = read_u32(xing_pos + 4)?;
+                let mut pos = xing_pos + 8;
+                let mut field = |flag: u32, len: usize| {
+                    if flags & flag != 0 {
+                        let value = read_u32(pos);
+                        pos += len;
+                        value
+                    } else {
+                        None
+                    }
+                };
+                let num_frames = field(0x1, 4);
+                let num_bytes = field(0x2, 4);
+                let _toc = field(0x4, 100);
+                let _quality = field(0x8, 4);
+
+                // The LAME extension follows the Xing fields, and stores the
+                // delay and padding as two 12-bit numbers.
+                let (encoder_delay, encoder_padding) = match frame.get(pos + 21..pos + 24) {
+                    Some(delay) if frame.get(pos..pos + 4) == Some(&b"LAME"[..]) => {
+                        let encoder_delay = (u32::from(delay[0]) << 4) | u32::from(delay[1] >> 4);
+                        let encoder_padding =
+                            (u32::from(delay[1] & 0x0F) << 8) | u32::from(delay[2]);
+                        (Some(encoder_delay), Some(encoder_padding))
+                    }
+                    _ => (None, None),
+                };
+
+                Some(Self {
+                    header,
+                    num_frames,
+                    num_bytes,
+                    encoder_delay,
+                    encoder_padding,
+                })
+            }
+            _ => {
+                // The VBRI header is always 32 bytes after the frame header.
+                let vbri_pos = 4 + 32;
+                if frame.get(vbri_pos..vbri_pos + 4) != Some(&b"VBRI"[..]) {
+                    return None;
+                }
+
+                Some(Self {
+                    header,
+                    num_frames: Some(read_u32(vbri_pos + 14)?),
+                    num_bytes: Some(read_u32(vbri_pos + 10)?),
+                    encoder_delay: None,
+                    encoder_padding: None,
+                })
+            }
+        }
+    }
+
+    /// The length of the metadata frame, which should be skipped when
+    /// decoding the stream.
+    pub fn header_frame_len(&self) -> usize {
+        self.header.frame_len
+    }
+
+    /// The number of sample frames to skip to reach the start of the audio,
+    /// if the encoder recorded its delay.
+    pub fn skip_sample_frames(&self) -> Option<u32> {
+        self.encoder_delay.map(|delay| delay + DECODER_DELAY)
+    }
+
+    /// The number of sample frames of audio in the stream, excluding the
+    /// encoder delay and padding.
+    pub fn num_sample_frames(&self) -> Option<u32> {
+        let num_frames = self.num_frames?;
+        let padding = self.encoder_delay.unwrap_or(0) + self.encoder_padding.unwrap_or(0);
+        Some(
+            num_frames
+                .saturating_mul(self.header.samples_per_frame)
+                .saturating_sub(padding),
+        )
+    }
+}
+
+/// The position of a frame within an MP3 stream, and how it uses the bit
+/// reservoir.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameEntry {
+    /// The offset of the frame from the start of the stream.
+    pub offset: usize,
+
+    /// How many bytes of main data this frame takes from previous frames.
+    main_data_begin: usize,
+
+    /// How many bytes of main data this frame contains.
+    main_data_len: usize,
+}
+
+/// A table of the positions of every frame in an MP3 stream.
+///
+/// Unlike the Xing table of contents, this is exact for both CBR and VBR
+/// streams, so it can be used for sample-accurate seeking.
+#[derive(Clone, Debug, Default)]
+pub struct SeekTable {
+    frames: Vec<FrameEntry>,
+    samples_per_frame: u32,
+}
+
+impl SeekTable {
+    /// Build the seek table for a stream by walking its frame headers.
+    ///
+    /// Any data between frames that isn't a valid frame header is skipped.
+    pub fn new(data: &[u8]) -> Self {
+        let mut frames = Vec::new();
+        let mut samples_per_frame = 0;
+        let mut pos = 0;
+        while pos + 4 <= data.len() {
+            let header = match FrameHeader::parse(&data[pos..]) {
+                Some(header) if header.frame_len > 0 => header,
+                _ => {
+                    pos += 1;
+                    continue;
+                }
+            };
+
+            let side_info = header.side_info_offset();
+            let main_data_begin = match (header.is_mpeg1, data.get(pos + side_info..)) {
+                (true, Some([a, b, ..])) => (usize::from(*a) << 1) | usize::from(b >> 7),
+                (false, Some([a, ..])) => usize::from(*a),
+                _ => break,
+            };
+            let main_data_len = header
+                .frame_len
+                .saturating_sub(side_info + header.side_info_len());
+
+            frames.push(FrameEntry {
+                offset: pos,
+                main_data_begin,
+                main_data_len,
+            });
+            samples_per_frame = header.samples_per_frame;
+            pos += header.frame_len;
+        }
+
+        Self {
+            frames,
+            samples_per_frame,
+        }
+    }
+
+    pub fn samples_per_frame(&self) -> u32 {
+        self.samples_per_frame
+    }
+
+    /// Find where to start decoding to reach a given sample frame.
+    ///
+    /// Frames can store part of their data in the frames before them, so
+    /// decoding has to start a few frames early to fill the bit reservoir.
+    /// Returns the byte offset to start decoding at, and the number of
+    /// sample frames the decoder will output before reaching the target.
+    /// Frames that can't be decoded for want of reservoir data output
+    /// nothing, and are not counted.
+    pub fn seek_point(&self, sample_frame: u32) -> Option<(usize, u32)> {
+        if self.samples_per_frame == 0 {
+            return None;
+        }
+
+        let target = (sample_frame / self.samples_per_frame) as usize;
+        let target_entry = self.frames.get(target)?;
+        let offset_in_frame = sample_frame % self.samples_per_frame;
+
+        for start in (target.saturating_sub(MAX_SEEK_PREROLL_FRAMES)..target).rev() {
+            // Simulate the decoder's reservoir to find which frames decode.
+            let mut reservoir = 0;
+            let mut decoded_frames = 0;
+            let mut previous_decoded = false;
+            for frame in &self.frames[start..target] {
+                previous_decoded = reservoir >= frame.main_data_begin;
+                if previous_decoded {
+                    decoded_frames += 1;
+                }
+                reservoir = (reservoir.min(frame.main_data_begin) + frame.main_data_len)
+                    .min(MAX_BIT_RESERVOIR_BYTES);
+            }
+
+            // The frame before the target must decode too, since its output
+            // overlaps with the target's.
+            if previous_decoded && reservoir >= target_entry.main_data_begin {
+                return Some((
+                    self.frames[start].offset,
+                    decoded_frames * self.samples_per_frame + offset_in_frame,
+                ));
+            }
+        }
+
+        // Fall back to decoding the whole stream up to the target.
+        Some((0, sample_frame))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 128kbps MPEG-1 joint stereo frame header at 44.1kHz.
+    const HEADER: [u8; 4] = [0xFF, 0xFB, 0x90, 0x64];
+
+    fn xing_frame(num_frames: u32, delay: u32, padding: u32) -> Vec<u8> {
+        let mut frame = vec![0; 417];
+        frame[..4].copy_from_slice(&HEADER);
+        frame[36..40].copy_from_slice(b"Info");
+        frame[40..44].copy_from_slice(&3u32.to_be_bytes());
+        frame[44..48].copy_from_slice(&num_frames.to_be_bytes());
+        frame[48..52].copy_from_slice(&100_000u32.to_be_bytes());
+        frame[52..61].copy_from_slice(b"LAME3.100");
+        frame[73] = (delay >> 4) as u8;
+        frame[74] = ((delay & 0x0F) << 4 | padding >> 8) as u8;
+        frame[75] = padding as u8;
+        frame
+    }
+
+    #[test]
+    fn frame_header() {
+        let header = FrameHeader::parse(&HEADER).unwrap();
+        assert_eq!(header.sample_rate, 44100);
+        assert_eq!(header.num_channels, 2);
+        assert_eq!(header.samples_per_frame, 1152);
+        assert_eq!(header.frame_len, 417);
+
+        // MPEG-2, 64kbps, 22.05kHz, mono.
+        let header = FrameHeader::parse(&[0xFF, 0xF3, 0x80, 0xC0]).unwrap();
+        assert_eq!(header.sample_rate, 22050);
+        assert_eq!(header.num_channels, 1);
+        assert_eq!(header.samples_per_frame, 576);
+        assert_eq!(header.frame_len, 208);
+
+        assert_eq!(FrameHeader::parse(&[0xFF, 0xFB, 0xF0, 0x64]), None);
+        assert_eq!(FrameHeader::parse(&[0x49, 0x44, 0x33, 0x03]), None);
+    }
+
+    #[test]
+    fn lame_tag() {
+        let info = Mp3Info::parse(&xing_frame(10, 576, 1000)).unwrap();
+        assert_eq!(info.num_frames, Some(10));
+        assert_eq!(info.num_bytes, Some(100_000));
+        assert_eq!(info.encoder_delay, Some(576));
+        assert_eq!(info.encoder_padding, Some(1000));
+        assert_eq!(info.header_frame_len(), 417);
+        assert_eq!(info.skip_sample_frames(), Some(576 + DECODER_DELAY));
+        assert_eq!(info.num_sample_frames(), Some(10 * 1152 - 576 - 1000));
+    }
+
+    #[test]
+    fn no_metadata_frame() {
+        let mut frame = vec![0; 417];
+        frame[..4].copy_from_slice(&HEADER);
+        assert_eq!(Mp3Info::parse(&frame), None);
+    }
+
+    #[test]
+    fn seek_point() {
+        // Each frame borrows 100 bytes from the frame before it.
+        let mut data = Vec::new();
+        for _ in 0..8 {
+            let mut frame = vec![0; 417];
+            frame[..4].copy_from_slice(&HEADER);
+            frame[4] = 100 >> 1;
+            data.extend(frame);
+        }
+        data[4] = 0;
+
+        let table = SeekTable::new(&data);
+        assert_eq!(table.frames.len(), 8);
+        assert_eq!(table.seek_point(0), Some((0, 0)));
+        assert_eq!(table.seek_point(100), Some((0, 100)));
+
+        // Starting one frame early isn't enough, since that frame can't be
+        // decoded; starting two frames early decodes one frame of preroll.
+        assert_eq!(table.seek_point(5 * 1152 + 7), Some((3 * 417, 1152 + 7)));
+    }
+}
@@ -20,6 +20,7 @@ pub mod property_decl;
 
 pub mod activation;
 mod callable_value;
+mod case_sensitivity;
 pub mod debug;
 pub mod error;
 mod fscommand;
@@ -28,7 +29,7 @@ pub mod object;
 pub mod property;
 pub mod property_map;
 mod scope;
-mod timer;
+mod target_path_cache;
 mod value;
 
 #[cfg(test)]
@@ -38,6 +39,7 @@ use crate::avm1::activation::{Activation, ActivationIdentifier};
 pub use crate::avm1::error::Error;
 use crate::avm1::globals::as_broadcaster;
 use crate::avm1::globals::as_broadcaster::BroadcasterFunctions;
+pub use case_sensitivity::CaseSensitivity;
 pub use globals::SystemPrototypes;
 pub use object::array_object::ArrayObject;
 pub use object::script_object::ScriptObject;
@@ -46,7 +48,7 @@ pub use object::stage_object::StageObject;
 pub use object::{Object, ObjectPtr, TObject};
 use scope::Scope;
 use smallvec::alloc::borrow::Cow;
-pub use timer::Timers;
+use target_path_cache::TargetPathCache;
 pub use value::Value;
 
 macro_rules! avm_debug {
@@ -123,6 +125,13 @@ pub struct Avm1<'gc> {
     /// The list of all movie clips in execution order.
     pub clip_exec_list: Option<DisplayObject<'gc>>,
 
+    /// Target paths that resolved through the display list.
+    target_path_cache: TargetPathCache<'gc>,
+
+    /// Overrides the case sensitivity implied by each movie's SWF version,
+    /// for content that relies on the other behavior.
+    case_sensitivity_override: Option<CaseSensitivity>,
+
     #[cfg(feature = "avm_debug")]
     pub debug_output: bool,
 }
@@ -149,6 +158,8 @@ impl<'gc> Avm1<'gc> {
             max_recursion_depth: 255,
             has_mouse_listener: false,
             clip_exec_list: None,
+            target_path_cache: TargetPathCache::new(),
+            case_sensitivity_override: None,
 
             #[cfg(feature = "avm_debug")]
             debug_output: false,
@@ -332,6 +343,8 @@ impl<'gc> Avm1<'gc> {
         let _ = obj.call_method(name, args, &mut activation);
     }
 
+    /// Broadcast a message to the listeners of a global broadcaster, such as
+    /// `Mouse`. Nested broadcasters are named with dots, as in `System.IME`.
     pub fn notify_system_listeners(
         active_clip: DisplayObject<'gc>,
         swf_version: u8,
@@ -350,10 +363,13 @@ impl<'gc> Avm1<'gc> {
             active_clip,
         );
 
-        let broadcaster = global
-            .get(broadcaster_name, &mut activation)
-            .unwrap()
-            .coerce_to_object(&mut activation);
+        let mut broadcaster = global;
+        for name in broadcaster_name.split('.') {
+            broadcaster = broadcaster
+                .get(name, &mut activation)
+                .unwrap()
+                .coerce_to_object(&mut activation);
+        }
 
         let has_listener =
             as_broadcaster::broadcast_internal(&mut activation, broadcaster, args, method)
@@ -425,6 +441,14 @@ impl<'gc> Avm1<'gc> {
         self.max_recursion_depth = max_recursion_depth
     }
 
+    pub fn case_sensitivity_override(&self) -> Option<CaseSensitivity> {
+        self.case_sensitivity_override
+    }
+
+    pub fn set_case_sensitivity_override(&mut self, case_sensitivity: Option<CaseSensitivity>) {
+        self.case_sensitivity_override = case_sensitivity
+    }
+
     pub fn broadcaster_functions(&self) -> BroadcasterFunctions<'gc> {
         self.broadcaster_functions
     }
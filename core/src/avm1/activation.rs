@@ -5,7 +5,8 @@ use crate::avm1::object::{Object, TObject};
 use crate::avm1::property::Attribute;
 use crate::avm1::scope::Scope;
 use crate::avm1::{
-    fscommand, globals, scope, skip_actions, start_drag, ArrayObject, ScriptObject, Value,
+    fscommand, globals, scope, skip_actions, start_drag, ArrayObject, CaseSensitivity,
+    ScriptObject, Value,
 };
 use crate::backend::navigator::{NavigationMethod, RequestOptions};
 use crate::context::UpdateContext;
@@ -657,9 +658,15 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
 
     fn action_ascii_to_char(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
         // In SWF6+, this operates on UTF-16 code units.
-        // TODO: In SWF5 and below, this operates on bytes, regardless of the locale encoding.
-        let char_code: u32 = self.context.avm1.pop().coerce_to_u16(self)?.into();
-        let result = if char_code != 0 {
+        // In SWF5 and below, this operates on a single byte, which is decoded
+        // in the locale encoding.
+        let mut char_code: u32 = self.context.avm1.pop().coerce_to_u16(self)?.into();
+        if self.swf_version() <= 5 {
+            char_code &= 0xFF;
+        }
+        let result = if char_code != 0 && self.swf_version() <= 5 {
+            crate::string::utils::decode_locale(&[char_code as u8], self.encoding())
+        } else if char_code != 0 {
             // Unpaired surrogates turn into replacement char.
             char::try_from(char_code)
                 .unwrap_or(std::char::REPLACEMENT_CHARACTER)
@@ -676,9 +683,16 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
     fn action_char_to_ascii(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
         // SWF4 ord function
         // In SWF6+, this operates on UTF-16 code units.
-        // TODO: In SWF5 and below, this operates on bytes, regardless of the locale.
+        // In SWF5 and below, this returns the first byte of the string once
+        // it is encoded in the locale encoding.
         let val = self.context.avm1.pop();
         let s = val.coerce_to_string(self)?;
+        if self.swf_version() <= 5 {
+            let bytes = crate::string::utils::encode_locale(&s, self.encoding());
+            let byte = bytes.first().copied().unwrap_or(0);
+            self.context.avm1.push(u32::from(byte));
+            return Ok(FrameControl::Continue);
+        }
         let char_code = s.encode_utf16().next().unwrap_or(0);
         // Unpaired surrogate characters should return the code point for the replacement character.
         // Try to convert the code unit back to a character, which will fail if this is invalid UTF-16 (unpaired surrogate).
@@ -1309,7 +1323,12 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                     Cow::Borrowed(&url),
                     NavigationMethod::from_send_vars_method(swf_method),
                 );
-                let fetch = self.context.navigator.fetch(&url, opts);
+                let fetch = self.context.security.fetch_data(
+                    self.context.navigator,
+                    self.context.swf,
+                    &url,
+                    opts,
+                );
                 let process = self.context.load_manager.load_form_into_object(
                     self.context.player.clone().unwrap(),
                     target_obj,
@@ -2089,7 +2108,8 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
     fn action_string_extract(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
         // SWF4 substring function
         // In SWF6+, this operates on UTF-16 code units.
-        // TODO: In SWF5 and below, this operates on bytes, regardless of the locale encoding.
+        // In SWF5 and below, this operates on the bytes of the string once it
+        // is encoded in the locale encoding.
 
         // len < 0 returns to the end of the string.
         let len = self.context.avm1.pop().coerce_to_i32(self)?;
@@ -2102,11 +2122,17 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         let val = self.context.avm1.pop();
         let s = val.coerce_to_string(self)?;
 
-        let result = crate::string::utils::utf16_iter_to_string(
-            s.encode_utf16()
-                .skip(start) // - 1 safe because max(1) above
-                .take(len),
-        );
+        let result = if self.swf_version() <= 5 {
+            let bytes = crate::string::utils::encode_locale(&s, self.encoding());
+            let bytes: Vec<u8> = bytes.into_iter().skip(start).take(len).collect();
+            crate::string::utils::decode_locale(&bytes, self.encoding())
+        } else {
+            crate::string::utils::utf16_iter_to_string(
+                s.encode_utf16()
+                    .skip(start) // - 1 safe because max(1) above
+                    .take(len),
+            )
+        };
         self.context
             .avm1
             .push(AvmString::new(self.context.gc_context, result));
@@ -2131,9 +2157,14 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
     fn action_string_length(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
         // AS1 strlen
         // In SWF6+, this is the same as String.length (returns number of UTF-16 code units).
-        // TODO: In SWF5, this returns the byte length, even though the encoding is locale dependent.
+        // In SWF5 and below, this returns the byte length, even though the encoding is locale dependent.
         let val = self.context.avm1.pop();
-        let len = val.coerce_to_string(self)?.encode_utf16().count();
+        let s = val.coerce_to_string(self)?;
+        let len = if self.swf_version() <= 5 {
+            crate::string::utils::encode_locale(&s, self.encoding()).len()
+        } else {
+            s.encode_utf16().count()
+        };
         self.context.avm1.push(len);
         Ok(FrameControl::Continue)
     }
@@ -2541,6 +2572,26 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             return Ok(Some(start));
         }
 
+        let case_sensitivity = self.case_sensitivity();
+        let case_sensitive = case_sensitivity.is_sensitive();
+        let generation = *self.context.display_list_generation;
+        if let Some(object) = self.context.avm1.target_path_cache().get(
+            generation,
+            root,
+            start,
+            path,
+            case_sensitive,
+            first_element,
+        ) {
+            return Ok(Some(object));
+        }
+        let full_path = path;
+        let cache_first_element = first_element;
+
+        // Only paths that resolve entirely through the display list can be
+        // cached, since anything else may change without it.
+        let mut cacheable = true;
+
         // Starting / means an absolute path starting from root.
         // (`/bar` means `_root.bar`)
         let mut path = path.as_bytes();
@@ -2551,8 +2602,6 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             (start, false)
         };
 
-        let case_sensitive = self.is_case_sensitive();
-
         // Iterate through each token in the path.
         while !path.is_empty() {
             // Skip any number of leading :
@@ -2601,9 +2650,11 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                 // Guaranteed to be valid UTF-8.
                 let name = unsafe { std::str::from_utf8_unchecked(ident) };
 
-                if first_element && name == "this" {
+                if first_element && case_sensitivity.eq(name, "this") {
+                    cacheable = false;
                     self.this_cell().into()
-                } else if first_element && name == "_root" {
+                } else if first_element && case_sensitivity.eq(name, "_root") {
+                    cacheable = false;
                     self.root_object()?
                 } else {
                     // Get the value from the object.
@@ -2616,6 +2667,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                     {
                         child.object()
                     } else {
+                        cacheable = false;
                         object.get(name, self).unwrap()
                     }
                 }
@@ -2632,6 +2684,18 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             };
         }
 
+        if cacheable {
+            self.context.avm1.target_path_cache().insert(
+                generation,
+                root,
+                start,
+                full_path,
+                case_sensitive,
+                cache_first_element,
+                object,
+            );
+        }
+
         Ok(Some(object))
     }
 
@@ -2880,9 +2944,18 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         Ok(self.base_clip().avm1_root(&self.context)?.object())
     }
 
-    /// Returns whether property keys should be case sensitive based on the current SWF version.
+    /// Returns how names are compared, based on the current SWF version
+    /// unless the player has been configured otherwise.
+    pub fn case_sensitivity(&self) -> CaseSensitivity {
+        self.context
+            .avm1
+            .case_sensitivity_override()
+            .unwrap_or_else(|| CaseSensitivity::for_swf_version(self.swf_version()))
+    }
+
+    /// Returns whether property keys should be case sensitive.
     pub fn is_case_sensitive(&self) -> bool {
-        self.swf_version() > 6
+        self.case_sensitivity().is_sensitive()
     }
 
     /// Resolve a particular named local variable within this activation.
@@ -2890,11 +2963,12 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
     /// Because scopes are object chains, the same rules for `Object::get`
     /// still apply here.
     pub fn resolve(&mut self, name: &str) -> Result<CallableValue<'gc>, Error<'gc>> {
-        if name == "this" {
+        let case_sensitivity = self.case_sensitivity();
+        if case_sensitivity.eq(name, "this") {
             return Ok(CallableValue::UnCallable(Value::Object(self.this_cell())));
         }
 
-        if name == "arguments" && self.arguments.is_some() {
+        if case_sensitivity.eq(name, "arguments") && self.arguments.is_some() {
             return Ok(CallableValue::UnCallable(Value::Object(
                 self.arguments.unwrap(),
             )));
@@ -2907,11 +2981,12 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
 
     /// Check if a particular property in the scope chain is defined.
     pub fn is_defined(&mut self, name: &str) -> bool {
-        if name == "this" {
+        let case_sensitivity = self.case_sensitivity();
+        if case_sensitivity.eq(name, "this") {
             return true;
         }
 
-        if name == "arguments" && self.arguments.is_some() {
+        if case_sensitivity.eq(name, "arguments") && self.arguments.is_some() {
             return true;
         }
 
@@ -0,0 +1,95 @@
+//! How names are compared by the ActionScript VM.
+//!
+//! SWFv6 and below look up properties, variables, instance names in target
+//! paths and keywords such as `this` and `_root` without regard to case.
+//! SWFv7 made all of them case sensitive.
+
+use crate::string::utils as string_utils;
+use gc_arena::Collect;
+
+/// Whether two names that differ only by case refer to the same thing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Collect)]
+#[collect(require_static)]
+pub enum CaseSensitivity {
+    /// Names must match exactly, as in SWFv7 and above.
+    Sensitive,
+
+    /// Names match if they are equal once every character is lowercased with
+    /// Flash Player's case mapping, as in SWFv6 and below.
+    ///
+    /// This mapping differs from Rust's `to_lowercase`: it maps each UTF-16
+    /// code unit to exactly one other, so folding never changes the length
+    /// of a name.
+    Insensitive,
+}
+
+impl CaseSensitivity {
+    /// The case sensitivity of code in a movie of the given SWF version.
+    pub fn for_swf_version(swf_version: u8) -> Self {
+        if swf_version > 6 {
+            CaseSensitivity::Sensitive
+        } else {
+            CaseSensitivity::Insensitive
+        }
+    }
+
+    pub fn is_sensitive(self) -> bool {
+        self == CaseSensitivity::Sensitive
+    }
+
+    /// Map a character to the form it is compared in.
+    pub fn fold(self, c: char) -> char {
+        match self {
+            CaseSensitivity::Sensitive => c,
+            CaseSensitivity::Insensitive => string_utils::swf_char_to_lowercase(c),
+        }
+    }
+
+    /// Compare two names for equality.
+    pub fn eq(self, a: &str, b: &str) -> bool {
+        string_utils::swf_string_eq(a, b, self.is_sensitive())
+    }
+
+    /// Check whether a name begins with the given prefix.
+    pub fn starts_with(self, name: &str, prefix: &str) -> bool {
+        let mut chars = name.chars();
+        prefix
+            .chars()
+            .all(|p| chars.next().map_or(false, |c| self.fold(c) == self.fold(p)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swf_version() {
+        assert_eq!(
+            CaseSensitivity::for_swf_version(6),
+            CaseSensitivity::Insensitive
+        );
+        assert_eq!(
+            CaseSensitivity::for_swf_version(7),
+            CaseSensitivity::Sensitive
+        );
+    }
+
+    #[test]
+    fn insensitive() {
+        let case = CaseSensitivity::Insensitive;
+        assert!(case.eq("_ROOT", "_root"));
+        assert!(case.eq("ÉTÉ", "été"));
+        assert!(!case.eq("foo", "foo2"));
+        assert!(case.starts_with("_LEVEL0", "_level"));
+        assert!(!case.starts_with("_lev", "_level"));
+    }
+
+    #[test]
+    fn sensitive() {
+        let case = CaseSensitivity::Sensitive;
+        assert!(case.eq("_root", "_root"));
+        assert!(!case.eq("_ROOT", "_root"));
+        assert!(!case.starts_with("_LEVEL0", "_level"));
+    }
+}
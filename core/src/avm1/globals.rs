@@ -36,11 +36,13 @@ pub mod gradient_bevel_filter;
 pub mod gradient_glow_filter;
 mod key;
 mod load_vars;
+mod local_connection;
 mod math;
 mod matrix;
 pub(crate) mod mouse;
 pub(crate) mod movie_clip;
 mod movie_clip_loader;
+mod net_connection;
 pub(crate) mod number;
 mod object;
 mod point;
@@ -59,6 +61,7 @@ mod text_format;
 mod transform;
 mod video;
 mod xml;
+mod xml_socket;
 
 const GLOBAL_DECLS: &[Declaration] = declare_properties! {
     "isFinite" => method(is_finite; DONT_ENUM);
@@ -322,18 +325,17 @@ pub fn create_timer<'gc>(
     is_timeout: bool,
 ) -> Result<Value<'gc>, Error<'gc>> {
     // `setInterval` was added in Flash Player 6 but is not version-gated.
-    use crate::avm1::timer::TimerCallback;
-    let (callback, i) = match args.get(0) {
-        Some(Value::Object(o)) if o.as_executable().is_some() => (TimerCallback::Function(*o), 1),
+    use crate::timer::TimerCallback;
+    let (object, method_name, i) = match args.get(0) {
+        Some(Value::Object(o)) if o.as_executable().is_some() => (*o, None, 1),
         Some(Value::Object(o)) => (
-            TimerCallback::Method {
-                this: *o,
-                method_name: args
-                    .get(1)
+            *o,
+            Some(
+                args.get(1)
                     .unwrap_or(&Value::Undefined)
                     .coerce_to_string(activation)?
                     .to_string(),
-            },
+            ),
             2,
         ),
         _ => return Ok(Value::Undefined),
@@ -349,10 +351,22 @@ pub fn create_timer<'gc>(
         vec![]
     };
 
+    let callback = match method_name {
+        Some(method_name) => TimerCallback::Avm1Method {
+            this: object,
+            method_name,
+            params,
+        },
+        None => TimerCallback::Avm1Function {
+            func: object,
+            params,
+        },
+    };
+
     let id = activation
         .context
         .timers
-        .add_timer(callback, interval, params, is_timeout);
+        .add_timer(callback, interval, is_timeout);
 
     Ok(id.into())
 }
@@ -574,6 +588,11 @@ pub fn create_globals<'gc>(
     let xmlnode_proto = xml::create_xmlnode_proto(gc_context, object_proto, function_proto);
 
     let xml_proto = xml::create_xml_proto(gc_context, xmlnode_proto, function_proto);
+    let xml_socket_proto = xml_socket::create_proto(gc_context, object_proto, function_proto);
+    let local_connection_proto =
+        local_connection::create_proto(gc_context, object_proto, function_proto);
+    let net_connection_proto =
+        net_connection::create_proto(gc_context, object_proto, function_proto);
 
     let string_proto = string::create_proto(gc_context, object_proto, function_proto);
     let number_proto = number::create_proto(gc_context, object_proto, function_proto);
@@ -696,6 +715,27 @@ pub fn create_globals<'gc>(
         Some(function_proto),
         xml_proto,
     );
+    let xml_socket = FunctionObject::constructor(
+        gc_context,
+        Executable::Native(xml_socket::constructor),
+        constructor_to_fn!(xml_socket::constructor),
+        Some(function_proto),
+        xml_socket_proto,
+    );
+    let local_connection = FunctionObject::constructor(
+        gc_context,
+        Executable::Native(local_connection::constructor),
+        constructor_to_fn!(local_connection::constructor),
+        Some(function_proto),
+        local_connection_proto,
+    );
+    let net_connection = FunctionObject::constructor(
+        gc_context,
+        Executable::Native(net_connection::constructor),
+        constructor_to_fn!(net_connection::constructor),
+        Some(function_proto),
+        net_connection_proto,
+    );
     let string = string::create_string_object(gc_context, string_proto, function_proto);
     let number = number::create_number_object(gc_context, number_proto, function_proto);
     let boolean = boolean::create_boolean_object(gc_context, boolean_proto, Some(function_proto));
@@ -996,6 +1036,24 @@ pub fn create_globals<'gc>(
     );
     globals.define_value(gc_context, "XMLNode", xmlnode.into(), Attribute::DONT_ENUM);
     globals.define_value(gc_context, "XML", xml.into(), Attribute::DONT_ENUM);
+    globals.define_value(
+        gc_context,
+        "XMLSocket",
+        xml_socket.into(),
+        Attribute::DONT_ENUM,
+    );
+    globals.define_value(
+        gc_context,
+        "LocalConnection",
+        local_connection.into(),
+        Attribute::DONT_ENUM,
+    );
+    globals.define_value(
+        gc_context,
+        "NetConnection",
+        net_connection.into(),
+        Attribute::DONT_ENUM,
+    );
     globals.define_value(gc_context, "String", string.into(), Attribute::DONT_ENUM);
     globals.define_value(gc_context, "Number", number.into(), Attribute::DONT_ENUM);
     globals.define_value(gc_context, "Boolean", boolean.into(), Attribute::DONT_ENUM);
@@ -3,14 +3,21 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject};
+use crate::avm1::globals::bitmap_filter;
+use crate::avm1::globals::color_transform::object_to_color_transform;
+use crate::avm1::globals::matrix::object_to_matrix;
 use crate::avm1::object::bitmap_data::BitmapDataObject;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{Object, TObject, Value};
-use crate::bitmap::bitmap_data::{BitmapData, ChannelOptions, Color};
+use crate::bitmap::bitmap_data::{
+    self, BitmapData, BitmapDrawable, ChannelOptions, Color, ThresholdOperation,
+};
 use crate::bitmap::is_size_valid;
 use crate::character::Character;
 use crate::display_object::TDisplayObject;
+use crate::transform::Transform;
 use gc_arena::{GcCell, MutationContext};
+use swf::BlendMode;
 
 const PROTO_DECLS: &[Declaration] = declare_properties! {
     "height" => property(height);
@@ -40,6 +47,7 @@ const PROTO_DECLS: &[Declaration] = declare_properties! {
     "pixelDissolve" => method(pixel_dissolve);
     "scroll" => method(scroll);
     "threshold" => method(threshold);
+    "compare" => method(compare);
 };
 
 const OBJECT_DECLS: &[Declaration] = declare_properties! {
@@ -345,14 +353,14 @@ pub fn fill_rect<'gc>(
             if let Some(color_val) = args.get(1) {
                 let color = color_val.coerce_to_i32(activation)?;
 
-                let x = rectangle.get("x", activation)?.coerce_to_u32(activation)?;
-                let y = rectangle.get("y", activation)?.coerce_to_u32(activation)?;
+                let x = rectangle.get("x", activation)?.coerce_to_f64(activation)? as i32;
+                let y = rectangle.get("y", activation)?.coerce_to_f64(activation)? as i32;
                 let width = rectangle
                     .get("width", activation)?
-                    .coerce_to_u32(activation)?;
+                    .coerce_to_f64(activation)? as i32;
                 let height = rectangle
                     .get("height", activation)?
-                    .coerce_to_u32(activation)?;
+                    .coerce_to_f64(activation)? as i32;
 
                 bitmap_data
                     .bitmap_data()
@@ -483,22 +491,140 @@ pub fn noise<'gc>(
 }
 
 pub fn apply_filter<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("BitmapData.applyFilter - not yet implemented");
+    if let Some(bitmap_data) = this.as_bitmap_data_object() {
+        if !bitmap_data.disposed() {
+            let source_bitmap = args
+                .get(0)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+
+            let source_rect = args
+                .get(1)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+
+            let src_min_x = source_rect
+                .get("x", activation)?
+                .coerce_to_f64(activation)? as i32;
+            let src_min_y = source_rect
+                .get("y", activation)?
+                .coerce_to_f64(activation)? as i32;
+            let src_width = source_rect
+                .get("width", activation)?
+                .coerce_to_f64(activation)? as i32;
+            let src_height = source_rect
+                .get("height", activation)?
+                .coerce_to_f64(activation)? as i32;
+
+            let dest_point = args
+                .get(2)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+
+            let dest_x = dest_point.get("x", activation)?.coerce_to_f64(activation)? as i32;
+            let dest_y = dest_point.get("y", activation)?.coerce_to_f64(activation)? as i32;
+
+            let filter = args
+                .get(3)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+            let filter = match bitmap_filter::object_to_bitmap_filter(filter) {
+                Some(filter) => filter,
+                None => {
+                    log::warn!("BitmapData.applyFilter - unsupported filter");
+                    return Ok((-1).into());
+                }
+            };
+
+            if let Some(src_bitmap) = source_bitmap.as_bitmap_data_object() {
+                if !src_bitmap.disposed() && src_width > 0 && src_height > 0 {
+                    let image = src_bitmap.bitmap_data().read().filter_image(
+                        src_min_x,
+                        src_min_y,
+                        src_width as u32,
+                        src_height as u32,
+                    );
+                    let image = filter.apply(&image);
+                    bitmap_data
+                        .bitmap_data()
+                        .write(activation.context.gc_context)
+                        .set_filter_image(&image, dest_x, dest_y);
+                    return Ok(0.into());
+                }
+            }
+        }
+    }
+
     Ok((-1).into())
 }
 
 pub fn draw<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(bitmap_data) = this.as_bitmap_data_object() {
         if !bitmap_data.disposed() {
-            log::warn!("BitmapData.draw - not yet implemented");
+            let source = match args.get(0) {
+                Some(Value::Object(source)) => *source,
+                _ => return Ok(Value::Undefined),
+            };
+            let source = if let Some(source_bitmap) = source.as_bitmap_data_object() {
+                BitmapDrawable::BitmapData(source_bitmap.bitmap_data())
+            } else if let Some(source_object) = source.as_display_object() {
+                BitmapDrawable::DisplayObject(source_object)
+            } else {
+                return Ok(Value::Undefined);
+            };
+
+            let mut transform = Transform::default();
+            if let Some(Value::Object(matrix)) = args.get(1) {
+                transform.matrix = object_to_matrix(*matrix, activation)?;
+            }
+            if let Some(Value::Object(color_transform)) = args.get(2) {
+                transform.color_transform =
+                    object_to_color_transform(*color_transform, activation)?;
+            }
+
+            let blend_mode = match args.get(3) {
+                Some(Value::String(name)) => bitmap_data::blend_mode_from_name(name),
+                Some(Value::Number(mode)) => BlendMode::from_u8(*mode as u8),
+                _ => None,
+            }
+            .unwrap_or(BlendMode::Normal);
+
+            let clip_rect = if let Some(Value::Object(rectangle)) = args.get(4) {
+                Some((
+                    rectangle.get("x", activation)?.coerce_to_f64(activation)? as i32,
+                    rectangle.get("y", activation)?.coerce_to_f64(activation)? as i32,
+                    rectangle
+                        .get("width", activation)?
+                        .coerce_to_f64(activation)? as i32,
+                    rectangle
+                        .get("height", activation)?
+                        .coerce_to_f64(activation)? as i32,
+                ))
+            } else {
+                None
+            };
+
+            let smoothing = args.get(5).map_or(false, |smoothing| {
+                smoothing.as_bool(activation.swf_version())
+            });
+
+            bitmap_data::draw(
+                bitmap_data.bitmap_data(),
+                source,
+                &transform,
+                smoothing,
+                blend_mode,
+                clip_rect,
+                &mut activation.context,
+            );
             return Ok(Value::Undefined);
         }
     }
@@ -677,14 +803,92 @@ pub fn perlin_noise<'gc>(
 }
 
 pub fn hit_test<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(bitmap_data) = this.as_bitmap_data_object() {
         if !bitmap_data.disposed() {
-            log::warn!("BitmapData.hitTest - not yet implemented");
-            return Ok(Value::Undefined);
+            let first_point = args
+                .get(0)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+            let top_left_x = first_point
+                .get("x", activation)?
+                .coerce_to_f64(activation)? as i32;
+            let top_left_y = first_point
+                .get("y", activation)?
+                .coerce_to_f64(activation)? as i32;
+
+            let alpha_threshold = args
+                .get(1)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_u32(activation)?;
+
+            let compare_object = args
+                .get(2)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+
+            if let Some(other_bitmap) = compare_object.as_bitmap_data_object() {
+                if other_bitmap.disposed() {
+                    return Ok(false.into());
+                }
+
+                let second_point = args
+                    .get(3)
+                    .unwrap_or(&Value::Undefined)
+                    .coerce_to_object(activation);
+                let second_x = second_point
+                    .get("x", activation)?
+                    .coerce_to_f64(activation)? as i32;
+                let second_y = second_point
+                    .get("y", activation)?
+                    .coerce_to_f64(activation)? as i32;
+
+                let second_alpha_threshold =
+                    args.get(4).unwrap_or(&1.into()).coerce_to_u32(activation)?;
+
+                let result = bitmap_data.bitmap_data().read().hit_test_bitmap(
+                    (top_left_x, top_left_y),
+                    alpha_threshold,
+                    &other_bitmap.bitmap_data().read(),
+                    (second_x, second_y),
+                    second_alpha_threshold,
+                );
+                return Ok(result.into());
+            }
+
+            let x = compare_object
+                .get("x", activation)?
+                .coerce_to_f64(activation)? as i32;
+            let y = compare_object
+                .get("y", activation)?
+                .coerce_to_f64(activation)? as i32;
+
+            // Rectangles have a size, points don't.
+            let result = if compare_object.has_property(activation, "width") {
+                let width = compare_object
+                    .get("width", activation)?
+                    .coerce_to_f64(activation)? as i32;
+                let height = compare_object
+                    .get("height", activation)?
+                    .coerce_to_f64(activation)? as i32;
+                bitmap_data.bitmap_data().read().hit_test_rectangle(
+                    alpha_threshold,
+                    x - top_left_x,
+                    y - top_left_y,
+                    width,
+                    height,
+                )
+            } else {
+                bitmap_data.bitmap_data().read().hit_test_point(
+                    alpha_threshold,
+                    x - top_left_x,
+                    y - top_left_y,
+                )
+            };
+            return Ok(result.into());
         }
     }
 
@@ -797,7 +1001,8 @@ pub fn copy_pixels<'gc>(
                                         source_bitmap_ref,
                                         (src_min_x, src_min_y, src_width, src_height),
                                         (dest_x, dest_y),
-                                        Some((alpha_bitmap_ref, (alpha_x, alpha_y), merge_alpha)),
+                                        Some((alpha_bitmap_ref, (alpha_x, alpha_y))),
+                                        merge_alpha,
                                     );
                             }
                         }
@@ -810,6 +1015,7 @@ pub fn copy_pixels<'gc>(
                                 (src_min_x, src_min_y, src_width, src_height),
                                 (dest_x, dest_y),
                                 None,
+                                false,
                             );
                     }
                 }
@@ -1049,20 +1255,171 @@ pub fn scroll<'gc>(
 }
 
 pub fn threshold<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(bitmap_data) = this.as_bitmap_data_object() {
         if !bitmap_data.disposed() {
-            log::warn!("BitmapData.threshold - not yet implemented");
-            return Ok(Value::Undefined);
+            let source_bitmap = args
+                .get(0)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+
+            let source_rect = args
+                .get(1)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+
+            let src_min_x = source_rect
+                .get("x", activation)?
+                .coerce_to_f64(activation)? as i32;
+            let src_min_y = source_rect
+                .get("y", activation)?
+                .coerce_to_f64(activation)? as i32;
+            let src_width = source_rect
+                .get("width", activation)?
+                .coerce_to_f64(activation)? as i32;
+            let src_height = source_rect
+                .get("height", activation)?
+                .coerce_to_f64(activation)? as i32;
+
+            let dest_point = args
+                .get(2)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+
+            let dest_x = dest_point.get("x", activation)?.coerce_to_f64(activation)? as i32;
+            let dest_y = dest_point.get("y", activation)?.coerce_to_f64(activation)? as i32;
+
+            let operation = args
+                .get(3)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_string(activation)?;
+            let operation = match ThresholdOperation::from_name(&operation) {
+                Some(operation) => operation,
+                None => return Ok(0.into()),
+            };
+
+            let threshold = args
+                .get(4)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_u32(activation)?;
+            let color = args.get(5).unwrap_or(&0.into()).coerce_to_u32(activation)?;
+            let mask = args
+                .get(6)
+                .unwrap_or(&0xFFFFFFFFu32.into())
+                .coerce_to_u32(activation)?;
+            let copy_source = args
+                .get(7)
+                .unwrap_or(&false.into())
+                .as_bool(activation.swf_version());
+
+            if let Some(src_bitmap) = source_bitmap.as_bitmap_data_object() {
+                if !src_bitmap.disposed() {
+                    // dealing with object aliasing...
+                    let src_bitmap_data_cell = src_bitmap.bitmap_data();
+                    let read;
+                    let source: Option<&BitmapData> =
+                        if GcCell::ptr_eq(src_bitmap_data_cell, bitmap_data.bitmap_data()) {
+                            None
+                        } else {
+                            read = src_bitmap_data_cell.read();
+                            Some(&read)
+                        };
+
+                    let count = bitmap_data
+                        .bitmap_data()
+                        .write(activation.context.gc_context)
+                        .threshold(
+                            source,
+                            (src_min_x, src_min_y, src_width, src_height),
+                            (dest_x, dest_y),
+                            operation,
+                            threshold,
+                            color,
+                            mask,
+                            copy_source,
+                        );
+                    return Ok(count.into());
+                }
+            }
+
+            return Ok(0.into());
         }
     }
 
     Ok((-1).into())
 }
 
+pub fn compare<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    const NOT_BITMAP: i32 = -1;
+    const BITMAP_DISPOSED: i32 = -2;
+    const DIFFERENT_WIDTH: i32 = -3;
+    const DIFFERENT_HEIGHT: i32 = -4;
+
+    let this_bitmap_data = match this.as_bitmap_data_object() {
+        Some(bitmap_data) => bitmap_data,
+        None => return Ok(NOT_BITMAP.into()),
+    };
+    if this_bitmap_data.disposed() {
+        return Ok(BITMAP_DISPOSED.into());
+    }
+
+    let other = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_object(activation);
+    let other_bitmap_data = match other.as_bitmap_data_object() {
+        Some(bitmap_data) => bitmap_data,
+        None => return Ok(NOT_BITMAP.into()),
+    };
+    if other_bitmap_data.disposed() {
+        return Ok(BITMAP_DISPOSED.into());
+    }
+
+    let this_bitmap_data = this_bitmap_data.bitmap_data();
+    let other_bitmap_data = other_bitmap_data.bitmap_data();
+    if GcCell::ptr_eq(this_bitmap_data, other_bitmap_data) {
+        return Ok(0.into());
+    }
+
+    let this_bitmap_data = this_bitmap_data.read();
+    let other_bitmap_data = other_bitmap_data.read();
+    if this_bitmap_data.width() != other_bitmap_data.width() {
+        return Ok(DIFFERENT_WIDTH.into());
+    }
+    if this_bitmap_data.height() != other_bitmap_data.height() {
+        return Ok(DIFFERENT_HEIGHT.into());
+    }
+
+    match this_bitmap_data.compare(&other_bitmap_data) {
+        Some(pixels) => {
+            let difference = BitmapDataObject::empty_object(
+                activation.context.gc_context,
+                Some(activation.context.avm1.prototypes.bitmap_data),
+            );
+            difference
+                .as_bitmap_data_object()
+                .unwrap()
+                .bitmap_data()
+                .write(activation.context.gc_context)
+                .set_pixels(
+                    this_bitmap_data.width(),
+                    this_bitmap_data.height(),
+                    true,
+                    pixels,
+                );
+            Ok(difference.into())
+        }
+        None => Ok(0.into()),
+    }
+}
+
 pub fn create_proto<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Object<'gc>,
@@ -2,8 +2,13 @@
 
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
+use crate::avm1::globals::point::point_to_object;
+use crate::avm1::object::bevel_filter::BevelFilterType;
+use crate::avm1::object::displacement_map_filter::DisplacementMapFilterMode;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
-use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::avm1::{ArrayObject, Object, ScriptObject, TObject, Value};
+use crate::bitmap::filter::{BitmapFilter, DisplacementMapMode, FilterType, GradientStop};
+use crate::string::AvmString;
 use gc_arena::MutationContext;
 
 const PROTO_DECLS: &[Declaration] = declare_properties! {
@@ -18,6 +23,434 @@ pub fn constructor<'gc>(
     Ok(this.into())
 }
 
+/// Get the software filter for a filter object, if it's one that can be
+/// applied to a `BitmapData`.
+pub fn object_to_bitmap_filter<'gc>(object: Object<'gc>) -> Option<BitmapFilter> {
+    if let Some(filter) = object.as_blur_filter_object() {
+        return Some(BitmapFilter::Blur {
+            blur_x: filter.blur_x(),
+            blur_y: filter.blur_y(),
+            quality: filter.quality(),
+        });
+    }
+
+    if let Some(filter) = object.as_color_matrix_filter_object() {
+        return Some(BitmapFilter::ColorMatrix {
+            matrix: filter.matrix(),
+        });
+    }
+
+    if let Some(filter) = object.as_convolution_filter_object() {
+        return Some(BitmapFilter::Convolution {
+            matrix_x: filter.matrix_x(),
+            matrix_y: filter.matrix_y(),
+            matrix: filter.matrix(),
+            divisor: filter.divisor(),
+            bias: filter.bias(),
+            preserve_alpha: filter.preserve_alpha(),
+            clamp: filter.clamp(),
+            color: filter.color(),
+            alpha: filter.alpha(),
+        });
+    }
+
+    if let Some(filter) = object.as_displacement_map_filter_object() {
+        let map = filter
+            .map_bitmap()
+            .and_then(|map| map.as_bitmap_data_object())
+            .filter(|map| !map.disposed())?;
+        let map = map.bitmap_data();
+        let map = map.read();
+        return Some(BitmapFilter::DisplacementMap {
+            map: map.filter_image(0, 0, map.width(), map.height()),
+            map_point: filter.map_point(),
+            component_x: filter.component_x(),
+            component_y: filter.component_y(),
+            scale_x: filter.scale_x(),
+            scale_y: filter.scale_y(),
+            mode: match filter.mode() {
+                DisplacementMapFilterMode::Wrap => DisplacementMapMode::Wrap,
+                DisplacementMapFilterMode::Clamp => DisplacementMapMode::Clamp,
+                DisplacementMapFilterMode::Ignore => DisplacementMapMode::Ignore,
+                DisplacementMapFilterMode::Color => DisplacementMapMode::Color,
+            },
+            color: filter.color(),
+            alpha: filter.alpha(),
+        });
+    }
+
+    if let Some(filter) = object.as_drop_shadow_filter_object() {
+        return Some(BitmapFilter::Shadow {
+            color: filter.color(),
+            alpha: filter.alpha(),
+            blur_x: filter.blur_x(),
+            blur_y: filter.blur_y(),
+            strength: filter.strength(),
+            quality: filter.quality(),
+            distance: filter.distance(),
+            angle: filter.angle(),
+            inner: filter.inner(),
+            knockout: filter.knockout(),
+            hide_object: filter.hide_object(),
+        });
+    }
+
+    if let Some(filter) = object.as_glow_filter_object() {
+        return Some(BitmapFilter::Glow {
+            color: filter.color() as u32,
+            alpha: filter.alpha(),
+            blur_x: filter.blur_x(),
+            blur_y: filter.blur_y(),
+            strength: filter.strength(),
+            quality: filter.quality(),
+            inner: filter.inner(),
+            knockout: filter.knockout(),
+        });
+    }
+
+    if let Some(filter) = object.as_bevel_filter_object() {
+        return Some(BitmapFilter::Bevel {
+            highlight_color: filter.highlight_color(),
+            highlight_alpha: filter.highlight_alpha(),
+            shadow_color: filter.shadow_color(),
+            shadow_alpha: filter.shadow_alpha(),
+            blur_x: filter.blur_x(),
+            blur_y: filter.blur_y(),
+            strength: filter.strength(),
+            quality: filter.quality(),
+            distance: filter.distance(),
+            angle: filter.angle(),
+            filter_type: filter_type(filter.get_type()),
+            knockout: filter.knockout(),
+        });
+    }
+
+    if let Some(filter) = object.as_gradient_glow_filter_object() {
+        return Some(BitmapFilter::GradientGlow {
+            gradient: gradient(&filter.colors(), &filter.alphas(), &filter.ratios()),
+            blur_x: filter.blur_x(),
+            blur_y: filter.blur_y(),
+            strength: filter.strength(),
+            quality: filter.quality(),
+            distance: filter.distance(),
+            angle: filter.angle(),
+            filter_type: filter_type(filter.get_type()),
+            knockout: filter.knockout(),
+        });
+    }
+
+    if let Some(filter) = object.as_gradient_bevel_filter_object() {
+        return Some(BitmapFilter::GradientBevel {
+            gradient: gradient(&filter.colors(), &filter.alphas(), &filter.ratios()),
+            blur_x: filter.blur_x(),
+            blur_y: filter.blur_y(),
+            strength: filter.strength(),
+            quality: filter.quality(),
+            distance: filter.distance(),
+            angle: filter.angle(),
+            filter_type: filter_type(filter.get_type()),
+            knockout: filter.knockout(),
+        });
+    }
+
+    None
+}
+
+fn filter_type(type_: BevelFilterType) -> FilterType {
+    match type_ {
+        BevelFilterType::Inner => FilterType::Inner,
+        BevelFilterType::Outer => FilterType::Outer,
+        BevelFilterType::Full => FilterType::Full,
+    }
+}
+
+fn bevel_filter_type(filter_type: FilterType) -> BevelFilterType {
+    match filter_type {
+        FilterType::Inner => BevelFilterType::Inner,
+        FilterType::Outer => BevelFilterType::Outer,
+        FilterType::Full => BevelFilterType::Full,
+    }
+}
+
+/// Combine the parallel `colors`, `alphas` and `ratios` of a gradient filter
+/// into a gradient.
+fn gradient(colors: &[u32], alphas: &[f64], ratios: &[u8]) -> Vec<GradientStop> {
+    colors
+        .iter()
+        .zip(alphas)
+        .zip(ratios)
+        .map(|((&color, &alpha), &ratio)| GradientStop {
+            ratio,
+            color,
+            alpha,
+        })
+        .collect()
+}
+
+/// Create a filter object for a software filter, as read back from the
+/// `filters` of a display object.
+///
+/// Returns `None` for filters that can't be turned back into an object.
+pub fn bitmap_filter_to_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    filter: &BitmapFilter,
+) -> Result<Option<Value<'gc>>, Error<'gc>> {
+    let prototypes = &activation.context.avm1.prototypes;
+    let array_proto = prototypes.array;
+    let (constructor, args) = match filter {
+        BitmapFilter::Blur {
+            blur_x,
+            blur_y,
+            quality,
+        } => (
+            prototypes.blur_filter_constructor,
+            vec![(*blur_x).into(), (*blur_y).into(), (*quality).into()],
+        ),
+        BitmapFilter::ColorMatrix { matrix } => (
+            prototypes.color_matrix_filter_constructor,
+            vec![ArrayObject::new(
+                activation.context.gc_context,
+                array_proto,
+                matrix.iter().map(|&x| x.into()),
+            )
+            .into()],
+        ),
+        BitmapFilter::Convolution {
+            matrix_x,
+            matrix_y,
+            matrix,
+            divisor,
+            bias,
+            preserve_alpha,
+            clamp,
+            color,
+            alpha,
+        } => (
+            prototypes.convolution_filter_constructor,
+            vec![
+                (*matrix_x).into(),
+                (*matrix_y).into(),
+                ArrayObject::new(
+                    activation.context.gc_context,
+                    array_proto,
+                    matrix.iter().map(|&x| x.into()),
+                )
+                .into(),
+                (*divisor).into(),
+                (*bias).into(),
+                (*preserve_alpha).into(),
+                (*clamp).into(),
+                (*color).into(),
+                (*alpha).into(),
+            ],
+        ),
+        BitmapFilter::DisplacementMap {
+            map,
+            map_point,
+            component_x,
+            component_y,
+            scale_x,
+            scale_y,
+            mode,
+            color,
+            alpha,
+        } => {
+            // The map was copied when the filter was set, so it's read back
+            // as a new bitmap.
+            let bitmap_data_constructor = prototypes.bitmap_data_constructor;
+            let map_bitmap = bitmap_data_constructor.construct(
+                activation,
+                &[
+                    map.width().into(),
+                    map.height().into(),
+                    true.into(),
+                    0.into(),
+                ],
+            )?;
+            if let Some(map_data) = map_bitmap
+                .coerce_to_object(activation)
+                .as_bitmap_data_object()
+            {
+                map_data
+                    .bitmap_data()
+                    .write(activation.context.gc_context)
+                    .set_filter_image(map, 0, 0);
+            }
+            let map_point =
+                point_to_object((f64::from(map_point.0), f64::from(map_point.1)), activation)?;
+            let mode: &str = match mode {
+                DisplacementMapMode::Wrap => DisplacementMapFilterMode::Wrap,
+                DisplacementMapMode::Clamp => DisplacementMapFilterMode::Clamp,
+                DisplacementMapMode::Ignore => DisplacementMapFilterMode::Ignore,
+                DisplacementMapMode::Color => DisplacementMapFilterMode::Color,
+            }
+            .into();
+            let prototypes = &activation.context.avm1.prototypes;
+            (
+                prototypes.displacement_map_filter_constructor,
+                vec![
+                    map_bitmap,
+                    map_point,
+                    (*component_x).into(),
+                    (*component_y).into(),
+                    (*scale_x).into(),
+                    (*scale_y).into(),
+                    AvmString::new(activation.context.gc_context, mode.to_string()).into(),
+                    (*color).into(),
+                    (*alpha).into(),
+                ],
+            )
+        }
+        BitmapFilter::Shadow {
+            color,
+            alpha,
+            blur_x,
+            blur_y,
+            strength,
+            quality,
+            distance,
+            angle,
+            inner,
+            knockout,
+            hide_object,
+        } => (
+            prototypes.drop_shadow_filter_constructor,
+            vec![
+                (*distance).into(),
+                (*angle).into(),
+                (*color).into(),
+                (*alpha).into(),
+                (*blur_x).into(),
+                (*blur_y).into(),
+                (*strength).into(),
+                (*quality).into(),
+                (*inner).into(),
+                (*knockout).into(),
+                (*hide_object).into(),
+            ],
+        ),
+        BitmapFilter::Glow {
+            color,
+            alpha,
+            blur_x,
+            blur_y,
+            strength,
+            quality,
+            inner,
+            knockout,
+        } => (
+            prototypes.glow_filter_constructor,
+            vec![
+                (*color).into(),
+                (*alpha).into(),
+                (*blur_x).into(),
+                (*blur_y).into(),
+                (*strength).into(),
+                (*quality).into(),
+                (*inner).into(),
+                (*knockout).into(),
+            ],
+        ),
+        BitmapFilter::Bevel {
+            highlight_color,
+            highlight_alpha,
+            shadow_color,
+            shadow_alpha,
+            blur_x,
+            blur_y,
+            strength,
+            quality,
+            distance,
+            angle,
+            filter_type,
+            knockout,
+        } => {
+            let type_: &str = bevel_filter_type(*filter_type).into();
+            (
+                prototypes.bevel_filter_constructor,
+                vec![
+                    (*distance).into(),
+                    (*angle).into(),
+                    (*highlight_color).into(),
+                    (*highlight_alpha).into(),
+                    (*shadow_color).into(),
+                    (*shadow_alpha).into(),
+                    (*blur_x).into(),
+                    (*blur_y).into(),
+                    (*strength).into(),
+                    (*quality).into(),
+                    AvmString::new(activation.context.gc_context, type_.to_string()).into(),
+                    (*knockout).into(),
+                ],
+            )
+        }
+        BitmapFilter::GradientGlow {
+            gradient,
+            blur_x,
+            blur_y,
+            strength,
+            quality,
+            distance,
+            angle,
+            filter_type,
+            knockout,
+        }
+        | BitmapFilter::GradientBevel {
+            gradient,
+            blur_x,
+            blur_y,
+            strength,
+            quality,
+            distance,
+            angle,
+            filter_type,
+            knockout,
+        } => {
+            let constructor = if let BitmapFilter::GradientGlow { .. } = filter {
+                prototypes.gradient_glow_filter_constructor
+            } else {
+                prototypes.gradient_bevel_filter_constructor
+            };
+            let gc_context = activation.context.gc_context;
+            let type_: &str = bevel_filter_type(*filter_type).into();
+            (
+                constructor,
+                vec![
+                    (*distance).into(),
+                    (*angle).into(),
+                    ArrayObject::new(
+                        gc_context,
+                        array_proto,
+                        gradient.iter().map(|stop| stop.color.into()),
+                    )
+                    .into(),
+                    ArrayObject::new(
+                        gc_context,
+                        array_proto,
+                        gradient.iter().map(|stop| stop.alpha.into()),
+                    )
+                    .into(),
+                    ArrayObject::new(
+                        gc_context,
+                        array_proto,
+                        gradient.iter().map(|stop| stop.ratio.into()),
+                    )
+                    .into(),
+                    (*blur_x).into(),
+                    (*blur_y).into(),
+                    (*strength).into(),
+                    (*quality).into(),
+                    AvmString::new(gc_context, type_.to_string()).into(),
+                    (*knockout).into(),
+                ],
+            )
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(constructor.construct(activation, &args)?))
+}
+
 pub fn clone<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
@@ -79,10 +512,14 @@ pub fn clone<'gc>(
         let blur_y = this.get("blurY", activation)?;
         let strength = this.get("strength", activation)?;
         let quality = this.get("quality", activation)?;
+        let inner = this.get("inner", activation)?;
+        let knockout = this.get("knockout", activation)?;
 
         let cloned = proto.construct(
             activation,
-            &[color, alpha, blur_x, blur_y, strength, quality],
+            &[
+                color, alpha, blur_x, blur_y, strength, quality, inner, knockout,
+            ],
         )?;
         return Ok(cloned);
     }
@@ -5,7 +5,6 @@ use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::Object;
 use crate::avm1::{ScriptObject, Value};
 use crate::context_menu;
-use crate::display_object::TDisplayObject;
 use gc_arena::MutationContext;
 
 const PROTO_DECLS: &[Declaration] = declare_properties! {
@@ -153,77 +152,21 @@ pub fn make_context_menu_state<'gc>(
 ) -> context_menu::ContextMenuState<'gc> {
     let mut result = context_menu::ContextMenuState::new();
 
-    let root_mc = activation.context.stage.root_clip().as_movie_clip();
-    let builtin_items = {
-        let is_multiframe_movie = root_mc.map(|mc| mc.total_frames() > 1).unwrap_or(false);
-        let mut names = if is_multiframe_movie {
-            vec![
-                "zoom",
-                "quality",
-                "play",
-                "loop",
-                "rewind",
-                "forward_back",
-                "print",
-            ]
-        } else {
-            vec!["zoom", "quality", "print"]
-        };
-        if let Some(menu) = menu {
-            if let Ok(Value::Object(builtins)) = menu.get("builtInItems", activation) {
-                names.retain(|name| {
-                    !matches!(builtins.get(name, activation), Ok(Value::Bool(false)))
-                });
-            }
+    let mut item_flags = context_menu::BuiltInItemFlags::for_stage(activation.context.stage);
+    if let Some(menu) = menu {
+        if let Ok(Value::Object(builtins)) = menu.get("builtInItems", activation) {
+            let mut is_hidden =
+                |name: &str| matches!(builtins.get(name, activation), Ok(Value::Bool(false)));
+            item_flags.zoom &= !is_hidden("zoom");
+            item_flags.quality &= !is_hidden("quality");
+            item_flags.play &= !is_hidden("play");
+            item_flags.loop_ &= !is_hidden("loop");
+            item_flags.rewind &= !is_hidden("rewind");
+            item_flags.forward_and_back &= !is_hidden("forward_back");
+            item_flags.print &= !is_hidden("print");
         }
-        names
-    };
-
-    if builtin_items.contains(&"play") {
-        let is_playing_root_movie = root_mc.unwrap().playing();
-        result.push(
-            context_menu::ContextMenuItem {
-                enabled: true,
-                separator_before: true,
-                caption: "Play".to_string(),
-                checked: is_playing_root_movie,
-            },
-            context_menu::ContextMenuCallback::Play,
-        );
-    }
-    if builtin_items.contains(&"rewind") {
-        let is_first_frame = root_mc.unwrap().current_frame() <= 1;
-        result.push(
-            context_menu::ContextMenuItem {
-                enabled: !is_first_frame,
-                separator_before: true,
-                caption: "Rewind".to_string(),
-                checked: false,
-            },
-            context_menu::ContextMenuCallback::Rewind,
-        );
-    }
-    if builtin_items.contains(&"forward_back") {
-        let is_first_frame = root_mc.unwrap().current_frame() <= 1;
-        result.push(
-            context_menu::ContextMenuItem {
-                enabled: true,
-                separator_before: false,
-                caption: "Forward".to_string(),
-                checked: false,
-            },
-            context_menu::ContextMenuCallback::Forward,
-        );
-        result.push(
-            context_menu::ContextMenuItem {
-                enabled: !is_first_frame,
-                separator_before: false,
-                caption: "Back".to_string(),
-                checked: false,
-            },
-            context_menu::ContextMenuCallback::Back,
-        );
     }
+    result.build_builtin_items(item_flags, activation.context.stage);
 
     if let Some(menu) = menu {
         if let Ok(Value::Object(custom_items)) = menu.get("customItems", activation) {
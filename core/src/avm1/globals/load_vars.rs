@@ -257,7 +257,12 @@ fn spawn_load_var_fetch<'gc>(
         (Cow::Borrowed(url.as_str()), RequestOptions::get())
     };
 
-    let fetch = activation.context.navigator.fetch(&url, request_options);
+    let fetch = activation.context.security.fetch_data(
+        activation.context.navigator,
+        activation.context.swf,
+        &url,
+        request_options,
+    );
     let process = activation.context.load_manager.load_form_into_load_vars(
         activation.context.player.clone().unwrap(),
         loader_object,
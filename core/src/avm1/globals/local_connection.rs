@@ -0,0 +1,128 @@
+//! AVM1 LocalConnection object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::property_decl::{define_properties_on, Declaration};
+use crate::avm1::{Object, ScriptObject, Value};
+use crate::local_connection::{LocalConnectionArgs, LocalConnectionTarget, LocalConnections};
+use crate::string::AvmString;
+use gc_arena::MutationContext;
+
+/// Methods of the connection itself, which can't be called through `send`.
+const RESERVED_METHODS: &[&str] = &[
+    "send",
+    "connect",
+    "close",
+    "allowDomain",
+    "allowInsecureDomain",
+    "domain",
+];
+
+const PROTO_DECLS: &[Declaration] = declare_properties! {
+    "connect" => method(connect; DONT_ENUM | DONT_DELETE);
+    "send" => method(send; DONT_ENUM | DONT_DELETE);
+    "close" => method(close; DONT_ENUM | DONT_DELETE);
+    "domain" => method(domain; DONT_ENUM | DONT_DELETE);
+};
+
+/// Implements `LocalConnection`
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let object = ScriptObject::object(gc_context, Some(proto));
+    define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
+    object.into()
+}
+
+/// Get the URL of the movie running the current code.
+fn movie_url(activation: &mut Activation<'_, '_, '_>) -> Option<String> {
+    activation
+        .base_clip()
+        .movie()
+        .and_then(|movie| movie.url().map(str::to_string))
+}
+
+fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = match args.get(0) {
+        Some(Value::String(name)) if !name.is_empty() && !name.contains(':') => *name,
+        _ => return Ok(false.into()),
+    };
+
+    let url = movie_url(activation);
+    let connected = activation.context.local_connections.connect(
+        LocalConnectionTarget::Avm1(this),
+        url.as_deref(),
+        &name,
+    );
+
+    Ok(connected.into())
+}
+
+fn send<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (connection_name, method_name) = match (args.get(0), args.get(1)) {
+        (Some(Value::String(connection_name)), Some(Value::String(method_name))) => {
+            (*connection_name, *method_name)
+        }
+        _ => return Ok(false.into()),
+    };
+
+    if connection_name.is_empty()
+        || method_name.is_empty()
+        || RESERVED_METHODS.contains(&method_name.as_str())
+    {
+        return Ok(false.into());
+    }
+
+    let url = movie_url(activation);
+    activation.context.local_connections.send(
+        LocalConnectionTarget::Avm1(this),
+        url.as_deref(),
+        &connection_name,
+        &method_name,
+        LocalConnectionArgs::Avm1(args[2..].to_vec()),
+    );
+
+    Ok(true.into())
+}
+
+fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    activation
+        .context
+        .local_connections
+        .close(LocalConnectionTarget::Avm1(this));
+
+    Ok(Value::Undefined)
+}
+
+fn domain<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let url = movie_url(activation);
+    let domain = LocalConnections::domain(url.as_deref());
+
+    Ok(AvmString::new(activation.context.gc_context, domain).into())
+}
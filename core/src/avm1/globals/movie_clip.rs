@@ -2,13 +2,15 @@
 
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
+use crate::avm1::globals::bitmap_filter;
 use crate::avm1::globals::display_object::{self, AVM_DEPTH_BIAS, AVM_MAX_DEPTH};
 use crate::avm1::globals::matrix::gradient_object_to_matrix;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
-use crate::avm1::{self, Object, ScriptObject, TObject, Value};
+use crate::avm1::{self, ArrayObject, Object, ScriptObject, TObject, Value};
 use crate::avm_error;
 use crate::avm_warn;
-use crate::backend::{navigator::NavigationMethod, render};
+use crate::backend::navigator::NavigationMethod;
+use crate::bitmap::bitmap_data;
 use crate::display_object::{
     Bitmap, DisplayObject, EditText, MovieClip, TDisplayObject, TDisplayObjectContainer,
 };
@@ -21,7 +23,7 @@ use crate::vminterface::Instantiator;
 use gc_arena::MutationContext;
 use std::borrow::Cow;
 use swf::{
-    FillStyle, Fixed8, Gradient, GradientInterpolation, GradientRecord, GradientSpread,
+    BlendMode, FillStyle, Fixed8, Gradient, GradientInterpolation, GradientRecord, GradientSpread,
     LineCapStyle, LineJoinStyle, LineStyle, Twips,
 };
 
@@ -101,10 +103,16 @@ const PROTO_DECLS: &[Declaration] = declare_properties! {
     "curveTo" => method(mc_method!(curve_to); DONT_ENUM | DONT_DELETE | READ_ONLY);
     "endFill" => method(mc_method!(end_fill); DONT_ENUM | DONT_DELETE | READ_ONLY);
     "lineStyle" => method(mc_method!(line_style); DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "lineGradientStyle" => method(mc_method!(line_gradient_style); DONT_ENUM | DONT_DELETE | READ_ONLY);
     "clear" => method(mc_method!(clear); DONT_ENUM | DONT_DELETE | READ_ONLY);
     "attachBitmap" => method(mc_method!(attach_bitmap); DONT_ENUM | DONT_DELETE | READ_ONLY);
     "removeMovieClip" => method(remove_movie_clip; DONT_ENUM | DONT_DELETE | READ_ONLY);
     "transform" => property(mc_getter!(transform), mc_setter!(set_transform); DONT_DELETE | DONT_ENUM);
+    "filters" => property(mc_getter!(filters), mc_setter!(set_filters); DONT_DELETE | DONT_ENUM);
+    "blendMode" => property(mc_getter!(blend_mode), mc_setter!(set_blend_mode); DONT_DELETE | DONT_ENUM);
+    "cacheAsBitmap" => property(mc_getter!(cache_as_bitmap), mc_setter!(set_cache_as_bitmap); DONT_DELETE | DONT_ENUM);
+    "scrollRect" => property(mc_getter!(scroll_rect), mc_setter!(set_scroll_rect); DONT_DELETE | DONT_ENUM);
+    "scale9Grid" => property(mc_getter!(scale_nine_grid), mc_setter!(set_scale_nine_grid); DONT_DELETE | DONT_ENUM);
     "enabled" => property(mc_getter!(enabled), mc_setter!(set_enabled); DONT_DELETE | DONT_ENUM);
     "focusEnabled" => property(mc_getter!(focus_enabled), mc_setter!(set_focus_enabled); DONT_DELETE | DONT_ENUM);
     "_lockroot" => property(mc_getter!(lock_root), mc_setter!(set_lock_root); DONT_DELETE | DONT_ENUM);
@@ -121,6 +129,14 @@ pub fn constructor<'gc>(
 }
 
 #[allow(clippy::comparison_chain)]
+/// Implements `MovieClip.hitTest`.
+///
+/// With a point, this tests against the clip's bounding box, ignoring any mask
+/// applied to it. Passing `true` for the shape flag tests against the clip's
+/// art instead, which respects masks. Neither mode considers `_visible`.
+///
+/// With another clip, this always compares bounding boxes; the shapes of the
+/// clips are never considered.
 pub fn hit_test<'gc>(
     movie_clip: MovieClip<'gc>,
     activation: &mut Activation<'_, 'gc, '_>,
@@ -140,11 +156,11 @@ pub fn hit_test<'gc>(
                 .avm1_root(&activation.context)?
                 .local_to_global((Twips::from_pixels(x), Twips::from_pixels(y)));
             let ret = if shape {
-                movie_clip.hit_test_shape(
-                    &mut activation.context,
-                    point,
-                    HitTestOptions::AVM_HIT_TEST,
-                )
+                // Masks are skipped when found within the tested clip, but a
+                // mask can still be tested directly.
+                let mut options = HitTestOptions::AVM_HIT_TEST;
+                options.set(HitTestOptions::SKIP_MASK, movie_clip.maskee().is_none());
+                movie_clip.hit_test_shape(&mut activation.context, point, options)
             } else {
                 movie_clip.hit_test_bounds(point)
             };
@@ -356,22 +372,15 @@ fn begin_bitmap_fill<'gc>(
         .and_then(|val| val.coerce_to_object(activation).as_bitmap_data_object())
     {
         // Register the bitmap data with the drawing.
-        let bitmap_data = bitmap_data.bitmap_data();
-        let mut bitmap_data = bitmap_data.write(activation.context.gc_context);
-        let handle = if let Some(handle) = bitmap_data.bitmap_handle(activation.context.renderer) {
-            handle
+        let id = if let Some(id) = movie_clip
+            .as_drawing(activation.context.gc_context)
+            .unwrap()
+            .add_bitmap_data(&mut activation.context, bitmap_data.bitmap_data())
+        {
+            id
         } else {
             return Ok(Value::Undefined);
         };
-        let bitmap = render::BitmapInfo {
-            handle,
-            width: bitmap_data.width() as u16,
-            height: bitmap_data.height() as u16,
-        };
-        let id = movie_clip
-            .as_drawing(activation.context.gc_context)
-            .unwrap()
-            .add_bitmap(bitmap);
 
         let mut matrix = avm1::globals::matrix::object_to_matrix_or_default(
             args.get(1)
@@ -418,11 +427,16 @@ fn begin_bitmap_fill<'gc>(
     Ok(Value::Undefined)
 }
 
-fn begin_gradient_fill<'gc>(
-    movie_clip: MovieClip<'gc>,
+/// Build a gradient fill from the `fillType`, `colors`, `alphas`, `ratios`,
+/// `matrix`, `spreadMethod`, `interpolationMethod` and `focalPointRatio`
+/// arguments of `beginGradientFill` or `lineGradientStyle`.
+///
+/// Returns `None` if the arguments don't describe a gradient.
+fn gradient_fill_from_args<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     args: &[Value<'gc>],
-) -> Result<Value<'gc>, Error<'gc>> {
+    method_name: &str,
+) -> Result<Option<FillStyle>, Error<'gc>> {
     if let (Some(method), Some(colors), Some(alphas), Some(ratios), Some(matrix)) = (
         args.get(0),
         args.get(1),
@@ -430,7 +444,7 @@ fn begin_gradient_fill<'gc>(
         args.get(3),
         args.get(4),
     ) {
-        let method = method.coerce_to_string(activation)?;
+        let fill_type = method.coerce_to_string(activation)?;
         let colors_object = colors.coerce_to_object(activation);
         let colors_length = colors_object.length(activation)?;
         let alphas_object = alphas.coerce_to_object(activation);
@@ -441,9 +455,10 @@ fn begin_gradient_fill<'gc>(
         if colors_length != alphas_length || colors_length != ratios_length {
             avm_warn!(
                 activation,
-                "beginGradientFill() received different sized arrays for colors, alphas and ratios"
+                "{}() received different sized arrays for colors, alphas and ratios",
+                method_name
             );
-            return Ok(Value::Undefined);
+            return Ok(None);
         }
         let records: Result<Vec<_>, Error<'gc>> = (0..colors_length)
             .map(|i| {
@@ -490,7 +505,7 @@ fn begin_gradient_fill<'gc>(
             interpolation,
             records,
         };
-        let style = match method.as_ref() {
+        let style = match fill_type.as_ref() {
             "linear" => FillStyle::LinearGradient(gradient),
             "radial" => {
                 if let Some(focal_point) = args.get(7) {
@@ -505,21 +520,48 @@ fn begin_gradient_fill<'gc>(
             other => {
                 avm_warn!(
                     activation,
-                    "beginGradientFill() received invalid fill type {:?}",
+                    "{}() received invalid fill type {:?}",
+                    method_name,
                     other
                 );
-                return Ok(Value::Undefined);
+                return Ok(None);
             }
         };
+        Ok(Some(style))
+    } else {
+        Ok(None)
+    }
+}
+
+fn begin_gradient_fill<'gc>(
+    movie_clip: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if args.len() < 5 {
+        movie_clip
+            .as_drawing(activation.context.gc_context)
+            .unwrap()
+            .set_fill_style(None);
+    } else if let Some(style) = gradient_fill_from_args(activation, args, "beginGradientFill")? {
         movie_clip
             .as_drawing(activation.context.gc_context)
             .unwrap()
             .set_fill_style(Some(style));
-    } else {
+    }
+    Ok(Value::Undefined)
+}
+
+fn line_gradient_style<'gc>(
+    movie_clip: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(style) = gradient_fill_from_args(activation, args, "lineGradientStyle")? {
         movie_clip
             .as_drawing(activation.context.gc_context)
             .unwrap()
-            .set_fill_style(None);
+            .set_line_fill_style(style);
     }
     Ok(Value::Undefined)
 }
@@ -645,7 +687,7 @@ fn attach_movie<'gc>(
         .and_then(|l| l.instantiate_by_export_name(&export_name, activation.context.gc_context))
     {
         // Set name and attach to parent.
-        new_clip.set_name(activation.context.gc_context, &new_instance_name);
+        new_clip.set_name(&mut activation.context, &new_instance_name);
         movie_clip.replace_at_depth(&mut activation.context, new_clip, depth);
         let init_object = if let Some(Value::Object(init_object)) = init_object {
             Some(init_object.to_owned())
@@ -696,7 +738,7 @@ fn create_empty_movie_clip<'gc>(
     let new_clip = MovieClip::new(SwfSlice::empty(swf_movie), activation.context.gc_context);
 
     // Set name and attach to parent.
-    new_clip.set_name(activation.context.gc_context, &new_instance_name);
+    new_clip.set_name(&mut activation.context, &new_instance_name);
     movie_clip.replace_at_depth(&mut activation.context, new_clip.into(), depth);
     new_clip.post_instantiation(
         &mut activation.context,
@@ -744,10 +786,8 @@ fn create_text_field<'gc>(
 
     let text_field: DisplayObject<'gc> =
         EditText::new(&mut activation.context, movie, x, y, width, height).into();
-    text_field.set_name(
-        activation.context.gc_context,
-        &instance_name.coerce_to_string(activation)?,
-    );
+    let instance_name = instance_name.coerce_to_string(activation)?;
+    text_field.set_name(&mut activation.context, &instance_name);
     movie_clip.replace_at_depth(
         &mut activation.context,
         text_field,
@@ -820,7 +860,7 @@ pub fn duplicate_movie_clip_with_bias<'gc>(
         .and_then(|l| l.instantiate_by_id(movie_clip.id(), activation.context.gc_context))
     {
         // Set name and attach to parent.
-        new_clip.set_name(activation.context.gc_context, &new_instance_name);
+        new_clip.set_name(&mut activation.context, &new_instance_name);
         parent.replace_at_depth(&mut activation.context, new_clip, depth);
 
         // Copy display properties from previous clip to new clip.
@@ -1361,7 +1401,12 @@ fn load_variables<'gc>(
     let method = args.get(1).cloned().unwrap_or(Value::Undefined);
     let method = NavigationMethod::from_method_str(&method.coerce_to_string(activation)?);
     let (url, opts) = activation.locals_into_request_options(Cow::Borrowed(&url), method);
-    let fetch = activation.context.navigator.fetch(&url, opts);
+    let fetch = activation.context.security.fetch_data(
+        activation.context.navigator,
+        activation.context.swf,
+        &url,
+        opts,
+    );
     let target = target.object().coerce_to_object(activation);
     let process = activation.context.load_manager.load_form_into_object(
         activation.context.player.clone().unwrap(),
@@ -1404,6 +1449,169 @@ fn set_transform<'gc>(
     Ok(())
 }
 
+fn filters<'gc>(
+    this: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let filters = this.filters().to_vec();
+    let mut objects = Vec::with_capacity(filters.len());
+    for filter in &filters {
+        if let Some(object) = bitmap_filter::bitmap_filter_to_object(activation, filter)? {
+            objects.push(object);
+        }
+    }
+    Ok(ArrayObject::new(
+        activation.context.gc_context,
+        activation.context.avm1.prototypes().array,
+        objects,
+    )
+    .into())
+}
+
+fn set_filters<'gc>(
+    this: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let mut filters = Vec::new();
+    if let Value::Object(array) = value {
+        for i in 0..array.length(activation)? {
+            if let Value::Object(filter) = array.get_element(activation, i) {
+                if let Some(filter) = bitmap_filter::object_to_bitmap_filter(filter) {
+                    filters.push(filter);
+                }
+            }
+        }
+    }
+    this.set_filters(activation.context.gc_context, filters);
+    Ok(())
+}
+
+fn cache_as_bitmap<'gc>(
+    this: MovieClip<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.cache_as_bitmap().into())
+}
+
+fn set_cache_as_bitmap<'gc>(
+    this: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let cache_as_bitmap = value.as_bool(activation.swf_version());
+    this.set_cache_as_bitmap(activation.context.gc_context, cache_as_bitmap);
+    Ok(())
+}
+
+fn blend_mode<'gc>(
+    this: MovieClip<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(bitmap_data::blend_mode_name(this.blend_mode()).into())
+}
+
+fn set_blend_mode<'gc>(
+    this: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    // Blend modes can be given by name or by number. Unknown ones are ignored.
+    let blend_mode = match value {
+        Value::Number(mode) => BlendMode::from_u8(mode as u8),
+        value => bitmap_data::blend_mode_from_name(&value.coerce_to_string(activation)?),
+    };
+    if let Some(blend_mode) = blend_mode {
+        this.set_blend_mode(activation.context.gc_context, blend_mode);
+    }
+    Ok(())
+}
+
+fn scroll_rect<'gc>(
+    this: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    match this.scroll_rect() {
+        Some(scroll_rect) => bounding_box_to_rectangle(activation, &scroll_rect),
+        None => Ok(Value::Undefined),
+    }
+}
+
+fn set_scroll_rect<'gc>(
+    this: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let scroll_rect = if let Value::Object(rectangle) = value {
+        Some(rectangle_to_bounding_box(activation, rectangle)?)
+    } else {
+        None
+    };
+    this.set_scroll_rect(activation.context.gc_context, scroll_rect);
+    Ok(())
+}
+
+fn scale_nine_grid<'gc>(
+    this: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    match this.scaling_grid() {
+        Some(scaling_grid) => bounding_box_to_rectangle(activation, &scaling_grid),
+        None => Ok(Value::Undefined),
+    }
+}
+
+fn set_scale_nine_grid<'gc>(
+    this: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let scaling_grid = if let Value::Object(rectangle) = value {
+        Some(rectangle_to_bounding_box(activation, rectangle)?)
+    } else {
+        None
+    };
+    this.set_scaling_grid(activation.context.gc_context, scaling_grid);
+    Ok(())
+}
+
+fn bounding_box_to_rectangle<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    bounds: &BoundingBox,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let constructor = activation.context.avm1.prototypes.rectangle_constructor;
+    constructor.construct(
+        activation,
+        &[
+            bounds.x_min.to_pixels().into(),
+            bounds.y_min.to_pixels().into(),
+            bounds.width().to_pixels().into(),
+            bounds.height().to_pixels().into(),
+        ],
+    )
+}
+
+fn rectangle_to_bounding_box<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    rectangle: Object<'gc>,
+) -> Result<BoundingBox, Error<'gc>> {
+    let x = rectangle.get("x", activation)?.coerce_to_f64(activation)?;
+    let y = rectangle.get("y", activation)?.coerce_to_f64(activation)?;
+    let width = rectangle
+        .get("width", activation)?
+        .coerce_to_f64(activation)?;
+    let height = rectangle
+        .get("height", activation)?
+        .coerce_to_f64(activation)?;
+    Ok(BoundingBox {
+        x_min: Twips::from_pixels(x),
+        y_min: Twips::from_pixels(y),
+        x_max: Twips::from_pixels(x + width),
+        y_max: Twips::from_pixels(y + height),
+        valid: true,
+    })
+}
+
 fn enabled<'gc>(
     this: MovieClip<'gc>,
     _activation: &mut Activation<'_, 'gc, '_>,
@@ -0,0 +1,189 @@
+//! AVM1 NetConnection object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::property::Attribute;
+use crate::avm1::property_decl::{define_properties_on, Declaration};
+use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::remoting::{self, NetConnectionObject, NetConnections, AMF0_VERSION};
+use gc_arena::MutationContext;
+
+const PROTO_DECLS: &[Declaration] = declare_properties! {
+    "connect" => method(connect; DONT_ENUM | DONT_DELETE);
+    "call" => method(call; DONT_ENUM | DONT_DELETE);
+    "close" => method(close; DONT_ENUM | DONT_DELETE);
+    "addHeader" => method(add_header; DONT_ENUM | DONT_DELETE);
+};
+
+/// Implements `NetConnection`
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let object = ScriptObject::object(gc_context, Some(proto));
+    define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
+    object.into()
+}
+
+/// Update the `isConnected` and `uri` properties of a connection.
+fn set_connection_properties<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    uri: Value<'gc>,
+) {
+    let gc_context = activation.context.gc_context;
+    let connected = activation
+        .context
+        .net_connections
+        .is_connected(NetConnectionObject::Avm1(this));
+    this.define_value(
+        gc_context,
+        "isConnected",
+        connected.into(),
+        Attribute::DONT_DELETE | Attribute::READ_ONLY,
+    );
+    this.define_value(
+        gc_context,
+        "uri",
+        uri,
+        Attribute::DONT_DELETE | Attribute::READ_ONLY,
+    );
+}
+
+/// Only `null`, which makes a local connection, and HTTP gateways for Flash
+/// Remoting are supported.
+fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let object = NetConnectionObject::Avm1(this);
+    match args.get(0).cloned().unwrap_or(Value::Null) {
+        Value::Null | Value::Undefined => {
+            activation.context.net_connections.connect(object, None);
+            set_connection_properties(activation, this, Value::Null);
+
+            let info = remoting::avm1_net_status_info(
+                activation,
+                "NetConnection.Connect.Success",
+                "status",
+                None,
+            );
+            this.call_method("onStatus", &[info.into()], activation)?;
+            Ok(true.into())
+        }
+        uri => {
+            let uri = uri.coerce_to_string(activation)?;
+            let lowercase = uri.to_ascii_lowercase();
+            if lowercase.starts_with("http://") || lowercase.starts_with("https://") {
+                // Gateways are only contacted when a call is made.
+                activation
+                    .context
+                    .net_connections
+                    .connect(object, Some(uri.to_string()));
+                set_connection_properties(activation, this, uri.into());
+                Ok(true.into())
+            } else {
+                log::warn!("NetConnection.connect: unsupported URL {}", uri);
+                activation.context.net_connections.close(object);
+                set_connection_properties(activation, this, uri.into());
+
+                let info = remoting::avm1_net_status_info(
+                    activation,
+                    "NetConnection.Connect.Failed",
+                    "error",
+                    None,
+                );
+                this.call_method("onStatus", &[info.into()], activation)?;
+                Ok(false.into())
+            }
+        }
+    }
+}
+
+fn call<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let command = match args.get(0) {
+        Some(command) => command.coerce_to_string(activation)?.to_string(),
+        None => return Ok(Value::Undefined),
+    };
+    let responder = match args.get(1) {
+        Some(Value::Object(responder)) => Some(NetConnectionObject::Avm1(*responder)),
+        _ => None,
+    };
+    let amf_args = args
+        .get(2..)
+        .unwrap_or_default()
+        .iter()
+        .map(|arg| remoting::avm1_to_amf(activation, *arg))
+        .collect();
+
+    NetConnections::call(
+        &mut activation.context,
+        NetConnectionObject::Avm1(this),
+        responder,
+        &command,
+        amf_args,
+        AMF0_VERSION,
+    );
+
+    Ok(Value::Undefined)
+}
+
+fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let object = NetConnectionObject::Avm1(this);
+    if activation.context.net_connections.close(object) {
+        set_connection_properties(activation, this, Value::Undefined);
+
+        let info = remoting::avm1_net_status_info(
+            activation,
+            "NetConnection.Connect.Closed",
+            "status",
+            None,
+        );
+        this.call_method("onStatus", &[info.into()], activation)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn add_header<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = match args.get(0) {
+        Some(name) => name.coerce_to_string(activation)?.to_string(),
+        None => return Ok(Value::Undefined),
+    };
+    let must_understand = args
+        .get(1)
+        .map(|value| value.as_bool(activation.swf_version()))
+        .unwrap_or(false);
+    let value = remoting::avm1_to_amf(activation, args.get(2).cloned().unwrap_or(Value::Null));
+
+    activation.context.net_connections.add_header(
+        NetConnectionObject::Avm1(this),
+        name,
+        must_understand,
+        value,
+    );
+
+    Ok(Value::Undefined)
+}
@@ -4,6 +4,7 @@ use crate::avm1::globals::as_broadcaster::BroadcasterFunctions;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{Object, ScriptObject, TDisplayObject, TObject, Value};
 use crate::display_object::{EditText, TextSelection};
+use crate::string::AvmString;
 use gc_arena::MutationContext;
 
 const OBJECT_DECLS: &[Declaration] = declare_properties! {
@@ -107,11 +108,21 @@ pub fn get_focus<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
+    // The focus is given as a target path. Text fields without an instance
+    // name are given by their variable name instead.
     let focus = activation.context.focus_tracker.get();
-    match focus {
-        Some(focus) => Ok(focus.object()),
-        None => Ok(Value::Null),
-    }
+    let path = match focus {
+        Some(focus) => match focus.as_edit_text() {
+            Some(text) if focus.name().is_empty() => match text.variable() {
+                Some(variable) => variable.to_string(),
+                None => focus.path(),
+            },
+            _ => focus.path(),
+        },
+        None => return Ok(Value::Null),
+    };
+
+    Ok(AvmString::new(activation.context.gc_context, path).into())
 }
 
 pub fn set_focus<'gc>(
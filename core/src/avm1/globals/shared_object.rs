@@ -7,6 +7,7 @@ use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{Object, ScriptObject, TObject, Value};
 use crate::avm_warn;
 use crate::display_object::TDisplayObject;
+use crate::shared_object;
 use crate::string::AvmString;
 use flash_lso::types::Value as AmfValue;
 use flash_lso::types::{AMFVersion, Element, Lso};
@@ -304,12 +305,6 @@ pub fn get_local<'gc>(
         .coerce_to_string(activation)?
         .to_string();
 
-    const INVALID_CHARS: &str = "~%&\\;:\"',<>?# ";
-    if name.contains(|c| INVALID_CHARS.contains(c)) {
-        log::error!("SharedObject::get_local: Invalid character in name");
-        return Ok(Value::Null);
-    }
-
     let movie = if let Some(movie) = activation.base_clip().movie() {
         movie
     } else {
@@ -317,87 +312,21 @@ pub fn get_local<'gc>(
         return Ok(Value::Null);
     };
 
-    let mut movie_url = if let Some(url) = movie.url() {
-        if let Ok(url) = url::Url::parse(url) {
-            url
-        } else {
-            log::error!("SharedObject::get_local: Unable to parse movie URL");
-            return Ok(Value::Null);
-        }
-    } else {
-        // No URL (loading local data). Use a dummy URL to allow SharedObjects to work.
-        url::Url::parse("file://localhost").unwrap()
+    let local_path = match args.get(1) {
+        Some(Value::String(local_path)) => Some(*local_path),
+        _ => None,
     };
-    movie_url.set_query(None);
-    movie_url.set_fragment(None);
 
     let secure = args
         .get(2)
         .unwrap_or(&Value::Undefined)
         .as_bool(activation.swf_version());
 
-    // Secure parameter disallows using the shared object from non-HTTPS.
-    if secure && movie_url.scheme() != "https" {
-        log::warn!(
-            "SharedObject.get_local: Tried to load a secure shared object from non-HTTPS origin"
-        );
-        return Ok(Value::Null);
-    }
-
-    // Shared objects are sandboxed per-domain.
-    // By default, they are keyed based on the SWF URL, but the `localHost` parameter can modify this path.
-    let mut movie_path = movie_url.path();
-    // Remove leading/trailing slashes.
-    movie_path = movie_path.strip_prefix('/').unwrap_or(movie_path);
-    movie_path = movie_path.strip_suffix('/').unwrap_or(movie_path);
-
-    let movie_host = if movie_url.scheme() == "file" {
-        // Remove drive letter on Windows (TODO: move this logic into DiskStorageBackend?)
-        if let [_, b':', b'/', ..] = movie_path.as_bytes() {
-            movie_path = &movie_path[3..];
-        }
-        "localhost"
-    } else {
-        movie_url.host_str().unwrap_or_default()
-    };
-
-    let local_path = if let Some(Value::String(local_path)) = args.get(1) {
-        // Empty local path always fails.
-        if local_path.is_empty() {
-            return Ok(Value::Null);
-        }
-
-        // Remove leading/trailing slashes.
-        let mut local_path = local_path.as_str().strip_prefix('/').unwrap_or(local_path);
-        local_path = local_path.strip_suffix('/').unwrap_or(local_path);
-
-        // Verify that local_path is a prefix of the SWF path.
-        if movie_path.starts_with(&local_path)
-            && (local_path.is_empty()
-                || movie_path.len() == local_path.len()
-                || movie_path[local_path.len()..].starts_with('/'))
-        {
-            local_path
-        } else {
-            log::warn!("SharedObject.get_local: localPath parameter does not match SWF path");
-            return Ok(Value::Null);
-        }
-    } else {
-        movie_path
-    };
-
-    // Final SO path: foo.com/folder/game.swf/SOName
-    // SOName may be a path containing slashes. In this case, prefix with # to mimic Flash Player behavior.
-    let prefix = if name.contains('/') { "#" } else { "" };
-    let full_name = format!("{}/{}/{}{}", movie_host, local_path, prefix, name);
-
-    // Avoid any paths with `..` to prevent SWFs from crawling the file system on desktop.
-    // Flash will generally fail to save shared objects with a path component starting with `.`,
-    // so let's disallow them altogether.
-    if full_name.split('/').any(|s| s.starts_with('.')) {
-        log::error!("SharedObject.get_local: Invalid path with .. segments");
-        return Ok(Value::Null);
-    }
+    let full_name =
+        match shared_object::local_name(movie.url(), &name, local_path.as_deref(), secure) {
+            Some(full_name) => full_name,
+            None => return Ok(Value::Null),
+        };
 
     // Check if this is referencing an existing shared object
     if let Some(so) = activation.context.shared_objects.get(&full_name) {
@@ -542,11 +471,11 @@ pub fn connect<'gc>(
     Ok(Value::Undefined)
 }
 
-pub fn flush<'gc>(
+/// Serialize the data of a shared object into a `.sol` file.
+fn serialize_shared_object<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
-) -> Result<Value<'gc>, Error<'gc>> {
+) -> Result<Vec<u8>, Error<'gc>> {
     let data = this.get("data", activation)?.coerce_to_object(activation);
 
     let this_obj = this.as_shared_object().unwrap();
@@ -554,28 +483,36 @@ pub fn flush<'gc>(
 
     let mut elements = Vec::new();
     recursive_serialize(activation, data, &mut elements);
-    let mut lso = Lso::new(
-        elements,
-        &name
-            .split('/')
-            .last()
-            .map(|e| e.to_string())
-            .unwrap_or_else(|| "<unknown>".to_string()),
-        AMFVersion::AMF0,
-    );
+    let mut lso = Lso::new(elements, shared_object::short_name(&name), AMFVersion::AMF0);
+
+    Ok(flash_lso::write::write_to_bytes(&mut lso).unwrap_or_default())
+}
+
+pub fn flush<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let bytes = serialize_shared_object(activation, this)?;
 
-    let bytes = flash_lso::write::write_to_bytes(&mut lso).unwrap_or_default();
+    let min_disk_space = match args.get(0) {
+        Some(min_disk_space) => min_disk_space.coerce_to_f64(activation)?.max(0.0) as usize,
+        None => 0,
+    };
+    if !shared_object::fits_in_storage(&*activation.context.storage, bytes.len(), min_disk_space) {
+        return Ok(false.into());
+    }
 
+    let name = this.as_shared_object().unwrap().get_name();
     Ok(activation.context.storage.put(&name, &bytes).into())
 }
 
 pub fn get_size<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
-    _this: Object<'gc>,
+    this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm_warn!(activation, "SharedObject.getSize() not implemented");
-    Ok(Value::Undefined)
+    Ok(serialize_shared_object(activation, this)?.len().into())
 }
 
 pub fn send<'gc>(
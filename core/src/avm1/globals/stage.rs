@@ -11,6 +11,7 @@ use gc_arena::MutationContext;
 
 const OBJECT_DECLS: &[Declaration] = declare_properties! {
     "align" => property(align, set_align; DONT_ENUM | DONT_DELETE);
+    "displayState" => property(display_state, set_display_state; DONT_ENUM | DONT_DELETE);
     "height" => property(height; DONT_ENUM | DONT_DELETE | READ_ONLY);
     "scaleMode" => property(scale_mode, set_scale_mode; DONT_ENUM | DONT_DELETE);
     "showMenu" => property(show_menu, set_show_menu; DONT_ENUM | DONT_DELETE);
@@ -84,6 +85,37 @@ fn height<'gc>(
     Ok(activation.context.stage.stage_size().1.into())
 }
 
+fn display_state<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let display_state = AvmString::new(
+        activation.context.gc_context,
+        activation.context.stage.display_state().to_string(),
+    );
+    Ok(display_state.into())
+}
+
+fn set_display_state<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Ok(display_state) = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?
+        .parse()
+    {
+        activation
+            .context
+            .stage
+            .set_display_state(&mut activation.context, display_state);
+    }
+    Ok(Value::Undefined)
+}
+
 fn scale_mode<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     _this: Object<'gc>,
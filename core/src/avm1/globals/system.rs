@@ -5,6 +5,7 @@ use crate::avm1::property::Attribute;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{Avm1, ScriptObject, TObject, Value};
 use crate::avm_warn;
+use crate::config::SpoofedCapabilities;
 use bitflags::bitflags;
 use core::fmt;
 use gc_arena::MutationContext;
@@ -38,6 +39,7 @@ impl fmt::Display for CpuArchitecture {
 }
 
 /// Available type of sandbox for a given SWF
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum SandboxType {
     Remote,
     LocalWithFile,
@@ -284,10 +286,16 @@ pub struct SystemProperties {
     pub cpu_architecture: CpuArchitecture,
     /// The highest supported h264 decoder level
     pub idc_level: String,
+    /// The capabilities reported in place of the real ones
+    pub spoofed: SpoofedCapabilities,
 }
 
 impl SystemProperties {
     pub fn get_version_string(&self, avm: &mut Avm1) -> String {
+        if let Some(version) = &self.spoofed.version {
+            return version.clone();
+        }
+
         format!(
             "{} {},0,0,0",
             self.manufacturer.get_platform_name(),
@@ -295,8 +303,45 @@ impl SystemProperties {
         )
     }
 
+    pub fn get_os_name(&self) -> String {
+        match &self.spoofed.os {
+            Some(os) => os.clone(),
+            None => self.os.to_string(),
+        }
+    }
+
+    pub fn get_manufacturer_string(&self, player_version: u8) -> String {
+        match &self.spoofed.manufacturer {
+            Some(manufacturer) => manufacturer.clone(),
+            None => self.manufacturer.get_manufacturer_string(player_version),
+        }
+    }
+
+    pub fn get_player_type(&self) -> String {
+        match &self.spoofed.player_type {
+            Some(player_type) => player_type.clone(),
+            None => self.player_type.to_string(),
+        }
+    }
+
+    pub fn get_language_code(&self, player_version: u8) -> &str {
+        match &self.spoofed.language {
+            Some(language) => language.as_str(),
+            None => self.language.get_language_code(player_version),
+        }
+    }
+
+    pub fn get_screen_resolution(&self) -> (u32, u32) {
+        self.spoofed
+            .screen_resolution
+            .unwrap_or(self.screen_resolution)
+    }
+
     pub fn has_capability(&self, cap: SystemCapabilities) -> bool {
-        self.capabilities.contains(cap)
+        match self.spoofed.is_debugger {
+            Some(is_debugger) if cap == SystemCapabilities::DEBUGGER => is_debugger,
+            _ => self.capabilities.contains(cap),
+        }
     }
 
     fn encode_capability(&self, cap: SystemCapabilities) -> &str {
@@ -320,6 +365,7 @@ impl SystemProperties {
     }
 
     pub fn get_server_string(&self, avm: &mut Avm1) -> String {
+        let resolution = self.get_screen_resolution();
         url::form_urlencoded::Serializer::new(String::new())
             .append_pair("A", self.encode_capability(SystemCapabilities::AUDIO))
             .append_pair(
@@ -359,22 +405,15 @@ impl SystemProperties {
             .append_pair("DEB", self.encode_capability(SystemCapabilities::DEBUGGER))
             .append_pair(
                 "M",
-                &self.encode_string(
-                    self.manufacturer
-                        .get_manufacturer_string(avm.player_version)
-                        .as_str(),
-                ),
-            )
-            .append_pair(
-                "R",
-                &format!("{}x{}", self.screen_resolution.0, self.screen_resolution.1),
+                &self.encode_string(&self.get_manufacturer_string(avm.player_version)),
             )
+            .append_pair("R", &format!("{}x{}", resolution.0, resolution.1))
             .append_pair("COL", &self.screen_color.to_string())
             .append_pair("AR", &self.aspect_ratio.to_string())
-            .append_pair("OS", &self.encode_string(&self.os.to_string()))
-            .append_pair("L", self.language.get_language_code(avm.player_version))
+            .append_pair("OS", &self.encode_string(&self.get_os_name()))
+            .append_pair("L", self.get_language_code(avm.player_version))
             .append_pair("IME", self.encode_capability(SystemCapabilities::IME))
-            .append_pair("PT", &self.player_type.to_string())
+            .append_pair("PT", &self.get_player_type())
             .append_pair(
                 "AVD",
                 self.encode_not_capability(SystemCapabilities::AV_HARDWARE),
@@ -408,6 +447,7 @@ impl Default for SystemProperties {
             sandbox_type: SandboxType::LocalTrusted,
             cpu_architecture: CpuArchitecture::X86,
             idc_level: "5.1".into(),
+            spoofed: SpoofedCapabilities::default(),
         }
     }
 }
@@ -528,3 +568,42 @@ pub fn create<'gc>(
     );
     system.into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spoofed_capabilities_replace_real_ones() {
+        let mut system = SystemProperties {
+            screen_resolution: (800, 600),
+            ..Default::default()
+        };
+        system.capabilities.insert(SystemCapabilities::AUDIO);
+        assert_eq!(system.get_os_name(), "Linux");
+        assert_eq!(system.get_player_type(), "StandAlone");
+        assert_eq!(system.get_language_code(10), "en");
+        assert_eq!(system.get_screen_resolution(), (800, 600));
+        assert!(!system.has_capability(SystemCapabilities::DEBUGGER));
+
+        system.spoofed = SpoofedCapabilities {
+            os: Some("Windows 7".to_string()),
+            manufacturer: Some("Adobe Windows".to_string()),
+            player_type: Some("PlugIn".to_string()),
+            language: Some("zh-CN".to_string()),
+            screen_resolution: Some((1920, 1080)),
+            is_debugger: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(system.get_os_name(), "Windows 7");
+        assert_eq!(system.get_manufacturer_string(10), "Adobe Windows");
+        assert_eq!(system.get_player_type(), "PlugIn");
+        assert_eq!(system.get_language_code(10), "zh-CN");
+        assert_eq!(system.get_screen_resolution(), (1920, 1080));
+        assert!(system.has_capability(SystemCapabilities::DEBUGGER));
+
+        // Only the debugger flag can be spoofed.
+        assert!(system.has_capability(SystemCapabilities::AUDIO));
+        assert!(!system.has_capability(SystemCapabilities::MP3));
+    }
+}
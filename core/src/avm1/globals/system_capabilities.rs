@@ -105,7 +105,7 @@ pub fn get_player_type<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     Ok(AvmString::new(
         activation.context.gc_context,
-        activation.context.system.player_type.to_string(),
+        activation.context.system.get_player_type(),
     )
     .into())
 }
@@ -132,7 +132,6 @@ pub fn get_language<'gc>(
         activation
             .context
             .system
-            .language
             .get_language_code(activation.context.avm1.player_version)
             .to_string(),
     )
@@ -144,7 +143,7 @@ pub fn get_screen_resolution_x<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(activation.context.system.screen_resolution.0.into())
+    Ok(activation.context.system.get_screen_resolution().0.into())
 }
 
 pub fn get_screen_resolution_y<'gc>(
@@ -152,7 +151,7 @@ pub fn get_screen_resolution_y<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(activation.context.system.screen_resolution.1.into())
+    Ok(activation.context.system.get_screen_resolution().1.into())
 }
 
 pub fn get_pixel_aspect_ratio<'gc>(
@@ -181,7 +180,6 @@ pub fn get_manufacturer<'gc>(
         activation
             .context
             .system
-            .manufacturer
             .get_manufacturer_string(activation.context.avm1.player_version),
     )
     .into())
@@ -194,7 +192,7 @@ pub fn get_os_name<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     Ok(AvmString::new(
         activation.context.gc_context,
-        activation.context.system.os.to_string(),
+        activation.context.system.get_os_name(),
     )
     .into())
 }
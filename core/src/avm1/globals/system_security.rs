@@ -21,33 +21,37 @@ const OBJECT_DECLS: &[Declaration] = declare_properties! {
 fn allow_domain<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm_warn!(activation, "System.security.allowDomain() not implemented");
+    for domain in args {
+        let domain = domain.coerce_to_string(activation)?;
+        activation.context.security.allow_domain(&domain);
+    }
+
     Ok(Value::Undefined)
 }
 
 fn allow_insecure_domain<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm_warn!(
-        activation,
-        "System.security.allowInsecureDomain() not implemented"
-    );
-    Ok(Value::Undefined)
+    allow_domain(activation, this, args)
 }
 
 fn load_policy_file<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm_warn!(
-        activation,
-        "System.security.allowInsecureDomain() not implemented"
-    );
+    if let Some(url) = args.get(0) {
+        let url = url.coerce_to_string(activation)?;
+        activation
+            .context
+            .security
+            .load_policy_file(activation.context.swf, &url);
+    }
+
     Ok(Value::Undefined)
 }
 
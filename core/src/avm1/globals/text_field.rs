@@ -70,9 +70,11 @@ const PROTO_DECLS: &[Declaration] = declare_properties! {
     "htmlText" => property(tf_getter!(html_text), tf_setter!(set_html_text); DONT_DELETE);
     "length" => property(tf_getter!(length); DONT_DELETE | READ_ONLY);
     "maxhscroll" => property(tf_getter!(maxhscroll); DONT_DELETE | READ_ONLY);
+    "maxChars" => property(tf_getter!(max_chars), tf_setter!(set_max_chars); DONT_DELETE);
     "maxscroll" => property(tf_getter!(maxscroll); DONT_DELETE | READ_ONLY);
     "multiline" => property(tf_getter!(multiline), tf_setter!(set_multiline); DONT_DELETE);
     "password" => property(tf_getter!(password), tf_setter!(set_password); DONT_DELETE);
+    "restrict" => property(tf_getter!(restrict), tf_setter!(set_restrict); DONT_DELETE);
     "scroll" => property(tf_getter!(scroll), tf_setter!(set_scroll); DONT_DELETE);
     "selectable" => property(tf_getter!(selectable), tf_setter!(set_selectable); DONT_DELETE);
     "text" => property(tf_getter!(text), tf_setter!(set_text); DONT_DELETE);
@@ -122,6 +124,54 @@ pub fn set_password<'gc>(
     Ok(())
 }
 
+pub fn max_chars<'gc>(
+    this: EditText<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    match this.max_chars() {
+        0 => Ok(Value::Null),
+        max_chars => Ok(max_chars.into()),
+    }
+}
+
+pub fn set_max_chars<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let max_chars = match value {
+        Value::Undefined | Value::Null => 0,
+        value => value.coerce_to_i32(activation)?,
+    };
+    this.set_max_chars(max_chars, &mut activation.context);
+    Ok(())
+}
+
+pub fn restrict<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    match this.restrict() {
+        Some(restrict) => Ok(AvmString::new(activation.context.gc_context, restrict).into()),
+        None => Ok(Value::Null),
+    }
+}
+
+pub fn set_restrict<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    match value {
+        Value::Undefined | Value::Null => this.set_restrict(None, &mut activation.context),
+        value => {
+            let restrict = value.coerce_to_string(activation)?;
+            this.set_restrict(Some(restrict.as_str()), &mut activation.context);
+        }
+    }
+    Ok(())
+}
+
 fn get_new_text_format<'gc>(
     text_field: EditText<'gc>,
     activation: &mut Activation<'_, 'gc, '_>,
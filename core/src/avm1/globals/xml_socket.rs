@@ -0,0 +1,121 @@
+//! AVM1 XMLSocket object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::property_decl::{define_properties_on, Declaration};
+use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::socket::{SocketTarget, Sockets};
+use gc_arena::MutationContext;
+use std::time::Duration;
+use url::Url;
+
+/// How long a connection attempt may take before it fails.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(20);
+
+const PROTO_DECLS: &[Declaration] = declare_properties! {
+    "connect" => method(connect; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "send" => method(send; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "close" => method(close; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "onData" => method(on_data; DONT_ENUM | DONT_DELETE | READ_ONLY);
+};
+
+/// Implements `XMLSocket`
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let object = ScriptObject::object(gc_context, Some(proto));
+    define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
+    object.into()
+}
+
+fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // Without a host, Flash connects to the server the movie came from.
+    let host = match args.get(0).unwrap_or(&Value::Undefined) {
+        Value::Null | Value::Undefined => activation
+            .context
+            .swf
+            .url()
+            .and_then(|url| Url::parse(url).ok())
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_else(|| "localhost".to_string()),
+        host => host.coerce_to_string(activation)?.to_string(),
+    };
+
+    // AVM1 sockets can't connect to the well-known ports.
+    let port = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(activation)?;
+    if !(1024..=65535).contains(&port) {
+        return Ok(false.into());
+    }
+
+    Sockets::connect(
+        &mut activation.context,
+        SocketTarget::Avm1XmlSocket(this),
+        &host,
+        port as u16,
+        CONNECT_TIMEOUT,
+    );
+
+    Ok(true.into())
+}
+
+fn send<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(message) = args.get(0) {
+        // Messages are terminated by a null byte.
+        let mut data = message.coerce_to_string(activation)?.as_bytes().to_vec();
+        data.push(0);
+
+        Sockets::send(
+            &mut activation.context,
+            SocketTarget::Avm1XmlSocket(this),
+            data,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Sockets::close(&mut activation.context, SocketTarget::Avm1XmlSocket(this));
+
+    Ok(Value::Undefined)
+}
+
+fn on_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // Default implementation parses the message and forwards it to onXML.
+    let source = args.get(0).cloned().unwrap_or(Value::Undefined);
+    let xml_constructor = activation.context.avm1.prototypes().xml_constructor;
+    let xml = xml_constructor.construct(activation, &[source])?;
+
+    this.call_method("onXML", &[xml], activation)?;
+
+    Ok(Value::Undefined)
+}
@@ -4,7 +4,9 @@ use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::property::Attribute;
 use crate::avm1::property_map::PropertyMap;
-use crate::avm1::{Object, ObjectPtr, ScriptObject, TDisplayObject, TObject, Value};
+use crate::avm1::{
+    CaseSensitivity, Object, ObjectPtr, ScriptObject, TDisplayObject, TObject, Value,
+};
 use crate::avm_warn;
 use crate::context::UpdateContext;
 use crate::display_object::{DisplayObject, EditText, MovieClip, TDisplayObjectContainer};
@@ -118,23 +120,16 @@ impl<'gc> StageObject<'gc> {
     fn get_level_by_path(
         name: &str,
         context: &mut UpdateContext<'_, 'gc, '_>,
-        case_sensitive: bool,
+        case_sensitivity: CaseSensitivity,
     ) -> Option<Value<'gc>> {
-        if let Some(slice) = name.get(0..name.len().min(6)) {
-            let is_level = if case_sensitive {
-                slice == "_level"
-            } else {
-                slice.eq_ignore_ascii_case("_level")
-            };
-            if is_level {
-                if let Some(level_id) = name.get(6..).and_then(|v| v.parse::<i32>().ok()) {
-                    let level = context
-                        .stage
-                        .child_by_depth(level_id)
-                        .map(|o| o.object())
-                        .unwrap_or(Value::Undefined);
-                    return Some(level);
-                }
+        if case_sensitivity.starts_with(name, "_level") {
+            if let Some(level_id) = name.get(6..).and_then(|v| v.parse::<i32>().ok()) {
+                let level = context
+                    .stage
+                    .child_by_depth(level_id)
+                    .map(|o| o.object())
+                    .unwrap_or(Value::Undefined);
+                return Some(level);
             }
         }
 
@@ -168,13 +163,14 @@ impl<'gc> TObject<'gc> for StageObject<'gc> {
     ) -> Option<Value<'gc>> {
         let obj = self.0.read();
         let props = activation.context.avm1.display_properties;
-        let case_sensitive = activation.is_case_sensitive();
+        let case_sensitivity = activation.case_sensitivity();
+        let case_sensitive = case_sensitivity.is_sensitive();
         // Property search order for DisplayObjects:
         if self.has_own_property(activation, name) {
             // 1) Actual properties on the underlying object
             obj.base.get_local_stored(name, activation)
         } else if let Some(level) =
-            Self::get_level_by_path(name, &mut activation.context, case_sensitive)
+            Self::get_level_by_path(name, &mut activation.context, case_sensitivity)
         {
             // 2) _levelN
             Some(level)
@@ -376,17 +372,17 @@ impl<'gc> TObject<'gc> for StageObject<'gc> {
             return true;
         }
 
-        let case_sensitive = activation.is_case_sensitive();
+        let case_sensitivity = activation.case_sensitivity();
         if obj
             .display_object
             .as_container()
-            .and_then(|o| o.child_by_name(name, case_sensitive))
+            .and_then(|o| o.child_by_name(name, case_sensitivity.is_sensitive()))
             .is_some()
         {
             return true;
         }
 
-        if Self::get_level_by_path(name, &mut activation.context, case_sensitive).is_some() {
+        if Self::get_level_by_path(name, &mut activation.context, case_sensitivity).is_some() {
             return true;
         }
 
@@ -787,7 +783,7 @@ fn set_name<'gc>(
     val: Value<'gc>,
 ) -> Result<(), Error<'gc>> {
     let name = val.coerce_to_string(activation)?;
-    this.set_name(activation.context.gc_context, &name);
+    this.set_name(&mut activation.context, &name);
     Ok(())
 }
 
@@ -0,0 +1,133 @@
+//! Cache of resolved target paths.
+//!
+//! Old movies often address clips by path strings such as
+//! `"_root.game.player.arm"` every frame, and resolving each of them walks
+//! the display list one name at a time. Paths that resolved purely through
+//! the display list are remembered here until the display list changes.
+
+use crate::avm1::{Object, TObject};
+use crate::display_object::{DisplayObject, TDisplayObject};
+use gc_arena::Collect;
+use std::collections::HashMap;
+
+/// The most paths that are remembered at once.
+///
+/// Paths are usually built from a handful of literals, so this is only
+/// reached by movies that build them from changing values. The cache is
+/// simply emptied when it fills up.
+const MAX_ENTRIES: usize = 1024;
+
+#[derive(Collect, Clone, PartialEq, Eq, Hash)]
+#[collect(require_static)]
+struct Key {
+    /// The address of the root clip that absolute paths start from.
+    root: usize,
+
+    /// The address of the object the path starts from.
+    start: usize,
+
+    path: String,
+    case_sensitive: bool,
+
+    /// Whether `this` and `_root` could start the path.
+    first_element: bool,
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+struct Entry<'gc> {
+    /// The root and start objects are held on to so that their addresses
+    /// can't be reused by other objects while the entry exists.
+    root: DisplayObject<'gc>,
+    start: Object<'gc>,
+
+    target: Object<'gc>,
+}
+
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct TargetPathCache<'gc> {
+    /// The display list generation that the entries were resolved in.
+    generation: u64,
+
+    entries: HashMap<Key, Entry<'gc>>,
+}
+
+impl<'gc> TargetPathCache<'gc> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the object a path resolved to, if the display list hasn't changed
+    /// since.
+    ///
+    /// `generation` is the player's current display list generation.
+    pub fn get(
+        &mut self,
+        generation: u64,
+        root: DisplayObject<'gc>,
+        start: Object<'gc>,
+        path: &str,
+        case_sensitive: bool,
+        first_element: bool,
+    ) -> Option<Object<'gc>> {
+        self.invalidate_if_stale(generation);
+        let key = Self::key(root, start, path, case_sensitive, first_element);
+        self.entries.get(&key).map(|entry| entry.target)
+    }
+
+    /// Remember the object a path resolved to.
+    ///
+    /// This must only be used for paths that were resolved entirely through
+    /// the display list. `generation` is the display list generation from
+    /// before the path was resolved, so that the entry is dropped if the
+    /// display list changed while resolving it.
+    pub fn insert(
+        &mut self,
+        generation: u64,
+        root: DisplayObject<'gc>,
+        start: Object<'gc>,
+        path: &str,
+        case_sensitive: bool,
+        first_element: bool,
+        target: Object<'gc>,
+    ) {
+        self.invalidate_if_stale(generation);
+        if self.entries.len() >= MAX_ENTRIES {
+            self.entries.clear();
+        }
+
+        let key = Self::key(root, start, path, case_sensitive, first_element);
+        self.entries.insert(
+            key,
+            Entry {
+                root,
+                start,
+                target,
+            },
+        );
+    }
+
+    fn invalidate_if_stale(&mut self, generation: u64) {
+        if self.generation != generation {
+            self.entries.clear();
+            self.generation = generation;
+        }
+    }
+
+    fn key(
+        root: DisplayObject<'gc>,
+        start: Object<'gc>,
+        path: &str,
+        case_sensitive: bool,
+        first_element: bool,
+    ) -> Key {
+        Key {
+            root: root.as_ptr() as usize,
+            start: start.as_ptr() as usize,
+            path: path.to_string(),
+            case_sensitive,
+            first_element,
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use crate::avm1::activation::{Activation, ActivationIdentifier};
 use crate::avm1::error::Error;
 use crate::avm1::globals::system::SystemProperties;
-use crate::avm1::{Avm1, Object, Timers, UpdateContext};
+use crate::avm1::{Avm1, Object, UpdateContext};
 use crate::avm2::Avm2;
 use crate::backend::audio::{AudioManager, NullAudioBackend};
 use crate::backend::locale::NullLocaleBackend;
@@ -12,12 +12,20 @@ use crate::backend::storage::MemoryStorageBackend;
 use crate::backend::ui::NullUiBackend;
 use crate::backend::video::NullVideoBackend;
 use crate::context::ActionQueue;
+use crate::cursor::MouseCursors;
 use crate::display_object::{MovieClip, Stage, TDisplayObject};
 use crate::focus_tracker::FocusTracker;
+use crate::game_input::GameInput;
 use crate::library::Library;
 use crate::loader::LoadManager;
+use crate::local_connection::LocalConnections;
+use crate::multitouch::Multitouch;
 use crate::prelude::*;
+use crate::remoting::NetConnections;
+use crate::security::Security;
+use crate::socket::Sockets;
 use crate::tag_utils::{SwfMovie, SwfSlice};
+use crate::timer::Timers;
 use crate::vminterface::Instantiator;
 use gc_arena::{rootless_arena, MutationContext};
 use instant::Instant;
@@ -66,11 +74,20 @@ where
             player: None,
             load_manager: &mut LoadManager::new(),
             system: &mut SystemProperties::default(),
+            security: &mut Security::new(),
+            mouse_cursors: &mut MouseCursors::new(),
+            multitouch: &mut Multitouch::new(),
+            game_input: &mut GameInput::new(),
             instance_counter: &mut 0,
+            display_list_generation: &mut 0,
             storage: &mut MemoryStorageBackend::default(),
             shared_objects: &mut HashMap::new(),
+            avm2_shared_objects: &mut HashMap::new(),
             unbound_text_fields: &mut Vec::new(),
             timers: &mut Timers::new(),
+            sockets: &mut Sockets::new(),
+            local_connections: &mut LocalConnections::new(),
+            net_connections: &mut NetConnections::default(),
             current_context_menu: &mut None,
             needs_render: &mut false,
             avm1: &mut avm1,
@@ -87,7 +104,7 @@ where
         context.stage.replace_at_depth(&mut context, root, 0);
 
         root.post_instantiation(&mut context, root, None, Instantiator::Movie, false);
-        root.set_name(context.gc_context, "");
+        root.set_name(&mut context, "");
 
         fn run_test<'a, 'gc: 'a, F>(
             activation: &mut Activation<'_, 'gc, '_>,
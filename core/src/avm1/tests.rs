@@ -1,6 +1,11 @@
+use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::test_utils::with_avm;
 use crate::avm1::TObject;
+use crate::display_object::MovieClip;
+use crate::prelude::*;
+use crate::tag_utils::SwfSlice;
+use crate::vminterface::Instantiator;
 
 #[test]
 fn locals_into_form_values() {
@@ -23,3 +28,65 @@ fn locals_into_form_values() {
         Ok(())
     });
 }
+
+/// Creates an empty clip in `parent`, as `createEmptyMovieClip` does.
+fn create_clip<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    parent: MovieClip<'gc>,
+    name: &str,
+    depth: Depth,
+) -> MovieClip<'gc> {
+    let movie = activation.base_clip().movie().unwrap();
+    let clip = MovieClip::new(SwfSlice::empty(movie), activation.context.gc_context);
+    clip.set_name(&mut activation.context, name);
+    parent.replace_at_depth(&mut activation.context, clip.into(), depth);
+    clip.post_instantiation(
+        &mut activation.context,
+        clip.into(),
+        None,
+        Instantiator::Avm1,
+        true,
+    );
+    clip
+}
+
+/// Resolves a target path from the root clip, returning the path of the clip
+/// that it finds.
+fn resolve_path<'gc>(activation: &mut Activation<'_, 'gc, '_>, path: &str) -> Option<String> {
+    let root = activation.base_clip();
+    let start = root.object().coerce_to_object(activation);
+    activation
+        .resolve_target_path(root, start, path, true)
+        .unwrap()
+        .and_then(|object| object.as_display_object())
+        .map(|clip| clip.path())
+}
+
+#[test]
+fn target_paths_follow_display_list_changes() {
+    with_avm(19, |activation, _this| -> Result<(), Error> {
+        let root = activation.base_clip().as_movie_clip().unwrap();
+        let a = create_clip(activation, root, "a", 1);
+        let mut b = create_clip(activation, root, "b", 2);
+        let child = create_clip(activation, a, "child", 1);
+
+        // Resolve each path twice, so that the second lookup comes from the cache.
+        for _ in 0..2 {
+            assert_eq!(resolve_path(activation, "a.child"), Some(child.path()));
+        }
+
+        child.set_name(&mut activation.context, "renamed");
+        assert_eq!(resolve_path(activation, "a.child"), None);
+        assert_eq!(resolve_path(activation, "a.renamed"), Some(child.path()));
+
+        b.insert_at_index(&mut activation.context, child.into(), 0);
+        assert_eq!(resolve_path(activation, "a.renamed"), None);
+        assert_eq!(resolve_path(activation, "b.renamed"), Some(child.path()));
+        assert!(child.path().ends_with("b.renamed"));
+
+        b.remove_child(&mut activation.context, child.into(), Lists::all());
+        assert_eq!(resolve_path(activation, "b.renamed"), None);
+
+        Ok(())
+    });
+}
@@ -4,12 +4,15 @@ use crate::avm2::globals::{SystemClasses, SystemPrototypes};
 use crate::avm2::method::Method;
 use crate::avm2::object::EventObject;
 use crate::avm2::script::{Script, TranslationUnit};
+use crate::avm2::string::SmallStrings;
+use crate::avm2::worker::{WorkerContext, WorkerRegistry};
 use crate::context::UpdateContext;
 use crate::string::AvmString;
-use crate::tag_utils::SwfSlice;
-use gc_arena::{Collect, MutationContext};
+use crate::tag_utils::{SwfMovie, SwfSlice};
+use gc_arena::{Collect, GcCell, MutationContext};
 use std::collections::HashMap;
 use std::rc::Rc;
+use std::sync::Arc;
 use swf::avm2::read::Reader;
 
 #[macro_export]
@@ -22,6 +25,7 @@ macro_rules! avm_debug {
 }
 
 mod activation;
+mod amf;
 mod array;
 mod bytearray;
 mod class;
@@ -29,10 +33,13 @@ mod domain;
 mod events;
 mod function;
 mod globals;
+#[cfg(feature = "avm2_jit")]
+mod jit;
 mod method;
 mod names;
 mod object;
 mod property;
+mod property_cache;
 mod property_map;
 mod regexp;
 mod return_value;
@@ -43,16 +50,31 @@ mod string;
 mod traits;
 mod value;
 mod vector;
+mod verify;
+mod worker;
 
 pub use crate::avm2::activation::Activation;
+pub use crate::avm2::amf::{Amf3Reader, Amf3Writer};
 pub use crate::avm2::array::ArrayStorage;
 pub use crate::avm2::domain::Domain;
 pub use crate::avm2::events::Event;
+pub use crate::avm2::globals::{
+    game_input_control_changed, game_input_device_added, game_input_device_removed,
+    ime_dispatch_composition, make_avm2_context_menu_state, responder_callback,
+};
 pub use crate::avm2::names::{Namespace, QName};
-pub use crate::avm2::object::{ArrayObject, Object, ScriptObject, StageObject, TObject};
+pub use crate::avm2::object::{
+    ArrayObject, LoaderStream, Object, ScriptObject, StageObject, TObject,
+};
 pub use crate::avm2::value::Value;
 
-const BROADCAST_WHITELIST: [&str; 3] = ["enterFrame", "exitFrame", "frameConstructed"];
+const BROADCAST_WHITELIST: [&str; 5] = [
+    "enterFrame",
+    "exitFrame",
+    "frameConstructed",
+    "activate",
+    "deactivate",
+];
 
 /// Boxed error alias.
 ///
@@ -86,6 +108,23 @@ pub struct Avm2<'gc> {
     /// collector does not support weak references.
     broadcast_list: HashMap<AvmString<'gc>, Vec<Object<'gc>>>,
 
+    /// Classes registered with `flash.net.registerClassAlias`, by alias.
+    ///
+    /// Instances of these classes are serialized along with their alias, so
+    /// that they can be reconstructed as instances of the same class.
+    class_aliases: HashMap<AvmString<'gc>, Object<'gc>>,
+
+    /// Preallocated strings that builtins can return without allocating.
+    small_strings: SmallStrings<'gc>,
+
+    /// The text being pasted, while a `paste` event is dispatched.
+    ///
+    /// The general clipboard can only be read by movies at this time.
+    pasted_text: Option<String>,
+
+    /// The worker that this VM runs.
+    worker: WorkerContext<'gc>,
+
     #[cfg(feature = "avm_debug")]
     pub debug_output: bool,
 }
@@ -93,6 +132,19 @@ pub struct Avm2<'gc> {
 impl<'gc> Avm2<'gc> {
     /// Construct a new AVM interpreter.
     pub fn new(mc: MutationContext<'gc, '_>) -> Self {
+        Self::with_worker(mc, WorkerContext::primordial(mc))
+    }
+
+    /// Construct the AVM interpreter of a background worker.
+    fn for_worker(
+        mc: MutationContext<'gc, '_>,
+        registry: GcCell<'gc, WorkerRegistry<'gc>>,
+        id: u32,
+    ) -> Self {
+        Self::with_worker(mc, WorkerContext::background(registry, id))
+    }
+
+    fn with_worker(mc: MutationContext<'gc, '_>, worker: WorkerContext<'gc>) -> Self {
         let globals = Domain::global_domain(mc);
 
         Self {
@@ -101,6 +153,10 @@ impl<'gc> Avm2<'gc> {
             system_prototypes: None,
             system_classes: None,
             broadcast_list: HashMap::new(),
+            class_aliases: HashMap::new(),
+            small_strings: SmallStrings::new(mc),
+            pasted_text: None,
+            worker,
 
             #[cfg(feature = "avm_debug")]
             debug_output: false,
@@ -113,6 +169,11 @@ impl<'gc> Avm2<'gc> {
         globals::load_player_globals(&mut activation, globals)
     }
 
+    /// Return the preallocated strings for commonly used values.
+    pub fn small_strings(&self) -> &SmallStrings<'gc> {
+        &self.small_strings
+    }
+
     /// Return the current set of system prototypes.
     ///
     /// This function panics if the interpreter has not yet been initialized.
@@ -127,6 +188,59 @@ impl<'gc> Avm2<'gc> {
         self.system_classes.as_ref().unwrap()
     }
 
+    /// The text being pasted, if a `paste` event is being dispatched.
+    pub fn pasted_text(&self) -> Option<&str> {
+        self.pasted_text.as_deref()
+    }
+
+    pub fn set_pasted_text(&mut self, text: Option<String>) {
+        self.pasted_text = text;
+    }
+
+    /// The worker that this VM runs.
+    pub fn worker(&self) -> &WorkerContext<'gc> {
+        &self.worker
+    }
+
+    pub fn worker_mut(&mut self) -> &mut WorkerContext<'gc> {
+        &mut self.worker
+    }
+
+    /// Bring up any background workers that were started during this frame,
+    /// and deliver the events that were sent between workers.
+    pub fn run_workers(context: &mut UpdateContext<'_, 'gc, '_>) {
+        worker::run_workers(context)
+    }
+
+    /// Run code with the VM of the worker with the given ID in the update
+    /// context. Returns `None` if that worker is not running.
+    pub fn run_in_worker<R>(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        id: u32,
+        f: impl FnOnce(&mut UpdateContext<'_, 'gc, '_>) -> R,
+    ) -> Option<R> {
+        worker::run_in_worker(context, id, f)
+    }
+
+    /// Register an alias for a class, to be used when serializing instances
+    /// of it.
+    pub fn register_class_alias(&mut self, alias: AvmString<'gc>, class: Object<'gc>) {
+        self.class_aliases.insert(alias, class);
+    }
+
+    /// Look up the class registered under a given alias.
+    pub fn class_for_alias(&self, alias: AvmString<'gc>) -> Option<Object<'gc>> {
+        self.class_aliases.get(&alias).copied()
+    }
+
+    /// Look up the alias a given class was registered under.
+    pub fn alias_for_class(&self, class: Object<'gc>) -> Option<AvmString<'gc>> {
+        self.class_aliases
+            .iter()
+            .find(|(_, c)| Object::ptr_eq(**c, class))
+            .map(|(alias, _)| *alias)
+    }
+
     /// Run a script's initializer method.
     pub fn run_script_initializer(
         script: Script<'gc>,
@@ -151,6 +265,12 @@ impl<'gc> Avm2<'gc> {
         Ok(())
     }
 
+    /// Write every shared object opened by AVM2 movies to storage.
+    pub fn flush_shared_objects(context: &mut UpdateContext<'_, 'gc, '_>) {
+        let mut activation = Activation::from_nothing(context.reborrow());
+        globals::flush_shared_objects(&mut activation);
+    }
+
     /// Dispatch an event on an object.
     ///
     /// The `bool` parameter reads true if the event was cancelled.
@@ -158,10 +278,25 @@ impl<'gc> Avm2<'gc> {
         context: &mut UpdateContext<'_, 'gc, '_>,
         event: Event<'gc>,
         target: Object<'gc>,
+    ) -> Result<bool, Error> {
+        let event_constr = context.avm2.classes().event;
+
+        Self::dispatch_event_of_class(context, event, event_constr, target)
+    }
+
+    /// Dispatch an event on an object, as an instance of a given `Event`
+    /// subclass.
+    ///
+    /// The event will be dispatched through the capture, target, and bubble
+    /// phases along the target's display list ancestry.
+    pub fn dispatch_event_of_class(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        event: Event<'gc>,
+        event_constr: Object<'gc>,
+        target: Object<'gc>,
     ) -> Result<bool, Error> {
         use crate::avm2::events::dispatch_event;
 
-        let event_constr = context.avm2.classes().event;
         let mut activation = Activation::from_nothing(context.reborrow());
 
         let event_object = EventObject::from_event(&mut activation, event_constr, event)?;
@@ -169,6 +304,105 @@ impl<'gc> Avm2<'gc> {
         dispatch_event(&mut activation, target, event_object)
     }
 
+    /// Dispatch an already-constructed event object on an object.
+    ///
+    /// The `bool` parameter reads true if the event was not cancelled.
+    pub fn dispatch_event_object(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        event: Object<'gc>,
+        target: Object<'gc>,
+    ) -> Result<bool, Error> {
+        use crate::avm2::events::dispatch_event;
+
+        let mut activation = Activation::from_nothing(context.reborrow());
+
+        dispatch_event(&mut activation, target, event)
+    }
+
+    /// Get the `UncaughtErrorEvents` dispatcher of a movie's `LoaderInfo`,
+    /// creating it if it doesn't exist yet.
+    pub fn uncaught_error_events(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        movie: Arc<SwfMovie>,
+    ) -> Result<Object<'gc>, Error> {
+        let library = context.library.library_for_movie_mut(movie.clone());
+        if let Some(uncaught_error_events) = library.avm2_uncaught_error_events() {
+            return Ok(uncaught_error_events);
+        }
+
+        let mut activation = Activation::from_nothing(context.reborrow());
+        let uncaught_error_events_class = activation.avm2().classes().uncaughterrorevents;
+        let uncaught_error_events = uncaught_error_events_class.construct(&mut activation, &[])?;
+        drop(activation);
+
+        context
+            .library
+            .library_for_movie_mut(movie)
+            .set_avm2_uncaught_error_events(uncaught_error_events);
+
+        Ok(uncaught_error_events)
+    }
+
+    /// Report an error that escaped to the top level of script execution.
+    ///
+    /// The error is dispatched as an `UncaughtErrorEvent` to the root movie's
+    /// `loaderInfo.uncaughtErrorEvents`, and is only logged if no listener
+    /// cancelled the event.
+    pub fn report_uncaught_error(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        error: Error,
+        description: &str,
+    ) {
+        let was_handled = match Self::dispatch_uncaught_error(context, &error) {
+            Ok(was_not_cancelled) => !was_not_cancelled,
+            Err(e) => {
+                log::error!("Unhandled AVM2 exception in uncaught error handler: {}", e);
+                false
+            }
+        };
+
+        if !was_handled {
+            log::error!("{}: {}", description, error);
+        }
+    }
+
+    /// Dispatch an `UncaughtErrorEvent` for an error.
+    ///
+    /// The `bool` parameter reads true if the event was not cancelled.
+    fn dispatch_uncaught_error(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        error: &Error,
+    ) -> Result<bool, Error> {
+        use crate::avm2::events::dispatch_event;
+
+        let movie = context.swf.clone();
+        let target = Self::uncaught_error_events(context, movie)?;
+        let mut activation = Activation::from_nothing(context.reborrow());
+
+        // Errors raised by the runtime aren't script values, so the event
+        // carries the error's message in place of an `Error` object.
+        let message = AvmString::new(activation.context.gc_context, error.to_string());
+        let event_class = activation.avm2().classes().uncaughterrorevent;
+        let event = event_class.construct(
+            &mut activation,
+            &[
+                "uncaughtError".into(),
+                true.into(),
+                true.into(),
+                message.into(),
+            ],
+        )?;
+
+        dispatch_event(&mut activation, target, event)
+    }
+
+    /// Whether any object is listening for a broadcast event.
+    pub fn has_broadcast_listeners(&self, event_name: &str) -> bool {
+        self.broadcast_list
+            .iter()
+            .any(|(name, objects)| &**name == event_name && !objects.is_empty())
+    }
+
     /// Add an object to the broadcast list.
     ///
     /// Each broadcastable event contains it's own broadcast list. You must
@@ -263,6 +497,13 @@ impl<'gc> Avm2<'gc> {
     /// Load an ABC file embedded in a `SwfSlice`.
     ///
     /// The `SwfSlice` must resolve to the contents of an ABC file.
+    ///
+    /// Every script's definitions are exported to the domain before any of
+    /// them run. Only the last script, which is the entry point of the ABC
+    /// file, is then run, and only if `lazy_init` is not set. Every other
+    /// script runs the first time one of its definitions is looked up, so
+    /// scripts run in the order that they are depended upon. If more than one
+    /// script defines a name, the first of them provides it.
     pub fn load_abc(
         abc: SwfSlice,
         _abc_name: &str,
@@ -275,11 +516,14 @@ impl<'gc> Avm2<'gc> {
         let abc_file = Rc::new(read.read()?);
         let tunit = TranslationUnit::from_abc(abc_file.clone(), domain, context.gc_context);
 
-        for i in (0..abc_file.scripts.len()).rev() {
-            let mut script = tunit.load_script(i as u32, context)?;
+        let mut entrypoint = None;
+        for i in 0..abc_file.scripts.len() {
+            entrypoint = Some(tunit.load_script(i as u32, context)?);
+        }
 
+        if let Some(mut entrypoint) = entrypoint {
             if !lazy_init {
-                script.globals(context)?;
+                entrypoint.globals(context)?;
             }
         }
 
@@ -311,12 +555,18 @@ impl<'gc> Avm2<'gc> {
     }
 
     fn pop_args(&mut self, arg_count: u32) -> Vec<Value<'gc>> {
-        let mut args = Vec::with_capacity(arg_count as usize);
-        args.resize(arg_count as usize, Value::Undefined);
-        for arg in args.iter_mut().rev() {
-            *arg = self.pop();
+        let arg_count = arg_count as usize;
+        let available = arg_count.min(self.stack.len());
+        if available < arg_count {
+            log::warn!("Avm2::pop_args: Stack underflow");
         }
 
+        let mut args = Vec::with_capacity(arg_count);
+        args.resize(arg_count - available, Value::Undefined);
+        args.extend(self.stack.drain(self.stack.len() - available..));
+
+        avm_debug!(self, "Stack pop {}: {:?}", self.stack.len(), args);
+
         args
     }
 
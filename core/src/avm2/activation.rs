@@ -2,25 +2,28 @@
 
 use crate::avm2::array::ArrayStorage;
 use crate::avm2::class::Class;
+#[cfg(feature = "avm2_jit")]
+use crate::avm2::jit::{self, Exit, JitType};
 use crate::avm2::method::{BytecodeMethod, Method, ParamConfig};
 use crate::avm2::names::{Multiname, Namespace, QName};
 use crate::avm2::object::{
     ArrayObject, ByteArrayObject, ClassObject, FunctionObject, NamespaceObject, ScriptObject,
 };
 use crate::avm2::object::{Object, TObject};
+use crate::avm2::property_cache::{CacheEntry, TraitLocation};
 use crate::avm2::scope::Scope;
 use crate::avm2::script::Script;
 use crate::avm2::value::Value;
-use crate::avm2::{value, Avm2, Error};
+use crate::avm2::verify::{Op as VerifiedOp, VerifiedBody};
+use crate::avm2::{Avm2, Error};
 use crate::context::UpdateContext;
 use crate::string::AvmString;
-use crate::swf::extensions::ReadSwfExt;
 use gc_arena::{Gc, GcCell, MutationContext};
 use smallvec::SmallVec;
 use std::borrow::Cow;
 use std::cmp::{min, Ordering};
+use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
-use swf::avm2::read::Reader;
 use swf::avm2::types::{
     Class as AbcClass, Index, Method as AbcMethod, Multiname as AbcMultiname,
     Namespace as AbcNamespace, Op,
@@ -66,6 +69,15 @@ enum FrameControl<'gc> {
     Return(Value<'gc>),
 }
 
+/// The name operand of a property access instruction.
+enum PropertyLookup<'gc> {
+    /// The instruction's inline cache matched the receiver.
+    Cached(CacheEntry<'gc>),
+
+    /// The multiname must be resolved against the receiver.
+    Uncached(Multiname<'gc>),
+}
+
 /// Represents a single activation of a given AVM2 function or keyframe.
 pub struct Activation<'a, 'gc: 'a, 'gc_context: 'a> {
     /// The immutable value of `this`.
@@ -106,6 +118,12 @@ pub struct Activation<'a, 'gc: 'a, 'gc_context: 'a> {
     /// A `scope` of `None` indicates that the scope stack is empty.
     scope: Option<GcCell<'gc, Scope<'gc>>>,
 
+    /// The scope stack that the activation started with.
+    ///
+    /// Scopes pushed on top of this one make up the method's local scope
+    /// stack, which is what `getscopeobject` indexes into.
+    outer_scope: Option<GcCell<'gc, Scope<'gc>>>,
+
     /// The class that yielded the currently executing method.
     ///
     /// This is used to maintain continuity when multiple methods supercall
@@ -152,6 +170,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             return_value: None,
             local_scope: ScriptObject::bare_object(context.gc_context),
             scope: None,
+            outer_scope: None,
             subclass_object: None,
             activation_class: None,
             context,
@@ -193,6 +212,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             return_value: None,
             local_scope: ScriptObject::bare_object(context.gc_context),
             scope,
+            outer_scope: scope,
             subclass_object: None,
             activation_class: None,
             context,
@@ -383,6 +403,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             return_value: None,
             local_scope: ScriptObject::bare_object(context.gc_context),
             scope,
+            outer_scope: scope,
             subclass_object,
             activation_class,
             context,
@@ -462,6 +483,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             return_value: None,
             local_scope: ScriptObject::bare_object(context.gc_context),
             scope,
+            outer_scope: scope,
             subclass_object,
             activation_class: None,
             context,
@@ -584,33 +606,6 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         self.subclass_object
     }
 
-    /// Retrieve a int from the current constant pool.
-    fn pool_int(
-        &self,
-        method: Gc<'gc, BytecodeMethod<'gc>>,
-        index: Index<i32>,
-    ) -> Result<i32, Error> {
-        value::abc_int(method.translation_unit(), index)
-    }
-
-    /// Retrieve a int from the current constant pool.
-    fn pool_uint(
-        &self,
-        method: Gc<'gc, BytecodeMethod<'gc>>,
-        index: Index<u32>,
-    ) -> Result<u32, Error> {
-        value::abc_uint(method.translation_unit(), index)
-    }
-
-    /// Retrieve a double from the current constant pool.
-    fn pool_double(
-        &self,
-        method: Gc<'gc, BytecodeMethod<'gc>>,
-        index: Index<f64>,
-    ) -> Result<f64, Error> {
-        value::abc_double(method.translation_unit(), index)
-    }
-
     /// Retrieve a string from the current constant pool.
     fn pool_string<'b>(
         &self,
@@ -637,7 +632,60 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         method: Gc<'gc, BytecodeMethod<'gc>>,
         index: Index<AbcMultiname>,
     ) -> Result<Multiname<'gc>, Error> {
-        Multiname::from_abc_multiname(method.translation_unit(), index, self)
+        let translation_unit = method.translation_unit();
+        if Multiname::is_static_index(&translation_unit.abc(), index.clone()) {
+            translation_unit.pool_multiname_static(index, self.context.gc_context)
+        } else {
+            Multiname::from_abc_multiname(translation_unit, index, self)
+        }
+    }
+
+    /// Pop the receiver of a property access instruction, consulting the
+    /// instruction's inline cache first.
+    ///
+    /// Only instructions with static multinames are ever cached, so a cached
+    /// instruction has no runtime name components to pop before the receiver.
+    /// Uncached instructions pop their multiname first, as usual.
+    fn pop_property_receiver(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        index: Index<AbcMultiname>,
+        offset: usize,
+    ) -> Result<(Object<'gc>, PropertyLookup<'gc>), Error> {
+        let cached = method.property_cache.read().get(offset);
+        if let Some(entry) = cached {
+            let receiver = self.context.avm2.pop().coerce_to_object(self)?;
+            if entry.matches(receiver) {
+                return Ok((receiver, PropertyLookup::Cached(entry)));
+            }
+
+            let multiname = self.pool_multiname(method, index)?;
+            return Ok((receiver, PropertyLookup::Uncached(multiname)));
+        }
+
+        let multiname = self.pool_multiname(method, index)?;
+        let receiver = self.context.avm2.pop().coerce_to_object(self)?;
+
+        Ok((receiver, PropertyLookup::Uncached(multiname)))
+    }
+
+    /// Record the name a property access instruction resolved to, so that
+    /// later executions on receivers of the same class can skip resolution.
+    fn fill_property_cache(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        index: Index<AbcMultiname>,
+        offset: usize,
+        receiver: Object<'gc>,
+        name: &QName<'gc>,
+    ) -> Result<(), Error> {
+        method.property_cache.write(self.context.gc_context).fill(
+            offset,
+            &method.abc,
+            index,
+            receiver,
+            name,
+        )
     }
 
     /// Retrieve a static, or non-runtime, multiname from the current constant
@@ -649,11 +697,9 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         method: Gc<'gc, BytecodeMethod<'gc>>,
         index: Index<AbcMultiname>,
     ) -> Result<Multiname<'gc>, Error> {
-        Multiname::from_abc_multiname_static(
-            method.translation_unit(),
-            index,
-            self.context.gc_context,
-        )
+        method
+            .translation_unit()
+            .pool_multiname_static(index, self.context.gc_context)
     }
 
     /// Retrieve a static, or non-runtime, multiname from the current constant
@@ -668,11 +714,9 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         if index.0 == 0 {
             Ok(Multiname::any())
         } else {
-            Multiname::from_abc_multiname_static(
-                method.translation_unit(),
-                index,
-                self.context.gc_context,
-            )
+            method
+                .translation_unit()
+                .pool_multiname_static(index, self.context.gc_context)
         }
     }
 
@@ -699,14 +743,33 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         &mut self,
         method: Gc<'gc, BytecodeMethod<'gc>>,
     ) -> Result<Value<'gc>, Error> {
-        let body: Result<_, Error> = method
-            .body()
-            .ok_or_else(|| "Cannot execute non-native method without body".into());
-        let body = body?;
-        let mut reader = Reader::new(&body.code);
+        let body = method.verified_body()?;
+        let mut ip = 0;
+
+        // Compiled code is entered at the start of the method, and after
+        // backward branches.
+        #[cfg(feature = "avm2_jit")]
+        let stack_base = self.context.avm2.stack.len();
+        #[cfg(feature = "avm2_jit")]
+        let mut entered = true;
 
         loop {
-            let result = self.do_next_opcode(method, &mut reader, &body.code);
+            #[cfg(feature = "avm2_jit")]
+            if entered {
+                if let Some(value) = self.run_compiled(&body, &mut ip, stack_base)? {
+                    break Ok(value);
+                }
+            }
+            #[cfg(feature = "avm2_jit")]
+            let previous_ip = ip;
+
+            let result = self.do_next_opcode(method, &body, &mut ip);
+
+            #[cfg(feature = "avm2_jit")]
+            {
+                entered = ip <= previous_ip;
+            }
+
             match result {
                 Ok(FrameControl::Return(value)) => break Ok(value),
                 Ok(FrameControl::Continue) => {}
@@ -715,95 +778,210 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         }
     }
 
-    /// Run a single action from a given action reader.
-    fn do_next_opcode<'b>(
+    /// Run the code compiled from `ip` in a verified method body, if `ip` has
+    /// been entered often enough to be compiled.
+    ///
+    /// Returns the method's return value if the compiled code returned, or
+    /// `None` to continue interpreting from `ip`.
+    #[cfg(feature = "avm2_jit")]
+    fn run_compiled(
+        &mut self,
+        body: &VerifiedBody,
+        ip: &mut usize,
+        stack_base: usize,
+    ) -> Result<Option<Value<'gc>>, Error> {
+        // Compiled code starts with an empty operand stack.
+        if self.context.avm2.stack.len() != stack_base || !body.jit.borrow_mut().is_hot(*ip) {
+            return Ok(None);
+        }
+
+        let registers = self.local_registers.read();
+        let local_types: Vec<JitType> = registers.0.iter().map(JitType::of).collect();
+        let code = match body.jit.borrow_mut().compiled(&body.ops, *ip, &local_types) {
+            Some(code) => code,
+            None => return Ok(None),
+        };
+        let mut locals: Vec<u64> = registers.0.iter().map(jit::encode).collect();
+        drop(registers);
+
+        let mut stack = vec![0; code.max_stack()];
+        let mut fuel = jit::FUEL;
+        match code.run(&mut locals, &mut stack, &mut fuel) {
+            Exit::Return(ty) => Ok(Some(jit::decode(*ty, stack[0]).unwrap_or(Value::Undefined))),
+            Exit::Resume {
+                ip: resume_ip,
+                stack: stack_types,
+                locals: local_types,
+            } => {
+                let mut registers = self.local_registers.write(self.context.gc_context);
+                for ((register, ty), bits) in registers.0.iter_mut().zip(local_types).zip(&locals) {
+                    if let Some(value) = jit::decode(*ty, *bits) {
+                        *register = value;
+                    }
+                }
+                drop(registers);
+
+                for (ty, bits) in stack_types.iter().zip(&stack) {
+                    self.context
+                        .avm2
+                        .push(jit::decode(*ty, *bits).unwrap_or(Value::Undefined));
+                }
+
+                *ip = *resume_ip;
+                if fuel == 0 {
+                    self.check_timeout()?;
+                }
+
+                Ok(None)
+            }
+        }
+    }
+
+    /// Raise an error if scripts have been running for too long.
+    fn check_timeout(&self) -> Result<(), Error> {
+        if self.context.update_start.elapsed() >= self.context.max_execution_duration {
+            return Err(
+                "A script in this movie has taken too long to execute and has been terminated."
+                    .into(),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Run the instruction at `ip` in a verified method body, advancing `ip`
+    /// to the next instruction to run.
+    fn do_next_opcode(
         &mut self,
         method: Gc<'gc, BytecodeMethod<'gc>>,
-        reader: &mut Reader<'b>,
-        full_data: &'b [u8],
+        body: &VerifiedBody,
+        ip: &mut usize,
     ) -> Result<FrameControl<'gc>, Error> {
         self.actions_since_timeout_check += 1;
         if self.actions_since_timeout_check >= 2000 {
             self.actions_since_timeout_check = 0;
-            if self.context.update_start.elapsed() >= self.context.max_execution_duration {
-                return Err(
-                    "A script in this movie has taken too long to execute and has been terminated."
-                        .into(),
-                );
-            }
+            self.check_timeout()?;
         }
 
-        let instruction_start = reader.pos(full_data);
-        let op = reader.read_op();
-        if let Ok(Some(op)) = op {
-            avm_debug!(self.avm2(), "Opcode: {:?}", op);
-
-            let result = match op {
-                Op::PushByte { value } => self.op_push_byte(value),
-                Op::PushDouble { value } => self.op_push_double(method, value),
+        let op: Result<_, Error> = body
+            .ops
+            .get(*ip)
+            .ok_or_else(|| "Execution fell off the end of the method".into());
+        let op = op?;
+        let instruction_start = body.offsets[*ip];
+        *ip += 1;
+
+        avm_debug!(self.avm2(), "Opcode: {:?}", op);
+
+        let result = match op {
+            VerifiedOp::PushDouble { value } => self.op_push_double(*value),
+            VerifiedOp::PushInt { value } => self.op_push_int(*value),
+            VerifiedOp::PushUint { value } => self.op_push_uint(*value),
+            VerifiedOp::Jump { target } => self.op_jump(*target, ip),
+            VerifiedOp::IfTrue { target } => self.op_if_true(*target, ip),
+            VerifiedOp::IfFalse { target } => self.op_if_false(*target, ip),
+            VerifiedOp::IfStrictEq { target } => self.op_if_strict_eq(*target, ip),
+            VerifiedOp::IfStrictNe { target } => self.op_if_strict_ne(*target, ip),
+            VerifiedOp::IfEq { target } => self.op_if_eq(*target, ip),
+            VerifiedOp::IfNe { target } => self.op_if_ne(*target, ip),
+            VerifiedOp::IfGe { target } => self.op_if_ge(*target, ip),
+            VerifiedOp::IfGt { target } => self.op_if_gt(*target, ip),
+            VerifiedOp::IfLe { target } => self.op_if_le(*target, ip),
+            VerifiedOp::IfLt { target } => self.op_if_lt(*target, ip),
+            VerifiedOp::IfNge { target } => self.op_if_nge(*target, ip),
+            VerifiedOp::IfNgt { target } => self.op_if_ngt(*target, ip),
+            VerifiedOp::IfNle { target } => self.op_if_nle(*target, ip),
+            VerifiedOp::IfNlt { target } => self.op_if_nlt(*target, ip),
+            VerifiedOp::LookupSwitch {
+                default_target,
+                case_targets,
+            } => self.op_lookup_switch(*default_target, case_targets, ip),
+            VerifiedOp::StringSwitch {
+                register,
+                string,
+                string_first,
+                cases,
+                default_target,
+            } => self.op_string_switch(
+                method,
+                *register,
+                string.clone(),
+                *string_first,
+                cases,
+                *default_target,
+                ip,
+            ),
+            VerifiedOp::Abc(op) => match op {
+                Op::PushByte { value } => self.op_push_byte(*value),
                 Op::PushFalse => self.op_push_false(),
-                Op::PushInt { value } => self.op_push_int(method, value),
-                Op::PushNamespace { value } => self.op_push_namespace(method, value),
+                Op::PushNamespace { value } => self.op_push_namespace(method, value.clone()),
                 Op::PushNaN => self.op_push_nan(),
                 Op::PushNull => self.op_push_null(),
-                Op::PushShort { value } => self.op_push_short(value),
-                Op::PushString { value } => self.op_push_string(method, value),
+                Op::PushShort { value } => self.op_push_short(*value),
+                Op::PushString { value } => self.op_push_string(method, value.clone()),
                 Op::PushTrue => self.op_push_true(),
-                Op::PushUint { value } => self.op_push_uint(method, value),
                 Op::PushUndefined => self.op_push_undefined(),
                 Op::Pop => self.op_pop(),
                 Op::Dup => self.op_dup(),
-                Op::GetLocal { index } => self.op_get_local(index),
-                Op::SetLocal { index } => self.op_set_local(index),
-                Op::Kill { index } => self.op_kill(index),
-                Op::Call { num_args } => self.op_call(num_args),
-                Op::CallMethod { index, num_args } => self.op_call_method(index, num_args),
+                Op::GetLocal { index } => self.op_get_local(*index),
+                Op::SetLocal { index } => self.op_set_local(*index),
+                Op::Kill { index } => self.op_kill(*index),
+                Op::Call { num_args } => self.op_call(*num_args),
+                Op::CallMethod { index, num_args } => self.op_call_method(index.clone(), *num_args),
                 Op::CallProperty { index, num_args } => {
-                    self.op_call_property(method, index, num_args)
+                    self.op_call_property(method, index.clone(), *num_args, instruction_start)
                 }
                 Op::CallPropLex { index, num_args } => {
-                    self.op_call_prop_lex(method, index, num_args)
+                    self.op_call_prop_lex(method, index.clone(), *num_args)
                 }
                 Op::CallPropVoid { index, num_args } => {
-                    self.op_call_prop_void(method, index, num_args)
+                    self.op_call_prop_void(method, index.clone(), *num_args, instruction_start)
+                }
+                Op::CallStatic { index, num_args } => {
+                    self.op_call_static(method, index.clone(), *num_args)
+                }
+                Op::CallSuper { index, num_args } => {
+                    self.op_call_super(method, index.clone(), *num_args)
                 }
-                Op::CallStatic { index, num_args } => self.op_call_static(method, index, num_args),
-                Op::CallSuper { index, num_args } => self.op_call_super(method, index, num_args),
                 Op::CallSuperVoid { index, num_args } => {
-                    self.op_call_super_void(method, index, num_args)
+                    self.op_call_super_void(method, index.clone(), *num_args)
                 }
                 Op::ReturnValue => self.op_return_value(),
                 Op::ReturnVoid => self.op_return_void(),
-                Op::GetProperty { index } => self.op_get_property(method, index),
-                Op::SetProperty { index } => self.op_set_property(method, index),
-                Op::InitProperty { index } => self.op_init_property(method, index),
-                Op::DeleteProperty { index } => self.op_delete_property(method, index),
-                Op::GetSuper { index } => self.op_get_super(method, index),
-                Op::SetSuper { index } => self.op_set_super(method, index),
+                Op::GetProperty { index } => {
+                    self.op_get_property(method, index.clone(), instruction_start)
+                }
+                Op::SetProperty { index } => {
+                    self.op_set_property(method, index.clone(), instruction_start)
+                }
+                Op::InitProperty { index } => self.op_init_property(method, index.clone()),
+                Op::DeleteProperty { index } => self.op_delete_property(method, index.clone()),
+                Op::GetSuper { index } => self.op_get_super(method, index.clone()),
+                Op::SetSuper { index } => self.op_set_super(method, index.clone()),
                 Op::In => self.op_in(),
                 Op::PushScope => self.op_push_scope(),
                 Op::PushWith => self.op_push_with(),
                 Op::PopScope => self.op_pop_scope(),
-                Op::GetScopeObject { index } => self.op_get_scope_object(index),
+                Op::GetScopeObject { index } => self.op_get_scope_object(*index),
                 Op::GetGlobalScope => self.op_get_global_scope(),
-                Op::FindProperty { index } => self.op_find_property(method, index),
-                Op::FindPropStrict { index } => self.op_find_prop_strict(method, index),
-                Op::GetLex { index } => self.op_get_lex(method, index),
-                Op::GetSlot { index } => self.op_get_slot(index),
-                Op::SetSlot { index } => self.op_set_slot(index),
-                Op::GetGlobalSlot { index } => self.op_get_global_slot(index),
-                Op::SetGlobalSlot { index } => self.op_set_global_slot(index),
-                Op::Construct { num_args } => self.op_construct(num_args),
+                Op::FindProperty { index } => self.op_find_property(method, index.clone()),
+                Op::FindPropStrict { index } => self.op_find_prop_strict(method, index.clone()),
+                Op::GetLex { index } => self.op_get_lex(method, index.clone()),
+                Op::GetSlot { index } => self.op_get_slot(*index),
+                Op::SetSlot { index } => self.op_set_slot(*index),
+                Op::GetGlobalSlot { index } => self.op_get_global_slot(*index),
+                Op::SetGlobalSlot { index } => self.op_set_global_slot(*index),
+                Op::Construct { num_args } => self.op_construct(*num_args),
                 Op::ConstructProp { index, num_args } => {
-                    self.op_construct_prop(method, index, num_args)
+                    self.op_construct_prop(method, index.clone(), *num_args)
                 }
-                Op::ConstructSuper { num_args } => self.op_construct_super(num_args),
+                Op::ConstructSuper { num_args } => self.op_construct_super(*num_args),
                 Op::NewActivation => self.op_new_activation(),
-                Op::NewObject { num_args } => self.op_new_object(num_args),
-                Op::NewFunction { index } => self.op_new_function(method, index),
-                Op::NewClass { index } => self.op_new_class(method, index),
-                Op::ApplyType { num_types } => self.op_apply_type(num_types),
-                Op::NewArray { num_args } => self.op_new_array(num_args),
+                Op::NewObject { num_args } => self.op_new_object(*num_args),
+                Op::NewFunction { index } => self.op_new_function(method, index.clone()),
+                Op::NewClass { index } => self.op_new_class(method, index.clone()),
+                Op::ApplyType { num_types } => self.op_apply_type(*num_types),
+                Op::NewArray { num_args } => self.op_new_array(*num_args),
                 Op::CoerceA => self.op_coerce_a(),
                 Op::CoerceS => self.op_coerce_s(),
                 Op::ConvertB => self.op_convert_b(),
@@ -818,13 +996,13 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                 Op::BitNot => self.op_bitnot(),
                 Op::BitOr => self.op_bitor(),
                 Op::BitXor => self.op_bitxor(),
-                Op::DecLocal { index } => self.op_declocal(index),
-                Op::DecLocalI { index } => self.op_declocal_i(index),
+                Op::DecLocal { index } => self.op_declocal(*index),
+                Op::DecLocalI { index } => self.op_declocal_i(*index),
                 Op::Decrement => self.op_decrement(),
                 Op::DecrementI => self.op_decrement_i(),
                 Op::Divide => self.op_divide(),
-                Op::IncLocal { index } => self.op_inclocal(index),
-                Op::IncLocalI { index } => self.op_inclocal_i(index),
+                Op::IncLocal { index } => self.op_inclocal(*index),
+                Op::IncLocalI { index } => self.op_inclocal_i(*index),
                 Op::Increment => self.op_increment(),
                 Op::IncrementI => self.op_increment_i(),
                 Op::LShift => self.op_lshift(),
@@ -838,21 +1016,6 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                 Op::SubtractI => self.op_subtract_i(),
                 Op::Swap => self.op_swap(),
                 Op::URShift => self.op_urshift(),
-                Op::Jump { offset } => self.op_jump(offset, reader, full_data),
-                Op::IfTrue { offset } => self.op_if_true(offset, reader, full_data),
-                Op::IfFalse { offset } => self.op_if_false(offset, reader, full_data),
-                Op::IfStrictEq { offset } => self.op_if_strict_eq(offset, reader, full_data),
-                Op::IfStrictNe { offset } => self.op_if_strict_ne(offset, reader, full_data),
-                Op::IfEq { offset } => self.op_if_eq(offset, reader, full_data),
-                Op::IfNe { offset } => self.op_if_ne(offset, reader, full_data),
-                Op::IfGe { offset } => self.op_if_ge(offset, reader, full_data),
-                Op::IfGt { offset } => self.op_if_gt(offset, reader, full_data),
-                Op::IfLe { offset } => self.op_if_le(offset, reader, full_data),
-                Op::IfLt { offset } => self.op_if_lt(offset, reader, full_data),
-                Op::IfNge { offset } => self.op_if_nge(offset, reader, full_data),
-                Op::IfNgt { offset } => self.op_if_ngt(offset, reader, full_data),
-                Op::IfNle { offset } => self.op_if_nle(offset, reader, full_data),
-                Op::IfNlt { offset } => self.op_if_nlt(offset, reader, full_data),
                 Op::StrictEquals => self.op_strict_equals(),
                 Op::Equals => self.op_equals(),
                 Op::GreaterEquals => self.op_greater_equals(),
@@ -865,12 +1028,12 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                 Op::HasNext2 {
                     object_register,
                     index_register,
-                } => self.op_has_next_2(object_register, index_register),
+                } => self.op_has_next_2(*object_register, *index_register),
                 Op::NextName => self.op_next_name(),
                 Op::NextValue => self.op_next_value(),
-                Op::IsType { index } => self.op_is_type(method, index),
+                Op::IsType { index } => self.op_is_type(method, index.clone()),
                 Op::IsTypeLate => self.op_is_type_late(),
-                Op::AsType { type_name } => self.op_as_type(method, type_name),
+                Op::AsType { type_name } => self.op_as_type(method, type_name.clone()),
                 Op::AsTypeLate => self.op_as_type_late(),
                 Op::InstanceOf => self.op_instance_of(),
                 Op::Label => Ok(FrameControl::Continue),
@@ -878,23 +1041,13 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                     is_local_register,
                     register_name,
                     register,
-                } => self.op_debug(method, is_local_register, register_name, register),
-                Op::DebugFile { file_name } => self.op_debug_file(method, file_name),
-                Op::DebugLine { line_num } => self.op_debug_line(line_num),
+                } => self.op_debug(method, *is_local_register, register_name.clone(), *register),
+                Op::DebugFile { file_name } => self.op_debug_file(method, file_name.clone()),
+                Op::DebugLine { line_num } => self.op_debug_line(*line_num),
                 Op::TypeOf => self.op_type_of(),
                 Op::EscXAttr => self.op_esc_xattr(),
                 Op::EscXElem => self.op_esc_elem(),
-                Op::LookupSwitch {
-                    default_offset,
-                    case_offsets,
-                } => self.op_lookup_switch(
-                    default_offset,
-                    &case_offsets,
-                    instruction_start,
-                    reader,
-                    full_data,
-                ),
-                Op::Coerce { index } => self.op_coerce(method, index),
+                Op::Coerce { index } => self.op_coerce(method, index.clone()),
                 Op::Si8 => self.op_si8(),
                 Op::Si16 => self.op_si16(),
                 Op::Si32 => self.op_si32(),
@@ -908,23 +1061,15 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                 Op::Sxi1 => self.op_sxi1(),
                 Op::Sxi8 => self.op_sxi8(),
                 Op::Sxi16 => self.op_sxi16(),
-                _ => self.unknown_op(op),
-            };
+                _ => self.unknown_op(op.clone()),
+            },
+        };
 
-            if let Err(e) = result {
-                log::error!("AVM2 error: {}", e);
-                return Err(e);
-            }
-            result
-        } else if let Ok(None) = op {
-            log::error!("Unknown opcode!");
-            Err("Unknown opcode!".into())
-        } else if let Err(e) = op {
-            log::error!("Parse error: {:?}", e);
-            Err(e.into())
-        } else {
-            unreachable!();
+        if let Err(e) = result {
+            log::error!("AVM2 error: {}", e);
+            return Err(e);
         }
+        result
     }
 
     fn unknown_op(&mut self, op: swf::avm2::types::Op) -> Result<FrameControl<'gc>, Error> {
@@ -939,12 +1084,8 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         Ok(FrameControl::Continue)
     }
 
-    fn op_push_double(
-        &mut self,
-        method: Gc<'gc, BytecodeMethod<'gc>>,
-        value: Index<f64>,
-    ) -> Result<FrameControl<'gc>, Error> {
-        self.context.avm2.push(self.pool_double(method, value)?);
+    fn op_push_double(&mut self, value: f64) -> Result<FrameControl<'gc>, Error> {
+        self.context.avm2.push(value);
         Ok(FrameControl::Continue)
     }
 
@@ -953,12 +1094,8 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         Ok(FrameControl::Continue)
     }
 
-    fn op_push_int(
-        &mut self,
-        method: Gc<'gc, BytecodeMethod<'gc>>,
-        value: Index<i32>,
-    ) -> Result<FrameControl<'gc>, Error> {
-        self.context.avm2.push(self.pool_int(method, value)?);
+    fn op_push_int(&mut self, value: i32) -> Result<FrameControl<'gc>, Error> {
+        self.context.avm2.push(value);
         Ok(FrameControl::Continue)
     }
 
@@ -1003,12 +1140,8 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         Ok(FrameControl::Continue)
     }
 
-    fn op_push_uint(
-        &mut self,
-        method: Gc<'gc, BytecodeMethod<'gc>>,
-        value: Index<u32>,
-    ) -> Result<FrameControl<'gc>, Error> {
-        self.context.avm2.push(self.pool_uint(method, value)?);
+    fn op_push_uint(&mut self, value: u32) -> Result<FrameControl<'gc>, Error> {
+        self.context.avm2.push(value);
         Ok(FrameControl::Continue)
     }
 
@@ -1085,19 +1218,42 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         Ok(FrameControl::Continue)
     }
 
-    fn op_call_property(
+    /// Pop the receiver of a `callproperty`-style instruction and look up the
+    /// method it calls.
+    ///
+    /// Yields the receiver, the method, and the superclass object to call the
+    /// method with.
+    fn pop_property_method(
         &mut self,
         method: Gc<'gc, BytecodeMethod<'gc>>,
         index: Index<AbcMultiname>,
-        arg_count: u32,
-    ) -> Result<FrameControl<'gc>, Error> {
-        let args = self.context.avm2.pop_args(arg_count);
-        let multiname = self.pool_multiname(method, index)?;
-        let receiver = self.context.avm2.pop().coerce_to_object(self)?;
+        offset: usize,
+    ) -> Result<(Object<'gc>, Object<'gc>, Option<Object<'gc>>), Error> {
+        let (receiver, lookup) = self.pop_property_receiver(method, index.clone(), offset)?;
+        let multiname = match lookup {
+            PropertyLookup::Cached(entry) => {
+                let function = match entry.location() {
+                    TraitLocation::Method(disp_id) => receiver.get_method(disp_id),
+                    _ => None,
+                };
+                let function = match function {
+                    Some(function) => function,
+                    None => receiver
+                        .get_property(receiver, entry.name(), self)?
+                        .coerce_to_object(self)?,
+                };
+
+                return Ok((receiver, function, entry.defining_class()));
+            }
+            PropertyLookup::Uncached(multiname) => multiname,
+        };
+
         let name: Result<QName, Error> = receiver
             .resolve_multiname(&multiname)?
             .ok_or_else(|| format!("Could not find method {:?}", multiname.local_name()).into());
         let name = name?;
+        self.fill_property_cache(method, index, offset, receiver, &name)?;
+
         let superclass_object = if let Some(c) = receiver.as_class_object() {
             c.find_class_for_trait(&name)?
         } else {
@@ -1106,6 +1262,20 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         let function = receiver
             .get_property(receiver, &name, self)?
             .coerce_to_object(self)?;
+
+        Ok((receiver, function, superclass_object))
+    }
+
+    fn op_call_property(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        index: Index<AbcMultiname>,
+        arg_count: u32,
+        offset: usize,
+    ) -> Result<FrameControl<'gc>, Error> {
+        let args = self.context.avm2.pop_args(arg_count);
+        let (receiver, function, superclass_object) =
+            self.pop_property_method(method, index, offset)?;
         let value = function.call(Some(receiver), &args, self, superclass_object)?;
 
         self.context.avm2.push(value);
@@ -1140,22 +1310,11 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         method: Gc<'gc, BytecodeMethod<'gc>>,
         index: Index<AbcMultiname>,
         arg_count: u32,
+        offset: usize,
     ) -> Result<FrameControl<'gc>, Error> {
         let args = self.context.avm2.pop_args(arg_count);
-        let multiname = self.pool_multiname(method, index)?;
-        let receiver = self.context.avm2.pop().coerce_to_object(self)?;
-        let name: Result<QName, Error> = receiver
-            .resolve_multiname(&multiname)?
-            .ok_or_else(|| format!("Could not find method {:?}", multiname.local_name()).into());
-        let name = name?;
-        let superclass_object = if let Some(c) = receiver.as_class_object() {
-            c.find_class_for_trait(&name)?
-        } else {
-            None
-        };
-        let function = receiver
-            .get_property(receiver, &name, self)?
-            .coerce_to_object(self)?;
+        let (receiver, function, superclass_object) =
+            self.pop_property_method(method, index, offset)?;
 
         function.call(Some(receiver), &args, self, superclass_object)?;
 
@@ -1256,9 +1415,23 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         &mut self,
         method: Gc<'gc, BytecodeMethod<'gc>>,
         index: Index<AbcMultiname>,
+        offset: usize,
     ) -> Result<FrameControl<'gc>, Error> {
-        let multiname = self.pool_multiname(method, index)?;
-        let object = self.context.avm2.pop().coerce_to_object(self)?;
+        let (object, lookup) = self.pop_property_receiver(method, index.clone(), offset)?;
+        let multiname = match lookup {
+            PropertyLookup::Cached(entry) => {
+                let value = match entry.location() {
+                    TraitLocation::Slot(slot_id) | TraitLocation::Const(slot_id) => {
+                        object.get_slot(slot_id)?
+                    }
+                    _ => object.get_property(object, entry.name(), self)?,
+                };
+                self.context.avm2.push(value);
+
+                return Ok(FrameControl::Continue);
+            }
+            PropertyLookup::Uncached(multiname) => multiname,
+        };
 
         let name: Result<QName, Error> = object.resolve_multiname(&multiname)?.ok_or_else(|| {
             format!("Could not resolve property {:?}", multiname.local_name()).into()
@@ -1276,7 +1449,10 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             return Ok(FrameControl::Continue);
         }
 
-        let value = object.get_property(object, &name?, self)?;
+        let name = name?;
+        self.fill_property_cache(method, index, offset, object, &name)?;
+
+        let value = object.get_property(object, &name, self)?;
         self.context.avm2.push(value);
 
         Ok(FrameControl::Continue)
@@ -1286,12 +1462,28 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         &mut self,
         method: Gc<'gc, BytecodeMethod<'gc>>,
         index: Index<AbcMultiname>,
+        offset: usize,
     ) -> Result<FrameControl<'gc>, Error> {
         let value = self.context.avm2.pop();
-        let multiname = self.pool_multiname(method, index)?;
-        let mut object = self.context.avm2.pop().coerce_to_object(self)?;
+        let (mut object, lookup) = self.pop_property_receiver(method, index.clone(), offset)?;
+        let multiname = match lookup {
+            PropertyLookup::Cached(entry) => {
+                // Only variables may be written directly. Consts go through
+                // `set_property`, which rejects the write.
+                match entry.location() {
+                    TraitLocation::Slot(slot_id) => {
+                        object.set_slot(slot_id, value, self.context.gc_context)?
+                    }
+                    _ => object.set_property(object, entry.name(), value, self)?,
+                }
+
+                return Ok(FrameControl::Continue);
+            }
+            PropertyLookup::Uncached(multiname) => multiname,
+        };
 
         if let Some(name) = object.resolve_multiname(&multiname)? {
+            self.fill_property_cache(method, index, offset, object, &name)?;
             object.set_property(object, &name, value, self)?;
         } else {
             //TODO: Non-dynamic objects should fail
@@ -1455,19 +1647,35 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         Ok(FrameControl::Continue)
     }
 
-    fn op_get_scope_object(&mut self, mut index: u8) -> Result<FrameControl<'gc>, Error> {
-        let mut scope = self.scope();
+    /// Implements `Op::GetScopeObject`
+    ///
+    /// The index counts from the bottom of this method's local scope stack,
+    /// not from the top, so that `with` and `catch` scopes pushed later don't
+    /// change which object a given index refers to.
+    fn op_get_scope_object(&mut self, index: u8) -> Result<FrameControl<'gc>, Error> {
+        let outer_scope = self.outer_scope;
+        let is_local = |scope: GcCell<'gc, Scope<'gc>>| match outer_scope {
+            Some(outer_scope) => !GcCell::ptr_eq(scope, outer_scope),
+            None => true,
+        };
 
-        while index > 0 {
-            if let Some(child_scope) = scope {
-                scope = child_scope.read().parent_cell();
-            }
+        // Count the local scopes, then walk down from the top to the one the
+        // index refers to.
+        let mut depth = 0;
+        let mut scope = self.scope();
+        while let Some(child_scope) = scope.filter(|s| is_local(*s)) {
+            depth += 1;
+            scope = child_scope.read().parent_cell();
+        }
 
-            index -= 1;
+        let mut scope = self.scope();
+        for _ in (index as usize + 1)..depth {
+            scope = scope.and_then(|s| s.read().parent_cell());
         }
 
         self.context.avm2.push(
             scope
+                .filter(|_| (index as usize) < depth)
                 .map(|s| (*s.read().locals()).into())
                 .unwrap_or(Value::Undefined),
         );
@@ -1750,7 +1958,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
     fn op_convert_i(&mut self) -> Result<FrameControl<'gc>, Error> {
         let value = self.context.avm2.pop().coerce_to_i32(self)?;
 
-        self.context.avm2.push(Value::Number(value.into()));
+        self.context.avm2.push(Value::Integer(value));
 
         Ok(FrameControl::Continue)
     }
@@ -1774,7 +1982,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
     fn op_convert_u(&mut self) -> Result<FrameControl<'gc>, Error> {
         let value = self.context.avm2.pop().coerce_to_u32(self)?;
 
-        self.context.avm2.push(Value::Number(value.into()));
+        self.context.avm2.push(Value::Unsigned(value));
 
         Ok(FrameControl::Continue)
     }
@@ -1794,6 +2002,10 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         // TODO: Special handling required for `Date` and ECMA-357/E4X `XML`
         let sum_value = match (value1, value2) {
             (Value::Number(n1), Value::Number(n2)) => Value::Number(n1 + n2),
+            (
+                value1 @ (Value::Number(_) | Value::Integer(_) | Value::Unsigned(_)),
+                value2 @ (Value::Number(_) | Value::Integer(_) | Value::Unsigned(_)),
+            ) => Value::Number(value1.coerce_to_number(self)? + value2.coerce_to_number(self)?),
             (Value::String(s), value2) => {
                 let mut out_s = s.to_string();
                 out_s.push_str(&value2.coerce_to_string(self)?);
@@ -1839,7 +2051,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         let value2 = self.context.avm2.pop().coerce_to_i32(self)?;
         let value1 = self.context.avm2.pop().coerce_to_i32(self)?;
 
-        self.context.avm2.push(value1 + value2);
+        self.context.avm2.push(value1.wrapping_add(value2));
 
         Ok(FrameControl::Continue)
     }
@@ -1890,7 +2102,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
     fn op_declocal_i(&mut self, index: u32) -> Result<FrameControl<'gc>, Error> {
         let value = self.local_register(index)?.coerce_to_i32(self)?;
 
-        self.set_local_register(index, value - 1, self.context.gc_context)?;
+        self.set_local_register(index, value.wrapping_sub(1), self.context.gc_context)?;
 
         Ok(FrameControl::Continue)
     }
@@ -1906,7 +2118,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
     fn op_decrement_i(&mut self) -> Result<FrameControl<'gc>, Error> {
         let value = self.context.avm2.pop().coerce_to_i32(self)?;
 
-        self.context.avm2.push(value - 1);
+        self.context.avm2.push(value.wrapping_sub(1));
 
         Ok(FrameControl::Continue)
     }
@@ -1931,7 +2143,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
     fn op_inclocal_i(&mut self, index: u32) -> Result<FrameControl<'gc>, Error> {
         let value = self.local_register(index)?.coerce_to_i32(self)?;
 
-        self.set_local_register(index, value + 1, self.context.gc_context)?;
+        self.set_local_register(index, value.wrapping_add(1), self.context.gc_context)?;
 
         Ok(FrameControl::Continue)
     }
@@ -1947,7 +2159,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
     fn op_increment_i(&mut self) -> Result<FrameControl<'gc>, Error> {
         let value = self.context.avm2.pop().coerce_to_i32(self)?;
 
-        self.context.avm2.push(value + 1);
+        self.context.avm2.push(value.wrapping_add(1));
 
         Ok(FrameControl::Continue)
     }
@@ -1983,7 +2195,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         let value2 = self.context.avm2.pop().coerce_to_i32(self)?;
         let value1 = self.context.avm2.pop().coerce_to_i32(self)?;
 
-        self.context.avm2.push(value1 * value2);
+        self.context.avm2.push(value1.wrapping_mul(value2));
 
         Ok(FrameControl::Continue)
     }
@@ -1999,7 +2211,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
     fn op_negate_i(&mut self) -> Result<FrameControl<'gc>, Error> {
         let value1 = self.context.avm2.pop().coerce_to_i32(self)?;
 
-        self.context.avm2.push(-value1);
+        self.context.avm2.push(value1.wrapping_neg());
 
         Ok(FrameControl::Continue)
     }
@@ -2026,7 +2238,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         let value2 = self.context.avm2.pop().coerce_to_i32(self)?;
         let value1 = self.context.avm2.pop().coerce_to_i32(self)?;
 
-        self.context.avm2.push(value1 - value2);
+        self.context.avm2.push(value1.wrapping_sub(value2));
 
         Ok(FrameControl::Continue)
     }
@@ -2050,234 +2262,167 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         Ok(FrameControl::Continue)
     }
 
-    fn op_jump<'b>(
-        &mut self,
-        offset: i32,
-        reader: &mut Reader<'b>,
-        full_data: &'b [u8],
-    ) -> Result<FrameControl<'gc>, Error> {
-        reader.seek(full_data, offset);
+    fn op_jump(&mut self, target: usize, ip: &mut usize) -> Result<FrameControl<'gc>, Error> {
+        *ip = target;
 
         Ok(FrameControl::Continue)
     }
 
-    fn op_if_true<'b>(
-        &mut self,
-        offset: i32,
-        reader: &mut Reader<'b>,
-        full_data: &'b [u8],
-    ) -> Result<FrameControl<'gc>, Error> {
+    fn op_if_true(&mut self, target: usize, ip: &mut usize) -> Result<FrameControl<'gc>, Error> {
         let value = self.context.avm2.pop().coerce_to_boolean();
 
         if value {
-            reader.seek(full_data, offset);
+            *ip = target;
         }
 
         Ok(FrameControl::Continue)
     }
 
-    fn op_if_false<'b>(
-        &mut self,
-        offset: i32,
-        reader: &mut Reader<'b>,
-        full_data: &'b [u8],
-    ) -> Result<FrameControl<'gc>, Error> {
+    fn op_if_false(&mut self, target: usize, ip: &mut usize) -> Result<FrameControl<'gc>, Error> {
         let value = self.context.avm2.pop().coerce_to_boolean();
 
         if !value {
-            reader.seek(full_data, offset);
+            *ip = target;
         }
 
         Ok(FrameControl::Continue)
     }
 
-    fn op_if_strict_eq<'b>(
+    fn op_if_strict_eq(
         &mut self,
-        offset: i32,
-        reader: &mut Reader<'b>,
-        full_data: &'b [u8],
+        target: usize,
+        ip: &mut usize,
     ) -> Result<FrameControl<'gc>, Error> {
         let value2 = self.context.avm2.pop();
         let value1 = self.context.avm2.pop();
 
         if value1 == value2 {
-            reader.seek(full_data, offset);
+            *ip = target;
         }
 
         Ok(FrameControl::Continue)
     }
 
-    fn op_if_strict_ne<'b>(
+    fn op_if_strict_ne(
         &mut self,
-        offset: i32,
-        reader: &mut Reader<'b>,
-        full_data: &'b [u8],
+        target: usize,
+        ip: &mut usize,
     ) -> Result<FrameControl<'gc>, Error> {
         let value2 = self.context.avm2.pop();
         let value1 = self.context.avm2.pop();
 
         if value1 != value2 {
-            reader.seek(full_data, offset);
+            *ip = target;
         }
 
         Ok(FrameControl::Continue)
     }
 
-    fn op_if_eq<'b>(
-        &mut self,
-        offset: i32,
-        reader: &mut Reader<'b>,
-        full_data: &'b [u8],
-    ) -> Result<FrameControl<'gc>, Error> {
+    fn op_if_eq(&mut self, target: usize, ip: &mut usize) -> Result<FrameControl<'gc>, Error> {
         let value2 = self.context.avm2.pop();
         let value1 = self.context.avm2.pop();
 
         if value1.abstract_eq(&value2, self)? {
-            reader.seek(full_data, offset);
+            *ip = target;
         }
 
         Ok(FrameControl::Continue)
     }
 
-    fn op_if_ne<'b>(
-        &mut self,
-        offset: i32,
-        reader: &mut Reader<'b>,
-        full_data: &'b [u8],
-    ) -> Result<FrameControl<'gc>, Error> {
+    fn op_if_ne(&mut self, target: usize, ip: &mut usize) -> Result<FrameControl<'gc>, Error> {
         let value2 = self.context.avm2.pop();
         let value1 = self.context.avm2.pop();
 
         if !value1.abstract_eq(&value2, self)? {
-            reader.seek(full_data, offset);
+            *ip = target;
         }
 
         Ok(FrameControl::Continue)
     }
 
-    fn op_if_ge<'b>(
-        &mut self,
-        offset: i32,
-        reader: &mut Reader<'b>,
-        full_data: &'b [u8],
-    ) -> Result<FrameControl<'gc>, Error> {
+    fn op_if_ge(&mut self, target: usize, ip: &mut usize) -> Result<FrameControl<'gc>, Error> {
         let value2 = self.context.avm2.pop();
         let value1 = self.context.avm2.pop();
 
         if value1.abstract_lt(&value2, self)? == Some(false) {
-            reader.seek(full_data, offset);
+            *ip = target;
         }
 
         Ok(FrameControl::Continue)
     }
 
-    fn op_if_gt<'b>(
-        &mut self,
-        offset: i32,
-        reader: &mut Reader<'b>,
-        full_data: &'b [u8],
-    ) -> Result<FrameControl<'gc>, Error> {
+    fn op_if_gt(&mut self, target: usize, ip: &mut usize) -> Result<FrameControl<'gc>, Error> {
         let value2 = self.context.avm2.pop();
         let value1 = self.context.avm2.pop();
 
         if value2.abstract_lt(&value1, self)? == Some(true) {
-            reader.seek(full_data, offset);
+            *ip = target;
         }
 
         Ok(FrameControl::Continue)
     }
 
-    fn op_if_le<'b>(
-        &mut self,
-        offset: i32,
-        reader: &mut Reader<'b>,
-        full_data: &'b [u8],
-    ) -> Result<FrameControl<'gc>, Error> {
+    fn op_if_le(&mut self, target: usize, ip: &mut usize) -> Result<FrameControl<'gc>, Error> {
         let value2 = self.context.avm2.pop();
         let value1 = self.context.avm2.pop();
 
         if value2.abstract_lt(&value1, self)? == Some(false) {
-            reader.seek(full_data, offset);
+            *ip = target;
         }
 
         Ok(FrameControl::Continue)
     }
 
-    fn op_if_lt<'b>(
-        &mut self,
-        offset: i32,
-        reader: &mut Reader<'b>,
-        full_data: &'b [u8],
-    ) -> Result<FrameControl<'gc>, Error> {
+    fn op_if_lt(&mut self, target: usize, ip: &mut usize) -> Result<FrameControl<'gc>, Error> {
         let value2 = self.context.avm2.pop();
         let value1 = self.context.avm2.pop();
 
         if value1.abstract_lt(&value2, self)? == Some(true) {
-            reader.seek(full_data, offset);
+            *ip = target;
         }
 
         Ok(FrameControl::Continue)
     }
 
-    fn op_if_nge<'b>(
-        &mut self,
-        offset: i32,
-        reader: &mut Reader<'b>,
-        full_data: &'b [u8],
-    ) -> Result<FrameControl<'gc>, Error> {
+    fn op_if_nge(&mut self, target: usize, ip: &mut usize) -> Result<FrameControl<'gc>, Error> {
         let value2 = self.context.avm2.pop();
         let value1 = self.context.avm2.pop();
 
         if value1.abstract_lt(&value2, self)?.unwrap_or(true) {
-            reader.seek(full_data, offset);
+            *ip = target;
         }
 
         Ok(FrameControl::Continue)
     }
 
-    fn op_if_ngt<'b>(
-        &mut self,
-        offset: i32,
-        reader: &mut Reader<'b>,
-        full_data: &'b [u8],
-    ) -> Result<FrameControl<'gc>, Error> {
+    fn op_if_ngt(&mut self, target: usize, ip: &mut usize) -> Result<FrameControl<'gc>, Error> {
         let value2 = self.context.avm2.pop();
         let value1 = self.context.avm2.pop();
 
         if !value2.abstract_lt(&value1, self)?.unwrap_or(false) {
-            reader.seek(full_data, offset);
+            *ip = target;
         }
 
         Ok(FrameControl::Continue)
     }
 
-    fn op_if_nle<'b>(
-        &mut self,
-        offset: i32,
-        reader: &mut Reader<'b>,
-        full_data: &'b [u8],
-    ) -> Result<FrameControl<'gc>, Error> {
+    fn op_if_nle(&mut self, target: usize, ip: &mut usize) -> Result<FrameControl<'gc>, Error> {
         let value2 = self.context.avm2.pop();
         let value1 = self.context.avm2.pop();
 
         if value2.abstract_lt(&value1, self)?.unwrap_or(true) {
-            reader.seek(full_data, offset);
+            *ip = target;
         }
 
         Ok(FrameControl::Continue)
     }
 
-    fn op_if_nlt<'b>(
-        &mut self,
-        offset: i32,
-        reader: &mut Reader<'b>,
-        full_data: &'b [u8],
-    ) -> Result<FrameControl<'gc>, Error> {
+    fn op_if_nlt(&mut self, target: usize, ip: &mut usize) -> Result<FrameControl<'gc>, Error> {
         let value2 = self.context.avm2.pop();
         let value1 = self.context.avm2.pop();
 
         if !value1.abstract_lt(&value2, self)?.unwrap_or(false) {
-            reader.seek(full_data, offset);
+            *ip = target;
         }
 
         Ok(FrameControl::Continue)
@@ -2361,15 +2506,15 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
 
     fn op_has_next(&mut self) -> Result<FrameControl<'gc>, Error> {
         let cur_index = self.context.avm2.pop().coerce_to_u32(self)?;
-        let object = self.context.avm2.pop().coerce_to_object(self)?;
+        let object = self.context.avm2.pop();
 
-        let next_index = cur_index + 1;
+        // Enumerating `null` or `undefined` does nothing.
+        let next_index = match object {
+            Value::Undefined | Value::Null => None,
+            object => object.coerce_to_object(self)?.get_next_enumerant(cur_index),
+        };
 
-        if object.get_enumerant_name(next_index).is_some() {
-            self.context.avm2.push(next_index);
-        } else {
-            self.context.avm2.push(0.0);
-        }
+        self.context.avm2.push(next_index.unwrap_or(0));
 
         Ok(FrameControl::Continue)
     }
@@ -2379,25 +2524,26 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         object_register: u32,
         index_register: u32,
     ) -> Result<FrameControl<'gc>, Error> {
-        let cur_index = self.local_register(index_register)?.coerce_to_u32(self)?;
-        let mut object = Some(
-            self.local_register(object_register)?
-                .coerce_to_object(self)?,
-        );
+        let mut cur_index = self.local_register(index_register)?.coerce_to_u32(self)?;
 
-        let mut next_index = cur_index + 1;
+        // Enumerating `null` or `undefined` does nothing.
+        let mut object = match self.local_register(object_register)? {
+            Value::Undefined | Value::Null => None,
+            object => Some(object.coerce_to_object(self)?),
+        };
 
+        // Enumeration continues onto the prototype chain once an object runs
+        // out of enumerants, starting again from the first enumerant of each
+        // prototype.
+        let mut next_index = 0;
         while let Some(cur_object) = object {
-            if cur_object.get_enumerant_name(next_index).is_none() {
-                next_index = 1;
-                object = cur_object.proto();
-            } else {
+            if let Some(index) = cur_object.get_next_enumerant(cur_index) {
+                next_index = index;
                 break;
             }
-        }
 
-        if object.is_none() {
-            next_index = 0;
+            cur_index = 0;
+            object = cur_object.proto();
         }
 
         self.context.avm2.push(next_index != 0);
@@ -2416,7 +2562,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         let object = self.context.avm2.pop().coerce_to_object(self)?;
 
         let name = object
-            .get_enumerant_name(cur_index as u32)
+            .get_enumerant_name(cur_index as u32, self)
             .map(|n| n.local_name().into());
 
         self.context.avm2.push(name.unwrap_or(Value::Undefined));
@@ -2428,7 +2574,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         let cur_index = self.context.avm2.pop().coerce_to_number(self)?;
         let object = self.context.avm2.pop().coerce_to_object(self)?;
 
-        let name = object.get_enumerant_name(cur_index as u32);
+        let name = object.get_enumerant_name(cur_index as u32, self);
         let value = if let Some(name) = name {
             object.get_property(object, &name, self)?
         } else {
@@ -2484,7 +2630,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         method: Gc<'gc, BytecodeMethod<'gc>>,
         type_name_index: Index<AbcMultiname>,
     ) -> Result<FrameControl<'gc>, Error> {
-        let value = self.context.avm2.pop().coerce_to_object(self)?;
+        let value = self.context.avm2.pop();
 
         let multiname = self.pool_multiname_static(method, type_name_index)?;
         let found: Result<Value<'gc>, Error> = if let Some(scope) = self.scope() {
@@ -2505,7 +2651,9 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             return Err("TypeError: The right-hand side of operator must be a class.".into());
         }
 
-        if value.is_of_type(class, self)? {
+        // Primitives are checked without boxing them, and pushed back as they
+        // were.
+        if value.is_of_type(self, class)? {
             self.context.avm2.push(value);
         } else {
             self.context.avm2.push(Value::Null);
@@ -2516,13 +2664,13 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
 
     fn op_as_type_late(&mut self) -> Result<FrameControl<'gc>, Error> {
         let class = self.context.avm2.pop().coerce_to_object(self)?;
-        let value = self.context.avm2.pop().coerce_to_object(self)?;
+        let value = self.context.avm2.pop();
 
         if class.as_class().is_none() {
             return Err("TypeError: The right-hand side of operator must be a class.".into());
         }
 
-        if value.is_of_type(class, self)? {
+        if value.is_of_type(self, class)? {
             self.context.avm2.push(value);
         } else {
             self.context.avm2.push(Value::Null);
@@ -2637,27 +2785,47 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
     }
 
     /// Implements `Op::LookupSwitch`
-    fn op_lookup_switch<'b>(
+    fn op_lookup_switch(
         &mut self,
-        default_offset: i32,
-        case_offsets: &[i32],
-        instruction_start: usize,
-        reader: &mut Reader<'b>,
-        full_data: &'b [u8],
+        default_target: usize,
+        case_targets: &[usize],
+        ip: &mut usize,
     ) -> Result<FrameControl<'gc>, Error> {
         let index = self.context.avm2.pop().coerce_to_i32(self)?;
 
-        let offset = case_offsets
+        *ip = case_targets
             .get(index as usize)
             .copied()
-            .unwrap_or(default_offset)
-            + instruction_start as i32
-            - reader.pos(full_data) as i32;
+            .unwrap_or(default_target);
 
-        reader.seek(full_data, offset);
         Ok(FrameControl::Continue)
     }
 
+    /// Implements a chain of string comparisons that was replaced with a
+    /// table by the verifier.
+    #[allow(clippy::too_many_arguments)]
+    fn op_string_switch(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        register: u32,
+        string: Index<String>,
+        string_first: bool,
+        cases: &HashMap<String, usize>,
+        default_target: usize,
+        ip: &mut usize,
+    ) -> Result<FrameControl<'gc>, Error> {
+        if let Value::String(value) = self.local_register(register)? {
+            *ip = cases.get(value.as_str()).copied().unwrap_or(default_target);
+            return Ok(FrameControl::Continue);
+        }
+
+        if string_first {
+            self.op_push_string(method, string)
+        } else {
+            self.op_get_local(register)
+        }
+    }
+
     /// Implements `Op::Coerce`
     fn op_coerce(
         &mut self,
@@ -0,0 +1,725 @@
+//! AMF3 serialization of AVM2 values
+//!
+//! This implements the encoding used by `ByteArray.readObject` and
+//! `ByteArray.writeObject`. Instances of classes registered with
+//! `flash.net.registerClassAlias` are written with their alias, and are
+//! reconstructed as instances of the same class when read back. Classes that
+//! implement `flash.utils.IExternalizable` serialize themselves by way of
+//! `writeExternal` and `readExternal`.
+
+use crate::avm2::activation::Activation;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{ArrayObject, DateObject, Object, ScriptObject, TObject};
+use crate::avm2::traits::TraitKind;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::string::AvmString;
+use chrono::{TimeZone, Utc};
+use std::collections::HashMap;
+
+const UNDEFINED_MARKER: u8 = 0x00;
+const NULL_MARKER: u8 = 0x01;
+const FALSE_MARKER: u8 = 0x02;
+const TRUE_MARKER: u8 = 0x03;
+const INTEGER_MARKER: u8 = 0x04;
+const DOUBLE_MARKER: u8 = 0x05;
+const STRING_MARKER: u8 = 0x06;
+const XML_DOC_MARKER: u8 = 0x07;
+const DATE_MARKER: u8 = 0x08;
+const ARRAY_MARKER: u8 = 0x09;
+const OBJECT_MARKER: u8 = 0x0A;
+const XML_MARKER: u8 = 0x0B;
+const BYTE_ARRAY_MARKER: u8 = 0x0C;
+
+/// The range of integers that fit in an AMF3 integer. Anything outside of
+/// this range is written as a double.
+const INTEGER_MIN: i32 = -0x1000_0000;
+const INTEGER_MAX: i32 = 0x0FFF_FFFF;
+
+/// Determine if an object's class implements `IExternalizable`.
+fn is_externalizable<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    class: Option<Object<'gc>>,
+) -> Result<bool, Error> {
+    match class {
+        Some(class) => {
+            let iexternalizable = activation.avm2().classes().iexternalizable;
+            class.has_class_in_chain(iexternalizable, activation)
+        }
+        None => Ok(false),
+    }
+}
+
+/// List the names of the public slots declared by a class and all of its
+/// superclasses, which are serialized as sealed members.
+fn sealed_members<'gc>(class: Option<Object<'gc>>) -> Vec<AvmString<'gc>> {
+    let mut names = Vec::new();
+    let mut class = class;
+
+    while let Some(class_object) = class {
+        if let Some(class_def) = class_object.as_class() {
+            for instance_trait in class_def.read().instance_traits() {
+                if instance_trait.name().namespace().is_public()
+                    && matches!(instance_trait.kind(), TraitKind::Slot { .. })
+                {
+                    names.push(instance_trait.name().local_name());
+                }
+            }
+        }
+
+        class = class_object.superclass_object();
+    }
+
+    names
+}
+
+/// Call one of the methods of `IExternalizable` on an object.
+fn call_externalizable_method<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+    method: &'static str,
+    bytearray: Object<'gc>,
+) -> Result<(), Error> {
+    object
+        .get_property(object, &QName::new(Namespace::public(), method), activation)?
+        .coerce_to_object(activation)?
+        .call(Some(object), &[bytearray.into()], activation, None)?;
+
+    Ok(())
+}
+
+/// Writes AVM2 values into a `ByteArray` as AMF3.
+///
+/// Reference tables only span the values written by a single writer.
+pub struct Amf3Writer<'gc> {
+    /// The `ByteArray` being written to.
+    target: Object<'gc>,
+
+    /// Encoded data that has yet to be written to the target.
+    buffer: Vec<u8>,
+
+    /// Strings that have already been written, by reference index.
+    strings: HashMap<AvmString<'gc>, u32>,
+
+    /// Objects that have already been written, in reference order.
+    objects: Vec<Object<'gc>>,
+
+    /// The classes whose traits have already been written, in reference
+    /// order.
+    traits: Vec<Option<Object<'gc>>>,
+}
+
+impl<'gc> Amf3Writer<'gc> {
+    pub fn new(target: Object<'gc>) -> Self {
+        Self {
+            target,
+            buffer: Vec::new(),
+            strings: HashMap::new(),
+            objects: Vec::new(),
+            traits: Vec::new(),
+        }
+    }
+
+    /// Write any remaining encoded data to the target `ByteArray`.
+    pub fn finish(mut self, activation: &mut Activation<'_, 'gc, '_>) -> Result<(), Error> {
+        self.flush(activation)
+    }
+
+    fn flush(&mut self, activation: &mut Activation<'_, 'gc, '_>) -> Result<(), Error> {
+        self.target
+            .as_bytearray_mut(activation.context.gc_context)
+            .ok_or("Cannot write AMF to an object that is not a ByteArray")?
+            .write_bytes(&self.buffer)?;
+        self.buffer.clear();
+
+        Ok(())
+    }
+
+    fn write_u29(&mut self, value: u32) {
+        let value = value & 0x1FFF_FFFF;
+
+        if value < 0x80 {
+            self.buffer.push(value as u8);
+        } else if value < 0x4000 {
+            self.buffer.push((value >> 7) as u8 | 0x80);
+            self.buffer.push((value & 0x7F) as u8);
+        } else if value < 0x20_0000 {
+            self.buffer.push((value >> 14) as u8 | 0x80);
+            self.buffer.push(((value >> 7) & 0x7F) as u8 | 0x80);
+            self.buffer.push((value & 0x7F) as u8);
+        } else {
+            self.buffer.push((value >> 22) as u8 | 0x80);
+            self.buffer.push(((value >> 15) & 0x7F) as u8 | 0x80);
+            self.buffer.push(((value >> 8) & 0x7F) as u8 | 0x80);
+            self.buffer.push((value & 0xFF) as u8);
+        }
+    }
+
+    fn write_string(&mut self, string: AvmString<'gc>) {
+        if string.is_empty() {
+            // The empty string is never sent by reference.
+            self.write_u29(1);
+        } else if let Some(index) = self.strings.get(&string) {
+            let index = *index;
+            self.write_u29(index << 1);
+        } else {
+            self.strings.insert(string, self.strings.len() as u32);
+            self.write_u29(((string.len() as u32) << 1) | 1);
+            self.buffer.extend_from_slice(string.as_bytes());
+        }
+    }
+
+    fn write_double(&mut self, value: f64) {
+        self.buffer.push(DOUBLE_MARKER);
+        self.buffer.extend_from_slice(&value.to_be_bytes());
+    }
+
+    /// Write a named member of a shared object, as found in the body of a
+    /// `.sol` file.
+    pub fn write_member(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        name: AvmString<'gc>,
+        value: Value<'gc>,
+    ) -> Result<(), Error> {
+        self.write_string(name);
+        self.write_value(activation, value)?;
+        self.buffer.push(0);
+
+        Ok(())
+    }
+
+    /// Write a single value.
+    pub fn write_value(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        value: Value<'gc>,
+    ) -> Result<(), Error> {
+        match value {
+            Value::Undefined => self.buffer.push(UNDEFINED_MARKER),
+            Value::Null => self.buffer.push(NULL_MARKER),
+            Value::Bool(false) => self.buffer.push(FALSE_MARKER),
+            Value::Bool(true) => self.buffer.push(TRUE_MARKER),
+            Value::Integer(i) if (INTEGER_MIN..=INTEGER_MAX).contains(&i) => {
+                self.buffer.push(INTEGER_MARKER);
+                self.write_u29(i as u32);
+            }
+            Value::Unsigned(u) if u <= INTEGER_MAX as u32 => {
+                self.buffer.push(INTEGER_MARKER);
+                self.write_u29(u);
+            }
+            Value::Integer(i) => self.write_double(i as f64),
+            Value::Unsigned(u) => self.write_double(u as f64),
+            Value::Number(n) => self.write_double(n),
+            Value::String(s) => {
+                self.buffer.push(STRING_MARKER);
+                self.write_string(s);
+            }
+            Value::Object(o) => self.write_object(activation, o)?,
+        }
+
+        Ok(())
+    }
+
+    fn write_object(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        object: Object<'gc>,
+    ) -> Result<(), Error> {
+        // Functions cannot be serialized.
+        if object.as_function_object().is_some() {
+            self.buffer.push(UNDEFINED_MARKER);
+            return Ok(());
+        }
+
+        let marker = if object.as_bytearray().is_some() {
+            BYTE_ARRAY_MARKER
+        } else if object.as_date_object().is_some() {
+            DATE_MARKER
+        } else if object.as_array_storage().is_some() {
+            ARRAY_MARKER
+        } else {
+            OBJECT_MARKER
+        };
+
+        self.buffer.push(marker);
+
+        if let Some(index) = self.objects.iter().position(|o| Object::ptr_eq(*o, object)) {
+            self.write_u29((index as u32) << 1);
+            return Ok(());
+        }
+
+        self.objects.push(object);
+
+        match marker {
+            BYTE_ARRAY_MARKER => {
+                let bytes = object.as_bytearray().unwrap().bytes().clone();
+                self.write_u29(((bytes.len() as u32) << 1) | 1);
+                self.buffer.extend_from_slice(&bytes);
+            }
+            DATE_MARKER => {
+                let time = object
+                    .as_date_object()
+                    .unwrap()
+                    .date_time()
+                    .map(|date_time| date_time.timestamp_millis() as f64)
+                    .unwrap_or(f64::NAN);
+                self.write_u29(1);
+                self.buffer.extend_from_slice(&time.to_be_bytes());
+            }
+            ARRAY_MARKER => {
+                // Elements up to the first hole are written as the dense
+                // portion. Everything else, including any later elements,
+                // is written as an associative member.
+                let dense: Vec<Value<'gc>> = object
+                    .as_array_storage()
+                    .unwrap()
+                    .iter()
+                    .take_while(|v| v.is_some())
+                    .flatten()
+                    .collect();
+
+                let mut names = Vec::new();
+                let mut last_index = 0;
+                while let Some(index) = object.get_next_enumerant(last_index) {
+                    if let Some(name) = object.get_enumerant_name(index, activation) {
+                        let is_dense = name
+                            .local_name()
+                            .parse::<usize>()
+                            .map(|index| index < dense.len())
+                            .unwrap_or(false);
+                        if name.namespace().is_public() && !is_dense {
+                            names.push(name);
+                        }
+                    }
+                    last_index = index;
+                }
+
+                self.write_u29(((dense.len() as u32) << 1) | 1);
+
+                for name in names {
+                    let value = object.get_property(object, &name, activation)?;
+                    self.write_string(name.local_name());
+                    self.write_value(activation, value)?;
+                }
+                self.write_string("".into());
+
+                for value in dense {
+                    self.write_value(activation, value)?;
+                }
+            }
+            _ => self.write_class_instance(activation, object)?,
+        }
+
+        Ok(())
+    }
+
+    fn write_class_instance(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        object: Object<'gc>,
+    ) -> Result<(), Error> {
+        let class = object.as_class_object();
+        let alias = class.and_then(|class| activation.avm2().alias_for_class(class));
+        let is_externalizable = is_externalizable(activation, class)?;
+        let is_dynamic = class
+            .and_then(|class| class.as_class())
+            .map(|class| !class.read().is_sealed())
+            .unwrap_or(true);
+        let sealed = if is_externalizable {
+            Vec::new()
+        } else {
+            sealed_members(class)
+        };
+
+        if is_externalizable && alias.is_none() {
+            return Err("Error: Externalizable classes must be registered with registerClassAlias before they can be serialized".into());
+        }
+
+        let traits_index = self
+            .traits
+            .iter()
+            .position(|written| match (written, class) {
+                (Some(written), Some(class)) => Object::ptr_eq(*written, class),
+                (None, None) => true,
+                _ => false,
+            });
+
+        match traits_index {
+            Some(index) => self.write_u29(((index as u32) << 2) | 0b01),
+            None => {
+                self.traits.push(class);
+
+                self.write_u29(
+                    ((sealed.len() as u32) << 4)
+                        | ((is_dynamic as u32) << 3)
+                        | ((is_externalizable as u32) << 2)
+                        | 0b011,
+                );
+                self.write_string(alias.unwrap_or_else(|| "".into()));
+                for name in sealed.iter() {
+                    self.write_string(*name);
+                }
+            }
+        }
+
+        if is_externalizable {
+            self.flush(activation)?;
+            return call_externalizable_method(activation, object, "writeExternal", self.target);
+        }
+
+        for name in sealed {
+            let value =
+                object.get_property(object, &QName::new(Namespace::public(), name), activation)?;
+            self.write_value(activation, value)?;
+        }
+
+        if is_dynamic {
+            let mut names = Vec::new();
+            let mut last_index = 0;
+            while let Some(index) = object.get_next_enumerant(last_index) {
+                if let Some(name) = object.get_enumerant_name(index, activation) {
+                    if name.namespace().is_public() {
+                        names.push(name);
+                    }
+                }
+                last_index = index;
+            }
+
+            for name in names {
+                let value = object.get_property(object, &name, activation)?;
+                self.write_string(name.local_name());
+                self.write_value(activation, value)?;
+            }
+
+            self.write_string("".into());
+        }
+
+        Ok(())
+    }
+}
+
+/// The traits of an object being read.
+#[derive(Clone)]
+struct Amf3Traits<'gc> {
+    class_name: AvmString<'gc>,
+    is_dynamic: bool,
+    is_externalizable: bool,
+    sealed: Vec<AvmString<'gc>>,
+}
+
+/// Reads AVM2 values out of a `ByteArray` encoded as AMF3.
+pub struct Amf3Reader<'gc> {
+    /// The `ByteArray` being read from.
+    source: Object<'gc>,
+
+    /// Strings that have been read, in reference order.
+    strings: Vec<AvmString<'gc>>,
+
+    /// Objects that have been read, in reference order.
+    objects: Vec<Value<'gc>>,
+
+    /// Traits that have been read, in reference order.
+    traits: Vec<Amf3Traits<'gc>>,
+}
+
+impl<'gc> Amf3Reader<'gc> {
+    pub fn new(source: Object<'gc>) -> Self {
+        Self {
+            source,
+            strings: Vec::new(),
+            objects: Vec::new(),
+            traits: Vec::new(),
+        }
+    }
+
+    fn read_bytes(&self, amnt: usize) -> Result<Vec<u8>, Error> {
+        Ok(self
+            .source
+            .as_bytearray()
+            .ok_or("Cannot read AMF from an object that is not a ByteArray")?
+            .read_bytes(amnt)?
+            .to_vec())
+    }
+
+    fn read_u8(&self) -> Result<u8, Error> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u29(&self) -> Result<u32, Error> {
+        let mut value = 0;
+
+        for _ in 0..3 {
+            let byte = self.read_u8()? as u32;
+            value = (value << 7) | (byte & 0x7F);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Ok((value << 8) | self.read_u8()? as u32)
+    }
+
+    fn read_double(&self) -> Result<f64, Error> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&self.read_bytes(8)?);
+
+        Ok(f64::from_be_bytes(bytes))
+    }
+
+    fn read_string(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<AvmString<'gc>, Error> {
+        let header = self.read_u29()?;
+        if header & 1 == 0 {
+            return self
+                .strings
+                .get((header >> 1) as usize)
+                .copied()
+                .ok_or_else(|| "Invalid AMF string reference".into());
+        }
+
+        let len = (header >> 1) as usize;
+        if len == 0 {
+            return Ok("".into());
+        }
+
+        let bytes = self.read_bytes(len)?;
+        let string = AvmString::new(
+            activation.context.gc_context,
+            String::from_utf8_lossy(&bytes).into_owned(),
+        );
+        self.strings.push(string);
+
+        Ok(string)
+    }
+
+    /// Read the header of a value that may be sent by reference.
+    ///
+    /// Yields the referenced value, or the remainder of the header if the
+    /// value follows inline.
+    fn read_object_header(&self) -> Result<Result<Value<'gc>, u32>, Error> {
+        let header = self.read_u29()?;
+        if header & 1 == 0 {
+            let object = self
+                .objects
+                .get((header >> 1) as usize)
+                .copied()
+                .ok_or("Invalid AMF object reference")?;
+            Ok(Ok(object))
+        } else {
+            Ok(Err(header >> 1))
+        }
+    }
+
+    /// Read a named member of a shared object, as written by
+    /// `Amf3Writer::write_member`.
+    ///
+    /// Yields `None` once the end of the data is reached.
+    pub fn read_member(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Option<(AvmString<'gc>, Value<'gc>)>, Error> {
+        let available = self
+            .source
+            .as_bytearray()
+            .ok_or("Cannot read AMF from an object that is not a ByteArray")?
+            .bytes_available();
+        if available == 0 {
+            return Ok(None);
+        }
+
+        let name = self.read_string(activation)?;
+        let value = self.read_value(activation)?;
+        self.read_u8()?;
+
+        Ok(Some((name, value)))
+    }
+
+    /// Read a single value.
+    pub fn read_value(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        let marker = self.read_u8()?;
+
+        Ok(match marker {
+            UNDEFINED_MARKER => Value::Undefined,
+            NULL_MARKER => Value::Null,
+            FALSE_MARKER => false.into(),
+            TRUE_MARKER => true.into(),
+            INTEGER_MARKER => {
+                // Sign-extend the 29-bit integer.
+                let value = ((self.read_u29()? << 3) as i32) >> 3;
+                value.into()
+            }
+            DOUBLE_MARKER => self.read_double()?.into(),
+            STRING_MARKER => self.read_string(activation)?.into(),
+            XML_DOC_MARKER | XML_MARKER => match self.read_object_header()? {
+                Ok(value) => value,
+                Err(len) => {
+                    let bytes = self.read_bytes(len as usize)?;
+                    let value: Value<'gc> = AvmString::new(
+                        activation.context.gc_context,
+                        String::from_utf8_lossy(&bytes).into_owned(),
+                    )
+                    .into();
+                    self.objects.push(value);
+                    value
+                }
+            },
+            DATE_MARKER => match self.read_object_header()? {
+                Ok(value) => value,
+                Err(_) => {
+                    let time = self.read_double()?;
+                    let date_time = if time.is_finite() {
+                        Utc.timestamp_millis_opt(time as i64).single()
+                    } else {
+                        None
+                    };
+                    let value: Value<'gc> =
+                        DateObject::from_date_time(activation, date_time)?.into();
+                    self.objects.push(value);
+                    value
+                }
+            },
+            ARRAY_MARKER => match self.read_object_header()? {
+                Ok(value) => value,
+                Err(len) => self.read_array(activation, len)?,
+            },
+            OBJECT_MARKER => match self.read_object_header()? {
+                Ok(value) => value,
+                Err(header) => self.read_class_instance(activation, header)?,
+            },
+            BYTE_ARRAY_MARKER => match self.read_object_header()? {
+                Ok(value) => value,
+                Err(len) => {
+                    let bytes = self.read_bytes(len as usize)?;
+                    let bytearray_class = activation.avm2().classes().bytearray;
+                    let bytearray = bytearray_class.construct(activation, &[])?;
+                    if let Some(mut storage) =
+                        bytearray.as_bytearray_mut(activation.context.gc_context)
+                    {
+                        storage.write_bytes(&bytes)?;
+                        storage.set_position(0);
+                    }
+                    self.objects.push(bytearray.into());
+                    bytearray.into()
+                }
+            },
+            _ => return Err(format!("Unsupported AMF3 type marker {:#04x}", marker).into()),
+        })
+    }
+
+    fn read_array(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        len: u32,
+    ) -> Result<Value<'gc>, Error> {
+        let mut array = ArrayObject::empty(activation)?;
+        self.objects.push(array.into());
+
+        loop {
+            let name = self.read_string(activation)?;
+            if name.is_empty() {
+                break;
+            }
+
+            let value = self.read_value(activation)?;
+            array.set_property(array, &QName::dynamic_name(name), value, activation)?;
+        }
+
+        for index in 0..len as usize {
+            let value = self.read_value(activation)?;
+            if let Some(mut storage) = array.as_array_storage_mut(activation.context.gc_context) {
+                storage.set(index, value);
+            }
+        }
+
+        Ok(array.into())
+    }
+
+    fn read_class_instance(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        header: u32,
+    ) -> Result<Value<'gc>, Error> {
+        let traits = if header & 1 == 0 {
+            self.traits
+                .get((header >> 1) as usize)
+                .cloned()
+                .ok_or("Invalid AMF traits reference")?
+        } else {
+            let is_externalizable = header & 0b10 != 0;
+            let is_dynamic = header & 0b100 != 0;
+            let sealed_count = header >> 3;
+            let class_name = self.read_string(activation)?;
+            let mut sealed = Vec::new();
+            for _ in 0..sealed_count {
+                sealed.push(self.read_string(activation)?);
+            }
+
+            let traits = Amf3Traits {
+                class_name,
+                is_dynamic,
+                is_externalizable,
+                sealed,
+            };
+            self.traits.push(traits.clone());
+            traits
+        };
+
+        let class = if traits.class_name.is_empty() {
+            None
+        } else {
+            activation.avm2().class_for_alias(traits.class_name)
+        };
+
+        let mut object = match class {
+            Some(class) => class.construct(activation, &[])?,
+            None => ScriptObject::object(
+                activation.context.gc_context,
+                activation.avm2().prototypes().object,
+            ),
+        };
+        self.objects.push(object.into());
+
+        if traits.is_externalizable {
+            if class.is_none() {
+                return Err(format!(
+                    "Error: Class {} must be registered with registerClassAlias before it can be deserialized",
+                    traits.class_name
+                )
+                .into());
+            }
+
+            call_externalizable_method(activation, object, "readExternal", self.source)?;
+            return Ok(object.into());
+        }
+
+        for name in traits.sealed.iter() {
+            let value = self.read_value(activation)?;
+            object.set_property(
+                object,
+                &QName::new(Namespace::public(), *name),
+                value,
+                activation,
+            )?;
+        }
+
+        if traits.is_dynamic {
+            loop {
+                let name = self.read_string(activation)?;
+                if name.is_empty() {
+                    break;
+                }
+
+                let value = self.read_value(activation)?;
+                object.set_property(object, &QName::dynamic_name(name), value, activation)?;
+            }
+        }
+
+        Ok(object.into())
+    }
+}
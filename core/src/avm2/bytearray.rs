@@ -59,6 +59,10 @@ pub struct ByteArrayStorage {
 
     /// This represents what endian to use while reading/writing data.
     endian: Endian,
+
+    /// Whether this ByteArray is shared, rather than copied, when sent to
+    /// another worker.
+    shareable: bool,
 }
 
 impl ByteArrayStorage {
@@ -68,6 +72,7 @@ impl ByteArrayStorage {
             bytes: Vec::new(),
             position: Cell::new(0),
             endian: Endian::Big,
+            shareable: false,
         }
     }
 
@@ -268,6 +273,16 @@ impl ByteArrayStorage {
         self.endian = new_endian;
     }
 
+    #[inline]
+    pub fn shareable(&self) -> bool {
+        self.shareable
+    }
+
+    #[inline]
+    pub fn set_shareable(&mut self, shareable: bool) {
+        self.shareable = shareable;
+    }
+
     #[inline]
     pub fn bytes_available(&self) -> usize {
         self.len().saturating_sub(self.position.get())
@@ -95,6 +95,12 @@ impl<'gc> Domain<'gc> {
         false
     }
 
+    /// Determine if something has been defined in this domain itself, not
+    /// counting its parents.
+    pub fn has_local_definition(self, name: QName<'gc>) -> bool {
+        self.0.read().defs.contains_key(&name)
+    }
+
     /// Resolve a QName and return the script that provided it.
     ///
     /// If a name does not exist or cannot be resolved, no script or name will
@@ -2,14 +2,13 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::names::{Namespace, QName};
-use crate::avm2::object::{Object, TObject};
+use crate::avm2::object::{FunctionObjectWeak, Object, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use crate::display_object::TDisplayObject;
 use crate::string::AvmString;
-use gc_arena::Collect;
+use gc_arena::{Collect, MutationContext};
 use std::collections::{BTreeMap, HashMap};
-use std::hash::{Hash, Hasher};
 
 /// Which phase of event dispatch is currently occurring.
 #[derive(Copy, Clone, Collect, Debug, PartialEq, Eq)]
@@ -169,6 +168,27 @@ impl<'gc> Event<'gc> {
     pub fn set_current_target(&mut self, current_target: Object<'gc>) {
         self.current_target = Some(current_target)
     }
+
+    /// Determine if this event has already been dispatched.
+    ///
+    /// Flash redispatches such events by cloning them first, so that the
+    /// original event's target and propagation state are left untouched.
+    pub fn is_dispatched(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// Create a copy of this event that has not yet been dispatched.
+    ///
+    /// Only the event type, bubbling, and cancelable flags are retained; all
+    /// dispatch state (targets, phase, propagation and cancellation) is reset.
+    pub fn undispatched_copy(&self) -> Self {
+        let mut copy = Self::new(self.event_type);
+
+        copy.bubbles = self.bubbles;
+        copy.cancelable = self.cancelable;
+
+        copy
+    }
 }
 
 /// A set of handlers organized by event type, priority, and order added.
@@ -191,17 +211,6 @@ impl<'gc> DispatchList<'gc> {
         self.0.get(&event.into())
     }
 
-    /// Get all of the event handlers for a given event type, for mutation.
-    ///
-    /// If the event type does not exist, it will be added to the dispatch
-    /// list.
-    fn get_event_mut(
-        &mut self,
-        event: impl Into<AvmString<'gc>>,
-    ) -> &mut BTreeMap<i32, Vec<EventHandler<'gc>>> {
-        self.0.entry(event.into()).or_insert_with(BTreeMap::new)
-    }
-
     /// Get a single priority level of event handlers for a given event type,
     /// for mutation.
     fn get_event_priority_mut(
@@ -222,25 +231,36 @@ impl<'gc> DispatchList<'gc> {
     /// more than one priority (since we can't enforce that with clever-er data
     /// structure selection). If an event handler already exists, it will not
     /// be added again, and this function will silently fail.
+    ///
+    /// If `use_weak_reference` is set, and the handler is a function, then
+    /// this dispatch list will not keep the handler alive. Handlers that have
+    /// been collected are pruned from the list the next time it is used.
     pub fn add_event_listener(
         &mut self,
-        event: impl Into<AvmString<'gc>> + Clone,
+        event: impl Into<AvmString<'gc>>,
         priority: i32,
         handler: Object<'gc>,
         use_capture: bool,
+        use_weak_reference: bool,
+        mc: MutationContext<'gc, '_>,
     ) {
-        let new_handler = EventHandler::new(handler, use_capture);
+        let event = event.into();
 
-        if let Some(event_sheaf) = self.get_event(event.clone()) {
+        self.prune_event_listeners(event, mc);
+
+        if let Some(event_sheaf) = self.get_event(event) {
             for (_other_prio, other_set) in event_sheaf.iter() {
-                if other_set.contains(&new_handler) {
+                if other_set
+                    .iter()
+                    .any(|h| h.is_handler(handler, use_capture, mc))
+                {
                     return;
                 }
             }
         }
 
         self.get_event_priority_mut(event, priority)
-            .push(new_handler);
+            .push(EventHandler::new(handler, use_capture, use_weak_reference));
     }
 
     /// Remove an event handler from this dispatch list.
@@ -252,21 +272,57 @@ impl<'gc> DispatchList<'gc> {
         event: impl Into<AvmString<'gc>>,
         handler: Object<'gc>,
         use_capture: bool,
+        mc: MutationContext<'gc, '_>,
+    ) {
+        let event = event.into();
+
+        self.prune_event_listeners(event, mc);
+
+        if let Some(event_sheaf) = self.0.get_mut(&event) {
+            for (_prio, set) in event_sheaf.iter_mut() {
+                if let Some(pos) = set
+                    .iter()
+                    .position(|h| h.is_handler(handler, use_capture, mc))
+                {
+                    set.remove(pos);
+                }
+            }
+
+            event_sheaf.retain(|_prio, set| !set.is_empty());
+        }
+    }
+
+    /// Remove any weakly-referenced event handlers for a given event that
+    /// have since been collected.
+    pub fn prune_event_listeners(
+        &mut self,
+        event: impl Into<AvmString<'gc>>,
+        mc: MutationContext<'gc, '_>,
     ) {
-        let old_handler = EventHandler::new(handler, use_capture);
+        let event = event.into();
 
-        for (_prio, set) in self.get_event_mut(event).iter_mut() {
-            if let Some(pos) = set.iter().position(|h| *h == old_handler) {
-                set.remove(pos);
+        if let Some(event_sheaf) = self.0.get_mut(&event) {
+            for (_prio, set) in event_sheaf.iter_mut() {
+                set.retain(|h| h.handler(mc).is_some());
+            }
+
+            event_sheaf.retain(|_prio, set| !set.is_empty());
+
+            if event_sheaf.is_empty() {
+                self.0.remove(&event);
             }
         }
     }
 
-    /// Determine if there are any event listeners in this dispatch list.
-    pub fn has_event_listener(&self, event: impl Into<AvmString<'gc>>) -> bool {
+    /// Determine if there are any live event listeners in this dispatch list.
+    pub fn has_event_listener(
+        &self,
+        event: impl Into<AvmString<'gc>>,
+        mc: MutationContext<'gc, '_>,
+    ) -> bool {
         if let Some(event_sheaf) = self.get_event(event) {
             for (_prio, set) in event_sheaf.iter() {
-                if !set.is_empty() {
+                if set.iter().any(|h| h.handler(mc).is_some()) {
                     return true;
                 }
             }
@@ -278,22 +334,25 @@ impl<'gc> DispatchList<'gc> {
     /// Yield the event handlers on this dispatch list for a given event.
     ///
     /// Event handlers will be yielded in the order they are intended to be
-    /// executed.
+    /// executed: highest priority first, and in the order they were added
+    /// within a single priority. Weakly-referenced handlers that have been
+    /// collected are skipped.
     ///
     /// `use_capture` indicates if you want handlers that execute during the
     /// capture phase, or handlers that execute during the bubble and target
     /// phases.
     pub fn iter_event_handlers<'a>(
-        &'a mut self,
+        &'a self,
         event: impl Into<AvmString<'gc>>,
         use_capture: bool,
+        mc: MutationContext<'gc, 'a>,
     ) -> impl 'a + Iterator<Item = Object<'gc>> {
-        self.get_event_mut(event)
-            .iter()
-            .rev()
+        self.get_event(event)
+            .into_iter()
+            .flat_map(|sheaf| sheaf.iter().rev())
             .flat_map(|(_p, v)| v.iter())
             .filter(move |eh| eh.use_capture == use_capture)
-            .map(|eh| eh.handler)
+            .filter_map(move |eh| eh.handler(mc))
     }
 }
 
@@ -302,7 +361,7 @@ impl<'gc> DispatchList<'gc> {
 #[collect(no_drop)]
 struct EventHandler<'gc> {
     /// The event handler to call.
-    handler: Object<'gc>,
+    handler: EventHandlerRef<'gc>,
 
     /// Indicates if this handler should only be called for capturing events
     /// (when `true`), or if it should only be called for bubbling and
@@ -310,27 +369,51 @@ struct EventHandler<'gc> {
     use_capture: bool,
 }
 
+/// How a dispatch list refers to an event handler.
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+enum EventHandlerRef<'gc> {
+    /// The handler is kept alive by the dispatch list.
+    Strong(Object<'gc>),
+
+    /// The handler is only kept alive by other references to it, and will be
+    /// pruned from the dispatch list once it has been collected.
+    Weak(FunctionObjectWeak<'gc>),
+}
+
 impl<'gc> EventHandler<'gc> {
-    fn new(handler: Object<'gc>, use_capture: bool) -> Self {
+    fn new(handler: Object<'gc>, use_capture: bool, use_weak_reference: bool) -> Self {
+        let handler = match handler.as_function_object() {
+            Some(function) if use_weak_reference => EventHandlerRef::Weak(function.downgrade()),
+            _ => EventHandlerRef::Strong(handler),
+        };
+
         Self {
             handler,
             use_capture,
         }
     }
-}
 
-impl<'gc> PartialEq for EventHandler<'gc> {
-    fn eq(&self, rhs: &Self) -> bool {
-        self.use_capture == rhs.use_capture && Object::ptr_eq(self.handler, rhs.handler)
+    /// Retrieve the handler to call, if it has not yet been collected.
+    fn handler(&self, mc: MutationContext<'gc, '_>) -> Option<Object<'gc>> {
+        match &self.handler {
+            EventHandlerRef::Strong(handler) => Some(*handler),
+            EventHandlerRef::Weak(handler) => handler.upgrade(mc),
+        }
     }
-}
-
-impl<'gc> Eq for EventHandler<'gc> {}
 
-impl<'gc> Hash for EventHandler<'gc> {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.use_capture.hash(state);
-        self.handler.as_ptr().hash(state);
+    /// Determine if this is a live registration of the given handler.
+    fn is_handler(
+        &self,
+        handler: Object<'gc>,
+        use_capture: bool,
+        mc: MutationContext<'gc, '_>,
+    ) -> bool {
+        self.use_capture == use_capture
+            && self
+                .handler(mc)
+                .map(|h| Object::ptr_eq(h, handler))
+                .unwrap_or(false)
     }
 }
 
@@ -360,6 +443,12 @@ pub fn parent_of(target: Object<'_>) -> Option<Object<'_>> {
 /// `EventObject`, or this function will panic. You must have already set the
 /// event's phase to match what targets you are dispatching to, or you will
 /// call the wrong handlers.
+///
+/// The list of handlers is captured before any of them are called. Listeners
+/// removed from `target` during dispatch will still be called, and listeners
+/// added to `target` during dispatch will not be called until the next time
+/// the event reaches it. `stopImmediatePropagation` halts the remaining
+/// handlers on this target; `stopPropagation` does not.
 pub fn dispatch_event_to_target<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     target: Object<'gc>,
@@ -394,12 +483,19 @@ pub fn dispatch_event_to_target<'gc>(
 
     drop(evtmut);
 
+    let mc = activation.context.gc_context;
+    let mut dispatch_list = dispatch_list
+        .as_dispatch_mut(mc)
+        .ok_or_else(|| Error::from("Internal dispatch list is missing during dispatch!"))?;
+
+    dispatch_list.prune_event_listeners(name, mc);
+
     let handlers: Vec<Object<'gc>> = dispatch_list
-        .as_dispatch_mut(activation.context.gc_context)
-        .ok_or_else(|| Error::from("Internal dispatch list is missing during dispatch!"))?
-        .iter_event_handlers(name, use_capture)
+        .iter_event_handlers(name, use_capture, mc)
         .collect();
 
+    drop(dispatch_list);
+
     for handler in handlers.iter() {
         if event
             .as_event()
@@ -420,6 +516,16 @@ pub fn dispatch_event_to_target<'gc>(
     Ok(())
 }
 
+/// Dispatch an event to a target and its ancestors.
+///
+/// The propagation path is determined before dispatch begins. The event is
+/// first delivered to capturing listeners on each ancestor, from the root
+/// down; then to non-capturing listeners on the target itself; and finally,
+/// if the event bubbles, to non-capturing listeners on each ancestor, from
+/// the target's parent up. Stopping propagation takes effect once the current
+/// target's handlers have finished.
+///
+/// Returns `false` if the event was cancelled.
 pub fn dispatch_event<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
@@ -81,6 +81,15 @@ impl<'gc> Executable<'gc> {
         }
     }
 
+    /// The number of parameters this method declares, not counting any rest
+    /// parameter.
+    pub fn num_parameters(&self) -> usize {
+        match self {
+            Executable::Native(bm) => bm.method.signature.len(),
+            Executable::Action(bm) => bm.method.signature().len(),
+        }
+    }
+
     /// Execute a method.
     ///
     /// The function will either be called directly if it is a Rust builtin, or
@@ -18,6 +18,7 @@ use gc_arena::{Collect, GcCell, MutationContext};
 mod array;
 mod boolean;
 mod class;
+mod date;
 mod flash;
 mod function;
 mod global_scope;
@@ -33,6 +34,19 @@ mod vector;
 mod xml;
 mod xml_list;
 
+pub use flash::net::responder::callback as responder_callback;
+pub use flash::net::sharedobject::flush_all as flush_shared_objects;
+pub use flash::system::ime::dispatch_composition as ime_dispatch_composition;
+pub use flash::system::message_channel::{
+    channel_id as message_channel_id, channel_object as message_channel_object,
+};
+pub use flash::system::worker::{worker_id, worker_object};
+pub use flash::ui::contextmenu::make_context_menu_state as make_avm2_context_menu_state;
+pub use flash::ui::gameinput::{
+    control_changed as game_input_control_changed, device_added as game_input_device_added,
+    device_removed as game_input_device_removed,
+};
+
 const NS_RUFFLE_INTERNAL: &str = "https://ruffle.rs/AS3/impl/";
 const NS_VECTOR: &str = "__AS3__.vec";
 
@@ -98,28 +112,70 @@ pub struct SystemPrototypes<'gc> {
     pub framelabel: Object<'gc>,
     pub scene: Object<'gc>,
     pub application_domain: Object<'gc>,
+    pub worker: Object<'gc>,
+    pub workerdomain: Object<'gc>,
+    pub messagechannel: Object<'gc>,
     pub event: Object<'gc>,
+    pub eventdispatcher: Object<'gc>,
+    pub mouseevent: Object<'gc>,
+    pub keyboardevent: Object<'gc>,
+    pub progressevent: Object<'gc>,
+    pub textevent: Object<'gc>,
+    pub imeevent: Object<'gc>,
+    pub fullscreenevent: Object<'gc>,
+    pub dataevent: Object<'gc>,
+    pub contextmenuevent: Object<'gc>,
+    pub touchevent: Object<'gc>,
+    pub gestureevent: Object<'gc>,
+    pub transformgestureevent: Object<'gc>,
+    pub gameinputevent: Object<'gc>,
+    pub ioerrorevent: Object<'gc>,
+    pub httpstatusevent: Object<'gc>,
+    pub statusevent: Object<'gc>,
+    pub netstatusevent: Object<'gc>,
+    pub asyncerrorevent: Object<'gc>,
+    pub securityerrorevent: Object<'gc>,
+    pub shaderevent: Object<'gc>,
+    pub uncaughterrorevent: Object<'gc>,
+    pub uncaughterrorevents: Object<'gc>,
     pub video: Object<'gc>,
     pub xml: Object<'gc>,
     pub xml_list: Object<'gc>,
     pub display_object: Object<'gc>,
     pub shape: Object<'gc>,
+    pub morphshape: Object<'gc>,
     pub point: Object<'gc>,
     pub rectangle: Object<'gc>,
     pub textfield: Object<'gc>,
     pub textformat: Object<'gc>,
+    pub textsnapshot: Object<'gc>,
     pub graphics: Object<'gc>,
     pub loaderinfo: Object<'gc>,
+    pub avm1movie: Object<'gc>,
     pub bytearray: Object<'gc>,
+    pub urlvariables: Object<'gc>,
+    pub sharedobject: Object<'gc>,
+    pub iexternalizable: Object<'gc>,
     pub stage: Object<'gc>,
     pub sprite: Object<'gc>,
     pub simplebutton: Object<'gc>,
     pub regexp: Object<'gc>,
+    pub date: Object<'gc>,
     pub vector: Object<'gc>,
     pub soundtransform: Object<'gc>,
     pub soundchannel: Object<'gc>,
     pub bitmap: Object<'gc>,
     pub bitmapdata: Object<'gc>,
+    pub stage3d: Object<'gc>,
+    pub context3d: Object<'gc>,
+    pub vertexbuffer3d: Object<'gc>,
+    pub indexbuffer3d: Object<'gc>,
+    pub program3d: Object<'gc>,
+    pub texture: Object<'gc>,
+    pub vector3d: Object<'gc>,
+    pub matrix3d: Object<'gc>,
+    pub perspectiveprojection: Object<'gc>,
+    pub transform: Object<'gc>,
 }
 
 impl<'gc> SystemPrototypes<'gc> {
@@ -152,28 +208,70 @@ impl<'gc> SystemPrototypes<'gc> {
             framelabel: empty,
             scene: empty,
             application_domain: empty,
+            worker: empty,
+            workerdomain: empty,
+            messagechannel: empty,
             event: empty,
+            eventdispatcher: empty,
+            mouseevent: empty,
+            keyboardevent: empty,
+            progressevent: empty,
+            textevent: empty,
+            imeevent: empty,
+            fullscreenevent: empty,
+            dataevent: empty,
+            contextmenuevent: empty,
+            touchevent: empty,
+            gestureevent: empty,
+            transformgestureevent: empty,
+            gameinputevent: empty,
+            ioerrorevent: empty,
+            httpstatusevent: empty,
+            statusevent: empty,
+            netstatusevent: empty,
+            asyncerrorevent: empty,
+            securityerrorevent: empty,
+            shaderevent: empty,
+            uncaughterrorevent: empty,
+            uncaughterrorevents: empty,
             video: empty,
             xml: empty,
             xml_list: empty,
             display_object: empty,
             shape: empty,
+            morphshape: empty,
             point: empty,
             rectangle: empty,
             textfield: empty,
             textformat: empty,
+            textsnapshot: empty,
             graphics: empty,
             loaderinfo: empty,
+            avm1movie: empty,
             bytearray: empty,
+            urlvariables: empty,
+            sharedobject: empty,
+            iexternalizable: empty,
             stage: empty,
             sprite: empty,
             simplebutton: empty,
             regexp: empty,
+            date: empty,
             vector: empty,
             soundtransform: empty,
             soundchannel: empty,
             bitmap: empty,
             bitmapdata: empty,
+            stage3d: empty,
+            context3d: empty,
+            vertexbuffer3d: empty,
+            indexbuffer3d: empty,
+            program3d: empty,
+            texture: empty,
+            vector3d: empty,
+            matrix3d: empty,
+            perspectiveprojection: empty,
+            transform: empty,
         }
     }
 }
@@ -197,28 +295,70 @@ pub struct SystemClasses<'gc> {
     pub framelabel: Object<'gc>,
     pub scene: Object<'gc>,
     pub application_domain: Object<'gc>,
+    pub worker: Object<'gc>,
+    pub workerdomain: Object<'gc>,
+    pub messagechannel: Object<'gc>,
     pub event: Object<'gc>,
+    pub eventdispatcher: Object<'gc>,
+    pub mouseevent: Object<'gc>,
+    pub keyboardevent: Object<'gc>,
+    pub progressevent: Object<'gc>,
+    pub textevent: Object<'gc>,
+    pub imeevent: Object<'gc>,
+    pub fullscreenevent: Object<'gc>,
+    pub dataevent: Object<'gc>,
+    pub contextmenuevent: Object<'gc>,
+    pub touchevent: Object<'gc>,
+    pub gestureevent: Object<'gc>,
+    pub transformgestureevent: Object<'gc>,
+    pub gameinputevent: Object<'gc>,
+    pub ioerrorevent: Object<'gc>,
+    pub httpstatusevent: Object<'gc>,
+    pub statusevent: Object<'gc>,
+    pub netstatusevent: Object<'gc>,
+    pub asyncerrorevent: Object<'gc>,
+    pub securityerrorevent: Object<'gc>,
+    pub shaderevent: Object<'gc>,
+    pub uncaughterrorevent: Object<'gc>,
+    pub uncaughterrorevents: Object<'gc>,
     pub video: Object<'gc>,
     pub xml: Object<'gc>,
     pub xml_list: Object<'gc>,
     pub display_object: Object<'gc>,
     pub shape: Object<'gc>,
+    pub morphshape: Object<'gc>,
     pub point: Object<'gc>,
     pub rectangle: Object<'gc>,
     pub textfield: Object<'gc>,
     pub textformat: Object<'gc>,
+    pub textsnapshot: Object<'gc>,
     pub graphics: Object<'gc>,
     pub loaderinfo: Object<'gc>,
+    pub avm1movie: Object<'gc>,
     pub bytearray: Object<'gc>,
+    pub urlvariables: Object<'gc>,
+    pub sharedobject: Object<'gc>,
+    pub iexternalizable: Object<'gc>,
     pub stage: Object<'gc>,
     pub sprite: Object<'gc>,
     pub simplebutton: Object<'gc>,
     pub regexp: Object<'gc>,
+    pub date: Object<'gc>,
     pub vector: Object<'gc>,
     pub soundtransform: Object<'gc>,
     pub soundchannel: Object<'gc>,
     pub bitmap: Object<'gc>,
     pub bitmapdata: Object<'gc>,
+    pub stage3d: Object<'gc>,
+    pub context3d: Object<'gc>,
+    pub vertexbuffer3d: Object<'gc>,
+    pub indexbuffer3d: Object<'gc>,
+    pub program3d: Object<'gc>,
+    pub texture: Object<'gc>,
+    pub vector3d: Object<'gc>,
+    pub matrix3d: Object<'gc>,
+    pub perspectiveprojection: Object<'gc>,
+    pub transform: Object<'gc>,
 }
 
 impl<'gc> SystemClasses<'gc> {
@@ -251,28 +391,70 @@ impl<'gc> SystemClasses<'gc> {
             framelabel: empty,
             scene: empty,
             application_domain: empty,
+            worker: empty,
+            workerdomain: empty,
+            messagechannel: empty,
             event: empty,
+            eventdispatcher: empty,
+            mouseevent: empty,
+            keyboardevent: empty,
+            progressevent: empty,
+            textevent: empty,
+            imeevent: empty,
+            fullscreenevent: empty,
+            dataevent: empty,
+            contextmenuevent: empty,
+            touchevent: empty,
+            gestureevent: empty,
+            transformgestureevent: empty,
+            gameinputevent: empty,
+            ioerrorevent: empty,
+            httpstatusevent: empty,
+            statusevent: empty,
+            netstatusevent: empty,
+            asyncerrorevent: empty,
+            securityerrorevent: empty,
+            shaderevent: empty,
+            uncaughterrorevent: empty,
+            uncaughterrorevents: empty,
             video: empty,
             xml: empty,
             xml_list: empty,
             display_object: empty,
             shape: empty,
+            morphshape: empty,
             point: empty,
             rectangle: empty,
             textfield: empty,
             textformat: empty,
+            textsnapshot: empty,
             graphics: empty,
             loaderinfo: empty,
+            avm1movie: empty,
             bytearray: empty,
+            urlvariables: empty,
+            sharedobject: empty,
+            iexternalizable: empty,
             stage: empty,
             sprite: empty,
             simplebutton: empty,
             regexp: empty,
+            date: empty,
             vector: empty,
             soundtransform: empty,
             soundchannel: empty,
             bitmap: empty,
             bitmapdata: empty,
+            stage3d: empty,
+            context3d: empty,
+            vertexbuffer3d: empty,
+            indexbuffer3d: empty,
+            program3d: empty,
+            texture: empty,
+            vector3d: empty,
+            matrix3d: empty,
+            perspectiveprojection: empty,
+            transform: empty,
         }
     }
 }
@@ -322,50 +504,57 @@ fn dynamic_class<'gc>(
     domain.export_definition(name, script, mc)
 }
 
-/// Add a class builtin to the global scope.
+/// Construct the class object of a builtin class and install it on a global
+/// scope.
 ///
-/// This function returns the class object and class prototype as a pair, which
-/// may be stored in `SystemClasses` and `SystemPrototypes`, respectively.
-fn class<'gc>(
+/// The superclass is looked up in the domain of the global scope, so that it
+/// may be a class that is constructed lazily.
+pub fn construct_class<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     class_def: GcCell<'gc, Class<'gc>>,
-    mut domain: Domain<'gc>,
-    script: Script<'gc>,
+    mut global: Object<'gc>,
 ) -> Result<(Object<'gc>, Object<'gc>), Error> {
-    let mut global = script.init().1;
     let global_scope = Scope::push_scope(global.get_scope(), global, activation.context.gc_context);
+    let domain = global
+        .as_application_domain()
+        .ok_or("Builtin classes must be installed on a global scope")?;
 
     let class_read = class_def.read();
-    let super_class = if let Some(sc_name) = class_read.super_class_name() {
-        let super_name = global
-            .resolve_multiname(sc_name)?
-            .unwrap_or_else(|| QName::dynamic_name("Object"));
-
-        let super_class: Result<Object<'gc>, Error> = global
-            .get_property(global, &super_name, activation)?
-            .coerce_to_object(activation)
-            .map_err(|_e| {
-                format!("Could not resolve superclass {:?}", super_name.local_name()).into()
-            });
+    let class_name = class_read.name().clone();
+    let super_class_name = class_read.super_class_name().clone();
+    drop(class_read);
+
+    // Looking up the superclass may construct it, and all of its superclasses.
+    let super_class = if let Some(sc_name) = super_class_name {
+        let super_class = match domain.get_defining_script(&sc_name)? {
+            Some((super_name, mut script)) => {
+                let super_global = script.globals(&mut activation.context)?;
+                super_global
+                    .get_property(super_global, &super_name, activation)?
+                    .coerce_to_object(activation)
+                    .map_err(|_e| {
+                        format!("Could not resolve superclass {:?}", super_name.local_name()).into()
+                    })
+            }
+            None => global
+                .get_property(global, &QName::dynamic_name("Object"), activation)?
+                .coerce_to_object(activation),
+        };
 
         Some(super_class?)
     } else {
         None
     };
 
-    let class_name = class_read.name().clone();
-    drop(class_read);
-
     let class_object =
         ClassObject::from_class(activation, class_def, super_class, Some(global_scope))?;
     global.install_const(
         activation.context.gc_context,
-        class_name.clone(),
+        class_name,
         0,
         class_object.into(),
         false,
     );
-    domain.export_definition(class_name, script, activation.context.gc_context)?;
 
     let proto = class_object
         .get_property(
@@ -378,6 +567,44 @@ fn class<'gc>(
     Ok((class_object, proto))
 }
 
+/// Add a class builtin to the global scope.
+///
+/// This function returns the class object and class prototype as a pair, which
+/// may be stored in `SystemClasses` and `SystemPrototypes`, respectively.
+fn class<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    class_def: GcCell<'gc, Class<'gc>>,
+    mut domain: Domain<'gc>,
+    script: Script<'gc>,
+) -> Result<(Object<'gc>, Object<'gc>), Error> {
+    let class_name = class_def.read().name().clone();
+    let (class_object, proto) = construct_class(activation, class_def, script.init().1)?;
+    domain.export_definition(class_name, script, activation.context.gc_context)?;
+
+    Ok((class_object, proto))
+}
+
+/// Add a class builtin to the global scope without constructing it.
+///
+/// The class is defined by a script of its own, which constructs the class
+/// object the first time the class is looked up in the domain. Most movies
+/// only use a handful of builtin classes, so this saves constructing the rest
+/// at startup.
+///
+/// Classes that are needed by the player itself must be stored in
+/// `SystemClasses` with `avm2_system_class!` instead.
+fn lazy_class<'gc>(
+    mc: MutationContext<'gc, '_>,
+    class_def: GcCell<'gc, Class<'gc>>,
+    mut domain: Domain<'gc>,
+    script: Script<'gc>,
+) -> Result<(), Error> {
+    let class_name = class_def.read().name().clone();
+    let class_script = Script::builtin_class(mc, script.init().1, class_def);
+
+    domain.export_definition(class_name, class_script, mc)
+}
+
 /// Add a builtin constant to the global scope.
 fn constant<'gc>(
     mc: MutationContext<'gc, '_>,
@@ -517,8 +744,9 @@ pub fn load_player_globals<'gc>(
     constant(mc, "", "NaN", f64::NAN.into(), domain, script)?;
     constant(mc, "", "Infinity", f64::INFINITY.into(), domain, script)?;
 
-    class(activation, math::create_class(mc), domain, script)?;
+    lazy_class(mc, math::create_class(mc), domain, script)?;
     avm2_system_class!(regexp, activation, regexp::create_class(mc), domain, script);
+    avm2_system_class!(date, activation, date::create_class(mc), domain, script);
     avm2_system_class!(vector, activation, vector::create_class(mc), domain, script);
     avm2_system_class!(xml, activation, xml::create_class(mc), domain, script);
     avm2_system_class!(
@@ -537,379 +765,1118 @@ pub fn load_player_globals<'gc>(
         domain,
         script
     );
-    class(
-        activation,
+    lazy_class(
+        mc,
         flash::system::capabilities::create_class(mc),
         domain,
         script,
     )?;
-    class(
-        activation,
-        flash::system::security::create_class(mc),
-        domain,
-        script,
-    )?;
-    class(
-        activation,
-        flash::system::system::create_class(mc),
-        domain,
-        script,
-    )?;
-
-    // package `flash.events`
-    avm2_system_class!(
-        event,
-        activation,
-        flash::events::event::create_class(mc),
-        domain,
-        script
-    );
-    class(
-        activation,
-        flash::events::ieventdispatcher::create_interface(mc),
-        domain,
-        script,
-    )?;
-    class(
-        activation,
-        flash::events::eventdispatcher::create_class(mc),
-        domain,
-        script,
-    )?;
-    class(
-        activation,
-        flash::events::mouseevent::create_class(mc),
+    lazy_class(
+        mc,
+        flash::system::image_decoding_policy::create_class(mc),
         domain,
         script,
     )?;
-    class(
-        activation,
-        flash::events::keyboardevent::create_class(mc),
+    lazy_class(
+        mc,
+        flash::system::security::create_class(mc),
         domain,
         script,
     )?;
-    class(
-        activation,
-        flash::events::progressevent::create_class(mc),
+    lazy_class(mc, flash::system::system::create_class(mc), domain, script)?;
+    lazy_class(mc, flash::system::ime::create_class(mc), domain, script)?;
+    lazy_class(
+        mc,
+        flash::system::loader_context::create_class(mc),
         domain,
         script,
     )?;
-    // package `flash.utils`
     avm2_system_class!(
-        bytearray,
+        workerdomain,
         activation,
-        flash::utils::bytearray::create_class(mc),
+        flash::system::worker_domain::create_class(mc),
         domain,
         script
     );
-
-    //We also have to do this to the global scope, too.
-    gs.as_application_domain()
-        .unwrap()
-        .init_default_domain_memory(activation)?;
-
-    class(
-        activation,
-        flash::utils::endian::create_class(mc),
-        domain,
-        script,
-    )?;
-
-    class(
-        activation,
-        flash::utils::compression_algorithm::create_class(mc),
-        domain,
-        script,
-    )?;
-
-    function(
+    lazy_class(
         mc,
-        "flash.utils",
-        "getTimer",
-        flash::utils::get_timer,
-        fn_proto,
+        flash::system::worker_state::create_class(mc),
         domain,
         script,
     )?;
-
-    function(
+    lazy_class(
         mc,
-        "flash.utils",
-        "getQualifiedClassName",
-        flash::utils::get_qualified_class_name,
-        fn_proto,
+        flash::system::message_channel_state::create_class(mc),
         domain,
         script,
     )?;
 
-    function(
-        mc,
-        "flash.utils",
-        "getQualifiedSuperclassName",
-        flash::utils::get_qualified_super_class_name,
-        fn_proto,
+    // package `flash.events`
+    avm2_system_class!(
+        event,
+        activation,
+        flash::events::event::create_class(mc),
         domain,
-        script,
-    )?;
-
-    function(
+        script
+    );
+    lazy_class(
         mc,
-        "flash.utils",
-        "getDefinitionByName",
-        flash::utils::get_definition_by_name,
-        fn_proto,
-        domain,
-        script,
-    )?;
-
-    // package `flash.display`
-    class(
-        activation,
-        flash::display::ibitmapdrawable::create_interface(mc),
+        flash::events::ieventdispatcher::create_interface(mc),
         domain,
         script,
     )?;
     avm2_system_class!(
-        display_object,
+        eventdispatcher,
         activation,
-        flash::display::displayobject::create_class(mc),
+        flash::events::eventdispatcher::create_class(mc),
         domain,
         script
     );
     avm2_system_class!(
-        shape,
+        mouseevent,
         activation,
-        flash::display::shape::create_class(mc),
+        flash::events::mouseevent::create_class(mc),
         domain,
         script
     );
-    class(
-        activation,
-        flash::display::interactiveobject::create_class(mc),
-        domain,
-        script,
-    )?;
     avm2_system_class!(
-        simplebutton,
+        keyboardevent,
         activation,
-        flash::display::simplebutton::create_class(mc),
+        flash::events::keyboardevent::create_class(mc),
         domain,
         script
     );
-    class(
-        activation,
-        flash::display::displayobjectcontainer::create_class(mc),
-        domain,
-        script,
-    )?;
     avm2_system_class!(
-        sprite,
+        progressevent,
         activation,
-        flash::display::sprite::create_class(mc),
+        flash::events::progressevent::create_class(mc),
         domain,
         script
     );
     avm2_system_class!(
-        movieclip,
+        textevent,
         activation,
-        flash::display::movieclip::create_class(mc),
+        flash::events::textevent::create_class(mc),
         domain,
         script
     );
     avm2_system_class!(
-        framelabel,
+        imeevent,
         activation,
-        flash::display::framelabel::create_class(mc),
+        flash::events::imeevent::create_class(mc),
         domain,
         script
     );
     avm2_system_class!(
-        scene,
+        fullscreenevent,
         activation,
-        flash::display::scene::create_class(mc),
+        flash::events::fullscreenevent::create_class(mc),
         domain,
         script
     );
     avm2_system_class!(
-        graphics,
+        dataevent,
         activation,
-        flash::display::graphics::create_class(mc),
+        flash::events::dataevent::create_class(mc),
         domain,
         script
     );
-    class(
-        activation,
-        flash::display::jointstyle::create_class(mc),
-        domain,
-        script,
-    )?;
-    class(
-        activation,
-        flash::display::linescalemode::create_class(mc),
-        domain,
-        script,
-    )?;
-    class(
-        activation,
-        flash::display::capsstyle::create_class(mc),
-        domain,
-        script,
-    )?;
     avm2_system_class!(
-        loaderinfo,
+        contextmenuevent,
         activation,
-        flash::display::loaderinfo::create_class(mc),
+        flash::events::contextmenuevent::create_class(mc),
         domain,
         script
     );
-    class(
-        activation,
-        flash::display::actionscriptversion::create_class(mc),
-        domain,
-        script,
-    )?;
-    class(
+    avm2_system_class!(
+        touchevent,
         activation,
-        flash::display::swfversion::create_class(mc),
+        flash::events::touchevent::create_class(mc),
         domain,
-        script,
-    )?;
+        script
+    );
     avm2_system_class!(
-        stage,
+        gestureevent,
         activation,
-        flash::display::stage::create_class(mc),
+        flash::events::gestureevent::create_class(mc),
         domain,
         script
     );
-    class(
+    avm2_system_class!(
+        transformgestureevent,
         activation,
-        flash::display::stagescalemode::create_class(mc),
+        flash::events::transformgestureevent::create_class(mc),
         domain,
-        script,
-    )?;
-    class(
+        script
+    );
+    avm2_system_class!(
+        gameinputevent,
         activation,
-        flash::display::stagealign::create_class(mc),
+        flash::events::gameinputevent::create_class(mc),
         domain,
-        script,
-    )?;
-    class(
-        activation,
-        flash::display::stagedisplaystate::create_class(mc),
+        script
+    );
+    lazy_class(
+        mc,
+        flash::events::gesturephase::create_class(mc),
         domain,
         script,
     )?;
-    class(
-        activation,
-        flash::display::stagequality::create_class(mc),
+    lazy_class(
+        mc,
+        flash::events::errorevent::create_class(mc),
         domain,
         script,
     )?;
     avm2_system_class!(
-        bitmap,
+        asyncerrorevent,
         activation,
-        flash::display::bitmap::create_class(mc),
+        flash::events::asyncerrorevent::create_class(mc),
         domain,
         script
     );
     avm2_system_class!(
-        bitmapdata,
+        ioerrorevent,
         activation,
-        flash::display::bitmapdata::create_class(mc),
+        flash::events::ioerrorevent::create_class(mc),
         domain,
         script
     );
-
-    // package `flash.geom`
     avm2_system_class!(
-        point,
+        httpstatusevent,
         activation,
-        flash::geom::point::create_class(mc),
+        flash::events::httpstatusevent::create_class(mc),
         domain,
         script
     );
     avm2_system_class!(
-        rectangle,
+        securityerrorevent,
         activation,
-        flash::geom::rectangle::create_class(mc),
+        flash::events::securityerrorevent::create_class(mc),
         domain,
         script
     );
-
-    // package `flash.media`
     avm2_system_class!(
-        video,
+        shaderevent,
         activation,
-        flash::media::video::create_class(mc),
+        flash::events::shaderevent::create_class(mc),
         domain,
         script
     );
-    class(
+    avm2_system_class!(
+        statusevent,
         activation,
-        flash::media::sound::create_class(mc),
+        flash::events::statusevent::create_class(mc),
         domain,
-        script,
-    )?;
+        script
+    );
     avm2_system_class!(
-        soundtransform,
+        netstatusevent,
         activation,
-        flash::media::soundtransform::create_class(mc),
+        flash::events::netstatusevent::create_class(mc),
         domain,
         script
     );
-    class(
+    avm2_system_class!(
+        uncaughterrorevent,
         activation,
-        flash::media::soundmixer::create_class(mc),
+        flash::events::uncaughterrorevent::create_class(mc),
         domain,
-        script,
-    )?;
+        script
+    );
     avm2_system_class!(
-        soundchannel,
+        uncaughterrorevents,
         activation,
-        flash::media::soundchannel::create_class(mc),
+        flash::events::uncaughterrorevents::create_class(mc),
         domain,
         script
     );
 
-    // package `flash.text`
+    // package `flash.system`, classes that extend `EventDispatcher`
     avm2_system_class!(
-        textfield,
+        worker,
         activation,
-        flash::text::textfield::create_class(mc),
+        flash::system::worker::create_class(mc),
         domain,
         script
     );
     avm2_system_class!(
-        textformat,
+        messagechannel,
         activation,
-        flash::text::textformat::create_class(mc),
+        flash::system::message_channel::create_class(mc),
         domain,
         script
     );
-    class(
-        activation,
-        flash::text::textfieldautosize::create_class(mc),
-        domain,
+
+    // package `flash.utils`
+    avm2_system_class!(
+        bytearray,
+        activation,
+        flash::utils::bytearray::create_class(mc),
+        domain,
+        script
+    );
+
+    //We also have to do this to the global scope, too.
+    gs.as_application_domain()
+        .unwrap()
+        .init_default_domain_memory(activation)?;
+
+    lazy_class(mc, flash::utils::endian::create_class(mc), domain, script)?;
+
+    lazy_class(
+        mc,
+        flash::utils::compression_algorithm::create_class(mc),
+        domain,
+        script,
+    )?;
+
+    avm2_system_class!(
+        iexternalizable,
+        activation,
+        flash::utils::iexternalizable::create_interface(mc),
+        domain,
+        script
+    );
+
+    function(
+        mc,
+        "flash.utils",
+        "getTimer",
+        flash::utils::get_timer,
+        fn_proto,
+        domain,
+        script,
+    )?;
+
+    function(
+        mc,
+        "flash.utils",
+        "setInterval",
+        flash::utils::set_interval,
+        fn_proto,
+        domain,
+        script,
+    )?;
+
+    function(
+        mc,
+        "flash.utils",
+        "setTimeout",
+        flash::utils::set_timeout,
+        fn_proto,
+        domain,
+        script,
+    )?;
+
+    function(
+        mc,
+        "flash.utils",
+        "clearInterval",
+        flash::utils::clear_timer,
+        fn_proto,
+        domain,
+        script,
+    )?;
+
+    function(
+        mc,
+        "flash.utils",
+        "clearTimeout",
+        flash::utils::clear_timer,
+        fn_proto,
+        domain,
+        script,
+    )?;
+
+    function(
+        mc,
+        "flash.utils",
+        "getQualifiedClassName",
+        flash::utils::get_qualified_class_name,
+        fn_proto,
+        domain,
+        script,
+    )?;
+
+    function(
+        mc,
+        "flash.utils",
+        "getQualifiedSuperclassName",
+        flash::utils::get_qualified_super_class_name,
+        fn_proto,
+        domain,
+        script,
+    )?;
+
+    function(
+        mc,
+        "flash.utils",
+        "getDefinitionByName",
+        flash::utils::get_definition_by_name,
+        fn_proto,
+        domain,
+        script,
+    )?;
+
+    // package `flash.net`
+    function(
+        mc,
+        "flash.net",
+        "registerClassAlias",
+        flash::net::register_class_alias,
+        fn_proto,
+        domain,
+        script,
+    )?;
+
+    function(
+        mc,
+        "flash.net",
+        "getClassByAlias",
+        flash::net::get_class_by_alias,
+        fn_proto,
+        domain,
+        script,
+    )?;
+
+    function(
+        mc,
+        "flash.net",
+        "navigateToURL",
+        flash::net::navigate_to_url,
+        fn_proto,
+        domain,
+        script,
+    )?;
+
+    function(
+        mc,
+        "flash.net",
+        "sendToURL",
+        flash::net::send_to_url,
+        fn_proto,
+        domain,
+        script,
+    )?;
+
+    lazy_class(
+        mc,
+        flash::net::localconnection::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::net::netconnection::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::net::objectencoding::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(mc, flash::net::responder::create_class(mc), domain, script)?;
+    avm2_system_class!(
+        sharedobject,
+        activation,
+        flash::net::sharedobject::create_class(mc),
+        domain,
+        script
+    );
+    lazy_class(
+        mc,
+        flash::net::sharedobjectflushstatus::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(mc, flash::net::socket::create_class(mc), domain, script)?;
+    lazy_class(mc, flash::net::urlloader::create_class(mc), domain, script)?;
+    lazy_class(
+        mc,
+        flash::net::urlloaderdataformat::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(mc, flash::net::urlrequest::create_class(mc), domain, script)?;
+    lazy_class(
+        mc,
+        flash::net::urlrequestheader::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::net::urlrequestmethod::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(mc, flash::net::urlstream::create_class(mc), domain, script)?;
+    avm2_system_class!(
+        urlvariables,
+        activation,
+        flash::net::urlvariables::create_class(mc),
+        domain,
+        script
+    );
+    lazy_class(mc, flash::net::xmlsocket::create_class(mc), domain, script)?;
+
+    // package `flash.desktop`
+    lazy_class(
+        mc,
+        flash::desktop::clipboard::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::desktop::clipboardformats::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::desktop::clipboardtransfermode::create_class(mc),
+        domain,
+        script,
+    )?;
+
+    // package `flash.ui`
+    lazy_class(mc, flash::ui::contextmenu::create_class(mc), domain, script)?;
+    lazy_class(
+        mc,
+        flash::ui::contextmenubuiltinitems::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::ui::contextmenuitem::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(mc, flash::ui::gameinput::create_class(mc), domain, script)?;
+    lazy_class(
+        mc,
+        flash::ui::gameinputcontrol::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::ui::gameinputdevice::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(mc, flash::ui::keyboard::create_class(mc), domain, script)?;
+    lazy_class(mc, flash::ui::keylocation::create_class(mc), domain, script)?;
+    lazy_class(mc, flash::ui::mouse::create_class(mc), domain, script)?;
+    lazy_class(mc, flash::ui::mousecursor::create_class(mc), domain, script)?;
+    lazy_class(
+        mc,
+        flash::ui::mousecursordata::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(mc, flash::ui::multitouch::create_class(mc), domain, script)?;
+    lazy_class(
+        mc,
+        flash::ui::multitouchinputmode::create_class(mc),
+        domain,
+        script,
+    )?;
+
+    // package `flash.external`
+    lazy_class(
+        mc,
+        flash::external::externalinterface::create_class(mc),
+        domain,
+        script,
+    )?;
+
+    // package `flash.display`
+    lazy_class(
+        mc,
+        flash::display::ibitmapdrawable::create_interface(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::igraphicsdata::create_interface(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::igraphicsfill::create_interface(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::igraphicspath::create_interface(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::igraphicsstroke::create_interface(mc),
+        domain,
+        script,
+    )?;
+    avm2_system_class!(
+        display_object,
+        activation,
+        flash::display::displayobject::create_class(mc),
+        domain,
+        script
+    );
+    avm2_system_class!(
+        shape,
+        activation,
+        flash::display::shape::create_class(mc),
+        domain,
+        script
+    );
+    avm2_system_class!(
+        morphshape,
+        activation,
+        flash::display::morphshape::create_class(mc),
+        domain,
+        script
+    );
+    lazy_class(
+        mc,
+        flash::display::interactiveobject::create_class(mc),
+        domain,
+        script,
+    )?;
+    avm2_system_class!(
+        simplebutton,
+        activation,
+        flash::display::simplebutton::create_class(mc),
+        domain,
+        script
+    );
+    lazy_class(
+        mc,
+        flash::display::displayobjectcontainer::create_class(mc),
+        domain,
+        script,
+    )?;
+    avm2_system_class!(
+        sprite,
+        activation,
+        flash::display::sprite::create_class(mc),
+        domain,
+        script
+    );
+    avm2_system_class!(
+        movieclip,
+        activation,
+        flash::display::movieclip::create_class(mc),
+        domain,
+        script
+    );
+    avm2_system_class!(
+        framelabel,
+        activation,
+        flash::display::framelabel::create_class(mc),
+        domain,
+        script
+    );
+    avm2_system_class!(
+        scene,
+        activation,
+        flash::display::scene::create_class(mc),
+        domain,
+        script
+    );
+    avm2_system_class!(
+        graphics,
+        activation,
+        flash::display::graphics::create_class(mc),
+        domain,
+        script
+    );
+    lazy_class(
+        mc,
+        flash::display::jointstyle::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::linescalemode::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::capsstyle::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::graphicsendfill::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::graphicspath::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::graphicspathcommand::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::graphicspathwinding::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::graphicssolidfill::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::graphicsstroke::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::triangleculling::create_class(mc),
+        domain,
+        script,
+    )?;
+    avm2_system_class!(
+        loaderinfo,
+        activation,
+        flash::display::loaderinfo::create_class(mc),
+        domain,
+        script
+    );
+    lazy_class(mc, flash::display::loader::create_class(mc), domain, script)?;
+    avm2_system_class!(
+        avm1movie,
+        activation,
+        flash::display::avm1movie::create_class(mc),
+        domain,
+        script
+    );
+    lazy_class(
+        mc,
+        flash::display::actionscriptversion::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::swfversion::create_class(mc),
+        domain,
+        script,
+    )?;
+    avm2_system_class!(
+        stage,
+        activation,
+        flash::display::stage::create_class(mc),
+        domain,
+        script
+    );
+    lazy_class(
+        mc,
+        flash::display::stagescalemode::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::stagealign::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::stagedisplaystate::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::stagequality::create_class(mc),
+        domain,
         script,
     )?;
-    class(
+    avm2_system_class!(
+        stage3d,
         activation,
-        flash::text::textformatalign::create_class(mc),
+        flash::display::stage3d::create_class(mc),
+        domain,
+        script
+    );
+    avm2_system_class!(
+        bitmap,
+        activation,
+        flash::display::bitmap::create_class(mc),
+        domain,
+        script
+    );
+    avm2_system_class!(
+        bitmapdata,
+        activation,
+        flash::display::bitmapdata::create_class(mc),
+        domain,
+        script
+    );
+    lazy_class(mc, flash::display::shader::create_class(mc), domain, script)?;
+    lazy_class(
+        mc,
+        flash::display::shaderdata::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::shaderinput::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::shaderjob::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::shaderparameter::create_class(mc),
         domain,
         script,
     )?;
-    class(
+    lazy_class(
+        mc,
+        flash::display::shaderparametertype::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display::shaderprecision::create_class(mc),
+        domain,
+        script,
+    )?;
+
+    // package `flash.display3D`
+    avm2_system_class!(
+        context3d,
         activation,
-        flash::text::textfieldtype::create_class(mc),
+        flash::display3d::context3d::create_class(mc),
+        domain,
+        script
+    );
+    lazy_class(
+        mc,
+        flash::display3d::context3dblendfactor::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display3d::context3dclearmask::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display3d::context3dcomparemode::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display3d::context3dprofile::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display3d::context3dprogramtype::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display3d::context3drendermode::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display3d::context3dstencilaction::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display3d::context3dtextureformat::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display3d::context3dtriangleface::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::display3d::context3dvertexbufferformat::create_class(mc),
+        domain,
+        script,
+    )?;
+    avm2_system_class!(
+        vertexbuffer3d,
+        activation,
+        flash::display3d::vertexbuffer3d::create_class(mc),
+        domain,
+        script
+    );
+    avm2_system_class!(
+        indexbuffer3d,
+        activation,
+        flash::display3d::indexbuffer3d::create_class(mc),
+        domain,
+        script
+    );
+    avm2_system_class!(
+        program3d,
+        activation,
+        flash::display3d::program3d::create_class(mc),
+        domain,
+        script
+    );
+    lazy_class(
+        mc,
+        flash::display3d::textures::texturebase::create_class(mc),
+        domain,
+        script,
+    )?;
+    avm2_system_class!(
+        texture,
+        activation,
+        flash::display3d::textures::texture::create_class(mc),
+        domain,
+        script
+    );
+
+    // package `flash.geom`
+    avm2_system_class!(
+        point,
+        activation,
+        flash::geom::point::create_class(mc),
+        domain,
+        script
+    );
+    avm2_system_class!(
+        rectangle,
+        activation,
+        flash::geom::rectangle::create_class(mc),
+        domain,
+        script
+    );
+    avm2_system_class!(
+        vector3d,
+        activation,
+        flash::geom::vector3d::create_class(mc),
+        domain,
+        script
+    );
+    avm2_system_class!(
+        matrix3d,
+        activation,
+        flash::geom::matrix3d::create_class(mc),
+        domain,
+        script
+    );
+    avm2_system_class!(
+        perspectiveprojection,
+        activation,
+        flash::geom::perspectiveprojection::create_class(mc),
+        domain,
+        script
+    );
+    avm2_system_class!(
+        transform,
+        activation,
+        flash::geom::transform::create_class(mc),
+        domain,
+        script
+    );
+
+    // package `flash.filters`
+    lazy_class(
+        mc,
+        flash::filters::bitmapfilter::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::filters::bevelfilter::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::filters::bitmapfiltertype::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::filters::blurfilter::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::filters::colormatrixfilter::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::filters::convolutionfilter::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::filters::displacementmapfilter::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::filters::displacementmapfiltermode::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::filters::dropshadowfilter::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::filters::glowfilter::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::filters::gradientglowfilter::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::filters::gradientbevelfilter::create_class(mc),
         domain,
         script,
     )?;
-    class(
+    lazy_class(
+        mc,
+        flash::filters::shaderfilter::create_class(mc),
+        domain,
+        script,
+    )?;
+
+    // package `flash.media`
+    avm2_system_class!(
+        video,
+        activation,
+        flash::media::video::create_class(mc),
+        domain,
+        script
+    );
+    lazy_class(mc, flash::media::sound::create_class(mc), domain, script)?;
+    avm2_system_class!(
+        soundtransform,
+        activation,
+        flash::media::soundtransform::create_class(mc),
+        domain,
+        script
+    );
+    lazy_class(
+        mc,
+        flash::media::soundmixer::create_class(mc),
+        domain,
+        script,
+    )?;
+    avm2_system_class!(
+        soundchannel,
+        activation,
+        flash::media::soundchannel::create_class(mc),
+        domain,
+        script
+    );
+
+    // package `flash.text`
+    avm2_system_class!(
+        textfield,
+        activation,
+        flash::text::textfield::create_class(mc),
+        domain,
+        script
+    );
+    avm2_system_class!(
+        textformat,
+        activation,
+        flash::text::textformat::create_class(mc),
+        domain,
+        script
+    );
+    avm2_system_class!(
+        textsnapshot,
         activation,
-        flash::text::font::create_class(mc),
+        flash::text::textsnapshot::create_class(mc),
+        domain,
+        script
+    );
+    lazy_class(
+        mc,
+        flash::text::textfieldautosize::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::text::textformatalign::create_class(mc),
+        domain,
+        script,
+    )?;
+    lazy_class(
+        mc,
+        flash::text::textfieldtype::create_class(mc),
         domain,
         script,
     )?;
+    lazy_class(mc, flash::text::font::create_class(mc), domain, script)?;
 
     // package `flash.crypto`
     function(
@@ -135,7 +135,11 @@ pub fn resolve_array_hole<'gc>(
                     p,
                     &QName::new(
                         Namespace::public(),
-                        AvmString::new(activation.context.gc_context, i.to_string()),
+                        activation
+                            .context
+                            .avm2
+                            .small_strings()
+                            .integer(activation.context.gc_context, i),
                     ),
                     activation,
                 )
@@ -161,27 +165,21 @@ where
     if let Some(this) = this {
         if let Some(array) = this.as_array_storage() {
             let string_separator = separator.coerce_to_string(activation)?;
-            let mut accum = Vec::with_capacity(array.length());
+            let mut accum = String::new();
 
             for (i, item) in array.iter().enumerate() {
+                if i > 0 {
+                    accum.push_str(&string_separator);
+                }
+
                 let item = resolve_array_hole(activation, this, i, item)?;
 
-                if matches!(item, Value::Undefined) || matches!(item, Value::Null) {
-                    accum.push("".into());
-                } else {
-                    accum.push(
-                        conv(item, activation)?
-                            .coerce_to_string(activation)?
-                            .to_string(),
-                    );
+                if !matches!(item, Value::Undefined) && !matches!(item, Value::Null) {
+                    accum.push_str(&conv(item, activation)?.coerce_to_string(activation)?);
                 }
             }
 
-            return Ok(AvmString::new(
-                activation.context.gc_context,
-                accum.join(&string_separator),
-            )
-            .into());
+            return Ok(AvmString::new(activation.context.gc_context, accum).into());
         }
     }
 
@@ -309,7 +307,11 @@ impl<'gc> ArrayIter<'gc> {
                         self.array_object,
                         &QName::new(
                             Namespace::public(),
-                            AvmString::new(activation.context.gc_context, i.to_string()),
+                            activation
+                                .context
+                                .avm2
+                                .small_strings()
+                                .integer(activation.context.gc_context, i as usize),
                         ),
                         activation,
                     )
@@ -339,7 +341,11 @@ impl<'gc> ArrayIter<'gc> {
                         self.array_object,
                         &QName::new(
                             Namespace::public(),
-                            AvmString::new(activation.context.gc_context, i.to_string()),
+                            activation
+                                .context
+                                .avm2
+                                .small_strings()
+                                .integer(activation.context.gc_context, i as usize),
                         ),
                         activation,
                     )
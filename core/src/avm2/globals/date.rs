@@ -0,0 +1,432 @@
+//! `Date` impl
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{date_allocator, Object, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::string::AvmString;
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, TimeZone, Timelike, Utc};
+use gc_arena::{GcCell, MutationContext};
+
+/// The format of `Date.toString`, which `Date` can also parse.
+const DATE_FORMAT: &str = "%a %b %-d %T GMT%z %-Y";
+
+/// The local timezone, as reported by the locale backend.
+fn local(activation: &mut Activation<'_, '_, '_>) -> FixedOffset {
+    activation.context.locale.get_timezone()
+}
+
+fn utc(_activation: &mut Activation<'_, '_, '_>) -> FixedOffset {
+    FixedOffset::east(0)
+}
+
+/// Split a date into the fields that `Date`'s constructor takes: the full
+/// year, the month from zero, the day of the month, hours, minutes, seconds
+/// and milliseconds.
+fn fields(date_time: &DateTime<FixedOffset>) -> [f64; 7] {
+    [
+        date_time.year() as f64,
+        date_time.month0() as f64,
+        date_time.day() as f64,
+        date_time.hour() as f64,
+        date_time.minute() as f64,
+        date_time.second() as f64,
+        date_time.timestamp_subsec_millis() as f64,
+    ]
+}
+
+/// The day of the week of a date, counting from Sunday.
+fn day_of_week(date_time: &DateTime<FixedOffset>) -> f64 {
+    date_time.weekday().num_days_from_sunday() as f64
+}
+
+/// Build a date from its fields in a timezone.
+///
+/// Fields out of their usual range carry over into the next larger field,
+/// so that, for example, month 12 is January of the following year. Yields
+/// `None` if any field is not finite, or the date is out of range.
+fn make_date(timezone: &FixedOffset, fields: [f64; 7]) -> Option<DateTime<Utc>> {
+    if fields.iter().any(|field| !field.is_finite()) {
+        return None;
+    }
+
+    let [year, month, day, hours, minutes, seconds, millis] = fields;
+    let year = year.trunc() + (month.trunc() / 12.0).floor();
+    let month = month.trunc().rem_euclid(12.0);
+    let first_of_month =
+        NaiveDate::from_ymd_opt(year as i32, month as u32 + 1, 1)?.and_hms(0, 0, 0);
+
+    let offset = (day.trunc() - 1.0) * 86_400_000.0
+        + hours.trunc() * 3_600_000.0
+        + minutes.trunc() * 60_000.0
+        + seconds.trunc() * 1000.0
+        + millis.trunc();
+    let local = first_of_month.checked_add_signed(Duration::milliseconds(offset as i64))?;
+
+    timezone
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|date_time| date_time.with_timezone(&Utc))
+}
+
+/// The timestamp of a date, in milliseconds, or `NaN` for an invalid date.
+fn timestamp(date_time: Option<DateTime<Utc>>) -> f64 {
+    date_time
+        .map(|date_time| date_time.timestamp_millis() as f64)
+        .unwrap_or(f64::NAN)
+}
+
+/// Convert a timestamp in milliseconds to a date.
+fn from_timestamp(timestamp: f64) -> Option<DateTime<Utc>> {
+    if timestamp.is_finite() {
+        Utc.timestamp_millis_opt(timestamp as i64).single()
+    } else {
+        None
+    }
+}
+
+/// Implements `Date`'s instance initializer.
+///
+/// With no arguments, the date is the current time. A single argument is a
+/// timestamp, or a string in the format that `toString` returns. Otherwise,
+/// the arguments are the date's fields in local time, with years below 100
+/// counting from 1900.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+
+        if let Some(date) = this.as_date_object() {
+            let date_time = match args {
+                [] => Some(activation.context.locale.get_current_date_time()),
+                [Value::String(string)] => {
+                    DateTime::parse_from_str(&string.to_string(), DATE_FORMAT)
+                        .ok()
+                        .map(|date_time| date_time.with_timezone(&Utc))
+                }
+                [value] => from_timestamp(value.coerce_to_number(activation)?),
+                _ => {
+                    let mut date_fields = [0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0];
+                    for (field, arg) in date_fields.iter_mut().zip(args) {
+                        *field = arg.coerce_to_number(activation)?;
+                    }
+                    if (0.0..100.0).contains(&date_fields[0].trunc()) {
+                        date_fields[0] += 1900.0;
+                    }
+
+                    make_date(&local(activation), date_fields)
+                }
+            };
+
+            date.set_date_time(activation.context.gc_context, date_time);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Date`'s class initializer.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `Date.UTC`.
+pub fn utc_date<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let mut date_fields = [f64::NAN, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0];
+    for (field, arg) in date_fields.iter_mut().zip(args) {
+        *field = arg.coerce_to_number(activation)?;
+    }
+    if (0.0..100.0).contains(&date_fields[0].trunc()) {
+        date_fields[0] += 1900.0;
+    }
+
+    Ok(timestamp(make_date(&utc(activation), date_fields)).into())
+}
+
+/// Implements `Date.getTime`, `Date.valueOf` and the `time` getter.
+pub fn get_time<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(date) = this.and_then(|this| this.as_date_object()) {
+        return Ok(timestamp(date.date_time()).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Date.setTime` and the `time` setter.
+pub fn set_time<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(date) = this.and_then(|this| this.as_date_object()) {
+        let time = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_number(activation)?;
+        let date_time = from_timestamp(time);
+        date.set_date_time(activation.context.gc_context, date_time);
+
+        return Ok(timestamp(date_time).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Date.toString`.
+pub fn to_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(date) = this.and_then(|this| this.as_date_object()) {
+        return Ok(match date.date_time() {
+            Some(date_time) => {
+                let local_time = date_time.with_timezone(&local(activation));
+                AvmString::new(
+                    activation.context.gc_context,
+                    local_time.format(DATE_FORMAT).to_string(),
+                )
+                .into()
+            }
+            None => "Invalid Date".into(),
+        });
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Date.getTimezoneOffset` and the `timezoneOffset` getter.
+pub fn get_timezone_offset<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(date) = this.and_then(|this| this.as_date_object()) {
+        if date.date_time().is_none() {
+            return Ok(f64::NAN.into());
+        }
+
+        let minutes = local(activation).utc_minus_local() as f64 / 60.0;
+        return Ok(minutes.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Set some of a date's fields, starting with the field at `first`, from
+/// the arguments of a setter.
+fn set_fields<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+    timezone: FixedOffset,
+    first: usize,
+    count: usize,
+) -> Result<Value<'gc>, Error> {
+    if let Some(date) = this.and_then(|this| this.as_date_object()) {
+        let mut date_fields = match date.date_time() {
+            Some(date_time) => fields(&date_time.with_timezone(&timezone)),
+            // Setting the year of an invalid date starts from the epoch.
+            None if first == 0 => fields(&timezone.timestamp(0, 0)),
+            None => return Ok(f64::NAN.into()),
+        };
+
+        let setters = date_fields[first..].iter_mut().take(count);
+        for (field, arg) in setters.zip(args) {
+            *field = arg.coerce_to_number(activation)?;
+        }
+
+        let date_time = make_date(&timezone, date_fields);
+        date.set_date_time(activation.context.gc_context, date_time);
+
+        return Ok(timestamp(date_time).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements a getter for one of a date's fields.
+macro_rules! field_getter {
+    ($name:ident, $timezone:ident, $get:expr) => {
+        pub fn $name<'gc>(
+            activation: &mut Activation<'_, 'gc, '_>,
+            this: Option<Object<'gc>>,
+            _args: &[Value<'gc>],
+        ) -> Result<Value<'gc>, Error> {
+            if let Some(date) = this.and_then(|this| this.as_date_object()) {
+                return Ok(match date.date_time() {
+                    Some(date_time) => {
+                        let date_time = date_time.with_timezone(&$timezone(activation));
+                        let get: fn(&DateTime<FixedOffset>) -> f64 = $get;
+                        get(&date_time)
+                    }
+                    None => f64::NAN,
+                }
+                .into());
+            }
+
+            Ok(Value::Undefined)
+        }
+    };
+}
+
+/// Implements a setter for some of a date's fields.
+macro_rules! field_setter {
+    ($name:ident, $timezone:ident, $first:expr, $count:expr) => {
+        pub fn $name<'gc>(
+            activation: &mut Activation<'_, 'gc, '_>,
+            this: Option<Object<'gc>>,
+            args: &[Value<'gc>],
+        ) -> Result<Value<'gc>, Error> {
+            let timezone = $timezone(activation);
+            set_fields(activation, this, args, timezone, $first, $count)
+        }
+    };
+}
+
+field_getter!(get_full_year, local, |d| fields(d)[0]);
+field_getter!(get_month, local, |d| fields(d)[1]);
+field_getter!(get_date, local, |d| fields(d)[2]);
+field_getter!(get_day, local, day_of_week);
+field_getter!(get_hours, local, |d| fields(d)[3]);
+field_getter!(get_minutes, local, |d| fields(d)[4]);
+field_getter!(get_seconds, local, |d| fields(d)[5]);
+field_getter!(get_milliseconds, local, |d| fields(d)[6]);
+field_getter!(get_utc_full_year, utc, |d| fields(d)[0]);
+field_getter!(get_utc_month, utc, |d| fields(d)[1]);
+field_getter!(get_utc_date, utc, |d| fields(d)[2]);
+field_getter!(get_utc_day, utc, day_of_week);
+field_getter!(get_utc_hours, utc, |d| fields(d)[3]);
+field_getter!(get_utc_minutes, utc, |d| fields(d)[4]);
+field_getter!(get_utc_seconds, utc, |d| fields(d)[5]);
+field_getter!(get_utc_milliseconds, utc, |d| fields(d)[6]);
+
+field_setter!(set_full_year, local, 0, 3);
+field_setter!(set_month, local, 1, 2);
+field_setter!(set_date, local, 2, 1);
+field_setter!(set_hours, local, 3, 4);
+field_setter!(set_minutes, local, 4, 3);
+field_setter!(set_seconds, local, 5, 2);
+field_setter!(set_milliseconds, local, 6, 1);
+field_setter!(set_utc_full_year, utc, 0, 3);
+field_setter!(set_utc_month, utc, 1, 2);
+field_setter!(set_utc_date, utc, 2, 1);
+field_setter!(set_utc_hours, utc, 3, 4);
+field_setter!(set_utc_minutes, utc, 4, 3);
+field_setter!(set_utc_seconds, utc, 5, 2);
+field_setter!(set_utc_milliseconds, utc, 6, 1);
+
+/// Construct `Date`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::public(), "Date"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<Date instance initializer>", mc),
+        Method::from_builtin(class_init, "<Date class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+    write.set_instance_allocator(date_allocator);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("time", Some(get_time), Some(set_time)),
+        ("timezoneOffset", Some(get_timezone_offset), None),
+        ("fullYear", Some(get_full_year), Some(set_full_year)),
+        ("month", Some(get_month), Some(set_month)),
+        ("date", Some(get_date), Some(set_date)),
+        ("day", Some(get_day), None),
+        ("hours", Some(get_hours), Some(set_hours)),
+        ("minutes", Some(get_minutes), Some(set_minutes)),
+        ("seconds", Some(get_seconds), Some(set_seconds)),
+        (
+            "milliseconds",
+            Some(get_milliseconds),
+            Some(set_milliseconds),
+        ),
+        (
+            "fullYearUTC",
+            Some(get_utc_full_year),
+            Some(set_utc_full_year),
+        ),
+        ("monthUTC", Some(get_utc_month), Some(set_utc_month)),
+        ("dateUTC", Some(get_utc_date), Some(set_utc_date)),
+        ("dayUTC", Some(get_utc_day), None),
+        ("hoursUTC", Some(get_utc_hours), Some(set_utc_hours)),
+        ("minutesUTC", Some(get_utc_minutes), Some(set_utc_minutes)),
+        ("secondsUTC", Some(get_utc_seconds), Some(set_utc_seconds)),
+        (
+            "millisecondsUTC",
+            Some(get_utc_milliseconds),
+            Some(set_utc_milliseconds),
+        ),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("getTime", get_time),
+        ("setTime", set_time),
+        ("valueOf", get_time),
+        ("toString", to_string),
+        ("getTimezoneOffset", get_timezone_offset),
+        ("getFullYear", get_full_year),
+        ("getMonth", get_month),
+        ("getDate", get_date),
+        ("getDay", get_day),
+        ("getHours", get_hours),
+        ("getMinutes", get_minutes),
+        ("getSeconds", get_seconds),
+        ("getMilliseconds", get_milliseconds),
+        ("getUTCFullYear", get_utc_full_year),
+        ("getUTCMonth", get_utc_month),
+        ("getUTCDate", get_utc_date),
+        ("getUTCDay", get_utc_day),
+        ("getUTCHours", get_utc_hours),
+        ("getUTCMinutes", get_utc_minutes),
+        ("getUTCSeconds", get_utc_seconds),
+        ("getUTCMilliseconds", get_utc_milliseconds),
+        ("setFullYear", set_full_year),
+        ("setMonth", set_month),
+        ("setDate", set_date),
+        ("setHours", set_hours),
+        ("setMinutes", set_minutes),
+        ("setSeconds", set_seconds),
+        ("setMilliseconds", set_milliseconds),
+        ("setUTCFullYear", set_utc_full_year),
+        ("setUTCMonth", set_utc_month),
+        ("setUTCDate", set_utc_date),
+        ("setUTCHours", set_utc_hours),
+        ("setUTCMinutes", set_utc_minutes),
+        ("setUTCSeconds", set_utc_seconds),
+        ("setUTCMilliseconds", set_utc_milliseconds),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    const PUBLIC_CLASS_METHODS: &[(&str, NativeMethodImpl)] = &[("UTC", utc_date)];
+    write.define_public_builtin_class_methods(mc, PUBLIC_CLASS_METHODS);
+
+    class
+}
@@ -1,10 +1,16 @@
 //! `flash` namespace
 
 pub mod crypto;
+pub mod desktop;
 pub mod display;
+pub mod display3d;
 pub mod events;
+pub mod external;
+pub mod filters;
 pub mod geom;
 pub mod media;
+pub mod net;
 pub mod system;
 pub mod text;
+pub mod ui;
 pub mod utils;
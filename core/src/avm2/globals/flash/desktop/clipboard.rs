@@ -0,0 +1,228 @@
+//! `flash.desktop.Clipboard` builtin/prototype
+//!
+//! Only the general clipboard exists outside of AIR, and it can only hold
+//! text. Movies may write to it at any time, but may only read from it while
+//! a `paste` event is being dispatched.
+
+use crate::avm2::activation::Activation;
+use crate::avm2::array::ArrayStorage;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{ArrayObject, Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::string::AvmString;
+use gc_arena::{GcCell, MutationContext};
+
+/// Namespace for the internal state of the `Clipboard` class.
+const NS_CLIPBOARD: &str = "https://ruffle.rs/AS3/impl/Clipboard/";
+
+/// The format that text is held in on the clipboard.
+const TEXT_FORMAT: &str = "air:text";
+
+/// Implements `flash.desktop.Clipboard`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.desktop.Clipboard`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `Clipboard.generalClipboard`.
+///
+/// The general clipboard is created the first time it is requested.
+pub fn general_clipboard<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let name = QName::new(Namespace::private(NS_CLIPBOARD), "generalClipboard");
+        if let Value::Object(clipboard) = this.get_property(this, &name, activation)? {
+            return Ok(clipboard.into());
+        }
+
+        let clipboard = this.construct(activation, &[])?;
+        this.set_property(this, &name, clipboard.into(), activation)?;
+
+        return Ok(clipboard.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Clipboard.formats`.
+///
+/// The formats on the clipboard are as private as its contents, so this is
+/// empty unless the user is pasting.
+pub fn formats<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if activation.avm2().pasted_text().is_some() {
+        let storage = ArrayStorage::from_args(&[TEXT_FORMAT.into()]);
+        return Ok(ArrayObject::from_storage(activation, storage)?.into());
+    }
+
+    Ok(ArrayObject::empty(activation)?.into())
+}
+
+/// Implements `Clipboard.hasFormat`.
+pub fn has_format<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let format = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+
+    Ok((format == TEXT_FORMAT && activation.avm2().pasted_text().is_some()).into())
+}
+
+/// Implements `Clipboard.getData`.
+pub fn get_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let text = match activation.avm2().pasted_text() {
+        Some(text) => text.to_string(),
+        None => return Err("SecurityError: Error #2179: The Clipboard.generalClipboard object may only be read while processing a flash.events.Event.PASTE event.".into()),
+    };
+
+    let format = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    if format != TEXT_FORMAT {
+        return Ok(Value::Null);
+    }
+
+    Ok(AvmString::new(activation.context.gc_context, text).into())
+}
+
+/// Implements `Clipboard.setData`.
+pub fn set_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let format = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    if format != TEXT_FORMAT {
+        log::warn!("Clipboard.setData: format {} is not supported", format);
+        return Ok(false.into());
+    }
+
+    let data = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    activation
+        .context
+        .ui
+        .set_clipboard_content(data.to_string());
+
+    Ok(true.into())
+}
+
+/// Implements `Clipboard.setDataHandler`.
+///
+/// The data is requested from the handler straight away, rather than when
+/// it is pasted.
+pub fn set_data_handler<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let format = args.get(0).cloned().unwrap_or(Value::Undefined);
+    let handler = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?;
+    let data = handler.call(None, &[], activation, None)?;
+
+    set_data(activation, this, &[format, data])
+}
+
+/// Implements `Clipboard.clear` and `Clipboard.clearData`.
+pub fn clear<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    activation.context.ui.set_clipboard_content(String::new());
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `Clipboard`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.desktop"), "Clipboard"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<Clipboard instance initializer>", mc),
+        Method::from_builtin(class_init, "<Clipboard class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_CLASS_PROPERTIES: &[(&str, Option<NativeMethodImpl>, Option<NativeMethodImpl>)] =
+        &[("generalClipboard", Some(general_clipboard), None)];
+    write.define_public_builtin_class_properties(mc, PUBLIC_CLASS_PROPERTIES);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[("formats", Some(formats), None)];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("hasFormat", has_format),
+        ("getData", get_data),
+        ("setData", set_data),
+        ("setDataHandler", set_data_handler),
+        ("clear", clear),
+        ("clearData", clear),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    write.define_class_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_CLIPBOARD), "generalClipboard"),
+        QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "BareObject").into(),
+        None,
+    ));
+
+    class
+}
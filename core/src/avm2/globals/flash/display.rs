@@ -1,6 +1,7 @@
 //! `flash.display` namespace
 
 pub mod actionscriptversion;
+pub mod avm1movie;
 pub mod bitmap;
 pub mod bitmapdata;
 pub mod capsstyle;
@@ -8,19 +9,40 @@ pub mod displayobject;
 pub mod displayobjectcontainer;
 pub mod framelabel;
 pub mod graphics;
+pub mod graphicsendfill;
+pub mod graphicspath;
+pub mod graphicspathcommand;
+pub mod graphicspathwinding;
+pub mod graphicssolidfill;
+pub mod graphicsstroke;
 pub mod ibitmapdrawable;
+pub mod igraphicsdata;
+pub mod igraphicsfill;
+pub mod igraphicspath;
+pub mod igraphicsstroke;
 pub mod interactiveobject;
 pub mod jointstyle;
 pub mod linescalemode;
+pub mod loader;
 pub mod loaderinfo;
+pub mod morphshape;
 pub mod movieclip;
 pub mod scene;
+pub mod shader;
+pub mod shaderdata;
+pub mod shaderinput;
+pub mod shaderjob;
+pub mod shaderparameter;
+pub mod shaderparametertype;
+pub mod shaderprecision;
 pub mod shape;
 pub mod simplebutton;
 pub mod sprite;
 pub mod stage;
+pub mod stage3d;
 pub mod stagealign;
 pub mod stagedisplaystate;
 pub mod stagequality;
 pub mod stagescalemode;
 pub mod swfversion;
+pub mod triangleculling;
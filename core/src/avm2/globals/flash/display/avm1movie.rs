@@ -0,0 +1,71 @@
+//! `flash.display.AVM1Movie` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::display_object::MovieClip;
+use crate::tag_utils::{SwfMovie, SwfSlice};
+use gc_arena::{GcCell, MutationContext};
+use std::sync::Arc;
+
+/// Implements `flash.display.AVM1Movie`'s instance constructor.
+///
+/// The display object of an `AVM1Movie` is an empty clip that holds the root
+/// of the AVM1 movie as its only child, keeping the AVM1 movie's own object
+/// out of reach of AVM2 code.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+
+        if this.as_display_object().is_none() {
+            let class_object = this
+                .as_class_object()
+                .ok_or("Attempted to construct non-instance AVM1Movie")?;
+            let movie = Arc::new(SwfMovie::empty(activation.context.swf.version()));
+            let new_do = MovieClip::new_with_avm2(
+                SwfSlice::empty(movie),
+                this,
+                class_object,
+                activation.context.gc_context,
+            );
+
+            this.init_display_object(activation.context.gc_context, new_do.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display.AVM1Movie`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `AVM1Movie`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.display"), "AVM1Movie"),
+        Some(QName::new(Namespace::package("flash.display"), "DisplayObject").into()),
+        Method::from_builtin(instance_init, "<AVM1Movie instance initializer>", mc),
+        Method::from_builtin(class_init, "<AVM1Movie class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    class
+}
@@ -2,15 +2,23 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::filters::bitmapfilter;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::names::{Namespace, QName};
-use crate::avm2::object::{bitmapdata_allocator, Object, TObject};
+use crate::avm2::object::{bitmapdata_allocator, Object, TObject, VectorObject};
 use crate::avm2::value::Value;
+use crate::avm2::vector::VectorStorage;
 use crate::avm2::Error;
-use crate::bitmap::bitmap_data::BitmapData;
+use crate::bitmap::bitmap_data::{self, BitmapData, BitmapDrawable, Color, ThresholdOperation};
+use crate::bitmap::color_transform_params::ColorTransformParams;
 use crate::bitmap::is_size_valid;
 use crate::character::Character;
+use crate::color_transform::ColorTransform;
+use crate::display_object::TDisplayObject;
+use crate::matrix::Matrix;
+use crate::transform::Transform;
 use gc_arena::{GcCell, MutationContext};
+use swf::{BlendMode, Fixed8, Twips};
 
 /// Implements `flash.display.BitmapData`'s instance constructor.
 pub fn instance_init<'gc>(
@@ -183,6 +191,935 @@ pub fn get_pixel<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Get a public property of an object as a number.
+fn number_property<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+    name: &'static str,
+) -> Result<f64, Error> {
+    object
+        .get_property(object, &QName::new(Namespace::public(), name), activation)?
+        .coerce_to_number(activation)
+}
+
+/// Get the `x`, `y`, `width` and `height` of a `Rectangle`.
+fn rectangle_bounds<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    rectangle: Object<'gc>,
+) -> Result<(i32, i32, i32, i32), Error> {
+    Ok((
+        number_property(activation, rectangle, "x")? as i32,
+        number_property(activation, rectangle, "y")? as i32,
+        number_property(activation, rectangle, "width")? as i32,
+        number_property(activation, rectangle, "height")? as i32,
+    ))
+}
+
+/// Implements `BitmapData.draw`.
+pub fn draw<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+        let source = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let source = if let Some(source_bitmap) = source.as_bitmap_data() {
+            BitmapDrawable::BitmapData(source_bitmap)
+        } else if let Some(source_object) = source.as_display_object() {
+            BitmapDrawable::DisplayObject(source_object)
+        } else {
+            return Err("TypeError: Error #1034: Type Coercion failed: cannot convert to flash.display.IBitmapDrawable.".into());
+        };
+
+        let mut transform = Transform::default();
+        if let Some(Value::Object(matrix)) = args.get(1) {
+            transform.matrix = Matrix {
+                a: number_property(activation, *matrix, "a")? as f32,
+                b: number_property(activation, *matrix, "b")? as f32,
+                c: number_property(activation, *matrix, "c")? as f32,
+                d: number_property(activation, *matrix, "d")? as f32,
+                tx: Twips::from_pixels(number_property(activation, *matrix, "tx")?),
+                ty: Twips::from_pixels(number_property(activation, *matrix, "ty")?),
+            };
+        }
+        if let Some(Value::Object(color_transform)) = args.get(2) {
+            let color_transform = *color_transform;
+            transform.color_transform = ColorTransform {
+                r_mult: Fixed8::from_f64(number_property(
+                    activation,
+                    color_transform,
+                    "redMultiplier",
+                )?),
+                g_mult: Fixed8::from_f64(number_property(
+                    activation,
+                    color_transform,
+                    "greenMultiplier",
+                )?),
+                b_mult: Fixed8::from_f64(number_property(
+                    activation,
+                    color_transform,
+                    "blueMultiplier",
+                )?),
+                a_mult: Fixed8::from_f64(number_property(
+                    activation,
+                    color_transform,
+                    "alphaMultiplier",
+                )?),
+                r_add: number_property(activation, color_transform, "redOffset")? as i16,
+                g_add: number_property(activation, color_transform, "greenOffset")? as i16,
+                b_add: number_property(activation, color_transform, "blueOffset")? as i16,
+                a_add: number_property(activation, color_transform, "alphaOffset")? as i16,
+            };
+        }
+
+        let blend_mode = match args.get(3) {
+            Some(Value::Undefined) | Some(Value::Null) | None => BlendMode::Normal,
+            Some(name) => {
+                let name = name.coerce_to_string(activation)?;
+                bitmap_data::blend_mode_from_name(&name).unwrap_or_else(|| {
+                    log::warn!("BitmapData.draw: unknown blend mode {}", name);
+                    BlendMode::Normal
+                })
+            }
+        };
+
+        let clip_rect = if let Some(Value::Object(rectangle)) = args.get(4) {
+            Some(rectangle_bounds(activation, *rectangle)?)
+        } else {
+            None
+        };
+
+        let smoothing = args
+            .get(5)
+            .cloned()
+            .unwrap_or(Value::Bool(false))
+            .coerce_to_boolean();
+
+        bitmap_data::draw(
+            bitmap_data,
+            source,
+            &transform,
+            smoothing,
+            blend_mode,
+            clip_rect,
+            &mut activation.context,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.drawWithQuality`.
+///
+/// Everything is drawn at the renderer's own quality.
+pub fn draw_with_quality<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    draw(activation, this, args)
+}
+
+/// Implements `BitmapData.getPixels`.
+pub fn get_pixels<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+        let rectangle = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let (x, y, width, height) = rectangle_bounds(activation, rectangle)?;
+        let bytearray_class = activation.avm2().classes().bytearray;
+        let bytearray = bytearray_class.construct(activation, &[])?;
+        copy_pixels_to(bitmap_data, x, y, width, height, bytearray, activation)?;
+
+        return Ok(bytearray.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.copyPixelsToByteArray`.
+pub fn copy_pixels_to_byte_array<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+        let rectangle = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let (x, y, width, height) = rectangle_bounds(activation, rectangle)?;
+        let bytearray = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        copy_pixels_to(bitmap_data, x, y, width, height, bytearray, activation)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Write the pixels in a rectangle to a `ByteArray` at its current
+/// position, as big-endian ARGB.
+fn copy_pixels_to<'gc>(
+    bitmap_data: GcCell<'gc, BitmapData<'gc>>,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    bytearray: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<(), Error> {
+    let colors = bitmap_data.read().get_pixels_argb(x, y, width, height);
+    let mut bytes = Vec::with_capacity(colors.len() * 4);
+    for color in colors {
+        bytes.extend_from_slice(&color.to_be_bytes());
+    }
+    bytearray
+        .as_bytearray_mut(activation.context.gc_context)
+        .ok_or("TypeError: Error #1034: Type Coercion failed: cannot convert to flash.utils.ByteArray.")?
+        .write_bytes(&bytes)
+}
+
+/// Implements `BitmapData.setPixels`.
+pub fn set_pixels<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+        let rectangle = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let (x, y, width, height) = rectangle_bounds(activation, rectangle)?;
+        let bytearray = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+
+        let len = bitmap_data.read().clipped_rect_len(x, y, width, height);
+        let mut colors = Vec::with_capacity(len);
+        {
+            let bytearray = bytearray.as_bytearray().ok_or(
+                "TypeError: Error #1034: Type Coercion failed: cannot convert to flash.utils.ByteArray.",
+            )?;
+            while colors.len() < len {
+                match bytearray.read_unsigned_int() {
+                    Ok(color) => colors.push(color),
+                    Err(_) => break,
+                }
+            }
+        }
+
+        bitmap_data
+            .write(activation.context.gc_context)
+            .set_pixels_argb(x, y, width, height, colors.iter().copied());
+        if colors.len() < len {
+            return Err("Error: Error #2030: End of file was encountered.".into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.getVector`.
+pub fn get_vector<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+        let rectangle = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let (x, y, width, height) = rectangle_bounds(activation, rectangle)?;
+        let colors = bitmap_data
+            .read()
+            .get_pixels_argb(x, y, width, height)
+            .into_iter()
+            .map(|color| color.into())
+            .collect();
+        let uint_class = activation.avm2().classes().uint;
+        let storage = VectorStorage::from_values(colors, false, uint_class);
+
+        return Ok(VectorObject::from_vector(storage, activation)?.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.setVector`.
+pub fn set_vector<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+        let rectangle = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let (x, y, width, height) = rectangle_bounds(activation, rectangle)?;
+        let vector = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let values: Vec<Value<'gc>> = vector
+            .as_vector_storage()
+            .map(|values| values.iter().collect())
+            .ok_or(
+                "TypeError: Error #1034: Type Coercion failed: cannot convert to Vector.<uint>.",
+            )?;
+
+        let len = bitmap_data.read().clipped_rect_len(x, y, width, height);
+        let mut colors = Vec::with_capacity(len.min(values.len()));
+        for value in values.iter().take(len) {
+            colors.push(value.coerce_to_u32(activation)?);
+        }
+
+        bitmap_data
+            .write(activation.context.gc_context)
+            .set_pixels_argb(x, y, width, height, colors);
+        if values.len() < len {
+            return Err(format!(
+                "RangeError: Error #1125: The index {} is out of range {}.",
+                values.len(),
+                values.len()
+            )
+            .into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.copyPixels`.
+pub fn copy_pixels<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+        let source = bitmap_data_arg(activation, args, 0)?;
+        let source_rect = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let source_rect = rectangle_bounds(activation, source_rect)?;
+        let dest_point = args
+            .get(2)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let dest_point = point_coordinates(activation, dest_point)?;
+
+        let alpha_bitmap = match args.get(3) {
+            Some(Value::Object(alpha_bitmap)) => alpha_bitmap.as_bitmap_data(),
+            _ => None,
+        };
+        let alpha_point = match args.get(4) {
+            Some(Value::Object(alpha_point)) => point_coordinates(activation, *alpha_point)?,
+            _ => (0, 0),
+        };
+        let merge_alpha = args
+            .get(5)
+            .cloned()
+            .unwrap_or(Value::Bool(false))
+            .coerce_to_boolean();
+
+        // A bitmap can be copied from itself, so anything we read from has
+        // to be copied before we write to it.
+        let read_bitmap = |bitmap: GcCell<'gc, BitmapData<'gc>>| {
+            if GcCell::ptr_eq(bitmap, bitmap_data) {
+                Some(bitmap.read().clone())
+            } else {
+                None
+            }
+        };
+        let source_clone = read_bitmap(source);
+        let source_ref = match source_clone {
+            Some(_) => None,
+            None => Some(source.read()),
+        };
+        let alpha_clone = alpha_bitmap.and_then(read_bitmap);
+        let alpha_ref = match alpha_clone {
+            Some(_) => None,
+            None => alpha_bitmap.map(|alpha_bitmap| alpha_bitmap.read()),
+        };
+
+        let source = source_clone
+            .as_ref()
+            .or_else(|| source_ref.as_deref())
+            .unwrap();
+        let alpha_source = alpha_clone
+            .as_ref()
+            .or_else(|| alpha_ref.as_deref())
+            .map(|alpha_bitmap| (alpha_bitmap, alpha_point));
+        bitmap_data
+            .write(activation.context.gc_context)
+            .copy_pixels(source, source_rect, dest_point, alpha_source, merge_alpha);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Get the `x` and `y` of a `Point`.
+fn point_coordinates<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    point: Object<'gc>,
+) -> Result<(i32, i32), Error> {
+    Ok((
+        number_property(activation, point, "x")? as i32,
+        number_property(activation, point, "y")? as i32,
+    ))
+}
+
+/// Get the `BitmapData` that's the argument at `index`.
+fn bitmap_data_arg<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+) -> Result<GcCell<'gc, BitmapData<'gc>>, Error> {
+    args.get(index)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?
+        .as_bitmap_data()
+        .ok_or_else(|| {
+            "TypeError: Error #1034: Type Coercion failed: cannot convert to flash.display.BitmapData."
+                .into()
+        })
+}
+
+/// Implements `BitmapData.applyFilter`.
+pub fn apply_filter<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+        let source = bitmap_data_arg(activation, args, 0)?;
+        let source_rect = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let (src_x, src_y, src_width, src_height) = rectangle_bounds(activation, source_rect)?;
+        let dest_point = args
+            .get(2)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let (dest_x, dest_y) = point_coordinates(activation, dest_point)?;
+        let filter = match args.get(3) {
+            Some(Value::Object(filter)) => *filter,
+            _ => return Err("TypeError: Error #2007: Parameter filter must be non-null.".into()),
+        };
+        let filter = match bitmapfilter::object_to_bitmap_filter(activation, filter)? {
+            Some(filter) => filter,
+            None => {
+                log::warn!("BitmapData.applyFilter - unsupported filter");
+                return Ok(Value::Undefined);
+            }
+        };
+
+        if src_width > 0 && src_height > 0 {
+            // The source is read before anything is written, so a bitmap can
+            // be filtered into itself.
+            let image =
+                source
+                    .read()
+                    .filter_image(src_x, src_y, src_width as u32, src_height as u32);
+            let image = filter.apply(&image);
+            bitmap_data
+                .write(activation.context.gc_context)
+                .set_filter_image(&image, dest_x, dest_y);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.colorTransform`.
+pub fn color_transform<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+        let rectangle = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let (x, y, width, height) = rectangle_bounds(activation, rectangle)?;
+        let color_transform = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let params = ColorTransformParams {
+            red_multiplier: number_property(activation, color_transform, "redMultiplier")?,
+            green_multiplier: number_property(activation, color_transform, "greenMultiplier")?,
+            blue_multiplier: number_property(activation, color_transform, "blueMultiplier")?,
+            alpha_multiplier: number_property(activation, color_transform, "alphaMultiplier")?,
+            red_offset: number_property(activation, color_transform, "redOffset")?,
+            green_offset: number_property(activation, color_transform, "greenOffset")?,
+            blue_offset: number_property(activation, color_transform, "blueOffset")?,
+            alpha_offset: number_property(activation, color_transform, "alphaOffset")?,
+        };
+
+        bitmap_data
+            .write(activation.context.gc_context)
+            .color_transform(
+                x.max(0) as u32,
+                y.max(0) as u32,
+                x.saturating_add(width).max(0) as u32,
+                y.saturating_add(height).max(0) as u32,
+                &params,
+            );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.merge`.
+pub fn merge<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+        let source = bitmap_data_arg(activation, args, 0)?;
+        let source_rect = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let source_rect = rectangle_bounds(activation, source_rect)?;
+        let dest_point = args
+            .get(2)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let dest_point = point_coordinates(activation, dest_point)?;
+        let mut multiplier = |index| -> Result<i32, Error> {
+            args.get(index)
+                .cloned()
+                .unwrap_or(Value::Undefined)
+                .coerce_to_i32(activation)
+        };
+        let rgba_mult = (
+            multiplier(3)?,
+            multiplier(4)?,
+            multiplier(5)?,
+            multiplier(6)?,
+        );
+
+        // Merging a bitmap into itself uses the pixels from before the merge.
+        let source_clone;
+        let source_ref;
+        let source: &BitmapData = if GcCell::ptr_eq(source, bitmap_data) {
+            source_clone = source.read().clone();
+            &source_clone
+        } else {
+            source_ref = source.read();
+            &source_ref
+        };
+        bitmap_data.write(activation.context.gc_context).merge(
+            source,
+            source_rect,
+            dest_point,
+            rgba_mult,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.paletteMap`.
+pub fn palette_map<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+        let source = bitmap_data_arg(activation, args, 0)?;
+        let source_rect = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let source_rect = rectangle_bounds(activation, source_rect)?;
+        let dest_point = args
+            .get(2)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let dest_point = point_coordinates(activation, dest_point)?;
+
+        let mut get_channel = |index: usize, shift: usize| -> Result<[u32; 256], Error> {
+            let values: Option<Vec<Option<Value<'gc>>>> = match args.get(index) {
+                Some(Value::Object(array)) => array
+                    .as_array_storage()
+                    .map(|array| (0..256).map(|i| array.get(i)).collect()),
+                _ => None,
+            };
+            let mut channel = [0_u32; 256];
+            for (i, item) in channel.iter_mut().enumerate() {
+                *item = match &values {
+                    Some(values) => values[i]
+                        .clone()
+                        .unwrap_or(Value::Undefined)
+                        .coerce_to_u32(activation)?,
+                    // Channels without an array are copied as they are.
+                    None => (i << shift) as u32,
+                };
+            }
+            Ok(channel)
+        };
+        let red_array = get_channel(3, 16)?;
+        let green_array = get_channel(4, 8)?;
+        let blue_array = get_channel(5, 0)?;
+        let alpha_array = get_channel(6, 24)?;
+
+        // Mapping a bitmap onto itself happens in place.
+        let read;
+        let source: Option<&BitmapData> = if GcCell::ptr_eq(source, bitmap_data) {
+            None
+        } else {
+            read = source.read();
+            Some(&read)
+        };
+        bitmap_data
+            .write(activation.context.gc_context)
+            .palette_map(
+                source,
+                source_rect,
+                dest_point,
+                (red_array, green_array, blue_array, alpha_array),
+            );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.threshold`.
+pub fn threshold<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+        let source = bitmap_data_arg(activation, args, 0)?;
+        let source_rect = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let source_rect = rectangle_bounds(activation, source_rect)?;
+        let dest_point = args
+            .get(2)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let dest_point = point_coordinates(activation, dest_point)?;
+        let operation = args
+            .get(3)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?;
+        let operation = ThresholdOperation::from_name(&operation)
+            .ok_or("ArgumentError: Error #2005: Parameter 3 is of the incorrect type. Should be type Operation.")?;
+        let threshold = args
+            .get(4)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_u32(activation)?;
+        let color = args
+            .get(5)
+            .cloned()
+            .unwrap_or(Value::Unsigned(0))
+            .coerce_to_u32(activation)?;
+        let mask = args
+            .get(6)
+            .cloned()
+            .unwrap_or(Value::Unsigned(0xFFFFFFFF))
+            .coerce_to_u32(activation)?;
+        let copy_source = args
+            .get(7)
+            .cloned()
+            .unwrap_or(Value::Bool(false))
+            .coerce_to_boolean();
+
+        // Thresholding a bitmap against itself happens in place.
+        let read;
+        let source: Option<&BitmapData> = if GcCell::ptr_eq(source, bitmap_data) {
+            None
+        } else {
+            read = source.read();
+            Some(&read)
+        };
+        let count = bitmap_data.write(activation.context.gc_context).threshold(
+            source,
+            source_rect,
+            dest_point,
+            operation,
+            threshold,
+            color,
+            mask,
+            copy_source,
+        );
+
+        return Ok(count.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.compare`.
+///
+/// Returns `0` if the bitmaps are the same, a negative number if their sizes
+/// differ, and otherwise a new `BitmapData` of the differences between them.
+pub fn compare<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    const DIFFERENT_WIDTH: i32 = -3;
+    const DIFFERENT_HEIGHT: i32 = -4;
+
+    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+        let other = bitmap_data_arg(activation, args, 0)?;
+        if GcCell::ptr_eq(bitmap_data, other) {
+            return Ok(0.into());
+        }
+
+        let (width, height, difference) = {
+            let bitmap_data = bitmap_data.read();
+            let other = other.read();
+            if bitmap_data.width() != other.width() {
+                return Ok(DIFFERENT_WIDTH.into());
+            }
+            if bitmap_data.height() != other.height() {
+                return Ok(DIFFERENT_HEIGHT.into());
+            }
+            (
+                bitmap_data.width(),
+                bitmap_data.height(),
+                bitmap_data.compare(&other),
+            )
+        };
+
+        return match difference {
+            Some(pixels) => {
+                let bitmapdata_class = activation.avm2().classes().bitmapdata;
+                let result = bitmapdata_class.construct(
+                    activation,
+                    &[width.into(), height.into(), true.into(), 0.into()],
+                )?;
+                if let Some(result_data) = result.as_bitmap_data() {
+                    result_data
+                        .write(activation.context.gc_context)
+                        .set_pixels(width, height, true, pixels);
+                }
+                Ok(result.into())
+            }
+            None => Ok(0.into()),
+        };
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.hitTest`.
+pub fn hit_test<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+        let first_point = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let (top_left_x, top_left_y) = point_coordinates(activation, first_point)?;
+        let alpha_threshold = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_u32(activation)?;
+        let compare_object = args
+            .get(2)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+
+        let other_bitmap = compare_object.as_bitmap_data().or_else(|| {
+            compare_object
+                .as_display_object()
+                .and_then(|object| object.as_bitmap())
+                .and_then(|bitmap| bitmap.bitmap_data())
+        });
+        if let Some(other_bitmap) = other_bitmap {
+            let second_point = match args.get(3) {
+                Some(Value::Object(second_point)) => point_coordinates(activation, *second_point)?,
+                _ => (0, 0),
+            };
+            let second_alpha_threshold = args
+                .get(4)
+                .cloned()
+                .unwrap_or(Value::Unsigned(1))
+                .coerce_to_u32(activation)?;
+
+            let result = bitmap_data.read().hit_test_bitmap(
+                (top_left_x, top_left_y),
+                alpha_threshold,
+                &other_bitmap.read(),
+                second_point,
+                second_alpha_threshold,
+            );
+            return Ok(result.into());
+        }
+
+        let rectangle_class = activation.avm2().classes().rectangle;
+        let point_class = activation.avm2().classes().point;
+        if compare_object.is_of_type(rectangle_class, activation)? {
+            let (x, y, width, height) = rectangle_bounds(activation, compare_object)?;
+            let result = bitmap_data.read().hit_test_rectangle(
+                alpha_threshold,
+                x - top_left_x,
+                y - top_left_y,
+                width,
+                height,
+            );
+            return Ok(result.into());
+        } else if compare_object.is_of_type(point_class, activation)? {
+            let (x, y) = point_coordinates(activation, compare_object)?;
+            let result =
+                bitmap_data
+                    .read()
+                    .hit_test_point(alpha_threshold, x - top_left_x, y - top_left_y);
+            return Ok(result.into());
+        }
+
+        return Err("TypeError: Error #2005: Parameter 2 is of the incorrect type. Should be type Point, Rectangle, Bitmap or BitmapData.".into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.fillRect`.
+pub fn fill_rect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+        let rectangle = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let (x, y, width, height) = rectangle_bounds(activation, rectangle)?;
+        let color = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+
+        bitmap_data.write(activation.context.gc_context).fill_rect(
+            x,
+            y,
+            width,
+            height,
+            color.into(),
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.floodFill`.
+pub fn flood_fill<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+        let x = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let y = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let color = args
+            .get(2)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+
+        let mut bitmap_data = bitmap_data.write(activation.context.gc_context);
+        if bitmap_data.is_point_in_bounds(x, y) {
+            let color = Color::from(color).to_premultiplied_alpha(bitmap_data.transparency());
+            bitmap_data.flood_fill(x as u32, y as u32, color);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapData.scroll`.
+pub fn scroll<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(bitmap_data) = this.and_then(|t| t.as_bitmap_data()) {
+        let x = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let y = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+
+        bitmap_data
+            .write(activation.context.gc_context)
+            .scroll(x, y);
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Construct `BitmapData`'s class.
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
@@ -211,7 +1148,27 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
     ];
     write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
 
-    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[("getPixel", get_pixel)];
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("getPixel", get_pixel),
+        ("draw", draw),
+        ("drawWithQuality", draw_with_quality),
+        ("getPixels", get_pixels),
+        ("setPixels", set_pixels),
+        ("getVector", get_vector),
+        ("setVector", set_vector),
+        ("copyPixelsToByteArray", copy_pixels_to_byte_array),
+        ("copyPixels", copy_pixels),
+        ("colorTransform", color_transform),
+        ("applyFilter", apply_filter),
+        ("merge", merge),
+        ("paletteMap", palette_map),
+        ("threshold", threshold),
+        ("compare", compare),
+        ("hitTest", hit_test),
+        ("fillRect", fill_rect),
+        ("floodFill", flood_fill),
+        ("scroll", scroll),
+    ];
     write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
 
     class
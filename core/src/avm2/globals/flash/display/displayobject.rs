@@ -1,13 +1,23 @@
 //! `flash.display.DisplayObject` builtin/prototype
 
 use crate::avm2::activation::Activation;
+use crate::avm2::array::ArrayStorage;
 use crate::avm2::class::Class;
+use crate::avm2::globals::flash::display::loader::content_loader_info;
+use crate::avm2::globals::flash::filters::bitmapfilter;
+use crate::avm2::globals::flash::geom::point::create_point;
+use crate::avm2::globals::flash::geom::transform::create_transform;
+use crate::avm2::globals::flash::geom::vector3d::{self, create_vector3d};
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::names::{Namespace, QName};
-use crate::avm2::object::{stage_allocator, LoaderInfoObject, Object, TObject};
+use crate::avm2::object::{stage_allocator, ArrayObject, LoaderInfoObject, Object, TObject};
+use crate::avm2::traits::Trait;
 use crate::avm2::value::Value;
 use crate::avm2::Error;
-use crate::display_object::{DisplayObject, HitTestOptions, TDisplayObject};
+use crate::bitmap::bitmap_data;
+use crate::display_object::{DisplayObject, HitTestOptions, TDisplayObject, Transform3D};
+use crate::prelude::BoundingBox;
 use crate::string::AvmString;
 use crate::types::{Degrees, Percent};
 use crate::vminterface::Instantiator;
@@ -346,6 +356,172 @@ pub fn set_rotation<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Change the 3D placement of a display object, placing it in 3D if it
+/// wasn't already.
+fn update_transform_3d<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+    f: impl FnOnce(&mut Transform3D, f64),
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        let value = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_number(activation)?;
+        let mut transform_3d = dobj.transform_3d().unwrap_or_default();
+        f(&mut transform_3d, value);
+        dobj.set_transform_3d(activation.context.gc_context, Some(transform_3d));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `z`'s getter.
+pub fn z<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        return Ok(dobj.transform_3d().unwrap_or_default().z.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `z`'s setter.
+pub fn set_z<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    update_transform_3d(activation, this, args, |transform_3d, z| {
+        transform_3d.z = z;
+    })
+}
+
+/// Implements `rotationX`'s getter.
+pub fn rotation_x<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        return Ok(dobj.transform_3d().unwrap_or_default().rotation_x.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `rotationX`'s setter.
+pub fn set_rotation_x<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    update_transform_3d(activation, this, args, |transform_3d, degrees| {
+        transform_3d.rotation_x = degrees;
+    })
+}
+
+/// Implements `rotationY`'s getter.
+pub fn rotation_y<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        return Ok(dobj.transform_3d().unwrap_or_default().rotation_y.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `rotationY`'s setter.
+pub fn set_rotation_y<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    update_transform_3d(activation, this, args, |transform_3d, degrees| {
+        transform_3d.rotation_y = degrees;
+    })
+}
+
+/// Implements `scaleZ`'s getter.
+pub fn scale_z<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        return Ok(dobj.transform_3d().unwrap_or_default().scale_z.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `scaleZ`'s setter.
+pub fn set_scale_z<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    update_transform_3d(activation, this, args, |transform_3d, scale| {
+        transform_3d.scale_z = scale;
+    })
+}
+
+/// Implements `transform`'s getter.
+pub fn transform<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        return create_transform(activation, dobj);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `transform`'s setter, which copies another display object's
+/// transform.
+pub fn set_transform<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        let transform = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let other = transform
+            .get_property(
+                transform,
+                &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "displayObject"),
+                activation,
+            )?
+            .coerce_to_object(activation)?
+            .as_display_object();
+        if let Some(other) = other {
+            let mc = activation.context.gc_context;
+            let matrix = *other.matrix();
+            let color_transform = *other.color_transform();
+            dobj.set_matrix(mc, &matrix);
+            dobj.set_color_transform(mc, &color_transform);
+            dobj.set_transform_3d(mc, other.transform_3d());
+            dobj.set_perspective_projection(mc, other.perspective_projection());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Implements `name`'s getter.
 pub fn name<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
@@ -380,7 +556,7 @@ pub fn set_name<'gc>(
             .into());
         }
 
-        dobj.set_name(activation.context.gc_context, &new_name);
+        dobj.set_name(&mut activation.context, &new_name);
     }
 
     Ok(Value::Undefined)
@@ -466,6 +642,247 @@ pub fn set_visible<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `cacheAsBitmap`'s getter.
+pub fn cache_as_bitmap<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        return Ok(dobj.cache_as_bitmap().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `cacheAsBitmap`'s setter.
+pub fn set_cache_as_bitmap<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        let cache_as_bitmap = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_boolean();
+
+        dobj.set_cache_as_bitmap(activation.context.gc_context, cache_as_bitmap);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `blendMode`'s getter.
+pub fn blend_mode<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        return Ok(bitmap_data::blend_mode_name(dobj.blend_mode()).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `blendMode`'s setter.
+pub fn set_blend_mode<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        let name = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?;
+        let blend_mode = bitmap_data::blend_mode_from_name(&name).ok_or(
+            "ArgumentError: Error #2008: Parameter blendMode must be one of the accepted values.",
+        )?;
+        dobj.set_blend_mode(activation.context.gc_context, blend_mode);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `scrollRect`'s getter.
+pub fn scroll_rect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        return match dobj.scroll_rect() {
+            Some(scroll_rect) => bounding_box_to_rectangle(activation, &scroll_rect),
+            None => Ok(Value::Null),
+        };
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `scrollRect`'s setter.
+pub fn set_scroll_rect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        let scroll_rect = match args.get(0) {
+            Some(Value::Object(rectangle)) => {
+                Some(rectangle_to_bounding_box(activation, *rectangle)?)
+            }
+            _ => None,
+        };
+        dobj.set_scroll_rect(activation.context.gc_context, scroll_rect);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `scale9Grid`'s getter.
+pub fn scale_nine_grid<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        return match dobj.scaling_grid() {
+            Some(scaling_grid) => bounding_box_to_rectangle(activation, &scaling_grid),
+            None => Ok(Value::Null),
+        };
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `scale9Grid`'s setter.
+pub fn set_scale_nine_grid<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        let scaling_grid = match args.get(0) {
+            Some(Value::Object(rectangle)) => {
+                Some(rectangle_to_bounding_box(activation, *rectangle)?)
+            }
+            _ => None,
+        };
+        dobj.set_scaling_grid(activation.context.gc_context, scaling_grid);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct a `Rectangle` with the same position and size as a bounding box.
+pub fn bounding_box_to_rectangle<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    bounds: &BoundingBox,
+) -> Result<Value<'gc>, Error> {
+    let rectangle_class = activation.avm2().classes().rectangle;
+    Ok(rectangle_class
+        .construct(
+            activation,
+            &[
+                bounds.x_min.to_pixels().into(),
+                bounds.y_min.to_pixels().into(),
+                bounds.width().to_pixels().into(),
+                bounds.height().to_pixels().into(),
+            ],
+        )?
+        .into())
+}
+
+/// Read the position and size of a `Rectangle` into a bounding box.
+pub fn rectangle_to_bounding_box<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    rectangle: Object<'gc>,
+) -> Result<BoundingBox, Error> {
+    let mut values = [0.0; 4];
+    for (value, name) in values.iter_mut().zip(&["x", "y", "width", "height"]) {
+        *value = rectangle
+            .get_property(
+                rectangle,
+                &QName::new(Namespace::public(), *name),
+                activation,
+            )?
+            .coerce_to_number(activation)?;
+    }
+    let [x, y, width, height] = values;
+    Ok(BoundingBox {
+        x_min: Twips::from_pixels(x),
+        y_min: Twips::from_pixels(y),
+        x_max: Twips::from_pixels(x + width),
+        y_max: Twips::from_pixels(y + height),
+        valid: true,
+    })
+}
+
+/// Implements `filters`'s getter.
+pub fn filters<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        let filters = dobj.filters().to_vec();
+        let mut objects = Vec::with_capacity(filters.len());
+        for filter in &filters {
+            if let Some(object) = bitmapfilter::bitmap_filter_to_object(activation, filter)? {
+                objects.push(Some(object.into()));
+            }
+        }
+
+        return Ok(
+            ArrayObject::from_storage(activation, ArrayStorage::from_storage(objects))?.into(),
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `filters`'s setter.
+pub fn set_filters<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        let values = match args.get(0) {
+            Some(Value::Object(array)) => array
+                .as_array_storage()
+                .map(|array| array.iter().collect::<Vec<_>>())
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+
+        let mut filters = Vec::with_capacity(values.len());
+        for value in values {
+            match value {
+                Some(Value::Object(object)) => {
+                    match bitmapfilter::object_to_bitmap_filter(activation, object)? {
+                        Some(filter) => filters.push(filter),
+                        None => log::warn!("DisplayObject.filters: unsupported filter"),
+                    }
+                }
+                _ => {
+                    return Err(
+                        "ArgumentError: Error #2005: Parameter 0 is of the incorrect type.".into(),
+                    )
+                }
+            }
+        }
+
+        dobj.set_filters(activation.context.gc_context, filters);
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Implements `mouseX`.
 pub fn mouse_x<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
@@ -558,27 +975,104 @@ pub fn hit_test_object<'gc>(
     Ok(Value::Undefined)
 }
 
-/// Implements `loaderInfo` getter
-pub fn loader_info<'gc>(
+/// Implements `local3DToGlobal`.
+pub fn local_3d_to_global<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
     if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
-        if let Some(root) = dobj.avm2_root(&mut activation.context) {
-            if DisplayObject::ptr_eq(root, dobj) {
-                let movie = dobj.movie();
+        let point = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let [x, y, z, _] = vector3d::components(point, activation)?;
+        let stage_size = activation.context.stage.stage_size();
+        let (x, y) = dobj
+            .local_3d_to_global([x, y, z], stage_size)
+            .map(|(x, y)| (x.to_pixels(), y.to_pixels()))
+            .unwrap_or((f64::NAN, f64::NAN));
+
+        return create_point(activation, (x, y));
+    }
 
-                if let Some(movie) = movie {
-                    let obj = LoaderInfoObject::from_movie(activation, movie, root)?;
+    Ok(Value::Undefined)
+}
 
-                    return Ok(obj.into());
+/// Implements `globalToLocal3D`.
+pub fn global_to_local_3d<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        let point = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let mut coords = [0.0; 2];
+        for (coord, name) in coords.iter_mut().zip(&["x", "y"]) {
+            *coord = point
+                .get_property(point, &QName::new(Namespace::public(), *name), activation)?
+                .coerce_to_number(activation)?;
+        }
+        let global = (Twips::from_pixels(coords[0]), Twips::from_pixels(coords[1]));
+        let stage_size = activation.context.stage.stage_size();
+        let [x, y, z] =
+            dobj.global_to_local_3d(global, stage_size)
+                .unwrap_or([f64::NAN, f64::NAN, f64::NAN]);
+
+        return create_vector3d(activation, [x, y, z, 0.0]);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `loaderInfo` getter
+pub fn loader_info<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        if let Some(dobj) = this.as_display_object() {
+            if let Some(root) = dobj.avm2_root(&mut activation.context) {
+                if DisplayObject::ptr_eq(root, dobj) {
+                    // Content loaded by a `Loader` shares its `contentLoaderInfo`.
+                    if let Some(Value::Object(parent)) =
+                        dobj.parent().map(|parent| parent.object2())
+                    {
+                        if let Some(loader_info) = content_loader_info(activation, parent)? {
+                            return Ok(loader_info.into());
+                        }
+                    }
+
+                    let movie = dobj.movie();
+
+                    if let Some(movie) = movie {
+                        // Keep the same loader info for the life of the movie,
+                        // so that listeners and `sharedEvents` stick to it.
+                        let loader_info_name =
+                            QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "loaderInfo");
+                        if let Value::Object(obj) =
+                            this.get_property(this, &loader_info_name, activation)?
+                        {
+                            return Ok(obj.into());
+                        }
+
+                        let obj = LoaderInfoObject::from_movie(activation, movie, root)?;
+                        this.set_property(this, &loader_info_name, obj.into(), activation)?;
+
+                        return Ok(obj.into());
+                    }
                 }
             }
-        }
 
-        if DisplayObject::ptr_eq(dobj, activation.context.stage.into()) {
-            return Ok(LoaderInfoObject::from_stage(activation)?.into());
+            if DisplayObject::ptr_eq(dobj, activation.context.stage.into()) {
+                return Ok(LoaderInfoObject::from_stage(activation)?.into());
+            }
         }
     }
 
@@ -619,11 +1113,30 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("x", Some(x), Some(set_x)),
         ("y", Some(y), Some(set_y)),
         ("rotation", Some(rotation), Some(set_rotation)),
+        ("z", Some(z), Some(set_z)),
+        ("rotationX", Some(rotation_x), Some(set_rotation_x)),
+        ("rotationY", Some(rotation_y), Some(set_rotation_y)),
+        ("rotationZ", Some(rotation), Some(set_rotation)),
+        ("scaleZ", Some(scale_z), Some(set_scale_z)),
+        ("transform", Some(transform), Some(set_transform)),
         ("name", Some(name), Some(set_name)),
         ("parent", Some(parent), None),
         ("root", Some(root), None),
         ("stage", Some(stage), None),
         ("visible", Some(visible), Some(set_visible)),
+        ("filters", Some(filters), Some(set_filters)),
+        ("blendMode", Some(blend_mode), Some(set_blend_mode)),
+        (
+            "cacheAsBitmap",
+            Some(cache_as_bitmap),
+            Some(set_cache_as_bitmap),
+        ),
+        ("scrollRect", Some(scroll_rect), Some(set_scroll_rect)),
+        (
+            "scale9Grid",
+            Some(scale_nine_grid),
+            Some(set_scale_nine_grid),
+        ),
         ("mouseX", Some(mouse_x), None),
         ("mouseY", Some(mouse_y), None),
         ("loaderInfo", Some(loader_info), None),
@@ -633,8 +1146,16 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
     const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
         ("hitTestPoint", hit_test_point),
         ("hitTestObject", hit_test_object),
+        ("local3DToGlobal", local_3d_to_global),
+        ("globalToLocal3D", global_to_local_3d),
     ];
     write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
 
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "loaderInfo"),
+        QName::new(Namespace::package("flash.display"), "LoaderInfo").into(),
+        None,
+    ));
+
     class
 }
@@ -2,6 +2,7 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::Class;
+use crate::avm2::globals::flash::text::textsnapshot;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::names::{Namespace, QName};
 use crate::avm2::object::{Object, TObject};
@@ -277,6 +278,21 @@ pub fn num_children<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `DisplayObjectContainer.textSnapshot`
+pub fn text_snapshot<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(ctr) = this.and_then(|this| this.as_display_object()) {
+        if ctr.as_container().is_some() {
+            return Ok(textsnapshot::snapshot_for(activation, ctr)?.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Implements `DisplayObjectContainer.contains`
 pub fn contains<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
@@ -608,7 +624,10 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         &str,
         Option<NativeMethodImpl>,
         Option<NativeMethodImpl>,
-    )] = &[("numChildren", Some(num_children), None)];
+    )] = &[
+        ("numChildren", Some(num_children), None),
+        ("textSnapshot", Some(text_snapshot), None),
+    ];
     write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
 
     const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
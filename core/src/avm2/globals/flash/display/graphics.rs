@@ -2,15 +2,23 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::display::shader::run_shader_object;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::names::{Namespace, QName};
-use crate::avm2::object::{stage_allocator, Object, TObject};
+use crate::avm2::object::{stage_allocator, Object, TObject, VectorObject};
 use crate::avm2::value::Value;
+use crate::avm2::vector::VectorStorage;
 use crate::avm2::Error;
-use crate::display_object::TDisplayObject;
-use crate::shape_utils::DrawCommand;
+use crate::bitmap::filter::FilterImage;
+use crate::display_object::{DisplayObject, TDisplayObject};
+use crate::drawing::DrawingCommand;
+use crate::matrix::Matrix;
+use crate::shape_utils::{DrawCommand, FillRule};
 use gc_arena::{GcCell, MutationContext};
-use swf::{Color, FillStyle, Fixed8, LineCapStyle, LineJoinStyle, LineStyle, Twips};
+use swf::{
+    Color, FillStyle, Fixed8, Gradient, GradientInterpolation, GradientRecord, GradientSpread,
+    LineCapStyle, LineJoinStyle, LineStyle, Twips,
+};
 
 /// Implements `flash.display.Graphics`'s instance constructor.
 pub fn instance_init<'gc>(
@@ -75,6 +83,261 @@ pub fn begin_fill<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Build a bitmap fill from the `bitmap`, `matrix`, `repeat` and `smooth`
+/// arguments of `beginBitmapFill` or `lineBitmapStyle`.
+///
+/// Returns `None` if the bitmap can't be used by the drawing.
+fn bitmap_fill_from_args<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: DisplayObject<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Option<FillStyle>, Error> {
+    let bitmap_data = match args.get(0) {
+        None | Some(Value::Null) | Some(Value::Undefined) => {
+            return Err("TypeError: Error #2007: Parameter bitmap must be non-null.".into())
+        }
+        Some(bitmap) => bitmap
+            .coerce_to_object(activation)?
+            .as_bitmap_data()
+            .ok_or_else(|| -> Error {
+                "TypeError: Error #1034: Type Coercion failed: cannot convert to flash.display.BitmapData."
+                    .into()
+            })?,
+    };
+    let mut matrix = match args.get(1) {
+        Some(Value::Object(matrix)) => object_to_matrix(activation, *matrix)?,
+        _ => Matrix::IDENTITY,
+    };
+    // Flash matrix is in pixels. Scale from pixels to twips.
+    matrix *= Matrix::scale(20.0, 20.0);
+    let is_repeating = args
+        .get(2)
+        .cloned()
+        .unwrap_or_else(|| true.into())
+        .coerce_to_boolean();
+    let is_smoothed = args
+        .get(3)
+        .cloned()
+        .unwrap_or_else(|| false.into())
+        .coerce_to_boolean();
+
+    let id = match this.as_drawing(activation.context.gc_context) {
+        Some(mut draw) => draw.add_bitmap_data(&mut activation.context, bitmap_data),
+        None => None,
+    };
+
+    Ok(id.map(|id| FillStyle::Bitmap {
+        id,
+        matrix: matrix.into(),
+        is_smoothed,
+        is_repeating,
+    }))
+}
+
+/// Implements `Graphics.beginBitmapFill`.
+pub fn begin_bitmap_fill<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        let fill_style = bitmap_fill_from_args(activation, this, args)?;
+
+        if let Some(mut draw) = this.as_drawing(activation.context.gc_context) {
+            draw.set_fill_style(fill_style);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Build a bitmap fill from the `shader` and `matrix` arguments of
+/// `beginShaderFill` or `lineShaderStyle`.
+///
+/// The shader is run once, over an area the size of the stage, and its
+/// output is used as a repeating bitmap.
+fn shader_fill_from_args<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: DisplayObject<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Option<FillStyle>, Error> {
+    let shader = match args.get(0) {
+        None | Some(Value::Null) | Some(Value::Undefined) => {
+            return Err("TypeError: Error #2007: Parameter shader must be non-null.".into())
+        }
+        Some(shader) => shader.coerce_to_object(activation)?,
+    };
+    let (width, height) = activation.context.stage.stage_size();
+    let (width, height) = (width.max(1), height.max(1));
+    let image = match run_shader_object(activation, shader, width, height)? {
+        Some(image) => image,
+        None => return Ok(None),
+    };
+
+    let bitmapdata_class = activation.avm2().classes().bitmapdata;
+    let bitmap = bitmapdata_class.construct(
+        activation,
+        &[width.into(), height.into(), true.into(), 0.into()],
+    )?;
+    if let Some(bitmap_data) = bitmap.as_bitmap_data() {
+        bitmap_data
+            .write(activation.context.gc_context)
+            .set_filter_image(&FilterImage::from_shader_image(&image), 0, 0);
+    }
+
+    let matrix = args.get(1).cloned().unwrap_or(Value::Null);
+    bitmap_fill_from_args(
+        activation,
+        this,
+        &[bitmap.into(), matrix, true.into(), false.into()],
+    )
+}
+
+/// Implements `Graphics.beginShaderFill`.
+pub fn begin_shader_fill<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        let fill_style = shader_fill_from_args(activation, this, args)?;
+
+        if let Some(mut draw) = this.as_drawing(activation.context.gc_context) {
+            draw.set_fill_style(fill_style);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Get the values held by an `Array`, or nothing if the value isn't one.
+fn array_values<'gc>(value: &Value<'gc>) -> Vec<Value<'gc>> {
+    match value {
+        Value::Object(object) => object
+            .as_array_storage()
+            .map(|array| {
+                array
+                    .iter()
+                    .map(|value| value.unwrap_or(Value::Undefined))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Build a gradient fill from the `type`, `colors`, `alphas`, `ratios`,
+/// `matrix`, `spreadMethod`, `interpolationMethod` and `focalPointRatio`
+/// arguments of `beginGradientFill` or `lineGradientStyle`.
+///
+/// Returns `None` if the `colors`, `alphas` and `ratios` don't line up.
+fn gradient_fill_from_args<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Option<FillStyle>, Error> {
+    let fill_type = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    let colors = array_values(args.get(1).unwrap_or(&Value::Undefined));
+    let alphas = array_values(args.get(2).unwrap_or(&Value::Undefined));
+    let ratios = array_values(args.get(3).unwrap_or(&Value::Undefined));
+    if colors.len() != alphas.len() || colors.len() != ratios.len() {
+        log::warn!("Graphics: gradient colors, alphas and ratios have different lengths");
+        return Ok(None);
+    }
+
+    let mut records = Vec::with_capacity(colors.len());
+    for ((color, alpha), ratio) in colors.iter().zip(&alphas).zip(&ratios) {
+        let color = color.coerce_to_u32(activation)?;
+        let alpha = alpha.coerce_to_number(activation)?.min(1.0).max(0.0);
+        let ratio = ratio.coerce_to_number(activation)?.min(255.0).max(0.0);
+        records.push(GradientRecord {
+            ratio: ratio as u8,
+            color: color_from_args(color, alpha),
+        });
+    }
+
+    let matrix = match args.get(4) {
+        Some(Value::Object(matrix)) => object_to_matrix(activation, *matrix)?,
+        _ => Matrix::IDENTITY,
+    };
+    let spread = match args
+        .get(5)
+        .cloned()
+        .unwrap_or_else(|| "pad".into())
+        .coerce_to_string(activation)?
+        .as_str()
+    {
+        "pad" => GradientSpread::Pad,
+        "reflect" => GradientSpread::Reflect,
+        "repeat" => GradientSpread::Repeat,
+        _ => {
+            return Err(
+                "ArgumentError: Error #2008: Parameter spreadMethod must be one of the accepted values."
+                    .into(),
+            )
+        }
+    };
+    let interpolation = match args
+        .get(6)
+        .cloned()
+        .unwrap_or_else(|| "rgb".into())
+        .coerce_to_string(activation)?
+        .as_str()
+    {
+        "rgb" => GradientInterpolation::Rgb,
+        "linearRGB" => GradientInterpolation::LinearRgb,
+        _ => return Err(
+            "ArgumentError: Error #2008: Parameter interpolationMethod must be one of the accepted values."
+                .into(),
+        ),
+    };
+    let focal_point = args
+        .get(7)
+        .cloned()
+        .unwrap_or_else(|| 0.0.into())
+        .coerce_to_number(activation)?
+        .min(1.0)
+        .max(-1.0);
+
+    let gradient = Gradient {
+        matrix: matrix.into(),
+        spread,
+        interpolation,
+        records,
+    };
+    match fill_type.as_str() {
+        "linear" => Ok(Some(FillStyle::LinearGradient(gradient))),
+        "radial" if focal_point == 0.0 => Ok(Some(FillStyle::RadialGradient(gradient))),
+        "radial" => Ok(Some(FillStyle::FocalGradient {
+            gradient,
+            focal_point: Fixed8::from_f64(focal_point),
+        })),
+        _ => Err(
+            "ArgumentError: Error #2008: Parameter type must be one of the accepted values.".into(),
+        ),
+    }
+}
+
+/// Implements `Graphics.beginGradientFill`.
+pub fn begin_gradient_fill<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        let fill_style = gradient_fill_from_args(activation, args)?;
+
+        if let Some(mut draw) = this.as_drawing(activation.context.gc_context) {
+            draw.set_fill_style(fill_style);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Implements `Graphics.clear`
 pub fn clear<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
@@ -130,6 +393,71 @@ pub fn curve_to<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `Graphics.cubicCurveTo`.
+pub fn cubic_curve_to<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        let mut points = [Twips::ZERO; 6];
+        for (i, point) in points.iter_mut().enumerate() {
+            *point = Twips::from_pixels(
+                args.get(i)
+                    .cloned()
+                    .unwrap_or(Value::Undefined)
+                    .coerce_to_number(activation)?,
+            );
+        }
+
+        if let Some(mut draw) = this.as_drawing(activation.context.gc_context) {
+            draw.draw_cubic_curve(
+                (points[0], points[1]),
+                (points[2], points[3]),
+                (points[4], points[5]),
+            );
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.copyFrom`.
+pub fn copy_from<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        let source = match args.get(0) {
+            None | Some(Value::Null) | Some(Value::Undefined) => {
+                return Err(
+                    "TypeError: Error #2007: Parameter sourceGraphics must be non-null.".into(),
+                )
+            }
+            Some(source) => source.coerce_to_object(activation)?.as_display_object(),
+        };
+
+        if let Some(source) = source {
+            if DisplayObject::ptr_eq(source, this) {
+                return Ok(Value::Undefined);
+            }
+
+            let source_drawing = source
+                .as_drawing(activation.context.gc_context)
+                .map(|draw| draw.clone());
+            if let (Some(source_drawing), Some(mut draw)) = (
+                source_drawing,
+                this.as_drawing(activation.context.gc_context),
+            ) {
+                draw.copy_from(&source_drawing);
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Implements `Graphics.endFill`.
 pub fn end_fill<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
@@ -261,6 +589,57 @@ pub fn line_style<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `Graphics.lineBitmapStyle`.
+pub fn line_bitmap_style<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        if let Some(fill_style) = bitmap_fill_from_args(activation, this, args)? {
+            if let Some(mut draw) = this.as_drawing(activation.context.gc_context) {
+                draw.set_line_fill_style(fill_style);
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.lineShaderStyle`.
+pub fn line_shader_style<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        if let Some(fill_style) = shader_fill_from_args(activation, this, args)? {
+            if let Some(mut draw) = this.as_drawing(activation.context.gc_context) {
+                draw.set_line_fill_style(fill_style);
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.lineGradientStyle`.
+pub fn line_gradient_style<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        if let Some(fill_style) = gradient_fill_from_args(activation, args)? {
+            if let Some(mut draw) = this.as_drawing(activation.context.gc_context) {
+                draw.set_line_fill_style(fill_style);
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Implements `Graphics.lineTo`.
 pub fn line_to<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
@@ -364,6 +743,501 @@ pub fn draw_rect<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Look up one of the `flash.display` classes by name.
+fn display_class<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    name: &'static str,
+) -> Result<Object<'gc>, Error> {
+    activation
+        .avm2()
+        .global_domain()
+        .get_defined_value(
+            activation,
+            QName::new(Namespace::package("flash.display"), name),
+        )?
+        .coerce_to_object(activation)
+}
+
+/// Read a public property of an object.
+fn object_property<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+    name: &'static str,
+) -> Result<Value<'gc>, Error> {
+    object.get_property(object, &QName::new(Namespace::public(), name), activation)
+}
+
+/// Read the `a`, `b`, `c`, `d`, `tx` and `ty` properties of a `Matrix`.
+fn object_to_matrix<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<Matrix, Error> {
+    let mut number = |name| -> Result<f64, Error> {
+        object_property(activation, object, name)?.coerce_to_number(activation)
+    };
+
+    Ok(Matrix {
+        a: number("a")? as f32,
+        b: number("b")? as f32,
+        c: number("c")? as f32,
+        d: number("d")? as f32,
+        tx: Twips::from_pixels(number("tx")?),
+        ty: Twips::from_pixels(number("ty")?),
+    })
+}
+
+/// Get the values held by a `Vector`, or nothing if the value isn't one.
+fn vector_values<'gc>(value: &Value<'gc>) -> Vec<Value<'gc>> {
+    match value {
+        Value::Object(object) => object
+            .as_vector_storage()
+            .map(|vector| vector.iter().collect())
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+fn winding_to_fill_rule<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    winding: Value<'gc>,
+) -> Result<FillRule, Error> {
+    match winding.coerce_to_string(activation)?.as_str() {
+        "evenOdd" => Ok(FillRule::EvenOdd),
+        "nonZero" => Ok(FillRule::NonZero),
+        _ => Err(
+            "ArgumentError: Error #2008: Parameter winding must be one of the accepted values."
+                .into(),
+        ),
+    }
+}
+
+/// Draw the commands of a path, each taking its coordinates from `data`.
+///
+/// Drawing stops at the first command that doesn't have enough data left.
+fn draw_path_commands<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: DisplayObject<'gc>,
+    commands: &[Value<'gc>],
+    data: &[Value<'gc>],
+    winding_rule: FillRule,
+) -> Result<(), Error> {
+    let mut commands_and_points = Vec::with_capacity(commands.len());
+    let mut data = data.iter();
+    for command in commands {
+        let command = command.coerce_to_i32(activation)?;
+        let length = match command {
+            1 | 2 => 2,
+            3..=5 => 4,
+            6 => 6,
+            _ => continue,
+        };
+        if data.len() < length {
+            break;
+        }
+
+        let mut points = Vec::with_capacity(length);
+        for value in data.by_ref().take(length) {
+            points.push(Twips::from_pixels(value.coerce_to_number(activation)?));
+        }
+        commands_and_points.push((command, points));
+    }
+
+    if let Some(mut draw) = this.as_drawing(activation.context.gc_context) {
+        draw.set_winding_rule(winding_rule);
+
+        for (command, points) in commands_and_points {
+            let command = match command {
+                1 => DrawCommand::MoveTo {
+                    x: points[0],
+                    y: points[1],
+                },
+                2 => DrawCommand::LineTo {
+                    x: points[0],
+                    y: points[1],
+                },
+                3 => DrawCommand::CurveTo {
+                    x1: points[0],
+                    y1: points[1],
+                    x2: points[2],
+                    y2: points[3],
+                },
+                4 => DrawCommand::MoveTo {
+                    x: points[2],
+                    y: points[3],
+                },
+                5 => DrawCommand::LineTo {
+                    x: points[2],
+                    y: points[3],
+                },
+                _ => {
+                    draw.draw_cubic_curve(
+                        (points[0], points[1]),
+                        (points[2], points[3]),
+                        (points[4], points[5]),
+                    );
+                    continue;
+                }
+            };
+            draw.draw_command(command);
+        }
+    }
+
+    Ok(())
+}
+
+/// Implements `Graphics.drawPath`.
+pub fn draw_path<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        let commands = vector_values(args.get(0).unwrap_or(&Value::Undefined));
+        let data = vector_values(args.get(1).unwrap_or(&Value::Undefined));
+        let winding_rule = winding_to_fill_rule(
+            activation,
+            args.get(2).cloned().unwrap_or_else(|| "evenOdd".into()),
+        )?;
+
+        draw_path_commands(activation, this, &commands, &data, winding_rule)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.drawGraphicsData`.
+pub fn draw_graphics_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let solid_fill_class = display_class(activation, "GraphicsSolidFill")?;
+        let end_fill_class = display_class(activation, "GraphicsEndFill")?;
+        let stroke_class = display_class(activation, "GraphicsStroke")?;
+        let path_class = display_class(activation, "GraphicsPath")?;
+
+        for item in vector_values(args.get(0).unwrap_or(&Value::Undefined)) {
+            let item = match item {
+                Value::Object(item) => item,
+                _ => continue,
+            };
+
+            if item.is_of_type(solid_fill_class, activation)? {
+                let color = object_property(activation, item, "color")?;
+                let alpha = object_property(activation, item, "alpha")?;
+                begin_fill(activation, Some(this), &[color, alpha])?;
+            } else if item.is_of_type(end_fill_class, activation)? {
+                end_fill(activation, Some(this), &[])?;
+            } else if item.is_of_type(stroke_class, activation)? {
+                let mut thickness = object_property(activation, item, "thickness")?;
+                let (color, alpha) = match object_property(activation, item, "fill")? {
+                    Value::Object(fill) if fill.is_of_type(solid_fill_class, activation)? => (
+                        object_property(activation, fill, "color")?,
+                        object_property(activation, fill, "alpha")?,
+                    ),
+                    Value::Object(_) => {
+                        log::warn!(
+                            "Graphics.drawGraphicsData: only solid stroke fills are supported"
+                        );
+                        (0.into(), 1.0.into())
+                    }
+                    _ => {
+                        thickness = f64::NAN.into();
+                        (0.into(), 1.0.into())
+                    }
+                };
+                let args = [
+                    thickness,
+                    color,
+                    alpha,
+                    object_property(activation, item, "pixelHinting")?,
+                    object_property(activation, item, "scaleMode")?,
+                    object_property(activation, item, "caps")?,
+                    object_property(activation, item, "joints")?,
+                    object_property(activation, item, "miterLimit")?,
+                ];
+                line_style(activation, Some(this), &args)?;
+            } else if item.is_of_type(path_class, activation)? {
+                if let Some(this) = this.as_display_object() {
+                    let commands = vector_values(&object_property(activation, item, "commands")?);
+                    let data = vector_values(&object_property(activation, item, "data")?);
+                    let winding = object_property(activation, item, "winding")?;
+                    let winding_rule = winding_to_fill_rule(activation, winding)?;
+
+                    draw_path_commands(activation, this, &commands, &data, winding_rule)?;
+                }
+            } else {
+                log::warn!("Graphics.drawGraphicsData: unsupported graphics data");
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Build a `GraphicsPath` out of the draw commands collected so far.
+fn flush_graphics_path<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    commands: &mut Vec<Value<'gc>>,
+    data: &mut Vec<Value<'gc>>,
+    winding_rule: FillRule,
+    graphics_data: &mut Vec<Value<'gc>>,
+) -> Result<(), Error> {
+    if commands.is_empty() {
+        return Ok(());
+    }
+
+    let int_class = activation.avm2().classes().int;
+    let number_class = activation.avm2().classes().number;
+    let commands = VectorStorage::from_values(std::mem::take(commands), false, int_class);
+    let data = VectorStorage::from_values(std::mem::take(data), false, number_class);
+    let commands = VectorObject::from_vector(commands, activation)?;
+    let data = VectorObject::from_vector(data, activation)?;
+    let winding = match winding_rule {
+        FillRule::EvenOdd => "evenOdd",
+        FillRule::NonZero => "nonZero",
+    };
+
+    let path = display_class(activation, "GraphicsPath")?
+        .construct(activation, &[commands.into(), data.into(), winding.into()])?;
+    graphics_data.push(path.into());
+
+    Ok(())
+}
+
+/// Implements `Graphics.readGraphicsData`.
+///
+/// Only the drawing made with this `Graphics` is read back; the drawings of
+/// any children are not included.
+pub fn read_graphics_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let history = this
+        .and_then(|t| t.as_display_object())
+        .and_then(|this| {
+            this.as_drawing(activation.context.gc_context)
+                .map(|draw| draw.history().to_vec())
+        })
+        .unwrap_or_default();
+
+    let mut graphics_data = Vec::new();
+    let mut commands = Vec::new();
+    let mut data = Vec::new();
+    let mut winding_rule = FillRule::EvenOdd;
+    for command in history {
+        if let DrawingCommand::Draw(command) = command {
+            let (id, points) = match command {
+                DrawCommand::MoveTo { x, y } => (1, vec![x, y]),
+                DrawCommand::LineTo { x, y } => (2, vec![x, y]),
+                DrawCommand::CurveTo { x1, y1, x2, y2 } => (3, vec![x1, y1, x2, y2]),
+            };
+            commands.push(Value::Integer(id));
+            data.extend(points.into_iter().map(|p| Value::Number(p.to_pixels())));
+            continue;
+        }
+
+        flush_graphics_path(
+            activation,
+            &mut commands,
+            &mut data,
+            winding_rule,
+            &mut graphics_data,
+        )?;
+
+        match command {
+            DrawingCommand::SetFill(Some(FillStyle::Color(color))) => {
+                winding_rule = FillRule::EvenOdd;
+                let fill = display_class(activation, "GraphicsSolidFill")?.construct(
+                    activation,
+                    &[color.to_rgb().into(), (f64::from(color.a) / 255.0).into()],
+                )?;
+                graphics_data.push(fill.into());
+            }
+            DrawingCommand::SetFill(Some(_)) => {
+                winding_rule = FillRule::EvenOdd;
+                log::warn!("Graphics.readGraphicsData: only solid fills are supported");
+            }
+            DrawingCommand::SetFill(None) => {
+                let end_fill =
+                    display_class(activation, "GraphicsEndFill")?.construct(activation, &[])?;
+                graphics_data.push(end_fill.into());
+            }
+            DrawingCommand::SetLine(Some(style)) => {
+                let fill = display_class(activation, "GraphicsSolidFill")?.construct(
+                    activation,
+                    &[
+                        style.color.to_rgb().into(),
+                        (f64::from(style.color.a) / 255.0).into(),
+                    ],
+                )?;
+                let scale_mode = match (style.allow_scale_x, style.allow_scale_y) {
+                    (true, true) => "normal",
+                    (false, false) => "none",
+                    (true, false) => "horizontal",
+                    (false, true) => "vertical",
+                };
+                let caps = match style.start_cap {
+                    LineCapStyle::None => "none",
+                    LineCapStyle::Round => "round",
+                    LineCapStyle::Square => "square",
+                };
+                let (joints, miter_limit) = match style.join_style {
+                    LineJoinStyle::Round => ("round", 3.0),
+                    LineJoinStyle::Bevel => ("bevel", 3.0),
+                    LineJoinStyle::Miter(limit) => ("miter", limit.to_f64()),
+                };
+                let stroke = display_class(activation, "GraphicsStroke")?.construct(
+                    activation,
+                    &[
+                        style.width.to_pixels().into(),
+                        style.is_pixel_hinted.into(),
+                        scale_mode.into(),
+                        caps.into(),
+                        joints.into(),
+                        miter_limit.into(),
+                        fill.into(),
+                    ],
+                )?;
+                graphics_data.push(stroke.into());
+            }
+            DrawingCommand::SetLine(None) => {
+                let stroke = display_class(activation, "GraphicsStroke")?
+                    .construct(activation, &[f64::NAN.into()])?;
+                graphics_data.push(stroke.into());
+            }
+            DrawingCommand::SetWindingRule(rule) => winding_rule = rule,
+            DrawingCommand::Draw(_) => {}
+        }
+    }
+    flush_graphics_path(
+        activation,
+        &mut commands,
+        &mut data,
+        winding_rule,
+        &mut graphics_data,
+    )?;
+
+    let value_type = display_class(activation, "IGraphicsData")?;
+    let vector = VectorStorage::from_values(graphics_data, false, value_type);
+    Ok(VectorObject::from_vector(vector, activation)?.into())
+}
+
+/// Read the numbers held by a `Vector`.
+fn vector_numbers<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: &Value<'gc>,
+) -> Result<Vec<f64>, Error> {
+    vector_values(value)
+        .iter()
+        .map(|value| value.coerce_to_number(activation))
+        .collect()
+}
+
+/// Implements `Graphics.drawTriangles`.
+pub fn draw_triangles<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        let vertices = vector_numbers(activation, args.get(0).unwrap_or(&Value::Undefined))?;
+        let indices = match args.get(1) {
+            None | Some(Value::Null) | Some(Value::Undefined) => None,
+            Some(indices) => Some(
+                vector_values(indices)
+                    .iter()
+                    .map(|index| index.coerce_to_i32(activation))
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+        };
+        let uvt_data = vector_numbers(activation, args.get(2).unwrap_or(&Value::Undefined))?;
+        let culling = args
+            .get(3)
+            .cloned()
+            .unwrap_or_else(|| "none".into())
+            .coerce_to_string(activation)?;
+        let culling = match culling.as_str() {
+            "none" => None,
+            "positive" => Some(true),
+            "negative" => Some(false),
+            _ => return Err(
+                "ArgumentError: Error #2008: Parameter culling must be one of the accepted values."
+                    .into(),
+            ),
+        };
+
+        let vertex_count = vertices.len() / 2;
+        let uvt_stride = if uvt_data.len() == vertex_count * 3 {
+            3
+        } else {
+            2
+        };
+        let vertex = |index: usize| {
+            let x = *vertices.get(index * 2)?;
+            let y = *vertices.get(index * 2 + 1)?;
+            Some((Twips::from_pixels(x), Twips::from_pixels(y)))
+        };
+        let uvt = |index: usize| {
+            let u = *uvt_data.get(index * uvt_stride)?;
+            let v = *uvt_data.get(index * uvt_stride + 1)?;
+            let t = if uvt_stride == 3 {
+                *uvt_data.get(index * uvt_stride + 2)?
+            } else {
+                1.0
+            };
+            Some((u, v, t))
+        };
+
+        let triangles: Vec<[usize; 3]> = match indices {
+            Some(indices) => indices
+                .chunks_exact(3)
+                .filter(|triangle| triangle.iter().all(|&index| index >= 0))
+                .map(|triangle| {
+                    [
+                        triangle[0] as usize,
+                        triangle[1] as usize,
+                        triangle[2] as usize,
+                    ]
+                })
+                .collect(),
+            None => (0..vertex_count / 3)
+                .map(|triangle| [triangle * 3, triangle * 3 + 1, triangle * 3 + 2])
+                .collect(),
+        };
+
+        if let Some(mut draw) = this.as_drawing(activation.context.gc_context) {
+            for [i0, i1, i2] in triangles {
+                let corners = match (vertex(i0), vertex(i1), vertex(i2)) {
+                    (Some(v0), Some(v1), Some(v2)) => [v0, v1, v2],
+                    _ => continue,
+                };
+
+                if let Some(cull_positive) = culling {
+                    // The z component of the triangle's normal, with y pointing down the screen.
+                    let normal = (corners[1].0 - corners[0].0).get() as f64
+                        * (corners[2].1 - corners[0].1).get() as f64
+                        - (corners[1].1 - corners[0].1).get() as f64
+                            * (corners[2].0 - corners[0].0).get() as f64;
+                    if (cull_positive && normal > 0.0) || (!cull_positive && normal < 0.0) {
+                        continue;
+                    }
+                }
+
+                let uvts = match (uvt(i0), uvt(i1), uvt(i2)) {
+                    (Some(uvt0), Some(uvt1), Some(uvt2)) => Some([uvt0, uvt1, uvt2]),
+                    _ => None,
+                };
+                draw.draw_triangle(corners, uvts);
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Construct `Graphics`'s class.
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
@@ -385,14 +1259,26 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
     ));
 
     const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("beginBitmapFill", begin_bitmap_fill),
         ("beginFill", begin_fill),
+        ("beginGradientFill", begin_gradient_fill),
+        ("beginShaderFill", begin_shader_fill),
         ("clear", clear),
+        ("copyFrom", copy_from),
+        ("cubicCurveTo", cubic_curve_to),
         ("curveTo", curve_to),
         ("endFill", end_fill),
+        ("lineBitmapStyle", line_bitmap_style),
+        ("lineGradientStyle", line_gradient_style),
+        ("lineShaderStyle", line_shader_style),
         ("lineStyle", line_style),
         ("lineTo", line_to),
         ("moveTo", move_to),
         ("drawRect", draw_rect),
+        ("drawPath", draw_path),
+        ("drawGraphicsData", draw_graphics_data),
+        ("readGraphicsData", read_graphics_data),
+        ("drawTriangles", draw_triangles),
     ];
     write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
 
@@ -0,0 +1,219 @@
+//! `flash.display.GraphicsPath` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Multiname, Namespace, QName};
+use crate::avm2::object::{Object, TObject, VectorObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::vector::VectorStorage;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.display.GraphicsPath`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let commands = args.get(0).cloned().unwrap_or(Value::Null);
+        let data = args.get(1).cloned().unwrap_or(Value::Null);
+        let winding = args
+            .get(2)
+            .cloned()
+            .unwrap_or_else(|| "evenOdd".into())
+            .coerce_to_string(activation)?;
+
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "commands"),
+            commands,
+            activation,
+        )?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "data"),
+            data,
+            activation,
+        )?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "winding"),
+            winding.into(),
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display.GraphicsPath`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Append values to one of the path's vectors, creating the vector if the
+/// path doesn't have one yet.
+fn push_to_vector<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    mut this: Object<'gc>,
+    name: &'static str,
+    value_type: Object<'gc>,
+    values: &[Value<'gc>],
+) -> Result<(), Error> {
+    let name = QName::new(Namespace::public(), name);
+    let vector = match this.get_property(this, &name, activation)? {
+        Value::Object(vector) if vector.as_vector_storage().is_some() => vector,
+        _ => {
+            let storage = VectorStorage::new(0, false, value_type, activation);
+            let vector = VectorObject::from_vector(storage, activation)?;
+            this.set_property(this, &name, vector.into(), activation)?;
+            vector
+        }
+    };
+
+    let mut storage = vector
+        .as_vector_storage_mut(activation.context.gc_context)
+        .unwrap();
+    for value in values {
+        storage.push(value.clone())?;
+    }
+
+    Ok(())
+}
+
+/// Append a command and its coordinates to the path.
+fn append_command<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    command: i32,
+    args: &[Value<'gc>],
+    arg_count: usize,
+    padding: usize,
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let mut data = vec![Value::Number(0.0); padding];
+        for i in 0..arg_count {
+            let value = args
+                .get(i)
+                .cloned()
+                .unwrap_or(Value::Undefined)
+                .coerce_to_number(activation)?;
+            data.push(value.into());
+        }
+
+        let int_class = activation.avm2().classes().int;
+        let number_class = activation.avm2().classes().number;
+        push_to_vector(activation, this, "commands", int_class, &[command.into()])?;
+        push_to_vector(activation, this, "data", number_class, &data)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `GraphicsPath.moveTo`.
+pub fn move_to<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    append_command(activation, this, 1, args, 2, 0)
+}
+
+/// Implements `GraphicsPath.lineTo`.
+pub fn line_to<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    append_command(activation, this, 2, args, 2, 0)
+}
+
+/// Implements `GraphicsPath.curveTo`.
+pub fn curve_to<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    append_command(activation, this, 3, args, 4, 0)
+}
+
+/// Implements `GraphicsPath.wideMoveTo`.
+pub fn wide_move_to<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    append_command(activation, this, 4, args, 2, 2)
+}
+
+/// Implements `GraphicsPath.wideLineTo`.
+pub fn wide_line_to<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    append_command(activation, this, 5, args, 2, 2)
+}
+
+/// Implements `GraphicsPath.cubicCurveTo`.
+pub fn cubic_curve_to<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    append_command(activation, this, 6, args, 6, 0)
+}
+
+/// Construct `GraphicsPath`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.display"), "GraphicsPath"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<GraphicsPath instance initializer>", mc),
+        Method::from_builtin(class_init, "<GraphicsPath class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+    write.implements(QName::new(Namespace::package("flash.display"), "IGraphicsPath").into());
+    write.implements(QName::new(Namespace::package("flash.display"), "IGraphicsData").into());
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "commands"),
+        Multiname::any(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "data"),
+        Multiname::any(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "winding"),
+        QName::new(Namespace::public(), "String").into(),
+        Some("evenOdd".into()),
+    ));
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("moveTo", move_to),
+        ("lineTo", line_to),
+        ("curveTo", curve_to),
+        ("wideMoveTo", wide_move_to),
+        ("wideLineTo", wide_line_to),
+        ("cubicCurveTo", cubic_curve_to),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    class
+}
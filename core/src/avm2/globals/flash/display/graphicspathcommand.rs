@@ -0,0 +1,63 @@
+//! `flash.display.GraphicsPathCommand` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::Object;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.display.GraphicsPathCommand`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display.GraphicsPathCommand`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `GraphicsPathCommand`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.display"), "GraphicsPathCommand"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(
+            instance_init,
+            "<GraphicsPathCommand instance initializer>",
+            mc,
+        ),
+        Method::from_builtin(class_init, "<GraphicsPathCommand class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+    const CONSTANTS: &[(&str, u32)] = &[
+        ("NO_OP", 0),
+        ("MOVE_TO", 1),
+        ("LINE_TO", 2),
+        ("CURVE_TO", 3),
+        ("WIDE_MOVE_TO", 4),
+        ("WIDE_LINE_TO", 5),
+        ("CUBIC_CURVE_TO", 6),
+    ];
+    write.define_public_constant_uint_class_traits(CONSTANTS);
+
+    class
+}
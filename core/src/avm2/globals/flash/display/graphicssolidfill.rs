@@ -0,0 +1,87 @@
+//! `flash.display.GraphicsSolidFill` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.display.GraphicsSolidFill`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let color = args
+            .get(0)
+            .cloned()
+            .unwrap_or_else(|| 0.into())
+            .coerce_to_u32(activation)?;
+        let alpha = args
+            .get(1)
+            .cloned()
+            .unwrap_or_else(|| 1.0.into())
+            .coerce_to_number(activation)?;
+
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "color"),
+            color.into(),
+            activation,
+        )?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "alpha"),
+            alpha.into(),
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display.GraphicsSolidFill`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `GraphicsSolidFill`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.display"), "GraphicsSolidFill"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(
+            instance_init,
+            "<GraphicsSolidFill instance initializer>",
+            mc,
+        ),
+        Method::from_builtin(class_init, "<GraphicsSolidFill class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+    write.implements(QName::new(Namespace::package("flash.display"), "IGraphicsFill").into());
+    write.implements(QName::new(Namespace::package("flash.display"), "IGraphicsData").into());
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "color"),
+        QName::new(Namespace::public(), "uint").into(),
+        Some(0u32.into()),
+    ));
+    write.define_public_slot_number_instance_traits(&[("alpha", Some(1.0))]);
+
+    class
+}
@@ -0,0 +1,132 @@
+//! `flash.display.GraphicsStroke` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.display.GraphicsStroke`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let thickness = args
+            .get(0)
+            .cloned()
+            .unwrap_or_else(|| f64::NAN.into())
+            .coerce_to_number(activation)?;
+        let pixel_hinting = args
+            .get(1)
+            .cloned()
+            .unwrap_or_else(|| false.into())
+            .coerce_to_boolean();
+        let scale_mode = args
+            .get(2)
+            .cloned()
+            .unwrap_or_else(|| "normal".into())
+            .coerce_to_string(activation)?;
+        let caps = args
+            .get(3)
+            .cloned()
+            .unwrap_or_else(|| "none".into())
+            .coerce_to_string(activation)?;
+        let joints = args
+            .get(4)
+            .cloned()
+            .unwrap_or_else(|| "round".into())
+            .coerce_to_string(activation)?;
+        let miter_limit = args
+            .get(5)
+            .cloned()
+            .unwrap_or_else(|| 3.0.into())
+            .coerce_to_number(activation)?;
+        let fill = args.get(6).cloned().unwrap_or(Value::Null);
+
+        let properties: [(&str, Value<'gc>); 7] = [
+            ("thickness", thickness.into()),
+            ("pixelHinting", pixel_hinting.into()),
+            ("scaleMode", scale_mode.into()),
+            ("caps", caps.into()),
+            ("joints", joints.into()),
+            ("miterLimit", miter_limit.into()),
+            ("fill", fill),
+        ];
+        for (name, value) in properties.iter() {
+            this.set_property(
+                this,
+                &QName::new(Namespace::public(), *name),
+                value.clone(),
+                activation,
+            )?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display.GraphicsStroke`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `GraphicsStroke`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.display"), "GraphicsStroke"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<GraphicsStroke instance initializer>", mc),
+        Method::from_builtin(class_init, "<GraphicsStroke class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+    write.implements(QName::new(Namespace::package("flash.display"), "IGraphicsStroke").into());
+    write.implements(QName::new(Namespace::package("flash.display"), "IGraphicsData").into());
+
+    write.define_public_slot_number_instance_traits(&[
+        ("thickness", Some(f64::NAN)),
+        ("miterLimit", Some(3.0)),
+    ]);
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "pixelHinting"),
+        QName::new(Namespace::public(), "Boolean").into(),
+        Some(false.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "scaleMode"),
+        QName::new(Namespace::public(), "String").into(),
+        Some("normal".into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "caps"),
+        QName::new(Namespace::public(), "String").into(),
+        Some("none".into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "joints"),
+        QName::new(Namespace::public(), "String").into(),
+        Some("round".into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "fill"),
+        QName::new(Namespace::package("flash.display"), "IGraphicsFill").into(),
+        None,
+    ));
+
+    class
+}
@@ -5,6 +5,7 @@ use crate::avm2::class::Class;
 use crate::avm2::method::Method;
 use crate::avm2::names::{Namespace, QName};
 use crate::avm2::object::Object;
+use crate::avm2::traits::Trait;
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use gc_arena::{GcCell, MutationContext};
@@ -62,5 +63,11 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         mc,
     ));
 
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "contextMenu"),
+        QName::new(Namespace::package("flash.ui"), "ContextMenu").into(),
+        None,
+    ));
+
     class
 }
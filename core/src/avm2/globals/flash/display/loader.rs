@@ -0,0 +1,432 @@
+//! `flash.display.Loader` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::net::urlrequest::request_options;
+use crate::avm2::globals::flash::net::urlvariables::object_into_form_values;
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{LoaderInfoObject, LoaderStream, Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::{Avm2, Domain, Error, Event};
+use crate::backend::navigator::OwnedFuture;
+use crate::context::UpdateContext;
+use crate::display_object::{
+    DisplayObject, Lists, MovieClip, TDisplayObject, TDisplayObjectContainer,
+};
+use crate::loader::{Error as LoaderError, ImageDecodingPolicy};
+use crate::tag_utils::{SwfMovie, SwfSlice};
+use gc_arena::{GcCell, MutationContext};
+use std::sync::Arc;
+
+/// Implements `flash.display.Loader`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        if this.as_display_object().is_none() {
+            let class_object = this
+                .as_class_object()
+                .ok_or("Attempted to construct non-instance Loader")?;
+            let movie = Arc::new(SwfMovie::empty(activation.context.swf.version()));
+            let new_do = MovieClip::new_with_avm2(
+                SwfSlice::empty(movie),
+                this,
+                class_object,
+                activation.context.gc_context,
+            );
+
+            this.init_display_object(activation.context.gc_context, new_do.into());
+        }
+
+        let loader_info = LoaderInfoObject::not_yet_loaded(activation)?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "contentLoaderInfo"),
+            loader_info.into(),
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display.Loader`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Get the `contentLoaderInfo` of an object, if it is a `Loader`.
+pub fn content_loader_info<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    loader: Object<'gc>,
+) -> Result<Option<Object<'gc>>, Error> {
+    match loader.get_property(
+        loader,
+        &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "contentLoaderInfo"),
+        activation,
+    )? {
+        Value::Object(loader_info) => Ok(Some(loader_info)),
+        _ => Ok(None),
+    }
+}
+
+/// Get the content a `Loader` has loaded, if any.
+fn loaded_content<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    loader: Object<'gc>,
+) -> Result<Option<DisplayObject<'gc>>, Error> {
+    let loader_info = match content_loader_info(activation, loader)? {
+        Some(loader_info) => loader_info,
+        None => return Ok(None),
+    };
+    let content = match loader_info.as_loader_stream().as_deref() {
+        Some(LoaderStream::Swf(_, content)) => Some(*content),
+        _ => None,
+    };
+
+    Ok(content)
+}
+
+/// Implements `content`'s getter.
+pub fn content<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return Ok(loaded_content(activation, this)?
+            .map(|content| content.object2())
+            .unwrap_or(Value::Null));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `contentLoaderInfo`'s getter.
+pub fn content_loader_info_getter<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return Ok(content_loader_info(activation, this)?
+            .map(Value::from)
+            .unwrap_or(Value::Null));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Determine the application domain that a load with the given
+/// `LoaderContext` defines its classes in.
+///
+/// Without an `applicationDomain` in the context, loaded movies get a child of
+/// the calling code's domain.
+fn load_domain<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    context: Value<'gc>,
+) -> Result<Domain<'gc>, Error> {
+    if let Value::Object(context) = context {
+        let domain = context.get_property(
+            context,
+            &QName::new(Namespace::public(), "applicationDomain"),
+            activation,
+        )?;
+        if let Some(domain) = domain
+            .coerce_to_object(activation)
+            .ok()
+            .and_then(|domain| domain.as_application_domain())
+        {
+            return Ok(domain);
+        }
+    }
+
+    let parent_domain = activation
+        .scope()
+        .map(|s| s.read().globals())
+        .and_then(|g| g.as_application_domain())
+        .unwrap_or_else(|| activation.avm2().global_domain());
+
+    Ok(Domain::movie_domain(activation, parent_domain))
+}
+
+/// Start loading into a `Loader`, replacing any content it already has and
+/// cancelling any load it already has in progress.
+///
+/// Loaded movies get the given `parameters` as flashvars, followed by the
+/// `parameters` of the `LoaderContext`. Loaded images are decoded in the
+/// background if the context's `imageDecodingPolicy` is `onLoad`.
+fn spawn_load<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    loader: Object<'gc>,
+    fetch: OwnedFuture<Vec<u8>, LoaderError>,
+    url: String,
+    mut parameters: Vec<(String, String)>,
+    context: Value<'gc>,
+) -> Result<(), Error> {
+    let loader_info = content_loader_info(activation, loader)?
+        .ok_or("Attempted to load into a Loader without a LoaderInfo")?;
+    let domain = load_domain(activation, context.clone())?;
+
+    let mut image_decoding_policy = ImageDecodingPolicy::OnDemand;
+    if let Value::Object(context) = context {
+        if let Value::Object(context_parameters) = context.get_property(
+            context,
+            &QName::new(Namespace::public(), "parameters"),
+            activation,
+        )? {
+            parameters.extend(object_into_form_values(activation, context_parameters)?);
+        }
+
+        let policy = context
+            .get_property(
+                context,
+                &QName::new(Namespace::public(), "imageDecodingPolicy"),
+                activation,
+            )?
+            .coerce_to_string(activation)?;
+        if let Ok(policy) = policy.as_str().parse() {
+            image_decoding_policy = policy;
+        }
+    }
+
+    activation
+        .context
+        .load_manager
+        .cancel_avm2_loader_loads(loader);
+    unload_content(activation, loader, false)?;
+
+    let loader_url = activation.context.swf.url().map(|url| url.to_string());
+    let process = activation.context.load_manager.load_content_into_loader(
+        activation.context.player.clone().unwrap(),
+        loader,
+        loader_info,
+        domain,
+        fetch,
+        url,
+        loader_url,
+        parameters,
+        image_decoding_policy,
+    );
+    activation.context.navigator.spawn_future(process);
+
+    Ok(())
+}
+
+/// Implements `Loader.load`
+pub fn load<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let request = match args.get(0).cloned().unwrap_or(Value::Undefined) {
+            Value::Object(request) => request,
+            _ => return Err("TypeError: Error #2007: Parameter request must be non-null.".into()),
+        };
+        let context = args.get(1).cloned().unwrap_or(Value::Null);
+
+        let (url, options) = request_options(activation, request)?;
+        let fetch = activation.context.navigator.fetch(&url, options);
+        let url = activation
+            .context
+            .navigator
+            .resolve_relative_url(&url)
+            .into_owned();
+
+        // Movies are given the query string of their URL as flashvars.
+        let parameters = match url::Url::parse(&url) {
+            Ok(url) => url.query_pairs().into_owned().collect(),
+            Err(_) => Vec::new(),
+        };
+
+        spawn_load(activation, this, fetch, url, parameters, context)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Loader.loadBytes`
+pub fn load_bytes<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let bytes = match args.get(0).cloned().unwrap_or(Value::Undefined) {
+            Value::Object(bytes) => bytes,
+            _ => return Err("TypeError: Error #2007: Parameter bytes must be non-null.".into()),
+        };
+        let data = bytes
+            .as_bytearray()
+            .map(|bytes| bytes.bytes().to_vec())
+            .ok_or("TypeError: Parameter bytes must be a ByteArray")?;
+        let context = args.get(1).cloned().unwrap_or(Value::Null);
+
+        // Content loaded from bytes reports the URL of the movie that loaded
+        // it.
+        let url = activation.context.swf.url().unwrap_or("").to_string();
+        let fetch: OwnedFuture<Vec<u8>, LoaderError> = Box::pin(async move { Ok(data) });
+
+        spawn_load(activation, this, fetch, url, Vec::new(), context)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Stop a display object and all of its children, along with any sounds they
+/// are playing.
+fn stop_content<'gc>(context: &mut UpdateContext<'_, 'gc, '_>, dobj: DisplayObject<'gc>) {
+    if let Some(mc) = dobj.as_movie_clip() {
+        mc.stop(context);
+    }
+    context.stop_sounds_with_display_object(dobj);
+
+    if let Some(ctr) = dobj.as_container() {
+        for child in ctr.iter_render_list() {
+            stop_content(context, child);
+        }
+    }
+}
+
+/// Remove a `Loader`'s content, firing `unload` on its `contentLoaderInfo` if
+/// there was any.
+///
+/// If `stop` is set, the content's timelines and sounds are stopped as well.
+fn unload_content<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    loader: Object<'gc>,
+    stop: bool,
+) -> Result<(), Error> {
+    let content = match loaded_content(activation, loader)? {
+        Some(content) => content,
+        None => return Ok(()),
+    };
+
+    if stop {
+        stop_content(&mut activation.context, content);
+    }
+
+    if let Some(mut ctr) = loader
+        .as_display_object()
+        .and_then(|dobj| dobj.as_container())
+    {
+        ctr.remove_child(&mut activation.context, content, Lists::all());
+    }
+
+    if let Some(loader_info) = content_loader_info(activation, loader)? {
+        loader_info.set_loader_stream(activation.context.gc_context, None);
+
+        Avm2::dispatch_event(&mut activation.context, Event::new("unload"), loader_info)?;
+    }
+
+    Ok(())
+}
+
+/// Implements `Loader.unload`
+pub fn unload<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        unload_content(activation, this, false)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Loader.unloadAndStop`
+///
+/// Ruffle's garbage collector doesn't need to be asked to run, so the `gc`
+/// parameter is ignored.
+pub fn unload_and_stop<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation
+            .context
+            .load_manager
+            .cancel_avm2_loader_loads(this);
+        unload_content(activation, this, true)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Loader.close`
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation
+            .context
+            .load_manager
+            .cancel_avm2_loader_loads(this);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `Loader`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.display"), "Loader"),
+        Some(
+            QName::new(
+                Namespace::package("flash.display"),
+                "DisplayObjectContainer",
+            )
+            .into(),
+        ),
+        Method::from_builtin(instance_init, "<Loader instance initializer>", mc),
+        Method::from_builtin(class_init, "<Loader class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("content", Some(content), None),
+        ("contentLoaderInfo", Some(content_loader_info_getter), None),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("load", load),
+        ("loadBytes", load_bytes),
+        ("unload", unload),
+        ("unloadAndStop", unload_and_stop),
+        ("close", close),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "contentLoaderInfo"),
+        QName::new(Namespace::package("flash.display"), "LoaderInfo").into(),
+        None,
+    ));
+
+    class
+}
@@ -3,11 +3,14 @@
 use crate::avm2::activation::Activation;
 use crate::avm2::bytearray::Endian;
 use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::display::loader::content_loader_info;
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::names::{Namespace, QName};
 use crate::avm2::object::{loaderinfo_allocator, DomainObject, LoaderStream, Object, TObject};
+use crate::avm2::traits::Trait;
 use crate::avm2::value::Value;
-use crate::avm2::{AvmString, Error};
+use crate::avm2::{Avm2, AvmString, Error};
 use crate::display_object::TDisplayObject;
 use gc_arena::{GcCell, MutationContext};
 use swf::{write_swf, Compression};
@@ -100,10 +103,19 @@ pub fn application_domain<'gc>(
     Ok(Value::Undefined)
 }
 
-/// `bytesTotal` getter
+/// `bytesLoaded` getter
 ///
-/// TODO: This is also the getter for `bytesLoaded` as we don't yet support
-/// streaming loads yet. When we do, we'll need another property for this.
+/// Loads only reach a `LoaderInfo` once they have been fetched in full, so
+/// this is always the same as `bytesTotal`.
+pub fn bytes_loaded<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    bytes_total(activation, this, args)
+}
+
+/// `bytesTotal` getter
 pub fn bytes_total<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
@@ -123,6 +135,25 @@ pub fn bytes_total<'gc>(
     Ok(Value::Undefined)
 }
 
+/// `uncaughtErrorEvents` getter
+pub fn uncaught_error_events<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let movie = match this.as_loader_stream().as_deref() {
+            Some(LoaderStream::Stage) => activation.context.swf.clone(),
+            Some(LoaderStream::Swf(movie, _)) => movie.clone(),
+            None => return Ok(Value::Undefined),
+        };
+
+        return Ok(Avm2::uncaught_error_events(&mut activation.context, movie)?.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// `content` getter
 pub fn content<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
@@ -133,7 +164,20 @@ pub fn content<'gc>(
         if let Some(loader_stream) = this.as_loader_stream() {
             match &*loader_stream {
                 LoaderStream::Stage => return Ok(activation.context.stage.root_clip().object2()),
-                LoaderStream::Swf(_, root) => {
+                LoaderStream::Swf(movie, root) => {
+                    if !activation
+                        .context
+                        .security
+                        .allows_scripting(movie.loader_url(), movie.url())
+                    {
+                        return Err(format!(
+                            "SecurityError: Error #2121: Security sandbox violation: LoaderInfo.content: {} cannot access {}. This may be worked around by calling Security.allowDomain.",
+                            movie.loader_url().unwrap_or(""),
+                            movie.url().unwrap_or("")
+                        )
+                        .into());
+                    }
+
                     return Ok(root.object2());
                 }
             }
@@ -143,6 +187,54 @@ pub fn content<'gc>(
     Ok(Value::Undefined)
 }
 
+/// `childAllowsParent` getter
+pub fn child_allows_parent<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(loader_stream) = this.as_loader_stream() {
+            match &*loader_stream {
+                LoaderStream::Stage => return Ok(true.into()),
+                LoaderStream::Swf(movie, _) => {
+                    return Ok(activation
+                        .context
+                        .security
+                        .allows_scripting(movie.loader_url(), movie.url())
+                        .into());
+                }
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// `parentAllowsChild` getter
+pub fn parent_allows_child<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(loader_stream) = this.as_loader_stream() {
+            match &*loader_stream {
+                LoaderStream::Stage => return Ok(true.into()),
+                LoaderStream::Swf(movie, _) => {
+                    return Ok(activation
+                        .context
+                        .security
+                        .allows_scripting(movie.url(), movie.loader_url())
+                        .into());
+                }
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// `contentType` getter
 pub fn content_type<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
@@ -336,7 +428,7 @@ pub fn bytes<'gc>(
     Ok(Value::Undefined)
 }
 
-/// `loaderUrl` getter
+/// `loaderURL` getter
 pub fn loader_url<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
@@ -403,6 +495,73 @@ pub fn parameters<'gc>(
     Ok(Value::Undefined)
 }
 
+/// `loader` getter
+///
+/// The stage and the root movie weren't loaded by a `Loader`, so this is
+/// `null` for them.
+pub fn loader<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let root = match this.as_loader_stream().as_deref() {
+            Some(LoaderStream::Swf(_, root)) => *root,
+            Some(LoaderStream::Stage) => return Ok(Value::Null),
+            None => return Ok(Value::Undefined),
+        };
+
+        if let Some(Value::Object(parent)) = root.parent().map(|parent| parent.object2()) {
+            if content_loader_info(activation, parent)?
+                .map(|loader_info| Object::ptr_eq(loader_info, this))
+                .unwrap_or(false)
+            {
+                return Ok(parent.into());
+            }
+        }
+
+        return Ok(Value::Null);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// `sharedEvents` getter
+///
+/// A `Loader`'s content shares its `LoaderInfo` with the `Loader`, so both
+/// sides of the load see the same dispatcher.
+pub fn shared_events<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        // Lazily initialize the dispatcher in a hidden property.
+        let shared_events = match this.get_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "sharedEvents"),
+            activation,
+        )? {
+            Value::Undefined | Value::Null => {
+                let dispatcher_class = activation.avm2().classes().eventdispatcher;
+                let shared_events: Value<'gc> = dispatcher_class.construct(activation, &[])?.into();
+                this.set_property(
+                    this,
+                    &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "sharedEvents"),
+                    shared_events.clone(),
+                    activation,
+                )?;
+                shared_events
+            }
+            shared_events => shared_events,
+        };
+
+        return Ok(shared_events);
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Construct `LoaderInfo`'s class.
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
@@ -430,21 +589,32 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
     )] = &[
         ("actionScriptVersion", Some(action_script_version), None),
         ("applicationDomain", Some(application_domain), None),
-        ("bytesLoaded", Some(bytes_total), None),
+        ("bytesLoaded", Some(bytes_loaded), None),
         ("bytesTotal", Some(bytes_total), None),
+        ("childAllowsParent", Some(child_allows_parent), None),
         ("content", Some(content), None),
         ("contentType", Some(content_type), None),
         ("frameRate", Some(frame_rate), None),
         ("height", Some(height), None),
         ("isURLInaccessible", Some(is_url_inaccessible), None),
+        ("parentAllowsChild", Some(parent_allows_child), None),
         ("swfVersion", Some(swf_version), None),
         ("url", Some(url), None),
         ("width", Some(width), None),
         ("bytes", Some(bytes), None),
-        ("loaderUrl", Some(loader_url), None),
+        ("loaderURL", Some(loader_url), None),
         ("parameters", Some(parameters), None),
+        ("uncaughtErrorEvents", Some(uncaught_error_events), None),
+        ("loader", Some(loader), None),
+        ("sharedEvents", Some(shared_events), None),
     ];
     write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
 
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "sharedEvents"),
+        QName::new(Namespace::package("flash.events"), "EventDispatcher").into(),
+        None,
+    ));
+
     class
 }
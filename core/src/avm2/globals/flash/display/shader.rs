@@ -0,0 +1,382 @@
+//! `flash.display.Shader` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::array::ArrayStorage;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{ArrayObject, Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::pixel_bender::{
+    run_shader, PixelBenderQualifier, PixelBenderShader, PixelBenderType, PixelBenderValue,
+    ShaderImage,
+};
+use crate::string::AvmString;
+use gc_arena::{GcCell, MutationContext};
+use std::sync::Arc;
+
+/// Implements `flash.display.Shader`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+
+        if let Some(Value::Object(_)) = args.get(0) {
+            set_byte_code(activation, Some(this), args)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display.Shader`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Look up one of the `flash.display` classes by name.
+pub fn display_class<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    name: &'static str,
+) -> Result<Object<'gc>, Error> {
+    activation
+        .avm2()
+        .global_domain()
+        .get_defined_value(
+            activation,
+            QName::new(Namespace::package("flash.display"), name),
+        )?
+        .coerce_to_object(activation)
+}
+
+/// Implements `Shader.byteCode`'s setter.
+///
+/// The bytecode is copied, and parsed into a new `ShaderData` for `data`.
+pub fn set_byte_code<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let bytes = match args.get(0) {
+            Some(Value::Object(code)) => code
+                .as_bytearray()
+                .map(|code| code.bytes().clone())
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        let byte_code = new_byte_array(activation, &bytes)?;
+        let shader_data_class = display_class(activation, "ShaderData")?;
+        let data = shader_data_class.construct(activation, &[byte_code.into()])?;
+
+        this.set_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "byteCode"),
+            byte_code.into(),
+            activation,
+        )?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "data"),
+            data.into(),
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Create a `ByteArray` holding the given bytes.
+fn new_byte_array<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    bytes: &[u8],
+) -> Result<Object<'gc>, Error> {
+    let bytearray_class = activation.avm2().classes().bytearray;
+    let byte_array = bytearray_class.construct(activation, &[])?;
+    if let Some(mut storage) = byte_array.as_bytearray_mut(activation.context.gc_context) {
+        storage.write_bytes(bytes)?;
+        storage.set_position(0);
+    }
+    Ok(byte_array)
+}
+
+/// Convert a shader parameter's value, or a piece of metadata, to an
+/// `Array` of its components.
+pub fn value_to_array<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: &PixelBenderValue,
+    value_type: PixelBenderType,
+) -> Result<Value<'gc>, Error> {
+    let is_bool = matches!(
+        value_type,
+        PixelBenderType::Bool
+            | PixelBenderType::Bool2
+            | PixelBenderType::Bool3
+            | PixelBenderType::Bool4
+    );
+    let values: Vec<Option<Value<'gc>>> = match value {
+        PixelBenderValue::Float(values) => {
+            values.iter().map(|v| Some(f64::from(*v).into())).collect()
+        }
+        PixelBenderValue::Int(values) if is_bool => {
+            values.iter().map(|v| Some((*v != 0).into())).collect()
+        }
+        PixelBenderValue::Int(values) => values.iter().map(|v| Some((*v).into())).collect(),
+        PixelBenderValue::String(string) => vec![Some(
+            AvmString::new(activation.context.gc_context, string.clone()).into(),
+        )],
+    };
+    Ok(ArrayObject::from_storage(activation, ArrayStorage::from_storage(values))?.into())
+}
+
+/// Read a public property of an object.
+fn property<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+    name: &str,
+) -> Result<Value<'gc>, Error> {
+    object.get_property(
+        object,
+        &QName::new(
+            Namespace::public(),
+            AvmString::new(activation.context.gc_context, name),
+        ),
+        activation,
+    )
+}
+
+/// A shader object's parsed bytecode, with the current values of its
+/// parameters and the images given to its inputs.
+pub struct ShaderState {
+    pub shader: Arc<PixelBenderShader>,
+    pub bytecode: Arc<[u8]>,
+    /// A value for each of the shader's parameters.
+    pub params: Vec<PixelBenderValue>,
+    /// An image for each of the shader's inputs.
+    pub inputs: Vec<Option<ShaderImage>>,
+}
+
+/// Read the state of a `Shader` object, or `None` if it has no valid
+/// bytecode.
+pub fn shader_state<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<Option<ShaderState>, Error> {
+    let byte_code = this.get_property(
+        this,
+        &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "byteCode"),
+        activation,
+    )?;
+    let bytecode: Arc<[u8]> = match byte_code {
+        Value::Object(byte_code) => match byte_code.as_bytearray() {
+            Some(byte_code) => byte_code.bytes().as_slice().into(),
+            None => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+    let shader = match PixelBenderShader::parse(&bytecode) {
+        Ok(shader) => Arc::new(shader),
+        Err(_) => return Ok(None),
+    };
+    let data = match property(activation, this, "data")? {
+        Value::Object(data) => data,
+        _ => return Ok(None),
+    };
+
+    let mut params = Vec::with_capacity(shader.params.len());
+    for param in &shader.params {
+        let default = param.default_value();
+        if param.qualifier == PixelBenderQualifier::Out || param.is_out_coord() {
+            params.push(default);
+            continue;
+        }
+
+        let value = match property(activation, data, &param.name)? {
+            Value::Object(parameter) => property(activation, parameter, "value")?,
+            _ => Value::Null,
+        };
+        let items: Vec<Value<'gc>> = match value {
+            Value::Object(array) => array
+                .as_array_storage()
+                .map(|array| {
+                    array
+                        .iter()
+                        .map(|value| value.unwrap_or(Value::Undefined))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        if items.is_empty() {
+            params.push(default);
+            continue;
+        }
+
+        let mut values = Vec::with_capacity(items.len());
+        for item in items.iter().take(param.param_type.components()) {
+            values.push(item.coerce_to_number(activation)?);
+        }
+        params.push(if param.param_type.is_float() {
+            PixelBenderValue::Float(values.into_iter().map(|v| v as f32).collect())
+        } else {
+            PixelBenderValue::Int(values.into_iter().map(|v| v as i32).collect())
+        });
+    }
+
+    let mut inputs = Vec::with_capacity(shader.inputs.len());
+    for input in &shader.inputs {
+        let channels = usize::from(input.channels);
+        let shader_input = match property(activation, data, &input.name)? {
+            Value::Object(shader_input) => shader_input,
+            _ => {
+                inputs.push(None);
+                continue;
+            }
+        };
+        let width = property(activation, shader_input, "width")?.coerce_to_u32(activation)?;
+        let height = property(activation, shader_input, "height")?.coerce_to_u32(activation)?;
+        let image = match property(activation, shader_input, "input")? {
+            Value::Object(object) => {
+                if let Some(bitmap_data) = object.as_bitmap_data() {
+                    let bitmap_data = bitmap_data.read();
+                    Some(
+                        bitmap_data
+                            .filter_image(0, 0, bitmap_data.width(), bitmap_data.height())
+                            .to_shader_image(channels),
+                    )
+                } else if let Some(bytes) = object.as_bytearray() {
+                    let mut image = ShaderImage::new(width, height, channels);
+                    let position = bytes.position();
+                    bytes.set_position(0);
+                    for value in image.data.iter_mut() {
+                        *value = bytes.read_float().unwrap_or(0.0);
+                    }
+                    bytes.set_position(position);
+                    Some(image)
+                } else if let Some(vector) = object.as_vector_storage() {
+                    let items: Vec<Value<'gc>> = vector.iter().collect();
+                    drop(vector);
+                    let mut image = ShaderImage::new(width, height, channels);
+                    for (value, item) in image.data.iter_mut().zip(items) {
+                        *value = item.coerce_to_number(activation)? as f32;
+                    }
+                    Some(image)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+        inputs.push(image);
+    }
+
+    Ok(Some(ShaderState {
+        shader,
+        bytecode,
+        params,
+        inputs,
+    }))
+}
+
+/// Run a `Shader` object over a `width` by `height` image.
+///
+/// Returns `None` if the shader has no valid bytecode or can't be run.
+pub fn run_shader_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    width: u32,
+    height: u32,
+) -> Result<Option<ShaderImage>, Error> {
+    let state = match shader_state(activation, this)? {
+        Some(state) => state,
+        None => return Ok(None),
+    };
+    let inputs: Vec<_> = state.inputs.iter().map(Option::as_ref).collect();
+    match run_shader(&state.shader, &state.params, &inputs, width, height) {
+        Ok(image) => Ok(Some(image)),
+        Err(e) => {
+            log::warn!("Couldn't run shader: {}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// Create a `Shader` object from bytecode, with its parameters set to the
+/// given values.
+pub fn shader_to_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    bytecode: &[u8],
+    params: &[PixelBenderValue],
+) -> Result<Object<'gc>, Error> {
+    let byte_code = new_byte_array(activation, bytecode)?;
+    let shader_class = display_class(activation, "Shader")?;
+    let this = shader_class.construct(activation, &[byte_code.into()])?;
+
+    if let Ok(shader) = PixelBenderShader::parse(bytecode) {
+        if let Value::Object(data) = property(activation, this, "data")? {
+            for (param, value) in shader.params.iter().zip(params) {
+                if let Value::Object(mut parameter) = property(activation, data, &param.name)? {
+                    let value = value_to_array(activation, value, param.param_type)?;
+                    parameter.set_property(
+                        parameter,
+                        &QName::new(Namespace::public(), "value"),
+                        value,
+                        activation,
+                    )?;
+                }
+            }
+        }
+    }
+
+    Ok(this)
+}
+
+/// Construct `Shader`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.display"), "Shader"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<Shader instance initializer>", mc),
+        Method::from_builtin(class_init, "<Shader class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[("byteCode", None, Some(set_byte_code))];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "data"),
+        QName::new(Namespace::package("flash.display"), "ShaderData").into(),
+        Some(Value::Null),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "precisionHint"),
+        QName::new(Namespace::public(), "String").into(),
+        Some("full".into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "byteCode"),
+        QName::new(Namespace::package("flash.utils"), "ByteArray").into(),
+        Some(Value::Null),
+    ));
+
+    class
+}
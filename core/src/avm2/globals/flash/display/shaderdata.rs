@@ -0,0 +1,138 @@
+//! `flash.display.ShaderData` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::display::shader::{display_class, value_to_array};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::pixel_bender::{
+    PixelBenderQualifier, PixelBenderShader, PixelBenderType, PixelBenderValue,
+};
+use crate::string::AvmString;
+use gc_arena::{GcCell, MutationContext};
+
+/// Set a dynamic public property of an object.
+fn set_dynamic<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    mut object: Object<'gc>,
+    name: &str,
+    value: Value<'gc>,
+) -> Result<(), Error> {
+    let name = AvmString::new(activation.context.gc_context, name);
+    object.set_property(
+        object,
+        &QName::new(Namespace::public(), name),
+        value,
+        activation,
+    )
+}
+
+/// Implements `flash.display.ShaderData`'s instance constructor.
+///
+/// The shader's metadata becomes properties of the object, along with a
+/// `ShaderParameter` for each parameter and a `ShaderInput` for each input.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+
+        let bytes = match args.get(0) {
+            Some(Value::Object(code)) => code
+                .as_bytearray()
+                .map(|code| code.bytes().clone())
+                .unwrap_or_default(),
+            _ => return Ok(Value::Undefined),
+        };
+        let shader = match PixelBenderShader::parse(&bytes) {
+            Ok(shader) => shader,
+            Err(e) => {
+                log::warn!("Couldn't parse shader: {}", e);
+                return Err("ArgumentError: Error #2004: One of the parameters is invalid.".into());
+            }
+        };
+
+        let name = AvmString::new(activation.context.gc_context, shader.name.clone());
+        set_dynamic(activation, this, "name", name.into())?;
+        for metadata in &shader.metadata {
+            let value = match &metadata.value {
+                PixelBenderValue::String(string) => {
+                    AvmString::new(activation.context.gc_context, string.clone()).into()
+                }
+                PixelBenderValue::Float(values) if values.len() == 1 => f64::from(values[0]).into(),
+                PixelBenderValue::Int(values) if values.len() == 1 => values[0].into(),
+                value => value_to_array(activation, value, PixelBenderType::Float)?,
+            };
+            set_dynamic(activation, this, &metadata.key, value)?;
+        }
+
+        let shader_parameter_class = display_class(activation, "ShaderParameter")?;
+        for (index, param) in shader.params.iter().enumerate() {
+            if param.qualifier == PixelBenderQualifier::Out || param.is_out_coord() {
+                continue;
+            }
+
+            let parameter = shader_parameter_class.construct(activation, &[])?;
+            let value = value_to_array(activation, &param.default_value(), param.param_type)?;
+            set_dynamic(activation, parameter, "value", value)?;
+            set_dynamic(
+                activation,
+                parameter,
+                "type",
+                param.param_type.name().into(),
+            )?;
+            set_dynamic(activation, parameter, "index", (index as i32).into())?;
+            for metadata in &param.metadata {
+                let value = match &metadata.value {
+                    PixelBenderValue::String(string) => {
+                        AvmString::new(activation.context.gc_context, string.clone()).into()
+                    }
+                    value => value_to_array(activation, value, param.param_type)?,
+                };
+                set_dynamic(activation, parameter, &metadata.key, value)?;
+            }
+            set_dynamic(activation, this, &param.name, parameter.into())?;
+        }
+
+        let shader_input_class = display_class(activation, "ShaderInput")?;
+        for input in &shader.inputs {
+            let shader_input = shader_input_class.construct(activation, &[])?;
+            set_dynamic(activation, shader_input, "index", input.index.into())?;
+            set_dynamic(activation, shader_input, "channels", input.channels.into())?;
+            set_dynamic(activation, this, &input.name, shader_input.into())?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display.ShaderData`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `ShaderData`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.display"), "ShaderData"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<ShaderData instance initializer>", mc),
+        Method::from_builtin(class_init, "<ShaderData class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::FINAL);
+
+    class
+}
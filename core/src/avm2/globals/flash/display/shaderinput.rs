@@ -0,0 +1,76 @@
+//! `flash.display.ShaderInput` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::Object;
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.display.ShaderInput`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display.ShaderInput`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `ShaderInput`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.display"), "ShaderInput"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<ShaderInput instance initializer>", mc),
+        Method::from_builtin(class_init, "<ShaderInput class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::FINAL);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "input"),
+        QName::new(Namespace::public(), "Object").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "width"),
+        QName::new(Namespace::public(), "int").into(),
+        Some(0.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "height"),
+        QName::new(Namespace::public(), "int").into(),
+        Some(0.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "channels"),
+        QName::new(Namespace::public(), "int").into(),
+        Some(0.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "index"),
+        QName::new(Namespace::public(), "int").into(),
+        Some(0.into()),
+    ));
+
+    class
+}
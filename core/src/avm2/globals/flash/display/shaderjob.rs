@@ -0,0 +1,254 @@
+//! `flash.display.ShaderJob` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::events::dispatch_event;
+use crate::avm2::globals::flash::display::shader::run_shader_object;
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::bitmap::filter::FilterImage;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.display.ShaderJob`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let shader = args.get(0).cloned().unwrap_or(Value::Null);
+        let target = args.get(1).cloned().unwrap_or(Value::Null);
+        let width = args
+            .get(2)
+            .cloned()
+            .unwrap_or_else(|| 0.into())
+            .coerce_to_i32(activation)?;
+        let height = args
+            .get(3)
+            .cloned()
+            .unwrap_or_else(|| 0.into())
+            .coerce_to_i32(activation)?;
+
+        let properties: [(&str, Value<'gc>); 4] = [
+            ("shader", shader),
+            ("target", target),
+            ("width", width.into()),
+            ("height", height.into()),
+        ];
+        for (name, value) in properties.iter() {
+            this.set_property(
+                this,
+                &QName::new(Namespace::public(), *name),
+                value.clone(),
+                activation,
+            )?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display.ShaderJob`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `ShaderJob.start`.
+///
+/// Jobs are always run to completion before this returns. When not asked to
+/// wait, the `complete` event is still dispatched, from within this call.
+pub fn start<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let wait_for_completion = args
+            .get(0)
+            .cloned()
+            .unwrap_or_else(|| false.into())
+            .coerce_to_boolean();
+        let shader = match this.get_property(
+            this,
+            &QName::new(Namespace::public(), "shader"),
+            activation,
+        )? {
+            Value::Object(shader) => shader,
+            _ => {
+                return Err("ArgumentError: Error #2007: Parameter shader must be non-null.".into())
+            }
+        };
+        let target = match this.get_property(
+            this,
+            &QName::new(Namespace::public(), "target"),
+            activation,
+        )? {
+            Value::Object(target) => target,
+            _ => {
+                return Err("ArgumentError: Error #2007: Parameter target must be non-null.".into())
+            }
+        };
+
+        let mut width = this
+            .get_property(this, &QName::new(Namespace::public(), "width"), activation)?
+            .coerce_to_u32(activation)?;
+        let mut height = this
+            .get_property(this, &QName::new(Namespace::public(), "height"), activation)?
+            .coerce_to_u32(activation)?;
+        if let Some(bitmap_data) = target.as_bitmap_data() {
+            let bitmap_data = bitmap_data.read();
+            if width == 0 || height == 0 {
+                width = bitmap_data.width();
+                height = bitmap_data.height();
+            }
+        }
+
+        let image = match run_shader_object(activation, shader, width, height)? {
+            Some(image) => image,
+            None => return Ok(Value::Undefined),
+        };
+
+        let mut event_args = [Value::Null, Value::Null, Value::Null];
+        if let Some(bitmap_data) = target.as_bitmap_data() {
+            bitmap_data
+                .write(activation.context.gc_context)
+                .set_filter_image(&FilterImage::from_shader_image(&image), 0, 0);
+            event_args[0] = target.into();
+        } else if let Some(mut bytes) = target.as_bytearray_mut(activation.context.gc_context) {
+            bytes.set_position(0);
+            for value in &image.data {
+                bytes.write_float(*value)?;
+            }
+            bytes.set_position(0);
+            event_args[1] = target.into();
+        } else if let Some(mut vector) = target.as_vector_storage_mut(activation.context.gc_context)
+        {
+            vector.replace_storage(
+                image
+                    .data
+                    .iter()
+                    .map(|value| f64::from(*value).into())
+                    .collect(),
+            );
+            event_args[2] = target.into();
+        }
+
+        this.set_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "progress"),
+            1.0.into(),
+            activation,
+        )?;
+
+        if !wait_for_completion {
+            let shader_event_class = activation.avm2().classes().shaderevent;
+            let event = shader_event_class.construct(
+                activation,
+                &[
+                    "complete".into(),
+                    false.into(),
+                    false.into(),
+                    event_args[0].clone(),
+                    event_args[1].clone(),
+                    event_args[2].clone(),
+                ],
+            )?;
+            dispatch_event(activation, this, event)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ShaderJob.cancel`.
+///
+/// Jobs finish as soon as they're started, so there's never one to cancel.
+pub fn cancel<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `ShaderJob.progress`'s getter.
+pub fn progress<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "progress"),
+            activation,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `ShaderJob`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.display"), "ShaderJob"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<ShaderJob instance initializer>", mc),
+        Method::from_builtin(class_init, "<ShaderJob class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] =
+        &[("start", start), ("cancel", cancel)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[("progress", Some(progress), None)];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "shader"),
+        QName::new(Namespace::package("flash.display"), "Shader").into(),
+        Some(Value::Null),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "target"),
+        QName::new(Namespace::public(), "Object").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "width"),
+        QName::new(Namespace::public(), "int").into(),
+        Some(0.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "height"),
+        QName::new(Namespace::public(), "int").into(),
+        Some(0.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "progress"),
+        QName::new(Namespace::public(), "Number").into(),
+        Some(0.0.into()),
+    ));
+
+    class
+}
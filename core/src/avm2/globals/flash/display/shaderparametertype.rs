@@ -0,0 +1,73 @@
+//! `flash.display.ShaderParameterType` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::Object;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.display.ShaderParameterType`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display.ShaderParameterType`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `ShaderParameterType`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.display"), "ShaderParameterType"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(
+            instance_init,
+            "<ShaderParameterType instance initializer>",
+            mc,
+        ),
+        Method::from_builtin(class_init, "<ShaderParameterType class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+
+    const CONSTANTS: &[(&str, &str)] = &[
+        ("BOOL", "bool"),
+        ("BOOL2", "bool2"),
+        ("BOOL3", "bool3"),
+        ("BOOL4", "bool4"),
+        ("FLOAT", "float"),
+        ("FLOAT2", "float2"),
+        ("FLOAT3", "float3"),
+        ("FLOAT4", "float4"),
+        ("INT", "int"),
+        ("INT2", "int2"),
+        ("INT3", "int3"),
+        ("INT4", "int4"),
+        ("MATRIX2X2", "matrix2x2"),
+        ("MATRIX3X3", "matrix3x3"),
+        ("MATRIX4X4", "matrix4x4"),
+    ];
+
+    write.define_public_constant_string_class_traits(CONSTANTS);
+
+    class
+}
@@ -2,16 +2,20 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::display::displayobject::{
+    bounding_box_to_rectangle, rectangle_to_bounding_box,
+};
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::names::{Namespace, QName};
-use crate::avm2::object::{Object, TObject};
+use crate::avm2::object::{Object, Stage3DObject, TObject, VectorObject};
 use crate::avm2::traits::Trait;
 use crate::avm2::value::Value;
+use crate::avm2::vector::VectorStorage;
 use crate::avm2::Error;
 use crate::display_object::TDisplayObject;
 use crate::string::AvmString;
 use gc_arena::{GcCell, MutationContext};
-use swf::Color;
+use swf::{Color, Twips};
 
 /// Implements `flash.display.Stage`'s instance constructor.
 pub fn instance_init<'gc>(
@@ -388,13 +392,67 @@ pub fn display_state<'gc>(
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    if activation.context.ui.is_fullscreen() {
-        Ok("fullScreenInteractive".into())
-    } else {
-        Ok("normal".into())
+    let display_state = AvmString::new(
+        activation.context.gc_context,
+        activation.context.stage.display_state().to_string(),
+    );
+    Ok(display_state.into())
+}
+
+/// Implement `displayState`'s setter
+pub fn set_display_state<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let display_state = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    match display_state.parse() {
+        Ok(display_state) => {
+            activation
+                .context
+                .stage
+                .set_display_state(&mut activation.context, display_state);
+            Ok(Value::Undefined)
+        }
+        Err(_) => Err("ArgumentError: Error #2008: Parameter displayState must be one of the accepted values.".into()),
     }
 }
 
+/// Implement `fullScreenSourceRect`'s getter
+pub fn full_screen_source_rect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    match activation.context.stage.full_screen_source_rect() {
+        Some(rect) => bounding_box_to_rectangle(activation, &rect),
+        None => Ok(Value::Null),
+    }
+}
+
+/// Implement `fullScreenSourceRect`'s setter
+pub fn set_full_screen_source_rect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let rect = match args.get(0) {
+        Some(Value::Object(rectangle)) => Some(rectangle_to_bounding_box(activation, *rectangle)?),
+        _ => None,
+    };
+    // An empty rectangle turns hardware scaling off.
+    let rect = rect.filter(|rect| rect.width() > Twips::ZERO && rect.height() > Twips::ZERO);
+    activation
+        .context
+        .stage
+        .set_full_screen_source_rect(&mut activation.context, rect);
+    Ok(Value::Undefined)
+}
+
 /// Implement `focus`'s getter
 pub fn focus<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
@@ -532,12 +590,27 @@ pub fn stage_width<'gc>(
 }
 
 /// Implement `stageWidth`'s setter
+///
+/// This only has an effect in `NO_SCALE` mode.
 pub fn set_stage_width<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    // For some reason this value is settable but it does nothing.
+    if let Some(stage) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_stage())
+    {
+        let width = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?
+            .max(0) as u32;
+        let (_, height) = stage.stage_size();
+        stage.set_stage_size(&mut activation.context, width, height);
+    }
+
     Ok(Value::Undefined)
 }
 
@@ -558,12 +631,27 @@ pub fn stage_height<'gc>(
 }
 
 /// Implement `stageHeight`'s setter
+///
+/// This only has an effect in `NO_SCALE` mode.
 pub fn set_stage_height<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    // For some reason this value is settable but it does nothing.
+    if let Some(stage) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_stage())
+    {
+        let height = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?
+            .max(0) as u32;
+        let (width, _) = stage.stage_size();
+        stage.set_stage_size(&mut activation.context, width, height);
+    }
+
     Ok(Value::Undefined)
 }
 
@@ -586,7 +674,7 @@ pub fn allows_full_screen_interactive<'gc>(
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Ok(false.into())
+    Ok(true.into())
 }
 
 /// Implement `quality`'s getter
@@ -620,6 +708,30 @@ pub fn set_quality<'gc>(
     Ok(Value::Undefined)
 }
 
+/// The number of `Stage3D`s a stage has.
+const NUM_STAGE3DS: usize = 4;
+
+/// Implement `stage3Ds`'s getter
+pub fn stage3ds<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let stage = activation.context.stage;
+    let mut stage3ds = stage.stage3ds();
+    if stage3ds.is_empty() {
+        for _ in 0..NUM_STAGE3DS {
+            stage3ds.push(Stage3DObject::construct(activation)?);
+        }
+        stage.set_stage3ds(activation.context.gc_context, stage3ds.clone());
+    }
+
+    let values = stage3ds.into_iter().map(Value::Object).collect();
+    let storage = VectorStorage::from_values(values, true, activation.avm2().classes().stage3d);
+
+    Ok(VectorObject::from_vector(storage, activation)?.into())
+}
+
 /// Construct `Stage`'s class.
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
@@ -708,7 +820,7 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("browserZoomFactor", Some(browser_zoom_factor), None),
         ("color", Some(color), Some(set_color)),
         ("contentsScaleFactor", Some(contents_scale_factor), None),
-        ("displayState", Some(display_state), None),
+        ("displayState", Some(display_state), Some(set_display_state)),
         ("focus", Some(focus), Some(set_focus)),
         ("frameRate", Some(frame_rate), Some(set_frame_rate)),
         ("scaleMode", Some(scale_mode), Some(set_scale_mode)),
@@ -717,6 +829,11 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
             Some(show_default_context_menu),
             Some(set_show_default_context_menu),
         ),
+        (
+            "fullScreenSourceRect",
+            Some(full_screen_source_rect),
+            Some(set_full_screen_source_rect),
+        ),
         ("stageWidth", Some(stage_width), Some(set_stage_width)),
         ("stageHeight", Some(stage_height), Some(set_stage_height)),
         ("allowsFullScreen", Some(allows_full_screen), None),
@@ -726,6 +843,7 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
             None,
         ),
         ("quality", Some(quality), Some(set_quality)),
+        ("stage3Ds", Some(stage3ds), None),
     ];
     write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
 
@@ -0,0 +1,272 @@
+//! `flash.display.Stage3D` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::events::dispatch_event;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{stage3d_allocator, Context3DObject, Object, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::string::AvmString;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.display.Stage3D`'s instance constructor.
+pub fn instance_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Err("You cannot construct new instances of the Stage3D.".into())
+}
+
+/// Implements `flash.display.Stage3D`'s native instance constructor.
+pub fn native_instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display.Stage3D`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `Stage3D.x`'s getter.
+pub fn x<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(stage3d) = this.and_then(|this| this.as_stage3d()) {
+        return Ok(stage3d.x().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Stage3D.x`'s setter.
+pub fn set_x<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(stage3d) = this.and_then(|this| this.as_stage3d()) {
+        let x = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_number(activation)?;
+        stage3d.set_x(activation.context.gc_context, x);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Stage3D.y`'s getter.
+pub fn y<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(stage3d) = this.and_then(|this| this.as_stage3d()) {
+        return Ok(stage3d.y().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Stage3D.y`'s setter.
+pub fn set_y<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(stage3d) = this.and_then(|this| this.as_stage3d()) {
+        let y = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_number(activation)?;
+        stage3d.set_y(activation.context.gc_context, y);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Stage3D.visible`'s getter.
+pub fn visible<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(stage3d) = this.and_then(|this| this.as_stage3d()) {
+        return Ok(stage3d.visible().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Stage3D.visible`'s setter.
+pub fn set_visible<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(stage3d) = this.and_then(|this| this.as_stage3d()) {
+        let visible = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_boolean();
+        stage3d.set_visible(activation.context.gc_context, visible);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Stage3D.context3D`'s getter.
+pub fn context3d<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(stage3d) = this.and_then(|this| this.as_stage3d()) {
+        return Ok(stage3d.context3d().map_or(Value::Null, Value::Object));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Stage3D.requestContext3D`.
+///
+/// The context is created straight away, so the `context3DCreate` event (or
+/// an `error` event, if the renderer doesn't support Stage3D) is dispatched
+/// before this returns.
+pub fn request_context3d<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(Value::Undefined),
+    };
+    let stage3d = match this.as_stage3d() {
+        Some(stage3d) => stage3d,
+        None => return Ok(Value::Undefined),
+    };
+
+    let event = if stage3d.context3d().is_some() {
+        activation
+            .avm2()
+            .classes()
+            .event
+            .construct(activation, &["context3DCreate".into()])?
+    } else {
+        match activation.context.renderer.create_context3d() {
+            Ok(context) => {
+                let context3d = Context3DObject::from_context(activation, context)?;
+                stage3d.set_context3d(activation.context.gc_context, Some(context3d));
+                activation
+                    .avm2()
+                    .classes()
+                    .event
+                    .construct(activation, &["context3DCreate".into()])?
+            }
+            Err(e) => {
+                log::warn!("Couldn't create a Context3D: {}", e);
+                let error_event_class = activation
+                    .avm2()
+                    .global_domain()
+                    .get_defined_value(
+                        activation,
+                        QName::new(Namespace::package("flash.events"), "ErrorEvent"),
+                    )?
+                    .coerce_to_object(activation)?;
+                let message = AvmString::new(
+                    activation.context.gc_context,
+                    "Error #3702: Context3D not available.",
+                );
+                error_event_class.construct(
+                    activation,
+                    &[
+                        "error".into(),
+                        false.into(),
+                        false.into(),
+                        message.into(),
+                        3702.into(),
+                    ],
+                )?
+            }
+        }
+    };
+    dispatch_event(activation, this, event)?;
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Stage3D.requestContext3DMatchingProfiles`.
+///
+/// Every profile is served by the same context.
+pub fn request_context3d_matching_profiles<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    request_context3d(activation, this, &[])
+}
+
+/// Construct `Stage3D`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.display"), "Stage3D"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<Stage3D instance initializer>", mc),
+        Method::from_builtin(class_init, "<Stage3D class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+    write.set_instance_allocator(stage3d_allocator);
+    write.set_native_instance_init(Method::from_builtin(
+        native_instance_init,
+        "<Stage3D native instance initializer>",
+        mc,
+    ));
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("x", Some(x), Some(set_x)),
+        ("y", Some(y), Some(set_y)),
+        ("visible", Some(visible), Some(set_visible)),
+        ("context3D", Some(context3d), None),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("requestContext3D", request_context3d),
+        (
+            "requestContext3DMatchingProfiles",
+            request_context3d_matching_profiles,
+        ),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    class
+}
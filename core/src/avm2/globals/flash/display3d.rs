@@ -0,0 +1,17 @@
+//! `flash.display3D` namespace
+
+pub mod context3d;
+pub mod context3dblendfactor;
+pub mod context3dclearmask;
+pub mod context3dcomparemode;
+pub mod context3dprofile;
+pub mod context3dprogramtype;
+pub mod context3drendermode;
+pub mod context3dstencilaction;
+pub mod context3dtextureformat;
+pub mod context3dtriangleface;
+pub mod context3dvertexbufferformat;
+pub mod indexbuffer3d;
+pub mod program3d;
+pub mod textures;
+pub mod vertexbuffer3d;
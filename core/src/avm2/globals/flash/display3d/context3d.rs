@@ -0,0 +1,861 @@
+//! `flash.display3D.Context3D` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{
+    context3d_allocator, Context3DObject, Context3DResource, Context3DResourceObject, Object,
+    TObject,
+};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::backend::render::context3d::{
+    BlendFactors, Context3DBlendFactor, Context3DCompareMode, Context3DProgramType,
+    Context3DStencilAction, Context3DTextureFormat, Context3DTriangleFace,
+    Context3DVertexBufferFormat, StencilActions, MAX_SAMPLERS, MAX_VERTEX_ATTRIBUTES,
+};
+use crate::string::AvmString;
+use gc_arena::{GcCell, MutationContext};
+
+/// The largest back buffer that can be configured.
+const MAX_BACK_BUFFER_SIZE: u32 = 4096;
+
+/// Implements `flash.display3D.Context3D`'s instance constructor.
+pub fn instance_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Err("You cannot construct new instances of the Context3D.".into())
+}
+
+/// Implements `flash.display3D.Context3D`'s native instance constructor.
+pub fn native_instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display3D.Context3D`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Get an argument, or `undefined` if it wasn't given.
+pub fn arg<'gc>(args: &[Value<'gc>], index: usize) -> Value<'gc> {
+    args.get(index).cloned().unwrap_or(Value::Undefined)
+}
+
+/// Get an optional argument, or its default if it wasn't given.
+pub fn arg_or<'gc>(args: &[Value<'gc>], index: usize, default: Value<'gc>) -> Value<'gc> {
+    match args.get(index) {
+        Some(Value::Undefined) | None => default,
+        Some(value) => value.clone(),
+    }
+}
+
+/// Convert one of the string constants of a `flash.display3D` class to the
+/// renderer's enum.
+pub fn parse_constant<'gc, T>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+    parameter: &str,
+    from_name: fn(&str) -> Option<T>,
+) -> Result<T, Error> {
+    let name = value.coerce_to_string(activation)?;
+    from_name(name.as_str()).ok_or_else(|| {
+        format!(
+            "ArgumentError: Error #2008: Parameter {} must be one of the accepted values.",
+            parameter
+        )
+        .into()
+    })
+}
+
+/// The context and resource held by a resource object, as long as neither
+/// has been disposed.
+pub fn context3d_resource<'gc>(
+    value: Value<'gc>,
+) -> Result<(Context3DObject<'gc>, Context3DResource), Error> {
+    let object = match value {
+        Value::Object(object) => Some(object),
+        _ => None,
+    };
+    object
+        .and_then(|object| object.as_context3d_resource())
+        .and_then(|resource| resource.resource())
+        .and_then(|(context3d, resource)| Some((context3d.as_context3d()?, resource)))
+        .ok_or_else(|| "TypeError: Error #1034: Type Coercion failed.".into())
+}
+
+/// The resource held by an optional argument, if one was given.
+fn optional_resource<'gc>(value: Value<'gc>) -> Result<Option<Context3DResource>, Error> {
+    match value {
+        Value::Null | Value::Undefined => Ok(None),
+        value => Ok(Some(context3d_resource(value)?.1)),
+    }
+}
+
+/// Implements `Context3D.driverInfo`'s getter.
+pub fn driver_info<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        let driver_info = context3d
+            .with_context(activation.context.gc_context, |context| {
+                context.driver_info()
+            })
+            .unwrap_or_else(|_| "Disposed".to_string());
+        return Ok(AvmString::new(activation.context.gc_context, driver_info).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.backBufferWidth`'s getter.
+pub fn back_buffer_width<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        return Ok(context3d.back_buffer_size().0.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.backBufferHeight`'s getter.
+pub fn back_buffer_height<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        return Ok(context3d.back_buffer_size().1.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.maxBackBufferWidth` and `maxBackBufferHeight`'s
+/// getters.
+pub fn max_back_buffer_size<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(MAX_BACK_BUFFER_SIZE.into())
+}
+
+/// Implements `Context3D.enableErrorChecking`'s getter.
+pub fn enable_error_checking<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        return Ok(context3d.enable_error_checking().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.enableErrorChecking`'s setter.
+///
+/// Errors are always checked for, so this has no effect.
+pub fn set_enable_error_checking<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        let value = arg(args, 0).coerce_to_boolean();
+        context3d.set_enable_error_checking(activation.context.gc_context, value);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.profile`'s getter.
+pub fn profile<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok("baseline".into())
+}
+
+/// Implements `Context3D.configureBackBuffer`.
+pub fn configure_back_buffer<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        let width = arg(args, 0).coerce_to_u32(activation)?;
+        let height = arg(args, 1).coerce_to_u32(activation)?;
+        let anti_alias = arg(args, 2).coerce_to_u32(activation)?;
+        let enable_depth_and_stencil = arg_or(args, 3, true.into()).coerce_to_boolean();
+
+        if !(32..=MAX_BACK_BUFFER_SIZE).contains(&width)
+            || !(32..=MAX_BACK_BUFFER_SIZE).contains(&height)
+        {
+            return Err("ArgumentError: Error #3669: Bad input size for the back buffer.".into());
+        }
+
+        context3d.with_context(activation.context.gc_context, |context| {
+            context.configure_back_buffer(width, height, anti_alias, enable_depth_and_stencil)
+        })?;
+        context3d.set_back_buffer_size(activation.context.gc_context, (width, height));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.clear`.
+pub fn clear<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        let red = arg_or(args, 0, 0.0.into()).coerce_to_number(activation)?;
+        let green = arg_or(args, 1, 0.0.into()).coerce_to_number(activation)?;
+        let blue = arg_or(args, 2, 0.0.into()).coerce_to_number(activation)?;
+        let alpha = arg_or(args, 3, 1.0.into()).coerce_to_number(activation)?;
+        let depth = arg_or(args, 4, 1.0.into()).coerce_to_number(activation)?;
+        let stencil = arg_or(args, 5, 0.into()).coerce_to_u32(activation)?;
+        let mask = arg_or(args, 6, 0xffffffffu32.into()).coerce_to_u32(activation)?;
+
+        let color = [red as f32, green as f32, blue as f32, alpha as f32];
+        context3d.with_context(activation.context.gc_context, |context| {
+            context.clear(color, depth as f32, stencil, mask)
+        })?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.createVertexBuffer`.
+pub fn create_vertex_buffer<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some((this, context3d)) = this.and_then(|this| Some((this, this.as_context3d()?))) {
+        let num_vertices = arg(args, 0).coerce_to_u32(activation)?;
+        let data32_per_vertex = arg(args, 1).coerce_to_u32(activation)?;
+        if num_vertices > 0xffff || data32_per_vertex == 0 || data32_per_vertex > 64 {
+            return Err("Error #3670: Buffer too big.".into());
+        }
+
+        let handle = context3d.with_context(activation.context.gc_context, |context| {
+            context.create_vertex_buffer(num_vertices, data32_per_vertex)
+        })?;
+        let class = activation.avm2().classes().vertexbuffer3d;
+        let resource = Context3DResource::VertexBuffer {
+            handle,
+            num_vertices,
+            data32_per_vertex,
+        };
+        return Ok(
+            Context3DResourceObject::from_resource(activation, class, this, resource)?.into(),
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.createIndexBuffer`.
+pub fn create_index_buffer<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some((this, context3d)) = this.and_then(|this| Some((this, this.as_context3d()?))) {
+        let num_indices = arg(args, 0).coerce_to_u32(activation)?;
+        if num_indices > 0xfffff {
+            return Err("Error #3670: Buffer too big.".into());
+        }
+
+        let handle = context3d.with_context(activation.context.gc_context, |context| {
+            context.create_index_buffer(num_indices)
+        })?;
+        let class = activation.avm2().classes().indexbuffer3d;
+        let resource = Context3DResource::IndexBuffer {
+            handle,
+            num_indices,
+        };
+        return Ok(
+            Context3DResourceObject::from_resource(activation, class, this, resource)?.into(),
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.createProgram`.
+pub fn create_program<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some((this, context3d)) = this.and_then(|this| Some((this, this.as_context3d()?))) {
+        let handle = context3d.with_context(activation.context.gc_context, |context| {
+            context.create_program()
+        })?;
+        let class = activation.avm2().classes().program3d;
+        let resource = Context3DResource::Program(handle);
+        return Ok(
+            Context3DResourceObject::from_resource(activation, class, this, resource)?.into(),
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.createTexture`.
+pub fn create_texture<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some((this, context3d)) = this.and_then(|this| Some((this, this.as_context3d()?))) {
+        let width = arg(args, 0).coerce_to_u32(activation)?;
+        let height = arg(args, 1).coerce_to_u32(activation)?;
+        let format = parse_constant(
+            activation,
+            arg(args, 2),
+            "format",
+            Context3DTextureFormat::from_name,
+        )?;
+        let optimize_for_render_to_texture = arg(args, 3).coerce_to_boolean();
+
+        let valid_size = |size: u32| size.is_power_of_two() && size <= MAX_BACK_BUFFER_SIZE;
+        if !valid_size(width) || !valid_size(height) {
+            return Err("ArgumentError: Texture dimensions must be powers of two.".into());
+        }
+
+        let handle = context3d
+            .with_context(activation.context.gc_context, |context| {
+                context.create_texture(width, height, format, optimize_for_render_to_texture)
+            })?
+            .map_err(|e| format!("ArgumentError: {}", e))?;
+        let class = activation.avm2().classes().texture;
+        let resource = Context3DResource::Texture {
+            handle,
+            width,
+            height,
+            format,
+        };
+        return Ok(
+            Context3DResourceObject::from_resource(activation, class, this, resource)?.into(),
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.setProgram`.
+pub fn set_program<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        let program = match optional_resource(arg(args, 0))? {
+            Some(Context3DResource::Program(handle)) => Some(handle),
+            Some(_) => return Err("TypeError: Error #1034: Type Coercion failed.".into()),
+            None => None,
+        };
+        context3d.with_context(activation.context.gc_context, |context| {
+            context.set_program(program)
+        })?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.setVertexBufferAt`.
+pub fn set_vertex_buffer_at<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        let index = arg(args, 0).coerce_to_u32(activation)? as usize;
+        if index >= MAX_VERTEX_ATTRIBUTES {
+            return Err("RangeError: Index out of range.".into());
+        }
+
+        let buffer = match optional_resource(arg(args, 1))? {
+            Some(Context3DResource::VertexBuffer {
+                handle,
+                data32_per_vertex,
+                ..
+            }) => {
+                let offset = arg_or(args, 2, 0.into()).coerce_to_u32(activation)?;
+                let format = parse_constant(
+                    activation,
+                    arg_or(args, 3, "float4".into()),
+                    "format",
+                    Context3DVertexBufferFormat::from_name,
+                )?;
+                if offset >= data32_per_vertex {
+                    return Err("RangeError: Index out of range.".into());
+                }
+                Some((handle, offset, format))
+            }
+            Some(_) => return Err("TypeError: Error #1034: Type Coercion failed.".into()),
+            None => None,
+        };
+        context3d.with_context(activation.context.gc_context, |context| {
+            context.set_vertex_buffer_at(index, buffer)
+        })?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.setProgramConstantsFromVector`.
+pub fn set_program_constants_from_vector<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        let program_type = parse_constant(
+            activation,
+            arg(args, 0),
+            "programType",
+            Context3DProgramType::from_name,
+        )?;
+        let first_register = arg(args, 1).coerce_to_u32(activation)?;
+        let data = arg(args, 2);
+        let num_registers = arg_or(args, 3, (-1).into()).coerce_to_i32(activation)?;
+
+        let values: Vec<Value<'gc>> = match &data {
+            Value::Object(object) => object
+                .as_vector_storage()
+                .map(|vector| vector.iter().collect())
+                .ok_or("TypeError: Error #1034: Type Coercion failed.")?,
+            _ => return Err("TypeError: Error #2007: Parameter data must be non-null.".into()),
+        };
+        let len = if num_registers < 0 {
+            values.len() / 4 * 4
+        } else {
+            (num_registers as usize * 4).min(values.len())
+        };
+        let mut constants = Vec::with_capacity(len);
+        for value in &values[..len] {
+            constants.push(value.coerce_to_number(activation)? as f32);
+        }
+
+        context3d.with_context(activation.context.gc_context, |context| {
+            context.set_program_constants(program_type, first_register, &constants)
+        })?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.setTextureAt`.
+pub fn set_texture_at<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        let sampler = arg(args, 0).coerce_to_u32(activation)? as usize;
+        if sampler >= MAX_SAMPLERS {
+            return Err("RangeError: Index out of range.".into());
+        }
+
+        let texture = match optional_resource(arg(args, 1))? {
+            Some(Context3DResource::Texture { handle, .. }) => Some(handle),
+            Some(_) => return Err("TypeError: Error #1034: Type Coercion failed.".into()),
+            None => None,
+        };
+        context3d.with_context(activation.context.gc_context, |context| {
+            context.set_texture_at(sampler, texture)
+        })?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.setBlendFactors`.
+pub fn set_blend_factors<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        let source = parse_constant(
+            activation,
+            arg(args, 0),
+            "sourceFactor",
+            Context3DBlendFactor::from_name,
+        )?;
+        let destination = parse_constant(
+            activation,
+            arg(args, 1),
+            "destinationFactor",
+            Context3DBlendFactor::from_name,
+        )?;
+        context3d.with_context(activation.context.gc_context, |context| {
+            context.set_blend_factors(BlendFactors {
+                source,
+                destination,
+            })
+        })?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.setCulling`.
+pub fn set_culling<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        let face = parse_constant(
+            activation,
+            arg(args, 0),
+            "triangleFaceToCull",
+            Context3DTriangleFace::from_name,
+        )?;
+        context3d.with_context(activation.context.gc_context, |context| {
+            context.set_culling(face)
+        })?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.setDepthTest`.
+pub fn set_depth_test<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        let depth_mask = arg(args, 0).coerce_to_boolean();
+        let pass_compare_mode = parse_constant(
+            activation,
+            arg(args, 1),
+            "passCompareMode",
+            Context3DCompareMode::from_name,
+        )?;
+        context3d.with_context(activation.context.gc_context, |context| {
+            context.set_depth_test(depth_mask, pass_compare_mode)
+        })?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.setColorMask`.
+pub fn set_color_mask<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        let red = arg(args, 0).coerce_to_boolean();
+        let green = arg(args, 1).coerce_to_boolean();
+        let blue = arg(args, 2).coerce_to_boolean();
+        let alpha = arg(args, 3).coerce_to_boolean();
+        context3d.with_context(activation.context.gc_context, |context| {
+            context.set_color_mask(red, green, blue, alpha)
+        })?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.setStencilActions`.
+pub fn set_stencil_actions<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        let triangle_face = parse_constant(
+            activation,
+            arg_or(args, 0, "frontAndBack".into()),
+            "triangleFace",
+            Context3DTriangleFace::from_name,
+        )?;
+        let compare_mode = parse_constant(
+            activation,
+            arg_or(args, 1, "always".into()),
+            "compareMode",
+            Context3DCompareMode::from_name,
+        )?;
+        let mut actions = [Context3DStencilAction::Keep; 3];
+        let parameters = [
+            "actionOnBothPass",
+            "actionOnDepthFail",
+            "actionOnDepthPassStencilFail",
+        ];
+        for (i, (action, parameter)) in actions.iter_mut().zip(&parameters).enumerate() {
+            *action = parse_constant(
+                activation,
+                arg_or(args, i + 2, "keep".into()),
+                parameter,
+                Context3DStencilAction::from_name,
+            )?;
+        }
+        let [action_on_both_pass, action_on_depth_fail, action_on_depth_pass_stencil_fail] =
+            actions;
+
+        context3d.with_context(activation.context.gc_context, |context| {
+            context.set_stencil_actions(StencilActions {
+                triangle_face,
+                compare_mode,
+                action_on_both_pass,
+                action_on_depth_fail,
+                action_on_depth_pass_stencil_fail,
+            })
+        })?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.setStencilReferenceValue`.
+pub fn set_stencil_reference_value<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        let reference = arg(args, 0).coerce_to_u32(activation)?;
+        let read_mask = arg_or(args, 1, 255.into()).coerce_to_u32(activation)?;
+        let write_mask = arg_or(args, 2, 255.into()).coerce_to_u32(activation)?;
+        context3d.with_context(activation.context.gc_context, |context| {
+            context.set_stencil_reference_value(reference, read_mask, write_mask)
+        })?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.setRenderToTexture`.
+///
+/// Only 2D textures are supported, so the surface selector and colour output
+/// index are ignored.
+pub fn set_render_to_texture<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        let texture = match context3d_resource(arg(args, 0))?.1 {
+            Context3DResource::Texture { handle, .. } => handle,
+            _ => return Err("TypeError: Error #1034: Type Coercion failed.".into()),
+        };
+        let enable_depth_and_stencil = arg(args, 1).coerce_to_boolean();
+        let anti_alias = arg_or(args, 2, 0.into()).coerce_to_u32(activation)?;
+
+        context3d
+            .with_context(activation.context.gc_context, |context| {
+                context.set_render_to_texture(texture, enable_depth_and_stencil, anti_alias)
+            })?
+            .map_err(|e| format!("Error: {}", e))?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.setRenderToBackBuffer`.
+pub fn set_render_to_back_buffer<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        context3d.with_context(activation.context.gc_context, |context| {
+            context.set_render_to_back_buffer()
+        })?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.setScissorRectangle`.
+pub fn set_scissor_rectangle<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        let rectangle = match arg(args, 0) {
+            Value::Null | Value::Undefined => None,
+            value => {
+                let rectangle = value.coerce_to_object(activation)?;
+                let mut values = [0; 4];
+                for (value, name) in values.iter_mut().zip(&["x", "y", "width", "height"]) {
+                    let number = rectangle
+                        .get_property(
+                            rectangle,
+                            &QName::new(Namespace::public(), *name),
+                            activation,
+                        )?
+                        .coerce_to_number(activation)?;
+                    *value = number.max(0.0) as u32;
+                }
+                let [x, y, width, height] = values;
+                Some((x, y, width, height))
+            }
+        };
+        context3d.with_context(activation.context.gc_context, |context| {
+            context.set_scissor_rectangle(rectangle)
+        })?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.drawTriangles`.
+pub fn draw_triangles<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        let index_buffer = match context3d_resource(arg(args, 0))?.1 {
+            Context3DResource::IndexBuffer { handle, .. } => handle,
+            _ => return Err("TypeError: Error #1034: Type Coercion failed.".into()),
+        };
+        let first_index = arg_or(args, 1, 0.into()).coerce_to_u32(activation)?;
+        let num_triangles = arg_or(args, 2, (-1).into()).coerce_to_i32(activation)?;
+        let num_triangles = if num_triangles < 0 {
+            None
+        } else {
+            Some(num_triangles as u32)
+        };
+
+        context3d
+            .with_context(activation.context.gc_context, |context| {
+                context.draw_triangles(index_buffer, first_index, num_triangles)
+            })?
+            .map_err(|e| format!("Error: {}", e))?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.present`.
+pub fn present<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        context3d.with_context(activation.context.gc_context, |context| context.present())?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Context3D.dispose`.
+pub fn dispose<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(context3d) = this.and_then(|this| this.as_context3d()) {
+        context3d.dispose(activation.context.gc_context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `Context3D`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.display3D"), "Context3D"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<Context3D instance initializer>", mc),
+        Method::from_builtin(class_init, "<Context3D class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+    write.set_instance_allocator(context3d_allocator);
+    write.set_native_instance_init(Method::from_builtin(
+        native_instance_init,
+        "<Context3D native instance initializer>",
+        mc,
+    ));
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("driverInfo", Some(driver_info), None),
+        ("backBufferWidth", Some(back_buffer_width), None),
+        ("backBufferHeight", Some(back_buffer_height), None),
+        ("maxBackBufferWidth", Some(max_back_buffer_size), None),
+        ("maxBackBufferHeight", Some(max_back_buffer_size), None),
+        (
+            "enableErrorChecking",
+            Some(enable_error_checking),
+            Some(set_enable_error_checking),
+        ),
+        ("profile", Some(profile), None),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("configureBackBuffer", configure_back_buffer),
+        ("clear", clear),
+        ("createVertexBuffer", create_vertex_buffer),
+        ("createIndexBuffer", create_index_buffer),
+        ("createProgram", create_program),
+        ("createTexture", create_texture),
+        ("setProgram", set_program),
+        ("setVertexBufferAt", set_vertex_buffer_at),
+        (
+            "setProgramConstantsFromVector",
+            set_program_constants_from_vector,
+        ),
+        ("setTextureAt", set_texture_at),
+        ("setBlendFactors", set_blend_factors),
+        ("setCulling", set_culling),
+        ("setDepthTest", set_depth_test),
+        ("setColorMask", set_color_mask),
+        ("setStencilActions", set_stencil_actions),
+        ("setStencilReferenceValue", set_stencil_reference_value),
+        ("setRenderToTexture", set_render_to_texture),
+        ("setRenderToBackBuffer", set_render_to_back_buffer),
+        ("setScissorRectangle", set_scissor_rectangle),
+        ("drawTriangles", draw_triangles),
+        ("present", present),
+        ("dispose", dispose),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    class
+}
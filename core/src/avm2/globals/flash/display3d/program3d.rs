@@ -0,0 +1,116 @@
+//! `flash.display3D.Program3D` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::display3d::context3d::{arg, context3d_resource};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{context3d_resource_allocator, Context3DResource, Object, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.display3D.Program3D`'s instance constructor.
+pub fn instance_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Err("You cannot construct new instances of the Program3D.".into())
+}
+
+/// Implements `flash.display3D.Program3D`'s native instance constructor.
+pub fn native_instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display3D.Program3D`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `Program3D.upload`.
+pub fn upload<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let (context3d, Context3DResource::Program(handle)) = context3d_resource(this.into())? {
+            let mut programs = Vec::with_capacity(2);
+            for i in 0..2 {
+                let byte_array = arg(args, i).coerce_to_object(activation)?;
+                let bytecode = byte_array
+                    .as_bytearray()
+                    .ok_or("TypeError: Error #1034: Type Coercion failed.")?
+                    .bytes()
+                    .clone();
+                programs.push(bytecode);
+            }
+
+            context3d
+                .with_context(activation.context.gc_context, |context| {
+                    context.upload_program(handle, &programs[0], &programs[1])
+                })?
+                .map_err(|e| format!("Error: AGAL validation failed: {}", e))?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Program3D.dispose`.
+pub fn dispose<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let (context3d, Context3DResource::Program(handle)) = context3d_resource(this.into())? {
+            context3d.with_context(activation.context.gc_context, |context| {
+                context.dispose_program(handle)
+            })?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `Program3D`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.display3D"), "Program3D"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<Program3D instance initializer>", mc),
+        Method::from_builtin(class_init, "<Program3D class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+    write.set_instance_allocator(context3d_resource_allocator);
+    write.set_native_instance_init(Method::from_builtin(
+        native_instance_init,
+        "<Program3D native instance initializer>",
+        mc,
+    ));
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] =
+        &[("upload", upload), ("dispose", dispose)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    class
+}
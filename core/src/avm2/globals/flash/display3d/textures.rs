@@ -0,0 +1,4 @@
+//! `flash.display3D.textures` namespace
+
+pub mod texture;
+pub mod texturebase;
@@ -0,0 +1,234 @@
+//! `flash.display3D.textures.Texture` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::events::dispatch_event;
+use crate::avm2::globals::flash::display3d::context3d::{arg, context3d_resource};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{context3d_resource_allocator, Context3DResource, Object, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::backend::render::context3d::atf;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.display3D.textures.Texture`'s instance constructor.
+pub fn instance_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Err("You cannot construct new instances of the Texture.".into())
+}
+
+/// Implements `flash.display3D.textures.Texture`'s native instance constructor.
+pub fn native_instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display3D.textures.Texture`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Upload premultiplied RGBA pixels to a mip level of a texture, which must
+/// be the size of that level.
+fn upload_pixels<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    mip_level: u32,
+    (width, height): (u32, u32),
+    rgba: &[u8],
+) -> Result<(), Error> {
+    if let (
+        context3d,
+        Context3DResource::Texture {
+            handle,
+            width: texture_width,
+            height: texture_height,
+            ..
+        },
+    ) = context3d_resource(this.into())?
+    {
+        if mip_level >= 32
+            || width != (texture_width >> mip_level).max(1)
+            || height != (texture_height >> mip_level).max(1)
+        {
+            return Err("ArgumentError: Error #3676: Texture size does not match.".into());
+        }
+
+        context3d.with_context(activation.context.gc_context, |context| {
+            context.upload_texture(handle, mip_level, rgba)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Implements `Texture.uploadFromBitmapData`.
+pub fn upload_from_bitmap_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let bitmap_data = arg(args, 0)
+            .coerce_to_object(activation)?
+            .as_bitmap_data()
+            .ok_or("TypeError: Error #1034: Type Coercion failed.")?;
+        let mip_level = arg(args, 1).coerce_to_u32(activation)?;
+
+        let bitmap_data = bitmap_data.read();
+        let size = (bitmap_data.width(), bitmap_data.height());
+        let rgba = bitmap_data.pixels_rgba();
+        drop(bitmap_data);
+        upload_pixels(activation, this, mip_level, size, &rgba)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Texture.uploadFromByteArray`.
+///
+/// The pixels are given in BGRA order.
+pub fn upload_from_byte_array<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let (_, Context3DResource::Texture { width, height, .. }) =
+            context3d_resource(this.into())?
+        {
+            let byte_array = arg(args, 0).coerce_to_object(activation)?;
+            let offset = arg(args, 1).coerce_to_u32(activation)? as usize;
+            let mip_level = arg(args, 2).coerce_to_u32(activation)?;
+            if mip_level >= 32 {
+                return Err("ArgumentError: Error #3676: Texture size does not match.".into());
+            }
+
+            let size = ((width >> mip_level).max(1), (height >> mip_level).max(1));
+            let rgba: Vec<u8> = byte_array
+                .as_bytearray()
+                .ok_or("TypeError: Error #1034: Type Coercion failed.")?
+                .read_at((size.0 * size.1 * 4) as usize, offset)?
+                .chunks_exact(4)
+                .flat_map(|bgra| [bgra[2], bgra[1], bgra[0], bgra[3]])
+                .collect();
+            upload_pixels(activation, this, mip_level, size, &rgba)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Texture.uploadCompressedTextureFromByteArray`.
+///
+/// The ATF data is decoded straight away, so the `textureReady` event of an
+/// asynchronous upload is dispatched before this returns.
+pub fn upload_compressed_texture_from_byte_array<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let (
+            _,
+            Context3DResource::Texture {
+                width,
+                height,
+                format,
+                ..
+            },
+        ) = context3d_resource(this.into())?
+        {
+            let byte_array = arg(args, 0).coerce_to_object(activation)?;
+            let offset = arg(args, 1).coerce_to_u32(activation)? as usize;
+            let is_async = arg(args, 2).coerce_to_boolean();
+
+            let texture = {
+                let byte_array = byte_array
+                    .as_bytearray()
+                    .ok_or("TypeError: Error #1034: Type Coercion failed.")?;
+                let data = byte_array
+                    .bytes()
+                    .get(offset..)
+                    .ok_or("RangeError: Error #2006: The supplied index is out of bounds.")?;
+                atf::decode(data).map_err(|e| format!("Error: {}", e))?
+            };
+            if texture.format != format {
+                return Err("Error: Texture format mismatch.".into());
+            }
+            if (texture.width, texture.height) != (width, height) {
+                return Err("ArgumentError: Error #3676: Texture size does not match.".into());
+            }
+
+            for (mip_level, rgba) in (0..).zip(&texture.mip_levels) {
+                let size = ((width >> mip_level).max(1), (height >> mip_level).max(1));
+                upload_pixels(activation, this, mip_level, size, rgba)?;
+            }
+
+            if is_async {
+                let event = activation
+                    .avm2()
+                    .classes()
+                    .event
+                    .construct(activation, &["textureReady".into()])?;
+                dispatch_event(activation, this, event)?;
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `Texture`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.display3D.textures"), "Texture"),
+        Some(
+            QName::new(
+                Namespace::package("flash.display3D.textures"),
+                "TextureBase",
+            )
+            .into(),
+        ),
+        Method::from_builtin(instance_init, "<Texture instance initializer>", mc),
+        Method::from_builtin(class_init, "<Texture class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+    write.set_instance_allocator(context3d_resource_allocator);
+    write.set_native_instance_init(Method::from_builtin(
+        native_instance_init,
+        "<Texture native instance initializer>",
+        mc,
+    ));
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("uploadFromBitmapData", upload_from_bitmap_data),
+        ("uploadFromByteArray", upload_from_byte_array),
+        (
+            "uploadCompressedTextureFromByteArray",
+            upload_compressed_texture_from_byte_array,
+        ),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    class
+}
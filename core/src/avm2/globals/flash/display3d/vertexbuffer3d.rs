@@ -0,0 +1,179 @@
+//! `flash.display3D.VertexBuffer3D` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::display3d::context3d::{arg, context3d_resource};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{context3d_resource_allocator, Context3DResource, Object, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.display3D.VertexBuffer3D`'s instance constructor.
+pub fn instance_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Err("You cannot construct new instances of the VertexBuffer3D.".into())
+}
+
+/// Implements `flash.display3D.VertexBuffer3D`'s native instance constructor.
+pub fn native_instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display3D.VertexBuffer3D`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `VertexBuffer3D.uploadFromVector`.
+pub fn upload_from_vector<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let (
+            context3d,
+            Context3DResource::VertexBuffer {
+                handle,
+                num_vertices,
+                data32_per_vertex,
+            },
+        ) = context3d_resource(this.into())?
+        {
+            let values: Vec<Value<'gc>> = match arg(args, 0) {
+                Value::Object(object) => object
+                    .as_vector_storage()
+                    .map(|vector| vector.iter().collect())
+                    .ok_or("TypeError: Error #1034: Type Coercion failed.")?,
+                _ => return Err("TypeError: Error #2007: Parameter data must be non-null.".into()),
+            };
+            let start_vertex = arg(args, 1).coerce_to_u32(activation)?;
+            let count = arg(args, 2).coerce_to_u32(activation)?;
+            if start_vertex.saturating_add(count) > num_vertices {
+                return Err("RangeError: Vertices out of range.".into());
+            }
+
+            let len = (count * data32_per_vertex) as usize;
+            if values.len() < len {
+                return Err("RangeError: Not enough vertex data.".into());
+            }
+            let mut data = Vec::with_capacity(len * 4);
+            for value in &values[..len] {
+                let number = value.coerce_to_number(activation)? as f32;
+                data.extend_from_slice(&number.to_le_bytes());
+            }
+
+            context3d.with_context(activation.context.gc_context, |context| {
+                context.upload_vertex_buffer(handle, start_vertex, &data)
+            })?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `VertexBuffer3D.uploadFromByteArray`.
+pub fn upload_from_byte_array<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let (
+            context3d,
+            Context3DResource::VertexBuffer {
+                handle,
+                num_vertices,
+                data32_per_vertex,
+            },
+        ) = context3d_resource(this.into())?
+        {
+            let byte_array = arg(args, 0).coerce_to_object(activation)?;
+            let offset = arg(args, 1).coerce_to_u32(activation)? as usize;
+            let start_vertex = arg(args, 2).coerce_to_u32(activation)?;
+            let count = arg(args, 3).coerce_to_u32(activation)?;
+            if start_vertex.saturating_add(count) > num_vertices {
+                return Err("RangeError: Vertices out of range.".into());
+            }
+
+            let len = (count * data32_per_vertex * 4) as usize;
+            let data = byte_array
+                .as_bytearray()
+                .ok_or("TypeError: Error #1034: Type Coercion failed.")?
+                .read_at(len, offset)?
+                .to_vec();
+
+            context3d.with_context(activation.context.gc_context, |context| {
+                context.upload_vertex_buffer(handle, start_vertex, &data)
+            })?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `VertexBuffer3D.dispose`.
+pub fn dispose<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let (context3d, Context3DResource::VertexBuffer { handle, .. }) =
+            context3d_resource(this.into())?
+        {
+            context3d.with_context(activation.context.gc_context, |context| {
+                context.dispose_vertex_buffer(handle)
+            })?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `VertexBuffer3D`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.display3D"), "VertexBuffer3D"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<VertexBuffer3D instance initializer>", mc),
+        Method::from_builtin(class_init, "<VertexBuffer3D class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+    write.set_instance_allocator(context3d_resource_allocator);
+    write.set_native_instance_init(Method::from_builtin(
+        native_instance_init,
+        "<VertexBuffer3D native instance initializer>",
+        mc,
+    ));
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("uploadFromVector", upload_from_vector),
+        ("uploadFromByteArray", upload_from_byte_array),
+        ("dispose", dispose),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    class
+}
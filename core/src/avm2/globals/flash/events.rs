@@ -1,8 +1,28 @@
 //! `flash.events` namespace
 
+pub mod asyncerrorevent;
+pub mod contextmenuevent;
+pub mod dataevent;
+pub mod errorevent;
 pub mod event;
 pub mod eventdispatcher;
+pub mod fullscreenevent;
+pub mod gameinputevent;
+pub mod gestureevent;
+pub mod gesturephase;
+pub mod httpstatusevent;
 pub mod ieventdispatcher;
+pub mod imeevent;
+pub mod ioerrorevent;
 pub mod keyboardevent;
 pub mod mouseevent;
+pub mod netstatusevent;
 pub mod progressevent;
+pub mod securityerrorevent;
+pub mod shaderevent;
+pub mod statusevent;
+pub mod textevent;
+pub mod touchevent;
+pub mod transformgestureevent;
+pub mod uncaughterrorevent;
+pub mod uncaughterrorevents;
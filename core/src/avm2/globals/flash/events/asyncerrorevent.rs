@@ -0,0 +1,69 @@
+//! `flash.events.AsyncErrorEvent` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.events.AsyncErrorEvent`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        // ErrorEvent takes the first four parameters, but no error ID.
+        activation.super_init(this, args.get(..4).unwrap_or(args))?;
+
+        let error = args.get(4).cloned().unwrap_or(Value::Null);
+
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "error"),
+            error,
+            activation,
+        )?;
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.events.AsyncErrorEvent`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `AsyncErrorEvent`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.events"), "AsyncErrorEvent"),
+        Some(QName::new(Namespace::package("flash.events"), "ErrorEvent").into()),
+        Method::from_builtin(instance_init, "<AsyncErrorEvent instance initializer>", mc),
+        Method::from_builtin(class_init, "<AsyncErrorEvent class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "error"),
+        QName::new(Namespace::public(), "Error").into(),
+        None,
+    ));
+
+    const CONSTANTS: &[(&str, &str)] = &[("ASYNC_ERROR", "asyncError")];
+
+    write.define_public_constant_string_class_traits(CONSTANTS);
+
+    class
+}
@@ -0,0 +1,131 @@
+//! `flash.events.ContextMenuEvent` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.events.ContextMenuEvent`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, args)?; // Event uses the first three parameters
+
+        let mouse_target = args.get(3).cloned().unwrap_or(Value::Null);
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "mouseTarget"),
+            mouse_target,
+            activation,
+        )?;
+
+        let context_menu_owner = args.get(4).cloned().unwrap_or(Value::Null);
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "contextMenuOwner"),
+            context_menu_owner,
+            activation,
+        )?;
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.events.ContextMenuEvent`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `clone`
+pub fn clone<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let (event_type, bubbles, cancelable) = match this.as_event() {
+            Some(evt) => (evt.event_type(), evt.is_bubbling(), evt.is_cancelable()),
+            None => return Ok(Value::Undefined),
+        };
+        let mouse_target = this.get_property(
+            this,
+            &QName::new(Namespace::public(), "mouseTarget"),
+            activation,
+        )?;
+        let context_menu_owner = this.get_property(
+            this,
+            &QName::new(Namespace::public(), "contextMenuOwner"),
+            activation,
+        )?;
+        let context_menu_event_class = activation.avm2().classes().contextmenuevent;
+
+        return Ok(context_menu_event_class
+            .construct(
+                activation,
+                &[
+                    event_type.into(),
+                    bubbles.into(),
+                    cancelable.into(),
+                    mouse_target,
+                    context_menu_owner,
+                ],
+            )?
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `ContextMenuEvent`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.events"), "ContextMenuEvent"),
+        Some(QName::new(Namespace::package("flash.events"), "Event").into()),
+        Method::from_builtin(instance_init, "<ContextMenuEvent instance initializer>", mc),
+        Method::from_builtin(class_init, "<ContextMenuEvent class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[("clone", clone)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "mouseTarget"),
+        QName::new(Namespace::package("flash.display"), "InteractiveObject").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "contextMenuOwner"),
+        QName::new(Namespace::package("flash.display"), "InteractiveObject").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "isMouseTargetInaccessible"),
+        QName::new(Namespace::public(), "Boolean").into(),
+        None,
+    ));
+
+    const CONSTANTS: &[(&str, &str)] = &[
+        ("MENU_ITEM_SELECT", "menuItemSelect"),
+        ("MENU_SELECT", "menuSelect"),
+    ];
+
+    write.define_public_constant_string_class_traits(CONSTANTS);
+
+    class
+}
@@ -144,7 +144,7 @@ pub fn clone<'gc>(
     if let Some(evt) = this.unwrap().as_event() {
         let evt_class = activation.avm2().classes().event;
 
-        return Ok(EventObject::from_event(activation, evt_class, evt.clone())?.into());
+        return Ok(EventObject::from_event(activation, evt_class, evt.undispatched_copy())?.into());
     }
 
     Ok(Value::Undefined)
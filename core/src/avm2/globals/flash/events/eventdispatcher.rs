@@ -77,12 +77,24 @@ pub fn add_event_listener<'gc>(
             .cloned()
             .unwrap_or(Value::Integer(0))
             .coerce_to_i32(activation)?;
+        let use_weak_reference = args
+            .get(4)
+            .cloned()
+            .unwrap_or(Value::Bool(false))
+            .coerce_to_boolean();
 
-        //TODO: If we ever get weak GC references, we should respect `useWeakReference`.
+        let mc = activation.context.gc_context;
         dispatch_list
-            .as_dispatch_mut(activation.context.gc_context)
+            .as_dispatch_mut(mc)
             .ok_or_else(|| Error::from("Internal properties should have what I put in them"))?
-            .add_event_listener(event_type, priority, listener, use_capture);
+            .add_event_listener(
+                event_type,
+                priority,
+                listener,
+                use_capture,
+                use_weak_reference,
+                mc,
+            );
 
         Avm2::register_broadcast_listener(&mut activation.context, this, event_type);
     }
@@ -120,10 +132,11 @@ pub fn remove_event_listener<'gc>(
             .unwrap_or(Value::Bool(false))
             .coerce_to_boolean();
 
+        let mc = activation.context.gc_context;
         dispatch_list
-            .as_dispatch_mut(activation.context.gc_context)
+            .as_dispatch_mut(mc)
             .ok_or_else(|| Error::from("Internal properties should have what I put in them"))?
-            .remove_event_listener(event_type, listener, use_capture);
+            .remove_event_listener(event_type, listener, use_capture, mc);
     }
 
     Ok(Value::Undefined)
@@ -152,7 +165,7 @@ pub fn has_event_listener<'gc>(
         return Ok(dispatch_list
             .as_dispatch_mut(activation.context.gc_context)
             .ok_or_else(|| Error::from("Internal properties should have what I put in them"))?
-            .has_event_listener(event_type)
+            .has_event_listener(event_type, activation.context.gc_context)
             .into());
     }
 
@@ -182,7 +195,7 @@ pub fn will_trigger<'gc>(
         if dispatch_list
             .as_dispatch_mut(activation.context.gc_context)
             .ok_or_else(|| Error::from("Internal properties should have what I put in them"))?
-            .has_event_listener(event_type)
+            .has_event_listener(event_type, activation.context.gc_context)
         {
             return Ok(true.into());
         }
@@ -211,14 +224,29 @@ pub fn dispatch_event<'gc>(
     this: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    let event = args
+    let mut event = args
         .get(0)
         .cloned()
         .unwrap_or(Value::Undefined)
         .coerce_to_object(activation)?;
 
-    if event.as_event().is_none() {
-        return Err("Dispatched Events must be subclasses of Event.".into());
+    let is_dispatched = match event.as_event() {
+        Some(evt) => evt.is_dispatched(),
+        None => return Err("Dispatched Events must be subclasses of Event.".into()),
+    };
+
+    if is_dispatched {
+        // Redispatched events are cloned, so that subclasses can carry over
+        // their own data.
+        event = event
+            .get_property(event, &QName::dynamic_name("clone"), activation)?
+            .coerce_to_object(activation)?
+            .call(Some(event), &[], activation, None)?
+            .coerce_to_object(activation)?;
+
+        if event.as_event().is_none() {
+            return Err("Event.clone() must return a subclass of Event.".into());
+        }
     }
 
     if let Some(this) = this {
@@ -0,0 +1,95 @@
+//! `flash.events.FullScreenEvent` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.events.FullScreenEvent`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, args.get(0..3).unwrap_or(args))?; // Event uses the first three parameters
+
+        let full_screen = args
+            .get(3)
+            .cloned()
+            .unwrap_or_else(|| false.into())
+            .coerce_to_boolean();
+        let interactive = args
+            .get(4)
+            .cloned()
+            .unwrap_or_else(|| false.into())
+            .coerce_to_boolean();
+
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "fullScreen"),
+            full_screen.into(),
+            activation,
+        )?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "interactive"),
+            interactive.into(),
+            activation,
+        )?;
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.events.FullScreenEvent`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `FullScreenEvent`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    // TODO: This should extend `ActivityEvent`, which Ruffle doesn't have yet.
+    let class = Class::new(
+        QName::new(Namespace::package("flash.events"), "FullScreenEvent"),
+        Some(QName::new(Namespace::package("flash.events"), "Event").into()),
+        Method::from_builtin(instance_init, "<FullScreenEvent instance initializer>", mc),
+        Method::from_builtin(class_init, "<FullScreenEvent class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "fullScreen"),
+        QName::new(Namespace::public(), "Boolean").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "interactive"),
+        QName::new(Namespace::public(), "Boolean").into(),
+        None,
+    ));
+
+    const CONSTANTS: &[(&str, &str)] = &[
+        ("FULL_SCREEN", "fullScreen"),
+        (
+            "FULL_SCREEN_INTERACTIVE_ACCEPTED",
+            "fullScreenInteractiveAccepted",
+        ),
+    ];
+
+    write.define_public_constant_string_class_traits(CONSTANTS);
+
+    class
+}
@@ -0,0 +1,90 @@
+//! `flash.events.GestureEvent` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// The properties set by the constructor after those of `Event`, in order.
+const CONSTRUCTOR_PROPERTIES: &[&str] =
+    &["phase", "localX", "localY", "ctrlKey", "altKey", "shiftKey"];
+
+/// Implements `flash.events.GestureEvent`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        // Unlike most events, gesture events bubble by default.
+        let event_type = args.get(0).cloned().unwrap_or(Value::Undefined);
+        let bubbles = args.get(1).cloned().unwrap_or_else(|| true.into());
+        let cancelable = args.get(2).cloned().unwrap_or_else(|| false.into());
+        activation.super_init(this, &[event_type, bubbles, cancelable])?;
+
+        for (name, value) in CONSTRUCTOR_PROPERTIES.iter().zip(args.iter().skip(3)) {
+            this.set_property(
+                this,
+                &QName::new(Namespace::public(), *name),
+                value.clone(),
+                activation,
+            )?;
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.events.GestureEvent`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `GestureEvent`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.events"), "GestureEvent"),
+        Some(QName::new(Namespace::package("flash.events"), "Event").into()),
+        Method::from_builtin(instance_init, "<GestureEvent instance initializer>", mc),
+        Method::from_builtin(class_init, "<GestureEvent class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "phase"),
+        QName::new(Namespace::public(), "String").into(),
+        None,
+    ));
+    for name in &["localX", "localY", "stageX", "stageY"] {
+        write.define_instance_trait(Trait::from_slot(
+            QName::new(Namespace::public(), *name),
+            QName::new(Namespace::public(), "Number").into(),
+            Some(0.into()),
+        ));
+    }
+    for name in &["ctrlKey", "altKey", "shiftKey"] {
+        write.define_instance_trait(Trait::from_slot(
+            QName::new(Namespace::public(), *name),
+            QName::new(Namespace::public(), "Boolean").into(),
+            Some(false.into()),
+        ));
+    }
+
+    const CONSTANTS: &[(&str, &str)] = &[("GESTURE_TWO_FINGER_TAP", "gestureTwoFingerTap")];
+
+    write.define_public_constant_string_class_traits(CONSTANTS);
+
+    class
+}
@@ -0,0 +1,109 @@
+//! `flash.events.IMEEvent` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.events.IMEEvent`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, args.get(0..4).unwrap_or(args))?; // TextEvent uses the first four parameters
+
+        let ime_client = args.get(4).cloned().unwrap_or(Value::Null);
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "imeClient"),
+            ime_client,
+            activation,
+        )?;
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.events.IMEEvent`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `clone`
+pub fn clone<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let (event_type, bubbles, cancelable) = match this.as_event() {
+            Some(evt) => (evt.event_type(), evt.is_bubbling(), evt.is_cancelable()),
+            None => return Ok(Value::Undefined),
+        };
+        let text = this.get_property(this, &QName::new(Namespace::public(), "text"), activation)?;
+        let ime_client = this.get_property(
+            this,
+            &QName::new(Namespace::public(), "imeClient"),
+            activation,
+        )?;
+        let ime_event_class = activation.avm2().classes().imeevent;
+
+        return Ok(ime_event_class
+            .construct(
+                activation,
+                &[
+                    event_type.into(),
+                    bubbles.into(),
+                    cancelable.into(),
+                    text,
+                    ime_client,
+                ],
+            )?
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `IMEEvent`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.events"), "IMEEvent"),
+        Some(QName::new(Namespace::package("flash.events"), "TextEvent").into()),
+        Method::from_builtin(instance_init, "<IMEEvent instance initializer>", mc),
+        Method::from_builtin(class_init, "<IMEEvent class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[("clone", clone)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "imeClient"),
+        QName::new(Namespace::public(), "Object").into(),
+        None,
+    ));
+
+    const CONSTANTS: &[(&str, &str)] = &[
+        ("IME_COMPOSITION", "imeComposition"),
+        ("IME_START_COMPOSITION", "imeStartComposition"),
+    ];
+
+    write.define_public_constant_string_class_traits(CONSTANTS);
+
+    class
+}
@@ -2,19 +2,52 @@ use crate::avm2::activation::Activation;
 use crate::avm2::class::{Class, ClassAttributes};
 use crate::avm2::method::Method;
 use crate::avm2::names::{Namespace, QName};
-use crate::avm2::object::Object;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use gc_arena::{GcCell, MutationContext};
 
+/// The properties of a `KeyboardEvent`, in the order that its constructor
+/// takes them after those of `Event`.
+const PROPERTIES: &[(&str, &str)] = &[
+    ("charCode", "uint"),
+    ("keyCode", "uint"),
+    ("keyLocation", "uint"),
+    ("ctrlKey", "Boolean"),
+    ("altKey", "Boolean"),
+    ("shiftKey", "Boolean"),
+];
+
 /// Implements `flash.events.KeyboardEvent`'s instance constructor.
 pub fn instance_init<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    if let Some(this) = this {
-        activation.super_init(this, args)?; // Event uses the first three parameters
+    if let Some(mut this) = this {
+        // Unlike other events, keyboard events bubble unless told otherwise.
+        let event_args = [
+            args.get(0).cloned().unwrap_or(Value::Undefined),
+            args.get(1).cloned().unwrap_or_else(|| true.into()),
+            args.get(2).cloned().unwrap_or_else(|| false.into()),
+        ];
+        activation.super_init(this, &event_args)?;
+
+        for (index, (name, type_name)) in PROPERTIES.iter().enumerate() {
+            let value = args.get(index + 3).cloned().unwrap_or(Value::Undefined);
+            let value = if *type_name == "Boolean" {
+                value.coerce_to_boolean().into()
+            } else {
+                value.coerce_to_u32(activation)?.into()
+            };
+            this.set_property(
+                this,
+                &QName::new(Namespace::public(), *name),
+                value,
+                activation,
+            )?;
+        }
     }
     Ok(Value::Undefined)
 }
@@ -42,6 +75,14 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
 
     write.set_attributes(ClassAttributes::SEALED);
 
+    for (name, type_name) in PROPERTIES {
+        write.define_instance_trait(Trait::from_slot(
+            QName::new(Namespace::public(), *name),
+            QName::new(Namespace::public(), *type_name).into(),
+            None,
+        ));
+    }
+
     const CONSTANTS: &[(&str, &str)] = &[("KEY_DOWN", "keyDown"), ("KEY_UP", "keyUp")];
 
     write.define_public_constant_string_class_traits(CONSTANTS);
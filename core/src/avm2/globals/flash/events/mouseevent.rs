@@ -1,21 +1,121 @@
 use crate::avm2::activation::Activation;
 use crate::avm2::class::{Class, ClassAttributes};
-use crate::avm2::method::Method;
+use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::names::{Namespace, QName};
-use crate::avm2::object::Object;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
 use crate::avm2::value::Value;
 use crate::avm2::Error;
+use crate::display_object::TDisplayObject;
 use gc_arena::{GcCell, MutationContext};
+use swf::Twips;
+
+/// The properties of a `MouseEvent`, in the order that its constructor takes
+/// them after those of `Event`.
+const PROPERTIES: &[(&str, &str)] = &[
+    ("localX", "Number"),
+    ("localY", "Number"),
+    ("relatedObject", "flash.display::InteractiveObject"),
+    ("ctrlKey", "Boolean"),
+    ("altKey", "Boolean"),
+    ("shiftKey", "Boolean"),
+    ("buttonDown", "Boolean"),
+    ("delta", "int"),
+];
 
 /// Implements `flash.events.MouseEvent`'s instance constructor.
 pub fn instance_init<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
     args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        // Unlike other events, mouse events bubble unless told otherwise.
+        let event_args = [
+            args.get(0).cloned().unwrap_or(Value::Undefined),
+            args.get(1).cloned().unwrap_or_else(|| true.into()),
+            args.get(2).cloned().unwrap_or_else(|| false.into()),
+        ];
+        activation.super_init(this, &event_args)?;
+
+        for (index, (name, type_name)) in PROPERTIES.iter().enumerate() {
+            let value = args.get(index + 3).cloned();
+            let value = match *type_name {
+                "Number" => value
+                    .map(|v| v.coerce_to_number(activation))
+                    .transpose()?
+                    .unwrap_or(f64::NAN)
+                    .into(),
+                "Boolean" => value.map(|v| v.coerce_to_boolean()).unwrap_or(false).into(),
+                "int" => value
+                    .map(|v| v.coerce_to_i32(activation))
+                    .transpose()?
+                    .unwrap_or(0)
+                    .into(),
+                _ => value.unwrap_or(Value::Null),
+            };
+            this.set_property(
+                this,
+                &QName::new(Namespace::public(), *name),
+                value,
+                activation,
+            )?;
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// Convert the event's local coordinates to stage coordinates, using the
+/// transform of its target.
+fn stage_position<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<(f64, f64), Error> {
+    let local_x = this
+        .get_property(this, &QName::new(Namespace::public(), "localX"), activation)?
+        .coerce_to_number(activation)?;
+    let local_y = this
+        .get_property(this, &QName::new(Namespace::public(), "localY"), activation)?
+        .coerce_to_number(activation)?;
+
+    let target = this
+        .as_event()
+        .and_then(|evt| evt.target())
+        .and_then(|target| target.as_display_object());
+
+    match target {
+        Some(target) if local_x.is_finite() && local_y.is_finite() => {
+            let (x, y) =
+                target.local_to_global((Twips::from_pixels(local_x), Twips::from_pixels(local_y)));
+            Ok((x.to_pixels(), y.to_pixels()))
+        }
+        _ => Ok((local_x, local_y)),
+    }
+}
+
+/// Implements `stageX`'s getter.
+pub fn stage_x<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
     if let Some(this) = this {
-        activation.super_init(this, args)?; // Event uses the first three parameters
+        return Ok(stage_position(activation, this)?.0.into());
     }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `stageY`'s getter.
+pub fn stage_y<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return Ok(stage_position(activation, this)?.1.into());
+    }
+
     Ok(Value::Undefined)
 }
 
@@ -42,6 +142,25 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
 
     write.set_attributes(ClassAttributes::SEALED);
 
+    for (name, type_name) in PROPERTIES {
+        let (package, local_name) = type_name.rsplit_once("::").unwrap_or(("", type_name));
+        write.define_instance_trait(Trait::from_slot(
+            QName::new(Namespace::public(), *name),
+            QName::new(Namespace::package(package), local_name).into(),
+            None,
+        ));
+    }
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("stageX", Some(stage_x), None),
+        ("stageY", Some(stage_y), None),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
     const CONSTANTS: &[(&str, &str)] = &[
         ("CLICK", "click"),
         ("CONTEXT_MENU", "contextMenu"),
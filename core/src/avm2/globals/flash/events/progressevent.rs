@@ -2,7 +2,8 @@ use crate::avm2::activation::Activation;
 use crate::avm2::class::{Class, ClassAttributes};
 use crate::avm2::method::Method;
 use crate::avm2::names::{Namespace, QName};
-use crate::avm2::object::Object;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use gc_arena::{GcCell, MutationContext};
@@ -13,8 +14,32 @@ pub fn instance_init<'gc>(
     this: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    if let Some(this) = this {
+    if let Some(mut this) = this {
         activation.super_init(this, args)?; // Event uses the first three parameters
+
+        let bytes_loaded = args
+            .get(3)
+            .cloned()
+            .unwrap_or_else(|| 0.into())
+            .coerce_to_number(activation)?;
+        let bytes_total = args
+            .get(4)
+            .cloned()
+            .unwrap_or_else(|| 0.into())
+            .coerce_to_number(activation)?;
+
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "bytesLoaded"),
+            bytes_loaded.into(),
+            activation,
+        )?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "bytesTotal"),
+            bytes_total.into(),
+            activation,
+        )?;
     }
     Ok(Value::Undefined)
 }
@@ -42,6 +67,17 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
 
     write.set_attributes(ClassAttributes::SEALED);
 
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "bytesLoaded"),
+        QName::new(Namespace::public(), "Number").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "bytesTotal"),
+        QName::new(Namespace::public(), "Number").into(),
+        None,
+    ));
+
     const CONSTANTS: &[(&str, &str)] = &[
         ("PROGRESS", "progress"),
         ("SOCKET_DATA", "socketData"),
@@ -0,0 +1,129 @@
+//! `flash.events.ShaderEvent` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.events.ShaderEvent`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, args.get(0..3).unwrap_or(args))?; // Event uses the first three parameters
+
+        let properties: [(&str, Value<'gc>); 3] = [
+            ("bitmapData", args.get(3).cloned().unwrap_or(Value::Null)),
+            ("byteArray", args.get(4).cloned().unwrap_or(Value::Null)),
+            ("vector", args.get(5).cloned().unwrap_or(Value::Null)),
+        ];
+        for (name, value) in properties.iter() {
+            this.set_property(
+                this,
+                &QName::new(Namespace::public(), *name),
+                value.clone(),
+                activation,
+            )?;
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.events.ShaderEvent`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `clone`
+pub fn clone<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let (event_type, bubbles, cancelable) = match this.as_event() {
+            Some(evt) => (evt.event_type(), evt.is_bubbling(), evt.is_cancelable()),
+            None => return Ok(Value::Undefined),
+        };
+        let bitmap_data = this.get_property(
+            this,
+            &QName::new(Namespace::public(), "bitmapData"),
+            activation,
+        )?;
+        let byte_array = this.get_property(
+            this,
+            &QName::new(Namespace::public(), "byteArray"),
+            activation,
+        )?;
+        let vector =
+            this.get_property(this, &QName::new(Namespace::public(), "vector"), activation)?;
+        let shader_event_class = activation.avm2().classes().shaderevent;
+
+        return Ok(shader_event_class
+            .construct(
+                activation,
+                &[
+                    event_type.into(),
+                    bubbles.into(),
+                    cancelable.into(),
+                    bitmap_data,
+                    byte_array,
+                    vector,
+                ],
+            )?
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `ShaderEvent`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.events"), "ShaderEvent"),
+        Some(QName::new(Namespace::package("flash.events"), "Event").into()),
+        Method::from_builtin(instance_init, "<ShaderEvent instance initializer>", mc),
+        Method::from_builtin(class_init, "<ShaderEvent class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[("clone", clone)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "bitmapData"),
+        QName::new(Namespace::package("flash.display"), "BitmapData").into(),
+        Some(Value::Null),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "byteArray"),
+        QName::new(Namespace::package("flash.utils"), "ByteArray").into(),
+        Some(Value::Null),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "vector"),
+        QName::new(Namespace::public(), "Object").into(),
+        None,
+    ));
+
+    const CONSTANTS: &[(&str, &str)] = &[("COMPLETE", "complete")];
+
+    write.define_public_constant_string_class_traits(CONSTANTS);
+
+    class
+}
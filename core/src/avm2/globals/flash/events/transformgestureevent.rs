@@ -0,0 +1,92 @@
+//! `flash.events.TransformGestureEvent` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// The properties set by the constructor after those of `GestureEvent`, in
+/// order.
+const CONSTRUCTOR_PROPERTIES: &[&str] = &[
+    "scaleX", "scaleY", "rotation", "offsetX", "offsetY", "ctrlKey", "altKey", "shiftKey",
+];
+
+/// Implements `flash.events.TransformGestureEvent`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        // GestureEvent takes the type, bubbles, cancelable, phase and local position.
+        activation.super_init(this, &args[..args.len().min(6)])?;
+
+        for (name, value) in CONSTRUCTOR_PROPERTIES.iter().zip(args.iter().skip(6)) {
+            this.set_property(
+                this,
+                &QName::new(Namespace::public(), *name),
+                value.clone(),
+                activation,
+            )?;
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.events.TransformGestureEvent`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `TransformGestureEvent`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.events"), "TransformGestureEvent"),
+        Some(QName::new(Namespace::package("flash.events"), "GestureEvent").into()),
+        Method::from_builtin(
+            instance_init,
+            "<TransformGestureEvent instance initializer>",
+            mc,
+        ),
+        Method::from_builtin(class_init, "<TransformGestureEvent class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    for (name, default) in &[
+        ("scaleX", 1),
+        ("scaleY", 1),
+        ("rotation", 0),
+        ("offsetX", 0),
+        ("offsetY", 0),
+    ] {
+        write.define_instance_trait(Trait::from_slot(
+            QName::new(Namespace::public(), *name),
+            QName::new(Namespace::public(), "Number").into(),
+            Some((*default).into()),
+        ));
+    }
+
+    const CONSTANTS: &[(&str, &str)] = &[
+        ("GESTURE_PAN", "gesturePan"),
+        ("GESTURE_ROTATE", "gestureRotate"),
+        ("GESTURE_SWIPE", "gestureSwipe"),
+        ("GESTURE_ZOOM", "gestureZoom"),
+    ];
+
+    write.define_public_constant_string_class_traits(CONSTANTS);
+
+    class
+}
@@ -0,0 +1,108 @@
+//! `flash.events.UncaughtErrorEvent` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Multiname, Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.events.UncaughtErrorEvent`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let event_type = args
+            .get(0)
+            .cloned()
+            .unwrap_or_else(|| "uncaughtError".into());
+        let bubbles = args.get(1).cloned().unwrap_or_else(|| true.into());
+        let cancelable = args.get(2).cloned().unwrap_or_else(|| true.into());
+
+        activation.super_init(this, &[event_type, bubbles, cancelable])?;
+
+        let error = args.get(3).cloned().unwrap_or(Value::Null);
+
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "error"),
+            error,
+            activation,
+        )?;
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.events.UncaughtErrorEvent`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `clone`
+pub fn clone<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let (event_type, bubbles, cancelable) = match this.as_event() {
+            Some(evt) => (evt.event_type(), evt.is_bubbling(), evt.is_cancelable()),
+            None => return Ok(Value::Undefined),
+        };
+        let error =
+            this.get_property(this, &QName::new(Namespace::public(), "error"), activation)?;
+        let uncaught_error_event_class = activation.avm2().classes().uncaughterrorevent;
+
+        return Ok(uncaught_error_event_class
+            .construct(
+                activation,
+                &[event_type.into(), bubbles.into(), cancelable.into(), error],
+            )?
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `UncaughtErrorEvent`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.events"), "UncaughtErrorEvent"),
+        Some(QName::new(Namespace::package("flash.events"), "ErrorEvent").into()),
+        Method::from_builtin(
+            instance_init,
+            "<UncaughtErrorEvent instance initializer>",
+            mc,
+        ),
+        Method::from_builtin(class_init, "<UncaughtErrorEvent class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[("clone", clone)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "error"),
+        Multiname::any(),
+        None,
+    ));
+
+    const CONSTANTS: &[(&str, &str)] = &[("UNCAUGHT_ERROR", "uncaughtError")];
+
+    write.define_public_constant_string_class_traits(CONSTANTS);
+
+    class
+}
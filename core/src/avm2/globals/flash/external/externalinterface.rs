@@ -0,0 +1,143 @@
+//! `flash.external.ExternalInterface` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::Object;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::external::{Callback, Value as ExternalValue};
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.external.ExternalInterface`'s instance constructor.
+pub fn instance_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Err("ArgumentError: Error #2012: ExternalInterface class cannot be instantiated.".into())
+}
+
+/// Implements `flash.external.ExternalInterface`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+fn check_available(activation: &mut Activation<'_, '_, '_>) -> Result<(), Error> {
+    if activation.context.external_interface.available() {
+        Ok(())
+    } else {
+        Err("Error: Error #2067: The ExternalInterface is not available in this container. ExternalInterface requires Internet Explorer ActiveX, Firefox, Mozilla 1.7.5 and greater, or other browsers that support NPRuntime.".into())
+    }
+}
+
+/// Implements `ExternalInterface.available`'s getter
+pub fn available<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(activation.context.external_interface.available().into())
+}
+
+/// Implements `ExternalInterface.objectID`'s getter
+///
+/// The hosting element's ID isn't known to the player, so this is always
+/// `null`.
+pub fn object_id<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Null)
+}
+
+/// Implements `ExternalInterface.addCallback`
+pub fn add_callback<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    check_available(activation)?;
+
+    let name = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    match args.get(1) {
+        Some(Value::Object(method)) => {
+            activation
+                .context
+                .external_interface
+                .add_callback(name.to_string(), Callback::Avm2 { method: *method });
+        }
+        _ => activation.context.external_interface.remove_callback(&name),
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ExternalInterface.call`
+pub fn call<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    check_available(activation)?;
+
+    let name = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    if let Some(method) = activation.context.external_interface.get_method_for(&name) {
+        let mut external_args = Vec::with_capacity(args.len().saturating_sub(1));
+        for arg in args.get(1..).unwrap_or_default() {
+            external_args.push(ExternalValue::from_avm2(activation, arg.clone())?);
+        }
+
+        method
+            .call(&mut activation.context, &external_args)
+            .into_avm2(activation)
+    } else {
+        Ok(Value::Null)
+    }
+}
+
+/// Construct `ExternalInterface`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.external"), "ExternalInterface"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(
+            instance_init,
+            "<ExternalInterface instance initializer>",
+            mc,
+        ),
+        Method::from_builtin(class_init, "<ExternalInterface class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+
+    const PUBLIC_CLASS_PROPERTIES: &[(&str, Option<NativeMethodImpl>, Option<NativeMethodImpl>)] =
+        &[
+            ("available", Some(available), None),
+            ("objectID", Some(object_id), None),
+        ];
+    write.define_public_builtin_class_properties(mc, PUBLIC_CLASS_PROPERTIES);
+
+    const PUBLIC_CLASS_METHODS: &[(&str, NativeMethodImpl)] =
+        &[("addCallback", add_callback), ("call", call)];
+    write.define_public_builtin_class_methods(mc, PUBLIC_CLASS_METHODS);
+
+    class
+}
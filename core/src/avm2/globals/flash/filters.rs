@@ -0,0 +1,15 @@
+//! `flash.filters` namespace
+
+pub mod bevelfilter;
+pub mod bitmapfilter;
+pub mod bitmapfiltertype;
+pub mod blurfilter;
+pub mod colormatrixfilter;
+pub mod convolutionfilter;
+pub mod displacementmapfilter;
+pub mod displacementmapfiltermode;
+pub mod dropshadowfilter;
+pub mod glowfilter;
+pub mod gradientbevelfilter;
+pub mod gradientglowfilter;
+pub mod shaderfilter;
@@ -0,0 +1,655 @@
+//! `flash.filters.BitmapFilter` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::array::ArrayStorage;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::display::shader::{shader_state, shader_to_object};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{ArrayObject, Object, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::bitmap::filter::{BitmapFilter, DisplacementMapMode, FilterType, GradientStop};
+use gc_arena::{GcCell, MutationContext};
+
+/// Look up one of the `flash.filters` classes by name.
+fn filter_class<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    name: &'static str,
+) -> Result<Object<'gc>, Error> {
+    activation
+        .avm2()
+        .global_domain()
+        .get_defined_value(
+            activation,
+            QName::new(Namespace::package("flash.filters"), name),
+        )?
+        .coerce_to_object(activation)
+}
+
+/// Read a public property of a filter object as a number.
+fn number_property<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+    name: &'static str,
+) -> Result<f64, Error> {
+    object
+        .get_property(object, &QName::new(Namespace::public(), name), activation)?
+        .coerce_to_number(activation)
+}
+
+/// Read a public property of a filter object as a boolean.
+fn boolean_property<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+    name: &'static str,
+) -> Result<bool, Error> {
+    Ok(object
+        .get_property(object, &QName::new(Namespace::public(), name), activation)?
+        .coerce_to_boolean())
+}
+
+/// Read the `type` of a filter object.
+fn filter_type_property<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<FilterType, Error> {
+    let type_ = object
+        .get_property(object, &QName::new(Namespace::public(), "type"), activation)?
+        .coerce_to_string(activation)?;
+    Ok(match &*type_ {
+        "inner" => FilterType::Inner,
+        "outer" => FilterType::Outer,
+        _ => FilterType::Full,
+    })
+}
+
+/// Read a public array property of a filter object, treating anything that
+/// isn't an array as empty.
+fn array_property<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+    name: &'static str,
+) -> Result<Vec<Value<'gc>>, Error> {
+    let value = object.get_property(object, &QName::new(Namespace::public(), name), activation)?;
+    Ok(match value {
+        Value::Object(array) => array
+            .as_array_storage()
+            .map(|array| {
+                array
+                    .iter()
+                    .map(|value| value.unwrap_or(Value::Undefined))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    })
+}
+
+/// Read the `colors`, `alphas` and `ratios` of a gradient filter object as
+/// a gradient.
+fn gradient_property<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<Vec<GradientStop>, Error> {
+    let colors = array_property(activation, object, "colors")?;
+    let alphas = array_property(activation, object, "alphas")?;
+    let ratios = array_property(activation, object, "ratios")?;
+
+    let mut gradient = Vec::with_capacity(colors.len());
+    for ((color, alpha), ratio) in colors.into_iter().zip(alphas).zip(ratios) {
+        gradient.push(GradientStop {
+            ratio: ratio.coerce_to_u32(activation)?.min(255) as u8,
+            color: color.coerce_to_u32(activation)?,
+            alpha: alpha.coerce_to_number(activation)?,
+        });
+    }
+    Ok(gradient)
+}
+
+/// Create an array of one of the values of each of a gradient's colors.
+fn gradient_array<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    gradient: &[GradientStop],
+    value: impl Fn(&GradientStop) -> Value<'gc>,
+) -> Result<Value<'gc>, Error> {
+    let storage = ArrayStorage::from_storage(gradient.iter().map(|s| Some(value(s))).collect());
+    Ok(ArrayObject::from_storage(activation, storage)?.into())
+}
+
+fn filter_type_name(filter_type: FilterType) -> &'static str {
+    match filter_type {
+        FilterType::Inner => "inner",
+        FilterType::Outer => "outer",
+        FilterType::Full => "full",
+    }
+}
+
+/// Get the software filter for a filter object, if it's one that can be
+/// drawn.
+pub fn object_to_bitmap_filter<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<Option<BitmapFilter>, Error> {
+    let blur_filter = filter_class(activation, "BlurFilter")?;
+    if object.is_of_type(blur_filter, activation)? {
+        return Ok(Some(BitmapFilter::Blur {
+            blur_x: number_property(activation, object, "blurX")?,
+            blur_y: number_property(activation, object, "blurY")?,
+            quality: number_property(activation, object, "quality")? as i32,
+        }));
+    }
+
+    let color_matrix_filter = filter_class(activation, "ColorMatrixFilter")?;
+    if object.is_of_type(color_matrix_filter, activation)? {
+        let mut matrix = [0.0; 20];
+        for (value, item) in matrix
+            .iter_mut()
+            .zip(array_property(activation, object, "matrix")?)
+        {
+            *value = item.coerce_to_number(activation)?;
+        }
+        return Ok(Some(BitmapFilter::ColorMatrix { matrix }));
+    }
+
+    let convolution_filter = filter_class(activation, "ConvolutionFilter")?;
+    if object.is_of_type(convolution_filter, activation)? {
+        let mut matrix = Vec::new();
+        for item in array_property(activation, object, "matrix")? {
+            matrix.push(item.coerce_to_number(activation)?);
+        }
+        return Ok(Some(BitmapFilter::Convolution {
+            matrix_x: number_property(activation, object, "matrixX")?
+                .max(0.0)
+                .min(15.0) as u8,
+            matrix_y: number_property(activation, object, "matrixY")?
+                .max(0.0)
+                .min(15.0) as u8,
+            matrix,
+            divisor: number_property(activation, object, "divisor")?,
+            bias: number_property(activation, object, "bias")?,
+            preserve_alpha: boolean_property(activation, object, "preserveAlpha")?,
+            clamp: boolean_property(activation, object, "clamp")?,
+            color: number_property(activation, object, "color")? as u32,
+            alpha: number_property(activation, object, "alpha")?,
+        }));
+    }
+
+    let displacement_map_filter = filter_class(activation, "DisplacementMapFilter")?;
+    if object.is_of_type(displacement_map_filter, activation)? {
+        let map = object.get_property(
+            object,
+            &QName::new(Namespace::public(), "mapBitmap"),
+            activation,
+        )?;
+        let map = match map {
+            Value::Object(map) => match map.as_bitmap_data() {
+                Some(map) => map,
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        let map = {
+            let map = map.read();
+            map.filter_image(0, 0, map.width(), map.height())
+        };
+        let map_point = match object.get_property(
+            object,
+            &QName::new(Namespace::public(), "mapPoint"),
+            activation,
+        )? {
+            Value::Object(point) => (
+                number_property(activation, point, "x")? as i32,
+                number_property(activation, point, "y")? as i32,
+            ),
+            _ => (0, 0),
+        };
+        let mode = object
+            .get_property(object, &QName::new(Namespace::public(), "mode"), activation)?
+            .coerce_to_string(activation)?;
+        return Ok(Some(BitmapFilter::DisplacementMap {
+            map,
+            map_point,
+            component_x: number_property(activation, object, "componentX")? as i32,
+            component_y: number_property(activation, object, "componentY")? as i32,
+            scale_x: number_property(activation, object, "scaleX")?,
+            scale_y: number_property(activation, object, "scaleY")?,
+            mode: match &*mode {
+                "clamp" => DisplacementMapMode::Clamp,
+                "ignore" => DisplacementMapMode::Ignore,
+                "color" => DisplacementMapMode::Color,
+                _ => DisplacementMapMode::Wrap,
+            },
+            color: number_property(activation, object, "color")? as u32,
+            alpha: number_property(activation, object, "alpha")?,
+        }));
+    }
+
+    let drop_shadow_filter = filter_class(activation, "DropShadowFilter")?;
+    if object.is_of_type(drop_shadow_filter, activation)? {
+        return Ok(Some(BitmapFilter::Shadow {
+            color: number_property(activation, object, "color")? as u32,
+            alpha: number_property(activation, object, "alpha")?,
+            blur_x: number_property(activation, object, "blurX")?,
+            blur_y: number_property(activation, object, "blurY")?,
+            strength: number_property(activation, object, "strength")?,
+            quality: number_property(activation, object, "quality")? as i32,
+            distance: number_property(activation, object, "distance")?,
+            angle: number_property(activation, object, "angle")?,
+            inner: boolean_property(activation, object, "inner")?,
+            knockout: boolean_property(activation, object, "knockout")?,
+            hide_object: boolean_property(activation, object, "hideObject")?,
+        }));
+    }
+
+    let glow_filter = filter_class(activation, "GlowFilter")?;
+    if object.is_of_type(glow_filter, activation)? {
+        return Ok(Some(BitmapFilter::Glow {
+            color: number_property(activation, object, "color")? as u32,
+            alpha: number_property(activation, object, "alpha")?,
+            blur_x: number_property(activation, object, "blurX")?,
+            blur_y: number_property(activation, object, "blurY")?,
+            strength: number_property(activation, object, "strength")?,
+            quality: number_property(activation, object, "quality")? as i32,
+            inner: boolean_property(activation, object, "inner")?,
+            knockout: boolean_property(activation, object, "knockout")?,
+        }));
+    }
+
+    let bevel_filter = filter_class(activation, "BevelFilter")?;
+    if object.is_of_type(bevel_filter, activation)? {
+        return Ok(Some(BitmapFilter::Bevel {
+            highlight_color: number_property(activation, object, "highlightColor")? as u32,
+            highlight_alpha: number_property(activation, object, "highlightAlpha")?,
+            shadow_color: number_property(activation, object, "shadowColor")? as u32,
+            shadow_alpha: number_property(activation, object, "shadowAlpha")?,
+            blur_x: number_property(activation, object, "blurX")?,
+            blur_y: number_property(activation, object, "blurY")?,
+            strength: number_property(activation, object, "strength")?,
+            quality: number_property(activation, object, "quality")? as i32,
+            distance: number_property(activation, object, "distance")?,
+            angle: number_property(activation, object, "angle")?,
+            filter_type: filter_type_property(activation, object)?,
+            knockout: boolean_property(activation, object, "knockout")?,
+        }));
+    }
+
+    let gradient_glow_filter = filter_class(activation, "GradientGlowFilter")?;
+    if object.is_of_type(gradient_glow_filter, activation)? {
+        return Ok(Some(BitmapFilter::GradientGlow {
+            gradient: gradient_property(activation, object)?,
+            blur_x: number_property(activation, object, "blurX")?,
+            blur_y: number_property(activation, object, "blurY")?,
+            strength: number_property(activation, object, "strength")?,
+            quality: number_property(activation, object, "quality")? as i32,
+            distance: number_property(activation, object, "distance")?,
+            angle: number_property(activation, object, "angle")?,
+            filter_type: filter_type_property(activation, object)?,
+            knockout: boolean_property(activation, object, "knockout")?,
+        }));
+    }
+
+    let gradient_bevel_filter = filter_class(activation, "GradientBevelFilter")?;
+    if object.is_of_type(gradient_bevel_filter, activation)? {
+        return Ok(Some(BitmapFilter::GradientBevel {
+            gradient: gradient_property(activation, object)?,
+            blur_x: number_property(activation, object, "blurX")?,
+            blur_y: number_property(activation, object, "blurY")?,
+            strength: number_property(activation, object, "strength")?,
+            quality: number_property(activation, object, "quality")? as i32,
+            distance: number_property(activation, object, "distance")?,
+            angle: number_property(activation, object, "angle")?,
+            filter_type: filter_type_property(activation, object)?,
+            knockout: boolean_property(activation, object, "knockout")?,
+        }));
+    }
+
+    let shader_filter = filter_class(activation, "ShaderFilter")?;
+    if object.is_of_type(shader_filter, activation)? {
+        let shader = match object.get_property(
+            object,
+            &QName::new(Namespace::public(), "shader"),
+            activation,
+        )? {
+            Value::Object(shader) => shader,
+            _ => return Ok(None),
+        };
+        let state = match shader_state(activation, shader)? {
+            Some(state) => state,
+            None => return Ok(None),
+        };
+        let left = number_property(activation, object, "leftExtension")? as i32;
+        let right = number_property(activation, object, "rightExtension")? as i32;
+        let top = number_property(activation, object, "topExtension")? as i32;
+        let bottom = number_property(activation, object, "bottomExtension")? as i32;
+        return Ok(Some(BitmapFilter::Shader {
+            shader: state.shader,
+            bytecode: state.bytecode,
+            params: state.params,
+            // The first input is the image being filtered.
+            inputs: state.inputs.into_iter().skip(1).collect(),
+            padding: (left.max(right).max(0), top.max(bottom).max(0)),
+        }));
+    }
+
+    Ok(None)
+}
+
+/// Create a filter object for a software filter, as read back from the
+/// `filters` of a display object.
+///
+/// Returns `None` for filters that can't be turned back into an object.
+pub fn bitmap_filter_to_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    filter: &BitmapFilter,
+) -> Result<Option<Object<'gc>>, Error> {
+    let (class, args) = match filter {
+        BitmapFilter::Blur {
+            blur_x,
+            blur_y,
+            quality,
+        } => (
+            filter_class(activation, "BlurFilter")?,
+            vec![(*blur_x).into(), (*blur_y).into(), (*quality).into()],
+        ),
+        BitmapFilter::ColorMatrix { matrix } => {
+            let matrix =
+                ArrayStorage::from_storage(matrix.iter().map(|&v| Some(v.into())).collect());
+            (
+                filter_class(activation, "ColorMatrixFilter")?,
+                vec![ArrayObject::from_storage(activation, matrix)?.into()],
+            )
+        }
+        BitmapFilter::Convolution {
+            matrix_x,
+            matrix_y,
+            matrix,
+            divisor,
+            bias,
+            preserve_alpha,
+            clamp,
+            color,
+            alpha,
+        } => {
+            let matrix =
+                ArrayStorage::from_storage(matrix.iter().map(|&v| Some(v.into())).collect());
+            (
+                filter_class(activation, "ConvolutionFilter")?,
+                vec![
+                    (*matrix_x).into(),
+                    (*matrix_y).into(),
+                    ArrayObject::from_storage(activation, matrix)?.into(),
+                    (*divisor).into(),
+                    (*bias).into(),
+                    (*preserve_alpha).into(),
+                    (*clamp).into(),
+                    (*color).into(),
+                    (*alpha).into(),
+                ],
+            )
+        }
+        BitmapFilter::DisplacementMap {
+            map,
+            map_point,
+            component_x,
+            component_y,
+            scale_x,
+            scale_y,
+            mode,
+            color,
+            alpha,
+        } => {
+            // The map was copied when the filter was set, so it's read back
+            // as a new bitmap.
+            let bitmapdata_class = activation.avm2().classes().bitmapdata;
+            let map_bitmap = bitmapdata_class.construct(
+                activation,
+                &[
+                    map.width().into(),
+                    map.height().into(),
+                    true.into(),
+                    0.into(),
+                ],
+            )?;
+            if let Some(map_data) = map_bitmap.as_bitmap_data() {
+                map_data
+                    .write(activation.context.gc_context)
+                    .set_filter_image(map, 0, 0);
+            }
+            let point_class = activation.avm2().classes().point;
+            let map_point =
+                point_class.construct(activation, &[map_point.0.into(), map_point.1.into()])?;
+            let mode = match mode {
+                DisplacementMapMode::Wrap => "wrap",
+                DisplacementMapMode::Clamp => "clamp",
+                DisplacementMapMode::Ignore => "ignore",
+                DisplacementMapMode::Color => "color",
+            };
+            (
+                filter_class(activation, "DisplacementMapFilter")?,
+                vec![
+                    map_bitmap.into(),
+                    map_point.into(),
+                    (*component_x).into(),
+                    (*component_y).into(),
+                    (*scale_x).into(),
+                    (*scale_y).into(),
+                    mode.into(),
+                    (*color).into(),
+                    (*alpha).into(),
+                ],
+            )
+        }
+        BitmapFilter::Shadow {
+            color,
+            alpha,
+            blur_x,
+            blur_y,
+            strength,
+            quality,
+            distance,
+            angle,
+            inner,
+            knockout,
+            hide_object,
+        } => (
+            filter_class(activation, "DropShadowFilter")?,
+            vec![
+                (*distance).into(),
+                (*angle).into(),
+                (*color).into(),
+                (*alpha).into(),
+                (*blur_x).into(),
+                (*blur_y).into(),
+                (*strength).into(),
+                (*quality).into(),
+                (*inner).into(),
+                (*knockout).into(),
+                (*hide_object).into(),
+            ],
+        ),
+        BitmapFilter::Glow {
+            color,
+            alpha,
+            blur_x,
+            blur_y,
+            strength,
+            quality,
+            inner,
+            knockout,
+        } => (
+            filter_class(activation, "GlowFilter")?,
+            vec![
+                (*color).into(),
+                (*alpha).into(),
+                (*blur_x).into(),
+                (*blur_y).into(),
+                (*strength).into(),
+                (*quality).into(),
+                (*inner).into(),
+                (*knockout).into(),
+            ],
+        ),
+        BitmapFilter::Bevel {
+            highlight_color,
+            highlight_alpha,
+            shadow_color,
+            shadow_alpha,
+            blur_x,
+            blur_y,
+            strength,
+            quality,
+            distance,
+            angle,
+            filter_type,
+            knockout,
+        } => (
+            filter_class(activation, "BevelFilter")?,
+            vec![
+                (*distance).into(),
+                (*angle).into(),
+                (*highlight_color).into(),
+                (*highlight_alpha).into(),
+                (*shadow_color).into(),
+                (*shadow_alpha).into(),
+                (*blur_x).into(),
+                (*blur_y).into(),
+                (*strength).into(),
+                (*quality).into(),
+                filter_type_name(*filter_type).into(),
+                (*knockout).into(),
+            ],
+        ),
+        BitmapFilter::GradientGlow {
+            gradient,
+            blur_x,
+            blur_y,
+            strength,
+            quality,
+            distance,
+            angle,
+            filter_type,
+            knockout,
+        }
+        | BitmapFilter::GradientBevel {
+            gradient,
+            blur_x,
+            blur_y,
+            strength,
+            quality,
+            distance,
+            angle,
+            filter_type,
+            knockout,
+        } => {
+            let class = if let BitmapFilter::GradientGlow { .. } = filter {
+                filter_class(activation, "GradientGlowFilter")?
+            } else {
+                filter_class(activation, "GradientBevelFilter")?
+            };
+            (
+                class,
+                vec![
+                    (*distance).into(),
+                    (*angle).into(),
+                    gradient_array(activation, gradient, |stop| stop.color.into())?,
+                    gradient_array(activation, gradient, |stop| stop.alpha.into())?,
+                    gradient_array(activation, gradient, |stop| stop.ratio.into())?,
+                    (*blur_x).into(),
+                    (*blur_y).into(),
+                    (*strength).into(),
+                    (*quality).into(),
+                    filter_type_name(*filter_type).into(),
+                    (*knockout).into(),
+                ],
+            )
+        }
+        BitmapFilter::Shader {
+            bytecode,
+            params,
+            padding,
+            ..
+        } => {
+            // The images given to the shader's other inputs aren't read back.
+            let shader = shader_to_object(activation, bytecode, params)?;
+            let mut object = filter_class(activation, "ShaderFilter")?
+                .construct(activation, &[shader.into()])?;
+            let extensions = [
+                ("leftExtension", padding.0),
+                ("rightExtension", padding.0),
+                ("topExtension", padding.1),
+                ("bottomExtension", padding.1),
+            ];
+            for (name, value) in extensions.iter() {
+                object.set_property(
+                    object,
+                    &QName::new(Namespace::public(), *name),
+                    (*value).into(),
+                    activation,
+                )?;
+            }
+            return Ok(Some(object));
+        }
+    };
+
+    Ok(Some(class.construct(activation, &args)?))
+}
+
+/// Implements `flash.filters.BitmapFilter`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.filters.BitmapFilter`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `BitmapFilter.clone`.
+pub fn clone<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(filter) = object_to_bitmap_filter(activation, this)? {
+            if let Some(cloned) = bitmap_filter_to_object(activation, &filter)? {
+                return Ok(cloned.into());
+            }
+        }
+    }
+
+    Ok(Value::Null)
+}
+
+/// Construct `BitmapFilter`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.filters"), "BitmapFilter"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<BitmapFilter instance initializer>", mc),
+        Method::from_builtin(class_init, "<BitmapFilter class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[("clone", clone)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    class
+}
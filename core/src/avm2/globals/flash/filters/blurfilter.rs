@@ -0,0 +1,92 @@
+//! `flash.filters.BlurFilter` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.filters.BlurFilter`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let blur_x = args
+            .get(0)
+            .cloned()
+            .unwrap_or_else(|| 4.0.into())
+            .coerce_to_number(activation)?;
+        let blur_y = args
+            .get(1)
+            .cloned()
+            .unwrap_or_else(|| 4.0.into())
+            .coerce_to_number(activation)?;
+        let quality = args
+            .get(2)
+            .cloned()
+            .unwrap_or_else(|| 1.into())
+            .coerce_to_i32(activation)?;
+
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "blurX"),
+            blur_x.into(),
+            activation,
+        )?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "blurY"),
+            blur_y.into(),
+            activation,
+        )?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "quality"),
+            quality.into(),
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.filters.BlurFilter`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `BlurFilter`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.filters"), "BlurFilter"),
+        Some(QName::new(Namespace::package("flash.filters"), "BitmapFilter").into()),
+        Method::from_builtin(instance_init, "<BlurFilter instance initializer>", mc),
+        Method::from_builtin(class_init, "<BlurFilter class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+
+    write.define_public_slot_number_instance_traits(&[("blurX", Some(4.0)), ("blurY", Some(4.0))]);
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "quality"),
+        QName::new(Namespace::public(), "int").into(),
+        Some(1.into()),
+    ));
+
+    class
+}
@@ -0,0 +1,144 @@
+//! `flash.filters.ConvolutionFilter` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.filters.ConvolutionFilter`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let matrix_x = args
+            .get(0)
+            .cloned()
+            .unwrap_or_else(|| 0.0.into())
+            .coerce_to_number(activation)?;
+        let matrix_y = args
+            .get(1)
+            .cloned()
+            .unwrap_or_else(|| 0.0.into())
+            .coerce_to_number(activation)?;
+        let matrix = args.get(2).cloned().unwrap_or(Value::Null);
+        let divisor = args
+            .get(3)
+            .cloned()
+            .unwrap_or_else(|| 1.0.into())
+            .coerce_to_number(activation)?;
+        let bias = args
+            .get(4)
+            .cloned()
+            .unwrap_or_else(|| 0.0.into())
+            .coerce_to_number(activation)?;
+        let preserve_alpha = args
+            .get(5)
+            .cloned()
+            .unwrap_or_else(|| true.into())
+            .coerce_to_boolean();
+        let clamp = args
+            .get(6)
+            .cloned()
+            .unwrap_or_else(|| true.into())
+            .coerce_to_boolean();
+        let color = args
+            .get(7)
+            .cloned()
+            .unwrap_or_else(|| 0.into())
+            .coerce_to_u32(activation)?;
+        let alpha = args
+            .get(8)
+            .cloned()
+            .unwrap_or_else(|| 0.0.into())
+            .coerce_to_number(activation)?;
+
+        let properties: [(&str, Value<'gc>); 9] = [
+            ("matrixX", matrix_x.into()),
+            ("matrixY", matrix_y.into()),
+            ("matrix", matrix),
+            ("divisor", divisor.into()),
+            ("bias", bias.into()),
+            ("preserveAlpha", preserve_alpha.into()),
+            ("clamp", clamp.into()),
+            ("color", color.into()),
+            ("alpha", alpha.into()),
+        ];
+        for (name, value) in properties.iter() {
+            this.set_property(
+                this,
+                &QName::new(Namespace::public(), *name),
+                value.clone(),
+                activation,
+            )?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.filters.ConvolutionFilter`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `ConvolutionFilter`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.filters"), "ConvolutionFilter"),
+        Some(QName::new(Namespace::package("flash.filters"), "BitmapFilter").into()),
+        Method::from_builtin(
+            instance_init,
+            "<ConvolutionFilter instance initializer>",
+            mc,
+        ),
+        Method::from_builtin(class_init, "<ConvolutionFilter class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+
+    write.define_public_slot_number_instance_traits(&[
+        ("matrixX", Some(0.0)),
+        ("matrixY", Some(0.0)),
+        ("divisor", Some(1.0)),
+        ("bias", Some(0.0)),
+        ("alpha", Some(0.0)),
+    ]);
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "matrix"),
+        QName::new(Namespace::public(), "Array").into(),
+        Some(Value::Null),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "preserveAlpha"),
+        QName::new(Namespace::public(), "Boolean").into(),
+        Some(true.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "clamp"),
+        QName::new(Namespace::public(), "Boolean").into(),
+        Some(true.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "color"),
+        QName::new(Namespace::public(), "uint").into(),
+        Some(0u32.into()),
+    ));
+
+    class
+}
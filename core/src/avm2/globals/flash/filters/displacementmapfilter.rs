@@ -0,0 +1,148 @@
+//! `flash.filters.DisplacementMapFilter` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.filters.DisplacementMapFilter`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let map_bitmap = args.get(0).cloned().unwrap_or(Value::Null);
+        let map_point = args.get(1).cloned().unwrap_or(Value::Null);
+        let component_x = args
+            .get(2)
+            .cloned()
+            .unwrap_or_else(|| 0.into())
+            .coerce_to_u32(activation)?;
+        let component_y = args
+            .get(3)
+            .cloned()
+            .unwrap_or_else(|| 0.into())
+            .coerce_to_u32(activation)?;
+        let scale_x = args
+            .get(4)
+            .cloned()
+            .unwrap_or_else(|| 0.0.into())
+            .coerce_to_number(activation)?;
+        let scale_y = args
+            .get(5)
+            .cloned()
+            .unwrap_or_else(|| 0.0.into())
+            .coerce_to_number(activation)?;
+        let mode = args
+            .get(6)
+            .cloned()
+            .unwrap_or_else(|| "wrap".into())
+            .coerce_to_string(activation)?;
+        let color = args
+            .get(7)
+            .cloned()
+            .unwrap_or_else(|| 0.into())
+            .coerce_to_u32(activation)?;
+        let alpha = args
+            .get(8)
+            .cloned()
+            .unwrap_or_else(|| 0.0.into())
+            .coerce_to_number(activation)?;
+
+        let properties: [(&str, Value<'gc>); 9] = [
+            ("mapBitmap", map_bitmap),
+            ("mapPoint", map_point),
+            ("componentX", component_x.into()),
+            ("componentY", component_y.into()),
+            ("scaleX", scale_x.into()),
+            ("scaleY", scale_y.into()),
+            ("mode", mode.into()),
+            ("color", color.into()),
+            ("alpha", alpha.into()),
+        ];
+        for (name, value) in properties.iter() {
+            this.set_property(
+                this,
+                &QName::new(Namespace::public(), *name),
+                value.clone(),
+                activation,
+            )?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.filters.DisplacementMapFilter`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `DisplacementMapFilter`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.filters"), "DisplacementMapFilter"),
+        Some(QName::new(Namespace::package("flash.filters"), "BitmapFilter").into()),
+        Method::from_builtin(
+            instance_init,
+            "<DisplacementMapFilter instance initializer>",
+            mc,
+        ),
+        Method::from_builtin(class_init, "<DisplacementMapFilter class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+
+    write.define_public_slot_number_instance_traits(&[
+        ("scaleX", Some(0.0)),
+        ("scaleY", Some(0.0)),
+        ("alpha", Some(0.0)),
+    ]);
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "mapBitmap"),
+        QName::new(Namespace::package("flash.display"), "BitmapData").into(),
+        Some(Value::Null),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "mapPoint"),
+        QName::new(Namespace::package("flash.geom"), "Point").into(),
+        Some(Value::Null),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "componentX"),
+        QName::new(Namespace::public(), "uint").into(),
+        Some(0u32.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "componentY"),
+        QName::new(Namespace::public(), "uint").into(),
+        Some(0u32.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "mode"),
+        QName::new(Namespace::public(), "String").into(),
+        Some("wrap".into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "color"),
+        QName::new(Namespace::public(), "uint").into(),
+        Some(0u32.into()),
+    ));
+
+    class
+}
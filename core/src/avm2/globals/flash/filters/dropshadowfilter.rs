@@ -0,0 +1,162 @@
+//! `flash.filters.DropShadowFilter` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.filters.DropShadowFilter`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let distance = args
+            .get(0)
+            .cloned()
+            .unwrap_or_else(|| 4.0.into())
+            .coerce_to_number(activation)?;
+        let angle = args
+            .get(1)
+            .cloned()
+            .unwrap_or_else(|| 45.0.into())
+            .coerce_to_number(activation)?;
+        let color = args
+            .get(2)
+            .cloned()
+            .unwrap_or_else(|| 0.into())
+            .coerce_to_u32(activation)?;
+        let alpha = args
+            .get(3)
+            .cloned()
+            .unwrap_or_else(|| 1.0.into())
+            .coerce_to_number(activation)?;
+        let blur_x = args
+            .get(4)
+            .cloned()
+            .unwrap_or_else(|| 4.0.into())
+            .coerce_to_number(activation)?;
+        let blur_y = args
+            .get(5)
+            .cloned()
+            .unwrap_or_else(|| 4.0.into())
+            .coerce_to_number(activation)?;
+        let strength = args
+            .get(6)
+            .cloned()
+            .unwrap_or_else(|| 1.0.into())
+            .coerce_to_number(activation)?;
+        let quality = args
+            .get(7)
+            .cloned()
+            .unwrap_or_else(|| 1.into())
+            .coerce_to_i32(activation)?;
+        let inner = args
+            .get(8)
+            .cloned()
+            .unwrap_or_else(|| false.into())
+            .coerce_to_boolean();
+        let knockout = args
+            .get(9)
+            .cloned()
+            .unwrap_or_else(|| false.into())
+            .coerce_to_boolean();
+        let hide_object = args
+            .get(10)
+            .cloned()
+            .unwrap_or_else(|| false.into())
+            .coerce_to_boolean();
+
+        let properties: [(&str, Value<'gc>); 11] = [
+            ("distance", distance.into()),
+            ("angle", angle.into()),
+            ("color", color.into()),
+            ("alpha", alpha.into()),
+            ("blurX", blur_x.into()),
+            ("blurY", blur_y.into()),
+            ("strength", strength.into()),
+            ("quality", quality.into()),
+            ("inner", inner.into()),
+            ("knockout", knockout.into()),
+            ("hideObject", hide_object.into()),
+        ];
+        for (name, value) in properties.iter() {
+            this.set_property(
+                this,
+                &QName::new(Namespace::public(), *name),
+                value.clone(),
+                activation,
+            )?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.filters.DropShadowFilter`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `DropShadowFilter`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.filters"), "DropShadowFilter"),
+        Some(QName::new(Namespace::package("flash.filters"), "BitmapFilter").into()),
+        Method::from_builtin(instance_init, "<DropShadowFilter instance initializer>", mc),
+        Method::from_builtin(class_init, "<DropShadowFilter class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+
+    write.define_public_slot_number_instance_traits(&[
+        ("distance", Some(4.0)),
+        ("angle", Some(45.0)),
+        ("alpha", Some(1.0)),
+        ("blurX", Some(4.0)),
+        ("blurY", Some(4.0)),
+        ("strength", Some(1.0)),
+    ]);
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "color"),
+        QName::new(Namespace::public(), "uint").into(),
+        Some(0u32.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "quality"),
+        QName::new(Namespace::public(), "int").into(),
+        Some(1.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "inner"),
+        QName::new(Namespace::public(), "Boolean").into(),
+        Some(false.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "knockout"),
+        QName::new(Namespace::public(), "Boolean").into(),
+        Some(false.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "hideObject"),
+        QName::new(Namespace::public(), "Boolean").into(),
+        Some(false.into()),
+    ));
+
+    class
+}
@@ -0,0 +1,137 @@
+//! `flash.filters.GlowFilter` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.filters.GlowFilter`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let color = args
+            .get(0)
+            .cloned()
+            .unwrap_or_else(|| 0xFF0000.into())
+            .coerce_to_u32(activation)?;
+        let alpha = args
+            .get(1)
+            .cloned()
+            .unwrap_or_else(|| 1.0.into())
+            .coerce_to_number(activation)?;
+        let blur_x = args
+            .get(2)
+            .cloned()
+            .unwrap_or_else(|| 6.0.into())
+            .coerce_to_number(activation)?;
+        let blur_y = args
+            .get(3)
+            .cloned()
+            .unwrap_or_else(|| 6.0.into())
+            .coerce_to_number(activation)?;
+        let strength = args
+            .get(4)
+            .cloned()
+            .unwrap_or_else(|| 2.0.into())
+            .coerce_to_number(activation)?;
+        let quality = args
+            .get(5)
+            .cloned()
+            .unwrap_or_else(|| 1.into())
+            .coerce_to_i32(activation)?;
+        let inner = args
+            .get(6)
+            .cloned()
+            .unwrap_or_else(|| false.into())
+            .coerce_to_boolean();
+        let knockout = args
+            .get(7)
+            .cloned()
+            .unwrap_or_else(|| false.into())
+            .coerce_to_boolean();
+
+        let properties: [(&str, Value<'gc>); 8] = [
+            ("color", color.into()),
+            ("alpha", alpha.into()),
+            ("blurX", blur_x.into()),
+            ("blurY", blur_y.into()),
+            ("strength", strength.into()),
+            ("quality", quality.into()),
+            ("inner", inner.into()),
+            ("knockout", knockout.into()),
+        ];
+        for (name, value) in properties.iter() {
+            this.set_property(
+                this,
+                &QName::new(Namespace::public(), *name),
+                value.clone(),
+                activation,
+            )?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.filters.GlowFilter`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `GlowFilter`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.filters"), "GlowFilter"),
+        Some(QName::new(Namespace::package("flash.filters"), "BitmapFilter").into()),
+        Method::from_builtin(instance_init, "<GlowFilter instance initializer>", mc),
+        Method::from_builtin(class_init, "<GlowFilter class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+
+    write.define_public_slot_number_instance_traits(&[
+        ("alpha", Some(1.0)),
+        ("blurX", Some(6.0)),
+        ("blurY", Some(6.0)),
+        ("strength", Some(2.0)),
+    ]);
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "color"),
+        QName::new(Namespace::public(), "uint").into(),
+        Some(0xFF0000u32.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "quality"),
+        QName::new(Namespace::public(), "int").into(),
+        Some(1.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "inner"),
+        QName::new(Namespace::public(), "Boolean").into(),
+        Some(false.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "knockout"),
+        QName::new(Namespace::public(), "Boolean").into(),
+        Some(false.into()),
+    ));
+
+    class
+}
@@ -1,4 +1,8 @@
 //! `flash.geom` namespace
 
+pub mod matrix3d;
+pub mod perspectiveprojection;
 pub mod point;
 pub mod rectangle;
+pub mod transform;
+pub mod vector3d;
@@ -0,0 +1,770 @@
+//! `flash.geom.Matrix3D` builtin/prototype
+
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::geom::vector3d::{self, create_vector3d};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::object::{matrix3d_allocator, Matrix3DObject, VectorObject};
+use crate::avm2::vector::VectorStorage;
+use crate::avm2::{Activation, Error, Namespace, Object, QName, TObject, Value};
+use crate::matrix3d::{Components, Matrix3D};
+use gc_arena::{GcCell, MutationContext};
+
+/// The only orientation style that `decompose` and `recompose` support.
+const EULER_ANGLES: &str = "eulerAngles";
+
+/// Implements `flash.geom.Matrix3D`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+
+        if let Some(Value::Object(_)) = args.get(0) {
+            set_raw_data(activation, Some(this), args)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.geom.Matrix3D`'s native instance constructor.
+pub fn native_instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.geom.Matrix3D`'s class initializer.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Read the matrix of the `Matrix3D` passed as an argument.
+pub fn arg_matrix<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+) -> Result<Matrix3D, Error> {
+    args.get(index)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?
+        .as_matrix3d()
+        .map(|matrix| matrix.matrix())
+        .ok_or_else(|| "TypeError: Error #1034: Type Coercion failed: not a Matrix3D".into())
+}
+
+/// Read the components of the `Vector3D` passed as an argument, ignoring
+/// `w`.
+fn arg_vector3d<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+) -> Result<[f64; 3], Error> {
+    let object = args
+        .get(index)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?;
+    let [x, y, z, _] = vector3d::components(object, activation)?;
+    Ok([x, y, z])
+}
+
+fn arg_number<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+) -> Result<f64, Error> {
+    args.get(index)
+        .unwrap_or(&0.into())
+        .coerce_to_number(activation)
+}
+
+/// Read the numbers in the `Vector.<Number>` passed as an argument.
+fn arg_numbers<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+) -> Result<(Object<'gc>, Vec<f64>), Error> {
+    let object = args
+        .get(index)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?;
+    let values: Vec<Value<'gc>> = match object.as_vector_storage() {
+        Some(vector) => vector.iter().collect(),
+        None => return Err("TypeError: Error #1034: Type Coercion failed: not a Vector".into()),
+    };
+    let numbers = values
+        .into_iter()
+        .map(|value| value.coerce_to_number(activation))
+        .collect::<Result<_, _>>()?;
+    Ok((object, numbers))
+}
+
+/// Write numbers into a `Vector.<Number>`, starting at the given index.
+fn write_numbers<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    vector: Object<'gc>,
+    index: usize,
+    numbers: &[f64],
+) -> Result<(), Error> {
+    if let Some(mut vector) = vector.as_vector_storage_mut(activation.context.gc_context) {
+        for (i, &number) in numbers.iter().enumerate() {
+            vector.set(index + i, number.into(), activation)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Construct a `Vector.<Number>` holding the given numbers.
+fn numbers_to_vector<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    numbers: &[f64],
+) -> Result<Value<'gc>, Error> {
+    let values = numbers.iter().map(|&number| number.into()).collect();
+    let storage = VectorStorage::from_values(values, false, activation.avm2().classes().number);
+
+    Ok(VectorObject::from_vector(storage, activation)?.into())
+}
+
+/// Replace the matrix of a `Matrix3D` with the result of a function.
+fn update<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    f: impl FnOnce(Matrix3D) -> Matrix3D,
+) -> Result<Value<'gc>, Error> {
+    if let Some(matrix) = this.and_then(|this| this.as_matrix3d()) {
+        matrix.set_matrix(activation.context.gc_context, f(matrix.matrix()));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `rawData`'s getter.
+pub fn raw_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(matrix) = this.and_then(|this| this.as_matrix3d()) {
+        return numbers_to_vector(activation, &matrix.matrix().raw);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `rawData`'s setter.
+pub fn set_raw_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let (_, numbers) = arg_numbers(activation, args, 0)?;
+    if numbers.len() < 16 {
+        return Err("ArgumentError: Error #2004: One of the parameters is invalid.".into());
+    }
+    let mut raw = [0.0; 16];
+    raw.copy_from_slice(&numbers[..16]);
+
+    update(activation, this, |_| Matrix3D { raw })
+}
+
+/// Implements `position`'s getter.
+pub fn position<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(matrix) = this.and_then(|this| this.as_matrix3d()) {
+        let [x, y, z] = matrix.matrix().position();
+        return create_vector3d(activation, [x, y, z, 0.0]);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `position`'s setter.
+pub fn set_position<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let position = arg_vector3d(activation, args, 0)?;
+
+    update(activation, this, |mut matrix| {
+        matrix.set_position(position);
+        matrix
+    })
+}
+
+/// Implements `determinant`'s getter.
+pub fn determinant<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(matrix) = this.and_then(|this| this.as_matrix3d()) {
+        return Ok(matrix.matrix().determinant().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `append`, which applies another matrix after this one.
+pub fn append<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let lhs = arg_matrix(activation, args, 0)?;
+
+    update(activation, this, |matrix| lhs * matrix)
+}
+
+/// Implements `prepend`, which applies another matrix before this one.
+pub fn prepend<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let rhs = arg_matrix(activation, args, 0)?;
+
+    update(activation, this, |matrix| matrix * rhs)
+}
+
+/// Read the rotation passed to `appendRotation` or `prependRotation`.
+fn arg_rotation<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Matrix3D, Error> {
+    let degrees = arg_number(activation, args, 0)?;
+    let axis = arg_vector3d(activation, args, 1)?;
+    match args.get(2) {
+        Some(Value::Object(_)) => {
+            let pivot = arg_vector3d(activation, args, 2)?;
+            Ok(Matrix3D::rotation_around(degrees, axis, pivot))
+        }
+        _ => Ok(Matrix3D::rotation(degrees, axis)),
+    }
+}
+
+/// Implements `appendRotation`.
+pub fn append_rotation<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let rotation = arg_rotation(activation, args)?;
+
+    update(activation, this, |matrix| rotation * matrix)
+}
+
+/// Implements `prependRotation`.
+pub fn prepend_rotation<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let rotation = arg_rotation(activation, args)?;
+
+    update(activation, this, |matrix| matrix * rotation)
+}
+
+/// Read three numbers passed as arguments.
+fn arg_xyz<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<(f64, f64, f64), Error> {
+    Ok((
+        arg_number(activation, args, 0)?,
+        arg_number(activation, args, 1)?,
+        arg_number(activation, args, 2)?,
+    ))
+}
+
+/// Implements `appendScale`.
+pub fn append_scale<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let (x, y, z) = arg_xyz(activation, args)?;
+
+    update(activation, this, |matrix| Matrix3D::scale(x, y, z) * matrix)
+}
+
+/// Implements `prependScale`.
+pub fn prepend_scale<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let (x, y, z) = arg_xyz(activation, args)?;
+
+    update(activation, this, |matrix| matrix * Matrix3D::scale(x, y, z))
+}
+
+/// Implements `appendTranslation`.
+pub fn append_translation<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let (x, y, z) = arg_xyz(activation, args)?;
+
+    update(activation, this, |matrix| {
+        Matrix3D::translation(x, y, z) * matrix
+    })
+}
+
+/// Implements `prependTranslation`.
+pub fn prepend_translation<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let (x, y, z) = arg_xyz(activation, args)?;
+
+    update(activation, this, |matrix| {
+        matrix * Matrix3D::translation(x, y, z)
+    })
+}
+
+/// Implements `clone`.
+pub fn clone<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(matrix) = this.and_then(|this| this.as_matrix3d()) {
+        return Ok(Matrix3DObject::from_matrix(activation, matrix.matrix())?.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `copyFrom`.
+pub fn copy_from<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let other = arg_matrix(activation, args, 0)?;
+
+    update(activation, this, |_| other)
+}
+
+/// Implements `identity`.
+pub fn identity<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    update(activation, this, |_| Matrix3D::IDENTITY)
+}
+
+/// Implements `invert`, which leaves the matrix alone and returns false if
+/// it can't be inverted.
+pub fn invert<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(matrix) = this.and_then(|this| this.as_matrix3d()) {
+        return Ok(match matrix.matrix().inverse() {
+            Some(inverse) => {
+                matrix.set_matrix(activation.context.gc_context, inverse);
+                true
+            }
+            None => false,
+        }
+        .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `transpose`.
+pub fn transpose<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    update(activation, this, |matrix| matrix.transpose())
+}
+
+/// Check the orientation style passed to `decompose` or `recompose`.
+fn check_orientation_style<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+) -> Result<(), Error> {
+    let style = match args.get(index) {
+        Some(Value::Undefined) | None => return Ok(()),
+        Some(style) => style.coerce_to_string(activation)?,
+    };
+    if &*style != EULER_ANGLES {
+        return Err(format!("Orientation style {} is not supported", style).into());
+    }
+
+    Ok(())
+}
+
+/// Implements `decompose`, which returns the translation, rotation and scale
+/// of the matrix as three `Vector3D`s.
+pub fn decompose<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    check_orientation_style(activation, args, 0)?;
+
+    if let Some(matrix) = this.and_then(|this| this.as_matrix3d()) {
+        let components = matrix.matrix().decompose().unwrap_or(Components {
+            translation: matrix.matrix().position(),
+            rotation: [0.0; 3],
+            scale: [0.0; 3],
+        });
+        let mut values = Vec::with_capacity(3);
+        for &[x, y, z] in &[
+            components.translation,
+            components.rotation,
+            components.scale,
+        ] {
+            values.push(create_vector3d(activation, [x, y, z, 0.0])?);
+        }
+        let storage =
+            VectorStorage::from_values(values, false, activation.avm2().classes().vector3d);
+
+        return Ok(VectorObject::from_vector(storage, activation)?.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `recompose`, which returns false and leaves the matrix alone
+/// if the components scale it to nothing.
+pub fn recompose<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    check_orientation_style(activation, args, 1)?;
+
+    let list = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?;
+    let values: Vec<Value<'gc>> = match list.as_vector_storage() {
+        Some(vector) => vector.iter().collect(),
+        None => return Err("TypeError: Error #1034: Type Coercion failed: not a Vector".into()),
+    };
+    if values.len() < 3 {
+        return Ok(false.into());
+    }
+
+    let mut vectors = [[0.0; 3]; 3];
+    for (vector, value) in vectors.iter_mut().zip(values) {
+        let [x, y, z, _] = vector3d::components(value.coerce_to_object(activation)?, activation)?;
+        *vector = [x, y, z];
+    }
+    let [translation, rotation, scale] = vectors;
+    if scale.contains(&0.0) {
+        return Ok(false.into());
+    }
+
+    update(activation, this, |_| {
+        Matrix3D::recompose(&Components {
+            translation,
+            rotation,
+            scale,
+        })
+    })?;
+    Ok(true.into())
+}
+
+/// Implements `transformVector`, which includes the translation.
+pub fn transform_vector<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(matrix) = this.and_then(|this| this.as_matrix3d()) {
+        let matrix = matrix.matrix();
+        let point = arg_vector3d(activation, args, 0)?;
+        let [x, y, z] = matrix.transform_point(point);
+        let w = matrix.get(3, 0) * point[0]
+            + matrix.get(3, 1) * point[1]
+            + matrix.get(3, 2) * point[2]
+            + matrix.get(3, 3);
+        return create_vector3d(activation, [x, y, z, w]);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `deltaTransformVector`, which ignores the translation.
+pub fn delta_transform_vector<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(matrix) = this.and_then(|this| this.as_matrix3d()) {
+        let vector = arg_vector3d(activation, args, 0)?;
+        let [x, y, z] = matrix.matrix().transform_vector(vector);
+        return create_vector3d(activation, [x, y, z, 0.0]);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `transformVectors`, which transforms a list of points held
+/// as consecutive coordinates.
+pub fn transform_vectors<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(matrix) = this.and_then(|this| this.as_matrix3d()) {
+        let matrix = matrix.matrix();
+        let (_, input) = arg_numbers(activation, args, 0)?;
+        let (output, _) = arg_numbers(activation, args, 1)?;
+        let transformed: Vec<f64> = input
+            .chunks_exact(3)
+            .flat_map(|point| matrix.transform_point([point[0], point[1], point[2]]))
+            .collect();
+        write_numbers(activation, output, 0, &transformed)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `copyRawDataFrom`.
+pub fn copy_raw_data_from<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let (_, numbers) = arg_numbers(activation, args, 0)?;
+    let index = arg_number(activation, args, 1)?.max(0.0) as usize;
+    let transposed = args
+        .get(2)
+        .cloned()
+        .unwrap_or_else(|| false.into())
+        .coerce_to_boolean();
+    let numbers = numbers
+        .get(index..index + 16)
+        .ok_or("RangeError: Error #1125: The index is out of range.")?;
+    let mut matrix = Matrix3D::IDENTITY;
+    matrix.raw.copy_from_slice(numbers);
+    if transposed {
+        matrix = matrix.transpose();
+    }
+
+    update(activation, this, |_| matrix)
+}
+
+/// Implements `copyRawDataTo`.
+pub fn copy_raw_data_to<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(matrix) = this.and_then(|this| this.as_matrix3d()) {
+        let (vector, _) = arg_numbers(activation, args, 0)?;
+        let index = arg_number(activation, args, 1)?.max(0.0) as usize;
+        let transposed = args
+            .get(2)
+            .cloned()
+            .unwrap_or_else(|| false.into())
+            .coerce_to_boolean();
+        let mut matrix = matrix.matrix();
+        if transposed {
+            matrix = matrix.transpose();
+        }
+        write_numbers(activation, vector, index, &matrix.raw)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Read the row or column index passed as the first argument.
+fn arg_index<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<usize, Error> {
+    let index = arg_number(activation, args, 0)?;
+    if !(0.0..4.0).contains(&index) {
+        return Err("ArgumentError: Error #2004: One of the parameters is invalid.".into());
+    }
+    Ok(index as usize)
+}
+
+/// Copy the components of a `Vector3D` into a row or a column of a
+/// `Matrix3D`.
+fn copy_line_from<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+    row: bool,
+) -> Result<Value<'gc>, Error> {
+    let index = arg_index(activation, args)?;
+    let object = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?;
+    let components = vector3d::components(object, activation)?;
+
+    update(activation, this, |mut matrix| {
+        for (i, &component) in components.iter().enumerate() {
+            let (r, c) = if row { (index, i) } else { (i, index) };
+            matrix.raw[c * 4 + r] = component;
+        }
+        matrix
+    })
+}
+
+/// Copy a row or a column of a `Matrix3D` into a `Vector3D`.
+fn copy_line_to<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+    row: bool,
+) -> Result<Value<'gc>, Error> {
+    if let Some(matrix) = this.and_then(|this| this.as_matrix3d()) {
+        let index = arg_index(activation, args)?;
+        let mut object = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let matrix = matrix.matrix();
+        for (i, name) in ["x", "y", "z", "w"].iter().enumerate() {
+            let value = if row {
+                matrix.get(index, i)
+            } else {
+                matrix.get(i, index)
+            };
+            object.set_property(
+                object,
+                &QName::new(Namespace::public(), *name),
+                value.into(),
+                activation,
+            )?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `copyRowFrom`.
+pub fn copy_row_from<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    copy_line_from(activation, this, args, true)
+}
+
+/// Implements `copyRowTo`.
+pub fn copy_row_to<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    copy_line_to(activation, this, args, true)
+}
+
+/// Implements `copyColumnFrom`.
+pub fn copy_column_from<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    copy_line_from(activation, this, args, false)
+}
+
+/// Implements `copyColumnTo`.
+pub fn copy_column_to<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    copy_line_to(activation, this, args, false)
+}
+
+/// Construct `Matrix3D`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.geom"), "Matrix3D"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<Matrix3D instance initializer>", mc),
+        Method::from_builtin(class_init, "<Matrix3D class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+    write.set_attributes(ClassAttributes::SEALED);
+    write.set_instance_allocator(matrix3d_allocator);
+    write.set_native_instance_init(Method::from_builtin(
+        native_instance_init,
+        "<Matrix3D native instance initializer>",
+        mc,
+    ));
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("rawData", Some(raw_data), Some(set_raw_data)),
+        ("position", Some(position), Some(set_position)),
+        ("determinant", Some(determinant), None),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("append", append),
+        ("appendRotation", append_rotation),
+        ("appendScale", append_scale),
+        ("appendTranslation", append_translation),
+        ("clone", clone),
+        ("copyColumnFrom", copy_column_from),
+        ("copyColumnTo", copy_column_to),
+        ("copyFrom", copy_from),
+        ("copyRawDataFrom", copy_raw_data_from),
+        ("copyRawDataTo", copy_raw_data_to),
+        ("copyRowFrom", copy_row_from),
+        ("copyRowTo", copy_row_to),
+        ("decompose", decompose),
+        ("deltaTransformVector", delta_transform_vector),
+        ("identity", identity),
+        ("invert", invert),
+        ("prepend", prepend),
+        ("prependRotation", prepend_rotation),
+        ("prependScale", prepend_scale),
+        ("prependTranslation", prepend_translation),
+        ("recompose", recompose),
+        ("transformVector", transform_vector),
+        ("transformVectors", transform_vectors),
+        ("transpose", transpose),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+    class
+}
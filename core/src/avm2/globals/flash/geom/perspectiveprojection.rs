@@ -0,0 +1,304 @@
+//! `flash.geom.PerspectiveProjection` builtin/prototype
+
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::geom::point::create_point;
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::object::Matrix3DObject;
+use crate::avm2::traits::Trait;
+use crate::avm2::{Activation, Error, Namespace, Object, QName, TObject, Value};
+use crate::matrix3d::PerspectiveProjection;
+use gc_arena::{GcCell, MutationContext};
+
+/// Construct a `PerspectiveProjection` with the given field of view and
+/// projection centre.
+pub fn create_perspective_projection<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    projection: PerspectiveProjection,
+) -> Result<Object<'gc>, Error> {
+    let projection_class = activation.context.avm2.classes().perspectiveprojection;
+    let mut object = projection_class.construct(activation, &[])?;
+
+    object.set_property(
+        object,
+        &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "fieldOfView"),
+        projection.field_of_view.into(),
+        activation,
+    )?;
+    let center = create_point(activation, projection.projection_center)?;
+    object.set_property(
+        object,
+        &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "projectionCenter"),
+        center,
+        activation,
+    )?;
+
+    Ok(object)
+}
+
+/// Read the field of view and projection centre of a
+/// `PerspectiveProjection`.
+pub fn perspective_projection<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<PerspectiveProjection, Error> {
+    let field_of_view = this
+        .get_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "fieldOfView"),
+            activation,
+        )?
+        .coerce_to_number(activation)?;
+    let center = this.get_property(
+        this,
+        &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "projectionCenter"),
+        activation,
+    )?;
+    let projection_center = match center {
+        Value::Object(center) => {
+            let x = center
+                .get_property(center, &QName::new(Namespace::public(), "x"), activation)?
+                .coerce_to_number(activation)?;
+            let y = center
+                .get_property(center, &QName::new(Namespace::public(), "y"), activation)?
+                .coerce_to_number(activation)?;
+            (x, y)
+        }
+        _ => (0.0, 0.0),
+    };
+
+    Ok(PerspectiveProjection {
+        field_of_view,
+        projection_center,
+    })
+}
+
+/// The width of the stage, which the focal length is measured against.
+fn stage_width(activation: &mut Activation<'_, '_, '_>) -> f64 {
+    activation.context.stage.stage_size().0.into()
+}
+
+/// Implements `flash.geom.PerspectiveProjection`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        // A new projection looks at the centre of the stage.
+        let projection = PerspectiveProjection::for_stage(activation.context.stage.stage_size());
+        this.set_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "fieldOfView"),
+            projection.field_of_view.into(),
+            activation,
+        )?;
+        let center = create_point(activation, projection.projection_center)?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "projectionCenter"),
+            center,
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.geom.PerspectiveProjection`'s class initializer.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `fieldOfView`'s getter.
+pub fn field_of_view<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "fieldOfView"),
+            activation,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `fieldOfView`'s setter.
+pub fn set_field_of_view<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let field_of_view = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_number(activation)?;
+        if field_of_view.is_nan() || field_of_view <= 0.0 || field_of_view >= 180.0 {
+            return Err("ArgumentError: Error #2182: Invalid fieldOfView value.  The value must be greater than 0 and less than 180.".into());
+        }
+
+        this.set_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "fieldOfView"),
+            field_of_view.into(),
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `focalLength`'s getter.
+pub fn focal_length<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let projection = perspective_projection(activation, this)?;
+        return Ok(projection.focal_length(stage_width(activation)).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `focalLength`'s setter, which changes the field of view.
+pub fn set_focal_length<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let focal_length = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_number(activation)?;
+        if focal_length.is_nan() || focal_length <= 0.0 {
+            return Err("ArgumentError: Error #2186: Invalid focalLength.".into());
+        }
+
+        let mut projection = perspective_projection(activation, this)?;
+        projection.set_focal_length(focal_length, stage_width(activation));
+        this.set_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "fieldOfView"),
+            projection.field_of_view.into(),
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `projectionCenter`'s getter.
+pub fn projection_center<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "projectionCenter"),
+            activation,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `projectionCenter`'s setter.
+pub fn set_projection_center<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let center = args.get(0).cloned().unwrap_or(Value::Undefined);
+        this.set_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "projectionCenter"),
+            center,
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `toMatrix3D`.
+pub fn to_matrix3d<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let projection = perspective_projection(activation, this)?;
+        let matrix = projection.to_matrix3d(stage_width(activation));
+        return Ok(Matrix3DObject::from_matrix(activation, matrix)?.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `PerspectiveProjection`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.geom"), "PerspectiveProjection"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(
+            instance_init,
+            "<PerspectiveProjection instance initializer>",
+            mc,
+        ),
+        Method::from_builtin(class_init, "<PerspectiveProjection class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("fieldOfView", Some(field_of_view), Some(set_field_of_view)),
+        ("focalLength", Some(focal_length), Some(set_focal_length)),
+        (
+            "projectionCenter",
+            Some(projection_center),
+            Some(set_projection_center),
+        ),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[("toMatrix3D", to_matrix3d)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "fieldOfView"),
+        QName::new(Namespace::public(), "Number").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "projectionCenter"),
+        QName::new(Namespace::package("flash.geom"), "Point").into(),
+        None,
+    ));
+
+    class
+}
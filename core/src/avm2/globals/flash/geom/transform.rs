@@ -0,0 +1,234 @@
+//! `flash.geom.Transform` builtin/prototype
+
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::geom::perspectiveprojection::{
+    self, create_perspective_projection,
+};
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::object::Matrix3DObject;
+use crate::avm2::traits::Trait;
+use crate::avm2::{Activation, Error, Namespace, Object, QName, TObject, Value};
+use crate::display_object::{DisplayObject, TDisplayObject, Transform3D};
+use crate::matrix::Matrix;
+use crate::matrix3d::Matrix3D;
+use gc_arena::{GcCell, MutationContext};
+use swf::Twips;
+
+/// Construct a `Transform` of the given display object.
+pub fn create_transform<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    display_object: DisplayObject<'gc>,
+) -> Result<Value<'gc>, Error> {
+    let transform_class = activation.context.avm2.classes().transform;
+
+    let args = [display_object.object2()];
+    let new_transform = transform_class.construct(activation, &args)?;
+
+    Ok(new_transform.into())
+}
+
+/// The display object that a `Transform` belongs to.
+fn display_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+) -> Result<Option<DisplayObject<'gc>>, Error> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(None),
+    };
+    let display_object = this.get_property(
+        this,
+        &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "displayObject"),
+        activation,
+    )?;
+
+    Ok(match display_object {
+        Value::Object(object) => object.as_display_object(),
+        _ => None,
+    })
+}
+
+/// Place a display object in 3D with a matrix, or return it to its parent's
+/// plane if there's no matrix.
+///
+/// The matrix is split into the display object's 2D matrix and its 3D
+/// transform, so that the 3D matrix it's drawn with is the same again.
+pub fn apply_matrix3d<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    display_object: DisplayObject<'gc>,
+    matrix: Option<Matrix3D>,
+) {
+    let mc = activation.context.gc_context;
+    let components = match matrix.map(|matrix| matrix.decompose()) {
+        Some(Some(components)) => components,
+        Some(None) => return,
+        None => {
+            display_object.set_transform_3d(mc, None);
+            return;
+        }
+    };
+
+    let [x, y, z] = components.translation;
+    let [rotation_x, rotation_y, rotation_z] = components.rotation;
+    let [scale_x, scale_y, scale_z] = components.scale;
+    let matrix = Matrix::translate(Twips::from_pixels(x), Twips::from_pixels(y))
+        * Matrix::rotate(rotation_z as f32)
+        * Matrix::scale(scale_x as f32, scale_y as f32);
+    display_object.set_matrix(mc, &matrix);
+    display_object.set_transform_3d(
+        mc,
+        Some(Transform3D {
+            z,
+            rotation_x: rotation_x.to_degrees(),
+            rotation_y: rotation_y.to_degrees(),
+            scale_z,
+        }),
+    );
+}
+
+/// Implements `flash.geom.Transform`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let display_object = match args.get(0) {
+            Some(Value::Object(object)) if object.as_display_object().is_some() => *object,
+            _ => {
+                return Err(
+                    "ArgumentError: Error #2007: Parameter displayObject must be non-null.".into(),
+                )
+            }
+        };
+        this.set_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "displayObject"),
+            display_object.into(),
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.geom.Transform`'s class initializer.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `matrix3D`'s getter, which is null unless the display object
+/// has been placed in 3D.
+pub fn matrix3d<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = display_object(activation, this)? {
+        if dobj.transform_3d().is_none() {
+            return Ok(Value::Null);
+        }
+        return Ok(Matrix3DObject::from_matrix(activation, dobj.matrix3d())?.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `matrix3D`'s setter.
+pub fn set_matrix3d<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = display_object(activation, this)? {
+        let matrix = match args.get(0) {
+            Some(Value::Object(object)) => match object.as_matrix3d() {
+                Some(matrix) => Some(matrix.matrix()),
+                None => return Err("TypeError: Error #1034: Type Coercion failed".into()),
+            },
+            _ => None,
+        };
+        apply_matrix3d(activation, dobj, matrix);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `perspectiveProjection`'s getter, which is null unless the
+/// display object has been given its own projection.
+pub fn perspective_projection<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = display_object(activation, this)? {
+        return match dobj.perspective_projection() {
+            Some(projection) => Ok(create_perspective_projection(activation, projection)?.into()),
+            None => Ok(Value::Null),
+        };
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `perspectiveProjection`'s setter.
+pub fn set_perspective_projection<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = display_object(activation, this)? {
+        let projection = match args.get(0) {
+            Some(Value::Object(object)) => Some(perspectiveprojection::perspective_projection(
+                activation, *object,
+            )?),
+            _ => None,
+        };
+        dobj.set_perspective_projection(activation.context.gc_context, projection);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `Transform`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.geom"), "Transform"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<Transform instance initializer>", mc),
+        Method::from_builtin(class_init, "<Transform class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("matrix3D", Some(matrix3d), Some(set_matrix3d)),
+        (
+            "perspectiveProjection",
+            Some(perspective_projection),
+            Some(set_perspective_projection),
+        ),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "displayObject"),
+        QName::new(Namespace::package("flash.display"), "DisplayObject").into(),
+        None,
+    ));
+
+    class
+}
@@ -0,0 +1,530 @@
+//! `flash.geom.Vector3D` builtin/prototype
+
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::{Activation, Error, Namespace, Object, QName, TObject, Value};
+use crate::string::AvmString;
+use gc_arena::{GcCell, MutationContext};
+
+const COMPONENTS: [&str; 4] = ["x", "y", "z", "w"];
+
+/// Construct a `Vector3D` with the given components.
+pub fn create_vector3d<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    components: [f64; 4],
+) -> Result<Value<'gc>, Error> {
+    let vector3d_class = activation.context.avm2.classes().vector3d;
+
+    let args = components
+        .iter()
+        .map(|&c| Value::Number(c))
+        .collect::<Vec<_>>();
+    let new_vector3d = vector3d_class.construct(activation, &args)?;
+
+    Ok(new_vector3d.into())
+}
+
+/// Read the `x`, `y`, `z` and `w` components of a `Vector3D`.
+pub fn components<'gc>(
+    this: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<[f64; 4], Error> {
+    let mut components = [0.0; 4];
+    for (component, name) in components.iter_mut().zip(&COMPONENTS) {
+        *component = this
+            .get_property(this, &QName::new(Namespace::public(), *name), activation)?
+            .coerce_to_number(activation)?;
+    }
+    Ok(components)
+}
+
+fn set_components<'gc>(
+    mut this: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    components: &[f64],
+) -> Result<(), Error> {
+    for (&component, name) in components.iter().zip(&COMPONENTS) {
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), *name),
+            component.into(),
+            activation,
+        )?;
+    }
+    Ok(())
+}
+
+/// Read the components of the `Vector3D` passed as an argument.
+fn arg_components<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+) -> Result<[f64; 4], Error> {
+    let other = args
+        .get(index)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?;
+    components(other, activation)
+}
+
+fn arg_number<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+) -> Result<f64, Error> {
+    args.get(index)
+        .unwrap_or(&0.into())
+        .coerce_to_number(activation)
+}
+
+fn length_of(components: [f64; 4]) -> f64 {
+    let [x, y, z, _] = components;
+    (x * x + y * y + z * z).sqrt()
+}
+
+/// Implements `flash.geom.Vector3D`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let mut components = [0.0; 4];
+        for (i, component) in components.iter_mut().enumerate() {
+            *component = arg_number(activation, args, i)?;
+        }
+        set_components(this, activation, &components)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.geom.Vector3D`'s class initializer.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `X_AXIS`.
+pub fn x_axis<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    create_vector3d(activation, [1.0, 0.0, 0.0, 0.0])
+}
+
+/// Implements `Y_AXIS`.
+pub fn y_axis<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    create_vector3d(activation, [0.0, 1.0, 0.0, 0.0])
+}
+
+/// Implements `Z_AXIS`.
+pub fn z_axis<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    create_vector3d(activation, [0.0, 0.0, 1.0, 0.0])
+}
+
+/// Implements the `length` property.
+pub fn length<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return Ok(length_of(components(this, activation)?).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements the `lengthSquared` property.
+pub fn length_squared<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let [x, y, z, _] = components(this, activation)?;
+        return Ok((x * x + y * y + z * z).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `add`.
+pub fn add<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let [x, y, z, _] = components(this, activation)?;
+        let [other_x, other_y, other_z, _] = arg_components(activation, args, 0)?;
+        return create_vector3d(activation, [x + other_x, y + other_y, z + other_z, 0.0]);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `subtract`.
+pub fn subtract<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let [x, y, z, _] = components(this, activation)?;
+        let [other_x, other_y, other_z, _] = arg_components(activation, args, 0)?;
+        return create_vector3d(activation, [x - other_x, y - other_y, z - other_z, 0.0]);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `incrementBy`.
+pub fn increment_by<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let [x, y, z, _] = components(this, activation)?;
+        let [other_x, other_y, other_z, _] = arg_components(activation, args, 0)?;
+        set_components(this, activation, &[x + other_x, y + other_y, z + other_z])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `decrementBy`.
+pub fn decrement_by<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let [x, y, z, _] = components(this, activation)?;
+        let [other_x, other_y, other_z, _] = arg_components(activation, args, 0)?;
+        set_components(this, activation, &[x - other_x, y - other_y, z - other_z])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `clone`.
+pub fn clone<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let components = components(this, activation)?;
+        return create_vector3d(activation, components);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `copyFrom`.
+pub fn copy_from<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let [x, y, z, _] = arg_components(activation, args, 0)?;
+        set_components(this, activation, &[x, y, z])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `setTo`.
+pub fn set_to<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let x = arg_number(activation, args, 0)?;
+        let y = arg_number(activation, args, 1)?;
+        let z = arg_number(activation, args, 2)?;
+        set_components(this, activation, &[x, y, z])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `crossProduct`.
+pub fn cross_product<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let [x, y, z, _] = components(this, activation)?;
+        let [other_x, other_y, other_z, _] = arg_components(activation, args, 0)?;
+        return create_vector3d(
+            activation,
+            [
+                y * other_z - z * other_y,
+                z * other_x - x * other_z,
+                x * other_y - y * other_x,
+                1.0,
+            ],
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `dotProduct`.
+pub fn dot_product<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let [x, y, z, _] = components(this, activation)?;
+        let [other_x, other_y, other_z, _] = arg_components(activation, args, 0)?;
+        return Ok((x * other_x + y * other_y + z * other_z).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `equals`.
+#[allow(clippy::float_cmp)]
+pub fn equals<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let ours = components(this, activation)?;
+        let theirs = arg_components(activation, args, 0)?;
+        let all_four = args
+            .get(1)
+            .cloned()
+            .unwrap_or_else(|| false.into())
+            .coerce_to_boolean();
+        let compared = if all_four { 4 } else { 3 };
+        return Ok((ours[..compared] == theirs[..compared]).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `nearEquals`.
+pub fn near_equals<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let ours = components(this, activation)?;
+        let theirs = arg_components(activation, args, 0)?;
+        let tolerance = arg_number(activation, args, 1)?;
+        let all_four = args
+            .get(2)
+            .cloned()
+            .unwrap_or_else(|| false.into())
+            .coerce_to_boolean();
+        let compared = if all_four { 4 } else { 3 };
+        return Ok(ours[..compared]
+            .iter()
+            .zip(&theirs[..compared])
+            .all(|(a, b)| (a - b).abs() < tolerance)
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `negate`.
+pub fn negate<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let [x, y, z, _] = components(this, activation)?;
+        set_components(this, activation, &[-x, -y, -z])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `normalize`, which returns the length before normalizing.
+pub fn normalize<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let components = components(this, activation)?;
+        let length = length_of(components);
+        if length > 0.0 {
+            let [x, y, z, _] = components;
+            set_components(this, activation, &[x / length, y / length, z / length])?;
+        }
+        return Ok(length.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `project`, which divides the other components by `w`.
+pub fn project<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let [x, y, z, w] = components(this, activation)?;
+        set_components(this, activation, &[x / w, y / w, z / w])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `scaleBy`.
+pub fn scale_by<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let [x, y, z, _] = components(this, activation)?;
+        let scale = arg_number(activation, args, 0)?;
+        set_components(this, activation, &[x * scale, y * scale, z * scale])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `toString`.
+pub fn to_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let mut strings = Vec::with_capacity(3);
+        for name in &COMPONENTS[..3] {
+            strings.push(
+                this.get_property(this, &QName::new(Namespace::public(), *name), activation)?
+                    .coerce_to_string(activation)?,
+            );
+        }
+        return Ok(AvmString::new(
+            activation.context.gc_context,
+            format!("Vector3D({}, {}, {})", strings[0], strings[1], strings[2]),
+        )
+        .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `angleBetween`, in radians.
+pub fn angle_between<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let a = arg_components(activation, args, 0)?;
+    let b = arg_components(activation, args, 1)?;
+    let dot = a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+    let cos = dot / (length_of(a) * length_of(b));
+
+    Ok(cos.max(-1.0).min(1.0).acos().into())
+}
+
+/// Implements `distance`.
+pub fn distance<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let [a_x, a_y, a_z, _] = arg_components(activation, args, 0)?;
+    let [b_x, b_y, b_z, _] = arg_components(activation, args, 1)?;
+
+    Ok(length_of([b_x - a_x, b_y - a_y, b_z - a_z, 0.0]).into())
+}
+
+/// Construct `Vector3D`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.geom"), "Vector3D"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<Vector3D instance initializer>", mc),
+        Method::from_builtin(class_init, "<Vector3D class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+    write.set_attributes(ClassAttributes::SEALED);
+
+    write.define_public_slot_number_instance_traits(&[
+        ("x", Some(0.0)),
+        ("y", Some(0.0)),
+        ("z", Some(0.0)),
+        ("w", Some(0.0)),
+    ]);
+
+    const PUBLIC_CLASS_PROPERTIES: &[(&str, Option<NativeMethodImpl>, Option<NativeMethodImpl>)] =
+        &[
+            ("X_AXIS", Some(x_axis), None),
+            ("Y_AXIS", Some(y_axis), None),
+            ("Z_AXIS", Some(z_axis), None),
+        ];
+    write.define_public_builtin_class_properties(mc, PUBLIC_CLASS_PROPERTIES);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("length", Some(length), None),
+        ("lengthSquared", Some(length_squared), None),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_CLASS_METHODS: &[(&str, NativeMethodImpl)] =
+        &[("angleBetween", angle_between), ("distance", distance)];
+    write.define_public_builtin_class_methods(mc, PUBLIC_CLASS_METHODS);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("add", add),
+        ("clone", clone),
+        ("copyFrom", copy_from),
+        ("crossProduct", cross_product),
+        ("decrementBy", decrement_by),
+        ("dotProduct", dot_product),
+        ("equals", equals),
+        ("incrementBy", increment_by),
+        ("nearEquals", near_equals),
+        ("negate", negate),
+        ("normalize", normalize),
+        ("project", project),
+        ("scaleBy", scale_by),
+        ("setTo", set_to),
+        ("subtract", subtract),
+        ("toString", to_string),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+    class
+}
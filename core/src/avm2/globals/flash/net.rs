@@ -0,0 +1,122 @@
+//! `flash.net` namespace
+
+use crate::avm2::object::TObject;
+use crate::avm2::{Activation, Error, Object, Value};
+
+pub mod localconnection;
+pub mod netconnection;
+pub mod objectencoding;
+pub mod responder;
+pub mod sharedobject;
+pub mod sharedobjectflushstatus;
+pub mod socket;
+pub mod urlloader;
+pub mod urlloaderdataformat;
+pub mod urlrequest;
+pub mod urlrequestheader;
+pub mod urlrequestmethod;
+pub mod urlstream;
+pub mod urlvariables;
+pub mod xmlsocket;
+
+/// Implements `flash.net.registerClassAlias`
+pub fn register_class_alias<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let alias = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    let class = match args.get(1).cloned().unwrap_or(Value::Undefined) {
+        Value::Object(class) if class.as_class().is_some() => class,
+        _ => return Err("TypeError: Error #2007: Parameter classObject must be non-null.".into()),
+    };
+
+    activation.avm2().register_class_alias(alias, class);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.net.getClassByAlias`
+pub fn get_class_by_alias<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let alias = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+
+    match activation.avm2().class_for_alias(alias) {
+        Some(class) => Ok(class.into()),
+        None => Err(format!(
+            "ReferenceError: Error #1014: Class {} could not be found.",
+            alias
+        )
+        .into()),
+    }
+}
+
+/// Implements `flash.net.navigateToURL`
+pub fn navigate_to_url<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let request = match args.get(0).cloned().unwrap_or(Value::Undefined) {
+        Value::Object(request) => request,
+        _ => return Err("TypeError: Error #2007: Parameter request must be non-null.".into()),
+    };
+
+    // Flash opens a new window unless the movie names one.
+    let window = match args.get(1).cloned().unwrap_or(Value::Undefined) {
+        Value::Null | Value::Undefined => "_blank".to_string(),
+        window => window.coerce_to_string(activation)?.to_string(),
+    };
+
+    let url = urlrequest::request_url(activation, request)?;
+    let method = urlrequest::request_method(activation, request)?;
+    let vars_method = urlrequest::request_variables(activation, request)?
+        .map(|variables| (method, variables.into_iter().collect()));
+
+    activation
+        .context
+        .navigator
+        .navigate_to_url(url, Some(window), vars_method);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.net.sendToURL`
+///
+/// The request is sent in the background, and any response is ignored.
+pub fn send_to_url<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let request = match args.get(0).cloned().unwrap_or(Value::Undefined) {
+        Value::Object(request) => request,
+        _ => return Err("TypeError: Error #2007: Parameter request must be non-null.".into()),
+    };
+
+    let (url, options) = urlrequest::request_options(activation, request)?;
+    let fetch = activation.context.navigator.fetch(&url, options);
+    activation
+        .context
+        .navigator
+        .spawn_future(Box::pin(async move {
+            if let Err(e) = fetch.await {
+                log::warn!("sendToURL: could not send request to {}: {}", url, e);
+            }
+
+            Ok(())
+        }));
+
+    Ok(Value::Undefined)
+}
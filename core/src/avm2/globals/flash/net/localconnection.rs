@@ -0,0 +1,219 @@
+//! `flash.net.LocalConnection` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::local_connection::{LocalConnectionArgs, LocalConnectionTarget, LocalConnections};
+use crate::string::AvmString;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.net.LocalConnection`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        // Calls are made on the connection itself until a client is set.
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "client"),
+            this.into(),
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.net.LocalConnection`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Coerce a parameter that may not be null to a string.
+fn non_null_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+    name: &str,
+) -> Result<AvmString<'gc>, Error> {
+    match args.get(index).cloned().unwrap_or(Value::Null) {
+        Value::Null | Value::Undefined => Err(format!(
+            "TypeError: Error #2007: Parameter {} must be non-null.",
+            name
+        )
+        .into()),
+        value => value.coerce_to_string(activation),
+    }
+}
+
+/// Implements `LocalConnection.connect`
+pub fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let name = non_null_string(activation, args, 0, "connectionName")?;
+        if name.is_empty() || name.contains(':') {
+            return Err("ArgumentError: Error #2004: One of the parameters is invalid.".into());
+        }
+
+        let url = activation.context.swf.url().map(str::to_string);
+        if !activation.context.local_connections.connect(
+            LocalConnectionTarget::Avm2(this),
+            url.as_deref(),
+            &name,
+        ) {
+            return Err(
+                "ArgumentError: Error #2082: Connect failed because the object is already connected."
+                    .into(),
+            );
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `LocalConnection.send`
+pub fn send<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let connection_name = non_null_string(activation, args, 0, "connectionName")?;
+        let method_name = non_null_string(activation, args, 1, "methodName")?;
+        let method_args = args.get(2..).unwrap_or_default().to_vec();
+
+        let url = activation.context.swf.url().map(str::to_string);
+        activation.context.local_connections.send(
+            LocalConnectionTarget::Avm2(this),
+            url.as_deref(),
+            &connection_name,
+            &method_name,
+            LocalConnectionArgs::Avm2(method_args),
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `LocalConnection.close`
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if !activation
+            .context
+            .local_connections
+            .close(LocalConnectionTarget::Avm2(this))
+        {
+            return Err(
+                "ArgumentError: Error #2083: Close failed because the object is not connected."
+                    .into(),
+            );
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `LocalConnection.allowDomain` and
+/// `LocalConnection.allowInsecureDomain`
+pub fn allow_domain<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let mut domains = Vec::with_capacity(args.len());
+        for domain in args {
+            domains.push(domain.coerce_to_string(activation)?.to_string());
+        }
+
+        activation
+            .context
+            .local_connections
+            .allow_domains(LocalConnectionTarget::Avm2(this), domains);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `LocalConnection.domain`
+pub fn domain<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let domain = LocalConnections::domain(activation.context.swf.url());
+
+    Ok(AvmString::new(activation.context.gc_context, domain).into())
+}
+
+/// Implements `LocalConnection.isSupported`
+pub fn is_supported<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(true.into())
+}
+
+/// Construct `LocalConnection`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.net"), "LocalConnection"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<LocalConnection instance initializer>", mc),
+        Method::from_builtin(class_init, "<LocalConnection class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_CLASS_PROPERTIES: &[(&str, Option<NativeMethodImpl>, Option<NativeMethodImpl>)] =
+        &[("isSupported", Some(is_supported), None)];
+    write.define_public_builtin_class_properties(mc, PUBLIC_CLASS_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("connect", connect),
+        ("send", send),
+        ("close", close),
+        ("allowDomain", allow_domain),
+        ("allowInsecureDomain", allow_domain),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[("domain", Some(domain), None)];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "client"),
+        QName::new(Namespace::public(), "Object").into(),
+        None,
+    ));
+
+    class
+}
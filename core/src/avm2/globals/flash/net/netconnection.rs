@@ -0,0 +1,323 @@
+//! `flash.net.NetConnection` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::events::dispatch_event;
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::remoting::{self, NetConnectionObject, NetConnections, AMF0_VERSION, AMF3_VERSION};
+use crate::string::AvmString;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.net.NetConnection`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        this.set_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "objectEncoding"),
+            AMF3_VERSION.into(),
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.net.NetConnection`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Dispatch a `netStatus` event to a connection.
+fn dispatch_net_status<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    code: &str,
+    level: &str,
+) -> Result<(), Error> {
+    let event = remoting::avm2_net_status_event(activation, code, level, None)?;
+    dispatch_event(activation, this, event)?;
+
+    Ok(())
+}
+
+/// Implements `NetConnection.connect`
+///
+/// Only `null`, which makes a local connection, and HTTP gateways for Flash
+/// Remoting are supported.
+pub fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let object = NetConnectionObject::Avm2(this);
+        match args.get(0).cloned().unwrap_or(Value::Null) {
+            Value::Null | Value::Undefined => {
+                activation.context.net_connections.connect(object, None);
+                dispatch_net_status(activation, this, "NetConnection.Connect.Success", "status")?;
+            }
+            command => {
+                let command = command.coerce_to_string(activation)?.to_string();
+                let lowercase = command.to_ascii_lowercase();
+                if lowercase.starts_with("http://") || lowercase.starts_with("https://") {
+                    // Gateways are only contacted when a call is made.
+                    activation
+                        .context
+                        .net_connections
+                        .connect(object, Some(command));
+                } else {
+                    log::warn!("NetConnection.connect: unsupported URL {}", command);
+                    activation.context.net_connections.close(object);
+                    dispatch_net_status(activation, this, "NetConnection.Connect.Failed", "error")?;
+                }
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `NetConnection.call`
+pub fn call<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let object = NetConnectionObject::Avm2(this);
+        if !activation.context.net_connections.is_connected(object) {
+            return Err("Error: Error #2126: NetConnection object must be connected.".into());
+        }
+
+        let command = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?
+            .to_string();
+        let responder = match args.get(1).cloned().unwrap_or(Value::Null) {
+            Value::Object(responder) => Some(NetConnectionObject::Avm2(responder)),
+            _ => None,
+        };
+
+        let version =
+            match object_encoding(activation, Some(this), &[])?.coerce_to_u32(activation)? {
+                3 => AMF3_VERSION,
+                _ => AMF0_VERSION,
+            };
+        let mut amf_args = Vec::new();
+        for arg in args.get(2..).unwrap_or_default() {
+            amf_args.push(remoting::avm2_to_amf(activation, arg.clone(), version)?);
+        }
+
+        NetConnections::call(
+            &mut activation.context,
+            object,
+            responder,
+            &command,
+            amf_args,
+            version,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `NetConnection.close`
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let object = NetConnectionObject::Avm2(this);
+        if activation.context.net_connections.close(object) {
+            dispatch_net_status(activation, this, "NetConnection.Connect.Closed", "status")?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `NetConnection.addHeader`
+pub fn add_header<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let name = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?
+            .to_string();
+        let must_understand = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Bool(false))
+            .coerce_to_boolean();
+        // Headers are always sent as AMF0.
+        let value = remoting::avm2_to_amf(
+            activation,
+            args.get(2).cloned().unwrap_or(Value::Null),
+            AMF0_VERSION,
+        )?;
+
+        activation.context.net_connections.add_header(
+            NetConnectionObject::Avm2(this),
+            name,
+            must_understand,
+            value,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `NetConnection.connected`'s getter
+pub fn connected<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return Ok(activation
+            .context
+            .net_connections
+            .is_connected(NetConnectionObject::Avm2(this))
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `NetConnection.uri`'s getter
+pub fn uri<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let object = NetConnectionObject::Avm2(this);
+        let connections = &activation.context.net_connections;
+        if let Some(gateway) = connections.gateway(object) {
+            return Ok(AvmString::new(activation.context.gc_context, gateway.to_string()).into());
+        } else if connections.is_connected(object) {
+            return Ok("null".into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `NetConnection.objectEncoding`'s getter
+pub fn object_encoding<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "objectEncoding"),
+            activation,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `NetConnection.objectEncoding`'s setter
+pub fn set_object_encoding<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let encoding = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_u32(activation)?;
+        if encoding != u32::from(AMF0_VERSION) && encoding != u32::from(AMF3_VERSION) {
+            return Err("ArgumentError: Error #2008: Parameter objectEncoding must be one of the accepted values.".into());
+        }
+
+        this.set_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "objectEncoding"),
+            encoding.into(),
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `NetConnection`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.net"), "NetConnection"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<NetConnection instance initializer>", mc),
+        Method::from_builtin(class_init, "<NetConnection class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("connect", connect),
+        ("call", call),
+        ("close", close),
+        ("addHeader", add_header),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("connected", Some(connected), None),
+        ("uri", Some(uri), None),
+        (
+            "objectEncoding",
+            Some(object_encoding),
+            Some(set_object_encoding),
+        ),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "client"),
+        QName::new(Namespace::public(), "Object").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "objectEncoding"),
+        QName::new(Namespace::public(), "uint").into(),
+        None,
+    ));
+
+    class
+}
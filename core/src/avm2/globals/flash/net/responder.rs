@@ -0,0 +1,97 @@
+//! `flash.net.Responder` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.net.Responder`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let result = args.get(0).cloned().unwrap_or(Value::Null);
+        let status = args.get(1).cloned().unwrap_or(Value::Null);
+        this.set_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "result"),
+            result,
+            activation,
+        )?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "status"),
+            status,
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.net.Responder`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Get the function a responder was given to handle either the result of a
+/// call or its failure.
+pub fn callback<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    responder: Object<'gc>,
+    is_result: bool,
+) -> Result<Option<Object<'gc>>, Error> {
+    let name = if is_result { "result" } else { "status" };
+    let callback = responder.get_property(
+        responder,
+        &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), name),
+        activation,
+    )?;
+
+    Ok(match callback {
+        Value::Object(callback) => Some(callback),
+        _ => None,
+    })
+}
+
+/// Construct `Responder`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.net"), "Responder"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<Responder instance initializer>", mc),
+        Method::from_builtin(class_init, "<Responder class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "result"),
+        QName::new(Namespace::public(), "Function").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "status"),
+        QName::new(Namespace::public(), "Function").into(),
+        None,
+    ));
+
+    class
+}
@@ -0,0 +1,391 @@
+//! `flash.net.SharedObject` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::amf::{Amf3Reader, Amf3Writer};
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, ScriptObject, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::shared_object;
+use crate::string::AvmString;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.net.SharedObject`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.net.SharedObject`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Get the name that a shared object is stored under.
+fn storage_name<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<String, Error> {
+    Ok(this
+        .get_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "name"),
+            activation,
+        )?
+        .coerce_to_string(activation)?
+        .to_string())
+}
+
+/// Get the object holding the data of a shared object.
+fn data_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<Object<'gc>, Error> {
+    this.get_property(this, &QName::new(Namespace::public(), "data"), activation)?
+        .coerce_to_object(activation)
+}
+
+/// Serialize the data of a shared object into a `.sol` file.
+fn serialize<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<Vec<u8>, Error> {
+    let name = storage_name(activation, this)?;
+    let data = data_object(activation, this)?;
+
+    let mut names = Vec::new();
+    let mut last_index = 0;
+    while let Some(index) = data.get_next_enumerant(last_index) {
+        if let Some(name) = data.get_enumerant_name(index, activation) {
+            if name.namespace().is_public() {
+                names.push(name);
+            }
+        }
+        last_index = index;
+    }
+
+    let bytearray_class = activation.avm2().classes().bytearray;
+    let body = bytearray_class.construct(activation, &[])?;
+    let mut writer = Amf3Writer::new(body);
+    for name in names {
+        let value = data.get_property(data, &name, activation)?;
+        writer.write_member(activation, name.local_name(), value)?;
+    }
+    writer.finish(activation)?;
+
+    let body = body
+        .as_bytearray()
+        .map(|bytes| bytes.bytes().clone())
+        .unwrap_or_default();
+
+    Ok(shared_object::write_sol(
+        shared_object::short_name(&name),
+        shared_object::AMF3_VERSION,
+        &body,
+    ))
+}
+
+/// Read the members of a `.sol` file written as AMF3 into a data object.
+fn deserialize<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    saved: &[u8],
+    mut data: Object<'gc>,
+) -> Result<(), Error> {
+    let body = match shared_object::read_sol(saved) {
+        Some((shared_object::AMF3_VERSION, body)) => body,
+        _ => return Ok(()),
+    };
+
+    let bytearray_class = activation.avm2().classes().bytearray;
+    let source = bytearray_class.construct(activation, &[])?;
+    source
+        .as_bytearray_mut(activation.context.gc_context)
+        .ok_or("Shared object data must be a ByteArray")?
+        .write_bytes(body)?;
+    source
+        .as_bytearray()
+        .ok_or("Shared object data must be a ByteArray")?
+        .set_position(0);
+
+    let mut reader = Amf3Reader::new(source);
+    while let Some((name, value)) = reader.read_member(activation)? {
+        data.set_property(
+            data,
+            &QName::new(Namespace::public(), name),
+            value,
+            activation,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Implements `SharedObject.getLocal`
+pub fn get_local<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let name = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    let local_path = match args.get(1).cloned().unwrap_or(Value::Null) {
+        Value::Null | Value::Undefined => None,
+        local_path => Some(local_path.coerce_to_string(activation)?),
+    };
+    let secure = args
+        .get(2)
+        .cloned()
+        .unwrap_or(Value::Bool(false))
+        .coerce_to_boolean();
+
+    let full_name = match shared_object::local_name(
+        activation.context.swf.url(),
+        &name,
+        local_path.as_deref(),
+        secure,
+    ) {
+        Some(full_name) => full_name,
+        None => return Err("Error: Error #2134: Cannot create SharedObject.".into()),
+    };
+
+    // Every request for the same name shares one object.
+    if let Some(so) = activation.context.avm2_shared_objects.get(&full_name) {
+        return Ok((*so).into());
+    }
+
+    let shared_object_class = activation.avm2().classes().sharedobject;
+    let mut this = shared_object_class.construct(activation, &[])?;
+    this.set_property(
+        this,
+        &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "name"),
+        AvmString::new(activation.context.gc_context, full_name.clone()).into(),
+        activation,
+    )?;
+
+    let data = ScriptObject::object(
+        activation.context.gc_context,
+        activation.avm2().prototypes().object,
+    );
+    if let Some(saved) = activation.context.storage.get(&full_name) {
+        deserialize(activation, &saved, data)?;
+    }
+    this.set_property(
+        this,
+        &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "data"),
+        data.into(),
+        activation,
+    )?;
+
+    activation
+        .context
+        .avm2_shared_objects
+        .insert(full_name, this);
+
+    Ok(this.into())
+}
+
+/// Implements `SharedObject.data`
+pub fn data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "data"),
+            activation,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `SharedObject.flush`
+///
+/// There is no way to ask the user for more space, so a flush that doesn't
+/// fit fails instead of returning `SharedObjectFlushStatus.PENDING`.
+pub fn flush<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let bytes = serialize(activation, this)?;
+        let min_disk_space = args
+            .get(0)
+            .cloned()
+            .unwrap_or_else(|| 0.into())
+            .coerce_to_number(activation)?
+            .max(0.0) as usize;
+
+        let name = storage_name(activation, this)?;
+        if !shared_object::fits_in_storage(
+            &*activation.context.storage,
+            bytes.len(),
+            min_disk_space,
+        ) || !activation.context.storage.put(&name, &bytes)
+        {
+            return Err("Error: Error #2130: Unable to flush SharedObject.".into());
+        }
+
+        return Ok("flushed".into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `SharedObject.clear`
+pub fn clear<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let data = ScriptObject::object(
+            activation.context.gc_context,
+            activation.avm2().prototypes().object,
+        );
+        this.set_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "data"),
+            data.into(),
+            activation,
+        )?;
+
+        let name = storage_name(activation, this)?;
+        activation.context.storage.remove_key(&name);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `SharedObject.close`
+///
+/// Local shared objects have no connection to close.
+pub fn close<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `SharedObject.setProperty`
+pub fn set_property<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let name = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?;
+        let value = args.get(1).cloned().unwrap_or(Value::Undefined);
+
+        let mut data = data_object(activation, this)?;
+        let name = QName::new(Namespace::public(), name);
+        if matches!(value, Value::Null | Value::Undefined) {
+            data.delete_property(activation.context.gc_context, &name);
+        } else {
+            data.set_property(data, &name, value, activation)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `SharedObject.size`
+pub fn size<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return Ok((serialize(activation, this)?.len() as u32).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Write every shared object the movie has opened to storage.
+pub fn flush_all<'gc>(activation: &mut Activation<'_, 'gc, '_>) {
+    let shared_objects: Vec<Object<'gc>> = activation
+        .context
+        .avm2_shared_objects
+        .values()
+        .copied()
+        .collect();
+
+    for so in shared_objects {
+        if let Err(e) = flush(activation, Some(so), &[]) {
+            log::warn!("Unable to flush SharedObject: {}", e);
+        }
+    }
+}
+
+/// Construct `SharedObject`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.net"), "SharedObject"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<SharedObject instance initializer>", mc),
+        Method::from_builtin(class_init, "<SharedObject class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_CLASS_METHODS: &[(&str, NativeMethodImpl)] = &[("getLocal", get_local)];
+    write.define_public_builtin_class_methods(mc, PUBLIC_CLASS_METHODS);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("flush", flush),
+        ("clear", clear),
+        ("close", close),
+        ("setProperty", set_property),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[("data", Some(data), None), ("size", Some(size), None)];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "name"),
+        QName::new(Namespace::public(), "String").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "data"),
+        QName::new(Namespace::public(), "Object").into(),
+        None,
+    ));
+
+    class
+}
@@ -0,0 +1,359 @@
+//! `flash.net.Socket` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::utils::bytearray;
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{bytearray_allocator, Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::socket::{SocketTarget, Sockets};
+use gc_arena::{GcCell, MutationContext};
+use std::convert::TryFrom;
+use std::time::Duration;
+use url::Url;
+
+/// The error thrown when using a socket that isn't connected.
+pub const INVALID_SOCKET: &str = "IOError: Error #2002: Operation attempted on invalid socket.";
+
+/// Implements `flash.net.Socket`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        // Data written to the socket is held in a separate `ByteArray` until
+        // it is flushed. The socket itself buffers the data it receives.
+        let bytearray_class = activation.avm2().classes().bytearray;
+        let output = bytearray_class.construct(activation, &[])?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "output"),
+            output.into(),
+            activation,
+        )?;
+
+        if let Some(host) = args.get(0) {
+            if !matches!(host, Value::Null | Value::Undefined) {
+                connect(activation, Some(this), args)?;
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.net.Socket`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Get the `ByteArray` holding the data written to a socket.
+fn output_buffer<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<Object<'gc>, Error> {
+    this.get_property(
+        this,
+        &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "output"),
+        activation,
+    )?
+    .coerce_to_object(activation)
+}
+
+/// Get the host and port that `connect` was called with.
+pub fn connect_address<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<(String, u16), Error> {
+    // Without a host, Flash connects to the server the movie came from.
+    let host = match args.get(0).cloned().unwrap_or(Value::Undefined) {
+        Value::Null | Value::Undefined => activation
+            .context
+            .swf
+            .url()
+            .and_then(|url| Url::parse(url).ok())
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_else(|| "localhost".to_string()),
+        host => host.coerce_to_string(activation)?.to_string(),
+    };
+    let port = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_i32(activation)?;
+    match u16::try_from(port) {
+        Ok(port) if port != 0 => Ok((host, port)),
+        _ => Err("SecurityError: Error #2003: Invalid socket port number specified.".into()),
+    }
+}
+
+/// Implements `Socket.connect`
+pub fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let (host, port) = connect_address(activation, args)?;
+        let timeout = this
+            .get_property(
+                this,
+                &QName::new(Namespace::public(), "timeout"),
+                activation,
+            )?
+            .coerce_to_u32(activation)?;
+
+        // Data left over from a previous connection can't be read anymore.
+        if let Some(mut input) = this.as_bytearray_mut(activation.context.gc_context) {
+            input.clear();
+        }
+        if let Some(mut output) =
+            output_buffer(activation, this)?.as_bytearray_mut(activation.context.gc_context)
+        {
+            output.clear();
+        }
+
+        Sockets::connect(
+            &mut activation.context,
+            SocketTarget::Avm2Socket(this),
+            &host,
+            port,
+            Duration::from_millis(timeout.into()),
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Socket.close`
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if !Sockets::close(&mut activation.context, SocketTarget::Avm2Socket(this)) {
+            return Err(INVALID_SOCKET.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Socket.flush`
+pub fn flush<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let output = output_buffer(activation, this)?;
+        let data = match output.as_bytearray_mut(activation.context.gc_context) {
+            Some(mut output) => {
+                let data = output.bytes().clone();
+                output.clear();
+                data
+            }
+            None => return Ok(Value::Undefined),
+        };
+
+        if !Sockets::send(
+            &mut activation.context,
+            SocketTarget::Avm2Socket(this),
+            data,
+        ) {
+            return Err(INVALID_SOCKET.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Socket.connected`
+pub fn connected<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return Ok(activation
+            .context
+            .sockets
+            .is_connected(SocketTarget::Avm2Socket(this))
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Socket.bytesPending`
+pub fn bytes_pending<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(output) = output_buffer(activation, this)?.as_bytearray() {
+            return Ok(output.len().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Socket.endian`'s setter.
+///
+/// Data is read and written with the same byte order.
+pub fn set_endian<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        bytearray::set_endian(activation, Some(this), args)?;
+
+        let output = output_buffer(activation, this)?;
+        bytearray::set_endian(activation, Some(output), args)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Define natives that buffer data to be sent, using the `ByteArray` natives
+/// of the same name on the socket's output buffer.
+macro_rules! output_methods {
+    ($($name:ident),* $(,)?) => {
+        $(
+            pub fn $name<'gc>(
+                activation: &mut Activation<'_, 'gc, '_>,
+                this: Option<Object<'gc>>,
+                args: &[Value<'gc>],
+            ) -> Result<Value<'gc>, Error> {
+                if let Some(this) = this {
+                    if !activation
+                        .context
+                        .sockets
+                        .is_connected(SocketTarget::Avm2Socket(this))
+                    {
+                        return Err(INVALID_SOCKET.into());
+                    }
+
+                    let output = output_buffer(activation, this)?;
+                    return bytearray::$name(activation, Some(output), args);
+                }
+
+                Ok(Value::Undefined)
+            }
+        )*
+    };
+}
+
+output_methods!(
+    write_boolean,
+    write_byte,
+    write_bytes,
+    write_double,
+    write_float,
+    write_int,
+    write_multibyte,
+    write_object,
+    write_short,
+    write_unsigned_int,
+    write_utf,
+    write_utf_bytes,
+);
+
+/// Construct `Socket`'s class.
+///
+/// Received data is buffered in the same storage as a `ByteArray`, so the
+/// `IDataInput` methods are shared with it.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.net"), "Socket"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<Socket instance initializer>", mc),
+        Method::from_builtin(class_init, "<Socket class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+    write.set_instance_allocator(bytearray_allocator);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("connect", connect),
+        ("close", close),
+        ("flush", flush),
+        ("readBoolean", bytearray::read_boolean),
+        ("readByte", bytearray::read_byte),
+        ("readBytes", bytearray::read_bytes),
+        ("readDouble", bytearray::read_double),
+        ("readFloat", bytearray::read_float),
+        ("readInt", bytearray::read_int),
+        ("readMultiByte", bytearray::read_multibyte),
+        ("readObject", bytearray::read_object),
+        ("readShort", bytearray::read_short),
+        ("readUnsignedByte", bytearray::read_unsigned_byte),
+        ("readUnsignedInt", bytearray::read_unsigned_int),
+        ("readUnsignedShort", bytearray::read_unsigned_short),
+        ("readUTF", bytearray::read_utf),
+        ("readUTFBytes", bytearray::read_utf_bytes),
+        ("writeBoolean", write_boolean),
+        ("writeByte", write_byte),
+        ("writeBytes", write_bytes),
+        ("writeDouble", write_double),
+        ("writeFloat", write_float),
+        ("writeInt", write_int),
+        ("writeMultiByte", write_multibyte),
+        ("writeObject", write_object),
+        ("writeShort", write_short),
+        ("writeUnsignedInt", write_unsigned_int),
+        ("writeUTF", write_utf),
+        ("writeUTFBytes", write_utf_bytes),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("bytesAvailable", Some(bytearray::bytes_available), None),
+        ("bytesPending", Some(bytes_pending), None),
+        ("connected", Some(connected), None),
+        ("endian", Some(bytearray::endian), Some(set_endian)),
+        (
+            "objectEncoding",
+            Some(bytearray::object_encoding),
+            Some(bytearray::set_object_encoding),
+        ),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "timeout"),
+        QName::new(Namespace::public(), "uint").into(),
+        Some(20000.into()),
+    ));
+
+    // Slot for the buffer of data waiting to be flushed.
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "output"),
+        QName::new(Namespace::package("flash.utils"), "ByteArray").into(),
+        None,
+    ));
+
+    class
+}
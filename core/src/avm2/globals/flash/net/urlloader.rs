@@ -0,0 +1,139 @@
+//! `flash.net.URLLoader` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::net::urlrequest::request_options;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Multiname, Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.net.URLLoader`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+
+        if let Some(Value::Object(request)) = args.get(0) {
+            spawn_load(activation, this, *request)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.net.URLLoader`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `URLLoader.load`
+pub fn load<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let request = match args.get(0).cloned().unwrap_or(Value::Undefined) {
+            Value::Object(request) => request,
+            _ => return Err("TypeError: Error #2007: Parameter request must be non-null.".into()),
+        };
+
+        spawn_load(activation, this, request)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `URLLoader.close`
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.context.load_manager.cancel_url_loads(this);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Start loading a `URLRequest` into a `URLLoader`, cancelling any load it
+/// already has in progress.
+fn spawn_load<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    loader: Object<'gc>,
+    request: Object<'gc>,
+) -> Result<(), Error> {
+    let (url, options) = request_options(activation, request)?;
+
+    activation.context.load_manager.cancel_url_loads(loader);
+
+    let fetch = activation.context.security.fetch_data(
+        activation.context.navigator,
+        activation.context.swf,
+        &url,
+        options,
+    );
+    let process = activation.context.load_manager.load_data_into_url_loader(
+        activation.context.player.clone().unwrap(),
+        loader,
+        fetch,
+        url,
+    );
+    activation.context.navigator.spawn_future(process);
+
+    Ok(())
+}
+
+/// Construct `URLLoader`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.net"), "URLLoader"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<URLLoader instance initializer>", mc),
+        Method::from_builtin(class_init, "<URLLoader class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] =
+        &[("load", load), ("close", close)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "data"),
+        Multiname::any(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "dataFormat"),
+        QName::new(Namespace::public(), "String").into(),
+        Some("text".into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "bytesLoaded"),
+        QName::new(Namespace::public(), "uint").into(),
+        Some(0.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "bytesTotal"),
+        QName::new(Namespace::public(), "uint").into(),
+        Some(0.into()),
+    ));
+
+    class
+}
@@ -0,0 +1,258 @@
+//! `flash.net.URLRequest` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::net::urlvariables::object_into_form_values;
+use crate::avm2::method::Method;
+use crate::avm2::names::{Multiname, Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::backend::navigator::{NavigationMethod, RequestOptions};
+use gc_arena::{GcCell, MutationContext};
+use url::form_urlencoded;
+
+/// The content type of requests that send variables in their body.
+const FORM_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+
+/// Implements `flash.net.URLRequest`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let url = match args.get(0) {
+            None | Some(Value::Null) | Some(Value::Undefined) => Value::Null,
+            Some(url) => url.coerce_to_string(activation)?.into(),
+        };
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "url"),
+            url,
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.net.URLRequest`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Read the URL of a `URLRequest`, as given by the movie.
+pub fn request_url<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    request: Object<'gc>,
+) -> Result<String, Error> {
+    match request.get_property(request, &QName::new(Namespace::public(), "url"), activation)? {
+        Value::Null | Value::Undefined => Ok(String::new()),
+        url => Ok(url.coerce_to_string(activation)?.to_string()),
+    }
+}
+
+/// Read the HTTP method of a `URLRequest`.
+///
+/// Methods other than `GET` and `POST` aren't supported by navigator
+/// backends, and are sent as `GET` requests.
+pub fn request_method<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    request: Object<'gc>,
+) -> Result<NavigationMethod, Error> {
+    let method = request
+        .get_property(
+            request,
+            &QName::new(Namespace::public(), "method"),
+            activation,
+        )?
+        .coerce_to_string(activation)?;
+
+    Ok(
+        NavigationMethod::from_method_str(&method.to_ascii_uppercase())
+            .unwrap_or(NavigationMethod::Get),
+    )
+}
+
+/// Read the variables sent by a `URLRequest` from its `data`.
+///
+/// `URLVariables` (or any other object) contribute their public dynamic
+/// properties, and strings are parsed as URL-encoded variables. Returns
+/// `None` if there are no variables to send, including when the data is a
+/// `ByteArray`.
+pub fn request_variables<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    request: Object<'gc>,
+) -> Result<Option<Vec<(String, String)>>, Error> {
+    match request.get_property(
+        request,
+        &QName::new(Namespace::public(), "data"),
+        activation,
+    )? {
+        Value::Null | Value::Undefined => Ok(None),
+        Value::Object(data) if data.as_bytearray().is_some() => Ok(None),
+        Value::Object(data) => Ok(Some(object_into_form_values(activation, data)?)),
+        data => {
+            let data = data.coerce_to_string(activation)?;
+            Ok(Some(
+                form_urlencoded::parse(data.as_bytes())
+                    .into_owned()
+                    .collect(),
+            ))
+        }
+    }
+}
+
+/// Read the HTTP headers of a `URLRequest` from its `requestHeaders`.
+///
+/// Each entry is expected to be a `URLRequestHeader`; headers without a name
+/// are skipped.
+pub fn request_headers<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    request: Object<'gc>,
+) -> Result<Vec<(String, String)>, Error> {
+    let headers = match request.get_property(
+        request,
+        &QName::new(Namespace::public(), "requestHeaders"),
+        activation,
+    )? {
+        Value::Object(headers) => headers,
+        _ => return Ok(Vec::new()),
+    };
+    let entries: Vec<_> = match headers.as_array_storage() {
+        Some(storage) => storage.iter().collect(),
+        None => return Ok(Vec::new()),
+    };
+
+    let mut result = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let header = match entry {
+            Some(Value::Object(header)) => header,
+            _ => continue,
+        };
+        let name = header
+            .get_property(header, &QName::new(Namespace::public(), "name"), activation)?
+            .coerce_to_string(activation)?;
+        let value = header
+            .get_property(
+                header,
+                &QName::new(Namespace::public(), "value"),
+                activation,
+            )?
+            .coerce_to_string(activation)?;
+        if !name.is_empty() {
+            result.push((name.to_string(), value.to_string()));
+        }
+    }
+
+    Ok(result)
+}
+
+/// Build the URL and options to fetch a `URLRequest` with.
+///
+/// `GET` requests have their variables appended to the URL, while `POST`
+/// requests send their data as the request body, with the request's content
+/// type. Both send the request's headers.
+pub fn request_options<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    request: Object<'gc>,
+) -> Result<(String, RequestOptions), Error> {
+    let mut url = request_url(activation, request)?;
+    let method = request_method(activation, request)?;
+    let headers = request_headers(activation, request)?;
+    let data = request.get_property(
+        request,
+        &QName::new(Namespace::public(), "data"),
+        activation,
+    )?;
+
+    let body = match data {
+        Value::Object(data) => data.as_bytearray().map(|bytes| bytes.bytes().clone()),
+        _ => None,
+    };
+    let body = match (body, request_variables(activation, request)?) {
+        (Some(body), _) => Some(body),
+        (None, Some(variables)) => {
+            let query_string = form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(variables)
+                .finish();
+            Some(query_string.into_bytes())
+        }
+        (None, None) => None,
+    };
+
+    match (method, body) {
+        (NavigationMethod::Post, body) => {
+            let content_type = request
+                .get_property(
+                    request,
+                    &QName::new(Namespace::public(), "contentType"),
+                    activation,
+                )?
+                .coerce_to_string(activation)?
+                .to_string();
+            Ok((
+                url,
+                RequestOptions::post(Some((body.unwrap_or_default(), content_type)))
+                    .with_headers(headers),
+            ))
+        }
+        (NavigationMethod::Get, Some(query_string)) if !query_string.is_empty() => {
+            url.push(if url.contains('?') { '&' } else { '?' });
+            url.push_str(&String::from_utf8_lossy(&query_string));
+            Ok((url, RequestOptions::get().with_headers(headers)))
+        }
+        (NavigationMethod::Get, _) => Ok((url, RequestOptions::get().with_headers(headers))),
+    }
+}
+
+/// Construct `URLRequest`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.net"), "URLRequest"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<URLRequest instance initializer>", mc),
+        Method::from_builtin(class_init, "<URLRequest class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "url"),
+        QName::new(Namespace::public(), "String").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "method"),
+        QName::new(Namespace::public(), "String").into(),
+        Some("GET".into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "contentType"),
+        QName::new(Namespace::public(), "String").into(),
+        Some(FORM_CONTENT_TYPE.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "data"),
+        Multiname::any(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "requestHeaders"),
+        QName::new(Namespace::public(), "Array").into(),
+        None,
+    ));
+
+    class
+}
@@ -0,0 +1,167 @@
+//! `flash.net.URLStream` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::net::urlrequest::request_options;
+use crate::avm2::globals::flash::utils::bytearray;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{bytearray_allocator, Object, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.net.URLStream`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.net.URLStream`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `URLStream.load`
+///
+/// Any load already in progress is cancelled, and data that hasn't been read
+/// yet is discarded.
+pub fn load<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let request = match args.get(0).cloned().unwrap_or(Value::Undefined) {
+            Value::Object(request) => request,
+            _ => return Err("TypeError: Error #2007: Parameter request must be non-null.".into()),
+        };
+
+        let (url, options) = request_options(activation, request)?;
+
+        activation.context.load_manager.cancel_url_loads(this);
+        if let Some(mut buffer) = this.as_bytearray_mut(activation.context.gc_context) {
+            buffer.clear();
+        }
+
+        let fetch = activation.context.security.fetch_data(
+            activation.context.navigator,
+            activation.context.swf,
+            &url,
+            options,
+        );
+        let process = activation.context.load_manager.load_data_into_url_stream(
+            activation.context.player.clone().unwrap(),
+            this,
+            fetch,
+            url,
+        );
+        activation.context.navigator.spawn_future(process);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `URLStream.close`
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if !activation.context.load_manager.has_url_loads(this) {
+            return Err(
+                "IOError: Error #2029: This URLStream object does not have a stream opened.".into(),
+            );
+        }
+
+        activation.context.load_manager.cancel_url_loads(this);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `URLStream.connected`
+pub fn connected<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return Ok(activation.context.load_manager.has_url_loads(this).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `URLStream`'s class.
+///
+/// Loaded data is buffered in the same storage as a `ByteArray`, so the
+/// `IDataInput` methods are shared with it.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.net"), "URLStream"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<URLStream instance initializer>", mc),
+        Method::from_builtin(class_init, "<URLStream class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+    write.set_instance_allocator(bytearray_allocator);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("load", load),
+        ("close", close),
+        ("readBoolean", bytearray::read_boolean),
+        ("readByte", bytearray::read_byte),
+        ("readBytes", bytearray::read_bytes),
+        ("readDouble", bytearray::read_double),
+        ("readFloat", bytearray::read_float),
+        ("readInt", bytearray::read_int),
+        ("readMultiByte", bytearray::read_multibyte),
+        ("readObject", bytearray::read_object),
+        ("readShort", bytearray::read_short),
+        ("readUnsignedByte", bytearray::read_unsigned_byte),
+        ("readUnsignedInt", bytearray::read_unsigned_int),
+        ("readUnsignedShort", bytearray::read_unsigned_short),
+        ("readUTF", bytearray::read_utf),
+        ("readUTFBytes", bytearray::read_utf_bytes),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("bytesAvailable", Some(bytearray::bytes_available), None),
+        ("connected", Some(connected), None),
+        (
+            "endian",
+            Some(bytearray::endian),
+            Some(bytearray::set_endian),
+        ),
+        (
+            "objectEncoding",
+            Some(bytearray::object_encoding),
+            Some(bytearray::set_object_encoding),
+        ),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    class
+}
@@ -0,0 +1,177 @@
+//! `flash.net.URLVariables` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::array::ArrayStorage;
+use crate::avm2::class::Class;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{ArrayObject, Object, TObject};
+use crate::avm2::string::AvmString;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+use indexmap::IndexMap;
+use url::form_urlencoded;
+
+/// Implements `flash.net.URLVariables`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+
+        match args.get(0) {
+            None | Some(Value::Null) | Some(Value::Undefined) => {}
+            Some(_) => {
+                decode(activation, Some(this), args)?;
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.net.URLVariables`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `URLVariables.decode`.
+///
+/// Variables that appear more than once become arrays of their values.
+pub fn decode<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let source = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?;
+
+        if source
+            .split('&')
+            .any(|pair| !pair.is_empty() && !pair.contains('='))
+        {
+            return Err("Error: Error #2101: The String passed to URLVariables.decode() must be a URL-encoded query string containing name/value pairs.".into());
+        }
+
+        let mut variables: IndexMap<String, Vec<String>> = IndexMap::new();
+        for (name, value) in form_urlencoded::parse(source.as_bytes()) {
+            variables
+                .entry(name.into_owned())
+                .or_default()
+                .push(value.into_owned());
+        }
+
+        for (name, mut values) in variables {
+            let value = if values.len() == 1 {
+                AvmString::new(activation.context.gc_context, values.remove(0)).into()
+            } else {
+                let storage: ArrayStorage<'gc> = values
+                    .into_iter()
+                    .map(|value| Value::from(AvmString::new(activation.context.gc_context, value)))
+                    .collect();
+                ArrayObject::from_storage(activation, storage)?.into()
+            };
+
+            let name = QName::dynamic_name(AvmString::new(activation.context.gc_context, name));
+            this.set_property(this, &name, value, activation)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `URLVariables.toString`.
+pub fn to_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let variables = object_into_form_values(activation, this)?;
+        let query_string = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(variables)
+            .finish();
+
+        return Ok(AvmString::new(activation.context.gc_context, query_string).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Collect the public dynamic properties of an object as name/value pairs,
+/// in the form they are sent with a request.
+///
+/// Array values are sent as one pair per element.
+pub fn object_into_form_values<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<Vec<(String, String)>, Error> {
+    let mut names = Vec::new();
+    let mut last_index = 0;
+    while let Some(index) = object.get_next_enumerant(last_index) {
+        if let Some(name) = object.get_enumerant_name(index, activation) {
+            if name.namespace().is_public() {
+                names.push(name);
+            }
+        }
+        last_index = index;
+    }
+
+    let mut form_values = Vec::new();
+    for name in names {
+        let value = object.get_property(object, &name, activation)?;
+        let elements = match value {
+            Value::Object(array) => array
+                .as_array_storage()
+                .map(|storage| storage.iter().collect::<Vec<_>>()),
+            _ => None,
+        };
+
+        match elements {
+            Some(elements) => {
+                for element in elements {
+                    let element = element
+                        .unwrap_or(Value::Undefined)
+                        .coerce_to_string(activation)?;
+                    form_values.push((name.local_name().to_string(), element.to_string()));
+                }
+            }
+            None => {
+                let value = value.coerce_to_string(activation)?;
+                form_values.push((name.local_name().to_string(), value.to_string()));
+            }
+        }
+    }
+
+    Ok(form_values)
+}
+
+/// Construct `URLVariables`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.net"), "URLVariables"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<URLVariables instance initializer>", mc),
+        Method::from_builtin(class_init, "<URLVariables class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] =
+        &[("decode", decode), ("toString", to_string)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    class
+}
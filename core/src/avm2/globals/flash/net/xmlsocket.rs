@@ -0,0 +1,166 @@
+//! `flash.net.XMLSocket` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::net::socket::{connect_address, INVALID_SOCKET};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::socket::{SocketTarget, Sockets};
+use gc_arena::{GcCell, MutationContext};
+use std::time::Duration;
+
+/// Implements `flash.net.XMLSocket`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+
+        if let Some(host) = args.get(0) {
+            if !matches!(host, Value::Null | Value::Undefined) {
+                connect(activation, Some(this), args)?;
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.net.XMLSocket`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `XMLSocket.connect`
+pub fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let (host, port) = connect_address(activation, args)?;
+        let timeout = this
+            .get_property(
+                this,
+                &QName::new(Namespace::public(), "timeout"),
+                activation,
+            )?
+            .coerce_to_u32(activation)?;
+
+        Sockets::connect(
+            &mut activation.context,
+            SocketTarget::Avm2XmlSocket(this),
+            &host,
+            port,
+            Duration::from_millis(timeout.into()),
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XMLSocket.send`
+///
+/// The object is sent as a string, terminated by a null byte.
+pub fn send<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let message = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?;
+
+        let mut data = message.as_str().as_bytes().to_vec();
+        data.push(0);
+
+        if !Sockets::send(
+            &mut activation.context,
+            SocketTarget::Avm2XmlSocket(this),
+            data,
+        ) {
+            return Err(INVALID_SOCKET.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XMLSocket.close`
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if !Sockets::close(&mut activation.context, SocketTarget::Avm2XmlSocket(this)) {
+            return Err(INVALID_SOCKET.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XMLSocket.connected`
+pub fn connected<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return Ok(activation
+            .context
+            .sockets
+            .is_connected(SocketTarget::Avm2XmlSocket(this))
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `XMLSocket`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.net"), "XMLSocket"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<XMLSocket instance initializer>", mc),
+        Method::from_builtin(class_init, "<XMLSocket class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] =
+        &[("connect", connect), ("send", send), ("close", close)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[("connected", Some(connected), None)];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "timeout"),
+        QName::new(Namespace::public(), "int").into(),
+        Some(20000.into()),
+    ));
+
+    class
+}
@@ -3,5 +3,13 @@
 
 pub mod application_domain;
 pub mod capabilities;
+pub mod image_decoding_policy;
+pub mod ime;
+pub mod loader_context;
+pub mod message_channel;
+pub mod message_channel_state;
 pub mod security;
 pub mod system;
+pub mod worker;
+pub mod worker_domain;
+pub mod worker_state;
@@ -1,5 +1,6 @@
 //! `flash.system.Capabilities` class
 
+use crate::avm1::globals::system::SystemCapabilities;
 use crate::avm2::activation::Activation;
 use crate::avm2::class::{Class, ClassAttributes};
 use crate::avm2::method::{Method, NativeMethodImpl};
@@ -7,6 +8,7 @@ use crate::avm2::names::{Namespace, QName};
 use crate::avm2::object::Object;
 use crate::avm2::value::Value;
 use crate::avm2::Error;
+use crate::string::AvmString;
 use gc_arena::{GcCell, MutationContext};
 
 /// Implements `flash.system.Capabilities`'s instance constructor.
@@ -27,31 +29,249 @@ pub fn class_init<'gc>(
     Ok(Value::Undefined)
 }
 
+macro_rules! capabilities_func {
+    ($func_name: ident, $capability: expr) => {
+        pub fn $func_name<'gc>(
+            activation: &mut Activation<'_, 'gc, '_>,
+            _this: Option<Object<'gc>>,
+            _args: &[Value<'gc>],
+        ) -> Result<Value<'gc>, Error> {
+            Ok(activation.context.system.has_capability($capability).into())
+        }
+    };
+}
+
+macro_rules! inverse_capabilities_func {
+    ($func_name: ident, $capability: expr) => {
+        pub fn $func_name<'gc>(
+            activation: &mut Activation<'_, 'gc, '_>,
+            _this: Option<Object<'gc>>,
+            _args: &[Value<'gc>],
+        ) -> Result<Value<'gc>, Error> {
+            Ok((!activation.context.system.has_capability($capability)).into())
+        }
+    };
+}
+
+capabilities_func!(
+    supports_64_bit_processes,
+    SystemCapabilities::PROCESS_64_BIT
+);
+capabilities_func!(
+    supports_32_bit_processes,
+    SystemCapabilities::PROCESS_32_BIT
+);
+capabilities_func!(is_embedded_in_acrobat, SystemCapabilities::ACROBAT_EMBEDDED);
+capabilities_func!(has_tls, SystemCapabilities::TLS);
+capabilities_func!(has_accessibility, SystemCapabilities::ACCESSIBILITY);
+capabilities_func!(has_audio, SystemCapabilities::AUDIO);
+capabilities_func!(has_audio_encoder, SystemCapabilities::AUDIO_ENCODER);
+capabilities_func!(has_embedded_video, SystemCapabilities::EMBEDDED_VIDEO);
+capabilities_func!(has_ime, SystemCapabilities::IME);
+capabilities_func!(has_mp3, SystemCapabilities::MP3);
+capabilities_func!(has_printing, SystemCapabilities::PRINTING);
+capabilities_func!(has_screen_broadcast, SystemCapabilities::SCREEN_BROADCAST);
+capabilities_func!(has_screen_playback, SystemCapabilities::SCREEN_PLAYBACK);
+capabilities_func!(has_streaming_audio, SystemCapabilities::STREAMING_AUDIO);
+capabilities_func!(has_streaming_video, SystemCapabilities::STREAMING_VIDEO);
+capabilities_func!(has_video_encoder, SystemCapabilities::VIDEO_ENCODER);
+capabilities_func!(is_debugger, SystemCapabilities::DEBUGGER);
+inverse_capabilities_func!(local_file_read_disable, SystemCapabilities::LOCAL_FILE_READ);
+inverse_capabilities_func!(av_hardware_disable, SystemCapabilities::AV_HARDWARE);
+
+/// `cpuAddressSize` static property.
+pub fn cpu_address_size<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if activation
+        .context
+        .system
+        .has_capability(SystemCapabilities::PROCESS_64_BIT)
+    {
+        Ok(64.into())
+    } else {
+        Ok(32.into())
+    }
+}
+
+/// `cpuArchitecture` static property.
+pub fn cpu_architecture<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(AvmString::new(
+        activation.context.gc_context,
+        activation.context.system.cpu_architecture.to_string(),
+    )
+    .into())
+}
+
+/// `language` static property.
+pub fn language<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(AvmString::new(
+        activation.context.gc_context,
+        activation
+            .context
+            .system
+            .get_language_code(activation.context.avm1.player_version)
+            .to_string(),
+    )
+    .into())
+}
+
+/// `manufacturer` static property.
+pub fn manufacturer<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(AvmString::new(
+        activation.context.gc_context,
+        activation
+            .context
+            .system
+            .get_manufacturer_string(activation.context.avm1.player_version),
+    )
+    .into())
+}
+
+/// `maxLevelIDC` static property.
+pub fn max_level_idc<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(AvmString::new(
+        activation.context.gc_context,
+        activation.context.system.idc_level.clone(),
+    )
+    .into())
+}
+
 /// `os` static property.
 pub fn os<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(AvmString::new(
+        activation.context.gc_context,
+        activation.context.system.get_os_name(),
+    )
+    .into())
+}
+
+/// `pixelAspectRatio` static property.
+pub fn pixel_aspect_ratio<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Ok(Value::String("Linux 5.10.49".into())) // Temporary
+    Ok(activation.context.system.aspect_ratio.into())
 }
 
 /// `playerType` static property.
 pub fn player_type<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(AvmString::new(
+        activation.context.gc_context,
+        activation.context.system.get_player_type(),
+    )
+    .into())
+}
+
+/// `screenColor` static property.
+pub fn screen_color<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(AvmString::new(
+        activation.context.gc_context,
+        activation.context.system.screen_color.to_string(),
+    )
+    .into())
+}
+
+/// `screenDPI` static property.
+pub fn screen_dpi<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(activation.context.system.dpi.into())
+}
+
+/// `screenResolutionX` static property.
+pub fn screen_resolution_x<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(activation.context.system.get_screen_resolution().0.into())
+}
+
+/// `screenResolutionY` static property.
+pub fn screen_resolution_y<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(activation.context.system.get_screen_resolution().1.into())
+}
+
+/// `serverString` static property.
+pub fn server_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let server_string = activation
+        .context
+        .system
+        .get_server_string(activation.context.avm1);
+    Ok(AvmString::new(activation.context.gc_context, server_string).into())
+}
+
+/// `touchscreenType` static property.
+pub fn touchscreen_type<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Ok(Value::String("StandAlone".into())) // Temporary
+    Ok("none".into())
 }
 
 /// `version` static property.
 pub fn version<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let version = activation
+        .context
+        .system
+        .get_version_string(activation.context.avm1);
+    Ok(AvmString::new(activation.context.gc_context, version).into())
+}
+
+/// `hasMultiChannelAudio` static method.
+pub fn has_multi_channel_audio<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Ok(Value::String("LNX 32,0,0,465".into())) // Temporary
+    Ok(false.into())
 }
 
 /// Construct `Capabilities`'s class.
@@ -69,12 +289,55 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
     write.set_attributes(ClassAttributes::SEALED);
 
     const PUBLIC_CLASS_TRAITS: &[(&str, Option<NativeMethodImpl>, Option<NativeMethodImpl>)] = &[
+        ("avHardwareDisable", Some(av_hardware_disable), None),
+        ("cpuAddressSize", Some(cpu_address_size), None),
+        ("cpuArchitecture", Some(cpu_architecture), None),
+        ("hasAccessibility", Some(has_accessibility), None),
+        ("hasAudio", Some(has_audio), None),
+        ("hasAudioEncoder", Some(has_audio_encoder), None),
+        ("hasEmbeddedVideo", Some(has_embedded_video), None),
+        ("hasIME", Some(has_ime), None),
+        ("hasMP3", Some(has_mp3), None),
+        ("hasPrinting", Some(has_printing), None),
+        ("hasScreenBroadcast", Some(has_screen_broadcast), None),
+        ("hasScreenPlayback", Some(has_screen_playback), None),
+        ("hasStreamingAudio", Some(has_streaming_audio), None),
+        ("hasStreamingVideo", Some(has_streaming_video), None),
+        ("hasTLS", Some(has_tls), None),
+        ("hasVideoEncoder", Some(has_video_encoder), None),
+        ("isDebugger", Some(is_debugger), None),
+        ("isEmbeddedInAcrobat", Some(is_embedded_in_acrobat), None),
+        ("language", Some(language), None),
+        ("localFileReadDisable", Some(local_file_read_disable), None),
+        ("manufacturer", Some(manufacturer), None),
+        ("maxLevelIDC", Some(max_level_idc), None),
         ("os", Some(os), None),
+        ("pixelAspectRatio", Some(pixel_aspect_ratio), None),
         ("playerType", Some(player_type), None),
+        ("screenColor", Some(screen_color), None),
+        ("screenDPI", Some(screen_dpi), None),
+        ("screenResolutionX", Some(screen_resolution_x), None),
+        ("screenResolutionY", Some(screen_resolution_y), None),
+        ("serverString", Some(server_string), None),
+        (
+            "supports32BitProcesses",
+            Some(supports_32_bit_processes),
+            None,
+        ),
+        (
+            "supports64BitProcesses",
+            Some(supports_64_bit_processes),
+            None,
+        ),
+        ("touchscreenType", Some(touchscreen_type), None),
         ("version", Some(version), None),
     ];
 
     write.define_public_builtin_class_properties(mc, PUBLIC_CLASS_TRAITS);
 
+    const PUBLIC_CLASS_METHODS: &[(&str, NativeMethodImpl)] =
+        &[("hasMultiChannelAudio", has_multi_channel_audio)];
+    write.define_public_builtin_class_methods(mc, PUBLIC_CLASS_METHODS);
+
     class
 }
@@ -0,0 +1,236 @@
+//! `flash.system.IME` class
+//!
+//! Movies listen for text composed with an input method on the single `IME`
+//! object, which is found at `System.ime`.
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::{Avm2, Error};
+use crate::context::UpdateContext;
+use crate::display_object::TDisplayObject;
+use crate::string::AvmString;
+use gc_arena::{GcCell, MutationContext};
+
+/// Namespace for the internal state of the `IME` class.
+const NS_IME: &str = "https://ruffle.rs/AS3/impl/IME/";
+
+/// Implements `flash.system.IME`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.system.IME`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Get the `IME` object, creating it the first time it's asked for.
+pub fn ime_object<'gc>(activation: &mut Activation<'_, 'gc, '_>) -> Result<Object<'gc>, Error> {
+    let mut class = activation
+        .avm2()
+        .global_domain()
+        .get_defined_value(
+            activation,
+            QName::new(Namespace::package("flash.system"), "IME"),
+        )?
+        .coerce_to_object(activation)?;
+    let name = QName::new(Namespace::private(NS_IME), "instance");
+
+    if let Value::Object(ime) = class.get_property(class, &name, activation)? {
+        return Ok(ime);
+    }
+
+    let ime = class.construct(activation, &[])?;
+    class.set_property(class, &name, ime.into(), activation)?;
+
+    Ok(ime)
+}
+
+/// Dispatch an `imeComposition` event on the `IME` object, for text that the
+/// user has finished composing.
+pub fn dispatch_composition<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    text: &str,
+) -> Result<(), Error> {
+    let mut activation = Activation::from_nothing(context.reborrow());
+    let ime = ime_object(&mut activation)?;
+    let ime_event_class = activation.avm2().classes().imeevent;
+    let text = AvmString::new(activation.context.gc_context, text.to_string());
+    let event = ime_event_class.construct(
+        &mut activation,
+        &[
+            "imeComposition".into(),
+            false.into(),
+            false.into(),
+            text.into(),
+        ],
+    )?;
+    Avm2::dispatch_event_object(&mut activation.context, event, ime)?;
+
+    Ok(())
+}
+
+/// Implements `IME.isSupported`.
+pub fn is_supported<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(true.into())
+}
+
+/// Implements `IME.enabled`.
+///
+/// The input method is used whenever an input text field has focus.
+pub fn enabled<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let enabled = activation
+        .context
+        .focus_tracker
+        .get()
+        .and_then(|focus| focus.as_edit_text())
+        .map_or(false, |text| text.is_editable() && !text.is_password());
+
+    Ok(enabled.into())
+}
+
+/// Implements `IME.enabled`'s setter.
+pub fn set_enabled<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    log::warn!("IME.enabled setter not implemented");
+    Ok(Value::Undefined)
+}
+
+/// Implements `IME.conversionMode`.
+///
+/// The platform doesn't tell us which mode its input method is in.
+pub fn conversion_mode<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok("UNKNOWN".into())
+}
+
+/// Implements `IME.conversionMode`'s setter.
+pub fn set_conversion_mode<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    log::warn!("IME.conversionMode setter not implemented");
+    Ok(Value::Undefined)
+}
+
+/// Implements `IME.compositionAbandoned`.
+pub fn composition_abandoned<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(text) = activation
+        .context
+        .focus_tracker
+        .get()
+        .and_then(|focus| focus.as_edit_text())
+    {
+        text.set_composition("", &mut activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `IME.compositionSelectionChanged`.
+pub fn composition_selection_changed<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `IME.doConversion`.
+pub fn do_conversion<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    log::warn!("IME.doConversion not implemented");
+    Ok(Value::Undefined)
+}
+
+/// Implements `IME.setCompositionString`.
+pub fn set_composition_string<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    log::warn!("IME.setCompositionString not implemented");
+    Ok(Value::Undefined)
+}
+
+/// Construct `IME`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.system"), "IME"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<IME instance initializer>", mc),
+        Method::from_builtin(class_init, "<IME class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+
+    const PUBLIC_CLASS_PROPERTIES: &[(&str, Option<NativeMethodImpl>, Option<NativeMethodImpl>)] =
+        &[
+            ("isSupported", Some(is_supported), None),
+            ("enabled", Some(enabled), Some(set_enabled)),
+            (
+                "conversionMode",
+                Some(conversion_mode),
+                Some(set_conversion_mode),
+            ),
+        ];
+    write.define_public_builtin_class_properties(mc, PUBLIC_CLASS_PROPERTIES);
+
+    const PUBLIC_CLASS_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("compositionAbandoned", composition_abandoned),
+        ("compositionSelectionChanged", composition_selection_changed),
+        ("doConversion", do_conversion),
+        ("setCompositionString", set_composition_string),
+    ];
+    write.define_public_builtin_class_methods(mc, PUBLIC_CLASS_METHODS);
+
+    write.define_class_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_IME), "instance"),
+        QName::new(Namespace::package("flash.system"), "IME").into(),
+        None,
+    ));
+
+    class
+}
@@ -0,0 +1,117 @@
+//! `flash.system.LoaderContext` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.system.LoaderContext`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let check_policy_file = args
+            .get(0)
+            .cloned()
+            .unwrap_or_else(|| false.into())
+            .coerce_to_boolean();
+        let application_domain = args.get(1).cloned().unwrap_or(Value::Null);
+        let security_domain = args.get(2).cloned().unwrap_or(Value::Null);
+
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "checkPolicyFile"),
+            check_policy_file.into(),
+            activation,
+        )?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "applicationDomain"),
+            application_domain,
+            activation,
+        )?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "securityDomain"),
+            security_domain,
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.system.LoaderContext`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `LoaderContext`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.system"), "LoaderContext"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<LoaderContext instance initializer>", mc),
+        Method::from_builtin(class_init, "<LoaderContext class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "allowCodeImport"),
+        QName::new(Namespace::public(), "Boolean").into(),
+        Some(true.into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "applicationDomain"),
+        QName::new(Namespace::package("flash.system"), "ApplicationDomain").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "checkPolicyFile"),
+        QName::new(Namespace::public(), "Boolean").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "imageDecodingPolicy"),
+        QName::new(Namespace::public(), "String").into(),
+        Some("onDemand".into()),
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "parameters"),
+        QName::new(Namespace::public(), "Object").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "requestedContentParent"),
+        QName::new(
+            Namespace::package("flash.display"),
+            "DisplayObjectContainer",
+        )
+        .into(),
+        None,
+    ));
+    // TODO: This should be typed as `SecurityDomain`, which Ruffle doesn't
+    // have yet.
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "securityDomain"),
+        QName::new(Namespace::public(), "Object").into(),
+        None,
+    ));
+
+    class
+}
@@ -0,0 +1,235 @@
+//! `flash.system.MessageChannel` builtin/prototype
+//!
+//! `MessageChannel` objects are handles to channels in the player's worker
+//! registry. Both ends of a channel have their own object, in their own
+//! worker's VM.
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::worker::{share_value, unshare_value, ChannelStatus};
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Namespace for the internal state of a `MessageChannel`.
+const NS_MESSAGE_CHANNEL: &str = "https://ruffle.rs/AS3/impl/MessageChannel/";
+
+/// Get this VM's `MessageChannel` object for a channel, creating it if
+/// necessary.
+pub fn channel_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    id: u32,
+) -> Result<Object<'gc>, Error> {
+    if let Some(object) = activation.avm2().worker().channel_object(id) {
+        return Ok(object);
+    }
+
+    let message_channel_class = activation.avm2().classes().messagechannel;
+    let mut object = message_channel_class.construct(activation, &[])?;
+    object.set_property(
+        object,
+        &QName::new(Namespace::private(NS_MESSAGE_CHANNEL), "id"),
+        id.into(),
+        activation,
+    )?;
+    activation
+        .avm2()
+        .worker_mut()
+        .set_channel_object(id, object);
+
+    Ok(object)
+}
+
+/// Get the ID of the channel that an object refers to, if it is a
+/// `MessageChannel`.
+pub fn channel_id<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<Option<u32>, Error> {
+    let message_channel_class = activation.avm2().classes().messagechannel;
+    if !object.is_of_type(message_channel_class, activation)? {
+        return Ok(None);
+    }
+
+    let id = object
+        .get_property(
+            object,
+            &QName::new(Namespace::private(NS_MESSAGE_CHANNEL), "id"),
+            activation,
+        )?
+        .coerce_to_u32(activation)?;
+
+    Ok(Some(id))
+}
+
+/// Implements `flash.system.MessageChannel`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.system.MessageChannel`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `MessageChannel.state`.
+pub fn state<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(id) = channel_id(activation, this)? {
+            let registry = activation.avm2().worker().registry();
+            let state = registry.read().channel_status(id).as_str();
+
+            return Ok(state.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `MessageChannel.messageAvailable`.
+pub fn message_available<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(id) = channel_id(activation, this)? {
+            let registry = activation.avm2().worker().registry();
+            let is_available = registry.read().message_available(id);
+
+            return Ok(is_available.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `MessageChannel.send`.
+///
+/// Messages are copied as they are sent, except for workers, message channels
+/// and shareable `ByteArray`s. The receiver is sent `channelMessage` at the
+/// end of the frame.
+pub fn send<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(id) = channel_id(activation, this)? {
+            let registry = activation.avm2().worker().registry();
+            if registry.read().channel_status(id) != ChannelStatus::Open {
+                return Err("Error: The MessageChannel is closed.".into());
+            }
+
+            let message = args.get(0).cloned().unwrap_or(Value::Undefined);
+            let message = share_value(activation, message)?;
+            registry
+                .write(activation.context.gc_context)
+                .send(id, message);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `MessageChannel.receive`.
+///
+/// Receiving never blocks, since every worker runs on the same thread. If no
+/// message is available, `null` is returned.
+pub fn receive<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(id) = channel_id(activation, this)? {
+            let registry = activation.avm2().worker().registry();
+            let message = registry.write(activation.context.gc_context).receive(id);
+
+            return match message {
+                Some(message) => unshare_value(activation, &message),
+                None => Ok(Value::Null),
+            };
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `MessageChannel.close`.
+///
+/// The channel stays in the `closing` state until every queued message has
+/// been received.
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(id) = channel_id(activation, this)? {
+            let registry = activation.avm2().worker().registry();
+            registry
+                .write(activation.context.gc_context)
+                .close_channel(id);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `MessageChannel`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.system"), "MessageChannel"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<MessageChannel instance initializer>", mc),
+        Method::from_builtin(class_init, "<MessageChannel class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::FINAL | ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("state", Some(state), None),
+        ("messageAvailable", Some(message_available), None),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] =
+        &[("send", send), ("receive", receive), ("close", close)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_MESSAGE_CHANNEL), "id"),
+        QName::new(Namespace::public(), "uint").into(),
+        None,
+    ));
+
+    class
+}
@@ -36,29 +36,43 @@ fn sandbox_type<'gc>(
 }
 
 fn allow_domain<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    log::warn!("Security.allowDomain not implemented");
+    for domain in args {
+        let domain = domain.coerce_to_string(activation)?;
+        activation.context.security.allow_domain(&domain);
+    }
+
     Ok(Value::Undefined)
 }
 
+/// Movies loaded over HTTP can't be told apart from ones loaded over HTTPS
+/// when scripting, so this is the same as `allowDomain`.
 fn allow_insecure_domain<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    log::warn!("Security.allowInsecureDomain not implemented");
-    Ok(Value::Undefined)
+    allow_domain(activation, this, args)
 }
 
 fn load_policy_file<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    log::warn!("Security.loadPolicyFile not implemented");
+    let url = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    activation
+        .context
+        .security
+        .load_policy_file(activation.context.swf, &url);
+
     Ok(Value::Undefined)
 }
 
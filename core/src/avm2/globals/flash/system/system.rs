@@ -2,6 +2,7 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::Class;
+use crate::avm2::globals::flash::system::ime;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::names::{Namespace, QName};
 use crate::avm2::object::Object;
@@ -36,6 +37,33 @@ pub fn gc<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `System.setClipboard`
+pub fn set_clipboard<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let new_content = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?
+        .to_string();
+
+    activation.context.ui.set_clipboard_content(new_content);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `System.ime`
+pub fn get_ime<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(ime::ime_object(activation)?.into())
+}
+
 /// Construct `System`'s class.
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
@@ -48,8 +76,13 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
 
     let mut write = class.write(mc);
 
-    const PUBLIC_CLASS_METHODS: &[(&str, NativeMethodImpl)] = &[("gc", gc)];
+    const PUBLIC_CLASS_METHODS: &[(&str, NativeMethodImpl)] =
+        &[("gc", gc), ("setClipboard", set_clipboard)];
     write.define_public_builtin_class_methods(mc, PUBLIC_CLASS_METHODS);
 
+    const PUBLIC_CLASS_PROPERTIES: &[(&str, Option<NativeMethodImpl>, Option<NativeMethodImpl>)] =
+        &[("ime", Some(get_ime), None)];
+    write.define_public_builtin_class_properties(mc, PUBLIC_CLASS_PROPERTIES);
+
     class
 }
@@ -0,0 +1,313 @@
+//! `flash.system.Worker` builtin/prototype
+//!
+//! `Worker` objects are handles to workers in the player's worker registry.
+//! Every VM has at most one `Worker` object for each worker.
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::system::message_channel::channel_object;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::worker::{share_value, unshare_value, PRIMORDIAL_WORKER};
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Namespace for the internal state of a `Worker`.
+const NS_WORKER: &str = "https://ruffle.rs/AS3/impl/Worker/";
+
+/// Get this VM's `Worker` object for a worker, creating it if necessary.
+pub fn worker_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    id: u32,
+) -> Result<Object<'gc>, Error> {
+    if let Some(object) = activation.avm2().worker().worker_object(id) {
+        return Ok(object);
+    }
+
+    let worker_class = activation.avm2().classes().worker;
+    let mut object = worker_class.construct(activation, &[])?;
+    object.set_property(
+        object,
+        &QName::new(Namespace::private(NS_WORKER), "id"),
+        id.into(),
+        activation,
+    )?;
+    activation.avm2().worker_mut().set_worker_object(id, object);
+
+    Ok(object)
+}
+
+/// Get the ID of the worker that an object refers to, if it is a `Worker`.
+pub fn worker_id<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<Option<u32>, Error> {
+    let worker_class = activation.avm2().classes().worker;
+    if !object.is_of_type(worker_class, activation)? {
+        return Ok(None);
+    }
+
+    let id = object
+        .get_property(
+            object,
+            &QName::new(Namespace::private(NS_WORKER), "id"),
+            activation,
+        )?
+        .coerce_to_u32(activation)?;
+
+    Ok(Some(id))
+}
+
+/// Get the ID of the worker that `this` refers to.
+fn this_id<'gc>(activation: &mut Activation<'_, 'gc, '_>, this: Object<'gc>) -> Result<u32, Error> {
+    Ok(worker_id(activation, this)?.unwrap_or(PRIMORDIAL_WORKER))
+}
+
+/// Implements `flash.system.Worker`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.system.Worker`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `Worker.isSupported`.
+pub fn is_supported<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(true.into())
+}
+
+/// Implements `Worker.current`.
+pub fn current<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let id = activation.avm2().worker().id();
+
+    Ok(worker_object(activation, id)?.into())
+}
+
+/// Implements `Worker.isPrimordial`.
+pub fn is_primordial<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return Ok((this_id(activation, this)? == PRIMORDIAL_WORKER).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Worker.state`.
+pub fn state<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let id = this_id(activation, this)?;
+        let registry = activation.avm2().worker().registry();
+        let state = registry.read().worker_status(id).as_str();
+
+        return Ok(state.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Worker.getSharedProperty`.
+pub fn get_shared_property<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let id = this_id(activation, this)?;
+        let key = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?;
+
+        let registry = activation.avm2().worker().registry();
+        let value = registry.read().shared_property(id, &key.to_string());
+        if let Some(value) = value {
+            return unshare_value(activation, &value);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Worker.setSharedProperty`.
+///
+/// Values are copied as they are shared, except for workers, message
+/// channels and shareable `ByteArray`s.
+pub fn set_shared_property<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let id = this_id(activation, this)?;
+        let key = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?;
+        let value = args.get(1).cloned().unwrap_or(Value::Undefined);
+        let value = share_value(activation, value)?;
+
+        let registry = activation.avm2().worker().registry();
+        registry
+            .write(activation.context.gc_context)
+            .set_shared_property(id, key.to_string(), value);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Worker.createMessageChannel`.
+///
+/// The new channel sends messages from this worker to the given receiver.
+pub fn create_message_channel<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let sender = this_id(activation, this)?;
+        let receiver = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let receiver = worker_id(activation, receiver)?
+            .ok_or("ArgumentError: Error #2004: One of the parameters is invalid.")?;
+
+        let registry = activation.avm2().worker().registry();
+        let channel = registry
+            .write(activation.context.gc_context)
+            .create_channel(sender, receiver);
+
+        return Ok(channel_object(activation, channel)?.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Worker.start`.
+///
+/// The worker is brought up at the end of the current frame, at which point
+/// `workerState` is dispatched.
+pub fn start<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let id = this_id(activation, this)?;
+        let registry = activation.avm2().worker().registry();
+        if !registry
+            .write(activation.context.gc_context)
+            .start_worker(id)
+        {
+            return Err("Error: This worker has already been started.".into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Worker.terminate`.
+///
+/// The primordial worker cannot be terminated.
+pub fn terminate<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let id = this_id(activation, this)?;
+        let registry = activation.avm2().worker().registry();
+        let was_running = registry
+            .write(activation.context.gc_context)
+            .terminate_worker(id);
+
+        return Ok(was_running.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `Worker`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.system"), "Worker"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<Worker instance initializer>", mc),
+        Method::from_builtin(class_init, "<Worker class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::FINAL | ClassAttributes::SEALED);
+
+    const PUBLIC_CLASS_PROPERTIES: &[(&str, Option<NativeMethodImpl>, Option<NativeMethodImpl>)] =
+        &[
+            ("isSupported", Some(is_supported), None),
+            ("current", Some(current), None),
+        ];
+    write.define_public_builtin_class_properties(mc, PUBLIC_CLASS_PROPERTIES);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("isPrimordial", Some(is_primordial), None),
+        ("state", Some(state), None),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("getSharedProperty", get_shared_property),
+        ("setSharedProperty", set_shared_property),
+        ("createMessageChannel", create_message_channel),
+        ("start", start),
+        ("terminate", terminate),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_WORKER), "id"),
+        QName::new(Namespace::public(), "uint").into(),
+        None,
+    ));
+
+    class
+}
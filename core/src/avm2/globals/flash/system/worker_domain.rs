@@ -0,0 +1,129 @@
+//! `flash.system.WorkerDomain` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::system::worker::worker_object;
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Namespace for the internal state of a `WorkerDomain`.
+const NS_WORKER_DOMAIN: &str = "https://ruffle.rs/AS3/impl/WorkerDomain/";
+
+/// Implements `flash.system.WorkerDomain`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.system.WorkerDomain`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `WorkerDomain.isSupported`.
+pub fn is_supported<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(true.into())
+}
+
+/// Implements `WorkerDomain.current`.
+pub fn current<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let name = QName::new(Namespace::private(NS_WORKER_DOMAIN), "current");
+        if let Value::Object(current) = this.get_property(this, &name, activation)? {
+            return Ok(current.into());
+        }
+
+        let worker_domain_class = activation.avm2().classes().workerdomain;
+        let current = worker_domain_class.construct(activation, &[])?;
+        this.set_property(this, &name, current.into(), activation)?;
+
+        return Ok(current.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `WorkerDomain.createWorker`.
+///
+/// The SWF is copied out of the given `ByteArray`, and is not loaded until
+/// the worker is started.
+pub fn create_worker<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let swf = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?;
+    let swf = swf
+        .as_bytearray()
+        .map(|bytearray| bytearray.bytes().clone())
+        .ok_or("ArgumentError: Error #2004: One of the parameters is invalid.")?;
+
+    let registry = activation.avm2().worker().registry();
+    let id = registry
+        .write(activation.context.gc_context)
+        .create_worker(swf);
+
+    Ok(worker_object(activation, id)?.into())
+}
+
+/// Construct `WorkerDomain`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.system"), "WorkerDomain"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<WorkerDomain instance initializer>", mc),
+        Method::from_builtin(class_init, "<WorkerDomain class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::FINAL | ClassAttributes::SEALED);
+
+    const PUBLIC_CLASS_PROPERTIES: &[(&str, Option<NativeMethodImpl>, Option<NativeMethodImpl>)] =
+        &[
+            ("isSupported", Some(is_supported), None),
+            ("current", Some(current), None),
+        ];
+    write.define_public_builtin_class_properties(mc, PUBLIC_CLASS_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[("createWorker", create_worker)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    write.define_class_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_WORKER_DOMAIN), "current"),
+        QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "BareObject").into(),
+        None,
+    ));
+
+    class
+}
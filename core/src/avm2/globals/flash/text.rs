@@ -6,3 +6,4 @@ pub mod textfieldautosize;
 pub mod textfieldtype;
 pub mod textformat;
 pub mod textformatalign;
+pub mod textsnapshot;
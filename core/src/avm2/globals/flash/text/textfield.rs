@@ -798,6 +798,155 @@ pub fn set_selection<'gc>(
     Ok(Value::Undefined)
 }
 
+pub fn selection_begin_index<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this
+            .selection()
+            .map(|selection| selection.start())
+            .unwrap_or(0)
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn selection_end_index<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this
+            .selection()
+            .map(|selection| selection.end())
+            .unwrap_or(0)
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn caret_index<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this
+            .selection()
+            .map(|selection| selection.to())
+            .unwrap_or(0)
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn selected_text<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(AvmString::new(activation.context.gc_context, this.selected_text()).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn max_chars<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this.max_chars().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_max_chars<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let max_chars = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+
+        this.set_max_chars(max_chars, &mut activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn restrict<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return match this.restrict() {
+            Some(restrict) => Ok(AvmString::new(activation.context.gc_context, restrict).into()),
+            None => Ok(Value::Null),
+        };
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_restrict<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        match args.get(0) {
+            Some(Value::Undefined) | Some(Value::Null) | None => {
+                this.set_restrict(None, &mut activation.context)
+            }
+            Some(value) => {
+                let restrict = value.coerce_to_string(activation)?;
+                this.set_restrict(Some(restrict.as_str()), &mut activation.context);
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
 pub fn set_text_format<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
@@ -878,6 +1027,7 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ),
         ("border", Some(border), Some(set_border)),
         ("borderColor", Some(border_color), Some(set_border_color)),
+        ("caretIndex", Some(caret_index), None),
         (
             "defaultTextFormat",
             Some(default_text_format),
@@ -891,8 +1041,13 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("embedFonts", Some(embed_fonts), Some(set_embed_fonts)),
         ("htmlText", Some(html_text), Some(set_html_text)),
         ("length", Some(length), None),
+        ("maxChars", Some(max_chars), Some(set_max_chars)),
         ("multiline", Some(multiline), Some(set_multiline)),
+        ("restrict", Some(restrict), Some(set_restrict)),
         ("selectable", Some(selectable), Some(set_selectable)),
+        ("selectedText", Some(selected_text), None),
+        ("selectionBeginIndex", Some(selection_begin_index), None),
+        ("selectionEndIndex", Some(selection_end_index), None),
         ("text", Some(text), Some(set_text)),
         ("textColor", Some(text_color), Some(set_text_color)),
         ("textHeight", Some(text_height), None),
@@ -0,0 +1,317 @@
+//! `flash.text.TextSnapshot` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, StageObject, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::display_object::{DisplayObject, TDisplayObject, TDisplayObjectContainer, Text};
+use crate::string::{utils as string_utils, AvmString};
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.text.TextSnapshot`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.text.TextSnapshot`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct the `TextSnapshot` of a container.
+pub fn snapshot_for<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    container: DisplayObject<'gc>,
+) -> Result<Object<'gc>, Error> {
+    let class = activation.avm2().classes().textsnapshot;
+    Ok(StageObject::for_display_object(activation, container, class)?.into())
+}
+
+/// The static texts a snapshot covers: those directly inside its container,
+/// in depth order.
+///
+/// A snapshot made with `new TextSnapshot()` has no container, and is empty.
+fn texts<'gc>(this: Option<Object<'gc>>) -> Vec<Text<'gc>> {
+    this.and_then(|this| this.as_display_object())
+        .and_then(|container| container.as_container())
+        .map(|container| {
+            container
+                .iter_render_list()
+                .filter_map(|child| child.as_text())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The characters of all of the texts in a snapshot, with the text that each
+/// one belongs to and its index in that text.
+fn characters<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    texts: &[Text<'gc>],
+) -> Vec<(char, Text<'gc>, usize)> {
+    texts
+        .iter()
+        .flat_map(|text| {
+            text.text(&activation.context)
+                .chars()
+                .enumerate()
+                .map(|(index, c)| (c, *text, index))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Join characters into a string, with a line break between characters from
+/// different texts if `line_endings` is set.
+fn collect_text<'gc>(
+    characters: impl Iterator<Item = (char, Text<'gc>, usize)>,
+    line_endings: bool,
+) -> String {
+    let mut text = String::new();
+    let mut last: Option<DisplayObject<'gc>> = None;
+    for (c, owner, _) in characters {
+        let owner: DisplayObject<'gc> = owner.into();
+        if let Some(last) = last {
+            if line_endings && !DisplayObject::ptr_eq(last, owner) {
+                text.push('\n');
+            }
+        }
+        text.push(c);
+        last = Some(owner);
+    }
+    text
+}
+
+fn arg_index<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+) -> Result<usize, Error> {
+    Ok(args
+        .get(index)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_i32(activation)?
+        .max(0) as usize)
+}
+
+/// Implements `TextSnapshot.charCount`
+pub fn char_count<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let count: usize = texts(this).iter().map(|text| text.glyph_count()).sum();
+    Ok(count.into())
+}
+
+/// Implements `TextSnapshot.findText`
+pub fn find_text<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let begin_index = arg_index(activation, args, 0)?;
+    let pattern = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    let case_sensitive = args
+        .get(2)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_boolean();
+
+    let texts = texts(this);
+    let text: String = characters(activation, &texts)
+        .into_iter()
+        .map(|(c, _, _)| c)
+        .collect();
+    let from = match text.char_indices().nth(begin_index) {
+        Some((from, _)) => from,
+        None => return Ok((-1).into()),
+    };
+
+    let found = if case_sensitive {
+        text[from..]
+            .find(pattern.as_str())
+            .map(|start| from + start)
+    } else {
+        string_utils::swf_find_ignore_case(&text, &pattern, from).map(|(start, _)| start)
+    };
+
+    Ok(match found {
+        Some(start) if !pattern.is_empty() => (text[..start].chars().count() as i32).into(),
+        _ => (-1).into(),
+    })
+}
+
+/// Implements `TextSnapshot.getSelected`
+pub fn get_selected<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let begin_index = arg_index(activation, args, 0)?;
+    let end_index = arg_index(activation, args, 1)?;
+
+    let texts = texts(this);
+    let selected = characters(activation, &texts)
+        .into_iter()
+        .take(end_index)
+        .skip(begin_index)
+        .any(|(_, text, index)| text.is_selected(index));
+
+    Ok(selected.into())
+}
+
+/// Implements `TextSnapshot.getSelectedText`
+pub fn get_selected_text<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let line_endings = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_boolean();
+
+    let texts = texts(this);
+    let text = collect_text(
+        characters(activation, &texts)
+            .into_iter()
+            .filter(|(_, text, index)| text.is_selected(*index)),
+        line_endings,
+    );
+
+    Ok(AvmString::new(activation.context.gc_context, text).into())
+}
+
+/// Implements `TextSnapshot.getText`
+pub fn get_text<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let begin_index = arg_index(activation, args, 0)?;
+    let end_index = arg_index(activation, args, 1)?;
+    let line_endings = args
+        .get(2)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_boolean();
+
+    let texts = texts(this);
+    let text = collect_text(
+        characters(activation, &texts)
+            .into_iter()
+            .take(end_index)
+            .skip(begin_index),
+        line_endings,
+    );
+
+    Ok(AvmString::new(activation.context.gc_context, text).into())
+}
+
+/// Implements `TextSnapshot.setSelectColor`
+pub fn set_select_color<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let color = match args.get(0) {
+        Some(color) => color.coerce_to_u32(activation)?,
+        None => 0xFFFF00,
+    };
+
+    for text in texts(this) {
+        text.set_select_color(
+            activation.context.gc_context,
+            swf::Color::from_rgb(color, 255),
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `TextSnapshot.setSelected`
+pub fn set_selected<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let begin_index = arg_index(activation, args, 0)?;
+    let end_index = arg_index(activation, args, 1)?;
+    let select = args
+        .get(2)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_boolean();
+
+    let mut offset = 0;
+    for text in texts(this) {
+        let count = text.glyph_count();
+        if begin_index < offset + count && offset < end_index {
+            text.set_selected(
+                activation.context.gc_context,
+                begin_index.saturating_sub(offset),
+                end_index - offset,
+                select,
+            );
+        }
+        offset += count;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `TextSnapshot`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.text"), "TextSnapshot"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<TextSnapshot instance initializer>", mc),
+        Method::from_builtin(class_init, "<TextSnapshot class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[("charCount", Some(char_count), None)];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("findText", find_text),
+        ("getSelected", get_selected),
+        ("getSelectedText", get_selected_text),
+        ("getText", get_text),
+        ("setSelectColor", set_select_color),
+        ("setSelected", set_selected),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    class
+}
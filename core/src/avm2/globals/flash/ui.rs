@@ -0,0 +1,15 @@
+//! `flash.ui` namespace
+
+pub mod contextmenu;
+pub mod contextmenubuiltinitems;
+pub mod contextmenuitem;
+pub mod gameinput;
+pub mod gameinputcontrol;
+pub mod gameinputdevice;
+pub mod keyboard;
+pub mod keylocation;
+pub mod mouse;
+pub mod mousecursor;
+pub mod mousecursordata;
+pub mod multitouch;
+pub mod multitouchinputmode;
@@ -0,0 +1,249 @@
+//! `flash.ui.ContextMenu` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::array::ArrayStorage;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::ui::{contextmenubuiltinitems, contextmenuitem};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{ArrayObject, Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::context_menu::{self, BuiltInItemFlags};
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.ui.ContextMenu`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let builtin_items = activation
+            .avm2()
+            .global_domain()
+            .get_defined_value(
+                activation,
+                QName::new(Namespace::package("flash.ui"), "ContextMenuBuiltInItems"),
+            )?
+            .coerce_to_object(activation)?
+            .construct(activation, &[])?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "builtInItems"),
+            builtin_items.into(),
+            activation,
+        )?;
+
+        let custom_items = ArrayObject::empty(activation)?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "customItems"),
+            custom_items.into(),
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.ui.ContextMenu`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `ContextMenu.hideBuiltInItems`.
+///
+/// The settings item can never be hidden.
+pub fn hide_builtin_items<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Value::Object(mut builtin_items) = this.get_property(
+            this,
+            &QName::new(Namespace::public(), "builtInItems"),
+            activation,
+        )? {
+            for name in contextmenubuiltinitems::ITEM_NAMES {
+                builtin_items.set_property(
+                    builtin_items,
+                    &QName::new(Namespace::public(), *name),
+                    false.into(),
+                    activation,
+                )?;
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ContextMenu.clone`.
+pub fn clone<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let mut copy = activation
+            .avm2()
+            .global_domain()
+            .get_defined_value(
+                activation,
+                QName::new(Namespace::package("flash.ui"), "ContextMenu"),
+            )?
+            .coerce_to_object(activation)?
+            .construct(activation, &[])?;
+
+        let builtin_items = this
+            .get_property(
+                this,
+                &QName::new(Namespace::public(), "builtInItems"),
+                activation,
+            )?
+            .coerce_to_object(activation)?;
+        let builtin_items = contextmenubuiltinitems::clone(activation, Some(builtin_items), &[])?;
+        copy.set_property(
+            copy,
+            &QName::new(Namespace::public(), "builtInItems"),
+            builtin_items,
+            activation,
+        )?;
+
+        let mut custom_items = ArrayStorage::new(0);
+        for item in custom_items_of(activation, this)? {
+            custom_items.push(contextmenuitem::clone(activation, Some(item), &[])?);
+        }
+        let custom_items = ArrayObject::from_storage(activation, custom_items)?;
+        copy.set_property(
+            copy,
+            &QName::new(Namespace::public(), "customItems"),
+            custom_items.into(),
+            activation,
+        )?;
+
+        return Ok(copy.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Collect the custom items of a context menu.
+fn custom_items_of<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    menu: Object<'gc>,
+) -> Result<Vec<Object<'gc>>, Error> {
+    let custom_items = menu.get_property(
+        menu,
+        &QName::new(Namespace::public(), "customItems"),
+        activation,
+    )?;
+    let custom_items = match custom_items {
+        Value::Object(custom_items) => custom_items,
+        _ => return Ok(Vec::new()),
+    };
+    let items = custom_items
+        .as_array_storage()
+        .map(|storage| {
+            storage
+                .iter()
+                .filter_map(|item| match item {
+                    Some(Value::Object(item)) => Some(item),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(items)
+}
+
+/// Build the items of the context menu for a movie that set `contextMenu`.
+pub fn make_context_menu_state<'gc>(
+    menu: Option<Object<'gc>>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<context_menu::ContextMenuState<'gc>, Error> {
+    let mut result = context_menu::ContextMenuState::new();
+
+    let mut item_flags = BuiltInItemFlags::for_stage(activation.context.stage);
+    if let Some(menu) = menu {
+        if let Value::Object(builtin_items) = menu.get_property(
+            menu,
+            &QName::new(Namespace::public(), "builtInItems"),
+            activation,
+        )? {
+            contextmenubuiltinitems::apply_to_flags(activation, builtin_items, &mut item_flags)?;
+        }
+    }
+    result.build_builtin_items(item_flags, activation.context.stage);
+
+    if let Some(menu) = menu {
+        for (i, item) in custom_items_of(activation, menu)?.into_iter().enumerate() {
+            let mut property = |name: &'static str| {
+                item.get_property(item, &QName::new(Namespace::public(), name), activation)
+            };
+            let visible = property("visible")?.coerce_to_boolean();
+            let enabled = property("enabled")?.coerce_to_boolean();
+            let separator_before = property("separatorBefore")?.coerce_to_boolean();
+            let caption = property("caption")?;
+            let caption = caption.coerce_to_string(activation)?;
+
+            if !visible {
+                continue;
+            }
+
+            result.push(
+                context_menu::ContextMenuItem {
+                    enabled,
+                    separator_before: separator_before || i == 0,
+                    caption: caption.to_string(),
+                    checked: false,
+                },
+                context_menu::ContextMenuCallback::Avm2 { item },
+            );
+        }
+    }
+
+    Ok(result)
+}
+
+/// Construct `ContextMenu`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.ui"), "ContextMenu"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<ContextMenu instance initializer>", mc),
+        Method::from_builtin(class_init, "<ContextMenu class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] =
+        &[("hideBuiltInItems", hide_builtin_items), ("clone", clone)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "builtInItems"),
+        QName::new(Namespace::package("flash.ui"), "ContextMenuBuiltInItems").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "customItems"),
+        QName::new(Namespace::public(), "Array").into(),
+        None,
+    ));
+
+    class
+}
@@ -0,0 +1,136 @@
+//! `flash.ui.ContextMenuBuiltInItems` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::context_menu::BuiltInItemFlags;
+use gc_arena::{GcCell, MutationContext};
+
+/// The names of the built-in items that can be hidden.
+pub const ITEM_NAMES: &[&str] = &[
+    "forwardAndBack",
+    "loop",
+    "play",
+    "print",
+    "quality",
+    "rewind",
+    "save",
+    "zoom",
+];
+
+/// Implements `flash.ui.ContextMenuBuiltInItems`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.ui.ContextMenuBuiltInItems`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `ContextMenuBuiltInItems.clone`.
+pub fn clone<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let mut copy = activation
+            .avm2()
+            .global_domain()
+            .get_defined_value(
+                activation,
+                QName::new(Namespace::package("flash.ui"), "ContextMenuBuiltInItems"),
+            )?
+            .coerce_to_object(activation)?
+            .construct(activation, &[])?;
+        for name in ITEM_NAMES {
+            let name = QName::new(Namespace::public(), *name);
+            let value = this.get_property(this, &name, activation)?;
+            copy.set_property(copy, &name, value, activation)?;
+        }
+
+        return Ok(copy.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Narrow down the built-in items to those that the movie hasn't hidden.
+pub fn apply_to_flags<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    builtin_items: Object<'gc>,
+    item_flags: &mut BuiltInItemFlags,
+) -> Result<(), Error> {
+    let mut is_shown = |name: &'static str| -> Result<bool, Error> {
+        builtin_items
+            .get_property(
+                builtin_items,
+                &QName::new(Namespace::public(), name),
+                activation,
+            )
+            .map(|value| value.coerce_to_boolean())
+    };
+    item_flags.forward_and_back &= is_shown("forwardAndBack")?;
+    item_flags.loop_ &= is_shown("loop")?;
+    item_flags.play &= is_shown("play")?;
+    item_flags.print &= is_shown("print")?;
+    item_flags.quality &= is_shown("quality")?;
+    item_flags.rewind &= is_shown("rewind")?;
+    item_flags.zoom &= is_shown("zoom")?;
+
+    Ok(())
+}
+
+/// Construct `ContextMenuBuiltInItems`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.ui"), "ContextMenuBuiltInItems"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(
+            instance_init,
+            "<ContextMenuBuiltInItems instance initializer>",
+            mc,
+        ),
+        Method::from_builtin(
+            class_init,
+            "<ContextMenuBuiltInItems class initializer>",
+            mc,
+        ),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[("clone", clone)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    for name in ITEM_NAMES {
+        write.define_instance_trait(Trait::from_slot(
+            QName::new(Namespace::public(), *name),
+            QName::new(Namespace::public(), "Boolean").into(),
+            Some(true.into()),
+        ));
+    }
+
+    class
+}
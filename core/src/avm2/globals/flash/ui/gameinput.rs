@@ -0,0 +1,264 @@
+//! `flash.ui.GameInput` builtin/prototype
+//!
+//! Every `GameInput` instance is told when devices are added or removed, so
+//! the class keeps a list of them, along with the objects for the devices
+//! that are connected.
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::ui::gameinputdevice;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{ArrayObject, Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::{Avm2, Error, Event};
+use crate::context::UpdateContext;
+use gc_arena::{GcCell, MutationContext};
+use std::convert::TryFrom;
+
+/// Namespace for the internal state of the `GameInput` class.
+const NS_GAME_INPUT: &str = "https://ruffle.rs/AS3/impl/GameInput/";
+
+/// Implements `flash.ui.GameInput`'s instance constructor.
+///
+/// Instances are never forgotten, as they may be the only thing listening
+/// for new devices.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+
+        let instances = class_array(activation, "instances")?;
+        if let Some(mut instances) = instances.as_array_storage_mut(activation.context.gc_context) {
+            instances.push(this.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.ui.GameInput`'s class constructor.
+pub fn class_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        for name in &["devices", "instances"] {
+            let array = ArrayObject::empty(activation)?;
+            this.set_property(
+                this,
+                &QName::new(Namespace::private(NS_GAME_INPUT), *name),
+                array.into(),
+                activation,
+            )?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Get one of the arrays that the `GameInput` class keeps.
+fn class_array<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    name: &'static str,
+) -> Result<Object<'gc>, Error> {
+    let class = activation
+        .avm2()
+        .global_domain()
+        .get_defined_value(
+            activation,
+            QName::new(Namespace::package("flash.ui"), "GameInput"),
+        )?
+        .coerce_to_object(activation)?;
+
+    class
+        .get_property(
+            class,
+            &QName::new(Namespace::private(NS_GAME_INPUT), name),
+            activation,
+        )?
+        .coerce_to_object(activation)
+}
+
+/// Get the values in one of the arrays that the `GameInput` class keeps.
+fn class_array_values<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    name: &'static str,
+) -> Result<Vec<Object<'gc>>, Error> {
+    let array = class_array(activation, name)?;
+    let values = array
+        .as_array_storage()
+        .map(|storage| {
+            storage
+                .iter()
+                .filter_map(|value| match value {
+                    Some(Value::Object(object)) => Some(object),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(values)
+}
+
+/// Find the object for a connected device, and its position among the
+/// connected devices.
+fn find_device<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    id: u32,
+) -> Result<Option<(usize, Object<'gc>)>, Error> {
+    for (index, device) in class_array_values(activation, "devices")?
+        .into_iter()
+        .enumerate()
+    {
+        if gameinputdevice::device_id(activation, device)? == id {
+            return Ok(Some((index, device)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Dispatch a `GameInputEvent` about a device to every `GameInput`.
+fn dispatch_device_event<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    event_type: &'static str,
+    device: Object<'gc>,
+) -> Result<(), Error> {
+    let game_input_event_class = activation.avm2().classes().gameinputevent;
+    for instance in class_array_values(activation, "instances")? {
+        let event = game_input_event_class.construct(
+            activation,
+            &[event_type.into(), false.into(), false.into(), device.into()],
+        )?;
+        Avm2::dispatch_event_object(&mut activation.context, event, instance)?;
+    }
+
+    Ok(())
+}
+
+/// Add the object for a newly connected device, and announce it with a
+/// `deviceAdded` event.
+pub fn device_added<'gc>(context: &mut UpdateContext<'_, 'gc, '_>, id: u32) -> Result<(), Error> {
+    let mut activation = Activation::from_nothing(context.reborrow());
+    let device = gameinputdevice::create_device(&mut activation, id)?;
+
+    let devices = class_array(&mut activation, "devices")?;
+    if let Some(mut devices) = devices.as_array_storage_mut(activation.context.gc_context) {
+        devices.push(device.into());
+    }
+
+    dispatch_device_event(&mut activation, "deviceAdded", device)
+}
+
+/// Remove the object for a disconnected device, and announce it with a
+/// `deviceRemoved` event.
+pub fn device_removed<'gc>(context: &mut UpdateContext<'_, 'gc, '_>, id: u32) -> Result<(), Error> {
+    let mut activation = Activation::from_nothing(context.reborrow());
+    let (index, device) = match find_device(&mut activation, id)? {
+        Some(found) => found,
+        None => return Ok(()),
+    };
+
+    let devices = class_array(&mut activation, "devices")?;
+    if let Some(mut devices) = devices.as_array_storage_mut(activation.context.gc_context) {
+        devices.splice(index..=index, Vec::new()).for_each(drop);
+    }
+
+    dispatch_device_event(&mut activation, "deviceRemoved", device)
+}
+
+/// Dispatch a `change` event on the object for a control whose value has
+/// changed.
+pub fn control_changed<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    id: u32,
+    index: usize,
+) -> Result<(), Error> {
+    let mut activation = Activation::from_nothing(context.reborrow());
+    if let Some((_, device)) = find_device(&mut activation, id)? {
+        if let Some(control) = gameinputdevice::control_at(&mut activation, device, index)? {
+            Avm2::dispatch_event(&mut activation.context, Event::new("change"), control)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Implements `GameInput.isSupported`.
+pub fn is_supported<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(true.into())
+}
+
+/// Implements `GameInput.numDevices`.
+pub fn num_devices<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok((class_array_values(activation, "devices")?.len() as i32).into())
+}
+
+/// Implements `GameInput.getDeviceAt`.
+pub fn get_device_at<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let index = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_i32(activation)?;
+    let devices = class_array_values(activation, "devices")?;
+
+    match usize::try_from(index).ok().and_then(|i| devices.get(i)) {
+        Some(device) => Ok((*device).into()),
+        None => Err("RangeError: Error #2006: The supplied index is out of bounds.".into()),
+    }
+}
+
+/// Construct `GameInput`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.ui"), "GameInput"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<GameInput instance initializer>", mc),
+        Method::from_builtin(class_init, "<GameInput class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_CLASS_PROPERTIES: &[(&str, Option<NativeMethodImpl>, Option<NativeMethodImpl>)] =
+        &[
+            ("isSupported", Some(is_supported), None),
+            ("numDevices", Some(num_devices), None),
+        ];
+    write.define_public_builtin_class_properties(mc, PUBLIC_CLASS_PROPERTIES);
+
+    const PUBLIC_CLASS_METHODS: &[(&str, NativeMethodImpl)] = &[("getDeviceAt", get_device_at)];
+    write.define_public_builtin_class_methods(mc, PUBLIC_CLASS_METHODS);
+
+    for name in &["devices", "instances"] {
+        write.define_class_trait(Trait::from_slot(
+            QName::new(Namespace::private(NS_GAME_INPUT), *name),
+            QName::new(Namespace::public(), "Array").into(),
+            None,
+        ));
+    }
+
+    class
+}
@@ -0,0 +1,210 @@
+//! `flash.ui.GameInputControl` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::ui::gameinputdevice;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::game_input;
+use crate::string::AvmString;
+use gc_arena::{GcCell, MutationContext};
+
+/// Namespace for the internal state of the `GameInputControl` class.
+const NS_GAME_INPUT_CONTROL: &str = "https://ruffle.rs/AS3/impl/GameInputControl/";
+
+/// Implements `flash.ui.GameInputControl`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.ui.GameInputControl`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Create the object for the control at an index on a device.
+pub fn create_control<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    device: Object<'gc>,
+    index: usize,
+) -> Result<Object<'gc>, Error> {
+    let mut control = activation
+        .avm2()
+        .global_domain()
+        .get_defined_value(
+            activation,
+            QName::new(Namespace::package("flash.ui"), "GameInputControl"),
+        )?
+        .coerce_to_object(activation)?
+        .construct(activation, &[])?;
+    control.set_property(
+        control,
+        &QName::new(Namespace::private(NS_GAME_INPUT_CONTROL), "device"),
+        device.into(),
+        activation,
+    )?;
+    control.set_property(
+        control,
+        &QName::new(Namespace::private(NS_GAME_INPUT_CONTROL), "index"),
+        (index as u32).into(),
+        activation,
+    )?;
+
+    Ok(control)
+}
+
+/// Get the index of a control object on its device.
+fn control_index<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<usize, Error> {
+    Ok(this
+        .get_property(
+            this,
+            &QName::new(Namespace::private(NS_GAME_INPUT_CONTROL), "index"),
+            activation,
+        )?
+        .coerce_to_u32(activation)? as usize)
+}
+
+/// Implements `GameInputControl.device`.
+pub fn device<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return this.get_property(
+            this,
+            &QName::new(Namespace::private(NS_GAME_INPUT_CONTROL), "device"),
+            activation,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `GameInputControl.id`.
+pub fn id<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let index = control_index(activation, this)?;
+        return Ok(
+            AvmString::new(activation.context.gc_context, game_input::control_id(index)).into(),
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `GameInputControl.minValue`.
+pub fn min_value<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let index = control_index(activation, this)?;
+        return Ok(game_input::control_range(index).0.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `GameInputControl.maxValue`.
+pub fn max_value<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let index = control_index(activation, this)?;
+        return Ok(game_input::control_range(index).1.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `GameInputControl.value`.
+///
+/// The value stops changing once the device has been disconnected.
+pub fn value<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let index = control_index(activation, this)?;
+        let device = device(activation, Some(this), &[])?.coerce_to_object(activation)?;
+        let id = gameinputdevice::device_id(activation, device)?;
+        let value = activation
+            .context
+            .game_input
+            .device(id)
+            .and_then(|device| device.value(index))
+            .unwrap_or(0.0);
+        return Ok(value.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `GameInputControl`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.ui"), "GameInputControl"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init, "<GameInputControl instance initializer>", mc),
+        Method::from_builtin(class_init, "<GameInputControl class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("device", Some(device), None),
+        ("id", Some(id), None),
+        ("minValue", Some(min_value), None),
+        ("maxValue", Some(max_value), None),
+        ("value", Some(value), None),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_GAME_INPUT_CONTROL), "device"),
+        QName::new(Namespace::package("flash.ui"), "GameInputDevice").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_GAME_INPUT_CONTROL), "index"),
+        QName::new(Namespace::public(), "uint").into(),
+        None,
+    ));
+
+    class
+}
@@ -0,0 +1,402 @@
+//! `flash.ui.GameInputDevice` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::array::ArrayStorage;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::ui::gameinputcontrol;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{ArrayObject, Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::game_input::{self, GameInputDevice};
+use crate::string::AvmString;
+use gc_arena::{GcCell, MutationContext};
+
+/// Namespace for the internal state of the `GameInputDevice` class.
+const NS_GAME_INPUT_DEVICE: &str = "https://ruffle.rs/AS3/impl/GameInputDevice/";
+
+/// Implements `flash.ui.GameInputDevice`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.ui.GameInputDevice`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Create the object for a newly connected device, along with the objects
+/// for its controls.
+pub fn create_device<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    id: u32,
+) -> Result<Object<'gc>, Error> {
+    let mut device = activation
+        .avm2()
+        .global_domain()
+        .get_defined_value(
+            activation,
+            QName::new(Namespace::package("flash.ui"), "GameInputDevice"),
+        )?
+        .coerce_to_object(activation)?
+        .construct(activation, &[])?;
+    device.set_property(
+        device,
+        &QName::new(Namespace::private(NS_GAME_INPUT_DEVICE), "deviceId"),
+        id.into(),
+        activation,
+    )?;
+
+    let mut controls = ArrayStorage::new(0);
+    for index in 0..game_input::NUM_CONTROLS {
+        let control = gameinputcontrol::create_control(activation, device, index)?;
+        controls.push(control.into());
+    }
+    let controls = ArrayObject::from_storage(activation, controls)?;
+    device.set_property(
+        device,
+        &QName::new(Namespace::private(NS_GAME_INPUT_DEVICE), "controls"),
+        controls.into(),
+        activation,
+    )?;
+
+    Ok(device)
+}
+
+/// Get the ID that the frontend gave to a device object's controller.
+pub fn device_id<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<u32, Error> {
+    this.get_property(
+        this,
+        &QName::new(Namespace::private(NS_GAME_INPUT_DEVICE), "deviceId"),
+        activation,
+    )?
+    .coerce_to_u32(activation)
+}
+
+/// Get the object for the control at an index on a device.
+pub fn control_at<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    index: usize,
+) -> Result<Option<Object<'gc>>, Error> {
+    let controls = this
+        .get_property(
+            this,
+            &QName::new(Namespace::private(NS_GAME_INPUT_DEVICE), "controls"),
+            activation,
+        )?
+        .coerce_to_object(activation)?;
+    let control = controls
+        .as_array_storage()
+        .and_then(|controls| controls.get(index));
+
+    Ok(match control {
+        Some(Value::Object(control)) => Some(control),
+        _ => None,
+    })
+}
+
+/// Run a function on the state of a device object's controller, if it is
+/// still connected.
+fn with_device<'gc, R>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    f: impl FnOnce(&mut GameInputDevice) -> R,
+) -> Result<Option<R>, Error> {
+    if let Some(this) = this {
+        let id = device_id(activation, this)?;
+        return Ok(activation.context.game_input.device_mut(id).map(f));
+    }
+
+    Ok(None)
+}
+
+/// Implements `GameInputDevice.enabled`'s getter.
+pub fn enabled<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let enabled = with_device(activation, this, |device| device.is_enabled())?;
+
+    Ok(enabled.unwrap_or(false).into())
+}
+
+/// Implements `GameInputDevice.enabled`'s setter.
+pub fn set_enabled<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let enabled = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_boolean();
+    with_device(activation, this, |device| device.set_enabled(enabled))?;
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `GameInputDevice.id`.
+pub fn id<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let id = device_id(activation, this)?;
+        return Ok(AvmString::new(activation.context.gc_context, id.to_string()).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `GameInputDevice.name`.
+pub fn name<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    match with_device(activation, this, |device| device.name().to_string())? {
+        Some(name) => Ok(AvmString::new(activation.context.gc_context, name).into()),
+        None => Ok(Value::Null),
+    }
+}
+
+/// Implements `GameInputDevice.numControls`.
+pub fn num_controls<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok((game_input::NUM_CONTROLS as i32).into())
+}
+
+/// Implements `GameInputDevice.sampleInterval`'s getter.
+pub fn sample_interval<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let sample_interval = with_device(activation, this, |device| device.sample_interval())?;
+
+    Ok((sample_interval.unwrap_or_default() as i32).into())
+}
+
+/// Implements `GameInputDevice.sampleInterval`'s setter.
+pub fn set_sample_interval<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let sample_interval = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_i32(activation)?;
+    if sample_interval < 0 {
+        return Err(format!(
+            "RangeError: Error #2027: Parameter sampleInterval must be a non-negative number; got {}.",
+            sample_interval
+        )
+        .into());
+    }
+    with_device(activation, this, |device| {
+        device.set_sample_interval(sample_interval as u32)
+    })?;
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `GameInputDevice.getControlAt`.
+pub fn get_control_at<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let index = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let control = if index >= 0 {
+            control_at(activation, this, index as usize)?
+        } else {
+            None
+        };
+
+        return match control {
+            Some(control) => Ok(control.into()),
+            None => Err("RangeError: Error #2006: The supplied index is out of bounds.".into()),
+        };
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `GameInputDevice.startCachingSamples`.
+pub fn start_caching_samples<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let num_samples = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_i32(activation)?;
+    let control_ids = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?;
+    let control_ids: Vec<Value<'gc>> = control_ids
+        .as_vector_storage()
+        .map(|ids| ids.iter().collect())
+        .unwrap_or_default();
+
+    let mut controls = Vec::with_capacity(control_ids.len());
+    for control_id in control_ids {
+        let control_id = control_id.coerce_to_string(activation)?;
+        let index = (0..game_input::NUM_CONTROLS)
+            .find(|index| game_input::control_id(*index) == control_id.as_str())
+            .ok_or("ArgumentError: Error #2004: One of the parameters is invalid.")?;
+        controls.push(index);
+    }
+
+    if num_samples <= 0
+        || controls.is_empty()
+        || num_samples as usize * controls.len() > game_input::MAX_BUFFER_SIZE
+    {
+        return Err("ArgumentError: Error #2004: One of the parameters is invalid.".into());
+    }
+
+    with_device(activation, this, |device| {
+        device.start_caching_samples(num_samples as usize, controls)
+    })?;
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `GameInputDevice.stopCachingSamples`.
+pub fn stop_caching_samples<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    with_device(activation, this, |device| device.stop_caching_samples())?;
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `GameInputDevice.getCachedSamples`.
+///
+/// Each sample is written as a double for every cached control, in the
+/// order they were given to `startCachingSamples`.
+pub fn get_cached_samples<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let data = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?;
+    let append = args
+        .get(1)
+        .cloned()
+        .unwrap_or_else(|| false.into())
+        .coerce_to_boolean();
+
+    let (count, values) =
+        with_device(activation, this, |device| device.take_cached_samples())?.unwrap_or_default();
+
+    if let Some(mut bytearray) = data.as_bytearray_mut(activation.context.gc_context) {
+        if !append {
+            bytearray.clear();
+        }
+        for value in values {
+            bytearray.write_double(value)?;
+        }
+    }
+
+    Ok((count as i32).into())
+}
+
+/// Construct `GameInputDevice`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.ui"), "GameInputDevice"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<GameInputDevice instance initializer>", mc),
+        Method::from_builtin(class_init, "<GameInputDevice class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("enabled", Some(enabled), Some(set_enabled)),
+        ("id", Some(id), None),
+        ("name", Some(name), None),
+        ("numControls", Some(num_controls), None),
+        (
+            "sampleInterval",
+            Some(sample_interval),
+            Some(set_sample_interval),
+        ),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("getControlAt", get_control_at),
+        ("startCachingSamples", start_caching_samples),
+        ("stopCachingSamples", stop_caching_samples),
+        ("getCachedSamples", get_cached_samples),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
+    write.define_public_constant_uint_class_traits(&[(
+        "MAX_BUFFER_SIZE",
+        game_input::MAX_BUFFER_SIZE as u32,
+    )]);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_GAME_INPUT_DEVICE), "deviceId"),
+        QName::new(Namespace::public(), "uint").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_GAME_INPUT_DEVICE), "controls"),
+        QName::new(Namespace::public(), "Array").into(),
+        None,
+    ));
+
+    class
+}
@@ -0,0 +1,154 @@
+//! `flash.ui.Keyboard` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::Object;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::events::KeyCode;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.ui.Keyboard`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.ui.Keyboard`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `Keyboard`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.ui"), "Keyboard"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<Keyboard instance initializer>", mc),
+        Method::from_builtin(class_init, "<Keyboard class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+
+    const CONSTANTS: &[(&str, u32)] = &[
+        ("BACKSPACE", KeyCode::Backspace as u32),
+        ("TAB", KeyCode::Tab as u32),
+        ("ENTER", KeyCode::Return as u32),
+        ("COMMAND", KeyCode::Command as u32),
+        ("SHIFT", KeyCode::Shift as u32),
+        ("CONTROL", KeyCode::Control as u32),
+        ("ALTERNATE", KeyCode::Alt as u32),
+        ("PAUSE", KeyCode::Pause as u32),
+        ("CAPS_LOCK", KeyCode::CapsLock as u32),
+        ("ESCAPE", KeyCode::Escape as u32),
+        ("SPACE", KeyCode::Space as u32),
+        ("PAGE_UP", KeyCode::PgUp as u32),
+        ("PAGE_DOWN", KeyCode::PgDown as u32),
+        ("END", KeyCode::End as u32),
+        ("HOME", KeyCode::Home as u32),
+        ("LEFT", KeyCode::Left as u32),
+        ("UP", KeyCode::Up as u32),
+        ("RIGHT", KeyCode::Right as u32),
+        ("DOWN", KeyCode::Down as u32),
+        ("INSERT", KeyCode::Insert as u32),
+        ("DELETE", KeyCode::Delete as u32),
+        ("NUMBER_0", KeyCode::Key0 as u32),
+        ("NUMBER_1", KeyCode::Key1 as u32),
+        ("NUMBER_2", KeyCode::Key2 as u32),
+        ("NUMBER_3", KeyCode::Key3 as u32),
+        ("NUMBER_4", KeyCode::Key4 as u32),
+        ("NUMBER_5", KeyCode::Key5 as u32),
+        ("NUMBER_6", KeyCode::Key6 as u32),
+        ("NUMBER_7", KeyCode::Key7 as u32),
+        ("NUMBER_8", KeyCode::Key8 as u32),
+        ("NUMBER_9", KeyCode::Key9 as u32),
+        ("A", KeyCode::A as u32),
+        ("B", KeyCode::B as u32),
+        ("C", KeyCode::C as u32),
+        ("D", KeyCode::D as u32),
+        ("E", KeyCode::E as u32),
+        ("F", KeyCode::F as u32),
+        ("G", KeyCode::G as u32),
+        ("H", KeyCode::H as u32),
+        ("I", KeyCode::I as u32),
+        ("J", KeyCode::J as u32),
+        ("K", KeyCode::K as u32),
+        ("L", KeyCode::L as u32),
+        ("M", KeyCode::M as u32),
+        ("N", KeyCode::N as u32),
+        ("O", KeyCode::O as u32),
+        ("P", KeyCode::P as u32),
+        ("Q", KeyCode::Q as u32),
+        ("R", KeyCode::R as u32),
+        ("S", KeyCode::S as u32),
+        ("T", KeyCode::T as u32),
+        ("U", KeyCode::U as u32),
+        ("V", KeyCode::V as u32),
+        ("W", KeyCode::W as u32),
+        ("X", KeyCode::X as u32),
+        ("Y", KeyCode::Y as u32),
+        ("Z", KeyCode::Z as u32),
+        ("NUMPAD_0", KeyCode::Numpad0 as u32),
+        ("NUMPAD_1", KeyCode::Numpad1 as u32),
+        ("NUMPAD_2", KeyCode::Numpad2 as u32),
+        ("NUMPAD_3", KeyCode::Numpad3 as u32),
+        ("NUMPAD_4", KeyCode::Numpad4 as u32),
+        ("NUMPAD_5", KeyCode::Numpad5 as u32),
+        ("NUMPAD_6", KeyCode::Numpad6 as u32),
+        ("NUMPAD_7", KeyCode::Numpad7 as u32),
+        ("NUMPAD_8", KeyCode::Numpad8 as u32),
+        ("NUMPAD_9", KeyCode::Numpad9 as u32),
+        ("NUMPAD_MULTIPLY", KeyCode::Multiply as u32),
+        ("NUMPAD_ADD", KeyCode::Plus as u32),
+        ("NUMPAD_SUBTRACT", KeyCode::NumpadMinus as u32),
+        ("NUMPAD_DECIMAL", KeyCode::NumpadPeriod as u32),
+        ("NUMPAD_DIVIDE", KeyCode::NumpadSlash as u32),
+        ("F1", KeyCode::F1 as u32),
+        ("F2", KeyCode::F2 as u32),
+        ("F3", KeyCode::F3 as u32),
+        ("F4", KeyCode::F4 as u32),
+        ("F5", KeyCode::F5 as u32),
+        ("F6", KeyCode::F6 as u32),
+        ("F7", KeyCode::F7 as u32),
+        ("F8", KeyCode::F8 as u32),
+        ("F9", KeyCode::F9 as u32),
+        ("F10", KeyCode::F10 as u32),
+        ("F11", KeyCode::F11 as u32),
+        ("F12", KeyCode::F12 as u32),
+        ("F13", KeyCode::F13 as u32),
+        ("F14", KeyCode::F14 as u32),
+        ("F15", KeyCode::F15 as u32),
+        ("NUM_LOCK", KeyCode::NumLock as u32),
+        ("SCROLL_LOCK", KeyCode::ScrollLock as u32),
+        ("SEMICOLON", KeyCode::Semicolon as u32),
+        ("EQUAL", KeyCode::Equals as u32),
+        ("COMMA", KeyCode::Comma as u32),
+        ("MINUS", KeyCode::Minus as u32),
+        ("PERIOD", KeyCode::Period as u32),
+        ("SLASH", KeyCode::Slash as u32),
+        ("BACKQUOTE", KeyCode::Grave as u32),
+        ("LEFTBRACKET", KeyCode::LBracket as u32),
+        ("BACKSLASH", KeyCode::Backslash as u32),
+        ("RIGHTBRACKET", KeyCode::RBracket as u32),
+        ("QUOTE", KeyCode::Apostrophe as u32),
+    ];
+    write.define_public_constant_uint_class_traits(CONSTANTS);
+
+    class
+}
@@ -0,0 +1,58 @@
+//! `flash.ui.KeyLocation` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::Object;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::events::KeyLocation;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.ui.KeyLocation`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.ui.KeyLocation`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `KeyLocation`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.ui"), "KeyLocation"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<KeyLocation instance initializer>", mc),
+        Method::from_builtin(class_init, "<KeyLocation class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+
+    const CONSTANTS: &[(&str, u32)] = &[
+        ("STANDARD", KeyLocation::Standard as u32),
+        ("LEFT", KeyLocation::Left as u32),
+        ("RIGHT", KeyLocation::Right as u32),
+        ("NUM_PAD", KeyLocation::NumPad as u32),
+    ];
+    write.define_public_constant_uint_class_traits(CONSTANTS);
+
+    class
+}
@@ -0,0 +1,227 @@
+//! `flash.ui.Mouse` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::backend::ui::CustomMouseCursor;
+use crate::cursor::CursorSetting;
+use crate::string::AvmString;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.ui.Mouse`'s instance constructor.
+pub fn instance_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Err("ArgumentError: Error #2012: Mouse class cannot be instantiated.".into())
+}
+
+/// Implements `flash.ui.Mouse`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `Mouse.hide`.
+pub fn hide<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    activation.context.ui.set_mouse_visible(false);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Mouse.show`.
+pub fn show<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    activation.context.ui.set_mouse_visible(true);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Mouse.cursor`'s getter.
+pub fn cursor<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let name = activation
+        .context
+        .mouse_cursors
+        .setting()
+        .name()
+        .to_string();
+
+    Ok(AvmString::new(activation.context.gc_context, name).into())
+}
+
+/// Implements `Mouse.cursor`'s setter.
+pub fn set_cursor<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let name = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+
+    let mouse_cursors = &mut activation.context.mouse_cursors;
+    let setting =
+        match CursorSetting::from_name(&name) {
+            Some(setting) => setting,
+            None if mouse_cursors.is_registered(&name) => CursorSetting::Custom(name.to_string()),
+            None => return Err(
+                "ArgumentError: Error #2008: Parameter cursor must be one of the accepted values."
+                    .into(),
+            ),
+        };
+    mouse_cursors.set_setting(setting);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Mouse.registerCursor`.
+///
+/// Animated cursors only show their first frame.
+pub fn register_cursor<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let name = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    let cursor_data = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?;
+
+    let data = cursor_data
+        .get_property(
+            cursor_data,
+            &QName::new(Namespace::public(), "data"),
+            activation,
+        )?
+        .coerce_to_object(activation)?;
+    let first_frame = data
+        .as_vector_storage()
+        .and_then(|frames| frames.get(0).ok())
+        .unwrap_or(Value::Undefined);
+    let bitmap_data = match first_frame {
+        Value::Object(frame) => frame.as_bitmap_data(),
+        _ => None,
+    }
+    .ok_or("ArgumentError: Error #2004: One of the parameters is invalid.")?;
+
+    let (hot_x, hot_y) = match cursor_data.get_property(
+        cursor_data,
+        &QName::new(Namespace::public(), "hotSpot"),
+        activation,
+    )? {
+        Value::Object(hot_spot) => {
+            let x = hot_spot
+                .get_property(hot_spot, &QName::new(Namespace::public(), "x"), activation)?
+                .coerce_to_number(activation)?;
+            let y = hot_spot
+                .get_property(hot_spot, &QName::new(Namespace::public(), "y"), activation)?
+                .coerce_to_number(activation)?;
+            (x, y)
+        }
+        _ => (0.0, 0.0),
+    };
+
+    let bitmap_data = bitmap_data.read();
+    let cursor = CustomMouseCursor {
+        width: bitmap_data.width(),
+        height: bitmap_data.height(),
+        rgba: bitmap_data.pixels_rgba(),
+        hot_spot: (
+            (hot_x.max(0.0) as u32).min(bitmap_data.width().saturating_sub(1)),
+            (hot_y.max(0.0) as u32).min(bitmap_data.height().saturating_sub(1)),
+        ),
+    };
+    drop(bitmap_data);
+
+    activation
+        .context
+        .mouse_cursors
+        .register(name.to_string(), cursor);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Mouse.unregisterCursor`.
+pub fn unregister_cursor<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let name = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    activation.context.mouse_cursors.unregister(&name);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Mouse.supportsCursor` and `Mouse.supportsNativeCursor`.
+pub fn supports_cursor<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(true.into())
+}
+
+/// Construct `Mouse`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.ui"), "Mouse"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<Mouse instance initializer>", mc),
+        Method::from_builtin(class_init, "<Mouse class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+
+    const PUBLIC_CLASS_PROPERTIES: &[(&str, Option<NativeMethodImpl>, Option<NativeMethodImpl>)] =
+        &[
+            ("cursor", Some(cursor), Some(set_cursor)),
+            ("supportsCursor", Some(supports_cursor), None),
+            ("supportsNativeCursor", Some(supports_cursor), None),
+        ];
+    write.define_public_builtin_class_properties(mc, PUBLIC_CLASS_PROPERTIES);
+
+    const PUBLIC_CLASS_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("hide", hide),
+        ("show", show),
+        ("registerCursor", register_cursor),
+        ("unregisterCursor", unregister_cursor),
+    ];
+    write.define_public_builtin_class_methods(mc, PUBLIC_CLASS_METHODS);
+
+    class
+}
@@ -0,0 +1,167 @@
+//! `flash.ui.Multitouch` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, VectorObject};
+use crate::avm2::value::Value;
+use crate::avm2::vector::VectorStorage;
+use crate::avm2::Error;
+use crate::multitouch::MultitouchInputMode;
+use gc_arena::{GcCell, MutationContext};
+
+/// The gestures that two fingers can make, as named by `TransformGestureEvent`
+/// and `GestureEvent`.
+const SUPPORTED_GESTURES: &[&str] = &[
+    "gesturePan",
+    "gestureRotate",
+    "gestureZoom",
+    "gestureTwoFingerTap",
+];
+
+/// The most fingers that the player will follow at once.
+///
+/// We can't ask the screen, so this is a typical count for a touch screen.
+const MAX_TOUCH_POINTS: i32 = 10;
+
+/// Implements `flash.ui.Multitouch`'s instance constructor.
+pub fn instance_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Err("ArgumentError: Error #2012: Multitouch class cannot be instantiated.".into())
+}
+
+/// Implements `flash.ui.Multitouch`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `Multitouch.inputMode`'s getter.
+pub fn input_mode<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(activation.context.multitouch.input_mode().name().into())
+}
+
+/// Implements `Multitouch.inputMode`'s setter.
+pub fn set_input_mode<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let name = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    let input_mode = MultitouchInputMode::from_name(&name).ok_or(
+        "ArgumentError: Error #2008: Parameter inputMode must be one of the accepted values.",
+    )?;
+    activation.context.multitouch.set_input_mode(input_mode);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Multitouch.mapTouchToMouse`'s getter.
+pub fn map_touch_to_mouse<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(activation.context.multitouch.map_touch_to_mouse().into())
+}
+
+/// Implements `Multitouch.mapTouchToMouse`'s setter.
+pub fn set_map_touch_to_mouse<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let map_touch_to_mouse = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_boolean();
+    activation
+        .context
+        .multitouch
+        .set_map_touch_to_mouse(map_touch_to_mouse);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Multitouch.maxTouchPoints`.
+pub fn max_touch_points<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(MAX_TOUCH_POINTS.into())
+}
+
+/// Implements `Multitouch.supportedGestures`.
+pub fn supported_gestures<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let gestures = SUPPORTED_GESTURES
+        .iter()
+        .map(|gesture| (*gesture).into())
+        .collect();
+    let string_class = activation.avm2().classes().string;
+    let storage = VectorStorage::from_values(gestures, false, string_class);
+
+    Ok(VectorObject::from_vector(storage, activation)?.into())
+}
+
+/// Implements `Multitouch.supportsGestureEvents` and
+/// `Multitouch.supportsTouchEvents`.
+pub fn supports_events<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(true.into())
+}
+
+/// Construct `Multitouch`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.ui"), "Multitouch"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<Multitouch instance initializer>", mc),
+        Method::from_builtin(class_init, "<Multitouch class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED | ClassAttributes::FINAL);
+
+    const PUBLIC_CLASS_PROPERTIES: &[(&str, Option<NativeMethodImpl>, Option<NativeMethodImpl>)] =
+        &[
+            ("inputMode", Some(input_mode), Some(set_input_mode)),
+            (
+                "mapTouchToMouse",
+                Some(map_touch_to_mouse),
+                Some(set_map_touch_to_mouse),
+            ),
+            ("maxTouchPoints", Some(max_touch_points), None),
+            ("supportedGestures", Some(supported_gestures), None),
+            ("supportsGestureEvents", Some(supports_events), None),
+            ("supportsTouchEvents", Some(supports_events), None),
+        ];
+    write.define_public_builtin_class_properties(mc, PUBLIC_CLASS_PROPERTIES);
+
+    class
+}
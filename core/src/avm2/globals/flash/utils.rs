@@ -4,10 +4,12 @@ use crate::avm2::object::TObject;
 use crate::avm2::QName;
 use crate::avm2::{Activation, Error, Object, Value};
 use crate::string::AvmString;
+use crate::timer::TimerCallback;
 
 pub mod bytearray;
 pub mod compression_algorithm;
 pub mod endian;
+pub mod iexternalizable;
 
 /// Implements `flash.utils.getTimer`
 pub fn get_timer<'gc>(
@@ -18,6 +20,77 @@ pub fn get_timer<'gc>(
     Ok((activation.context.navigator.time_since_launch().as_millis() as u32).into())
 }
 
+/// Schedule an AVM2 closure to run after a delay, optionally repeatedly.
+///
+/// The timer shares the player's timer queue with AVM1's `setInterval`, and
+/// is subject to the same minimum delay. It fires in the worker that set it.
+fn create_timer<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    is_timeout: bool,
+) -> Result<Value<'gc>, Error> {
+    let closure = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?;
+    let delay = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_number(activation)?;
+    let params = args.get(2..).unwrap_or(&[]).to_vec();
+    let worker = activation.avm2().worker().id();
+
+    let id = activation.context.timers.add_timer(
+        TimerCallback::Avm2Callback {
+            closure,
+            params,
+            worker,
+        },
+        delay as i32,
+        is_timeout,
+    );
+
+    Ok((id as u32).into())
+}
+
+/// Implements `flash.utils.setInterval`
+pub fn set_interval<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    create_timer(activation, args, false)
+}
+
+/// Implements `flash.utils.setTimeout`
+pub fn set_timeout<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    create_timer(activation, args, true)
+}
+
+/// Implements `flash.utils.clearInterval` and `flash.utils.clearTimeout`
+pub fn clear_timer<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let id = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_u32(activation)?;
+    if !activation.context.timers.remove(id as i32) {
+        log::info!("clearInterval: Timer {} does not exist", id);
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Implements `flash.utils.getQualifiedClassName`
 pub fn get_qualified_class_name<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
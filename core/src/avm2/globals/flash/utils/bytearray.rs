@@ -1,4 +1,5 @@
 use crate::avm2::activation::Activation;
+use crate::avm2::amf::{Amf3Reader, Amf3Writer};
 use crate::avm2::bytearray::{CompressionAlgorithm, Endian};
 use crate::avm2::class::{Class, ClassAttributes};
 use crate::avm2::method::{Method, NativeMethodImpl};
@@ -345,6 +346,92 @@ pub fn set_endian<'gc>(
     Ok(Value::Undefined)
 }
 
+pub fn shareable<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(bytearray) = this.as_bytearray() {
+            return Ok(bytearray.shareable().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_shareable<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            let shareable = args
+                .get(0)
+                .cloned()
+                .unwrap_or(Value::Bool(false))
+                .coerce_to_boolean();
+            bytearray.set_shareable(shareable);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn write_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+        let mut writer = Amf3Writer::new(this);
+        writer.write_value(activation, value)?;
+        writer.finish(activation)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn read_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return Amf3Reader::new(this).read_value(activation);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn object_encoding<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    // Only AMF3 is supported.
+    Ok(3.into())
+}
+
+pub fn set_object_encoding<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let object_encoding = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_u32(activation)?;
+    if object_encoding != 3 {
+        log::warn!("ByteArray.objectEncoding: only AMF3 is supported");
+    }
+
+    Ok(Value::Undefined)
+}
+
 pub fn read_short<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
@@ -783,6 +870,8 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("readMultiByte", read_multibyte),
         ("writeUTFBytes", write_utf_bytes),
         ("readUTFBytes", read_utf_bytes),
+        ("writeObject", write_object),
+        ("readObject", read_object),
     ];
     write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
 
@@ -795,6 +884,12 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("length", Some(length), Some(set_length)),
         ("position", Some(position), Some(set_position)),
         ("endian", Some(endian), Some(set_endian)),
+        ("shareable", Some(shareable), Some(set_shareable)),
+        (
+            "objectEncoding",
+            Some(object_encoding),
+            Some(set_object_encoding),
+        ),
     ];
     write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
 
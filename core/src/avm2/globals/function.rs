@@ -32,15 +32,81 @@ pub fn class_init<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Determine the `this` value a function is called with by `call` or
+/// `apply`.
+///
+/// Functions called with a `null` or `undefined` receiver are bound to the
+/// global object, and primitive receivers are boxed.
+fn resolve_receiver<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    receiver: Value<'gc>,
+) -> Result<Option<Object<'gc>>, Error> {
+    match receiver {
+        Value::Undefined | Value::Null => match activation.global_scope() {
+            Value::Object(global) => Ok(Some(global)),
+            _ => Ok(None),
+        },
+        receiver => Ok(Some(receiver.coerce_to_object(activation)?)),
+    }
+}
+
+/// Collect the arguments that `apply` passes to a function.
+///
+/// Arrays are read directly. Any other object is treated as array-like, and
+/// provides as many arguments as its `length` property indicates.
+fn resolve_apply_arguments<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    arg_array: Value<'gc>,
+) -> Result<Vec<Value<'gc>>, Error> {
+    let arg_array = match arg_array {
+        Value::Undefined | Value::Null => return Ok(Vec::new()),
+        Value::Object(arg_array) => arg_array,
+        _ => return Err("Second parameter of apply must be an array or undefined".into()),
+    };
+
+    let arg_storage: Option<Vec<Option<Value<'gc>>>> =
+        arg_array.as_array_storage().map(|a| a.iter().collect());
+    if let Some(arg_storage) = arg_storage {
+        let mut resolved_args = Vec::with_capacity(arg_storage.len());
+        for (i, v) in arg_storage.iter().enumerate() {
+            resolved_args.push(resolve_array_hole(activation, arg_array, i, v.clone())?);
+        }
+
+        return Ok(resolved_args);
+    }
+
+    let length = arg_array
+        .get_property(
+            arg_array,
+            &QName::new(Namespace::public(), "length"),
+            activation,
+        )?
+        .coerce_to_u32(activation)?;
+
+    let mut resolved_args = Vec::with_capacity(length as usize);
+    for i in 0..length {
+        let name = activation
+            .context
+            .avm2
+            .small_strings()
+            .integer(activation.context.gc_context, i as usize);
+        resolved_args.push(arg_array.get_property(
+            arg_array,
+            &QName::new(Namespace::public(), name),
+            activation,
+        )?);
+    }
+
+    Ok(resolved_args)
+}
+
 /// Implements `Function.prototype.call`
 fn call<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     func: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    let this = args
-        .get(0)
-        .and_then(|v| v.coerce_to_object(activation).ok());
+    let this = resolve_receiver(activation, args.get(0).cloned().unwrap_or(Value::Undefined))?;
     let base_proto = this.and_then(|that| that.proto());
 
     if let Some(func) = func {
@@ -60,34 +126,12 @@ fn apply<'gc>(
     func: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    let this = args
-        .get(0)
-        .and_then(|v| v.coerce_to_object(activation).ok());
+    let this = resolve_receiver(activation, args.get(0).cloned().unwrap_or(Value::Undefined))?;
     let base_proto = this.and_then(|that| that.proto());
 
     if let Some(func) = func {
-        let arg_array = args
-            .get(1)
-            .cloned()
-            .unwrap_or(Value::Undefined)
-            .coerce_to_object(activation);
-        let resolved_args = if let Ok(arg_array) = arg_array {
-            let arg_storage: Vec<Option<Value<'gc>>> = arg_array
-                .as_array_storage()
-                .map(|a| a.iter().collect())
-                .ok_or_else(|| {
-                    Error::from("Second parameter of apply must be an array or undefined")
-                })?;
-
-            let mut resolved_args = Vec::with_capacity(arg_storage.len());
-            for (i, v) in arg_storage.iter().enumerate() {
-                resolved_args.push(resolve_array_hole(activation, arg_array, i, v.clone())?);
-            }
-
-            resolved_args
-        } else {
-            Vec::new()
-        };
+        let resolved_args =
+            resolve_apply_arguments(activation, args.get(1).cloned().unwrap_or(Value::Undefined))?;
 
         Ok(func.call(this, &resolved_args, activation, base_proto)?)
     } else {
@@ -95,6 +139,20 @@ fn apply<'gc>(
     }
 }
 
+/// Implements `Function.prototype.length`
+fn length<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    func: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let num_parameters = func
+        .and_then(|func| func.as_executable())
+        .map(|exec| exec.num_parameters())
+        .unwrap_or(0);
+
+    Ok((num_parameters as u32).into())
+}
+
 /// Create Function prototype.
 ///
 /// This function creates a suitable prototype and returns it.
@@ -132,29 +190,46 @@ pub fn fill_proto<'gc>(
     );
     let scope = Scope::push_scope(globals.get_scope(), globals, activation.context.gc_context);
 
-    function_proto.install_dynamic_property(
-        activation.context.gc_context,
-        QName::new(Namespace::as3_namespace(), "call"),
-        FunctionObject::from_method_and_proto(
+    // `call` and `apply` are reachable both as AS3 methods and through the
+    // prototype, for untyped code.
+    for ns in &[Namespace::as3_namespace(), Namespace::public()] {
+        function_proto.install_dynamic_property(
             activation.context.gc_context,
-            Method::from_builtin(call, "call", activation.context.gc_context),
-            None,
-            function_proto,
-            None,
-        )
-        .into(),
-    )?;
-    function_proto.install_dynamic_property(
+            QName::new(ns.clone(), "call"),
+            FunctionObject::from_method_and_proto(
+                activation.context.gc_context,
+                Method::from_builtin(call, "call", activation.context.gc_context),
+                None,
+                function_proto,
+                None,
+            )
+            .into(),
+        )?;
+        function_proto.install_dynamic_property(
+            activation.context.gc_context,
+            QName::new(ns.clone(), "apply"),
+            FunctionObject::from_method_and_proto(
+                activation.context.gc_context,
+                Method::from_builtin(apply, "apply", activation.context.gc_context),
+                None,
+                function_proto,
+                None,
+            )
+            .into(),
+        )?;
+    }
+    function_proto.install_getter(
         activation.context.gc_context,
-        QName::new(Namespace::as3_namespace(), "apply"),
+        QName::new(Namespace::public(), "length"),
+        0,
         FunctionObject::from_method_and_proto(
             activation.context.gc_context,
-            Method::from_builtin(apply, "apply", activation.context.gc_context),
+            Method::from_builtin(length, "length", activation.context.gc_context),
             None,
             function_proto,
             None,
-        )
-        .into(),
+        ),
+        false,
     )?;
 
     ClassObject::from_builtin_class(
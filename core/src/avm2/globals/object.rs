@@ -58,6 +58,21 @@ fn value_of<'gc>(
         .unwrap_or(Ok(Value::Undefined))
 }
 
+/// Get the public name of the property that an `Object.prototype` method was
+/// asked about.
+fn public_property_name<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<QName<'gc>, Error> {
+    let name = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+
+    Ok(QName::new(Namespace::public(), name))
+}
+
 /// `Object.prototype.hasOwnProperty`
 pub fn has_own_property<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
@@ -66,17 +81,9 @@ pub fn has_own_property<'gc>(
 ) -> Result<Value<'gc>, Error> {
     let this: Result<Object<'gc>, Error> = this.ok_or_else(|| "No valid this parameter".into());
     let this = this?;
-    let name: Result<&Value<'gc>, Error> = args.get(0).ok_or_else(|| "No name specified".into());
-    let name = name?.coerce_to_string(activation)?;
-
-    if let Some(ns) = this.resolve_any(name)? {
-        if !ns.is_private() {
-            let qname = QName::new(ns, name);
-            return Ok(this.has_own_property(&qname)?.into());
-        }
-    }
+    let name = public_property_name(activation, args)?;
 
-    Ok(false.into())
+    Ok(this.has_own_property(&name)?.into())
 }
 
 /// `Object.prototype.isPrototypeOf`
@@ -88,14 +95,20 @@ pub fn is_prototype_of<'gc>(
     let search_proto: Result<Object<'gc>, Error> =
         this.ok_or_else(|| "No valid this parameter".into());
     let search_proto = search_proto?;
-    let mut target_proto = args.get(0).cloned().unwrap_or(Value::Undefined);
 
-    while let Value::Object(proto) = target_proto {
+    // An object is not a prototype of itself, so the search starts at the
+    // target's prototype.
+    let mut target_proto = match args.get(0) {
+        Some(Value::Object(target)) => target.proto(),
+        _ => None,
+    };
+
+    while let Some(proto) = target_proto {
         if Object::ptr_eq(search_proto, proto) {
             return Ok(true.into());
         }
 
-        target_proto = proto.proto().map(|o| o.into()).unwrap_or(Value::Undefined);
+        target_proto = proto.proto();
     }
 
     Ok(false.into())
@@ -109,17 +122,9 @@ pub fn property_is_enumerable<'gc>(
 ) -> Result<Value<'gc>, Error> {
     let this: Result<Object<'gc>, Error> = this.ok_or_else(|| "No valid this parameter".into());
     let this = this?;
-    let name: Result<&Value<'gc>, Error> = args.get(0).ok_or_else(|| "No name specified".into());
-    let name = name?.coerce_to_string(activation)?;
-
-    if let Some(ns) = this.resolve_any(name)? {
-        if !ns.is_private() {
-            let qname = QName::new(ns, name);
-            return Ok(this.property_is_enumerable(&qname).into());
-        }
-    }
+    let name = public_property_name(activation, args)?;
 
-    Ok(false.into())
+    Ok(this.property_is_enumerable(&name).into())
 }
 
 /// `Object.prototype.setPropertyIsEnumerable`
@@ -130,21 +135,14 @@ pub fn set_property_is_enumerable<'gc>(
 ) -> Result<Value<'gc>, Error> {
     let this: Result<Object<'gc>, Error> = this.ok_or_else(|| "No valid this parameter".into());
     let this = this?;
-    let name: Result<&Value<'gc>, Error> = args.get(0).ok_or_else(|| "No name specified".into());
-    let name = name?.coerce_to_string(activation)?;
+    let name = public_property_name(activation, args)?;
+    let is_enumerable = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_boolean();
 
-    if let Some(Value::Bool(is_enum)) = args.get(1) {
-        if let Some(ns) = this.resolve_any(name)? {
-            if !ns.is_private() {
-                let qname = QName::new(ns, name);
-                this.set_local_property_is_enumerable(
-                    activation.context.gc_context,
-                    &qname,
-                    *is_enum,
-                )?;
-            }
-        }
-    }
+    this.set_local_property_is_enumerable(activation.context.gc_context, &name, is_enumerable)?;
 
     Ok(Value::Undefined)
 }
@@ -81,9 +81,14 @@ fn char_at<'gc>(
             let ret = s
                 .encode_utf16()
                 .nth(index)
-                .map(|c| string_utils::utf16_code_unit_to_char(c).to_string())
-                .unwrap_or_default();
-            return Ok(AvmString::new(activation.context.gc_context, ret).into());
+                .map(|c| {
+                    activation.context.avm2.small_strings().char(
+                        activation.context.gc_context,
+                        string_utils::utf16_code_unit_to_char(c),
+                    )
+                })
+                .unwrap_or_else(|| "".into());
+            return Ok(ret.into());
         }
     }
 
@@ -157,7 +162,13 @@ fn split<'gc>(
                 activation,
                 this.chars()
                     .take(limit)
-                    .map(|c| AvmString::new(activation.context.gc_context, c.to_string()))
+                    .map(|c| {
+                        activation
+                            .context
+                            .avm2
+                            .small_strings()
+                            .char(activation.context.gc_context, c)
+                    })
                     .collect(),
             )
             .unwrap()
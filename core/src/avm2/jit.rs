@@ -0,0 +1,1635 @@
+//! Native code compilation of hot AVM2 method bodies
+//!
+//! Only numeric code is compiled. Code is compiled starting from an
+//! instruction that is entered often, which is either the start of a method or
+//! the target of a backward branch, and is specialized on the types of the
+//! local registers at that point. Values that compiled code can't represent
+//! are left in their registers, and instructions that it can't run become side
+//! exits: the registers and operand stack are written back, and the
+//! interpreter resumes at that instruction.
+
+use crate::avm2::value::Value;
+use crate::avm2::verify::Op;
+use crate::ecma_conversions::f64_to_wrapping_i32;
+use cranelift_codegen::binemit::{NullStackMapSink, NullTrapSink};
+use cranelift_codegen::ir::condcodes::{FloatCC, IntCC};
+use cranelift_codegen::ir::{types, AbiParam, Block, FuncRef, InstBuilder, MemFlags};
+use cranelift_codegen::settings::{self, Configurable};
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext, Variable};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{Linkage, Module};
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+use swf::avm2::types::Op as AbcOp;
+
+type IrValue = cranelift_codegen::ir::Value;
+
+/// How many times an instruction must be entered before the code starting at
+/// it is compiled.
+pub const HOT_THRESHOLD: u32 = 1000;
+
+/// How many backward branches compiled code may take before it returns to the
+/// interpreter, so that the script timeout still applies.
+pub const FUEL: u64 = 100_000;
+
+/// The most specializations that are kept for a single method body.
+const MAX_COMPILED: usize = 32;
+
+/// The type of a value, as far as compiled code is concerned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum JitType {
+    Number,
+    Int,
+    Uint,
+    Bool,
+    Undefined,
+
+    /// A value that compiled code can't use, such as a string or an object.
+    Other,
+}
+
+impl JitType {
+    pub fn of(value: &Value<'_>) -> Self {
+        match value {
+            Value::Number(_) => Self::Number,
+            Value::Integer(_) => Self::Int,
+            Value::Unsigned(_) => Self::Uint,
+            Value::Bool(_) => Self::Bool,
+            Value::Undefined => Self::Undefined,
+            _ => Self::Other,
+        }
+    }
+
+    /// Whether values of this type are kept in a register or a stack slot.
+    fn is_stored(self) -> bool {
+        matches!(self, Self::Number | Self::Int | Self::Uint | Self::Bool)
+    }
+
+    fn is_numeric(self) -> bool {
+        matches!(self, Self::Number | Self::Int | Self::Uint)
+    }
+}
+
+/// Encode a value for compiled code.
+///
+/// Numbers are stored as their bits, and integers and booleans in the low 32
+/// bits. Values of other types aren't read by compiled code.
+pub fn encode(value: &Value<'_>) -> u64 {
+    match value {
+        Value::Number(n) => n.to_bits(),
+        Value::Integer(i) => *i as u32 as u64,
+        Value::Unsigned(u) => *u as u64,
+        Value::Bool(b) => *b as u64,
+        _ => 0,
+    }
+}
+
+/// Decode a value written by compiled code.
+///
+/// Returns `None` for values that compiled code didn't write.
+pub fn decode<'gc>(ty: JitType, bits: u64) -> Option<Value<'gc>> {
+    match ty {
+        JitType::Number => Some(Value::Number(f64::from_bits(bits))),
+        JitType::Int => Some(Value::Integer(bits as u32 as i32)),
+        JitType::Uint => Some(Value::Unsigned(bits as u32)),
+        JitType::Bool => Some(Value::Bool(bits as u32 != 0)),
+        JitType::Undefined => Some(Value::Undefined),
+        JitType::Other => None,
+    }
+}
+
+/// How compiled code left off.
+#[derive(Debug, PartialEq)]
+pub enum Exit {
+    /// The method returned a value of the given type, which is at the bottom
+    /// of the operand stack.
+    Return(JitType),
+
+    /// The interpreter should continue at `ip`, with the given types in the
+    /// operand stack and local registers.
+    Resume {
+        ip: usize,
+        stack: Vec<JitType>,
+        locals: Vec<JitType>,
+    },
+}
+
+type EntryFn = unsafe extern "C" fn(*mut u64, *mut u64, *mut u64) -> u32;
+
+/// Native code compiled from part of a method body.
+pub struct CompiledCode {
+    module: Option<JITModule>,
+    entry: EntryFn,
+    exits: Vec<Exit>,
+    num_locals: usize,
+    max_stack: usize,
+}
+
+impl CompiledCode {
+    /// The number of operand stack slots that `run` needs.
+    pub fn max_stack(&self) -> usize {
+        self.max_stack.max(1)
+    }
+
+    /// Run the code.
+    ///
+    /// `locals` holds the encoded local registers, and is updated with the
+    /// values of the registers when the code exits. The operand stack at the
+    /// exit is written to `stack`. `fuel` is the number of backward branches
+    /// the code may take, and is updated with what was left of it.
+    pub fn run(&self, locals: &mut [u64], stack: &mut [u64], fuel: &mut u64) -> &Exit {
+        assert!(locals.len() >= self.num_locals);
+        assert!(stack.len() >= self.max_stack());
+
+        // SAFETY: The code only accesses the registers and stack slots it was
+        // compiled for, and both buffers were just checked to be big enough.
+        let exit = unsafe { (self.entry)(locals.as_mut_ptr(), stack.as_mut_ptr(), fuel) };
+        &self.exits[exit as usize]
+    }
+}
+
+impl Drop for CompiledCode {
+    fn drop(&mut self) {
+        if let Some(module) = self.module.take() {
+            // SAFETY: The code can't be running, as running it borrows `self`.
+            unsafe { module.free_memory() };
+        }
+    }
+}
+
+impl fmt::Debug for CompiledCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompiledCode")
+            .field("exits", &self.exits)
+            .field("num_locals", &self.num_locals)
+            .field("max_stack", &self.max_stack)
+            .finish()
+    }
+}
+
+/// The compiled code of a method body, and how often each instruction that
+/// compiled code can start from has been entered.
+#[derive(Default)]
+pub struct JitCache {
+    entries: HashMap<usize, u32>,
+    compiled: HashMap<(usize, Vec<JitType>), Option<Rc<CompiledCode>>>,
+}
+
+impl JitCache {
+    /// Count an entry to `ip`, and return whether it is now hot.
+    pub fn is_hot(&mut self, ip: usize) -> bool {
+        let count = self.entries.entry(ip).or_insert(0);
+        *count = count.saturating_add(1);
+        *count >= HOT_THRESHOLD
+    }
+
+    /// Get the code compiled from `ip` for the given local register types,
+    /// compiling it if needed.
+    ///
+    /// Returns `None` if the code can't be compiled.
+    pub fn compiled(
+        &mut self,
+        ops: &[Op],
+        ip: usize,
+        locals: &[JitType],
+    ) -> Option<Rc<CompiledCode>> {
+        let key = (ip, locals.to_vec());
+        if let Some(code) = self.compiled.get(&key) {
+            return code.clone();
+        }
+
+        if self.compiled.len() >= MAX_COMPILED {
+            return None;
+        }
+
+        let code = compile(ops, ip, locals).map(Rc::new);
+        self.compiled.insert(key, code.clone());
+        code
+    }
+}
+
+impl fmt::Debug for JitCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JitCache")
+            .field("entries", &self.entries)
+            .field("compiled", &self.compiled.len())
+            .finish()
+    }
+}
+
+/// The types of the operand stack and local registers before an instruction.
+#[derive(Clone, Debug, PartialEq)]
+struct State {
+    stack: Vec<JitType>,
+    locals: Vec<JitType>,
+}
+
+/// Where execution goes after an instruction.
+enum Flow {
+    /// The next instruction.
+    Next,
+
+    /// The next instruction, or the target.
+    Branch(usize),
+
+    /// The target.
+    Jump(usize),
+
+    /// One of the targets.
+    Switch(Vec<usize>),
+
+    /// Out of the method.
+    Return,
+}
+
+fn pop_n(stack: &mut Vec<JitType>, count: usize) -> Option<()> {
+    let len = stack.len().checked_sub(count)?;
+    stack.truncate(len);
+    Some(())
+}
+
+fn push_type(stack: &mut Vec<JitType>, ty: JitType) -> Flow {
+    stack.push(ty);
+    Flow::Next
+}
+
+fn unary(stack: &mut Vec<JitType>, result: JitType) -> Option<Flow> {
+    pop_n(stack, 1)?;
+    stack.push(result);
+    Some(Flow::Next)
+}
+
+fn binary(stack: &mut Vec<JitType>, result: JitType) -> Option<Flow> {
+    pop_n(stack, 2)?;
+    stack.push(result);
+    Some(Flow::Next)
+}
+
+/// The state after an instruction, and where execution goes next.
+///
+/// Returns `None` if the instruction can't be compiled, and must be a side
+/// exit instead.
+fn step(op: &Op, state: &State) -> Option<(State, Flow)> {
+    use JitType::*;
+
+    let mut next = state.clone();
+    let stack = &mut next.stack;
+    let locals = &mut next.locals;
+    let flow = match op {
+        Op::PushDouble { .. } => push_type(stack, Number),
+        Op::PushInt { .. } => push_type(stack, Int),
+        Op::PushUint { .. } => push_type(stack, Uint),
+        Op::Jump { target } => Flow::Jump(*target),
+        Op::IfTrue { target } | Op::IfFalse { target } => {
+            pop_n(stack, 1)?;
+            Flow::Branch(*target)
+        }
+        Op::IfStrictEq { target }
+        | Op::IfStrictNe { target }
+        | Op::IfEq { target }
+        | Op::IfNe { target }
+        | Op::IfGe { target }
+        | Op::IfGt { target }
+        | Op::IfLe { target }
+        | Op::IfLt { target }
+        | Op::IfNge { target }
+        | Op::IfNgt { target }
+        | Op::IfNle { target }
+        | Op::IfNlt { target } => {
+            pop_n(stack, 2)?;
+            Flow::Branch(*target)
+        }
+        Op::LookupSwitch {
+            default_target,
+            case_targets,
+        } => {
+            pop_n(stack, 1)?;
+            let mut targets = vec![*default_target];
+            targets.extend_from_slice(case_targets);
+            Flow::Switch(targets)
+        }
+        // Compiled code can't look at strings, so string switches always
+        // leave it.
+        Op::StringSwitch { .. } => return None,
+        Op::Abc(op) => match op {
+            AbcOp::PushByte { .. } | AbcOp::PushShort { .. } => push_type(stack, Int),
+            AbcOp::PushTrue | AbcOp::PushFalse => push_type(stack, Bool),
+            AbcOp::PushNaN => push_type(stack, Number),
+            AbcOp::PushUndefined => push_type(stack, Undefined),
+            AbcOp::Pop => {
+                pop_n(stack, 1)?;
+                Flow::Next
+            }
+            AbcOp::Dup => {
+                let top = *stack.last()?;
+                stack.push(top);
+                Flow::Next
+            }
+            AbcOp::Swap => {
+                let len = stack.len();
+                if len < 2 {
+                    return None;
+                }
+                stack.swap(len - 1, len - 2);
+                Flow::Next
+            }
+            AbcOp::GetLocal { index } => {
+                let ty = *locals.get(*index as usize)?;
+                if ty == Other {
+                    return None;
+                }
+                stack.push(ty);
+                Flow::Next
+            }
+            AbcOp::SetLocal { index } => {
+                let ty = stack.pop()?;
+                *locals.get_mut(*index as usize)? = ty;
+                Flow::Next
+            }
+            AbcOp::Kill { index } => {
+                *locals.get_mut(*index as usize)? = Undefined;
+                Flow::Next
+            }
+            AbcOp::IncLocal { index }
+            | AbcOp::DecLocal { index }
+            | AbcOp::IncLocalI { index }
+            | AbcOp::DecLocalI { index } => {
+                let local = locals.get_mut(*index as usize)?;
+                if *local == Other {
+                    return None;
+                }
+                *local = match op {
+                    AbcOp::IncLocal { .. } | AbcOp::DecLocal { .. } => Number,
+                    _ => Int,
+                };
+                Flow::Next
+            }
+            AbcOp::Add | AbcOp::Subtract | AbcOp::Multiply | AbcOp::Divide | AbcOp::Modulo => {
+                binary(stack, Number)?
+            }
+            AbcOp::AddI
+            | AbcOp::SubtractI
+            | AbcOp::MultiplyI
+            | AbcOp::BitAnd
+            | AbcOp::BitOr
+            | AbcOp::BitXor
+            | AbcOp::LShift
+            | AbcOp::RShift => binary(stack, Int)?,
+            AbcOp::URShift => binary(stack, Uint)?,
+            AbcOp::Equals
+            | AbcOp::StrictEquals
+            | AbcOp::LessThan
+            | AbcOp::LessEquals
+            | AbcOp::GreaterThan
+            | AbcOp::GreaterEquals => binary(stack, Bool)?,
+            AbcOp::Negate
+            | AbcOp::Increment
+            | AbcOp::Decrement
+            | AbcOp::ConvertD
+            | AbcOp::ConvertI
+            | AbcOp::ConvertU => unary(stack, Number)?,
+            AbcOp::NegateI | AbcOp::IncrementI | AbcOp::DecrementI | AbcOp::BitNot => {
+                unary(stack, Int)?
+            }
+            AbcOp::ConvertB | AbcOp::Not => unary(stack, Bool)?,
+            AbcOp::CoerceA | AbcOp::Nop | AbcOp::Label => Flow::Next,
+            AbcOp::Debug { .. } | AbcOp::DebugFile { .. } | AbcOp::DebugLine { .. }
+                if !cfg!(avm_debug) =>
+            {
+                Flow::Next
+            }
+            AbcOp::ReturnValue => {
+                pop_n(stack, 1)?;
+                Flow::Return
+            }
+            AbcOp::ReturnVoid => Flow::Return,
+            _ => return None,
+        },
+    };
+
+    Some((next, flow))
+}
+
+/// The state before each instruction that can be reached from the entry point.
+struct Analysis {
+    states: Vec<Option<State>>,
+    max_stack: usize,
+}
+
+/// Find the state before each reachable instruction.
+///
+/// Returns `None` if an instruction can be reached with two different states,
+/// or if execution can fall off the end of the body.
+fn analyze(ops: &[Op], entry: usize, locals: &[JitType]) -> Option<Analysis> {
+    let mut states: Vec<Option<State>> = vec![None; ops.len()];
+    let mut max_stack = 0;
+    let mut worklist = vec![(
+        entry,
+        State {
+            stack: Vec::new(),
+            locals: locals.to_vec(),
+        },
+    )];
+
+    while let Some((ip, state)) = worklist.pop() {
+        let known = states.get_mut(ip)?;
+        if let Some(existing) = &*known {
+            if *existing != state {
+                return None;
+            }
+            continue;
+        }
+        *known = Some(state.clone());
+        max_stack = max_stack.max(state.stack.len());
+
+        let (next, flow) = match step(&ops[ip], &state) {
+            Some(step) => step,
+            None => continue,
+        };
+        max_stack = max_stack.max(next.stack.len());
+
+        match flow {
+            Flow::Next => worklist.push((ip + 1, next)),
+            Flow::Branch(target) => {
+                worklist.push((ip + 1, next.clone()));
+                worklist.push((target, next));
+            }
+            Flow::Jump(target) => worklist.push((target, next)),
+            Flow::Switch(targets) => {
+                for target in targets {
+                    worklist.push((target, next.clone()));
+                }
+            }
+            Flow::Return => {}
+        }
+    }
+
+    Some(Analysis { states, max_stack })
+}
+
+/// Compile a method body, starting from `entry` with the given local register
+/// types and an empty operand stack.
+///
+/// Returns `None` if the code can't be compiled.
+pub fn compile(ops: &[Op], entry: usize, locals: &[JitType]) -> Option<CompiledCode> {
+    let analysis = analyze(ops, entry, locals)?;
+
+    // Code that would exit straight away isn't worth compiling.
+    let entry_state = analysis.states.get(entry)?.as_ref()?;
+    step(&ops[entry], entry_state)?;
+
+    match build(ops, entry, locals.len(), &analysis) {
+        Ok(code) => Some(code),
+        Err(e) => {
+            log::warn!("Could not compile AVM2 method body: {}", e);
+            None
+        }
+    }
+}
+
+extern "C" fn to_int32(value: f64) -> i32 {
+    f64_to_wrapping_i32(value)
+}
+
+extern "C" fn modulo(value1: f64, value2: f64) -> f64 {
+    value1 % value2
+}
+
+fn build(
+    ops: &[Op],
+    entry: usize,
+    num_locals: usize,
+    analysis: &Analysis,
+) -> Result<CompiledCode, String> {
+    let mut flag_builder = settings::builder();
+    flag_builder
+        .set("opt_level", "speed")
+        .map_err(|e| e.to_string())?;
+    let isa = cranelift_native::builder()?.finish(settings::Flags::new(flag_builder));
+    let mut jit_builder = JITBuilder::with_isa(isa, cranelift_module::default_libcall_names());
+    jit_builder.symbol("ruffle_jit_to_int32", to_int32 as *const u8);
+    jit_builder.symbol("ruffle_jit_modulo", modulo as *const u8);
+    let mut module = JITModule::new(jit_builder);
+
+    match define(&mut module, ops, entry, num_locals, analysis) {
+        Ok((entry, exits)) => Ok(CompiledCode {
+            module: Some(module),
+            entry,
+            exits,
+            num_locals,
+            max_stack: analysis.max_stack,
+        }),
+        Err(e) => {
+            // SAFETY: None of the module's code was handed out.
+            unsafe { module.free_memory() };
+            Err(e)
+        }
+    }
+}
+
+fn define(
+    module: &mut JITModule,
+    ops: &[Op],
+    entry: usize,
+    num_locals: usize,
+    analysis: &Analysis,
+) -> Result<(EntryFn, Vec<Exit>), String> {
+    let pointer = module.target_config().pointer_type();
+    let mut ctx = module.make_context();
+    for _ in 0..3 {
+        ctx.func.signature.params.push(AbiParam::new(pointer));
+    }
+    ctx.func.signature.returns.push(AbiParam::new(types::I32));
+
+    let mut to_int32_signature = module.make_signature();
+    to_int32_signature.params.push(AbiParam::new(types::F64));
+    to_int32_signature.returns.push(AbiParam::new(types::I32));
+    let to_int32 = module
+        .declare_function("ruffle_jit_to_int32", Linkage::Import, &to_int32_signature)
+        .map_err(|e| e.to_string())?;
+
+    let mut modulo_signature = module.make_signature();
+    modulo_signature.params.push(AbiParam::new(types::F64));
+    modulo_signature.params.push(AbiParam::new(types::F64));
+    modulo_signature.returns.push(AbiParam::new(types::F64));
+    let modulo = module
+        .declare_function("ruffle_jit_modulo", Linkage::Import, &modulo_signature)
+        .map_err(|e| e.to_string())?;
+
+    let id = module
+        .declare_function("method", Linkage::Local, &ctx.func.signature)
+        .map_err(|e| e.to_string())?;
+
+    let helpers = Helpers {
+        to_int32: module.declare_func_in_func(to_int32, &mut ctx.func),
+        modulo: module.declare_func_in_func(modulo, &mut ctx.func),
+    };
+
+    let mut builder_context = FunctionBuilderContext::new();
+    let exits = {
+        let builder = FunctionBuilder::new(&mut ctx.func, &mut builder_context);
+        Translator::new(builder, helpers, num_locals, analysis).translate(ops, entry)
+    };
+
+    module
+        .define_function(id, &mut ctx, &mut NullTrapSink {}, &mut NullStackMapSink {})
+        .map_err(|e| e.to_string())?;
+    module.clear_context(&mut ctx);
+    module.finalize_definitions();
+
+    let code = module.get_finalized_function(id);
+
+    // SAFETY: The function was declared with this signature.
+    let entry = unsafe { std::mem::transmute::<*const u8, EntryFn>(code) };
+
+    Ok((entry, exits))
+}
+
+/// Runtime functions called by compiled code.
+struct Helpers {
+    to_int32: FuncRef,
+    modulo: FuncRef,
+}
+
+/// Translates the reachable instructions of a method body into a function.
+///
+/// Every local register and operand stack slot is a pair of variables: one for
+/// numbers, and one for integers and booleans. Which of them holds the value
+/// at any point is known from the analysis.
+struct Translator<'a, 'b> {
+    builder: FunctionBuilder<'a>,
+    helpers: Helpers,
+    analysis: &'b Analysis,
+    num_locals: usize,
+    blocks: Vec<Option<Block>>,
+    back_edges: Vec<(Block, usize, State)>,
+    exits: Vec<Exit>,
+    fuel: Variable,
+    locals_ptr: IrValue,
+    stack_ptr: IrValue,
+    fuel_ptr: IrValue,
+}
+
+impl<'a, 'b> Translator<'a, 'b> {
+    fn new(
+        mut builder: FunctionBuilder<'a>,
+        helpers: Helpers,
+        num_locals: usize,
+        analysis: &'b Analysis,
+    ) -> Self {
+        let slots = num_locals + analysis.max_stack;
+        for slot in 0..slots {
+            builder.declare_var(Variable::new(slot * 2), types::F64);
+            builder.declare_var(Variable::new(slot * 2 + 1), types::I32);
+        }
+        let fuel = Variable::new(slots * 2);
+        builder.declare_var(fuel, types::I64);
+
+        let entry_block = builder.create_block();
+        builder.append_block_params_for_function_params(entry_block);
+        builder.switch_to_block(entry_block);
+        let params = builder.block_params(entry_block).to_vec();
+
+        let blocks = analysis
+            .states
+            .iter()
+            .map(|state| state.as_ref().map(|_| builder.create_block()))
+            .collect();
+
+        Self {
+            builder,
+            helpers,
+            analysis,
+            num_locals,
+            blocks,
+            back_edges: Vec::new(),
+            exits: Vec::new(),
+            fuel,
+            locals_ptr: params[0],
+            stack_ptr: params[1],
+            fuel_ptr: params[2],
+        }
+    }
+
+    /// Translate every reachable instruction, returning the exits of the
+    /// function.
+    fn translate(mut self, ops: &[Op], entry: usize) -> Vec<Exit> {
+        let fuel = self
+            .builder
+            .ins()
+            .load(types::I64, MemFlags::trusted(), self.fuel_ptr, 0);
+        self.builder.def_var(self.fuel, fuel);
+
+        let entry_state = self.analysis.states[entry].clone().unwrap();
+        for (index, ty) in entry_state.locals.iter().enumerate() {
+            if ty.is_stored() {
+                let value = self.load(self.locals_ptr, index, *ty);
+                self.set(index, *ty, value);
+            }
+        }
+        let entry_block = self.block(entry);
+        self.builder.ins().jump(entry_block, &[]);
+
+        let analysis = self.analysis;
+        for (ip, op) in ops.iter().enumerate() {
+            if let Some(state) = &analysis.states[ip] {
+                let block = self.block(ip);
+                self.builder.switch_to_block(block);
+                self.translate_op(ip, op, state.clone());
+            }
+        }
+
+        for (block, target, state) in std::mem::take(&mut self.back_edges) {
+            let out_of_fuel = self.builder.create_block();
+            self.builder.switch_to_block(block);
+            let fuel = self.builder.use_var(self.fuel);
+            let fuel = self.builder.ins().iadd_imm(fuel, -1);
+            self.builder.def_var(self.fuel, fuel);
+            self.builder.ins().brz(fuel, out_of_fuel, &[]);
+            let target_block = self.block(target);
+            self.builder.ins().jump(target_block, &[]);
+
+            self.builder.switch_to_block(out_of_fuel);
+            self.exit(target, &state);
+        }
+
+        self.builder.seal_all_blocks();
+        self.builder.finalize();
+        self.exits
+    }
+
+    fn block(&self, ip: usize) -> Block {
+        self.blocks[ip].expect("Branch targets are reachable")
+    }
+
+    /// The block that an edge from `from` to `to` jumps to.
+    ///
+    /// Backward edges go through a block that uses up fuel, and exits once
+    /// there is none left.
+    fn edge(&mut self, from: usize, to: usize, state: &State) -> Block {
+        if to > from {
+            return self.block(to);
+        }
+
+        let block = self.builder.create_block();
+        self.back_edges.push((block, to, state.clone()));
+        block
+    }
+
+    fn variable(&self, slot: usize, ty: JitType) -> Variable {
+        if ty == JitType::Number {
+            Variable::new(slot * 2)
+        } else {
+            Variable::new(slot * 2 + 1)
+        }
+    }
+
+    /// Read a local register or stack slot.
+    ///
+    /// `undefined` has no value, and reads as a placeholder.
+    fn get(&mut self, slot: usize, ty: JitType) -> IrValue {
+        if ty.is_stored() {
+            let variable = self.variable(slot, ty);
+            self.builder.use_var(variable)
+        } else {
+            self.builder.ins().iconst(types::I32, 0)
+        }
+    }
+
+    fn set(&mut self, slot: usize, ty: JitType, value: IrValue) {
+        if ty.is_stored() {
+            let variable = self.variable(slot, ty);
+            self.builder.def_var(variable, value);
+        }
+    }
+
+    fn pop(&mut self, state: &mut State) -> (JitType, IrValue) {
+        let ty = state.stack.pop().expect("Stack depth is verified");
+        let value = self.get(self.num_locals + state.stack.len(), ty);
+        (ty, value)
+    }
+
+    fn push(&mut self, state: &mut State, ty: JitType, value: IrValue) {
+        self.set(self.num_locals + state.stack.len(), ty, value);
+        state.stack.push(ty);
+    }
+
+    fn load(&mut self, base: IrValue, index: usize, ty: JitType) -> IrValue {
+        let offset = (index * 8) as i32;
+        if ty == JitType::Number {
+            self.builder
+                .ins()
+                .load(types::F64, MemFlags::trusted(), base, offset)
+        } else {
+            let value = self
+                .builder
+                .ins()
+                .load(types::I64, MemFlags::trusted(), base, offset);
+            self.builder.ins().ireduce(types::I32, value)
+        }
+    }
+
+    fn store(&mut self, base: IrValue, index: usize, ty: JitType, value: IrValue) {
+        let offset = (index * 8) as i32;
+        let value = if ty == JitType::Number {
+            value
+        } else {
+            self.builder.ins().uextend(types::I64, value)
+        };
+        self.builder
+            .ins()
+            .store(MemFlags::trusted(), value, base, offset);
+    }
+
+    /// Write back the registers and stack, and resume the interpreter at `ip`.
+    fn exit(&mut self, ip: usize, state: &State) {
+        for (index, ty) in state.locals.iter().enumerate() {
+            if ty.is_stored() {
+                let value = self.get(index, *ty);
+                self.store(self.locals_ptr, index, *ty, value);
+            }
+        }
+
+        for (depth, ty) in state.stack.iter().enumerate() {
+            if ty.is_stored() {
+                let value = self.get(self.num_locals + depth, *ty);
+                self.store(self.stack_ptr, depth, *ty, value);
+            }
+        }
+
+        self.finish(Exit::Resume {
+            ip,
+            stack: state.stack.clone(),
+            locals: state.locals.clone(),
+        });
+    }
+
+    fn finish(&mut self, exit: Exit) {
+        let fuel = self.builder.use_var(self.fuel);
+        self.builder
+            .ins()
+            .store(MemFlags::trusted(), fuel, self.fuel_ptr, 0);
+        let index = self
+            .builder
+            .ins()
+            .iconst(types::I32, self.exits.len() as i64);
+        self.exits.push(exit);
+        self.builder.ins().return_(&[index]);
+    }
+
+    fn coerce_to_number(&mut self, ty: JitType, value: IrValue) -> IrValue {
+        match ty {
+            JitType::Number => value,
+            JitType::Int => self.builder.ins().fcvt_from_sint(types::F64, value),
+            JitType::Uint | JitType::Bool => self.builder.ins().fcvt_from_uint(types::F64, value),
+            _ => self.builder.ins().f64const(f64::NAN),
+        }
+    }
+
+    /// Convert a value to an integer, following ECMA-262 `ToInt32`.
+    ///
+    /// This is also `ToUint32`, as the two only differ in how the bits are
+    /// read.
+    fn coerce_to_i32(&mut self, ty: JitType, value: IrValue) -> IrValue {
+        match ty {
+            JitType::Int | JitType::Uint | JitType::Bool => value,
+            JitType::Number => {
+                let call = self.builder.ins().call(self.helpers.to_int32, &[value]);
+                self.builder.inst_results(call)[0]
+            }
+            _ => self.builder.ins().iconst(types::I32, 0),
+        }
+    }
+
+    fn coerce_to_bool(&mut self, ty: JitType, value: IrValue) -> IrValue {
+        match ty {
+            JitType::Number => {
+                let zero = self.builder.ins().f64const(0.0);
+                self.builder
+                    .ins()
+                    .fcmp(FloatCC::OrderedNotEqual, value, zero)
+            }
+            JitType::Int | JitType::Uint | JitType::Bool => {
+                self.builder.ins().icmp_imm(IntCC::NotEqual, value, 0)
+            }
+            _ => self.builder.ins().bconst(types::B1, false),
+        }
+    }
+
+    /// Compare the top two values of the stack as numbers.
+    fn compare(&mut self, state: &mut State, cond: FloatCC) -> IrValue {
+        let (ty2, value2) = self.pop(state);
+        let (ty1, value1) = self.pop(state);
+        let value1 = self.coerce_to_number(ty1, value1);
+        let value2 = self.coerce_to_number(ty2, value2);
+        self.builder.ins().fcmp(cond, value1, value2)
+    }
+
+    /// Compare the top two values of the stack with `==`.
+    fn equals(&mut self, state: &mut State) -> IrValue {
+        let (ty2, value2) = self.pop(state);
+        let (ty1, value1) = self.pop(state);
+        if ty1 == JitType::Undefined || ty2 == JitType::Undefined {
+            return self.builder.ins().bconst(types::B1, ty1 == ty2);
+        }
+
+        let value1 = self.coerce_to_number(ty1, value1);
+        let value2 = self.coerce_to_number(ty2, value2);
+        self.builder.ins().fcmp(FloatCC::Equal, value1, value2)
+    }
+
+    /// Compare the top two values of the stack with `===`.
+    fn strict_equals(&mut self, state: &mut State) -> IrValue {
+        let (ty2, value2) = self.pop(state);
+        let (ty1, value1) = self.pop(state);
+        if ty1.is_numeric() && ty2.is_numeric() {
+            let value1 = self.coerce_to_number(ty1, value1);
+            let value2 = self.coerce_to_number(ty2, value2);
+            self.builder.ins().fcmp(FloatCC::Equal, value1, value2)
+        } else if ty1 == JitType::Bool && ty2 == JitType::Bool {
+            self.builder.ins().icmp(IntCC::Equal, value1, value2)
+        } else {
+            self.builder.ins().bconst(types::B1, ty1 == ty2)
+        }
+    }
+
+    fn branch(&mut self, ip: usize, target: usize, cond: IrValue, state: &State) {
+        let taken = self.edge(ip, target, state);
+        let next = self.edge(ip, ip + 1, state);
+        self.builder.ins().brnz(cond, taken, &[]);
+        self.builder.ins().jump(next, &[]);
+    }
+
+    fn translate_op(&mut self, ip: usize, op: &Op, mut state: State) {
+        use JitType::*;
+
+        if step(op, &state).is_none() {
+            self.exit(ip, &state);
+            return;
+        }
+
+        match op {
+            Op::PushDouble { value } => {
+                let value = self.builder.ins().f64const(*value);
+                self.push(&mut state, Number, value);
+            }
+            Op::PushInt { value } => {
+                let value = self.builder.ins().iconst(types::I32, *value as i64);
+                self.push(&mut state, Int, value);
+            }
+            Op::PushUint { value } => {
+                let value = self.builder.ins().iconst(types::I32, *value as i32 as i64);
+                self.push(&mut state, Uint, value);
+            }
+            Op::Jump { target } => {
+                let block = self.edge(ip, *target, &state);
+                self.builder.ins().jump(block, &[]);
+                return;
+            }
+            Op::IfTrue { target } | Op::IfFalse { target } => {
+                let (ty, value) = self.pop(&mut state);
+                let mut cond = self.coerce_to_bool(ty, value);
+                if matches!(op, Op::IfFalse { .. }) {
+                    cond = self.builder.ins().bnot(cond);
+                }
+                self.branch(ip, *target, cond, &state);
+                return;
+            }
+            Op::IfStrictEq { target } | Op::IfStrictNe { target } => {
+                let mut cond = self.strict_equals(&mut state);
+                if matches!(op, Op::IfStrictNe { .. }) {
+                    cond = self.builder.ins().bnot(cond);
+                }
+                self.branch(ip, *target, cond, &state);
+                return;
+            }
+            Op::IfEq { target } | Op::IfNe { target } => {
+                let mut cond = self.equals(&mut state);
+                if matches!(op, Op::IfNe { .. }) {
+                    cond = self.builder.ins().bnot(cond);
+                }
+                self.branch(ip, *target, cond, &state);
+                return;
+            }
+            Op::IfGe { target }
+            | Op::IfGt { target }
+            | Op::IfLe { target }
+            | Op::IfLt { target }
+            | Op::IfNge { target }
+            | Op::IfNgt { target }
+            | Op::IfNle { target }
+            | Op::IfNlt { target } => {
+                // Comparisons involving NaN are false, so only the negated
+                // branches are taken.
+                let cond = match op {
+                    Op::IfGe { .. } => FloatCC::GreaterThanOrEqual,
+                    Op::IfGt { .. } => FloatCC::GreaterThan,
+                    Op::IfLe { .. } => FloatCC::LessThanOrEqual,
+                    Op::IfLt { .. } => FloatCC::LessThan,
+                    Op::IfNge { .. } => FloatCC::UnorderedOrLessThan,
+                    Op::IfNgt { .. } => FloatCC::UnorderedOrLessThanOrEqual,
+                    Op::IfNle { .. } => FloatCC::UnorderedOrGreaterThan,
+                    _ => FloatCC::UnorderedOrGreaterThanOrEqual,
+                };
+                let cond = self.compare(&mut state, cond);
+                self.branch(ip, *target, cond, &state);
+                return;
+            }
+            Op::LookupSwitch {
+                default_target,
+                case_targets,
+            } => {
+                let (ty, value) = self.pop(&mut state);
+                let index = self.coerce_to_i32(ty, value);
+                for (case, target) in case_targets.iter().enumerate() {
+                    let taken = self.edge(ip, *target, &state);
+                    let next = self.builder.create_block();
+                    let cond = self
+                        .builder
+                        .ins()
+                        .icmp_imm(IntCC::Equal, index, case as i64);
+                    self.builder.ins().brnz(cond, taken, &[]);
+                    self.builder.ins().jump(next, &[]);
+                    self.builder.switch_to_block(next);
+                }
+                let default = self.edge(ip, *default_target, &state);
+                self.builder.ins().jump(default, &[]);
+                return;
+            }
+            Op::StringSwitch { .. } => {}
+            Op::Abc(op) => match op {
+                AbcOp::PushByte { value } => {
+                    let value = self.builder.ins().iconst(types::I32, *value as i8 as i64);
+                    self.push(&mut state, Int, value);
+                }
+                AbcOp::PushShort { value } => {
+                    let value = self.builder.ins().iconst(types::I32, *value as i64);
+                    self.push(&mut state, Int, value);
+                }
+                AbcOp::PushTrue | AbcOp::PushFalse => {
+                    let value = matches!(op, AbcOp::PushTrue);
+                    let value = self.builder.ins().iconst(types::I32, value as i64);
+                    self.push(&mut state, Bool, value);
+                }
+                AbcOp::PushNaN => {
+                    let value = self.builder.ins().f64const(f64::NAN);
+                    self.push(&mut state, Number, value);
+                }
+                AbcOp::PushUndefined => {
+                    let value = self.get(0, Undefined);
+                    self.push(&mut state, Undefined, value);
+                }
+                AbcOp::Pop => {
+                    self.pop(&mut state);
+                }
+                AbcOp::Dup => {
+                    let (ty, value) = self.pop(&mut state);
+                    self.push(&mut state, ty, value);
+                    self.push(&mut state, ty, value);
+                }
+                AbcOp::Swap => {
+                    let (ty2, value2) = self.pop(&mut state);
+                    let (ty1, value1) = self.pop(&mut state);
+                    self.push(&mut state, ty2, value2);
+                    self.push(&mut state, ty1, value1);
+                }
+                AbcOp::GetLocal { index } => {
+                    let ty = state.locals[*index as usize];
+                    let value = self.get(*index as usize, ty);
+                    self.push(&mut state, ty, value);
+                }
+                AbcOp::SetLocal { index } => {
+                    let (ty, value) = self.pop(&mut state);
+                    self.set(*index as usize, ty, value);
+                    state.locals[*index as usize] = ty;
+                }
+                AbcOp::Kill { index } => {
+                    state.locals[*index as usize] = Undefined;
+                }
+                AbcOp::IncLocal { index } | AbcOp::DecLocal { index } => {
+                    let index = *index as usize;
+                    let value = self.get(index, state.locals[index]);
+                    let value = self.coerce_to_number(state.locals[index], value);
+                    let delta = if matches!(op, AbcOp::IncLocal { .. }) {
+                        1.0
+                    } else {
+                        -1.0
+                    };
+                    let delta = self.builder.ins().f64const(delta);
+                    let value = self.builder.ins().fadd(value, delta);
+                    self.set(index, Number, value);
+                    state.locals[index] = Number;
+                }
+                AbcOp::IncLocalI { index } | AbcOp::DecLocalI { index } => {
+                    let index = *index as usize;
+                    let value = self.get(index, state.locals[index]);
+                    let value = self.coerce_to_i32(state.locals[index], value);
+                    let delta = if matches!(op, AbcOp::IncLocalI { .. }) {
+                        1
+                    } else {
+                        -1
+                    };
+                    let value = self.builder.ins().iadd_imm(value, delta);
+                    self.set(index, Int, value);
+                    state.locals[index] = Int;
+                }
+                AbcOp::Add | AbcOp::Subtract | AbcOp::Multiply | AbcOp::Divide | AbcOp::Modulo => {
+                    let (ty2, value2) = self.pop(&mut state);
+                    let (ty1, value1) = self.pop(&mut state);
+                    let value1 = self.coerce_to_number(ty1, value1);
+                    let value2 = self.coerce_to_number(ty2, value2);
+                    let result = match op {
+                        AbcOp::Add => self.builder.ins().fadd(value1, value2),
+                        AbcOp::Subtract => self.builder.ins().fsub(value1, value2),
+                        AbcOp::Multiply => self.builder.ins().fmul(value1, value2),
+                        AbcOp::Divide => self.builder.ins().fdiv(value1, value2),
+                        _ => {
+                            let call = self
+                                .builder
+                                .ins()
+                                .call(self.helpers.modulo, &[value1, value2]);
+                            self.builder.inst_results(call)[0]
+                        }
+                    };
+                    self.push(&mut state, Number, result);
+                }
+                AbcOp::AddI
+                | AbcOp::SubtractI
+                | AbcOp::MultiplyI
+                | AbcOp::BitAnd
+                | AbcOp::BitOr
+                | AbcOp::BitXor
+                | AbcOp::LShift
+                | AbcOp::RShift
+                | AbcOp::URShift => {
+                    let (ty2, value2) = self.pop(&mut state);
+                    let (ty1, value1) = self.pop(&mut state);
+                    let value1 = self.coerce_to_i32(ty1, value1);
+                    let value2 = self.coerce_to_i32(ty2, value2);
+                    let amount = self.builder.ins().band_imm(value2, 0x1F);
+                    let (ty, result) = match op {
+                        AbcOp::AddI => (Int, self.builder.ins().iadd(value1, value2)),
+                        AbcOp::SubtractI => (Int, self.builder.ins().isub(value1, value2)),
+                        AbcOp::MultiplyI => (Int, self.builder.ins().imul(value1, value2)),
+                        AbcOp::BitAnd => (Int, self.builder.ins().band(value1, value2)),
+                        AbcOp::BitOr => (Int, self.builder.ins().bor(value1, value2)),
+                        AbcOp::BitXor => (Int, self.builder.ins().bxor(value1, value2)),
+                        AbcOp::LShift => (Int, self.builder.ins().ishl(value1, amount)),
+                        AbcOp::RShift => (Int, self.builder.ins().sshr(value1, amount)),
+                        _ => (Uint, self.builder.ins().ushr(value1, amount)),
+                    };
+                    self.push(&mut state, ty, result);
+                }
+                AbcOp::Equals => {
+                    let cond = self.equals(&mut state);
+                    let result = self.builder.ins().bint(types::I32, cond);
+                    self.push(&mut state, Bool, result);
+                }
+                AbcOp::StrictEquals => {
+                    let cond = self.strict_equals(&mut state);
+                    let result = self.builder.ins().bint(types::I32, cond);
+                    self.push(&mut state, Bool, result);
+                }
+                AbcOp::LessThan | AbcOp::LessEquals | AbcOp::GreaterThan | AbcOp::GreaterEquals => {
+                    let cond = match op {
+                        AbcOp::LessThan => FloatCC::LessThan,
+                        AbcOp::LessEquals => FloatCC::LessThanOrEqual,
+                        AbcOp::GreaterThan => FloatCC::GreaterThan,
+                        _ => FloatCC::GreaterThanOrEqual,
+                    };
+                    let cond = self.compare(&mut state, cond);
+                    let result = self.builder.ins().bint(types::I32, cond);
+                    self.push(&mut state, Bool, result);
+                }
+                AbcOp::Negate
+                | AbcOp::Increment
+                | AbcOp::Decrement
+                | AbcOp::ConvertD
+                | AbcOp::ConvertI
+                | AbcOp::ConvertU => {
+                    let (ty, value) = self.pop(&mut state);
+                    let result = match op {
+                        AbcOp::ConvertI => {
+                            let value = self.coerce_to_i32(ty, value);
+                            self.builder.ins().fcvt_from_sint(types::F64, value)
+                        }
+                        AbcOp::ConvertU => {
+                            let value = self.coerce_to_i32(ty, value);
+                            self.builder.ins().fcvt_from_uint(types::F64, value)
+                        }
+                        _ => {
+                            let value = self.coerce_to_number(ty, value);
+                            match op {
+                                AbcOp::Negate => self.builder.ins().fneg(value),
+                                AbcOp::Increment | AbcOp::Decrement => {
+                                    let delta = if matches!(op, AbcOp::Increment) {
+                                        1.0
+                                    } else {
+                                        -1.0
+                                    };
+                                    let delta = self.builder.ins().f64const(delta);
+                                    self.builder.ins().fadd(value, delta)
+                                }
+                                _ => value,
+                            }
+                        }
+                    };
+                    self.push(&mut state, Number, result);
+                }
+                AbcOp::NegateI | AbcOp::IncrementI | AbcOp::DecrementI | AbcOp::BitNot => {
+                    let (ty, value) = self.pop(&mut state);
+                    let value = self.coerce_to_i32(ty, value);
+                    let result = match op {
+                        AbcOp::NegateI => self.builder.ins().ineg(value),
+                        AbcOp::IncrementI => self.builder.ins().iadd_imm(value, 1),
+                        AbcOp::DecrementI => self.builder.ins().iadd_imm(value, -1),
+                        _ => self.builder.ins().bnot(value),
+                    };
+                    self.push(&mut state, Int, result);
+                }
+                AbcOp::ConvertB | AbcOp::Not => {
+                    let (ty, value) = self.pop(&mut state);
+                    let mut cond = self.coerce_to_bool(ty, value);
+                    if matches!(op, AbcOp::Not) {
+                        cond = self.builder.ins().bnot(cond);
+                    }
+                    let result = self.builder.ins().bint(types::I32, cond);
+                    self.push(&mut state, Bool, result);
+                }
+                AbcOp::ReturnValue => {
+                    let (ty, value) = self.pop(&mut state);
+                    if ty.is_stored() {
+                        self.store(self.stack_ptr, 0, ty, value);
+                    }
+                    self.finish(Exit::Return(ty));
+                    return;
+                }
+                AbcOp::ReturnVoid => {
+                    self.finish(Exit::Return(Undefined));
+                    return;
+                }
+                _ => {}
+            },
+        }
+
+        let next = self.block(ip + 1);
+        self.builder.ins().jump(next, &[]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swf::avm2::types::Index;
+
+    fn abc(op: AbcOp) -> Op {
+        Op::Abc(op)
+    }
+
+    /// Compile `ops` from `entry`, run them with the given registers, and
+    /// return the exit with the decoded operand stack and registers.
+    fn run(
+        ops: &[Op],
+        entry: usize,
+        registers: &[Value<'static>],
+        fuel: u64,
+    ) -> (Exit, Vec<Value<'static>>, Vec<Value<'static>>) {
+        let types: Vec<JitType> = registers.iter().map(JitType::of).collect();
+        let code = compile(ops, entry, &types).expect("Code should compile");
+        let mut locals: Vec<u64> = registers.iter().map(encode).collect();
+        let mut stack = vec![0; code.max_stack()];
+        let mut fuel = fuel;
+
+        let exit = match code.run(&mut locals, &mut stack, &mut fuel) {
+            Exit::Return(ty) => Exit::Return(*ty),
+            Exit::Resume { ip, stack, locals } => Exit::Resume {
+                ip: *ip,
+                stack: stack.clone(),
+                locals: locals.clone(),
+            },
+        };
+
+        let (stack_types, local_types) = match &exit {
+            Exit::Return(ty) => (vec![*ty], types),
+            Exit::Resume { stack, locals, .. } => (stack.clone(), locals.clone()),
+        };
+        let stack = stack_types
+            .iter()
+            .zip(&stack)
+            .map(|(ty, bits)| decode(*ty, *bits).unwrap())
+            .collect();
+        let registers = local_types
+            .iter()
+            .zip(&locals)
+            .zip(registers)
+            .map(|((ty, bits), register)| decode(*ty, *bits).unwrap_or_else(|| register.clone()))
+            .collect();
+
+        (exit, stack, registers)
+    }
+
+    /// Compile and run code that returns a value.
+    fn run_value(ops: &[Op], registers: &[Value<'static>]) -> Value<'static> {
+        let (exit, stack, _) = run(ops, 0, registers, FUEL);
+        assert!(
+            matches!(exit, Exit::Return(_)),
+            "Unexpected exit {:?}",
+            exit
+        );
+        stack[0].clone()
+    }
+
+    /// `var sum:Number = 0; for (var i:int = 0; i < 1000; i++) sum += i * 0.5; return sum;`
+    fn sum_loop() -> Vec<Op> {
+        vec![
+            abc(AbcOp::PushByte { value: 0 }),
+            abc(AbcOp::ConvertD),
+            abc(AbcOp::SetLocal { index: 1 }),
+            abc(AbcOp::PushByte { value: 0 }),
+            abc(AbcOp::SetLocal { index: 2 }),
+            Op::Jump { target: 14 },
+            abc(AbcOp::Label),
+            abc(AbcOp::GetLocal { index: 1 }),
+            abc(AbcOp::GetLocal { index: 2 }),
+            Op::PushDouble { value: 0.5 },
+            abc(AbcOp::Multiply),
+            abc(AbcOp::Add),
+            abc(AbcOp::SetLocal { index: 1 }),
+            abc(AbcOp::IncLocalI { index: 2 }),
+            abc(AbcOp::GetLocal { index: 2 }),
+            abc(AbcOp::PushShort { value: 1000 }),
+            Op::IfLt { target: 6 },
+            abc(AbcOp::GetLocal { index: 1 }),
+            abc(AbcOp::ReturnValue),
+        ]
+    }
+
+    fn registers() -> Vec<Value<'static>> {
+        vec!["this".into(), Value::Undefined, Value::Undefined]
+    }
+
+    #[test]
+    fn loop_returns() {
+        assert_eq!(
+            run_value(&sum_loop(), &registers()),
+            Value::Number(249750.0)
+        );
+    }
+
+    #[test]
+    fn loop_from_back_edge() {
+        let registers = vec!["this".into(), Value::Number(10.0), Value::Integer(998)];
+        let (exit, stack, _) = run(&sum_loop(), 6, &registers, FUEL);
+        assert_eq!(exit, Exit::Return(JitType::Number));
+        assert_eq!(stack, vec![Value::Number(10.0 + 998.0 * 0.5 + 999.0 * 0.5)]);
+    }
+
+    #[test]
+    fn out_of_fuel() {
+        let (exit, stack, registers) = run(&sum_loop(), 0, &registers(), 3);
+        assert_eq!(
+            exit,
+            Exit::Resume {
+                ip: 6,
+                stack: vec![],
+                locals: vec![JitType::Other, JitType::Number, JitType::Int],
+            }
+        );
+        assert!(stack.is_empty());
+        assert_eq!(
+            registers,
+            vec!["this".into(), Value::Number(1.5), Value::Integer(3)]
+        );
+    }
+
+    #[test]
+    fn side_exit() {
+        let ops = vec![
+            abc(AbcOp::PushByte { value: 2 }),
+            abc(AbcOp::SetLocal { index: 1 }),
+            abc(AbcOp::PushByte { value: 3 }),
+            abc(AbcOp::GetLocal { index: 1 }),
+            abc(AbcOp::AddI),
+            abc(AbcOp::PushString {
+                value: Index::new(1),
+            }),
+            abc(AbcOp::ReturnValue),
+        ];
+        let (exit, stack, registers) = run(&ops, 0, &registers(), FUEL);
+        assert_eq!(
+            exit,
+            Exit::Resume {
+                ip: 5,
+                stack: vec![JitType::Int],
+                locals: vec![JitType::Other, JitType::Int, JitType::Undefined],
+            }
+        );
+        assert_eq!(stack, vec![Value::Integer(5)]);
+        assert_eq!(
+            registers,
+            vec!["this".into(), Value::Integer(2), Value::Undefined]
+        );
+    }
+
+    #[test]
+    fn other_registers_exit() {
+        let ops = vec![
+            abc(AbcOp::PushByte { value: 1 }),
+            abc(AbcOp::GetLocal { index: 0 }),
+            abc(AbcOp::ReturnValue),
+        ];
+        let (exit, stack, _) = run(&ops, 0, &registers(), FUEL);
+        assert_eq!(
+            exit,
+            Exit::Resume {
+                ip: 1,
+                stack: vec![JitType::Int],
+                locals: vec![JitType::Other, JitType::Undefined, JitType::Undefined],
+            }
+        );
+        assert_eq!(stack, vec![Value::Integer(1)]);
+    }
+
+    #[test]
+    fn conflicting_types() {
+        // `var x = flag ? 1 : 1.5;` leaves `x` with a different type on each
+        // path.
+        let ops = vec![
+            abc(AbcOp::GetLocal { index: 1 }),
+            Op::IfTrue { target: 5 },
+            abc(AbcOp::PushByte { value: 1 }),
+            abc(AbcOp::SetLocal { index: 2 }),
+            Op::Jump { target: 7 },
+            Op::PushDouble { value: 1.5 },
+            abc(AbcOp::SetLocal { index: 2 }),
+            abc(AbcOp::ReturnVoid),
+        ];
+        let types = vec![JitType::Other, JitType::Bool, JitType::Undefined];
+        assert!(compile(&ops, 0, &types).is_none());
+    }
+
+    #[test]
+    fn unsupported_entry() {
+        let ops = vec![abc(AbcOp::GetGlobalScope), abc(AbcOp::ReturnValue)];
+        assert!(compile(&ops, 0, &[JitType::Other]).is_none());
+    }
+
+    #[test]
+    fn string_switch_exits() {
+        let ops = vec![
+            Op::PushInt { value: 1 },
+            Op::StringSwitch {
+                register: 0,
+                string: Index::new(1),
+                string_first: false,
+                cases: Default::default(),
+                default_target: 2,
+            },
+            abc(AbcOp::ReturnValue),
+        ];
+        let (exit, stack, _) = run(&ops, 0, &[Value::Undefined], FUEL);
+        assert!(matches!(exit, Exit::Resume { ip: 1, .. }));
+        assert_eq!(stack, vec![Value::Integer(1)]);
+    }
+
+    #[test]
+    fn integer_arithmetic() {
+        let add = vec![
+            Op::PushInt { value: i32::MAX },
+            abc(AbcOp::PushByte { value: 1 }),
+            abc(AbcOp::AddI),
+            abc(AbcOp::ReturnValue),
+        ];
+        assert_eq!(run_value(&add, &[]), Value::Integer(i32::MIN));
+
+        let urshift = vec![
+            abc(AbcOp::PushByte { value: 0xFF }),
+            abc(AbcOp::PushByte { value: 60 }),
+            abc(AbcOp::URShift),
+            abc(AbcOp::ReturnValue),
+        ];
+        assert_eq!(run_value(&urshift, &[]), Value::Unsigned(15));
+
+        let bitand = vec![
+            Op::PushDouble {
+                value: 4294967297.0,
+            },
+            abc(AbcOp::PushByte { value: 3 }),
+            abc(AbcOp::BitAnd),
+            abc(AbcOp::ReturnValue),
+        ];
+        assert_eq!(run_value(&bitand, &[]), Value::Integer(1));
+
+        let convert = vec![
+            abc(AbcOp::PushByte { value: 0xFF }),
+            abc(AbcOp::ConvertU),
+            abc(AbcOp::ReturnValue),
+        ];
+        assert_eq!(run_value(&convert, &[]), Value::Number(4294967295.0));
+    }
+
+    #[test]
+    fn number_arithmetic() {
+        let modulo = vec![
+            Op::PushDouble { value: -7.5 },
+            abc(AbcOp::PushByte { value: 2 }),
+            abc(AbcOp::Modulo),
+            abc(AbcOp::ReturnValue),
+        ];
+        assert_eq!(run_value(&modulo, &[]), Value::Number(-1.5));
+
+        let undefined = vec![
+            abc(AbcOp::PushUndefined),
+            abc(AbcOp::Increment),
+            abc(AbcOp::ReturnValue),
+        ];
+        match run_value(&undefined, &[]) {
+            Value::Number(n) => assert!(n.is_nan()),
+            value => panic!("Unexpected value {:?}", value),
+        }
+
+        let divide = vec![
+            abc(AbcOp::PushTrue),
+            abc(AbcOp::PushByte { value: 0 }),
+            abc(AbcOp::Divide),
+            abc(AbcOp::ReturnValue),
+        ];
+        assert_eq!(run_value(&divide, &[]), Value::Number(f64::INFINITY));
+    }
+
+    #[test]
+    fn nan_comparisons() {
+        let compare = |op: Op| {
+            vec![
+                abc(AbcOp::PushNaN),
+                abc(AbcOp::PushByte { value: 0 }),
+                op,
+                abc(AbcOp::PushFalse),
+                abc(AbcOp::ReturnValue),
+                abc(AbcOp::PushTrue),
+                abc(AbcOp::ReturnValue),
+            ]
+        };
+
+        for op in [
+            Op::IfLt { target: 5 },
+            Op::IfLe { target: 5 },
+            Op::IfGt { target: 5 },
+            Op::IfGe { target: 5 },
+            Op::IfEq { target: 5 },
+        ] {
+            assert_eq!(run_value(&compare(op), &[]), Value::Bool(false));
+        }
+
+        for op in [
+            Op::IfNlt { target: 5 },
+            Op::IfNle { target: 5 },
+            Op::IfNgt { target: 5 },
+            Op::IfNge { target: 5 },
+            Op::IfNe { target: 5 },
+        ] {
+            assert_eq!(run_value(&compare(op), &[]), Value::Bool(true));
+        }
+    }
+
+    #[test]
+    fn equality() {
+        let equals = |value1: Op, value2: Op, op: AbcOp| {
+            vec![value1, value2, abc(op), abc(AbcOp::ReturnValue)]
+        };
+
+        let cases = [
+            (
+                abc(AbcOp::PushUndefined),
+                abc(AbcOp::PushUndefined),
+                AbcOp::Equals,
+                true,
+            ),
+            (
+                abc(AbcOp::PushUndefined),
+                abc(AbcOp::PushNaN),
+                AbcOp::Equals,
+                false,
+            ),
+            (
+                abc(AbcOp::PushTrue),
+                abc(AbcOp::PushByte { value: 1 }),
+                AbcOp::Equals,
+                true,
+            ),
+            (
+                abc(AbcOp::PushTrue),
+                abc(AbcOp::PushByte { value: 1 }),
+                AbcOp::StrictEquals,
+                false,
+            ),
+            (
+                Op::PushDouble { value: 1.0 },
+                abc(AbcOp::PushByte { value: 1 }),
+                AbcOp::StrictEquals,
+                true,
+            ),
+            (
+                Op::PushUint { value: u32::MAX },
+                abc(AbcOp::PushByte { value: 0xFF }),
+                AbcOp::StrictEquals,
+                false,
+            ),
+            (
+                abc(AbcOp::PushNaN),
+                abc(AbcOp::PushNaN),
+                AbcOp::StrictEquals,
+                false,
+            ),
+            (
+                abc(AbcOp::PushFalse),
+                abc(AbcOp::PushFalse),
+                AbcOp::StrictEquals,
+                true,
+            ),
+        ];
+
+        for (value1, value2, op, expected) in cases {
+            let ops = equals(value1, value2, op);
+            assert_eq!(run_value(&ops, &[]), Value::Bool(expected), "{:?}", ops);
+        }
+    }
+
+    #[test]
+    fn lookup_switch() {
+        let ops = vec![
+            abc(AbcOp::GetLocal { index: 0 }),
+            Op::LookupSwitch {
+                default_target: 6,
+                case_targets: vec![2, 4],
+            },
+            abc(AbcOp::PushByte { value: 10 }),
+            abc(AbcOp::ReturnValue),
+            abc(AbcOp::PushByte { value: 20 }),
+            abc(AbcOp::ReturnValue),
+            abc(AbcOp::PushByte { value: 30 }),
+            abc(AbcOp::ReturnValue),
+        ];
+
+        assert_eq!(run_value(&ops, &[Value::Integer(1)]), Value::Integer(20));
+        assert_eq!(run_value(&ops, &[Value::Integer(-1)]), Value::Integer(30));
+        assert_eq!(run_value(&ops, &[Value::Number(0.5)]), Value::Integer(10));
+        assert_eq!(run_value(&ops, &[Value::Unsigned(2)]), Value::Integer(30));
+    }
+
+    #[test]
+    fn hot_entries() {
+        let mut cache = JitCache::default();
+        for _ in 1..HOT_THRESHOLD {
+            assert!(!cache.is_hot(0));
+        }
+        assert!(cache.is_hot(0));
+        assert!(!cache.is_hot(1));
+
+        let ops = sum_loop();
+        let types: Vec<JitType> = registers().iter().map(JitType::of).collect();
+        let code = cache.compiled(&ops, 0, &types).unwrap();
+        assert!(Rc::ptr_eq(&code, &cache.compiled(&ops, 0, &types).unwrap()));
+    }
+}
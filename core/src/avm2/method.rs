@@ -3,11 +3,14 @@
 use crate::avm2::activation::Activation;
 use crate::avm2::names::Multiname;
 use crate::avm2::object::Object;
+use crate::avm2::property_cache::PropertyCache;
 use crate::avm2::script::TranslationUnit;
 use crate::avm2::value::{abc_default_value, Value};
+use crate::avm2::verify::VerifiedBody;
 use crate::avm2::Error;
 use crate::string::AvmString;
-use gc_arena::{Collect, CollectionContext, Gc, MutationContext};
+use gc_arena::{Collect, CollectionContext, Gc, GcCell, MutationContext};
+use std::cell::RefCell;
 use std::fmt;
 use std::rc::Rc;
 use swf::avm2::types::{
@@ -130,6 +133,13 @@ pub struct BytecodeMethod<'gc> {
     /// A free-standing function corresponds to the `Function` trait type, and
     /// is instantiated with the `newfunction` opcode.
     pub is_function: bool,
+
+    /// Inline caches for this method's property access instructions.
+    pub property_cache: GcCell<'gc, PropertyCache<'gc>>,
+
+    /// The verified form of this method's body, once it has been verified.
+    #[collect(require_static)]
+    verified_body: RefCell<Option<Rc<VerifiedBody>>>,
 }
 
 impl<'gc> BytecodeMethod<'gc> {
@@ -171,6 +181,11 @@ impl<'gc> BytecodeMethod<'gc> {
                             signature,
                             return_type,
                             is_function,
+                            property_cache: GcCell::allocate(
+                                activation.context.gc_context,
+                                PropertyCache::default(),
+                            ),
+                            verified_body: RefCell::new(None),
                         },
                     ));
                 }
@@ -187,6 +202,11 @@ impl<'gc> BytecodeMethod<'gc> {
                 signature,
                 return_type: Multiname::any(),
                 is_function,
+                property_cache: GcCell::allocate(
+                    activation.context.gc_context,
+                    PropertyCache::default(),
+                ),
+                verified_body: RefCell::new(None),
             },
         ))
     }
@@ -217,6 +237,21 @@ impl<'gc> BytecodeMethod<'gc> {
         }
     }
 
+    /// Get the verified form of this method's body.
+    ///
+    /// The body is verified the first time this is called; later calls reuse
+    /// the result.
+    pub fn verified_body(&self) -> Result<Rc<VerifiedBody>, Error> {
+        if let Some(verified_body) = &*self.verified_body.borrow() {
+            return Ok(verified_body.clone());
+        }
+
+        let verified_body = Rc::new(VerifiedBody::from_method(self)?);
+        *self.verified_body.borrow_mut() = Some(verified_body.clone());
+
+        Ok(verified_body)
+    }
+
     /// Get the list of method params for this method.
     pub fn signature(&self) -> &[ParamConfig<'gc>] {
         &self.signature
@@ -383,6 +383,18 @@ impl<'gc> Multiname<'gc> {
         abc_multiname
     }
 
+    /// Check if a multiname can be read from the constant pool without
+    /// popping runtime name components from the stack.
+    pub fn is_static_index(abc: &AbcFile, multiname_index: Index<AbcMultiname>) -> bool {
+        matches!(
+            Self::resolve_multiname_index(abc, multiname_index),
+            Ok(AbcMultiname::QName { .. })
+                | Ok(AbcMultiname::QNameA { .. })
+                | Ok(AbcMultiname::Multiname { .. })
+                | Ok(AbcMultiname::MultinameA { .. })
+        )
+    }
+
     /// Read a multiname from the ABC constant pool, copying it into the most
     /// general form of multiname.
     pub fn from_abc_multiname(
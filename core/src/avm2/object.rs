@@ -8,6 +8,7 @@ use crate::avm2::domain::Domain;
 use crate::avm2::events::{DispatchList, Event};
 use crate::avm2::function::Executable;
 use crate::avm2::names::{Multiname, Namespace, QName};
+use crate::avm2::property_cache::ClassLayout;
 use crate::avm2::regexp::RegExp;
 use crate::avm2::scope::Scope;
 use crate::avm2::traits::{Trait, TraitKind};
@@ -18,7 +19,7 @@ use crate::backend::audio::{SoundHandle, SoundInstanceHandle};
 use crate::bitmap::bitmap_data::BitmapData;
 use crate::display_object::DisplayObject;
 use crate::string::AvmString;
-use gc_arena::{Collect, GcCell, MutationContext};
+use gc_arena::{Collect, Gc, GcCell, MutationContext};
 use ruffle_macros::enum_trait_object;
 use std::cell::{Ref, RefMut};
 use std::fmt::Debug;
@@ -28,18 +29,23 @@ mod array_object;
 mod bitmapdata_object;
 mod bytearray_object;
 mod class_object;
+mod context3d_object;
+mod context3d_resource_object;
 mod custom_object;
+mod date_object;
 mod dispatch_object;
 mod domain_object;
 mod event_object;
 mod function_object;
 mod loaderinfo_object;
+mod matrix3d_object;
 mod namespace_object;
 mod primitive_object;
 mod regexp_object;
 mod script_object;
 mod sound_object;
 mod soundchannel_object;
+mod stage3d_object;
 mod stage_object;
 mod vector_object;
 mod xml_object;
@@ -48,19 +54,26 @@ pub use crate::avm2::object::array_object::{array_allocator, ArrayObject};
 pub use crate::avm2::object::bitmapdata_object::{bitmapdata_allocator, BitmapDataObject};
 pub use crate::avm2::object::bytearray_object::{bytearray_allocator, ByteArrayObject};
 pub use crate::avm2::object::class_object::ClassObject;
+pub use crate::avm2::object::context3d_object::{context3d_allocator, Context3DObject};
+pub use crate::avm2::object::context3d_resource_object::{
+    context3d_resource_allocator, Context3DResource, Context3DResourceObject,
+};
+pub use crate::avm2::object::date_object::{date_allocator, DateObject};
 pub use crate::avm2::object::dispatch_object::DispatchObject;
 pub use crate::avm2::object::domain_object::{appdomain_allocator, DomainObject};
 pub use crate::avm2::object::event_object::{event_allocator, EventObject};
-pub use crate::avm2::object::function_object::FunctionObject;
+pub use crate::avm2::object::function_object::{FunctionObject, FunctionObjectWeak};
 pub use crate::avm2::object::loaderinfo_object::{
     loaderinfo_allocator, LoaderInfoObject, LoaderStream,
 };
+pub use crate::avm2::object::matrix3d_object::{matrix3d_allocator, Matrix3DObject};
 pub use crate::avm2::object::namespace_object::{namespace_allocator, NamespaceObject};
 pub use crate::avm2::object::primitive_object::{primitive_allocator, PrimitiveObject};
 pub use crate::avm2::object::regexp_object::{regexp_allocator, RegExpObject};
 pub use crate::avm2::object::script_object::ScriptObject;
 pub use crate::avm2::object::sound_object::{sound_allocator, SoundObject};
 pub use crate::avm2::object::soundchannel_object::{soundchannel_allocator, SoundChannelObject};
+pub use crate::avm2::object::stage3d_object::{stage3d_allocator, Stage3DObject};
 pub use crate::avm2::object::stage_object::{stage_allocator, StageObject};
 pub use crate::avm2::object::vector_object::{vector_allocator, VectorObject};
 pub use crate::avm2::object::xml_object::{xml_allocator, XmlObject};
@@ -90,6 +103,11 @@ pub use crate::avm2::object::xml_object::{xml_allocator, XmlObject};
         SoundObject(SoundObject<'gc>),
         SoundChannelObject(SoundChannelObject<'gc>),
         BitmapDataObject(BitmapDataObject<'gc>),
+        Stage3DObject(Stage3DObject<'gc>),
+        Context3DObject(Context3DObject<'gc>),
+        Context3DResourceObject(Context3DResourceObject<'gc>),
+        Matrix3DObject(Matrix3DObject<'gc>),
+        DateObject(DateObject<'gc>),
     }
 )]
 pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy {
@@ -334,14 +352,33 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
 
     /// Retrieve a given enumerable name by index.
     ///
-    /// Enumerants are listed by index, starting from zero. A value of `None`
-    /// indicates that no enumerant with that index, or any greater index,
-    /// exists. (In other words, it means stop.)
+    /// Enumerants are listed by index, starting from one. A value of `None`
+    /// indicates that no enumerant with that index exists.
     ///
     /// Objects are responsible for maintaining a consistently ordered and
     /// indexed list of enumerable names which can be queried by this
     /// mechanism.
-    fn get_enumerant_name(&self, index: u32) -> Option<QName<'gc>>;
+    fn get_enumerant_name(
+        &self,
+        index: u32,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Option<QName<'gc>>;
+
+    /// Find the index of the next enumerant after a given index.
+    ///
+    /// Enumeration starts by asking for the enumerant after index zero. A
+    /// value of `None` indicates that there are no further enumerants. (In
+    /// other words, it means stop.)
+    ///
+    /// Objects with sparse enumerants, such as arrays with holes, must skip
+    /// any indices that `get_enumerant_name` would not yield a name for.
+    ///
+    /// The object may change between calls, as a `for..in` loop can modify
+    /// the object it enumerates. Properties added during enumeration are
+    /// visited, and properties deleted before they are reached are not.
+    /// Deleting a property that was already visited must not cause any other
+    /// property to be skipped or visited twice.
+    fn get_next_enumerant(&self, last_index: u32) -> Option<u32>;
 
     /// Determine if a property is currently enumerable.
     ///
@@ -1108,6 +1145,11 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         None
     }
 
+    /// Get the layout of this class's instance traits.
+    fn instance_layout(self) -> Option<Gc<'gc, ClassLayout<'gc>>> {
+        None
+    }
+
     /// Get this object's `Executable`, if it has one.
     fn as_executable(&self) -> Option<Executable<'gc>> {
         None
@@ -1183,6 +1225,11 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         None
     }
 
+    /// Unwrap this object as a function object.
+    fn as_function_object(&self) -> Option<FunctionObject<'gc>> {
+        None
+    }
+
     /// Unwrap this object as a list of event handlers.
     fn as_dispatch(&self) -> Option<Ref<DispatchList<'gc>>> {
         None
@@ -1222,6 +1269,12 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         None
     }
 
+    /// Replace this object's loader stream.
+    ///
+    /// This does nothing if the object is not a loader info.
+    fn set_loader_stream(&self, _mc: MutationContext<'gc, '_>, _stream: Option<LoaderStream<'gc>>) {
+    }
+
     /// Unwrap this object's sound handle.
     fn as_sound(self) -> Option<SoundHandle> {
         None
@@ -1259,6 +1312,31 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         _new_bitmap: GcCell<'gc, BitmapData<'gc>>,
     ) {
     }
+
+    /// Unwrap this object as a Stage3D.
+    fn as_stage3d(&self) -> Option<Stage3DObject<'gc>> {
+        None
+    }
+
+    /// Unwrap this object as a Context3D.
+    fn as_context3d(&self) -> Option<Context3DObject<'gc>> {
+        None
+    }
+
+    /// Unwrap this object as a resource belonging to a Context3D.
+    fn as_context3d_resource(&self) -> Option<Context3DResourceObject<'gc>> {
+        None
+    }
+
+    /// Unwrap this object as a Matrix3D.
+    fn as_matrix3d(&self) -> Option<Matrix3DObject<'gc>> {
+        None
+    }
+
+    /// Unwrap this object as a Date.
+    fn as_date_object(&self) -> Option<DateObject<'gc>> {
+        None
+    }
 }
 
 pub enum ObjectPtr {}
@@ -14,6 +14,13 @@ use crate::{impl_avm2_custom_object, impl_avm2_custom_object_instance};
 use gc_arena::{Collect, GcCell, MutationContext};
 use std::cell::{Ref, RefMut};
 
+/// Set on the enumerant indices of an array's dynamic properties.
+///
+/// Elements and dynamic properties are numbered separately, so that changing
+/// the array's length during a `for..in` loop can't make an index that
+/// referred to a dynamic property refer to an element instead.
+pub const DYNAMIC_ENUMERANT: u32 = 1 << 31;
+
 /// A class instance allocator that allocates array objects.
 pub fn array_allocator<'gc>(
     class: Object<'gc>,
@@ -181,6 +188,61 @@ impl<'gc> TObject<'gc> for ArrayObject<'gc> {
         self.0.write(gc_context).base.delete_property(name)
     }
 
+    fn get_enumerant_name(
+        &self,
+        index: u32,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Option<QName<'gc>> {
+        // Array elements are enumerated first, in index order, followed by
+        // any dynamic properties on the array.
+        let read = self.0.read();
+        if index & DYNAMIC_ENUMERANT != 0 {
+            return read.base.get_enumerant_name(index & !DYNAMIC_ENUMERANT);
+        }
+
+        let array_index = index.checked_sub(1)? as usize;
+        read.array.get(array_index)?;
+
+        let name = activation
+            .context
+            .avm2
+            .small_strings()
+            .integer(activation.context.gc_context, array_index);
+
+        Some(QName::new(Namespace::public(), name))
+    }
+
+    fn get_next_enumerant(&self, last_index: u32) -> Option<u32> {
+        let read = self.0.read();
+
+        // The length is checked on every call, so elements pushed during
+        // enumeration are visited unless it has already moved on to the
+        // dynamic properties, and elements removed by shortening the array
+        // are not. Holes are not enumerated.
+        if last_index & DYNAMIC_ENUMERANT == 0 {
+            let length = (read.array.length() as u32).min(DYNAMIC_ENUMERANT - 1);
+            for index in last_index..length {
+                if read.array.get(index as usize).is_some() {
+                    return Some(index + 1);
+                }
+            }
+        }
+
+        read.base
+            .get_next_enumerant(last_index & !DYNAMIC_ENUMERANT)
+            .map(|index| index | DYNAMIC_ENUMERANT)
+    }
+
+    fn property_is_enumerable(&self, name: &QName<'gc>) -> bool {
+        if name.namespace().is_public() {
+            if let Ok(index) = name.local_name().parse::<usize>() {
+                return self.0.read().array.get(index).is_some();
+            }
+        }
+
+        self.0.read().base.property_is_enumerable(name)
+    }
+
     fn has_own_property(self, name: &QName<'gc>) -> Result<bool, Error> {
         if name.namespace().is_public() {
             if let Ok(index) = name.local_name().parse::<usize>() {
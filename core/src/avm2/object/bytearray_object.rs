@@ -155,6 +155,22 @@ impl<'gc> TObject<'gc> for ByteArrayObject<'gc> {
         self.0.write(gc_context).base.delete_property(name)
     }
 
+    fn get_enumerant_name(
+        &self,
+        index: u32,
+        _activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Option<QName<'gc>> {
+        self.0.read().base.get_enumerant_name(index)
+    }
+
+    fn get_next_enumerant(&self, last_index: u32) -> Option<u32> {
+        self.0.read().base.get_next_enumerant(last_index)
+    }
+
+    fn property_is_enumerable(&self, name: &QName<'gc>) -> bool {
+        self.0.read().base.property_is_enumerable(name)
+    }
+
     fn has_own_property(self, name: &QName<'gc>) -> Result<bool, Error> {
         if name.namespace().is_public() {
             if let Ok(index) = name.local_name().parse::<usize>() {
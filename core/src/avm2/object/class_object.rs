@@ -7,12 +7,13 @@ use crate::avm2::names::{Namespace, QName};
 use crate::avm2::object::function_object::FunctionObject;
 use crate::avm2::object::script_object::{scriptobject_allocator, ScriptObject, ScriptObjectData};
 use crate::avm2::object::{Object, ObjectPtr, TObject};
+use crate::avm2::property_cache::ClassLayout;
 use crate::avm2::scope::Scope;
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use crate::string::AvmString;
 use crate::{impl_avm2_custom_object, impl_avm2_custom_object_properties};
-use gc_arena::{Collect, GcCell, MutationContext};
+use gc_arena::{Collect, Gc, GcCell, MutationContext};
 use std::collections::HashMap;
 
 /// An Object which can be called to execute its function code.
@@ -41,6 +42,9 @@ pub struct ClassObjectData<'gc> {
     /// The instance allocator for this class.
     instance_allocator: Allocator,
 
+    /// The layout of this class's instance traits.
+    instance_layout: Gc<'gc, ClassLayout<'gc>>,
+
     /// The instance constructor function
     constructor: Executable<'gc>,
 
@@ -137,6 +141,9 @@ impl<'gc> ClassObject<'gc> {
             activation.context.gc_context,
         );
 
+        let instance_layout =
+            ClassLayout::new(activation.context.gc_context, class, superclass_object);
+
         let mut class_object = ClassObject(GcCell::allocate(
             activation.context.gc_context,
             ClassObjectData {
@@ -145,6 +152,7 @@ impl<'gc> ClassObject<'gc> {
                 scope,
                 superclass_object,
                 instance_allocator: Allocator(instance_allocator),
+                instance_layout,
                 constructor,
                 native_constructor,
                 params: None,
@@ -193,6 +201,7 @@ impl<'gc> ClassObject<'gc> {
         let constructor = Executable::from_method(class.read().instance_init(), scope, None, mc);
         let native_constructor =
             Executable::from_method(class.read().native_instance_init(), scope, None, mc);
+        let instance_layout = ClassLayout::new(mc, class, superclass_object);
         let mut base: Object<'gc> = ClassObject(GcCell::allocate(
             mc,
             ClassObjectData {
@@ -201,6 +210,7 @@ impl<'gc> ClassObject<'gc> {
                 scope,
                 superclass_object,
                 instance_allocator: Allocator(instance_allocator),
+                instance_layout,
                 constructor,
                 native_constructor,
                 params: None,
@@ -442,6 +452,10 @@ impl<'gc> TObject<'gc> for ClassObject<'gc> {
         Some(self.0.read().instance_allocator.0)
     }
 
+    fn instance_layout(self) -> Option<Gc<'gc, ClassLayout<'gc>>> {
+        Some(self.0.read().instance_layout)
+    }
+
     fn get_scope(self) -> Option<GcCell<'gc, Scope<'gc>>> {
         self.0.read().scope
     }
@@ -589,6 +603,11 @@ impl<'gc> TObject<'gc> for ClassObject<'gc> {
 
         let constructor = self.0.read().constructor.clone();
         let native_constructor = self.0.read().native_constructor.clone();
+        let instance_layout = ClassLayout::new(
+            activation.context.gc_context,
+            parameterized_class,
+            superclass_object,
+        );
 
         let mut class_object = ClassObject(GcCell::allocate(
             activation.context.gc_context,
@@ -598,6 +617,7 @@ impl<'gc> TObject<'gc> for ClassObject<'gc> {
                 scope,
                 superclass_object,
                 instance_allocator,
+                instance_layout,
                 constructor,
                 native_constructor,
                 params: Some(object_params[0]),
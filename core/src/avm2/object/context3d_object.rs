@@ -0,0 +1,170 @@
+//! Object representation for Context3D
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::script_object::ScriptObjectData;
+use crate::avm2::object::{Object, ObjectPtr, TObject};
+use crate::avm2::scope::Scope;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::backend::render::context3d::Context3D;
+use crate::backend::render::{RenderBackend, Transform};
+use crate::string::AvmString;
+use crate::{
+    impl_avm2_custom_object, impl_avm2_custom_object_instance, impl_avm2_custom_object_properties,
+};
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::fmt;
+
+/// A class instance allocator that allocates Context3D objects.
+pub fn context3d_allocator<'gc>(
+    class: Object<'gc>,
+    proto: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error> {
+    let base = ScriptObjectData::base_new(Some(proto), Some(class));
+
+    Ok(Context3DObject(GcCell::allocate(
+        activation.context.gc_context,
+        Context3DObjectData::new(base, None),
+    ))
+    .into())
+}
+
+#[derive(Clone, Collect, Debug, Copy)]
+#[collect(no_drop)]
+pub struct Context3DObject<'gc>(GcCell<'gc, Context3DObjectData<'gc>>);
+
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct Context3DObjectData<'gc> {
+    /// Base script object
+    base: ScriptObjectData<'gc>,
+
+    /// The renderer's context, until it's disposed.
+    #[collect(require_static)]
+    context: Option<Box<dyn Context3D>>,
+
+    /// The size of the back buffer, as last configured.
+    #[collect(require_static)]
+    back_buffer_size: (u32, u32),
+
+    enable_error_checking: bool,
+}
+
+impl<'gc> Context3DObjectData<'gc> {
+    fn new(base: ScriptObjectData<'gc>, context: Option<Box<dyn Context3D>>) -> Self {
+        Self {
+            base,
+            context,
+            back_buffer_size: (0, 0),
+            enable_error_checking: false,
+        }
+    }
+}
+
+impl fmt::Debug for Context3DObjectData<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Context3DObjectData")
+            .field("base", &self.base)
+            .field("context", &self.context.as_ref().map(|_| "Context3D"))
+            .field("back_buffer_size", &self.back_buffer_size)
+            .field("enable_error_checking", &self.enable_error_checking)
+            .finish()
+    }
+}
+
+impl<'gc> Context3DObject<'gc> {
+    /// Wrap a renderer's context in an object.
+    pub fn from_context(
+        activation: &mut Activation<'_, 'gc, '_>,
+        context: Box<dyn Context3D>,
+    ) -> Result<Object<'gc>, Error> {
+        let class = activation.avm2().classes().context3d;
+        let proto = class
+            .get_property(
+                class,
+                &QName::new(Namespace::public(), "prototype"),
+                activation,
+            )?
+            .coerce_to_object(activation)?;
+        let base = ScriptObjectData::base_new(Some(proto), Some(class));
+
+        let mut this: Object<'gc> = Context3DObject(GcCell::allocate(
+            activation.context.gc_context,
+            Context3DObjectData::new(base, Some(context)),
+        ))
+        .into();
+        this.install_instance_traits(activation, class)?;
+
+        class.call_native_init(Some(this), &[], activation, Some(class))?;
+
+        Ok(this)
+    }
+
+    /// Run a function with the renderer's context.
+    ///
+    /// Fails if the context has been disposed.
+    pub fn with_context<R>(
+        self,
+        mc: MutationContext<'gc, '_>,
+        f: impl FnOnce(&mut dyn Context3D) -> R,
+    ) -> Result<R, Error> {
+        match &mut self.0.write(mc).context {
+            Some(context) => Ok(f(context.as_mut())),
+            None => Err("Context3D has been disposed".into()),
+        }
+    }
+
+    /// Draw what the context last presented.
+    pub fn render(self, renderer: &mut dyn RenderBackend, transform: &Transform) {
+        if let Some(context) = &self.0.read().context {
+            renderer.render_context3d(context.as_ref(), transform);
+        }
+    }
+
+    pub fn dispose(self, mc: MutationContext<'gc, '_>) {
+        self.0.write(mc).context = None;
+    }
+
+    pub fn back_buffer_size(self) -> (u32, u32) {
+        self.0.read().back_buffer_size
+    }
+
+    pub fn set_back_buffer_size(self, mc: MutationContext<'gc, '_>, size: (u32, u32)) {
+        self.0.write(mc).back_buffer_size = size;
+    }
+
+    pub fn enable_error_checking(self) -> bool {
+        self.0.read().enable_error_checking
+    }
+
+    pub fn set_enable_error_checking(self, mc: MutationContext<'gc, '_>, value: bool) {
+        self.0.write(mc).enable_error_checking = value;
+    }
+}
+
+impl<'gc> TObject<'gc> for Context3DObject<'gc> {
+    impl_avm2_custom_object!(base);
+    impl_avm2_custom_object_properties!(base);
+    impl_avm2_custom_object_instance!(base);
+
+    fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error> {
+        Ok(Object::from(*self).into())
+    }
+
+    fn derive(&self, activation: &mut Activation<'_, 'gc, '_>) -> Result<Object<'gc>, Error> {
+        let base = ScriptObjectData::base_new(Some((*self).into()), None);
+
+        Ok(Context3DObject(GcCell::allocate(
+            activation.context.gc_context,
+            Context3DObjectData::new(base, None),
+        ))
+        .into())
+    }
+
+    fn as_context3d(&self) -> Option<Context3DObject<'gc>> {
+        Some(*self)
+    }
+}
@@ -0,0 +1,146 @@
+//! Object representation for the resources of a Context3D
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::script_object::ScriptObjectData;
+use crate::avm2::object::{Object, ObjectPtr, TObject};
+use crate::avm2::scope::Scope;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::backend::render::context3d::{
+    Context3DTextureFormat, IndexBuffer3DHandle, Program3DHandle, Texture3DHandle,
+    VertexBuffer3DHandle,
+};
+use crate::string::AvmString;
+use crate::{
+    impl_avm2_custom_object, impl_avm2_custom_object_instance, impl_avm2_custom_object_properties,
+};
+use gc_arena::{Collect, GcCell, MutationContext};
+
+/// A class instance allocator that allocates Context3D resource objects.
+///
+/// Resources can only be created by their context, so these objects are
+/// never associated with one.
+pub fn context3d_resource_allocator<'gc>(
+    class: Object<'gc>,
+    proto: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error> {
+    let base = ScriptObjectData::base_new(Some(proto), Some(class));
+
+    Ok(Context3DResourceObject(GcCell::allocate(
+        activation.context.gc_context,
+        Context3DResourceObjectData {
+            base,
+            context3d: None,
+            resource: None,
+        },
+    ))
+    .into())
+}
+
+/// A resource belonging to a Context3D, and what it was created with.
+#[derive(Copy, Clone, Debug)]
+pub enum Context3DResource {
+    VertexBuffer {
+        handle: VertexBuffer3DHandle,
+        num_vertices: u32,
+        data32_per_vertex: u32,
+    },
+    IndexBuffer {
+        handle: IndexBuffer3DHandle,
+        num_indices: u32,
+    },
+    Program(Program3DHandle),
+    Texture {
+        handle: Texture3DHandle,
+        width: u32,
+        height: u32,
+        format: Context3DTextureFormat,
+    },
+}
+
+#[derive(Clone, Collect, Debug, Copy)]
+#[collect(no_drop)]
+pub struct Context3DResourceObject<'gc>(GcCell<'gc, Context3DResourceObjectData<'gc>>);
+
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub struct Context3DResourceObjectData<'gc> {
+    /// Base script object
+    base: ScriptObjectData<'gc>,
+
+    /// The `Context3D` that the resource belongs to.
+    context3d: Option<Object<'gc>>,
+
+    #[collect(require_static)]
+    resource: Option<Context3DResource>,
+}
+
+impl<'gc> Context3DResourceObject<'gc> {
+    pub fn from_resource(
+        activation: &mut Activation<'_, 'gc, '_>,
+        class: Object<'gc>,
+        context3d: Object<'gc>,
+        resource: Context3DResource,
+    ) -> Result<Object<'gc>, Error> {
+        let proto = class
+            .get_property(
+                class,
+                &QName::new(Namespace::public(), "prototype"),
+                activation,
+            )?
+            .coerce_to_object(activation)?;
+        let base = ScriptObjectData::base_new(Some(proto), Some(class));
+
+        let mut this: Object<'gc> = Context3DResourceObject(GcCell::allocate(
+            activation.context.gc_context,
+            Context3DResourceObjectData {
+                base,
+                context3d: Some(context3d),
+                resource: Some(resource),
+            },
+        ))
+        .into();
+        this.install_instance_traits(activation, class)?;
+
+        class.call_native_init(Some(this), &[], activation, Some(class))?;
+
+        Ok(this)
+    }
+
+    /// The context that the resource belongs to, and the resource.
+    pub fn resource(self) -> Option<(Object<'gc>, Context3DResource)> {
+        let read = self.0.read();
+        Some((read.context3d?, read.resource?))
+    }
+}
+
+impl<'gc> TObject<'gc> for Context3DResourceObject<'gc> {
+    impl_avm2_custom_object!(base);
+    impl_avm2_custom_object_properties!(base);
+    impl_avm2_custom_object_instance!(base);
+
+    fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error> {
+        Ok(Object::from(*self).into())
+    }
+
+    fn derive(&self, activation: &mut Activation<'_, 'gc, '_>) -> Result<Object<'gc>, Error> {
+        let base = ScriptObjectData::base_new(Some((*self).into()), None);
+
+        Ok(Context3DResourceObject(GcCell::allocate(
+            activation.context.gc_context,
+            Context3DResourceObjectData {
+                base,
+                context3d: None,
+                resource: None,
+            },
+        ))
+        .into())
+    }
+
+    fn as_context3d_resource(&self) -> Option<Context3DResourceObject<'gc>> {
+        Some(*self)
+    }
+}
@@ -80,6 +80,22 @@ macro_rules! impl_avm2_custom_object_properties {
             self.0.write(gc_context).$field.delete_property(multiname)
         }
 
+        fn get_enumerant_name(
+            &self,
+            index: u32,
+            _activation: &mut Activation<'_, 'gc, '_>,
+        ) -> Option<QName<'gc>> {
+            self.0.read().$field.get_enumerant_name(index)
+        }
+
+        fn get_next_enumerant(&self, last_index: u32) -> Option<u32> {
+            self.0.read().$field.get_next_enumerant(last_index)
+        }
+
+        fn property_is_enumerable(&self, name: &QName<'gc>) -> bool {
+            self.0.read().$field.property_is_enumerable(name)
+        }
+
         fn has_own_property(self, name: &QName<'gc>) -> Result<bool, Error> {
             self.0.read().$field.has_own_property(name)
         }
@@ -183,14 +199,6 @@ macro_rules! impl_avm2_custom_object {
             self.0.write(mc).$field.set_proto(proto)
         }
 
-        fn get_enumerant_name(&self, index: u32) -> Option<QName<'gc>> {
-            self.0.read().$field.get_enumerant_name(index)
-        }
-
-        fn property_is_enumerable(&self, name: &QName<'gc>) -> bool {
-            self.0.read().$field.property_is_enumerable(name)
-        }
-
         fn as_ptr(&self) -> *const ObjectPtr {
             self.0.as_ptr() as *const ObjectPtr
         }
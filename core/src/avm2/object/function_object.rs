@@ -14,13 +14,26 @@ use crate::string::AvmString;
 use crate::{
     impl_avm2_custom_object, impl_avm2_custom_object_instance, impl_avm2_custom_object_properties,
 };
-use gc_arena::{Collect, GcCell, MutationContext};
+use gc_arena::{Collect, GcCell, GcWeakCell, MutationContext};
 
 /// An Object which can be called to execute its function code.
 #[derive(Collect, Debug, Clone, Copy)]
 #[collect(no_drop)]
 pub struct FunctionObject<'gc>(GcCell<'gc, FunctionObjectData<'gc>>);
 
+/// A weak reference to a `FunctionObject`, which does not keep the function
+/// alive.
+#[derive(Collect, Debug, Clone, Copy)]
+#[collect(no_drop)]
+pub struct FunctionObjectWeak<'gc>(GcWeakCell<'gc, FunctionObjectData<'gc>>);
+
+impl<'gc> FunctionObjectWeak<'gc> {
+    /// Retrieve the function, if it has not yet been collected.
+    pub fn upgrade(self, mc: MutationContext<'gc, '_>) -> Option<Object<'gc>> {
+        self.0.upgrade(mc).map(|cell| FunctionObject(cell).into())
+    }
+}
+
 #[derive(Collect, Debug, Clone)]
 #[collect(no_drop)]
 pub struct FunctionObjectData<'gc> {
@@ -32,6 +45,11 @@ pub struct FunctionObjectData<'gc> {
 }
 
 impl<'gc> FunctionObject<'gc> {
+    /// Create a weak reference to this function.
+    pub fn downgrade(self) -> FunctionObjectWeak<'gc> {
+        FunctionObjectWeak(GcCell::downgrade(self.0))
+    }
+
     /// Construct a function from an ABC method and the current closure scope.
     ///
     /// This associated constructor will also create and initialize an empty
@@ -122,6 +140,10 @@ impl<'gc> TObject<'gc> for FunctionObject<'gc> {
         Ok("function Function() {}".into())
     }
 
+    fn as_function_object(&self) -> Option<FunctionObject<'gc>> {
+        Some(*self)
+    }
+
     fn to_locale_string(&self, mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error> {
         self.to_string(mc)
     }
@@ -96,6 +96,28 @@ impl<'gc> LoaderInfoObject<'gc> {
         Ok(this)
     }
 
+    /// Create a loader info object for a `Loader` that hasn't loaded
+    /// anything yet.
+    pub fn not_yet_loaded(activation: &mut Activation<'_, 'gc, '_>) -> Result<Object<'gc>, Error> {
+        let class = activation.avm2().classes().loaderinfo;
+        let proto = activation.avm2().prototypes().loaderinfo;
+        let base = ScriptObjectData::base_new(Some(proto), Some(class));
+
+        let mut this: Object<'gc> = LoaderInfoObject(GcCell::allocate(
+            activation.context.gc_context,
+            LoaderInfoObjectData {
+                base,
+                loaded_stream: None,
+            },
+        ))
+        .into();
+        this.install_instance_traits(activation, class)?;
+
+        class.call_native_init(Some(this), &[], activation, Some(class))?;
+
+        Ok(this)
+    }
+
     /// Create a loader info object for the stage.
     pub fn from_stage(activation: &mut Activation<'_, 'gc, '_>) -> Result<Object<'gc>, Error> {
         let class = activation.avm2().classes().loaderinfo;
@@ -155,4 +177,8 @@ impl<'gc> TObject<'gc> for LoaderInfoObject<'gc> {
             None
         }
     }
+
+    fn set_loader_stream(&self, mc: MutationContext<'gc, '_>, stream: Option<LoaderStream<'gc>>) {
+        self.0.write(mc).loaded_stream = stream;
+    }
 }
@@ -0,0 +1,113 @@
+//! Object representation for Matrix3D
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::script_object::ScriptObjectData;
+use crate::avm2::object::{Object, ObjectPtr, TObject};
+use crate::avm2::scope::Scope;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::matrix3d::Matrix3D;
+use crate::string::AvmString;
+use crate::{
+    impl_avm2_custom_object, impl_avm2_custom_object_instance, impl_avm2_custom_object_properties,
+};
+use gc_arena::{Collect, GcCell, MutationContext};
+
+/// A class instance allocator that allocates Matrix3D objects.
+pub fn matrix3d_allocator<'gc>(
+    class: Object<'gc>,
+    proto: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error> {
+    let base = ScriptObjectData::base_new(Some(proto), Some(class));
+
+    Ok(Matrix3DObject(GcCell::allocate(
+        activation.context.gc_context,
+        Matrix3DObjectData {
+            base,
+            matrix: Matrix3D::IDENTITY,
+        },
+    ))
+    .into())
+}
+
+#[derive(Clone, Collect, Debug, Copy)]
+#[collect(no_drop)]
+pub struct Matrix3DObject<'gc>(GcCell<'gc, Matrix3DObjectData<'gc>>);
+
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub struct Matrix3DObjectData<'gc> {
+    /// Base script object
+    base: ScriptObjectData<'gc>,
+
+    /// The matrix itself.
+    #[collect(require_static)]
+    matrix: Matrix3D,
+}
+
+impl<'gc> Matrix3DObject<'gc> {
+    /// Construct a `Matrix3D` holding the given matrix.
+    pub fn from_matrix(
+        activation: &mut Activation<'_, 'gc, '_>,
+        matrix: Matrix3D,
+    ) -> Result<Object<'gc>, Error> {
+        let class = activation.avm2().classes().matrix3d;
+        let proto = class
+            .get_property(
+                class,
+                &QName::new(Namespace::public(), "prototype"),
+                activation,
+            )?
+            .coerce_to_object(activation)?;
+        let base = ScriptObjectData::base_new(Some(proto), Some(class));
+
+        let mut this: Object<'gc> = Matrix3DObject(GcCell::allocate(
+            activation.context.gc_context,
+            Matrix3DObjectData { base, matrix },
+        ))
+        .into();
+        this.install_instance_traits(activation, class)?;
+
+        class.call_native_init(Some(this), &[], activation, Some(class))?;
+
+        Ok(this)
+    }
+
+    pub fn matrix(self) -> Matrix3D {
+        self.0.read().matrix
+    }
+
+    pub fn set_matrix(self, mc: MutationContext<'gc, '_>, matrix: Matrix3D) {
+        self.0.write(mc).matrix = matrix;
+    }
+}
+
+impl<'gc> TObject<'gc> for Matrix3DObject<'gc> {
+    impl_avm2_custom_object!(base);
+    impl_avm2_custom_object_properties!(base);
+    impl_avm2_custom_object_instance!(base);
+
+    fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error> {
+        Ok(Object::from(*self).into())
+    }
+
+    fn derive(&self, activation: &mut Activation<'_, 'gc, '_>) -> Result<Object<'gc>, Error> {
+        let base = ScriptObjectData::base_new(Some((*self).into()), None);
+
+        Ok(Matrix3DObject(GcCell::allocate(
+            activation.context.gc_context,
+            Matrix3DObjectData {
+                base,
+                matrix: Matrix3D::IDENTITY,
+            },
+        ))
+        .into())
+    }
+
+    fn as_matrix3d(&self) -> Option<Matrix3DObject<'gc>> {
+        Some(*self)
+    }
+}
@@ -57,7 +57,10 @@ pub struct ScriptObjectData<'gc> {
     instance_of: Option<Object<'gc>>,
 
     /// Enumeratable property names.
-    enumerants: Vec<QName<'gc>>,
+    ///
+    /// Names that stop being enumerable leave a hole behind, so that the
+    /// indices of the names after them don't change during a `for..in` loop.
+    enumerants: Vec<Option<QName<'gc>>>,
 
     /// Interfaces implemented by this object. (classes only)
     interfaces: Vec<Object<'gc>>,
@@ -193,10 +196,18 @@ impl<'gc> TObject<'gc> for ScriptObject<'gc> {
         self.0.write(mc).set_proto(proto)
     }
 
-    fn get_enumerant_name(&self, index: u32) -> Option<QName<'gc>> {
+    fn get_enumerant_name(
+        &self,
+        index: u32,
+        _activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Option<QName<'gc>> {
         self.0.read().get_enumerant_name(index)
     }
 
+    fn get_next_enumerant(&self, last_index: u32) -> Option<u32> {
+        self.0.read().get_next_enumerant(last_index)
+    }
+
     fn property_is_enumerable(&self, name: &QName<'gc>) -> bool {
         self.0.read().property_is_enumerable(name)
     }
@@ -422,7 +433,7 @@ impl<'gc> ScriptObjectData<'gc> {
             )
         } else {
             //TODO: Not all classes are dynamic like this
-            self.enumerants.push(name.clone());
+            self.enumerants.push(Some(name.clone()));
             self.values
                 .insert(name.clone(), Property::new_dynamic_property(value));
 
@@ -451,6 +462,7 @@ impl<'gc> ScriptObjectData<'gc> {
             }
         } else {
             //TODO: Not all classes are dynamic like this
+            self.enumerants.push(Some(name.clone()));
             self.values
                 .insert(name.clone(), Property::new_dynamic_property(value));
 
@@ -478,6 +490,7 @@ impl<'gc> ScriptObjectData<'gc> {
 
         if can_delete {
             self.values.remove(name);
+            self.remove_enumerant(name);
         }
 
         can_delete
@@ -646,11 +659,37 @@ impl<'gc> ScriptObjectData<'gc> {
         // sentinel.
         let true_index = (index as usize).checked_sub(1)?;
 
-        self.enumerants.get(true_index).cloned()
+        self.enumerants.get(true_index).cloned().flatten()
+    }
+
+    pub fn get_next_enumerant(&self, last_index: u32) -> Option<u32> {
+        let position = self
+            .enumerants
+            .iter()
+            .skip(last_index as usize)
+            .position(Option::is_some)?;
+
+        Some(last_index + position as u32 + 1)
     }
 
     pub fn property_is_enumerable(&self, name: &QName<'gc>) -> bool {
-        self.enumerants.contains(name)
+        self.enumerants
+            .iter()
+            .any(|enumerant| enumerant.as_ref() == Some(name))
+    }
+
+    /// Stop enumerating a property.
+    fn remove_enumerant(&mut self, name: &QName<'gc>) {
+        for enumerant in self.enumerants.iter_mut() {
+            if enumerant.as_ref() == Some(name) {
+                *enumerant = None;
+            }
+        }
+
+        // Holes at the end can't be passed over by an enumeration.
+        while let Some(None) = self.enumerants.last() {
+            self.enumerants.pop();
+        }
     }
 
     pub fn set_local_property_is_enumerable(
@@ -663,19 +702,10 @@ impl<'gc> ScriptObjectData<'gc> {
             return Ok(());
         }
 
-        if is_enumerable && self.values.contains_key(name) && !self.enumerants.contains(name) {
-            self.enumerants.push(name.clone());
-        } else if !is_enumerable && self.enumerants.contains(name) {
-            let mut index = None;
-            for (i, other_name) in self.enumerants.iter().enumerate() {
-                if other_name == name {
-                    index = Some(i);
-                }
-            }
-
-            if let Some(index) = index {
-                self.enumerants.remove(index);
-            }
+        if is_enumerable && self.values.contains_key(name) && !self.property_is_enumerable(name) {
+            self.enumerants.push(Some(name.clone()));
+        } else if !is_enumerable {
+            self.remove_enumerant(name);
         }
 
         Ok(())
@@ -0,0 +1,145 @@
+//! Object representation for Stage3D
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::script_object::ScriptObjectData;
+use crate::avm2::object::{Object, ObjectPtr, TObject};
+use crate::avm2::scope::Scope;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::string::AvmString;
+use crate::{
+    impl_avm2_custom_object, impl_avm2_custom_object_instance, impl_avm2_custom_object_properties,
+};
+use gc_arena::{Collect, GcCell, MutationContext};
+
+/// A class instance allocator that allocates Stage3D objects.
+pub fn stage3d_allocator<'gc>(
+    class: Object<'gc>,
+    proto: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error> {
+    let base = ScriptObjectData::base_new(Some(proto), Some(class));
+
+    Ok(Stage3DObject(GcCell::allocate(
+        activation.context.gc_context,
+        Stage3DObjectData::new(base),
+    ))
+    .into())
+}
+
+#[derive(Clone, Collect, Debug, Copy)]
+#[collect(no_drop)]
+pub struct Stage3DObject<'gc>(GcCell<'gc, Stage3DObjectData<'gc>>);
+
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub struct Stage3DObjectData<'gc> {
+    /// Base script object
+    base: ScriptObjectData<'gc>,
+
+    /// The position of the context's back buffer on the stage, in pixels.
+    x: f64,
+    y: f64,
+
+    visible: bool,
+
+    /// The `Context3D` requested for this stage, once it's been created.
+    context3d: Option<Object<'gc>>,
+}
+
+impl<'gc> Stage3DObjectData<'gc> {
+    fn new(base: ScriptObjectData<'gc>) -> Self {
+        Self {
+            base,
+            x: 0.0,
+            y: 0.0,
+            visible: true,
+            context3d: None,
+        }
+    }
+}
+
+impl<'gc> Stage3DObject<'gc> {
+    /// Construct a new Stage3D, one of the fixed set that belongs to the
+    /// stage.
+    pub fn construct(activation: &mut Activation<'_, 'gc, '_>) -> Result<Object<'gc>, Error> {
+        let class = activation.avm2().classes().stage3d;
+        let proto = class
+            .get_property(
+                class,
+                &QName::new(Namespace::public(), "prototype"),
+                activation,
+            )?
+            .coerce_to_object(activation)?;
+        let base = ScriptObjectData::base_new(Some(proto), Some(class));
+
+        let mut this: Object<'gc> = Stage3DObject(GcCell::allocate(
+            activation.context.gc_context,
+            Stage3DObjectData::new(base),
+        ))
+        .into();
+        this.install_instance_traits(activation, class)?;
+
+        class.call_native_init(Some(this), &[], activation, Some(class))?;
+
+        Ok(this)
+    }
+
+    pub fn x(self) -> f64 {
+        self.0.read().x
+    }
+
+    pub fn set_x(self, mc: MutationContext<'gc, '_>, x: f64) {
+        self.0.write(mc).x = x;
+    }
+
+    pub fn y(self) -> f64 {
+        self.0.read().y
+    }
+
+    pub fn set_y(self, mc: MutationContext<'gc, '_>, y: f64) {
+        self.0.write(mc).y = y;
+    }
+
+    pub fn visible(self) -> bool {
+        self.0.read().visible
+    }
+
+    pub fn set_visible(self, mc: MutationContext<'gc, '_>, visible: bool) {
+        self.0.write(mc).visible = visible;
+    }
+
+    pub fn context3d(self) -> Option<Object<'gc>> {
+        self.0.read().context3d
+    }
+
+    pub fn set_context3d(self, mc: MutationContext<'gc, '_>, context3d: Option<Object<'gc>>) {
+        self.0.write(mc).context3d = context3d;
+    }
+}
+
+impl<'gc> TObject<'gc> for Stage3DObject<'gc> {
+    impl_avm2_custom_object!(base);
+    impl_avm2_custom_object_properties!(base);
+    impl_avm2_custom_object_instance!(base);
+
+    fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error> {
+        Ok(Object::from(*self).into())
+    }
+
+    fn derive(&self, activation: &mut Activation<'_, 'gc, '_>) -> Result<Object<'gc>, Error> {
+        let base = ScriptObjectData::base_new(Some((*self).into()), None);
+
+        Ok(Stage3DObject(GcCell::allocate(
+            activation.context.gc_context,
+            Stage3DObjectData::new(base),
+        ))
+        .into())
+    }
+
+    fn as_stage3d(&self) -> Option<Stage3DObject<'gc>> {
+        Some(*self)
+    }
+}
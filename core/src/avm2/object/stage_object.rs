@@ -258,10 +258,18 @@ impl<'gc> TObject<'gc> for StageObject<'gc> {
         self.0.write(mc).base.set_proto(proto)
     }
 
-    fn get_enumerant_name(&self, index: u32) -> Option<QName<'gc>> {
+    fn get_enumerant_name(
+        &self,
+        index: u32,
+        _activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Option<QName<'gc>> {
         self.0.read().base.get_enumerant_name(index)
     }
 
+    fn get_next_enumerant(&self, last_index: u32) -> Option<u32> {
+        self.0.read().base.get_next_enumerant(last_index)
+    }
+
     fn property_is_enumerable(&self, name: &QName<'gc>) -> bool {
         self.0.read().base.property_is_enumerable(name)
     }
@@ -3,6 +3,7 @@
 use crate::avm2::activation::Activation;
 use crate::avm2::class::Class;
 use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::array_object::DYNAMIC_ENUMERANT;
 use crate::avm2::object::script_object::ScriptObjectData;
 use crate::avm2::object::{Object, ObjectPtr, TObject};
 use crate::avm2::scope::Scope;
@@ -209,6 +210,58 @@ impl<'gc> TObject<'gc> for VectorObject<'gc> {
         self.0.write(gc_context).base.delete_property(name)
     }
 
+    fn get_enumerant_name(
+        &self,
+        index: u32,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Option<QName<'gc>> {
+        // Vector elements are enumerated first, in index order, followed by
+        // any dynamic properties on the vector.
+        let read = self.0.read();
+        if index & DYNAMIC_ENUMERANT != 0 {
+            return read.base.get_enumerant_name(index & !DYNAMIC_ENUMERANT);
+        }
+
+        let vector_index = index.checked_sub(1)? as usize;
+        if !read.vector.is_in_range(vector_index) {
+            return None;
+        }
+
+        let name = activation
+            .context
+            .avm2
+            .small_strings()
+            .integer(activation.context.gc_context, vector_index);
+
+        Some(QName::new(Namespace::package(""), name))
+    }
+
+    fn get_next_enumerant(&self, last_index: u32) -> Option<u32> {
+        let read = self.0.read();
+
+        // As with arrays, the length is checked on every call.
+        if last_index & DYNAMIC_ENUMERANT == 0 {
+            let length = (read.vector.length() as u32).min(DYNAMIC_ENUMERANT - 1);
+            if last_index < length {
+                return Some(last_index + 1);
+            }
+        }
+
+        read.base
+            .get_next_enumerant(last_index & !DYNAMIC_ENUMERANT)
+            .map(|index| index | DYNAMIC_ENUMERANT)
+    }
+
+    fn property_is_enumerable(&self, name: &QName<'gc>) -> bool {
+        if name.namespace().is_package("") {
+            if let Ok(index) = name.local_name().parse::<usize>() {
+                return self.0.read().vector.is_in_range(index);
+            }
+        }
+
+        self.0.read().base.property_is_enumerable(name)
+    }
+
     fn has_own_property(self, name: &QName<'gc>) -> Result<bool, Error> {
         if name.namespace().is_package("") {
             if let Ok(index) = name.local_name().parse::<usize>() {
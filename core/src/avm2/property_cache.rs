@@ -0,0 +1,196 @@
+//! Class trait layouts and inline property caches
+
+use crate::avm2::class::Class;
+use crate::avm2::names::{Multiname, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::property_map::PropertyMap;
+use crate::avm2::traits::TraitKind;
+use crate::avm2::Error;
+use fnv::FnvHashMap;
+use gc_arena::{Collect, Gc, GcCell, MutationContext};
+use swf::avm2::types::{AbcFile, Index, Multiname as AbcMultiname};
+
+/// Where an instance trait lives on objects of a given class.
+#[derive(Clone, Copy, Debug, Collect)]
+#[collect(require_static)]
+pub enum TraitLocation {
+    /// A variable stored in the given slot.
+    Slot(u32),
+
+    /// A const, class, or function trait stored in the given slot.
+    Const(u32),
+
+    /// A method stored at the given dispatch ID.
+    Method(u32),
+
+    /// A trait which must be accessed by name, such as an accessor, or a
+    /// trait that was not given a fixed slot or dispatch ID.
+    Named,
+}
+
+/// The instance trait layout of a class, including all inherited traits.
+///
+/// Layouts are computed once when a class is linked, so that property
+/// accesses which hit the inline cache don't have to walk the class
+/// hierarchy or the object's property map.
+#[derive(Clone, Debug, Collect)]
+#[collect(no_drop)]
+pub struct ClassLayout<'gc> {
+    /// The location of every instance trait, keyed by name.
+    locations: PropertyMap<'gc, TraitLocation>,
+
+    /// Whether or not instances of this class are sealed.
+    ///
+    /// Dynamic instances can shadow traits with dynamic properties, so
+    /// accesses to them are never cached.
+    is_sealed: bool,
+}
+
+impl<'gc> ClassLayout<'gc> {
+    /// Compute the layout of a class from its instance traits and those of
+    /// its superclass.
+    pub fn new(
+        mc: MutationContext<'gc, '_>,
+        class: GcCell<'gc, Class<'gc>>,
+        superclass_object: Option<Object<'gc>>,
+    ) -> Gc<'gc, Self> {
+        let mut locations = superclass_object
+            .and_then(|superclass| superclass.instance_layout())
+            .map(|layout| layout.locations.clone())
+            .unwrap_or_default();
+
+        let read = class.read();
+        for trait_entry in read.instance_traits() {
+            let location = match trait_entry.kind() {
+                TraitKind::Slot { slot_id, .. } if *slot_id != 0 => TraitLocation::Slot(*slot_id),
+                TraitKind::Const { slot_id, .. }
+                | TraitKind::Class { slot_id, .. }
+                | TraitKind::Function { slot_id, .. }
+                    if *slot_id != 0 =>
+                {
+                    TraitLocation::Const(*slot_id)
+                }
+                TraitKind::Method { disp_id, .. } if *disp_id != 0 => {
+                    TraitLocation::Method(*disp_id)
+                }
+                _ => TraitLocation::Named,
+            };
+
+            locations.insert(trait_entry.name().clone(), location);
+        }
+
+        Gc::allocate(
+            mc,
+            Self {
+                locations,
+                is_sealed: read.is_sealed(),
+            },
+        )
+    }
+
+    /// Look up where a trait is stored on instances of this class.
+    pub fn location(&self, name: &QName<'gc>) -> Option<TraitLocation> {
+        self.locations.get(name).copied()
+    }
+}
+
+/// A single inline cache entry.
+#[derive(Clone, Debug, Collect)]
+#[collect(no_drop)]
+pub struct CacheEntry<'gc> {
+    /// The class of the receivers this entry is valid for.
+    class: Object<'gc>,
+
+    /// The name the instruction's multiname resolved to.
+    name: QName<'gc>,
+
+    /// The class that defines the trait, used as the superclass object when
+    /// calling methods.
+    defining_class: Option<Object<'gc>>,
+
+    /// Where the trait is stored on the receiver.
+    location: TraitLocation,
+}
+
+impl<'gc> CacheEntry<'gc> {
+    /// Check if this entry applies to a given receiver.
+    pub fn matches(&self, receiver: Object<'gc>) -> bool {
+        receiver
+            .as_class_object()
+            .map(|class| Object::ptr_eq(class, self.class))
+            .unwrap_or(false)
+    }
+
+    pub fn name(&self) -> &QName<'gc> {
+        &self.name
+    }
+
+    pub fn defining_class(&self) -> Option<Object<'gc>> {
+        self.defining_class
+    }
+
+    pub fn location(&self) -> TraitLocation {
+        self.location
+    }
+}
+
+/// Monomorphic inline caches for the property access instructions of a
+/// single method, keyed by instruction offset.
+///
+/// Only instructions with static multinames are cached, since runtime
+/// multinames can resolve differently on each execution.
+#[derive(Clone, Debug, Collect, Default)]
+#[collect(no_drop)]
+pub struct PropertyCache<'gc> {
+    entries: FnvHashMap<usize, CacheEntry<'gc>>,
+}
+
+impl<'gc> PropertyCache<'gc> {
+    /// Retrieve the cache entry for the instruction at a given offset.
+    pub fn get(&self, offset: usize) -> Option<CacheEntry<'gc>> {
+        self.entries.get(&offset).cloned()
+    }
+
+    /// Record how the instruction at a given offset resolved its multiname
+    /// on a particular receiver.
+    ///
+    /// Nothing is recorded if the receiver is dynamic, the multiname is
+    /// resolved at runtime, or the name did not resolve to an instance trait.
+    pub fn fill(
+        &mut self,
+        offset: usize,
+        abc: &AbcFile,
+        index: Index<AbcMultiname>,
+        receiver: Object<'gc>,
+        name: &QName<'gc>,
+    ) -> Result<(), Error> {
+        if !Multiname::is_static_index(abc, index) {
+            return Ok(());
+        }
+
+        let class = match receiver.as_class_object() {
+            Some(class) => class,
+            None => return Ok(()),
+        };
+        let layout = match class.instance_layout() {
+            Some(layout) if layout.is_sealed => layout,
+            _ => return Ok(()),
+        };
+
+        if let Some(location) = layout.location(name) {
+            let defining_class = class.find_class_for_trait(name)?;
+
+            self.entries.insert(
+                offset,
+                CacheEntry {
+                    class,
+                    name: name.clone(),
+                    defining_class,
+                    location,
+                },
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 //! Represents AVM2 scope chain resolution.
 
 use crate::avm2::activation::Activation;
-use crate::avm2::names::Multiname;
+use crate::avm2::names::{Multiname, QName};
 use crate::avm2::object::{Object, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
@@ -16,8 +16,9 @@ pub enum ScopeClass {
     GlobalOrClosure,
 
     /// Scope represents an object added to the scope chain with `with`.
-    /// It is not inherited when closures are defined. Furthermore, a `with`
-    /// scope gains the ability to be searched for dynamic properties.
+    /// Like any other scope, it is captured by closures defined inside of the
+    /// `with` block. Unlike other scopes, properties are looked up along the
+    /// object's entire prototype chain.
     With,
 }
 
@@ -104,6 +105,34 @@ impl<'gc> Scope<'gc> {
         self.parent
     }
 
+    /// Resolve a multiname against this scope's object, yielding the name of
+    /// the property it refers to if the object has one.
+    ///
+    /// `with` scopes also search the object's entire prototype chain, so that
+    /// inherited properties of the `with` object shadow outer scopes.
+    fn resolve_local(&self, name: &Multiname<'gc>) -> Result<Option<QName<'gc>>, Error> {
+        if let Some(qname) = self.values.resolve_multiname(name)? {
+            if self.values.has_property(&qname)? {
+                return Ok(Some(qname));
+            }
+        }
+
+        if self.class == ScopeClass::With {
+            let mut proto = self.values.proto();
+            while let Some(p) = proto {
+                if let Some(qname) = p.resolve_multiname(name)? {
+                    if p.has_own_property(&qname)? {
+                        return Ok(Some(qname));
+                    }
+                }
+
+                proto = p.proto();
+            }
+        }
+
+        Ok(None)
+    }
+
     /// Find an object that contains a given property in the scope stack.
     ///
     /// This function yields `None` if no such scope exists.
@@ -112,10 +141,8 @@ impl<'gc> Scope<'gc> {
         name: &Multiname<'gc>,
         activation: &mut Activation<'_, 'gc, '_>,
     ) -> Result<Option<Object<'gc>>, Error> {
-        if let Some(qname) = self.locals().resolve_multiname(name)? {
-            if self.locals().has_property(&qname)? {
-                return Ok(Some(*self.locals()));
-            }
+        if self.resolve_local(name)?.is_some() {
+            return Ok(Some(*self.locals()));
         }
 
         if let Some(scope) = self.parent() {
@@ -142,14 +169,12 @@ impl<'gc> Scope<'gc> {
         name: &Multiname<'gc>,
         activation: &mut Activation<'_, 'gc, '_>,
     ) -> Result<Option<Value<'gc>>, Error> {
-        if let Some(qname) = self.locals().resolve_multiname(name)? {
-            if self.locals().has_property(&qname)? {
-                return Ok(Some(self.values.get_property(
-                    self.values,
-                    &qname,
-                    activation,
-                )?));
-            }
+        if let Some(qname) = self.resolve_local(name)? {
+            return Ok(Some(self.values.get_property(
+                self.values,
+                &qname,
+                activation,
+            )?));
         }
 
         if let Some(parent) = self.parent {
@@ -3,9 +3,11 @@
 use crate::avm2::activation::Activation;
 use crate::avm2::class::Class;
 use crate::avm2::domain::Domain;
+use crate::avm2::globals::construct_class;
 use crate::avm2::method::{BytecodeMethod, Method};
+use crate::avm2::names::Multiname;
 use crate::avm2::object::{DomainObject, Object, TObject};
-use crate::avm2::traits::Trait;
+use crate::avm2::traits::{Trait, TraitKind};
 use crate::avm2::value::Value;
 use crate::avm2::{Avm2, Error};
 use crate::context::UpdateContext;
@@ -15,7 +17,7 @@ use gc_arena::{Collect, Gc, GcCell, MutationContext};
 use std::cell::Ref;
 use std::mem::drop;
 use std::rc::Rc;
-use swf::avm2::types::{AbcFile, Index, Script as AbcScript};
+use swf::avm2::types::{AbcFile, Index, Multiname as AbcMultiname, Script as AbcScript};
 
 #[derive(Copy, Clone, Debug, Collect)]
 #[collect(no_drop)]
@@ -54,6 +56,9 @@ pub struct TranslationUnitData<'gc> {
 
     /// All strings loaded from the ABC's strings list.
     strings: FnvHashMap<u32, AvmString<'gc>>,
+
+    /// All static multinames loaded from the ABC's multiname list.
+    multinames: FnvHashMap<u32, Multiname<'gc>>,
 }
 
 impl<'gc> TranslationUnit<'gc> {
@@ -69,6 +74,7 @@ impl<'gc> TranslationUnit<'gc> {
                 methods: FnvHashMap::default(),
                 scripts: FnvHashMap::default(),
                 strings: FnvHashMap::default(),
+                multinames: FnvHashMap::default(),
             },
         ))
     }
@@ -161,6 +167,14 @@ impl<'gc> TranslationUnit<'gc> {
         script.load_traits(self, script_index, &mut activation)?;
 
         for traitdef in script.traits()?.iter() {
+            // As in Flash Player, the first script to define a name keeps it,
+            // and later definitions are only visible to their own script.
+            // Only this domain is checked, so a definition here shadows one
+            // of the same name in a parent domain.
+            if domain.has_local_definition(traitdef.name().clone()) {
+                continue;
+            }
+
             domain.export_definition(
                 traitdef.name().clone(),
                 script,
@@ -220,6 +234,29 @@ impl<'gc> TranslationUnit<'gc> {
             .pool_string_option(string_index, mc)?
             .unwrap_or_default())
     }
+
+    /// Load a static, or non-runtime, multiname from the ABC file's constant
+    /// pool.
+    ///
+    /// Multinames are interned, so each one is only resolved the first time
+    /// it is requested.
+    pub fn pool_multiname_static(
+        self,
+        multiname_index: Index<AbcMultiname>,
+        mc: MutationContext<'gc, '_>,
+    ) -> Result<Multiname<'gc>, Error> {
+        if let Some(multiname) = self.0.read().multinames.get(&multiname_index.0) {
+            return Ok(multiname.clone());
+        }
+
+        let multiname = Multiname::from_abc_multiname_static(self, multiname_index.clone(), mc)?;
+        self.0
+            .write(mc)
+            .multinames
+            .insert(multiname_index.0, multiname.clone());
+
+        Ok(multiname)
+    }
 }
 
 /// A loaded Script from an ABC file.
@@ -274,6 +311,22 @@ impl<'gc> Script<'gc> {
         ))
     }
 
+    /// Create a script that defines a single builtin class.
+    ///
+    /// The class object is constructed when the script is initialized, which
+    /// happens the first time the class is looked up in a domain that the
+    /// script was exported to.
+    pub fn builtin_class(
+        mc: MutationContext<'gc, '_>,
+        globals: Object<'gc>,
+        class: GcCell<'gc, Class<'gc>>,
+    ) -> Self {
+        let script = Self::empty_script(mc, globals);
+        script.0.write(mc).traits.push(Trait::from_class(class));
+
+        script
+    }
+
     /// Construct a script from a `TranslationUnit` and its script index.
     ///
     /// The returned script will be allocated, but no traits will be loaded.
@@ -368,12 +421,29 @@ impl<'gc> Script<'gc> {
             write.initialized = true;
 
             let mut globals = write.globals;
+            let is_builtin = matches!(write.init, Method::Native(_));
             let mut null_activation = Activation::from_nothing(context.reborrow());
 
             drop(write);
 
             globals.install_traits(&mut null_activation, &self.traits()?)?;
 
+            // Builtin scripts have no bytecode to construct their classes, so
+            // we construct them here instead.
+            if is_builtin {
+                let classes: Vec<_> = self
+                    .traits()?
+                    .iter()
+                    .filter_map(|t| match t.kind() {
+                        TraitKind::Class { class, .. } => Some(*class),
+                        _ => None,
+                    })
+                    .collect();
+                for class in classes {
+                    construct_class(&mut null_activation, class, globals)?;
+                }
+            }
+
             Avm2::run_script_initializer(*self, context)?;
 
             Ok(globals)
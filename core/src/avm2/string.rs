@@ -1,3 +1,90 @@
 //! AVM2 String representation
 
 pub use crate::string::AvmString;
+use gc_arena::{Collect, MutationContext};
+
+/// The number of non-negative integers that have a preallocated string.
+const SMALL_INTEGER_COUNT: u32 = 256;
+
+/// Preallocated strings for values that builtins convert to strings very
+/// often, such as single characters and array indices.
+///
+/// Handing out one of these instead of allocating a new string each time
+/// keeps hot natives from creating garbage for short-lived values.
+#[derive(Clone, Debug, Collect)]
+#[collect(no_drop)]
+pub struct SmallStrings<'gc> {
+    /// Single-character strings for each ASCII character.
+    ascii_chars: Vec<AvmString<'gc>>,
+
+    /// Decimal strings for each integer below `SMALL_INTEGER_COUNT`.
+    integers: Vec<AvmString<'gc>>,
+}
+
+impl<'gc> SmallStrings<'gc> {
+    pub fn new(mc: MutationContext<'gc, '_>) -> Self {
+        Self {
+            ascii_chars: (0..128u8)
+                .map(|c| AvmString::new(mc, char::from(c).to_string()))
+                .collect(),
+            integers: (0..SMALL_INTEGER_COUNT)
+                .map(|i| AvmString::new(mc, i.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Get a string containing a single character, allocating one only if
+    /// the character is not ASCII.
+    pub fn char(&self, mc: MutationContext<'gc, '_>, c: char) -> AvmString<'gc> {
+        match self.ascii_chars.get(c as usize) {
+            Some(s) => *s,
+            None => AvmString::new(mc, c.to_string()),
+        }
+    }
+
+    /// Get the decimal string of a non-negative integer, allocating one only
+    /// if the integer is large.
+    pub fn integer(&self, mc: MutationContext<'gc, '_>, i: usize) -> AvmString<'gc> {
+        match self.integers.get(i) {
+            Some(s) => *s,
+            None => AvmString::new(mc, i.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gc_arena::rootless_arena;
+
+    #[test]
+    fn small_strings_are_shared() {
+        rootless_arena(|mc| {
+            let strings = SmallStrings::new(mc);
+
+            assert_eq!(strings.char(mc, 'a').as_str(), "a");
+            assert!(std::ptr::eq(
+                strings.char(mc, 'a').as_str(),
+                strings.char(mc, 'a').as_str()
+            ));
+            assert_eq!(strings.integer(mc, 0).as_str(), "0");
+            assert_eq!(strings.integer(mc, 255).as_str(), "255");
+            assert!(std::ptr::eq(
+                strings.integer(mc, 255).as_str(),
+                strings.integer(mc, 255).as_str()
+            ));
+        });
+    }
+
+    #[test]
+    fn other_strings_are_allocated() {
+        rootless_arena(|mc| {
+            let strings = SmallStrings::new(mc);
+
+            assert_eq!(strings.char(mc, 'é').as_str(), "é");
+            assert_eq!(strings.char(mc, '€').as_str(), "€");
+            assert_eq!(strings.integer(mc, 256).as_str(), "256");
+            assert_eq!(strings.integer(mc, 4294967295).as_str(), "4294967295");
+        });
+    }
+}
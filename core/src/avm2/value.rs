@@ -27,7 +27,17 @@ pub enum Hint {
 
 /// An AVM2 value.
 ///
+/// Values are a plain enum, kept to four words so that they stay cheap to
+/// copy around the operand stack.
+///
 /// TODO: AVM2 also needs Scope, Namespace, and XML values.
+///
+/// TODO: Values are not NaN-boxed, and no compact tagged representation has
+/// been measured yet. NaN-boxing would hide the `Gc` pointers of strings and
+/// objects in the payload bits of a float, where `Collect` can't trace them,
+/// so each would need tracing by hand. The ignored
+/// `as3_number_arithmetic_benchmark` test is meant for comparing any such
+/// representation against this enum.
 #[derive(Clone, Collect, Debug)]
 #[collect(no_drop)]
 pub enum Value<'gc> {
@@ -496,6 +506,11 @@ impl<'gc> Value<'gc> {
                 format!("-{}", Value::Number(-n).coerce_to_string(activation)?),
             ),
             Value::Number(n) if n.is_infinite() => "Infinity".into(),
+            Value::Number(n) if n.fract() == 0.0 && *n <= u32::MAX as f64 => activation
+                .context
+                .avm2
+                .small_strings()
+                .integer(activation.context.gc_context, *n as usize),
             Value::Number(n) => {
                 let digits = n.log10().floor();
 
@@ -518,7 +533,16 @@ impl<'gc> Value<'gc> {
                     AvmString::new(activation.context.gc_context, format!("{}", n))
                 }
             }
-            Value::Unsigned(u) => AvmString::new(activation.context.gc_context, format!("{}", u)),
+            Value::Unsigned(u) => activation
+                .context
+                .avm2
+                .small_strings()
+                .integer(activation.context.gc_context, *u as usize),
+            Value::Integer(i) if *i >= 0 => activation
+                .context
+                .avm2
+                .small_strings()
+                .integer(activation.context.gc_context, *i as usize),
             Value::Integer(i) => AvmString::new(activation.context.gc_context, format!("{}", i)),
             Value::String(s) => *s,
             Value::Object(_) => self
@@ -607,7 +631,13 @@ impl<'gc> Value<'gc> {
 
         if let Ok(object) = self.coerce_to_object(activation) {
             if object.is_of_type(class, activation)? {
-                return Ok(object.into());
+                // Primitives stay unboxed, so that arithmetic on them doesn't
+                // have to look through an object.
+                return Ok(if self.is_primitive() {
+                    self.clone()
+                } else {
+                    object.into()
+                });
             }
         }
 
@@ -671,18 +701,16 @@ impl<'gc> Value<'gc> {
         activation: &mut Activation<'_, 'gc, '_>,
         type_object: Object<'gc>,
     ) -> Result<bool, Error> {
-        if let Some(type_class) = type_object.as_class() {
-            if type_class.read().name() == &QName::new(Namespace::public(), "Number") {
-                return Ok(self.is_number());
-            }
+        if Object::ptr_eq(type_object, activation.avm2().classes().number) {
+            return Ok(self.is_number());
+        }
 
-            if type_class.read().name() == &QName::new(Namespace::public(), "uint") {
-                return Ok(self.is_u32());
-            }
+        if Object::ptr_eq(type_object, activation.avm2().classes().uint) {
+            return Ok(self.is_u32());
+        }
 
-            if type_class.read().name() == &QName::new(Namespace::public(), "int") {
-                return Ok(self.is_i32());
-            }
+        if Object::ptr_eq(type_object, activation.avm2().classes().int) {
+            return Ok(self.is_i32());
         }
 
         if let Ok(o) = self.coerce_to_object(activation) {
@@ -805,3 +833,13 @@ impl<'gc> Value<'gc> {
         Ok(Some(num_self < num_other))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_is_at_most_four_words() {
+        assert!(std::mem::size_of::<Value<'static>>() <= 4 * std::mem::size_of::<usize>());
+    }
+}
@@ -0,0 +1,669 @@
+//! AVM2 bytecode verification and translation
+
+#[cfg(feature = "avm2_jit")]
+use crate::avm2::jit::JitCache;
+use crate::avm2::method::BytecodeMethod;
+use crate::avm2::value;
+use crate::avm2::Error;
+use crate::swf::extensions::ReadSwfExt;
+#[cfg(feature = "avm2_jit")]
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::rc::Rc;
+use swf::avm2::read::Reader;
+use swf::avm2::types::{Index, Op as AbcOp};
+
+/// The fewest string comparisons a chain must have to be replaced with a
+/// `StringSwitch`. Shorter chains are as fast to run one by one.
+const MIN_STRING_SWITCH_CASES: usize = 4;
+
+/// An instruction of a verified method body.
+///
+/// Numeric constant pool operands are resolved ahead of time, and branches
+/// refer to the index of the instruction they jump to rather than to a byte
+/// offset. Every other instruction is kept as it was read from the ABC file.
+#[derive(Clone, Debug)]
+pub enum Op {
+    Abc(AbcOp),
+    PushDouble {
+        value: f64,
+    },
+    PushInt {
+        value: i32,
+    },
+    PushUint {
+        value: u32,
+    },
+    Jump {
+        target: usize,
+    },
+    IfTrue {
+        target: usize,
+    },
+    IfFalse {
+        target: usize,
+    },
+    IfStrictEq {
+        target: usize,
+    },
+    IfStrictNe {
+        target: usize,
+    },
+    IfEq {
+        target: usize,
+    },
+    IfNe {
+        target: usize,
+    },
+    IfGe {
+        target: usize,
+    },
+    IfGt {
+        target: usize,
+    },
+    IfLe {
+        target: usize,
+    },
+    IfLt {
+        target: usize,
+    },
+    IfNge {
+        target: usize,
+    },
+    IfNgt {
+        target: usize,
+    },
+    IfNle {
+        target: usize,
+    },
+    IfNlt {
+        target: usize,
+    },
+    LookupSwitch {
+        default_target: usize,
+        case_targets: Vec<usize>,
+    },
+
+    /// The start of a chain of comparisons between a local register and
+    /// string constants, such as the ones compilers emit for a `switch` on a
+    /// string or a long `if`/`else if` ladder.
+    ///
+    /// If the register holds a string, execution continues wherever the
+    /// chain would have gone for it, or at `default_target` if no comparison
+    /// matches. Otherwise, the replaced instruction runs as normal: it
+    /// pushes `string` if `string_first` is set, and the register if not.
+    StringSwitch {
+        register: u32,
+        string: Index<String>,
+        string_first: bool,
+        cases: Rc<HashMap<String, usize>>,
+        default_target: usize,
+    },
+}
+
+/// A method body which has been verified and translated for execution.
+#[derive(Debug)]
+pub struct VerifiedBody {
+    /// The translated instructions of the method.
+    pub ops: Vec<Op>,
+
+    /// The byte offset each instruction started at in the original method
+    /// body.
+    pub offsets: Vec<usize>,
+
+    /// Native code compiled from this body.
+    #[cfg(feature = "avm2_jit")]
+    pub jit: RefCell<JitCache>,
+}
+
+impl VerifiedBody {
+    /// Verify and translate the body of a method.
+    ///
+    /// Instructions are read by following every path through the body from
+    /// its entry point and exception handlers, so that malformed bytecode is
+    /// rejected before any of it runs. Bytes that no path reaches are never
+    /// read, as compilers are free to leave junk there. Branch targets must
+    /// land on an instruction boundary, and local register operands must be
+    /// within the method's declared register count.
+    pub fn from_method(method: &BytecodeMethod<'_>) -> Result<Self, Error> {
+        let body = method
+            .body()
+            .ok_or("VerifyError: Cannot verify non-native method without body")?;
+        let code = &body.code[..];
+        let verify_error = |message: String| -> Error {
+            format!("VerifyError: {} in method {}", message, method.abc_method).into()
+        };
+
+        let mut entry_points = vec![0];
+        entry_points.extend(body.exceptions.iter().map(|e| e.target_offset as usize));
+        let instructions = read_reachable(code, &entry_points).map_err(verify_error)?;
+        let offsets: Vec<usize> = instructions.iter().map(|(start, _, _)| *start).collect();
+        let ends: Vec<usize> = instructions.iter().map(|(_, _, end)| *end).collect();
+
+        let check_register = |instruction: usize, register: u32| -> Result<(), Error> {
+            if register < body.num_locals {
+                Ok(())
+            } else {
+                Err(verify_error(format!(
+                    "Instruction at offset {} uses register {}, but only {} are declared",
+                    offsets[instruction], register, body.num_locals
+                )))
+            }
+        };
+
+        let mut ops = Vec::with_capacity(instructions.len());
+        for (i, (_, op, _)) in instructions.into_iter().enumerate() {
+            if let Some(branch) = translate_branch(&op, i, &offsets, &ends).map_err(verify_error)? {
+                ops.push(branch);
+                continue;
+            }
+
+            let op = match op {
+                AbcOp::PushDouble { value } => Op::PushDouble {
+                    value: value::abc_double(method.translation_unit(), value)?,
+                },
+                AbcOp::PushInt { value } => Op::PushInt {
+                    value: value::abc_int(method.translation_unit(), value)?,
+                },
+                AbcOp::PushUint { value } => Op::PushUint {
+                    value: value::abc_uint(method.translation_unit(), value)?,
+                },
+                AbcOp::GetLocal { index }
+                | AbcOp::SetLocal { index }
+                | AbcOp::Kill { index }
+                | AbcOp::IncLocal { index }
+                | AbcOp::IncLocalI { index }
+                | AbcOp::DecLocal { index }
+                | AbcOp::DecLocalI { index } => {
+                    check_register(i, index)?;
+                    Op::Abc(op)
+                }
+                AbcOp::HasNext2 {
+                    object_register,
+                    index_register,
+                } => {
+                    check_register(i, object_register)?;
+                    check_register(i, index_register)?;
+                    Op::Abc(op)
+                }
+                op => Op::Abc(op),
+            };
+
+            ops.push(op);
+        }
+
+        lower_string_switches(
+            &mut ops,
+            &method.translation_unit().abc().constant_pool.strings,
+        );
+
+        Ok(Self {
+            ops,
+            offsets,
+            #[cfg(feature = "avm2_jit")]
+            jit: Default::default(),
+        })
+    }
+}
+
+/// Read every instruction that can be reached from the given entry points,
+/// in the order they appear in the code.
+///
+/// Each instruction is yielded along with the byte offsets it starts and
+/// ends at. Branches that leave the code are not followed; they are reported
+/// by `translate_branch` instead.
+fn read_reachable(
+    code: &[u8],
+    entry_points: &[usize],
+) -> Result<Vec<(usize, AbcOp, usize)>, String> {
+    let mut reachable = BTreeMap::new();
+    let mut pending = entry_points.to_vec();
+    while let Some(start) = pending.pop() {
+        if start >= code.len() || reachable.contains_key(&start) {
+            continue;
+        }
+
+        let mut reader = Reader::new(&code[start..]);
+        let op = match reader.read_op() {
+            Ok(Some(op)) => op,
+            Ok(None) => return Err(format!("Unknown opcode at offset {}", start)),
+            Err(e) => {
+                return Err(format!(
+                    "Could not read instruction at offset {} ({})",
+                    start, e
+                ))
+            }
+        };
+        let end = start + reader.pos(&code[start..]);
+
+        for successor in successors(&op, start, end) {
+            if successor >= 0 {
+                pending.push(successor as usize);
+            }
+        }
+
+        reachable.insert(start, (op, end));
+    }
+
+    let mut instructions: Vec<(usize, AbcOp, usize)> = Vec::with_capacity(reachable.len());
+    for (start, (op, end)) in reachable {
+        if let Some((previous_start, _, previous_end)) = instructions.last() {
+            if start < *previous_end {
+                return Err(format!(
+                    "Instruction at offset {} overlaps the instruction at offset {}",
+                    start, previous_start
+                ));
+            }
+        }
+
+        instructions.push((start, op, end));
+    }
+
+    Ok(instructions)
+}
+
+/// The byte offsets execution can continue at after an instruction which
+/// starts at `start` and ends at `end`.
+fn successors(op: &AbcOp, start: usize, end: usize) -> Vec<i64> {
+    match op {
+        AbcOp::Jump { offset } => vec![end as i64 + *offset as i64],
+        AbcOp::IfTrue { offset }
+        | AbcOp::IfFalse { offset }
+        | AbcOp::IfStrictEq { offset }
+        | AbcOp::IfStrictNe { offset }
+        | AbcOp::IfEq { offset }
+        | AbcOp::IfNe { offset }
+        | AbcOp::IfGe { offset }
+        | AbcOp::IfGt { offset }
+        | AbcOp::IfLe { offset }
+        | AbcOp::IfLt { offset }
+        | AbcOp::IfNge { offset }
+        | AbcOp::IfNgt { offset }
+        | AbcOp::IfNle { offset }
+        | AbcOp::IfNlt { offset } => vec![end as i64 + *offset as i64, end as i64],
+        AbcOp::LookupSwitch {
+            default_offset,
+            case_offsets,
+        } => std::iter::once(default_offset)
+            .chain(case_offsets.iter())
+            .map(|offset| start as i64 + *offset as i64)
+            .collect(),
+        AbcOp::ReturnValue | AbcOp::ReturnVoid | AbcOp::Throw => vec![],
+        _ => vec![end as i64],
+    }
+}
+
+/// Translate the branch instruction at index `i` into one that refers to
+/// the instructions it jumps to by index.
+///
+/// Yields `None` if the instruction is not a branch.
+fn translate_branch(
+    op: &AbcOp,
+    i: usize,
+    offsets: &[usize],
+    ends: &[usize],
+) -> Result<Option<Op>, String> {
+    let target = |base: usize, offset: i32| -> Result<usize, String> {
+        let byte_offset = base as i64 + offset as i64;
+        if byte_offset >= 0 {
+            if let Ok(target) = offsets.binary_search(&(byte_offset as usize)) {
+                return Ok(target);
+            }
+        }
+
+        Err(format!(
+            "Branch at offset {} targets offset {}, which is not an instruction",
+            offsets[i], byte_offset
+        ))
+    };
+    let end = ends[i];
+
+    Ok(Some(match op {
+        AbcOp::Jump { offset } => Op::Jump {
+            target: target(end, *offset)?,
+        },
+        AbcOp::IfTrue { offset } => Op::IfTrue {
+            target: target(end, *offset)?,
+        },
+        AbcOp::IfFalse { offset } => Op::IfFalse {
+            target: target(end, *offset)?,
+        },
+        AbcOp::IfStrictEq { offset } => Op::IfStrictEq {
+            target: target(end, *offset)?,
+        },
+        AbcOp::IfStrictNe { offset } => Op::IfStrictNe {
+            target: target(end, *offset)?,
+        },
+        AbcOp::IfEq { offset } => Op::IfEq {
+            target: target(end, *offset)?,
+        },
+        AbcOp::IfNe { offset } => Op::IfNe {
+            target: target(end, *offset)?,
+        },
+        AbcOp::IfGe { offset } => Op::IfGe {
+            target: target(end, *offset)?,
+        },
+        AbcOp::IfGt { offset } => Op::IfGt {
+            target: target(end, *offset)?,
+        },
+        AbcOp::IfLe { offset } => Op::IfLe {
+            target: target(end, *offset)?,
+        },
+        AbcOp::IfLt { offset } => Op::IfLt {
+            target: target(end, *offset)?,
+        },
+        AbcOp::IfNge { offset } => Op::IfNge {
+            target: target(end, *offset)?,
+        },
+        AbcOp::IfNgt { offset } => Op::IfNgt {
+            target: target(end, *offset)?,
+        },
+        AbcOp::IfNle { offset } => Op::IfNle {
+            target: target(end, *offset)?,
+        },
+        AbcOp::IfNlt { offset } => Op::IfNlt {
+            target: target(end, *offset)?,
+        },
+        AbcOp::LookupSwitch {
+            default_offset,
+            case_offsets,
+        } => {
+            // Switch offsets are relative to the start of the instruction,
+            // not the end.
+            let start = offsets[i];
+            let mut case_targets = Vec::with_capacity(case_offsets.len());
+            for case_offset in case_offsets.iter() {
+                case_targets.push(target(start, *case_offset)?);
+            }
+
+            Op::LookupSwitch {
+                default_target: target(start, *default_offset)?,
+                case_targets,
+            }
+        }
+        _ => return Ok(None),
+    }))
+}
+
+/// A comparison between a local register and a string constant, followed by
+/// a branch on the result.
+struct StringCompare {
+    register: u32,
+    string: Index<String>,
+    string_first: bool,
+
+    /// Where execution continues if the register holds the string.
+    on_match: usize,
+
+    /// Where execution continues if it doesn't.
+    on_mismatch: usize,
+}
+
+/// Read the string comparison starting at instruction `i`, if there is one.
+fn string_compare(ops: &[Op], i: usize) -> Option<StringCompare> {
+    let (register, string, string_first) = match (ops.get(i)?, ops.get(i + 1)?) {
+        (Op::Abc(AbcOp::PushString { value }), Op::Abc(AbcOp::GetLocal { index })) => {
+            (*index, value.clone(), true)
+        }
+        (Op::Abc(AbcOp::GetLocal { index }), Op::Abc(AbcOp::PushString { value })) => {
+            (*index, value.clone(), false)
+        }
+        _ => return None,
+    };
+
+    // Equality between two strings is the same whether or not it is strict.
+    let (on_match, on_mismatch) = match ops.get(i + 2)? {
+        Op::IfStrictEq { target } | Op::IfEq { target } => (*target, i + 3),
+        Op::IfStrictNe { target } | Op::IfNe { target } => (i + 3, *target),
+        _ => return None,
+    };
+
+    Some(StringCompare {
+        register,
+        string,
+        string_first,
+        on_match,
+        on_mismatch,
+    })
+}
+
+/// Find where a branch into a `lookupswitch` ends up.
+///
+/// A `switch` on strings compiles into comparisons that each push the index
+/// of their case and jump to a `lookupswitch`. Branches that do so are
+/// followed through to the case; any other branch is left as it is.
+fn resolve_switch_target(ops: &[Op], target: usize) -> usize {
+    let index = match ops.get(target) {
+        Some(Op::Abc(AbcOp::PushByte { value })) => *value as i8 as i32,
+        Some(Op::Abc(AbcOp::PushShort { value })) => *value as i32,
+        Some(Op::PushInt { value }) => *value,
+        _ => return target,
+    };
+
+    let mut switch = target + 1;
+    if let Some(Op::Jump { target }) = ops.get(switch) {
+        switch = *target;
+    }
+
+    match ops.get(switch) {
+        Some(Op::LookupSwitch {
+            default_target,
+            case_targets,
+        }) => case_targets
+            .get(index as usize)
+            .copied()
+            .unwrap_or(*default_target),
+        _ => target,
+    }
+}
+
+/// Replace the first instruction of every long chain of string comparisons
+/// on one register with a `StringSwitch`.
+///
+/// The rest of the chain is left in place, so branches into the middle of it
+/// still work.
+fn lower_string_switches(ops: &mut [Op], strings: &[String]) {
+    let mut lowered = HashSet::new();
+
+    for i in 0..ops.len() {
+        if lowered.contains(&i) {
+            continue;
+        }
+
+        let first = match string_compare(ops, i) {
+            Some(first) => first,
+            None => continue,
+        };
+
+        let mut chain = vec![i];
+        let mut cases = HashMap::new();
+        let mut compare = string_compare(ops, i);
+        let default_target = loop {
+            let current = match compare {
+                Some(current) => current,
+                None => break None,
+            };
+
+            let string = match current.string.0 {
+                0 => "",
+                index => match strings.get(index as usize - 1) {
+                    Some(string) => string.as_str(),
+                    None => break None,
+                },
+            };
+
+            // The earliest comparison wins if a string appears twice.
+            cases
+                .entry(string.to_string())
+                .or_insert_with(|| resolve_switch_target(ops, current.on_match));
+
+            let next = current.on_mismatch;
+            match string_compare(ops, next) {
+                Some(next_compare)
+                    if next_compare.register == first.register && !chain.contains(&next) =>
+                {
+                    chain.push(next);
+                    compare = Some(next_compare);
+                }
+                _ => break Some(resolve_switch_target(ops, next)),
+            }
+        };
+
+        if let Some(default_target) = default_target {
+            if cases.len() >= MIN_STRING_SWITCH_CASES {
+                ops[i] = Op::StringSwitch {
+                    register: first.register,
+                    string: first.string,
+                    string_first: first.string_first,
+                    cases: Rc::new(cases),
+                    default_target,
+                };
+                lowered.extend(chain);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compare(string: u32, branch: Op) -> Vec<Op> {
+        vec![
+            Op::Abc(AbcOp::PushString {
+                value: Index::new(string),
+            }),
+            Op::Abc(AbcOp::GetLocal { index: 1 }),
+            branch,
+        ]
+    }
+
+    /// Read and translate every branch in some code.
+    fn branches(code: &[u8]) -> Result<Vec<(usize, Option<Op>)>, String> {
+        let instructions = read_reachable(code, &[0])?;
+        let offsets: Vec<usize> = instructions.iter().map(|(start, _, _)| *start).collect();
+        let ends: Vec<usize> = instructions.iter().map(|(_, _, end)| *end).collect();
+
+        instructions
+            .iter()
+            .enumerate()
+            .map(|(i, (start, op, _))| Ok((*start, translate_branch(op, i, &offsets, &ends)?)))
+            .collect()
+    }
+
+    #[test]
+    fn resolves_branch_targets() {
+        // nop; jump -5 (back to the nop)
+        let branches = branches(&[0x02, 0x10, 0xFB, 0xFF, 0xFF]).unwrap();
+        assert_eq!(branches.len(), 2);
+        assert!(matches!(branches[1], (1, Some(Op::Jump { target: 0 }))));
+
+        // pushtrue; iftrue +1; returnvoid; returnvoid
+        let branches = branches(&[0x26, 0x11, 0x01, 0x00, 0x00, 0x47, 0x47]).unwrap();
+        assert_eq!(branches.len(), 4);
+        assert!(matches!(branches[1], (1, Some(Op::IfTrue { target: 3 }))));
+    }
+
+    #[test]
+    fn resolves_lookupswitch_offsets_from_instruction_start() {
+        // pushbyte 0; lookupswitch 11, [12, 13]; returnvoid; returnvoid; returnvoid
+        let code = [
+            0x24, 0x00, 0x1B, 0x0B, 0x00, 0x00, 0x01, 0x0C, 0x00, 0x00, 0x0D, 0x00, 0x00, 0x47,
+            0x47, 0x47,
+        ];
+        let branches = branches(&code).unwrap();
+        let offsets: Vec<usize> = branches.iter().map(|(offset, _)| *offset).collect();
+        assert_eq!(offsets, vec![0, 2, 13, 14, 15]);
+
+        match &branches[1].1 {
+            Some(Op::LookupSwitch {
+                default_target,
+                case_targets,
+            }) => {
+                assert_eq!(*default_target, 2);
+                assert_eq!(case_targets, &vec![3, 4]);
+            }
+            op => panic!("Expected a lookupswitch, got {:?}", op),
+        }
+    }
+
+    #[test]
+    fn ignores_unreachable_code() {
+        // jump +1; <junk>; returnvoid
+        let branches = branches(&[0x10, 0x01, 0x00, 0x00, 0xFF, 0x47]).unwrap();
+        let offsets: Vec<usize> = branches.iter().map(|(offset, _)| *offset).collect();
+        assert_eq!(offsets, vec![0, 5]);
+        assert!(matches!(branches[0].1, Some(Op::Jump { target: 1 })));
+
+        // returnvoid; <junk>
+        assert_eq!(branches(&[0x47, 0xFF]).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn rejects_reachable_junk() {
+        assert!(branches(&[0xFF]).is_err());
+
+        // nop; <junk>
+        assert!(branches(&[0x02, 0xFF]).is_err());
+    }
+
+    #[test]
+    fn rejects_branches_between_instructions() {
+        // pushtrue; iftrue +1 (into the operand of the pushbyte); pushbyte 0x47; returnvoid
+        assert!(branches(&[0x26, 0x11, 0x01, 0x00, 0x00, 0x24, 0x47, 0x47]).is_err());
+
+        // jump +16, past the end of the code
+        let error = branches(&[0x10, 0x10, 0x00, 0x00, 0x47]).unwrap_err();
+        assert!(error.contains("targets offset 20"));
+    }
+
+    #[test]
+    fn lowers_string_switch() {
+        let strings: Vec<String> = ["a", "b", "c", "d"].iter().map(|s| s.to_string()).collect();
+
+        // switch (s) { case "a": case "b": case "c": case "d": ... }
+        let mut ops = Vec::new();
+        for case in 0..4 {
+            let next = ops.len() + 5;
+            ops.extend(compare(case + 1, Op::IfStrictNe { target: next }));
+            ops.push(Op::Abc(AbcOp::PushByte { value: case as u8 }));
+            ops.push(Op::Jump { target: 21 });
+        }
+        ops.push(Op::Abc(AbcOp::PushByte { value: 0xFF }));
+        ops.push(Op::LookupSwitch {
+            default_target: 26,
+            case_targets: vec![22, 23, 24, 25],
+        });
+
+        lower_string_switches(&mut ops, &strings);
+
+        match &ops[0] {
+            Op::StringSwitch {
+                register,
+                cases,
+                default_target,
+                ..
+            } => {
+                assert_eq!(*register, 1);
+                assert_eq!(cases.get("a"), Some(&22));
+                assert_eq!(cases.get("d"), Some(&25));
+                assert_eq!(*default_target, 26);
+            }
+            op => panic!("Expected a string switch, got {:?}", op),
+        }
+
+        // The rest of the chain is left alone.
+        assert!(matches!(ops[5], Op::Abc(AbcOp::PushString { .. })));
+    }
+
+    #[test]
+    fn ignores_short_chains() {
+        let strings = vec!["a".to_string()];
+        let mut ops = compare(1, Op::IfStrictNe { target: 3 });
+        ops.push(Op::Abc(AbcOp::ReturnVoid));
+
+        lower_string_switches(&mut ops, &strings);
+
+        assert!(matches!(ops[0], Op::Abc(AbcOp::PushString { .. })));
+    }
+}
@@ -0,0 +1,721 @@
+//! Background workers
+//!
+//! Every worker runs in its own AVM2 instance, with its own global domain and
+//! builtin classes, but all workers share the player's thread. A worker is
+//! brought up at the end of the frame in which it was started, and events
+//! sent between workers are delivered at the end of every frame by swapping
+//! the receiving worker's VM into the update context.
+//!
+//! Background workers run their document class constructor and the scripts
+//! of their first frame. Their timelines do not advance, and frame events are
+//! only supported in the primordial worker. Timers set by `setTimeout` and
+//! `setInterval` call back into the worker that set them.
+
+use crate::avm2::activation::Activation;
+use crate::avm2::amf::{Amf3Reader, Amf3Writer};
+use crate::avm2::domain::Domain;
+use crate::avm2::events::dispatch_event;
+use crate::avm2::globals::{message_channel_id, message_channel_object, worker_id, worker_object};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::{Avm2, Error};
+use crate::context::UpdateContext;
+use crate::display_object::{DisplayObject, MovieClip, TDisplayObject};
+use crate::tag_utils::SwfMovie;
+use crate::vminterface::Instantiator;
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The ID of the primordial worker, which runs the main movie.
+pub const PRIMORDIAL_WORKER: u32 = 0;
+
+/// A value as it is passed from one worker to another.
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+pub enum SharedValue<'gc> {
+    /// A value that is copied, encoded as AMF3.
+    Copied(Vec<u8>),
+
+    /// A shareable `ByteArray`.
+    ///
+    /// The object itself is passed to the other worker, so it keeps the
+    /// class of the worker that created it.
+    ByteArray(Object<'gc>),
+
+    /// A `MessageChannel`, by ID.
+    MessageChannel(u32),
+
+    /// A `Worker`, by ID.
+    Worker(u32),
+}
+
+/// The lifecycle of a worker.
+#[derive(Clone, Copy, Collect, Debug, PartialEq, Eq)]
+#[collect(require_static)]
+pub enum WorkerStatus {
+    /// The worker has been created, but not started.
+    New,
+
+    /// The worker will be brought up at the end of the current frame.
+    Starting,
+
+    /// The worker is running.
+    Running,
+
+    /// The worker has been terminated, or failed to start.
+    Terminated,
+}
+
+impl WorkerStatus {
+    /// The name of this status, as found in `flash.system.WorkerState`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::New | Self::Starting => "new",
+            Self::Running => "running",
+            Self::Terminated => "terminated",
+        }
+    }
+}
+
+/// The lifecycle of a message channel.
+#[derive(Clone, Copy, Collect, Debug, PartialEq, Eq)]
+#[collect(require_static)]
+pub enum ChannelStatus {
+    Open,
+
+    /// The channel was closed while messages were still queued.
+    Closing,
+
+    Closed,
+}
+
+impl ChannelStatus {
+    /// The name of this status, as found in
+    /// `flash.system.MessageChannelState`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Closing => "closing",
+            Self::Closed => "closed",
+        }
+    }
+}
+
+/// An event waiting to be delivered to a worker.
+#[derive(Clone, Copy, Collect, Debug)]
+#[collect(require_static)]
+enum WorkerEvent {
+    /// A message was sent over a channel, and should be announced to its
+    /// receiver.
+    ChannelMessage(u32),
+
+    /// A channel changed state, and should be announced to both ends.
+    ChannelState(u32),
+
+    /// A worker changed state, and should be announced to the primordial
+    /// worker.
+    WorkerState(u32),
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+struct BackgroundWorker<'gc> {
+    /// The worker's VM.
+    ///
+    /// This is `None` before the worker starts, while it is running code, and
+    /// after it is terminated.
+    vm: Option<Box<Avm2<'gc>>>,
+
+    /// The SWF that the worker runs.
+    swf: Vec<u8>,
+
+    /// The root movie clip of the worker's SWF.
+    root: Option<DisplayObject<'gc>>,
+
+    status: WorkerStatus,
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+struct Channel<'gc> {
+    /// The ID of the worker that sends messages.
+    sender: u32,
+
+    /// The ID of the worker that receives messages.
+    receiver: u32,
+
+    /// Messages that have yet to be received.
+    queue: Vec<SharedValue<'gc>>,
+
+    status: ChannelStatus,
+}
+
+/// State shared between every worker in the player.
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct WorkerRegistry<'gc> {
+    /// Background workers, by ID. The primordial worker has no entry, so
+    /// the worker with ID `n` is at index `n - 1`.
+    workers: Vec<BackgroundWorker<'gc>>,
+
+    /// Every worker's shared properties, by worker ID.
+    shared_properties: Vec<HashMap<String, SharedValue<'gc>>>,
+
+    /// Every message channel, by ID.
+    channels: Vec<Channel<'gc>>,
+
+    /// Events waiting to be delivered at the end of the frame.
+    events: Vec<WorkerEvent>,
+}
+
+impl<'gc> WorkerRegistry<'gc> {
+    fn worker(&self, id: u32) -> Option<&BackgroundWorker<'gc>> {
+        self.workers.get((id as usize).checked_sub(1)?)
+    }
+
+    fn worker_mut(&mut self, id: u32) -> Option<&mut BackgroundWorker<'gc>> {
+        self.workers.get_mut((id as usize).checked_sub(1)?)
+    }
+
+    /// Create a new worker that will run the given SWF, returning its ID.
+    pub fn create_worker(&mut self, swf: Vec<u8>) -> u32 {
+        self.workers.push(BackgroundWorker {
+            vm: None,
+            swf,
+            root: None,
+            status: WorkerStatus::New,
+        });
+        self.shared_properties.push(HashMap::new());
+
+        self.workers.len() as u32
+    }
+
+    pub fn worker_status(&self, id: u32) -> WorkerStatus {
+        match self.worker(id) {
+            Some(worker) => worker.status,
+            None if id == PRIMORDIAL_WORKER => WorkerStatus::Running,
+            None => WorkerStatus::Terminated,
+        }
+    }
+
+    /// Mark a new worker to be brought up at the end of the frame.
+    ///
+    /// Returns false if the worker was already started.
+    pub fn start_worker(&mut self, id: u32) -> bool {
+        match self.worker_mut(id) {
+            Some(worker) if worker.status == WorkerStatus::New => {
+                worker.status = WorkerStatus::Starting;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Terminate a background worker, discarding its VM.
+    ///
+    /// Returns false if the worker was not running.
+    pub fn terminate_worker(&mut self, id: u32) -> bool {
+        let was_running = match self.worker_mut(id) {
+            Some(worker) if worker.status != WorkerStatus::Terminated => {
+                let was_running = worker.status == WorkerStatus::Running;
+                worker.status = WorkerStatus::Terminated;
+                worker.vm = None;
+                worker.root = None;
+                was_running
+            }
+            _ => return false,
+        };
+
+        self.events.push(WorkerEvent::WorkerState(id));
+
+        was_running
+    }
+
+    pub fn shared_property(&self, id: u32, key: &str) -> Option<SharedValue<'gc>> {
+        self.shared_properties.get(id as usize)?.get(key).cloned()
+    }
+
+    pub fn set_shared_property(&mut self, id: u32, key: String, value: SharedValue<'gc>) {
+        if let Some(shared_properties) = self.shared_properties.get_mut(id as usize) {
+            shared_properties.insert(key, value);
+        }
+    }
+
+    /// Create a channel from one worker to another, returning its ID.
+    pub fn create_channel(&mut self, sender: u32, receiver: u32) -> u32 {
+        self.channels.push(Channel {
+            sender,
+            receiver,
+            queue: Vec::new(),
+            status: ChannelStatus::Open,
+        });
+
+        self.channels.len() as u32 - 1
+    }
+
+    pub fn channel_status(&self, id: u32) -> ChannelStatus {
+        self.channels
+            .get(id as usize)
+            .map(|channel| channel.status)
+            .unwrap_or(ChannelStatus::Closed)
+    }
+
+    pub fn message_available(&self, id: u32) -> bool {
+        self.channels
+            .get(id as usize)
+            .map(|channel| !channel.queue.is_empty())
+            .unwrap_or(false)
+    }
+
+    /// Queue a message on a channel.
+    ///
+    /// Returns false if the channel is not open.
+    pub fn send(&mut self, id: u32, message: SharedValue<'gc>) -> bool {
+        match self.channels.get_mut(id as usize) {
+            Some(channel) if channel.status == ChannelStatus::Open => {
+                channel.queue.push(message);
+                self.events.push(WorkerEvent::ChannelMessage(id));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Take the oldest message off of a channel.
+    ///
+    /// A closing channel is closed once its last message is received.
+    pub fn receive(&mut self, id: u32) -> Option<SharedValue<'gc>> {
+        let channel = self.channels.get_mut(id as usize)?;
+        if channel.queue.is_empty() {
+            return None;
+        }
+
+        let message = channel.queue.remove(0);
+        if channel.status == ChannelStatus::Closing && channel.queue.is_empty() {
+            channel.status = ChannelStatus::Closed;
+            self.events.push(WorkerEvent::ChannelState(id));
+        }
+
+        Some(message)
+    }
+
+    /// Close a channel.
+    ///
+    /// The channel stays in the `closing` state until every queued message
+    /// has been received.
+    pub fn close_channel(&mut self, id: u32) {
+        if let Some(channel) = self.channels.get_mut(id as usize) {
+            if channel.status == ChannelStatus::Open {
+                channel.status = if channel.queue.is_empty() {
+                    ChannelStatus::Closed
+                } else {
+                    ChannelStatus::Closing
+                };
+                self.events.push(WorkerEvent::ChannelState(id));
+            }
+        }
+    }
+}
+
+/// The state of the worker that an AVM2 instance runs.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct WorkerContext<'gc> {
+    /// The ID of this worker.
+    id: u32,
+
+    /// State shared with every other worker.
+    registry: GcCell<'gc, WorkerRegistry<'gc>>,
+
+    /// This worker's `Worker` objects, by worker ID.
+    workers: HashMap<u32, Object<'gc>>,
+
+    /// This worker's `MessageChannel` objects, by channel ID.
+    channels: HashMap<u32, Object<'gc>>,
+}
+
+impl<'gc> WorkerContext<'gc> {
+    /// Create the context of the primordial worker.
+    pub fn primordial(mc: MutationContext<'gc, '_>) -> Self {
+        let registry = WorkerRegistry {
+            shared_properties: vec![HashMap::new()],
+            ..Default::default()
+        };
+
+        Self::background(GcCell::allocate(mc, registry), PRIMORDIAL_WORKER)
+    }
+
+    /// Create the context of a background worker.
+    pub fn background(registry: GcCell<'gc, WorkerRegistry<'gc>>, id: u32) -> Self {
+        Self {
+            id,
+            registry,
+            workers: HashMap::new(),
+            channels: HashMap::new(),
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn registry(&self) -> GcCell<'gc, WorkerRegistry<'gc>> {
+        self.registry
+    }
+
+    pub fn worker_object(&self, id: u32) -> Option<Object<'gc>> {
+        self.workers.get(&id).copied()
+    }
+
+    pub fn set_worker_object(&mut self, id: u32, object: Object<'gc>) {
+        self.workers.insert(id, object);
+    }
+
+    pub fn channel_object(&self, id: u32) -> Option<Object<'gc>> {
+        self.channels.get(&id).copied()
+    }
+
+    pub fn set_channel_object(&mut self, id: u32, object: Object<'gc>) {
+        self.channels.insert(id, object);
+    }
+}
+
+/// Prepare a value to be passed to another worker.
+///
+/// Workers, message channels and shareable `ByteArray`s are passed by
+/// reference. Everything else is copied as AMF3.
+pub fn share_value<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<SharedValue<'gc>, Error> {
+    if let Value::Object(object) = value {
+        if let Some(id) = message_channel_id(activation, object)? {
+            return Ok(SharedValue::MessageChannel(id));
+        }
+
+        if let Some(id) = worker_id(activation, object)? {
+            return Ok(SharedValue::Worker(id));
+        }
+
+        if object
+            .as_bytearray()
+            .map(|bytearray| bytearray.shareable())
+            .unwrap_or(false)
+        {
+            return Ok(SharedValue::ByteArray(object));
+        }
+    }
+
+    let bytearray_class = activation.avm2().classes().bytearray;
+    let target = bytearray_class.construct(activation, &[])?;
+
+    let mut writer = Amf3Writer::new(target);
+    writer.write_value(activation, value)?;
+    writer.finish(activation)?;
+
+    let bytes = target
+        .as_bytearray()
+        .map(|bytearray| bytearray.bytes().clone())
+        .unwrap_or_default();
+
+    Ok(SharedValue::Copied(bytes))
+}
+
+/// Reconstruct a value that was passed from another worker.
+pub fn unshare_value<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: &SharedValue<'gc>,
+) -> Result<Value<'gc>, Error> {
+    match value {
+        SharedValue::Copied(bytes) => {
+            let bytearray_class = activation.avm2().classes().bytearray;
+            let source = bytearray_class.construct(activation, &[])?;
+
+            if let Some(mut bytearray) = source.as_bytearray_mut(activation.context.gc_context) {
+                bytearray.write_bytes(bytes)?;
+                bytearray.set_position(0);
+            }
+
+            Amf3Reader::new(source).read_value(activation)
+        }
+        SharedValue::ByteArray(object) => Ok((*object).into()),
+        SharedValue::MessageChannel(id) => Ok(message_channel_object(activation, *id)?.into()),
+        SharedValue::Worker(id) => Ok(worker_object(activation, *id)?.into()),
+    }
+}
+
+/// Run code in a worker's VM.
+///
+/// The worker's VM is swapped into the update context for the duration of the
+/// call. Returns `None` if the worker is not running.
+pub fn run_in_worker<'gc, R>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    id: u32,
+    f: impl FnOnce(&mut UpdateContext<'_, 'gc, '_>) -> R,
+) -> Option<R> {
+    if id == context.avm2.worker().id() {
+        return Some(f(context));
+    }
+
+    let registry = context.avm2.worker().registry();
+    let mut vm = registry
+        .write(context.gc_context)
+        .worker_mut(id)?
+        .vm
+        .take()?;
+
+    std::mem::swap(&mut *context.avm2, &mut *vm);
+    let result = f(context);
+    std::mem::swap(&mut *context.avm2, &mut *vm);
+
+    // The worker may have been terminated while it was running.
+    if let Some(worker) = registry.write(context.gc_context).worker_mut(id) {
+        if worker.status != WorkerStatus::Terminated {
+            worker.vm = Some(vm);
+        }
+    }
+
+    Some(result)
+}
+
+/// Load a worker's SWF and construct its document class.
+///
+/// This must be run with the worker's VM in the update context.
+fn load_worker_movie<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    swf: &[u8],
+) -> Result<DisplayObject<'gc>, Error> {
+    Avm2::load_player_globals(context)?;
+
+    let movie = Arc::new(SwfMovie::from_data(swf, None, None)?);
+
+    let mut activation = Activation::from_nothing(context.reborrow());
+    let global_domain = activation.avm2().global_domain();
+    let domain = Domain::movie_domain(&mut activation, global_domain);
+    drop(activation);
+
+    context
+        .library
+        .library_for_movie_mut(movie.clone())
+        .set_avm2_domain(domain);
+
+    let clip = MovieClip::from_movie(context.gc_context, movie);
+    let root: DisplayObject<'gc> = clip.into();
+    root.post_instantiation(context, root, None, Instantiator::Movie, false);
+
+    // Background workers are never rendered, so their morph shapes are
+    // not needed.
+    clip.preload(context, &mut fnv::FnvHashMap::default());
+
+    root.construct_frame(context);
+    root.run_frame_scripts(context);
+
+    Ok(root)
+}
+
+/// Bring up a worker that was started since the last frame.
+fn start_worker<'gc>(context: &mut UpdateContext<'_, 'gc, '_>, id: u32) {
+    let registry = context.avm2.worker().registry();
+    let swf = match registry.write(context.gc_context).worker_mut(id) {
+        Some(worker) => {
+            let vm = Avm2::for_worker(context.gc_context, registry, id);
+            worker.vm = Some(Box::new(vm));
+            worker.status = WorkerStatus::Running;
+            std::mem::take(&mut worker.swf)
+        }
+        None => return,
+    };
+
+    let result = run_in_worker(context, id, |context| load_worker_movie(context, &swf));
+
+    let mut write = registry.write(context.gc_context);
+    match result {
+        Some(Ok(root)) => {
+            // The worker may have terminated itself while it was starting.
+            if let Some(worker) = write.worker_mut(id) {
+                if worker.status == WorkerStatus::Running {
+                    worker.root = Some(root);
+                    write.events.push(WorkerEvent::WorkerState(id));
+                }
+            }
+        }
+        Some(Err(e)) => {
+            log::warn!("Could not start worker: {}", e);
+            write.terminate_worker(id);
+        }
+        None => {}
+    }
+}
+
+/// Dispatch a plain event on a worker's object for a message channel.
+fn dispatch_channel_event<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    worker: u32,
+    channel: u32,
+    event_type: &'static str,
+) {
+    let result = run_in_worker(context, worker, |context| -> Result<(), Error> {
+        let mut activation = Activation::from_nothing(context.reborrow());
+        let target = message_channel_object(&mut activation, channel)?;
+
+        let event_class = activation.avm2().classes().event;
+        let event = event_class.construct(&mut activation, &[event_type.into()])?;
+        dispatch_event(&mut activation, target, event)?;
+
+        Ok(())
+    });
+
+    if let Some(Err(e)) = result {
+        log::error!(
+            "Encountered AVM2 error when dispatching {}: {}",
+            event_type,
+            e
+        );
+    }
+}
+
+/// Dispatch `workerState` on the primordial worker's object for a worker.
+fn dispatch_worker_state<'gc>(context: &mut UpdateContext<'_, 'gc, '_>, worker: u32) {
+    let result = run_in_worker(context, PRIMORDIAL_WORKER, |context| -> Result<(), Error> {
+        let mut activation = Activation::from_nothing(context.reborrow());
+        let target = worker_object(&mut activation, worker)?;
+
+        let event_class = activation.avm2().classes().event;
+        let event = event_class.construct(&mut activation, &["workerState".into()])?;
+        dispatch_event(&mut activation, target, event)?;
+
+        Ok(())
+    });
+
+    if let Some(Err(e)) = result {
+        log::error!("Encountered AVM2 error when dispatching workerState: {}", e);
+    }
+}
+
+/// Bring up any workers that were started during this frame, then deliver
+/// every event that was sent between workers.
+///
+/// Events that are sent while these are being delivered wait for the next
+/// frame. This must be called with the primordial worker's VM in the update
+/// context.
+pub fn run_workers<'gc>(context: &mut UpdateContext<'_, 'gc, '_>) {
+    let registry = context.avm2.worker().registry();
+
+    let starting: Vec<u32> = registry
+        .read()
+        .workers
+        .iter()
+        .enumerate()
+        .filter(|(_, worker)| worker.status == WorkerStatus::Starting)
+        .map(|(index, _)| index as u32 + 1)
+        .collect();
+    for id in starting {
+        start_worker(context, id);
+    }
+
+    let events = std::mem::take(&mut registry.write(context.gc_context).events);
+    for event in events {
+        match event {
+            WorkerEvent::ChannelMessage(channel) => {
+                let receiver = registry.read().channels[channel as usize].receiver;
+                dispatch_channel_event(context, receiver, channel, "channelMessage");
+            }
+            WorkerEvent::ChannelState(channel) => {
+                let (sender, receiver) = {
+                    let read = registry.read();
+                    let channel = &read.channels[channel as usize];
+                    (channel.sender, channel.receiver)
+                };
+
+                dispatch_channel_event(context, sender, channel, "channelState");
+                if receiver != sender {
+                    dispatch_channel_event(context, receiver, channel, "channelState");
+                }
+            }
+            WorkerEvent::WorkerState(worker) => dispatch_worker_state(context, worker),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(byte: u8) -> SharedValue<'static> {
+        SharedValue::Copied(vec![byte])
+    }
+
+    fn received(registry: &mut WorkerRegistry<'static>, channel: u32) -> Option<Vec<u8>> {
+        match registry.receive(channel)? {
+            SharedValue::Copied(bytes) => Some(bytes),
+            _ => panic!("expected a copied message"),
+        }
+    }
+
+    #[test]
+    fn workers_start_once() {
+        let mut registry = WorkerRegistry::default();
+        let worker = registry.create_worker(vec![]);
+        assert_eq!(worker, 1);
+        assert_eq!(
+            registry.worker_status(PRIMORDIAL_WORKER),
+            WorkerStatus::Running
+        );
+        assert_eq!(registry.worker_status(worker), WorkerStatus::New);
+
+        assert!(registry.start_worker(worker));
+        assert!(!registry.start_worker(worker));
+        assert_eq!(registry.worker_status(worker).as_str(), "new");
+
+        // The worker never got to run, so terminating it reports false.
+        assert!(!registry.terminate_worker(worker));
+        assert_eq!(registry.worker_status(worker), WorkerStatus::Terminated);
+        assert!(!registry.terminate_worker(worker));
+        assert!(!registry.start_worker(worker));
+    }
+
+    #[test]
+    fn messages_are_received_in_order() {
+        let mut registry = WorkerRegistry::default();
+        let worker = registry.create_worker(vec![]);
+        let channel = registry.create_channel(PRIMORDIAL_WORKER, worker);
+
+        assert!(!registry.message_available(channel));
+        assert!(registry.send(channel, message(1)));
+        assert!(registry.send(channel, message(2)));
+        assert!(registry.message_available(channel));
+
+        assert_eq!(received(&mut registry, channel), Some(vec![1]));
+        assert_eq!(received(&mut registry, channel), Some(vec![2]));
+        assert_eq!(received(&mut registry, channel), None);
+    }
+
+    #[test]
+    fn closing_channel_delivers_queued_messages() {
+        let mut registry = WorkerRegistry::default();
+        let worker = registry.create_worker(vec![]);
+        let channel = registry.create_channel(PRIMORDIAL_WORKER, worker);
+
+        assert!(registry.send(channel, message(1)));
+        registry.close_channel(channel);
+        assert_eq!(registry.channel_status(channel), ChannelStatus::Closing);
+        assert!(!registry.send(channel, message(2)));
+
+        assert_eq!(received(&mut registry, channel), Some(vec![1]));
+        assert_eq!(registry.channel_status(channel), ChannelStatus::Closed);
+        assert_eq!(received(&mut registry, channel), None);
+    }
+
+    #[test]
+    fn empty_channel_closes_immediately() {
+        let mut registry = WorkerRegistry::default();
+        let worker = registry.create_worker(vec![]);
+        let channel = registry.create_channel(worker, PRIMORDIAL_WORKER);
+
+        registry.close_channel(channel);
+        assert_eq!(registry.channel_status(channel).as_str(), "closed");
+        assert!(!registry.send(channel, message(1)));
+        assert_eq!(registry.channel_status(channel + 1), ChannelStatus::Closed);
+    }
+}
@@ -13,8 +13,9 @@ pub use mp3::minimp3::Mp3Decoder;
 pub use mp3::symphonia::Mp3Decoder;
 pub use nellymoser::NellymoserDecoder;
 pub use pcm::PcmDecoder;
+pub use ruffle_audio_api::mp3_info::{FrameHeader, Mp3Info, SeekTable};
 
-use crate::tag_utils::SwfSlice;
+use super::StreamData;
 use std::io::{Cursor, Read};
 use swf::{AudioCompression, SoundFormat, TagCode};
 
@@ -98,7 +99,7 @@ struct StandardStreamDecoder {
 impl StandardStreamDecoder {
     /// Constructs a new `StandardStreamDecoder.
     /// `swf_data` should be the tag data of the MovieClip that contains the stream.
-    fn new(format: &SoundFormat, swf_data: SwfSlice) -> Result<Self, Error> {
+    fn new(format: &SoundFormat, swf_data: StreamData) -> Result<Self, Error> {
         // Create a tag reader to get the audio data from SoundStreamBlock tags.
         let tag_reader = StreamTagReader::new(format.compression, swf_data);
         // Wrap the tag reader in the decoder.
@@ -132,14 +133,14 @@ impl Iterator for StandardStreamDecoder {
 pub struct AdpcmStreamDecoder {
     format: SoundFormat,
     tag_reader: StreamTagReader,
-    decoder: AdpcmDecoder<Cursor<SwfSlice>>,
+    decoder: AdpcmDecoder<Cursor<StreamData>>,
 }
 
 impl AdpcmStreamDecoder {
-    fn new(format: &SoundFormat, swf_data: SwfSlice) -> Self {
-        let movie = swf_data.movie.clone();
+    fn new(format: &SoundFormat, swf_data: StreamData) -> Self {
+        let empty = swf_data.to_empty();
         let mut tag_reader = StreamTagReader::new(format.compression, swf_data);
-        let audio_data = tag_reader.next().unwrap_or_else(|| SwfSlice::empty(movie));
+        let audio_data = tag_reader.next().unwrap_or(empty);
         let decoder = AdpcmDecoder::new(
             Cursor::new(audio_data),
             format.is_stereo,
@@ -192,7 +193,7 @@ impl Iterator for AdpcmStreamDecoder {
 /// Generally this will return a `StandardStreamDecoder`, except for ADPCM streams.
 pub fn make_stream_decoder(
     format: &swf::SoundFormat,
-    swf_data: SwfSlice,
+    swf_data: StreamData,
 ) -> Result<Box<dyn Decoder + Send>, Error> {
     let decoder: Box<dyn Decoder + Send> = if format.compression == AudioCompression::Adpcm {
         Box::new(AdpcmStreamDecoder::new(format, swf_data))
@@ -226,18 +227,18 @@ pub trait SeekableDecoder: Decoder {
 /// audio data from the `SoundStreamBlock` tags. It can be used as an `Iterator` that
 /// will return consecutive slices of the underlying audio data.
 struct StreamTagReader {
-    swf_data: SwfSlice,
+    swf_data: StreamData,
     pos: usize,
     current_frame: u16,
-    current_audio_data: SwfSlice,
+    current_audio_data: StreamData,
     compression: AudioCompression,
 }
 
 impl StreamTagReader {
     /// Builds a new `StreamTagReader` from the given SWF data.
     /// `swf_data` should be the tag data of a MovieClip.
-    fn new(compression: AudioCompression, swf_data: SwfSlice) -> Self {
-        let current_audio_data = SwfSlice::empty(swf_data.movie.clone());
+    fn new(compression: AudioCompression, swf_data: StreamData) -> Self {
+        let current_audio_data = swf_data.to_empty();
         Self {
             swf_data,
             pos: 0,
@@ -249,7 +250,7 @@ impl StreamTagReader {
 }
 
 impl Iterator for StreamTagReader {
-    type Item = SwfSlice;
+    type Item = StreamData;
 
     fn next(&mut self) -> Option<Self::Item> {
         let current_frame = &mut self.current_frame;
@@ -271,7 +272,7 @@ impl Iterator for StreamTagReader {
                 Ok(())
             }
             TagCode::SoundStreamBlock => {
-                // TODO: Implement index ops on `SwfSlice`.
+                // TODO: Implement index ops on `StreamData`.
                 //let pos = reader.get_ref().as_ptr() as usize - swf_data.as_ref().as_ptr() as usize;
                 found = true;
                 if tag_len >= skip_len {
@@ -1,6 +1,6 @@
 #[cfg(feature = "minimp3")]
 pub mod minimp3 {
-    use crate::backend::audio::decoders::{Decoder, SeekableDecoder};
+    use crate::backend::audio::decoders::{Decoder, SeekTable, SeekableDecoder};
     use std::io::{Cursor, Read};
 
     pub struct Mp3Decoder<R: Read> {
@@ -78,6 +78,21 @@ pub mod minimp3 {
             cursor.set_position(0);
             *self = Mp3Decoder::new(self.num_channels, self.sample_rate, cursor);
         }
+
+        fn seek_to_sample_frame(&mut self, frame: u32) {
+            // Jump to a frame shortly before the target instead of decoding
+            // the whole stream up to it. This is exact for VBR streams too,
+            // since the table is built from the frame headers themselves.
+            let data = self.decoder.reader_mut().get_ref().as_ref();
+            let (offset, skip) = SeekTable::new(data).seek_point(frame).unwrap_or((0, frame));
+
+            let mut cursor = std::mem::take(self.decoder.reader_mut());
+            cursor.set_position(offset as u64);
+            *self = Mp3Decoder::new(self.num_channels, self.sample_rate, cursor);
+            for _ in 0..skip {
+                self.next();
+            }
+        }
     }
 
     impl<R: Read> Decoder for Mp3Decoder<R> {
@@ -1,6 +1,5 @@
 use super::decoders::{self, AdpcmDecoder, NellymoserDecoder, PcmDecoder, SeekableDecoder};
-use super::{SoundHandle, SoundInstanceHandle, SoundTransform};
-use crate::tag_utils::SwfSlice;
+use super::{SoundHandle, SoundInstanceHandle, SoundTransform, StreamData};
 use generational_arena::Arena;
 use std::io::Cursor;
 use std::sync::{Arc, Mutex};
@@ -244,7 +243,7 @@ impl AudioMixer {
     fn make_signal_from_stream<'a>(
         &self,
         format: &swf::SoundFormat,
-        data_stream: SwfSlice,
+        data_stream: StreamData,
     ) -> Result<Box<dyn 'a + Send + dasp::signal::Signal<Frame = [i16; 2]>>, Error> {
         // Instantiate a decoder for the compression that the sound data uses.
         let clip_stream_decoder = decoders::make_stream_decoder(format, data_stream)?;
@@ -306,10 +305,28 @@ impl AudioMixer {
 
     /// Registers a sound with the audio mixer.
     pub fn register_sound(&mut self, swf_sound: &swf::Sound) -> Result<SoundHandle, Error> {
+        let mut num_sample_frames = swf_sound.num_samples;
+
         // Slice off latency seek for MP3 data.
         let (skip_sample_frames, data) = if swf_sound.format.compression == AudioCompression::Mp3 {
-            let skip_sample_frames = u16::from_le_bytes([swf_sound.data[0], swf_sound.data[1]]);
-            (skip_sample_frames, &swf_sound.data[2..])
+            let mut skip_sample_frames = u16::from_le_bytes([swf_sound.data[0], swf_sound.data[1]]);
+            let mut data = &swf_sound.data[2..];
+
+            // Xing/Info and VBRI frames decode to silence, which would be
+            // heard as a gap each time the sound loops.
+            if let Some(info) = decoders::Mp3Info::parse(data) {
+                data = &data[info.header_frame_len()..];
+                if skip_sample_frames == 0 {
+                    if let Some(skip) = info.skip_sample_frames() {
+                        skip_sample_frames = skip.min(u16::MAX.into()) as u16;
+                    }
+                }
+                if num_sample_frames == 0 {
+                    num_sample_frames = info.num_sample_frames().unwrap_or(0);
+                }
+            }
+
+            (skip_sample_frames, data)
         } else {
             (0, swf_sound.data)
         };
@@ -317,7 +334,7 @@ impl AudioMixer {
         let sound = Sound {
             format: swf_sound.format.clone(),
             data: Arc::from(data),
-            num_sample_frames: swf_sound.num_samples,
+            num_sample_frames,
             skip_sample_frames,
         };
         Ok(self.sounds.insert(sound))
@@ -328,7 +345,7 @@ impl AudioMixer {
         &mut self,
         _stream_handle: Option<SoundHandle>,
         _clip_frame: u16,
-        clip_data: SwfSlice,
+        clip_data: StreamData,
         stream_info: &swf::SoundStreamHead,
     ) -> Result<SoundInstanceHandle, Error> {
         let format = &stream_info.stream_format;
@@ -687,7 +704,7 @@ macro_rules! impl_audio_mixer_backend {
             &mut self,
             stream_handle: Option<SoundHandle>,
             clip_frame: u16,
-            clip_data: $crate::tag_utils::SwfSlice,
+            clip_data: $crate::backend::audio::StreamData,
             stream_info: &swf::SoundStreamHead,
         ) -> Result<SoundInstanceHandle, Error> {
             self.$mixer
@@ -1,205 +1,9 @@
-use crate::matrix::Matrix;
-use crate::shape_utils::DistilledShape;
-pub use crate::{library::MovieLibrary, transform::Transform, Color};
-use downcast_rs::Downcast;
-use gc_arena::Collect;
+pub use crate::library::MovieLibrary;
+pub use ruffle_render_api::backend::*;
 use std::io::Read;
-pub use swf;
-
-pub trait RenderBackend: Downcast {
-    fn set_viewport_dimensions(&mut self, width: u32, height: u32);
-    fn register_shape(
-        &mut self,
-        shape: DistilledShape,
-        bitmap_source: &dyn BitmapSource,
-    ) -> ShapeHandle;
-    fn replace_shape(
-        &mut self,
-        shape: DistilledShape,
-        bitmap_source: &dyn BitmapSource,
-        handle: ShapeHandle,
-    );
-    fn register_glyph_shape(&mut self, shape: &swf::Glyph) -> ShapeHandle;
-    fn register_bitmap_jpeg(
-        &mut self,
-        data: &[u8],
-        jpeg_tables: Option<&[u8]>,
-    ) -> Result<BitmapInfo, Error>;
-    fn register_bitmap_jpeg_2(&mut self, data: &[u8]) -> Result<BitmapInfo, Error>;
-    fn register_bitmap_jpeg_3(
-        &mut self,
-        jpeg_data: &[u8],
-        alpha_data: &[u8],
-    ) -> Result<BitmapInfo, Error>;
-    fn register_bitmap_png(
-        &mut self,
-        swf_tag: &swf::DefineBitsLossless,
-    ) -> Result<BitmapInfo, Error>;
-
-    fn begin_frame(&mut self, clear: Color);
-    fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform, smoothing: bool);
-    fn render_shape(&mut self, shape: ShapeHandle, transform: &Transform);
-    fn draw_rect(&mut self, color: Color, matrix: &Matrix);
-    fn end_frame(&mut self);
-    fn push_mask(&mut self);
-    fn activate_mask(&mut self);
-    fn deactivate_mask(&mut self);
-    fn pop_mask(&mut self);
-
-    fn get_bitmap_pixels(&mut self, bitmap: BitmapHandle) -> Option<Bitmap>;
-    fn register_bitmap_raw(
-        &mut self,
-        width: u32,
-        height: u32,
-        rgba: Vec<u8>,
-    ) -> Result<BitmapHandle, Error>;
-    fn update_texture(
-        &mut self,
-        bitmap: BitmapHandle,
-        width: u32,
-        height: u32,
-        rgba: Vec<u8>,
-    ) -> Result<BitmapHandle, Error>;
-}
-impl_downcast!(RenderBackend);
 
 type Error = Box<dyn std::error::Error>;
 
-#[derive(Copy, Clone, Debug)]
-pub struct ShapeHandle(pub usize);
-
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Collect)]
-#[collect(no_drop)]
-pub struct BitmapHandle(pub usize);
-
-/// Info returned by the `register_bitmap` methods.
-#[derive(Copy, Clone, Debug)]
-pub struct BitmapInfo {
-    pub handle: BitmapHandle,
-    pub width: u16,
-    pub height: u16,
-}
-
-/// An object that returns a bitmap given an ID.
-///
-/// This is used by render backends to get the bitmap used in a bitmap fill.
-/// For movie libraries, this will return the bitmap with the given character ID.
-pub trait BitmapSource {
-    fn bitmap(&self, id: u16) -> Option<BitmapInfo>;
-}
-
-pub struct NullBitmapSource;
-impl BitmapSource for NullBitmapSource {
-    fn bitmap(&self, _id: u16) -> Option<BitmapInfo> {
-        None
-    }
-}
-
-pub struct NullRenderer;
-
-impl NullRenderer {
-    pub fn new() -> Self {
-        Self
-    }
-}
-
-impl Default for NullRenderer {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl RenderBackend for NullRenderer {
-    fn set_viewport_dimensions(&mut self, _width: u32, _height: u32) {}
-    fn register_shape(
-        &mut self,
-        _shape: DistilledShape,
-        _bitmap_source: &dyn BitmapSource,
-    ) -> ShapeHandle {
-        ShapeHandle(0)
-    }
-    fn replace_shape(
-        &mut self,
-        _shape: DistilledShape,
-        _bitmap_source: &dyn BitmapSource,
-        _handle: ShapeHandle,
-    ) {
-    }
-    fn register_glyph_shape(&mut self, _shape: &swf::Glyph) -> ShapeHandle {
-        ShapeHandle(0)
-    }
-    fn register_bitmap_jpeg(
-        &mut self,
-        _data: &[u8],
-        _jpeg_tables: Option<&[u8]>,
-    ) -> Result<BitmapInfo, Error> {
-        Ok(BitmapInfo {
-            handle: BitmapHandle(0),
-            width: 0,
-            height: 0,
-        })
-    }
-    fn register_bitmap_jpeg_2(&mut self, _data: &[u8]) -> Result<BitmapInfo, Error> {
-        Ok(BitmapInfo {
-            handle: BitmapHandle(0),
-            width: 0,
-            height: 0,
-        })
-    }
-    fn register_bitmap_jpeg_3(
-        &mut self,
-        _data: &[u8],
-        _alpha_data: &[u8],
-    ) -> Result<BitmapInfo, Error> {
-        Ok(BitmapInfo {
-            handle: BitmapHandle(0),
-            width: 0,
-            height: 0,
-        })
-    }
-    fn register_bitmap_png(
-        &mut self,
-        _swf_tag: &swf::DefineBitsLossless,
-    ) -> Result<BitmapInfo, Error> {
-        Ok(BitmapInfo {
-            handle: BitmapHandle(0),
-            width: 0,
-            height: 0,
-        })
-    }
-    fn begin_frame(&mut self, _clear: Color) {}
-    fn end_frame(&mut self) {}
-    fn render_bitmap(&mut self, _bitmap: BitmapHandle, _transform: &Transform, _smoothing: bool) {}
-    fn render_shape(&mut self, _shape: ShapeHandle, _transform: &Transform) {}
-    fn draw_rect(&mut self, _color: Color, _matrix: &Matrix) {}
-    fn push_mask(&mut self) {}
-    fn activate_mask(&mut self) {}
-    fn deactivate_mask(&mut self) {}
-    fn pop_mask(&mut self) {}
-
-    fn get_bitmap_pixels(&mut self, _bitmap: BitmapHandle) -> Option<Bitmap> {
-        None
-    }
-    fn register_bitmap_raw(
-        &mut self,
-        _width: u32,
-        _height: u32,
-        _rgba: Vec<u8>,
-    ) -> Result<BitmapHandle, Error> {
-        Ok(BitmapHandle(0))
-    }
-
-    fn update_texture(
-        &mut self,
-        _bitmap: BitmapHandle,
-        _width: u32,
-        _height: u32,
-        _rgba: Vec<u8>,
-    ) -> Result<BitmapHandle, Error> {
-        Ok(BitmapHandle(0))
-    }
-}
-
 /// The format of image data in a DefineBitsJpeg2/3 tag.
 /// Generally this will be JPEG, but according to SWF19, these tags can also contain PNG and GIF data.
 /// SWF19 pp.138-139
@@ -211,48 +15,6 @@ pub enum JpegTagFormat {
     Unknown,
 }
 
-/// Decoded bitmap data from an SWF tag.
-#[derive(Debug, Clone)]
-pub struct Bitmap {
-    pub width: u32,
-    pub height: u32,
-    pub data: BitmapFormat,
-}
-
-/// Decoded bitmap data from an SWF tag.
-/// The image data will have pre-multiplied alpha.
-#[derive(Debug, Clone)]
-pub enum BitmapFormat {
-    Rgb(Vec<u8>),
-    Rgba(Vec<u8>),
-}
-
-impl From<BitmapFormat> for Vec<i32> {
-    fn from(format: BitmapFormat) -> Self {
-        match format {
-            BitmapFormat::Rgb(x) => x
-                .chunks_exact(3)
-                .map(|chunk| {
-                    let red = chunk[0];
-                    let green = chunk[1];
-                    let blue = chunk[2];
-                    i32::from_le_bytes([blue, green, red, 0xFF])
-                })
-                .collect(),
-            BitmapFormat::Rgba(x) => x
-                .chunks_exact(4)
-                .map(|chunk| {
-                    let red = chunk[0];
-                    let green = chunk[1];
-                    let blue = chunk[2];
-                    let alpha = chunk[3];
-                    i32::from_le_bytes([blue, green, red, alpha])
-                })
-                .collect(),
-        }
-    }
-}
-
 /// Determines the format of the image data in `data` from a DefineBitsJPEG2/3 tag.
 pub fn determine_jpeg_tag_format(data: &[u8]) -> JpegTagFormat {
     match data {
@@ -403,137 +165,80 @@ fn rgb5_component(compressed: u16, shift: u16) -> u8 {
 /// Decodes the bitmap data in DefineBitsLossless tag into RGBA.
 /// DefineBitsLossless is Zlib encoded pixel data (similar to PNG), possibly
 /// palletized.
+///
+/// Each row of pixel data is padded to a multiple of 4 bytes. Colors in
+/// DefineBitsLossless2 tags have premultiplied alpha. Data that is missing
+/// from a truncated tag is decoded as if it were zero.
 pub fn decode_define_bits_lossless(
     swf_tag: &swf::DefineBitsLossless,
 ) -> Result<Bitmap, Box<dyn std::error::Error>> {
     // Decompress the image data (DEFLATE compression).
-    let mut decoded_data = decompress_zlib(swf_tag.data)?;
+    let decoded_data = decompress_zlib(swf_tag.data)?;
+
+    let has_alpha = match swf_tag.version {
+        1 => false,
+        2 => true,
+        _ => {
+            return Err(format!(
+                "Unexpected DefineBitsLossless{} format: {:?} ",
+                swf_tag.version, swf_tag.format,
+            )
+            .into());
+        }
+    };
+    let width = usize::from(swf_tag.width);
+    let height = usize::from(swf_tag.height);
+    let byte = |i: usize| decoded_data.get(i).copied().unwrap_or(0);
 
     // Swizzle/de-palettize the bitmap.
-    let out_data = match (swf_tag.version, swf_tag.format) {
-        (1, swf::BitmapFormat::Rgb15) => {
-            let padded_width = (swf_tag.width + 0b1) & !0b1;
-            let mut out_data: Vec<u8> =
-                Vec::with_capacity(swf_tag.width as usize * swf_tag.height as usize * 4);
-            let mut i = 0;
-            for _ in 0..swf_tag.height {
-                for _ in 0..swf_tag.width {
-                    let compressed = u16::from_be_bytes([decoded_data[i], decoded_data[i + 1]]);
-                    out_data.push(rgb5_component(compressed, 10));
-                    out_data.push(rgb5_component(compressed, 5));
-                    out_data.push(rgb5_component(compressed, 0));
-                    out_data.push(0xff);
-                    i += 2;
+    let mut out_data: Vec<u8> = Vec::with_capacity(width * height * 4);
+    match swf_tag.format {
+        swf::BitmapFormat::Rgb15 => {
+            let row_size = (width * 2 + 0b11) & !0b11;
+            for y in 0..height {
+                for x in 0..width {
+                    let i = y * row_size + x * 2;
+                    let compressed = u16::from_be_bytes([byte(i), byte(i + 1)]);
+                    out_data.extend_from_slice(&[
+                        rgb5_component(compressed, 10),
+                        rgb5_component(compressed, 5),
+                        rgb5_component(compressed, 0),
+                        0xff,
+                    ]);
                 }
-                i += (padded_width - swf_tag.width) as usize * 2;
             }
-            out_data
         }
-        (1, swf::BitmapFormat::Rgb32) => {
-            let mut i = 0;
-            while i < decoded_data.len() {
-                decoded_data[i] = decoded_data[i + 1];
-                decoded_data[i + 1] = decoded_data[i + 2];
-                decoded_data[i + 2] = decoded_data[i + 3];
-                decoded_data[i + 3] = 0xff;
-                i += 4;
+        swf::BitmapFormat::Rgb32 => {
+            for i in (0..width * height * 4).step_by(4) {
+                let alpha = if has_alpha { byte(i) } else { 0xff };
+                push_premultiplied(&mut out_data, byte(i + 1), byte(i + 2), byte(i + 3), alpha);
             }
-            decoded_data
         }
-        (2, swf::BitmapFormat::Rgb32) => {
-            let mut i = 0;
-            while i < decoded_data.len() {
-                let alpha = decoded_data[i];
-                decoded_data[i] = decoded_data[i + 1];
-                decoded_data[i + 1] = decoded_data[i + 2];
-                decoded_data[i + 2] = decoded_data[i + 3];
-                decoded_data[i + 3] = alpha;
-                i += 4;
+        swf::BitmapFormat::ColorMap8 { num_colors } => {
+            let entry_size = if has_alpha { 4 } else { 3 };
+            let palette_size = (usize::from(num_colors) + 1) * entry_size;
+
+            let mut palette = Vec::with_capacity(palette_size / entry_size * 4);
+            for i in (0..palette_size).step_by(entry_size) {
+                let alpha = if has_alpha { byte(i + 3) } else { 0xff };
+                push_premultiplied(&mut palette, byte(i), byte(i + 1), byte(i + 2), alpha);
             }
-            decoded_data
-        }
-        (1, swf::BitmapFormat::ColorMap8 { num_colors }) => {
-            let mut i = 0;
-            let padded_width = (swf_tag.width + 0b11) & !0b11;
-
-            let mut palette = Vec::with_capacity(num_colors as usize + 1);
-            for _ in 0..=num_colors {
-                palette.push(Color {
-                    r: decoded_data[i],
-                    g: decoded_data[i + 1],
-                    b: decoded_data[i + 2],
-                    a: 255,
-                });
-                i += 3;
-            }
-            let mut out_data: Vec<u8> =
-                Vec::with_capacity(swf_tag.width as usize * swf_tag.height as usize * 4);
-            for _ in 0..swf_tag.height {
-                for _ in 0..swf_tag.width {
-                    let entry = decoded_data[i] as usize;
-                    if entry < palette.len() {
-                        let color = &palette[entry];
-                        out_data.push(color.r);
-                        out_data.push(color.g);
-                        out_data.push(color.b);
-                        out_data.push(color.a);
-                    } else {
-                        out_data.push(0);
-                        out_data.push(0);
-                        out_data.push(0);
-                        out_data.push(255);
-                    }
-                    i += 1;
-                }
-                i += (padded_width - swf_tag.width) as usize;
-            }
-            out_data
-        }
-        (2, swf::BitmapFormat::ColorMap8 { num_colors }) => {
-            let mut i = 0;
-            let padded_width = (swf_tag.width + 0b11) & !0b11;
-
-            let mut palette = Vec::with_capacity(num_colors as usize + 1);
-            for _ in 0..=num_colors {
-                palette.push(Color {
-                    r: decoded_data[i],
-                    g: decoded_data[i + 1],
-                    b: decoded_data[i + 2],
-                    a: decoded_data[i + 3],
-                });
-                i += 4;
-            }
-            let mut out_data: Vec<u8> =
-                Vec::with_capacity(swf_tag.width as usize * swf_tag.height as usize * 4);
-            for _ in 0..swf_tag.height {
-                for _ in 0..swf_tag.width {
-                    let entry = decoded_data[i] as usize;
-                    if entry < palette.len() {
-                        let color = &palette[entry];
-                        out_data.push(color.r);
-                        out_data.push(color.g);
-                        out_data.push(color.b);
-                        out_data.push(color.a);
-                    } else {
-                        out_data.push(0);
-                        out_data.push(0);
-                        out_data.push(0);
-                        out_data.push(0);
-                    }
-                    i += 1;
+
+            // Out-of-range entries are black, or transparent if the bitmap
+            // has alpha.
+            let missing_color = [0, 0, 0, if has_alpha { 0 } else { 0xff }];
+            let row_size = (width + 0b11) & !0b11;
+            for y in 0..height {
+                for x in 0..width {
+                    let entry = usize::from(byte(palette_size + y * row_size + x));
+                    let color = palette
+                        .get(entry * 4..entry * 4 + 4)
+                        .unwrap_or(&missing_color);
+                    out_data.extend_from_slice(color);
                 }
-                i += (padded_width - swf_tag.width) as usize;
             }
-            out_data
-        }
-        _ => {
-            return Err(format!(
-                "Unexpected DefineBitsLossless{} format: {:?} ",
-                swf_tag.version, swf_tag.format,
-            )
-            .into());
         }
-    };
+    }
 
     Ok(Bitmap {
         width: swf_tag.width.into(),
@@ -542,6 +247,15 @@ pub fn decode_define_bits_lossless(
     })
 }
 
+/// Appends a premultiplied RGBA color to some bitmap data.
+///
+/// Color components can't be larger than alpha in a valid premultiplied
+/// color, but some encoders produce them anyway. They are clamped so that
+/// they don't blend as if they were brighter than white.
+fn push_premultiplied(out_data: &mut Vec<u8>, r: u8, g: u8, b: u8, a: u8) {
+    out_data.extend_from_slice(&[r.min(a), g.min(a), b.min(a), a]);
+}
+
 /// Decodes the bitmap data in DefineBitsLossless tag into RGBA.
 /// DefineBitsLossless is Zlib encoded pixel data (similar to PNG), possibly
 /// palletized.
@@ -584,36 +298,6 @@ pub fn decode_gif(data: &[u8]) -> Result<Bitmap, Error> {
     })
 }
 
-/// Images in SWFs are stored with premultiplied alpha.
-/// Converts RGBA premultiplied alpha to standard RBGA.
-pub fn unmultiply_alpha_rgba(rgba: &mut [u8]) {
-    rgba.chunks_exact_mut(4).for_each(|rgba| {
-        if rgba[3] > 0 {
-            let a = f32::from(rgba[3]) / 255.0;
-            rgba[0] = f32::min(f32::from(rgba[0]) / a, 255.0) as u8;
-            rgba[1] = f32::min(f32::from(rgba[1]) / a, 255.0) as u8;
-            rgba[2] = f32::min(f32::from(rgba[2]) / a, 255.0) as u8;
-        }
-    })
-}
-
-/// Converts an RGBA color from sRGB space to linear color space.
-pub fn srgb_to_linear(color: [f32; 4]) -> [f32; 4] {
-    fn to_linear_channel(n: f32) -> f32 {
-        if n <= 0.04045 {
-            n / 12.92
-        } else {
-            f32::powf((n + 0.055) / 1.055, 2.4)
-        }
-    }
-    [
-        to_linear_channel(color[0]),
-        to_linear_channel(color[1]),
-        to_linear_channel(color[2]),
-        color[3],
-    ]
-}
-
 /// Decodes zlib-compressed data.
 fn decompress_zlib(data: &[u8]) -> Result<Vec<u8>, std::io::Error> {
     let mut out_data = Vec::new();
@@ -622,3 +306,73 @@ fn decompress_zlib(data: &[u8]) -> Result<Vec<u8>, std::io::Error> {
     out_data.shrink_to_fit();
     Ok(out_data)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use std::io::Write;
+
+    fn decode_lossless(version: u8, format: swf::BitmapFormat, width: u16, data: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let tag = swf::DefineBitsLossless {
+            version,
+            id: 1,
+            format,
+            width,
+            height: 2,
+            data: &compressed,
+        };
+        match decode_define_bits_lossless(&tag).unwrap().data {
+            BitmapFormat::Rgba(data) => data,
+            BitmapFormat::Rgb(_) => panic!("Expected RGBA bitmap data"),
+        }
+    }
+
+    #[test]
+    fn lossless_color_map_skips_row_padding() {
+        let data = [
+            0xff, 0, 0, // Palette entry 0: red
+            0, 0, 0xff, // Palette entry 1: blue
+            0, 1, 0, 0, // Row 0, padded to 4 bytes
+            1, 5, 0, 0, // Row 1, with an out-of-range entry
+        ];
+        let rgba = decode_lossless(1, swf::BitmapFormat::ColorMap8 { num_colors: 1 }, 2, &data);
+        assert_eq!(
+            rgba,
+            [
+                0xff, 0, 0, 0xff, 0, 0, 0xff, 0xff, // Row 0
+                0, 0, 0xff, 0xff, 0, 0, 0, 0xff, // Row 1
+            ]
+        );
+    }
+
+    #[test]
+    fn lossless_rgb15_skips_row_padding() {
+        let data = [
+            0x7c, 0x00, 0, 0, // Row 0: red, padded to 4 bytes
+            0x00, 0x1f, 0, 0, // Row 1: blue, padded to 4 bytes
+        ];
+        let rgba = decode_lossless(1, swf::BitmapFormat::Rgb15, 1, &data);
+        assert_eq!(rgba, [0xff, 0, 0, 0xff, 0, 0, 0xff, 0xff]);
+    }
+
+    #[test]
+    fn lossless2_clamps_premultiplied_colors() {
+        let data = [
+            0x80, 0x80, 0x40, 0xff, // Blue is larger than alpha
+            0x00, 0x10, 0x10, 0x10, // Fully transparent
+        ];
+        let rgba = decode_lossless(2, swf::BitmapFormat::Rgb32, 1, &data);
+        assert_eq!(rgba, [0x80, 0x40, 0x80, 0x80, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn lossless_truncated_data_is_zero() {
+        let rgba = decode_lossless(1, swf::BitmapFormat::Rgb32, 1, &[0, 0x10, 0x20, 0x30]);
+        assert_eq!(rgba, [0x10, 0x20, 0x30, 0xff, 0, 0, 0, 0xff]);
+    }
+}
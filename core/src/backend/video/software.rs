@@ -1,8 +1,8 @@
 //! Pure software video decoding backend.
 
-use crate::backend::render::{BitmapHandle, BitmapInfo, RenderBackend};
+use crate::backend::render::{BitmapHandle, BitmapInfo, RenderBackend, Yuv420Frame};
 use crate::backend::video::{
-    DecodedFrame, EncodedFrame, Error, FrameDependency, VideoBackend, VideoStreamHandle,
+    EncodedFrame, Error, FrameDependency, VideoBackend, VideoStreamHandle,
 };
 use generational_arena::Arena;
 use swf::{VideoCodec, VideoDeblocking};
@@ -71,21 +71,49 @@ impl VideoBackend for SoftwareVideoBackend {
             .ok_or("Unregistered video stream")?;
 
         let frame = stream.decoder.decode_frame(encoded_frame)?;
-        let handle = if let Some(bitmap) = stream.bitmap {
-            renderer.update_texture(bitmap, frame.width.into(), frame.height.into(), frame.rgba)?
+        let handle = if renderer.supports_yuv420() {
+            // The renderer converts the planes itself, usually on the GPU.
+            if let Some(bitmap) = stream.bitmap {
+                renderer.update_texture_yuv420(bitmap, frame)?
+            } else {
+                renderer.register_bitmap_yuv420(frame)?
+            }
         } else {
-            renderer.register_bitmap_raw(frame.width.into(), frame.height.into(), frame.rgba)?
+            let rgba = yuv420_to_rgba(&frame);
+            if let Some(bitmap) = stream.bitmap {
+                renderer.update_texture(bitmap, frame.width, frame.height, rgba)?
+            } else {
+                renderer.register_bitmap_raw(frame.width, frame.height, rgba)?
+            }
         };
         stream.bitmap = Some(handle);
 
         Ok(BitmapInfo {
             handle,
-            width: frame.width,
-            height: frame.height,
+            width: frame.width as u16,
+            height: frame.height as u16,
         })
     }
 }
 
+/// Convert a decoded frame to RGBA on the CPU, for renderers that can't
+/// upload YUV frames.
+#[cfg(feature = "h263")]
+fn yuv420_to_rgba(frame: &Yuv420Frame<'_>) -> Vec<u8> {
+    h263_rs_yuv::bt601::yuv420_to_rgba(
+        frame.y,
+        frame.u,
+        frame.v,
+        frame.width as usize,
+        frame.chroma_width as usize,
+    )
+}
+
+#[cfg(not(feature = "h263"))]
+fn yuv420_to_rgba(_frame: &Yuv420Frame<'_>) -> Vec<u8> {
+    unreachable!("No video decoders are enabled")
+}
+
 /// A single preloaded video stream.
 struct VideoStream {
     bitmap: Option<BitmapHandle>,
@@ -126,18 +154,19 @@ trait VideoDecoder {
     /// Frames may be decoded in any order that does not violate the frame
     /// dependencies declared by the output of `preload_video_stream_frame`.
     ///
-    /// The decoded frame should be returned. An `Error` can be returned if
-    /// a drawable bitmap can not be produced.
-    fn decode_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<DecodedFrame, Error>;
+    /// The decoded frame should be returned, borrowing its planes from the
+    /// decoder. An `Error` can be returned if a drawable bitmap can not be
+    /// produced.
+    fn decode_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<Yuv420Frame<'_>, Error>;
 }
 
 #[cfg(feature = "h263")]
 mod h263 {
+    use crate::backend::render::Yuv420Frame;
     use crate::backend::video::software::VideoDecoder;
-    use crate::backend::video::{DecodedFrame, EncodedFrame, Error, FrameDependency};
+    use crate::backend::video::{EncodedFrame, Error, FrameDependency};
     use h263_rs::parser::H263Reader;
     use h263_rs::{DecoderOption, H263State, PictureTypeCode};
-    use h263_rs_yuv::bt601::yuv420_to_rgba;
 
     /// H263 video decoder.
     pub struct H263Decoder(H263State);
@@ -167,7 +196,10 @@ mod h263 {
             }
         }
 
-        fn decode_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<DecodedFrame, Error> {
+        fn decode_frame(
+            &mut self,
+            encoded_frame: EncodedFrame<'_>,
+        ) -> Result<Yuv420Frame<'_>, Error> {
             let mut reader = H263Reader::from_source(encoded_frame.data());
 
             self.0.decode_next_picture(&mut reader)?;
@@ -183,11 +215,13 @@ mod h263 {
                 .ok_or("H.263 decoder error!")?;
             let chroma_width = picture.chroma_samples_per_row();
             let (y, b, r) = picture.as_yuv();
-            let rgba = yuv420_to_rgba(y, b, r, width.into(), chroma_width);
-            Ok(DecodedFrame {
-                width,
-                height,
-                rgba,
+            Ok(Yuv420Frame {
+                width: width.into(),
+                height: height.into(),
+                y,
+                u: b,
+                v: r,
+                chroma_width: chroma_width as u32,
             })
         }
     }
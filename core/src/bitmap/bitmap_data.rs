@@ -1,12 +1,17 @@
-use gc_arena::Collect;
+use gc_arena::{Collect, GcCell};
 
 use crate::avm2::{Object as Avm2Object, Value as Avm2Value};
-use crate::backend::render::{BitmapHandle, RenderBackend};
+use crate::backend::render::{Bitmap, BitmapFormat, BitmapHandle, RenderBackend};
 use crate::bitmap::color_transform_params::ColorTransformParams;
+use crate::bitmap::filter::FilterImage;
 use crate::bitmap::turbulence::Turbulence;
+use crate::context::{RenderContext, UpdateContext};
+use crate::display_object::{DisplayObject, TDisplayObject};
+use crate::transform::{Transform, TransformStack};
 use bitflags::bitflags;
 use downcast_rs::__std::fmt::Formatter;
 use std::ops::Range;
+use swf::BlendMode;
 
 /// An implementation of the Lehmer/Park-Miller random number generator
 /// Uses the fixed parameters m = 2,147,483,647 and a = 16,807
@@ -93,6 +98,99 @@ impl Color {
         let a = source.alpha() + ((self.alpha() as u16 * (255 - sa as u16)) >> 8) as u8;
         Self::argb(a, r, g, b)
     }
+
+    /// Blend a color onto this one with one of Flash's blend modes. Both
+    /// colors are premultiplied.
+    pub fn blend(&self, source: &Self, blend_mode: BlendMode) -> Self {
+        fn hard_light(s: f32, sa: f32, d: f32, da: f32) -> f32 {
+            let uncovered = s * (1.0 - da) + d * (1.0 - sa);
+            if 2.0 * s <= sa {
+                2.0 * s * d + uncovered
+            } else {
+                sa * da - 2.0 * (da - d) * (sa - s) + uncovered
+            }
+        }
+
+        let sa = f32::from(source.alpha()) / 255.0;
+        let da = f32::from(self.alpha()) / 255.0;
+        let alpha = match blend_mode {
+            BlendMode::Invert => da,
+            BlendMode::Alpha => da * sa,
+            BlendMode::Erase => da * (1.0 - sa),
+            _ => sa + da - sa * da,
+        };
+
+        let channel = |s: u8, d: u8| {
+            let s = f32::from(s) / 255.0;
+            let d = f32::from(d) / 255.0;
+            let uncovered = s * (1.0 - da) + d * (1.0 - sa);
+            let value = match blend_mode {
+                BlendMode::Normal | BlendMode::Layer => s + d * (1.0 - sa),
+                BlendMode::Multiply => s * d + uncovered,
+                BlendMode::Screen => s + d - s * d,
+                BlendMode::Lighten => (s * da).max(d * sa) + uncovered,
+                BlendMode::Darken => (s * da).min(d * sa) + uncovered,
+                BlendMode::Difference => s + d - 2.0 * (s * da).min(d * sa),
+                BlendMode::Add => s + d,
+                BlendMode::Subtract => d - s,
+                BlendMode::Invert => (da - d) * sa + d * (1.0 - sa),
+                BlendMode::Alpha => d * sa,
+                BlendMode::Erase => d * (1.0 - sa),
+                BlendMode::Overlay => hard_light(d, da, s, sa),
+                BlendMode::HardLight => hard_light(s, sa, d, da),
+            };
+            // A premultiplied channel can't be brighter than its alpha.
+            (value.max(0.0).min(alpha) * 255.0).round() as u8
+        };
+
+        Self::argb(
+            (alpha * 255.0).round() as u8,
+            channel(source.red(), self.red()),
+            channel(source.green(), self.green()),
+            channel(source.blue(), self.blue()),
+        )
+    }
+}
+
+/// Get the blend mode with the given ActionScript name, such as `"multiply"`.
+pub fn blend_mode_from_name(name: &str) -> Option<BlendMode> {
+    Some(match name {
+        "normal" => BlendMode::Normal,
+        "layer" => BlendMode::Layer,
+        "multiply" => BlendMode::Multiply,
+        "screen" => BlendMode::Screen,
+        "lighten" => BlendMode::Lighten,
+        "darken" => BlendMode::Darken,
+        "difference" => BlendMode::Difference,
+        "add" => BlendMode::Add,
+        "subtract" => BlendMode::Subtract,
+        "invert" => BlendMode::Invert,
+        "alpha" => BlendMode::Alpha,
+        "erase" => BlendMode::Erase,
+        "overlay" => BlendMode::Overlay,
+        "hardlight" => BlendMode::HardLight,
+        _ => return None,
+    })
+}
+
+/// Get the ActionScript name of a blend mode, such as `"multiply"`.
+pub fn blend_mode_name(blend_mode: BlendMode) -> &'static str {
+    match blend_mode {
+        BlendMode::Normal => "normal",
+        BlendMode::Layer => "layer",
+        BlendMode::Multiply => "multiply",
+        BlendMode::Screen => "screen",
+        BlendMode::Lighten => "lighten",
+        BlendMode::Darken => "darken",
+        BlendMode::Difference => "difference",
+        BlendMode::Add => "add",
+        BlendMode::Subtract => "subtract",
+        BlendMode::Invert => "invert",
+        BlendMode::Alpha => "alpha",
+        BlendMode::Erase => "erase",
+        BlendMode::Overlay => "overlay",
+        BlendMode::HardLight => "hardlight",
+    }
 }
 
 impl std::fmt::Display for Color {
@@ -129,6 +227,43 @@ bitflags! {
     }
 }
 
+/// How `BitmapData.threshold` compares pixels to its threshold.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ThresholdOperation {
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl ThresholdOperation {
+    /// Get the operation with the given ActionScript name, such as `"<="`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "<" => Self::LessThan,
+            "<=" => Self::LessThanOrEqual,
+            ">" => Self::GreaterThan,
+            ">=" => Self::GreaterThanOrEqual,
+            "==" => Self::Equal,
+            "!=" => Self::NotEqual,
+            _ => return None,
+        })
+    }
+
+    pub fn matches(self, value: u32, threshold: u32) -> bool {
+        match self {
+            Self::LessThan => value < threshold,
+            Self::LessThanOrEqual => value <= threshold,
+            Self::GreaterThan => value > threshold,
+            Self::GreaterThanOrEqual => value >= threshold,
+            Self::Equal => value == threshold,
+            Self::NotEqual => value != threshold,
+        }
+    }
+}
+
 #[derive(Clone, Collect, Default, Debug)]
 #[collect(no_drop)]
 pub struct BitmapData<'gc> {
@@ -143,6 +278,7 @@ pub struct BitmapData<'gc> {
     ///
     /// This is lazily initialized; a value of `None` indicates that
     /// initialization has not yet happened.
+    #[collect(require_static)]
     bitmap_handle: Option<BitmapHandle>,
 
     /// The AVM2 side of this `BitmapData`.
@@ -184,6 +320,29 @@ impl<'gc> BitmapData<'gc> {
         self.bitmap_handle
     }
 
+    /// Get the bitmap handle for this data, first uploading the pixels to the
+    /// renderer if they've changed.
+    pub fn update_bitmap_handle(
+        &mut self,
+        renderer: &mut dyn RenderBackend,
+    ) -> Option<BitmapHandle> {
+        let registered = self.bitmap_handle.is_some();
+        let bitmap_handle = self.bitmap_handle(renderer)?;
+        if self.dirty {
+            if registered {
+                let _ = renderer.update_texture(
+                    bitmap_handle,
+                    self.width(),
+                    self.height(),
+                    self.pixels_rgba(),
+                );
+            }
+            self.dirty = false;
+        }
+
+        Some(bitmap_handle)
+    }
+
     pub fn transparency(&self) -> bool {
         self.transparency
     }
@@ -284,16 +443,145 @@ impl<'gc> BitmapData<'gc> {
         }
     }
 
-    pub fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32, color: Color) {
-        for x_offset in 0..width {
-            for y_offset in 0..height {
-                self.set_pixel32((x + x_offset) as i32, (y + y_offset) as i32, color)
+    /// Clip a rectangle to the bounds of this bitmap, giving the left, top,
+    /// right and bottom edges of what's left of it.
+    fn clip_rect(&self, x: i32, y: i32, width: i32, height: i32) -> (u32, u32, u32, u32) {
+        let clamp_x = |x: i32| x.max(0).min(self.width() as i32) as u32;
+        let clamp_y = |y: i32| y.max(0).min(self.height() as i32) as u32;
+        let left = clamp_x(x);
+        let top = clamp_y(y);
+        let right = clamp_x(x.saturating_add(width.max(0))).max(left);
+        let bottom = clamp_y(y.saturating_add(height.max(0))).max(top);
+        (left, top, right, bottom)
+    }
+
+    /// The unmultiplied ARGB colors of the pixels in a rectangle, row by row.
+    /// Parts of the rectangle outside of the bitmap are left out.
+    pub fn get_pixels_argb(&self, x: i32, y: i32, width: i32, height: i32) -> Vec<u32> {
+        let (left, top, right, bottom) = self.clip_rect(x, y, width, height);
+        let mut colors = Vec::with_capacity(((right - left) * (bottom - top)) as usize);
+        for y in top..bottom {
+            for x in left..right {
+                colors.push(self.get_pixel32(x as i32, y as i32).into());
+            }
+        }
+        colors
+    }
+
+    /// Set the pixels in a rectangle, row by row, from unmultiplied ARGB
+    /// colors. Parts of the rectangle outside of the bitmap are left out.
+    ///
+    /// Returns how many pixels were set, which is less than the size of the
+    /// rectangle if `colors` runs out first.
+    pub fn set_pixels_argb(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        colors: impl IntoIterator<Item = u32>,
+    ) -> usize {
+        let (left, top, right, bottom) = self.clip_rect(x, y, width, height);
+        let mut colors = colors.into_iter();
+        let mut count = 0;
+        for y in top..bottom {
+            for x in left..right {
+                match colors.next() {
+                    Some(color) => self.set_pixel32(x as i32, y as i32, (color as i32).into()),
+                    None => return count,
+                }
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// How many pixels of a rectangle are inside of this bitmap.
+    pub fn clipped_rect_len(&self, x: i32, y: i32, width: i32, height: i32) -> usize {
+        let (left, top, right, bottom) = self.clip_rect(x, y, width, height);
+        ((right - left) * (bottom - top)) as usize
+    }
+
+    /// Whether the pixel at a point has at least the given alpha.
+    pub fn hit_test_point(&self, alpha_threshold: u32, x: i32, y: i32) -> bool {
+        if !self.is_point_in_bounds(x, y) {
+            return false;
+        }
+        let alpha = self.get_pixel_raw(x as u32, y as u32).unwrap().alpha();
+        u32::from(alpha) >= alpha_threshold
+    }
+
+    /// Whether any pixel in a rectangle has at least the given alpha.
+    pub fn hit_test_rectangle(
+        &self,
+        alpha_threshold: u32,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    ) -> bool {
+        let (left, top, right, bottom) = self.clip_rect(x, y, width, height);
+        (top..bottom).any(|y| {
+            (left..right).any(|x| {
+                let alpha = self.get_pixel_raw(x, y).unwrap().alpha();
+                u32::from(alpha) >= alpha_threshold
+            })
+        })
+    }
+
+    /// Whether this bitmap overlaps another one, with each placed at its
+    /// point. Pixels only overlap if both have at least the alpha threshold
+    /// of their bitmap.
+    pub fn hit_test_bitmap(
+        &self,
+        position: (i32, i32),
+        alpha_threshold: u32,
+        other: &Self,
+        other_position: (i32, i32),
+        other_alpha_threshold: u32,
+    ) -> bool {
+        // The position of the other bitmap, relative to this one.
+        let offset_x = other_position.0 - position.0;
+        let offset_y = other_position.1 - position.1;
+        let (left, top, right, bottom) = self.clip_rect(
+            offset_x,
+            offset_y,
+            other.width() as i32,
+            other.height() as i32,
+        );
+        (top..bottom).any(|y| {
+            (left..right).any(|x| {
+                self.hit_test_point(alpha_threshold, x as i32, y as i32)
+                    && other.hit_test_point(
+                        other_alpha_threshold,
+                        x as i32 - offset_x,
+                        y as i32 - offset_y,
+                    )
+            })
+        })
+    }
+
+    /// Fill a rectangle with an unmultiplied color. Parts of the rectangle
+    /// outside of the bitmap are left out.
+    pub fn fill_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: Color) {
+        let (left, top, right, bottom) = self.clip_rect(x, y, width, height);
+        let color = color.to_premultiplied_alpha(self.transparency());
+        for y in top..bottom {
+            for x in left..right {
+                self.set_pixel32_raw(x, y, color);
             }
         }
     }
 
     pub fn flood_fill(&mut self, x: u32, y: u32, replace_color: Color) {
-        let expected_color = self.get_pixel_raw(x, y).unwrap_or_else(|| 0.into());
+        let expected_color = match self.get_pixel_raw(x, y) {
+            Some(color) => color,
+            None => return,
+        };
+        if expected_color == replace_color {
+            // Nothing would change, and we'd never stop finding pixels to fill.
+            return;
+        }
 
         let mut pending = vec![(x, y)];
 
@@ -473,6 +761,68 @@ impl<'gc> BitmapData<'gc> {
         }
     }
 
+    /// Blend premultiplied RGBA pixels, laid out like the pixels of this
+    /// bitmap, onto it. Only the pixels inside the given bounds are changed.
+    pub fn blend_rgba(
+        &mut self,
+        rgba: &[u8],
+        blend_mode: BlendMode,
+        min_x: u32,
+        min_y: u32,
+        end_x: u32,
+        end_y: u32,
+    ) {
+        let width = self.width();
+        if rgba.len() < self.pixels.len() * 4 {
+            log::warn!("Not enough pixels to blend onto bitmap");
+            return;
+        }
+
+        for y in min_y..end_y.min(self.height()) {
+            for x in min_x..end_x.min(width) {
+                let i = (x + y * width) as usize * 4;
+                let source = Color::argb(rgba[i + 3], rgba[i], rgba[i + 1], rgba[i + 2]);
+                let dest = self.get_pixel_raw(x, y).unwrap_or_else(|| 0.into());
+                let mut color = dest.blend(&source, blend_mode);
+                if !self.transparency() {
+                    color = color.with_alpha(255);
+                }
+                self.set_pixel32_raw(x, y, color);
+            }
+        }
+    }
+
+    /// Copy a rectangle of pixels out of this bitmap to be filtered. Pixels
+    /// outside of the bitmap are transparent.
+    pub fn filter_image(&self, x: i32, y: i32, width: u32, height: u32) -> FilterImage {
+        let mut image = FilterImage::new(width, height);
+        for image_y in 0..height as i32 {
+            for image_x in 0..width as i32 {
+                if let Some(color) = self.get_pixel_raw((x + image_x) as u32, (y + image_y) as u32)
+                {
+                    image.set_color(image_x, image_y, color);
+                }
+            }
+        }
+        image
+    }
+
+    /// Copy filtered pixels into this bitmap, with their top-left corner at
+    /// the given point.
+    pub fn set_filter_image(&mut self, image: &FilterImage, x: i32, y: i32) {
+        for image_y in 0..image.height() as i32 {
+            for image_x in 0..image.width() as i32 {
+                if self.is_point_in_bounds(x + image_x, y + image_y) {
+                    let mut color = image.color(image_x, image_y);
+                    if !self.transparency() {
+                        color = color.with_alpha(255);
+                    }
+                    self.set_pixel32_raw((x + image_x) as u32, (y + image_y) as u32, color);
+                }
+            }
+        }
+    }
+
     pub fn color_bounds_rect(
         &self,
         find_color: bool,
@@ -514,12 +864,19 @@ impl<'gc> BitmapData<'gc> {
         }
     }
 
+    /// Copy a rectangle of pixels from another bitmap.
+    ///
+    /// If there's an alpha source, the copied pixels take their alpha from
+    /// its bitmap, starting at its point. With `merge_alpha`, the copied
+    /// pixels are blended over the ones already in this bitmap, rather than
+    /// replacing them.
     pub fn copy_pixels(
         &mut self,
         source_bitmap: &Self,
         src_rect: (i32, i32, i32, i32),
         dest_point: (i32, i32),
-        alpha_source: Option<(&Self, (i32, i32), bool)>,
+        alpha_source: Option<(&Self, (i32, i32))>,
+        merge_alpha: bool,
     ) {
         let (src_min_x, src_min_y, src_width, src_height) = src_rect;
         let (dest_min_x, dest_min_y) = dest_point;
@@ -541,8 +898,7 @@ impl<'gc> BitmapData<'gc> {
 
                 let mut dest_color = self.get_pixel_raw(dest_x as u32, dest_y as u32).unwrap();
 
-                if let Some((alpha_bitmap, (alpha_min_x, alpha_min_y), merge_alpha)) = alpha_source
-                {
+                if let Some((alpha_bitmap, (alpha_min_x, alpha_min_y))) = alpha_source {
                     let alpha_x = src_x - src_min_x + alpha_min_x;
                     let alpha_y = src_y - src_min_y + alpha_min_y;
 
@@ -587,11 +943,12 @@ impl<'gc> BitmapData<'gc> {
                         intermediate_color
                     };
                 } else {
-                    dest_color = if source_bitmap.transparency && !self.transparency {
-                        dest_color.blend_over(&source_color)
-                    } else {
-                        source_color
-                    };
+                    dest_color =
+                        if source_bitmap.transparency && (merge_alpha || !self.transparency) {
+                            dest_color.blend_over(&source_color)
+                        } else {
+                            source_color
+                        };
                 }
 
                 self.set_pixel32_raw(dest_x as u32, dest_y as u32, dest_color);
@@ -699,13 +1056,123 @@ impl<'gc> BitmapData<'gc> {
                 let a = channel_arrays.3[source_color.alpha() as usize];
 
                 let sum = u32::wrapping_add(u32::wrapping_add(r, g), u32::wrapping_add(b, a));
-                let mix_color = Color(sum as i32).to_premultiplied_alpha(true);
+                // Opaque bitmaps keep full alpha, whatever the alpha array maps to.
+                let mix_color = Color(sum as i32).to_premultiplied_alpha(self.transparency);
 
                 self.set_pixel32_raw(dest_x as u32, dest_y as u32, mix_color);
             }
         }
     }
 
+    /// Test the pixels of a rectangle of the source bitmap against a
+    /// threshold, setting the matching ones to `color` in this bitmap.
+    /// Returns how many pixels matched.
+    ///
+    /// Pixels are compared as unmultiplied ARGB, after both they and the
+    /// threshold are masked with `mask`. Pixels that don't match are copied
+    /// from the source if `copy_source` is set, and left alone otherwise.
+    ///
+    /// As with `palette_map`, a `source_bitmap` of `None` means that this
+    /// bitmap is also the source.
+    #[allow(clippy::too_many_arguments)]
+    pub fn threshold(
+        &mut self,
+        source_bitmap: Option<&Self>,
+        src_rect: (i32, i32, i32, i32),
+        dest_point: (i32, i32),
+        operation: ThresholdOperation,
+        threshold: u32,
+        color: u32,
+        mask: u32,
+        copy_source: bool,
+    ) -> u32 {
+        let (src_min_x, src_min_y, src_width, src_height) = src_rect;
+        let (dest_min_x, dest_min_y) = dest_point;
+        let mut count = 0;
+
+        // When this bitmap is its own source, a pixel written here may be
+        // read again further on, so the source rectangle is read first.
+        let source = source_bitmap.unwrap_or(self);
+        let (left, top, right, bottom) =
+            source.clip_rect(src_min_x, src_min_y, src_width, src_height);
+        let source_pixels: Vec<Color> = (top..bottom)
+            .flat_map(|y| (left..right).map(move |x| (x, y)))
+            .map(|(x, y)| source.get_pixel_raw(x, y).unwrap())
+            .collect();
+
+        for src_y in top..bottom {
+            for src_x in left..right {
+                let dest_x = src_x as i32 - src_min_x + dest_min_x;
+                let dest_y = src_y as i32 - src_min_y + dest_min_y;
+
+                if !self.is_point_in_bounds(dest_x, dest_y) {
+                    continue;
+                }
+
+                let source_color =
+                    source_pixels[(src_x - left + (src_y - top) * (right - left)) as usize];
+                let value = u32::from(source_color.to_un_multiplied_alpha());
+
+                if operation.matches(value & mask, threshold & mask) {
+                    count += 1;
+                    let color = Color(color as i32).to_premultiplied_alpha(self.transparency);
+                    self.set_pixel32_raw(dest_x as u32, dest_y as u32, color);
+                } else if copy_source {
+                    let color = if self.transparency {
+                        source_color
+                    } else {
+                        source_color.with_alpha(255)
+                    };
+                    self.set_pixel32_raw(dest_x as u32, dest_y as u32, color);
+                }
+            }
+        }
+
+        count
+    }
+
+    /// Compare this bitmap with another of the same size, giving the pixels
+    /// of a transparent bitmap of the differences between them, or `None` if
+    /// they're the same.
+    ///
+    /// Where the colors of a pixel differ, the difference of each color
+    /// channel is kept, with full alpha. Where only the alpha differs, the
+    /// difference of the alpha is kept, in white.
+    pub fn compare(&self, other: &Self) -> Option<Vec<Color>> {
+        let mut different = false;
+        let pixels: Vec<Color> = self
+            .pixels
+            .iter()
+            .zip(other.pixels.iter())
+            .map(|(a, b)| {
+                let a = a.to_un_multiplied_alpha();
+                let b = b.to_un_multiplied_alpha();
+                if a.with_alpha(0) != b.with_alpha(0) {
+                    different = true;
+                    Color::argb(
+                        255,
+                        a.red().wrapping_sub(b.red()),
+                        a.green().wrapping_sub(b.green()),
+                        a.blue().wrapping_sub(b.blue()),
+                    )
+                    .to_premultiplied_alpha(true)
+                } else if a.alpha() != b.alpha() {
+                    different = true;
+                    Color::argb(a.alpha().wrapping_sub(b.alpha()), 255, 255, 255)
+                        .to_premultiplied_alpha(true)
+                } else {
+                    0.into()
+                }
+            })
+            .collect();
+
+        if different {
+            Some(pixels)
+        } else {
+            None
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn perlin_noise(
         &mut self,
@@ -864,3 +1331,421 @@ impl<'gc> BitmapData<'gc> {
         self.avm2_object = Some(object)
     }
 }
+
+/// Something that can be drawn onto a bitmap with `BitmapData.draw`.
+#[derive(Clone, Copy)]
+pub enum BitmapDrawable<'gc> {
+    BitmapData(GcCell<'gc, BitmapData<'gc>>),
+    DisplayObject(DisplayObject<'gc>),
+}
+
+/// Draw a display object or another bitmap onto a bitmap, as
+/// `BitmapData.draw` does.
+///
+/// The source is drawn by the renderer into an offscreen bitmap, ignoring
+/// its own transform in favour of `transform`, and read back. The result is
+/// then blended onto the target, within `clip_rect` (x, y, width and height)
+/// if one is given.
+pub fn draw<'gc>(
+    target: GcCell<'gc, BitmapData<'gc>>,
+    source: BitmapDrawable<'gc>,
+    transform: &Transform,
+    smoothing: bool,
+    blend_mode: BlendMode,
+    clip_rect: Option<(i32, i32, i32, i32)>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) {
+    let (width, height) = {
+        let target = target.read();
+        (target.width(), target.height())
+    };
+    if width == 0 || height == 0 {
+        return;
+    }
+
+    let source_bitmap = match source {
+        BitmapDrawable::BitmapData(bitmap_data) => {
+            match bitmap_data
+                .write(context.gc_context)
+                .update_bitmap_handle(context.renderer)
+            {
+                Some(bitmap_handle) => Some(bitmap_handle),
+                None => return,
+            }
+        }
+        BitmapDrawable::DisplayObject(_) => None,
+    };
+
+    if !context.renderer.begin_offscreen(width, height) {
+        log::warn!("BitmapData.draw: the renderer can't draw offscreen");
+        return;
+    }
+
+    let mut transform_stack = TransformStack::new();
+    let mut render_context = RenderContext {
+        renderer: context.renderer,
+        ui: context.ui,
+        library: context.library,
+        transform_stack: &mut transform_stack,
+        stage: context.stage,
+        clip_depth_stack: vec![],
+        allow_mask: true,
+        cursor_overlay: None,
+        is_offscreen: true,
+    };
+
+    render_context.transform_stack.push(transform);
+    match (source, source_bitmap) {
+        (BitmapDrawable::DisplayObject(object), _) => object.render_self(&mut render_context),
+        (_, Some(bitmap_handle)) => render_context.renderer.render_bitmap(
+            bitmap_handle,
+            render_context.transform_stack.transform(),
+            smoothing && render_context.stage.quality().smooth_bitmaps(),
+        ),
+        _ => (),
+    }
+    render_context.transform_stack.pop();
+
+    let rendered = context.renderer.end_offscreen();
+    let rgba = match rendered {
+        Some(Bitmap {
+            data: BitmapFormat::Rgba(rgba),
+            ..
+        }) => rgba,
+        _ => {
+            log::warn!("BitmapData.draw: couldn't read back what was drawn");
+            return;
+        }
+    };
+
+    let (min_x, min_y, end_x, end_y) = match clip_rect {
+        Some((x, y, clip_width, clip_height)) => (
+            x.max(0) as u32,
+            y.max(0) as u32,
+            (x + clip_width).max(0) as u32,
+            (y + clip_height).max(0) as u32,
+        ),
+        None => (0, 0, width, height),
+    };
+    target
+        .write(context.gc_context)
+        .blend_rgba(&rgba, blend_mode, min_x, min_y, end_x, end_y);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blend_opaque_colors() {
+        let dest = Color::argb(255, 128, 64, 0);
+        let source = Color::argb(255, 255, 128, 0);
+        let blend = |blend_mode| dest.blend(&source, blend_mode);
+
+        assert_eq!(blend(BlendMode::Normal), source);
+        assert_eq!(blend(BlendMode::Multiply), Color::argb(255, 128, 32, 0));
+        assert_eq!(blend(BlendMode::Screen), Color::argb(255, 255, 160, 0));
+        assert_eq!(blend(BlendMode::Add), Color::argb(255, 255, 192, 0));
+        assert_eq!(blend(BlendMode::Subtract), Color::argb(255, 0, 0, 0));
+        assert_eq!(blend(BlendMode::Difference), Color::argb(255, 127, 64, 0));
+        assert_eq!(blend(BlendMode::Darken), dest);
+        assert_eq!(blend(BlendMode::Lighten), source);
+        assert_eq!(blend(BlendMode::Alpha), dest);
+        assert_eq!(blend(BlendMode::Erase), Color::argb(0, 0, 0, 0));
+    }
+
+    #[test]
+    fn blend_translucent_source() {
+        let source = Color::argb(128, 128, 0, 0);
+        let transparent = Color::argb(0, 0, 0, 0);
+        assert_eq!(transparent.blend(&source, BlendMode::Normal), source);
+
+        let white = Color::argb(255, 255, 255, 255);
+        assert_eq!(
+            white.blend(&source, BlendMode::Normal),
+            Color::argb(255, 255, 127, 127)
+        );
+        assert_eq!(
+            white.blend(&source, BlendMode::Erase),
+            Color::argb(127, 127, 127, 127)
+        );
+    }
+
+    #[test]
+    fn blend_mode_names() {
+        assert_eq!(
+            blend_mode_from_name("hardlight"),
+            Some(BlendMode::HardLight)
+        );
+        assert_eq!(blend_mode_from_name("normal"), Some(BlendMode::Normal));
+        assert_eq!(blend_mode_from_name("HARDLIGHT"), None);
+        assert_eq!(blend_mode_from_name("shader"), None);
+    }
+
+    #[test]
+    fn blend_mode_names_round_trip() {
+        for n in 0..=14 {
+            let blend_mode = BlendMode::from_u8(n).unwrap();
+            assert_eq!(
+                blend_mode_from_name(blend_mode_name(blend_mode)),
+                Some(blend_mode)
+            );
+        }
+        assert_eq!(blend_mode_name(BlendMode::from_u8(1).unwrap()), "normal");
+    }
+
+    #[test]
+    fn blend_contrast_modes() {
+        let dest = Color::argb(255, 128, 64, 0);
+        let source = Color::argb(255, 255, 128, 0);
+        let blend = |blend_mode| dest.blend(&source, blend_mode);
+
+        assert_eq!(blend(BlendMode::Layer), source);
+        assert_eq!(blend(BlendMode::Invert), Color::argb(255, 127, 191, 255));
+        assert_eq!(blend(BlendMode::HardLight), Color::argb(255, 255, 65, 0));
+        assert_eq!(blend(BlendMode::Overlay), Color::argb(255, 255, 64, 0));
+    }
+
+    #[test]
+    fn blend_rgba_inside_bounds() {
+        let red = [255, 0, 0, 255];
+        let rgba = red.repeat(4);
+
+        let mut transparent = BitmapData::default();
+        transparent.init_pixels(2, 2, true, 0);
+        transparent.blend_rgba(&rgba, BlendMode::Normal, 1, 0, 2, 1);
+        assert_eq!(transparent.get_pixel32(0, 0), Color::argb(0, 0, 0, 0));
+        assert_eq!(transparent.get_pixel32(1, 0), Color::argb(255, 255, 0, 0));
+        assert_eq!(transparent.get_pixel32(1, 1), Color::argb(0, 0, 0, 0));
+
+        // Opaque bitmaps stay opaque, even when erased.
+        let mut opaque = BitmapData::default();
+        opaque.init_pixels(2, 2, false, 0xFFFFFF);
+        opaque.blend_rgba(&rgba, BlendMode::Erase, 0, 0, 2, 2);
+        assert_eq!(opaque.get_pixel32(0, 0).alpha(), 255);
+
+        // Too few pixels are ignored.
+        opaque.blend_rgba(&red, BlendMode::Normal, 0, 0, 2, 2);
+        assert_eq!(
+            opaque.get_pixel32(0, 0),
+            Color::argb(255, 0, 0, 0),
+            "the erased pixel is left as it was"
+        );
+    }
+
+    #[test]
+    fn pixels_argb_are_clipped() {
+        let mut bitmap_data = BitmapData::default();
+        bitmap_data.init_pixels(2, 2, true, 0);
+        assert_eq!(bitmap_data.clipped_rect_len(1, 1, 5, 5), 1);
+        assert_eq!(bitmap_data.clipped_rect_len(-4, -4, 2, 2), 0);
+        assert_eq!(bitmap_data.clipped_rect_len(0, 0, -1, 2), 0);
+
+        let set = bitmap_data.set_pixels_argb(-1, 0, 3, 2, vec![0xFF00_FF00, 0x8000_00FF]);
+        assert_eq!(set, 2, "only the pixels inside the bitmap are set");
+        assert_eq!(
+            bitmap_data.get_pixels_argb(0, 0, 2, 2),
+            vec![0xFF00_FF00, 0x8000_00FF, 0, 0]
+        );
+
+        // Running out of colors stops partway through.
+        let set = bitmap_data.set_pixels_argb(0, 1, 2, 1, vec![0xFFFF_0000]);
+        assert_eq!(set, 1);
+        assert_eq!(
+            bitmap_data.get_pixels_argb(0, 1, 5, 5),
+            vec![0xFFFF_0000, 0]
+        );
+    }
+
+    #[test]
+    fn copy_pixels_alpha_source() {
+        let mut source = BitmapData::default();
+        source.init_pixels(2, 1, false, 0xFF0000);
+        let mut alpha = BitmapData::default();
+        alpha.init_pixels(3, 1, true, 0x8000_0000u32 as i32);
+        alpha.set_pixel32(2, 0, Color::argb(255, 0, 0, 0));
+
+        let mut dest = BitmapData::default();
+        dest.init_pixels(3, 1, true, 0);
+        dest.copy_pixels(&source, (0, 0, 2, 1), (0, 0), Some((&alpha, (1, 0))), false);
+        assert_eq!(dest.get_pixel32(0, 0), Color::argb(128, 255, 0, 0));
+        assert_eq!(dest.get_pixel32(1, 0), Color::argb(255, 255, 0, 0));
+        assert_eq!(dest.get_pixel32(2, 0), Color::argb(0, 0, 0, 0));
+    }
+
+    #[test]
+    fn copy_pixels_merge_alpha() {
+        let mut source = BitmapData::default();
+        source.init_pixels(1, 1, true, 0x80FF_0000u32 as i32);
+
+        let mut dest = BitmapData::default();
+        dest.init_pixels(2, 1, true, 0xFF00_00FFu32 as i32);
+        dest.copy_pixels(&source, (0, 0, 1, 1), (0, 0), None, true);
+        dest.copy_pixels(&source, (0, 0, 1, 1), (1, 0), None, false);
+        assert_eq!(
+            dest.get_pixel_raw(0, 0),
+            Some(Color::argb(254, 128, 0, 126))
+        );
+        assert_eq!(dest.get_pixel32(1, 0), Color::argb(128, 255, 0, 0));
+    }
+
+    #[test]
+    fn palette_map_keeps_opaque_bitmaps_opaque() {
+        let channel = |shift: u32| {
+            let mut channel = [0; 256];
+            for (i, value) in channel.iter_mut().enumerate() {
+                *value = (i as u32) << shift;
+            }
+            channel
+        };
+        let channels = (channel(16), channel(8), channel(0), [0; 256]);
+
+        let mut opaque = BitmapData::default();
+        opaque.init_pixels(1, 1, false, 0x123456);
+        opaque.palette_map(None, (0, 0, 1, 1), (0, 0), channels);
+        assert_eq!(opaque.get_pixel32(0, 0), Color::argb(255, 0x12, 0x34, 0x56));
+
+        let mut transparent = BitmapData::default();
+        transparent.init_pixels(1, 1, true, 0xFF12_3456u32 as i32);
+        transparent.palette_map(None, (0, 0, 1, 1), (0, 0), channels);
+        assert_eq!(
+            transparent.get_pixel_raw(0, 0),
+            Some(Color::argb(0, 0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn threshold_reads_own_source_before_writing() {
+        let mut bitmap_data = BitmapData::default();
+        bitmap_data.init_pixels(3, 1, false, 0xFF0000);
+        bitmap_data.set_pixel32(1, 0, Color::argb(255, 0, 255, 0));
+        bitmap_data.set_pixel32(2, 0, Color::argb(255, 0, 0, 255));
+
+        // The first pixel matches and is whitened into the second, which is
+        // then copied into the third as it was before.
+        let count = bitmap_data.threshold(
+            None,
+            (0, 0, 2, 1),
+            (1, 0),
+            ThresholdOperation::Equal,
+            0xFFFF_0000,
+            0xFFFF_FFFF,
+            0xFFFF_FFFF,
+            true,
+        );
+        assert_eq!(count, 1);
+        assert_eq!(
+            bitmap_data.get_pixels_argb(0, 0, 3, 1),
+            vec![0xFFFF_0000, 0xFFFF_FFFF, 0xFF00_FF00]
+        );
+    }
+
+    #[test]
+    fn threshold_masks_and_skips_outside_pixels() {
+        let mut source = BitmapData::default();
+        source.init_pixels(2, 1, true, 0x80FF_0000u32 as i32);
+        source.set_pixel32(1, 0, Color::argb(255, 0x10, 0, 0));
+
+        let mut dest = BitmapData::default();
+        dest.init_pixels(2, 1, true, 0);
+        let count = dest.threshold(
+            Some(&source),
+            (-1, 0, 3, 1),
+            (-1, 0),
+            ThresholdOperation::GreaterThan,
+            0x0080_0000,
+            0xFF00_00FF,
+            0x00FF_0000,
+            false,
+        );
+        assert_eq!(count, 1, "only the red channel is compared");
+        assert_eq!(dest.get_pixels_argb(0, 0, 2, 1), vec![0xFF00_00FF, 0]);
+    }
+
+    #[test]
+    fn compare_differences() {
+        let mut a = BitmapData::default();
+        a.init_pixels(3, 1, true, 0xFFFF_0000u32 as i32);
+        let mut b = a.clone();
+        assert_eq!(a.compare(&b), None);
+
+        b.set_pixel32(1, 0, Color::argb(255, 0x0F, 0x10, 0));
+        b.set_pixel32(2, 0, Color::argb(0x80, 255, 0, 0));
+        let pixels = a.compare(&b).unwrap();
+        assert_eq!(pixels[0], Color::argb(0, 0, 0, 0));
+        assert_eq!(
+            pixels[1].to_un_multiplied_alpha(),
+            Color::argb(255, 0xF0, 0xF0, 0)
+        );
+        assert_eq!(
+            pixels[2].to_un_multiplied_alpha(),
+            Color::argb(0x7F, 255, 255, 255)
+        );
+    }
+
+    #[test]
+    fn fill_rect_is_clipped() {
+        let red = Color::argb(255, 255, 0, 0);
+        let mut bitmap_data = BitmapData::default();
+        bitmap_data.init_pixels(3, 3, true, 0);
+        bitmap_data.fill_rect(-1, -1, 2, 2, red);
+        bitmap_data.fill_rect(1, 1, -1, 5, red);
+        bitmap_data.fill_rect(2, 2, 10, 10, red);
+        assert_eq!(
+            bitmap_data.get_pixels_argb(0, 0, 3, 3),
+            vec![0xFFFF_0000, 0, 0, 0, 0, 0, 0, 0, 0xFFFF_0000]
+        );
+    }
+
+    #[test]
+    fn flood_fill_stops_at_other_colors() {
+        let red = Color::argb(255, 255, 0, 0);
+        let mut bitmap_data = BitmapData::default();
+        bitmap_data.init_pixels(3, 3, true, 0);
+        bitmap_data.fill_rect(1, 0, 1, 3, Color::argb(255, 0, 0, 0));
+
+        bitmap_data.flood_fill(0, 1, red);
+        // Filling with the color that's already there, or outside of the
+        // bitmap, does nothing.
+        bitmap_data.flood_fill(0, 1, red);
+        bitmap_data.flood_fill(5, 5, red);
+        assert_eq!(
+            bitmap_data.get_pixels_argb(0, 0, 3, 3),
+            [0xFFFF_0000u32, 0xFF00_0000, 0].repeat(3)
+        );
+    }
+
+    #[test]
+    fn scroll_in_each_direction() {
+        let row = |width, height| {
+            let mut bitmap_data = BitmapData::default();
+            bitmap_data.init_pixels(width, height, false, 0xFF0000);
+            bitmap_data.set_pixel32(1, 0, Color::argb(255, 0, 255, 0));
+            bitmap_data.set_pixel32(0, 1, Color::argb(255, 0, 255, 0));
+            bitmap_data.set_pixel32(2, 0, Color::argb(255, 0, 0, 255));
+            bitmap_data.set_pixel32(0, 2, Color::argb(255, 0, 0, 255));
+            bitmap_data
+        };
+        let (red, green, blue) = (0xFFFF_0000, 0xFF00_FF00, 0xFF00_00FF);
+
+        let mut right = row(3, 1);
+        right.scroll(1, 0);
+        assert_eq!(right.get_pixels_argb(0, 0, 3, 1), vec![red, red, green]);
+
+        let mut left = row(3, 1);
+        left.scroll(-1, 0);
+        assert_eq!(left.get_pixels_argb(0, 0, 3, 1), vec![green, blue, blue]);
+
+        let mut down = row(1, 3);
+        down.scroll(0, 1);
+        assert_eq!(down.get_pixels_argb(0, 0, 1, 3), vec![red, red, green]);
+
+        let mut up = row(1, 3);
+        up.scroll(0, -2);
+        assert_eq!(up.get_pixels_argb(0, 0, 1, 3), vec![blue, green, blue]);
+
+        let mut too_far = row(3, 1);
+        too_far.scroll(3, 0);
+        assert_eq!(too_far.get_pixels_argb(0, 0, 3, 1), vec![red, green, blue]);
+    }
+}
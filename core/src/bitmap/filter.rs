@@ -0,0 +1,1742 @@
+//! Software implementations of the bitmap filters, for `BitmapData.applyFilter`
+//! and the `filters` of display objects.
+//!
+//! Filters work on a `FilterImage`, a rectangle of premultiplied pixels cut
+//! out of a bitmap or drawn offscreen, and produce a new image of the same
+//! size.
+
+use crate::bitmap::bitmap_data::Color;
+use crate::pixel_bender::{run_shader, PixelBenderShader, PixelBenderValue, ShaderImage};
+use std::sync::Arc;
+
+/// A rectangle of pixels being filtered.
+///
+/// Pixels are premultiplied RGBA, with each channel in `0.0..=1.0`. Reading
+/// outside of the image gives transparent black.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FilterImage {
+    width: u32,
+    height: u32,
+    pixels: Vec<[f32; 4]>,
+}
+
+impl FilterImage {
+    /// Create a transparent image.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0.0; 4]; width as usize * height as usize],
+        }
+    }
+
+    /// Create an image from premultiplied RGBA bytes, such as those drawn
+    /// offscreen by a renderer.
+    pub fn from_rgba(width: u32, height: u32, rgba: &[u8]) -> Self {
+        Self {
+            width,
+            height,
+            pixels: rgba
+                .chunks_exact(4)
+                .map(|pixel| {
+                    [
+                        f32::from(pixel[0]) / 255.0,
+                        f32::from(pixel[1]) / 255.0,
+                        f32::from(pixel[2]) / 255.0,
+                        f32::from(pixel[3]) / 255.0,
+                    ]
+                })
+                .collect(),
+        }
+    }
+
+    /// The premultiplied RGBA bytes of this image.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        let to_u8 = |v: f32| (v.max(0.0).min(1.0) * 255.0).round() as u8;
+        let mut rgba = Vec::with_capacity(self.pixels.len() * 4);
+        for &[r, g, b, a] in &self.pixels {
+            rgba.extend_from_slice(&[to_u8(r), to_u8(g), to_u8(b), to_u8(a)]);
+        }
+        rgba
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && (x as u32) < self.width && (y as u32) < self.height
+    }
+
+    fn get(&self, x: i32, y: i32) -> [f32; 4] {
+        if self.in_bounds(x, y) {
+            self.pixels[x as usize + y as usize * self.width as usize]
+        } else {
+            [0.0; 4]
+        }
+    }
+
+    fn set(&mut self, x: i32, y: i32, pixel: [f32; 4]) {
+        if self.in_bounds(x, y) {
+            self.pixels[x as usize + y as usize * self.width as usize] = pixel;
+        }
+    }
+
+    /// Get a pixel as a premultiplied color.
+    pub fn color(&self, x: i32, y: i32) -> Color {
+        let [r, g, b, a] = self.get(x, y);
+        let to_u8 = |v: f32| (v.max(0.0).min(1.0) * 255.0).round() as u8;
+        Color::argb(to_u8(a), to_u8(r), to_u8(g), to_u8(b))
+    }
+
+    /// This image as un-premultiplied channels, for a shader input that
+    /// reads the first `channels` of them.
+    pub fn to_shader_image(&self, channels: usize) -> ShaderImage {
+        let channels = channels.max(1).min(4);
+        let mut image = ShaderImage::new(self.width, self.height, channels);
+        for (pixel, output) in self
+            .pixels
+            .iter()
+            .zip(image.data.chunks_exact_mut(channels))
+        {
+            for (output, value) in output.iter_mut().zip(&unmultiply(*pixel)) {
+                *output = value / 255.0;
+            }
+        }
+        image
+    }
+
+    /// Create an image from the un-premultiplied output of a shader, which is
+    /// opaque if it has no alpha channel.
+    pub fn from_shader_image(image: &ShaderImage) -> Self {
+        Self {
+            width: image.width,
+            height: image.height,
+            pixels: image
+                .data
+                .chunks_exact(image.channels.max(1))
+                .map(|channels| {
+                    let mut rgba = [0.0, 0.0, 0.0, 255.0];
+                    for (output, value) in rgba.iter_mut().zip(channels) {
+                        *output = value * 255.0;
+                    }
+                    premultiply(rgba)
+                })
+                .collect(),
+        }
+    }
+
+    /// Set a pixel from a premultiplied color.
+    pub fn set_color(&mut self, x: i32, y: i32, color: Color) {
+        self.set(
+            x,
+            y,
+            [
+                f32::from(color.red()) / 255.0,
+                f32::from(color.green()) / 255.0,
+                f32::from(color.blue()) / 255.0,
+                f32::from(color.alpha()) / 255.0,
+            ],
+        );
+    }
+}
+
+/// Un-premultiply a pixel into RGBA values in `0.0..=255.0`.
+fn unmultiply([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    if a <= 0.0 {
+        [0.0; 4]
+    } else {
+        [r / a * 255.0, g / a * 255.0, b / a * 255.0, a * 255.0]
+    }
+}
+
+/// Premultiply RGBA values in `0.0..=255.0`, clamping them first.
+fn premultiply(rgba: [f32; 4]) -> [f32; 4] {
+    let [r, g, b, a] = [
+        rgba[0].max(0.0).min(255.0) / 255.0,
+        rgba[1].max(0.0).min(255.0) / 255.0,
+        rgba[2].max(0.0).min(255.0) / 255.0,
+        rgba[3].max(0.0).min(255.0) / 255.0,
+    ];
+    [r * a, g * a, b * a, a]
+}
+
+/// A color given as `0xRRGGBB` with a separate alpha, as a premultiplied
+/// pixel.
+fn color_pixel(color: u32, alpha: f64) -> [f32; 4] {
+    let alpha = alpha.max(0.0).min(1.0) as f32;
+    [
+        ((color >> 16) & 0xFF) as f32 / 255.0 * alpha,
+        ((color >> 8) & 0xFF) as f32 / 255.0 * alpha,
+        (color & 0xFF) as f32 / 255.0 * alpha,
+        alpha,
+    ]
+}
+
+/// How a `DisplacementMapFilter` fills pixels that are displaced from
+/// outside of the image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplacementMapMode {
+    /// Wrap around to the other side of the image.
+    Wrap,
+    /// Use the nearest pixel on the edge of the image.
+    Clamp,
+    /// Don't displace the pixel.
+    Ignore,
+    /// Use the filter's color.
+    Color,
+}
+
+/// Which parts of an image a glow or bevel is drawn on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterType {
+    /// Only where the image is.
+    Inner,
+    /// Only around the image.
+    Outer,
+    /// Both, drawn over the image.
+    Full,
+}
+
+impl FilterType {
+    fn from_swf(is_inner: bool, is_on_top: bool) -> Self {
+        if is_on_top {
+            FilterType::Full
+        } else if is_inner {
+            FilterType::Inner
+        } else {
+            FilterType::Outer
+        }
+    }
+}
+
+/// One of the colors of the gradient used by a gradient filter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradientStop {
+    /// Where this color is on the gradient, from `0` to `255`.
+    pub ratio: u8,
+    /// The color as `0xRRGGBB`.
+    pub color: u32,
+    /// The alpha of the color, from `0.0` to `1.0`.
+    pub alpha: f64,
+}
+
+impl GradientStop {
+    fn from_swf(record: &swf::GradientRecord) -> Self {
+        Self {
+            ratio: record.ratio,
+            color: record.color.to_rgb(),
+            alpha: f64::from(record.color.a) / 255.0,
+        }
+    }
+}
+
+/// A filter that can be applied to a `FilterImage`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BitmapFilter {
+    /// A box blur, repeated `quality` times.
+    Blur {
+        blur_x: f64,
+        blur_y: f64,
+        quality: i32,
+    },
+
+    /// A 4x5 matrix that transforms un-premultiplied RGBA values.
+    ColorMatrix { matrix: [f64; 20] },
+
+    /// A convolution of each pixel with its neighbours.
+    Convolution {
+        matrix_x: u8,
+        matrix_y: u8,
+        matrix: Vec<f64>,
+        divisor: f64,
+        bias: f64,
+        preserve_alpha: bool,
+        clamp: bool,
+        color: u32,
+        alpha: f64,
+    },
+
+    /// Move pixels by amounts read from a map image.
+    DisplacementMap {
+        map: FilterImage,
+        map_point: (i32, i32),
+        component_x: i32,
+        component_y: i32,
+        scale_x: f64,
+        scale_y: f64,
+        mode: DisplacementMapMode,
+        color: u32,
+        alpha: f64,
+    },
+
+    /// A blurred, colored copy of the image's alpha, moved by a distance
+    /// and drawn behind or inside it.
+    Shadow {
+        color: u32,
+        alpha: f64,
+        blur_x: f64,
+        blur_y: f64,
+        strength: f64,
+        quality: i32,
+        distance: f64,
+        angle: f64,
+        inner: bool,
+        knockout: bool,
+        hide_object: bool,
+    },
+
+    /// A blurred, colored copy of the image's alpha drawn around or inside
+    /// it; a shadow that isn't moved.
+    Glow {
+        color: u32,
+        alpha: f64,
+        blur_x: f64,
+        blur_y: f64,
+        strength: f64,
+        quality: i32,
+        inner: bool,
+        knockout: bool,
+    },
+
+    /// A highlight and a shadow along opposite edges of the image, as if it
+    /// were lit from `angle` degrees.
+    Bevel {
+        highlight_color: u32,
+        highlight_alpha: f64,
+        shadow_color: u32,
+        shadow_alpha: f64,
+        blur_x: f64,
+        blur_y: f64,
+        strength: f64,
+        quality: i32,
+        distance: f64,
+        angle: f64,
+        filter_type: FilterType,
+        knockout: bool,
+    },
+
+    /// A glow that takes its colors from a gradient, from the faintest part
+    /// of the glow at ratio 0 to the strongest at 255.
+    GradientGlow {
+        gradient: Vec<GradientStop>,
+        blur_x: f64,
+        blur_y: f64,
+        strength: f64,
+        quality: i32,
+        distance: f64,
+        angle: f64,
+        filter_type: FilterType,
+        knockout: bool,
+    },
+
+    /// A bevel that takes its colors from a gradient, from the highlight at
+    /// ratio 0 to the shadow at 255.
+    GradientBevel {
+        gradient: Vec<GradientStop>,
+        blur_x: f64,
+        blur_y: f64,
+        strength: f64,
+        quality: i32,
+        distance: f64,
+        angle: f64,
+        filter_type: FilterType,
+        knockout: bool,
+    },
+
+    /// A Pixel Bender shader, which reads the image being filtered as its
+    /// first input.
+    Shader {
+        shader: Arc<PixelBenderShader>,
+        /// The bytecode the shader was parsed from.
+        bytecode: Arc<[u8]>,
+        params: Vec<PixelBenderValue>,
+        /// The images given to the shader's other inputs.
+        inputs: Vec<Option<ShaderImage>>,
+        padding: (i32, i32),
+    },
+}
+
+impl BitmapFilter {
+    /// Get the filter for one of the filters placed on an object by a SWF
+    /// tag, if it's one that can be drawn.
+    pub fn from_swf(filter: &swf::Filter) -> Option<Self> {
+        match filter {
+            swf::Filter::BlurFilter(filter) => Some(BitmapFilter::Blur {
+                blur_x: filter.blur_x.to_f64(),
+                blur_y: filter.blur_y.to_f64(),
+                quality: filter.num_passes.into(),
+            }),
+            swf::Filter::DropShadowFilter(filter) => Some(BitmapFilter::Shadow {
+                color: filter.color.to_rgb(),
+                alpha: f64::from(filter.color.a) / 255.0,
+                blur_x: filter.blur_x.to_f64(),
+                blur_y: filter.blur_y.to_f64(),
+                strength: filter.strength.to_f64(),
+                quality: filter.num_passes.into(),
+                distance: filter.distance.to_f64(),
+                angle: filter.angle.to_f64().to_degrees(),
+                inner: filter.is_inner,
+                knockout: filter.is_knockout,
+                hide_object: false,
+            }),
+            swf::Filter::GlowFilter(filter) => Some(BitmapFilter::Glow {
+                color: filter.color.to_rgb(),
+                alpha: f64::from(filter.color.a) / 255.0,
+                blur_x: filter.blur_x.to_f64(),
+                blur_y: filter.blur_y.to_f64(),
+                strength: filter.strength.to_f64(),
+                quality: filter.num_passes.into(),
+                inner: filter.is_inner,
+                knockout: filter.is_knockout,
+            }),
+            swf::Filter::ColorMatrixFilter(filter) => {
+                let mut matrix = [0.0; 20];
+                for (value, swf_value) in matrix.iter_mut().zip(&filter.matrix) {
+                    *value = swf_value.to_f64();
+                }
+                Some(BitmapFilter::ColorMatrix { matrix })
+            }
+            swf::Filter::ConvolutionFilter(filter) => Some(BitmapFilter::Convolution {
+                matrix_x: filter.num_matrix_cols,
+                matrix_y: filter.num_matrix_rows,
+                matrix: filter.matrix.iter().map(|value| value.to_f64()).collect(),
+                divisor: filter.divisor.to_f64(),
+                bias: filter.bias.to_f64(),
+                preserve_alpha: filter.is_preserve_alpha,
+                clamp: filter.is_clamped,
+                color: filter.default_color.to_rgb(),
+                alpha: f64::from(filter.default_color.a) / 255.0,
+            }),
+            swf::Filter::BevelFilter(filter) => Some(BitmapFilter::Bevel {
+                highlight_color: filter.highlight_color.to_rgb(),
+                highlight_alpha: f64::from(filter.highlight_color.a) / 255.0,
+                shadow_color: filter.shadow_color.to_rgb(),
+                shadow_alpha: f64::from(filter.shadow_color.a) / 255.0,
+                blur_x: filter.blur_x.to_f64(),
+                blur_y: filter.blur_y.to_f64(),
+                strength: filter.strength.to_f64(),
+                quality: filter.num_passes.into(),
+                distance: filter.distance.to_f64(),
+                angle: filter.angle.to_f64().to_degrees(),
+                filter_type: FilterType::from_swf(filter.is_inner, filter.is_on_top),
+                knockout: filter.is_knockout,
+            }),
+            swf::Filter::GradientGlowFilter(filter) => Some(BitmapFilter::GradientGlow {
+                gradient: filter.colors.iter().map(GradientStop::from_swf).collect(),
+                blur_x: filter.blur_x.to_f64(),
+                blur_y: filter.blur_y.to_f64(),
+                strength: filter.strength.to_f64(),
+                quality: filter.num_passes.into(),
+                distance: filter.distance.to_f64(),
+                angle: filter.angle.to_f64().to_degrees(),
+                filter_type: FilterType::from_swf(filter.is_inner, filter.is_on_top),
+                knockout: filter.is_knockout,
+            }),
+            swf::Filter::GradientBevelFilter(filter) => Some(BitmapFilter::GradientBevel {
+                gradient: filter.colors.iter().map(GradientStop::from_swf).collect(),
+                blur_x: filter.blur_x.to_f64(),
+                blur_y: filter.blur_y.to_f64(),
+                strength: filter.strength.to_f64(),
+                quality: filter.num_passes.into(),
+                distance: filter.distance.to_f64(),
+                angle: filter.angle.to_f64().to_degrees(),
+                filter_type: FilterType::from_swf(filter.is_inner, filter.is_on_top),
+                knockout: filter.is_knockout,
+            }),
+            _ => None,
+        }
+    }
+
+    /// How many pixels this filter can spread an image by horizontally and
+    /// vertically, on each side.
+    pub fn padding(&self) -> (i32, i32) {
+        match self {
+            BitmapFilter::Blur {
+                blur_x,
+                blur_y,
+                quality,
+            } => (
+                blur_padding(*blur_x, *quality),
+                blur_padding(*blur_y, *quality),
+            ),
+            BitmapFilter::Shadow {
+                blur_x,
+                blur_y,
+                quality,
+                distance,
+                angle,
+                ..
+            }
+            | BitmapFilter::Bevel {
+                blur_x,
+                blur_y,
+                quality,
+                distance,
+                angle,
+                ..
+            }
+            | BitmapFilter::GradientGlow {
+                blur_x,
+                blur_y,
+                quality,
+                distance,
+                angle,
+                ..
+            }
+            | BitmapFilter::GradientBevel {
+                blur_x,
+                blur_y,
+                quality,
+                distance,
+                angle,
+                ..
+            } => {
+                let angle = angle.to_radians();
+                (
+                    blur_padding(*blur_x, *quality) + (distance * angle.cos()).abs().ceil() as i32,
+                    blur_padding(*blur_y, *quality) + (distance * angle.sin()).abs().ceil() as i32,
+                )
+            }
+            BitmapFilter::Glow {
+                blur_x,
+                blur_y,
+                quality,
+                ..
+            } => (
+                blur_padding(*blur_x, *quality),
+                blur_padding(*blur_y, *quality),
+            ),
+            BitmapFilter::Convolution {
+                matrix_x, matrix_y, ..
+            } => (i32::from(*matrix_x) / 2, i32::from(*matrix_y) / 2),
+            BitmapFilter::ColorMatrix { .. } | BitmapFilter::DisplacementMap { .. } => (0, 0),
+            BitmapFilter::Shader { padding, .. } => *padding,
+        }
+    }
+
+    /// Apply this filter, giving a new image of the same size.
+    pub fn apply(&self, image: &FilterImage) -> FilterImage {
+        match self {
+            BitmapFilter::Blur {
+                blur_x,
+                blur_y,
+                quality,
+            } => blur(image, *blur_x, *blur_y, *quality),
+            BitmapFilter::ColorMatrix { matrix } => color_matrix(image, matrix),
+            BitmapFilter::Convolution {
+                matrix_x,
+                matrix_y,
+                matrix,
+                divisor,
+                bias,
+                preserve_alpha,
+                clamp,
+                color,
+                alpha,
+            } => {
+                let edge = if *clamp {
+                    None
+                } else {
+                    Some(color_pixel(*color, *alpha))
+                };
+                convolution(
+                    image,
+                    (*matrix_x).into(),
+                    (*matrix_y).into(),
+                    matrix,
+                    *divisor,
+                    *bias,
+                    *preserve_alpha,
+                    edge,
+                )
+            }
+            BitmapFilter::DisplacementMap {
+                map,
+                map_point,
+                component_x,
+                component_y,
+                scale_x,
+                scale_y,
+                mode,
+                color,
+                alpha,
+            } => {
+                let mut output = FilterImage::new(image.width, image.height);
+                let component = |pixel: [f32; 4], component: i32| match component {
+                    1 => pixel[0],
+                    2 => pixel[1],
+                    4 => pixel[2],
+                    8 => pixel[3],
+                    _ => 128.0,
+                };
+                for y in 0..image.height as i32 {
+                    for x in 0..image.width as i32 {
+                        let (map_x, map_y) = (x - map_point.0, y - map_point.1);
+                        let offset = if map.in_bounds(map_x, map_y) {
+                            let pixel = unmultiply(map.get(map_x, map_y));
+                            (
+                                (component(pixel, *component_x) - 128.0) as f64 * scale_x / 256.0,
+                                (component(pixel, *component_y) - 128.0) as f64 * scale_y / 256.0,
+                            )
+                        } else {
+                            (0.0, 0.0)
+                        };
+                        let source_x = x + offset.0.floor() as i32;
+                        let source_y = y + offset.1.floor() as i32;
+                        let width = image.width as i32;
+                        let height = image.height as i32;
+
+                        let pixel = if image.in_bounds(source_x, source_y) {
+                            image.get(source_x, source_y)
+                        } else {
+                            match mode {
+                                DisplacementMapMode::Wrap => image
+                                    .get(source_x.rem_euclid(width), source_y.rem_euclid(height)),
+                                DisplacementMapMode::Clamp => image.get(
+                                    source_x.max(0).min(width - 1),
+                                    source_y.max(0).min(height - 1),
+                                ),
+                                DisplacementMapMode::Ignore => image.get(x, y),
+                                DisplacementMapMode::Color => color_pixel(*color, *alpha),
+                            }
+                        };
+                        output.set(x, y, pixel);
+                    }
+                }
+                output
+            }
+            BitmapFilter::Shadow {
+                color,
+                alpha,
+                blur_x,
+                blur_y,
+                strength,
+                quality,
+                distance,
+                angle,
+                inner,
+                knockout,
+                hide_object,
+            } => shadow(
+                image,
+                &solid_gradient(*color, *alpha),
+                (*blur_x, *blur_y),
+                *strength,
+                *quality,
+                (*distance, *angle),
+                if *inner {
+                    FilterType::Inner
+                } else {
+                    FilterType::Outer
+                },
+                *knockout,
+                *hide_object,
+            ),
+            BitmapFilter::Glow {
+                color,
+                alpha,
+                blur_x,
+                blur_y,
+                strength,
+                quality,
+                inner,
+                knockout,
+            } => shadow(
+                image,
+                &solid_gradient(*color, *alpha),
+                (*blur_x, *blur_y),
+                *strength,
+                *quality,
+                (0.0, 0.0),
+                if *inner {
+                    FilterType::Inner
+                } else {
+                    FilterType::Outer
+                },
+                *knockout,
+                false,
+            ),
+            BitmapFilter::Bevel {
+                highlight_color,
+                highlight_alpha,
+                shadow_color,
+                shadow_alpha,
+                blur_x,
+                blur_y,
+                strength,
+                quality,
+                distance,
+                angle,
+                filter_type,
+                knockout,
+            } => {
+                // Fade the highlight out towards the middle of the gradient,
+                // and the shadow in from it.
+                let gradient = [
+                    GradientStop {
+                        ratio: 0,
+                        color: *highlight_color,
+                        alpha: *highlight_alpha,
+                    },
+                    GradientStop {
+                        ratio: 128,
+                        color: *highlight_color,
+                        alpha: 0.0,
+                    },
+                    GradientStop {
+                        ratio: 128,
+                        color: *shadow_color,
+                        alpha: 0.0,
+                    },
+                    GradientStop {
+                        ratio: 255,
+                        color: *shadow_color,
+                        alpha: *shadow_alpha,
+                    },
+                ];
+                bevel(
+                    image,
+                    &gradient,
+                    (*blur_x, *blur_y),
+                    *strength,
+                    *quality,
+                    (*distance, *angle),
+                    *filter_type,
+                    *knockout,
+                )
+            }
+            BitmapFilter::GradientGlow {
+                gradient,
+                blur_x,
+                blur_y,
+                strength,
+                quality,
+                distance,
+                angle,
+                filter_type,
+                knockout,
+            } => shadow(
+                image,
+                gradient,
+                (*blur_x, *blur_y),
+                *strength,
+                *quality,
+                (*distance, *angle),
+                *filter_type,
+                *knockout,
+                false,
+            ),
+            BitmapFilter::GradientBevel {
+                gradient,
+                blur_x,
+                blur_y,
+                strength,
+                quality,
+                distance,
+                angle,
+                filter_type,
+                knockout,
+            } => bevel(
+                image,
+                gradient,
+                (*blur_x, *blur_y),
+                *strength,
+                *quality,
+                (*distance, *angle),
+                *filter_type,
+                *knockout,
+            ),
+            BitmapFilter::Shader {
+                shader,
+                params,
+                inputs,
+                ..
+            } => {
+                let channels = shader
+                    .inputs
+                    .first()
+                    .map_or(4, |input| usize::from(input.channels));
+                let source = image.to_shader_image(channels);
+                let mut shader_inputs = vec![Some(&source)];
+                shader_inputs.extend(inputs.iter().map(Option::as_ref));
+                match run_shader(shader, params, &shader_inputs, image.width, image.height) {
+                    Ok(output) => FilterImage::from_shader_image(&output),
+                    Err(e) => {
+                        log::warn!("Couldn't run shader filter: {}", e);
+                        image.clone()
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Draw a blurred copy of an image's alpha behind, inside or over it, moved
+/// by `distance` pixels at `angle` degrees.
+///
+/// The copy is colored by mapping its alpha onto `gradient`.
+#[allow(clippy::too_many_arguments)]
+fn shadow(
+    image: &FilterImage,
+    gradient: &[GradientStop],
+    (blur_x, blur_y): (f64, f64),
+    strength: f64,
+    quality: i32,
+    (distance, angle): (f64, f64),
+    filter_type: FilterType,
+    knockout: bool,
+    hide_object: bool,
+) -> FilterImage {
+    let (offset_x, offset_y) = offset(distance, angle);
+
+    // The shadow starts as the (inverted, for inner shadows) alpha of the
+    // image, moved by the distance.
+    let mut shadow = FilterImage::new(image.width, image.height);
+    for y in 0..image.height as i32 {
+        for x in 0..image.width as i32 {
+            let mut a = image.get(x - offset_x, y - offset_y)[3];
+            if filter_type == FilterType::Inner {
+                a = 1.0 - a;
+            }
+            shadow.set(x, y, [0.0, 0.0, 0.0, a]);
+        }
+    }
+    let shadow = blur(&shadow, blur_x, blur_y, quality);
+
+    let mut output = FilterImage::new(image.width, image.height);
+    for y in 0..image.height as i32 {
+        for x in 0..image.width as i32 {
+            let source = image.get(x, y);
+            let shadow_alpha = (shadow.get(x, y)[3] * strength as f32).min(1.0);
+            let mut shadow_pixel = gradient_pixel(gradient, shadow_alpha * 255.0);
+
+            let pixel = match filter_type {
+                FilterType::Inner => {
+                    // Inner shadows only show where the image is.
+                    for channel in &mut shadow_pixel {
+                        *channel *= source[3];
+                    }
+                    if knockout || hide_object {
+                        shadow_pixel
+                    } else {
+                        over(shadow_pixel, source)
+                    }
+                }
+                FilterType::Outer if knockout => {
+                    let uncovered = 1.0 - source[3];
+                    [
+                        shadow_pixel[0] * uncovered,
+                        shadow_pixel[1] * uncovered,
+                        shadow_pixel[2] * uncovered,
+                        shadow_pixel[3] * uncovered,
+                    ]
+                }
+                FilterType::Outer if hide_object => shadow_pixel,
+                FilterType::Outer => over(source, shadow_pixel),
+                FilterType::Full if knockout || hide_object => shadow_pixel,
+                FilterType::Full => over(shadow_pixel, source),
+            };
+            output.set(x, y, pixel);
+        }
+    }
+    output
+}
+
+/// Draw a highlight and a shadow along the edges of an image, as if it were
+/// lit from `angle` degrees.
+///
+/// Each pixel is colored from `gradient` by how much more it is in shadow
+/// than in highlight: fully highlighted at ratio 0, and fully in shadow at
+/// 255.
+#[allow(clippy::too_many_arguments)]
+fn bevel(
+    image: &FilterImage,
+    gradient: &[GradientStop],
+    (blur_x, blur_y): (f64, f64),
+    strength: f64,
+    quality: i32,
+    (distance, angle): (f64, f64),
+    filter_type: FilterType,
+    knockout: bool,
+) -> FilterImage {
+    let (offset_x, offset_y) = offset(distance, angle);
+
+    let mut alpha = FilterImage::new(image.width, image.height);
+    for (out, pixel) in alpha.pixels.iter_mut().zip(&image.pixels) {
+        *out = [0.0, 0.0, 0.0, pixel[3]];
+    }
+    let alpha = blur(&alpha, blur_x, blur_y, quality);
+
+    let mut output = FilterImage::new(image.width, image.height);
+    for y in 0..image.height as i32 {
+        for x in 0..image.width as i32 {
+            let source = image.get(x, y);
+
+            // The shadow is a copy of the image moved along the angle, and
+            // the highlight one moved the other way.
+            let shadow = alpha.get(x - offset_x, y - offset_y)[3];
+            let highlight = alpha.get(x + offset_x, y + offset_y)[3];
+            let amount = ((shadow - highlight) * strength as f32).max(-1.0).min(1.0);
+            let mut bevel_pixel = gradient_pixel(gradient, 127.5 + amount * 127.5);
+
+            let coverage = match filter_type {
+                FilterType::Inner => source[3],
+                FilterType::Outer => 1.0 - source[3],
+                FilterType::Full => 1.0,
+            };
+            for channel in &mut bevel_pixel {
+                *channel *= coverage;
+            }
+
+            let pixel = if knockout {
+                bevel_pixel
+            } else if filter_type == FilterType::Outer {
+                over(source, bevel_pixel)
+            } else {
+                over(bevel_pixel, source)
+            };
+            output.set(x, y, pixel);
+        }
+    }
+    output
+}
+
+/// The whole-pixel offset of `distance` pixels at `angle` degrees.
+fn offset(distance: f64, angle: f64) -> (i32, i32) {
+    let angle = angle.to_radians();
+    (
+        (distance * angle.cos()).round() as i32,
+        (distance * angle.sin()).round() as i32,
+    )
+}
+
+/// A gradient that fades a single color in from transparent.
+fn solid_gradient(color: u32, alpha: f64) -> [GradientStop; 2] {
+    [
+        GradientStop {
+            ratio: 0,
+            color,
+            alpha: 0.0,
+        },
+        GradientStop {
+            ratio: 255,
+            color,
+            alpha,
+        },
+    ]
+}
+
+/// The premultiplied color at `ratio` (from `0.0` to `255.0`) along a
+/// gradient.
+///
+/// Colors are interpolated un-premultiplied, and the ends of the gradient
+/// extend to either side of it.
+fn gradient_pixel(gradient: &[GradientStop], ratio: f32) -> [f32; 4] {
+    let stop_pixel = |stop: &GradientStop| {
+        [
+            ((stop.color >> 16) & 0xFF) as f32,
+            ((stop.color >> 8) & 0xFF) as f32,
+            (stop.color & 0xFF) as f32,
+            (stop.alpha.max(0.0).min(1.0) * 255.0) as f32,
+        ]
+    };
+
+    let (first, last) = match (gradient.first(), gradient.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return [0.0; 4],
+    };
+    if ratio <= f32::from(first.ratio) {
+        return premultiply(stop_pixel(first));
+    }
+    for pair in gradient.windows(2) {
+        let (start, end) = (&pair[0], &pair[1]);
+        if ratio <= f32::from(end.ratio) {
+            let span = f32::from(end.ratio) - f32::from(start.ratio);
+            let t = if span > 0.0 {
+                (ratio - f32::from(start.ratio)) / span
+            } else {
+                1.0
+            };
+            let (start, end) = (stop_pixel(start), stop_pixel(end));
+            let mut result = [0.0; 4];
+            for ((result, start), end) in result.iter_mut().zip(&start).zip(&end) {
+                *result = start + (end - start) * t;
+            }
+            return premultiply(result);
+        }
+    }
+    premultiply(stop_pixel(last))
+}
+
+/// Composite one premultiplied pixel over another.
+fn over(top: [f32; 4], bottom: [f32; 4]) -> [f32; 4] {
+    let uncovered = 1.0 - top[3];
+    [
+        top[0] + bottom[0] * uncovered,
+        top[1] + bottom[1] * uncovered,
+        top[2] + bottom[2] * uncovered,
+        top[3] + bottom[3] * uncovered,
+    ]
+}
+
+/// The number of passes a blur makes for a `quality`, as Flash clamps it.
+fn blur_passes(quality: i32) -> i32 {
+    quality.max(0).min(15)
+}
+
+/// How many pixels a blur spreads an image by on each side.
+fn blur_padding(blur: f64, quality: i32) -> i32 {
+    if blur <= 1.0 {
+        return 0;
+    }
+    ((blur.min(255.0) / 2.0).ceil() as i32) * blur_passes(quality)
+}
+
+/// Box blur an image, `quality` times in each direction.
+///
+/// The blur amounts are the width and height of the box, as in Flash. A
+/// quality of 0 leaves the image as it is.
+fn blur(image: &FilterImage, blur_x: f64, blur_y: f64, quality: i32) -> FilterImage {
+    let blur_x = blur_x.max(0.0).min(255.0);
+    let blur_y = blur_y.max(0.0).min(255.0);
+    let mut output = image.clone();
+    for _ in 0..blur_passes(quality) {
+        if blur_x > 1.0 {
+            output = box_blur(&output, blur_x, true);
+        }
+        if blur_y > 1.0 {
+            output = box_blur(&output, blur_y, false);
+        }
+    }
+    output
+}
+
+/// Box blur an image along one axis, treating everything outside of it as
+/// transparent.
+///
+/// The box is `size` pixels wide. When that isn't an odd number of whole
+/// pixels, the pixels at either end of the box are weighted by how much of
+/// them it covers.
+fn box_blur(image: &FilterImage, size: f64, horizontal: bool) -> FilterImage {
+    let (length, lines) = if horizontal {
+        (image.width as i32, image.height as i32)
+    } else {
+        (image.height as i32, image.width as i32)
+    };
+    let at = |line: i32, i: i32| if horizontal { (i, line) } else { (line, i) };
+    let reach = (size - 1.0) / 2.0;
+    let radius = reach.floor() as i32;
+    let edge_weight = (reach - f64::from(radius)) as f32;
+    let box_size = size as f32;
+
+    let mut output = FilterImage::new(image.width, image.height);
+    for line in 0..lines {
+        let pixel = |i: i32| {
+            let (x, y) = at(line, i);
+            image.get(x, y)
+        };
+
+        // The sum of the pixels fully inside the box.
+        let mut sum = [0.0f32; 4];
+        for i in -radius..=radius {
+            for (sum, channel) in sum.iter_mut().zip(&pixel(i)) {
+                *sum += channel;
+            }
+        }
+
+        for i in 0..length {
+            let before = pixel(i - radius - 1);
+            let after = pixel(i + radius + 1);
+            let mut result = [0.0f32; 4];
+            for (((result, sum), before), after) in
+                result.iter_mut().zip(&sum).zip(&before).zip(&after)
+            {
+                *result = (sum + (before + after) * edge_weight) / box_size;
+            }
+            let (x, y) = at(line, i);
+            output.set(x, y, result);
+
+            let remove = pixel(i - radius);
+            for ((sum, add), remove) in sum.iter_mut().zip(&after).zip(&remove) {
+                *sum += add - remove;
+            }
+        }
+    }
+    output
+}
+
+/// Transform every pixel with a color matrix.
+fn color_matrix(image: &FilterImage, matrix: &[f64; 20]) -> FilterImage {
+    let mut output = FilterImage::new(image.width, image.height);
+    for (out, pixel) in output.pixels.iter_mut().zip(&image.pixels) {
+        let [r, g, b, a] = unmultiply(*pixel);
+        let mut result = [0.0; 4];
+        for (row, value) in result.iter_mut().enumerate() {
+            let m = &matrix[row * 5..row * 5 + 5];
+            *value = (m[0] * f64::from(r)
+                + m[1] * f64::from(g)
+                + m[2] * f64::from(b)
+                + m[3] * f64::from(a)
+                + m[4]) as f32;
+        }
+        *out = premultiply(result);
+    }
+    output
+}
+
+/// Convolve every pixel with a matrix centered on it.
+///
+/// Pixels beyond the edges are `edge`, or the nearest edge pixel if that's
+/// `None`.
+#[allow(clippy::too_many_arguments, clippy::float_cmp)]
+fn convolution(
+    image: &FilterImage,
+    matrix_x: i32,
+    matrix_y: i32,
+    matrix: &[f64],
+    divisor: f64,
+    bias: f64,
+    preserve_alpha: bool,
+    edge: Option<[f32; 4]>,
+) -> FilterImage {
+    let divisor = if divisor == 0.0 { 1.0 } else { divisor };
+    let width = image.width as i32;
+    let height = image.height as i32;
+
+    let mut output = FilterImage::new(image.width, image.height);
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = [0.0f64; 4];
+            for j in 0..matrix_y {
+                for i in 0..matrix_x {
+                    let weight = matrix.get((j * matrix_x + i) as usize).copied();
+                    let weight = weight.unwrap_or(0.0);
+                    let source_x = x + i - matrix_x / 2;
+                    let source_y = y + j - matrix_y / 2;
+                    let pixel = if image.in_bounds(source_x, source_y) {
+                        image.get(source_x, source_y)
+                    } else if let Some(edge) = edge {
+                        edge
+                    } else {
+                        image.get(
+                            source_x.max(0).min(width - 1),
+                            source_y.max(0).min(height - 1),
+                        )
+                    };
+                    for (sum, channel) in sum.iter_mut().zip(&unmultiply(pixel)) {
+                        *sum += weight * f64::from(*channel);
+                    }
+                }
+            }
+
+            let mut result = [0.0f32; 4];
+            for (result, sum) in result.iter_mut().zip(&sum) {
+                *result = (sum / divisor + bias) as f32;
+            }
+            if preserve_alpha {
+                result[3] = image.get(x, y)[3] * 255.0;
+            }
+            output.set(x, y, premultiply(result));
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 3x3 transparent image with one opaque pixel in the middle.
+    fn dot(color: u32) -> FilterImage {
+        let mut image = FilterImage::new(3, 3);
+        image.set_color(1, 1, Color::from((color | 0xFF00_0000) as i32));
+        image
+    }
+
+    #[test]
+    fn identity_color_matrix() {
+        let image = FilterImage::from_rgba(2, 1, &[255, 128, 0, 255, 64, 32, 16, 128]);
+        let mut matrix = [0.0; 20];
+        for i in 0..4 {
+            matrix[i * 6] = 1.0;
+        }
+        let output = BitmapFilter::ColorMatrix { matrix }.apply(&image);
+        assert_eq!(output.to_rgba(), image.to_rgba());
+    }
+
+    #[test]
+    fn color_matrix_swaps_channels() {
+        let mut matrix = [0.0; 20];
+        matrix[2] = 1.0;
+        matrix[6] = 1.0;
+        matrix[10] = 1.0;
+        matrix[18] = 1.0;
+        let output = BitmapFilter::ColorMatrix { matrix }.apply(&dot(0xFF8000));
+        assert_eq!(output.color(1, 1), Color::argb(255, 0, 128, 255));
+        assert_eq!(output.color(0, 0), Color::argb(0, 0, 0, 0));
+    }
+
+    #[test]
+    fn blur_spreads_pixels() {
+        let filter = BitmapFilter::Blur {
+            blur_x: 3.0,
+            blur_y: 3.0,
+            quality: 1,
+        };
+        let output = filter.apply(&dot(0xFFFFFF));
+        let total: f32 = output.pixels.iter().map(|pixel| pixel[3]).sum();
+        assert!((total - 1.0).abs() < 1e-5);
+        for y in 0..3 {
+            for x in 0..3 {
+                assert!((output.get(x, y)[3] - 1.0 / 9.0).abs() < 1e-5);
+            }
+        }
+        assert_eq!(filter.padding(), (2, 2));
+    }
+
+    #[test]
+    fn blur_quality_zero_does_nothing() {
+        let image = dot(0xFFFFFF);
+        let filter = BitmapFilter::Blur {
+            blur_x: 8.0,
+            blur_y: 8.0,
+            quality: 0,
+        };
+        assert_eq!(filter.apply(&image), image);
+    }
+
+    #[test]
+    fn fractional_blur_weights_edges() {
+        // A box 2 pixels wide covers the pixel and half of each neighbour.
+        let filter = BitmapFilter::Blur {
+            blur_x: 2.0,
+            blur_y: 1.0,
+            quality: 1,
+        };
+        let output = filter.apply(&dot(0xFFFFFF));
+        assert!((output.get(1, 1)[3] - 0.5).abs() < 1e-5);
+        assert!((output.get(0, 1)[3] - 0.25).abs() < 1e-5);
+        assert!((output.get(2, 1)[3] - 0.25).abs() < 1e-5);
+        assert_eq!(output.get(1, 0)[3], 0.0);
+        assert_eq!(filter.padding(), (1, 0));
+    }
+
+    #[test]
+    fn blur_padding_grows_with_quality() {
+        let padding = |blur_x, blur_y, quality| {
+            BitmapFilter::Blur {
+                blur_x,
+                blur_y,
+                quality,
+            }
+            .padding()
+        };
+        assert_eq!(padding(4.0, 4.0, 1), (2, 2));
+        assert_eq!(padding(4.0, 5.0, 3), (6, 9));
+        assert_eq!(padding(4.0, 4.0, 0), (0, 0));
+        assert_eq!(padding(1.0, 0.0, 2), (0, 0));
+        // Blurs are capped at 255 pixels and 15 passes.
+        assert_eq!(padding(1000.0, 4.0, 20), (128 * 15, 2 * 15));
+    }
+
+    #[test]
+    fn blur_from_swf() {
+        let filter = swf::Filter::BlurFilter(Box::new(swf::BlurFilter {
+            blur_x: swf::Fixed16::from_f64(2.5),
+            blur_y: swf::Fixed16::from_f64(6.0),
+            num_passes: 2,
+        }));
+        assert_eq!(
+            BitmapFilter::from_swf(&filter),
+            Some(BitmapFilter::Blur {
+                blur_x: 2.5,
+                blur_y: 6.0,
+                quality: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn identity_convolution() {
+        let image = dot(0x336699);
+        let filter = BitmapFilter::Convolution {
+            matrix_x: 3,
+            matrix_y: 3,
+            matrix: vec![0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0],
+            divisor: 1.0,
+            bias: 0.0,
+            preserve_alpha: false,
+            clamp: true,
+            color: 0,
+            alpha: 0.0,
+        };
+        assert_eq!(filter.apply(&image).to_rgba(), image.to_rgba());
+    }
+
+    #[test]
+    fn convolution_edge_color() {
+        let filter = BitmapFilter::Convolution {
+            matrix_x: 3,
+            matrix_y: 1,
+            matrix: vec![1.0, 0.0, 0.0],
+            divisor: 1.0,
+            bias: 0.0,
+            preserve_alpha: false,
+            clamp: false,
+            color: 0x00FF00,
+            alpha: 1.0,
+        };
+        let output = filter.apply(&dot(0xFF0000));
+        // Each pixel takes the one to its left, or the edge color at the
+        // left edge.
+        assert_eq!(output.color(0, 1), Color::argb(255, 0, 255, 0));
+        assert_eq!(output.color(1, 1), Color::argb(0, 0, 0, 0));
+        assert_eq!(output.color(2, 1), Color::argb(255, 255, 0, 0));
+    }
+
+    #[test]
+    fn matrix_filters_from_swf() {
+        let mut matrix = [swf::Fixed16::ZERO; 20];
+        matrix[2] = swf::Fixed16::from_f64(1.0);
+        matrix[19] = swf::Fixed16::from_f64(0.5);
+        let color_matrix =
+            swf::Filter::ColorMatrixFilter(Box::new(swf::ColorMatrixFilter { matrix }));
+        let mut expected = [0.0; 20];
+        expected[2] = 1.0;
+        expected[19] = 0.5;
+        assert_eq!(
+            BitmapFilter::from_swf(&color_matrix),
+            Some(BitmapFilter::ColorMatrix { matrix: expected })
+        );
+
+        let convolution = swf::Filter::ConvolutionFilter(Box::new(swf::ConvolutionFilter {
+            num_matrix_rows: 1,
+            num_matrix_cols: 3,
+            matrix: vec![
+                swf::Fixed16::from_f64(1.0),
+                swf::Fixed16::from_f64(2.0),
+                swf::Fixed16::from_f64(1.0),
+            ],
+            divisor: swf::Fixed16::from_f64(4.0),
+            bias: swf::Fixed16::from_f64(8.0),
+            default_color: swf::Color::from_rgb(0x00FF00, 51),
+            is_clamped: false,
+            is_preserve_alpha: true,
+        }));
+        assert_eq!(
+            BitmapFilter::from_swf(&convolution),
+            Some(BitmapFilter::Convolution {
+                matrix_x: 3,
+                matrix_y: 1,
+                matrix: vec![1.0, 2.0, 1.0],
+                divisor: 4.0,
+                bias: 8.0,
+                preserve_alpha: true,
+                clamp: false,
+                color: 0x00FF00,
+                alpha: 0.2,
+            })
+        );
+    }
+
+    #[test]
+    fn drop_shadow_is_offset() {
+        let filter = BitmapFilter::Shadow {
+            color: 0x0000FF,
+            alpha: 1.0,
+            blur_x: 0.0,
+            blur_y: 0.0,
+            strength: 1.0,
+            quality: 1,
+            distance: 1.0,
+            angle: 0.0,
+            inner: false,
+            knockout: false,
+            hide_object: false,
+        };
+        let output = filter.apply(&dot(0xFF0000));
+        assert_eq!(output.color(1, 1), Color::argb(255, 255, 0, 0));
+        assert_eq!(output.color(2, 1), Color::argb(255, 0, 0, 255));
+        assert_eq!(output.color(0, 1), Color::argb(0, 0, 0, 0));
+    }
+
+    #[test]
+    fn hidden_object_leaves_only_shadow() {
+        let filter = BitmapFilter::Shadow {
+            color: 0x0000FF,
+            alpha: 1.0,
+            blur_x: 0.0,
+            blur_y: 0.0,
+            strength: 1.0,
+            quality: 1,
+            distance: 1.0,
+            angle: 0.0,
+            inner: false,
+            knockout: false,
+            hide_object: true,
+        };
+        let output = filter.apply(&dot(0xFF0000));
+        assert_eq!(output.color(1, 1), Color::argb(0, 0, 0, 0));
+        assert_eq!(output.color(2, 1), Color::argb(255, 0, 0, 255));
+    }
+
+    /// A red glow, as strong as it takes to fill the blurred area.
+    fn glow(inner: bool, knockout: bool, strength: f64) -> BitmapFilter {
+        BitmapFilter::Glow {
+            color: 0xFF0000,
+            alpha: 1.0,
+            blur_x: 3.0,
+            blur_y: 3.0,
+            strength,
+            quality: 1,
+            inner,
+            knockout,
+        }
+    }
+
+    #[test]
+    fn glow_surrounds_image() {
+        let output = glow(false, false, 10.0).apply(&dot(0xFFFFFF));
+        assert_eq!(output.color(1, 1), Color::argb(255, 255, 255, 255));
+        assert_eq!(output.color(0, 0), Color::argb(255, 255, 0, 0));
+        assert_eq!(output.color(2, 1), Color::argb(255, 255, 0, 0));
+        assert_eq!(glow(false, false, 10.0).padding(), (2, 2));
+    }
+
+    #[test]
+    fn knockout_glow_removes_image() {
+        let output = glow(false, true, 10.0).apply(&dot(0xFFFFFF));
+        assert_eq!(output.color(1, 1), Color::argb(0, 0, 0, 0));
+        assert_eq!(output.color(0, 0), Color::argb(255, 255, 0, 0));
+    }
+
+    #[test]
+    fn inner_glow_stays_inside_image() {
+        // Eight of the nine pixels around the dot are outside of it.
+        let output = glow(true, false, 1.0).apply(&dot(0xFFFFFF));
+        assert_eq!(output.color(1, 1), Color::argb(255, 255, 28, 28));
+        assert_eq!(output.color(0, 0), Color::argb(0, 0, 0, 0));
+    }
+
+    #[test]
+    fn shadow_and_glow_from_swf() {
+        let shadow = swf::Filter::DropShadowFilter(Box::new(swf::DropShadowFilter {
+            color: swf::Color::from_rgb(0x0000FF, 128),
+            blur_x: swf::Fixed16::from_f64(4.0),
+            blur_y: swf::Fixed16::from_f64(2.0),
+            angle: swf::Fixed16::from_f64(std::f64::consts::FRAC_PI_2),
+            distance: swf::Fixed16::from_f64(5.0),
+            strength: swf::Fixed8::from_f64(1.5),
+            is_inner: true,
+            is_knockout: false,
+            num_passes: 3,
+        }));
+        match BitmapFilter::from_swf(&shadow) {
+            Some(BitmapFilter::Shadow {
+                color,
+                alpha,
+                blur_x,
+                blur_y,
+                strength,
+                quality,
+                distance,
+                angle,
+                inner,
+                knockout,
+                hide_object,
+            }) => {
+                assert_eq!(color, 0x0000FF);
+                assert!((alpha - 128.0 / 255.0).abs() < 1e-9);
+                assert_eq!((blur_x, blur_y), (4.0, 2.0));
+                assert_eq!(strength, 1.5);
+                assert_eq!(quality, 3);
+                assert_eq!(distance, 5.0);
+                assert!((angle - 90.0).abs() < 0.01);
+                assert!(inner && !knockout && !hide_object);
+            }
+            filter => panic!("expected a shadow, got {:?}", filter),
+        }
+
+        let glow = swf::Filter::GlowFilter(Box::new(swf::GlowFilter {
+            color: swf::Color::from_rgb(0xFF0000, 255),
+            blur_x: swf::Fixed16::from_f64(6.0),
+            blur_y: swf::Fixed16::from_f64(6.0),
+            strength: swf::Fixed8::from_f64(2.0),
+            is_inner: false,
+            is_knockout: true,
+            num_passes: 1,
+        }));
+        assert_eq!(
+            BitmapFilter::from_swf(&glow),
+            Some(BitmapFilter::Glow {
+                color: 0xFF0000,
+                alpha: 1.0,
+                blur_x: 6.0,
+                blur_y: 6.0,
+                strength: 2.0,
+                quality: 1,
+                inner: false,
+                knockout: true,
+            })
+        );
+    }
+
+    #[test]
+    fn gradient_pixel_interpolates_unmultiplied() {
+        let gradient = [
+            GradientStop {
+                ratio: 64,
+                color: 0xFF0000,
+                alpha: 1.0,
+            },
+            GradientStop {
+                ratio: 192,
+                color: 0x0000FF,
+                alpha: 0.5,
+            },
+        ];
+        assert_eq!(gradient_pixel(&gradient, 0.0), [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(gradient_pixel(&gradient, 128.0), [0.375, 0.0, 0.375, 0.75]);
+        assert_eq!(gradient_pixel(&gradient, 255.0), [0.0, 0.0, 0.5, 0.5]);
+        assert_eq!(gradient_pixel(&[], 128.0), [0.0; 4]);
+    }
+
+    #[test]
+    fn bevel_lights_opposite_edges() {
+        let filter = BitmapFilter::Bevel {
+            highlight_color: 0xFFFFFF,
+            highlight_alpha: 1.0,
+            shadow_color: 0x000000,
+            shadow_alpha: 1.0,
+            blur_x: 0.0,
+            blur_y: 0.0,
+            strength: 1.0,
+            quality: 1,
+            distance: 1.0,
+            angle: 0.0,
+            filter_type: FilterType::Full,
+            knockout: true,
+        };
+        let output = filter.apply(&dot(0xFF0000));
+        // Lit from the left, the highlight is on the left and the shadow on
+        // the right.
+        assert_eq!(output.color(0, 1), Color::argb(255, 255, 255, 255));
+        assert_eq!(output.color(2, 1), Color::argb(255, 0, 0, 0));
+        assert!(output.get(1, 1)[3] < 0.01);
+        assert!(output.get(1, 0)[3] < 0.01);
+    }
+
+    #[test]
+    fn gradient_glow_is_moved_and_colored() {
+        let filter = BitmapFilter::GradientGlow {
+            gradient: vec![
+                GradientStop {
+                    ratio: 0,
+                    color: 0xFF0000,
+                    alpha: 0.0,
+                },
+                GradientStop {
+                    ratio: 255,
+                    color: 0x0000FF,
+                    alpha: 1.0,
+                },
+            ],
+            blur_x: 3.0,
+            blur_y: 3.0,
+            strength: 10.0,
+            quality: 1,
+            distance: 1.0,
+            angle: 0.0,
+            filter_type: FilterType::Outer,
+            knockout: false,
+        };
+        let output = filter.apply(&dot(0xFFFFFF));
+        assert_eq!(output.color(0, 1), Color::argb(0, 0, 0, 0));
+        assert_eq!(output.color(1, 1), Color::argb(255, 255, 255, 255));
+        assert_eq!(output.color(2, 1), Color::argb(255, 0, 0, 255));
+    }
+
+    #[test]
+    fn gradient_filters_from_swf() {
+        let colors = vec![
+            swf::GradientRecord {
+                ratio: 0,
+                color: swf::Color::from_rgb(0xFFFFFF, 255),
+            },
+            swf::GradientRecord {
+                ratio: 255,
+                color: swf::Color::from_rgb(0x000000, 0),
+            },
+        ];
+        let gradient = vec![
+            GradientStop {
+                ratio: 0,
+                color: 0xFFFFFF,
+                alpha: 1.0,
+            },
+            GradientStop {
+                ratio: 255,
+                color: 0x000000,
+                alpha: 0.0,
+            },
+        ];
+
+        let bevel = swf::Filter::BevelFilter(Box::new(swf::BevelFilter {
+            shadow_color: swf::Color::from_rgb(0x000000, 255),
+            highlight_color: swf::Color::from_rgb(0xFFFFFF, 255),
+            blur_x: swf::Fixed16::from_f64(4.0),
+            blur_y: swf::Fixed16::from_f64(4.0),
+            angle: swf::Fixed16::ZERO,
+            distance: swf::Fixed16::from_f64(4.0),
+            strength: swf::Fixed8::from_f64(1.0),
+            is_inner: true,
+            is_knockout: false,
+            is_on_top: true,
+            num_passes: 1,
+        }));
+        assert!(matches!(
+            BitmapFilter::from_swf(&bevel),
+            Some(BitmapFilter::Bevel {
+                filter_type: FilterType::Full,
+                ..
+            })
+        ));
+
+        let glow = swf::Filter::GradientGlowFilter(Box::new(swf::GradientGlowFilter {
+            colors: colors.clone(),
+            blur_x: swf::Fixed16::from_f64(2.0),
+            blur_y: swf::Fixed16::from_f64(3.0),
+            angle: swf::Fixed16::ZERO,
+            distance: swf::Fixed16::from_f64(1.0),
+            strength: swf::Fixed8::from_f64(2.0),
+            is_inner: true,
+            is_knockout: true,
+            is_on_top: false,
+            num_passes: 2,
+        }));
+        assert_eq!(
+            BitmapFilter::from_swf(&glow),
+            Some(BitmapFilter::GradientGlow {
+                gradient: gradient.clone(),
+                blur_x: 2.0,
+                blur_y: 3.0,
+                strength: 2.0,
+                quality: 2,
+                distance: 1.0,
+                angle: 0.0,
+                filter_type: FilterType::Inner,
+                knockout: true,
+            })
+        );
+
+        let bevel = swf::Filter::GradientBevelFilter(Box::new(swf::GradientBevelFilter {
+            colors,
+            blur_x: swf::Fixed16::from_f64(2.0),
+            blur_y: swf::Fixed16::from_f64(3.0),
+            angle: swf::Fixed16::ZERO,
+            distance: swf::Fixed16::from_f64(1.0),
+            strength: swf::Fixed8::from_f64(2.0),
+            is_inner: false,
+            is_knockout: false,
+            is_on_top: false,
+            num_passes: 1,
+        }));
+        assert_eq!(
+            BitmapFilter::from_swf(&bevel),
+            Some(BitmapFilter::GradientBevel {
+                gradient,
+                blur_x: 2.0,
+                blur_y: 3.0,
+                strength: 2.0,
+                quality: 1,
+                distance: 1.0,
+                angle: 0.0,
+                filter_type: FilterType::Outer,
+                knockout: false,
+            })
+        );
+    }
+
+    #[test]
+    fn displacement_map_moves_pixels() {
+        // A map that reads every pixel from one to the right.
+        let mut map = FilterImage::new(3, 3);
+        for y in 0..3 {
+            for x in 0..3 {
+                map.set_color(x, y, Color::argb(255, 255, 128, 128));
+            }
+        }
+        let filter = BitmapFilter::DisplacementMap {
+            map,
+            map_point: (0, 0),
+            component_x: 1,
+            component_y: 2,
+            scale_x: 4.0,
+            scale_y: 0.0,
+            mode: DisplacementMapMode::Color,
+            color: 0x00FF00,
+            alpha: 1.0,
+        };
+        let output = filter.apply(&dot(0xFF0000));
+        assert_eq!(output.color(0, 1), Color::argb(255, 255, 0, 0));
+        assert_eq!(output.color(1, 1), Color::argb(0, 0, 0, 0));
+        assert_eq!(output.color(2, 1), Color::argb(255, 0, 255, 0));
+    }
+
+    #[test]
+    fn displacement_map_modes() {
+        let mut image = FilterImage::new(3, 1);
+        image.set_color(0, 0, Color::argb(255, 255, 0, 0));
+        image.set_color(1, 0, Color::argb(255, 0, 255, 0));
+        image.set_color(2, 0, Color::argb(255, 0, 0, 255));
+        let [a, b, c] = [image.color(0, 0), image.color(1, 0), image.color(2, 0)];
+
+        // A map that reads every pixel from two to the left.
+        let mut map = FilterImage::new(3, 1);
+        for x in 0..3 {
+            map.set_color(x, 0, Color::argb(255, 0, 128, 128));
+        }
+        let displace = |map: &FilterImage, map_point, mode| {
+            let filter = BitmapFilter::DisplacementMap {
+                map: map.clone(),
+                map_point,
+                component_x: 1,
+                component_y: 2,
+                scale_x: 4.0,
+                scale_y: 0.0,
+                mode,
+                color: 0xFFFFFF,
+                alpha: 1.0,
+            };
+            let output = filter.apply(&image);
+            [output.color(0, 0), output.color(1, 0), output.color(2, 0)]
+        };
+        let white = Color::argb(255, 255, 255, 255);
+        assert_eq!(displace(&map, (0, 0), DisplacementMapMode::Wrap), [b, c, a]);
+        assert_eq!(
+            displace(&map, (0, 0), DisplacementMapMode::Clamp),
+            [a, a, a]
+        );
+        assert_eq!(
+            displace(&map, (0, 0), DisplacementMapMode::Ignore),
+            [a, b, a]
+        );
+        assert_eq!(
+            displace(&map, (0, 0), DisplacementMapMode::Color),
+            [white, white, a]
+        );
+
+        // Only the pixels covered by the map are moved.
+        let mut small_map = FilterImage::new(1, 1);
+        small_map.set_color(0, 0, Color::argb(255, 0, 128, 128));
+        assert_eq!(
+            displace(&small_map, (1, 0), DisplacementMapMode::Wrap),
+            [a, c, c]
+        );
+    }
+}
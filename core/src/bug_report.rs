@@ -0,0 +1,188 @@
+//! Bug reports, packaged as a single `.zip` that can be attached to an issue.
+//!
+//! A report is made up of the diagnostics of the system that Ruffle is
+//! running on, the movie that was playing, the warnings that were logged
+//! while it played, and optionally a screenshot of it.
+
+use crate::zip::{CompressionMethod, ZipError, ZipWriter};
+use indexmap::IndexMap;
+use log::{Level, Log, Metadata, Record};
+use std::sync::{Arc, Mutex};
+
+/// How many different warnings are kept for a report.
+const MAX_WARNINGS: usize = 200;
+
+/// The most recent warnings and errors that were logged.
+///
+/// Repeats of a warning are counted rather than kept, so a movie that
+/// logs the same warning every frame doesn't push out everything else.
+#[derive(Debug, Default)]
+pub struct RecentWarnings {
+    /// Each warning and how many times it was logged, oldest first.
+    warnings: Mutex<IndexMap<String, usize>>,
+}
+
+impl RecentWarnings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember a warning, moving it to the end if it was seen before.
+    pub fn record(&self, message: String) {
+        let mut warnings = self.warnings.lock().unwrap();
+        let count = warnings.shift_remove(&message).unwrap_or(0);
+        warnings.insert(message, count + 1);
+        if warnings.len() > MAX_WARNINGS {
+            warnings.shift_remove_index(0);
+        }
+    }
+
+    /// The warnings, oldest first, with how many times each was logged.
+    pub fn warnings(&self) -> Vec<(String, usize)> {
+        let warnings = self.warnings.lock().unwrap();
+        warnings
+            .iter()
+            .map(|(message, count)| (message.clone(), *count))
+            .collect()
+    }
+}
+
+/// A logger that remembers warnings and errors for bug reports, and passes
+/// every message on to another logger.
+pub struct WarningLogger<L> {
+    inner: L,
+    warnings: Arc<RecentWarnings>,
+}
+
+impl<L: Log> WarningLogger<L> {
+    pub fn new(inner: L, warnings: Arc<RecentWarnings>) -> Self {
+        Self { inner, warnings }
+    }
+}
+
+impl<L: Log> Log for WarningLogger<L> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Warn || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if record.level() <= Level::Warn {
+            self.warnings.record(format!(
+                "[{} {}] {}",
+                record.level(),
+                record.target(),
+                record.args()
+            ));
+        }
+
+        if self.inner.enabled(record.metadata()) {
+            self.inner.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// The files that make up a bug report.
+#[derive(Debug)]
+pub struct BugReport {
+    files: Vec<(&'static str, Vec<u8>)>,
+}
+
+impl BugReport {
+    /// Start a report from the diagnostics of the system, as JSON.
+    pub fn new(diagnostics: String) -> Self {
+        Self {
+            files: vec![("diagnostics.json", diagnostics.into_bytes())],
+        }
+    }
+
+    /// Add the URL of the movie that was playing.
+    pub fn with_movie_url(mut self, url: &str) -> Self {
+        self.files
+            .push(("movie.txt", format!("{}\n", url).into_bytes()));
+        self
+    }
+
+    /// Add the warnings that were logged, one per line.
+    pub fn with_warnings(mut self, warnings: &RecentWarnings) -> Self {
+        let mut text = String::new();
+        for (message, count) in warnings.warnings() {
+            if count > 1 {
+                text.push_str(&format!("{} (logged {} times)\n", message, count));
+            } else {
+                text.push_str(&format!("{}\n", message));
+            }
+        }
+        self.files.push(("warnings.txt", text.into_bytes()));
+        self
+    }
+
+    /// Add a screenshot of the movie, as a PNG.
+    pub fn with_screenshot(mut self, png: Vec<u8>) -> Self {
+        self.files.push(("screenshot.png", png));
+        self
+    }
+
+    /// Package the report as a `.zip`.
+    pub fn to_zip(&self) -> Result<Vec<u8>, ZipError> {
+        let mut writer = ZipWriter::new();
+        for (name, contents) in &self.files {
+            // PNGs are already compressed.
+            let method = if name.ends_with(".png") {
+                CompressionMethod::Stored
+            } else {
+                CompressionMethod::Deflated
+            };
+            writer.add_file(name, contents, method)?;
+        }
+        writer.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zip::read_archive;
+
+    #[test]
+    fn repeated_warnings_are_counted() {
+        let warnings = RecentWarnings::new();
+        warnings.record("first".to_string());
+        warnings.record("second".to_string());
+        warnings.record("first".to_string());
+        assert_eq!(
+            warnings.warnings(),
+            [("second".to_string(), 1), ("first".to_string(), 2)]
+        );
+
+        for i in 0..MAX_WARNINGS {
+            warnings.record(i.to_string());
+        }
+        let kept = warnings.warnings();
+        assert_eq!(kept.len(), MAX_WARNINGS);
+        assert_eq!(kept[0].0, "0");
+    }
+
+    #[test]
+    fn report_is_a_zip() {
+        let warnings = RecentWarnings::new();
+        warnings.record("Unimplemented".to_string());
+        warnings.record("Unimplemented".to_string());
+        let data = BugReport::new("{}".to_string())
+            .with_movie_url("https://ruffle.rs/game.swf")
+            .with_warnings(&warnings)
+            .to_zip()
+            .unwrap();
+
+        let files = read_archive(&data).unwrap();
+        assert_eq!(files.len(), 3);
+        let warnings = files
+            .iter()
+            .find(|file| file.name == "warnings.txt")
+            .unwrap();
+        assert_eq!(warnings.contents, b"Unimplemented (logged 2 times)\n");
+    }
+}
@@ -0,0 +1,303 @@
+//! Bundles of a movie and the files it loads, distributed as a single `.zip`.
+//!
+//! A bundle is served from below the URL it was loaded from, as if the
+//! archive were a directory: the main movie of `file:///games/game.zip`
+//! might be `file:///games/game.zip/game.swf`, and the relative URLs that it
+//! requests are then answered from the archive.
+//!
+//! Flashpoint-style bundles keep the files of each site in
+//! `content/<host>/`, and requests to those sites are answered from there.
+
+use crate::backend::navigator::{
+    NavigationMethod, NavigatorBackend, OwnedFuture, RequestOptions, SocketEvent, SocketHandle,
+};
+use crate::loader::Error;
+use crate::tag_utils::{self, SwfMovie};
+use crate::zip::{self, ZipError};
+use indexmap::IndexMap;
+use percent_encoding::percent_decode_str;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+use thiserror::Error;
+use url::Url;
+
+#[derive(Error, Debug)]
+pub enum BundleError {
+    #[error("Invalid zip archive: {0}")]
+    InvalidZip(#[from] ZipError),
+
+    #[error("Invalid bundle URL: {0}")]
+    InvalidUrl(#[from] url::ParseError),
+
+    #[error("The bundle does not contain a movie")]
+    NoMovie,
+}
+
+/// A movie and the files it loads, read from a `.zip` archive.
+pub struct Bundle {
+    /// The URL that files are served from below, ending with a `/`.
+    url: Url,
+
+    /// The contents of every file in the archive, by path.
+    files: HashMap<String, Vec<u8>>,
+
+    /// The path of the movie to play.
+    main_movie: String,
+}
+
+impl Bundle {
+    /// Whether some data looks like a bundle rather than a movie.
+    pub fn is_bundle(data: &[u8]) -> bool {
+        data.starts_with(b"PK\x03\x04")
+    }
+
+    /// Read a bundle that was loaded from `url`.
+    ///
+    /// The movie to play is the SWF closest to the root of the archive.
+    pub fn from_zip(data: &[u8], url: &str) -> Result<Self, BundleError> {
+        let mut url = Url::parse(url)?;
+        url.set_query(None);
+        url.set_fragment(None);
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+
+        let files: HashMap<_, _> = zip::read_archive(data)?
+            .into_iter()
+            .map(|file| (normalize_path(&file.name), file.contents))
+            .collect();
+
+        let main_movie = files
+            .keys()
+            .filter(|path| !path.starts_with("__MACOSX/"))
+            .filter(|path| {
+                let path = path.to_ascii_lowercase();
+                path.ends_with(".swf") || path.ends_with(".spl")
+            })
+            .min_by_key(|path| (path.matches('/').count(), path.to_string()))
+            .cloned()
+            .ok_or(BundleError::NoMovie)?;
+
+        Ok(Self {
+            url,
+            files,
+            main_movie,
+        })
+    }
+
+    /// The URL of the movie to play.
+    pub fn movie_url(&self) -> Url {
+        self.url_of(&self.main_movie)
+    }
+
+    /// Read the movie to play.
+    pub fn movie(&self) -> Result<SwfMovie, tag_utils::Error> {
+        SwfMovie::from_data(
+            &self.files[&self.main_movie],
+            Some(self.movie_url().to_string()),
+            None,
+        )
+    }
+
+    /// Get the contents of the file that a URL points to, if it is in this
+    /// bundle. Relative URLs are relative to the movie being played.
+    pub fn file(&self, url: &str) -> Option<&[u8]> {
+        let mut url = self.movie_url().join(url).ok()?;
+        url.set_query(None);
+        url.set_fragment(None);
+
+        let path = match url.as_str().strip_prefix(self.url.as_str()) {
+            Some(path) => path.to_string(),
+            None if matches!(url.scheme(), "http" | "https") => {
+                format!("content/{}{}", url.host_str()?, url.path())
+            }
+            None => return None,
+        };
+        let path = normalize_path(&percent_decode_str(&path).decode_utf8_lossy());
+
+        if let Some(contents) = self.files.get(&path) {
+            return Some(contents);
+        }
+
+        // Movies made on Windows may not use the same case as their files.
+        self.files
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&path))
+            .map(|(_, contents)| contents.as_slice())
+    }
+
+    fn url_of(&self, path: &str) -> Url {
+        self.url.join(path).unwrap_or_else(|_| self.url.clone())
+    }
+}
+
+/// Make the paths of files in an archive and the paths requested from it
+/// look alike.
+fn normalize_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let mut path = path.as_str();
+    while let Some(rest) = path.strip_prefix("./").or_else(|| path.strip_prefix('/')) {
+        path = rest;
+    }
+    path.to_string()
+}
+
+/// The bundle being played by a player, if any.
+///
+/// This is shared with the `BundleNavigatorBackend` that serves its files,
+/// so that a bundle can be played once the player has been created.
+#[derive(Clone, Default)]
+pub struct BundleMount(Rc<RefCell<Option<Rc<Bundle>>>>);
+
+impl BundleMount {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve the files of a bundle, replacing any bundle already being
+    /// served.
+    pub fn mount(&self, bundle: Bundle) {
+        *self.0.borrow_mut() = Some(Rc::new(bundle));
+    }
+
+    fn get(&self) -> Option<Rc<Bundle>> {
+        self.0.borrow().clone()
+    }
+}
+
+/// A navigator that answers requests for files in the mounted bundle, and
+/// passes everything else on to another navigator.
+pub struct BundleNavigatorBackend {
+    inner: Box<dyn NavigatorBackend>,
+    bundle: BundleMount,
+}
+
+impl BundleNavigatorBackend {
+    pub fn new(inner: Box<dyn NavigatorBackend>, bundle: BundleMount) -> Self {
+        Self { inner, bundle }
+    }
+}
+
+impl NavigatorBackend for BundleNavigatorBackend {
+    fn navigate_to_url(
+        &self,
+        url: String,
+        window: Option<String>,
+        vars_method: Option<(NavigationMethod, IndexMap<String, String>)>,
+    ) {
+        self.inner.navigate_to_url(url, window, vars_method)
+    }
+
+    fn fetch(&self, url: &str, request_options: RequestOptions) -> OwnedFuture<Vec<u8>, Error> {
+        if let Some(bundle) = self.bundle.get() {
+            if let Some(contents) = bundle.file(url) {
+                let contents = contents.to_vec();
+                return Box::pin(async move { Ok(contents) });
+            }
+        }
+
+        self.inner.fetch(url, request_options)
+    }
+
+    fn time_since_launch(&mut self) -> Duration {
+        self.inner.time_since_launch()
+    }
+
+    fn spawn_future(&mut self, future: OwnedFuture<(), Error>) {
+        self.inner.spawn_future(future)
+    }
+
+    fn resolve_relative_url<'a>(&self, url: &'a str) -> Cow<'a, str> {
+        // Relative URLs are relative to the movie in the bundle, wherever the
+        // navigator would otherwise resolve them.
+        match self.bundle.get() {
+            Some(bundle) if Url::parse(url).is_err() => match bundle.movie_url().join(url) {
+                Ok(url) => url.to_string().into(),
+                Err(_) => self.inner.resolve_relative_url(url),
+            },
+            _ => self.inner.resolve_relative_url(url),
+        }
+    }
+
+    fn pre_process_url(&self, url: Url) -> Url {
+        self.inner.pre_process_url(url)
+    }
+
+    fn enforces_cors(&self) -> bool {
+        self.inner.enforces_cors()
+    }
+
+    fn connect_socket(&mut self, host: &str, port: u16, timeout: Duration) -> SocketHandle {
+        self.inner.connect_socket(host, port, timeout)
+    }
+
+    fn send_socket(&mut self, handle: SocketHandle, data: Vec<u8>) {
+        self.inner.send_socket(handle, data)
+    }
+
+    fn close_socket(&mut self, handle: SocketHandle) {
+        self.inner.close_socket(handle)
+    }
+
+    fn poll_sockets(&mut self) -> Vec<(SocketHandle, SocketEvent)> {
+        self.inner.poll_sockets()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zip::{CompressionMethod, ZipWriter};
+
+    fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = ZipWriter::new();
+        for (name, contents) in files {
+            writer
+                .add_file(name, contents, CompressionMethod::Deflated)
+                .unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn serves_files_below_bundle_url() {
+        let data = zip(&[
+            ("levels/level1.swf", b"level"),
+            ("game.swf", b"game"),
+            ("assets/Music.mp3", b"music"),
+            ("content/www.example.com/scores.xml", b"scores"),
+        ]);
+        assert!(Bundle::is_bundle(&data));
+
+        let bundle = Bundle::from_zip(&data, "https://ruffle.rs/games/game.zip?v=2").unwrap();
+        assert_eq!(
+            bundle.movie_url().as_str(),
+            "https://ruffle.rs/games/game.zip/game.swf"
+        );
+        assert_eq!(bundle.file("assets/music.mp3"), Some(&b"music"[..]));
+        assert_eq!(bundle.file("./levels/level1.swf"), Some(&b"level"[..]));
+        assert_eq!(
+            bundle.file("https://ruffle.rs/games/game.zip/game.swf"),
+            Some(&b"game"[..])
+        );
+        assert_eq!(
+            bundle.file("http://www.example.com/scores.xml?user=1"),
+            Some(&b"scores"[..])
+        );
+        assert_eq!(bundle.file("https://ruffle.rs/games/other.swf"), None);
+        assert_eq!(bundle.file("missing.png"), None);
+    }
+
+    #[test]
+    fn requires_a_movie() {
+        let data = zip(&[("readme.txt", b"No movies here")]);
+        assert!(matches!(
+            Bundle::from_zip(&data, "file:///game.zip"),
+            Err(BundleError::NoMovie)
+        ));
+        assert!(!Bundle::is_bundle(b"FWS"));
+    }
+}
@@ -1,6 +1,8 @@
+use crate::display_object::ParseEnumError;
 use gc_arena::Collect;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 /// Controls whether the content is letterboxed or pillarboxed when the
 /// player's aspect ratio does not match the movie's aspect ratio.
@@ -30,3 +32,109 @@ impl Default for Letterbox {
         Letterbox::Fullscreen
     }
 }
+
+/// A group of writing systems that device text may need a dedicated fallback
+/// font for, when the default device font has no glyphs for them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Collect)]
+#[collect(require_static)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FontScript {
+    /// Chinese, Japanese and Korean text, including kana and hangul.
+    #[cfg_attr(feature = "serde", serde(rename = "cjk"))]
+    Cjk,
+
+    /// Cyrillic text.
+    #[cfg_attr(feature = "serde", serde(rename = "cyrillic"))]
+    Cyrillic,
+
+    /// Arabic text, including presentation forms.
+    #[cfg_attr(feature = "serde", serde(rename = "arabic"))]
+    Arabic,
+}
+
+impl FontScript {
+    /// Determine which script a character belongs to, if it belongs to one
+    /// that can have a fallback font.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c as u32 {
+            0x0400..=0x052F | 0x1C80..=0x1C8F | 0x2DE0..=0x2DFF | 0xA640..=0xA69F => {
+                Some(FontScript::Cyrillic)
+            }
+            0x0600..=0x06FF
+            | 0x0750..=0x077F
+            | 0x08A0..=0x08FF
+            | 0xFB50..=0xFDFF
+            | 0xFE70..=0xFEFF => Some(FontScript::Arabic),
+            0x1100..=0x11FF
+            | 0x2E80..=0x9FFF
+            | 0xA960..=0xA97F
+            | 0xAC00..=0xD7FF
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFFEF
+            | 0x20000..=0x2FFFF => Some(FontScript::Cjk),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for FontScript {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let script = match s.to_ascii_lowercase().as_str() {
+            "cjk" => FontScript::Cjk,
+            "cyrillic" => FontScript::Cyrillic,
+            "arabic" => FontScript::Arabic,
+            _ => return Err(ParseEnumError),
+        };
+        Ok(script)
+    }
+}
+
+/// Controls how device text renders characters that neither the device font
+/// nor any of its fallbacks have a glyph for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Collect)]
+#[collect(require_static)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename = "notdef"))]
+pub enum NotdefStrategy {
+    /// Missing characters are not rendered and take up no space.
+    #[cfg_attr(feature = "serde", serde(rename = "hide"))]
+    Hide,
+
+    /// Missing characters are rendered as a hollow box.
+    #[cfg_attr(feature = "serde", serde(rename = "box"))]
+    Box,
+}
+
+impl Default for NotdefStrategy {
+    fn default() -> Self {
+        NotdefStrategy::Hide
+    }
+}
+
+/// Capabilities to report to movies in place of the real ones.
+///
+/// Many movies refuse to run, or hide features, unless they find a particular
+/// player version or operating system. Anything left as `None` is reported
+/// as it really is.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, rename_all = "camelCase"))]
+pub struct SpoofedCapabilities {
+    /// The version string, such as `WIN 11,2,202,235`.
+    pub version: Option<String>,
+    /// The name of the operating system, such as `Windows 7`.
+    pub os: Option<String>,
+    /// The manufacturer of the player, such as `Adobe Windows`.
+    pub manufacturer: Option<String>,
+    /// The type of the player, such as `PlugIn` or `ActiveX`.
+    pub player_type: Option<String>,
+    /// The language code, such as `en` or `zh-CN`.
+    pub language: Option<String>,
+    /// The resolution of the screen.
+    pub screen_resolution: Option<(u32, u32)>,
+    /// Whether the player is a debugger version.
+    pub is_debugger: Option<bool>,
+}
@@ -1,27 +1,35 @@
 //! Contexts and helper types passed between functions.
 
 use crate::avm1::globals::system::SystemProperties;
-use crate::avm1::{Avm1, Object as Avm1Object, Timers, Value as Avm1Value};
+use crate::avm1::{Avm1, Object as Avm1Object, Value as Avm1Value};
 use crate::avm2::{Avm2, Event as Avm2Event, Object as Avm2Object, Value as Avm2Value};
 use crate::backend::{
     audio::{AudioBackend, AudioManager, SoundHandle, SoundInstanceHandle},
     locale::LocaleBackend,
     log::LogBackend,
     navigator::NavigatorBackend,
-    render::RenderBackend,
+    render::{BitmapHandle, RenderBackend},
     storage::StorageBackend,
     ui::UiBackend,
     video::VideoBackend,
 };
 use crate::context_menu::ContextMenuState;
+use crate::cursor::MouseCursors;
 use crate::display_object::{EditText, MovieClip, SoundTransform, Stage};
 use crate::external::ExternalInterface;
 use crate::focus_tracker::FocusTracker;
+use crate::game_input::GameInput;
 use crate::library::Library;
 use crate::loader::LoadManager;
+use crate::local_connection::LocalConnections;
+use crate::multitouch::Multitouch;
 use crate::player::Player;
 use crate::prelude::*;
+use crate::remoting::NetConnections;
+use crate::security::Security;
+use crate::socket::Sockets;
 use crate::tag_utils::{SwfMovie, SwfSlice};
+use crate::timer::Timers;
 use crate::transform::TransformStack;
 use core::fmt;
 use gc_arena::{Collect, MutationContext};
@@ -119,18 +127,47 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
     /// The system properties
     pub system: &'a mut SystemProperties,
 
+    /// The security sandbox settings of the player.
+    pub security: &'a mut Security,
+
+    /// The mouse cursors chosen by the movie.
+    pub mouse_cursors: &'a mut MouseCursors,
+
+    /// The fingers on a touch screen, and how the movie wants to hear about them.
+    pub multitouch: &'a mut Multitouch,
+
+    /// The game controllers connected to the player.
+    pub game_input: &'a mut GameInput,
+
     /// The current instance ID. Used to generate default `instanceN` names.
     pub instance_counter: &'a mut i32,
 
+    /// Counts changes to display lists and to the names and parents of
+    /// display objects, so that lookups through the display list can be
+    /// cached until it changes.
+    pub display_list_generation: &'a mut u64,
+
     /// Shared objects cache
     pub shared_objects: &'a mut HashMap<String, Avm1Object<'gc>>,
 
+    /// Shared objects cache for AVM2
+    pub avm2_shared_objects: &'a mut HashMap<String, Avm2Object<'gc>>,
+
     /// Text fields with unbound variable bindings.
     pub unbound_text_fields: &'a mut Vec<EditText<'gc>>,
 
     /// Timed callbacks created with `setInterval`/`setTimeout`.
     pub timers: &'a mut Timers<'gc>,
 
+    /// Sockets opened by the movie.
+    pub sockets: &'a mut Sockets<'gc>,
+
+    /// Connections made by `LocalConnection` objects.
+    pub local_connections: &'a mut LocalConnections<'gc>,
+
+    /// Connections made by `NetConnection` objects.
+    pub net_connections: &'a mut NetConnections<'gc>,
+
     pub current_context_menu: &'a mut Option<ContextMenuState<'gc>>,
 
     /// The AVM1 global state.
@@ -260,6 +297,12 @@ impl<'a, 'gc, 'gc_context> UpdateContext<'a, 'gc, 'gc_context> {
     pub fn set_sound_transforms_dirty(&mut self) {
         self.audio_manager.set_sound_transforms_dirty()
     }
+
+    /// Record that a display list, or the name or parent of a display object,
+    /// has changed.
+    pub fn display_list_changed(&mut self) {
+        *self.display_list_generation = self.display_list_generation.wrapping_add(1);
+    }
 }
 
 impl<'a, 'gc, 'gc_context> UpdateContext<'a, 'gc, 'gc_context> {
@@ -299,10 +342,19 @@ impl<'a, 'gc, 'gc_context> UpdateContext<'a, 'gc, 'gc_context> {
             player: self.player.clone(),
             load_manager: self.load_manager,
             system: self.system,
+            security: self.security,
+            mouse_cursors: self.mouse_cursors,
+            multitouch: self.multitouch,
+            game_input: self.game_input,
             instance_counter: self.instance_counter,
+            display_list_generation: self.display_list_generation,
             shared_objects: self.shared_objects,
+            avm2_shared_objects: self.avm2_shared_objects,
             unbound_text_fields: self.unbound_text_fields,
             timers: self.timers,
+            sockets: self.sockets,
+            local_connections: self.local_connections,
+            net_connections: self.net_connections,
             current_context_menu: self.current_context_menu,
             avm1: self.avm1,
             avm2: self.avm2,
@@ -415,6 +467,14 @@ pub struct RenderContext<'a, 'gc> {
     /// Whether to allow pushing a new mask. A masker-inside-a-masker does not work in Flash, instead
     /// causing the inner mask to be included as part of the outer mask. Maskee-inside-a-maskee works as one expects.
     pub allow_mask: bool,
+
+    /// A custom mouse cursor that the UI can't show natively, drawn over
+    /// everything else with the given matrix.
+    pub cursor_overlay: Option<(BitmapHandle, Matrix)>,
+
+    /// Whether this is drawing into an offscreen bitmap rather than the
+    /// stage, in which case nothing is culled by the stage's bounds.
+    pub is_offscreen: bool,
 }
 
 /// The type of action being run.
@@ -5,6 +5,8 @@
 //! items work even if the movie changed `object.menu` in the meantime.
 
 use crate::avm1;
+use crate::avm2;
+use crate::display_object::{Stage, TDisplayObject};
 use gc_arena::Collect;
 #[cfg(feature = "serde")]
 use serde::Serialize;
@@ -30,6 +32,94 @@ impl<'gc> ContextMenuState<'gc> {
     pub fn callback(&self, index: usize) -> &ContextMenuCallback<'gc> {
         &self.callbacks[index]
     }
+
+    /// Add the built-in items that the movie hasn't hidden.
+    pub fn build_builtin_items(&mut self, item_flags: BuiltInItemFlags, stage: Stage<'gc>) {
+        let root_mc = match stage.root_clip().as_movie_clip() {
+            Some(root_mc) => root_mc,
+            None => return,
+        };
+
+        if item_flags.play {
+            let is_playing_root_movie = root_mc.playing();
+            self.push(
+                ContextMenuItem {
+                    enabled: true,
+                    separator_before: true,
+                    caption: "Play".to_string(),
+                    checked: is_playing_root_movie,
+                },
+                ContextMenuCallback::Play,
+            );
+        }
+        if item_flags.rewind {
+            let is_first_frame = root_mc.current_frame() <= 1;
+            self.push(
+                ContextMenuItem {
+                    enabled: !is_first_frame,
+                    separator_before: true,
+                    caption: "Rewind".to_string(),
+                    checked: false,
+                },
+                ContextMenuCallback::Rewind,
+            );
+        }
+        if item_flags.forward_and_back {
+            let is_first_frame = root_mc.current_frame() <= 1;
+            self.push(
+                ContextMenuItem {
+                    enabled: true,
+                    separator_before: false,
+                    caption: "Forward".to_string(),
+                    checked: false,
+                },
+                ContextMenuCallback::Forward,
+            );
+            self.push(
+                ContextMenuItem {
+                    enabled: !is_first_frame,
+                    separator_before: false,
+                    caption: "Back".to_string(),
+                    checked: false,
+                },
+                ContextMenuCallback::Back,
+            );
+        }
+    }
+}
+
+/// Which of the built-in context menu items should be shown.
+#[derive(Clone, Copy, Debug)]
+pub struct BuiltInItemFlags {
+    pub forward_and_back: bool,
+    pub loop_: bool,
+    pub play: bool,
+    pub print: bool,
+    pub quality: bool,
+    pub rewind: bool,
+    pub zoom: bool,
+}
+
+impl BuiltInItemFlags {
+    /// The items shown for a movie that hasn't hidden any.
+    ///
+    /// The playback items are only shown for movies with more than one frame.
+    pub fn for_stage(stage: Stage<'_>) -> Self {
+        let is_multiframe_movie = stage
+            .root_clip()
+            .as_movie_clip()
+            .map(|mc| mc.total_frames() > 1)
+            .unwrap_or(false);
+        Self {
+            forward_and_back: is_multiframe_movie,
+            loop_: is_multiframe_movie,
+            play: is_multiframe_movie,
+            print: true,
+            quality: true,
+            rewind: is_multiframe_movie,
+            zoom: true,
+        }
+    }
 }
 
 #[derive(Collect, Clone)]
@@ -58,4 +148,7 @@ pub enum ContextMenuCallback<'gc> {
         item: avm1::Object<'gc>,
         callback: avm1::Object<'gc>,
     },
+    Avm2 {
+        item: avm2::Object<'gc>,
+    },
 }
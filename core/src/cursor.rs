@@ -0,0 +1,178 @@
+//! Mouse cursors chosen by movies through `flash.ui.Mouse`.
+//!
+//! By default, the cursor follows whatever is under the mouse: a hand over
+//! buttons, an I-beam over text fields and an arrow elsewhere. A movie may
+//! instead pick one of these cursors by name, or register bitmap cursors of
+//! its own with `Mouse.registerCursor`.
+
+use crate::backend::ui::{CustomMouseCursor, MouseCursor};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// The cursor that a movie asked for with `Mouse.cursor`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CursorSetting {
+    /// The cursor for whatever is under the mouse.
+    Auto,
+
+    /// One of the player's own cursors.
+    Builtin(MouseCursor),
+
+    /// A cursor registered with `Mouse.registerCursor`.
+    Custom(String),
+}
+
+impl CursorSetting {
+    /// Find the setting for a value of `Mouse.cursor`.
+    ///
+    /// Registered cursors are checked by the caller.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "auto" => CursorSetting::Auto,
+            "arrow" => CursorSetting::Builtin(MouseCursor::Arrow),
+            "button" => CursorSetting::Builtin(MouseCursor::Hand),
+            "hand" => CursorSetting::Builtin(MouseCursor::Grab),
+            "ibeam" => CursorSetting::Builtin(MouseCursor::IBeam),
+            _ => return None,
+        })
+    }
+
+    /// The value of `Mouse.cursor` for this setting.
+    pub fn name(&self) -> &str {
+        match self {
+            CursorSetting::Auto => "auto",
+            CursorSetting::Builtin(MouseCursor::Arrow) => "arrow",
+            CursorSetting::Builtin(MouseCursor::Hand) => "button",
+            CursorSetting::Builtin(MouseCursor::Grab) => "hand",
+            CursorSetting::Builtin(MouseCursor::IBeam) => "ibeam",
+            CursorSetting::Custom(name) => name,
+        }
+    }
+}
+
+impl Default for CursorSetting {
+    fn default() -> Self {
+        CursorSetting::Auto
+    }
+}
+
+/// The cursors that the movie has registered, and the one it wants shown.
+#[derive(Default)]
+pub struct MouseCursors {
+    setting: CursorSetting,
+    registered: HashMap<String, Rc<CustomMouseCursor>>,
+
+    /// Whether the cursor has changed since the UI was last updated.
+    changed: bool,
+}
+
+impl MouseCursors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn setting(&self) -> &CursorSetting {
+        &self.setting
+    }
+
+    pub fn set_setting(&mut self, setting: CursorSetting) {
+        if setting != self.setting {
+            self.setting = setting;
+            self.changed = true;
+        }
+    }
+
+    /// Register a bitmap cursor that `Mouse.cursor` may then be set to.
+    pub fn register(&mut self, name: String, cursor: CustomMouseCursor) {
+        if self.setting == CursorSetting::Custom(name.clone()) {
+            self.changed = true;
+        }
+        self.registered.insert(name, Rc::new(cursor));
+    }
+
+    /// Forget a registered cursor, going back to the automatic cursor if it
+    /// was being shown.
+    pub fn unregister(&mut self, name: &str) {
+        self.registered.remove(name);
+        if self.setting == CursorSetting::Custom(name.to_string()) {
+            self.set_setting(CursorSetting::Auto);
+        }
+    }
+
+    pub fn is_registered(&self, name: &str) -> bool {
+        self.registered.contains_key(name)
+    }
+
+    /// The bitmap cursor to show, if the movie wants one.
+    pub fn custom_cursor(&self) -> Option<Rc<CustomMouseCursor>> {
+        match &self.setting {
+            CursorSetting::Custom(name) => self.registered.get(name).cloned(),
+            _ => None,
+        }
+    }
+
+    /// Whether the cursor has changed since this was last called.
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor() -> CustomMouseCursor {
+        CustomMouseCursor {
+            width: 1,
+            height: 1,
+            rgba: vec![128, 0, 0, 128],
+            hot_spot: (0, 0),
+        }
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for name in &["auto", "arrow", "button", "hand", "ibeam"] {
+            assert_eq!(CursorSetting::from_name(name).unwrap().name(), *name);
+        }
+        assert_eq!(CursorSetting::from_name("AUTO"), None);
+        assert_eq!(CursorSetting::from_name("dot"), None);
+    }
+
+    #[test]
+    fn changes_are_reported_once() {
+        let mut cursors = MouseCursors::new();
+        assert!(!cursors.take_changed());
+
+        cursors.set_setting(CursorSetting::Auto);
+        assert!(!cursors.take_changed());
+
+        cursors.set_setting(CursorSetting::Builtin(MouseCursor::IBeam));
+        assert!(cursors.take_changed());
+        assert!(!cursors.take_changed());
+    }
+
+    #[test]
+    fn registered_cursors() {
+        let mut cursors = MouseCursors::new();
+        cursors.register("dot".to_string(), cursor());
+        assert!(cursors.is_registered("dot"));
+        // Registering a cursor that isn't shown doesn't change anything.
+        assert!(!cursors.take_changed());
+        assert!(cursors.custom_cursor().is_none());
+
+        cursors.set_setting(CursorSetting::Custom("dot".to_string()));
+        assert!(cursors.take_changed());
+        assert_eq!(cursors.custom_cursor().unwrap().rgba, [128, 0, 0, 128]);
+
+        // Replacing the cursor being shown does.
+        cursors.register("dot".to_string(), cursor());
+        assert!(cursors.take_changed());
+
+        cursors.unregister("dot");
+        assert!(cursors.take_changed());
+        assert!(!cursors.is_registered("dot"));
+        assert_eq!(cursors.setting(), &CursorSetting::Auto);
+        assert!(cursors.custom_cursor().is_none());
+    }
+}
@@ -6,21 +6,25 @@ use crate::avm2::{
     Namespace as Avm2Namespace, Object as Avm2Object, QName as Avm2QName, TObject as Avm2TObject,
     Value as Avm2Value,
 };
+use crate::backend::render::{Bitmap as RenderBitmap, BitmapFormat, BitmapHandle, RenderBackend};
+use crate::bitmap::filter::{BitmapFilter, FilterImage};
 use crate::context::{RenderContext, UpdateContext};
 use crate::drawing::Drawing;
+use crate::matrix::round_to_twips;
+use crate::matrix3d::{Matrix3D, PerspectiveProjection};
 use crate::player::NEWEST_PLAYER_VERSION;
 use crate::prelude::*;
 use crate::tag_utils::SwfMovie;
-use crate::transform::Transform;
+use crate::transform::{Transform, TransformStack};
 use crate::types::{Degrees, Percent};
 use crate::vminterface::{AvmType, Instantiator};
 use bitflags::bitflags;
 use gc_arena::{Collect, MutationContext};
 use ruffle_macros::enum_trait_object;
-use std::cell::{Ref, RefMut};
+use std::cell::{Ref, RefCell, RefMut};
 use std::fmt::Debug;
 use std::sync::Arc;
-use swf::Fixed8;
+use swf::{BlendMode, Fixed8};
 
 mod avm1_button;
 mod avm2_button;
@@ -47,7 +51,9 @@ pub use edit_text::{AutoSizeMode, EditText, TextSelection};
 pub use graphic::Graphic;
 pub use morph_shape::{MorphShape, MorphShapeStatic};
 pub use movie_clip::{MovieClip, Scene};
-pub use stage::{Stage, StageAlign, StageQuality, StageScaleMode};
+pub use stage::{
+    ParseEnumError, Stage, StageAlign, StageDisplayState, StageQuality, StageScaleMode,
+};
 pub use text::Text;
 pub use video::Video;
 
@@ -57,6 +63,7 @@ pub struct DisplayObjectBase<'gc> {
     parent: Option<DisplayObject<'gc>>,
     place_frame: u16,
     depth: Depth,
+    #[collect(require_static)]
     transform: Transform,
     name: String,
     clip_depth: Depth,
@@ -94,6 +101,79 @@ pub struct DisplayObjectBase<'gc> {
 
     /// Bit flags for various display object properties.
     flags: DisplayObjectFlags,
+
+    /// The filters that this display object is drawn through.
+    #[collect(require_static)]
+    filters: Vec<BitmapFilter>,
+
+    /// The last result of drawing this display object through its filters.
+    #[collect(require_static)]
+    filter_cache: RefCell<Option<FilterCache>>,
+
+    /// How this display object is blended onto what's behind it.
+    #[collect(require_static)]
+    blend_mode: BlendMode,
+
+    /// The rectangle of this display object's contents that's shown, if
+    /// it's scrolled.
+    #[collect(require_static)]
+    scroll_rect: Option<BoundingBox>,
+
+    /// The grid that slices this display object into nine regions when
+    /// it's scaled.
+    #[collect(require_static)]
+    scaling_grid: Option<BoundingBox>,
+
+    /// The placement of this display object in 3D space, if it's been moved
+    /// out of its parent's plane.
+    #[collect(require_static)]
+    transform_3d: Option<Transform3D>,
+
+    /// How this display object's descendants are projected when they're
+    /// placed in 3D, if it's been given its own projection.
+    #[collect(require_static)]
+    perspective_projection: Option<PerspectiveProjection>,
+}
+
+/// The placement of a display object in 3D space, on top of its 2D matrix.
+///
+/// A display object only has one once it's been given a `z` position, an X
+/// or Y rotation, or a Z scale. It's then drawn projected onto its parent's
+/// plane.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform3D {
+    pub z: f64,
+
+    /// The rotation around the X axis, in degrees.
+    pub rotation_x: f64,
+
+    /// The rotation around the Y axis, in degrees.
+    pub rotation_y: f64,
+    pub scale_z: f64,
+}
+
+impl Default for Transform3D {
+    fn default() -> Self {
+        Self {
+            z: 0.0,
+            rotation_x: 0.0,
+            rotation_y: 0.0,
+            scale_z: 1.0,
+        }
+    }
+}
+
+/// A display object drawn through its filters, kept so that the filters
+/// only run again when what the object draws changes.
+#[derive(Clone, Debug)]
+struct FilterCache {
+    /// What the object drew offscreen, before it was filtered.
+    source: Vec<u8>,
+    width: u32,
+    height: u32,
+
+    /// The filtered bitmap.
+    bitmap: BitmapHandle,
 }
 
 impl<'gc> Default for DisplayObjectBase<'gc> {
@@ -115,6 +195,13 @@ impl<'gc> Default for DisplayObjectBase<'gc> {
             maskee: None,
             sound_transform: Default::default(),
             flags: DisplayObjectFlags::VISIBLE,
+            filters: Vec::new(),
+            filter_cache: RefCell::new(None),
+            blend_mode: BlendMode::Normal,
+            scroll_rect: None,
+            scaling_grid: None,
+            transform_3d: None,
+            perspective_projection: None,
         }
     }
 }
@@ -370,6 +457,116 @@ impl<'gc> DisplayObjectBase<'gc> {
         self.flags.contains(DisplayObjectFlags::VISIBLE)
     }
 
+    fn filters(&self) -> &[BitmapFilter] {
+        &self.filters
+    }
+
+    fn set_filters(&mut self, filters: Vec<BitmapFilter>) {
+        self.filters = filters;
+        // Keep the cached bitmap around so that its texture can be reused.
+        if let Some(cache) = self.filter_cache.get_mut() {
+            cache.source.clear();
+        }
+    }
+
+    fn cache_as_bitmap(&self) -> bool {
+        // Objects with filters are always cached as bitmaps.
+        self.flags.contains(DisplayObjectFlags::CACHE_AS_BITMAP) || !self.filters.is_empty()
+    }
+
+    fn set_cache_as_bitmap(&mut self, value: bool) {
+        self.flags.set(DisplayObjectFlags::CACHE_AS_BITMAP, value);
+    }
+
+    fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
+    fn scroll_rect(&self) -> Option<BoundingBox> {
+        self.scroll_rect.clone()
+    }
+
+    fn set_scroll_rect(&mut self, scroll_rect: Option<BoundingBox>) {
+        self.scroll_rect = scroll_rect;
+    }
+
+    fn scaling_grid(&self) -> Option<BoundingBox> {
+        self.scaling_grid.clone()
+    }
+
+    fn set_scaling_grid(&mut self, scaling_grid: Option<BoundingBox>) {
+        self.scaling_grid = scaling_grid;
+    }
+
+    fn transform_3d(&self) -> Option<Transform3D> {
+        self.transform_3d
+    }
+
+    fn set_transform_3d(&mut self, transform_3d: Option<Transform3D>) {
+        self.set_transformed_by_script(true);
+        self.transform_3d = transform_3d;
+    }
+
+    fn perspective_projection(&self) -> Option<PerspectiveProjection> {
+        self.perspective_projection
+    }
+
+    fn set_perspective_projection(&mut self, projection: Option<PerspectiveProjection>) {
+        self.perspective_projection = projection;
+    }
+
+    /// Filter what this display object drew offscreen, giving a bitmap of
+    /// the result.
+    ///
+    /// If the object drew the same thing as last time, the bitmap from last
+    /// time is reused without filtering again.
+    fn filter(
+        &self,
+        renderer: &mut dyn RenderBackend,
+        width: u32,
+        height: u32,
+        source: Vec<u8>,
+    ) -> Option<BitmapHandle> {
+        let mut cache = self.filter_cache.borrow_mut();
+        if let Some(cache) = &*cache {
+            if cache.width == width && cache.height == height && cache.source == source {
+                return Some(cache.bitmap);
+            }
+        }
+
+        let mut image = FilterImage::from_rgba(width, height, &source);
+        for filter in &self.filters {
+            image = filter.apply(&image);
+        }
+        let rgba = image.to_rgba();
+
+        let bitmap = match &*cache {
+            Some(cache) if cache.width == width && cache.height == height => {
+                renderer.update_texture(cache.bitmap, width, height, rgba)
+            }
+            _ => renderer.register_bitmap_raw(width, height, rgba),
+        };
+        match bitmap {
+            Ok(bitmap) => {
+                *cache = Some(FilterCache {
+                    source,
+                    width,
+                    height,
+                    bitmap,
+                });
+                Some(bitmap)
+            }
+            Err(e) => {
+                log::error!("Couldn't upload a filtered display object: {}", e);
+                None
+            }
+        }
+    }
+
     fn set_visible(&mut self, value: bool) {
         self.flags.set(DisplayObjectFlags::VISIBLE, value);
     }
@@ -447,35 +644,339 @@ pub fn render_base<'gc>(this: DisplayObject<'gc>, context: &mut RenderContext<'_
     if this.maskee().is_some() {
         return;
     }
-    context.transform_stack.push(&*this.transform());
+    // An object placed in 3D is projected onto its parent's plane, and isn't
+    // drawn at all if it's behind the viewer.
+    if this.transform_3d().is_some() {
+        match this.projected_matrix(context.stage.stage_size()) {
+            Some(matrix) => context.transform_stack.push(&Transform {
+                matrix,
+                color_transform: *this.color_transform(),
+            }),
+            None => return,
+        }
+    } else {
+        context.transform_stack.push(&*this.transform());
+    }
+
+    // Like filters, blend modes can't be applied while drawing offscreen.
+    let blend_mode = if context.is_offscreen {
+        BlendMode::Normal
+    } else {
+        effective_blend_mode(this)
+    };
+    if blend_mode != BlendMode::Normal {
+        context.renderer.push_blend_mode(blend_mode);
+    }
+
+    // A scrolled object is clipped to its scroll rectangle, and its contents
+    // are moved so that the rectangle's corner is at its origin.
+    let scroll_rect = this.scroll_rect();
+    let scroll_mask = scroll_rect.as_ref().map(|scroll_rect| {
+        context.transform_stack.transform().matrix
+            * Matrix::create_box(
+                scroll_rect.width().to_pixels() as f32,
+                scroll_rect.height().to_pixels() as f32,
+                0.0,
+                Twips::ZERO,
+                Twips::ZERO,
+            )
+    });
+    if let (Some(scroll_rect), Some(scroll_mask)) = (&scroll_rect, &scroll_mask) {
+        context.renderer.push_mask();
+        context
+            .renderer
+            .draw_rect(Color::from_rgb(0, 0xff), scroll_mask);
+        context.renderer.activate_mask();
+        context.transform_stack.push(&Transform {
+            matrix: Matrix::translate(
+                Twips::new(-scroll_rect.x_min.get()),
+                Twips::new(-scroll_rect.y_min.get()),
+            ),
+            color_transform: Default::default(),
+        });
+    }
 
     let mask = this.masker();
     let mut mask_transform = crate::transform::Transform::default();
+    let mut alpha_mask = false;
     if let Some(m) = mask {
         mask_transform.matrix = this.global_to_local_matrix();
         mask_transform.matrix *= m.local_to_global_matrix();
-        context.renderer.push_mask();
-        context.allow_mask = false;
-        context.transform_stack.push(&mask_transform);
-        m.render_self(context);
-        context.transform_stack.pop();
-        context.allow_mask = true;
-        context.renderer.activate_mask();
+        // When both the mask and the masked object are cached as bitmaps,
+        // the mask is applied by its alpha. The content is drawn first, and
+        // the mask is drawn over it afterwards.
+        if this.cache_as_bitmap() && m.cache_as_bitmap() {
+            alpha_mask = context.renderer.push_alpha_mask();
+        }
+        if !alpha_mask {
+            context.renderer.push_mask();
+            context.allow_mask = false;
+            context.transform_stack.push(&mask_transform);
+            m.render_self(context);
+            context.transform_stack.pop();
+            context.allow_mask = true;
+            context.renderer.activate_mask();
+        }
+    }
+    // Filters can't be drawn while already drawing offscreen, so objects
+    // drawn into a bitmap are drawn without them.
+    if this.filters().is_empty() || context.is_offscreen {
+        render_sliced(this, context);
+    } else {
+        render_filtered(this, context);
     }
-    this.render_self(context);
     if let Some(m) = mask {
-        context.renderer.deactivate_mask();
-        context.allow_mask = false;
-        context.transform_stack.push(&mask_transform);
-        m.render_self(context);
+        if alpha_mask {
+            // An alpha mask is drawn in full, including its filters.
+            context.renderer.activate_alpha_mask();
+            context.allow_mask = false;
+            context.transform_stack.push(&mask_transform);
+            if m.filters().is_empty() || context.is_offscreen {
+                m.render_self(context);
+            } else {
+                render_filtered(m, context);
+            }
+            context.transform_stack.pop();
+            context.allow_mask = true;
+            context.renderer.pop_alpha_mask();
+        } else {
+            context.renderer.deactivate_mask();
+            context.allow_mask = false;
+            context.transform_stack.push(&mask_transform);
+            m.render_self(context);
+            context.transform_stack.pop();
+            context.allow_mask = true;
+            context.renderer.pop_mask();
+        }
+    }
+    if let Some(scroll_mask) = &scroll_mask {
         context.transform_stack.pop();
-        context.allow_mask = true;
+        context.renderer.deactivate_mask();
+        context
+            .renderer
+            .draw_rect(Color::from_rgb(0, 0xff), scroll_mask);
         context.renderer.pop_mask();
     }
+    if blend_mode != BlendMode::Normal {
+        context.renderer.pop_blend_mode();
+    }
 
     context.transform_stack.pop();
 }
 
+/// How far a display object's contents are scrolled by its scroll rectangle,
+/// in pixels.
+fn scroll_offset(scroll_rect: Option<BoundingBox>) -> (f64, f64) {
+    match scroll_rect {
+        Some(scroll_rect) => (scroll_rect.x_min.to_pixels(), scroll_rect.y_min.to_pixels()),
+        None => (0.0, 0.0),
+    }
+}
+
+/// The blend mode that a display object is drawn with.
+///
+/// `ALPHA` and `ERASE` change what's already been drawn within their
+/// parent, so they only work inside a parent with the `LAYER` blend mode.
+/// Anywhere else, the object is drawn normally.
+fn effective_blend_mode(this: DisplayObject<'_>) -> BlendMode {
+    match this.blend_mode() {
+        BlendMode::Alpha | BlendMode::Erase
+            if this.parent().map(|parent| parent.blend_mode()) != Some(BlendMode::Layer) =>
+        {
+            BlendMode::Normal
+        }
+        blend_mode => blend_mode,
+    }
+}
+
+/// Draw a display object through its filters.
+///
+/// The object is drawn offscreen into a bitmap covering its bounds on the
+/// screen, grown by however far its filters can spread it. The bitmap is
+/// filtered, then drawn in place of the object.
+fn render_filtered<'gc>(this: DisplayObject<'gc>, context: &mut RenderContext<'_, 'gc>) {
+    /// The largest offscreen bitmap that an object is drawn into, in pixels.
+    const MAX_SIZE: i32 = 4096;
+
+    let transform = context.transform_stack.transform().clone();
+    // The contents of a scrolled object have already been moved by its
+    // scroll rectangle, but its bounds are measured from before that.
+    let bounds_matrix = match this.scroll_rect() {
+        Some(scroll_rect) => {
+            transform.matrix * Matrix::translate(scroll_rect.x_min, scroll_rect.y_min)
+        }
+        None => transform.matrix,
+    };
+    let bounds = this.bounds_with_transform(&bounds_matrix);
+    let (pad_x, pad_y) = this
+        .filters()
+        .iter()
+        .map(|filter| filter.padding())
+        .fold((0, 0), |(x, y), (pad_x, pad_y)| (x + pad_x, y + pad_y));
+
+    // Nothing beyond the edges of the screen is drawn, except for what the
+    // filters could spread onto it.
+    let (viewport_width, viewport_height) = context.stage.viewport_size();
+    let x_min = (bounds.x_min.to_pixels().floor() as i32 - pad_x).max(-pad_x);
+    let y_min = (bounds.y_min.to_pixels().floor() as i32 - pad_y).max(-pad_y);
+    let x_max = (bounds.x_max.to_pixels().ceil() as i32 + pad_x).min(viewport_width as i32 + pad_x);
+    let y_max =
+        (bounds.y_max.to_pixels().ceil() as i32 + pad_y).min(viewport_height as i32 + pad_y);
+    if !bounds.valid || x_max <= x_min || y_max <= y_min {
+        return;
+    }
+    let width = (x_max - x_min).min(MAX_SIZE) as u32;
+    let height = (y_max - y_min).min(MAX_SIZE) as u32;
+
+    if !context.renderer.begin_offscreen(width, height) {
+        render_sliced(this, context);
+        return;
+    }
+
+    let offscreen_transform = Transform {
+        matrix: Matrix::translate(
+            Twips::from_pixels((-x_min).into()),
+            Twips::from_pixels((-y_min).into()),
+        ) * transform.matrix,
+        ..transform
+    };
+    let mut transform_stack = TransformStack::new();
+    transform_stack.push(&offscreen_transform);
+    let mut offscreen_context = RenderContext {
+        renderer: &mut *context.renderer,
+        ui: &mut *context.ui,
+        library: context.library,
+        transform_stack: &mut transform_stack,
+        stage: context.stage,
+        clip_depth_stack: vec![],
+        allow_mask: true,
+        cursor_overlay: None,
+        is_offscreen: true,
+    };
+    render_sliced(this, &mut offscreen_context);
+
+    let source = match context.renderer.end_offscreen() {
+        Some(RenderBitmap {
+            data: BitmapFormat::Rgba(rgba),
+            ..
+        }) => rgba,
+        _ => return,
+    };
+
+    // The object's color transform was applied as it was drawn offscreen.
+    if let Some(bitmap) = this.filter(context.renderer, width, height, source) {
+        let transform = Transform {
+            matrix: Matrix::translate(
+                Twips::from_pixels(x_min.into()),
+                Twips::from_pixels(y_min.into()),
+            ),
+            color_transform: Default::default(),
+        };
+        context.renderer.render_bitmap(bitmap, &transform, false);
+    }
+}
+
+/// Draw a display object's contents, sliced by its scaling grid.
+///
+/// Each of the nine regions of the grid is drawn separately, clipped to
+/// where it ends up. Objects without a grid, or that are rotated, skewed or
+/// flipped, are drawn as they are.
+fn render_sliced<'gc>(this: DisplayObject<'gc>, context: &mut RenderContext<'_, 'gc>) {
+    let matrix = *this.matrix();
+    let is_sliceable = matrix.b == 0.0
+        && matrix.c == 0.0
+        && matrix.a > 0.0
+        && matrix.d > 0.0
+        && (matrix.a != 1.0 || matrix.d != 1.0);
+    let bounds = this.bounds();
+    let grid = match this.scaling_grid() {
+        Some(grid) if is_sliceable && grid.valid && bounds.valid => grid,
+        _ => {
+            this.render_self(context);
+            return;
+        }
+    };
+
+    let columns = scaling_grid_slices(bounds.x_min, grid.x_min, grid.x_max, bounds.x_max, matrix.a);
+    let rows = scaling_grid_slices(bounds.y_min, grid.y_min, grid.y_max, bounds.y_max, matrix.d);
+    let base_matrix = context.transform_stack.transform().matrix;
+    for &(src_x_min, src_x_max, x_min, x_max) in &columns {
+        for &(src_y_min, src_y_max, y_min, y_max) in &rows {
+            if src_x_max <= src_x_min || src_y_max <= src_y_min || x_max <= x_min || y_max <= y_min
+            {
+                continue;
+            }
+            let region_mask = base_matrix
+                * Matrix::create_box(
+                    ((x_max - x_min) / Twips::TWIPS_PER_PIXEL) as f32,
+                    ((y_max - y_min) / Twips::TWIPS_PER_PIXEL) as f32,
+                    0.0,
+                    Twips::new(x_min as i32),
+                    Twips::new(y_min as i32),
+                );
+            let scale_x = (x_max - x_min) / (src_x_max - src_x_min);
+            let scale_y = (y_max - y_min) / (src_y_max - src_y_min);
+
+            context.renderer.push_mask();
+            context
+                .renderer
+                .draw_rect(Color::from_rgb(0, 0xff), &region_mask);
+            context.renderer.activate_mask();
+            context.transform_stack.push(&Transform {
+                matrix: Matrix {
+                    a: scale_x as f32,
+                    b: 0.0,
+                    c: 0.0,
+                    d: scale_y as f32,
+                    tx: Twips::new((x_min - src_x_min * scale_x) as i32),
+                    ty: Twips::new((y_min - src_y_min * scale_y) as i32),
+                },
+                color_transform: Default::default(),
+            });
+            this.render_self(context);
+            context.transform_stack.pop();
+            context.renderer.deactivate_mask();
+            context
+                .renderer
+                .draw_rect(Color::from_rgb(0, 0xff), &region_mask);
+            context.renderer.pop_mask();
+        }
+    }
+}
+
+/// Split the span from `min` to `max` into three at the edges of a scaling
+/// grid, giving the start and end of each part, and where each part is
+/// drawn to in the same unscaled space, in twips.
+///
+/// The parts outside of the grid keep their size once scaled by `scale`,
+/// unless there isn't room for them, in which case they share the space.
+fn scaling_grid_slices(
+    min: Twips,
+    grid_min: Twips,
+    grid_max: Twips,
+    max: Twips,
+    scale: f32,
+) -> [(f64, f64, f64, f64); 3] {
+    let min = f64::from(min.get());
+    let max = f64::from(max.get());
+    let grid_min = f64::from(grid_min.get()).max(min).min(max);
+    let grid_max = f64::from(grid_max.get()).max(grid_min).min(max);
+
+    let outside = (grid_min - min) + (max - grid_max);
+    let outside_scale = if outside / f64::from(scale) > max - min {
+        (max - min) / outside
+    } else {
+        1.0 / f64::from(scale)
+    };
+    let start = min + (grid_min - min) * outside_scale;
+    let end = max - (max - grid_max) * outside_scale;
+    [
+        (min, grid_min, min, start),
+        (grid_min, grid_max, start, end),
+        (grid_max, max, end, max),
+    ]
+}
+
 #[enum_trait_object(
     #[derive(Clone, Collect, Debug, Copy)]
     #[collect(no_drop)]
@@ -528,6 +1029,19 @@ pub trait TDisplayObject<'gc>:
     /// it to the bounding box. This gives a tighter AABB then if we simply transformed
     /// the overall AABB.
     fn bounds_with_transform(&self, matrix: &Matrix) -> BoundingBox {
+        // A scrolled object's bounds are its scroll rectangle, whatever its
+        // contents are.
+        if let Some(scroll_rect) = self.scroll_rect() {
+            let bounds = BoundingBox {
+                x_min: Twips::ZERO,
+                y_min: Twips::ZERO,
+                x_max: scroll_rect.width(),
+                y_max: scroll_rect.height(),
+                valid: true,
+            };
+            return bounds.transform(matrix);
+        }
+
         let mut bounds = self.self_bounds().transform(matrix);
 
         if let Some(ctr) = self.as_container() {
@@ -555,10 +1069,240 @@ pub trait TDisplayObject<'gc>:
         color_transform: &ColorTransform,
     );
 
+    /// The rectangle of this display object's contents that's shown, if it's
+    /// scrolled. Returned by the `scrollRect` ActionScript property.
+    fn scroll_rect(&self) -> Option<BoundingBox>;
+
+    /// Sets the rectangle of this display object's contents that's shown.
+    /// Set by the `scrollRect` ActionScript property.
+    fn set_scroll_rect(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        scroll_rect: Option<BoundingBox>,
+    );
+
+    /// The grid that slices this display object into nine regions when it's
+    /// scaled, so that its corners keep their size and its edges only stretch
+    /// along their length. Returned by the `scale9Grid` ActionScript property.
+    fn scaling_grid(&self) -> Option<BoundingBox>;
+
+    /// Sets the grid that slices this display object when it's scaled.
+    /// Set by the `scale9Grid` ActionScript property and by `DefineScalingGrid`
+    /// tags.
+    fn set_scaling_grid(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        scaling_grid: Option<BoundingBox>,
+    );
+
+    /// The placement of this display object in 3D space, if it's been moved
+    /// out of its parent's plane by the `z`, `rotationX`, `rotationY` or
+    /// `scaleZ` ActionScript properties.
+    fn transform_3d(&self) -> Option<Transform3D>;
+
+    /// Sets the placement of this display object in 3D space.
+    fn set_transform_3d(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        transform_3d: Option<Transform3D>,
+    );
+
+    /// The projection that this display object's descendants are drawn
+    /// through, if it's been given its own by `transform.perspectiveProjection`.
+    fn perspective_projection(&self) -> Option<PerspectiveProjection>;
+
+    /// Sets the projection that this display object's descendants are drawn
+    /// through.
+    fn set_perspective_projection(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        projection: Option<PerspectiveProjection>,
+    );
+
+    /// Returns the matrix that places this object in its parent's 3D space.
+    ///
+    /// The object is rotated around the X and Y axes after its 2D scale and
+    /// skew, but in the frame of its 2D rotation, so that `rotationZ` stays
+    /// the same as `rotation`.
+    fn matrix3d(&self) -> Matrix3D {
+        let matrix = *self.matrix();
+        let transform_3d = match self.transform_3d() {
+            Some(transform_3d) => transform_3d,
+            None => return Matrix3D::from_matrix(&matrix),
+        };
+        let rotation = f64::from(matrix.b).atan2(f64::from(matrix.a)).to_degrees();
+        let linear = Matrix {
+            tx: Twips::ZERO,
+            ty: Twips::ZERO,
+            ..matrix
+        };
+        Matrix3D::translation(matrix.tx.to_pixels(), matrix.ty.to_pixels(), transform_3d.z)
+            * Matrix3D::rotation(rotation, [0.0, 0.0, 1.0])
+            * Matrix3D::rotation(transform_3d.rotation_y, [0.0, 1.0, 0.0])
+            * Matrix3D::rotation(transform_3d.rotation_x, [1.0, 0.0, 0.0])
+            * Matrix3D::rotation(-rotation, [0.0, 0.0, 1.0])
+            * Matrix3D::from_matrix(&linear)
+            * Matrix3D::scale(1.0, 1.0, transform_3d.scale_z)
+    }
+
+    /// The projection that this object is drawn through when it's placed in
+    /// 3D, with its centre moved into the parent's space.
+    ///
+    /// This is the projection of the nearest ancestor that has one, or else
+    /// one looking at the centre of the stage.
+    fn inherited_projection(&self, stage_size: (u32, u32)) -> PerspectiveProjection {
+        let mut node = self.parent();
+        let mut owner = None;
+        while let Some(ancestor) = node {
+            if let Some(projection) = ancestor.perspective_projection() {
+                owner = Some((ancestor, projection));
+                break;
+            }
+            node = ancestor.parent();
+        }
+
+        let mut projection = match owner {
+            Some((_, projection)) => projection,
+            None => PerspectiveProjection::for_stage(stage_size),
+        };
+        let (center_x, center_y) = projection.projection_center;
+        let mut center = (Twips::from_pixels(center_x), Twips::from_pixels(center_y));
+        if let Some((ancestor, _)) = owner {
+            if ancestor.as_stage().is_none() {
+                center = ancestor.local_to_global(center);
+            }
+        }
+        if let Some(parent) = self.parent() {
+            if parent.as_stage().is_none() {
+                center = parent.global_to_local(center);
+            }
+        }
+        projection.projection_center = (center.0.to_pixels(), center.1.to_pixels());
+        projection
+    }
+
+    /// Returns the matrix that draws this object's contents projected onto
+    /// its parent's plane, or `None` if it's behind the viewer.
+    ///
+    /// Perspective can't be drawn by a 2D matrix, so the projection is
+    /// approximated by the matrix that matches it at the centre of the
+    /// object's bounds.
+    fn projected_matrix(&self, stage_size: (u32, u32)) -> Option<Matrix> {
+        let projection = self.inherited_projection(stage_size);
+        let focal_length = projection.focal_length(stage_size.0.into());
+        let matrix3d = self.matrix3d();
+        let project = |x: f64, y: f64| {
+            projection.project(focal_length, matrix3d.transform_point([x, y, 0.0]))
+        };
+
+        let bounds = self.bounds();
+        let (x, y) = if bounds.valid {
+            (
+                (bounds.x_min + bounds.x_max).to_pixels() / 2.0,
+                (bounds.y_min + bounds.y_max).to_pixels() / 2.0,
+            )
+        } else {
+            (0.0, 0.0)
+        };
+        let origin = project(x, y)?;
+        let right = project(x + 1.0, y)?;
+        let down = project(x, y + 1.0)?;
+        let (a, b) = (right.0 - origin.0, right.1 - origin.1);
+        let (c, d) = (down.0 - origin.0, down.1 - origin.1);
+        Some(Matrix {
+            a: a as f32,
+            b: b as f32,
+            c: c as f32,
+            d: d as f32,
+            tx: Twips::from_pixels(origin.0 - a * x - c * y),
+            ty: Twips::from_pixels(origin.1 - b * x - d * y),
+        })
+    }
+
+    /// Converts a point in this object's 3D space to a position on the
+    /// stage, projecting it onto its parent's plane.
+    /// Returns `None` if the point is behind the viewer.
+    fn local_3d_to_global(
+        &self,
+        point: [f64; 3],
+        stage_size: (u32, u32),
+    ) -> Option<(Twips, Twips)> {
+        let (scroll_x, scroll_y) = scroll_offset(self.scroll_rect());
+        let point =
+            self.matrix3d()
+                .transform_point([point[0] - scroll_x, point[1] - scroll_y, point[2]]);
+        let projection = self.inherited_projection(stage_size);
+        let (x, y) = projection.project(projection.focal_length(stage_size.0.into()), point)?;
+        let position = (Twips::from_pixels(x), Twips::from_pixels(y));
+        Some(match self.parent() {
+            Some(parent) if parent.as_stage().is_none() => parent.local_to_global(position),
+            _ => position,
+        })
+    }
+
+    /// Converts a position on the stage to the point on this object's plane
+    /// that's seen there, through the projection it's drawn with.
+    /// Returns `None` if the object is seen edge-on.
+    fn global_to_local_3d(
+        &self,
+        global: (Twips, Twips),
+        stage_size: (u32, u32),
+    ) -> Option<[f64; 3]> {
+        let (x, y) = match self.parent() {
+            Some(parent) if parent.as_stage().is_none() => parent.global_to_local(global),
+            _ => global,
+        };
+        let projection = self.inherited_projection(stage_size);
+        let focal_length = projection.focal_length(stage_size.0.into());
+        let (center_x, center_y) = projection.projection_center;
+
+        // Follow the line of sight from the viewer through the point on the
+        // parent's plane, until it meets this object's plane.
+        let inverse = self.matrix3d().inverse()?;
+        let eye = inverse.transform_point([center_x, center_y, -focal_length]);
+        let target = inverse.transform_point([x.to_pixels(), y.to_pixels(), 0.0]);
+        let direction = [target[0] - eye[0], target[1] - eye[1], target[2] - eye[2]];
+        if direction[2] == 0.0 {
+            return None;
+        }
+        let distance = -eye[2] / direction[2];
+        let (scroll_x, scroll_y) = scroll_offset(self.scroll_rect());
+        Some([
+            eye[0] + direction[0] * distance + scroll_x,
+            eye[1] + direction[1] * distance + scroll_y,
+            0.0,
+        ])
+    }
+
+    /// Returns the matrix for transforming from this object's contents to its
+    /// parent's space: its own matrix, after scrolling its contents.
+    fn scrolled_matrix(&self) -> Matrix {
+        let matrix = *self.matrix();
+        match self.scroll_rect() {
+            Some(scroll_rect) => {
+                matrix
+                    * Matrix::translate(
+                        Twips::new(-scroll_rect.x_min.get()),
+                        Twips::new(-scroll_rect.y_min.get()),
+                    )
+            }
+            None => matrix,
+        }
+    }
+
+    /// Whether a point on the stage is inside this object's scroll rectangle.
+    /// Always true for objects that aren't scrolled.
+    fn hit_test_scroll_rect(&self, point: (Twips, Twips)) -> bool {
+        match self.scroll_rect() {
+            Some(scroll_rect) => scroll_rect.contains(self.global_to_local(point)),
+            None => true,
+        }
+    }
+
     /// Returns the matrix for transforming from this object's local space to global stage space.
     fn local_to_global_matrix(&self) -> Matrix {
         let mut node = self.parent();
-        let mut matrix = *self.matrix();
+        let mut matrix = self.scrolled_matrix();
         while let Some(display_object) = node {
             // TODO: We don't want to include the stage transform because it includes the scale
             // mode and alignment transform, but the AS APIs expect "global" to be relative to the
@@ -567,7 +1311,7 @@ pub trait TDisplayObject<'gc>:
             if display_object.as_stage().is_some() {
                 break;
             }
-            matrix = *display_object.matrix() * matrix;
+            matrix = display_object.scrolled_matrix() * matrix;
             node = display_object.parent();
         }
         matrix
@@ -586,8 +1330,27 @@ pub trait TDisplayObject<'gc>:
     }
 
     /// Converts a local position on the stage to a local position on this display object
+    ///
+    /// Each ancestor's transform is undone in turn at full precision, and the result is
+    /// rounded to the nearest twip only once, matching the values Flash reports for
+    /// `mouseX`/`_xmouse` inside scaled or rotated clips.
     fn global_to_local(&self, global: (Twips, Twips)) -> (Twips, Twips) {
-        self.global_to_local_matrix() * global
+        let mut matrices = vec![self.scrolled_matrix()];
+        let mut node = self.parent();
+        while let Some(display_object) = node {
+            if display_object.as_stage().is_some() {
+                break;
+            }
+            matrices.push(display_object.scrolled_matrix());
+            node = display_object.parent();
+        }
+
+        let point = (global.0.get() as f64, global.1.get() as f64);
+        let point = matrices
+            .iter()
+            .rev()
+            .fold(point, |point, matrix| matrix.inverse_transform_point(point));
+        round_to_twips(point)
     }
 
     /// The `x` position in pixels of this display object in local space.
@@ -743,7 +1506,7 @@ pub trait TDisplayObject<'gc>:
     fn set_alpha(&self, gc_context: MutationContext<'gc, '_>, value: f64);
 
     fn name(&self) -> Ref<str>;
-    fn set_name(&self, gc_context: MutationContext<'gc, '_>, name: &str);
+    fn set_name(&self, context: &mut UpdateContext<'_, 'gc, '_>, name: &str);
 
     /// Returns the dot-syntax path to this display object, e.g. `_level0.foo.clip`
     fn path(&self) -> String {
@@ -855,6 +1618,42 @@ pub trait TDisplayObject<'gc>:
     /// Returned by the `_visible`/`visible` ActionScript properties.
     fn set_visible(&self, gc_context: MutationContext<'gc, '_>, value: bool);
 
+    /// The filters that this display object is drawn through.
+    /// Returned by the `filters` ActionScript property.
+    fn filters(&self) -> Ref<[BitmapFilter]>;
+
+    /// Sets the filters that this display object is drawn through.
+    /// Set by the `filters` ActionScript property and by `PlaceObject` tags.
+    fn set_filters(&self, gc_context: MutationContext<'gc, '_>, filters: Vec<BitmapFilter>);
+
+    /// Whether this display object is cached as a bitmap, which is always the
+    /// case for objects with filters. A mask that's cached as a bitmap masks
+    /// an object that's also cached as a bitmap by its alpha.
+    /// Returned by the `cacheAsBitmap` ActionScript property.
+    fn cache_as_bitmap(&self) -> bool;
+
+    /// Sets whether this display object is cached as a bitmap.
+    /// Set by the `cacheAsBitmap` ActionScript property and by `PlaceObject` tags.
+    fn set_cache_as_bitmap(&self, gc_context: MutationContext<'gc, '_>, value: bool);
+
+    /// How this display object is blended onto what's behind it.
+    /// Returned by the `blendMode` ActionScript property.
+    fn blend_mode(&self) -> BlendMode;
+
+    /// Sets how this display object is blended onto what's behind it.
+    /// Set by the `blendMode` ActionScript property and by `PlaceObject` tags.
+    fn set_blend_mode(&self, gc_context: MutationContext<'gc, '_>, blend_mode: BlendMode);
+
+    /// Filter what this display object drew offscreen for its filters,
+    /// giving a bitmap of the result.
+    fn filter(
+        &self,
+        renderer: &mut dyn RenderBackend,
+        width: u32,
+        height: u32,
+        source: Vec<u8>,
+    ) -> Option<BitmapHandle>;
+
     /// Whether this display object represents the root of loaded content.
     fn is_root(&self) -> bool;
 
@@ -938,9 +1737,10 @@ pub trait TDisplayObject<'gc>:
         let dobject_constr = context.avm2.classes().display_object;
 
         if let Err(e) = Avm2::broadcast_event(context, enter_frame_evt, dobject_constr) {
-            log::error!(
-                "Encountered AVM2 error when broadcasting enterFrame event: {}",
-                e
+            Avm2::report_uncaught_error(
+                context,
+                e,
+                "Encountered AVM2 error when broadcasting enterFrame event",
             );
         }
     }
@@ -984,9 +1784,10 @@ pub trait TDisplayObject<'gc>:
         let dobject_constr = context.avm2.classes().display_object;
 
         if let Err(e) = Avm2::broadcast_event(context, frame_constructed_evt, dobject_constr) {
-            log::error!(
-                "Encountered AVM2 error when broadcasting frameConstructed event: {}",
-                e
+            Avm2::report_uncaught_error(
+                context,
+                e,
+                "Encountered AVM2 error when broadcasting frameConstructed event",
             );
         }
     }
@@ -1009,9 +1810,10 @@ pub trait TDisplayObject<'gc>:
         let dobject_constr = context.avm2.classes().display_object;
 
         if let Err(e) = Avm2::broadcast_event(context, exit_frame_evt, dobject_constr) {
-            log::error!(
-                "Encountered AVM2 error when broadcasting exitFrame event: {}",
-                e
+            Avm2::report_uncaught_error(
+                context,
+                e,
+                "Encountered AVM2 error when broadcasting exitFrame event",
             );
         }
     }
@@ -1064,13 +1866,19 @@ pub trait TDisplayObject<'gc>:
     fn as_morph_shape(&self) -> Option<MorphShape<'gc>> {
         None
     }
+    fn as_text(&self) -> Option<Text<'gc>> {
+        None
+    }
     fn as_container(self) -> Option<DisplayObjectContainer<'gc>> {
         None
     }
     fn as_video(self) -> Option<Video<'gc>> {
         None
     }
-    fn as_drawing(&self, _gc_context: MutationContext<'gc, '_>) -> Option<RefMut<'_, Drawing>> {
+    fn as_drawing(
+        &self,
+        _gc_context: MutationContext<'gc, '_>,
+    ) -> Option<RefMut<'_, Drawing<'gc>>> {
         None
     }
     fn as_bitmap(self) -> Option<Bitmap<'gc>> {
@@ -1094,7 +1902,7 @@ pub trait TDisplayObject<'gc>:
             if let Some(name) = &place_object.name {
                 let encoding = swf::SwfStr::encoding_for_version(self.swf_version());
                 let name = name.to_str_lossy(encoding);
-                self.set_name(context.gc_context, &name);
+                self.set_name(context, &name);
             }
             if let Some(clip_depth) = place_object.clip_depth {
                 self.set_clip_depth(context.gc_context, clip_depth.into());
@@ -1131,6 +1939,18 @@ pub trait TDisplayObject<'gc>:
                     log::error!("No movie when trying to set clip event");
                 }
             }
+            if let Some(filters) = &place_object.filters {
+                self.set_filters(
+                    context.gc_context,
+                    filters.iter().filter_map(BitmapFilter::from_swf).collect(),
+                );
+            }
+            if let Some(blend_mode) = place_object.blend_mode {
+                self.set_blend_mode(context.gc_context, blend_mode);
+            }
+            if let Some(is_bitmap_cached) = place_object.is_bitmap_cached {
+                self.set_cache_as_bitmap(context.gc_context, is_bitmap_cached);
+            }
             if self.swf_version() >= 11 {
                 if let Some(visible) = place_object.is_visible {
                     self.set_visible(context.gc_context, visible);
@@ -1339,7 +2159,7 @@ pub trait TDisplayObject<'gc>:
     fn set_default_instance_name(&self, context: &mut UpdateContext<'_, 'gc, '_>) {
         if self.name().is_empty() {
             let name = format!("instance{}", *context.instance_counter);
-            self.set_name(context.gc_context, &name);
+            self.set_name(context, &name);
             *context.instance_counter = context.instance_counter.wrapping_add(1);
         }
     }
@@ -1352,9 +2172,9 @@ pub trait TDisplayObject<'gc>:
         let vm_type = self.avm_type();
 
         if matches!(vm_type, AvmType::Avm2) {
-            self.set_name(context.gc_context, &format!("root{}", self.depth() + 1));
+            self.set_name(context, &format!("root{}", self.depth() + 1));
         } else if matches!(vm_type, AvmType::Avm1) {
-            self.set_name(context.gc_context, "");
+            self.set_name(context, "");
         }
     }
 
@@ -1452,8 +2272,9 @@ macro_rules! impl_display_object_sansbounds {
         fn name(&self) -> std::cell::Ref<str> {
             std::cell::Ref::map(self.0.read(), |o| o.$field.name())
         }
-        fn set_name(&self, context: gc_arena::MutationContext<'gc, '_>, name: &str) {
-            self.0.write(context).$field.set_name(name)
+        fn set_name(&self, context: &mut crate::context::UpdateContext<'_, 'gc, '_>, name: &str) {
+            self.0.write(context.gc_context).$field.set_name(name);
+            context.display_list_changed();
         }
         fn clip_depth(&self) -> crate::prelude::Depth {
             self.0.read().$field.clip_depth()
@@ -1553,6 +2374,84 @@ macro_rules! impl_display_object_sansbounds {
         fn set_visible(&self, context: gc_arena::MutationContext<'gc, '_>, value: bool) {
             self.0.write(context).$field.set_visible(value);
         }
+        fn filters(&self) -> std::cell::Ref<[crate::bitmap::filter::BitmapFilter]> {
+            std::cell::Ref::map(self.0.read(), |r| r.$field.filters())
+        }
+        fn set_filters(
+            &self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            filters: Vec<crate::bitmap::filter::BitmapFilter>,
+        ) {
+            self.0.write(context).$field.set_filters(filters);
+        }
+        fn cache_as_bitmap(&self) -> bool {
+            self.0.read().$field.cache_as_bitmap()
+        }
+        fn set_cache_as_bitmap(&self, context: gc_arena::MutationContext<'gc, '_>, value: bool) {
+            self.0.write(context).$field.set_cache_as_bitmap(value);
+        }
+        fn blend_mode(&self) -> swf::BlendMode {
+            self.0.read().$field.blend_mode()
+        }
+        fn scroll_rect(&self) -> Option<crate::prelude::BoundingBox> {
+            self.0.read().$field.scroll_rect()
+        }
+        fn set_scroll_rect(
+            &self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            scroll_rect: Option<crate::prelude::BoundingBox>,
+        ) {
+            self.0.write(context).$field.set_scroll_rect(scroll_rect);
+        }
+        fn scaling_grid(&self) -> Option<crate::prelude::BoundingBox> {
+            self.0.read().$field.scaling_grid()
+        }
+        fn set_scaling_grid(
+            &self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            scaling_grid: Option<crate::prelude::BoundingBox>,
+        ) {
+            self.0.write(context).$field.set_scaling_grid(scaling_grid);
+        }
+        fn transform_3d(&self) -> Option<crate::display_object::Transform3D> {
+            self.0.read().$field.transform_3d()
+        }
+        fn set_transform_3d(
+            &self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            transform_3d: Option<crate::display_object::Transform3D>,
+        ) {
+            self.0.write(context).$field.set_transform_3d(transform_3d);
+        }
+        fn perspective_projection(&self) -> Option<crate::matrix3d::PerspectiveProjection> {
+            self.0.read().$field.perspective_projection()
+        }
+        fn set_perspective_projection(
+            &self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            projection: Option<crate::matrix3d::PerspectiveProjection>,
+        ) {
+            self.0
+                .write(context)
+                .$field
+                .set_perspective_projection(projection);
+        }
+        fn set_blend_mode(
+            &self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            blend_mode: swf::BlendMode,
+        ) {
+            self.0.write(context).$field.set_blend_mode(blend_mode);
+        }
+        fn filter(
+            &self,
+            renderer: &mut dyn crate::backend::render::RenderBackend,
+            width: u32,
+            height: u32,
+            source: Vec<u8>,
+        ) -> Option<crate::backend::render::BitmapHandle> {
+            self.0.read().$field.filter(renderer, width, height, source)
+        }
         fn is_root(&self) -> bool {
             self.0.read().$field.is_root()
         }
@@ -1655,7 +2554,7 @@ bitflags! {
     /// Bit flags used by `DisplayObject`.
     #[derive(Collect)]
     #[collect(no_drop)]
-    struct DisplayObjectFlags: u8 {
+    struct DisplayObjectFlags: u16 {
         /// Whether this object has been removed from the display list.
         /// Necessary in AVM1 to throw away queued actions from removed movie clips.
         const REMOVED                  = 1 << 0;
@@ -1685,6 +2584,9 @@ bitflags! {
         /// Whether this object has `_lockroot` set to true, in which case
         /// it becomes the _root of itself and of any children
         const LOCK_ROOT                = 1 << 7;
+
+        /// Whether this object has `cacheAsBitmap` set to true.
+        const CACHE_AS_BITMAP          = 1 << 8;
     }
 }
 
@@ -1876,3 +2778,111 @@ impl Default for SoundTransform {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::render::NullRenderer;
+
+    #[test]
+    fn filtered_bitmap_is_cached() {
+        let mut base = DisplayObjectBase::default();
+        base.set_filters(vec![BitmapFilter::Blur {
+            blur_x: 2.0,
+            blur_y: 2.0,
+            quality: 1,
+        }]);
+        let mut renderer = NullRenderer::new();
+        let source = vec![255; 4 * 4];
+
+        assert!(base.filter(&mut renderer, 2, 2, source.clone()).is_some());
+        base.filter_cache.get_mut().as_mut().unwrap().bitmap = BitmapHandle(5);
+
+        // Drawing the same pixels reuses the filtered bitmap.
+        assert_eq!(
+            base.filter(&mut renderer, 2, 2, source.clone()),
+            Some(BitmapHandle(5))
+        );
+
+        // New filters are applied even if the pixels are the same.
+        base.set_filters(vec![]);
+        assert!(base
+            .filter_cache
+            .get_mut()
+            .as_ref()
+            .unwrap()
+            .source
+            .is_empty());
+        assert_eq!(
+            base.filter(&mut renderer, 2, 2, source.clone()),
+            Some(BitmapHandle(0))
+        );
+        assert_eq!(base.filter_cache.get_mut().as_ref().unwrap().source, source);
+    }
+
+    #[test]
+    fn filtered_objects_are_cached_as_bitmaps() {
+        let mut base = DisplayObjectBase::default();
+        assert!(!base.cache_as_bitmap());
+
+        base.set_filters(vec![BitmapFilter::Blur {
+            blur_x: 2.0,
+            blur_y: 2.0,
+            quality: 1,
+        }]);
+        assert!(base.cache_as_bitmap());
+
+        // Turning caching off does nothing while there are filters.
+        base.set_cache_as_bitmap(false);
+        assert!(base.cache_as_bitmap());
+        base.set_filters(vec![]);
+        assert!(!base.cache_as_bitmap());
+
+        base.set_cache_as_bitmap(true);
+        assert!(base.cache_as_bitmap());
+    }
+
+    #[test]
+    fn scaling_grid_corners_keep_their_size() {
+        let slices = |grid_min, grid_max, scale| {
+            scaling_grid_slices(
+                Twips::ZERO,
+                Twips::from_pixels(grid_min),
+                Twips::from_pixels(grid_max),
+                Twips::from_pixels(100.0),
+                scale,
+            )
+        };
+
+        // Scaled up, the corners are drawn at half their size, which the
+        // scale doubles again.
+        assert_eq!(
+            slices(10.0, 90.0, 2.0),
+            [
+                (0.0, 200.0, 0.0, 100.0),
+                (200.0, 1800.0, 100.0, 1900.0),
+                (1800.0, 2000.0, 1900.0, 2000.0),
+            ]
+        );
+
+        // Too small for the corners, they share all the space.
+        assert_eq!(
+            slices(10.0, 90.0, 0.1),
+            [
+                (0.0, 200.0, 0.0, 1000.0),
+                (200.0, 1800.0, 1000.0, 1000.0),
+                (1800.0, 2000.0, 1000.0, 2000.0),
+            ]
+        );
+
+        // A grid bigger than the object leaves nothing outside of it.
+        assert_eq!(
+            slices(-10.0, 150.0, 2.0),
+            [
+                (0.0, 0.0, 0.0, 0.0),
+                (0.0, 2000.0, 0.0, 2000.0),
+                (2000.0, 2000.0, 2000.0, 2000.0),
+            ]
+        );
+    }
+}
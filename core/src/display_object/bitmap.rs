@@ -39,6 +39,7 @@ pub struct BitmapData<'gc> {
     /// that it can be accessed without a mutation context.
     ///
     /// If this is `None`, then the bitmap does not render anything.
+    #[collect(require_static)]
     bitmap_handle: Option<BitmapHandle>,
 
     /// Whether or not bitmap smoothing is enabled.
@@ -249,17 +250,19 @@ impl<'gc> TDisplayObject<'gc> for Bitmap<'gc> {
     }
 
     fn render_self(&self, context: &mut RenderContext) {
-        if !self.world_bounds().intersects(&context.stage.view_bounds()) {
+        if !context.is_offscreen && !self.world_bounds().intersects(&context.stage.view_bounds()) {
             // Off-screen; culled
             return;
         }
 
         let bitmap_data = self.0.read();
         if let Some(bitmap_handle) = bitmap_data.bitmap_handle {
+            // Smoothing is only honored at `HIGH` quality and above.
+            let smoothing = bitmap_data.smoothing && context.stage.quality().smooth_bitmaps();
             context.renderer.render_bitmap(
                 bitmap_handle,
                 context.transform_stack.transform(),
-                bitmap_data.smoothing,
+                smoothing,
             );
         }
     }
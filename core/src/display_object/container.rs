@@ -425,10 +425,12 @@ macro_rules! impl_display_object_container {
             child.set_parent(context.gc_context, Some(self.into()));
             child.set_place_frame(context.gc_context, 0);
             child.set_depth(context.gc_context, depth);
+            context.display_list_changed();
 
             if let Some(removed_child) = removed_child {
                 removed_child.unload(context);
                 removed_child.set_parent(context.gc_context, None);
+                context.display_list_changed();
             }
 
             removed_child
@@ -454,6 +456,7 @@ macro_rules! impl_display_object_container {
                 child,
                 depth,
             );
+            context.display_list_changed();
         }
 
         fn insert_at_index(
@@ -486,6 +489,7 @@ macro_rules! impl_display_object_container {
                 .write(context.gc_context)
                 .$field
                 .insert_at_id(child, index);
+            context.display_list_changed();
 
             if parent_changed {
                 dispatch_added_event(
@@ -507,6 +511,7 @@ macro_rules! impl_display_object_container {
                 .write(context.gc_context)
                 .$field
                 .swap_at_id(index1, index2);
+            context.display_list_changed();
         }
 
         fn remove_child(
@@ -533,6 +538,7 @@ macro_rules! impl_display_object_container {
             drop(write);
 
             if removed_from_depth_list || removed_from_render_list {
+                context.display_list_changed();
                 child.unload(context);
 
                 //TODO: This is an awful, *awful* hack to deal with the fact
@@ -540,6 +546,7 @@ macro_rules! impl_display_object_container {
                 //don't.
                 if !matches!(child.object2(), Avm2Value::Undefined) {
                     child.set_parent(context.gc_context, None);
+                    context.display_list_changed();
                 }
             }
 
@@ -572,11 +579,13 @@ macro_rules! impl_display_object_container {
                 write.$field.remove_child_from_depth_list(removed);
 
                 drop(write);
+                context.display_list_changed();
 
                 removed.unload(context);
 
                 if !matches!(removed.object2(), Avm2Value::Undefined) {
                     removed.set_parent(context.gc_context, None);
+                    context.display_list_changed();
                 }
 
                 write = self.0.write(context.gc_context);
@@ -591,7 +600,8 @@ macro_rules! impl_display_object_container {
                 dispatch_removed_event(removed, context);
             }
 
-            self.0.write(context.gc_context).$field.clear()
+            self.0.write(context.gc_context).$field.clear();
+            context.display_list_changed();
         }
 
         fn is_empty(self) -> bool {
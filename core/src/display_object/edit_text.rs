@@ -6,7 +6,8 @@ use crate::avm1::{
     Value as Avm1Value,
 };
 use crate::avm2::{
-    Activation as Avm2Activation, Object as Avm2Object, StageObject as Avm2StageObject,
+    Activation as Avm2Activation, Avm2, Object as Avm2Object, StageObject as Avm2StageObject,
+    TObject as Avm2TObject,
 };
 use crate::backend::ui::MouseCursor;
 use crate::context::{RenderContext, UpdateContext};
@@ -96,6 +97,13 @@ pub struct EditTextData<'gc> {
     /// If this is a password input field
     is_password: bool,
 
+    /// The characters that the user is allowed to enter.
+    restrict: EditTextRestrict,
+
+    /// The maximum number of characters that the user may enter, or 0 if
+    /// there is no limit.
+    max_chars: i32,
+
     /// If the text field should have a background. Only applied when has_border.
     has_background: bool,
 
@@ -115,7 +123,7 @@ pub struct EditTextData<'gc> {
     is_html: bool,
 
     /// The current border drawing.
-    drawing: Drawing,
+    drawing: Drawing<'gc>,
 
     /// Whether or not the width of the field should change in response to text
     /// changes, and in what direction should added or removed width should
@@ -129,6 +137,7 @@ pub struct EditTextData<'gc> {
     intrinsic_bounds: BoxBounds<Twips>,
 
     /// The current intrinsic bounds of the text field.
+    #[collect(require_static)]
     bounds: BoundingBox,
 
     /// The AVM1 object handle
@@ -149,6 +158,30 @@ pub struct EditTextData<'gc> {
     /// Whether or not this EditText has the current keyboard focus
     has_focus: bool,
 
+    /// Text that the user is composing with an input method. It is shown
+    /// underlined in place of the selection, but isn't part of the text until
+    /// it's committed.
+    composition: Option<String>,
+
+    /// The text spans with the composition in place of the selection, which
+    /// are laid out and rendered instead of `text_spans` while composing.
+    composed_spans: Option<FormatSpans>,
+
+    /// The text and selection from before each user edit, most recent last.
+    undo_history: Vec<(String, TextSelection)>,
+
+    /// The text and selection from before each undo, most recent last.
+    redo_history: Vec<(String, TextSelection)>,
+
+    /// Whether the last user edit was a typed character.
+    is_typing: bool,
+
+    /// When the user last pressed the mouse on this field, in milliseconds.
+    last_click_time: i64,
+
+    /// How many consecutive clicks the user has made on this field, from 1 to 3.
+    click_count: u8,
+
     /// Which rendering engine this text field will use.
     render_settings: TextRenderSettings,
 
@@ -320,6 +353,8 @@ impl<'gc> EditText<'gc> {
                 is_editable,
                 is_word_wrap,
                 is_password,
+                restrict: EditTextRestrict::from(None),
+                max_chars: swf_tag.max_length.unwrap_or(0).into(),
                 has_background,
                 background_color,
                 has_border,
@@ -337,6 +372,13 @@ impl<'gc> EditText<'gc> {
                 firing_variable_binding: false,
                 selection: None,
                 has_focus: false,
+                composition: None,
+                composed_spans: None,
+                undo_history: Vec::new(),
+                redo_history: Vec::new(),
+                is_typing: false,
+                last_click_time: 0,
+                click_count: 0,
                 render_settings: Default::default(),
                 hscroll: 0.0,
                 line_data,
@@ -375,7 +417,7 @@ impl<'gc> EditText<'gc> {
                 b: 0,
                 a: 0xFF,
             }),
-            max_length: Some(width as u16),
+            max_length: None,
             layout: Some(swf::TextLayout {
                 align: swf::TextAlign::Left,
                 left_margin: Twips::from_pixels(0.0),
@@ -554,6 +596,22 @@ impl<'gc> EditText<'gc> {
         self.relayout(context);
     }
 
+    pub fn restrict(self) -> Option<String> {
+        self.0.read().restrict.value().map(str::to_string)
+    }
+
+    pub fn set_restrict(self, text: Option<&str>, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.0.write(context.gc_context).restrict = EditTextRestrict::from(text);
+    }
+
+    pub fn max_chars(self) -> i32 {
+        self.0.read().max_chars
+    }
+
+    pub fn set_max_chars(self, value: i32, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.0.write(context.gc_context).max_chars = value.max(0);
+    }
+
     pub fn set_multiline(self, is_multiline: bool, context: &mut UpdateContext<'_, 'gc, '_>) {
         self.0.write(context.gc_context).is_multiline = is_multiline;
         self.relayout(context);
@@ -796,6 +854,13 @@ impl<'gc> EditText<'gc> {
     /// Applies to each side.
     const INTERNAL_PADDING: f64 = 2.0;
 
+    /// The maximum number of user edits that can be undone.
+    const MAX_UNDO_HISTORY: usize = 100;
+
+    /// The longest time between two clicks for them to count as a double- or
+    /// triple-click, in milliseconds.
+    const MULTI_CLICK_MILLIS: i64 = 500;
+
     /// Relayout the `EditText`.
     ///
     /// This function operats exclusively with the text-span representation of
@@ -817,8 +882,29 @@ impl<'gc> EditText<'gc> {
             edit_text.text_spans.clear_displayed_text();
         }
 
+        let composed_spans = match (&edit_text.composition, edit_text.selection) {
+            (Some(composition), Some(selection)) => {
+                let mut spans = edit_text.text_spans.clone();
+                spans.replace_text(selection.start(), selection.end(), composition, None);
+                spans.set_text_format(
+                    selection.start(),
+                    selection.start() + composition.len(),
+                    &TextFormat {
+                        underline: Some(true),
+                        ..Default::default()
+                    },
+                );
+                Some(spans)
+            }
+            _ => None,
+        };
+        edit_text.composed_spans = composed_spans;
+
         let (new_layout, intrinsic_bounds) = LayoutBox::lower_from_text_spans(
-            &edit_text.text_spans,
+            edit_text
+                .composed_spans
+                .as_ref()
+                .unwrap_or(&edit_text.text_spans),
             context,
             movie,
             width,
@@ -961,7 +1047,18 @@ impl<'gc> EditText<'gc> {
         context.transform_stack.push(&box_transform);
 
         let edit_text = self.0.read();
-        let selection = edit_text.selection;
+
+        // While the user is composing, the caret follows the composition.
+        let (selection, displayed_text) = match (&edit_text.composition, &edit_text.composed_spans)
+        {
+            (Some(composition), Some(composed_spans)) => (
+                edit_text
+                    .selection
+                    .map(|s| TextSelection::for_position(s.start() + composition.len())),
+                composed_spans.displayed_text(),
+            ),
+            _ => (edit_text.selection, edit_text.text_spans.displayed_text()),
+        };
 
         let caret = if let LayoutContent::Text { start, end, .. } = &lbox.content() {
             if let Some(selection) = selection {
@@ -992,9 +1089,7 @@ impl<'gc> EditText<'gc> {
         // We're cheating a bit and not actually rendering text using the OS/web.
         // Instead, we embed an SWF version of Noto Sans to use as the "device font", and render
         // it the same as any other SWF outline text.
-        if let Some((text, _tf, font, params, color)) =
-            lbox.as_renderable_text(edit_text.text_spans.displayed_text())
-        {
+        if let Some((text, _tf, font, params, color)) = lbox.as_renderable_text(displayed_text) {
             let baseline_adjustment =
                 font.get_baseline_for_height(params.height()) - params.height();
             font.evaluate(
@@ -1220,6 +1315,180 @@ impl<'gc> EditText<'gc> {
         }
     }
 
+    /// The text covered by the current selection.
+    pub fn selected_text(self) -> String {
+        let read = self.0.read();
+        read.selection
+            .and_then(|selection| {
+                read.text_spans
+                    .text()
+                    .get(selection.start()..selection.end())
+            })
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    /// Select all of the text in this field.
+    pub fn select_all(self, gc_context: MutationContext<'gc, '_>) {
+        if self.is_selectable() {
+            let length = self.text_length();
+            self.set_selection(Some(TextSelection::for_range(0, length)), gc_context);
+        }
+    }
+
+    /// Copy the selected text to the clipboard.
+    ///
+    /// Password fields never expose their contents this way.
+    pub fn copy_selection(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        if !self.is_selectable() || self.is_password() {
+            return;
+        }
+
+        let text = self.selected_text();
+        if !text.is_empty() {
+            context.ui.set_clipboard_content(text);
+        }
+    }
+
+    /// Cut the selected text to the clipboard.
+    pub fn cut_selection(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        if !self.is_editable() || self.is_password() {
+            return;
+        }
+
+        let selection = match self.selection() {
+            Some(selection) if !selection.is_caret() => selection,
+            _ => return,
+        };
+
+        self.copy_selection(context);
+
+        let old_text = self.text();
+        self.replace_text(selection.start(), selection.end(), "", context);
+        self.set_selection(
+            Some(TextSelection::for_position(selection.start())),
+            context.gc_context,
+        );
+        self.push_undo_state(old_text, selection, false, context.gc_context);
+        self.notify_user_edit(context);
+    }
+
+    /// Paste text from the clipboard over the selection.
+    ///
+    /// Pasted text takes on the format of the text it replaces. Line breaks
+    /// are normalized, and only the first line is pasted into single-line
+    /// fields. Characters that `restrict` doesn't allow are dropped, and the
+    /// text is cut short if it would go over `maxChars`.
+    pub fn paste(self, text: &str, context: &mut UpdateContext<'_, 'gc, '_>) {
+        if !self.is_editable() {
+            return;
+        }
+
+        let selection = match self.selection() {
+            Some(selection) => selection,
+            None => return,
+        };
+
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        let text = if self.is_multiline() {
+            &text[..]
+        } else {
+            text.split('\n').next().unwrap_or_default()
+        };
+
+        let old_text = self.text();
+        let max_chars = self.max_chars();
+        let available = if max_chars > 0 {
+            let replaced_chars = old_text
+                .get(selection.start()..selection.end())
+                .map(|s| s.chars().count())
+                .unwrap_or_default();
+            (max_chars as usize).saturating_sub(old_text.chars().count() - replaced_chars)
+        } else {
+            usize::MAX
+        };
+
+        let pasted: String = {
+            let read = self.0.read();
+            text.chars()
+                .filter(|c| *c == '\n' || !c.is_control())
+                .filter_map(|c| {
+                    if c == '\n' {
+                        Some(c)
+                    } else {
+                        read.restrict.to_allowed(c)
+                    }
+                })
+                .take(available)
+                .collect()
+        };
+        if pasted.is_empty() || !self.dispatch_text_input_event(&pasted, context) {
+            return;
+        }
+
+        self.replace_text(selection.start(), selection.end(), &pasted, context);
+        self.set_selection(
+            Some(TextSelection::for_position(
+                selection.start() + pasted.len(),
+            )),
+            context.gc_context,
+        );
+        self.push_undo_state(old_text, selection, false, context.gc_context);
+        self.notify_user_edit(context);
+    }
+
+    /// Show text that the user is composing with an input method in place of
+    /// the selection, or stop showing it if `text` is empty.
+    ///
+    /// Password fields don't show compositions.
+    pub fn set_composition(self, text: &str, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let composition = if text.is_empty() {
+            None
+        } else if self.is_editable() && !self.is_password() && self.selection().is_some() {
+            Some(text.to_string())
+        } else {
+            return;
+        };
+
+        let mut write = self.0.write(context.gc_context);
+        if write.composition.is_none() && composition.is_none() {
+            return;
+        }
+        write.composition = composition;
+        drop(write);
+        self.relayout(context);
+    }
+
+    /// Enter text that the user has finished composing with an input method.
+    ///
+    /// It replaces the selection the same way as pasted text.
+    pub fn commit_composition(self, text: &str, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.set_composition("", context);
+        self.paste(text, context);
+    }
+
+    /// Where the input method should show its candidate window for this
+    /// field, in window pixels: just below its bottom left corner.
+    pub fn ime_position(self, context: &UpdateContext<'_, 'gc, '_>) -> (f64, f64) {
+        let bounds = self.world_bounds().transform(&context.stage.matrix());
+        (bounds.x_min.to_pixels(), bounds.y_max.to_pixels())
+    }
+
+    /// Move the end of the selection to follow the mouse while it is held
+    /// down, after a press on this field.
+    pub fn drag_selection(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        if !self.is_selectable() {
+            return;
+        }
+
+        if let Some(position) = self.screen_position_to_index(*context.mouse_position) {
+            let mut write = self.0.write(context.gc_context);
+            if let Some(selection) = write.selection.as_mut() {
+                selection.to = position;
+            }
+        }
+    }
+
     pub fn set_render_settings(
         self,
         gc_context: MutationContext<'gc, '_>,
@@ -1306,9 +1575,11 @@ impl<'gc> EditText<'gc> {
         }
 
         if let Some(selection) = self.selection() {
+            let old_text = self.text();
             let mut changed = false;
-            match character as u8 {
-                8 | 127 if !selection.is_caret() => {
+            let mut is_typing = false;
+            match character {
+                '\u{8}' | '\u{7f}' if !selection.is_caret() => {
                     // Backspace or delete with multiple characters selected
                     self.replace_text(selection.start(), selection.end(), "", context);
                     self.set_selection(
@@ -1317,12 +1588,15 @@ impl<'gc> EditText<'gc> {
                     );
                     changed = true;
                 }
-                8 => {
+                '\u{8}' => {
                     // Backspace with caret
                     if selection.start() > 0 {
-                        // Delete previous character
-                        let text = self.text();
-                        let start = string_utils::prev_char_boundary(&text, selection.start());
+                        // Delete previous character, or word if Ctrl is held
+                        let start = if context.ui.is_key_down(KeyCode::Control) {
+                            string_utils::prev_word_boundary(&old_text, selection.start())
+                        } else {
+                            string_utils::prev_char_boundary(&old_text, selection.start())
+                        };
                         self.replace_text(start, selection.start(), "", context);
                         self.set_selection(
                             Some(TextSelection::for_position(start)),
@@ -1331,18 +1605,41 @@ impl<'gc> EditText<'gc> {
                         changed = true;
                     }
                 }
-                127 => {
+                '\u{7f}' => {
                     // Delete with caret
                     if selection.end() < self.text_length() {
-                        // Delete next character
-                        let text = self.text();
-                        let end = string_utils::next_char_boundary(&text, selection.start());
+                        // Delete next character, or word if Ctrl is held
+                        let end = if context.ui.is_key_down(KeyCode::Control) {
+                            string_utils::next_word_boundary(&old_text, selection.start())
+                        } else {
+                            string_utils::next_char_boundary(&old_text, selection.start())
+                        };
                         self.replace_text(selection.start(), end, "", context);
                         // No need to change selection
                         changed = true;
                     }
                 }
-                code if !(code as char).is_control() => {
+                _ if !character.is_control() => {
+                    let character = match self.0.read().restrict.to_allowed(character) {
+                        Some(character) => character,
+                        None => return,
+                    };
+
+                    let max_chars = self.max_chars();
+                    if max_chars > 0 {
+                        let replaced_chars = old_text
+                            .get(selection.start()..selection.end())
+                            .map(|s| s.chars().count())
+                            .unwrap_or_default();
+                        if old_text.chars().count() - replaced_chars >= max_chars as usize {
+                            return;
+                        }
+                    }
+
+                    if !self.dispatch_text_input_event(&character.to_string(), context) {
+                        return;
+                    }
+
                     self.replace_text(
                         selection.start(),
                         selection.end(),
@@ -1355,22 +1652,128 @@ impl<'gc> EditText<'gc> {
                         context.gc_context,
                     );
                     changed = true;
+                    is_typing = true;
                 }
                 _ => {}
             }
 
             if changed {
-                let globals = context.avm1.global_object_cell();
-                let swf_version = context.swf.version();
-                let mut activation = Avm1Activation::from_nothing(
-                    context.reborrow(),
-                    ActivationIdentifier::root("[Propagate Text Binding]"),
-                    swf_version,
-                    globals,
-                    self.into(),
+                self.push_undo_state(old_text, selection, is_typing, context.gc_context);
+                self.notify_user_edit(context);
+            }
+        }
+    }
+
+    /// Notify scripts that the user has changed the text of this field.
+    fn notify_user_edit(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let globals = context.avm1.global_object_cell();
+        let swf_version = context.swf.version();
+        let mut activation = Avm1Activation::from_nothing(
+            context.reborrow(),
+            ActivationIdentifier::root("[Propagate Text Binding]"),
+            swf_version,
+            globals,
+            self.into(),
+        );
+        self.propagate_text_binding(&mut activation);
+        self.on_changed(&mut activation);
+    }
+
+    /// Record the text and selection from before a user edit, so that the
+    /// edit can be undone.
+    ///
+    /// Consecutively typed characters are undone together.
+    fn push_undo_state(
+        self,
+        text: String,
+        selection: TextSelection,
+        is_typing: bool,
+        gc_context: MutationContext<'gc, '_>,
+    ) {
+        let mut edit_text = self.0.write(gc_context);
+        if !(is_typing && edit_text.is_typing) {
+            edit_text.undo_history.push((text, selection));
+            if edit_text.undo_history.len() > Self::MAX_UNDO_HISTORY {
+                edit_text.undo_history.remove(0);
+            }
+        }
+        edit_text.is_typing = is_typing;
+        edit_text.redo_history.clear();
+    }
+
+    /// Undo the user's last edit to this field.
+    pub fn undo(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.restore_edit_history(context, true);
+    }
+
+    /// Redo the user's last undone edit to this field.
+    pub fn redo(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.restore_edit_history(context, false);
+    }
+
+    fn restore_edit_history(self, context: &mut UpdateContext<'_, 'gc, '_>, is_undo: bool) {
+        if !self.is_editable() {
+            return;
+        }
+
+        let current_selection = self
+            .selection()
+            .unwrap_or_else(|| TextSelection::for_position(self.text_length()));
+        let current = (self.text(), current_selection);
+
+        let mut write = self.0.write(context.gc_context);
+        let edit_text = &mut *write;
+        let (from, to) = if is_undo {
+            (&mut edit_text.undo_history, &mut edit_text.redo_history)
+        } else {
+            (&mut edit_text.redo_history, &mut edit_text.undo_history)
+        };
+        let (text, selection) = match from.pop() {
+            Some(state) => state,
+            None => return,
+        };
+        to.push(current);
+        edit_text.is_typing = false;
+        drop(write);
+
+        self.replace_text(0, self.text_length(), &text, context);
+        self.set_selection(Some(selection), context.gc_context);
+        self.notify_user_edit(context);
+    }
+
+    /// Fire a cancellable AVM2 `textInput` event for text that the user is
+    /// about to enter.
+    ///
+    /// Returns `false` if a listener cancelled the event, in which case the
+    /// text should not be entered.
+    fn dispatch_text_input_event(
+        self,
+        text: &str,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> bool {
+        let target = match self.object2() {
+            Avm2Value::Object(target) => target,
+            _ => return true,
+        };
+
+        let mut activation = Avm2Activation::from_nothing(context.reborrow());
+        let text_event_class = activation.avm2().classes().textevent;
+        let text = AvmString::new(activation.context.gc_context, text.to_string());
+        let text_event = text_event_class.construct(
+            &mut activation,
+            &["textInput".into(), true.into(), true.into(), text.into()],
+        );
+
+        match text_event
+            .and_then(|event| Avm2::dispatch_event_object(&mut activation.context, event, target))
+        {
+            Ok(was_not_cancelled) => was_not_cancelled,
+            Err(e) => {
+                log::error!(
+                    "Encountered AVM2 error when dispatching textInput event: {}",
+                    e
                 );
-                self.propagate_text_binding(&mut activation);
-                self.on_changed(&mut activation);
+                true
             }
         }
     }
@@ -1394,7 +1797,11 @@ impl<'gc> EditText<'gc> {
                         if (context.ui.is_key_down(KeyCode::Shift) || selection.is_caret())
                             && selection.to > 0
                         {
-                            selection.to = string_utils::prev_char_boundary(text, selection.to);
+                            selection.to = if context.ui.is_key_down(KeyCode::Control) {
+                                string_utils::prev_word_boundary(text, selection.to)
+                            } else {
+                                string_utils::prev_char_boundary(text, selection.to)
+                            };
                             if !context.ui.is_key_down(KeyCode::Shift) {
                                 selection.from = selection.to;
                             }
@@ -1410,7 +1817,11 @@ impl<'gc> EditText<'gc> {
                         if (context.ui.is_key_down(KeyCode::Shift) || selection.is_caret())
                             && selection.to < length
                         {
-                            selection.to = string_utils::next_char_boundary(text, selection.to);
+                            selection.to = if context.ui.is_key_down(KeyCode::Control) {
+                                string_utils::next_word_boundary(text, selection.to)
+                            } else {
+                                string_utils::next_char_boundary(text, selection.to)
+                            };
                             if !context.ui.is_key_down(KeyCode::Shift) {
                                 selection.from = selection.to;
                             }
@@ -1422,6 +1833,21 @@ impl<'gc> EditText<'gc> {
                         edit_text.selection = Some(selection);
                         return ClipEventResult::Handled;
                     }
+                    ButtonKeyCode::Home | ButtonKeyCode::End => {
+                        let (line_start, line_end) = string_utils::line_bounds(text, selection.to);
+                        selection.to = match (key_code, context.ui.is_key_down(KeyCode::Control)) {
+                            (ButtonKeyCode::Home, true) => 0,
+                            (ButtonKeyCode::Home, false) => line_start,
+                            (_, true) => length,
+                            (_, false) => line_end,
+                        };
+                        if !context.ui.is_key_down(KeyCode::Shift) {
+                            selection.from = selection.to;
+                        }
+                        selection.clamp(length);
+                        edit_text.selection = Some(selection);
+                        return ClipEventResult::Handled;
+                    }
                     _ => (),
                 }
             }
@@ -1688,7 +2114,7 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
     }
 
     fn render_self(&self, context: &mut RenderContext<'_, 'gc>) {
-        if !self.world_bounds().intersects(&context.stage.view_bounds()) {
+        if !context.is_offscreen && !self.world_bounds().intersects(&context.stage.view_bounds()) {
             // Off-screen; culled
             return;
         }
@@ -1863,15 +2289,37 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         if event == ClipEvent::Press {
             let tracker = context.focus_tracker;
             tracker.set(Some((*self).into()), context);
-            if let Some(position) = self
+            if !self.is_selectable() {
+                return ClipEventResult::Handled;
+            }
+
+            let position = self
                 .screen_position_to_index(*context.mouse_position)
-                .map(TextSelection::for_position)
-            {
-                self.0.write(context.gc_context).selection = Some(position);
+                .unwrap_or_else(|| self.text_length());
+            let now = Utc::now().timestamp_millis();
+
+            // Double-clicking selects a word, and triple-clicking selects a line.
+            let mut edit_text = self.0.write(context.gc_context);
+            edit_text.click_count = if now - edit_text.last_click_time <= Self::MULTI_CLICK_MILLIS {
+                edit_text.click_count % 3 + 1
             } else {
-                self.0.write(context.gc_context).selection =
-                    Some(TextSelection::for_position(self.text_length()));
-            }
+                1
+            };
+            edit_text.last_click_time = now;
+
+            let text = edit_text.text_spans.text();
+            let selection = match edit_text.click_count {
+                2 => {
+                    let (start, end) = string_utils::word_bounds(text, position);
+                    TextSelection::for_range(start, end)
+                }
+                3 => {
+                    let (start, end) = string_utils::line_bounds(text, position);
+                    TextSelection::for_range(start, end)
+                }
+                _ => TextSelection::for_position(position),
+            };
+            edit_text.selection = Some(selection);
             return ClipEventResult::Handled;
         }
         ClipEventResult::NotHandled
@@ -1912,6 +2360,106 @@ struct EditTextStaticData {
     is_device_font: bool,
 }
 
+/// The set of characters that a user is allowed to enter into a text field,
+/// as described by the `restrict` property.
+///
+/// The syntax is a list of characters and ranges (such as `A-Z`). A `^`
+/// toggles between including and excluding the characters that follow it; if
+/// the string starts with `^`, all characters are allowed unless excluded.
+/// `\` escapes the next character.
+#[derive(Clone, Debug, Collect)]
+#[collect(require_static)]
+struct EditTextRestrict {
+    /// The `restrict` string, or `None` if all characters are allowed.
+    value: Option<String>,
+
+    /// Whether characters that match no range are allowed.
+    allow_by_default: bool,
+
+    /// Character ranges in order of appearance, and if they include or
+    /// exclude the characters they match.
+    ranges: Vec<(bool, char, char)>,
+}
+
+impl EditTextRestrict {
+    fn from(value: Option<&str>) -> Self {
+        let mut restrict = Self {
+            value: value.map(str::to_string),
+            allow_by_default: value.map(|v| v.starts_with('^')).unwrap_or(true),
+            ranges: Vec::new(),
+        };
+
+        let mut is_including = true;
+        let mut chars = value.unwrap_or_default().chars().peekable();
+        while let Some(c) = chars.next() {
+            let low = match c {
+                '^' => {
+                    is_including = !is_including;
+                    continue;
+                }
+                '\\' => match chars.next() {
+                    Some(c) => c,
+                    None => break,
+                },
+                c => c,
+            };
+
+            let mut high = low;
+            if chars.peek() == Some(&'-') {
+                chars.next();
+                match chars.next() {
+                    Some('\\') => high = chars.next().unwrap_or(low),
+                    Some(c) => high = c,
+                    None => restrict.ranges.push((is_including, '-', '-')),
+                }
+            }
+
+            restrict
+                .ranges
+                .push((is_including, low.min(high), low.max(high)));
+        }
+
+        restrict
+    }
+
+    fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    fn is_allowed(&self, character: char) -> bool {
+        if self.value.is_none() {
+            return true;
+        }
+
+        let mut is_allowed = self.allow_by_default;
+        for &(is_including, low, high) in self.ranges.iter() {
+            if (low..=high).contains(&character) {
+                is_allowed = is_including;
+            }
+        }
+
+        is_allowed
+    }
+
+    /// Determine the character to enter when the user types `character`.
+    ///
+    /// Characters that are only allowed in the opposite case are converted to
+    /// that case. Returns `None` if the character cannot be entered at all.
+    fn to_allowed(&self, character: char) -> Option<char> {
+        if self.is_allowed(character) {
+            return Some(character);
+        }
+
+        let mut upper = character.to_uppercase();
+        let mut lower = character.to_lowercase();
+        [upper.next(), lower.next()]
+            .iter()
+            .flatten()
+            .copied()
+            .find(|c| *c != character && self.is_allowed(*c))
+    }
+}
+
 #[derive(Copy, Clone, Debug, Collect)]
 #[collect(require_static)]
 pub struct TextSelection {
@@ -1992,3 +2540,66 @@ impl TextSelection {
         self.to == self.from
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::EditTextRestrict;
+
+    fn allowed(restrict: &str, chars: &str) -> String {
+        let restrict = EditTextRestrict::from(Some(restrict));
+        chars.chars().filter(|c| restrict.is_allowed(*c)).collect()
+    }
+
+    #[test]
+    fn restrict_none_allows_everything() {
+        let restrict = EditTextRestrict::from(None);
+        assert!("aZ09 -^\\\u{3042}".chars().all(|c| restrict.is_allowed(c)));
+    }
+
+    #[test]
+    fn restrict_empty_allows_nothing() {
+        assert_eq!(allowed("", "aZ09 -"), "");
+    }
+
+    #[test]
+    fn restrict_ranges() {
+        assert_eq!(allowed("A-Z", "aAmMzZ09"), "AMZ");
+        assert_eq!(allowed("a-c0-2", "abcd0123"), "abc012");
+        assert_eq!(allowed("z-a", "amz0"), "amz");
+        assert_eq!(allowed("abc", "abcd-"), "abc");
+    }
+
+    #[test]
+    fn restrict_caret() {
+        assert_eq!(allowed("^0-9", "a5Z-"), "aZ-");
+        assert_eq!(allowed("A-Z^Q", "AQZa"), "AZ");
+        assert_eq!(allowed("^a-z^m", "amz0"), "m0");
+    }
+
+    #[test]
+    fn restrict_escapes() {
+        assert_eq!(allowed("\\^", "^a"), "^");
+        assert_eq!(allowed("\\-", "-a"), "-");
+        assert_eq!(allowed("\\\\", "\\a"), "\\");
+        assert_eq!(allowed("a\\-c", "abc-"), "ac-");
+        assert_eq!(allowed("!-\\-", "!,-."), "!,-");
+    }
+
+    #[test]
+    fn restrict_dash_literals() {
+        assert_eq!(allowed("-a", "-ab"), "-a");
+        assert_eq!(allowed("a-", "-ab"), "-a");
+        assert_eq!(allowed("-", "-a"), "-");
+    }
+
+    #[test]
+    fn restrict_converts_case() {
+        let restrict = EditTextRestrict::from(Some("A-Z"));
+        assert_eq!(restrict.to_allowed('a'), Some('A'));
+        assert_eq!(restrict.to_allowed('A'), Some('A'));
+        assert_eq!(restrict.to_allowed('1'), None);
+
+        let restrict = EditTextRestrict::from(Some("a-z"));
+        assert_eq!(restrict.to_allowed('Q'), Some('q'));
+    }
+}
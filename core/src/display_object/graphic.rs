@@ -24,7 +24,7 @@ pub struct GraphicData<'gc> {
     base: DisplayObjectBase<'gc>,
     static_data: gc_arena::Gc<'gc, GraphicStatic>,
     avm2_object: Option<Avm2Object<'gc>>,
-    drawing: Option<Drawing>,
+    drawing: Option<Drawing<'gc>>,
 }
 
 impl<'gc> Graphic<'gc> {
@@ -144,12 +144,14 @@ impl<'gc> TDisplayObject<'gc> for Graphic<'gc> {
         }
     }
 
-    fn run_frame(&self, _context: &mut UpdateContext) {
-        // Noop
+    fn run_frame(&self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        if let Some(drawing) = &mut self.0.write(context.gc_context).drawing {
+            drawing.update_bitmap_data(context);
+        }
     }
 
     fn render_self(&self, context: &mut RenderContext) {
-        if !self.world_bounds().intersects(&context.stage.view_bounds()) {
+        if !context.is_offscreen && !self.world_bounds().intersects(&context.stage.view_bounds()) {
             // Off-screen; culled
             return;
         }
@@ -223,7 +225,7 @@ impl<'gc> TDisplayObject<'gc> for Graphic<'gc> {
         self.0.write(mc).avm2_object = Some(to);
     }
 
-    fn as_drawing(&self, gc_context: MutationContext<'gc, '_>) -> Option<RefMut<'_, Drawing>> {
+    fn as_drawing(&self, gc_context: MutationContext<'gc, '_>) -> Option<RefMut<'_, Drawing<'gc>>> {
         let mut write = self.0.write(gc_context);
         if write.drawing.is_none() {
             write.drawing = Some(Drawing::new());
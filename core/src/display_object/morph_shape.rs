@@ -1,9 +1,14 @@
+use crate::avm2::{
+    Activation as Avm2Activation, Object as Avm2Object, StageObject as Avm2StageObject,
+    Value as Avm2Value,
+};
 use crate::backend::render::ShapeHandle;
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::{DisplayObjectBase, TDisplayObject};
 use crate::prelude::*;
 use crate::tag_utils::SwfMovie;
 use crate::types::{Degrees, Percent};
+use crate::vminterface::AvmType;
 use gc_arena::{Collect, Gc, GcCell, MutationContext};
 use std::sync::Arc;
 use swf::{Fixed16, Fixed8, Twips};
@@ -18,6 +23,7 @@ pub struct MorphShapeData<'gc> {
     base: DisplayObjectBase<'gc>,
     static_data: Gc<'gc, MorphShapeStatic>,
     ratio: u16,
+    avm2_object: Option<Avm2Object<'gc>>,
 }
 
 impl<'gc> MorphShape<'gc> {
@@ -31,6 +37,7 @@ impl<'gc> MorphShape<'gc> {
                 base: Default::default(),
                 static_data: Gc::allocate(gc_context, static_data),
                 ratio: 0,
+                avm2_object: None,
             },
         ))
     }
@@ -67,10 +74,40 @@ impl<'gc> TDisplayObject<'gc> for MorphShape<'gc> {
         }
     }
 
+    fn construct_frame(&self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        if self.avm_type() == AvmType::Avm2 && matches!(self.object2(), Avm2Value::Undefined) {
+            let morph_shape_constr = context.avm2.classes().morphshape;
+            let mut activation = Avm2Activation::from_nothing(context.reborrow());
+
+            match Avm2StageObject::for_display_object_childless(
+                &mut activation,
+                (*self).into(),
+                morph_shape_constr,
+            ) {
+                Ok(object) => {
+                    self.0.write(activation.context.gc_context).avm2_object = Some(object.into())
+                }
+                Err(e) => log::error!("Got {} when constructing AVM2 side of display object", e),
+            }
+        }
+    }
+
     fn run_frame(&self, _context: &mut UpdateContext) {
         // Noop
     }
 
+    fn object2(&self) -> Avm2Value<'gc> {
+        self.0
+            .read()
+            .avm2_object
+            .map(Avm2Value::from)
+            .unwrap_or(Avm2Value::Undefined)
+    }
+
+    fn set_object2(&mut self, mc: MutationContext<'gc, '_>, to: Avm2Object<'gc>) {
+        self.0.write(mc).avm2_object = Some(to);
+    }
+
     fn render_self(&self, context: &mut RenderContext) {
         if let Some(frame) = self.0.read().static_data.frames.get(&self.ratio()) {
             context
@@ -161,7 +198,7 @@ impl MorphShapeStatic {
 
         // Interpolate MorphShapes into a Shape.
         use swf::{FillStyle, LineStyle, ShapeRecord, ShapeStyles};
-        // Start shape is ratio 65535, end shape is ratio 0.
+        // Start shape is ratio 0, end shape is ratio 65535.
         let b = f32::from(ratio) / 65535.0;
         let a = 1.0 - b;
         let fill_styles: Vec<FillStyle> = self
@@ -176,18 +213,7 @@ impl MorphShapeStatic {
             .line_styles
             .iter()
             .zip(self.end.line_styles.iter())
-            .map(|(start, end)| LineStyle {
-                width: lerp_twips(start.width, end.width, a, b),
-                color: lerp_color(&start.color, &end.color, a, b),
-                start_cap: start.start_cap,
-                end_cap: start.end_cap,
-                join_style: start.join_style,
-                fill_style: None,
-                allow_scale_x: start.allow_scale_x,
-                allow_scale_y: start.allow_scale_y,
-                is_pixel_hinted: start.is_pixel_hinted,
-                allow_close: start.allow_close,
-            })
+            .map(|(start, end)| lerp_line_style(start, end, a, b))
             .collect();
 
         let mut shape = Vec::with_capacity(self.start.shape.len());
@@ -252,7 +278,7 @@ impl MorphShapeStatic {
                         ));
                     }
                     shape.push(ShapeRecord::StyleChange(style_change));
-                    Self::update_pos(&mut end_x, &mut end_y, s);
+                    Self::update_pos(&mut end_x, &mut end_y, e);
                     end = end_iter.next();
                     continue;
                 }
@@ -322,20 +348,54 @@ impl MorphShapeStatic {
 // Interpolation functions
 // These interpolate between two SWF shape structures.
 // a + b should = 1.0
+//
+// Shape tweens are the only tweens the player interpolates. Classic motion
+// tweens are baked by the authoring tool into a matrix and color transform for
+// every frame, with easing and rotation direction already applied, and the
+// timeline places them as they are.
 
 fn lerp_color(start: &Color, end: &Color, a: f32, b: f32) -> Color {
     // f32 -> u8 cast is defined to saturate for out of bounds values,
     // so we don't have to worry about clamping.
     Color {
-        r: (a * f32::from(start.r) + b * f32::from(end.r)) as u8,
-        g: (a * f32::from(start.g) + b * f32::from(end.g)) as u8,
-        b: (a * f32::from(start.b) + b * f32::from(end.b)) as u8,
-        a: (a * f32::from(start.a) + b * f32::from(end.a)) as u8,
+        r: lerp_u8(start.r, end.r, a, b),
+        g: lerp_u8(start.g, end.g, a, b),
+        b: lerp_u8(start.b, end.b, a, b),
+        a: lerp_u8(start.a, end.a, a, b),
     }
 }
 
+/// Interpolate a byte, rounding to the nearest value.
+///
+/// Truncating instead would darken colors and shift gradient stops partway
+/// through a tween, even when the start and end values are the same.
+fn lerp_u8(start: u8, end: u8, a: f32, b: f32) -> u8 {
+    (a * f32::from(start) + b * f32::from(end)).round() as u8
+}
+
+/// Interpolate a coordinate, rounding to the nearest twip.
 fn lerp_twips(start: Twips, end: Twips, a: f32, b: f32) -> Twips {
-    Twips::new((start.get() as f32 * a + end.get() as f32 * b) as i32)
+    Twips::new((start.get() as f32 * a + end.get() as f32 * b).round() as i32)
+}
+
+fn lerp_line_style(start: &swf::LineStyle, end: &swf::LineStyle, a: f32, b: f32) -> swf::LineStyle {
+    swf::LineStyle {
+        width: lerp_twips(start.width, end.width, a, b),
+        color: lerp_color(&start.color, &end.color, a, b),
+        start_cap: start.start_cap,
+        end_cap: start.end_cap,
+        join_style: lerp_join_style(start.join_style, end.join_style, b),
+        // Strokes with a fill (`DefineMorphShape2`) tween it like a shape
+        // fill.
+        fill_style: match (&start.fill_style, &end.fill_style) {
+            (Some(start), Some(end)) => Some(lerp_fill(start, end, a, b)),
+            _ => None,
+        },
+        allow_scale_x: start.allow_scale_x,
+        allow_scale_y: start.allow_scale_y,
+        is_pixel_hinted: start.is_pixel_hinted,
+        allow_close: start.allow_close,
+    }
 }
 
 fn lerp_fill(start: &swf::FillStyle, end: &swf::FillStyle, a: f32, b: f32) -> swf::FillStyle {
@@ -385,7 +445,7 @@ fn lerp_fill(start: &swf::FillStyle, end: &swf::FillStyle, a: f32, b: f32) -> sw
             },
         ) => FillStyle::FocalGradient {
             gradient: lerp_gradient(start, end, a, b),
-            focal_point: *start_focal * Fixed8::from_f32(a) + *end_focal * Fixed8::from_f32(b),
+            focal_point: *start_focal + (*end_focal - *start_focal) * Fixed8::from_f32(b),
         },
 
         // All other combinations should not occur, because SWF stores the start/end fill as the same type, always.
@@ -495,16 +555,34 @@ fn lerp_edges(
     }
 }
 
+fn lerp_join_style(
+    start: swf::LineJoinStyle,
+    end: swf::LineJoinStyle,
+    b: f32,
+) -> swf::LineJoinStyle {
+    use swf::LineJoinStyle;
+    match (start, end) {
+        // Miter limits tween along with the stroke.
+        (LineJoinStyle::Miter(start), LineJoinStyle::Miter(end)) => {
+            LineJoinStyle::Miter(start + (end - start) * Fixed8::from_f32(b))
+        }
+        _ => start,
+    }
+}
+
 fn lerp_matrix(start: &swf::Matrix, end: &swf::Matrix, a: f32, b: f32) -> swf::Matrix {
     // TODO: Lerping a matrix element-wise is geometrically wrong,
     // but I doubt Flash is decomposing the matrix into scale-rotate-translate?
-    let af = Fixed16::from_f32(a);
+    // Weighting `start` by `a` and `end` by `b` after both are truncated to
+    // fixed point would leave weights summing to less than one, shrinking
+    // elements that don't change over the tween partway through it.
     let bf = Fixed16::from_f32(b);
+    let lerp = |start: Fixed16, end: Fixed16| start + (end - start) * bf;
     swf::Matrix {
-        a: start.a * af + end.a * bf,
-        b: start.b * af + end.b * bf,
-        c: start.c * af + end.c * bf,
-        d: start.d * af + end.d * bf,
+        a: lerp(start.a, end.a),
+        b: lerp(start.b, end.b),
+        c: lerp(start.c, end.c),
+        d: lerp(start.d, end.d),
         tx: lerp_twips(start.tx, end.tx, a, b),
         ty: lerp_twips(start.ty, end.ty, a, b),
     }
@@ -519,7 +597,7 @@ fn lerp_gradient(start: &swf::Gradient, end: &swf::Gradient, a: f32, b: f32) ->
         .iter()
         .zip(end.records.iter())
         .map(|(start, end)| swf::GradientRecord {
-            ratio: (f32::from(start.ratio) * a + f32::from(end.ratio) * b) as u8,
+            ratio: lerp_u8(start.ratio, end.ratio, a, b),
             color: lerp_color(&start.color, &end.color, a, b),
         })
         .collect();
@@ -531,3 +609,121 @@ fn lerp_gradient(start: &swf::Gradient, end: &swf::Gradient, a: f32, b: f32) ->
         records,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The weights of a ratio a third of the way through a tween.
+    const A: f32 = 2.0 / 3.0;
+    const B: f32 = 1.0 / 3.0;
+
+    #[test]
+    fn unchanged_values_stay_put() {
+        let matrix = swf::Matrix {
+            a: Fixed16::from_f32(1.5),
+            b: Fixed16::from_f32(-0.25),
+            c: Fixed16::from_f32(0.25),
+            d: Fixed16::ONE,
+            tx: Twips::new(-12345),
+            ty: Twips::new(99999),
+        };
+        assert_eq!(lerp_matrix(&matrix, &matrix, A, B), matrix);
+
+        let miter = swf::LineJoinStyle::Miter(Fixed8::from_f32(3.0));
+        assert_eq!(lerp_join_style(miter, miter, B), miter);
+    }
+
+    #[test]
+    fn coordinates_round_to_nearest_twip() {
+        assert_eq!(
+            lerp_twips(Twips::new(0), Twips::new(2), A, B),
+            Twips::new(1)
+        );
+        assert_eq!(
+            lerp_twips(Twips::new(0), Twips::new(-2), A, B),
+            Twips::new(-1)
+        );
+        assert_eq!(
+            lerp_twips(Twips::new(10), Twips::new(20), 0.5, 0.5),
+            Twips::new(15)
+        );
+    }
+
+    #[test]
+    fn colors_round_to_nearest() {
+        assert_eq!(lerp_u8(0, 255, A, B), 85);
+        assert_eq!(lerp_u8(0, 2, A, B), 1);
+        assert_eq!(lerp_u8(200, 200, A, B), 200);
+    }
+
+    fn gradient(stops: &[(u8, u32)]) -> swf::Gradient {
+        swf::Gradient {
+            matrix: swf::Matrix::IDENTITY,
+            spread: swf::GradientSpread::Pad,
+            interpolation: swf::GradientInterpolation::Rgb,
+            records: stops
+                .iter()
+                .map(|&(ratio, rgb)| swf::GradientRecord {
+                    ratio,
+                    color: Color::from_rgb(rgb, 255),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn gradient_stops_tween_ratios_and_colors() {
+        let start = gradient(&[(0, 0x000000), (128, 0xFF0000)]);
+        let end = gradient(&[(0, 0x000000), (255, 0x0000FF)]);
+        assert_eq!(
+            lerp_gradient(&start, &end, 0.5, 0.5),
+            gradient(&[(0, 0x000000), (192, 0x800080)])
+        );
+    }
+
+    #[test]
+    fn strokes_tween_their_fill_and_miter_limit() {
+        let start = swf::LineStyle {
+            join_style: swf::LineJoinStyle::Miter(Fixed8::from_f32(2.0)),
+            fill_style: Some(swf::FillStyle::LinearGradient(gradient(&[
+                (0, 0x000000),
+                (128, 0xFF0000),
+            ]))),
+            ..swf::LineStyle::new_v1(Twips::new(20), Color::from_rgb(0x000000, 255))
+        };
+        let end = swf::LineStyle {
+            width: Twips::new(40),
+            join_style: swf::LineJoinStyle::Miter(Fixed8::from_f32(5.0)),
+            fill_style: Some(swf::FillStyle::LinearGradient(gradient(&[
+                (0, 0x000000),
+                (255, 0x0000FF),
+            ]))),
+            ..start.clone()
+        };
+
+        let tweened = lerp_line_style(&start, &end, 0.5, 0.5);
+        assert_eq!(tweened.width, Twips::new(30));
+        assert_eq!(
+            tweened.join_style,
+            swf::LineJoinStyle::Miter(Fixed8::from_f32(3.5))
+        );
+        assert_eq!(
+            tweened.fill_style,
+            Some(swf::FillStyle::LinearGradient(gradient(&[
+                (0, 0x000000),
+                (192, 0x800080),
+            ])))
+        );
+    }
+
+    #[test]
+    fn mixed_joins_keep_the_start_join() {
+        let miter = swf::LineJoinStyle::Miter(Fixed8::from_f32(2.0));
+        assert_eq!(lerp_join_style(miter, swf::LineJoinStyle::Round, B), miter);
+        assert_eq!(
+            lerp_join_style(swf::LineJoinStyle::Bevel, miter, B),
+            swf::LineJoinStyle::Bevel
+        );
+    }
+}
@@ -80,7 +80,7 @@ pub struct MovieClipData<'gc> {
     frame_scripts: Vec<Avm2FrameScript<'gc>>,
     flags: MovieClipFlags,
     avm2_class: Option<Avm2Object<'gc>>,
-    drawing: Drawing,
+    drawing: Drawing<'gc>,
     is_focusable: bool,
     has_focus: bool,
     enabled: bool,
@@ -318,6 +318,14 @@ impl<'gc> MovieClip<'gc> {
                 .0
                 .write(context.gc_context)
                 .define_font_4(context, reader),
+            TagCode::DefineFontInfo => self
+                .0
+                .write(context.gc_context)
+                .define_font_info(context, reader, 1),
+            TagCode::DefineFontInfo2 => self
+                .0
+                .write(context.gc_context)
+                .define_font_info(context, reader, 2),
             TagCode::DefineMorphShape => self.0.write(context.gc_context).define_morph_shape(
                 context,
                 reader,
@@ -471,6 +479,10 @@ impl<'gc> MovieClip<'gc> {
                 .0
                 .write(context.gc_context)
                 .define_binary_data(context, reader),
+            TagCode::DefineScalingGrid => self
+                .0
+                .write(context.gc_context)
+                .define_scaling_grid(context, reader),
             _ => Ok(()),
         };
         let _ = tag_utils::decode_tags(&mut reader, tag_callback, TagCode::End);
@@ -1763,6 +1775,11 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
             self.run_frame_internal(context, true);
         }
 
+        self.0
+            .write(context.gc_context)
+            .drawing
+            .update_bitmap_data(context);
+
         if is_load_frame {
             self.0.write(context.gc_context).run_clip_postevent(
                 (*self).into(),
@@ -1796,7 +1813,11 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
                                 &[],
                                 context,
                             ) {
-                                log::error!("Error occured when running AVM2 frame script: {}", e);
+                                Avm2::report_uncaught_error(
+                                    context,
+                                    e,
+                                    "Error occured when running AVM2 frame script",
+                                );
                             }
                             write = self.0.write(context.gc_context);
                         }
@@ -1842,7 +1863,7 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
             return false;
         }
 
-        if self.world_bounds().contains(point) {
+        if self.world_bounds().contains(point) && self.hit_test_scroll_rect(point) {
             if let Some(masker) = self.masker() {
                 if !masker.hit_test_shape(context, point, HitTestOptions::SKIP_INVISIBLE) {
                     return false;
@@ -1894,6 +1915,12 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
                 }
             }
 
+            // Nothing outside of the scroll rectangle is shown, so nothing
+            // there can be clicked.
+            if !self.hit_test_scroll_rect(point) {
+                return None;
+            }
+
             if self.world_bounds().contains(point) {
                 // This MovieClip operates in "button mode" if it has a mouse handler,
                 // either via on(..) or via property mc.onRelease, etc.
@@ -1948,7 +1975,9 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
     }
 
     fn mouse_cursor(&self) -> MouseCursor {
-        if self.use_hand_cursor() {
+        // AVM2 clips are picked whether or not they act as buttons, and we
+        // don't support `buttonMode` yet.
+        if self.use_hand_cursor() && self.avm_type() != AvmType::Avm2 {
             MouseCursor::Hand
         } else {
             MouseCursor::Arrow
@@ -2005,7 +2034,7 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
         Some(self.into())
     }
 
-    fn as_drawing(&self, gc_context: MutationContext<'gc, '_>) -> Option<RefMut<'_, Drawing>> {
+    fn as_drawing(&self, gc_context: MutationContext<'gc, '_>) -> Option<RefMut<'_, Drawing<'gc>>> {
         Some(RefMut::map(self.0.write(gc_context), |s| &mut s.drawing))
     }
 
@@ -2769,6 +2798,39 @@ impl<'gc, 'a> MovieClipData<'gc> {
         Ok(())
     }
 
+    #[inline]
+    fn define_scaling_grid(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<'a>,
+    ) -> DecodeResult {
+        let id = reader.read_u16()?;
+        let splitter_rect = reader.read_rectangle()?;
+        let scaling_grid = Some(BoundingBox::from(splitter_rect));
+        match context
+            .library
+            .library_for_movie_mut(self.movie())
+            .character_by_id(id)
+        {
+            Some(Character::MovieClip(clip)) => {
+                clip.set_scaling_grid(context.gc_context, scaling_grid);
+            }
+            Some(Character::Avm1Button(button)) => {
+                button.set_scaling_grid(context.gc_context, scaling_grid);
+            }
+            Some(Character::Avm2Button(button)) => {
+                button.set_scaling_grid(context.gc_context, scaling_grid);
+            }
+            Some(_) => {
+                log::warn!("DefineScalingGrid: Tried to apply on non-sprite ID {}", id);
+            }
+            None => {
+                log::warn!("DefineScalingGrid: Character ID {} doesn't exist", id);
+            }
+        }
+        Ok(())
+    }
+
     #[inline]
     fn define_button_sound(
         &mut self,
@@ -2914,6 +2976,44 @@ impl<'gc, 'a> MovieClipData<'gc> {
         Ok(())
     }
 
+    /// Attaches a code table and font name to a previously defined font.
+    ///
+    /// `DefineFont` (v1) fonts carry only glyph outlines; SWF1-5 content
+    /// follows them with a `DefineFontInfo` tag so that dynamic text can map
+    /// characters to glyphs.
+    #[inline]
+    fn define_font_info(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<'a>,
+        version: u8,
+    ) -> DecodeResult {
+        let font_info = match reader.read_define_font_info(version)? {
+            swf::Tag::DefineFontInfo(font_info) => font_info,
+            _ => unreachable!(),
+        };
+        let library = context.library.library_for_movie_mut(self.movie());
+        match library.character_by_id(font_info.id) {
+            Some(Character::Font(font)) => {
+                let font = font.with_font_info(context.gc_context, &font_info, reader.encoding());
+                library.replace_font(font_info.id, font);
+            }
+            Some(_) => {
+                log::warn!(
+                    "DefineFontInfo: Tried to apply on non-font ID {}",
+                    font_info.id
+                );
+            }
+            None => {
+                log::warn!(
+                    "DefineFontInfo: Character ID {} doesn't exist",
+                    font_info.id
+                );
+            }
+        }
+        Ok(())
+    }
+
     #[inline]
     fn define_sound(
         &mut self,
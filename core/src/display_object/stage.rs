@@ -3,7 +3,8 @@
 use crate::avm1::Object as Avm1Object;
 use crate::avm2::{
     Activation as Avm2Activation, Event as Avm2Event, Object as Avm2Object,
-    ScriptObject as Avm2ScriptObject, StageObject as Avm2StageObject, Value as Avm2Value,
+    ScriptObject as Avm2ScriptObject, StageObject as Avm2StageObject, TObject as Avm2TObject,
+    Value as Avm2Value,
 };
 use crate::backend::ui::UiBackend;
 use crate::config::Letterbox;
@@ -12,7 +13,9 @@ use crate::display_object::container::{
     ChildContainer, DisplayObjectContainer, TDisplayObjectContainer,
 };
 use crate::display_object::{render_base, DisplayObject, DisplayObjectBase, TDisplayObject};
+use crate::matrix::round_to_twips;
 use crate::prelude::*;
+use crate::transform::Transform;
 use crate::types::{Degrees, Percent};
 use crate::vminterface::{AvmType, Instantiator};
 use bitflags::bitflags;
@@ -20,6 +23,8 @@ use gc_arena::{Collect, GcCell, MutationContext};
 use std::fmt::{self, Display, Formatter};
 use std::str::FromStr;
 
+pub use ruffle_render_api::quality::StageQuality;
+
 /// The Stage is the root of the display object hierarchy. It contains all AVM1
 /// levels as well as AVM2 movies.
 #[derive(Clone, Debug, Collect, Copy)]
@@ -55,6 +60,7 @@ pub struct StageData<'gc> {
     movie_size: (u32, u32),
 
     /// The quality settings of the stage.
+    #[collect(require_static)]
     quality: StageQuality,
 
     /// The dimensions of the stage, as reported to ActionScript.
@@ -83,13 +89,30 @@ pub struct StageData<'gc> {
     viewport_scale_factor: f64,
 
     /// The bounds of the current viewport in twips, used for culling.
+    #[collect(require_static)]
     view_bounds: BoundingBox,
 
     /// Whether to show default context menu items
     show_menu: bool,
 
+    /// Whether the player was fullscreen when the viewport last changed size.
+    ///
+    /// Used to tell content when it enters or leaves fullscreen.
+    is_fullscreen: bool,
+
+    /// The kind of fullscreen the stage is in, when it's fullscreen.
+    display_state: StageDisplayState,
+
+    /// The area of the stage that's scaled up to fill the screen in fullscreen.
+    #[collect(require_static)]
+    full_screen_source_rect: Option<BoundingBox>,
+
     /// The AVM2 view of this stage object.
     avm2_object: Avm2Object<'gc>,
+
+    /// The stage's `Stage3D` objects, created the first time they're asked
+    /// for.
+    stage3ds: Vec<Avm2Object<'gc>>,
 }
 
 impl<'gc> Stage<'gc> {
@@ -111,7 +134,11 @@ impl<'gc> Stage<'gc> {
                 viewport_scale_factor: 1.0,
                 view_bounds: Default::default(),
                 show_menu: true,
+                is_fullscreen: false,
+                display_state: StageDisplayState::Normal,
+                full_screen_source_rect: None,
                 avm2_object: Avm2ScriptObject::bare_object(gc_context),
+                stage3ds: Vec::new(),
             },
         ));
         stage.set_is_root(gc_context, true);
@@ -126,11 +153,26 @@ impl<'gc> Stage<'gc> {
         self.0.write(gc_context).background_color = color;
     }
 
-    pub fn inverse_view_matrix(self) -> Matrix {
-        let mut inverse_view_matrix = *(self.matrix());
-        inverse_view_matrix.invert();
+    /// Converts a position in viewport pixels to a position on the stage,
+    /// rounded to the nearest twip.
+    pub fn view_to_stage(self, (x, y): (f64, f64)) -> (Twips, Twips) {
+        round_to_twips(
+            self.matrix()
+                .inverse_transform_point((x * Twips::TWIPS_PER_PIXEL, y * Twips::TWIPS_PER_PIXEL)),
+        )
+    }
+
+    /// Get the stage's `Stage3D` objects.
+    pub fn stage3ds(self) -> Vec<Avm2Object<'gc>> {
+        self.0.read().stage3ds.clone()
+    }
 
-        inverse_view_matrix
+    pub fn set_stage3ds(
+        self,
+        gc_context: MutationContext<'gc, '_>,
+        stage3ds: Vec<Avm2Object<'gc>>,
+    ) {
+        self.0.write(gc_context).stage3ds = stage3ds;
     }
 
     pub fn letterbox(self) -> Letterbox {
@@ -153,8 +195,7 @@ impl<'gc> Stage<'gc> {
 
     /// Returns the quality setting of the stage.
     ///
-    /// In the Flash Player, the quality setting affects anti-aliasing and smoothing of bitmaps.
-    /// This setting is currently ignored in Ruffle.
+    /// The quality setting affects anti-aliasing and smoothing of bitmaps.
     /// Used by AVM1 `stage.quality` and AVM2 `Stage.quality` properties.
     pub fn quality(self) -> StageQuality {
         self.0.read().quality
@@ -162,8 +203,7 @@ impl<'gc> Stage<'gc> {
 
     /// Sets the quality setting of the stage.
     ///
-    /// In the Flash Player, the quality setting affects anti-aliasing and smoothing of bitmaps.
-    /// This setting is currently ignored in Ruffle.
+    /// The quality setting affects anti-aliasing and smoothing of bitmaps.
     /// Used by AVM1 `stage.quality` and AVM2 `Stage.quality` properties.
     pub fn set_quality(self, gc_context: MutationContext<'gc, '_>, quality: StageQuality) {
         let mut this = self.0.write(gc_context);
@@ -186,6 +226,23 @@ impl<'gc> Stage<'gc> {
         self.0.read().stage_size
     }
 
+    /// Set the size of the stage, as reported to ActionScript.
+    ///
+    /// Content can only resize the stage in `StageScaleMode::NoScale`; this is
+    /// ignored in every other mode. The size is reset to the viewport size the
+    /// next time the viewport changes.
+    /// Used by the AVM2 `Stage.stageWidth`/`stageHeight` setters.
+    pub fn set_stage_size(self, context: &mut UpdateContext<'_, 'gc, '_>, width: u32, height: u32) {
+        let mut write = self.0.write(context.gc_context);
+        if write.scale_mode != StageScaleMode::NoScale || write.stage_size == (width, height) {
+            return;
+        }
+        write.stage_size = (width, height);
+        drop(write);
+
+        self.fire_resize_event(context);
+    }
+
     /// Get the stage mode.
     /// This controls how the content scales to fill the viewport.
     pub fn scale_mode(self) -> StageScaleMode {
@@ -253,7 +310,27 @@ impl<'gc> Stage<'gc> {
         write.viewport_scale_factor = scale_factor;
         drop(write);
 
+        // Entering or leaving fullscreen always resizes the viewport.
+        let is_fullscreen = context.ui.is_fullscreen();
+        let mut write = self.0.write(context.gc_context);
+        let fullscreen_changed = write.is_fullscreen != is_fullscreen;
+        if fullscreen_changed {
+            write.is_fullscreen = is_fullscreen;
+            if !is_fullscreen {
+                write.display_state = StageDisplayState::Normal;
+            } else if write.display_state == StageDisplayState::Normal {
+                // The user made the player fullscreen themselves, so there's no need
+                // to restrict the keyboard.
+                write.display_state = StageDisplayState::FullScreenInteractive;
+            }
+        }
+        drop(write);
+
         self.build_matrices(context);
+
+        if fullscreen_changed {
+            self.fire_fullscreen_event(context, is_fullscreen);
+        }
     }
 
     pub fn view_bounds(self) -> BoundingBox {
@@ -269,14 +346,66 @@ impl<'gc> Stage<'gc> {
         write.show_menu = show_menu;
     }
 
+    /// Get the display state of the stage.
+    /// Used by AVM1 `Stage.displayState` and AVM2 `Stage.displayState` properties.
+    pub fn display_state(self) -> StageDisplayState {
+        let stage = self.0.read();
+        if stage.is_fullscreen {
+            stage.display_state
+        } else {
+            StageDisplayState::Normal
+        }
+    }
+
+    /// Ask the player to enter or leave fullscreen.
+    ///
+    /// Platforms may refuse, or enter fullscreen later. The display state only
+    /// changes once the player actually is fullscreen, which is when
+    /// `FullScreenEvent.FULL_SCREEN` is fired.
+    pub fn set_display_state(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        display_state: StageDisplayState,
+    ) {
+        let mut write = self.0.write(context.gc_context);
+        write.display_state = display_state;
+        let was_fullscreen = write.is_fullscreen;
+        drop(write);
+
+        let is_fullscreen = display_state != StageDisplayState::Normal;
+        if is_fullscreen != was_fullscreen {
+            context.ui.set_fullscreen(is_fullscreen);
+        }
+    }
+
+    /// Get the area of the stage that's scaled up to fill the screen in fullscreen.
+    pub fn full_screen_source_rect(self) -> Option<BoundingBox> {
+        self.0.read().full_screen_source_rect.clone()
+    }
+
+    /// Set the area of the stage that's scaled up to fill the screen in fullscreen.
+    ///
+    /// This ignores the scale mode and alignment; the area is always scaled to
+    /// fit the screen while keeping its aspect ratio.
+    pub fn set_full_screen_source_rect(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        rect: Option<BoundingBox>,
+    ) {
+        self.0.write(context.gc_context).full_screen_source_rect = rect;
+        self.build_matrices(context);
+    }
+
     /// Determine if we should letterbox the stage content.
     fn should_letterbox(self, ui: &mut dyn UiBackend) -> bool {
         // Only enable letterbox is the default `ShowAll` scale mode.
         // If content changes the scale mode or alignment, it signals that it is size-aware.
         // For example, `NoScale` is used to make responsive layouts; don't letterbox over it.
+        // The fullscreen source rect is always shown in full, so it has nothing to hide.
         let stage = self.0.read();
         stage.scale_mode == StageScaleMode::ShowAll
             && stage.align.is_empty()
+            && !(stage.is_fullscreen && stage.full_screen_source_rect.is_some())
             && (stage.letterbox == Letterbox::On
                 || (stage.letterbox == Letterbox::Fullscreen && ui.is_fullscreen()))
     }
@@ -284,12 +413,27 @@ impl<'gc> Stage<'gc> {
     /// Update the stage's transform matrix in response to a root movie change.
     pub fn build_matrices(self, context: &mut UpdateContext<'_, 'gc, '_>) {
         let mut stage = self.0.write(context.gc_context);
-        let scale_mode = stage.scale_mode;
-        let align = stage.align;
         let prev_stage_size = stage.stage_size;
 
+        // In fullscreen, content can choose an area of the stage to be scaled up to fill the
+        // screen instead. This is done as if it were the whole movie in the default scale mode.
+        let source_rect = stage
+            .full_screen_source_rect
+            .clone()
+            .filter(|_| stage.is_fullscreen);
+        let (scale_mode, align) = if source_rect.is_some() {
+            (StageScaleMode::ShowAll, StageAlign::default())
+        } else {
+            (stage.scale_mode, stage.align)
+        };
+
         // Update stage size based on scale mode and DPI.
-        stage.stage_size = if stage.scale_mode == StageScaleMode::NoScale {
+        stage.stage_size = if let Some(source_rect) = &source_rect {
+            (
+                source_rect.width().to_pixels().round() as u32,
+                source_rect.height().to_pixels().round() as u32,
+            )
+        } else if stage.scale_mode == StageScaleMode::NoScale {
             // Viewport size is adjusted for HiDPI.
             let width = f64::from(stage.viewport_size.0) / stage.viewport_scale_factor;
             let height = f64::from(stage.viewport_size.1) / stage.viewport_scale_factor;
@@ -300,9 +444,20 @@ impl<'gc> Stage<'gc> {
         let stage_size_changed = prev_stage_size != stage.stage_size;
 
         // Create view matrix to scale stage into viewport area.
-        let (movie_width, movie_height) = stage.movie_size;
-        let movie_width = movie_width as f64;
-        let movie_height = movie_height as f64;
+        let (origin_x, origin_y, movie_width, movie_height) = match &source_rect {
+            Some(source_rect) => (
+                source_rect.x_min.to_pixels(),
+                source_rect.y_min.to_pixels(),
+                source_rect.width().to_pixels(),
+                source_rect.height().to_pixels(),
+            ),
+            None => (
+                0.0,
+                0.0,
+                stage.movie_size.0 as f64,
+                stage.movie_size.1 as f64,
+            ),
+        };
 
         let (viewport_width, viewport_height) = stage.viewport_size;
         let viewport_width = viewport_width as f64;
@@ -365,8 +520,8 @@ impl<'gc> Stage<'gc> {
             b: 0.0,
             c: 0.0,
             d: scale_y as f32,
-            tx: Twips::from_pixels(tx),
-            ty: Twips::from_pixels(ty),
+            tx: Twips::from_pixels(tx - origin_x * scale_x),
+            ty: Twips::from_pixels(ty - origin_y * scale_y),
         };
 
         self.0.write(context.gc_context).view_bounds = if self.should_letterbox(context.ui) {
@@ -385,10 +540,10 @@ impl<'gc> Stage<'gc> {
             let margin_top = ty / scale_y;
             let margin_bottom = (height_delta - ty) / scale_y;
             BoundingBox {
-                x_min: Twips::from_pixels(-margin_left),
-                y_min: Twips::from_pixels(-margin_top),
-                x_max: Twips::from_pixels(movie_width + margin_right),
-                y_max: Twips::from_pixels(movie_height + margin_bottom),
+                x_min: Twips::from_pixels(origin_x - margin_left),
+                y_min: Twips::from_pixels(origin_y - margin_top),
+                x_max: Twips::from_pixels(origin_x + movie_width + margin_right),
+                y_max: Twips::from_pixels(origin_y + movie_height + margin_bottom),
                 valid: true,
             }
         };
@@ -504,6 +659,42 @@ impl<'gc> Stage<'gc> {
             }
         }
     }
+
+    /// Fires `Stage.onFullScreen` in AVM1 or `FullScreenEvent.FULL_SCREEN` in AVM2.
+    fn fire_fullscreen_event(self, context: &mut UpdateContext<'_, 'gc, '_>, is_fullscreen: bool) {
+        let library = context.library.library_for_movie_mut(context.swf.clone());
+        if library.avm_type() == AvmType::Avm1 {
+            crate::avm1::Avm1::notify_system_listeners(
+                self.root_clip(),
+                context.swf.version(),
+                context,
+                "Stage",
+                "onFullScreen",
+                &[is_fullscreen.into()],
+            );
+        } else if let Avm2Value::Object(stage) = self.object2() {
+            let mut activation = Avm2Activation::from_nothing(context.reborrow());
+            let fullscreen_event_class = activation.avm2().classes().fullscreenevent;
+            // Ruffle's fullscreen always allows keyboard input.
+            let result = fullscreen_event_class
+                .construct(
+                    &mut activation,
+                    &[
+                        "fullScreen".into(),
+                        false.into(),
+                        false.into(),
+                        is_fullscreen.into(),
+                        is_fullscreen.into(),
+                    ],
+                )
+                .and_then(|event| {
+                    crate::avm2::Avm2::dispatch_event_object(&mut activation.context, event, stage)
+                });
+            if let Err(e) = result {
+                log::error!("Encountered AVM2 error when dispatching event: {}", e);
+            }
+        }
+    }
 }
 
 impl<'gc> TDisplayObject<'gc> for Stage<'gc> {
@@ -562,14 +753,42 @@ impl<'gc> TDisplayObject<'gc> for Stage<'gc> {
             .background_color()
             .unwrap_or_else(|| Color::from_rgb(0xffffff, 255));
 
+        context.renderer.set_quality(self.quality());
         context.renderer.begin_frame(background_color);
 
+        // Stage3D contexts are drawn beneath all display objects.
+        for stage3d in self.stage3ds().iter().filter_map(|o| o.as_stage3d()) {
+            if let Some(context3d) = stage3d
+                .context3d()
+                .and_then(|o| o.as_context3d())
+                .filter(|_| stage3d.visible())
+            {
+                let transform = Transform {
+                    matrix: self.matrix()
+                        * Matrix::translate(
+                            Twips::from_pixels(stage3d.x()),
+                            Twips::from_pixels(stage3d.y()),
+                        ),
+                    color_transform: Default::default(),
+                };
+                context3d.render(context.renderer, &transform);
+            }
+        }
+
         render_base((*self).into(), context);
 
         if self.should_letterbox(context.ui) {
             self.draw_letterbox(context);
         }
 
+        if let Some((bitmap, matrix)) = context.cursor_overlay {
+            let transform = Transform {
+                matrix,
+                color_transform: Default::default(),
+            };
+            context.renderer.render_bitmap(bitmap, &transform, false);
+        }
+
         context.renderer.end_frame();
     }
 
@@ -645,6 +864,48 @@ impl FromStr for StageScaleMode {
     }
 }
 
+/// The display state of a stage.
+/// This controls whether the player is fullscreen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Collect)]
+#[collect(require_static)]
+pub enum StageDisplayState {
+    /// The player is fullscreen, but keyboard input is limited to keys that can't be used to
+    /// enter text.
+    FullScreen,
+
+    /// The player is fullscreen with full keyboard input.
+    FullScreenInteractive,
+
+    /// The player isn't fullscreen.
+    Normal,
+}
+
+impl Display for StageDisplayState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Match string values returned by AS.
+        let s = match *self {
+            StageDisplayState::FullScreen => "fullScreen",
+            StageDisplayState::FullScreenInteractive => "fullScreenInteractive",
+            StageDisplayState::Normal => "normal",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for StageDisplayState {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let display_state = match s.to_ascii_lowercase().as_str() {
+            "fullscreen" => StageDisplayState::FullScreen,
+            "fullscreeninteractive" => StageDisplayState::FullScreenInteractive,
+            "normal" => StageDisplayState::Normal,
+            _ => return Err(ParseEnumError),
+        };
+        Ok(display_state)
+    }
+}
+
 bitflags! {
     /// The alignment of the stage.
     /// This controls the position of the movie after scaling to fill the viewport.
@@ -688,103 +949,3 @@ impl FromStr for StageAlign {
         Ok(align)
     }
 }
-
-/// The quality setting of the `Stage`.
-///
-/// In the Flash Player, this settings affects anti-aliasing and bitmap smoothing.
-/// These settings currently have no effect in Ruffle, but the active setting is still stored.
-/// [StageQuality in the AS3 Reference](https://help.adobe.com/en_US/FlashPlatform/reference/actionscript/3/flash/display/StageQuality.html)
-#[derive(Clone, Collect, Copy, Debug, Eq, PartialEq)]
-#[collect(require_static)]
-pub enum StageQuality {
-    /// No anti-aliasing, and bitmaps are never smoothed.
-    Low,
-
-    /// 2x anti-aliasing.
-    Medium,
-
-    /// 4x anti-aliasing.
-    High,
-
-    /// 4x anti-aliasing with high quality downsampling.
-    /// Bitmaps will use high quality downsampling when scaled down.
-    /// Despite the name, this is not the best quality setting as 8x8 and 16x16 modes were added to
-    /// Flash Player 11.3.
-    Best,
-
-    /// 8x anti-aliasing.
-    /// Bitmaps will use high quality downsampling when scaled down.
-    High8x8,
-
-    /// 8x anti-aliasing done in linear sRGB space.
-    /// Bitmaps will use high quality downsampling when scaled down.
-    High8x8Linear,
-
-    /// 16x anti-aliasing.
-    /// Bitmaps will use high quality downsampling when scaled down.
-    High16x16,
-
-    /// 16x anti-aliasing done in linear sRGB space.
-    /// Bitmaps will use high quality downsampling when scaled down.
-    High16x16Linear,
-}
-
-impl StageQuality {
-    /// Returns the string representing the quality setting as returned by AVM1 `_quality` and
-    /// AVM2 `Stage.quality`.
-    pub fn into_avm_str(self) -> &'static str {
-        // Flash Player always returns quality in uppercase, despite the AVM2 `StageQuality` being
-        // lowercase.
-        match self {
-            StageQuality::Low => "LOW",
-            StageQuality::Medium => "MEDIUM",
-            StageQuality::High => "HIGH",
-            StageQuality::Best => "BEST",
-            // The linear sRGB quality settings are not returned even if they are active.
-            StageQuality::High8x8 | StageQuality::High8x8Linear => "8X8",
-            StageQuality::High16x16 | StageQuality::High16x16Linear => "16X16",
-        }
-    }
-}
-
-impl Default for StageQuality {
-    fn default() -> StageQuality {
-        StageQuality::High
-    }
-}
-
-impl Display for StageQuality {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        // Match string values returned by AS.
-        let s = match *self {
-            StageQuality::Low => "low",
-            StageQuality::Medium => "medium",
-            StageQuality::High => "high",
-            StageQuality::Best => "best",
-            StageQuality::High8x8 => "8x8",
-            StageQuality::High8x8Linear => "8x8linear",
-            StageQuality::High16x16 => "16x16",
-            StageQuality::High16x16Linear => "16x16linear",
-        };
-        f.write_str(s)
-    }
-}
-
-impl FromStr for StageQuality {
-    type Err = ParseEnumError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let quality = match s.to_ascii_lowercase().as_str() {
-            "low" => StageQuality::Low,
-            "medium" => StageQuality::Medium,
-            "high" => StageQuality::High,
-            "best" => StageQuality::Best,
-            "8x8" => StageQuality::High8x8,
-            "8x8linear" => StageQuality::High8x8Linear,
-            "16x16" => StageQuality::High16x16,
-            "16x16linear" => StageQuality::High16x16Linear,
-            _ => return Err(ParseEnumError),
-        };
-        Ok(quality)
-    }
-}
@@ -18,6 +18,7 @@ pub struct TextData<'gc> {
     base: DisplayObjectBase<'gc>,
     static_data: gc_arena::Gc<'gc, TextStatic>,
     render_settings: TextRenderSettings,
+    highlight: TextHighlight,
 }
 
 impl<'gc> Text<'gc> {
@@ -41,10 +42,94 @@ impl<'gc> Text<'gc> {
                     },
                 ),
                 render_settings: Default::default(),
+                highlight: TextHighlight {
+                    selected: Vec::new(),
+                    found: None,
+                    color: Color::from_rgb(0xFFFF00, 255),
+                },
             },
         ))
     }
 
+    /// The number of glyphs in this text.
+    pub fn glyph_count(self) -> usize {
+        self.0
+            .read()
+            .static_data
+            .text_blocks
+            .iter()
+            .map(|block| block.glyphs.len())
+            .sum()
+    }
+
+    /// The characters this text displays, one for each glyph.
+    ///
+    /// Glyphs that their font maps no code point to, such as those of fonts
+    /// without a code table, are given as U+FFFD.
+    pub fn text(self, context: &UpdateContext<'_, 'gc, '_>) -> String {
+        let tf = self.0.read();
+        let library = context
+            .library
+            .library_for_movie(tf.static_data.swf.clone());
+        let mut text = String::new();
+        let mut font_id = 0;
+        for block in &tf.static_data.text_blocks {
+            font_id = block.font_id.unwrap_or(font_id);
+            let font = library.and_then(|library| library.get_font(font_id));
+            for glyph in &block.glyphs {
+                text.push(
+                    font.and_then(|font| font.get_char_for_glyph(glyph.index as usize))
+                        .unwrap_or(char::REPLACEMENT_CHARACTER),
+                );
+            }
+        }
+        text
+    }
+
+    /// Whether the glyph at `index` is selected.
+    pub fn is_selected(self, index: usize) -> bool {
+        self.0
+            .read()
+            .highlight
+            .selected
+            .get(index)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Select or deselect the glyphs from `start` up to, but not including,
+    /// `end`.
+    pub fn set_selected(
+        self,
+        gc_context: MutationContext<'gc, '_>,
+        start: usize,
+        end: usize,
+        selected: bool,
+    ) {
+        let end = end.min(self.glyph_count());
+        let mut tf = self.0.write(gc_context);
+        if tf.highlight.selected.len() < end {
+            tf.highlight.selected.resize(end, false);
+        }
+        for glyph in tf.highlight.selected.iter_mut().take(end).skip(start) {
+            *glyph = selected;
+        }
+    }
+
+    /// Set the color that selected glyphs are highlighted with.
+    pub fn set_select_color(self, gc_context: MutationContext<'gc, '_>, color: Color) {
+        self.0.write(gc_context).highlight.color = color;
+    }
+
+    /// Highlight the glyphs of a match from the player's find-in-text,
+    /// or clear the highlight with `None`.
+    ///
+    /// This is kept apart from the selection, so that finding text doesn't
+    /// disturb what the movie has selected.
+    pub fn set_found(self, gc_context: MutationContext<'gc, '_>, found: Option<(usize, usize)>) {
+        self.0.write(gc_context).highlight.found = found;
+    }
+
     pub fn set_render_settings(
         self,
         gc_context: MutationContext<'gc, '_>,
@@ -81,6 +166,10 @@ impl<'gc> TDisplayObject<'gc> for Text<'gc> {
         // Noop
     }
 
+    fn as_text(&self) -> Option<Text<'gc>> {
+        Some(*self)
+    }
+
     fn render_self(&self, context: &mut RenderContext) {
         let tf = self.0.read();
         context.transform_stack.push(&Transform {
@@ -97,6 +186,7 @@ impl<'gc> TDisplayObject<'gc> for Text<'gc> {
         let mut font_id = 0;
         let mut height = Twips::ZERO;
         let mut transform: Transform = Default::default();
+        let mut index = 0;
         for block in &tf.static_data.text_blocks {
             if let Some(x) = block.x_offset {
                 transform.matrix.tx = x;
@@ -118,15 +208,42 @@ impl<'gc> TDisplayObject<'gc> for Text<'gc> {
                 transform.matrix.d = scale;
                 transform.color_transform.set_mult_color(&color);
                 for c in &block.glyphs {
+                    if tf.highlight.contains(index) {
+                        // Fonts without layout information have no ascent,
+                        // so their highlight sits on top of the baseline.
+                        let ascent = match font.get_baseline_for_height(height) {
+                            Twips::ZERO => height,
+                            ascent => ascent,
+                        };
+                        let highlight_box = context.transform_stack.transform().matrix
+                            * Matrix::create_box(
+                                Twips::new(c.advance).to_pixels() as f32,
+                                height.to_pixels() as f32,
+                                0.0,
+                                transform.matrix.tx,
+                                transform.matrix.ty - ascent,
+                            );
+                        context
+                            .renderer
+                            .draw_rect(tf.highlight.color.clone(), &highlight_box);
+                    }
+                    index += 1;
+
                     if let Some(glyph) = font.get_glyph(c.index as usize) {
                         context.transform_stack.push(&transform);
                         context
                             .renderer
                             .render_shape(glyph.shape_handle, context.transform_stack.transform());
                         context.transform_stack.pop();
-                        transform.matrix.tx += Twips::new(c.advance);
                     }
+
+                    // Glyph advances are stored in the text record itself, and
+                    // apply even when the font lacks an outline for the glyph
+                    // (commonly the space character).
+                    transform.matrix.tx += Twips::new(c.advance);
                 }
+            } else {
+                index += block.glyphs.len();
             }
         }
         context.transform_stack.pop();
@@ -193,9 +310,9 @@ impl<'gc> TDisplayObject<'gc> for Text<'gc> {
                             {
                                 return true;
                             }
-
-                            glyph_matrix.tx += Twips::new(c.advance);
                         }
+
+                        glyph_matrix.tx += Twips::new(c.advance);
                     }
                 }
             }
@@ -216,3 +333,25 @@ struct TextStatic {
     text_transform: Matrix,
     text_blocks: Vec<swf::TextRecord>,
 }
+
+/// What is highlighted in a text, drawn as a box behind each glyph.
+#[derive(Clone, Debug, Collect)]
+#[collect(require_static)]
+struct TextHighlight {
+    /// Which glyphs are selected through `TextSnapshot`. Glyphs past the end
+    /// of this list are not selected.
+    selected: Vec<bool>,
+
+    /// The range of glyphs matched by the player's find-in-text.
+    found: Option<(usize, usize)>,
+
+    /// The color of the highlight.
+    color: Color,
+}
+
+impl TextHighlight {
+    fn contains(&self, index: usize) -> bool {
+        self.selected.get(index).copied().unwrap_or(false)
+            || matches!(self.found, Some((start, end)) if start <= index && index < end)
+    }
+}
@@ -1,33 +1,85 @@
 use crate::backend::render::{BitmapInfo, BitmapSource, ShapeHandle};
+use crate::bitmap::bitmap_data::BitmapData;
 use crate::bounding_box::BoundingBox;
-use crate::context::RenderContext;
-use crate::shape_utils::{DistilledShape, DrawCommand, DrawPath};
-use gc_arena::Collect;
+use crate::context::{RenderContext, UpdateContext};
+use crate::matrix::Matrix;
+use crate::shape_utils::{DistilledShape, DrawCommand, DrawPath, FillRule};
+use gc_arena::{Collect, GcCell};
 use std::cell::Cell;
 use swf::{FillStyle, LineStyle, Twips};
 
 #[derive(Clone, Debug, Collect)]
-#[collect(require_static)]
-pub struct Drawing {
+#[collect(no_drop)]
+pub struct Drawing<'gc> {
+    #[collect(require_static)]
     render_handle: Cell<Option<ShapeHandle>>,
+    #[collect(require_static)]
     shape_bounds: BoundingBox,
+    #[collect(require_static)]
     edge_bounds: BoundingBox,
+    #[collect(require_static)]
     dirty: Cell<bool>,
-    fills: Vec<(FillStyle, Vec<DrawCommand>)>,
+    #[collect(require_static)]
+    fills: Vec<DrawingFill>,
+    #[collect(require_static)]
     lines: Vec<(LineStyle, Vec<DrawCommand>)>,
+    #[collect(require_static)]
     bitmaps: Vec<BitmapInfo>,
-    current_fill: Option<(FillStyle, Vec<DrawCommand>)>,
+    bitmap_data: Vec<DrawingBitmapData<'gc>>,
+    #[collect(require_static)]
+    current_fill: Option<DrawingFill>,
+    #[collect(require_static)]
     current_line: Option<(LineStyle, Vec<DrawCommand>)>,
+    #[collect(require_static)]
     cursor: (Twips, Twips),
+    #[collect(require_static)]
+    history: Vec<DrawingCommand>,
 }
 
-impl Default for Drawing {
+/// A `BitmapData` used by the fills of a drawing, whose pixels may change
+/// after it was added.
+#[derive(Clone, Debug, Collect)]
+#[collect(no_drop)]
+struct DrawingBitmapData<'gc> {
+    /// The id of the bitmap that the fills refer to.
+    #[collect(require_static)]
+    id: u16,
+
+    bitmap_data: GcCell<'gc, BitmapData<'gc>>,
+}
+
+/// A fill of a drawing, and the path that it fills.
+#[derive(Clone, Debug)]
+struct DrawingFill {
+    style: FillStyle,
+    winding_rule: FillRule,
+    commands: Vec<DrawCommand>,
+}
+
+/// One of the changes that built up a drawing, kept in the order they were
+/// made so that the drawing can be read back.
+#[derive(Clone, Debug)]
+pub enum DrawingCommand {
+    /// Start filling with a style, or stop filling.
+    SetFill(Option<FillStyle>),
+
+    /// Start stroking with a style, or stop stroking.
+    SetLine(Option<LineStyle>),
+
+    /// Change how the current fill decides what's inside of it.
+    SetWindingRule(FillRule),
+
+    /// Draw with the current fill and stroke.
+    Draw(DrawCommand),
+}
+
+impl<'gc> Default for Drawing<'gc> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl Drawing {
+impl<'gc> Drawing<'gc> {
     pub fn new() -> Self {
         Self {
             render_handle: Cell::new(None),
@@ -37,9 +89,11 @@ impl Drawing {
             fills: Vec::new(),
             lines: Vec::new(),
             bitmaps: Vec::new(),
+            bitmap_data: Vec::new(),
             current_fill: None,
             current_line: None,
             cursor: (Twips::ZERO, Twips::ZERO),
+            history: Vec::new(),
         }
     }
 
@@ -52,9 +106,11 @@ impl Drawing {
             fills: Vec::new(),
             lines: Vec::new(),
             bitmaps: Vec::new(),
+            bitmap_data: Vec::new(),
             current_fill: None,
             current_line: None,
             cursor: (Twips::ZERO, Twips::ZERO),
+            history: Vec::new(),
         };
 
         let shape: DistilledShape = shape.into();
@@ -73,7 +129,11 @@ impl Drawing {
 
                     this.set_line_style(None);
                 }
-                DrawPath::Fill { style, commands } => {
+                DrawPath::Fill {
+                    style,
+                    commands,
+                    winding_rule: _,
+                } => {
                     this.set_fill_style(Some(style.clone()));
 
                     for command in commands {
@@ -91,28 +151,46 @@ impl Drawing {
     pub fn set_fill_style(&mut self, style: Option<FillStyle>) {
         // TODO: If current_fill is not closed, we should close it and also close current_line
 
+        self.history.push(DrawingCommand::SetFill(style.clone()));
+
         if let Some(existing) = self.current_fill.take() {
             self.fills.push(existing);
         }
         if let Some(style) = style {
-            self.current_fill = Some((
+            self.current_fill = Some(DrawingFill {
                 style,
-                vec![DrawCommand::MoveTo {
+                winding_rule: FillRule::EvenOdd,
+                commands: vec![DrawCommand::MoveTo {
                     x: self.cursor.0,
                     y: self.cursor.1,
                 }],
-            ));
+            });
         }
 
         self.dirty.set(true);
     }
 
+    /// Set how the current fill decides which of the areas enclosed by its
+    /// path are inside of it.
+    pub fn set_winding_rule(&mut self, winding_rule: FillRule) {
+        if let Some(fill) = &mut self.current_fill {
+            if fill.winding_rule != winding_rule {
+                self.history
+                    .push(DrawingCommand::SetWindingRule(winding_rule));
+                fill.winding_rule = winding_rule;
+                self.dirty.set(true);
+            }
+        }
+    }
+
     pub fn clear(&mut self) {
         self.current_fill = None;
         self.current_line = None;
         self.fills.clear();
         self.lines.clear();
         self.bitmaps.clear();
+        self.bitmap_data.clear();
+        self.history.clear();
         self.edge_bounds = BoundingBox::default();
         self.shape_bounds = BoundingBox::default();
         self.dirty.set(true);
@@ -120,6 +198,8 @@ impl Drawing {
     }
 
     pub fn set_line_style(&mut self, style: Option<LineStyle>) {
+        self.history.push(DrawingCommand::SetLine(style.clone()));
+
         if let Some(existing) = self.current_line.take() {
             self.lines.push(existing);
         }
@@ -136,7 +216,21 @@ impl Drawing {
         self.dirty.set(true);
     }
 
+    /// Stroke the current line with a fill, such as a bitmap, instead of its
+    /// color. Does nothing if there is no current line.
+    pub fn set_line_fill_style(&mut self, fill_style: FillStyle) {
+        if let Some((style, _)) = &self.current_line {
+            let style = LineStyle {
+                fill_style: Some(fill_style),
+                ..style.clone()
+            };
+            self.set_line_style(Some(style));
+        }
+    }
+
     pub fn draw_command(&mut self, command: DrawCommand) {
+        self.history.push(DrawingCommand::Draw(command.clone()));
+
         let mut include_last = false;
         let stroke_width = if let Some((style, _)) = &self.current_line {
             style.width
@@ -163,15 +257,15 @@ impl Drawing {
         if let Some((_, commands)) = &mut self.current_line {
             commands.push(command.clone());
         }
-        if let Some((_, commands)) = &mut self.current_fill {
-            commands.push(command);
+        if let Some(fill) = &mut self.current_fill {
+            fill.commands.push(command);
         }
 
         if include_last {
             if let Some(command) = self
                 .current_fill
                 .as_ref()
-                .and_then(|(_, commands)| commands.last())
+                .and_then(|fill| fill.commands.last())
             {
                 stretch_bounding_box(&mut self.shape_bounds, command, stroke_width);
                 stretch_bounding_box(&mut self.edge_bounds, command, Twips::ZERO);
@@ -190,30 +284,269 @@ impl Drawing {
         self.dirty.set(true);
     }
 
+    /// Draw a cubic Bézier curve from the cursor to `end`.
+    ///
+    /// Shapes can only hold quadratic curves, so the cubic is split into as
+    /// many quadratic pieces as it takes to stay within half a pixel of it.
+    pub fn draw_cubic_curve(
+        &mut self,
+        control_a: (Twips, Twips),
+        control_b: (Twips, Twips),
+        end: (Twips, Twips),
+    ) {
+        let point = |(x, y): (Twips, Twips)| (x.get() as f64, y.get() as f64);
+        let p0 = point(self.cursor);
+        let p1 = point(control_a);
+        let p2 = point(control_b);
+        let p3 = point(end);
+
+        // The distance between a cubic and its quadratic approximation is at
+        // most sqrt(3) / 36 of this, shrinking with the cube of the pieces.
+        let third_difference = (
+            p3.0 - 3.0 * p2.0 + 3.0 * p1.0 - p0.0,
+            p3.1 - 3.0 * p2.1 + 3.0 * p1.1 - p0.1,
+        );
+        let error = 3f64.sqrt() / 36.0 * third_difference.0.hypot(third_difference.1);
+        let tolerance = 10.0;
+        let pieces = (error / tolerance).cbrt().ceil().max(1.0).min(16.0) as usize;
+
+        let position = |t: f64| {
+            let s = 1.0 - t;
+            (
+                s * s * s * p0.0
+                    + 3.0 * s * s * t * p1.0
+                    + 3.0 * s * t * t * p2.0
+                    + t * t * t * p3.0,
+                s * s * s * p0.1
+                    + 3.0 * s * s * t * p1.1
+                    + 3.0 * s * t * t * p2.1
+                    + t * t * t * p3.1,
+            )
+        };
+        let tangent = |t: f64| {
+            let s = 1.0 - t;
+            (
+                3.0 * (s * s * (p1.0 - p0.0) + 2.0 * s * t * (p2.0 - p1.0) + t * t * (p3.0 - p2.0)),
+                3.0 * (s * s * (p1.1 - p0.1) + 2.0 * s * t * (p2.1 - p1.1) + t * t * (p3.1 - p2.1)),
+            )
+        };
+
+        for piece in 0..pieces {
+            let t0 = piece as f64 / pieces as f64;
+            let t1 = (piece + 1) as f64 / pieces as f64;
+            let step = (t1 - t0) / 3.0;
+
+            // The control points of this piece of the cubic.
+            let start = position(t0);
+            let finish = if piece + 1 == pieces {
+                p3
+            } else {
+                position(t1)
+            };
+            let start_tangent = tangent(t0);
+            let finish_tangent = tangent(t1);
+            let c1 = (
+                start.0 + step * start_tangent.0,
+                start.1 + step * start_tangent.1,
+            );
+            let c2 = (
+                finish.0 - step * finish_tangent.0,
+                finish.1 - step * finish_tangent.1,
+            );
+
+            // The quadratic that best matches this piece of the cubic.
+            let control = (
+                (3.0 * (c1.0 + c2.0) - start.0 - finish.0) / 4.0,
+                (3.0 * (c1.1 + c2.1) - start.1 - finish.1) / 4.0,
+            );
+
+            self.draw_command(DrawCommand::CurveTo {
+                x1: Twips::new(control.0.round() as i32),
+                y1: Twips::new(control.1.round() as i32),
+                x2: Twips::new(finish.0.round() as i32),
+                y2: Twips::new(finish.1.round() as i32),
+            });
+        }
+    }
+
+    /// Replace this drawing with a copy of `other`, keeping this drawing's
+    /// render handle so that the copy is drawn in its place.
+    pub fn copy_from(&mut self, other: &Drawing<'gc>) {
+        let render_handle = self.render_handle.get();
+        *self = other.clone();
+        self.render_handle.set(render_handle);
+        self.dirty.set(true);
+    }
+
+    /// Draw one triangle of a mesh, filling it with the current fill and
+    /// outlining it with the current line.
+    ///
+    /// When the current fill is a bitmap, `uvts` gives the point of the bitmap
+    /// drawn at each corner, scaled from 0 to 1 across its width and height,
+    /// along with the corner's perspective weight.
+    pub fn draw_triangle(
+        &mut self,
+        vertices: [(Twips, Twips); 3],
+        uvts: Option<[(f64, f64, f64); 3]>,
+    ) {
+        let stroke_width = if let Some((style, _)) = &self.current_line {
+            style.width
+        } else {
+            Twips::ZERO
+        };
+        let outline = [
+            DrawCommand::MoveTo {
+                x: vertices[0].0,
+                y: vertices[0].1,
+            },
+            DrawCommand::LineTo {
+                x: vertices[1].0,
+                y: vertices[1].1,
+            },
+            DrawCommand::LineTo {
+                x: vertices[2].0,
+                y: vertices[2].1,
+            },
+            DrawCommand::LineTo {
+                x: vertices[0].0,
+                y: vertices[0].1,
+            },
+        ];
+        for command in &outline {
+            stretch_bounding_box(&mut self.shape_bounds, command, stroke_width);
+            stretch_bounding_box(&mut self.edge_bounds, command, Twips::ZERO);
+        }
+
+        let mut triangle_fills = Vec::new();
+        if let Some(fill) = &self.current_fill {
+            match (&fill.style, uvts) {
+                (
+                    FillStyle::Bitmap {
+                        id,
+                        is_smoothed,
+                        is_repeating,
+                        ..
+                    },
+                    Some(uvts),
+                ) => {
+                    if let Some(bitmap) = self.bitmaps.get(usize::from(*id)) {
+                        let size = (f64::from(bitmap.width), f64::from(bitmap.height));
+                        for (vertices, uvs) in perspective_triangles(vertices, uvts) {
+                            if let Some(matrix) = triangle_bitmap_matrix(vertices, uvs, size) {
+                                triangle_fills.push(DrawingFill {
+                                    style: FillStyle::Bitmap {
+                                        id: *id,
+                                        matrix: matrix.into(),
+                                        is_smoothed: *is_smoothed,
+                                        is_repeating: *is_repeating,
+                                    },
+                                    winding_rule: FillRule::EvenOdd,
+                                    commands: triangle_commands(vertices),
+                                });
+                            }
+                        }
+                    }
+                }
+                (style, _) => triangle_fills.push(DrawingFill {
+                    style: style.clone(),
+                    winding_rule: FillRule::EvenOdd,
+                    commands: outline.to_vec(),
+                }),
+            }
+        }
+        self.fills.extend(triangle_fills);
+
+        if let Some((_, commands)) = &mut self.current_line {
+            commands.extend_from_slice(&outline);
+            commands.push(DrawCommand::MoveTo {
+                x: self.cursor.0,
+                y: self.cursor.1,
+            });
+        }
+
+        self.dirty.set(true);
+    }
+
+    /// The changes that built up this drawing, in the order they were made.
+    pub fn history(&self) -> &[DrawingCommand] {
+        &self.history
+    }
+
     pub fn add_bitmap(&mut self, bitmap: BitmapInfo) -> u16 {
         let id = self.bitmaps.len() as u16;
         self.bitmaps.push(bitmap);
         id
     }
 
+    /// Add a `BitmapData` for fills to refer to, returning its bitmap id.
+    ///
+    /// Unlike bitmaps added with `add_bitmap`, the drawing keeps up with
+    /// changes made to the `BitmapData` after it was added. Adding the same
+    /// `BitmapData` again returns the id it already has.
+    pub fn add_bitmap_data(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        bitmap_data: GcCell<'gc, BitmapData<'gc>>,
+    ) -> Option<u16> {
+        if let Some(existing) = self
+            .bitmap_data
+            .iter()
+            .find(|existing| GcCell::ptr_eq(existing.bitmap_data, bitmap_data))
+        {
+            return Some(existing.id);
+        }
+
+        let mut write = bitmap_data.write(context.gc_context);
+        let handle = write.update_bitmap_handle(context.renderer)?;
+        let id = self.add_bitmap(BitmapInfo {
+            handle,
+            width: write.width() as u16,
+            height: write.height() as u16,
+        });
+        self.bitmap_data.push(DrawingBitmapData { id, bitmap_data });
+
+        Some(id)
+    }
+
+    /// Upload the pixels of any `BitmapData` used by this drawing that have
+    /// changed, redrawing the drawing if any of them were resized.
+    pub fn update_bitmap_data(&mut self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        for drawing_bitmap in &self.bitmap_data {
+            let mut bitmap_data = drawing_bitmap.bitmap_data.write(context.gc_context);
+            if !bitmap_data.dirty() {
+                continue;
+            }
+
+            if let Some(handle) = bitmap_data.update_bitmap_handle(context.renderer) {
+                let bitmap = BitmapInfo {
+                    handle,
+                    width: bitmap_data.width() as u16,
+                    height: bitmap_data.height() as u16,
+                };
+                let existing = &mut self.bitmaps[usize::from(drawing_bitmap.id)];
+                if existing.handle != bitmap.handle
+                    || existing.width != bitmap.width
+                    || existing.height != bitmap.height
+                {
+                    *existing = bitmap;
+                    self.dirty.set(true);
+                }
+            }
+        }
+    }
+
     pub fn render(&self, context: &mut RenderContext) {
         if self.dirty.get() {
             self.dirty.set(false);
             let mut paths = Vec::new();
 
-            for (style, commands) in &self.fills {
-                paths.push(DrawPath::Fill {
-                    style,
-                    commands: commands.to_owned(),
-                })
-            }
-
             // TODO: If the current_fill is not closed, we should automatically close current_line
 
-            if let Some((style, commands)) = &self.current_fill {
+            for fill in self.fills.iter().chain(self.current_fill.iter()) {
                 paths.push(DrawPath::Fill {
-                    style,
-                    commands: commands.to_owned(),
+                    style: &fill.style,
+                    commands: fill.commands.to_owned(),
+                    winding_rule: fill.winding_rule,
                 })
             }
 
@@ -260,8 +593,8 @@ impl Drawing {
 
     pub fn hit_test(&self, point: (Twips, Twips), local_matrix: &crate::matrix::Matrix) -> bool {
         use crate::shape_utils;
-        for path in &self.fills {
-            if shape_utils::draw_command_fill_hit_test(&path.1, point) {
+        for fill in &self.fills {
+            if shape_utils::draw_command_fill_hit_test(&fill.commands, point) {
                 return true;
             }
         }
@@ -286,7 +619,7 @@ impl Drawing {
     }
 }
 
-impl BitmapSource for Drawing {
+impl<'gc> BitmapSource for Drawing<'gc> {
     fn bitmap(&self, id: u16) -> Option<BitmapInfo> {
         self.bitmaps.get(id as usize).cloned()
     }
@@ -315,3 +648,330 @@ fn stretch_bounding_box(
         }
     }
 }
+
+/// The closed path around a triangle whose corners are given in twips.
+fn triangle_commands(vertices: [(f64, f64); 3]) -> Vec<DrawCommand> {
+    let point = |(x, y): (f64, f64)| (Twips::new(x.round() as i32), Twips::new(y.round() as i32));
+    let (x0, y0) = point(vertices[0]);
+    let (x1, y1) = point(vertices[1]);
+    let (x2, y2) = point(vertices[2]);
+    vec![
+        DrawCommand::MoveTo { x: x0, y: y0 },
+        DrawCommand::LineTo { x: x1, y: y1 },
+        DrawCommand::LineTo { x: x2, y: y2 },
+        DrawCommand::LineTo { x: x0, y: y0 },
+    ]
+}
+
+/// Split a triangle into smaller triangles that each map their bitmap
+/// coordinates affinely, approximating perspective-correct mapping.
+///
+/// Triangles whose corners all have the same perspective weight are already
+/// mapped correctly, and are returned whole.
+#[allow(clippy::type_complexity)]
+fn perspective_triangles(
+    vertices: [(Twips, Twips); 3],
+    uvts: [(f64, f64, f64); 3],
+) -> Vec<([(f64, f64); 3], [(f64, f64); 3])> {
+    let (min_t, max_t) = uvts
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), uvt| {
+            (min.min(uvt.2), max.max(uvt.2))
+        });
+    let (divisions, weights) = if min_t > 0.0 && max_t.is_finite() {
+        let divisions = ((max_t / min_t - 1.0) * 8.0).ceil().max(1.0).min(8.0) as usize;
+        (divisions, [uvts[0].2, uvts[1].2, uvts[2].2])
+    } else {
+        (1, [1.0; 3])
+    };
+
+    let x = |i: usize| vertices[i].0.get() as f64;
+    let y = |i: usize| vertices[i].1.get() as f64;
+    let point = |i: usize, j: usize| {
+        let b = i as f64 / divisions as f64;
+        let c = j as f64 / divisions as f64;
+        let a = 1.0 - b - c;
+        let position = (
+            a * x(0) + b * x(1) + c * x(2),
+            a * y(0) + b * y(1) + c * y(2),
+        );
+        let weight = a * weights[0] + b * weights[1] + c * weights[2];
+        let uv = (
+            (a * uvts[0].0 * weights[0] + b * uvts[1].0 * weights[1] + c * uvts[2].0 * weights[2])
+                / weight,
+            (a * uvts[0].1 * weights[0] + b * uvts[1].1 * weights[1] + c * uvts[2].1 * weights[2])
+                / weight,
+        );
+        (position, uv)
+    };
+    let triangle = |corners: [(usize, usize); 3]| {
+        let corners = [
+            point(corners[0].0, corners[0].1),
+            point(corners[1].0, corners[1].1),
+            point(corners[2].0, corners[2].1),
+        ];
+        (
+            [corners[0].0, corners[1].0, corners[2].0],
+            [corners[0].1, corners[1].1, corners[2].1],
+        )
+    };
+
+    let mut triangles = Vec::with_capacity(divisions * divisions);
+    for i in 0..divisions {
+        for j in 0..divisions - i {
+            triangles.push(triangle([(i, j), (i + 1, j), (i, j + 1)]));
+            if i + j + 1 < divisions {
+                triangles.push(triangle([(i + 1, j), (i + 1, j + 1), (i, j + 1)]));
+            }
+        }
+    }
+    triangles
+}
+
+/// Find the bitmap fill matrix that draws the given points of a bitmap at the
+/// corners of a triangle.
+///
+/// Returns `None` if the bitmap points don't form a triangle.
+fn triangle_bitmap_matrix(
+    vertices: [(f64, f64); 3],
+    uvs: [(f64, f64); 3],
+    (width, height): (f64, f64),
+) -> Option<Matrix> {
+    // Bitmap fill matrices map from twips of the bitmap to twips of the shape.
+    let bitmap_point = |(u, v): (f64, f64)| (u * width * 20.0, v * height * 20.0);
+    let (qx0, qy0) = bitmap_point(uvs[0]);
+    let (qx1, qy1) = bitmap_point(uvs[1]);
+    let (qx2, qy2) = bitmap_point(uvs[2]);
+    let (dqx1, dqy1) = (qx1 - qx0, qy1 - qy0);
+    let (dqx2, dqy2) = (qx2 - qx0, qy2 - qy0);
+    let det = dqx1 * dqy2 - dqx2 * dqy1;
+    if det == 0.0 || !det.is_finite() {
+        return None;
+    }
+
+    let (dpx1, dpy1) = (vertices[1].0 - vertices[0].0, vertices[1].1 - vertices[0].1);
+    let (dpx2, dpy2) = (vertices[2].0 - vertices[0].0, vertices[2].1 - vertices[0].1);
+    let a = (dpx1 * dqy2 - dpx2 * dqy1) / det;
+    let c = (dpx2 * dqx1 - dpx1 * dqx2) / det;
+    let b = (dpy1 * dqy2 - dpy2 * dqy1) / det;
+    let d = (dpy2 * dqx1 - dpy1 * dqx2) / det;
+    let tx = vertices[0].0 - (a * qx0 + c * qy0);
+    let ty = vertices[0].1 - (b * qx0 + d * qy0);
+
+    Some(Matrix {
+        a: a as f32,
+        b: b as f32,
+        c: c as f32,
+        d: d as f32,
+        tx: Twips::new(tx.round() as i32),
+        ty: Twips::new(ty.round() as i32),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_is_kept_in_order() {
+        let mut drawing = Drawing::new();
+        // Without a fill, there's no winding rule to change.
+        drawing.set_winding_rule(FillRule::NonZero);
+        drawing.set_fill_style(Some(FillStyle::Color(swf::Color::from_rgb(0xFF0000, 255))));
+        drawing.set_winding_rule(FillRule::EvenOdd);
+        drawing.set_winding_rule(FillRule::NonZero);
+        drawing.draw_command(DrawCommand::LineTo {
+            x: Twips::from_pixels(10.0),
+            y: Twips::ZERO,
+        });
+        drawing.set_fill_style(None);
+
+        let history = drawing.history();
+        assert_eq!(history.len(), 4);
+        assert!(matches!(
+            history[0],
+            DrawingCommand::SetFill(Some(FillStyle::Color(_)))
+        ));
+        assert!(matches!(
+            history[1],
+            DrawingCommand::SetWindingRule(FillRule::NonZero)
+        ));
+        assert!(matches!(
+            history[2],
+            DrawingCommand::Draw(DrawCommand::LineTo { .. })
+        ));
+        assert!(matches!(history[3], DrawingCommand::SetFill(None)));
+
+        drawing.clear();
+        assert!(drawing.history().is_empty());
+    }
+
+    #[test]
+    fn cubic_curves_are_split_into_quadratics() {
+        let point = |x, y| (Twips::new(x), Twips::new(y));
+        let cubic = |t: f64, p: [f64; 4]| {
+            let s = 1.0 - t;
+            s * s * s * p[0] + 3.0 * s * s * t * p[1] + 3.0 * s * t * t * p[2] + t * t * t * p[3]
+        };
+
+        let mut drawing = Drawing::new();
+        drawing.draw_cubic_curve(point(0, 2000), point(2000, 2000), point(2000, 0));
+        let curves: Vec<_> = drawing
+            .history()
+            .iter()
+            .map(|command| match command {
+                DrawingCommand::Draw(DrawCommand::CurveTo { x1, y1, x2, y2 }) => {
+                    [x1.get(), y1.get(), x2.get(), y2.get()]
+                }
+                _ => panic!("cubic should only draw quadratic curves"),
+            })
+            .collect();
+        assert_eq!(curves.len(), 3);
+        assert_eq!(&curves[2][2..], &[2000, 0]);
+        assert_eq!(drawing.cursor, point(2000, 0));
+
+        // The middle of each quadratic stays within half a pixel of the cubic.
+        let mut start = (0.0, 0.0);
+        for (i, curve) in curves.iter().enumerate() {
+            let middle = (
+                0.25 * start.0 + 0.5 * curve[0] as f64 + 0.25 * curve[2] as f64,
+                0.25 * start.1 + 0.5 * curve[1] as f64 + 0.25 * curve[3] as f64,
+            );
+            let t = (i as f64 + 0.5) / 3.0;
+            let expected = (
+                cubic(t, [0.0, 0.0, 2000.0, 2000.0]),
+                cubic(t, [0.0, 2000.0, 2000.0, 0.0]),
+            );
+            assert!((middle.0 - expected.0).hypot(middle.1 - expected.1) <= 10.0);
+            start = (curve[2] as f64, curve[3] as f64);
+        }
+
+        // A cubic that is already a quadratic needs only one piece.
+        let mut drawing = Drawing::new();
+        drawing.draw_cubic_curve(point(200, 200), point(400, 400), point(600, 600));
+        assert_eq!(drawing.history().len(), 1);
+    }
+
+    #[test]
+    fn copy_from_keeps_render_handle() {
+        let mut source = Drawing::new();
+        source.set_fill_style(Some(FillStyle::Color(swf::Color::from_rgb(0xFF0000, 255))));
+        source.draw_command(DrawCommand::LineTo {
+            x: Twips::from_pixels(10.0),
+            y: Twips::from_pixels(20.0),
+        });
+
+        let mut drawing = Drawing::new();
+        drawing.render_handle.set(Some(ShapeHandle(3)));
+        drawing.set_line_style(Some(LineStyle::new_v1(
+            Twips::from_pixels(1.0),
+            swf::Color::from_rgb(0, 255),
+        )));
+        drawing.dirty.set(false);
+        drawing.copy_from(&source);
+
+        assert!(matches!(drawing.render_handle.get(), Some(ShapeHandle(3))));
+        assert!(drawing.dirty.get());
+        assert!(drawing.current_line.is_none());
+        assert_eq!(drawing.history().len(), 2);
+        assert_eq!(drawing.self_bounds(), source.self_bounds());
+    }
+
+    #[test]
+    fn draw_triangle_adds_a_fill() {
+        let mut drawing = Drawing::new();
+        let vertices = [
+            (Twips::from_pixels(10.0), Twips::from_pixels(10.0)),
+            (Twips::from_pixels(30.0), Twips::from_pixels(10.0)),
+            (Twips::from_pixels(10.0), Twips::from_pixels(50.0)),
+        ];
+
+        // Without a fill, a triangle still takes up space.
+        drawing.draw_triangle(vertices, None);
+        assert!(drawing.fills.is_empty());
+        let bounds = drawing.self_bounds();
+        assert_eq!(bounds.x_min, Twips::from_pixels(10.0));
+        assert_eq!(bounds.x_max, Twips::from_pixels(30.0));
+        assert_eq!(bounds.y_min, Twips::from_pixels(10.0));
+        assert_eq!(bounds.y_max, Twips::from_pixels(50.0));
+
+        drawing.set_fill_style(Some(FillStyle::Color(swf::Color::from_rgb(0xFF0000, 255))));
+        drawing.draw_triangle(vertices, Some([(0.0, 0.0, 1.0); 3]));
+        assert_eq!(drawing.fills.len(), 1);
+        let fill = &drawing.fills[0];
+        assert!(matches!(fill.style, FillStyle::Color(_)));
+        assert_eq!(fill.commands.len(), 4);
+        assert!(matches!(
+            fill.commands[0],
+            DrawCommand::MoveTo { x, y } if (x, y) == vertices[0]
+        ));
+        assert!(matches!(
+            fill.commands[3],
+            DrawCommand::LineTo { x, y } if (x, y) == vertices[0]
+        ));
+    }
+
+    #[test]
+    fn triangle_bitmap_matrix_maps_corners() {
+        let vertices = [(100.0, 100.0), (300.0, 100.0), (100.0, 500.0)];
+        let uvs = [(1.0, 0.0), (1.0, 1.0), (0.0, 0.0)];
+        let matrix = triangle_bitmap_matrix(vertices, uvs, (10.0, 10.0)).unwrap();
+
+        // The bitmap is 200 twips square.
+        let corners = [(200, 0), (200, 200), (0, 0)];
+        for (corner, vertex) in corners.iter().zip(&vertices) {
+            let (x, y) = matrix * (Twips::new(corner.0), Twips::new(corner.1));
+            assert_eq!((x.get() as f64, y.get() as f64), *vertex);
+        }
+
+        // Bitmap points in a line don't make a triangle.
+        let uvs = [(0.0, 0.0), (0.5, 0.5), (1.0, 1.0)];
+        assert!(triangle_bitmap_matrix(vertices, uvs, (10.0, 10.0)).is_none());
+    }
+
+    #[test]
+    fn perspective_triangles_keep_flat_triangles_whole() {
+        let vertices = [
+            (Twips::new(0), Twips::new(0)),
+            (Twips::new(200), Twips::new(0)),
+            (Twips::new(0), Twips::new(200)),
+        ];
+        let uvts = [(0.0, 0.0, 2.0), (1.0, 0.0, 2.0), (0.0, 1.0, 2.0)];
+        let triangles = perspective_triangles(vertices, uvts);
+        assert_eq!(
+            triangles,
+            vec![(
+                [(0.0, 0.0), (200.0, 0.0), (0.0, 200.0)],
+                [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]
+            )]
+        );
+    }
+
+    #[test]
+    fn perspective_triangles_subdivide_by_depth() {
+        let vertices = [
+            (Twips::new(0), Twips::new(0)),
+            (Twips::new(800), Twips::new(0)),
+            (Twips::new(0), Twips::new(800)),
+        ];
+        let uvts = [(0.0, 0.0, 1.0), (1.0, 0.0, 2.0), (0.0, 1.0, 1.0)];
+        let triangles = perspective_triangles(vertices, uvts);
+        assert_eq!(triangles.len(), 64);
+
+        // The smaller triangles cover the whole triangle.
+        let area = |[(x0, y0), (x1, y1), (x2, y2)]: [(f64, f64); 3]| {
+            ((x1 - x0) * (y2 - y0) - (x2 - x0) * (y1 - y0)) / 2.0
+        };
+        let total: f64 = triangles.iter().map(|(vertices, _)| area(*vertices)).sum();
+        assert!((total - 320000.0).abs() < 1e-6);
+
+        // The middle of the far edge is nearer to its deeper corner in the bitmap.
+        let (_, uv) = triangles
+            .iter()
+            .flat_map(|(vertices, uvs)| vertices.iter().zip(uvs.iter()))
+            .find(|(vertex, _)| **vertex == (400.0, 0.0))
+            .unwrap();
+        assert!((uv.0 - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(uv.1, 0.0);
+    }
+}
@@ -63,3 +63,33 @@ pub fn f64_to_wrapping_u32(n: f64) -> u32 {
 pub fn f64_to_wrapping_i32(n: f64) -> i32 {
     f64_to_wrapping_u32(n) as i32
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrapping_u32() {
+        assert_eq!(f64_to_wrapping_u32(0.0), 0);
+        assert_eq!(f64_to_wrapping_u32(-0.0), 0);
+        assert_eq!(f64_to_wrapping_u32(f64::NAN), 0);
+        assert_eq!(f64_to_wrapping_u32(f64::INFINITY), 0);
+        assert_eq!(f64_to_wrapping_u32(f64::NEG_INFINITY), 0);
+        assert_eq!(f64_to_wrapping_u32(-1.0), 0xFFFF_FFFF);
+        assert_eq!(f64_to_wrapping_u32(-1.9), 0xFFFF_FFFF);
+        assert_eq!(f64_to_wrapping_u32(4_294_967_296.0), 0);
+        assert_eq!(f64_to_wrapping_u32(4_294_967_297.5), 1);
+        assert_eq!(f64_to_wrapping_u32(1e20), 1_661_992_960);
+    }
+
+    #[test]
+    fn wrapping_i32() {
+        assert_eq!(f64_to_wrapping_i32(2_147_483_647.0), i32::MAX);
+        assert_eq!(f64_to_wrapping_i32(2_147_483_648.0), i32::MIN);
+        assert_eq!(f64_to_wrapping_i32(-2_147_483_649.0), i32::MAX);
+        assert_eq!(f64_to_wrapping_i32(4_294_967_295.0), -1);
+        assert_eq!(f64_to_wrapping_i32(-3.7), -3);
+        assert_eq!(f64_to_wrapping_i32(3.7), 3);
+        assert_eq!(f64_to_wrapping_i32(-1e20), -1_661_992_960);
+    }
+}
@@ -1,15 +1,115 @@
+pub use ruffle_ui_api::keyboard::{KeyCode, KeyLocation};
+use std::collections::HashMap;
 use swf::ClipEventFlag;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum PlayerEvent {
-    KeyDown { key_code: KeyCode },
-    KeyUp { key_code: KeyCode },
-    MouseMove { x: f64, y: f64 },
-    MouseUp { x: f64, y: f64 },
-    MouseDown { x: f64, y: f64 },
+    KeyDown {
+        key_code: KeyCode,
+    },
+    KeyUp {
+        key_code: KeyCode,
+    },
+    MouseMove {
+        x: f64,
+        y: f64,
+    },
+    MouseUp {
+        x: f64,
+        y: f64,
+    },
+    MouseDown {
+        x: f64,
+        y: f64,
+    },
     MouseLeft,
-    MouseWheel { delta: MouseWheelDelta },
-    TextInput { codepoint: char },
+    MouseWheel {
+        delta: MouseWheelDelta,
+    },
+    TextInput {
+        codepoint: char,
+    },
+    Touch {
+        id: i32,
+        phase: TouchPhase,
+        x: f64,
+        y: f64,
+    },
+    /// The user pasted text through the platform, rather than with a
+    /// shortcut that the player handles itself.
+    Paste {
+        text: String,
+    },
+    /// The user started composing text with an input method.
+    ImeStart,
+    /// The text that the user is composing with an input method changed.
+    /// It is shown in the focused text field, but isn't entered yet.
+    ImeUpdate {
+        text: String,
+    },
+    /// The user finished composing text with an input method. The text is
+    /// empty if the composition was cancelled.
+    ImeCommit {
+        text: String,
+    },
+    GamepadConnected {
+        id: u32,
+        name: String,
+    },
+    GamepadDisconnected {
+        id: u32,
+    },
+    GamepadInput {
+        id: u32,
+        control: GamepadControl,
+        value: f64,
+    },
+}
+
+impl PlayerEvent {
+    /// Whether content sees this event in plain `FULL_SCREEN` mode.
+    ///
+    /// That mode only lets through keys that can't be used to enter text.
+    pub fn is_allowed_in_full_screen(&self) -> bool {
+        match self {
+            PlayerEvent::KeyDown { key_code } | PlayerEvent::KeyUp { key_code } => matches!(
+                key_code,
+                KeyCode::Tab
+                    | KeyCode::Shift
+                    | KeyCode::Control
+                    | KeyCode::Space
+                    | KeyCode::PgUp
+                    | KeyCode::PgDown
+                    | KeyCode::End
+                    | KeyCode::Home
+                    | KeyCode::Left
+                    | KeyCode::Up
+                    | KeyCode::Right
+                    | KeyCode::Down
+            ),
+            PlayerEvent::TextInput { .. } => false,
+            _ => true,
+        }
+    }
+}
+
+/// A control on a game controller, by its index in the W3C "standard
+/// gamepad" layout.
+///
+/// Axes range from -1 to 1, and buttons from 0 to 1.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GamepadControl {
+    Axis(usize),
+    Button(usize),
+}
+
+/// The stage of a finger's contact with a touch screen.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TouchPhase {
+    Began,
+    Moved,
+    Ended,
+    Cancelled,
 }
 
 /// The distance scrolled by the mouse wheel.
@@ -162,110 +262,41 @@ impl ClipEvent {
     }
 }
 
-/// Flash virtual keycode.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, FromPrimitive)]
-pub enum KeyCode {
-    Unknown = 0,
-    Backspace = 8,
-    Tab = 9,
-    Return = 13,
-    Shift = 16,
-    Control = 17,
-    Alt = 18,
-    Pause = 19,
-    CapsLock = 20,
-    Escape = 27,
-    Space = 32,
-    PgUp = 33,
-    PgDown = 34,
-    End = 35,
-    Home = 36,
-    Left = 37,
-    Up = 38,
-    Right = 39,
-    Down = 40,
-    Insert = 45,
-    Delete = 46,
-    Key0 = 48,
-    Key1 = 49,
-    Key2 = 50,
-    Key3 = 51,
-    Key4 = 52,
-    Key5 = 53,
-    Key6 = 54,
-    Key7 = 55,
-    Key8 = 56,
-    Key9 = 57,
-    A = 65,
-    B = 66,
-    C = 67,
-    D = 68,
-    E = 69,
-    F = 70,
-    G = 71,
-    H = 72,
-    I = 73,
-    J = 74,
-    K = 75,
-    L = 76,
-    M = 77,
-    N = 78,
-    O = 79,
-    P = 80,
-    Q = 81,
-    R = 82,
-    S = 83,
-    T = 84,
-    U = 85,
-    V = 86,
-    W = 87,
-    X = 88,
-    Y = 89,
-    Z = 90,
-    Numpad0 = 96,
-    Numpad1 = 97,
-    Numpad2 = 98,
-    Numpad3 = 99,
-    Numpad4 = 100,
-    Numpad5 = 101,
-    Numpad6 = 102,
-    Numpad7 = 103,
-    Numpad8 = 104,
-    Numpad9 = 105,
-    Multiply = 106,
-    Plus = 107,
-    NumpadMinus = 109,
-    NumpadPeriod = 110,
-    NumpadSlash = 111,
-    F1 = 112,
-    F2 = 113,
-    F3 = 114,
-    F4 = 115,
-    F5 = 116,
-    F6 = 117,
-    F7 = 118,
-    F8 = 119,
-    F9 = 120,
-    F10 = 121,
-    F11 = 122,
-    F12 = 123,
-    ScrollLock = 145,
-    Semicolon = 186,
-    Equals = 187,
-    Comma = 188,
-    Minus = 189,
-    Period = 190,
-    Slash = 191,
-    Grave = 192,
-    LBracket = 219,
-    Backslash = 220,
-    RBracket = 221,
-    Apostrophe = 222,
+/// Rewrites the keys that the user presses into the keys that a movie sees,
+/// for games that are hardcoded to an awkward layout.
+#[derive(Debug, Clone, Default)]
+pub struct KeyRemap {
+    keys: HashMap<KeyCode, KeyCode>,
 }
 
-impl KeyCode {
-    pub fn from_u8(n: u8) -> Option<Self> {
-        num_traits::FromPrimitive::from_u8(n)
+impl KeyRemap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make pressing `from` look like pressing `to` to the movie.
+    pub fn insert(&mut self, from: KeyCode, to: KeyCode) {
+        self.keys.insert(from, to);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The key that the movie sees when `key` is pressed.
+    pub fn remap(&self, key: KeyCode) -> KeyCode {
+        self.keys.get(&key).copied().unwrap_or(key)
+    }
+
+    /// The keys that the user may press for the movie to see `key`.
+    pub fn sources(&self, key: KeyCode) -> impl Iterator<Item = KeyCode> + '_ {
+        let unmapped = Some(key).filter(|key| !self.keys.contains_key(key));
+        let mapped = self
+            .keys
+            .iter()
+            .filter(move |(_, to)| **to == key)
+            .map(|(from, _)| *from);
+        unmapped.into_iter().chain(mapped)
     }
 }
 
@@ -414,3 +445,47 @@ pub fn key_code_to_button_key_code(key_code: KeyCode) -> Option<ButtonKeyCode> {
     };
     Some(out)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_remap() {
+        let mut remap = KeyRemap::new();
+        remap.insert(KeyCode::W, KeyCode::Up);
+        remap.insert(KeyCode::Up, KeyCode::W);
+        remap.insert(KeyCode::Z, KeyCode::Space);
+
+        assert_eq!(remap.remap(KeyCode::W), KeyCode::Up);
+        assert_eq!(remap.remap(KeyCode::Up), KeyCode::W);
+        assert_eq!(remap.remap(KeyCode::A), KeyCode::A);
+
+        assert_eq!(remap.sources(KeyCode::Up).collect::<Vec<_>>(), [KeyCode::W]);
+        assert_eq!(remap.sources(KeyCode::A).collect::<Vec<_>>(), [KeyCode::A]);
+        let mut space = remap.sources(KeyCode::Space).collect::<Vec<_>>();
+        space.sort_by_key(|key| *key as u8);
+        assert_eq!(space, [KeyCode::Space, KeyCode::Z]);
+    }
+
+    #[test]
+    fn full_screen_allows_keys_that_cannot_enter_text() {
+        let key_down = |key_code| PlayerEvent::KeyDown { key_code };
+        let key_up = |key_code| PlayerEvent::KeyUp { key_code };
+        for key in [KeyCode::Space, KeyCode::Tab, KeyCode::Left, KeyCode::PgDown] {
+            assert!(key_down(key).is_allowed_in_full_screen());
+            assert!(key_up(key).is_allowed_in_full_screen());
+        }
+        for key in [
+            KeyCode::A,
+            KeyCode::Key1,
+            KeyCode::Return,
+            KeyCode::Backspace,
+        ] {
+            assert!(!key_down(key).is_allowed_in_full_screen());
+            assert!(!key_up(key).is_allowed_in_full_screen());
+        }
+        assert!(!PlayerEvent::TextInput { codepoint: 'a' }.is_allowed_in_full_screen());
+        assert!(PlayerEvent::MouseDown { x: 0.0, y: 0.0 }.is_allowed_in_full_screen());
+    }
+}
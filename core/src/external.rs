@@ -7,10 +7,18 @@ use crate::avm1::{
     ArrayObject as Avm1ArrayObject, Error as Avm1Error, Object as Avm1Object,
     ScriptObject as Avm1ScriptObject,
 };
+use crate::avm2::{
+    Activation as Avm2Activation, ArrayObject as Avm2ArrayObject, ArrayStorage, Error as Avm2Error,
+    Namespace as Avm2Namespace, Object as Avm2Object, QName as Avm2QName, TObject as _,
+    Value as Avm2Value,
+};
 use crate::context::UpdateContext;
 use crate::string::AvmString;
 use gc_arena::Collect;
+use quick_xml::events::Event;
+use quick_xml::Reader;
 use std::collections::BTreeMap;
+use std::rc::Rc;
 
 /// An intermediate format of representing shared data between ActionScript and elsewhere.
 /// Regardless of the capabilities of both sides, all data will be translated to this potentially
@@ -176,6 +184,305 @@ impl Value {
             .into(),
         }
     }
+
+    pub fn from_avm2<'gc>(
+        activation: &mut Avm2Activation<'_, 'gc, '_>,
+        value: Avm2Value<'gc>,
+    ) -> Result<Value, Avm2Error> {
+        Ok(match value {
+            Avm2Value::Undefined | Avm2Value::Null => Value::Null,
+            Avm2Value::Bool(value) => value.into(),
+            Avm2Value::Number(value) => value.into(),
+            Avm2Value::Unsigned(value) => value.into(),
+            Avm2Value::Integer(value) => value.into(),
+            Avm2Value::String(value) => Value::String(value.to_string()),
+            Avm2Value::Object(object) if object.as_executable().is_some() => Value::Null,
+            Avm2Value::Object(object) => {
+                let values = object
+                    .as_array_storage()
+                    .map(|array| array.iter().collect::<Vec<_>>());
+                if let Some(values) = values {
+                    let mut list = Vec::with_capacity(values.len());
+                    for value in values {
+                        let value = value.unwrap_or(Avm2Value::Undefined);
+                        list.push(Value::from_avm2(activation, value)?);
+                    }
+                    Value::List(list)
+                } else {
+                    let mut values = BTreeMap::new();
+                    let mut last_index = 0;
+                    while let Some(index) = object.get_next_enumerant(last_index) {
+                        if let Some(name) = object.get_enumerant_name(index, activation) {
+                            if name.namespace().is_public() {
+                                let value = object.get_property(object, &name, activation)?;
+                                values.insert(
+                                    name.local_name().to_string(),
+                                    Value::from_avm2(activation, value)?,
+                                );
+                            }
+                        }
+                        last_index = index;
+                    }
+                    Value::Object(values)
+                }
+            }
+        })
+    }
+
+    pub fn into_avm2<'gc>(
+        self,
+        activation: &mut Avm2Activation<'_, 'gc, '_>,
+    ) -> Result<Avm2Value<'gc>, Avm2Error> {
+        Ok(match self {
+            Value::Null => Avm2Value::Null,
+            Value::Bool(value) => Avm2Value::Bool(value),
+            Value::Number(value) => Avm2Value::Number(value),
+            Value::String(value) => {
+                Avm2Value::String(AvmString::new(activation.context.gc_context, value))
+            }
+            Value::Object(values) => {
+                let mut object = activation
+                    .avm2()
+                    .classes()
+                    .object
+                    .construct(activation, &[])?;
+                for (key, value) in values {
+                    let key = AvmString::new(activation.context.gc_context, key);
+                    let value = value.into_avm2(activation)?;
+                    object.set_property(
+                        object,
+                        &Avm2QName::new(Avm2Namespace::public(), key),
+                        value,
+                        activation,
+                    )?;
+                }
+                object.into()
+            }
+            Value::List(values) => {
+                let mut storage = Vec::with_capacity(values.len());
+                for value in values {
+                    storage.push(Some(value.into_avm2(activation)?));
+                }
+                Avm2ArrayObject::from_storage(activation, ArrayStorage::from_storage(storage))?
+                    .into()
+            }
+        })
+    }
+
+    /// Encode this value in the XML format that Flash Player uses to pass
+    /// values to and from JavaScript, such as `<number>1</number>`.
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::new();
+        self.write_xml(&mut xml);
+        xml
+    }
+
+    fn write_xml(&self, xml: &mut String) {
+        match self {
+            Value::Null => xml.push_str("<null/>"),
+            Value::Bool(true) => xml.push_str("<true/>"),
+            Value::Bool(false) => xml.push_str("<false/>"),
+            Value::Number(value) => {
+                xml.push_str("<number>");
+                if value.is_infinite() && *value > 0.0 {
+                    xml.push_str("Infinity");
+                } else if value.is_infinite() {
+                    xml.push_str("-Infinity");
+                } else {
+                    xml.push_str(&value.to_string());
+                }
+                xml.push_str("</number>");
+            }
+            Value::String(value) => {
+                xml.push_str("<string>");
+                xml.push_str(&escape_xml(value));
+                xml.push_str("</string>");
+            }
+            Value::Object(values) => {
+                xml.push_str("<object>");
+                for (key, value) in values {
+                    xml.push_str(&format!("<property id=\"{}\">", escape_xml(key)));
+                    value.write_xml(xml);
+                    xml.push_str("</property>");
+                }
+                xml.push_str("</object>");
+            }
+            Value::List(values) => {
+                xml.push_str("<array>");
+                for (index, value) in values.iter().enumerate() {
+                    xml.push_str(&format!("<property id=\"{}\">", index));
+                    value.write_xml(xml);
+                    xml.push_str("</property>");
+                }
+                xml.push_str("</array>");
+            }
+        }
+    }
+
+    /// Decode a value from the XML format that Flash Player uses to pass
+    /// values to and from JavaScript.
+    pub fn from_xml(xml: &str) -> Option<Value> {
+        XmlElement::parse(xml).and_then(|element| Value::from_xml_element(&element))
+    }
+
+    fn from_xml_element(element: &XmlElement) -> Option<Value> {
+        Some(match element.name.as_str() {
+            "undefined" | "null" => Value::Null,
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            // Dates are passed as their timestamp in milliseconds.
+            "number" | "date" => match element.text.trim() {
+                "Infinity" => Value::Number(f64::INFINITY),
+                "-Infinity" => Value::Number(f64::NEG_INFINITY),
+                text => Value::Number(text.parse().ok()?),
+            },
+            "string" => Value::String(element.text.clone()),
+            "array" => {
+                let mut values = Vec::new();
+                for (id, value) in element.properties() {
+                    let index: usize = id.parse().ok()?;
+                    if values.len() <= index {
+                        values.resize(index + 1, Value::Null);
+                    }
+                    values[index] = value;
+                }
+                Value::List(values)
+            }
+            "object" => Value::Object(element.properties().collect()),
+            _ => return None,
+        })
+    }
+}
+
+/// Parse a request to call a callback, in the XML format that Flash Player
+/// uses for calls from JavaScript:
+///
+/// ```xml
+/// <invoke name="callback" returntype="xml">
+///     <arguments><string>hello</string><number>1</number></arguments>
+/// </invoke>
+/// ```
+///
+/// Returns the name of the callback and its arguments.
+pub fn parse_invoke(xml: &str) -> Option<(String, Vec<Value>)> {
+    let invoke = XmlElement::parse(xml)?;
+    if invoke.name != "invoke" {
+        return None;
+    }
+
+    let name = invoke.attribute("name")?.to_string();
+    let args = match invoke
+        .children
+        .iter()
+        .find(|child| child.name == "arguments")
+    {
+        Some(arguments) => arguments
+            .children
+            .iter()
+            .map(Value::from_xml_element)
+            .collect::<Option<Vec<_>>>()?,
+        None => vec![],
+    };
+
+    Some((name, args))
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// An element of a value or request encoded in XML.
+struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    text: String,
+    children: Vec<XmlElement>,
+}
+
+impl XmlElement {
+    fn parse(xml: &str) -> Option<Self> {
+        let mut reader = Reader::from_str(xml);
+        let mut buf = Vec::new();
+        let mut open: Vec<XmlElement> = vec![];
+
+        loop {
+            let (element, is_empty) = match reader.read_event(&mut buf).ok()? {
+                Event::Start(start) => (start, false),
+                Event::Empty(start) => (start, true),
+                Event::End(_) => {
+                    let element = open.pop()?;
+                    match open.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => return Some(element),
+                    }
+                    buf.clear();
+                    continue;
+                }
+                Event::Text(text) | Event::CData(text) => {
+                    if let Some(element) = open.last_mut() {
+                        let text = text.unescaped().ok()?;
+                        element.text.push_str(&String::from_utf8_lossy(&text));
+                    }
+                    buf.clear();
+                    continue;
+                }
+                Event::Eof => return None,
+                _ => {
+                    buf.clear();
+                    continue;
+                }
+            };
+
+            let mut attributes = vec![];
+            for attribute in element.attributes() {
+                let attribute = attribute.ok()?;
+                let value = attribute.unescaped_value().ok()?;
+                attributes.push((
+                    String::from_utf8_lossy(attribute.key).into_owned(),
+                    String::from_utf8_lossy(&value).into_owned(),
+                ));
+            }
+            let element = XmlElement {
+                name: String::from_utf8_lossy(element.name()).into_owned(),
+                attributes,
+                text: String::new(),
+                children: vec![],
+            };
+            buf.clear();
+
+            if !is_empty {
+                open.push(element);
+            } else if let Some(parent) = open.last_mut() {
+                parent.children.push(element);
+            } else {
+                return Some(element);
+            }
+        }
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The values of the `<property>` children of an `<array>` or `<object>`,
+    /// by their `id`.
+    fn properties(&self) -> impl Iterator<Item = (String, Value)> + '_ {
+        self.children
+            .iter()
+            .filter(|child| child.name == "property")
+            .filter_map(|property| {
+                let id = property.attribute("id")?.to_string();
+                let value = Value::from_xml_element(property.children.first()?)?;
+                Some((id, value))
+            })
+    }
 }
 
 #[derive(Collect, Clone)]
@@ -185,6 +492,9 @@ pub enum Callback<'gc> {
         this: Avm1Value<'gc>,
         method: Avm1Object<'gc>,
     },
+    Avm2 {
+        method: Avm2Object<'gc>,
+    },
 }
 
 impl<'gc> Callback<'gc> {
@@ -220,8 +530,35 @@ impl<'gc> Callback<'gc> {
                     Value::Null
                 }
             }
+            Callback::Avm2 { method } => {
+                let mut activation = Avm2Activation::from_nothing(context.reborrow());
+                match Self::call_avm2(&mut activation, *method, args) {
+                    Ok(result) => result,
+                    Err(e) => {
+                        log::error!(
+                            "Unhandled AVM2 error in ExternalInterface callback {}: {}",
+                            name,
+                            e
+                        );
+                        Value::Null
+                    }
+                }
+            }
         }
     }
+
+    fn call_avm2(
+        activation: &mut Avm2Activation<'_, 'gc, '_>,
+        method: Avm2Object<'gc>,
+        args: impl IntoIterator<Item = Value>,
+    ) -> Result<Value, Avm2Error> {
+        let args = args
+            .into_iter()
+            .map(|v| v.into_avm2(activation))
+            .collect::<Result<Vec<_>, _>>()?;
+        let result = method.call(None, &args, activation, None)?;
+        Value::from_avm2(activation, result)
+    }
 }
 
 pub trait ExternalInterfaceProvider {
@@ -245,6 +582,72 @@ where
     }
 }
 
+/// An `ExternalInterfaceProvider` that answers calls with Rust functions.
+///
+/// Native embedders have no JavaScript container for movies to call into, and
+/// can use this to emulate the parts of one that a movie expects:
+///
+/// ```rust,ignore
+/// let provider = FunctionProvider::new().with_method(
+///     "getHighScore",
+///     |_: &mut UpdateContext<'_, '_, '_>, _: &[Value]| Value::Number(100.0),
+/// );
+/// player.add_external_interface(Box::new(provider));
+/// ```
+#[derive(Default)]
+pub struct FunctionProvider {
+    methods: BTreeMap<String, Rc<dyn ExternalInterfaceMethod>>,
+    fs_command: Option<Box<dyn Fn(&str, &str) -> bool>>,
+}
+
+impl FunctionProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer calls to `name` with `method`, replacing any method already
+    /// registered under that name.
+    pub fn with_method(
+        mut self,
+        name: impl Into<String>,
+        method: impl ExternalInterfaceMethod + 'static,
+    ) -> Self {
+        self.methods.insert(name.into(), Rc::new(method));
+        self
+    }
+
+    /// Handle `fscommand` calls with `handler`, which returns whether it
+    /// handled the command.
+    pub fn with_fs_command(mut self, handler: impl Fn(&str, &str) -> bool + 'static) -> Self {
+        self.fs_command = Some(Box::new(handler));
+        self
+    }
+}
+
+impl ExternalInterfaceProvider for FunctionProvider {
+    fn get_method(&self, name: &str) -> Option<Box<dyn ExternalInterfaceMethod>> {
+        let method = self.methods.get(name)?.clone();
+        Some(Box::new(SharedMethod(method)))
+    }
+
+    fn on_callback_available(&self, _name: &str) {}
+
+    fn on_fs_command(&self, command: &str, args: &str) -> bool {
+        match &self.fs_command {
+            Some(handler) => handler(command, args),
+            None => false,
+        }
+    }
+}
+
+struct SharedMethod(Rc<dyn ExternalInterfaceMethod>);
+
+impl ExternalInterfaceMethod for SharedMethod {
+    fn call(&self, context: &mut UpdateContext<'_, '_, '_>, args: &[Value]) -> Value {
+        self.0.call(context, args)
+    }
+}
+
 #[derive(Default, Collect)]
 #[collect(no_drop)]
 pub struct ExternalInterface<'gc> {
@@ -269,6 +672,10 @@ impl<'gc> ExternalInterface<'gc> {
         }
     }
 
+    pub fn remove_callback(&mut self, name: &str) {
+        self.callbacks.remove(name);
+    }
+
     pub fn get_callback(&self, name: &str) -> Option<Callback<'gc>> {
         self.callbacks.get(name).cloned()
     }
@@ -295,3 +702,72 @@ impl<'gc> ExternalInterface<'gc> {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xml_round_trip() {
+        let mut object = BTreeMap::new();
+        object.insert("a<b".to_string(), Value::Bool(true));
+        object.insert("list".to_string(), vec![1.into(), Value::Null].into());
+        let value = Value::List(vec![
+            "\"quoted\" & <tagged>".into(),
+            1.5.into(),
+            f64::NEG_INFINITY.into(),
+            object.into(),
+        ]);
+
+        assert_eq!(Value::from_xml(&value.to_xml()), Some(value));
+        assert_eq!(
+            Value::from_xml("<number>12</number>"),
+            Some(Value::Number(12.0))
+        );
+        assert_eq!(Value::from_xml("<undefined/>"), Some(Value::Null));
+        assert_eq!(Value::from_xml("<bogus/>"), None);
+    }
+
+    #[test]
+    fn invoke_request() {
+        let request = r#"<invoke name="submitScore" returntype="xml">
+            <arguments><string>player</string><number>100</number><array><property id="1"><false/></property></array></arguments>
+        </invoke>"#;
+        assert_eq!(
+            parse_invoke(request),
+            Some((
+                "submitScore".to_string(),
+                vec![
+                    "player".into(),
+                    100.into(),
+                    vec![Value::Null, false.into()].into()
+                ]
+            ))
+        );
+        assert_eq!(
+            parse_invoke(r#"<invoke name="ping"/>"#),
+            Some(("ping".to_string(), vec![]))
+        );
+        assert_eq!(parse_invoke("<arguments/>"), None);
+    }
+
+    #[test]
+    fn function_provider() {
+        let provider = FunctionProvider::new()
+            .with_method(
+                "answer",
+                |_: &mut UpdateContext<'_, '_, '_>, _: &[Value]| Value::Number(42.0),
+            )
+            .with_fs_command(|command, _| command == "quit");
+        assert!(provider.get_method("answer").is_some());
+        assert!(provider.get_method("question").is_none());
+        assert!(provider.on_fs_command("quit", ""));
+        assert!(!provider.on_fs_command("fullscreen", "true"));
+
+        let mut external_interface = ExternalInterface::new();
+        assert!(!external_interface.available());
+        external_interface.add_provider(Box::new(provider));
+        assert!(external_interface.available());
+        assert!(external_interface.get_method_for("answer").is_some());
+    }
+}
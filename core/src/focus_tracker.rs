@@ -41,6 +41,16 @@ impl<'gc> FocusTracker<'gc> {
             new.on_focus_changed(context.gc_context, true);
         }
 
+        // Input methods are only used while a text field is being edited.
+        if let Some(old_text) = old.and_then(|o| o.as_edit_text()) {
+            old_text.set_composition("", context);
+        }
+        let ime_position = focused_element
+            .and_then(|o| o.as_edit_text())
+            .filter(|text| text.is_editable() && !text.is_password())
+            .map(|text| text.ime_position(context));
+        context.ui.set_ime_position(ime_position);
+
         log::info!("Focus is now on {:?}", focused_element);
 
         let level0 = context.stage.root_clip();
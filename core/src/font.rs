@@ -6,6 +6,43 @@ use gc_arena::{Collect, Gc, MutationContext};
 
 pub use swf::TextGridFit;
 
+/// Characters that may not begin a line under CJK line breaking rules.
+const CJK_NO_LINE_START: &str = "!),.:;?]}¢°’”‰′″℃、。〉》」』】〕〗〙〟・！％），．：；？］｝～";
+
+/// Characters that additionally may not begin a line in Japanese text
+/// (small kana, the prolonged sound mark and iteration marks).
+const JAPANESE_NO_LINE_START: &str =
+    "ぁぃぅぇぉっゃゅょゎゕゖァィゥェォッャュョヮヵヶーヽヾゝゞ々〻";
+
+/// Characters that may not end a line under CJK line breaking rules.
+const CJK_NO_LINE_END: &str = "([{£¥‘“〈《「『【〔〖〘〝＄（［｛￡￥";
+
+/// Returns whether a character belongs to a script that is written without
+/// spaces between words (ideographs, kana, and their fullwidth punctuation).
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x2E80..=0x9FFF | 0xF900..=0xFAFF | 0xFE30..=0xFE4F | 0xFF00..=0xFFEF | 0x20000..=0x2FFFF
+    )
+}
+
+/// Determine if a line may be broken between two adjacent characters that
+/// have no space between them.
+///
+/// Only text in CJK scripts can be broken this way. The font's language
+/// code selects which characters are forbidden from starting a line.
+fn can_break_between(language: swf::Language, prev: char, next: char) -> bool {
+    if language == swf::Language::Korean || !(is_cjk(prev) || is_cjk(next)) {
+        return false;
+    }
+
+    if CJK_NO_LINE_END.contains(prev) || CJK_NO_LINE_START.contains(next) {
+        return false;
+    }
+
+    !(language == swf::Language::Japanese && JAPANESE_NO_LINE_START.contains(next))
+}
+
 /// Certain Flash routines measure text by rounding down to the nearest whole pixel.
 pub fn round_down_to_pixel(t: Twips) -> Twips {
     Twips::from_pixels(t.to_pixels().floor())
@@ -95,8 +132,16 @@ struct FontData {
     /// another, in EM-square coordinates.
     leading: i16,
 
+    /// The language code of the font, which controls line breaking of CJK
+    /// text.
+    language: swf::Language,
+
     /// The identity of the font.
     descriptor: FontDescriptor,
+
+    /// The glyph to render in place of characters that this font has no
+    /// glyph for. If `None`, such characters are skipped.
+    notdef: Option<Glyph>,
 }
 
 impl<'gc> Font<'gc> {
@@ -109,11 +154,7 @@ impl<'gc> Font<'gc> {
         let mut glyphs = vec![];
         let mut code_point_to_glyph = fnv::FnvHashMap::default();
         for swf_glyph in &tag.glyphs {
-            let glyph = Glyph {
-                shape_handle: renderer.register_glyph_shape(swf_glyph),
-                advance: swf_glyph.advance.unwrap_or(0),
-                shape: crate::shape_utils::swf_glyph_to_shape(swf_glyph),
-            };
+            let glyph = Glyph::from_swf_glyph(renderer, swf_glyph);
             let index = glyphs.len();
             glyphs.push(glyph);
             code_point_to_glyph.insert(swf_glyph.code, index);
@@ -148,11 +189,120 @@ impl<'gc> Font<'gc> {
                 ascent,
                 descent,
                 leading,
+                language: tag.language,
                 descriptor,
+                notdef: None,
             },
         )))
     }
 
+    /// Produce a copy of this font with the code table, name and style flags
+    /// of a `DefineFontInfo` tag applied.
+    ///
+    /// Glyph `i` of the font is mapped to the `i`th entry of the code table.
+    pub fn with_font_info(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        font_info: &swf::FontInfo,
+        encoding: &'static swf::Encoding,
+    ) -> Font<'gc> {
+        let code_point_to_glyph = font_info
+            .code_table
+            .iter()
+            .take(self.0.glyphs.len())
+            .enumerate()
+            .map(|(index, code)| (*code, index))
+            .collect();
+        let descriptor = FontDescriptor::from_parts(
+            &font_info.name.to_string_lossy(encoding),
+            font_info.is_bold,
+            font_info.is_italic,
+        );
+
+        Font(Gc::allocate(
+            gc_context,
+            FontData {
+                glyphs: self.0.glyphs.clone(),
+                code_point_to_glyph,
+                scale: self.0.scale,
+                kerning_pairs: self.0.kerning_pairs.clone(),
+                ascent: self.0.ascent,
+                descent: self.0.descent,
+                leading: self.0.leading,
+                language: font_info.language,
+                descriptor,
+                notdef: self.0.notdef.clone(),
+            },
+        ))
+    }
+
+    /// Produce a copy of this font that renders any character it has no
+    /// glyph for as a hollow box, instead of skipping over it.
+    pub fn with_notdef_glyph(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        renderer: &mut dyn RenderBackend,
+    ) -> Font<'gc> {
+        // Glyph coordinates are in EM-square units, which depend on the
+        // DefineFont version.
+        let unit = self.0.scale as i32 / 16;
+        let (left, right, top, bottom) = (unit, unit * 7, -unit * 11, 0);
+        let rect = |l: i32, r: i32, t: i32, b: i32| {
+            vec![
+                swf::ShapeRecord::StyleChange(swf::StyleChangeData {
+                    move_to: Some((Twips::new(l), Twips::new(t))),
+                    fill_style_0: None,
+                    fill_style_1: Some(1),
+                    line_style: None,
+                    new_styles: None,
+                }),
+                swf::ShapeRecord::StraightEdge {
+                    delta_x: Twips::new(r - l),
+                    delta_y: Twips::ZERO,
+                },
+                swf::ShapeRecord::StraightEdge {
+                    delta_x: Twips::ZERO,
+                    delta_y: Twips::new(b - t),
+                },
+                swf::ShapeRecord::StraightEdge {
+                    delta_x: Twips::new(l - r),
+                    delta_y: Twips::ZERO,
+                },
+                swf::ShapeRecord::StraightEdge {
+                    delta_x: Twips::ZERO,
+                    delta_y: Twips::new(t - b),
+                },
+            ]
+        };
+
+        // The inner rectangle cuts a hole out of the outer one.
+        let mut shape_records = rect(left, right, top, bottom);
+        shape_records.extend(rect(left + unit, right - unit, top + unit, bottom - unit));
+
+        let notdef = Glyph::from_swf_glyph(
+            renderer,
+            &swf::Glyph {
+                shape_records,
+                code: 0,
+                advance: Some((unit * 8) as i16),
+                bounds: None,
+            },
+        );
+
+        Font(Gc::allocate(
+            gc_context,
+            FontData {
+                notdef: Some(notdef),
+                ..(*self.0).clone()
+            },
+        ))
+    }
+
+    /// Determine if two font handles refer to the same font.
+    pub fn ptr_eq(a: Font<'gc>, b: Font<'gc>) -> bool {
+        Gc::ptr_eq(a.0, b.0)
+    }
+
     /// Returns whether this font contains glyph shapes.
     /// If not, this font should be rendered as a device font.
     pub fn has_glyphs(&self) -> bool {
@@ -177,6 +327,19 @@ impl<'gc> Font<'gc> {
         }
     }
 
+    /// Returns the character that a glyph stands for, if the font maps any
+    /// code point to it.
+    /// Used by `Text` display objects.
+    pub fn get_char_for_glyph(&self, i: usize) -> Option<char> {
+        self.0
+            .code_point_to_glyph
+            .iter()
+            .filter(|(_, index)| **index == i)
+            .map(|(code_point, _)| *code_point)
+            .min()
+            .and_then(|code_point| char::from_u32(code_point.into()))
+    }
+
     /// Determine if this font contains all the glyphs within a given string.
     pub fn has_glyphs_for_str(&self, target_str: &str) -> bool {
         for character in target_str.chars() {
@@ -251,7 +414,10 @@ impl<'gc> Font<'gc> {
         let has_kerning_info = self.has_kerning_info();
         let mut x = Twips::ZERO;
         while let Some((pos, c)) = char_indices.next() {
-            if let Some(glyph) = self.get_glyph_for_char(c) {
+            if let Some(glyph) = self
+                .get_glyph_for_char(c)
+                .or_else(|| self.0.notdef.as_ref())
+            {
                 let mut advance = Twips::new(glyph.advance);
                 if has_kerning_info && params.kerning {
                     let next_char = char_indices.peek().cloned().unwrap_or((0, '\0')).1;
@@ -301,7 +467,8 @@ impl<'gc> Font<'gc> {
     ///
     /// This function assumes only `" "` is valid whitespace to split words on,
     /// and will not attempt to break words that are longer than `width`, nor
-    /// will it break at newlines.
+    /// will it break at newlines. CJK text, which has no spaces, may also be
+    /// broken between characters according to the font's language rules.
     ///
     /// The given `offset` determines the start of the initial line, while the
     /// `width` indicates how long the line is supposed to be. Be careful to
@@ -328,16 +495,14 @@ impl<'gc> Font<'gc> {
 
         let mut line_end = 0;
 
-        for word in text.split(' ') {
-            let word_start = word.as_ptr() as usize - text.as_ptr() as usize;
-            let word_end = word_start + word.len();
-
-            let measure = self.measure(
-                // +1 is fine because ' ' is 1 byte
-                text.get(word_start..word_end + 1).unwrap_or(word),
-                params,
-                false,
-            );
+        for (word_start, word_end, breakpoint) in self.line_segments(text) {
+            // Include the space that follows the word, if any.
+            let measure_end = if text[word_end..].starts_with(' ') {
+                word_end + 1
+            } else {
+                word_end
+            };
+            let measure = self.measure(&text[word_start..measure_end], params, false);
 
             if is_start_of_line && measure.0 > remaining_width {
                 //Failsafe for if we get a word wider than the field.
@@ -369,14 +534,14 @@ impl<'gc> Font<'gc> {
                 return Some(line_end);
             } else {
                 //Space remains for our current word, move up the word pointer.
-                line_end = word_end;
-                is_start_of_line = is_start_of_line && text[0..line_end].trim().is_empty();
+                line_end = breakpoint;
+                is_start_of_line = is_start_of_line && text[0..word_end].trim().is_empty();
 
                 //If the additional space were to cause an overflow, then
                 //return now.
                 remaining_width -= measure.0;
                 if remaining_width < Twips::from_pixels(0.0) {
-                    return Some(word_end);
+                    return Some(breakpoint);
                 }
             }
         }
@@ -384,6 +549,38 @@ impl<'gc> Font<'gc> {
         None
     }
 
+    /// Split a line of text into the segments that `wrap_line` may break
+    /// between.
+    ///
+    /// Each segment is yielded as the start and end of its text, followed by
+    /// the breakpoint `wrap_line` should return if the line ends after it.
+    /// Segments ending in a space break on that space; segments split in
+    /// the middle of CJK text break on their own last character, since there
+    /// is no space for the caller to consume.
+    fn line_segments(&self, text: &str) -> Vec<(usize, usize, usize)> {
+        let mut segments = vec![];
+
+        for word in text.split(' ') {
+            let word_start = word.as_ptr() as usize - text.as_ptr() as usize;
+            let word_end = word_start + word.len();
+            let mut segment_start = word_start;
+            let mut chars = word.char_indices().peekable();
+
+            while let Some((i, c)) = chars.next() {
+                if let Some(&(j, next)) = chars.peek() {
+                    if can_break_between(self.0.language, c, next) {
+                        segments.push((segment_start, word_start + j, word_start + i));
+                        segment_start = word_start + j;
+                    }
+                }
+            }
+
+            segments.push((segment_start, word_end, word_end));
+        }
+
+        segments
+    }
+
     pub fn descriptor(&self) -> &FontDescriptor {
         &self.0.descriptor
     }
@@ -396,6 +593,200 @@ pub struct Glyph {
     pub advance: i16,
 }
 
+impl Glyph {
+    /// Register a glyph from a SWF font tag with the renderer.
+    fn from_swf_glyph(renderer: &mut dyn RenderBackend, swf_glyph: &swf::Glyph) -> Self {
+        Self {
+            shape_handle: renderer.register_glyph_shape(swf_glyph),
+            advance: swf_glyph.advance.unwrap_or(0),
+            shape: crate::shape_utils::swf_glyph_to_shape(swf_glyph),
+        }
+    }
+}
+
+/// The number of quadratic curves that each cubic curve of an OpenType font
+/// is approximated with, as SWF shapes only have quadratic curves.
+const CUBIC_SUBDIVISIONS: u32 = 4;
+
+/// Returns whether font data is a TrueType or OpenType font file (or a
+/// collection of them), rather than the body of a `DefineFont3` tag.
+pub fn is_font_file(data: &[u8]) -> bool {
+    matches!(
+        data.get(..4),
+        Some(b"\x00\x01\x00\x00") | Some(b"OTTO") | Some(b"true") | Some(b"ttcf")
+    )
+}
+
+/// Convert a TrueType or OpenType font file into a `DefineFont3` tag.
+///
+/// Only characters in the Basic Multilingual Plane are converted, as SWF
+/// fonts store 16-bit character codes. Kerning isn't converted, and the
+/// font has no name. The first font of a collection is used.
+pub fn swf_font_from_font_file(data: &[u8]) -> Result<swf::Font<'static>, Error> {
+    let face = ttf_parser::Font::from_data(data, 0).ok_or("Invalid font file")?;
+    let units_per_em = face.units_per_em().ok_or("Font file has no units per em")?;
+    // DefineFont3 glyphs are drawn on a 20480 unit em square.
+    let scale = 20480.0 / f32::from(units_per_em);
+
+    let mut glyphs = vec![];
+    for code in 0..=u16::MAX {
+        let glyph_id = match std::char::from_u32(code.into()).and_then(|c| face.glyph_index(c)) {
+            Some(glyph_id) if glyph_id.0 != 0 => glyph_id,
+            _ => continue,
+        };
+        let mut outline = GlyphOutline::new(scale);
+        face.outline_glyph(glyph_id, &mut outline);
+        let advance = face
+            .glyph_hor_advance(glyph_id)
+            .map(|advance| (f32::from(advance) * scale).min(i16::MAX.into()) as i16);
+        glyphs.push(swf::Glyph {
+            shape_records: outline.records,
+            code,
+            advance,
+            bounds: None,
+        });
+    }
+
+    let scaled = |units: i16| (f32::from(units) * scale).round() as i32;
+    Ok(swf::Font {
+        id: 0,
+        version: 3,
+        name: "".into(),
+        language: swf::Language::Unknown,
+        layout: Some(swf::FontLayout {
+            ascent: scaled(face.ascender()).max(0) as u16,
+            descent: (-scaled(face.descender())).max(0) as u16,
+            leading: scaled(face.line_gap()) as i16,
+            kerning: vec![],
+        }),
+        glyphs,
+        is_small_text: false,
+        is_shift_jis: false,
+        is_ansi: false,
+        is_bold: false,
+        is_italic: false,
+    })
+}
+
+/// Builds the shape records of a glyph from the outline of a glyph in a font
+/// file.
+///
+/// Font files are y-up, while SWF shapes are y-down. Points are rounded to
+/// whole units before edges are made from them, so that rounding errors
+/// don't add up along a contour.
+struct GlyphOutline {
+    records: Vec<swf::ShapeRecord>,
+    scale: f32,
+    start: (f32, f32),
+    last: (f32, f32),
+    position: (i32, i32),
+}
+
+impl GlyphOutline {
+    fn new(scale: f32) -> Self {
+        Self {
+            records: vec![],
+            scale,
+            start: (0.0, 0.0),
+            last: (0.0, 0.0),
+            position: (0, 0),
+        }
+    }
+
+    fn to_units(&self, x: f32, y: f32) -> (i32, i32) {
+        (
+            (x * self.scale).round() as i32,
+            (-y * self.scale).round() as i32,
+        )
+    }
+}
+
+impl ttf_parser::OutlineBuilder for GlyphOutline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let position = self.to_units(x, y);
+        self.records
+            .push(swf::ShapeRecord::StyleChange(swf::StyleChangeData {
+                move_to: Some((Twips::new(position.0), Twips::new(position.1))),
+                fill_style_0: if self.records.is_empty() {
+                    Some(1)
+                } else {
+                    None
+                },
+                fill_style_1: None,
+                line_style: None,
+                new_styles: None,
+            }));
+        self.start = (x, y);
+        self.last = (x, y);
+        self.position = position;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let position = self.to_units(x, y);
+        self.records.push(swf::ShapeRecord::StraightEdge {
+            delta_x: Twips::new(position.0 - self.position.0),
+            delta_y: Twips::new(position.1 - self.position.1),
+        });
+        self.last = (x, y);
+        self.position = position;
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let control = self.to_units(x1, y1);
+        let anchor = self.to_units(x, y);
+        self.records.push(swf::ShapeRecord::CurvedEdge {
+            control_delta_x: Twips::new(control.0 - self.position.0),
+            control_delta_y: Twips::new(control.1 - self.position.1),
+            anchor_delta_x: Twips::new(anchor.0 - control.0),
+            anchor_delta_y: Twips::new(anchor.1 - control.1),
+        });
+        self.last = (x, y);
+        self.position = anchor;
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x0, y0) = self.last;
+        let point = |t: f32| {
+            let u = 1.0 - t;
+            let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+            (
+                a * x0 + b * x1 + c * x2 + d * x,
+                a * y0 + b * y1 + c * y2 + d * y,
+            )
+        };
+        let tangent = |t: f32| {
+            let u = 1.0 - t;
+            let (a, b, c) = (3.0 * u * u, 6.0 * u * t, 3.0 * t * t);
+            (
+                a * (x1 - x0) + b * (x2 - x1) + c * (x - x2),
+                a * (y1 - y0) + b * (y2 - y1) + c * (y - y2),
+            )
+        };
+
+        // Each piece of the cubic is replaced by the quadratic whose control
+        // point is the average of where its two tangents lead.
+        let step = 1.0 / CUBIC_SUBDIVISIONS as f32;
+        for i in 0..CUBIC_SUBDIVISIONS {
+            let (t0, t1) = (i as f32 * step, (i + 1) as f32 * step);
+            let (p0, p3) = (point(t0), point(t1));
+            let (d0, d3) = (tangent(t0), tangent(t1));
+            let c1 = (p0.0 + d0.0 * step / 3.0, p0.1 + d0.1 * step / 3.0);
+            let c2 = (p3.0 - d3.0 * step / 3.0, p3.1 - d3.1 * step / 3.0);
+            let control = (
+                (3.0 * (c1.0 + c2.0) - p0.0 - p3.0) / 4.0,
+                (3.0 * (c1.1 + c2.1) - p0.1 - p3.1) / 4.0,
+            );
+            self.quad_to(control.0, control.1, p3.0, p3.1);
+        }
+    }
+
+    fn close(&mut self) {
+        if self.to_units(self.start.0, self.start.1) != self.position {
+            self.line_to(self.start.0, self.start.1);
+        }
+    }
+}
+
 /// Structure which identifies a particular font by name and properties.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Collect)]
 #[collect(require_static)]
@@ -500,11 +891,16 @@ impl From<swf::CsmTextSettings> for TextRenderSettings {
 #[cfg(test)]
 mod tests {
     use crate::backend::render::{NullRenderer, RenderBackend};
-    use crate::font::{EvalParameters, Font};
+    use crate::font::{
+        can_break_between, is_font_file, swf_font_from_font_file, EvalParameters, Font,
+        GlyphOutline,
+    };
     use crate::player::{Player, DEVICE_FONT_TAG};
+    use crate::string::utils as string_utils;
     use gc_arena::{rootless_arena, MutationContext};
     use std::ops::DerefMut;
-    use swf::Twips;
+    use swf::{ShapeRecord, Twips};
+    use ttf_parser::OutlineBuilder;
 
     fn with_device_font<F>(callback: F)
     where
@@ -519,6 +915,56 @@ mod tests {
         })
     }
 
+    /// Like `with_device_font`, but characters missing from the device font
+    /// are measured with its notdef glyph, so CJK text takes up space.
+    fn with_device_font_notdef<F>(callback: F)
+    where
+        F: for<'gc> FnOnce(MutationContext<'gc, '_>, Font<'gc>),
+    {
+        rootless_arena(|mc| {
+            let mut renderer: Box<dyn RenderBackend> = Box::new(NullRenderer::new());
+            let device_font =
+                Player::load_device_font(mc, DEVICE_FONT_TAG, renderer.deref_mut()).unwrap();
+            let device_font = device_font.with_notdef_glyph(mc, renderer.deref_mut());
+
+            callback(mc, device_font);
+        })
+    }
+
+    /// Break `text` into lines of at most `width`, the same way text fields
+    /// lay out word-wrapped text.
+    fn wrap_lines<'a>(
+        df: Font<'_>,
+        text: &'a str,
+        params: EvalParameters,
+        width: Twips,
+    ) -> Vec<&'a str> {
+        let mut lines = vec![];
+        let mut last_breakpoint = 0;
+
+        while let Some(breakpoint) =
+            df.wrap_line(&text[last_breakpoint..], params, width, Twips::ZERO, true)
+        {
+            if breakpoint == 0 {
+                break;
+            }
+
+            let next_breakpoint =
+                string_utils::next_char_boundary(text, last_breakpoint + breakpoint);
+            lines.push(&text[last_breakpoint..next_breakpoint]);
+            last_breakpoint = next_breakpoint;
+            if last_breakpoint >= text.len() {
+                break;
+            }
+        }
+
+        if last_breakpoint < text.len() {
+            lines.push(&text[last_breakpoint..]);
+        }
+
+        lines
+    }
+
     #[test]
     fn wrap_line_no_breakpoint() {
         with_device_font(|_mc, df| {
@@ -676,4 +1122,131 @@ mod tests {
             assert_eq!(None, breakpoint5);
         });
     }
+
+    #[test]
+    fn cjk_line_break_opportunities() {
+        use swf::Language;
+
+        // Latin text only breaks on spaces.
+        assert!(!can_break_between(Language::Latin, 'a', 'b'));
+        assert!(!can_break_between(Language::Unknown, 'a', 'b'));
+
+        // Ideographs may be broken between, regardless of font language.
+        assert!(can_break_between(Language::Unknown, '漢', '字'));
+        assert!(can_break_between(Language::SimplifiedChinese, '汉', 'a'));
+
+        // Closing punctuation may not start a line, opening may not end one.
+        assert!(!can_break_between(Language::Japanese, '字', '。'));
+        assert!(!can_break_between(Language::TraditionalChinese, '「', '字'));
+
+        // Small kana are only kept together with the previous character in
+        // Japanese.
+        assert!(!can_break_between(Language::Japanese, 'キ', 'ャ'));
+        assert!(can_break_between(Language::SimplifiedChinese, 'キ', 'ャ'));
+
+        // Korean text is broken on spaces.
+        assert!(!can_break_between(Language::Korean, '漢', '字'));
+    }
+
+    #[test]
+    fn wrap_line_cjk_per_character() {
+        with_device_font_notdef(|_mc, df| {
+            let params =
+                EvalParameters::from_parts(Twips::from_pixels(12.0), Twips::from_pixels(0.0), true);
+
+            // Each ideograph is a 6px notdef box, so three fit on a line.
+            assert_eq!(
+                wrap_lines(df, "漢字漢字漢字漢字", params, Twips::from_pixels(20.0)),
+                vec!["漢字漢", "字漢字", "漢字"]
+            );
+        });
+    }
+
+    #[test]
+    fn wrap_line_mixed_latin_cjk() {
+        with_device_font_notdef(|_mc, df| {
+            let params =
+                EvalParameters::from_parts(Twips::from_pixels(12.0), Twips::from_pixels(0.0), true);
+
+            // The ideographs are broken between, but the Latin words are
+            // only ever broken on spaces.
+            assert_eq!(
+                wrap_lines(
+                    df,
+                    "abc 漢字漢字漢字 defg",
+                    params,
+                    Twips::from_pixels(40.0)
+                ),
+                vec!["abc 漢字", "漢字漢字 ", "defg"]
+            );
+            assert_eq!(
+                wrap_lines(
+                    df,
+                    "abc 漢字漢字漢字 defg",
+                    params,
+                    Twips::from_pixels(50.0)
+                ),
+                vec!["abc 漢字漢字", "漢字 defg"]
+            );
+        });
+    }
+
+    #[test]
+    fn font_files_are_told_apart_from_tags() {
+        assert!(is_font_file(b"\x00\x01\x00\x00"));
+        assert!(is_font_file(b"OTTO"));
+        assert!(is_font_file(b"ttcf"));
+        assert!(!is_font_file(DEVICE_FONT_TAG));
+        assert!(swf_font_from_font_file(b"OTTO but not really").is_err());
+    }
+
+    #[test]
+    fn glyph_outlines_are_flipped_and_closed() {
+        let mut outline = GlyphOutline::new(2.0);
+        outline.move_to(0.0, 0.0);
+        outline.line_to(10.0, 0.0);
+        outline.line_to(10.0, 10.0);
+        outline.close();
+
+        assert_eq!(outline.records.len(), 4);
+        assert!(matches!(
+            &outline.records[0],
+            ShapeRecord::StyleChange(style) if style.move_to == Some((Twips::ZERO, Twips::ZERO))
+                && style.fill_style_0 == Some(1)
+        ));
+        let edges: Vec<_> = outline.records[1..]
+            .iter()
+            .map(|record| match record {
+                ShapeRecord::StraightEdge { delta_x, delta_y } => (delta_x.get(), delta_y.get()),
+                _ => panic!("Expected a straight edge, got {:?}", record),
+            })
+            .collect();
+        assert_eq!(edges, vec![(20, 0), (0, -20), (-20, 20)]);
+    }
+
+    #[test]
+    fn glyph_outline_cubics_become_quadratics() {
+        let mut outline = GlyphOutline::new(1.0);
+        outline.move_to(0.0, 0.0);
+        outline.curve_to(0.0, 100.0, 100.0, 100.0, 100.0, 0.0);
+
+        // The quadratics end where the cubic does.
+        let mut end = (0, 0);
+        for record in &outline.records[1..] {
+            match record {
+                ShapeRecord::CurvedEdge {
+                    control_delta_x,
+                    control_delta_y,
+                    anchor_delta_x,
+                    anchor_delta_y,
+                } => {
+                    end.0 += control_delta_x.get() + anchor_delta_x.get();
+                    end.1 += control_delta_y.get() + anchor_delta_y.get();
+                }
+                _ => panic!("Expected a curved edge, got {:?}", record),
+            }
+        }
+        assert_eq!(outline.records.len(), 5);
+        assert_eq!(end, (100, 0));
+    }
 }
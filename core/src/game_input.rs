@@ -0,0 +1,307 @@
+//! Game controllers, as seen by movies through `flash.ui.GameInput`.
+//!
+//! Frontends report every controller as a W3C "standard gamepad": four axes
+//! for the two sticks, followed by seventeen buttons. Flash numbers all of
+//! the controls of a device in one sequence, so they are named `AXIS_0` to
+//! `AXIS_3`, then `BUTTON_4` to `BUTTON_20`.
+
+use crate::events::GamepadControl;
+use std::collections::VecDeque;
+
+/// The number of axes on a standard gamepad.
+pub const NUM_AXES: usize = 4;
+
+/// The number of buttons on a standard gamepad.
+pub const NUM_BUTTONS: usize = 17;
+
+/// The number of controls on every device.
+pub const NUM_CONTROLS: usize = NUM_AXES + NUM_BUTTONS;
+
+/// The most values that a device may cache, as given by
+/// `GameInputDevice.MAX_BUFFER_SIZE`.
+pub const MAX_BUFFER_SIZE: usize = 32000;
+
+/// The position of a control among all of a device's controls.
+fn control_index(control: GamepadControl) -> Option<usize> {
+    match control {
+        GamepadControl::Axis(axis) if axis < NUM_AXES => Some(axis),
+        GamepadControl::Button(button) if button < NUM_BUTTONS => Some(NUM_AXES + button),
+        _ => None,
+    }
+}
+
+/// The ID of the control at an index, as given by `GameInputControl.id`.
+pub fn control_id(index: usize) -> String {
+    if index < NUM_AXES {
+        format!("AXIS_{}", index)
+    } else {
+        format!("BUTTON_{}", index)
+    }
+}
+
+/// The lowest and highest values of the control at an index.
+pub fn control_range(index: usize) -> (f64, f64) {
+    if index < NUM_AXES {
+        (-1.0, 1.0)
+    } else {
+        (0.0, 1.0)
+    }
+}
+
+/// Samples of some controls, collected for `GameInputDevice.getCachedSamples`.
+struct SampleCache {
+    /// The indices of the sampled controls.
+    controls: Vec<usize>,
+
+    /// How many samples to keep before the oldest are dropped.
+    num_samples: usize,
+
+    /// The values of the controls at each sample, oldest first.
+    samples: VecDeque<Vec<f64>>,
+}
+
+/// A connected game controller.
+pub struct GameInputDevice {
+    /// The ID given to the controller by the frontend.
+    id: u32,
+
+    name: String,
+    values: [f64; NUM_CONTROLS],
+
+    /// Whether the movie has enabled this device. Changes to its controls
+    /// are only reported while it is enabled.
+    enabled: bool,
+
+    /// How often, in milliseconds, cached samples are taken.
+    sample_interval: u32,
+
+    time_since_sample: f64,
+    cache: Option<SampleCache>,
+}
+
+impl GameInputDevice {
+    fn new(id: u32, name: String) -> Self {
+        Self {
+            id,
+            name,
+            values: [0.0; NUM_CONTROLS],
+            enabled: false,
+            sample_interval: 0,
+            time_since_sample: 0.0,
+            cache: None,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn value(&self, index: usize) -> Option<f64> {
+        self.values.get(index).copied()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn sample_interval(&self) -> u32 {
+        self.sample_interval
+    }
+
+    /// Set how often cached samples are taken. An interval of 0 takes one
+    /// sample each time the player ticks.
+    pub fn set_sample_interval(&mut self, sample_interval: u32) {
+        self.sample_interval = sample_interval;
+    }
+
+    /// Start caching samples of the given controls, keeping the most recent
+    /// `num_samples` of them.
+    pub fn start_caching_samples(&mut self, num_samples: usize, controls: Vec<usize>) {
+        self.time_since_sample = 0.0;
+        self.cache = Some(SampleCache {
+            controls,
+            num_samples,
+            samples: VecDeque::with_capacity(num_samples),
+        });
+    }
+
+    pub fn stop_caching_samples(&mut self) {
+        self.cache = None;
+    }
+
+    /// Take the cached samples, oldest first, with the values of each
+    /// sample in the order the controls were given.
+    ///
+    /// Returns the number of samples and their values.
+    pub fn take_cached_samples(&mut self) -> (usize, Vec<f64>) {
+        match &mut self.cache {
+            Some(cache) => {
+                let count = cache.samples.len();
+                let values = cache.samples.drain(..).flatten().collect();
+                (count, values)
+            }
+            None => (0, Vec::new()),
+        }
+    }
+
+    fn tick(&mut self, dt: f64) {
+        let cache = match &mut self.cache {
+            Some(cache) if self.enabled => cache,
+            _ => return,
+        };
+
+        self.time_since_sample += dt;
+        if self.time_since_sample < f64::from(self.sample_interval) {
+            return;
+        }
+        self.time_since_sample = 0.0;
+
+        if cache.samples.len() >= cache.num_samples {
+            cache.samples.pop_front();
+        }
+        let values = &self.values;
+        cache
+            .samples
+            .push_back(cache.controls.iter().map(|i| values[*i]).collect());
+    }
+}
+
+/// The game controllers that are connected to the player.
+#[derive(Default)]
+pub struct GameInput {
+    devices: Vec<GameInputDevice>,
+}
+
+impl GameInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The connected devices, in the order they were connected.
+    pub fn devices(&self) -> &[GameInputDevice] {
+        &self.devices
+    }
+
+    pub fn device(&self, id: u32) -> Option<&GameInputDevice> {
+        self.devices.iter().find(|d| d.id == id)
+    }
+
+    pub fn device_mut(&mut self, id: u32) -> Option<&mut GameInputDevice> {
+        self.devices.iter_mut().find(|d| d.id == id)
+    }
+
+    /// Add a newly connected device.
+    ///
+    /// Returns `false` if the device was already connected.
+    pub fn connect(&mut self, id: u32, name: String) -> bool {
+        if self.device(id).is_some() {
+            return false;
+        }
+
+        self.devices.push(GameInputDevice::new(id, name));
+        true
+    }
+
+    /// Remove a disconnected device.
+    ///
+    /// Returns `false` if the device wasn't connected.
+    pub fn disconnect(&mut self, id: u32) -> bool {
+        let count = self.devices.len();
+        self.devices.retain(|d| d.id != id);
+        self.devices.len() != count
+    }
+
+    /// Update the value of a control.
+    ///
+    /// Returns the index of the control if the movie should be told that it
+    /// changed.
+    pub fn set_value(&mut self, id: u32, control: GamepadControl, value: f64) -> Option<usize> {
+        let device = self.device_mut(id)?;
+        let index = control_index(control)?;
+        let (min, max) = control_range(index);
+        let value = value.clamp(min, max);
+        if device.values[index] == value {
+            return None;
+        }
+
+        device.values[index] = value;
+        if device.enabled {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    /// Take cached samples from every device that is due one.
+    pub fn tick(&mut self, dt: f64) {
+        for device in self.devices.iter_mut() {
+            device.tick(dt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn control_ids() {
+        assert_eq!(control_id(0), "AXIS_0");
+        assert_eq!(control_id(3), "AXIS_3");
+        assert_eq!(control_id(4), "BUTTON_4");
+        assert_eq!(control_id(NUM_CONTROLS - 1), "BUTTON_20");
+    }
+
+    #[test]
+    fn changes_are_reported_while_enabled() {
+        let mut game_input = GameInput::new();
+        assert!(game_input.connect(3, "Pad".to_string()));
+        assert!(!game_input.connect(3, "Pad".to_string()));
+
+        assert_eq!(
+            game_input.set_value(3, GamepadControl::Button(0), 1.0),
+            None
+        );
+        assert_eq!(game_input.device(3).unwrap().value(4), Some(1.0));
+
+        game_input.device_mut(3).unwrap().set_enabled(true);
+        assert_eq!(
+            game_input.set_value(3, GamepadControl::Axis(1), -2.0),
+            Some(1)
+        );
+        assert_eq!(game_input.device(3).unwrap().value(1), Some(-1.0));
+        assert_eq!(game_input.set_value(3, GamepadControl::Axis(1), -1.0), None);
+        assert_eq!(game_input.set_value(4, GamepadControl::Axis(1), 0.5), None);
+
+        assert!(game_input.disconnect(3));
+        assert!(game_input.devices().is_empty());
+    }
+
+    #[test]
+    fn cached_samples_keep_the_most_recent() {
+        let mut game_input = GameInput::new();
+        game_input.connect(0, "Pad".to_string());
+        let device = game_input.device_mut(0).unwrap();
+        device.set_enabled(true);
+        device.set_sample_interval(10);
+        device.start_caching_samples(2, vec![0, 4]);
+
+        for value in &[0.25, 0.5, 0.75] {
+            game_input.set_value(0, GamepadControl::Axis(0), *value);
+            game_input.tick(10.0);
+        }
+        game_input.tick(5.0);
+
+        let device = game_input.device_mut(0).unwrap();
+        assert_eq!(device.take_cached_samples(), (2, vec![0.5, 0.0, 0.75, 0.0]));
+        assert_eq!(device.take_cached_samples(), (0, vec![]));
+    }
+}
@@ -1,4 +1,6 @@
 //! CSS dimension types
+use crate::matrix::Matrix;
+use crate::transform::Transform;
 use gc_arena::Collect;
 use std::cmp::{max, min, Ord};
 use std::ops::{Add, AddAssign, Sub};
@@ -80,6 +82,22 @@ where
     }
 }
 
+impl From<Position<Twips>> for Transform {
+    fn from(pos: Position<Twips>) -> Self {
+        Self {
+            matrix: Matrix {
+                a: 1.0,
+                b: 0.0,
+                c: 0.0,
+                d: 1.0,
+                tx: pos.x(),
+                ty: pos.y(),
+            },
+            color_transform: Default::default(),
+        }
+    }
+}
+
 /// A type which represents the size of a layout box.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Collect)]
 #[collect(require_static)]
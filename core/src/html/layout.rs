@@ -16,7 +16,7 @@ use swf::Twips;
 /// Draw an underline on a particular drawing.
 ///
 /// This will not draw underlines shorter than a pixel in width.
-fn draw_underline(drawing: &mut Drawing, starting_pos: Position<Twips>, width: Twips) {
+fn draw_underline(drawing: &mut Drawing<'_>, starting_pos: Position<Twips>, width: Twips) {
     if width < Twips::from_pixels(1.0) {
         return;
     }
@@ -48,6 +48,10 @@ pub struct LayoutContext<'a, 'gc> {
     /// The resolved font object to use when measuring text.
     font: Option<Font<'gc>>,
 
+    /// Whether the current font is the device font, in which case characters
+    /// it cannot render are laid out with a fallback font.
+    is_device_font: bool,
+
     /// The underlying bundle of text being formatted.
     text: &'a str,
 
@@ -95,6 +99,7 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
             movie,
             cursor: Default::default(),
             font: None,
+            is_device_font: false,
             text,
             max_font_size: Default::default(),
             boxes: Vec::new(),
@@ -400,11 +405,13 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
         // If this text field is set to use device fonts, fallback to using our embedded Noto Sans.
         // Note that the SWF can still contain a DefineFont tag with no glyphs/layout info in this case (see #451).
         // In an ideal world, device fonts would search for a matching font on the system and render it in some way.
-        if let Some(font) = library
+        let font = library
             .get_font_by_name(&span.font, span.bold, span.italic)
-            .filter(|f| !is_device_font && f.has_glyphs())
-            .or_else(|| context.library.device_font())
-        {
+            .filter(|f| !is_device_font && f.has_glyphs());
+
+        self.is_device_font = font.is_none();
+
+        if let Some(font) = font.or_else(|| context.library.device_font()) {
             self.font = Some(font);
             return self.font;
         }
@@ -416,13 +423,21 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
     ///
     /// The text given may or may not be separated into fragments, depending on
     /// what the layout calls for.
-    fn append_text(&mut self, text: &'a str, start: usize, end: usize, span: &TextSpan) {
+    fn append_text(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        text: &'a str,
+        start: usize,
+        end: usize,
+        span: &TextSpan,
+    ) {
         if self.effective_alignment() == swf::TextAlign::Justify {
             for word in text.split(' ') {
                 let word_start = word.as_ptr() as usize - text.as_ptr() as usize;
                 let word_end = min(word_start + word.len() + 1, text.len());
 
                 self.append_text_fragment(
+                    context,
                     text.get(word_start..word_end).unwrap(),
                     start + word_start,
                     start + word_end,
@@ -430,7 +445,7 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
                 );
             }
         } else {
-            self.append_text_fragment(text, start, end, span);
+            self.append_text_fragment(context, text, start, end, span);
         }
     }
 
@@ -438,11 +453,63 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
     ///
     /// This function bypasses the text fragmentation necessary for justify to
     /// work and it should only be called internally.
-    fn append_text_fragment(&mut self, text: &'a str, start: usize, end: usize, span: &TextSpan) {
+    ///
+    /// Device text is further split into runs of characters that share the
+    /// same font, so that characters the device font has no glyphs for can be
+    /// rendered with the fallback font for their script.
+    fn append_text_fragment(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        text: &'a str,
+        start: usize,
+        end: usize,
+        span: &TextSpan,
+    ) {
+        let font = self.font.unwrap();
+
+        if !self.is_device_font {
+            self.append_text_run(font, text, start, end, span);
+            return;
+        }
+
+        let mut run: Option<(Font<'gc>, usize)> = None;
+        for (pos, c) in text.char_indices() {
+            let char_font = context.library.device_font_for_char(c).unwrap_or(font);
+            match run {
+                Some((run_font, _)) if Font::ptr_eq(run_font, char_font) => {}
+                Some((run_font, run_start)) => {
+                    self.append_text_run(
+                        run_font,
+                        &text[run_start..pos],
+                        start + run_start,
+                        start + pos,
+                        span,
+                    );
+                    run = Some((char_font, pos));
+                }
+                None => run = Some((char_font, pos)),
+            }
+        }
+
+        if let Some((run_font, run_start)) = run {
+            self.append_text_run(run_font, &text[run_start..], start + run_start, end, span);
+        }
+    }
+
+    /// Append a run of text, all rendered in a single font, to the current
+    /// line.
+    fn append_text_run(
+        &mut self,
+        font: Font<'gc>,
+        text: &'a str,
+        start: usize,
+        end: usize,
+        span: &TextSpan,
+    ) {
         let params = EvalParameters::from_span(span);
-        let text_size = Size::from(self.font.unwrap().measure(text, params, false));
+        let text_size = Size::from(font.measure(text, params, false));
         let text_bounds = BoxBounds::from_position_and_size(self.cursor, text_size);
-        let mut new_text = LayoutBox::from_text(start, end, self.font.unwrap(), span);
+        let mut new_text = LayoutBox::from_text(start, end, font, span);
 
         new_text.bounds = text_bounds;
 
@@ -618,7 +685,7 @@ pub enum LayoutContent<'gc> {
     /// The drawing will be rendered with its origin at the position of the
     /// layout box's bounds. The size of those bounds do not affect the
     /// rendering of the drawing.
-    Drawing(Drawing),
+    Drawing(Drawing<'gc>),
 }
 
 impl<'gc> LayoutBox<'gc> {
@@ -655,7 +722,7 @@ impl<'gc> LayoutBox<'gc> {
     }
 
     /// Construct a drawing.
-    pub fn from_drawing(drawing: Drawing) -> Self {
+    pub fn from_drawing(drawing: Drawing<'gc>) -> Self {
         Self {
             bounds: Default::default(),
             content: LayoutContent::Drawing(drawing),
@@ -743,6 +810,7 @@ impl<'gc> LayoutBox<'gc> {
                             );
 
                             layout_context.append_text(
+                                context,
                                 &text[last_breakpoint..next_breakpoint],
                                 start + last_breakpoint,
                                 start + next_breakpoint,
@@ -766,6 +834,7 @@ impl<'gc> LayoutBox<'gc> {
 
                     if last_breakpoint < span_end {
                         layout_context.append_text(
+                            context,
                             &text[last_breakpoint..span_end],
                             start + last_breakpoint,
                             start + span_end,
@@ -819,7 +888,7 @@ impl<'gc> LayoutBox<'gc> {
     }
 
     /// Returns a reference to the drawing this box contains, if it has one.
-    pub fn as_renderable_drawing(&self) -> Option<&Drawing> {
+    pub fn as_renderable_drawing(&self) -> Option<&Drawing<'gc>> {
         match &self.content {
             LayoutContent::Text { .. } => None,
             LayoutContent::Bullet { .. } => None,
@@ -26,34 +26,49 @@ mod avm1;
 mod avm2;
 mod binary_data;
 pub mod bitmap;
-mod bounding_box;
+pub mod bug_report;
+pub mod bundle;
+use ruffle_render_api::bounding_box;
 mod character;
-pub mod color_transform;
+pub use ruffle_render_api::color_transform;
 pub mod context;
 pub mod context_menu;
+mod cursor;
 mod drawing;
 mod ecma_conversions;
 pub mod events;
 pub mod focus_tracker;
 mod font;
+mod game_input;
 mod html;
 mod library;
 pub mod loader;
-pub mod matrix;
+mod local_connection;
+mod multitouch;
+pub use ruffle_render_api::matrix;
+mod matrix3d;
+mod pixel_bender;
 mod player;
 mod prelude;
-pub mod shape_utils;
+mod remoting;
+mod security;
+mod shared_object;
+pub use ruffle_render_api::shape_utils;
+mod socket;
 pub mod string;
 pub mod tag_utils;
-mod transform;
+mod timer;
+use ruffle_render_api::transform;
 mod types;
 mod vminterface;
 mod xml;
+pub mod zip;
 
 pub mod backend;
 pub mod config;
 pub mod external;
 
+pub use avm1::CaseSensitivity;
 pub use chrono;
 pub use context_menu::ContextMenuItem;
 pub use events::PlayerEvent;
@@ -3,6 +3,7 @@ use crate::avm1::property_map::PropertyMap as Avm1PropertyMap;
 use crate::avm2::{Domain as Avm2Domain, Object as Avm2Object};
 use crate::backend::{audio::SoundHandle, render};
 use crate::character::Character;
+use crate::config::{FontScript, NotdefStrategy};
 use crate::display_object::{Bitmap, Graphic, MorphShape, TDisplayObject, Text};
 use crate::font::{Font, FontDescriptor};
 use crate::prelude::*;
@@ -141,6 +142,12 @@ pub struct MovieLibrary<'gc> {
     avm_type: AvmType,
     avm2_domain: Option<Avm2Domain<'gc>>,
 
+    /// The `UncaughtErrorEvents` dispatcher of this movie's `LoaderInfo`.
+    ///
+    /// This is created the first time it is needed, and kept here so that
+    /// every `LoaderInfo` for the movie shares the same dispatcher.
+    avm2_uncaught_error_events: Option<Avm2Object<'gc>>,
+
     /// Shared reference to the constructor registry used for this movie.
     /// Should be `None` if this is an AVM2 movie.
     avm1_constructor_registry: Option<Gc<'gc, Avm1ConstructorRegistry<'gc>>>,
@@ -155,6 +162,7 @@ impl<'gc> MovieLibrary<'gc> {
             fonts: HashMap::new(),
             avm_type,
             avm2_domain: None,
+            avm2_uncaught_error_events: None,
             avm1_constructor_registry: None,
         }
     }
@@ -172,6 +180,18 @@ impl<'gc> MovieLibrary<'gc> {
         }
     }
 
+    /// Replaces an already registered font with an updated version of itself.
+    ///
+    /// This is used by `DefineFontInfo`, which amends a font defined by an
+    /// earlier tag.
+    pub fn replace_font(&mut self, id: CharacterId, font: Font<'gc>) {
+        if let Some(Character::Font(old_font)) = self.characters.get(&id) {
+            self.fonts.remove(old_font.descriptor());
+        }
+        self.fonts.insert(font.descriptor().clone(), font);
+        self.characters.insert(id, Character::Font(font));
+    }
+
     /// Registers an export name for a given character ID.
     /// This character will then be instantiable from AVM1.
     pub fn register_export(
@@ -376,6 +396,14 @@ impl<'gc> MovieLibrary<'gc> {
     pub fn avm2_domain(&self) -> Avm2Domain<'gc> {
         self.avm2_domain.unwrap()
     }
+
+    pub fn avm2_uncaught_error_events(&self) -> Option<Avm2Object<'gc>> {
+        self.avm2_uncaught_error_events
+    }
+
+    pub fn set_avm2_uncaught_error_events(&mut self, uncaught_error_events: Avm2Object<'gc>) {
+        self.avm2_uncaught_error_events = Some(uncaught_error_events);
+    }
 }
 
 impl<'gc> render::BitmapSource for MovieLibrary<'gc> {
@@ -398,6 +426,16 @@ pub struct Library<'gc> {
     /// The embedded device font.
     device_font: Option<Font<'gc>>,
 
+    /// Fonts to search, in order, for characters of a given script that the
+    /// device font has no glyphs for.
+    device_font_fallbacks: HashMap<FontScript, Vec<Font<'gc>>>,
+
+    /// A copy of the device font that renders missing characters as boxes.
+    notdef_font: Option<Font<'gc>>,
+
+    /// How to render characters that no device font has a glyph for.
+    notdef_strategy: NotdefStrategy,
+
     constructor_registry_case_insensitive: Gc<'gc, Avm1ConstructorRegistry<'gc>>,
     constructor_registry_case_sensitive: Gc<'gc, Avm1ConstructorRegistry<'gc>>,
 
@@ -413,6 +451,10 @@ unsafe impl<'gc> gc_arena::Collect for Library<'gc> {
             val.trace(cc);
         }
         self.device_font.trace(cc);
+        for (_, fonts) in self.device_font_fallbacks.iter() {
+            fonts.trace(cc);
+        }
+        self.notdef_font.trace(cc);
         self.constructor_registry_case_insensitive.trace(cc);
         self.constructor_registry_case_sensitive.trace(cc);
         self.avm2_class_registry.trace(cc);
@@ -424,6 +466,9 @@ impl<'gc> Library<'gc> {
         Self {
             movie_libraries: PtrWeakKeyHashMap::new(),
             device_font: None,
+            device_font_fallbacks: HashMap::new(),
+            notdef_font: None,
+            notdef_strategy: NotdefStrategy::default(),
             constructor_registry_case_insensitive: Gc::allocate(
                 gc_context,
                 Avm1ConstructorRegistry::new(false, gc_context),
@@ -461,8 +506,53 @@ impl<'gc> Library<'gc> {
     }
 
     /// Sets the device font.
-    pub fn set_device_font(&mut self, font: Option<Font<'gc>>) {
+    ///
+    /// `notdef_font` should be a copy of the same font with a notdef glyph,
+    /// used when the notdef strategy calls for missing characters to be
+    /// drawn.
+    pub fn set_device_font(&mut self, font: Option<Font<'gc>>, notdef_font: Option<Font<'gc>>) {
         self.device_font = font;
+        self.notdef_font = notdef_font;
+    }
+
+    /// Append a font to the end of the fallback chain for a given script.
+    pub fn add_device_font_fallback(&mut self, script: FontScript, font: Font<'gc>) {
+        self.device_font_fallbacks
+            .entry(script)
+            .or_insert_with(Vec::new)
+            .push(font);
+    }
+
+    /// Sets how characters that no device font can render are displayed.
+    pub fn set_notdef_strategy(&mut self, notdef_strategy: NotdefStrategy) {
+        self.notdef_strategy = notdef_strategy;
+    }
+
+    /// Returns the device font to render a given character with.
+    ///
+    /// The device font itself is used if it has a glyph for the character,
+    /// followed by the first font in the fallback chain for the character's
+    /// script that has one. If no font can render the character, the notdef
+    /// strategy decides what font is returned.
+    pub fn device_font_for_char(&self, c: char) -> Option<Font<'gc>> {
+        let device_font = self.device_font?;
+        if device_font.get_glyph_for_char(c).is_some() {
+            return Some(device_font);
+        }
+
+        let fallback = FontScript::from_char(c)
+            .and_then(|script| self.device_font_fallbacks.get(&script))
+            .and_then(|fonts| {
+                fonts
+                    .iter()
+                    .find(|font| font.get_glyph_for_char(c).is_some())
+            });
+
+        match (fallback, self.notdef_strategy) {
+            (Some(font), _) => Some(*font),
+            (None, NotdefStrategy::Box) => self.notdef_font.or(Some(device_font)),
+            (None, NotdefStrategy::Hide) => Some(device_font),
+        }
     }
 
     /// Gets the constructor registry to use for the given SWF version.
@@ -489,3 +579,113 @@ impl<'gc> Library<'gc> {
         &mut self.avm2_class_registry
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::render::{NullRenderer, RenderBackend};
+    use crate::player::{Player, DEVICE_FONT_TAG};
+    use gc_arena::rootless_arena;
+    use std::ops::DerefMut;
+
+    struct Fonts<'gc> {
+        device: Font<'gc>,
+        notdef: Font<'gc>,
+        first: Font<'gc>,
+        second: Font<'gc>,
+    }
+
+    /// A font with empty glyphs for the given characters.
+    fn font_with_chars<'gc>(
+        gc_context: MutationContext<'gc, '_>,
+        renderer: &mut dyn RenderBackend,
+        chars: &str,
+    ) -> Font<'gc> {
+        let glyphs = chars
+            .encode_utf16()
+            .map(|code| swf::Glyph {
+                shape_records: vec![],
+                code,
+                advance: Some(1024),
+                bounds: None,
+            })
+            .collect();
+        let tag = swf::Font {
+            id: 1,
+            version: 3,
+            name: "Fallback".into(),
+            glyphs,
+            language: swf::Language::Unknown,
+            layout: None,
+            is_small_text: false,
+            is_shift_jis: false,
+            is_ansi: false,
+            is_bold: false,
+            is_italic: false,
+        };
+        Font::from_swf_tag(gc_context, renderer, &tag, swf::UTF_8).unwrap()
+    }
+
+    /// Run a test with a library whose device font is the embedded Noto
+    /// Sans, with a CJK fallback chain of a font for 中 followed by a font
+    /// for 中 and 文.
+    fn with_fallbacks<F>(callback: F)
+    where
+        F: for<'gc> FnOnce(&mut Library<'gc>, Fonts<'gc>),
+    {
+        rootless_arena(|mc| {
+            let mut renderer: Box<dyn RenderBackend> = Box::new(NullRenderer::new());
+            let device =
+                Player::load_device_font(mc, DEVICE_FONT_TAG, renderer.deref_mut()).unwrap();
+            let fonts = Fonts {
+                device,
+                notdef: device.with_notdef_glyph(mc, renderer.deref_mut()),
+                first: font_with_chars(mc, renderer.deref_mut(), "中"),
+                second: font_with_chars(mc, renderer.deref_mut(), "中文"),
+            };
+
+            let mut library = Library::empty(mc);
+            library.set_device_font(Some(fonts.device), Some(fonts.notdef));
+            library.add_device_font_fallback(FontScript::Cjk, fonts.first);
+            library.add_device_font_fallback(FontScript::Cjk, fonts.second);
+
+            callback(&mut library, fonts);
+        })
+    }
+
+    #[test]
+    fn device_font_renders_its_own_glyphs() {
+        with_fallbacks(|library, fonts| {
+            let font = library.device_font_for_char('a').unwrap();
+            assert!(Font::ptr_eq(font, fonts.device));
+        });
+    }
+
+    #[test]
+    fn first_fallback_with_glyph_is_used() {
+        with_fallbacks(|library, fonts| {
+            let font = library.device_font_for_char('中').unwrap();
+            assert!(Font::ptr_eq(font, fonts.first));
+            let font = library.device_font_for_char('文').unwrap();
+            assert!(Font::ptr_eq(font, fonts.second));
+        });
+    }
+
+    #[test]
+    fn missing_chars_follow_notdef_strategy() {
+        with_fallbacks(|library, fonts| {
+            // No fallback has a glyph for 字, and there are no fallbacks for
+            // Arabic at all.
+            for c in &['字', 'ب'] {
+                let font = library.device_font_for_char(*c).unwrap();
+                assert!(Font::ptr_eq(font, fonts.device));
+            }
+
+            library.set_notdef_strategy(NotdefStrategy::Box);
+            for c in &['字', 'ب'] {
+                let font = library.device_font_for_char(*c).unwrap();
+                assert!(Font::ptr_eq(font, fonts.notdef));
+            }
+        });
+    }
+}
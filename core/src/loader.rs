@@ -2,74 +2,150 @@
 
 use crate::avm1::activation::{Activation, ActivationIdentifier};
 use crate::avm1::{Avm1, Object, TObject, Value};
-use crate::avm2::{Activation as Avm2Activation, Domain as Avm2Domain};
+use crate::avm2::{
+    Activation as Avm2Activation, Avm2, Domain as Avm2Domain, Event as Avm2Event, LoaderStream,
+    Namespace as Avm2Namespace, Object as Avm2Object, QName as Avm2QName, TObject as _,
+    Value as Avm2Value,
+};
 use crate::backend::navigator::OwnedFuture;
-use crate::context::{ActionQueue, ActionType};
-use crate::display_object::{DisplayObject, MorphShape, TDisplayObject};
+use crate::backend::render::{decode_define_bits_jpeg, Bitmap};
+use crate::bundle::{Bundle, BundleError};
+use crate::context::{ActionQueue, ActionType, UpdateContext};
+use crate::display_object::{
+    DisplayObject, MorphShape, MovieClip, ParseEnumError, TDisplayObject, TDisplayObjectContainer,
+};
 use crate::player::{Player, NEWEST_PLAYER_VERSION};
+use crate::remoting::{NetConnectionObject, NetConnections};
 use crate::string::AvmString;
 use crate::tag_utils::SwfMovie;
-use crate::vminterface::Instantiator;
+use crate::vminterface::{AvmType, Instantiator};
 use crate::xml::XmlNode;
 use encoding_rs::UTF_8;
 use gc_arena::{Collect, CollectionContext};
 use generational_arena::{Arena, Index};
-use std::string::FromUtf8Error;
+use std::str::FromStr;
 use std::sync::{Arc, Mutex, Weak};
-use thiserror::Error;
 use url::form_urlencoded;
 
 pub type Handle = Index;
 
-#[derive(Error, Debug)]
-pub enum Error {
-    #[error("Load cancelled")]
-    Cancelled,
+pub use ruffle_navigator_api::Error;
 
-    #[error("Non-root-movie loader spawned as root movie loader")]
-    NotRootMovieLoader,
+pub type FormLoadHandler<'gc> =
+    fn(&mut Activation<'_, 'gc, '_>, Object<'gc>, data: &[u8]) -> Result<(), Error>;
 
-    #[error("Non-movie loader spawned as movie loader")]
-    NotMovieLoader,
+pub type FormErrorHandler<'gc> = fn(&mut Activation<'_, 'gc, '_>, Object<'gc>) -> Result<(), Error>;
 
-    #[error("Non-form loader spawned as form loader")]
-    NotFormLoader,
+impl From<BundleError> for Error {
+    fn from(error: BundleError) -> Self {
+        Error::InvalidBundle(error.to_string())
+    }
+}
 
-    #[error("Non-load vars loader spawned as load vars loader")]
-    NotLoadVarsLoader,
+impl From<crate::avm1::error::Error<'_>> for Error {
+    fn from(error: crate::avm1::error::Error<'_>) -> Self {
+        Error::Avm1Error(error.to_string())
+    }
+}
 
-    #[error("Non-XML loader spawned as XML loader")]
-    NotXmlLoader,
+/// How many bytes of a loaded movie are reported per progress event.
+///
+/// Fetches complete all at once, so progress is reported afterwards in steps
+/// of roughly the size Flash Player receives from the network, rather than
+/// as a single event. Preloaders that expect more than one progress event
+/// depend on this.
+const LOAD_PROGRESS_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The byte counts to report in the progress events of a load of the given
+/// length, ending with the full length. Empty loads still report progress
+/// once.
+fn load_progress_steps(length: usize) -> impl Iterator<Item = usize> {
+    let steps = ((length + LOAD_PROGRESS_CHUNK_SIZE - 1) / LOAD_PROGRESS_CHUNK_SIZE).max(1);
+    (1..=steps).map(move |step| (step * LOAD_PROGRESS_CHUNK_SIZE).min(length))
+}
 
-    #[error("Could not fetch movie {0}")]
-    FetchError(String),
+/// When an image loaded into an AVM2 `Loader` is decoded, as set by the
+/// `imageDecodingPolicy` of its `LoaderContext`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageDecodingPolicy {
+    /// The image is decoded on the player's thread as the load completes.
+    OnDemand,
 
-    #[error("Invalid SWF")]
-    InvalidSwf(#[from] crate::tag_utils::Error),
+    /// The image is decoded in the background before the load completes.
+    OnLoad,
+}
 
-    #[error("Invalid XML encoding")]
-    InvalidXmlEncoding(#[from] FromUtf8Error),
+impl FromStr for ImageDecodingPolicy {
+    type Err = ParseEnumError;
 
-    #[error("Network error")]
-    NetworkError(#[from] std::io::Error),
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "onDemand" => Ok(ImageDecodingPolicy::OnDemand),
+            "onLoad" => Ok(ImageDecodingPolicy::OnLoad),
+            _ => Err(ParseEnumError),
+        }
+    }
+}
 
-    #[error("Network unavailable.")]
-    NetworkUnavailable,
+/// Decode an image without holding up the player.
+///
+/// Where threads are available, the image is decoded on a thread of its own.
+/// Otherwise it is decoded when the future is first polled, which is still
+/// outside of any frame update.
+fn decode_image_in_background(data: Vec<u8>) -> OwnedFuture<Bitmap, Error> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let task = Arc::new(Mutex::new(BackgroundTask {
+            result: None,
+            waker: None,
+        }));
+        let thread_task = task.clone();
+        std::thread::spawn(move || {
+            let result = decode_define_bits_jpeg(&data, None).map_err(|e| e.to_string());
+            let mut task = thread_task.lock().unwrap();
+            task.result = Some(result);
+            if let Some(waker) = task.waker.take() {
+                waker.wake();
+            }
+        });
 
-    // TODO: We can't support lifetimes on this error object yet (or we'll need some backends inside
-    // the GC arena). We're losing info here. How do we fix that?
-    #[error("Error running avm1 script: {0}")]
-    Avm1Error(String),
-}
+        Box::pin(async move {
+            BackgroundTaskFuture(task)
+                .await
+                .map_err(Error::InvalidImage)
+        })
+    }
 
-pub type FormLoadHandler<'gc> =
-    fn(&mut Activation<'_, 'gc, '_>, Object<'gc>, data: &[u8]) -> Result<(), Error>;
+    #[cfg(target_arch = "wasm32")]
+    Box::pin(async move {
+        decode_define_bits_jpeg(&data, None).map_err(|e| Error::InvalidImage(e.to_string()))
+    })
+}
 
-pub type FormErrorHandler<'gc> = fn(&mut Activation<'_, 'gc, '_>, Object<'gc>) -> Result<(), Error>;
+/// The result of work done on another thread, and the task waiting for it.
+#[cfg(not(target_arch = "wasm32"))]
+struct BackgroundTask<T> {
+    result: Option<T>,
+    waker: Option<std::task::Waker>,
+}
 
-impl From<crate::avm1::error::Error<'_>> for Error {
-    fn from(error: crate::avm1::error::Error<'_>) -> Self {
-        Error::Avm1Error(error.to_string())
+/// A future that resolves once a background thread has finished its work.
+#[cfg(not(target_arch = "wasm32"))]
+struct BackgroundTaskFuture<T>(Arc<Mutex<BackgroundTask<T>>>);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T> std::future::Future for BackgroundTaskFuture<T> {
+    type Output = T;
+
+    fn poll(self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<T> {
+        let mut task = self.0.lock().unwrap();
+        match task.result.take() {
+            Some(result) => std::task::Poll::Ready(result),
+            None => {
+                task.waker = Some(cx.waker().clone());
+                std::task::Poll::Pending
+            }
+        }
     }
 }
 
@@ -251,6 +327,145 @@ impl<'gc> LoadManager<'gc> {
 
         loader.xml_loader(player, fetch)
     }
+
+    /// Kick off a data load into an AVM2 `URLLoader`.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_data_into_url_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: Avm2Object<'gc>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+        url: String,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::UrlLoader {
+            self_handle: None,
+            target_object,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.url_loader(player, fetch, url)
+    }
+
+    /// Kick off a progressive data load into an AVM2 `URLStream`.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_data_into_url_stream(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: Avm2Object<'gc>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+        url: String,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::UrlStream {
+            self_handle: None,
+            target_object,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.url_stream(player, fetch, url)
+    }
+
+    /// Kick off a `NetConnection.call`, waiting for the response of the
+    /// gateway.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_net_connection_response(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        connection: NetConnectionObject<'gc>,
+        responder: Option<NetConnectionObject<'gc>>,
+        response_uri: String,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::NetConnectionCall {
+            self_handle: None,
+            connection,
+            responder,
+            response_uri,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.net_connection_call(player, fetch)
+    }
+
+    /// Kick off a load of a SWF or image into an AVM2 `Loader`.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    #[allow(clippy::too_many_arguments)]
+    pub fn load_content_into_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: Avm2Object<'gc>,
+        loader_info: Avm2Object<'gc>,
+        domain: Avm2Domain<'gc>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+        url: String,
+        loader_url: Option<String>,
+        parameters: Vec<(String, String)>,
+        image_decoding_policy: ImageDecodingPolicy,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::Avm2Loader {
+            self_handle: None,
+            target_object,
+            loader_info,
+            domain,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.avm2_loader(
+            player,
+            fetch,
+            url,
+            loader_url,
+            parameters,
+            image_decoding_policy,
+        )
+    }
+
+    /// Cancel all loads in progress into an AVM2 `Loader`.
+    ///
+    /// The futures of cancelled loads finish without firing any events.
+    pub fn cancel_avm2_loader_loads(&mut self, target: Avm2Object<'gc>) {
+        self.0.retain(|_, loader| match loader {
+            Loader::Avm2Loader { target_object, .. } => !Avm2Object::ptr_eq(*target_object, target),
+            _ => true,
+        });
+    }
+
+    /// Cancel all loads in progress into an AVM2 `URLLoader` or `URLStream`.
+    ///
+    /// The futures of cancelled loads finish without firing any events.
+    pub fn cancel_url_loads(&mut self, target: Avm2Object<'gc>) {
+        self.0.retain(|_, loader| match loader {
+            Loader::UrlLoader { target_object, .. } | Loader::UrlStream { target_object, .. } => {
+                !Avm2Object::ptr_eq(*target_object, target)
+            }
+            _ => true,
+        });
+    }
+
+    /// Whether a `URLLoader` or `URLStream` has a load in progress.
+    pub fn has_url_loads(&self, target: Avm2Object<'gc>) -> bool {
+        self.0.iter().any(|(_, loader)| match loader {
+            Loader::UrlLoader { target_object, .. } | Loader::UrlStream { target_object, .. } => {
+                Avm2Object::ptr_eq(*target_object, target)
+            }
+            _ => false,
+        })
+    }
 }
 
 impl<'gc> Default for LoadManager<'gc> {
@@ -343,6 +558,59 @@ pub enum Loader<'gc> {
         /// The target node whose contents will be replaced with the parsed XML.
         target_node: XmlNode<'gc>,
     },
+
+    /// Loader that is loading data into an AVM2 `URLLoader`.
+    UrlLoader {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// The target `URLLoader` to load data into.
+        target_object: Avm2Object<'gc>,
+    },
+
+    /// Loader that is streaming data into an AVM2 `URLStream`.
+    UrlStream {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// The target `URLStream` to append data to.
+        target_object: Avm2Object<'gc>,
+    },
+
+    /// Loader that is loading a SWF or image into an AVM2 `Loader`.
+    Avm2Loader {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// The target `Loader` to add the loaded content to.
+        target_object: Avm2Object<'gc>,
+
+        /// The `LoaderInfo` that events about the load are fired on.
+        loader_info: Avm2Object<'gc>,
+
+        /// The application domain that loaded movies define their classes in.
+        domain: Avm2Domain<'gc>,
+    },
+
+    /// Loader that is waiting for the response to a `NetConnection.call`.
+    NetConnectionCall {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// The connection that made the call.
+        connection: NetConnectionObject<'gc>,
+
+        /// The responder to pass the response on to.
+        responder: Option<NetConnectionObject<'gc>>,
+
+        /// The URI the response to the call is sent to.
+        #[collect(require_static)]
+        response_uri: String,
+    },
 }
 
 impl<'gc> Loader<'gc> {
@@ -357,6 +625,10 @@ impl<'gc> Loader<'gc> {
             Loader::Form { self_handle, .. } => *self_handle = Some(handle),
             Loader::LoadVars { self_handle, .. } => *self_handle = Some(handle),
             Loader::Xml { self_handle, .. } => *self_handle = Some(handle),
+            Loader::UrlLoader { self_handle, .. } => *self_handle = Some(handle),
+            Loader::UrlStream { self_handle, .. } => *self_handle = Some(handle),
+            Loader::NetConnectionCall { self_handle, .. } => *self_handle = Some(handle),
+            Loader::Avm2Loader { self_handle, .. } => *self_handle = Some(handle),
         }
     }
 
@@ -391,6 +663,13 @@ impl<'gc> Loader<'gc> {
                 })?;
 
             let data = (fetch.await).and_then(|data| {
+                if Bundle::is_bundle(&data) {
+                    let bundle = Bundle::from_zip(&data, &url)?;
+                    let movie = bundle.movie()?;
+                    player.lock().unwrap().mount_bundle(bundle);
+                    return Ok((data.len(), movie));
+                }
+
                 Ok((
                     data.len(),
                     SwfMovie::from_data(&data, Some(url.clone()), None)?,
@@ -512,19 +791,21 @@ impl<'gc> Loader<'gc> {
                             .set_avm2_domain(domain);
 
                         if let Some(broadcaster) = broadcaster {
-                            Avm1::run_stack_frame_for_method(
-                                clip,
-                                broadcaster,
-                                NEWEST_PLAYER_VERSION,
-                                uc,
-                                "broadcastMessage",
-                                &[
-                                    "onLoadProgress".into(),
-                                    Value::Object(broadcaster),
-                                    length.into(),
-                                    length.into(),
-                                ],
-                            );
+                            for loaded in load_progress_steps(length) {
+                                Avm1::run_stack_frame_for_method(
+                                    clip,
+                                    broadcaster,
+                                    NEWEST_PLAYER_VERSION,
+                                    uc,
+                                    "broadcastMessage",
+                                    &[
+                                        "onLoadProgress".into(),
+                                        Value::Object(broadcaster),
+                                        loaded.into(),
+                                        length.into(),
+                                    ],
+                                );
+                            }
                         }
 
                         let mut mc = clip
@@ -859,4 +1140,605 @@ impl<'gc> Loader<'gc> {
             Ok(())
         })
     }
+
+    /// Creates a future for a `URLLoader` load call.
+    pub fn url_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+        url: String,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::UrlLoader { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => return Box::pin(async { Err(Error::NotUrlLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let data = fetch.await;
+
+            player.lock().unwrap().update(|uc| {
+                let target = match uc.load_manager.get_loader(handle) {
+                    Some(&Loader::UrlLoader { target_object, .. }) => target_object,
+                    None => return Err(Error::Cancelled),
+                    _ => return Err(Error::NotUrlLoader),
+                };
+
+                // The load is over, so `close` no longer needs to find it.
+                uc.load_manager.0.remove(handle);
+
+                match data {
+                    Ok(data) => {
+                        let length = data.len();
+
+                        Avm2::dispatch_event(uc, Avm2Event::new("open"), target)?;
+
+                        for loaded in load_progress_steps(length) {
+                            let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+                            let mut target = target;
+                            target.set_property(
+                                target,
+                                &Avm2QName::new(Avm2Namespace::public(), "bytesLoaded"),
+                                loaded.into(),
+                                &mut activation,
+                            )?;
+                            target.set_property(
+                                target,
+                                &Avm2QName::new(Avm2Namespace::public(), "bytesTotal"),
+                                length.into(),
+                                &mut activation,
+                            )?;
+
+                            let progress_class = activation.avm2().classes().progressevent;
+                            let progress_evt = progress_class.construct(
+                                &mut activation,
+                                &[
+                                    "progress".into(),
+                                    false.into(),
+                                    false.into(),
+                                    loaded.into(),
+                                    length.into(),
+                                ],
+                            )?;
+                            drop(activation);
+
+                            Avm2::dispatch_event_object(uc, progress_evt, target)?;
+                        }
+
+                        // TODO: Navigator backends don't report the response
+                        // status yet, so a successful fetch is assumed to
+                        // have been a 200.
+                        url_loader_http_status(uc, target, 200)?;
+
+                        let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+                        let data_value = url_loader_data(&mut activation, target, &data)?;
+                        let mut target = target;
+                        target.set_property(
+                            target,
+                            &Avm2QName::new(Avm2Namespace::public(), "data"),
+                            data_value,
+                            &mut activation,
+                        )?;
+                        drop(activation);
+
+                        Avm2::dispatch_event(uc, Avm2Event::new("complete"), target)?;
+                    }
+                    Err(Error::SecurityError(message)) => {
+                        url_loader_security_error(uc, target, &message)?;
+                    }
+                    Err(_) => {
+                        // Flash Player reports a status of 0 when no response
+                        // was received.
+                        url_loader_http_status(uc, target, 0)?;
+
+                        url_loader_io_error(uc, target, &url)?;
+                    }
+                }
+
+                Ok(())
+            })
+        })
+    }
+
+    /// Creates a future for a `URLStream` load call.
+    ///
+    /// Each chunk of the response is appended to the stream's buffer before
+    /// the `progress` event announcing it, so that it can be read from the
+    /// event handler. Reading doesn't move the point where data is appended.
+    pub fn url_stream(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+        url: String,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::UrlStream { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => return Box::pin(async { Err(Error::NotUrlStream) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let data = fetch.await;
+
+            player.lock().unwrap().update(|uc| {
+                let target = match uc.load_manager.get_loader(handle) {
+                    Some(&Loader::UrlStream { target_object, .. }) => target_object,
+                    None => return Err(Error::Cancelled),
+                    _ => return Err(Error::NotUrlStream),
+                };
+
+                let data = match data {
+                    Ok(data) => data,
+                    Err(Error::SecurityError(message)) => {
+                        uc.load_manager.0.remove(handle);
+                        url_loader_security_error(uc, target, &message)?;
+
+                        return Ok(());
+                    }
+                    Err(_) => {
+                        uc.load_manager.0.remove(handle);
+
+                        // Flash Player reports a status of 0 when no response
+                        // was received.
+                        url_loader_http_status(uc, target, 0)?;
+                        url_loader_io_error(uc, target, &url)?;
+
+                        return Ok(());
+                    }
+                };
+
+                Avm2::dispatch_event(uc, Avm2Event::new("open"), target)?;
+
+                let length = data.len();
+                let mut appended = 0;
+                for loaded in load_progress_steps(length) {
+                    // `close` stops the stream, even from an event handler.
+                    if uc.load_manager.get_loader(handle).is_none() {
+                        return Ok(());
+                    }
+
+                    if let Some(mut buffer) = target.as_bytearray_mut(uc.gc_context) {
+                        let end = buffer.len();
+                        buffer.write_at(&data[appended..loaded], end)?;
+                    }
+                    appended = loaded;
+
+                    let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+                    let progress_class = activation.avm2().classes().progressevent;
+                    let progress_evt = progress_class.construct(
+                        &mut activation,
+                        &[
+                            "progress".into(),
+                            false.into(),
+                            false.into(),
+                            loaded.into(),
+                            length.into(),
+                        ],
+                    )?;
+                    drop(activation);
+
+                    Avm2::dispatch_event_object(uc, progress_evt, target)?;
+                }
+
+                if uc.load_manager.0.remove(handle).is_none() {
+                    return Ok(());
+                }
+
+                // TODO: Navigator backends don't report the response status
+                // yet, so a successful fetch is assumed to have been a 200.
+                url_loader_http_status(uc, target, 200)?;
+
+                Avm2::dispatch_event(uc, Avm2Event::new("complete"), target)?;
+
+                Ok(())
+            })
+        })
+    }
+
+    /// Creates a future for a `NetConnection.call`.
+    pub fn net_connection_call(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::NetConnectionCall { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => return Box::pin(async { Err(Error::NotNetConnectionCall) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let data = fetch.await;
+
+            player.lock().unwrap().update(|uc| {
+                let (connection, responder, response_uri) = match uc.load_manager.0.remove(handle) {
+                    Some(Loader::NetConnectionCall {
+                        connection,
+                        responder,
+                        response_uri,
+                        ..
+                    }) => (connection, responder, response_uri),
+                    None => return Err(Error::Cancelled),
+                    _ => return Err(Error::NotNetConnectionCall),
+                };
+
+                NetConnections::handle_response(uc, connection, responder, &response_uri, data)
+            })
+        })
+    }
+
+    /// Creates a future for an AVM2 `Loader` load call.
+    ///
+    /// The loaded SWF or image becomes the content of the `Loader`, while
+    /// events about the load are fired on its `contentLoaderInfo`. Loaded
+    /// SWFs are given `parameters` as their flashvars, and loaded images are
+    /// decoded according to `image_decoding_policy`.
+    pub fn avm2_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+        url: String,
+        loader_url: Option<String>,
+        parameters: Vec<(String, String)>,
+        image_decoding_policy: ImageDecodingPolicy,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::Avm2Loader { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => return Box::pin(async { Err(Error::NotAvm2Loader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let data = fetch.await;
+
+            let bitmap = match &data {
+                Ok(data)
+                    if image_decoding_policy == ImageDecodingPolicy::OnLoad
+                        && !is_swf_data(data) =>
+                {
+                    Some(decode_image_in_background(data.clone()).await)
+                }
+                _ => None,
+            };
+
+            player.lock().unwrap().update(|uc| {
+                let (target, loader_info, domain) = match uc.load_manager.get_loader(handle) {
+                    Some(&Loader::Avm2Loader {
+                        target_object,
+                        loader_info,
+                        domain,
+                        ..
+                    }) => (target_object, loader_info, domain),
+                    None => return Err(Error::Cancelled),
+                    _ => return Err(Error::NotAvm2Loader),
+                };
+
+                // The load is over, so `close` no longer needs to find it.
+                uc.load_manager.0.remove(handle);
+
+                let data = match data {
+                    Ok(data) => data,
+                    Err(_) => return url_loader_io_error(uc, loader_info, &url),
+                };
+
+                Avm2::dispatch_event(uc, Avm2Event::new("open"), loader_info)?;
+
+                let loaded = loader_content(
+                    uc,
+                    &data,
+                    bitmap,
+                    url.clone(),
+                    loader_url,
+                    parameters,
+                    domain,
+                );
+                let (movie, content) = match loaded {
+                    Ok(loaded) => loaded,
+                    Err(e) => {
+                        log::warn!("Could not load content from {}: {}", url, e);
+                        return url_loader_io_error(uc, loader_info, &url);
+                    }
+                };
+                loader_info
+                    .set_loader_stream(uc.gc_context, Some(LoaderStream::Swf(movie, content)));
+
+                let length = data.len();
+                for loaded in load_progress_steps(length) {
+                    let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+                    let progress_class = activation.avm2().classes().progressevent;
+                    let progress_evt = progress_class.construct(
+                        &mut activation,
+                        &[
+                            "progress".into(),
+                            false.into(),
+                            false.into(),
+                            loaded.into(),
+                            length.into(),
+                        ],
+                    )?;
+                    drop(activation);
+
+                    Avm2::dispatch_event_object(uc, progress_evt, loader_info)?;
+                }
+
+                if let Some(mut ctr) = target
+                    .as_display_object()
+                    .and_then(|dobj| dobj.as_container())
+                {
+                    ctr.insert_at_index(uc, content, 0);
+                    content.set_placed_by_script(uc.gc_context, true);
+                }
+
+                // Loaded movies are constructed before `init`, so that their
+                // document class is ready for its listeners.
+                content.construct_frame(uc);
+
+                Avm2::dispatch_event(uc, Avm2Event::new("init"), loader_info)?;
+                Avm2::dispatch_event(uc, Avm2Event::new("complete"), loader_info)?;
+
+                Ok(())
+            })
+        })
+    }
+}
+
+/// Whether loaded data is a SWF, rather than an image.
+fn is_swf_data(data: &[u8]) -> bool {
+    matches!(data.get(0..3), Some(b"FWS") | Some(b"CWS") | Some(b"ZWS"))
+}
+
+/// Build the content of an AVM2 `Loader` from loaded data, which is either an
+/// AVM1 or AVM2 SWF, or a JPEG, PNG or GIF image.
+///
+/// Images that were already decoded in the background are passed in as
+/// `bitmap`; others are decoded here. They are given an empty movie to stand
+/// in for the SWF they would otherwise have been loaded from.
+#[allow(clippy::too_many_arguments)]
+fn loader_content<'gc>(
+    uc: &mut UpdateContext<'_, 'gc, '_>,
+    data: &[u8],
+    bitmap: Option<Result<Bitmap, Error>>,
+    url: String,
+    loader_url: Option<String>,
+    parameters: Vec<(String, String)>,
+    domain: Avm2Domain<'gc>,
+) -> Result<(Arc<SwfMovie>, DisplayObject<'gc>), Error> {
+    if is_swf_data(data) {
+        let mut movie = SwfMovie::from_data(data, Some(url), loader_url)?;
+        movie.append_parameters(parameters);
+        let movie = Arc::new(movie);
+        uc.library
+            .library_for_movie_mut(movie.clone())
+            .set_avm2_domain(domain);
+
+        let clip = MovieClip::from_movie(uc.gc_context, movie.clone());
+
+        // AVM2 code can't touch AVM1 objects, so AVM1 movies are given to it
+        // wrapped in an `AVM1Movie`. The wrapper doesn't become the AVM1
+        // movie's `_root`.
+        let content: DisplayObject<'gc> = if movie.avm_type() == AvmType::Avm1 {
+            clip.set_lock_root(uc.gc_context, true);
+
+            let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+            let avm1movie_class = activation.avm2().classes().avm1movie;
+            let wrapper = avm1movie_class
+                .construct(&mut activation, &[])?
+                .as_display_object()
+                .ok_or_else(|| Error::Avm2Error("AVM1Movie has no display object".to_string()))?;
+            drop(activation);
+
+            wrapper.set_is_root(uc.gc_context, true);
+            if let Some(mut ctr) = wrapper.as_container() {
+                ctr.insert_at_index(uc, clip.into(), 0);
+            }
+
+            wrapper
+        } else {
+            clip.into()
+        };
+
+        clip.post_instantiation(uc, clip.into(), None, Instantiator::Movie, false);
+
+        let mut morph_shapes = fnv::FnvHashMap::default();
+        clip.preload(uc, &mut morph_shapes);
+
+        // Finalize morph shapes.
+        for (id, static_data) in morph_shapes {
+            let morph_shape = MorphShape::new(uc.gc_context, static_data);
+            uc.library
+                .library_for_movie_mut(movie.clone())
+                .register_character(id, crate::character::Character::MorphShape(morph_shape));
+        }
+
+        return Ok((movie, content));
+    }
+
+    let bitmap = match bitmap {
+        Some(bitmap) => bitmap?,
+        None => {
+            decode_define_bits_jpeg(data, None).map_err(|e| Error::InvalidImage(e.to_string()))?
+        }
+    };
+    let movie = Arc::new(SwfMovie::from_loaded_image(
+        uc.swf.version(),
+        url,
+        loader_url,
+        data.len(),
+    ));
+
+    let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+    let bitmapdata_class = activation.avm2().classes().bitmapdata;
+    let bitmap_data = bitmapdata_class.construct(
+        &mut activation,
+        &[
+            bitmap.width.into(),
+            bitmap.height.into(),
+            true.into(),
+            0.into(),
+        ],
+    )?;
+    if let Some(bd) = bitmap_data.as_bitmap_data() {
+        let pixels: Vec<i32> = bitmap.data.into();
+        bd.write(activation.context.gc_context).set_pixels(
+            bitmap.width,
+            bitmap.height,
+            true,
+            pixels.into_iter().map(|p| p.into()).collect(),
+        );
+    }
+
+    let bitmap_class = activation.avm2().classes().bitmap;
+    let content = bitmap_class
+        .construct(&mut activation, &[bitmap_data.into()])?
+        .as_display_object()
+        .ok_or_else(|| Error::InvalidImage("Bitmap has no display object".to_string()))?;
+    // Loaded images are the root of their own display tree, like SWFs.
+    content.set_is_root(activation.context.gc_context, true);
+
+    Ok((movie, content))
+}
+
+/// Dispatch an `IOErrorEvent` for a failed load on a `URLLoader`, `URLStream`
+/// or `LoaderInfo`.
+fn url_loader_io_error<'gc>(
+    uc: &mut UpdateContext<'_, 'gc, '_>,
+    target: Avm2Object<'gc>,
+    url: &str,
+) -> Result<(), Error> {
+    let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+    let message = AvmString::new(
+        activation.context.gc_context,
+        format!("Error #2032: Stream Error. URL: {}", url),
+    );
+    let io_error_class = activation.avm2().classes().ioerrorevent;
+    let io_error_evt = io_error_class.construct(
+        &mut activation,
+        &[
+            "ioError".into(),
+            false.into(),
+            false.into(),
+            message.into(),
+            2032.into(),
+        ],
+    )?;
+    drop(activation);
+
+    Avm2::dispatch_event_object(uc, io_error_evt, target)?;
+
+    Ok(())
+}
+
+/// Dispatch a `SecurityErrorEvent` for a load on a `URLLoader` or `URLStream`
+/// that was blocked by the security sandbox.
+fn url_loader_security_error<'gc>(
+    uc: &mut UpdateContext<'_, 'gc, '_>,
+    target: Avm2Object<'gc>,
+    message: &str,
+) -> Result<(), Error> {
+    let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+    let message = AvmString::new(
+        activation.context.gc_context,
+        format!("Error #2048: Security sandbox violation: {}", message),
+    );
+    let security_error_class = activation.avm2().classes().securityerrorevent;
+    let security_error_evt = security_error_class.construct(
+        &mut activation,
+        &[
+            "securityError".into(),
+            false.into(),
+            false.into(),
+            message.into(),
+            2048.into(),
+        ],
+    )?;
+    drop(activation);
+
+    Avm2::dispatch_event_object(uc, security_error_evt, target)?;
+
+    Ok(())
+}
+
+/// Dispatch an `HTTPStatusEvent` with the given status on a `URLLoader` or
+/// `URLStream`.
+fn url_loader_http_status<'gc>(
+    uc: &mut UpdateContext<'_, 'gc, '_>,
+    target: Avm2Object<'gc>,
+    status: i32,
+) -> Result<(), Error> {
+    let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+    let http_status_class = activation.avm2().classes().httpstatusevent;
+    let http_status_evt = http_status_class.construct(
+        &mut activation,
+        &[
+            "httpStatus".into(),
+            false.into(),
+            false.into(),
+            status.into(),
+        ],
+    )?;
+    drop(activation);
+
+    Avm2::dispatch_event_object(uc, http_status_evt, target)?;
+
+    Ok(())
+}
+
+/// Convert loaded data to the value stored in `URLLoader.data`, according to
+/// the loader's `dataFormat`.
+fn url_loader_data<'gc>(
+    activation: &mut Avm2Activation<'_, 'gc, '_>,
+    target: Avm2Object<'gc>,
+    data: &[u8],
+) -> Result<Avm2Value<'gc>, Error> {
+    let data_format = target
+        .get_property(
+            target,
+            &Avm2QName::new(Avm2Namespace::public(), "dataFormat"),
+            activation,
+        )?
+        .coerce_to_string(activation)?;
+
+    match &*data_format {
+        "binary" => {
+            let bytearray_class = activation.avm2().classes().bytearray;
+            let bytearray = bytearray_class.construct(activation, &[])?;
+            let mut write = bytearray
+                .as_bytearray_mut(activation.context.gc_context)
+                .unwrap();
+            write.set_length(data.len());
+            write.bytes_mut().copy_from_slice(data);
+            drop(write);
+
+            Ok(bytearray.into())
+        }
+        "variables" => {
+            let text = AvmString::new(activation.context.gc_context, UTF_8.decode(data).0);
+            let variables_class = activation.avm2().classes().urlvariables;
+
+            Ok(variables_class
+                .construct(activation, &[text.into()])?
+                .into())
+        }
+        _ => Ok(AvmString::new(activation.context.gc_context, UTF_8.decode(data).0).into()),
+    }
 }
@@ -0,0 +1,474 @@
+//! Connections between movies made with `LocalConnection`, for AVM1 and AVM2.
+//!
+//! A movie listens on a connection by name, and any movie in the same player
+//! can then call methods on it. Like in Flash Player, calls are queued when
+//! they are sent and delivered between frames, after which the sender is told
+//! whether the call reached a connection.
+//!
+//! Connection names that start with an underscore or contain a colon are
+//! shared by all domains. Any other name is prefixed with the domain of the
+//! movie that uses it, so that unrelated movies can't collide.
+
+use crate::avm1::activation::{
+    Activation as Avm1Activation, ActivationIdentifier as Avm1ActivationIdentifier,
+};
+use crate::avm1::property::Attribute as Avm1Attribute;
+use crate::avm1::{
+    Object as Avm1Object, ScriptObject as Avm1ScriptObject, TObject as _, Value as Avm1Value,
+};
+use crate::avm2::{
+    Activation as Avm2Activation, Avm2, Namespace, Object as Avm2Object, QName, TObject as _,
+    Value as Avm2Value,
+};
+use crate::context::UpdateContext;
+use crate::string::AvmString;
+use gc_arena::Collect;
+use std::collections::HashMap;
+use url::Url;
+
+/// The object that owns a connection, or sent a call.
+#[derive(Clone, Copy, Collect)]
+#[collect(no_drop)]
+pub enum LocalConnectionTarget<'gc> {
+    Avm1(Avm1Object<'gc>),
+    Avm2(Avm2Object<'gc>),
+}
+
+impl<'gc> LocalConnectionTarget<'gc> {
+    fn is(&self, other: LocalConnectionTarget<'gc>) -> bool {
+        match (*self, other) {
+            (LocalConnectionTarget::Avm1(a), LocalConnectionTarget::Avm1(b)) => {
+                Avm1Object::ptr_eq(a, b)
+            }
+            (LocalConnectionTarget::Avm2(a), LocalConnectionTarget::Avm2(b)) => {
+                Avm2Object::ptr_eq(a, b)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The arguments of a call, as they were passed by the sender.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub enum LocalConnectionArgs<'gc> {
+    Avm1(Vec<Avm1Value<'gc>>),
+    Avm2(Vec<Avm2Value<'gc>>),
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+struct Connection<'gc> {
+    target: LocalConnectionTarget<'gc>,
+
+    /// The domain of the movie that made the connection.
+    domain: String,
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+struct Message<'gc> {
+    sender: LocalConnectionTarget<'gc>,
+
+    /// The domain of the movie that sent the call.
+    sender_domain: String,
+
+    connection_name: String,
+    method_name: String,
+    args: LocalConnectionArgs<'gc>,
+}
+
+/// Manages the connections made by movies, and the calls between them.
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct LocalConnections<'gc> {
+    connections: HashMap<String, Connection<'gc>>,
+
+    /// Calls waiting to be delivered.
+    messages: Vec<Message<'gc>>,
+
+    /// Domains that AVM2 connections have accepted calls from.
+    allowed_domains: Vec<(LocalConnectionTarget<'gc>, Vec<String>)>,
+}
+
+impl<'gc> LocalConnections<'gc> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the domain of a movie, as used in connection names.
+    pub fn domain(movie_url: Option<&str>) -> String {
+        movie_url
+            .and_then(|url| Url::parse(url).ok())
+            .and_then(|url| url.host_str().map(str::to_lowercase))
+            .unwrap_or_else(|| "localhost".to_string())
+    }
+
+    /// Start listening for calls on a connection.
+    ///
+    /// Returns `false` if the target is already connected, or another object
+    /// is using the name.
+    pub fn connect(
+        &mut self,
+        target: LocalConnectionTarget<'gc>,
+        movie_url: Option<&str>,
+        name: &str,
+    ) -> bool {
+        let domain = Self::domain(movie_url);
+        let name = qualified_name(&domain, name);
+        if self.is_connected(target) || self.connections.contains_key(&name) {
+            return false;
+        }
+
+        self.connections.insert(name, Connection { target, domain });
+        true
+    }
+
+    /// Stop listening for calls.
+    ///
+    /// Returns `false` if the target wasn't connected.
+    pub fn close(&mut self, target: LocalConnectionTarget<'gc>) -> bool {
+        self.allowed_domains.retain(|(t, _)| !t.is(target));
+
+        let count = self.connections.len();
+        self.connections
+            .retain(|_, connection| !connection.target.is(target));
+        self.connections.len() != count
+    }
+
+    /// Whether a target is listening for calls.
+    pub fn is_connected(&self, target: LocalConnectionTarget<'gc>) -> bool {
+        self.connections
+            .values()
+            .any(|connection| connection.target.is(target))
+    }
+
+    /// Accept calls to an AVM2 connection from other domains.
+    ///
+    /// The domain `*` accepts calls from every domain.
+    pub fn allow_domains(&mut self, target: LocalConnectionTarget<'gc>, domains: Vec<String>) {
+        let domains = domains.into_iter().map(|d| d.to_lowercase());
+        match self.allowed_domains.iter_mut().find(|(t, _)| t.is(target)) {
+            Some((_, allowed)) => allowed.extend(domains),
+            None => self.allowed_domains.push((target, domains.collect())),
+        }
+    }
+
+    /// Queue a call to a method of a connection.
+    pub fn send(
+        &mut self,
+        sender: LocalConnectionTarget<'gc>,
+        movie_url: Option<&str>,
+        connection_name: &str,
+        method_name: &str,
+        args: LocalConnectionArgs<'gc>,
+    ) {
+        let sender_domain = Self::domain(movie_url);
+        let connection_name = qualified_name(&sender_domain, connection_name);
+        self.messages.push(Message {
+            sender,
+            sender_domain,
+            connection_name,
+            method_name: method_name.to_string(),
+            args,
+        });
+    }
+
+    /// Deliver every queued call, and report to each sender whether its call
+    /// reached a connection.
+    pub fn update_local_connections(context: &mut UpdateContext<'_, 'gc, '_>) {
+        let messages = std::mem::take(&mut context.local_connections.messages);
+
+        for message in messages {
+            let receiver = context
+                .local_connections
+                .connections
+                .get(&message.connection_name)
+                .map(|connection| (connection.target, connection.domain.clone()));
+
+            let delivered = match receiver {
+                Some((receiver, domain)) => {
+                    if Self::is_allowed(context, receiver, &domain, &message.sender_domain) {
+                        Self::deliver(context, receiver, &message);
+                        true
+                    } else {
+                        false
+                    }
+                }
+                None => false,
+            };
+
+            Self::report_status(context, message.sender, delivered);
+        }
+    }
+
+    /// Whether a connection accepts calls from a domain.
+    ///
+    /// Calls from the domain that made the connection are always accepted.
+    /// AVM1 connections decide through their `allowDomain` method.
+    fn is_allowed(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        receiver: LocalConnectionTarget<'gc>,
+        domain: &str,
+        sender_domain: &str,
+    ) -> bool {
+        if domain == sender_domain {
+            return true;
+        }
+
+        match receiver {
+            LocalConnectionTarget::Avm1(object) => {
+                let mut activation = Self::avm1_activation(context, "[LocalConnection]");
+                let sender_domain = AvmString::new(activation.context.gc_context, sender_domain);
+                match object.call_method("allowDomain", &[sender_domain.into()], &mut activation) {
+                    Ok(allowed) => allowed.as_bool(activation.swf_version()),
+                    Err(_) => false,
+                }
+            }
+            LocalConnectionTarget::Avm2(_) => context
+                .local_connections
+                .allowed_domains
+                .iter()
+                .find(|(target, _)| target.is(receiver))
+                .map(|(_, allowed)| allowed.iter().any(|d| d == "*" || d == sender_domain))
+                .unwrap_or(false),
+        }
+    }
+
+    /// Call the method of a connection.
+    fn deliver(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        receiver: LocalConnectionTarget<'gc>,
+        message: &Message<'gc>,
+    ) {
+        match receiver {
+            LocalConnectionTarget::Avm1(object) => {
+                let args = match &message.args {
+                    LocalConnectionArgs::Avm1(args) => args.clone(),
+                    LocalConnectionArgs::Avm2(args) => args.iter().map(avm2_to_avm1).collect(),
+                };
+
+                let mut activation = Self::avm1_activation(context, &message.method_name);
+                if let Err(e) = object.call_method(&message.method_name, &args, &mut activation) {
+                    log::error!(
+                        "Error in LocalConnection method {}: {}",
+                        message.method_name,
+                        e
+                    );
+                }
+            }
+            LocalConnectionTarget::Avm2(object) => {
+                let args: Vec<_> = match &message.args {
+                    LocalConnectionArgs::Avm1(args) => args.iter().map(avm1_to_avm2).collect(),
+                    LocalConnectionArgs::Avm2(args) => args.clone(),
+                };
+
+                if let Err(e) = Self::call_avm2_method(context, object, &message.method_name, &args)
+                {
+                    // Errors in the method go to the connection that received
+                    // the call.
+                    if let Err(e) = Self::dispatch_async_error(context, object, &e.to_string()) {
+                        log::error!("Encountered AVM2 error when dispatching asyncError: {}", e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Call a method of the client of an AVM2 connection.
+    fn call_avm2_method(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        object: Avm2Object<'gc>,
+        method_name: &str,
+        args: &[Avm2Value<'gc>],
+    ) -> Result<(), crate::avm2::Error> {
+        let mut activation = Avm2Activation::from_nothing(context.reborrow());
+        let client = object
+            .get_property(
+                object,
+                &QName::new(Namespace::public(), "client"),
+                &mut activation,
+            )?
+            .coerce_to_object(&mut activation)?;
+
+        let name = AvmString::new(activation.context.gc_context, method_name);
+        let method = client.get_property(
+            client,
+            &QName::new(Namespace::public(), name),
+            &mut activation,
+        )?;
+        let method = match method {
+            Avm2Value::Object(method) if method.as_executable().is_some() => method,
+            _ => {
+                return Err(format!(
+                    "Error #2095: flash.net.LocalConnection was unable to invoke callback {}.",
+                    method_name
+                )
+                .into())
+            }
+        };
+
+        method.call(Some(client), args, &mut activation, None)?;
+
+        Ok(())
+    }
+
+    fn dispatch_async_error(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: Avm2Object<'gc>,
+        message: &str,
+    ) -> Result<(), crate::avm2::Error> {
+        let mut activation = Avm2Activation::from_nothing(context.reborrow());
+        let message = AvmString::new(activation.context.gc_context, message);
+        let async_error_class = activation.avm2().classes().asyncerrorevent;
+        let event = async_error_class.construct(
+            &mut activation,
+            &[
+                "asyncError".into(),
+                false.into(),
+                false.into(),
+                message.into(),
+                Avm2Value::Null,
+            ],
+        )?;
+
+        Avm2::dispatch_event_object(context, event, target)?;
+
+        Ok(())
+    }
+
+    /// Tell the sender of a call whether it reached a connection.
+    fn report_status(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        sender: LocalConnectionTarget<'gc>,
+        delivered: bool,
+    ) {
+        let level = if delivered { "status" } else { "error" };
+
+        match sender {
+            LocalConnectionTarget::Avm1(object) => {
+                let mut activation = Self::avm1_activation(context, "[LocalConnection]");
+                let info = Avm1ScriptObject::object(
+                    activation.context.gc_context,
+                    Some(activation.context.avm1.prototypes().object),
+                );
+                info.define_value(
+                    activation.context.gc_context,
+                    "level",
+                    level.into(),
+                    Avm1Attribute::empty(),
+                );
+
+                let _ = object.call_method("onStatus", &[info.into()], &mut activation);
+            }
+            LocalConnectionTarget::Avm2(object) => {
+                if let Err(e) = Self::dispatch_status(context, object, level) {
+                    log::error!("Encountered AVM2 error when dispatching status: {}", e);
+                }
+            }
+        }
+    }
+
+    fn dispatch_status(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: Avm2Object<'gc>,
+        level: &'static str,
+    ) -> Result<(), crate::avm2::Error> {
+        let mut activation = Avm2Activation::from_nothing(context.reborrow());
+        let status_class = activation.avm2().classes().statusevent;
+        let event = status_class.construct(
+            &mut activation,
+            &[
+                "status".into(),
+                false.into(),
+                false.into(),
+                Avm2Value::Null,
+                level.into(),
+            ],
+        )?;
+
+        Avm2::dispatch_event_object(context, event, target)?;
+
+        Ok(())
+    }
+
+    fn avm1_activation<'a, 'gc_context>(
+        context: &'a mut UpdateContext<'_, 'gc, 'gc_context>,
+        name: &str,
+    ) -> Avm1Activation<'a, 'gc, 'gc_context> {
+        let root_clip = context.stage.root_clip();
+        let swf_version = context.swf.version();
+        let globals = context.avm1.global_object_cell();
+
+        Avm1Activation::from_nothing(
+            context.reborrow(),
+            Avm1ActivationIdentifier::root(name.to_owned()),
+            swf_version,
+            globals,
+            root_clip,
+        )
+    }
+}
+
+/// Get the name a connection is stored under.
+fn qualified_name(domain: &str, name: &str) -> String {
+    let name = name.to_lowercase();
+    if name.starts_with('_') || name.contains(':') {
+        name
+    } else {
+        format!("{}:{}", domain, name)
+    }
+}
+
+/// Convert an argument sent from AVM2 for an AVM1 connection.
+///
+/// Only primitive values can be passed between the two.
+fn avm2_to_avm1<'gc>(value: &Avm2Value<'gc>) -> Avm1Value<'gc> {
+    match value {
+        Avm2Value::Undefined => Avm1Value::Undefined,
+        Avm2Value::Null => Avm1Value::Null,
+        Avm2Value::Bool(b) => Avm1Value::Bool(*b),
+        Avm2Value::Number(n) => Avm1Value::Number(*n),
+        Avm2Value::Unsigned(u) => Avm1Value::Number(*u as f64),
+        Avm2Value::Integer(i) => Avm1Value::Number(*i as f64),
+        Avm2Value::String(s) => Avm1Value::String(*s),
+        Avm2Value::Object(_) => {
+            log::warn!("LocalConnection: Objects can't be sent from AVM2 to AVM1");
+            Avm1Value::Undefined
+        }
+    }
+}
+
+/// Convert an argument sent from AVM1 for an AVM2 connection.
+///
+/// Only primitive values can be passed between the two.
+fn avm1_to_avm2<'gc>(value: &Avm1Value<'gc>) -> Avm2Value<'gc> {
+    match value {
+        Avm1Value::Undefined => Avm2Value::Undefined,
+        Avm1Value::Null => Avm2Value::Null,
+        Avm1Value::Bool(b) => Avm2Value::Bool(*b),
+        Avm1Value::Number(n) => Avm2Value::Number(*n),
+        Avm1Value::String(s) => Avm2Value::String(*s),
+        Avm1Value::Object(_) => {
+            log::warn!("LocalConnection: Objects can't be sent from AVM1 to AVM2");
+            Avm2Value::Undefined
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connection_names() {
+        assert_eq!(qualified_name("foo.com", "Chat"), "foo.com:chat");
+        assert_eq!(qualified_name("foo.com", "_Chat"), "_chat");
+        assert_eq!(qualified_name("foo.com", "bar.com:chat"), "bar.com:chat");
+        assert_eq!(
+            LocalConnections::domain(Some("https://Foo.com/game.swf")),
+            "foo.com"
+        );
+        assert_eq!(LocalConnections::domain(None), "localhost");
+    }
+}
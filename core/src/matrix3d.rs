@@ -0,0 +1,413 @@
+//! 3D transformation matrices and perspective projection.
+//!
+//! Display objects with a `z` position or an X/Y rotation are placed in 3D
+//! space, and projected back onto their parent's plane when they're drawn.
+
+use crate::matrix::Matrix;
+use std::ops::Mul;
+
+/// The field of view of a perspective projection that hasn't been changed,
+/// in degrees.
+pub const DEFAULT_FIELD_OF_VIEW: f64 = 55.0;
+
+/// A 4x4 matrix that transforms points in 3D space.
+///
+/// The elements are stored column by column, which is the order of
+/// `Matrix3D.rawData`. Points are column vectors, so the translation is held
+/// in the 13th, 14th and 15th elements.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Matrix3D {
+    pub raw: [f64; 16],
+}
+
+/// The translation, rotation and scale that a `Matrix3D` is made from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Components {
+    pub translation: [f64; 3],
+
+    /// The rotation around each axis in radians, applied in X, Y, Z order.
+    pub rotation: [f64; 3],
+    pub scale: [f64; 3],
+}
+
+impl Matrix3D {
+    pub const IDENTITY: Self = Self {
+        raw: [
+            1.0, 0.0, 0.0, 0.0, //
+            0.0, 1.0, 0.0, 0.0, //
+            0.0, 0.0, 1.0, 0.0, //
+            0.0, 0.0, 0.0, 1.0,
+        ],
+    };
+
+    /// The element in the given row and column.
+    pub fn get(&self, row: usize, column: usize) -> f64 {
+        self.raw[column * 4 + row]
+    }
+
+    fn set(&mut self, row: usize, column: usize, value: f64) {
+        self.raw[column * 4 + row] = value;
+    }
+
+    /// Embed a 2D matrix, leaving Z coordinates as they are. The translation
+    /// is in pixels.
+    pub fn from_matrix(matrix: &Matrix) -> Self {
+        let mut result = Self::IDENTITY;
+        result.set(0, 0, matrix.a.into());
+        result.set(1, 0, matrix.b.into());
+        result.set(0, 1, matrix.c.into());
+        result.set(1, 1, matrix.d.into());
+        result.set(0, 3, matrix.tx.to_pixels());
+        result.set(1, 3, matrix.ty.to_pixels());
+        result
+    }
+
+    pub fn translation(x: f64, y: f64, z: f64) -> Self {
+        let mut result = Self::IDENTITY;
+        result.set_position([x, y, z]);
+        result
+    }
+
+    pub fn scale(x: f64, y: f64, z: f64) -> Self {
+        let mut result = Self::IDENTITY;
+        result.set(0, 0, x);
+        result.set(1, 1, y);
+        result.set(2, 2, z);
+        result
+    }
+
+    /// A rotation by the given number of degrees around an axis through the
+    /// origin.
+    ///
+    /// Rotations are clockwise when looking along the axis, so that a
+    /// rotation around the Z axis turns the same way as a 2D rotation on the
+    /// stage, where Y points down.
+    pub fn rotation(degrees: f64, axis: [f64; 3]) -> Self {
+        let length = (axis[0] * axis[0] + axis[1] * axis[1] + axis[2] * axis[2]).sqrt();
+        if length == 0.0 {
+            return Self::IDENTITY;
+        }
+        let [x, y, z] = [axis[0] / length, axis[1] / length, axis[2] / length];
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let t = 1.0 - cos;
+
+        let mut result = Self::IDENTITY;
+        result.set(0, 0, cos + x * x * t);
+        result.set(0, 1, x * y * t - z * sin);
+        result.set(0, 2, x * z * t + y * sin);
+        result.set(1, 0, y * x * t + z * sin);
+        result.set(1, 1, cos + y * y * t);
+        result.set(1, 2, y * z * t - x * sin);
+        result.set(2, 0, z * x * t - y * sin);
+        result.set(2, 1, z * y * t + x * sin);
+        result.set(2, 2, cos + z * z * t);
+        result
+    }
+
+    /// A rotation by the given number of degrees around an axis through
+    /// `pivot`.
+    pub fn rotation_around(degrees: f64, axis: [f64; 3], pivot: [f64; 3]) -> Self {
+        Self::translation(pivot[0], pivot[1], pivot[2])
+            * Self::rotation(degrees, axis)
+            * Self::translation(-pivot[0], -pivot[1], -pivot[2])
+    }
+
+    /// The translation of this matrix.
+    pub fn position(&self) -> [f64; 3] {
+        [self.get(0, 3), self.get(1, 3), self.get(2, 3)]
+    }
+
+    pub fn set_position(&mut self, position: [f64; 3]) {
+        self.set(0, 3, position[0]);
+        self.set(1, 3, position[1]);
+        self.set(2, 3, position[2]);
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut result = *self;
+        for row in 0..4 {
+            for column in 0..4 {
+                result.set(row, column, self.get(column, row));
+            }
+        }
+        result
+    }
+
+    /// Reduce this matrix to the identity by Gauss-Jordan elimination,
+    /// applying the same row operations to `other`. Returns the determinant,
+    /// which is zero if the matrix can't be reduced.
+    fn eliminate(&self, other: &mut Self) -> f64 {
+        let mut matrix = *self;
+        let mut determinant = 1.0;
+        for column in 0..4 {
+            // Pick the largest pivot, for stability.
+            let pivot = (column..4)
+                .max_by(|&a, &b| {
+                    matrix
+                        .get(a, column)
+                        .abs()
+                        .partial_cmp(&matrix.get(b, column).abs())
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .unwrap_or(column);
+            if matrix.get(pivot, column) == 0.0 {
+                return 0.0;
+            }
+            if pivot != column {
+                matrix.swap_rows(pivot, column);
+                other.swap_rows(pivot, column);
+                determinant = -determinant;
+            }
+
+            let value = matrix.get(column, column);
+            determinant *= value;
+            matrix.scale_row(column, 1.0 / value);
+            other.scale_row(column, 1.0 / value);
+            for row in 0..4 {
+                if row != column {
+                    let factor = matrix.get(row, column);
+                    matrix.subtract_row(row, column, factor);
+                    other.subtract_row(row, column, factor);
+                }
+            }
+        }
+        determinant
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for column in 0..4 {
+            self.raw.swap(column * 4 + a, column * 4 + b);
+        }
+    }
+
+    fn scale_row(&mut self, row: usize, factor: f64) {
+        for column in 0..4 {
+            self.set(row, column, self.get(row, column) * factor);
+        }
+    }
+
+    /// Subtract `factor` times the row `source` from the row `row`.
+    fn subtract_row(&mut self, row: usize, source: usize, factor: f64) {
+        for column in 0..4 {
+            let value = self.get(row, column) - factor * self.get(source, column);
+            self.set(row, column, value);
+        }
+    }
+
+    pub fn determinant(&self) -> f64 {
+        self.eliminate(&mut Self::IDENTITY)
+    }
+
+    /// The inverse of this matrix, or `None` if it can't be inverted.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut inverse = Self::IDENTITY;
+        if self.eliminate(&mut inverse) == 0.0 {
+            None
+        } else {
+            Some(inverse)
+        }
+    }
+
+    /// Transform a point, including this matrix's translation.
+    pub fn transform_point(&self, point: [f64; 3]) -> [f64; 3] {
+        let [x, y, z] = self.transform_vector(point);
+        [x + self.get(0, 3), y + self.get(1, 3), z + self.get(2, 3)]
+    }
+
+    /// Transform a direction, ignoring this matrix's translation.
+    pub fn transform_vector(&self, vector: [f64; 3]) -> [f64; 3] {
+        let mut result = [0.0; 3];
+        for (row, value) in result.iter_mut().enumerate() {
+            *value = self.get(row, 0) * vector[0]
+                + self.get(row, 1) * vector[1]
+                + self.get(row, 2) * vector[2];
+        }
+        result
+    }
+
+    /// Build a matrix that scales, then rotates around the X, Y and Z axes
+    /// in turn, then translates.
+    pub fn recompose(components: &Components) -> Self {
+        let [x, y, z] = components.translation;
+        let [rotation_x, rotation_y, rotation_z] = components.rotation;
+        let [scale_x, scale_y, scale_z] = components.scale;
+        Self::translation(x, y, z)
+            * Self::rotation(rotation_z.to_degrees(), [0.0, 0.0, 1.0])
+            * Self::rotation(rotation_y.to_degrees(), [0.0, 1.0, 0.0])
+            * Self::rotation(rotation_x.to_degrees(), [1.0, 0.0, 0.0])
+            * Self::scale(scale_x, scale_y, scale_z)
+    }
+
+    /// Split this matrix into the translation, rotation and scale that
+    /// `recompose` would build it from. Returns `None` if the matrix
+    /// flattens space in some direction.
+    pub fn decompose(&self) -> Option<Components> {
+        let column = |i: usize| [self.get(0, i), self.get(1, i), self.get(2, i)];
+        let length = |v: [f64; 3]| (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+        let mut scale = [length(column(0)), length(column(1)), length(column(2))];
+        if scale.contains(&0.0) {
+            return None;
+        }
+        // A mirrored matrix is treated as having a negative X scale.
+        if self.determinant() < 0.0 {
+            scale[0] = -scale[0];
+        }
+
+        let r = |row: usize, column: usize| self.get(row, column) / scale[column];
+        let rotation_y = (-r(2, 0)).max(-1.0).min(1.0).asin();
+        let (rotation_x, rotation_z) = if rotation_y.cos().abs() > 1e-9 {
+            (r(2, 1).atan2(r(2, 2)), r(1, 0).atan2(r(0, 0)))
+        } else {
+            // In gimbal lock, the X and Z rotations turn around the same
+            // axis, so all of it is put into the X rotation.
+            ((-r(1, 2)).atan2(r(1, 1)), 0.0)
+        };
+
+        Some(Components {
+            translation: self.position(),
+            rotation: [rotation_x, rotation_y, rotation_z],
+            scale,
+        })
+    }
+}
+
+impl Default for Matrix3D {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Mul for Matrix3D {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let mut result = Self { raw: [0.0; 16] };
+        for row in 0..4 {
+            for column in 0..4 {
+                let value = (0..4).map(|i| self.get(row, i) * rhs.get(i, column)).sum();
+                result.set(row, column, value);
+            }
+        }
+        result
+    }
+}
+
+/// How points in 3D space are projected onto the plane of the display
+/// object that they're in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PerspectiveProjection {
+    /// The angle that the viewer can see, in degrees, between 0 and 180.
+    pub field_of_view: f64,
+
+    /// The point on the plane that the viewer looks at, in pixels.
+    pub projection_center: (f64, f64),
+}
+
+impl PerspectiveProjection {
+    /// The projection of a display object that hasn't been given its own,
+    /// looking at the center of a stage of the given size.
+    pub fn for_stage(stage_size: (u32, u32)) -> Self {
+        Self {
+            field_of_view: DEFAULT_FIELD_OF_VIEW,
+            projection_center: (f64::from(stage_size.0) / 2.0, f64::from(stage_size.1) / 2.0),
+        }
+    }
+
+    /// The distance from the viewer to the plane, for a stage of the given
+    /// width.
+    pub fn focal_length(&self, stage_width: f64) -> f64 {
+        let half_angle = (self.field_of_view.max(0.01).min(179.99) / 2.0).to_radians();
+        stage_width / 2.0 / half_angle.tan()
+    }
+
+    /// Set the field of view that gives the given focal length.
+    pub fn set_focal_length(&mut self, focal_length: f64, stage_width: f64) {
+        self.field_of_view = 2.0 * (stage_width / 2.0 / focal_length).atan().to_degrees();
+    }
+
+    /// Project a point onto the plane. Returns `None` if the point is level
+    /// with or behind the viewer.
+    pub fn project(&self, focal_length: f64, point: [f64; 3]) -> Option<(f64, f64)> {
+        let depth = focal_length + point[2];
+        if depth <= 0.0 {
+            return None;
+        }
+        let ratio = focal_length / depth;
+        let (center_x, center_y) = self.projection_center;
+        Some((
+            center_x + (point[0] - center_x) * ratio,
+            center_y + (point[1] - center_y) * ratio,
+        ))
+    }
+
+    /// The matrix that this projection applies, as returned by
+    /// `PerspectiveProjection.toMatrix3D`. It projects around the origin, and
+    /// its last row divides by the depth.
+    pub fn to_matrix3d(&self, stage_width: f64) -> Matrix3D {
+        let focal_length = self.focal_length(stage_width);
+        let mut result = Matrix3D::scale(focal_length, focal_length, 1.0);
+        result.set(3, 2, 1.0);
+        result.set(3, 3, 0.0);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: &[f64], b: &[f64]) {
+        for (a, b) in a.iter().zip(b) {
+            assert!((a - b).abs() < 1e-9, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn rotation_matches_2d() {
+        let matrix = Matrix3D::rotation(90.0, [0.0, 0.0, 1.0]);
+        assert_close(&matrix.transform_point([1.0, 0.0, 0.0]), &[0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn inverse() {
+        let matrix = Matrix3D::translation(10.0, -5.0, 3.0)
+            * Matrix3D::rotation(30.0, [1.0, 2.0, 3.0])
+            * Matrix3D::scale(2.0, 3.0, 4.0);
+        let inverse = matrix.inverse().unwrap();
+        assert_close(&(matrix * inverse).raw, &Matrix3D::IDENTITY.raw);
+        assert!((matrix.determinant() - 24.0).abs() < 1e-9);
+        assert!(Matrix3D::scale(1.0, 0.0, 1.0).inverse().is_none());
+    }
+
+    #[test]
+    fn decompose_and_recompose() {
+        let components = Components {
+            translation: [1.0, 2.0, 3.0],
+            rotation: [0.1, -0.4, 1.2],
+            scale: [2.0, 0.5, 1.5],
+        };
+        let matrix = Matrix3D::recompose(&components);
+        let decomposed = matrix.decompose().unwrap();
+        assert_close(&decomposed.translation, &components.translation);
+        assert_close(&decomposed.rotation, &components.rotation);
+        assert_close(&decomposed.scale, &components.scale);
+    }
+
+    #[test]
+    fn project() {
+        let projection = PerspectiveProjection::for_stage((200, 100));
+        let focal_length = projection.focal_length(200.0);
+        assert_eq!(
+            projection.project(focal_length, [150.0, 50.0, 0.0]),
+            Some((150.0, 50.0))
+        );
+        let (x, y) = projection
+            .project(focal_length, [200.0, 50.0, focal_length])
+            .unwrap();
+        assert_close(&[x, y], &[150.0, 50.0]);
+        assert_eq!(
+            projection.project(focal_length, [0.0, 0.0, -focal_length]),
+            None
+        );
+    }
+}
@@ -0,0 +1,418 @@
+//! Touch input, as seen by movies through `flash.ui.Multitouch`.
+//!
+//! Every finger on the screen is a touch point. The first finger down is the
+//! primary touch point, which also moves the mouse. Depending on the input
+//! mode chosen by the movie, touch points are either reported to it one by
+//! one, or two of them are recognized as a gesture.
+
+use crate::events::TouchPhase;
+use std::collections::BTreeMap;
+use swf::Twips;
+
+/// How far, in pixels, a finger may move before it no longer counts as a tap.
+const TAP_SLOP: f64 = 10.0;
+
+/// How touch input is reported to the movie, as set by `Multitouch.inputMode`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MultitouchInputMode {
+    /// Touches are only seen as mouse input.
+    None,
+
+    /// Each touch point dispatches `TouchEvent`s.
+    TouchPoint,
+
+    /// Two touch points together dispatch `GestureEvent`s.
+    Gesture,
+}
+
+impl MultitouchInputMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "none" => Some(Self::None),
+            "touchPoint" => Some(Self::TouchPoint),
+            "gesture" => Some(Self::Gesture),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::TouchPoint => "touchPoint",
+            Self::Gesture => "gesture",
+        }
+    }
+}
+
+impl Default for MultitouchInputMode {
+    fn default() -> Self {
+        Self::Gesture
+    }
+}
+
+/// The stage that a gesture is at, as given by `GestureEvent.phase`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GesturePhase {
+    Begin,
+    Update,
+    End,
+}
+
+impl GesturePhase {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Begin => "begin",
+            Self::Update => "update",
+            Self::End => "end",
+        }
+    }
+}
+
+/// A gesture made by two fingers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gesture {
+    /// The fingers moved, zooming, rotating and panning what is between them.
+    Transform {
+        phase: GesturePhase,
+
+        /// The point between the fingers, in stage coordinates.
+        center: (Twips, Twips),
+
+        /// How much further apart the fingers are than before.
+        scale: f64,
+
+        /// How far, in degrees, the fingers turned clockwise.
+        rotation: f64,
+
+        /// How far, in pixels, the point between the fingers moved.
+        offset: (f64, f64),
+    },
+
+    /// One of the fingers was lifted without either of them moving.
+    TwoFingerTap { center: (Twips, Twips) },
+}
+
+/// What a change to a touch point means to the movie.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TouchUpdate {
+    /// Whether this is the primary touch point.
+    pub is_primary: bool,
+
+    /// Whether the touch point was lifted without moving.
+    pub is_tap: bool,
+
+    /// The gesture that this touch point is part of.
+    pub gesture: Option<Gesture>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct TouchPoint {
+    start: (f64, f64),
+    position: (f64, f64),
+    moved: bool,
+}
+
+impl TouchPoint {
+    fn new(position: (f64, f64)) -> Self {
+        Self {
+            start: position,
+            position,
+            moved: false,
+        }
+    }
+
+    fn move_to(&mut self, position: (f64, f64)) {
+        self.position = position;
+        let (dx, dy) = (position.0 - self.start.0, position.1 - self.start.1);
+        if dx.hypot(dy) > TAP_SLOP {
+            self.moved = true;
+        }
+    }
+}
+
+/// The fingers on the screen, and what the movie wants to hear about them.
+pub struct Multitouch {
+    input_mode: MultitouchInputMode,
+
+    /// Whether the primary touch point also moves the mouse.
+    map_touch_to_mouse: bool,
+
+    /// The touch points that are down, with positions in pixels on the stage.
+    touch_points: BTreeMap<i32, TouchPoint>,
+
+    primary: Option<i32>,
+
+    /// The two touch points making a gesture.
+    gesture: Option<(i32, i32)>,
+}
+
+impl Multitouch {
+    pub fn new() -> Self {
+        Self {
+            input_mode: MultitouchInputMode::default(),
+            map_touch_to_mouse: true,
+            touch_points: BTreeMap::new(),
+            primary: None,
+            gesture: None,
+        }
+    }
+
+    pub fn input_mode(&self) -> MultitouchInputMode {
+        self.input_mode
+    }
+
+    pub fn set_input_mode(&mut self, input_mode: MultitouchInputMode) {
+        self.input_mode = input_mode;
+        self.gesture = None;
+    }
+
+    pub fn map_touch_to_mouse(&self) -> bool {
+        self.map_touch_to_mouse
+    }
+
+    pub fn set_map_touch_to_mouse(&mut self, map_touch_to_mouse: bool) {
+        self.map_touch_to_mouse = map_touch_to_mouse;
+    }
+
+    /// Record a change to a touch point, with its position in stage
+    /// coordinates.
+    pub fn update(&mut self, id: i32, phase: TouchPhase, position: (Twips, Twips)) -> TouchUpdate {
+        let position = (position.0.to_pixels(), position.1.to_pixels());
+        let before = self.gesture_points();
+
+        let mut update = TouchUpdate::default();
+        match phase {
+            TouchPhase::Began => {
+                if self.touch_points.is_empty() {
+                    self.primary = Some(id);
+                }
+                self.touch_points.insert(id, TouchPoint::new(position));
+                update.is_primary = self.primary == Some(id);
+
+                if self.input_mode == MultitouchInputMode::Gesture && self.gesture.is_none() {
+                    if let [first, second] =
+                        self.touch_points.keys().copied().collect::<Vec<_>>()[..]
+                    {
+                        self.gesture = Some((first, second));
+                        update.gesture = self.gesture_points().map(|points| Gesture::Transform {
+                            phase: GesturePhase::Begin,
+                            center: to_twips(center(points)),
+                            scale: 1.0,
+                            rotation: 0.0,
+                            offset: (0.0, 0.0),
+                        });
+                    }
+                }
+            }
+            TouchPhase::Moved => {
+                if let Some(point) = self.touch_points.get_mut(&id) {
+                    point.move_to(position);
+                }
+                update.is_primary = self.primary == Some(id);
+
+                if let (Some(before), Some(after)) = (before, self.gesture_points()) {
+                    if self.is_gesture_point(id) {
+                        update.gesture = Some(transform(GesturePhase::Update, before, after));
+                    }
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                if let Some(mut point) = self.touch_points.remove(&id) {
+                    point.move_to(position);
+                    update.is_tap = phase == TouchPhase::Ended && !point.moved;
+                }
+                update.is_primary = self.primary == Some(id);
+                if update.is_primary {
+                    self.primary = None;
+                }
+
+                if let (Some(before), true) = (before, self.is_gesture_point(id)) {
+                    let (first, second) = self.gesture.take().unwrap();
+                    let other = if first == id { second } else { first };
+                    let other_moved = self
+                        .touch_points
+                        .get(&other)
+                        .map(|point| point.moved)
+                        .unwrap_or(true);
+                    update.gesture = Some(if update.is_tap && !other_moved {
+                        Gesture::TwoFingerTap {
+                            center: to_twips(center(before)),
+                        }
+                    } else {
+                        transform(GesturePhase::End, before, before)
+                    });
+                }
+            }
+        }
+
+        update
+    }
+
+    fn is_gesture_point(&self, id: i32) -> bool {
+        matches!(self.gesture, Some((first, second)) if first == id || second == id)
+    }
+
+    fn gesture_points(&self) -> Option<[(f64, f64); 2]> {
+        let (first, second) = self.gesture?;
+        Some([
+            self.touch_points.get(&first)?.position,
+            self.touch_points.get(&second)?.position,
+        ])
+    }
+}
+
+impl Default for Multitouch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn center([a, b]: [(f64, f64); 2]) -> (f64, f64) {
+    ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0)
+}
+
+fn to_twips((x, y): (f64, f64)) -> (Twips, Twips) {
+    (Twips::from_pixels(x), Twips::from_pixels(y))
+}
+
+/// The transform gesture made by two fingers moving from `before` to `after`.
+fn transform(phase: GesturePhase, before: [(f64, f64); 2], after: [(f64, f64); 2]) -> Gesture {
+    let span = |[a, b]: [(f64, f64); 2]| (b.0 - a.0, b.1 - a.1);
+    let (before_x, before_y) = span(before);
+    let (after_x, after_y) = span(after);
+
+    let before_length = before_x.hypot(before_y);
+    let scale = if before_length > 0.0 {
+        after_x.hypot(after_y) / before_length
+    } else {
+        1.0
+    };
+    let mut rotation = (after_y.atan2(after_x) - before_y.atan2(before_x)).to_degrees();
+    if rotation > 180.0 {
+        rotation -= 360.0;
+    } else if rotation <= -180.0 {
+        rotation += 360.0;
+    }
+
+    let (before_center, after_center) = (center(before), center(after));
+    Gesture::Transform {
+        phase,
+        center: to_twips(after_center),
+        scale,
+        rotation,
+        offset: (
+            after_center.0 - before_center.0,
+            after_center.1 - before_center.1,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(x: f64, y: f64) -> (Twips, Twips) {
+        (Twips::from_pixels(x), Twips::from_pixels(y))
+    }
+
+    #[test]
+    fn primary_touch_point() {
+        let mut multitouch = Multitouch::new();
+        assert!(
+            multitouch
+                .update(1, TouchPhase::Began, at(0.0, 0.0))
+                .is_primary
+        );
+        assert!(
+            !multitouch
+                .update(2, TouchPhase::Began, at(50.0, 0.0))
+                .is_primary
+        );
+        assert!(
+            multitouch
+                .update(1, TouchPhase::Ended, at(0.0, 0.0))
+                .is_primary
+        );
+        assert!(
+            !multitouch
+                .update(2, TouchPhase::Moved, at(60.0, 0.0))
+                .is_primary
+        );
+    }
+
+    #[test]
+    fn tap() {
+        let mut multitouch = Multitouch::new();
+        multitouch.update(1, TouchPhase::Began, at(0.0, 0.0));
+        assert!(multitouch.update(1, TouchPhase::Ended, at(2.0, 2.0)).is_tap);
+
+        multitouch.update(1, TouchPhase::Began, at(0.0, 0.0));
+        multitouch.update(1, TouchPhase::Moved, at(40.0, 0.0));
+        assert!(!multitouch.update(1, TouchPhase::Ended, at(0.0, 0.0)).is_tap);
+    }
+
+    #[test]
+    fn transform_gesture() {
+        let mut multitouch = Multitouch::new();
+        multitouch.update(1, TouchPhase::Began, at(0.0, 0.0));
+        let update = multitouch.update(2, TouchPhase::Began, at(100.0, 0.0));
+        assert!(matches!(
+            update.gesture,
+            Some(Gesture::Transform {
+                phase: GesturePhase::Begin,
+                ..
+            })
+        ));
+
+        let update = multitouch.update(2, TouchPhase::Moved, at(0.0, 200.0));
+        match update.gesture {
+            Some(Gesture::Transform {
+                phase,
+                scale,
+                rotation,
+                offset,
+                ..
+            }) => {
+                assert_eq!(phase, GesturePhase::Update);
+                assert!((scale - 2.0).abs() < 1e-9);
+                assert!((rotation - 90.0).abs() < 1e-9);
+                assert_eq!(offset, (-50.0, 100.0));
+            }
+            gesture => panic!("Expected a transform gesture, got {:?}", gesture),
+        }
+    }
+
+    #[test]
+    fn two_finger_tap() {
+        let mut multitouch = Multitouch::new();
+        multitouch.update(1, TouchPhase::Began, at(0.0, 0.0));
+        multitouch.update(2, TouchPhase::Began, at(50.0, 0.0));
+        assert_eq!(
+            multitouch
+                .update(2, TouchPhase::Ended, at(50.0, 0.0))
+                .gesture,
+            Some(Gesture::TwoFingerTap {
+                center: at(25.0, 0.0)
+            })
+        );
+        assert_eq!(
+            multitouch
+                .update(1, TouchPhase::Ended, at(0.0, 0.0))
+                .gesture,
+            None
+        );
+    }
+
+    #[test]
+    fn touch_point_mode_has_no_gestures() {
+        let mut multitouch = Multitouch::new();
+        multitouch.set_input_mode(MultitouchInputMode::TouchPoint);
+        multitouch.update(1, TouchPhase::Began, at(0.0, 0.0));
+        assert_eq!(
+            multitouch
+                .update(2, TouchPhase::Began, at(50.0, 0.0))
+                .gesture,
+            None
+        );
+    }
+}
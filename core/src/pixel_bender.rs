@@ -0,0 +1,865 @@
+//! Pixel Bender shaders, as used by `Shader`, `ShaderFilter`, `ShaderJob` and
+//! shader fills.
+//!
+//! Shaders are given as the PBJ bytecode that the Pixel Bender Toolkit
+//! compiles kernels to. The bytecode is a list of metadata records describing
+//! the kernel's parameters and inputs, followed by its instructions, which we
+//! interpret for every pixel of the output.
+
+mod interpreter;
+
+pub use interpreter::{run_shader, ShaderImage};
+
+use std::fmt;
+
+/// An error from parsing or running a shader.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PixelBenderError {
+    /// The bytecode ended in the middle of a record.
+    UnexpectedEnd,
+
+    /// The bytecode contained an opcode we don't know.
+    UnknownOpcode(u8),
+
+    /// A parameter or metadata value had a type we don't know.
+    UnknownType(u8),
+
+    /// The `if`, `else` and `endif` instructions weren't properly nested.
+    UnbalancedIf,
+
+    /// The shader doesn't have an output parameter.
+    NoOutput,
+}
+
+impl fmt::Display for PixelBenderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PixelBenderError::UnexpectedEnd => write!(f, "Unexpected end of shader bytecode"),
+            PixelBenderError::UnknownOpcode(opcode) => {
+                write!(f, "Unknown shader opcode {:#04x}", opcode)
+            }
+            PixelBenderError::UnknownType(value_type) => {
+                write!(f, "Unknown shader value type {}", value_type)
+            }
+            PixelBenderError::UnbalancedIf => write!(f, "Unbalanced if/else/endif in shader"),
+            PixelBenderError::NoOutput => write!(f, "Shader has no output parameter"),
+        }
+    }
+}
+
+impl std::error::Error for PixelBenderError {}
+
+/// The type of a shader parameter or metadata value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelBenderType {
+    Float,
+    Float2,
+    Float3,
+    Float4,
+    Float2x2,
+    Float3x3,
+    Float4x4,
+    Int,
+    Int2,
+    Int3,
+    Int4,
+    String,
+    Bool,
+    Bool2,
+    Bool3,
+    Bool4,
+}
+
+impl PixelBenderType {
+    fn from_u8(value: u8) -> Result<Self, PixelBenderError> {
+        Ok(match value {
+            1 => PixelBenderType::Float,
+            2 => PixelBenderType::Float2,
+            3 => PixelBenderType::Float3,
+            4 => PixelBenderType::Float4,
+            5 => PixelBenderType::Float2x2,
+            6 => PixelBenderType::Float3x3,
+            7 => PixelBenderType::Float4x4,
+            8 => PixelBenderType::Int,
+            9 => PixelBenderType::Int2,
+            10 => PixelBenderType::Int3,
+            11 => PixelBenderType::Int4,
+            12 => PixelBenderType::String,
+            13 => PixelBenderType::Bool,
+            14 => PixelBenderType::Bool2,
+            15 => PixelBenderType::Bool3,
+            16 => PixelBenderType::Bool4,
+            _ => return Err(PixelBenderError::UnknownType(value)),
+        })
+    }
+
+    /// The name of this type, as given by `ShaderParameter.type`.
+    pub fn name(self) -> &'static str {
+        match self {
+            PixelBenderType::Float => "float",
+            PixelBenderType::Float2 => "float2",
+            PixelBenderType::Float3 => "float3",
+            PixelBenderType::Float4 => "float4",
+            PixelBenderType::Float2x2 => "matrix2x2",
+            PixelBenderType::Float3x3 => "matrix3x3",
+            PixelBenderType::Float4x4 => "matrix4x4",
+            PixelBenderType::Int => "int",
+            PixelBenderType::Int2 => "int2",
+            PixelBenderType::Int3 => "int3",
+            PixelBenderType::Int4 => "int4",
+            PixelBenderType::String => "string",
+            PixelBenderType::Bool => "bool",
+            PixelBenderType::Bool2 => "bool2",
+            PixelBenderType::Bool3 => "bool3",
+            PixelBenderType::Bool4 => "bool4",
+        }
+    }
+
+    /// How many numbers a value of this type holds.
+    pub fn components(self) -> usize {
+        match self {
+            PixelBenderType::Float | PixelBenderType::Int | PixelBenderType::Bool => 1,
+            PixelBenderType::Float2 | PixelBenderType::Int2 | PixelBenderType::Bool2 => 2,
+            PixelBenderType::Float3 | PixelBenderType::Int3 | PixelBenderType::Bool3 => 3,
+            PixelBenderType::Float4 | PixelBenderType::Int4 | PixelBenderType::Bool4 => 4,
+            PixelBenderType::Float2x2 => 4,
+            PixelBenderType::Float3x3 => 9,
+            PixelBenderType::Float4x4 => 16,
+            PixelBenderType::String => 0,
+        }
+    }
+
+    /// Whether values of this type are held in float registers.
+    pub fn is_float(self) -> bool {
+        matches!(
+            self,
+            PixelBenderType::Float
+                | PixelBenderType::Float2
+                | PixelBenderType::Float3
+                | PixelBenderType::Float4
+                | PixelBenderType::Float2x2
+                | PixelBenderType::Float3x3
+                | PixelBenderType::Float4x4
+        )
+    }
+
+    /// The size of a square matrix type, or `None` for other types.
+    fn matrix_size(self) -> Option<usize> {
+        match self {
+            PixelBenderType::Float2x2 => Some(2),
+            PixelBenderType::Float3x3 => Some(3),
+            PixelBenderType::Float4x4 => Some(4),
+            _ => None,
+        }
+    }
+}
+
+/// A parameter value or a piece of metadata.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PixelBenderValue {
+    /// The components of a float, vector or matrix value.
+    Float(Vec<f32>),
+    /// The components of an int or bool value.
+    Int(Vec<i32>),
+    String(String),
+}
+
+/// Metadata about a shader or one of its parameters, such as its
+/// `description` or `defaultValue`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PixelBenderMetadata {
+    pub key: String,
+    pub value: PixelBenderValue,
+}
+
+/// One of the registers of a shader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelBenderRegister {
+    pub index: u16,
+    pub is_int: bool,
+}
+
+impl PixelBenderRegister {
+    fn from_u16(value: u16) -> Self {
+        Self {
+            index: value & 0x7FFF,
+            is_int: value & 0x8000 != 0,
+        }
+    }
+}
+
+/// Whether a parameter is given to a shader or produced by it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelBenderQualifier {
+    In,
+    Out,
+}
+
+/// A parameter of a shader.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PixelBenderParam {
+    pub qualifier: PixelBenderQualifier,
+    pub param_type: PixelBenderType,
+    pub name: String,
+    pub register: PixelBenderRegister,
+    /// The components of the register that hold the value, as a write mask.
+    pub mask: u8,
+    pub metadata: Vec<PixelBenderMetadata>,
+}
+
+impl PixelBenderParam {
+    /// Whether this is the parameter that a shader reads the coordinates of
+    /// the pixel being drawn from. It's set by the player, so isn't exposed
+    /// to scripts.
+    pub fn is_out_coord(&self) -> bool {
+        self.name == "_OutCoord"
+    }
+
+    /// The value of this parameter before it's set, from its `defaultValue`
+    /// metadata or zero.
+    pub fn default_value(&self) -> PixelBenderValue {
+        let default = self
+            .metadata
+            .iter()
+            .find(|metadata| metadata.key == "defaultValue");
+        match default {
+            Some(metadata) => metadata.value.clone(),
+            None if self.param_type.is_float() => {
+                PixelBenderValue::Float(vec![0.0; self.param_type.components()])
+            }
+            None => PixelBenderValue::Int(vec![0; self.param_type.components()]),
+        }
+    }
+}
+
+/// An image a shader samples from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PixelBenderInput {
+    pub index: u8,
+    pub channels: u8,
+    pub name: String,
+}
+
+/// How an instruction combines its operands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Opcode {
+    Nop,
+    Add,
+    Subtract,
+    Multiply,
+    Reciprocal,
+    Divide,
+    Atan2,
+    Pow,
+    Mod,
+    Min,
+    Max,
+    Step,
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Exp,
+    Exp2,
+    Log,
+    Log2,
+    Sqrt,
+    RSqrt,
+    Abs,
+    Sign,
+    Floor,
+    Ceil,
+    Fract,
+    Mov,
+    FloatToInt,
+    IntToFloat,
+    MatMatMul,
+    VecMatMul,
+    MatVecMul,
+    Normalize,
+    Length,
+    Distance,
+    DotProduct,
+    CrossProduct,
+    Equal,
+    NotEqual,
+    LessThan,
+    LessThanEqual,
+    LogicalNot,
+    LogicalAnd,
+    LogicalOr,
+    LogicalXor,
+    FloatToBool,
+    BoolToFloat,
+    IntToBool,
+    BoolToInt,
+    VectorEqual,
+    VectorNotEqual,
+    BoolAny,
+    BoolAll,
+}
+
+impl Opcode {
+    fn from_u8(value: u8) -> Option<Self> {
+        Some(match value {
+            0x00 => Opcode::Nop,
+            0x01 => Opcode::Add,
+            0x02 => Opcode::Subtract,
+            0x03 => Opcode::Multiply,
+            0x04 => Opcode::Reciprocal,
+            0x05 => Opcode::Divide,
+            0x06 => Opcode::Atan2,
+            0x07 => Opcode::Pow,
+            0x08 => Opcode::Mod,
+            0x09 => Opcode::Min,
+            0x0A => Opcode::Max,
+            0x0B => Opcode::Step,
+            0x0C => Opcode::Sin,
+            0x0D => Opcode::Cos,
+            0x0E => Opcode::Tan,
+            0x0F => Opcode::Asin,
+            0x10 => Opcode::Acos,
+            0x11 => Opcode::Atan,
+            0x12 => Opcode::Exp,
+            0x13 => Opcode::Exp2,
+            0x14 => Opcode::Log,
+            0x15 => Opcode::Log2,
+            0x16 => Opcode::Sqrt,
+            0x17 => Opcode::RSqrt,
+            0x18 => Opcode::Abs,
+            0x19 => Opcode::Sign,
+            0x1A => Opcode::Floor,
+            0x1B => Opcode::Ceil,
+            0x1C => Opcode::Fract,
+            0x1D => Opcode::Mov,
+            0x1E => Opcode::FloatToInt,
+            0x1F => Opcode::IntToFloat,
+            0x20 => Opcode::MatMatMul,
+            0x21 => Opcode::VecMatMul,
+            0x22 => Opcode::MatVecMul,
+            0x23 => Opcode::Normalize,
+            0x24 => Opcode::Length,
+            0x25 => Opcode::Distance,
+            0x26 => Opcode::DotProduct,
+            0x27 => Opcode::CrossProduct,
+            0x28 => Opcode::Equal,
+            0x29 => Opcode::NotEqual,
+            0x2A => Opcode::LessThan,
+            0x2B => Opcode::LessThanEqual,
+            0x2C => Opcode::LogicalNot,
+            0x2D => Opcode::LogicalAnd,
+            0x2E => Opcode::LogicalOr,
+            0x2F => Opcode::LogicalXor,
+            0x37 => Opcode::FloatToBool,
+            0x38 => Opcode::BoolToFloat,
+            0x39 => Opcode::IntToBool,
+            0x3A => Opcode::BoolToInt,
+            0x3B => Opcode::VectorEqual,
+            0x3C => Opcode::VectorNotEqual,
+            0x3D => Opcode::BoolAny,
+            0x3E => Opcode::BoolAll,
+            _ => return None,
+        })
+    }
+}
+
+const OP_SAMPLE_NEAREST: u8 = 0x30;
+const OP_SAMPLE_LINEAR: u8 = 0x31;
+const OP_LOAD_CONSTANT: u8 = 0x32;
+const OP_SELECT: u8 = 0x33;
+const OP_IF: u8 = 0x34;
+const OP_ELSE: u8 = 0x35;
+const OP_END_IF: u8 = 0x36;
+const OP_KERNEL_METADATA: u8 = 0xA0;
+const OP_PARAMETER: u8 = 0xA1;
+const OP_PARAMETER_METADATA: u8 = 0xA2;
+const OP_TEXTURE: u8 = 0xA3;
+const OP_KERNEL_NAME: u8 = 0xA4;
+const OP_VERSION: u8 = 0xA5;
+
+/// A register read by an instruction, with the components it reads in
+/// order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Source {
+    register: PixelBenderRegister,
+    swizzle: u8,
+}
+
+impl Source {
+    /// The `index`th component read.
+    fn component(self, index: usize) -> usize {
+        usize::from((self.swizzle >> (6 - index * 2)) & 3)
+    }
+}
+
+/// A register written by an instruction, with the components it writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Destination {
+    register: PixelBenderRegister,
+    mask: u8,
+}
+
+/// The components set in a write mask, in order.
+fn mask_components(mask: u8) -> impl Iterator<Item = usize> {
+    (0..4).filter(move |i| mask & (0x8 >> i) != 0)
+}
+
+/// One of the instructions of a shader.
+#[derive(Debug, Clone, PartialEq)]
+enum Operation {
+    /// `dst = dst op src`, or `dst = op(src)` for single operand opcodes,
+    /// on `size` components or square matrices of `matrix + 1` size.
+    Normal {
+        opcode: Opcode,
+        dst: Destination,
+        src: Source,
+        size: usize,
+        matrix: usize,
+    },
+    /// `dst = sample(texture, src)`.
+    Sample {
+        linear: bool,
+        dst: Destination,
+        src: Source,
+        texture: u8,
+    },
+    /// `dst = value`.
+    LoadFloat {
+        dst: Destination,
+        value: f32,
+    },
+    /// `dst = value`.
+    LoadInt {
+        dst: Destination,
+        value: i32,
+    },
+    /// `dst = condition ? src1 : src2`.
+    Select {
+        dst: Destination,
+        condition: Source,
+        src1: Source,
+        src2: Source,
+        size: usize,
+    },
+    /// Run the following instructions if `condition` is true, otherwise
+    /// skip to `skip_to`: just past the matching `Else`, or the `EndIf`.
+    If {
+        condition: Source,
+        skip_to: usize,
+    },
+    /// Skip to the matching `EndIf`, at the end of the true branch of an
+    /// `If`.
+    Else {
+        skip_to: usize,
+    },
+    EndIf,
+}
+
+/// A parsed shader.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PixelBenderShader {
+    pub name: String,
+    pub version: i32,
+    pub metadata: Vec<PixelBenderMetadata>,
+    pub params: Vec<PixelBenderParam>,
+    pub inputs: Vec<PixelBenderInput>,
+    operations: Vec<Operation>,
+    float_registers: usize,
+    int_registers: usize,
+}
+
+impl PixelBenderShader {
+    /// Parse a shader from its PBJ bytecode.
+    pub fn parse(data: &[u8]) -> Result<Self, PixelBenderError> {
+        let mut reader = Reader { data };
+        let mut shader = PixelBenderShader {
+            name: String::new(),
+            version: 0,
+            metadata: Vec::new(),
+            params: Vec::new(),
+            inputs: Vec::new(),
+            operations: Vec::new(),
+            float_registers: 0,
+            int_registers: 0,
+        };
+        let mut open_ifs = Vec::new();
+
+        while !reader.data.is_empty() {
+            let opcode = reader.u8()?;
+            match opcode {
+                OP_KERNEL_METADATA => {
+                    let metadata = reader.metadata()?;
+                    shader.metadata.push(metadata);
+                }
+                OP_PARAMETER => {
+                    let qualifier = if reader.u8()? == 2 {
+                        PixelBenderQualifier::Out
+                    } else {
+                        PixelBenderQualifier::In
+                    };
+                    let param_type = PixelBenderType::from_u8(reader.u8()?)?;
+                    let register = PixelBenderRegister::from_u16(reader.u16()?);
+                    let mask = reader.u8()?;
+                    let name = reader.string()?;
+                    let registers = param_type.matrix_size().unwrap_or(1);
+                    shader.use_register(register, registers);
+                    shader.params.push(PixelBenderParam {
+                        qualifier,
+                        param_type,
+                        name,
+                        register,
+                        mask,
+                        metadata: Vec::new(),
+                    });
+                }
+                OP_PARAMETER_METADATA => {
+                    let metadata = reader.metadata()?;
+                    if let Some(param) = shader.params.last_mut() {
+                        param.metadata.push(metadata);
+                    }
+                }
+                OP_TEXTURE => {
+                    let index = reader.u8()?;
+                    let channels = reader.u8()?;
+                    let name = reader.string()?;
+                    shader.inputs.push(PixelBenderInput {
+                        index,
+                        channels,
+                        name,
+                    });
+                }
+                OP_KERNEL_NAME => {
+                    let length = reader.u16()?;
+                    let name = reader.bytes(usize::from(length))?;
+                    shader.name = String::from_utf8_lossy(name).into_owned();
+                }
+                OP_VERSION => {
+                    shader.version = reader.i32()?;
+                }
+                OP_LOAD_CONSTANT => {
+                    let dst = reader.destination()?;
+                    shader.use_register(dst.register, 1);
+                    let operation = if dst.register.is_int {
+                        Operation::LoadInt {
+                            dst,
+                            value: reader.i32()?,
+                        }
+                    } else {
+                        Operation::LoadFloat {
+                            dst,
+                            value: f32::from_bits(u32::from_be_bytes(reader.array()?)),
+                        }
+                    };
+                    shader.operations.push(operation);
+                }
+                OP_SAMPLE_NEAREST | OP_SAMPLE_LINEAR => {
+                    let dst = reader.destination()?;
+                    let src = reader.source()?;
+                    let texture = reader.u8()?;
+                    shader.use_register(dst.register, 1);
+                    shader.use_register(src.register, 1);
+                    shader.operations.push(Operation::Sample {
+                        linear: opcode == OP_SAMPLE_LINEAR,
+                        dst,
+                        src,
+                        texture,
+                    });
+                }
+                OP_SELECT => {
+                    let dst = reader.destination()?;
+                    let condition = reader.source()?;
+                    reader.u8()?;
+                    let src1 = reader.source()?;
+                    let src2 = reader.source()?;
+                    reader.u8()?;
+                    reader.u8()?;
+                    for register in &[
+                        dst.register,
+                        condition.register,
+                        src1.register,
+                        src2.register,
+                    ] {
+                        shader.use_register(*register, 1);
+                    }
+                    shader.operations.push(Operation::Select {
+                        size: mask_components(dst.mask).count(),
+                        dst,
+                        condition,
+                        src1,
+                        src2,
+                    });
+                }
+                OP_IF => {
+                    reader.destination()?;
+                    let condition = reader.source()?;
+                    reader.u8()?;
+                    shader.use_register(condition.register, 1);
+                    open_ifs.push(shader.operations.len());
+                    shader.operations.push(Operation::If {
+                        condition,
+                        skip_to: 0,
+                    });
+                }
+                OP_ELSE => {
+                    reader.bytes(7)?;
+                    let else_index = shader.operations.len();
+                    let if_index = open_ifs.pop().ok_or(PixelBenderError::UnbalancedIf)?;
+                    match &mut shader.operations[if_index] {
+                        Operation::If { skip_to, .. } => *skip_to = else_index + 1,
+                        _ => return Err(PixelBenderError::UnbalancedIf),
+                    }
+                    open_ifs.push(else_index);
+                    shader.operations.push(Operation::Else { skip_to: 0 });
+                }
+                OP_END_IF => {
+                    reader.bytes(7)?;
+                    let end_index = shader.operations.len();
+                    let open_index = open_ifs.pop().ok_or(PixelBenderError::UnbalancedIf)?;
+                    match &mut shader.operations[open_index] {
+                        Operation::If { skip_to, .. } | Operation::Else { skip_to } => {
+                            *skip_to = end_index
+                        }
+                        _ => return Err(PixelBenderError::UnbalancedIf),
+                    }
+                    shader.operations.push(Operation::EndIf);
+                }
+                _ => {
+                    let opcode =
+                        Opcode::from_u8(opcode).ok_or(PixelBenderError::UnknownOpcode(opcode))?;
+                    let register = PixelBenderRegister::from_u16(reader.u16()?);
+                    let mask = reader.u8()?;
+                    let src = reader.source()?;
+                    reader.u8()?;
+                    let size = usize::from(mask & 3) + 1;
+                    let matrix = usize::from((mask >> 2) & 3);
+                    let registers = if matrix > 0 { matrix + 1 } else { 1 };
+                    shader.use_register(register, registers);
+                    shader.use_register(src.register, registers);
+                    shader.operations.push(Operation::Normal {
+                        opcode,
+                        dst: Destination {
+                            register,
+                            mask: mask >> 4,
+                        },
+                        src,
+                        size,
+                        matrix,
+                    });
+                }
+            }
+        }
+
+        if !open_ifs.is_empty() {
+            return Err(PixelBenderError::UnbalancedIf);
+        }
+        if !shader
+            .params
+            .iter()
+            .any(|param| param.qualifier == PixelBenderQualifier::Out)
+        {
+            return Err(PixelBenderError::NoOutput);
+        }
+
+        Ok(shader)
+    }
+
+    /// Make sure there are enough registers for `count` registers starting
+    /// at `register`.
+    fn use_register(&mut self, register: PixelBenderRegister, count: usize) {
+        let end = usize::from(register.index) + count;
+        if register.is_int {
+            self.int_registers = self.int_registers.max(end);
+        } else {
+            self.float_registers = self.float_registers.max(end);
+        }
+    }
+
+    /// The parameter the shader writes its result to.
+    pub fn output(&self) -> Option<&PixelBenderParam> {
+        self.params
+            .iter()
+            .find(|param| param.qualifier == PixelBenderQualifier::Out)
+    }
+
+    /// Get a piece of the shader's metadata.
+    pub fn metadata(&self, key: &str) -> Option<&PixelBenderValue> {
+        self.metadata
+            .iter()
+            .find(|metadata| metadata.key == key)
+            .map(|metadata| &metadata.value)
+    }
+}
+
+/// Reads values out of shader bytecode.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, length: usize) -> Result<&'a [u8], PixelBenderError> {
+        if self.data.len() < length {
+            return Err(PixelBenderError::UnexpectedEnd);
+        }
+        let (bytes, rest) = self.data.split_at(length);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], PixelBenderError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.bytes(N)?);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> Result<u8, PixelBenderError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, PixelBenderError> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+
+    fn i32(&mut self) -> Result<i32, PixelBenderError> {
+        Ok(i32::from_le_bytes(self.array()?))
+    }
+
+    /// A null-terminated string.
+    fn string(&mut self) -> Result<String, PixelBenderError> {
+        let length = self
+            .data
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(PixelBenderError::UnexpectedEnd)?;
+        let string = String::from_utf8_lossy(&self.data[..length]).into_owned();
+        self.data = &self.data[length + 1..];
+        Ok(string)
+    }
+
+    fn destination(&mut self) -> Result<Destination, PixelBenderError> {
+        let register = PixelBenderRegister::from_u16(self.u16()?);
+        let mask = self.u8()? >> 4;
+        Ok(Destination { register, mask })
+    }
+
+    fn source(&mut self) -> Result<Source, PixelBenderError> {
+        let register = PixelBenderRegister::from_u16(self.u16()?);
+        let swizzle = self.u8()?;
+        Ok(Source { register, swizzle })
+    }
+
+    fn metadata(&mut self) -> Result<PixelBenderMetadata, PixelBenderError> {
+        let value_type = PixelBenderType::from_u8(self.u8()?)?;
+        let key = self.string()?;
+        let value = match value_type {
+            PixelBenderType::String => PixelBenderValue::String(self.string()?),
+            value_type if value_type.is_float() => {
+                let mut values = Vec::with_capacity(value_type.components());
+                for _ in 0..value_type.components() {
+                    values.push(f32::from_bits(u32::from_be_bytes(self.array()?)));
+                }
+                PixelBenderValue::Float(values)
+            }
+            value_type => {
+                let mut values = Vec::with_capacity(value_type.components());
+                for _ in 0..value_type.components() {
+                    values.push(i32::from(i16::from_be_bytes(self.array()?)));
+                }
+                PixelBenderValue::Int(values)
+            }
+        };
+        Ok(PixelBenderMetadata { key, value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Assemble a shader that writes `color` multiplied by a `float4`
+    /// parameter `tint` to every pixel, swapping its red and blue if the int
+    /// parameter `swap` is set.
+    fn tint_shader() -> Vec<u8> {
+        let mut data = vec![OP_VERSION, 1, 0, 0, 0];
+        data.extend_from_slice(&[OP_KERNEL_NAME, 4, 0]);
+        data.extend_from_slice(b"Tint");
+        // `_OutCoord` in f0.xy, `tint` in f1, `swap` in i0.x, `dst` in f2.
+        data.extend_from_slice(&[OP_PARAMETER, 1, 2, 0, 0, 0xC]);
+        data.extend_from_slice(b"_OutCoord\0");
+        data.extend_from_slice(&[OP_PARAMETER, 1, 4, 1, 0, 0xF]);
+        data.extend_from_slice(b"tint\0");
+        data.extend_from_slice(&[OP_PARAMETER_METADATA, 4]);
+        data.extend_from_slice(b"defaultValue\0");
+        for value in &[1.0f32, 1.0, 1.0, 1.0] {
+            data.extend_from_slice(&value.to_bits().to_be_bytes());
+        }
+        data.extend_from_slice(&[OP_PARAMETER, 1, 8, 0, 0x80, 0x8]);
+        data.extend_from_slice(b"swap\0");
+        data.extend_from_slice(&[OP_PARAMETER, 2, 4, 2, 0, 0xF]);
+        data.extend_from_slice(b"dst\0");
+        data.extend_from_slice(&[OP_TEXTURE, 0, 4]);
+        data.extend_from_slice(b"src\0");
+        // f2 = sample_nearest(src, f0.xy)
+        data.extend_from_slice(&[OP_SAMPLE_NEAREST, 2, 0, 0xF0, 0, 0, 0x10, 0]);
+        // f2 *= f1
+        data.extend_from_slice(&[0x03, 2, 0, 0xF3, 1, 0, 0x1B, 0]);
+        // if (i0.x) { f3.x = f2.x; f2.x = f2.z; f2.z = f3.x }
+        data.extend_from_slice(&[OP_IF, 0, 0, 0, 0, 0x80, 0, 0]);
+        data.extend_from_slice(&[0x1D, 3, 0, 0x80, 2, 0, 0x00, 0]);
+        data.extend_from_slice(&[0x1D, 2, 0, 0x80, 2, 0, 0x80, 0]);
+        data.extend_from_slice(&[0x1D, 2, 0, 0x20, 3, 0, 0x00, 0]);
+        data.extend_from_slice(&[OP_END_IF, 0, 0, 0, 0, 0, 0, 0]);
+        data
+    }
+
+    #[test]
+    fn parse_params() {
+        let shader = PixelBenderShader::parse(&tint_shader()).unwrap();
+        assert_eq!(shader.name, "Tint");
+        assert_eq!(shader.version, 1);
+        assert_eq!(shader.params.len(), 4);
+        assert!(shader.params[0].is_out_coord());
+        assert_eq!(shader.params[1].param_type, PixelBenderType::Float4);
+        assert_eq!(
+            shader.params[1].default_value(),
+            PixelBenderValue::Float(vec![1.0, 1.0, 1.0, 1.0])
+        );
+        assert!(shader.params[2].register.is_int);
+        assert_eq!(shader.output().unwrap().name, "dst");
+        assert_eq!(shader.inputs[0].name, "src");
+    }
+
+    #[test]
+    fn parse_errors() {
+        let mut data = tint_shader();
+        data.push(0xFF);
+        assert_eq!(
+            PixelBenderShader::parse(&data),
+            Err(PixelBenderError::UnknownOpcode(0xFF))
+        );
+
+        let data = tint_shader();
+        assert_eq!(
+            PixelBenderShader::parse(&data[..data.len() - 3]),
+            Err(PixelBenderError::UnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn run_tint() {
+        let shader = PixelBenderShader::parse(&tint_shader()).unwrap();
+        let mut source = ShaderImage::new(2, 1, 4);
+        source.data = vec![1.0, 0.5, 0.0, 1.0, 0.0, 0.0, 1.0, 0.5];
+        let mut params: Vec<_> = shader.params.iter().map(|p| p.default_value()).collect();
+        params[1] = PixelBenderValue::Float(vec![0.5, 1.0, 1.0, 1.0]);
+
+        let output = run_shader(&shader, &params, &[Some(&source)], 2, 1).unwrap();
+        assert_eq!(output.channels, 4);
+        assert_eq!(output.data, vec![0.5, 0.5, 0.0, 1.0, 0.0, 0.0, 1.0, 0.5]);
+
+        params[2] = PixelBenderValue::Int(vec![1]);
+        let output = run_shader(&shader, &params, &[Some(&source)], 2, 1).unwrap();
+        assert_eq!(output.data, vec![0.0, 0.5, 0.5, 1.0, 1.0, 0.0, 0.0, 0.5]);
+    }
+}
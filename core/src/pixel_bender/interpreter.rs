@@ -0,0 +1,464 @@
+//! Running Pixel Bender shaders.
+
+use super::{
+    mask_components, Destination, Opcode, Operation, PixelBenderError, PixelBenderQualifier,
+    PixelBenderRegister, PixelBenderShader, PixelBenderValue, Source,
+};
+
+/// An image a shader reads from or writes to.
+///
+/// Pixels are stored row by row with `channels` non-premultiplied
+/// components each, usually between 0 and 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShaderImage {
+    pub width: u32,
+    pub height: u32,
+    pub channels: usize,
+    pub data: Vec<f32>,
+}
+
+impl ShaderImage {
+    /// Create an image with every component set to 0.
+    pub fn new(width: u32, height: u32, channels: usize) -> Self {
+        Self {
+            width,
+            height,
+            channels,
+            data: vec![0.0; width as usize * height as usize * channels],
+        }
+    }
+
+    /// The pixel at the given coordinates, or transparent black if they're
+    /// outside the image.
+    fn pixel(&self, x: i64, y: i64) -> [f32; 4] {
+        let mut pixel = [0.0; 4];
+        if x >= 0 && y >= 0 && x < i64::from(self.width) && y < i64::from(self.height) {
+            let offset = (y as usize * self.width as usize + x as usize) * self.channels;
+            let channels = self.channels.min(4);
+            pixel[..channels].copy_from_slice(&self.data[offset..offset + channels]);
+        }
+        pixel
+    }
+
+    /// Sample the pixel containing the given coordinates.
+    fn sample_nearest(&self, x: f32, y: f32) -> [f32; 4] {
+        self.pixel(x.floor() as i64, y.floor() as i64)
+    }
+
+    /// Sample the given coordinates, interpolating between the four nearest
+    /// pixel centers.
+    fn sample_linear(&self, x: f32, y: f32) -> [f32; 4] {
+        let x = x - 0.5;
+        let y = y - 0.5;
+        let left = x.floor();
+        let top = y.floor();
+        let fx = x - left;
+        let fy = y - top;
+        let (left, top) = (left as i64, top as i64);
+
+        let top_left = self.pixel(left, top);
+        let top_right = self.pixel(left + 1, top);
+        let bottom_left = self.pixel(left, top + 1);
+        let bottom_right = self.pixel(left + 1, top + 1);
+        let mut pixel = [0.0; 4];
+        for (i, component) in pixel.iter_mut().enumerate() {
+            let upper = top_left[i] + (top_right[i] - top_left[i]) * fx;
+            let lower = bottom_left[i] + (bottom_right[i] - bottom_left[i]) * fx;
+            *component = upper + (lower - upper) * fy;
+        }
+        pixel
+    }
+}
+
+/// The registers of a running shader.
+///
+/// Int registers hold their values as floats too, which are truncated
+/// whenever they're written.
+struct Registers {
+    floats: Vec<[f32; 4]>,
+    ints: Vec<[f32; 4]>,
+}
+
+impl Registers {
+    fn get(&self, register: PixelBenderRegister) -> [f32; 4] {
+        let bank = if register.is_int {
+            &self.ints
+        } else {
+            &self.floats
+        };
+        bank.get(usize::from(register.index))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn set_component(&mut self, register: PixelBenderRegister, component: usize, value: f32) {
+        let (bank, value) = if register.is_int {
+            (&mut self.ints, value.trunc())
+        } else {
+            (&mut self.floats, value)
+        };
+        if let Some(register) = bank.get_mut(usize::from(register.index)) {
+            register[component] = value;
+        }
+    }
+
+    /// The components read by `src`, in order.
+    fn read(&self, src: Source) -> [f32; 4] {
+        let register = self.get(src.register);
+        let mut values = [0.0; 4];
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = register[src.component(i)];
+        }
+        values
+    }
+
+    /// The components written by `dst`, in order.
+    fn read_destination(&self, dst: Destination) -> [f32; 4] {
+        let register = self.get(dst.register);
+        let mut values = [0.0; 4];
+        for (value, component) in values.iter_mut().zip(mask_components(dst.mask)) {
+            *value = register[component];
+        }
+        values
+    }
+
+    /// Write `values` in order to the components written by `dst`.
+    fn write(&mut self, dst: Destination, values: [f32; 4]) {
+        for (value, component) in values.iter().zip(mask_components(dst.mask)) {
+            self.set_component(dst.register, component, *value);
+        }
+    }
+
+    /// Read a column-major square matrix of the given size. 2x2 matrices
+    /// fit in one register, larger ones take a register per column.
+    fn read_matrix(&self, register: PixelBenderRegister, size: usize) -> [[f32; 4]; 4] {
+        let mut matrix = [[0.0; 4]; 4];
+        if size == 2 {
+            let values = self.get(register);
+            matrix[0][..2].copy_from_slice(&values[..2]);
+            matrix[1][..2].copy_from_slice(&values[2..]);
+        } else {
+            for (i, column) in matrix.iter_mut().enumerate().take(size) {
+                *column = self.get(PixelBenderRegister {
+                    index: register.index + i as u16,
+                    ..register
+                });
+            }
+        }
+        matrix
+    }
+
+    fn write_matrix(&mut self, register: PixelBenderRegister, size: usize, matrix: [[f32; 4]; 4]) {
+        for (column, values) in matrix.iter().enumerate().take(size) {
+            for (row, value) in values.iter().enumerate().take(size) {
+                if size == 2 {
+                    self.set_component(register, column * 2 + row, *value);
+                } else {
+                    let register = PixelBenderRegister {
+                        index: register.index + column as u16,
+                        ..register
+                    };
+                    self.set_component(register, row, *value);
+                }
+            }
+        }
+    }
+
+    /// Set a parameter's register to the given value.
+    fn set_param(
+        &mut self,
+        register: PixelBenderRegister,
+        mask: u8,
+        matrix_size: Option<usize>,
+        value: &PixelBenderValue,
+    ) {
+        let values: Vec<f32> = match value {
+            PixelBenderValue::Float(values) => values.clone(),
+            PixelBenderValue::Int(values) => values.iter().map(|v| *v as f32).collect(),
+            PixelBenderValue::String(_) => return,
+        };
+        if let Some(size) = matrix_size {
+            let mut matrix = [[0.0; 4]; 4];
+            for (i, value) in values.iter().enumerate().take(size * size) {
+                matrix[i / size][i % size] = *value;
+            }
+            self.write_matrix(register, size, matrix);
+        } else {
+            for (value, component) in values.iter().zip(mask_components(mask)) {
+                self.set_component(register, component, *value);
+            }
+        }
+    }
+}
+
+fn from_bool(value: bool) -> f32 {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/// Apply a component-wise opcode to `dst` and `src`.
+fn component_op(opcode: Opcode, dst: f32, src: f32) -> f32 {
+    match opcode {
+        Opcode::Add => dst + src,
+        Opcode::Subtract => dst - src,
+        Opcode::Multiply => dst * src,
+        Opcode::Reciprocal => 1.0 / src,
+        Opcode::Divide => dst / src,
+        Opcode::Atan2 => dst.atan2(src),
+        Opcode::Pow => dst.powf(src),
+        Opcode::Mod => dst - src * (dst / src).floor(),
+        Opcode::Min => dst.min(src),
+        Opcode::Max => dst.max(src),
+        Opcode::Step => from_bool(src >= dst),
+        Opcode::Sin => src.sin(),
+        Opcode::Cos => src.cos(),
+        Opcode::Tan => src.tan(),
+        Opcode::Asin => src.asin(),
+        Opcode::Acos => src.acos(),
+        Opcode::Atan => src.atan(),
+        Opcode::Exp => src.exp(),
+        Opcode::Exp2 => src.exp2(),
+        Opcode::Log => src.ln(),
+        Opcode::Log2 => src.log2(),
+        Opcode::Sqrt => src.sqrt(),
+        Opcode::RSqrt => 1.0 / src.sqrt(),
+        Opcode::Abs => src.abs(),
+        Opcode::Sign => {
+            if src > 0.0 {
+                1.0
+            } else if src < 0.0 {
+                -1.0
+            } else {
+                0.0
+            }
+        }
+        Opcode::Floor => src.floor(),
+        Opcode::Ceil => src.ceil(),
+        Opcode::Fract => src - src.floor(),
+        Opcode::FloatToInt => src.trunc(),
+        Opcode::Equal => from_bool(dst == src),
+        Opcode::NotEqual => from_bool(dst != src),
+        Opcode::LessThan => from_bool(dst < src),
+        Opcode::LessThanEqual => from_bool(dst <= src),
+        Opcode::LogicalNot => from_bool(src == 0.0),
+        Opcode::LogicalAnd => from_bool(dst != 0.0 && src != 0.0),
+        Opcode::LogicalOr => from_bool(dst != 0.0 || src != 0.0),
+        Opcode::LogicalXor => from_bool((dst != 0.0) != (src != 0.0)),
+        Opcode::FloatToBool | Opcode::IntToBool => from_bool(src != 0.0),
+        Opcode::Nop => dst,
+        _ => src,
+    }
+}
+
+/// Run a `Normal` instruction.
+fn run_normal(
+    registers: &mut Registers,
+    opcode: Opcode,
+    dst: Destination,
+    src: Source,
+    size: usize,
+    matrix: usize,
+) {
+    if matrix > 0 {
+        let n = matrix + 1;
+        let left = registers.read_matrix(dst.register, n);
+        let right = registers.read_matrix(src.register, n);
+        let mut result = [[0.0; 4]; 4];
+        match opcode {
+            Opcode::MatMatMul => {
+                for (column, values) in result.iter_mut().enumerate().take(n) {
+                    for (row, value) in values.iter_mut().enumerate().take(n) {
+                        *value = (0..n).map(|k| left[k][row] * right[column][k]).sum();
+                    }
+                }
+                registers.write_matrix(dst.register, n, result);
+            }
+            Opcode::VecMatMul | Opcode::MatVecMul => {
+                let vector = registers.read_destination(dst);
+                let mut values = [0.0; 4];
+                for (i, value) in values.iter_mut().enumerate().take(n) {
+                    *value = if opcode == Opcode::VecMatMul {
+                        dot(&vector[..n], &right[i][..n])
+                    } else {
+                        (0..n).map(|k| right[k][i] * vector[k]).sum()
+                    };
+                }
+                registers.write(dst, values);
+            }
+            _ => {
+                for (column, values) in result.iter_mut().enumerate().take(n) {
+                    for (row, value) in values.iter_mut().enumerate().take(n) {
+                        *value = component_op(opcode, left[column][row], right[column][row]);
+                    }
+                }
+                registers.write_matrix(dst.register, n, result);
+            }
+        }
+        return;
+    }
+
+    let left = registers.read_destination(dst);
+    let right = registers.read(src);
+    let mut values = [0.0; 4];
+    match opcode {
+        Opcode::Normalize => {
+            let length = dot(&right[..size], &right[..size]).sqrt();
+            for (value, right) in values.iter_mut().zip(&right).take(size) {
+                *value = right / length;
+            }
+        }
+        Opcode::Length => values[0] = dot(&right[..size], &right[..size]).sqrt(),
+        Opcode::DotProduct | Opcode::Distance | Opcode::VectorEqual | Opcode::VectorNotEqual => {
+            // These combine whole vectors into a single component, so the
+            // left vector is the start of the destination register.
+            let left = registers.get(dst.register);
+            values[0] = match opcode {
+                Opcode::DotProduct => dot(&left[..size], &right[..size]),
+                Opcode::Distance => left[..size]
+                    .iter()
+                    .zip(&right[..size])
+                    .map(|(l, r)| (l - r) * (l - r))
+                    .sum::<f32>()
+                    .sqrt(),
+                Opcode::VectorEqual => from_bool(left[..size] == right[..size]),
+                _ => from_bool(left[..size] != right[..size]),
+            };
+        }
+        Opcode::CrossProduct => {
+            values[0] = left[1] * right[2] - left[2] * right[1];
+            values[1] = left[2] * right[0] - left[0] * right[2];
+            values[2] = left[0] * right[1] - left[1] * right[0];
+        }
+        Opcode::BoolAny => values[0] = from_bool(right[..size].iter().any(|v| *v != 0.0)),
+        Opcode::BoolAll => values[0] = from_bool(right[..size].iter().all(|v| *v != 0.0)),
+        _ => {
+            for (value, (left, right)) in values.iter_mut().zip(left.iter().zip(&right)).take(size)
+            {
+                *value = component_op(opcode, *left, *right);
+            }
+        }
+    }
+    registers.write(dst, values);
+}
+
+/// Run a shader over every pixel of a `width` by `height` image.
+///
+/// `params` holds a value for each of the shader's parameters, and `inputs`
+/// an image for each of its inputs; missing inputs read as transparent
+/// black.
+pub fn run_shader(
+    shader: &PixelBenderShader,
+    params: &[PixelBenderValue],
+    inputs: &[Option<&ShaderImage>],
+    width: u32,
+    height: u32,
+) -> Result<ShaderImage, PixelBenderError> {
+    let output = shader.output().ok_or(PixelBenderError::NoOutput)?;
+    let channels = output.param_type.components().min(4);
+    let mut image = ShaderImage::new(width, height, channels);
+
+    let mut registers = Registers {
+        floats: vec![[0.0; 4]; shader.float_registers],
+        ints: vec![[0.0; 4]; shader.int_registers],
+    };
+    for (param, value) in shader.params.iter().zip(params) {
+        if param.qualifier == PixelBenderQualifier::In && !param.is_out_coord() {
+            registers.set_param(
+                param.register,
+                param.mask,
+                param.param_type.matrix_size(),
+                value,
+            );
+        }
+    }
+    let out_coord = shader.params.iter().find(|param| param.is_out_coord());
+    let initial = registers.floats.clone();
+    let initial_ints = registers.ints.clone();
+
+    for y in 0..height {
+        for x in 0..width {
+            registers.floats.copy_from_slice(&initial);
+            registers.ints.copy_from_slice(&initial_ints);
+            if let Some(out_coord) = out_coord {
+                let coord = [x as f32 + 0.5, y as f32 + 0.5];
+                for (value, component) in coord.iter().zip(mask_components(out_coord.mask)) {
+                    registers.set_component(out_coord.register, component, *value);
+                }
+            }
+
+            let mut pc = 0;
+            while let Some(operation) = shader.operations.get(pc) {
+                pc += 1;
+                match operation {
+                    Operation::Normal {
+                        opcode,
+                        dst,
+                        src,
+                        size,
+                        matrix,
+                    } => run_normal(&mut registers, *opcode, *dst, *src, *size, *matrix),
+                    Operation::Sample {
+                        linear,
+                        dst,
+                        src,
+                        texture,
+                    } => {
+                        let coord = registers.read(*src);
+                        let input = shader
+                            .inputs
+                            .iter()
+                            .position(|input| input.index == *texture)
+                            .and_then(|i| inputs.get(i).copied().flatten());
+                        let pixel = match input {
+                            Some(input) if *linear => input.sample_linear(coord[0], coord[1]),
+                            Some(input) => input.sample_nearest(coord[0], coord[1]),
+                            None => [0.0; 4],
+                        };
+                        registers.write(*dst, pixel);
+                    }
+                    Operation::LoadFloat { dst, value } => {
+                        registers.write(*dst, [*value; 4]);
+                    }
+                    Operation::LoadInt { dst, value } => {
+                        registers.write(*dst, [*value as f32; 4]);
+                    }
+                    Operation::Select {
+                        dst,
+                        condition,
+                        src1,
+                        src2,
+                        size,
+                    } => {
+                        let condition = registers.read(*condition)[0] != 0.0;
+                        let values = registers.read(if condition { *src1 } else { *src2 });
+                        let mut selected = [0.0; 4];
+                        selected[..*size].copy_from_slice(&values[..*size]);
+                        registers.write(*dst, selected);
+                    }
+                    Operation::If { condition, skip_to } => {
+                        if registers.read(*condition)[0] == 0.0 {
+                            pc = *skip_to;
+                        }
+                    }
+                    Operation::Else { skip_to } => pc = *skip_to,
+                    Operation::EndIf => (),
+                }
+            }
+
+            let result = registers.get(output.register);
+            let offset = (y as usize * width as usize + x as usize) * channels;
+            for (i, component) in mask_components(output.mask).take(channels).enumerate() {
+                image.data[offset + i] = result[component];
+            }
+        }
+    }
+
+    Ok(image)
+}
@@ -3,33 +3,48 @@ use crate::avm1::debug::VariableDumper;
 use crate::avm1::globals::system::SystemProperties;
 use crate::avm1::object::Object;
 use crate::avm1::property::Attribute;
-use crate::avm1::{Avm1, ScriptObject, TObject, Timers, Value};
-use crate::avm2::{Activation as Avm2Activation, Avm2, Domain as Avm2Domain};
+use crate::avm1::{Avm1, CaseSensitivity, ScriptObject, TObject, Value};
+use crate::avm2::{
+    game_input_control_changed, game_input_device_added, game_input_device_removed,
+    ime_dispatch_composition, Activation as Avm2Activation, Avm2, Domain as Avm2Domain,
+    Event as Avm2Event, Namespace as Avm2Namespace, Object as Avm2Object, QName as Avm2QName,
+    TObject as Avm2TObject, Value as Avm2Value,
+};
 use crate::backend::{
     audio::{AudioBackend, AudioManager},
     locale::LocaleBackend,
     log::LogBackend,
     navigator::{NavigatorBackend, RequestOptions},
-    render::RenderBackend,
+    render::{BitmapHandle, RenderBackend},
     storage::StorageBackend,
-    ui::{MouseCursor, UiBackend},
+    ui::{CustomMouseCursor, MouseCursor, UiBackend},
     video::VideoBackend,
 };
-use crate::config::Letterbox;
+use crate::bundle::{Bundle, BundleMount, BundleNavigatorBackend};
+use crate::config::{FontScript, Letterbox, NotdefStrategy, SpoofedCapabilities};
 use crate::context::{ActionQueue, ActionType, RenderContext, UpdateContext};
 use crate::context_menu::{ContextMenuCallback, ContextMenuItem, ContextMenuState};
+use crate::cursor::{CursorSetting, MouseCursors};
 use crate::display_object::{
-    EditText, MorphShape, MovieClip, Stage, StageAlign, StageQuality, StageScaleMode,
+    EditText, MorphShape, MovieClip, Stage, StageAlign, StageDisplayState, StageQuality,
+    StageScaleMode, TextSelection,
 };
-use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult, KeyCode, PlayerEvent};
+use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult, KeyCode, PlayerEvent, TouchPhase};
 use crate::external::Value as ExternalValue;
 use crate::external::{ExternalInterface, ExternalInterfaceProvider};
 use crate::focus_tracker::FocusTracker;
+use crate::game_input::GameInput;
 use crate::library::Library;
 use crate::loader::LoadManager;
+use crate::local_connection::LocalConnections;
+use crate::multitouch::{Gesture, GesturePhase, Multitouch, MultitouchInputMode};
 use crate::prelude::*;
-use crate::string::AvmString;
+use crate::remoting::NetConnections;
+use crate::security::Security;
+use crate::socket::Sockets;
+use crate::string::{utils as string_utils, AvmString};
 use crate::tag_utils::SwfMovie;
+use crate::timer::Timers;
 use crate::transform::TransformStack;
 use crate::vminterface::{AvmType, Instantiator};
 use gc_arena::{make_arena, ArenaParameters, Collect, GcCell};
@@ -38,6 +53,7 @@ use log::info;
 use rand::{rngs::SmallRng, SeedableRng};
 use std::collections::{HashMap, VecDeque};
 use std::ops::DerefMut;
+use std::rc::Rc;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex, Weak};
 use std::time::Duration;
@@ -86,12 +102,24 @@ struct GcRootData<'gc> {
 
     shared_objects: HashMap<String, Object<'gc>>,
 
+    /// Shared objects created by AVM2 movies.
+    avm2_shared_objects: HashMap<String, Avm2Object<'gc>>,
+
     /// Text fields with unbound variable bindings.
     unbound_text_fields: Vec<EditText<'gc>>,
 
     /// Timed callbacks created with `setInterval`/`setTimeout`.
     timers: Timers<'gc>,
 
+    /// Sockets opened by the movie.
+    sockets: Sockets<'gc>,
+
+    /// Connections made by `LocalConnection` objects.
+    local_connections: LocalConnections<'gc>,
+
+    /// Connections made by `NetConnection` objects.
+    net_connections: NetConnections<'gc>,
+
     current_context_menu: Option<ContextMenuState<'gc>>,
 
     /// External interface for (for example) JavaScript <-> ActionScript interaction
@@ -119,8 +147,12 @@ impl<'gc> GcRootData<'gc> {
         &mut Option<DragObject<'gc>>,
         &mut LoadManager<'gc>,
         &mut HashMap<String, Object<'gc>>,
+        &mut HashMap<String, Avm2Object<'gc>>,
         &mut Vec<EditText<'gc>>,
         &mut Timers<'gc>,
+        &mut Sockets<'gc>,
+        &mut LocalConnections<'gc>,
+        &mut NetConnections<'gc>,
         &mut Option<ContextMenuState<'gc>>,
         &mut ExternalInterface<'gc>,
         &mut AudioManager<'gc>,
@@ -134,8 +166,12 @@ impl<'gc> GcRootData<'gc> {
             &mut self.drag_object,
             &mut self.load_manager,
             &mut self.shared_objects,
+            &mut self.avm2_shared_objects,
             &mut self.unbound_text_fields,
             &mut self.timers,
+            &mut self.sockets,
+            &mut self.local_connections,
+            &mut self.net_connections,
             &mut self.current_context_menu,
             &mut self.external_interface,
             &mut self.audio_manager,
@@ -173,6 +209,9 @@ pub struct Player {
     warn_on_unsupported_content: bool,
 
     is_playing: bool,
+
+    /// Whether the player has the focus of the operating system.
+    is_focused: bool,
     needs_render: bool,
 
     renderer: Renderer,
@@ -210,9 +249,32 @@ pub struct Player {
 
     system: SystemProperties,
 
+    /// The security sandbox settings of the player.
+    security: Security,
+
+    /// The cursors that the movie has chosen with `flash.ui.Mouse`.
+    mouse_cursors: MouseCursors,
+
+    /// The fingers on a touch screen.
+    multitouch: Multitouch,
+
+    /// The game controllers connected to the player.
+    game_input: GameInput,
+
+    /// A bitmap cursor that the UI can't show natively, drawn over the stage.
+    cursor_overlay: Option<(Rc<CustomMouseCursor>, BitmapHandle)>,
+
+    /// The bundle whose files are served to the movie, if it was loaded from
+    /// one.
+    bundle: BundleMount,
+
     /// The current instance ID. Used to generate default `instanceN` names.
     instance_counter: i32,
 
+    /// Counts changes to the display list. See
+    /// `UpdateContext::display_list_generation`.
+    display_list_generation: u64,
+
     /// Time remaining until the next timer will fire.
     time_til_next_timer: Option<f64>,
 
@@ -220,6 +282,22 @@ pub struct Player {
     /// is raised. This defaults to 15 seconds but can be changed.
     max_execution_duration: Duration,
 
+    /// The maximum amount of time a single `tick` may spend running frames
+    /// to catch up.
+    ///
+    /// This only skips catch-up frames: once the budget is spent, any further
+    /// frames that `tick` would have run to catch up are dropped, and control
+    /// is returned to the host. The first frame of a tick always runs, and a
+    /// frame is never sliced or resumed later, so a single long frame can
+    /// still take as long as it takes. This is `None` by default, which runs
+    /// every frame a tick calls for.
+    frame_budget: Option<Duration>,
+
+    /// The last query given to `find_text`, with the position of its last
+    /// match: the index of the text it was found in, and the byte offset just
+    /// past it.
+    find_state: Option<(String, usize, usize)>,
+
     /// Self-reference to ourselves.
     ///
     /// This is a weak reference that is upgraded and handed out in various
@@ -246,6 +324,8 @@ impl Player {
         ui: Ui,
     ) -> Result<Arc<Mutex<Self>>, Error> {
         let fake_movie = Arc::new(SwfMovie::empty(NEWEST_PLAYER_VERSION));
+        let bundle = BundleMount::new();
+        let navigator: Navigator = Box::new(BundleNavigatorBackend::new(navigator, bundle.clone()));
         let movie_width = 550;
         let movie_height = 400;
         let frame_rate = 12.0;
@@ -260,6 +340,7 @@ impl Player {
             warn_on_unsupported_content: true,
 
             is_playing: false,
+            is_focused: true,
             needs_render: true,
 
             transform_stack: TransformStack::new(),
@@ -280,8 +361,12 @@ impl Player {
                         action_queue: ActionQueue::new(),
                         load_manager: LoadManager::new(),
                         shared_objects: HashMap::new(),
+                        avm2_shared_objects: HashMap::new(),
                         unbound_text_fields: Vec::new(),
                         timers: Timers::new(),
+                        sockets: Sockets::new(),
+                        local_connections: LocalConnections::new(),
+                        net_connections: NetConnections::default(),
                         current_context_menu: None,
                         external_interface: ExternalInterface::new(),
                         focus_tracker: FocusTracker::new(gc_context),
@@ -308,10 +393,19 @@ impl Player {
             video,
             self_reference: None,
             system: SystemProperties::default(),
+            security: Security::new(),
+            mouse_cursors: MouseCursors::new(),
+            multitouch: Multitouch::new(),
+            game_input: GameInput::new(),
+            cursor_overlay: None,
+            bundle,
             instance_counter: 0,
+            display_list_generation: 0,
             time_til_next_timer: None,
             storage,
             max_execution_duration: Duration::from_secs(max_execution_duration),
+            frame_budget: None,
+            find_state: None,
             current_frame: None,
         };
 
@@ -370,6 +464,11 @@ impl Player {
         });
     }
 
+    /// The root movie that is playing.
+    pub fn swf(&self) -> &Arc<SwfMovie> {
+        &self.swf
+    }
+
     /// Change the root movie.
     ///
     /// This should only be called once, as it makes no attempt at removing
@@ -384,6 +483,7 @@ impl Player {
         );
 
         self.frame_rate = movie.frame_rate().into();
+        self.system.sandbox_type = self.security.sandbox_type(&movie);
         self.swf = movie;
         self.instance_counter = 0;
 
@@ -436,7 +536,10 @@ impl Player {
                 if let Err(e) = &device_font {
                     log::error!("Unable to load device font: {}", e);
                 }
-                context.library.set_device_font(device_font.ok());
+                let device_font = device_font.ok();
+                let notdef_font = device_font
+                    .map(|font| font.with_notdef_glyph(context.gc_context, context.renderer));
+                context.library.set_device_font(device_font, notdef_font);
             }
 
             // Set the version parameter on the root.
@@ -512,8 +615,15 @@ impl Player {
 
             let max_frames_per_tick = self.max_frames_per_tick();
             let mut frame = 0;
+            let tick_start = Instant::now();
 
             while frame < max_frames_per_tick && self.frame_accumulator >= frame_time {
+                if let Some(frame_budget) = self.frame_budget {
+                    if frame > 0 && tick_start.elapsed() >= frame_budget {
+                        break;
+                    }
+                }
+
                 let timer = Instant::now();
                 self.run_frame();
                 let elapsed = timer.elapsed().as_millis() as f64;
@@ -547,6 +657,9 @@ impl Player {
             }
 
             self.update_timers(dt);
+            self.update_sockets();
+            self.update_local_connections();
+            self.game_input.tick(dt);
             self.audio.tick();
         }
     }
@@ -576,49 +689,252 @@ impl Player {
         self.is_playing
     }
 
+    /// Tell the player whether it has the focus of the operating system.
+    ///
+    /// Movies are sent `activate` and `deactivate` events when this changes.
+    pub fn set_focused(&mut self, focused: bool) {
+        if self.is_focused == focused {
+            return;
+        }
+        self.is_focused = focused;
+
+        self.mutate_with_update_context(|context| {
+            let mut event = Avm2Event::new(if focused { "activate" } else { "deactivate" });
+            event.set_bubbles(false);
+            event.set_cancelable(false);
+
+            let dobject_constr = context.avm2.classes().display_object;
+            if let Err(e) = Avm2::broadcast_event(context, event, dobject_constr) {
+                Avm2::report_uncaught_error(
+                    context,
+                    e,
+                    "Encountered AVM2 error when broadcasting focus change",
+                );
+            }
+        });
+    }
+
+    /// Whether the movie reacts to losing focus itself, by listening for
+    /// `deactivate` events.
+    pub fn handles_deactivate(&mut self) -> bool {
+        self.mutate_with_update_context(|context| {
+            context.avm2.has_broadcast_listeners("deactivate")
+        })
+    }
+
     pub fn prepare_context_menu(&mut self) -> Vec<ContextMenuItem> {
         self.mutate_with_update_context(|context| {
             if !context.stage.show_menu() {
                 return vec![];
             }
 
-            let mut activation = Activation::from_stub(
-                context.reborrow(),
-                ActivationIdentifier::root("[ContextMenu]"),
-            );
+            let menu = if context.swf.avm_type() == AvmType::Avm2 {
+                Self::prepare_avm2_context_menu(context)
+            } else {
+                Self::prepare_avm1_context_menu(context)
+            };
+            let ret = menu.info().clone();
+            *context.current_context_menu = Some(menu);
+            ret
+        })
+    }
 
-            // TODO: This should use a pointed display object with `.menu`
-            let menu_object = {
-                let dobj = activation.context.stage.root_clip();
-                if let Value::Object(obj) = dobj.object() {
-                    if let Ok(Value::Object(menu)) = obj.get("menu", &mut activation) {
-                        Some(menu)
-                    } else {
-                        None
-                    }
+    fn prepare_avm1_context_menu<'gc>(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> ContextMenuState<'gc> {
+        let mut activation = Activation::from_stub(
+            context.reborrow(),
+            ActivationIdentifier::root("[ContextMenu]"),
+        );
+
+        // TODO: This should use a pointed display object with `.menu`
+        let menu_object = {
+            let dobj = activation.context.stage.root_clip();
+            if let Value::Object(obj) = dobj.object() {
+                if let Ok(Value::Object(menu)) = obj.get("menu", &mut activation) {
+                    Some(menu)
                 } else {
                     None
                 }
-            };
+            } else {
+                None
+            }
+        };
+
+        if let Some(menu) = menu_object {
+            if let Ok(Value::Object(on_select)) = menu.get("onSelect", &mut activation) {
+                Self::run_context_menu_custom_callback(menu, on_select, &mut activation.context);
+            }
+        }
+
+        crate::avm1::globals::context_menu::make_context_menu_state(menu_object, &mut activation)
+    }
+
+    fn prepare_avm2_context_menu<'gc>(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> ContextMenuState<'gc> {
+        let root_clip = context.stage.root_clip();
+        let mut activation = Avm2Activation::from_nothing(context.reborrow());
+
+        // TODO: This should use the `contextMenu` of the object under the mouse.
+        let menu_object = match root_clip.object2() {
+            Avm2Value::Object(root) => root
+                .get_property(
+                    root,
+                    &Avm2QName::new(Avm2Namespace::public(), "contextMenu"),
+                    &mut activation,
+                )
+                .ok()
+                .and_then(|menu| menu.coerce_to_object(&mut activation).ok()),
+            _ => None,
+        };
+
+        if let Some(menu) = menu_object {
+            if let Err(e) =
+                Self::dispatch_context_menu_event("menuSelect", menu, &mut activation.context)
+            {
+                log::error!("Error dispatching menuSelect event: {}", e);
+            }
+        }
+
+        match crate::avm2::make_avm2_context_menu_state(menu_object, &mut activation) {
+            Ok(menu) => menu,
+            Err(e) => {
+                log::error!("Error preparing context menu: {}", e);
+                ContextMenuState::new()
+            }
+        }
+    }
+
+    /// Dispatch a `ContextMenuEvent` from the object under the mouse.
+    fn dispatch_context_menu_event<'gc>(
+        event_type: &'static str,
+        target: Avm2Object<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), crate::avm2::Error> {
+        let mouse_target = context
+            .mouse_over_object
+            .unwrap_or_else(|| context.stage.root_clip())
+            .object2();
+        let owner = context.stage.root_clip().object2();
+
+        let mut activation = Avm2Activation::from_nothing(context.reborrow());
+        let context_menu_event_class = activation.avm2().classes().contextmenuevent;
+        let event = context_menu_event_class.construct(
+            &mut activation,
+            &[
+                event_type.into(),
+                false.into(),
+                false.into(),
+                mouse_target,
+                owner,
+            ],
+        )?;
+        Avm2::dispatch_event_object(&mut activation.context, event, target)?;
+
+        Ok(())
+    }
+
+    /// Paste text into the focused object.
+    ///
+    /// Text fields take the text themselves, while other objects are sent a
+    /// `paste` event during which they may read the clipboard.
+    fn paste<'gc>(context: &mut UpdateContext<'_, 'gc, '_>, text: String) {
+        let focus = match context.focus_tracker.get() {
+            Some(focus) => focus,
+            None => return,
+        };
+
+        if let Some(edit_text) = focus.as_edit_text() {
+            edit_text.paste(&text, context);
+        } else {
+            Self::dispatch_clipboard_event(context, focus, "paste", Some(text));
+        }
+    }
+
+    /// Show or enter text that the user is composing with an input method in
+    /// the focused text field, and tell movies about it.
+    fn handle_ime_event<'gc>(context: &mut UpdateContext<'_, 'gc, '_>, event: PlayerEvent) {
+        let focus = context.focus_tracker.get();
+        let edit_text = focus.and_then(|o| o.as_edit_text());
+
+        match event {
+            PlayerEvent::ImeStart => {
+                if let Some(Avm2Value::Object(target)) = focus.map(|o| o.object2()) {
+                    let mut activation = Avm2Activation::from_nothing(context.reborrow());
+                    let ime_event_class = activation.avm2().classes().imeevent;
+                    let result = ime_event_class
+                        .construct(
+                            &mut activation,
+                            &["imeStartComposition".into(), false.into(), false.into()],
+                        )
+                        .and_then(|event| {
+                            Avm2::dispatch_event_object(&mut activation.context, event, target)
+                        });
+                    if let Err(e) = result {
+                        log::error!(
+                            "Encountered AVM2 error when dispatching imeStartComposition event: {}",
+                            e
+                        );
+                    }
+                }
+            }
+            PlayerEvent::ImeUpdate { text } => {
+                if let Some(edit_text) = edit_text {
+                    edit_text.set_composition(&text, context);
+                }
+            }
+            PlayerEvent::ImeCommit { text } => {
+                if let Some(edit_text) = edit_text {
+                    edit_text.commit_composition(&text, context);
+                }
+                if text.is_empty() {
+                    return;
+                }
 
-            if let Some(menu) = menu_object {
-                if let Ok(Value::Object(on_select)) = menu.get("onSelect", &mut activation) {
-                    Self::run_context_menu_custom_callback(
-                        menu,
-                        on_select,
-                        &mut activation.context,
+                let level0 = context.stage.root_clip();
+                let text_value: Value<'gc> =
+                    AvmString::new(context.gc_context, text.clone()).into();
+                Avm1::notify_system_listeners(
+                    level0,
+                    context.swf.version(),
+                    context,
+                    "System.IME",
+                    "onIMEComposition",
+                    &[text_value],
+                );
+                if let Err(e) = ime_dispatch_composition(context, &text) {
+                    log::error!(
+                        "Encountered AVM2 error when dispatching imeComposition event: {}",
+                        e
                     );
                 }
             }
+            _ => {}
+        }
+    }
 
-            let menu = crate::avm1::globals::context_menu::make_context_menu_state(
-                menu_object,
-                &mut activation,
-            );
-            let ret = menu.info().clone();
-            *activation.context.current_context_menu = Some(menu);
-            ret
-        })
+    /// Send a clipboard event, such as `copy` or `paste`, to a focused AVM2
+    /// object.
+    ///
+    /// The clipboard can be read while a `paste` event is being dispatched.
+    fn dispatch_clipboard_event<'gc>(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: DisplayObject<'gc>,
+        event_type: &'static str,
+        pasted_text: Option<String>,
+    ) {
+        if let Avm2Value::Object(target) = target.object2() {
+            context.avm2.set_pasted_text(pasted_text);
+            if let Err(e) = Avm2::dispatch_event(context, Avm2Event::new(event_type), target) {
+                log::error!(
+                    "Encountered AVM2 error when dispatching {} event: {}",
+                    event_type,
+                    e
+                );
+            }
+            context.avm2.set_pasted_text(None);
+        }
     }
 
     pub fn clear_custom_menu_items(&mut self) {
@@ -636,6 +952,13 @@ impl Player {
                     ContextMenuCallback::Avm1 { item, callback } => {
                         Self::run_context_menu_custom_callback(*item, *callback, context)
                     }
+                    ContextMenuCallback::Avm2 { item } => {
+                        if let Err(e) =
+                            Self::dispatch_context_menu_event("menuItemSelect", *item, context)
+                        {
+                            log::error!("Error dispatching menuItemSelect event: {}", e);
+                        }
+                    }
                     ContextMenuCallback::Play => Self::toggle_play_root_movie(context),
                     ContextMenuCallback::Forward => Self::forward_root_movie(context),
                     ContextMenuCallback::Back => Self::back_root_movie(context),
@@ -750,6 +1073,77 @@ impl Player {
         self.warn_on_unsupported_content = warn_on_unsupported_content
     }
 
+    /// Register an additional font to fall back to for device text in a
+    /// given script, after the default device font and any fonts that were
+    /// previously registered for that script.
+    ///
+    /// The font data may be a TrueType or OpenType font file, or a
+    /// `DefineFont3` tag body, as with `load_device_font`.
+    pub fn register_device_font_fallback(
+        &mut self,
+        script: FontScript,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        self.mutate_with_update_context(|context| {
+            let font = Self::load_device_font(context.gc_context, data, context.renderer)?;
+            context.library.add_device_font_fallback(script, font);
+            Ok(())
+        })
+    }
+
+    /// Set how device text renders characters that no device font has a
+    /// glyph for.
+    pub fn set_notdef_strategy(&mut self, notdef_strategy: NotdefStrategy) {
+        self.mutate_with_update_context(|context| {
+            context.library.set_notdef_strategy(notdef_strategy)
+        })
+    }
+
+    /// Whether movies are trusted, and so run without any security sandbox
+    /// restrictions.
+    pub fn is_trusted(&self) -> bool {
+        self.security.is_trusted()
+    }
+
+    /// Set whether movies are trusted.
+    ///
+    /// Trusted movies run in the `localTrusted` sandbox, and may load data
+    /// from anywhere without a cross-domain policy file allowing it.
+    pub fn set_trusted(&mut self, trusted: bool) {
+        self.security.set_trusted(trusted);
+        self.system.sandbox_type = self.security.sandbox_type(&self.swf);
+    }
+
+    /// Report the given capabilities to the movie in place of the real ones,
+    /// through `$version` and `System.capabilities`.
+    ///
+    /// This must be set before the root movie for `$version` to be affected.
+    pub fn set_spoofed_capabilities(&mut self, spoofed: SpoofedCapabilities) {
+        self.system.spoofed = spoofed;
+    }
+
+    /// Set the resolution of the screen that the player is shown on.
+    pub fn set_screen_resolution(&mut self, width: u32, height: u32) {
+        self.system.screen_resolution = (width, height);
+    }
+
+    /// Serve the files of a bundle to the movie.
+    ///
+    /// The movie of the bundle must be set as the root movie separately.
+    pub fn mount_bundle(&mut self, bundle: Bundle) {
+        self.bundle.mount(bundle);
+    }
+
+    /// Force AVM1 names to be compared with the given case sensitivity,
+    /// instead of the one implied by the SWF version of the running code.
+    ///
+    /// Passing `None` restores the default behavior.
+    pub fn set_avm1_case_sensitivity(&mut self, case_sensitivity: Option<CaseSensitivity>) {
+        self.mutate_with_update_context(|context| {
+            context.avm1.set_case_sensitivity_override(case_sensitivity)
+        })
+    }
+
     pub fn movie_width(&mut self) -> u32 {
         self.mutate_with_update_context(|context| context.stage.movie_size().0)
     }
@@ -803,7 +1197,112 @@ impl Player {
         })
     }
 
+    /// Find the next occurrence of `query` in the text on stage and highlight
+    /// it, the way a browser's find bar does.
+    ///
+    /// Both text fields and static text are searched, ignoring case, in
+    /// display list order. Repeating the same query moves on to the next
+    /// match, wrapping around at the end. The contents of password fields are
+    /// never searched. Returns `false` if there is no match at all.
+    pub fn find_text(&mut self, query: &str) -> bool {
+        let (start_index, start_offset) = match &self.find_state {
+            Some((last_query, index, offset)) if last_query == query => (*index, *offset),
+            _ => (0, 0),
+        };
+
+        let found = self.mutate_with_update_context(|context| {
+            let mut objects = Vec::new();
+            collect_searchable_text(context.stage.into(), &mut objects);
+
+            let texts: Vec<String> = objects
+                .iter()
+                .map(|object| {
+                    if let Some(text) = object.as_text() {
+                        text.set_found(context.gc_context, None);
+                        text.text(context)
+                    } else if let Some(edit_text) = object.as_edit_text() {
+                        edit_text.text()
+                    } else {
+                        String::new()
+                    }
+                })
+                .collect();
+
+            // Search the rest of the text of the last match, every text after
+            // it, and then wrap around to the start.
+            let order = (start_index..texts.len())
+                .map(|i| (i, if i == start_index { start_offset } else { 0 }))
+                .chain((0..=start_index.min(texts.len())).map(|i| (i, 0)));
+            for (i, from) in order {
+                let text = match texts.get(i) {
+                    Some(text) => text,
+                    None => continue,
+                };
+                // The text may have changed since the last match.
+                let from = if text.is_char_boundary(from) { from } else { 0 };
+                let (start, end) = match string_utils::swf_find_ignore_case(text, query, from) {
+                    Some(range) => range,
+                    None => continue,
+                };
+
+                if let Some(static_text) = objects[i].as_text() {
+                    let start_glyph = text[..start].chars().count();
+                    let end_glyph = start_glyph + text[start..end].chars().count();
+                    static_text.set_found(context.gc_context, Some((start_glyph, end_glyph)));
+                } else if let Some(edit_text) = objects[i].as_edit_text() {
+                    edit_text.set_selection(
+                        Some(TextSelection::for_range(start, end)),
+                        context.gc_context,
+                    );
+                }
+
+                return Some((i, end));
+            }
+
+            None
+        });
+
+        self.needs_render = true;
+        self.find_state = found.map(|(index, offset)| (query.to_string(), index, offset));
+        self.find_state.is_some()
+    }
+
     pub fn handle_event(&mut self, event: PlayerEvent) {
+        if let PlayerEvent::Touch { id, phase, x, y } = event {
+            self.handle_touch_event(id, phase, x, y);
+            return;
+        }
+
+        if let PlayerEvent::Paste { text } = event {
+            self.mutate_with_update_context(|context| Self::paste(context, text));
+            return;
+        }
+
+        if matches!(
+            event,
+            PlayerEvent::ImeStart | PlayerEvent::ImeUpdate { .. } | PlayerEvent::ImeCommit { .. }
+        ) {
+            self.mutate_with_update_context(|context| Self::handle_ime_event(context, event));
+            return;
+        }
+
+        if matches!(
+            event,
+            PlayerEvent::GamepadConnected { .. }
+                | PlayerEvent::GamepadDisconnected { .. }
+                | PlayerEvent::GamepadInput { .. }
+        ) {
+            self.handle_gamepad_event(event);
+            return;
+        }
+
+        // Plain `FULL_SCREEN` mode only lets content see keys that can't be used to enter text.
+        let display_state =
+            self.mutate_with_update_context(|context| context.stage.display_state());
+        if display_state == StageDisplayState::FullScreen && !event.is_allowed_in_full_screen() {
+            return;
+        }
+
         if cfg!(feature = "avm_debug") {
             if let PlayerEvent::KeyDown {
                 key_code: KeyCode::V,
@@ -864,6 +1363,45 @@ impl Player {
             }
         }
 
+        // Clipboard and selection shortcuts for the focused text field.
+        // Command is the shortcut modifier on macOS.
+        if let PlayerEvent::KeyDown { key_code } = event {
+            if self.ui.is_key_down(KeyCode::Control) || self.ui.is_key_down(KeyCode::Command) {
+                self.mutate_with_update_context(|context| {
+                    let focus = context.focus_tracker.get();
+                    if let Some(text) = focus.and_then(|o| o.as_edit_text()) {
+                        match key_code {
+                            KeyCode::C => text.copy_selection(context),
+                            KeyCode::X => text.cut_selection(context),
+                            KeyCode::A => text.select_all(context.gc_context),
+                            KeyCode::Z if context.ui.is_key_down(KeyCode::Shift) => {
+                                text.redo(context)
+                            }
+                            KeyCode::Z => text.undo(context),
+                            KeyCode::Y => text.redo(context),
+                            _ => (),
+                        }
+                    } else if let Some(focus) = focus {
+                        let event_type = match key_code {
+                            KeyCode::C => "copy",
+                            KeyCode::X => "cut",
+                            KeyCode::A => "selectAll",
+                            _ => "",
+                        };
+                        if !event_type.is_empty() {
+                            Self::dispatch_clipboard_event(context, focus, event_type, None);
+                        }
+                    }
+
+                    if key_code == KeyCode::V {
+                        if let Some(text) = context.ui.clipboard_content() {
+                            Self::paste(context, text);
+                        }
+                    }
+                });
+            }
+        }
+
         // Propagate button events.
         let button_event = match event {
             // ASCII characters convert directly to keyPress button events.
@@ -972,6 +1510,36 @@ impl Player {
                 );
             }
 
+            // AVM2 keyboard events go to the focused object, or the stage if
+            // nothing has focus, and bubble up from there.
+            if context.swf.avm_type() == AvmType::Avm2 {
+                let key = match event {
+                    PlayerEvent::KeyDown { key_code } => Some(("keyDown", key_code)),
+                    PlayerEvent::KeyUp { key_code } => Some(("keyUp", key_code)),
+                    _ => None,
+                };
+
+                if let Some((event_type, key_code)) = key {
+                    let target = context
+                        .focus_tracker
+                        .get()
+                        .map(|o| o.object2())
+                        .unwrap_or_else(|| context.stage.object2());
+
+                    if let Avm2Value::Object(target) = target {
+                        if let Err(e) = Self::dispatch_avm2_keyboard_event(
+                            context, event_type, key_code, target,
+                        ) {
+                            log::error!(
+                                "Encountered AVM2 error when dispatching {} event: {}",
+                                event_type,
+                                e
+                            );
+                        }
+                    }
+                }
+            }
+
             Self::run_actions(context);
         });
 
@@ -982,6 +1550,86 @@ impl Player {
         }
     }
 
+    /// Dispatch a `KeyboardEvent` about the key that was just pressed or
+    /// released, describing it with what the UI backend knows about it.
+    fn dispatch_avm2_keyboard_event<'gc>(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        event_type: &'static str,
+        key_code: KeyCode,
+        target: Avm2Object<'gc>,
+    ) -> Result<(), crate::avm2::Error> {
+        let char_code = context.ui.last_key_char().map_or(0, |c| c as u32);
+        let key_location = context.ui.last_key_location() as u32;
+        let ctrl_key = context.ui.is_key_down(KeyCode::Control);
+        let alt_key = context.ui.is_key_down(KeyCode::Alt);
+        let shift_key = context.ui.is_key_down(KeyCode::Shift);
+
+        let mut activation = Avm2Activation::from_nothing(context.reborrow());
+        let keyboard_event_class = activation.avm2().classes().keyboardevent;
+        let event = keyboard_event_class.construct(
+            &mut activation,
+            &[
+                event_type.into(),
+                true.into(),
+                false.into(),
+                char_code.into(),
+                (key_code as u32).into(),
+                key_location.into(),
+                ctrl_key.into(),
+                alt_key.into(),
+                shift_key.into(),
+            ],
+        )?;
+        Avm2::dispatch_event_object(&mut activation.context, event, target)?;
+
+        Ok(())
+    }
+
+    /// Dispatch a `MouseEvent` at the current mouse position to a display
+    /// object, describing the mouse and modifier keys as they are now.
+    fn dispatch_avm2_mouse_event<'gc>(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        event_type: &'static str,
+        bubbles: bool,
+        target: DisplayObject<'gc>,
+        related_object: Option<DisplayObject<'gc>>,
+        button_down: bool,
+    ) -> Result<(), crate::avm2::Error> {
+        let target_object = match target.object2() {
+            Avm2Value::Object(target_object) => target_object,
+            _ => return Ok(()),
+        };
+        let related_object = related_object
+            .map(|o| o.object2())
+            .filter(|o| matches!(o, Avm2Value::Object(_)))
+            .unwrap_or(Avm2Value::Null);
+        let (local_x, local_y) = target.global_to_local(*context.mouse_position);
+        let ctrl_key = context.ui.is_key_down(KeyCode::Control);
+        let alt_key = context.ui.is_key_down(KeyCode::Alt);
+        let shift_key = context.ui.is_key_down(KeyCode::Shift);
+
+        let mut activation = Avm2Activation::from_nothing(context.reborrow());
+        let mouse_event_class = activation.avm2().classes().mouseevent;
+        let event = mouse_event_class.construct(
+            &mut activation,
+            &[
+                event_type.into(),
+                bubbles.into(),
+                false.into(),
+                local_x.to_pixels().into(),
+                local_y.to_pixels().into(),
+                related_object,
+                ctrl_key.into(),
+                alt_key.into(),
+                shift_key.into(),
+                button_down.into(),
+            ],
+        )?;
+        Avm2::dispatch_event_object(&mut activation.context, event, target_object)?;
+
+        Ok(())
+    }
+
     /// Update dragged object, if any.
     fn update_drag(&mut self) {
         let mouse_pos = self.mouse_pos;
@@ -1026,11 +1674,255 @@ impl Player {
         });
     }
 
+    /// Find the topmost interactive object at a point on the stage.
+    fn object_under_point<'gc>(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        point: (Twips, Twips),
+        require_button_mode: bool,
+    ) -> Option<DisplayObject<'gc>> {
+        context
+            .stage
+            .iter_depth_list()
+            .rev()
+            .filter_map(|(_depth, level)| level.mouse_pick(context, point, require_button_mode))
+            .next()
+    }
+
+    /// Keep track of game controllers, telling AVM2 movies about them through
+    /// `flash.ui.GameInput`.
+    fn handle_gamepad_event(&mut self, event: PlayerEvent) {
+        self.mutate_with_update_context(|context| {
+            let result = match event {
+                PlayerEvent::GamepadConnected { id, name } => {
+                    if context.game_input.connect(id, name) {
+                        game_input_device_added(context, id)
+                    } else {
+                        Ok(())
+                    }
+                }
+                PlayerEvent::GamepadDisconnected { id } => {
+                    if context.game_input.disconnect(id) {
+                        game_input_device_removed(context, id)
+                    } else {
+                        Ok(())
+                    }
+                }
+                PlayerEvent::GamepadInput { id, control, value } => {
+                    match context.game_input.set_value(id, control, value) {
+                        Some(index) => game_input_control_changed(context, id, index),
+                        None => Ok(()),
+                    }
+                }
+                _ => Ok(()),
+            };
+
+            if let Err(e) = result {
+                log::error!(
+                    "Encountered AVM2 error when dispatching game input event: {}",
+                    e
+                );
+            }
+        });
+    }
+
+    /// Handle a finger touching, moving across or leaving the screen.
+    ///
+    /// AVM2 movies hear about it as a `TouchEvent` or as part of a gesture,
+    /// depending on `Multitouch.inputMode`. The primary touch point also
+    /// moves the mouse, unless the movie turned off `mapTouchToMouse`.
+    fn handle_touch_event(&mut self, id: i32, phase: TouchPhase, x: f64, y: f64) {
+        let (is_primary, map_touch_to_mouse) = self.mutate_with_update_context(|context| {
+            let position = context.stage.view_to_stage((x, y));
+            let update = context.multitouch.update(id, phase, position);
+
+            if context.swf.avm_type() == AvmType::Avm2 {
+                match context.multitouch.input_mode() {
+                    MultitouchInputMode::TouchPoint => {
+                        let event_type = match phase {
+                            TouchPhase::Began => "touchBegin",
+                            TouchPhase::Moved => "touchMove",
+                            TouchPhase::Ended | TouchPhase::Cancelled => "touchEnd",
+                        };
+                        let mut event_types = vec![event_type];
+                        if update.is_tap {
+                            event_types.push("touchTap");
+                        }
+
+                        let object = Self::object_under_point(context, position, false);
+                        for event_type in event_types {
+                            let touch_event_class = context.avm2.classes().touchevent;
+                            let args = |local: (f64, f64)| {
+                                vec![
+                                    event_type.into(),
+                                    true.into(),
+                                    false.into(),
+                                    id.into(),
+                                    update.is_primary.into(),
+                                    local.0.into(),
+                                    local.1.into(),
+                                ]
+                            };
+                            if let Err(e) = Self::dispatch_avm2_pointer_event(
+                                context,
+                                touch_event_class,
+                                args,
+                                object,
+                                position,
+                            ) {
+                                log::error!("Error dispatching {} event: {}", event_type, e);
+                            }
+                        }
+                    }
+                    MultitouchInputMode::Gesture => {
+                        if let Some(gesture) = update.gesture {
+                            Self::dispatch_gesture(context, gesture);
+                        }
+                    }
+                    MultitouchInputMode::None => (),
+                }
+
+                Self::run_actions(context);
+            }
+
+            (update.is_primary, context.multitouch.map_touch_to_mouse())
+        });
+
+        if is_primary && map_touch_to_mouse {
+            self.handle_event(match phase {
+                TouchPhase::Began => PlayerEvent::MouseDown { x, y },
+                TouchPhase::Moved => PlayerEvent::MouseMove { x, y },
+                TouchPhase::Ended | TouchPhase::Cancelled => PlayerEvent::MouseUp { x, y },
+            });
+        }
+    }
+
+    /// Dispatch the events for a gesture to the object under its center.
+    fn dispatch_gesture<'gc>(context: &mut UpdateContext<'_, 'gc, '_>, gesture: Gesture) {
+        let (center, events) = match gesture {
+            Gesture::Transform {
+                phase,
+                center,
+                scale,
+                rotation,
+                offset,
+            } => {
+                // Between beginning and ending, only the parts of the gesture
+                // that changed are dispatched.
+                let is_update = phase == GesturePhase::Update;
+                let mut events = vec![];
+                if !is_update || scale != 1.0 {
+                    events.push(("gestureZoom", phase, (scale, scale), 0.0, (0.0, 0.0)));
+                }
+                if !is_update || rotation != 0.0 {
+                    events.push(("gestureRotate", phase, (1.0, 1.0), rotation, (0.0, 0.0)));
+                }
+                if !is_update || offset != (0.0, 0.0) {
+                    events.push(("gesturePan", phase, (1.0, 1.0), 0.0, offset));
+                }
+                (center, events)
+            }
+            Gesture::TwoFingerTap { center } => {
+                let object = Self::object_under_point(context, center, false);
+                let gesture_event_class = context.avm2.classes().gestureevent;
+                let args = |local: (f64, f64)| {
+                    vec![
+                        "gestureTwoFingerTap".into(),
+                        true.into(),
+                        false.into(),
+                        "all".into(),
+                        local.0.into(),
+                        local.1.into(),
+                    ]
+                };
+                if let Err(e) = Self::dispatch_avm2_pointer_event(
+                    context,
+                    gesture_event_class,
+                    args,
+                    object,
+                    center,
+                ) {
+                    log::error!("Error dispatching gestureTwoFingerTap event: {}", e);
+                }
+                return;
+            }
+        };
+
+        let object = Self::object_under_point(context, center, false);
+        for (event_type, phase, (scale_x, scale_y), rotation, (offset_x, offset_y)) in events {
+            let transform_gesture_event_class = context.avm2.classes().transformgestureevent;
+            let args = |local: (f64, f64)| {
+                vec![
+                    event_type.into(),
+                    true.into(),
+                    false.into(),
+                    phase.name().into(),
+                    local.0.into(),
+                    local.1.into(),
+                    scale_x.into(),
+                    scale_y.into(),
+                    rotation.into(),
+                    offset_x.into(),
+                    offset_y.into(),
+                ]
+            };
+            if let Err(e) = Self::dispatch_avm2_pointer_event(
+                context,
+                transform_gesture_event_class,
+                args,
+                object,
+                center,
+            ) {
+                log::error!("Error dispatching {} event: {}", event_type, e);
+            }
+        }
+    }
+
+    /// Dispatch an event about a point on the stage to the object under it,
+    /// or the stage if there is none.
+    ///
+    /// The event is constructed with the arguments given by `args`, from
+    /// the point's position local to the object in pixels. Its `stageX` and
+    /// `stageY` are then set to the point's position on the stage.
+    fn dispatch_avm2_pointer_event<'gc>(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        event_class: Avm2Object<'gc>,
+        args: impl FnOnce((f64, f64)) -> Vec<Avm2Value<'gc>>,
+        object: Option<DisplayObject<'gc>>,
+        position: (Twips, Twips),
+    ) -> Result<(), crate::avm2::Error> {
+        let (target, local) = match object {
+            Some(object) => (object.object2(), object.global_to_local(position)),
+            None => (context.stage.object2(), position),
+        };
+        let target = match target {
+            Avm2Value::Object(target) => target,
+            _ => return Ok(()),
+        };
+
+        let mut activation = Avm2Activation::from_nothing(context.reborrow());
+        let mut event = event_class.construct(
+            &mut activation,
+            &args((local.0.to_pixels(), local.1.to_pixels())),
+        )?;
+        event.set_property(
+            event,
+            &Avm2QName::new(Avm2Namespace::public(), "stageX"),
+            position.0.to_pixels().into(),
+            &mut activation,
+        )?;
+        event.set_property(
+            event,
+            &Avm2QName::new(Avm2Namespace::public(), "stageY"),
+            position.1.to_pixels().into(),
+            &mut activation,
+        )?;
+        Avm2::dispatch_event_object(&mut activation.context, event, target)?;
+
+        Ok(())
+    }
+
     /// Updates the hover state of buttons.
     fn update_mouse_state(&mut self, event: Option<&PlayerEvent>) -> bool {
-        let inverse_view_matrix =
-            self.mutate_with_update_context(|context| context.stage.inverse_view_matrix());
-
         // Update mouse state based on event type.
         let mut is_mouse_down = self.is_mouse_down;
         let mut new_mouse_pos = None;
@@ -1052,7 +1944,8 @@ impl Player {
             _ => return false,
         }
         if let Some((x, y)) = new_mouse_pos {
-            self.mouse_pos = inverse_view_matrix * (Twips::from_pixels(x), Twips::from_pixels(y))
+            self.mouse_pos =
+                self.mutate_with_update_context(|context| context.stage.view_to_stage((x, y)));
         }
         let is_mouse_button_changed = self.is_mouse_down != is_mouse_down;
         self.is_mouse_down = is_mouse_down;
@@ -1061,18 +1954,27 @@ impl Player {
         // Determine the display object the mouse is hovering over.
         // Search through levels from top-to-bottom, returning the first display object that is under the mouse.
         let needs_render = self.mutate_with_update_context(|context| {
-            let new_over_object = context
-                .stage
-                .iter_depth_list()
-                .rev()
-                .filter_map(|(_depth, level)| {
-                    level.mouse_pick(context, *context.mouse_position, true)
-                })
-                .next();
+            // AVM2 delivers mouse events to any interactive object, not just those acting as
+            // buttons.
+            let is_avm2 = context.swf.avm_type() == AvmType::Avm2;
+            let mouse_position = *context.mouse_position;
+            let new_over_object = Self::object_under_point(context, mouse_position, !is_avm2);
 
             let mut events: smallvec::SmallVec<[(DisplayObject<'_>, ClipEvent); 2]> =
                 Default::default();
 
+            // AVM2 mouse events, as (target, event type, bubbles, related object).
+            // A target of `None` means the event is dispatched on the stage.
+            #[allow(clippy::type_complexity)]
+            let mut avm2_events: smallvec::SmallVec<
+                [(
+                    Option<DisplayObject<'_>>,
+                    &'static str,
+                    bool,
+                    Option<DisplayObject<'_>>,
+                ); 4],
+            > = Default::default();
+
             // Cancel hover if an object is removed from the stage.
             if let Some(hovered) = context.mouse_over_object {
                 if hovered.removed() {
@@ -1088,6 +1990,36 @@ impl Player {
             let cur_over_object = context.mouse_over_object;
             // Check if a new object has been hovered over.
             if !DisplayObject::option_ptr_eq(cur_over_object, new_over_object) {
+                // AVM2 hover events fire regardless of the mouse button state.
+                // `rollOut` and `rollOver` fire on every ancestor that the mouse left or entered.
+                if is_avm2 {
+                    let cur_ancestors = Self::display_ancestors(cur_over_object);
+                    let new_ancestors = Self::display_ancestors(new_over_object);
+
+                    if cur_over_object.is_some() {
+                        avm2_events.push((cur_over_object, "mouseOut", true, new_over_object));
+                    }
+                    for object in cur_ancestors.iter() {
+                        if !new_ancestors
+                            .iter()
+                            .any(|o| DisplayObject::ptr_eq(*o, *object))
+                        {
+                            avm2_events.push((Some(*object), "rollOut", false, new_over_object));
+                        }
+                    }
+                    if new_over_object.is_some() {
+                        avm2_events.push((new_over_object, "mouseOver", true, cur_over_object));
+                    }
+                    for object in new_ancestors.iter().rev() {
+                        if !cur_ancestors
+                            .iter()
+                            .any(|o| DisplayObject::ptr_eq(*o, *object))
+                        {
+                            avm2_events.push((Some(*object), "rollOver", false, cur_over_object));
+                        }
+                    }
+                }
+
                 // If the mouse button is down, the object the user clicked on grabs the focus
                 // and fires "drag" events. Other objects are ignroed.
                 if is_mouse_down {
@@ -1122,9 +2054,24 @@ impl Player {
             }
             context.mouse_over_object = new_over_object;
 
+            if is_avm2 && matches!(event, Some(PlayerEvent::MouseMove { .. })) {
+                avm2_events.push((new_over_object, "mouseMove", true, None));
+            }
+
+            // Dragging across a text field after pressing on it extends the selection.
+            let mut is_selecting = false;
+            if is_mouse_down && !is_mouse_button_changed && new_mouse_pos.is_some() {
+                if let Some(text) = context.mouse_down_object.and_then(|o| o.as_edit_text()) {
+                    text.drag_selection(context);
+                    is_selecting = true;
+                }
+            }
+
             // Handle presses and releases.
             if is_mouse_button_changed {
                 if is_mouse_down {
+                    avm2_events.push((context.mouse_over_object, "mouseDown", true, None));
+
                     // Pressed on a hovered object.
                     if let Some(over_object) = context.mouse_over_object {
                         events.push((over_object, ClipEvent::Press));
@@ -1135,6 +2082,12 @@ impl Player {
                         context.mouse_down_object,
                         context.mouse_over_object,
                     );
+
+                    avm2_events.push((context.mouse_over_object, "mouseUp", true, None));
+                    if released_inside {
+                        avm2_events.push((context.mouse_over_object, "click", true, None));
+                    }
+
                     if released_inside {
                         // Released inside the clicked object.
                         if let Some(down_object) = context.mouse_down_object {
@@ -1159,7 +2112,7 @@ impl Player {
 
             // Fire any pending mouse events.
             let needs_render = if events.is_empty() {
-                false
+                is_selecting
             } else {
                 for (object, event) in events {
                     if !object.removed() {
@@ -1168,17 +2121,102 @@ impl Player {
                 }
                 true
             };
+
+            if is_avm2 {
+                for (object, event_type, bubbles, related_object) in avm2_events {
+                    let target = match object {
+                        Some(object) if object.removed() => continue,
+                        Some(object) => object,
+                        None => context.stage.into(),
+                    };
+
+                    if let Err(e) = Self::dispatch_avm2_mouse_event(
+                        context,
+                        event_type,
+                        bubbles,
+                        target,
+                        related_object,
+                        is_mouse_down,
+                    ) {
+                        log::error!(
+                            "Encountered AVM2 error when dispatching {} event: {}",
+                            event_type,
+                            e
+                        );
+                    }
+                }
+            }
+
             Self::run_actions(context);
             needs_render
         });
 
         // Update mouse cursor if it has changed.
-        if new_cursor != self.mouse_cursor {
-            self.mouse_cursor = new_cursor;
-            self.ui.set_mouse_cursor(new_cursor)
+        let hover_changed = new_cursor != self.mouse_cursor;
+        self.mouse_cursor = new_cursor;
+        self.update_mouse_cursor(hover_changed);
+
+        // A cursor drawn by the player has to follow the mouse.
+        needs_render || (new_mouse_pos.is_some() && self.cursor_overlay.is_some())
+    }
+
+    /// Show the cursor that the movie has chosen, if it has changed.
+    ///
+    /// `hover_changed` is whether the cursor for the object under the mouse
+    /// has changed, which is shown unless the movie chose another.
+    fn update_mouse_cursor(&mut self, hover_changed: bool) {
+        let setting_changed = self.mouse_cursors.take_changed();
+        match self.mouse_cursors.setting() {
+            CursorSetting::Auto if hover_changed || setting_changed => {
+                self.ui.set_mouse_cursor(self.mouse_cursor);
+            }
+            CursorSetting::Builtin(cursor) if setting_changed => {
+                self.ui.set_mouse_cursor(*cursor);
+            }
+            _ => (),
+        }
+
+        if !setting_changed {
+            return;
+        }
+
+        let custom_cursor = self.mouse_cursors.custom_cursor();
+        let is_native = self.ui.set_custom_mouse_cursor(custom_cursor.as_deref());
+        self.cursor_overlay = match custom_cursor {
+            Some(cursor) if !is_native => match self.cursor_overlay.take() {
+                Some((overlay, bitmap)) if Rc::ptr_eq(&overlay, &cursor) => Some((overlay, bitmap)),
+                _ => match self.renderer.register_bitmap_raw(
+                    cursor.width,
+                    cursor.height,
+                    cursor.rgba.clone(),
+                ) {
+                    Ok(bitmap) => Some((cursor, bitmap)),
+                    Err(e) => {
+                        log::error!("Unable to draw custom mouse cursor: {}", e);
+                        None
+                    }
+                },
+            },
+            _ => None,
+        };
+        self.needs_render = true;
+    }
+
+    /// Collect a display object and all of it's ancestors below the stage,
+    /// innermost first.
+    fn display_ancestors(object: Option<DisplayObject<'_>>) -> Vec<DisplayObject<'_>> {
+        let mut ancestors = Vec::new();
+        let mut current = object;
+        while let Some(object) = current {
+            if object.as_stage().is_some() {
+                break;
+            }
+
+            ancestors.push(object);
+            current = object.parent();
         }
 
-        needs_render
+        ancestors
     }
 
     /// Preload the first movie in the player.
@@ -1232,19 +2270,38 @@ impl Player {
                     stage.frame_constructed(context);
                     stage.run_frame_avm2(context);
                     stage.run_frame_scripts(context);
+                    Avm2::run_workers(context);
                 }
             }
             context.update_sounds();
         });
+        self.update_mouse_cursor(false);
         self.needs_render = true;
     }
 
     pub fn render(&mut self) {
         let (renderer, ui, transform_stack) =
             (&mut self.renderer, &mut self.ui, &mut self.transform_stack);
+        let mouse_pos = self.mouse_pos;
+        let cursor_overlay = self
+            .cursor_overlay
+            .as_ref()
+            .filter(|_| ui.mouse_visible())
+            .map(|(cursor, bitmap)| (cursor.hot_spot, *bitmap));
 
         self.gc_arena.mutate(|_gc_context, gc_root| {
             let root_data = gc_root.0.read();
+
+            // Custom cursors are drawn at their own size, wherever the mouse is on screen.
+            let cursor_overlay = cursor_overlay.map(|((hot_x, hot_y), bitmap)| {
+                let (x, y) = *root_data.stage.matrix() * mouse_pos;
+                let matrix = Matrix::translate(
+                    x - Twips::from_pixels(hot_x.into()),
+                    y - Twips::from_pixels(hot_y.into()),
+                );
+                (bitmap, matrix)
+            });
+
             let mut render_context = RenderContext {
                 renderer: renderer.deref_mut(),
                 ui: ui.deref_mut(),
@@ -1253,6 +2310,8 @@ impl Player {
                 stage: root_data.stage,
                 clip_depth_stack: vec![],
                 allow_mask: true,
+                cursor_overlay,
+                is_offscreen: false,
             };
 
             root_data.stage.render(&mut render_context);
@@ -1420,13 +2479,21 @@ impl Player {
                     if let Err(e) =
                         Avm2::run_stack_frame_for_callable(callable, reciever, &args[..], context)
                     {
-                        log::error!("Unhandled AVM2 exception in event handler: {}", e);
+                        Avm2::report_uncaught_error(
+                            context,
+                            e,
+                            "Unhandled AVM2 exception in event handler",
+                        );
                     }
                 }
 
                 ActionType::Event2 { event, target } => {
                     if let Err(e) = Avm2::dispatch_event(context, event, target) {
-                        log::error!("Unhandled AVM2 exception in event handler: {}", e);
+                        Avm2::report_uncaught_error(
+                            context,
+                            e,
+                            "Unhandled AVM2 exception in event handler",
+                        );
                     }
                 }
             }
@@ -1452,7 +2519,12 @@ impl Player {
             mouse_position,
             player,
             system_properties,
+            security,
+            mouse_cursors,
+            multitouch,
+            game_input,
             instance_counter,
+            display_list_generation,
             storage,
             locale,
             logging,
@@ -1473,7 +2545,12 @@ impl Player {
             &self.mouse_pos,
             self.self_reference.clone(),
             &mut self.system,
+            &mut self.security,
+            &mut self.mouse_cursors,
+            &mut self.multitouch,
+            &mut self.game_input,
             &mut self.instance_counter,
+            &mut self.display_list_generation,
             self.storage.deref_mut(),
             self.locale.deref_mut(),
             self.log.deref_mut(),
@@ -1499,8 +2576,12 @@ impl Player {
                 drag_object,
                 load_manager,
                 shared_objects,
+                avm2_shared_objects,
                 unbound_text_fields,
                 timers,
+                sockets,
+                local_connections,
+                net_connections,
                 current_context_menu,
                 external_interface,
                 audio_manager,
@@ -1525,14 +2606,23 @@ impl Player {
                 player,
                 load_manager,
                 system: system_properties,
+                security,
+                mouse_cursors,
+                multitouch,
+                game_input,
                 instance_counter,
+                display_list_generation,
                 storage,
                 locale,
                 log: logging,
                 video,
                 shared_objects,
+                avm2_shared_objects,
                 unbound_text_fields,
                 timers,
+                sockets,
+                local_connections,
+                net_connections,
                 current_context_menu,
                 needs_render,
                 avm1,
@@ -1576,13 +2666,18 @@ impl Player {
     }
 
     /// Loads font data from the given buffer.
-    /// The buffer should be the `DefineFont3` info for the tag.
-    /// The tag header should not be included.
+    /// The buffer should either be a TrueType or OpenType font file, or the
+    /// `DefineFont3` info for the tag. The tag header should not be included.
     pub fn load_device_font<'gc>(
         gc_context: gc_arena::MutationContext<'gc, '_>,
         data: &[u8],
         renderer: &mut dyn RenderBackend,
     ) -> Result<crate::font::Font<'gc>, Error> {
+        if crate::font::is_font_file(data) {
+            let tag = crate::font::swf_font_from_font_file(data)?;
+            return crate::font::Font::from_swf_tag(gc_context, renderer, &tag, swf::UTF_8);
+        }
+
         let mut reader = swf::read::Reader::new(data, 8);
         let device_font = crate::font::Font::from_swf_tag(
             gc_context,
@@ -1632,6 +2727,9 @@ impl Player {
             for so in shared_objects.values() {
                 let _ = crate::avm1::globals::shared_object::flush(&mut activation, *so, &[]);
             }
+            drop(activation);
+
+            Avm2::flush_shared_objects(context);
         });
     }
 
@@ -1642,12 +2740,27 @@ impl Player {
             self.mutate_with_update_context(|context| Timers::update_timers(context, dt));
     }
 
+    /// Dispatch the events of sockets opened by the movie.
+    pub fn update_sockets(&mut self) {
+        self.mutate_with_update_context(|context| Sockets::update_sockets(context));
+    }
+
+    /// Deliver the messages sent between `LocalConnection`s.
+    pub fn update_local_connections(&mut self) {
+        self.mutate_with_update_context(|context| {
+            LocalConnections::update_local_connections(context)
+        });
+    }
+
     /// Returns whether this player consumes mouse wheel events.
     /// Used by web to prevent scrolling.
     pub fn should_prevent_scrolling(&mut self) -> bool {
         self.mutate_with_update_context(|context| context.avm1.has_mouse_listener())
     }
 
+    /// Answer `ExternalInterface` calls and `fscommand`s from the movie with
+    /// `provider`, such as a `FunctionProvider` on platforms without a page to
+    /// call into.
     pub fn add_external_interface(&mut self, provider: Box<dyn ExternalInterfaceProvider>) {
         self.mutate_with_update_context(|context| {
             context.external_interface.add_provider(provider)
@@ -1679,6 +2792,33 @@ impl Player {
     pub fn set_max_execution_duration(&mut self, max_execution_duration: Duration) {
         self.max_execution_duration = max_execution_duration
     }
+
+    pub fn frame_budget(&self) -> Option<Duration> {
+        self.frame_budget
+    }
+
+    /// Limit how long a `tick` spends running catch-up frames.
+    ///
+    /// This does not split up long frames; see the `frame_budget` field.
+    pub fn set_frame_budget(&mut self, frame_budget: Option<Duration>) {
+        self.frame_budget = frame_budget
+    }
+}
+
+/// Collect the display objects under `object` whose text `Player::find_text`
+/// searches, depth first.
+fn collect_searchable_text<'gc>(object: DisplayObject<'gc>, out: &mut Vec<DisplayObject<'gc>>) {
+    if object.as_text().is_some() {
+        out.push(object);
+    } else if let Some(edit_text) = object.as_edit_text() {
+        if !edit_text.is_password() {
+            out.push(object);
+        }
+    } else if let Some(container) = object.as_container() {
+        for child in container.iter_render_list() {
+            collect_searchable_text(child, out);
+        }
+    }
 }
 
 #[derive(Collect)]
@@ -0,0 +1,664 @@
+//! Flash Remoting, used by `NetConnection.call` in AVM1 and AVM2.
+//!
+//! A connection to an HTTP gateway sends each call as an AMF0 packet holding
+//! a single message, whose target is the remote method and whose body is an
+//! array of the arguments. The gateway answers with a message targeting the
+//! response URI of the call followed by `/onResult` or `/onStatus`, which is
+//! passed on to the responder of the call.
+//!
+//! AVM2 connections encode their values as AMF3 by default, in which case
+//! each argument is written as AMF3 inside the AMF0 array, and the gateway
+//! will usually answer with an AMF3 body too.
+
+mod packet;
+
+pub use packet::AmfValue;
+
+use crate::avm1::activation::{
+    Activation as Avm1Activation, ActivationIdentifier as Avm1ActivationIdentifier,
+};
+use crate::avm1::property::Attribute as Avm1Attribute;
+use crate::avm1::{
+    ArrayObject as Avm1ArrayObject, Object as Avm1Object, ScriptObject as Avm1ScriptObject,
+    TObject as _, Value as Avm1Value,
+};
+use crate::avm2::{
+    responder_callback, Activation as Avm2Activation, Amf3Reader, Amf3Writer,
+    ArrayObject as Avm2ArrayObject, ArrayStorage, Avm2, Namespace, Object as Avm2Object, QName,
+    TObject as _, Value as Avm2Value,
+};
+use crate::backend::navigator::RequestOptions;
+use crate::context::UpdateContext;
+use crate::loader::Error as LoaderError;
+use crate::string::AvmString;
+use gc_arena::Collect;
+use packet::{Header, Message, Packet};
+
+/// The AMF version of packets that only contain AMF0 values.
+pub const AMF0_VERSION: u16 = 0;
+
+/// The AMF version of packets that may contain AMF3 values.
+pub const AMF3_VERSION: u16 = 3;
+
+/// A `NetConnection`, or the responder of a call made with one.
+#[derive(Clone, Copy, Collect)]
+#[collect(no_drop)]
+pub enum NetConnectionObject<'gc> {
+    Avm1(Avm1Object<'gc>),
+    Avm2(Avm2Object<'gc>),
+}
+
+impl<'gc> NetConnectionObject<'gc> {
+    fn is(&self, other: NetConnectionObject<'gc>) -> bool {
+        match (*self, other) {
+            (NetConnectionObject::Avm1(a), NetConnectionObject::Avm1(b)) => {
+                Avm1Object::ptr_eq(a, b)
+            }
+            (NetConnectionObject::Avm2(a), NetConnectionObject::Avm2(b)) => {
+                Avm2Object::ptr_eq(a, b)
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+struct NetConnection<'gc> {
+    object: NetConnectionObject<'gc>,
+
+    /// The URL of the gateway calls are sent to, or `None` for a connection
+    /// that isn't to a server.
+    #[collect(require_static)]
+    gateway: Option<String>,
+
+    /// Headers added with `addHeader`, which are sent with every call.
+    #[collect(require_static)]
+    headers: Vec<Header>,
+}
+
+/// All open `NetConnection`s.
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct NetConnections<'gc> {
+    connections: Vec<NetConnection<'gc>>,
+
+    /// The number of calls that have been made, which is used to give each
+    /// one a distinct response URI.
+    call_count: u32,
+}
+
+impl<'gc> NetConnections<'gc> {
+    /// Open a connection, closing any it already had.
+    ///
+    /// A connection without a gateway is considered connected, but can't make
+    /// calls.
+    pub fn connect(&mut self, object: NetConnectionObject<'gc>, gateway: Option<String>) {
+        self.close(object);
+        self.connections.push(NetConnection {
+            object,
+            gateway,
+            headers: Vec::new(),
+        });
+    }
+
+    /// Close a connection. Returns `false` if it wasn't open.
+    pub fn close(&mut self, object: NetConnectionObject<'gc>) -> bool {
+        let len = self.connections.len();
+        self.connections
+            .retain(|connection| !connection.object.is(object));
+        self.connections.len() != len
+    }
+
+    fn get(&self, object: NetConnectionObject<'gc>) -> Option<&NetConnection<'gc>> {
+        self.connections
+            .iter()
+            .find(|connection| connection.object.is(object))
+    }
+
+    fn get_mut(&mut self, object: NetConnectionObject<'gc>) -> Option<&mut NetConnection<'gc>> {
+        self.connections
+            .iter_mut()
+            .find(|connection| connection.object.is(object))
+    }
+
+    /// Whether a connection is open to something other than a gateway.
+    ///
+    /// Remoting is connectionless, so a connection to a gateway is never
+    /// considered connected.
+    pub fn is_connected(&self, object: NetConnectionObject<'gc>) -> bool {
+        self.get(object)
+            .map_or(false, |connection| connection.gateway.is_none())
+    }
+
+    /// The URL of the gateway of a connection.
+    pub fn gateway(&self, object: NetConnectionObject<'gc>) -> Option<&str> {
+        self.get(object)
+            .and_then(|connection| connection.gateway.as_deref())
+    }
+
+    /// Add a header to be sent with every call, replacing any header with the
+    /// same name.
+    ///
+    /// Returns `false` if the connection isn't open.
+    pub fn add_header(
+        &mut self,
+        object: NetConnectionObject<'gc>,
+        name: String,
+        must_understand: bool,
+        value: AmfValue,
+    ) -> bool {
+        let connection = match self.get_mut(object) {
+            Some(connection) => connection,
+            None => return false,
+        };
+
+        connection.headers.retain(|header| header.name != name);
+        connection.headers.push(Header {
+            name,
+            must_understand,
+            value,
+        });
+        true
+    }
+
+    /// Call a method on the gateway of a connection.
+    ///
+    /// Returns `false` if the connection has no gateway.
+    pub fn call(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        object: NetConnectionObject<'gc>,
+        responder: Option<NetConnectionObject<'gc>>,
+        command: &str,
+        args: Vec<AmfValue>,
+        version: u16,
+    ) -> bool {
+        let connections = &mut context.net_connections;
+        connections.call_count += 1;
+        let response_uri = format!("/{}", connections.call_count);
+
+        let connection = match connections.get(object) {
+            Some(connection) => connection,
+            None => return false,
+        };
+        let gateway = match &connection.gateway {
+            Some(gateway) => gateway.clone(),
+            None => return false,
+        };
+
+        let packet = Packet {
+            version,
+            headers: connection.headers.clone(),
+            messages: vec![Message {
+                target: command.to_string(),
+                response: response_uri.clone(),
+                value: AmfValue::StrictArray(args),
+            }],
+        };
+        let options =
+            RequestOptions::post(Some((packet.encode(), "application/x-amf".to_string())));
+
+        let fetch = context
+            .security
+            .fetch_data(context.navigator, context.swf, &gateway, options);
+        let process = context.load_manager.load_net_connection_response(
+            context.player.clone().unwrap(),
+            object,
+            responder,
+            response_uri,
+            fetch,
+        );
+        context.navigator.spawn_future(process);
+
+        true
+    }
+
+    /// Pass the response to a call on to its responder.
+    pub fn handle_response(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        object: NetConnectionObject<'gc>,
+        responder: Option<NetConnectionObject<'gc>>,
+        response_uri: &str,
+        data: Result<Vec<u8>, LoaderError>,
+    ) -> Result<(), LoaderError> {
+        let packet = match data.ok().and_then(|data| Packet::decode(&data)) {
+            Some(packet) => packet,
+            None => return Self::report_call_failed(context, object),
+        };
+
+        // Gateways can move the connection to another URL, which is usually
+        // used to add a session ID to it.
+        if let Some(connection) = context.net_connections.get_mut(object) {
+            for header in &packet.headers {
+                match (header.name.as_str(), &header.value, &mut connection.gateway) {
+                    ("AppendToGatewayUrl", AmfValue::String(suffix), Some(gateway)) => {
+                        gateway.push_str(suffix)
+                    }
+                    ("ReplaceGatewayUrl", AmfValue::String(url), Some(gateway)) => {
+                        *gateway = url.clone()
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for message in packet.messages {
+            let is_result = match message.target.strip_prefix(response_uri) {
+                Some("/onResult") => true,
+                Some("/onStatus") => false,
+                _ => continue,
+            };
+
+            match object {
+                NetConnectionObject::Avm1(object) => {
+                    let responder = match responder {
+                        Some(NetConnectionObject::Avm1(responder)) => Some(responder),
+                        _ => None,
+                    };
+                    Self::deliver_avm1(context, object, responder, is_result, &message.value)?;
+                }
+                NetConnectionObject::Avm2(_) => {
+                    if let Some(NetConnectionObject::Avm2(responder)) = responder {
+                        Self::deliver_avm2(context, responder, is_result, &message.value)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn deliver_avm1(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        object: Avm1Object<'gc>,
+        responder: Option<Avm1Object<'gc>>,
+        is_result: bool,
+        value: &AmfValue,
+    ) -> Result<(), LoaderError> {
+        let mut activation = avm1_activation(context, "[NetConnection]");
+        let value = amf_to_avm1(&mut activation, value);
+
+        match (responder, is_result) {
+            (Some(responder), true) => {
+                responder.call_method("onResult", &[value], &mut activation)?;
+            }
+            (Some(responder), false) => {
+                responder.call_method("onStatus", &[value], &mut activation)?;
+            }
+            // Without a responder, errors are reported to the connection.
+            (None, false) => {
+                object.call_method("onStatus", &[value], &mut activation)?;
+            }
+            (None, true) => {}
+        }
+
+        Ok(())
+    }
+
+    fn deliver_avm2(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        responder: Avm2Object<'gc>,
+        is_result: bool,
+        value: &AmfValue,
+    ) -> Result<(), LoaderError> {
+        let mut activation = Avm2Activation::from_nothing(context.reborrow());
+        let value = amf_to_avm2(&mut activation, value)?;
+
+        if let Some(callback) = responder_callback(&mut activation, responder, is_result)? {
+            callback.call(None, &[value], &mut activation, None)?;
+        }
+
+        Ok(())
+    }
+
+    /// Tell a connection that a call failed to reach its gateway.
+    fn report_call_failed(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        object: NetConnectionObject<'gc>,
+    ) -> Result<(), LoaderError> {
+        let gateway = context
+            .net_connections
+            .gateway(object)
+            .unwrap_or_default()
+            .to_string();
+
+        match object {
+            NetConnectionObject::Avm1(object) => {
+                let mut activation = avm1_activation(context, "[NetConnection]");
+                let info = avm1_net_status_info(
+                    &mut activation,
+                    "NetConnection.Call.Failed",
+                    "error",
+                    Some(&gateway),
+                );
+                object.call_method("onStatus", &[info.into()], &mut activation)?;
+            }
+            NetConnectionObject::Avm2(object) => {
+                let mut activation = Avm2Activation::from_nothing(context.reborrow());
+                let event = avm2_net_status_event(
+                    &mut activation,
+                    "NetConnection.Call.Failed",
+                    "error",
+                    Some(&gateway),
+                )?;
+                drop(activation);
+
+                Avm2::dispatch_event_object(context, event, object)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn avm1_activation<'a, 'gc, 'gc_context>(
+    context: &'a mut UpdateContext<'_, 'gc, 'gc_context>,
+    name: &str,
+) -> Avm1Activation<'a, 'gc, 'gc_context> {
+    let root_clip = context.stage.root_clip();
+    let swf_version = context.swf.version();
+    let globals = context.avm1.global_object_cell();
+
+    Avm1Activation::from_nothing(
+        context.reborrow(),
+        Avm1ActivationIdentifier::root(name.to_owned()),
+        swf_version,
+        globals,
+        root_clip,
+    )
+}
+
+/// Build the info object passed to `NetConnection.onStatus` in AVM1.
+pub fn avm1_net_status_info<'gc>(
+    activation: &mut Avm1Activation<'_, 'gc, '_>,
+    code: &str,
+    level: &str,
+    details: Option<&str>,
+) -> Avm1Object<'gc> {
+    let gc_context = activation.context.gc_context;
+    let info = Avm1ScriptObject::object(
+        gc_context,
+        Some(activation.context.avm1.prototypes().object),
+    );
+    info.define_value(
+        gc_context,
+        "code",
+        AvmString::new(gc_context, code).into(),
+        Avm1Attribute::empty(),
+    );
+    info.define_value(
+        gc_context,
+        "level",
+        AvmString::new(gc_context, level).into(),
+        Avm1Attribute::empty(),
+    );
+    if let Some(details) = details {
+        info.define_value(
+            gc_context,
+            "details",
+            AvmString::new(gc_context, details).into(),
+            Avm1Attribute::empty(),
+        );
+    }
+    info.into()
+}
+
+/// Build a `netStatus` event for an AVM2 `NetConnection`.
+pub fn avm2_net_status_event<'gc>(
+    activation: &mut Avm2Activation<'_, 'gc, '_>,
+    code: &str,
+    level: &str,
+    details: Option<&str>,
+) -> Result<Avm2Object<'gc>, crate::avm2::Error> {
+    let mut info = activation
+        .avm2()
+        .classes()
+        .object
+        .construct(activation, &[])?;
+    let gc_context = activation.context.gc_context;
+    let mut fields = vec![("code", code), ("level", level)];
+    if let Some(details) = details {
+        fields.push(("details", details));
+    }
+    for (name, value) in fields {
+        info.set_property(
+            info,
+            &QName::new(Namespace::public(), name),
+            AvmString::new(gc_context, value).into(),
+            activation,
+        )?;
+    }
+
+    let net_status_class = activation.avm2().classes().netstatusevent;
+    net_status_class.construct(
+        activation,
+        &["netStatus".into(), false.into(), false.into(), info.into()],
+    )
+}
+
+/// Convert an AVM1 value to be sent to a gateway.
+pub fn avm1_to_amf<'gc>(
+    activation: &mut Avm1Activation<'_, 'gc, '_>,
+    value: Avm1Value<'gc>,
+) -> AmfValue {
+    match value {
+        Avm1Value::Undefined => AmfValue::Undefined,
+        Avm1Value::Null => AmfValue::Null,
+        Avm1Value::Bool(b) => AmfValue::Bool(b),
+        Avm1Value::Number(n) => AmfValue::Number(n),
+        Avm1Value::String(s) => AmfValue::String(s.to_string()),
+        Avm1Value::Object(o) => {
+            if o.as_executable().is_some() || o.as_display_object().is_some() {
+                AmfValue::Undefined
+            } else if o.as_array_object().is_some() {
+                let length = o.length(activation).unwrap_or_default();
+                let values = (0..length)
+                    .map(|i| {
+                        let value = o.get_element(activation, i);
+                        avm1_to_amf(activation, value)
+                    })
+                    .collect();
+                AmfValue::StrictArray(values)
+            } else if let Some(date) = o.as_date_object() {
+                date.date_time().map_or(AmfValue::Null, |date_time| {
+                    AmfValue::Date(date_time.timestamp_millis() as f64)
+                })
+            } else if let Some(xml_node) = o.as_xml_node() {
+                xml_node
+                    .into_string(&mut |_| true)
+                    .map_or(AmfValue::Null, AmfValue::Xml)
+            } else {
+                let mut members = Vec::new();
+                // Reversed to match Flash Player's ordering.
+                for name in o.get_keys(activation).into_iter().rev() {
+                    if let Ok(value) = o.get(&name, activation) {
+                        members.push((name, avm1_to_amf(activation, value)));
+                    }
+                }
+                AmfValue::Object(members)
+            }
+        }
+    }
+}
+
+/// Convert a value received from a gateway to AVM1.
+///
+/// AVM1 can't read AMF3, so any AMF3 values are `undefined`.
+fn amf_to_avm1<'gc>(
+    activation: &mut Avm1Activation<'_, 'gc, '_>,
+    value: &AmfValue,
+) -> Avm1Value<'gc> {
+    let gc_context = activation.context.gc_context;
+    match value {
+        AmfValue::Number(n) => (*n).into(),
+        AmfValue::Bool(b) => (*b).into(),
+        AmfValue::String(s) => AvmString::new(gc_context, s.as_str()).into(),
+        AmfValue::Object(members)
+        | AmfValue::TypedObject(_, members)
+        | AmfValue::EcmaArray(members) => {
+            let object: Avm1Object<'gc> = if matches!(value, AmfValue::EcmaArray(_)) {
+                Avm1ArrayObject::empty(activation).into()
+            } else {
+                Avm1ScriptObject::object(
+                    gc_context,
+                    Some(activation.context.avm1.prototypes().object),
+                )
+                .into()
+            };
+            for (name, value) in members {
+                let value = amf_to_avm1(activation, value);
+                if let (true, Ok(index)) = (object.as_array_object().is_some(), name.parse()) {
+                    object.set_element(activation, index, value).ok();
+                } else {
+                    object.define_value(gc_context, name, value, Avm1Attribute::empty());
+                }
+            }
+            object.into()
+        }
+        AmfValue::Null => Avm1Value::Null,
+        AmfValue::Undefined | AmfValue::Amf3(_) => Avm1Value::Undefined,
+        AmfValue::StrictArray(values) => {
+            let values: Vec<_> = values
+                .iter()
+                .map(|value| amf_to_avm1(activation, value))
+                .collect();
+            Avm1ArrayObject::new(
+                gc_context,
+                activation.context.avm1.prototypes().array,
+                values,
+            )
+            .into()
+        }
+        AmfValue::Date(time) => {
+            let date_constructor = activation.context.avm1.prototypes().date_constructor;
+            date_constructor
+                .construct(activation, &[(*time).into()])
+                .unwrap_or(Avm1Value::Undefined)
+        }
+        AmfValue::Xml(content) => {
+            let xml_constructor = activation.context.avm1.prototypes().xml_constructor;
+            let content = AvmString::new(gc_context, content.as_str());
+            xml_constructor
+                .construct(activation, &[content.into()])
+                .unwrap_or(Avm1Value::Undefined)
+        }
+    }
+}
+
+/// Convert an AVM2 value to be sent to a gateway, as AMF3 if `version` is
+/// `AMF3_VERSION`.
+pub fn avm2_to_amf<'gc>(
+    activation: &mut Avm2Activation<'_, 'gc, '_>,
+    value: Avm2Value<'gc>,
+    version: u16,
+) -> Result<AmfValue, crate::avm2::Error> {
+    if version == AMF3_VERSION {
+        let bytearray_class = activation.avm2().classes().bytearray;
+        let target = bytearray_class.construct(activation, &[])?;
+        let mut writer = Amf3Writer::new(target);
+        writer.write_value(activation, value)?;
+        writer.finish(activation)?;
+
+        let data = target
+            .as_bytearray()
+            .map(|bytes| bytes.bytes().clone())
+            .unwrap_or_default();
+        return Ok(AmfValue::Amf3(data));
+    }
+
+    Ok(match value {
+        Avm2Value::Undefined => AmfValue::Undefined,
+        Avm2Value::Null => AmfValue::Null,
+        Avm2Value::Bool(b) => AmfValue::Bool(b),
+        Avm2Value::Number(n) => AmfValue::Number(n),
+        Avm2Value::Unsigned(u) => AmfValue::Number(u.into()),
+        Avm2Value::Integer(i) => AmfValue::Number(i.into()),
+        Avm2Value::String(s) => AmfValue::String(s.to_string()),
+        Avm2Value::Object(o) if o.as_executable().is_some() => AmfValue::Undefined,
+        Avm2Value::Object(o) => {
+            let values = o
+                .as_array_storage()
+                .map(|array| array.iter().collect::<Vec<_>>());
+            if let Some(values) = values {
+                let mut amf_values = Vec::with_capacity(values.len());
+                for value in values {
+                    let value = value.unwrap_or(Avm2Value::Undefined);
+                    amf_values.push(avm2_to_amf(activation, value, version)?);
+                }
+                AmfValue::StrictArray(amf_values)
+            } else {
+                let mut members = Vec::new();
+                let mut last_index = 0;
+                while let Some(index) = o.get_next_enumerant(last_index) {
+                    if let Some(name) = o.get_enumerant_name(index, activation) {
+                        if name.namespace().is_public() {
+                            let value = o.get_property(o, &name, activation)?;
+                            let value = avm2_to_amf(activation, value, version)?;
+                            members.push((name.local_name().to_string(), value));
+                        }
+                    }
+                    last_index = index;
+                }
+                AmfValue::Object(members)
+            }
+        }
+    })
+}
+
+/// Convert a value received from a gateway to AVM2.
+fn amf_to_avm2<'gc>(
+    activation: &mut Avm2Activation<'_, 'gc, '_>,
+    value: &AmfValue,
+) -> Result<Avm2Value<'gc>, crate::avm2::Error> {
+    let gc_context = activation.context.gc_context;
+    Ok(match value {
+        AmfValue::Number(n) => (*n).into(),
+        AmfValue::Bool(b) => (*b).into(),
+        AmfValue::String(s) | AmfValue::Xml(s) => AvmString::new(gc_context, s.as_str()).into(),
+        AmfValue::Object(members)
+        | AmfValue::TypedObject(_, members)
+        | AmfValue::EcmaArray(members) => {
+            let mut object = if matches!(value, AmfValue::EcmaArray(_)) {
+                Avm2ArrayObject::empty(activation)?
+            } else {
+                activation
+                    .avm2()
+                    .classes()
+                    .object
+                    .construct(activation, &[])?
+            };
+            for (name, value) in members {
+                let value = amf_to_avm2(activation, value)?;
+                let name = AvmString::new(gc_context, name.as_str());
+                object.set_property(
+                    object,
+                    &QName::new(Namespace::public(), name),
+                    value,
+                    activation,
+                )?;
+            }
+            object.into()
+        }
+        AmfValue::Null => Avm2Value::Null,
+        AmfValue::Undefined => Avm2Value::Undefined,
+        AmfValue::StrictArray(values) => {
+            let mut storage = Vec::with_capacity(values.len());
+            for value in values {
+                storage.push(Some(amf_to_avm2(activation, value)?));
+            }
+            Avm2ArrayObject::from_storage(activation, ArrayStorage::from_storage(storage))?.into()
+        }
+        // There is no `Date` class yet, so dates are read as their timestamp
+        // in milliseconds, like in AMF3.
+        AmfValue::Date(time) => (*time).into(),
+        AmfValue::Amf3(data) => {
+            let bytearray_class = activation.avm2().classes().bytearray;
+            let source = bytearray_class.construct(activation, &[])?;
+            if let Some(mut bytes) = source.as_bytearray_mut(gc_context) {
+                bytes.write_bytes(data)?;
+                bytes.set_position(0);
+            }
+            Amf3Reader::new(source).read_value(activation)?
+        }
+    })
+}
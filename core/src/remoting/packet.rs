@@ -0,0 +1,474 @@
+//! The AMF0 packets exchanged with a Flash Remoting gateway.
+
+const NUMBER_MARKER: u8 = 0x00;
+const BOOLEAN_MARKER: u8 = 0x01;
+const STRING_MARKER: u8 = 0x02;
+const OBJECT_MARKER: u8 = 0x03;
+const NULL_MARKER: u8 = 0x05;
+const UNDEFINED_MARKER: u8 = 0x06;
+const REFERENCE_MARKER: u8 = 0x07;
+const ECMA_ARRAY_MARKER: u8 = 0x08;
+const OBJECT_END_MARKER: u8 = 0x09;
+const STRICT_ARRAY_MARKER: u8 = 0x0A;
+const DATE_MARKER: u8 = 0x0B;
+const LONG_STRING_MARKER: u8 = 0x0C;
+const UNSUPPORTED_MARKER: u8 = 0x0D;
+const XML_DOCUMENT_MARKER: u8 = 0x0F;
+const TYPED_OBJECT_MARKER: u8 = 0x10;
+const AVMPLUS_MARKER: u8 = 0x11;
+
+/// The length written for a message body of unknown length.
+const UNKNOWN_LENGTH: u32 = 0xFFFF_FFFF;
+
+/// An AMF0 value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AmfValue {
+    Number(f64),
+    Bool(bool),
+    String(String),
+    Object(Vec<(String, AmfValue)>),
+
+    /// An object written along with the alias of its class.
+    TypedObject(String, Vec<(String, AmfValue)>),
+
+    Null,
+    Undefined,
+    EcmaArray(Vec<(String, AmfValue)>),
+    StrictArray(Vec<AmfValue>),
+
+    /// A date, in milliseconds since the epoch.
+    Date(f64),
+
+    Xml(String),
+
+    /// A value encoded as AMF3, following an AVM+ marker.
+    ///
+    /// AMF3 values don't carry their length, so one can only be read when it
+    /// makes up the rest of a message body.
+    Amf3(Vec<u8>),
+}
+
+/// A header of a packet, which applies to every message in it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Header {
+    pub name: String,
+    pub must_understand: bool,
+    pub value: AmfValue,
+}
+
+/// A call to a remote method, or the response to one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Message {
+    /// The method being called, or the URI that a response is for followed
+    /// by `/onResult` or `/onStatus`.
+    pub target: String,
+
+    /// The URI a response should be sent to.
+    pub response: String,
+
+    pub value: AmfValue,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Packet {
+    /// The AMF version of the client, which is 3 if any values may be
+    /// written as AMF3.
+    pub version: u16,
+
+    pub headers: Vec<Header>,
+    pub messages: Vec<Message>,
+}
+
+impl Packet {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut writer = Writer::default();
+        writer.write_u16(self.version);
+
+        writer.write_u16(self.headers.len() as u16);
+        for header in &self.headers {
+            writer.write_utf8(&header.name);
+            writer.buffer.push(header.must_understand as u8);
+            writer.write_with_length(&header.value);
+        }
+
+        writer.write_u16(self.messages.len() as u16);
+        for message in &self.messages {
+            writer.write_utf8(&message.target);
+            writer.write_utf8(&message.response);
+            writer.write_with_length(&message.value);
+        }
+
+        writer.buffer
+    }
+
+    /// Returns `None` if the data is not a valid packet.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let mut reader = Reader::new(data);
+        let version = reader.read_u16()?;
+
+        let header_count = reader.read_u16()?;
+        let mut headers = Vec::with_capacity(header_count.into());
+        for _ in 0..header_count {
+            let name = reader.read_utf8()?;
+            let must_understand = reader.read_u8()? != 0;
+            let value = reader.read_with_length()?;
+            headers.push(Header {
+                name,
+                must_understand,
+                value,
+            });
+        }
+
+        let message_count = reader.read_u16()?;
+        let mut messages = Vec::with_capacity(message_count.into());
+        for _ in 0..message_count {
+            let target = reader.read_utf8()?;
+            let response = reader.read_utf8()?;
+            let value = reader.read_with_length()?;
+            messages.push(Message {
+                target,
+                response,
+                value,
+            });
+        }
+
+        Some(Self {
+            version,
+            headers,
+            messages,
+        })
+    }
+}
+
+#[derive(Default)]
+struct Writer {
+    buffer: Vec<u8>,
+}
+
+impl Writer {
+    fn write_u16(&mut self, value: u16) {
+        self.buffer.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.buffer.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn write_utf8(&mut self, value: &str) {
+        self.write_u16(value.len() as u16);
+        self.buffer.extend_from_slice(value.as_bytes());
+    }
+
+    fn write_long_utf8(&mut self, value: &str) {
+        self.write_u32(value.len() as u32);
+        self.buffer.extend_from_slice(value.as_bytes());
+    }
+
+    /// Write a value preceded by its length.
+    fn write_with_length(&mut self, value: &AmfValue) {
+        let start = self.buffer.len();
+        self.write_u32(0);
+        self.write_value(value);
+        let length = (self.buffer.len() - start - 4) as u32;
+        self.buffer[start..start + 4].copy_from_slice(&length.to_be_bytes());
+    }
+
+    fn write_members(&mut self, members: &[(String, AmfValue)]) {
+        for (name, value) in members {
+            self.write_utf8(name);
+            self.write_value(value);
+        }
+        self.write_utf8("");
+        self.buffer.push(OBJECT_END_MARKER);
+    }
+
+    fn write_value(&mut self, value: &AmfValue) {
+        match value {
+            AmfValue::Number(value) => {
+                self.buffer.push(NUMBER_MARKER);
+                self.buffer.extend_from_slice(&value.to_be_bytes());
+            }
+            AmfValue::Bool(value) => {
+                self.buffer.push(BOOLEAN_MARKER);
+                self.buffer.push(*value as u8);
+            }
+            AmfValue::String(value) if value.len() > u16::MAX.into() => {
+                self.buffer.push(LONG_STRING_MARKER);
+                self.write_long_utf8(value);
+            }
+            AmfValue::String(value) => {
+                self.buffer.push(STRING_MARKER);
+                self.write_utf8(value);
+            }
+            AmfValue::Object(members) => {
+                self.buffer.push(OBJECT_MARKER);
+                self.write_members(members);
+            }
+            AmfValue::TypedObject(class_name, members) => {
+                self.buffer.push(TYPED_OBJECT_MARKER);
+                self.write_utf8(class_name);
+                self.write_members(members);
+            }
+            AmfValue::Null => self.buffer.push(NULL_MARKER),
+            AmfValue::Undefined => self.buffer.push(UNDEFINED_MARKER),
+            AmfValue::EcmaArray(members) => {
+                self.buffer.push(ECMA_ARRAY_MARKER);
+                self.write_u32(members.len() as u32);
+                self.write_members(members);
+            }
+            AmfValue::StrictArray(values) => {
+                self.buffer.push(STRICT_ARRAY_MARKER);
+                self.write_u32(values.len() as u32);
+                for value in values {
+                    self.write_value(value);
+                }
+            }
+            AmfValue::Date(time) => {
+                self.buffer.push(DATE_MARKER);
+                self.buffer.extend_from_slice(&time.to_be_bytes());
+                // The time zone is reserved, and always written as UTC.
+                self.write_u16(0);
+            }
+            AmfValue::Xml(value) => {
+                self.buffer.push(XML_DOCUMENT_MARKER);
+                self.write_long_utf8(value);
+            }
+            AmfValue::Amf3(data) => {
+                self.buffer.push(AVMPLUS_MARKER);
+                self.buffer.extend_from_slice(data);
+            }
+        }
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+
+    /// The end of the message body being read.
+    end: usize,
+
+    /// Complex values that have been read from the current body, which later
+    /// values may refer back to.
+    references: Vec<AmfValue>,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            position: 0,
+            end: data.len(),
+            references: Vec::new(),
+        }
+    }
+
+    fn read_bytes(&mut self, length: usize) -> Option<&'a [u8]> {
+        let bytes = self
+            .data
+            .get(self.position..self.position.checked_add(length)?)?;
+        self.position += length;
+        Some(bytes)
+    }
+
+    fn read_u8(&mut self) -> Option<u8> {
+        Some(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Option<u16> {
+        let bytes = self.read_bytes(2)?;
+        Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        let bytes = self.read_bytes(4)?;
+        Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_f64(&mut self) -> Option<f64> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.read_bytes(8)?);
+        Some(f64::from_be_bytes(bytes))
+    }
+
+    fn read_utf8(&mut self) -> Option<String> {
+        let length = self.read_u16()?;
+        let bytes = self.read_bytes(length.into())?;
+        Some(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    fn read_long_utf8(&mut self) -> Option<String> {
+        let length = self.read_u32()?;
+        let bytes = self.read_bytes(length as usize)?;
+        Some(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    /// Read a value preceded by its length.
+    fn read_with_length(&mut self) -> Option<AmfValue> {
+        let length = self.read_u32()?;
+        self.end = if length == UNKNOWN_LENGTH {
+            self.data.len()
+        } else {
+            self.position.checked_add(length as usize)?
+        };
+        self.references.clear();
+
+        let value = self.read_value()?;
+        if length != UNKNOWN_LENGTH {
+            self.position = self.end;
+        }
+        Some(value)
+    }
+
+    fn read_members(&mut self) -> Option<Vec<(String, AmfValue)>> {
+        let mut members = Vec::new();
+        loop {
+            let name = self.read_utf8()?;
+            if name.is_empty() && self.data.get(self.position) == Some(&OBJECT_END_MARKER) {
+                self.position += 1;
+                return Some(members);
+            }
+            members.push((name, self.read_value()?));
+        }
+    }
+
+    /// Read a complex value, making it available to later references.
+    fn read_referenced(
+        &mut self,
+        read: impl FnOnce(&mut Self) -> Option<AmfValue>,
+    ) -> Option<AmfValue> {
+        let index = self.references.len();
+        self.references.push(AmfValue::Undefined);
+        let value = read(self)?;
+        self.references[index] = value.clone();
+        Some(value)
+    }
+
+    fn read_value(&mut self) -> Option<AmfValue> {
+        let value = match self.read_u8()? {
+            NUMBER_MARKER => AmfValue::Number(self.read_f64()?),
+            BOOLEAN_MARKER => AmfValue::Bool(self.read_u8()? != 0),
+            STRING_MARKER => AmfValue::String(self.read_utf8()?),
+            OBJECT_MARKER => {
+                self.read_referenced(|reader| Some(AmfValue::Object(reader.read_members()?)))?
+            }
+            NULL_MARKER => AmfValue::Null,
+            UNDEFINED_MARKER | UNSUPPORTED_MARKER => AmfValue::Undefined,
+            REFERENCE_MARKER => {
+                let index = self.read_u16()?;
+                self.references.get(usize::from(index))?.clone()
+            }
+            ECMA_ARRAY_MARKER => self.read_referenced(|reader| {
+                // The count is only a hint, as the members are terminated
+                // like those of an object.
+                reader.read_u32()?;
+                Some(AmfValue::EcmaArray(reader.read_members()?))
+            })?,
+            STRICT_ARRAY_MARKER => self.read_referenced(|reader| {
+                let length = reader.read_u32()?;
+                let mut values = Vec::new();
+                for _ in 0..length {
+                    values.push(reader.read_value()?);
+                }
+                Some(AmfValue::StrictArray(values))
+            })?,
+            DATE_MARKER => {
+                let time = self.read_f64()?;
+                self.read_u16()?;
+                AmfValue::Date(time)
+            }
+            LONG_STRING_MARKER => AmfValue::String(self.read_long_utf8()?),
+            XML_DOCUMENT_MARKER => AmfValue::Xml(self.read_long_utf8()?),
+            TYPED_OBJECT_MARKER => self.read_referenced(|reader| {
+                let class_name = reader.read_utf8()?;
+                Some(AmfValue::TypedObject(class_name, reader.read_members()?))
+            })?,
+            AVMPLUS_MARKER => {
+                let data = self.data.get(self.position..self.end)?.to_vec();
+                self.position = self.end;
+                AmfValue::Amf3(data)
+            }
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packet_round_trip() {
+        let packet = Packet {
+            version: 0,
+            headers: vec![Header {
+                name: "Credentials".to_string(),
+                must_understand: false,
+                value: AmfValue::Object(vec![
+                    ("userid".to_string(), AmfValue::String("user".to_string())),
+                    ("password".to_string(), AmfValue::Null),
+                ]),
+            }],
+            messages: vec![Message {
+                target: "Service.method".to_string(),
+                response: "/1".to_string(),
+                value: AmfValue::StrictArray(vec![
+                    AmfValue::Number(1.5),
+                    AmfValue::Bool(true),
+                    AmfValue::EcmaArray(vec![("a".to_string(), AmfValue::Undefined)]),
+                    AmfValue::Date(1000.0),
+                    AmfValue::TypedObject(
+                        "Point".to_string(),
+                        vec![("x".to_string(), AmfValue::Number(2.0))],
+                    ),
+                ]),
+            }],
+        };
+
+        assert_eq!(Packet::decode(&packet.encode()), Some(packet));
+    }
+
+    #[test]
+    fn decode_response() {
+        let mut data = vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x01];
+        data.extend_from_slice(b"\x00\x0b/1/onResult");
+        data.extend_from_slice(b"\x00\x04null");
+        data.extend_from_slice(&UNKNOWN_LENGTH.to_be_bytes());
+        // An object, followed by a reference back to it.
+        data.extend_from_slice(b"\x0a\x00\x00\x00\x02\x03\x00\x01a\x02\x00\x01b\x00\x00\x09");
+        data.extend_from_slice(b"\x07\x00\x01");
+
+        let object = AmfValue::Object(vec![("a".to_string(), AmfValue::String("b".to_string()))]);
+        assert_eq!(
+            Packet::decode(&data),
+            Some(Packet {
+                version: 0,
+                headers: vec![],
+                messages: vec![Message {
+                    target: "/1/onResult".to_string(),
+                    response: "null".to_string(),
+                    value: AmfValue::StrictArray(vec![object.clone(), object]),
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn decode_amf3_body() {
+        let mut data = vec![0x00, 0x03, 0x00, 0x00, 0x00, 0x01];
+        data.extend_from_slice(b"\x00\x0b/1/onResult");
+        data.extend_from_slice(b"\x00\x00");
+        data.extend_from_slice(&3u32.to_be_bytes());
+        data.extend_from_slice(b"\x11\x04\x05");
+
+        let packet = Packet::decode(&data).unwrap();
+        assert_eq!(packet.messages[0].value, AmfValue::Amf3(vec![0x04, 0x05]));
+    }
+
+    #[test]
+    fn decode_invalid() {
+        assert_eq!(Packet::decode(b"\x00\x00\x00"), None);
+    }
+}
@@ -0,0 +1,770 @@
+//! Security sandboxes and cross-domain policy files.
+//!
+//! Every movie is placed in a sandbox based on where it was loaded from, which
+//! decides what it is allowed to load:
+//!
+//! * `remote` movies may load anything from their own site. Data from other
+//!   sites is only given to them if the site serves a `crossdomain.xml`
+//!   policy file that allows the movie's domain.
+//! * `localWithFile` movies may only load local files.
+//! * `localWithNetwork` movies may only load from the network, and only from
+//!   sites whose policy file allows every domain.
+//! * `localTrusted` movies may load anything.
+//!
+//! Socket connections are also checked against a policy file. It is requested
+//! over a socket from port 843 of the host, and then from the port being
+//! connected to.
+//!
+//! In a web browser, data from other sites is only readable at all if the
+//! site allows it with CORS headers. That is taken in place of a policy file,
+//! as most sites that serve CORS headers have no `crossdomain.xml`.
+//!
+//! A player can be made trusted, in which case every movie is `localTrusted`
+//! and nothing is checked.
+
+use crate::avm1::globals::system::SandboxType;
+use crate::backend::navigator::{NavigatorBackend, OwnedFuture, RequestOptions};
+use crate::loader::Error;
+use crate::tag_utils::SwfMovie;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use url::Url;
+
+/// The port that socket policy files are served from.
+pub const SOCKET_POLICY_PORT: u16 = 843;
+
+/// The message that asks a socket server for its policy file.
+pub const SOCKET_POLICY_REQUEST: &[u8] = b"<policy-file-request/>\0";
+
+/// The security settings of a player.
+#[derive(Default)]
+pub struct Security {
+    /// Whether sandbox checks are skipped entirely.
+    trusted: bool,
+
+    /// Domains that movies have allowed to script them with
+    /// `Security.allowDomain`.
+    ///
+    /// Flash Player tracks these per movie, but they are shared by every
+    /// movie in the player here.
+    allowed_domains: Vec<String>,
+
+    /// Policy files other than `/crossdomain.xml` that movies have asked to
+    /// be consulted with `Security.loadPolicyFile`.
+    policy_urls: Vec<Url>,
+
+    /// Policy files that have been fetched, by URL. Fetching is asynchronous,
+    /// so this is shared with the loads waiting on them.
+    policies: Rc<RefCell<HashMap<String, Rc<PolicyFile>>>>,
+}
+
+impl Security {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_trusted(&self) -> bool {
+        self.trusted
+    }
+
+    pub fn set_trusted(&mut self, trusted: bool) {
+        self.trusted = trusted;
+    }
+
+    /// The sandbox that a movie runs in.
+    pub fn sandbox_type(&self, movie: &SwfMovie) -> SandboxType {
+        if self.trusted {
+            return SandboxType::LocalTrusted;
+        }
+
+        match movie.url().and_then(|url| Url::parse(url).ok()) {
+            Some(url) if is_network_url(&url) => SandboxType::Remote,
+            _ if movie.header().use_network_sandbox() => SandboxType::LocalWithNetwork,
+            _ => SandboxType::LocalWithFile,
+        }
+    }
+
+    /// Implements `Security.allowDomain`.
+    ///
+    /// Movies may also pass a URL, which allows the domain it is on.
+    pub fn allow_domain(&mut self, domain: &str) {
+        let domain = match Url::parse(domain) {
+            Ok(url) => match url.host_str() {
+                Some(host) => host.to_ascii_lowercase(),
+                None => return,
+            },
+            Err(_) => domain.to_ascii_lowercase(),
+        };
+        if !self.allowed_domains.contains(&domain) {
+            self.allowed_domains.push(domain);
+        }
+    }
+
+    /// Whether a movie loaded from `accessor_url` may script the movie loaded
+    /// from `target_url`.
+    pub fn allows_scripting(&self, accessor_url: Option<&str>, target_url: Option<&str>) -> bool {
+        if self.trusted {
+            return true;
+        }
+
+        let accessor = accessor_url.and_then(|url| Url::parse(url).ok());
+        let target = target_url.and_then(|url| Url::parse(url).ok());
+        match (accessor, target) {
+            (Some(accessor), Some(target)) => {
+                accessor.origin() == target.origin()
+                    || accessor.host_str().map_or(false, |host| {
+                        self.allowed_domains
+                            .iter()
+                            .any(|domain| domain_matches(domain, host))
+                    })
+            }
+            // Movies that weren't loaded from anywhere can't be told apart.
+            _ => true,
+        }
+    }
+
+    /// Implements `Security.loadPolicyFile`.
+    pub fn load_policy_file(&mut self, movie: &SwfMovie, url: &str) {
+        let url = match movie.url().and_then(|base| Url::parse(base).ok()) {
+            Some(base) => base.join(url),
+            None => Url::parse(url),
+        };
+
+        match url {
+            Ok(url) if is_network_url(&url) => {
+                if !self.policy_urls.contains(&url) {
+                    self.policy_urls.push(url);
+                }
+            }
+            Ok(url) => log::warn!("Security.loadPolicyFile: ignoring non-HTTP URL {}", url),
+            Err(e) => log::warn!("Security.loadPolicyFile: invalid URL: {}", e),
+        }
+    }
+
+    /// Fetch data for a movie, if its sandbox allows it to be read.
+    ///
+    /// When a policy file has to be consulted, the request isn't made until
+    /// the policy file has been fetched and allows it.
+    pub fn fetch_data(
+        &self,
+        navigator: &mut dyn NavigatorBackend,
+        movie: &SwfMovie,
+        url: &str,
+        options: RequestOptions,
+    ) -> OwnedFuture<Vec<u8>, Error> {
+        let fetch = navigator.fetch(url, options);
+
+        let movie_url = movie.url().and_then(|url| Url::parse(url).ok());
+        let target = match &movie_url {
+            Some(base) => base.join(url),
+            None => Url::parse(url),
+        };
+        let target = match target {
+            Ok(target) => target,
+            // Let the navigator decide what to make of it.
+            Err(_) => return fetch,
+        };
+
+        let requester = match (self.sandbox_type(movie), movie_url) {
+            (SandboxType::LocalTrusted, _) => return fetch,
+            (SandboxType::LocalWithFile, _) if target.scheme() == "file" => return fetch,
+            (SandboxType::Remote, Some(movie_url))
+                if is_network_url(&target) && movie_url.origin() == target.origin() =>
+            {
+                return fetch
+            }
+            (SandboxType::LocalWithNetwork, _) if is_network_url(&target) => None,
+            (SandboxType::Remote, Some(movie_url)) if is_network_url(&target) => Some(movie_url),
+            _ => {
+                let message = format!("{} cannot load data from {}", movie_name(movie), url);
+                return Box::pin(async move { Err(Error::SecurityError(message)) });
+            }
+        };
+
+        if navigator.enforces_cors() {
+            return fetch;
+        }
+
+        let policy_url = self.policy_url_for(&target);
+        let cached = self.policies.borrow().get(policy_url.as_str()).cloned();
+        // Fetches are only made once awaited, so this costs nothing if the
+        // policy file has already been fetched.
+        let policy_fetch = navigator.fetch(policy_url.as_str(), RequestOptions::get());
+        let policies = self.policies.clone();
+        let url = url.to_string();
+
+        Box::pin(async move {
+            let policy = match cached {
+                Some(policy) => policy,
+                None => {
+                    let policy = policy_fetch
+                        .await
+                        .ok()
+                        .and_then(|data| PolicyFile::parse(&data))
+                        .unwrap_or_default();
+                    let policy = Rc::new(policy);
+                    policies
+                        .borrow_mut()
+                        .insert(policy_url.to_string(), policy.clone());
+                    policy
+                }
+            };
+
+            let is_secure = policy_url.scheme() == "https";
+            let allowed = match &requester {
+                Some(requester) => policy.allows(
+                    requester.host_str(),
+                    requester.scheme() == "https",
+                    is_secure,
+                ),
+                None => policy.allows(None, false, is_secure),
+            };
+
+            if allowed {
+                fetch.await
+            } else {
+                Err(Error::SecurityError(format!(
+                    "{} does not allow access to {}",
+                    policy_url, url
+                )))
+            }
+        })
+    }
+
+    /// Decide whether a movie may open socket connections.
+    pub fn socket_access(&self, movie: &SwfMovie) -> SocketAccess {
+        match self.sandbox_type(movie) {
+            SandboxType::LocalTrusted => SocketAccess::Allowed,
+            SandboxType::LocalWithFile => SocketAccess::Denied,
+            SandboxType::LocalWithNetwork => SocketAccess::NeedsPolicy(SocketPolicyRequester(None)),
+            SandboxType::Remote => SocketAccess::NeedsPolicy(SocketPolicyRequester(
+                movie
+                    .url()
+                    .and_then(|url| Url::parse(url).ok())
+                    .and_then(|url| url.host_str().map(str::to_string)),
+            )),
+        }
+    }
+
+    /// Find the policy file that applies to a URL.
+    ///
+    /// A policy file loaded with `Security.loadPolicyFile` applies to its own
+    /// directory and everything below it.
+    fn policy_url_for(&self, target: &Url) -> Url {
+        self.policy_urls
+            .iter()
+            .filter(|policy_url| policy_url.origin() == target.origin())
+            .filter(|policy_url| {
+                let path = policy_url.path();
+                let directory = &path[..path.rfind('/').map_or(0, |i| i + 1)];
+                target.path().starts_with(directory)
+            })
+            .max_by_key(|policy_url| policy_url.path().len())
+            .cloned()
+            .unwrap_or_else(|| {
+                let mut policy_url = target.clone();
+                policy_url.set_path("/crossdomain.xml");
+                policy_url.set_query(None);
+                policy_url.set_fragment(None);
+                policy_url
+            })
+    }
+}
+
+/// Whether a movie may open socket connections.
+pub enum SocketAccess {
+    Allowed,
+    Denied,
+
+    /// Connections are only allowed by the host's socket policy file.
+    NeedsPolicy(SocketPolicyRequester),
+}
+
+/// The domain of a movie that a socket policy file must allow, if it has
+/// one.
+#[derive(Clone, Debug)]
+pub struct SocketPolicyRequester(Option<String>);
+
+impl SocketPolicyRequester {
+    /// Whether a socket policy file allows this movie to connect to a port.
+    pub fn is_allowed(&self, policy: &[u8], port: u16) -> bool {
+        match PolicyFile::parse(policy) {
+            Some(policy) => policy.allows_port(self.0.as_deref(), port),
+            None => false,
+        }
+    }
+}
+
+fn is_network_url(url: &Url) -> bool {
+    matches!(url.scheme(), "http" | "https")
+}
+
+fn movie_name(movie: &SwfMovie) -> String {
+    match movie.url() {
+        Some(url) => url.to_string(),
+        None => "A movie".to_string(),
+    }
+}
+
+/// Check whether a domain pattern from a policy file or `allowDomain` matches
+/// a host.
+///
+/// `*` matches every host, and `*.example.com` matches `example.com` and all
+/// of its subdomains.
+fn domain_matches(pattern: &str, host: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    let pattern = pattern.to_ascii_lowercase();
+
+    if pattern == "*" {
+        true
+    } else if let Some(suffix) = pattern.strip_prefix("*.") {
+        host == suffix || host.ends_with(&format!(".{}", suffix))
+    } else {
+        host == pattern
+    }
+}
+
+/// An `allow-access-from` entry in a policy file.
+#[derive(Clone, Debug, PartialEq)]
+struct AllowAccessFrom {
+    domain: String,
+
+    /// The ports that sockets may connect to, for socket policy files.
+    to_ports: Option<String>,
+
+    /// Whether requesters must have been loaded over HTTPS, if the policy
+    /// file was.
+    secure: bool,
+}
+
+/// A cross-domain policy file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PolicyFile {
+    allow_access_from: Vec<AllowAccessFrom>,
+
+    /// Whether a `site-control` element forbids every policy file on the
+    /// site.
+    permits_none: bool,
+}
+
+impl PolicyFile {
+    /// Parse a policy file.
+    ///
+    /// Returns `None` if it isn't a `cross-domain-policy` document.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let data = match data.iter().position(|&b| b == 0) {
+            Some(end) => &data[..end],
+            None => data,
+        };
+
+        let mut reader = Reader::from_reader(data);
+        reader.trim_text(true);
+        let mut buf = Vec::new();
+        let mut policy = PolicyFile::default();
+        let mut is_policy = false;
+
+        loop {
+            match reader.read_event(&mut buf) {
+                Ok(Event::Start(element)) | Ok(Event::Empty(element)) => match element.name() {
+                    b"cross-domain-policy" => is_policy = true,
+                    b"allow-access-from" if is_policy => {
+                        if let Some(domain) = attribute(&element, b"domain") {
+                            policy.allow_access_from.push(AllowAccessFrom {
+                                domain,
+                                to_ports: attribute(&element, b"to-ports"),
+                                secure: attribute(&element, b"secure").as_deref() != Some("false"),
+                            });
+                        }
+                    }
+                    b"site-control" if is_policy => {
+                        policy.permits_none =
+                            attribute(&element, b"permitted-cross-domain-policies").as_deref()
+                                == Some("none");
+                    }
+                    _ => {}
+                },
+                Ok(Event::Eof) => break,
+                Ok(_) => {}
+                Err(_) => return None,
+            }
+            buf.clear();
+        }
+
+        if is_policy {
+            Some(policy)
+        } else {
+            None
+        }
+    }
+
+    /// Whether this policy allows a movie to read data.
+    ///
+    /// `requester` is the domain of the movie, or `None` for local movies,
+    /// which are only allowed by `*`.
+    fn allows(&self, requester: Option<&str>, requester_secure: bool, is_secure: bool) -> bool {
+        !self.permits_none
+            && self.allow_access_from.iter().any(|entry| {
+                (!is_secure || !entry.secure || requester_secure)
+                    && Self::matches(&entry.domain, requester)
+            })
+    }
+
+    /// Whether this socket policy allows a movie to connect to a port.
+    fn allows_port(&self, requester: Option<&str>, port: u16) -> bool {
+        self.allow_access_from.iter().any(|entry| {
+            Self::matches(&entry.domain, requester)
+                && entry
+                    .to_ports
+                    .as_deref()
+                    .map_or(false, |ports| ports_contain(ports, port))
+        })
+    }
+
+    fn matches(pattern: &str, requester: Option<&str>) -> bool {
+        match requester {
+            Some(requester) => domain_matches(pattern, requester),
+            None => pattern == "*",
+        }
+    }
+}
+
+fn attribute(element: &BytesStart<'_>, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .filter_map(Result::ok)
+        .find(|attribute| attribute.key == name)
+        .and_then(|attribute| {
+            attribute
+                .unescaped_value()
+                .ok()
+                .map(|value| String::from_utf8_lossy(&value).into_owned())
+        })
+}
+
+/// Check whether a `to-ports` list, such as `80,443,1000-2000`, contains a
+/// port.
+fn ports_contain(ports: &str, port: u16) -> bool {
+    ports.split(',').map(str::trim).any(|range| {
+        if range == "*" {
+            return true;
+        }
+
+        let (start, end) = match range.find('-') {
+            Some(i) => (&range[..i], &range[i + 1..]),
+            None => (range, range),
+        };
+        match (start.trim().parse::<u16>(), end.trim().parse::<u16>()) {
+            (Ok(start), Ok(end)) => (start..=end).contains(&port),
+            _ => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::navigator::{
+        NavigationMethod, NullExecutor, NullNavigatorBackend, SocketEvent, SocketHandle,
+    };
+    use indexmap::IndexMap;
+    use std::borrow::Cow;
+    use std::time::Duration;
+
+    const POLICY: &[u8] = br#"<?xml version="1.0"?>
+        <!DOCTYPE cross-domain-policy SYSTEM "http://www.adobe.com/xml/dtds/cross-domain-policy.dtd">
+        <cross-domain-policy>
+            <allow-access-from domain="*.example.com" />
+            <allow-access-from domain="ruffle.rs" to-ports="507,1000-2000" secure="false" />
+        </cross-domain-policy>"#;
+
+    #[test]
+    fn parse_policy() {
+        let policy = PolicyFile::parse(POLICY).unwrap();
+        assert!(policy.allows(Some("example.com"), false, false));
+        assert!(policy.allows(Some("www.EXAMPLE.com"), false, false));
+        assert!(!policy.allows(Some("notexample.com"), false, false));
+        assert!(!policy.allows(None, false, false));
+
+        // Served over HTTPS, only the insecure entry admits HTTP movies.
+        assert!(!policy.allows(Some("example.com"), false, true));
+        assert!(policy.allows(Some("example.com"), true, true));
+        assert!(policy.allows(Some("ruffle.rs"), false, true));
+    }
+
+    #[test]
+    fn socket_policy() {
+        let policy = PolicyFile::parse(POLICY).unwrap();
+        assert!(policy.allows_port(Some("ruffle.rs"), 507));
+        assert!(policy.allows_port(Some("ruffle.rs"), 1500));
+        assert!(!policy.allows_port(Some("ruffle.rs"), 80));
+        // Entries without `to-ports` don't allow any sockets.
+        assert!(!policy.allows_port(Some("example.com"), 507));
+    }
+
+    #[test]
+    fn site_control() {
+        let policy = PolicyFile::parse(
+            br#"<cross-domain-policy>
+                <site-control permitted-cross-domain-policies="none"/>
+                <allow-access-from domain="*" />
+            </cross-domain-policy>"#,
+        )
+        .unwrap();
+        assert!(!policy.allows(Some("example.com"), false, false));
+    }
+
+    #[test]
+    fn not_a_policy() {
+        assert_eq!(PolicyFile::parse(b"<html><body>404</body></html>"), None);
+        assert_eq!(PolicyFile::parse(b"<cross-domain-policy"), None);
+    }
+
+    /// A navigator that serves files from memory and records what was
+    /// actually fetched.
+    struct TestNavigator {
+        inner: NullNavigatorBackend,
+        files: HashMap<String, Vec<u8>>,
+        fetched: Rc<RefCell<Vec<String>>>,
+        cors: bool,
+    }
+
+    impl TestNavigator {
+        fn new(files: &[(&str, &[u8])], cors: bool) -> Self {
+            Self {
+                inner: NullNavigatorBackend::new(),
+                files: files
+                    .iter()
+                    .map(|(url, data)| (url.to_string(), data.to_vec()))
+                    .collect(),
+                fetched: Rc::new(RefCell::new(Vec::new())),
+                cors,
+            }
+        }
+    }
+
+    impl NavigatorBackend for TestNavigator {
+        fn navigate_to_url(
+            &self,
+            url: String,
+            window: Option<String>,
+            vars_method: Option<(NavigationMethod, IndexMap<String, String>)>,
+        ) {
+            self.inner.navigate_to_url(url, window, vars_method)
+        }
+
+        fn fetch(&self, url: &str, _options: RequestOptions) -> OwnedFuture<Vec<u8>, Error> {
+            let url = url.to_string();
+            let data = self.files.get(&url).cloned();
+            let fetched = self.fetched.clone();
+            Box::pin(async move {
+                fetched.borrow_mut().push(url.clone());
+                data.ok_or(Error::FetchError(url))
+            })
+        }
+
+        fn time_since_launch(&mut self) -> Duration {
+            self.inner.time_since_launch()
+        }
+
+        fn spawn_future(&mut self, future: OwnedFuture<(), Error>) {
+            self.inner.spawn_future(future)
+        }
+
+        fn resolve_relative_url<'a>(&self, url: &'a str) -> Cow<'a, str> {
+            self.inner.resolve_relative_url(url)
+        }
+
+        fn pre_process_url(&self, url: Url) -> Url {
+            self.inner.pre_process_url(url)
+        }
+
+        fn enforces_cors(&self) -> bool {
+            self.cors
+        }
+
+        fn connect_socket(&mut self, host: &str, port: u16, timeout: Duration) -> SocketHandle {
+            self.inner.connect_socket(host, port, timeout)
+        }
+
+        fn send_socket(&mut self, handle: SocketHandle, data: Vec<u8>) {
+            self.inner.send_socket(handle, data)
+        }
+
+        fn close_socket(&mut self, handle: SocketHandle) {
+            self.inner.close_socket(handle)
+        }
+
+        fn poll_sockets(&mut self) -> Vec<(SocketHandle, SocketEvent)> {
+            self.inner.poll_sockets()
+        }
+    }
+
+    /// An empty movie loaded from `url`, optionally asking for the network
+    /// sandbox when run locally.
+    fn movie(url: &str, use_network_sandbox: bool) -> SwfMovie {
+        // An uncompressed SWF 10 header, with an empty stage, 24 frames per
+        // second and one frame.
+        let mut data = b"FWS\x0a\x15\x00\x00\x00\x00\x00\x18\x01\x00".to_vec();
+        // FileAttributes
+        data.extend_from_slice(&[0x44, 0x11, use_network_sandbox as u8, 0, 0, 0]);
+        // End
+        data.extend_from_slice(&[0, 0]);
+        SwfMovie::from_data(&data, Some(url.to_string()), None).unwrap()
+    }
+
+    /// Run `fetch_data` to completion.
+    fn fetch(
+        security: &Security,
+        navigator: &mut TestNavigator,
+        movie: &SwfMovie,
+        url: &str,
+    ) -> Result<Vec<u8>, Error> {
+        let future = security.fetch_data(navigator, movie, url, RequestOptions::get());
+        let (mut executor, sender) = NullExecutor::new();
+        let result = Rc::new(RefCell::new(None));
+        let output = result.clone();
+        sender
+            .send(Box::pin(async move {
+                *output.borrow_mut() = Some(future.await);
+                Ok(())
+            }))
+            .unwrap();
+        executor.block_all().unwrap();
+        result.take().unwrap()
+    }
+
+    const LOCAL_MOVIE: &str = "file:///movies/movie.swf";
+    const LOCAL_DATA: &str = "file:///movies/data.txt";
+    const REMOTE_MOVIE: &str = "https://ruffle.rs/movie.swf";
+    const SAME_ORIGIN_DATA: &str = "https://ruffle.rs/data.txt";
+    const OTHER_ORIGIN_DATA: &str = "http://example.com/data.txt";
+    const OTHER_ORIGIN_POLICY: &str = "http://example.com/crossdomain.xml";
+
+    const ALLOW_ALL: &[u8] =
+        br#"<cross-domain-policy><allow-access-from domain="*" /></cross-domain-policy>"#;
+    const ALLOW_RUFFLE: &[u8] =
+        br#"<cross-domain-policy><allow-access-from domain="ruffle.rs" /></cross-domain-policy>"#;
+
+    #[test]
+    fn sandbox_matrix() {
+        let files: &[(&str, &[u8])] = &[
+            (LOCAL_DATA, &b"local"[..]),
+            (SAME_ORIGIN_DATA, &b"same"[..]),
+            (OTHER_ORIGIN_DATA, &b"other"[..]),
+        ];
+        let local = movie(LOCAL_MOVIE, false);
+        let network = movie(LOCAL_MOVIE, true);
+        let remote = movie(REMOTE_MOVIE, false);
+
+        // Each case is a sandbox, the origin of the data, and whether the
+        // data is readable without a policy file.
+        let cases = [
+            (&local, SandboxType::LocalTrusted, LOCAL_DATA, true),
+            (&local, SandboxType::LocalTrusted, OTHER_ORIGIN_DATA, true),
+            (&local, SandboxType::LocalWithFile, LOCAL_DATA, true),
+            (&local, SandboxType::LocalWithFile, OTHER_ORIGIN_DATA, false),
+            (&network, SandboxType::LocalWithNetwork, LOCAL_DATA, false),
+            (
+                &network,
+                SandboxType::LocalWithNetwork,
+                OTHER_ORIGIN_DATA,
+                false,
+            ),
+            (&remote, SandboxType::Remote, LOCAL_DATA, false),
+            (&remote, SandboxType::Remote, SAME_ORIGIN_DATA, true),
+            (&remote, SandboxType::Remote, OTHER_ORIGIN_DATA, false),
+        ];
+
+        for (movie, sandbox_type, url, allowed) in cases.iter() {
+            let mut security = Security::new();
+            security.set_trusted(*sandbox_type == SandboxType::LocalTrusted);
+            assert_eq!(security.sandbox_type(movie), *sandbox_type);
+
+            let mut navigator = TestNavigator::new(files, false);
+            let result = fetch(&security, &mut navigator, movie, url);
+            let message = format!("{:?} loading {}", sandbox_type, url);
+            if *allowed {
+                assert!(result.is_ok(), "{}", message);
+            } else {
+                assert!(
+                    matches!(result, Err(Error::SecurityError(_))),
+                    "{}",
+                    message
+                );
+                let fetched = navigator.fetched.borrow();
+                assert!(!fetched.contains(&url.to_string()), "{}", message);
+            }
+        }
+    }
+
+    #[test]
+    fn policy_file_matrix() {
+        let network = movie(LOCAL_MOVIE, true);
+        let remote = movie(REMOTE_MOVIE, false);
+        let security = Security::new();
+
+        // Each case is a sandbox, the policy file served by the other site,
+        // and whether it lets the sandbox read the data.
+        let cases: [(&SwfMovie, Option<&[u8]>, bool); 6] = [
+            (&network, None, false),
+            (&network, Some(ALLOW_RUFFLE), false),
+            (&network, Some(ALLOW_ALL), true),
+            (&remote, None, false),
+            (&remote, Some(ALLOW_RUFFLE), true),
+            (&remote, Some(ALLOW_ALL), true),
+        ];
+
+        for (movie, policy, allowed) in cases.iter() {
+            let mut files: Vec<(&str, &[u8])> = vec![(OTHER_ORIGIN_DATA, &b"other"[..])];
+            if let Some(policy) = policy {
+                files.push((OTHER_ORIGIN_POLICY, *policy));
+            }
+            let mut navigator = TestNavigator::new(&files, false);
+            let result = fetch(&security, &mut navigator, movie, OTHER_ORIGIN_DATA);
+            assert_eq!(
+                result.ok(),
+                if *allowed {
+                    Some(b"other".to_vec())
+                } else {
+                    None
+                },
+                "{:?} with policy {:?}",
+                security.sandbox_type(movie),
+                policy.map(String::from_utf8_lossy)
+            );
+            assert_eq!(
+                navigator.fetched.borrow()[0],
+                OTHER_ORIGIN_POLICY,
+                "The policy file is fetched first"
+            );
+        }
+    }
+
+    #[test]
+    fn cors_allows_network_data() {
+        let files: &[(&str, &[u8])] = &[
+            (LOCAL_DATA, &b"local"[..]),
+            (OTHER_ORIGIN_DATA, &b"other"[..]),
+        ];
+        let security = Security::new();
+
+        for movie in [movie(LOCAL_MOVIE, true), movie(REMOTE_MOVIE, false)].iter() {
+            let mut navigator = TestNavigator::new(files, true);
+            let result = fetch(&security, &mut navigator, movie, OTHER_ORIGIN_DATA);
+            assert_eq!(result.ok(), Some(b"other".to_vec()));
+            assert_eq!(*navigator.fetched.borrow(), vec![OTHER_ORIGIN_DATA]);
+        }
+
+        // CORS doesn't let sandboxes load from places they never could.
+        let mut navigator = TestNavigator::new(files, true);
+        let remote = movie(REMOTE_MOVIE, false);
+        let result = fetch(&security, &mut navigator, &remote, LOCAL_DATA);
+        assert!(matches!(result, Err(Error::SecurityError(_))));
+        let local = movie(LOCAL_MOVIE, false);
+        let result = fetch(&security, &mut navigator, &local, OTHER_ORIGIN_DATA);
+        assert!(matches!(result, Err(Error::SecurityError(_))));
+    }
+}
@@ -0,0 +1,200 @@
+//! Local shared objects, shared by AVM1 and AVM2.
+//!
+//! Shared objects are stored by the storage backend in the `.sol` format used
+//! by Flash Player, so that saves can be moved between the two. AVM1 writes
+//! their bodies as AMF0, and AVM2 as AMF3.
+
+use crate::backend::storage::StorageBackend;
+
+/// The magic bytes that begin a `.sol` file.
+const SOL_MAGIC: [u8; 2] = [0x00, 0xBF];
+
+/// The signature following the length of a `.sol` file.
+const SOL_SIGNATURE: [u8; 10] = *b"TCSO\x00\x04\x00\x00\x00\x00";
+
+/// The AMF version stored in the header of AVM2 shared objects.
+pub const AMF3_VERSION: u32 = 3;
+
+/// Get the name that a local shared object is stored under, such as
+/// `foo.com/folder/game.swf/SOName`.
+///
+/// Returns `None` if the movie isn't allowed to use the shared object.
+pub fn local_name(
+    movie_url: Option<&str>,
+    name: &str,
+    local_path: Option<&str>,
+    secure: bool,
+) -> Option<String> {
+    const INVALID_CHARS: &str = "~%&\\;:\"',<>?# ";
+    if name.contains(|c| INVALID_CHARS.contains(c)) {
+        log::error!("SharedObject.getLocal: Invalid character in name");
+        return None;
+    }
+
+    let mut movie_url = if let Some(url) = movie_url {
+        if let Ok(url) = url::Url::parse(url) {
+            url
+        } else {
+            log::error!("SharedObject.getLocal: Unable to parse movie URL");
+            return None;
+        }
+    } else {
+        // No URL (loading local data). Use a dummy URL to allow SharedObjects to work.
+        url::Url::parse("file://localhost").unwrap()
+    };
+    movie_url.set_query(None);
+    movie_url.set_fragment(None);
+
+    // Secure parameter disallows using the shared object from non-HTTPS.
+    if secure && movie_url.scheme() != "https" {
+        log::warn!(
+            "SharedObject.getLocal: Tried to load a secure shared object from non-HTTPS origin"
+        );
+        return None;
+    }
+
+    // Shared objects are sandboxed per-domain.
+    // By default, they are keyed based on the SWF URL, but the `localHost` parameter can modify this path.
+    let mut movie_path = movie_url.path();
+    // Remove leading/trailing slashes.
+    movie_path = movie_path.strip_prefix('/').unwrap_or(movie_path);
+    movie_path = movie_path.strip_suffix('/').unwrap_or(movie_path);
+
+    let movie_host = if movie_url.scheme() == "file" {
+        // Remove drive letter on Windows (TODO: move this logic into DiskStorageBackend?)
+        if let [_, b':', b'/', ..] = movie_path.as_bytes() {
+            movie_path = &movie_path[3..];
+        }
+        "localhost"
+    } else {
+        movie_url.host_str().unwrap_or_default()
+    };
+
+    let local_path = if let Some(local_path) = local_path {
+        // Empty local path always fails.
+        if local_path.is_empty() {
+            return None;
+        }
+
+        // Remove leading/trailing slashes.
+        let mut local_path = local_path.strip_prefix('/').unwrap_or(local_path);
+        local_path = local_path.strip_suffix('/').unwrap_or(local_path);
+
+        // Verify that local_path is a prefix of the SWF path.
+        if movie_path.starts_with(&local_path)
+            && (local_path.is_empty()
+                || movie_path.len() == local_path.len()
+                || movie_path[local_path.len()..].starts_with('/'))
+        {
+            local_path
+        } else {
+            log::warn!("SharedObject.getLocal: localPath parameter does not match SWF path");
+            return None;
+        }
+    } else {
+        movie_path
+    };
+
+    // Final SO path: foo.com/folder/game.swf/SOName
+    // SOName may be a path containing slashes. In this case, prefix with # to mimic Flash Player behavior.
+    let prefix = if name.contains('/') { "#" } else { "" };
+    let full_name = format!("{}/{}/{}{}", movie_host, local_path, prefix, name);
+
+    // Avoid any paths with `..` to prevent SWFs from crawling the file system on desktop.
+    // Flash will generally fail to save shared objects with a path component starting with `.`,
+    // so let's disallow them altogether.
+    if full_name.split('/').any(|s| s.starts_with('.')) {
+        log::error!("SharedObject.getLocal: Invalid path with .. segments");
+        return None;
+    }
+
+    Some(full_name)
+}
+
+/// Get the name stored in the header of a shared object, which is the last
+/// component of its full name.
+pub fn short_name(full_name: &str) -> &str {
+    full_name.rsplit('/').next().unwrap_or("<unknown>")
+}
+
+/// Whether a shared object of `size` bytes may be written, when the movie
+/// asked for at least `min_disk_space` bytes to be available.
+pub fn fits_in_storage(storage: &dyn StorageBackend, size: usize, min_disk_space: usize) -> bool {
+    size.max(min_disk_space) <= storage.max_size()
+}
+
+/// Build a `.sol` file holding an already encoded body.
+pub fn write_sol(name: &str, amf_version: u32, body: &[u8]) -> Vec<u8> {
+    let mut contents = Vec::with_capacity(SOL_SIGNATURE.len() + name.len() + body.len() + 6);
+    contents.extend_from_slice(&SOL_SIGNATURE);
+    contents.extend_from_slice(&(name.len() as u16).to_be_bytes());
+    contents.extend_from_slice(name.as_bytes());
+    contents.extend_from_slice(&amf_version.to_be_bytes());
+    contents.extend_from_slice(body);
+
+    let mut sol = Vec::with_capacity(contents.len() + 6);
+    sol.extend_from_slice(&SOL_MAGIC);
+    sol.extend_from_slice(&(contents.len() as u32).to_be_bytes());
+    sol.extend_from_slice(&contents);
+    sol
+}
+
+/// Split a `.sol` file into the AMF version and the encoded body.
+///
+/// Returns `None` if the data is not a `.sol` file.
+pub fn read_sol(data: &[u8]) -> Option<(u32, &[u8])> {
+    let data = data.strip_prefix(&SOL_MAGIC[..])?;
+    let data = data.get(4..)?; // The length is implied by the data.
+    let data = data.strip_prefix(&SOL_SIGNATURE[..])?;
+
+    let name_len = u16::from_be_bytes([*data.get(0)?, *data.get(1)?]) as usize;
+    let data = data.get(2 + name_len..)?;
+
+    let version = data.get(..4)?;
+    let version = u32::from_be_bytes([version[0], version[1], version[2], version[3]]);
+    Some((version, &data[4..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sol_round_trip() {
+        let sol = write_sol("save", AMF3_VERSION, b"body");
+        assert_eq!(&sol[..2], &[0x00, 0xBF]);
+        assert_eq!(
+            u32::from_be_bytes([sol[2], sol[3], sol[4], sol[5]]) as usize,
+            sol.len() - 6
+        );
+        assert_eq!(read_sol(&sol), Some((AMF3_VERSION, &b"body"[..])));
+        assert_eq!(read_sol(b"not a sol file"), None);
+    }
+
+    #[test]
+    fn local_names() {
+        assert_eq!(
+            local_name(
+                Some("https://foo.com/folder/game.swf?a=b"),
+                "save",
+                None,
+                false
+            ),
+            Some("foo.com/folder/game.swf/save".to_string())
+        );
+        assert_eq!(
+            local_name(
+                Some("https://foo.com/folder/game.swf"),
+                "a/b",
+                Some("/folder"),
+                false
+            ),
+            Some("foo.com/folder/#a/b".to_string())
+        );
+        assert_eq!(
+            local_name(Some("http://foo.com/game.swf"), "save", None, true),
+            None
+        );
+        assert_eq!(local_name(None, "bad name", None, false), None);
+    }
+}
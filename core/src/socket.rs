@@ -0,0 +1,524 @@
+//! Socket connections for `flash.net.Socket` and the AVM1 and AVM2
+//! `XMLSocket` classes.
+//!
+//! Connections are made by the navigator backend, which reports what happened
+//! to each socket when it is polled. Like timers, sockets are polled during
+//! the normal frame loop, so their events are always dispatched between
+//! frames.
+//!
+//! `XMLSocket`s exchange strings terminated by a null byte. Data received by
+//! them is held here until a message is complete.
+//!
+//! Unless the player is trusted, a movie may only connect to a port that the
+//! host's socket policy file allows. The policy file is requested from port
+//! 843 of the host first, then from the port being connected to, and the
+//! real connection is only made once it has arrived.
+
+use crate::avm1::{Avm1, Object as Avm1Object, Value as Avm1Value};
+use crate::avm2::{
+    Activation as Avm2Activation, Avm2, Event as Avm2Event, Object as Avm2Object, TObject as _,
+};
+use crate::backend::navigator::{SocketEvent, SocketHandle};
+use crate::context::UpdateContext;
+use crate::security::{
+    SocketAccess, SocketPolicyRequester, SOCKET_POLICY_PORT, SOCKET_POLICY_REQUEST,
+};
+use crate::string::AvmString;
+use gc_arena::Collect;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// The object that receives the events of a socket.
+#[derive(Clone, Copy, Collect)]
+#[collect(no_drop)]
+pub enum SocketTarget<'gc> {
+    /// An AVM2 `flash.net.Socket`, which also buffers the received data.
+    Avm2Socket(Avm2Object<'gc>),
+
+    /// An AVM1 `XMLSocket`.
+    Avm1XmlSocket(Avm1Object<'gc>),
+
+    /// An AVM2 `flash.net.XMLSocket`.
+    Avm2XmlSocket(Avm2Object<'gc>),
+}
+
+impl<'gc> SocketTarget<'gc> {
+    fn is(&self, other: SocketTarget<'gc>) -> bool {
+        match (*self, other) {
+            (SocketTarget::Avm2Socket(a), SocketTarget::Avm2Socket(b))
+            | (SocketTarget::Avm2XmlSocket(a), SocketTarget::Avm2XmlSocket(b)) => {
+                Avm2Object::ptr_eq(a, b)
+            }
+            (SocketTarget::Avm1XmlSocket(a), SocketTarget::Avm1XmlSocket(b)) => {
+                Avm1Object::ptr_eq(a, b)
+            }
+            _ => false,
+        }
+    }
+
+    fn is_xml_socket(&self) -> bool {
+        matches!(
+            self,
+            SocketTarget::Avm1XmlSocket(_) | SocketTarget::Avm2XmlSocket(_)
+        )
+    }
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+struct Socket<'gc> {
+    target: SocketTarget<'gc>,
+
+    /// Whether the backend has reported the connection as established.
+    connected: bool,
+
+    /// The start of an `XMLSocket` message whose terminator hasn't arrived.
+    partial_message: Vec<u8>,
+
+    /// Set while this socket is fetching the policy file that decides
+    /// whether the target may connect.
+    #[collect(require_static)]
+    policy: Option<PolicyRequest>,
+}
+
+impl<'gc> Socket<'gc> {
+    fn new(target: SocketTarget<'gc>, policy: Option<PolicyRequest>) -> Self {
+        Self {
+            target,
+            connected: false,
+            partial_message: vec![],
+            policy,
+        }
+    }
+}
+
+/// A socket policy file being fetched before connecting to a port.
+struct PolicyRequest {
+    host: String,
+    port: u16,
+    timeout: Duration,
+    requester: SocketPolicyRequester,
+
+    /// Whether the policy file is being requested from port 843, rather than
+    /// the port being connected to.
+    on_master_port: bool,
+
+    /// The policy file received so far.
+    data: Vec<u8>,
+}
+
+/// Manages the sockets opened by the movie.
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct Sockets<'gc> {
+    sockets: HashMap<SocketHandle, Socket<'gc>>,
+
+    /// Targets that were refused a connection by the security sandbox, and
+    /// have yet to be told about it.
+    denied: Vec<SocketTarget<'gc>>,
+}
+
+impl<'gc> Sockets<'gc> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start connecting a socket, closing any socket the target already has.
+    pub fn connect(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: SocketTarget<'gc>,
+        host: &str,
+        port: u16,
+        timeout: Duration,
+    ) {
+        Self::close(context, target);
+
+        match context.security.socket_access(context.swf) {
+            SocketAccess::Allowed => {
+                let handle = context.navigator.connect_socket(host, port, timeout);
+                context
+                    .sockets
+                    .sockets
+                    .insert(handle, Socket::new(target, None));
+            }
+            SocketAccess::Denied => context.sockets.denied.push(target),
+            SocketAccess::NeedsPolicy(requester) => {
+                let handle = context
+                    .navigator
+                    .connect_socket(host, SOCKET_POLICY_PORT, timeout);
+                let policy = PolicyRequest {
+                    host: host.to_string(),
+                    port,
+                    timeout,
+                    requester,
+                    on_master_port: true,
+                    data: vec![],
+                };
+                context
+                    .sockets
+                    .sockets
+                    .insert(handle, Socket::new(target, Some(policy)));
+            }
+        }
+    }
+
+    /// Close the socket of a target, whether or not it has connected yet.
+    ///
+    /// Returns `false` if the target had no socket to close.
+    pub fn close(context: &mut UpdateContext<'_, 'gc, '_>, target: SocketTarget<'gc>) -> bool {
+        let denied = context.sockets.denied.len();
+        context.sockets.denied.retain(|other| !other.is(target));
+        if context.sockets.denied.len() != denied {
+            return true;
+        }
+
+        match context.sockets.handle(target) {
+            Some(handle) => {
+                context.sockets.sockets.remove(&handle);
+                context.navigator.close_socket(handle);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Send data over the socket of a target.
+    ///
+    /// Returns `false` if the target has no connected socket.
+    pub fn send(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: SocketTarget<'gc>,
+        data: Vec<u8>,
+    ) -> bool {
+        match context.sockets.handle(target) {
+            Some(handle) if context.sockets.sockets[&handle].connected => {
+                context.navigator.send_socket(handle, data);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether a target has a connected socket.
+    pub fn is_connected(&self, target: SocketTarget<'gc>) -> bool {
+        self.sockets
+            .values()
+            .any(|socket| socket.connected && socket.target.is(target))
+    }
+
+    fn handle(&self, target: SocketTarget<'gc>) -> Option<SocketHandle> {
+        self.sockets
+            .iter()
+            .find(|(_, socket)| socket.target.is(target))
+            .map(|(handle, _)| *handle)
+    }
+
+    /// Dispatch the events of all sockets reported by the navigator backend.
+    pub fn update_sockets(context: &mut UpdateContext<'_, 'gc, '_>) {
+        for target in std::mem::take(&mut context.sockets.denied) {
+            Self::dispatch_event(context, target, SocketEvent::SecurityError, vec![], false);
+        }
+
+        for (handle, event) in context.navigator.poll_sockets() {
+            let socket = match context.sockets.sockets.get_mut(&handle) {
+                Some(socket) => socket,
+                // The socket was closed by the movie since the event happened.
+                None => continue,
+            };
+            if socket.policy.is_some() {
+                Self::update_policy_request(context, handle, event);
+                continue;
+            }
+            let target = socket.target;
+            let was_connected = socket.connected;
+            let messages = match &event {
+                SocketEvent::Data(data) if target.is_xml_socket() => {
+                    split_messages(&mut socket.partial_message, data)
+                }
+                _ => vec![],
+            };
+
+            match event {
+                SocketEvent::Connected => socket.connected = true,
+                SocketEvent::Data(_) => {}
+                SocketEvent::Closed | SocketEvent::IoError | SocketEvent::SecurityError => {
+                    context.sockets.sockets.remove(&handle);
+                }
+            }
+
+            Self::dispatch_event(context, target, event, messages, was_connected);
+        }
+    }
+
+    /// Handle an event of a socket that is fetching a policy file, and make
+    /// the real connection once the policy file allows it.
+    fn update_policy_request(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        handle: SocketHandle,
+        event: SocketEvent,
+    ) {
+        let mut socket = match context.sockets.sockets.remove(&handle) {
+            Some(socket) => socket,
+            None => return,
+        };
+        let mut policy = match socket.policy.take() {
+            Some(policy) => policy,
+            None => return,
+        };
+
+        let is_complete = match event {
+            SocketEvent::Connected => {
+                context
+                    .navigator
+                    .send_socket(handle, SOCKET_POLICY_REQUEST.to_vec());
+                false
+            }
+            SocketEvent::Data(data) => {
+                policy.data.extend_from_slice(&data);
+                policy.data.contains(&0)
+            }
+            SocketEvent::Closed => true,
+            SocketEvent::IoError | SocketEvent::SecurityError if policy.on_master_port => {
+                // Nothing is serving policy files on port 843, so ask the
+                // port being connected to instead.
+                context.navigator.close_socket(handle);
+                let handle =
+                    context
+                        .navigator
+                        .connect_socket(&policy.host, policy.port, policy.timeout);
+                policy.on_master_port = false;
+                policy.data.clear();
+                socket.policy = Some(policy);
+                context.sockets.sockets.insert(handle, socket);
+                return;
+            }
+            SocketEvent::IoError | SocketEvent::SecurityError => {
+                policy.data.clear();
+                true
+            }
+        };
+
+        if !is_complete {
+            socket.policy = Some(policy);
+            context.sockets.sockets.insert(handle, socket);
+            return;
+        }
+
+        context.navigator.close_socket(handle);
+        if policy.requester.is_allowed(&policy.data, policy.port) {
+            let handle =
+                context
+                    .navigator
+                    .connect_socket(&policy.host, policy.port, policy.timeout);
+            context.sockets.sockets.insert(handle, socket);
+        } else {
+            log::warn!(
+                "Socket policy file of {} does not allow connecting to port {}",
+                policy.host,
+                policy.port
+            );
+            Self::dispatch_event(
+                context,
+                socket.target,
+                SocketEvent::SecurityError,
+                vec![],
+                false,
+            );
+        }
+    }
+
+    fn dispatch_event(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: SocketTarget<'gc>,
+        event: SocketEvent,
+        messages: Vec<String>,
+        was_connected: bool,
+    ) {
+        match target {
+            SocketTarget::Avm2Socket(object) | SocketTarget::Avm2XmlSocket(object) => {
+                if let Err(e) = Self::dispatch_avm2_event(context, target, object, event, messages)
+                {
+                    log::error!(
+                        "Encountered AVM2 error when dispatching socket event: {}",
+                        e
+                    );
+                }
+            }
+            SocketTarget::Avm1XmlSocket(object) => {
+                Self::dispatch_avm1_event(context, object, event, messages, was_connected)
+            }
+        }
+    }
+
+    /// Call the handler methods of an AVM1 `XMLSocket`.
+    ///
+    /// A socket that fails before connecting reports it through `onConnect`,
+    /// and one that fails afterwards through `onClose`.
+    fn dispatch_avm1_event(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: Avm1Object<'gc>,
+        event: SocketEvent,
+        messages: Vec<String>,
+        was_connected: bool,
+    ) {
+        match event {
+            SocketEvent::Connected => {
+                Self::call_avm1_method(context, target, "onConnect", &[true.into()])
+            }
+            SocketEvent::Data(_) => {
+                for message in messages {
+                    let message = AvmString::new(context.gc_context, message);
+                    Self::call_avm1_method(context, target, "onData", &[message.into()]);
+                }
+            }
+            SocketEvent::Closed => Self::call_avm1_method(context, target, "onClose", &[]),
+            SocketEvent::IoError | SocketEvent::SecurityError => {
+                if was_connected {
+                    Self::call_avm1_method(context, target, "onClose", &[]);
+                } else {
+                    Self::call_avm1_method(context, target, "onConnect", &[false.into()]);
+                }
+            }
+        }
+    }
+
+    fn call_avm1_method(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: Avm1Object<'gc>,
+        name: &str,
+        args: &[Avm1Value<'gc>],
+    ) {
+        let root_clip = context.stage.root_clip();
+        let swf_version = context.swf.version();
+        Avm1::run_stack_frame_for_method(root_clip, target, swf_version, context, name, args);
+    }
+
+    fn dispatch_avm2_event(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        socket: SocketTarget<'gc>,
+        target: Avm2Object<'gc>,
+        event: SocketEvent,
+        messages: Vec<String>,
+    ) -> Result<(), crate::avm2::Error> {
+        let event = match event {
+            SocketEvent::Connected => {
+                Avm2::dispatch_event(context, Avm2Event::new("connect"), target)?;
+                return Ok(());
+            }
+            SocketEvent::Closed => {
+                Avm2::dispatch_event(context, Avm2Event::new("close"), target)?;
+                return Ok(());
+            }
+            SocketEvent::Data(_) if socket.is_xml_socket() => {
+                for message in messages {
+                    let mut activation = Avm2Activation::from_nothing(context.reborrow());
+                    let message = AvmString::new(activation.context.gc_context, message);
+                    let data_event_class = activation.avm2().classes().dataevent;
+                    let event = data_event_class.construct(
+                        &mut activation,
+                        &["data".into(), false.into(), false.into(), message.into()],
+                    )?;
+
+                    Avm2::dispatch_event_object(context, event, target)?;
+                }
+                return Ok(());
+            }
+            SocketEvent::Data(data) => {
+                if let Some(mut buffer) = target.as_bytearray_mut(context.gc_context) {
+                    // Drop the data that has already been read, so the buffer
+                    // doesn't grow for as long as the socket is open.
+                    let unread = buffer
+                        .bytes()
+                        .get(buffer.position()..)
+                        .unwrap_or_default()
+                        .to_vec();
+                    buffer.clear();
+                    buffer.write_at(&unread, 0)?;
+                    buffer.write_at(&data, unread.len())?;
+                }
+
+                let mut activation = Avm2Activation::from_nothing(context.reborrow());
+                let progress_class = activation.avm2().classes().progressevent;
+                progress_class.construct(
+                    &mut activation,
+                    &[
+                        "socketData".into(),
+                        false.into(),
+                        false.into(),
+                        data.len().into(),
+                        0.into(),
+                    ],
+                )?
+            }
+            SocketEvent::IoError => {
+                let mut activation = Avm2Activation::from_nothing(context.reborrow());
+                let message =
+                    AvmString::new(activation.context.gc_context, "Error #2031: Socket Error.");
+                let io_error_class = activation.avm2().classes().ioerrorevent;
+                io_error_class.construct(
+                    &mut activation,
+                    &[
+                        "ioError".into(),
+                        false.into(),
+                        false.into(),
+                        message.into(),
+                        2031.into(),
+                    ],
+                )?
+            }
+            SocketEvent::SecurityError => {
+                let mut activation = Avm2Activation::from_nothing(context.reborrow());
+                let message = AvmString::new(
+                    activation.context.gc_context,
+                    "Error #2048: Security sandbox violation: cannot load data from the socket.",
+                );
+                let security_error_class = activation.avm2().classes().securityerrorevent;
+                security_error_class.construct(
+                    &mut activation,
+                    &[
+                        "securityError".into(),
+                        false.into(),
+                        false.into(),
+                        message.into(),
+                        2048.into(),
+                    ],
+                )?
+            }
+        };
+
+        Avm2::dispatch_event_object(context, event, target)?;
+
+        Ok(())
+    }
+}
+
+/// Add received data to the partial message of an `XMLSocket`, and split off
+/// each message it completes.
+fn split_messages(partial_message: &mut Vec<u8>, data: &[u8]) -> Vec<String> {
+    partial_message.extend_from_slice(data);
+
+    let mut messages = vec![];
+    while let Some(end) = partial_message.iter().position(|byte| *byte == 0) {
+        let message: Vec<u8> = partial_message.drain(..=end).collect();
+        messages.push(String::from_utf8_lossy(&message[..end]).into_owned());
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xml_socket_framing() {
+        let mut partial = vec![];
+        assert_eq!(split_messages(&mut partial, b"<a/>\0<b"), vec!["<a/>"]);
+        assert_eq!(partial, b"<b");
+        assert_eq!(
+            split_messages(&mut partial, b"/>\0\0<c/>\0"),
+            vec!["<b/>", "", "<c/>"]
+        );
+        assert!(partial.is_empty());
+        assert!(split_messages(&mut partial, b"<d").is_empty());
+    }
+}
@@ -25,6 +25,79 @@ pub fn next_char_boundary(slice: &str, pos: usize) -> usize {
     }
 }
 
+/// Whether a char is part of a word, for word-wise caret movement and selection.
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Gets the byte position of the start of the word before `pos`, skipping
+/// any non-word chars in between.
+/// `pos` must already lie on a char boundary
+pub fn prev_word_boundary(slice: &str, pos: usize) -> usize {
+    let before = &slice[..pos];
+    let word_end = before
+        .rfind(is_word_char)
+        .map(|i| next_char_boundary(before, i))
+        .unwrap_or(0);
+
+    before[..word_end]
+        .rfind(|c| !is_word_char(c))
+        .map(|i| next_char_boundary(before, i))
+        .unwrap_or(0)
+}
+
+/// Gets the byte position of the start of the word after `pos`, skipping the
+/// rest of the current word and any non-word chars after it.
+/// `pos` must already lie on a char boundary
+pub fn next_word_boundary(slice: &str, pos: usize) -> usize {
+    let after = &slice[pos..];
+    let word_end = after.find(|c| !is_word_char(c)).unwrap_or(after.len());
+    let next_word = after[word_end..]
+        .find(is_word_char)
+        .map(|i| word_end + i)
+        .unwrap_or(after.len());
+
+    pos + next_word
+}
+
+/// Gets the byte range of the word containing the char at `pos`.
+/// If that char is not part of a word, the range covers only that char.
+/// `pos` must already lie on a char boundary
+pub fn word_bounds(slice: &str, pos: usize) -> (usize, usize) {
+    match slice[pos..].chars().next() {
+        Some(c) if is_word_char(c) => {
+            let start = slice[..pos]
+                .rfind(|c| !is_word_char(c))
+                .map(|i| next_char_boundary(slice, i))
+                .unwrap_or(0);
+            let end = slice[pos..]
+                .find(|c| !is_word_char(c))
+                .map(|i| pos + i)
+                .unwrap_or_else(|| slice.len());
+
+            (start, end)
+        }
+        _ => (pos, next_char_boundary(slice, pos)),
+    }
+}
+
+/// Gets the byte range of the line containing `pos`, not including the
+/// line break at its end.
+/// `pos` must already lie on a char boundary
+pub fn line_bounds(slice: &str, pos: usize) -> (usize, usize) {
+    let is_line_break = |c: char| c == '\n' || c == '\r';
+    let start = slice[..pos]
+        .rfind(is_line_break)
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let end = slice[pos..]
+        .find(is_line_break)
+        .map(|i| pos + i)
+        .unwrap_or_else(|| slice.len());
+
+    (start, end)
+}
+
 /// Creates a `String` from an iterator of UTF-16 code units.
 /// TODO: Unpaired surrogates will get replaced with the Unicode replacement character.
 pub fn utf16_iter_to_string<I: Iterator<Item = u16>>(it: I) -> String {
@@ -44,6 +117,28 @@ pub fn utf16_code_unit_to_char(c: u16) -> char {
         .unwrap_or(char::REPLACEMENT_CHARACTER)
 }
 
+/// Encodes a string into the bytes seen by the SWF4 string actions in SWF5 and
+/// below, which operate on the locale encoding rather than on characters.
+/// Characters that the encoding can't represent become `?`.
+pub fn encode_locale(s: &str, encoding: &'static swf::Encoding) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut buf = [0; 4];
+    for c in s.chars() {
+        let (encoded, _, had_errors) = encoding.encode(c.encode_utf8(&mut buf));
+        if had_errors {
+            bytes.push(b'?');
+        } else {
+            bytes.extend_from_slice(&encoded);
+        }
+    }
+    bytes
+}
+
+/// Decodes bytes in the locale encoding back into a `String`.
+pub fn decode_locale(bytes: &[u8], encoding: &'static swf::Encoding) -> String {
+    encoding.decode_without_bom_handling(bytes).0.into_owned()
+}
+
 /// Maps a char to its lowercase variant according to the Flash Player.
 /// Note that this mapping is different that Rust's `to_lowercase`.
 pub fn swf_char_to_lowercase(c: char) -> char {
@@ -103,3 +198,111 @@ pub fn swf_string_cmp_ignore_case(a: &str, b: &str) -> std::cmp::Ordering {
         .map(swf_char_to_lowercase)
         .cmp(b.chars().map(swf_char_to_lowercase))
 }
+
+/// Finds the first match of `pattern` in `slice` at or after `from`,
+/// ignoring case as done by the Flash Player, and returns its byte range.
+/// An empty `pattern` never matches.
+/// `from` must already lie on a char boundary.
+pub fn swf_find_ignore_case(slice: &str, pattern: &str, from: usize) -> Option<(usize, usize)> {
+    if pattern.is_empty() {
+        return None;
+    }
+
+    slice[from..].char_indices().find_map(|(i, _)| {
+        let start = from + i;
+        let mut rest = slice[start..].char_indices();
+        for p in pattern.chars() {
+            match rest.next() {
+                Some((_, c)) if swf_char_to_lowercase(c) == swf_char_to_lowercase(p) => (),
+                _ => return None,
+            }
+        }
+        let len = rest.next().map(|(i, _)| i).unwrap_or(slice.len() - start);
+        Some((start, start + len))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prev_word_boundary_skips_punctuation_and_whitespace() {
+        let text = "Hello, world!  foo_bar";
+        assert_eq!(prev_word_boundary(text, 0), 0);
+        assert_eq!(prev_word_boundary(text, 3), 0);
+        assert_eq!(prev_word_boundary(text, 7), 0);
+        assert_eq!(prev_word_boundary(text, 12), 7);
+        assert_eq!(prev_word_boundary(text, 15), 7);
+        assert_eq!(prev_word_boundary(text, text.len()), 15);
+    }
+
+    #[test]
+    fn next_word_boundary_skips_punctuation_and_whitespace() {
+        let text = "Hello, world!  foo_bar";
+        assert_eq!(next_word_boundary(text, 0), 7);
+        assert_eq!(next_word_boundary(text, 5), 7);
+        assert_eq!(next_word_boundary(text, 7), 15);
+        assert_eq!(next_word_boundary(text, 12), 15);
+        assert_eq!(next_word_boundary(text, 15), text.len());
+        assert_eq!(next_word_boundary(text, text.len()), text.len());
+    }
+
+    #[test]
+    fn word_boundaries_without_words() {
+        let text = "  ,,  ";
+        assert_eq!(prev_word_boundary(text, 3), 0);
+        assert_eq!(prev_word_boundary(text, text.len()), 0);
+        assert_eq!(next_word_boundary(text, 0), text.len());
+        assert_eq!(next_word_boundary(text, 3), text.len());
+        assert_eq!(prev_word_boundary("", 0), 0);
+        assert_eq!(next_word_boundary("", 0), 0);
+    }
+
+    #[test]
+    fn word_boundaries_multibyte() {
+        let text = "héllo wörld 漢字 ok";
+        assert_eq!(next_word_boundary(text, 0), 7);
+        assert_eq!(next_word_boundary(text, 7), 14);
+        assert_eq!(next_word_boundary(text, 17), 21);
+        assert_eq!(prev_word_boundary(text, 13), 7);
+        assert_eq!(prev_word_boundary(text, 20), 14);
+        assert_eq!(prev_word_boundary(text, text.len()), 21);
+    }
+
+    #[test]
+    fn word_bounds_selects_words_or_single_chars() {
+        let text = "Hello, world!  foo_bar";
+        assert_eq!(word_bounds(text, 0), (0, 5));
+        assert_eq!(word_bounds(text, 3), (0, 5));
+        assert_eq!(word_bounds(text, 5), (5, 6));
+        assert_eq!(word_bounds(text, 14), (14, 15));
+        assert_eq!(word_bounds(text, 15), (15, text.len()));
+        assert_eq!(word_bounds(text, text.len()), (text.len(), text.len()));
+
+        let text = "héllo 漢字";
+        assert_eq!(word_bounds(text, 1), (0, 6));
+        assert_eq!(word_bounds(text, 10), (7, 13));
+    }
+
+    #[test]
+    fn line_bounds_excludes_line_breaks() {
+        let text = "ab\ncd\r\nef";
+        assert_eq!(line_bounds(text, 0), (0, 2));
+        assert_eq!(line_bounds(text, 2), (0, 2));
+        assert_eq!(line_bounds(text, 4), (3, 5));
+        assert_eq!(line_bounds(text, 7), (7, 9));
+        assert_eq!(line_bounds(text, text.len()), (7, 9));
+    }
+
+    #[test]
+    fn find_ignore_case_returns_byte_ranges() {
+        let text = "Hello HELLO héllo";
+        assert_eq!(swf_find_ignore_case(text, "hello", 0), Some((0, 5)));
+        assert_eq!(swf_find_ignore_case(text, "hello", 1), Some((6, 11)));
+        assert_eq!(swf_find_ignore_case(text, "HÉLLO", 0), Some((12, 18)));
+        assert_eq!(swf_find_ignore_case(text, "hello", 12), None);
+        assert_eq!(swf_find_ignore_case(text, "", 0), None);
+        assert_eq!(swf_find_ignore_case("he", "hello", 0), None);
+    }
+}
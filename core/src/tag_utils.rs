@@ -1,3 +1,4 @@
+use crate::backend::audio::{StreamData, SwfData};
 use crate::backend::navigator::url_from_relative_path;
 use crate::vminterface::AvmType;
 use gc_arena::Collect;
@@ -51,6 +52,22 @@ impl SwfMovie {
         }
     }
 
+    /// Construct a movie to stand in for an image loaded by a `Loader`, so
+    /// that the image's `LoaderInfo` has a URL and length to report.
+    pub fn from_loaded_image(
+        swf_version: u8,
+        url: String,
+        loader_url: Option<String>,
+        length: usize,
+    ) -> Self {
+        Self {
+            url: Some(url),
+            loader_url,
+            compressed_len: length,
+            ..Self::empty(swf_version)
+        }
+    }
+
     /// Utility method to construct a movie from a file on disk.
     pub fn from_path<P: AsRef<Path>>(path: P, loader_url: Option<String>) -> Result<Self, Error> {
         let mut url = path.as_ref().to_string_lossy().to_owned().to_string();
@@ -182,6 +199,22 @@ impl From<Arc<SwfMovie>> for SwfSlice {
     }
 }
 
+impl SwfData for SwfMovie {
+    fn data(&self) -> &[u8] {
+        SwfMovie::data(self)
+    }
+
+    fn version(&self) -> u8 {
+        SwfMovie::version(self)
+    }
+}
+
+impl From<SwfSlice> for StreamData {
+    fn from(slice: SwfSlice) -> Self {
+        StreamData::new(slice.movie, slice.start, slice.end)
+    }
+}
+
 impl AsRef<[u8]> for SwfSlice {
     #[inline]
     fn as_ref(&self) -> &[u8] {
@@ -318,12 +351,34 @@ pub fn decode_tags<'a, F>(
 where
     F: for<'b> FnMut(&'b mut SwfStream<'a>, TagCode, usize) -> DecodeResult,
 {
+    let data_len = reader.get_ref().len();
     loop {
-        let (tag_code, tag_len) = reader.read_tag_code_and_length()?;
+        let tag_offset = data_len - reader.get_ref().len();
+        let (tag_code, mut tag_len) = match reader.read_tag_code_and_length() {
+            Ok(header) => header,
+            Err(_) => {
+                // Truncated movies can end partway through a tag header, or
+                // without an End tag at all. Keep whatever was parsed so far.
+                if !reader.get_ref().is_empty() {
+                    log::warn!("Truncated tag header at offset {}", tag_offset);
+                }
+                *reader.get_mut() = &reader.get_ref()[reader.get_ref().len()..];
+                break;
+            }
+        };
+
         if tag_len > reader.get_ref().len() {
-            log::error!("Unexpected EOF when reading tag");
-            *reader.get_mut() = &reader.get_ref()[reader.get_ref().len()..];
-            break;
+            // Either the movie was truncated, or the exporter wrote a length
+            // that overruns the enclosing data. The original player still
+            // parses what is there, so clamp the tag instead of dropping it.
+            log::warn!(
+                "Tag {} at offset {} claims {} bytes, but only {} remain",
+                tag_code,
+                tag_offset,
+                tag_len,
+                reader.get_ref().len()
+            );
+            tag_len = reader.get_ref().len();
         }
 
         let tag_slice = &reader.get_ref()[..tag_len];
@@ -333,7 +388,12 @@ where
             let result = tag_callback(reader, tag, tag_len);
 
             if let Err(e) = result {
-                log::error!("Error running definition tag: {:?}, got {}", tag, e);
+                log::error!(
+                    "Error running definition tag: {:?} at offset {}, got {}",
+                    tag,
+                    tag_offset,
+                    e
+                );
             }
 
             if stop_tag == tag {
@@ -349,3 +409,49 @@ where
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decode a tag stream, returning the codes and lengths of the tags
+    /// passed to the callback.
+    fn decoded_tags(data: &[u8]) -> Vec<(TagCode, usize)> {
+        let mut tags = Vec::new();
+        let mut reader = SwfStream::new(data, 10);
+        decode_tags(
+            &mut reader,
+            |_, tag, tag_len| {
+                tags.push((tag, tag_len));
+                Ok(())
+            },
+            TagCode::End,
+        )
+        .unwrap();
+        tags
+    }
+
+    #[test]
+    fn decode_tags_without_end_tag() {
+        // ShowFrame, ShowFrame, and then the data stops.
+        let data = [0b0100_0000, 0, 0b0100_0000, 0];
+        assert_eq!(
+            decoded_tags(&data),
+            [(TagCode::ShowFrame, 0), (TagCode::ShowFrame, 0)]
+        );
+    }
+
+    #[test]
+    fn decode_tags_truncated_header() {
+        // ShowFrame, then half of a tag header.
+        let data = [0b0100_0000, 0, 0b0100_0000];
+        assert_eq!(decoded_tags(&data), [(TagCode::ShowFrame, 0)]);
+    }
+
+    #[test]
+    fn decode_tags_overlong_tag() {
+        // SetBackgroundColor claiming 10 bytes, with only 3 present.
+        let data = [0b0100_1010, 0b0000_0010, 0xff, 0x00, 0x00];
+        assert_eq!(decoded_tags(&data), [(TagCode::SetBackgroundColor, 3)]);
+    }
+}
@@ -1,13 +1,18 @@
-//! Timer handling for `setInterval` AVM timers.
+//! Timer handling for `setInterval`/`setTimeout` AVM timers.
 //!
 //! We tick the timers during our normal frame loop for deterministic operation.
 //! The timers are stored in a priority queue, where we check if the nearest timer
 //! is ready to tick each frame.
 //!
-//! TODO: Could we use this for AVM2 timers as well?
+//! Both AVM1 and AVM2 timers share this queue. As timers only fire during the
+//! frame loop, a callback will observe `getTimer()` values that have advanced
+//! by at least one frame's worth of time, even for very short delays.
 
 use crate::avm1::object::search_prototype;
-use crate::avm1::{Activation, ActivationIdentifier, Object, TObject, Value};
+use crate::avm1::{
+    Activation, ActivationIdentifier, Object as Avm1Object, TObject as _, Value as Avm1Value,
+};
+use crate::avm2::{Avm2, Object as Avm2Object, Value as Avm2Value};
 use crate::context::UpdateContext;
 use gc_arena::Collect;
 use std::collections::{binary_heap::PeekMut, BinaryHeap};
@@ -41,37 +46,27 @@ impl<'gc> Timers<'gc> {
         let globals = context.avm1.global_object_cell();
         let level0 = context.stage.root_clip();
 
-        let mut activation = Activation::from_nothing(
-            context.reborrow(),
-            ActivationIdentifier::root("[Timer Callback]"),
-            version,
-            globals,
-            level0,
-        );
-
-        // TODO: `this` is undefined for non-method timer callbacks, but our VM
-        // currently doesn't allow `this` to be a Value (#843).
-        let undefined = Value::Undefined.coerce_to_object(&mut activation);
+        // AVM1 callbacks that aren't methods all share one `this` object.
+        let mut undefined_this = None;
 
         let mut tick_count = 0;
-        let cur_time = activation.context.timers.cur_time;
+        let cur_time = context.timers.cur_time;
 
         // We have to be careful because the timer list can be mutated while updating;
         // a timer callback could add more timers, clear timers, etc.
-        while activation
-            .context
+        while context
             .timers
             .peek()
             .map(|timer| timer.tick_time)
             .unwrap_or(cur_time)
             < cur_time
         {
-            let timer = activation.context.timers.peek().unwrap();
+            let timer = context.timers.peek().unwrap();
 
             // TODO: This is only really necessary because BinaryHeap lacks `remove` or `retain` on stable.
             // We can remove the timers straight away in `clearInterval` once this is stable.
             if !timer.is_alive.get() {
-                activation.context.timers.pop();
+                context.timers.pop();
                 continue;
             }
 
@@ -79,47 +74,108 @@ impl<'gc> Timers<'gc> {
             // SANITY: Only allow so many ticks per timer per update.
             if tick_count > Self::MAX_TICKS {
                 // Reset our time to a little bit before the nearest timer.
-                let next_time = activation.context.timers.peek_mut().unwrap().tick_time;
-                activation.context.timers.cur_time = next_time.wrapping_sub(100);
+                let next_time = context.timers.peek_mut().unwrap().tick_time;
+                context.timers.cur_time = next_time.wrapping_sub(100);
                 break;
             }
 
-            // TODO: Can we avoid these clones?
-            let params = timer.params.clone();
+            // TODO: Can we avoid this clone?
             let callback = timer.callback.clone();
 
-            let callback = match callback {
-                TimerCallback::Function(f) => Some((undefined, None, f)),
-                TimerCallback::Method { this, method_name } => {
+            // Queued actions only run after a callback that was actually called,
+            // so a missing AVM1 method doesn't run them early.
+            let called = match callback {
+                TimerCallback::Avm1Function { func, params } => {
+                    let mut activation = Activation::from_nothing(
+                        context.reborrow(),
+                        ActivationIdentifier::root("[Timer Callback]"),
+                        version,
+                        globals,
+                        level0,
+                    );
+
+                    // TODO: `this` is undefined for non-method timer callbacks, but our VM
+                    // currently doesn't allow `this` to be a Value (#843).
+                    let undefined = *undefined_this.get_or_insert_with(|| {
+                        Avm1Value::Undefined.coerce_to_object(&mut activation)
+                    });
+
+                    let _ = func.call(
+                        "[Timer Callback]",
+                        &mut activation,
+                        undefined,
+                        None,
+                        &params,
+                    );
+                    true
+                }
+                TimerCallback::Avm1Method {
+                    this,
+                    method_name,
+                    params,
+                } => {
+                    let mut activation = Activation::from_nothing(
+                        context.reborrow(),
+                        ActivationIdentifier::root("[Timer Callback]"),
+                        version,
+                        globals,
+                        level0,
+                    );
+
                     // Fetch the callback method from the object.
-                    if let Ok((f, base_proto)) =
-                        search_prototype(Value::Object(this), &method_name, &mut activation, this)
-                    {
+                    if let Ok((f, base_proto)) = search_prototype(
+                        Avm1Value::Object(this),
+                        &method_name,
+                        &mut activation,
+                        this,
+                    ) {
                         let f = f.coerce_to_object(&mut activation);
-                        Some((this, base_proto, f))
+                        let _ = f.call(
+                            "[Timer Callback]",
+                            &mut activation,
+                            this,
+                            base_proto,
+                            &params,
+                        );
+                        true
                     } else {
-                        None
+                        false
                     }
                 }
+                TimerCallback::Avm2Callback {
+                    closure,
+                    params,
+                    worker,
+                } => {
+                    // The closure belongs to the VM of the worker that set the timer.
+                    let result = Avm2::run_in_worker(context, worker, |context| {
+                        if let Err(e) =
+                            Avm2::run_stack_frame_for_callable(closure, None, &params, context)
+                        {
+                            Avm2::report_uncaught_error(
+                                context,
+                                e,
+                                "Unhandled AVM2 error in timer callback",
+                            );
+                        }
+                    });
+                    if result.is_none() {
+                        // The worker has been terminated, so this timer can never fire again.
+                        context.timers.peek().unwrap().is_alive.set(false);
+                    }
+                    result.is_some()
+                }
             };
 
-            if let Some((this, base_proto, function)) = callback {
-                let _ = function.call(
-                    "[Timer Callback]",
-                    &mut activation,
-                    this,
-                    base_proto,
-                    &params,
-                );
-
-                crate::player::Player::run_actions(&mut activation.context);
+            if called {
+                crate::player::Player::run_actions(context);
             }
 
-            let mut timer = activation.context.timers.peek_mut().unwrap();
+            let mut timer = context.timers.peek_mut().unwrap();
             if timer.is_timeout {
                 // Timeouts only fire once.
                 drop(timer);
-                activation.context.timers.pop();
+                context.timers.pop();
             } else {
                 // Reset setInterval timers. `peek_mut` re-sorts the timer in the priority queue.
                 timer.tick_time = timer.tick_time.wrapping_add(timer.interval);
@@ -127,8 +183,7 @@ impl<'gc> Timers<'gc> {
         }
 
         // Return estimated time until next timer tick.
-        activation
-            .context
+        context
             .timers
             .peek()
             .map(|timer| (timer.tick_time.wrapping_sub(cur_time)) as f64 / Self::TIMER_SCALE)
@@ -162,7 +217,6 @@ impl<'gc> Timers<'gc> {
         &mut self,
         callback: TimerCallback<'gc>,
         interval: i32,
-        params: Vec<Value<'gc>>,
         is_timeout: bool,
     ) -> i32 {
         // SANITY: Set a minimum interval so we don't spam too much.
@@ -173,7 +227,6 @@ impl<'gc> Timers<'gc> {
         let timer = Timer {
             id,
             callback,
-            params,
             tick_time: self.cur_time + interval,
             interval,
             is_timeout,
@@ -228,13 +281,10 @@ struct Timer<'gc> {
     /// The ID of the timer.
     id: i32,
 
-    /// The callback that this timer runs when it fires.
-    /// A callback is either a function object, or a parent object with a method name.
+    /// The callback that this timer runs when it fires, along with the
+    /// parameters to pass to it.
     callback: TimerCallback<'gc>,
 
-    /// The parameters to pass to the callback function.
-    params: Vec<Value<'gc>>,
-
     /// The time when this timer should fire.
     tick_time: u64,
 
@@ -275,9 +325,26 @@ impl Ord for Timer<'_> {
 #[derive(Debug, Collect, Clone)]
 #[collect(no_drop)]
 pub enum TimerCallback<'gc> {
-    Function(Object<'gc>),
-    Method {
-        this: Object<'gc>,
+    /// An AVM1 function object.
+    Avm1Function {
+        func: Avm1Object<'gc>,
+        params: Vec<Avm1Value<'gc>>,
+    },
+
+    /// An AVM1 parent object with the name of a method to call on it.
+    Avm1Method {
+        this: Avm1Object<'gc>,
         method_name: String,
+        params: Vec<Avm1Value<'gc>>,
+    },
+
+    /// An AVM2 closure.
+    Avm2Callback {
+        closure: Avm2Object<'gc>,
+        params: Vec<Avm2Value<'gc>>,
+
+        /// The ID of the worker that set the timer, whose VM the closure
+        /// must run in.
+        worker: u32,
     },
 }
@@ -0,0 +1,442 @@
+//! Reading and writing `.zip` archives.
+//!
+//! Only what bundles and bug reports need is supported: files that are either
+//! stored or deflated, in archives without encryption, spanning, or ZIP64
+//! extensions.
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+use thiserror::Error;
+
+const LOCAL_FILE_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_DIRECTORY_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+
+/// The size of the end of central directory record, without its comment.
+const END_OF_CENTRAL_DIRECTORY_LEN: usize = 22;
+
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATED: u16 = 8;
+
+const FLAG_ENCRYPTED: u16 = 1 << 0;
+const FLAG_UTF8: u16 = 1 << 11;
+
+/// The version of the format needed to read the files we write, which is the
+/// first to support deflate.
+const VERSION_NEEDED: u16 = 20;
+
+/// 1980-01-01, the earliest date an archive can hold, in MS-DOS format.
+const DOS_EPOCH: u16 = (1 << 5) | 1;
+
+/// The size of a central directory entry, without its name, extra field and
+/// comment.
+const CENTRAL_DIRECTORY_HEADER_LEN: usize = 46;
+
+/// The size of a local file header, without its name and extra field.
+const LOCAL_FILE_HEADER_LEN: usize = 30;
+
+/// The most that deflate can compress data by.
+const MAX_DEFLATE_RATIO: usize = 1032;
+
+#[derive(Error, Debug)]
+pub enum ZipError {
+    #[error("Not a zip archive, or the archive is truncated")]
+    InvalidArchive,
+
+    #[error("File {0} uses unsupported compression method {1}")]
+    UnsupportedCompression(String, u16),
+
+    #[error("File {0} is encrypted")]
+    Encrypted(String),
+
+    #[error("File {0} is corrupt")]
+    Corrupt(String),
+
+    #[error("Archive is too large")]
+    TooLarge,
+
+    #[error("Could not compress or decompress a file: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// How the contents of a file are stored in an archive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompressionMethod {
+    Stored,
+    Deflated,
+}
+
+/// A file read from an archive.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ZipFile {
+    /// The path of the file within the archive.
+    pub name: String,
+
+    /// The decompressed contents of the file.
+    pub contents: Vec<u8>,
+}
+
+/// Add to an offset read from an archive, which may be large enough to
+/// overflow on 32-bit platforms.
+fn advance(offset: usize, len: usize) -> Result<usize, ZipError> {
+    offset.checked_add(len).ok_or(ZipError::InvalidArchive)
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Result<u16, ZipError> {
+    bytes_at(data, offset, 2).map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<u32, ZipError> {
+    bytes_at(data, offset, 4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn bytes_at(data: &[u8], offset: usize, len: usize) -> Result<&[u8], ZipError> {
+    data.get(offset..advance(offset, len)?)
+        .ok_or(ZipError::InvalidArchive)
+}
+
+/// Find the end of central directory record, which is followed by a comment
+/// of up to 64KiB.
+fn find_end_of_central_directory(data: &[u8]) -> Result<usize, ZipError> {
+    let last = data
+        .len()
+        .checked_sub(END_OF_CENTRAL_DIRECTORY_LEN)
+        .ok_or(ZipError::InvalidArchive)?;
+    let first = last.saturating_sub(u16::MAX as usize);
+
+    (first..=last)
+        .rev()
+        .find(|offset| u32_at(data, *offset).ok() == Some(END_OF_CENTRAL_DIRECTORY))
+        .ok_or(ZipError::InvalidArchive)
+}
+
+/// Read every file in an archive, in the order of its central directory.
+///
+/// Directories are skipped.
+pub fn read_archive(data: &[u8]) -> Result<Vec<ZipFile>, ZipError> {
+    let end = find_end_of_central_directory(data)?;
+    let count = u16_at(data, end + 10)?;
+    let mut offset = u32_at(data, end + 16)? as usize;
+
+    // The count is only trusted as far as the archive could hold that many
+    // entries.
+    let mut files = Vec::with_capacity(usize::from(count).min(end / CENTRAL_DIRECTORY_HEADER_LEN));
+    for _ in 0..count {
+        if u32_at(data, offset)? != CENTRAL_DIRECTORY_HEADER {
+            return Err(ZipError::InvalidArchive);
+        }
+
+        let header = bytes_at(data, offset, CENTRAL_DIRECTORY_HEADER_LEN)?;
+        let flags = u16_at(header, 8)?;
+        let method = u16_at(header, 10)?;
+        let crc = u32_at(header, 16)?;
+        let compressed_size = u32_at(header, 20)? as usize;
+        let size = u32_at(header, 24)? as usize;
+        let name_len = u16_at(header, 28)? as usize;
+        let extra_len = u16_at(header, 30)? as usize;
+        let comment_len = u16_at(header, 32)? as usize;
+        let header_offset = u32_at(header, 42)? as usize;
+        offset = advance(offset, CENTRAL_DIRECTORY_HEADER_LEN)?;
+        let name = String::from_utf8_lossy(bytes_at(data, offset, name_len)?).into_owned();
+        offset = advance(offset, name_len + extra_len + comment_len)?;
+
+        if name.ends_with('/') {
+            continue;
+        }
+
+        if flags & FLAG_ENCRYPTED != 0 {
+            return Err(ZipError::Encrypted(name));
+        }
+
+        // The local header repeats most of the central directory entry, but
+        // its extra field may differ in length.
+        if u32_at(data, header_offset)? != LOCAL_FILE_HEADER {
+            return Err(ZipError::InvalidArchive);
+        }
+        let local_header = bytes_at(data, header_offset, LOCAL_FILE_HEADER_LEN)?;
+        let local_name_len = u16_at(local_header, 26)? as usize;
+        let local_extra_len = u16_at(local_header, 28)? as usize;
+        let compressed = bytes_at(
+            data,
+            advance(
+                header_offset,
+                LOCAL_FILE_HEADER_LEN + local_name_len + local_extra_len,
+            )?,
+            compressed_size,
+        )?;
+
+        let contents = match method {
+            METHOD_STORED => compressed.to_vec(),
+            METHOD_DEFLATED => {
+                let capacity = size.min(compressed.len().saturating_mul(MAX_DEFLATE_RATIO));
+                let mut contents = Vec::with_capacity(capacity);
+                // Decompress at most one byte more than the declared size, which
+                // is enough to tell that the file is corrupt.
+                DeflateDecoder::new(compressed)
+                    .take(size as u64 + 1)
+                    .read_to_end(&mut contents)?;
+                contents
+            }
+            method => return Err(ZipError::UnsupportedCompression(name, method)),
+        };
+
+        let mut actual_crc = Crc::new();
+        actual_crc.update(&contents);
+        if contents.len() != size || actual_crc.sum() != crc {
+            return Err(ZipError::Corrupt(name));
+        }
+
+        files.push(ZipFile { name, contents });
+    }
+
+    Ok(files)
+}
+
+/// Writes files into a new archive.
+#[derive(Debug, Default)]
+pub struct ZipWriter {
+    /// The local headers and contents of the files written so far.
+    data: Vec<u8>,
+
+    /// The central directory entries of the files written so far.
+    central_directory: Vec<u8>,
+
+    /// How many files have been written.
+    count: u16,
+}
+
+impl ZipWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file to the archive.
+    pub fn add_file(
+        &mut self,
+        name: &str,
+        contents: &[u8],
+        method: CompressionMethod,
+    ) -> Result<(), ZipError> {
+        let (method, compressed) = match method {
+            CompressionMethod::Stored => (METHOD_STORED, contents.to_vec()),
+            CompressionMethod::Deflated => {
+                let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(contents)?;
+                (METHOD_DEFLATED, encoder.finish()?)
+            }
+        };
+
+        let mut crc = Crc::new();
+        crc.update(contents);
+
+        let too_large = |len: usize| u32::try_from(len).map_err(|_| ZipError::TooLarge);
+        let header_offset = too_large(self.data.len())?;
+        let compressed_size = too_large(compressed.len())?;
+        let size = too_large(contents.len())?;
+        let name_len = u16::try_from(name.len()).map_err(|_| ZipError::TooLarge)?;
+        self.count = self.count.checked_add(1).ok_or(ZipError::TooLarge)?;
+
+        // The fields shared by the local header and the central directory,
+        // from the version needed to extract up to the extra field length.
+        let mut common = Vec::with_capacity(26);
+        common.extend_from_slice(&VERSION_NEEDED.to_le_bytes());
+        common.extend_from_slice(&FLAG_UTF8.to_le_bytes());
+        common.extend_from_slice(&method.to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes()); // Time
+        common.extend_from_slice(&DOS_EPOCH.to_le_bytes());
+        common.extend_from_slice(&crc.sum().to_le_bytes());
+        common.extend_from_slice(&compressed_size.to_le_bytes());
+        common.extend_from_slice(&size.to_le_bytes());
+        common.extend_from_slice(&name_len.to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes()); // Extra field length
+
+        self.data
+            .extend_from_slice(&LOCAL_FILE_HEADER.to_le_bytes());
+        self.data.extend_from_slice(&common);
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(&compressed);
+
+        let directory = &mut self.central_directory;
+        directory.extend_from_slice(&CENTRAL_DIRECTORY_HEADER.to_le_bytes());
+        directory.extend_from_slice(&VERSION_NEEDED.to_le_bytes()); // Version made by
+        directory.extend_from_slice(&common);
+        directory.extend_from_slice(&0u16.to_le_bytes()); // Comment length
+        directory.extend_from_slice(&0u16.to_le_bytes()); // Disk number
+        directory.extend_from_slice(&0u16.to_le_bytes()); // Internal attributes
+        directory.extend_from_slice(&0u32.to_le_bytes()); // External attributes
+        directory.extend_from_slice(&header_offset.to_le_bytes());
+        directory.extend_from_slice(name.as_bytes());
+
+        Ok(())
+    }
+
+    /// Finish the archive, returning its contents.
+    pub fn finish(mut self) -> Result<Vec<u8>, ZipError> {
+        let directory_offset = u32::try_from(self.data.len()).map_err(|_| ZipError::TooLarge)?;
+        let directory_size =
+            u32::try_from(self.central_directory.len()).map_err(|_| ZipError::TooLarge)?;
+
+        self.data.append(&mut self.central_directory);
+        self.data
+            .extend_from_slice(&END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+        self.data.extend_from_slice(&0u16.to_le_bytes()); // Disk number
+        self.data.extend_from_slice(&0u16.to_le_bytes()); // Disk with the directory
+        self.data.extend_from_slice(&self.count.to_le_bytes());
+        self.data.extend_from_slice(&self.count.to_le_bytes());
+        self.data.extend_from_slice(&directory_size.to_le_bytes());
+        self.data.extend_from_slice(&directory_offset.to_le_bytes());
+        self.data.extend_from_slice(&0u16.to_le_bytes()); // Comment length
+
+        Ok(self.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An archive written by Python's `zipfile`, with a directory, a stored
+    /// file, a deflated file, and a comment.
+    const ARCHIVE: &[u8] = &[
+        0x50, 0x4B, 0x03, 0x04, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x4F, 0x92, 0x4F, 0x5D, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00,
+        0x64, 0x69, 0x72, 0x2F, 0x50, 0x4B, 0x03, 0x04, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0xA3,
+        0x20, 0x43, 0x2A, 0x0B, 0xF9, 0x43, 0x56, 0x06, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00,
+        0x0A, 0x00, 0x00, 0x00, 0x73, 0x74, 0x6F, 0x72, 0x65, 0x64, 0x2E, 0x74, 0x78, 0x74, 0x73,
+        0x74, 0x6F, 0x72, 0x65, 0x64, 0x50, 0x4B, 0x03, 0x04, 0x14, 0x00, 0x00, 0x00, 0x08, 0x00,
+        0xA3, 0x20, 0x43, 0x2A, 0xFD, 0x2E, 0x67, 0xC7, 0x0E, 0x00, 0x00, 0x00, 0x1A, 0x00, 0x00,
+        0x00, 0x10, 0x00, 0x00, 0x00, 0x64, 0x69, 0x72, 0x2F, 0x64, 0x65, 0x66, 0x6C, 0x61, 0x74,
+        0x65, 0x64, 0x2E, 0x74, 0x78, 0x74, 0x4B, 0x49, 0x4D, 0xCB, 0x49, 0x2C, 0x49, 0x4D, 0x51,
+        0x48, 0x41, 0x67, 0x00, 0x00, 0x50, 0x4B, 0x01, 0x02, 0x14, 0x03, 0x14, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x4F, 0x92, 0x4F, 0x5D, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00,
+        0xFD, 0x41, 0x00, 0x00, 0x00, 0x00, 0x64, 0x69, 0x72, 0x2F, 0x50, 0x4B, 0x01, 0x02, 0x14,
+        0x03, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0xA3, 0x20, 0x43, 0x2A, 0x0B, 0xF9, 0x43, 0x56,
+        0x06, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x01, 0x22, 0x00, 0x00, 0x00, 0x73, 0x74, 0x6F, 0x72,
+        0x65, 0x64, 0x2E, 0x74, 0x78, 0x74, 0x50, 0x4B, 0x01, 0x02, 0x14, 0x03, 0x14, 0x00, 0x00,
+        0x00, 0x08, 0x00, 0xA3, 0x20, 0x43, 0x2A, 0xFD, 0x2E, 0x67, 0xC7, 0x0E, 0x00, 0x00, 0x00,
+        0x1A, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x80, 0x01, 0x50, 0x00, 0x00, 0x00, 0x64, 0x69, 0x72, 0x2F, 0x64, 0x65, 0x66, 0x6C,
+        0x61, 0x74, 0x65, 0x64, 0x2E, 0x74, 0x78, 0x74, 0x50, 0x4B, 0x05, 0x06, 0x00, 0x00, 0x00,
+        0x00, 0x03, 0x00, 0x03, 0x00, 0xA8, 0x00, 0x00, 0x00, 0x8C, 0x00, 0x00, 0x00, 0x07, 0x00,
+        0x63, 0x6F, 0x6D, 0x6D, 0x65, 0x6E, 0x74,
+    ];
+
+    #[test]
+    fn reads_archive() {
+        let files = read_archive(ARCHIVE).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                ZipFile {
+                    name: "stored.txt".to_string(),
+                    contents: b"stored".to_vec(),
+                },
+                ZipFile {
+                    name: "dir/deflated.txt".to_string(),
+                    contents: b"deflated deflated deflated".to_vec(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        let mut writer = ZipWriter::new();
+        writer
+            .add_file("a.txt", b"aaaaaaaaaaaaaaaa", CompressionMethod::Deflated)
+            .unwrap();
+        writer
+            .add_file("b/b.png", b"not really a png", CompressionMethod::Stored)
+            .unwrap();
+        let data = writer.finish().unwrap();
+
+        let files = read_archive(&data).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].name, "a.txt");
+        assert_eq!(files[0].contents, b"aaaaaaaaaaaaaaaa");
+        assert_eq!(files[1].name, "b/b.png");
+        assert_eq!(files[1].contents, b"not really a png");
+    }
+
+    #[test]
+    fn rejects_invalid_archives() {
+        assert!(matches!(
+            read_archive(b"FWS"),
+            Err(ZipError::InvalidArchive)
+        ));
+        assert!(matches!(
+            read_archive(&ARCHIVE[..ARCHIVE.len() / 2]),
+            Err(ZipError::InvalidArchive)
+        ));
+
+        // Flip a byte of the stored file's contents.
+        let mut corrupt = ARCHIVE.to_vec();
+        corrupt[0x4A] ^= 0xFF;
+        assert!(matches!(read_archive(&corrupt), Err(ZipError::Corrupt(_))));
+    }
+
+    /// An archive with one highly compressed file and no comment.
+    fn deflated_archive() -> Vec<u8> {
+        let mut writer = ZipWriter::new();
+        writer
+            .add_file("a.txt", &[b'a'; 4096], CompressionMethod::Deflated)
+            .unwrap();
+        writer.finish().unwrap()
+    }
+
+    /// Overwrite a little-endian `u32` in an archive.
+    fn set_u32(data: &mut [u8], offset: usize, value: u32) {
+        data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    #[test]
+    fn rejects_wrong_declared_size() {
+        let end = deflated_archive().len() - END_OF_CENTRAL_DIRECTORY_LEN;
+
+        // Declaring less than the file decompresses to, as a zip bomb would,
+        // or far more.
+        for size in [16, u32::MAX].iter() {
+            let mut data = deflated_archive();
+            let directory = u32_at(&data, end + 16).unwrap() as usize;
+            set_u32(&mut data, directory + 24, *size);
+            assert!(matches!(read_archive(&data), Err(ZipError::Corrupt(_))));
+        }
+    }
+
+    #[test]
+    fn rejects_out_of_range_offsets() {
+        let end = deflated_archive().len() - END_OF_CENTRAL_DIRECTORY_LEN;
+
+        // A central directory past the end of the archive.
+        let mut data = deflated_archive();
+        set_u32(&mut data, end + 16, u32::MAX);
+        assert!(matches!(read_archive(&data), Err(ZipError::InvalidArchive)));
+
+        // A local header past the end of the archive.
+        let mut data = deflated_archive();
+        let directory = u32_at(&data, end + 16).unwrap() as usize;
+        set_u32(&mut data, directory + 42, u32::MAX);
+        assert!(matches!(read_archive(&data), Err(ZipError::InvalidArchive)));
+
+        // Far more entries than the archive could hold.
+        let mut data = deflated_archive();
+        data[end + 10..end + 12].copy_from_slice(&u16::MAX.to_le_bytes());
+        assert!(matches!(read_archive(&data), Err(ZipError::InvalidArchive)));
+
+        // Offsets that would overflow, as they can on 32-bit platforms.
+        assert!(matches!(
+            advance(usize::MAX, 1),
+            Err(ZipError::InvalidArchive)
+        ));
+        assert!(matches!(
+            u32_at(&data, usize::MAX - 1),
+            Err(ZipError::InvalidArchive)
+        ));
+    }
+}
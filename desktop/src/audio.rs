@@ -1,78 +1,524 @@
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use ruffle_core::backend::audio::{
-    swf, AudioBackend, AudioMixer, SoundHandle, SoundInstanceHandle, SoundTransform,
+    swf, AudioBackend, AudioMixer, AudioMixerProxy, SoundHandle, SoundInstanceHandle,
+    SoundTransform,
 };
 use ruffle_core::impl_audio_mixer_backend;
-use std::convert::TryInto;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How often to check whether the output device should change.
+const DEVICE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Records when audio output is interrupted, such as by its device being
+/// unplugged, so that the player can react to it.
+#[derive(Clone, Default)]
+pub struct AudioInterruptions(Arc<AtomicBool>);
+
+impl AudioInterruptions {
+    /// Whether audio was interrupted since the last call.
+    pub fn take(&self) -> bool {
+        self.0.swap(false, Ordering::Relaxed)
+    }
+
+    fn raise(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
 
 pub struct CpalAudioBackend {
-    #[allow(dead_code)]
-    device: cpal::Device,
-    #[allow(dead_code)]
-    config: cpal::StreamConfig,
-    stream: cpal::Stream,
+    /// The name of the output device the user asked for, or `None` to follow
+    /// the system default device.
+    requested_device: Option<String>,
+
+    /// The name of the device that is currently playing audio.
+    device_name: Option<String>,
+
+    /// The stream playing audio, or `None` if no device is available.
+    stream: Option<cpal::Stream>,
+
+    /// Set by the stream when its device is no longer available.
+    stream_failed: Arc<AtomicBool>,
+
+    /// Raised along with `stream_failed`, but only cleared by the player.
+    interruptions: AudioInterruptions,
+
+    /// When the output device was last checked for changes.
+    last_device_check: Instant,
+
+    is_playing: bool,
+
+    /// The output format of the mixer. This is fixed when the backend is
+    /// created, so that sounds keep playing from the same position when the
+    /// stream is moved to a device with a different format.
+    mixer_channels: u16,
+    mixer_sample_rate: u32,
     mixer: AudioMixer,
 }
 
 type Error = Box<dyn std::error::Error>;
 
 impl CpalAudioBackend {
-    pub fn new() -> Result<Self, Error> {
-        // Create CPAL audio device.
-        let host = cpal::default_host();
-        let device = host
-            .default_output_device()
-            .ok_or("No audio devices available")?;
-
-        // Create audio stream for device.
+    /// Create an audio backend playing on the named output device, or on the
+    /// system default device if `device_name` is `None`.
+    pub fn new(device_name: Option<&str>) -> Result<Self, Error> {
+        let requested_device = device_name.map(str::to_string);
+        let (device, is_fallback) = find_output_device(requested_device.as_deref())?;
+        if is_fallback {
+            log_fallback(device_name);
+        }
         let config = device.default_output_config()?;
-        let sample_format = config.sample_format();
-        let config = cpal::StreamConfig::from(config);
-        let mixer = AudioMixer::new(config.channels.try_into()?, config.sample_rate.0);
-
-        // Start the audio stream.
-        let stream = {
-            let mixer = mixer.proxy();
-            let error_handler = move |err| log::error!("Audio stream error: {}", err);
-
-            match sample_format {
-                cpal::SampleFormat::F32 => device.build_output_stream(
-                    &config,
-                    move |buffer, _| mixer.mix::<f32>(buffer),
-                    error_handler,
-                ),
-                cpal::SampleFormat::I16 => device.build_output_stream(
-                    &config,
-                    move |buffer, _| mixer.mix::<i16>(buffer),
-                    error_handler,
-                ),
-                cpal::SampleFormat::U16 => device.build_output_stream(
-                    &config,
-                    move |buffer, _| mixer.mix::<u16>(buffer),
-                    error_handler,
-                ),
-            }?
-        };
 
+        // The mixer only supports mono and stereo output; any extra channels
+        // of the device are left silent.
+        let mixer_channels = config.channels().min(2);
+        let mixer_sample_rate = config.sample_rate().0;
+        let mixer = AudioMixer::new(mixer_channels as u8, mixer_sample_rate);
+
+        let stream_failed = Arc::new(AtomicBool::new(false));
+        let interruptions = AudioInterruptions::default();
+        let stream = open_stream(
+            &device,
+            mixer.proxy(),
+            mixer_channels,
+            mixer_sample_rate,
+            Arc::clone(&stream_failed),
+            interruptions.clone(),
+        )?;
         stream.play()?;
 
         Ok(Self {
-            device,
-            config,
-            stream,
+            requested_device,
+            device_name: device.name().ok(),
+            stream: Some(stream),
+            stream_failed,
+            interruptions,
+            last_device_check: Instant::now(),
+            is_playing: true,
+            mixer_channels,
+            mixer_sample_rate,
             mixer,
         })
     }
+
+    /// Move audio output to the device that should currently be used.
+    ///
+    /// Sounds are kept in the mixer rather than the stream, so they carry on
+    /// from where they were on the new device.
+    fn reopen_stream(&mut self) {
+        self.stream = None;
+        self.device_name = None;
+        self.stream_failed.store(false, Ordering::Relaxed);
+
+        let result = find_output_device(self.requested_device.as_deref()).and_then(
+            |(device, is_fallback)| {
+                if is_fallback {
+                    log_fallback(self.requested_device.as_deref());
+                }
+
+                let stream = open_stream(
+                    &device,
+                    self.mixer.proxy(),
+                    self.mixer_channels,
+                    self.mixer_sample_rate,
+                    Arc::clone(&self.stream_failed),
+                    self.interruptions.clone(),
+                )?;
+                if self.is_playing {
+                    stream.play()?;
+                }
+                Ok((stream, device.name().ok()))
+            },
+        );
+
+        match result {
+            Ok((stream, device_name)) => {
+                log::info!(
+                    "Audio output is now on {}",
+                    device_name.as_deref().unwrap_or("an unnamed device")
+                );
+                self.stream = Some(stream);
+                self.device_name = device_name;
+            }
+            Err(e) => log::warn!("Unable to open audio device: {}", e),
+        }
+    }
+
+    /// A handle that reports when audio output is interrupted.
+    pub fn interruptions(&self) -> AudioInterruptions {
+        self.interruptions.clone()
+    }
+
+    /// The stream to play or pause, unless it has failed. A failed stream is
+    /// left alone until it is reopened.
+    fn stream(&self) -> Option<&cpal::Stream> {
+        if self.stream_failed.load(Ordering::Relaxed) {
+            None
+        } else {
+            self.stream.as_ref()
+        }
+    }
+
+    /// Whether the device we would pick now differs from the one playing.
+    fn device_changed(&self) -> bool {
+        match find_output_device(self.requested_device.as_deref()) {
+            Ok((device, _)) => device.name().ok() != self.device_name,
+            Err(_) => false,
+        }
+    }
 }
 
 impl AudioBackend for CpalAudioBackend {
     impl_audio_mixer_backend!(mixer);
 
     fn play(&mut self) {
-        self.stream.play().expect("Error trying to resume CPAL audio stream. This feature may not be supported by your audio device.");
+        self.is_playing = true;
+        if let Some(stream) = self.stream() {
+            stream.play().expect("Error trying to resume CPAL audio stream. This feature may not be supported by your audio device.");
+        }
     }
 
     fn pause(&mut self) {
-        self.stream.pause().expect("Error trying to pause CPAL audio stream. This feature may not be supported by your audio device.");
+        self.is_playing = false;
+        if let Some(stream) = self.stream() {
+            stream.pause().expect("Error trying to pause CPAL audio stream. This feature may not be supported by your audio device.");
+        }
+    }
+
+    fn tick(&mut self) {
+        if self.last_device_check.elapsed() < DEVICE_CHECK_INTERVAL {
+            return;
+        }
+        self.last_device_check = Instant::now();
+
+        // Reopen the stream if its device was unplugged, or if the system
+        // default (or the requested device, once it's plugged back in) is now
+        // a different device.
+        if self.stream.is_none()
+            || self.stream_failed.load(Ordering::Relaxed)
+            || self.device_changed()
+        {
+            self.reopen_stream();
+        }
+    }
+}
+
+/// List the names of the available audio output devices.
+pub fn output_device_names() -> Result<Vec<String>, Error> {
+    let host = cpal::default_host();
+    Ok(host
+        .output_devices()?
+        .filter_map(|device| device.name().ok())
+        .collect())
+}
+
+/// Find an output device by name, falling back to the default device if it
+/// isn't available.
+///
+/// Names are matched exactly if possible, and otherwise by a case-insensitive
+/// substring. Also returns whether the default device was used in place of
+/// the named one.
+fn find_output_device(name: Option<&str>) -> Result<(cpal::Device, bool), Error> {
+    let host = cpal::default_host();
+
+    if let Some(name) = name {
+        let devices: Vec<_> = host.output_devices()?.collect();
+        let names: Vec<_> = devices.iter().map(|device| device.name().ok()).collect();
+        if let Some(index) = match_device_name(&names, name) {
+            return Ok((devices.into_iter().nth(index).unwrap(), false));
+        }
+    }
+
+    let device = host
+        .default_output_device()
+        .ok_or("No audio devices available")?;
+    Ok((device, name.is_some()))
+}
+
+/// Find the index of the device name that best matches `name`: an exact
+/// match if there is one, and otherwise the first name containing it,
+/// ignoring case.
+fn match_device_name(names: &[Option<String>], name: &str) -> Option<usize> {
+    let lowercase_name = name.to_lowercase();
+    names
+        .iter()
+        .position(|device_name| device_name.as_deref() == Some(name))
+        .or_else(|| {
+            names.iter().position(|device_name| {
+                device_name
+                    .as_ref()
+                    .map(|device_name| device_name.to_lowercase().contains(&lowercase_name))
+                    .unwrap_or(false)
+            })
+        })
+}
+
+fn log_fallback(name: Option<&str>) {
+    log::warn!(
+        "Audio device \"{}\" is not available, using the default device",
+        name.unwrap_or_default()
+    );
+}
+
+/// Start an audio stream on a device, fed by the mixer.
+fn open_stream(
+    device: &cpal::Device,
+    mixer: AudioMixerProxy,
+    mixer_channels: u16,
+    mixer_sample_rate: u32,
+    stream_failed: Arc<AtomicBool>,
+    interruptions: AudioInterruptions,
+) -> Result<cpal::Stream, Error> {
+    let config = device.default_output_config()?;
+    let sample_format = config.sample_format();
+    let config = cpal::StreamConfig::from(config);
+
+    let error_handler = move |err: cpal::StreamError| {
+        log::error!("Audio stream error: {}", err);
+        if let cpal::StreamError::DeviceNotAvailable = err {
+            stream_failed.store(true, Ordering::Relaxed);
+            interruptions.raise();
+        }
+    };
+
+    let stream = if config.channels == mixer_channels && config.sample_rate.0 == mixer_sample_rate {
+        match sample_format {
+            cpal::SampleFormat::F32 => device.build_output_stream(
+                &config,
+                move |buffer, _| mixer.mix::<f32>(buffer),
+                error_handler,
+            ),
+            cpal::SampleFormat::I16 => device.build_output_stream(
+                &config,
+                move |buffer, _| mixer.mix::<i16>(buffer),
+                error_handler,
+            ),
+            cpal::SampleFormat::U16 => device.build_output_stream(
+                &config,
+                move |buffer, _| mixer.mix::<u16>(buffer),
+                error_handler,
+            ),
+        }?
+    } else {
+        let mut converter = FormatConverter::new(
+            move |samples: &mut [f32]| mixer.mix::<f32>(samples),
+            mixer_channels,
+            mixer_sample_rate,
+            config.channels,
+            config.sample_rate.0,
+        );
+        match sample_format {
+            cpal::SampleFormat::F32 => device.build_output_stream(
+                &config,
+                move |buffer: &mut [f32], _| converter.mix(buffer),
+                error_handler,
+            ),
+            cpal::SampleFormat::I16 => device.build_output_stream(
+                &config,
+                move |buffer: &mut [i16], _| converter.mix(buffer),
+                error_handler,
+            ),
+            cpal::SampleFormat::U16 => device.build_output_stream(
+                &config,
+                move |buffer: &mut [u16], _| converter.mix(buffer),
+                error_handler,
+            ),
+        }?
+    };
+
+    Ok(stream)
+}
+
+/// Converts the output of the mixer to the channel count and sample rate of
+/// a device that doesn't match it.
+struct FormatConverter<M> {
+    /// Fills a buffer with interleaved samples from the mixer.
+    mixer: M,
+    mixer_channels: usize,
+    output_channels: usize,
+
+    /// How many mixer sample frames pass for each device sample frame.
+    step: f64,
+
+    /// Mixed samples that haven't been fully consumed yet, interleaved.
+    samples: Vec<f32>,
+
+    /// The position of the next device sample frame, in mixer sample frames
+    /// from the start of `samples`.
+    position: f64,
+}
+
+impl<M: FnMut(&mut [f32])> FormatConverter<M> {
+    /// How many sample frames to mix at a time.
+    const MIX_FRAMES: usize = 256;
+
+    fn new(
+        mixer: M,
+        mixer_channels: u16,
+        mixer_sample_rate: u32,
+        output_channels: u16,
+        output_sample_rate: u32,
+    ) -> Self {
+        Self {
+            mixer,
+            mixer_channels: mixer_channels.into(),
+            output_channels: output_channels.into(),
+            step: f64::from(mixer_sample_rate) / f64::from(output_sample_rate),
+            samples: Vec::new(),
+            position: 0.0,
+        }
+    }
+
+    fn mix<T: cpal::Sample>(&mut self, output: &mut [T]) {
+        let mixer_channels = self.mixer_channels;
+        for output_frame in output.chunks_mut(self.output_channels) {
+            // Linearly interpolate between the two nearest mixer frames.
+            let index = self.position as usize;
+            while (index + 2) * mixer_channels > self.samples.len() {
+                let start = self.samples.len();
+                self.samples
+                    .resize(start + Self::MIX_FRAMES * mixer_channels, 0.0);
+                (self.mixer)(&mut self.samples[start..]);
+            }
+            let fraction = self.position.fract() as f32;
+            let current = &self.samples[index * mixer_channels..];
+            let next = &self.samples[(index + 1) * mixer_channels..];
+
+            for (channel, output_sample) in output_frame.iter_mut().enumerate() {
+                let sample_at = |c: usize| current[c] + (next[c] - current[c]) * fraction;
+
+                // Mono is played on both front channels, and stereo is mixed
+                // down for mono devices.
+                let sample = if self.output_channels == 1 && mixer_channels == 2 {
+                    (sample_at(0) + sample_at(1)) / 2.0
+                } else if channel < mixer_channels {
+                    sample_at(channel)
+                } else if mixer_channels == 1 && channel == 1 {
+                    sample_at(0)
+                } else {
+                    0.0
+                };
+                *output_sample = T::from(&sample);
+            }
+
+            self.position += self.step;
+        }
+
+        // Discard the frames that have been played.
+        let consumed = self.position as usize;
+        self.samples.drain(..consumed * mixer_channels);
+        self.position -= consumed as f64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A converter fed by a mono ramp, counting up by one each sample frame.
+    fn ramp_converter(
+        mixer_sample_rate: u32,
+        output_channels: u16,
+        output_sample_rate: u32,
+    ) -> FormatConverter<impl FnMut(&mut [f32])> {
+        let mut next = 0.0;
+        FormatConverter::new(
+            move |samples: &mut [f32]| {
+                for sample in samples {
+                    *sample = next;
+                    next += 1.0;
+                }
+            },
+            1,
+            mixer_sample_rate,
+            output_channels,
+            output_sample_rate,
+        )
+    }
+
+    #[test]
+    fn mono_is_played_on_both_front_channels() {
+        let mut converter = ramp_converter(44100, 4, 44100);
+        let mut output = [f32::NAN; 8];
+        converter.mix(&mut output);
+        assert_eq!(output, [0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn stereo_is_mixed_down_for_mono_devices() {
+        let mut frame = 0.0;
+        let mut converter = FormatConverter::new(
+            move |samples: &mut [f32]| {
+                for pair in samples.chunks_mut(2) {
+                    pair[0] = frame;
+                    pair[1] = -2.0 * frame;
+                    frame += 1.0;
+                }
+            },
+            2,
+            22050,
+            1,
+            22050,
+        );
+        let mut output = [f32::NAN; 3];
+        converter.mix(&mut output);
+        assert_eq!(output, [0.0, -0.5, -1.0]);
+    }
+
+    #[test]
+    fn samples_are_linearly_resampled() {
+        let mut converter = ramp_converter(22050, 1, 44100);
+        let mut output = [f32::NAN; 5];
+        converter.mix(&mut output);
+        assert_eq!(output, [0.0, 0.5, 1.0, 1.5, 2.0]);
+
+        let mut converter = ramp_converter(44100, 1, 22050);
+        converter.mix(&mut output);
+        assert_eq!(output, [0.0, 2.0, 4.0, 6.0, 8.0]);
+    }
+
+    #[test]
+    fn resampling_continues_across_buffers() {
+        let mut converter = ramp_converter(22050, 1, 44100);
+        let mut output = [f32::NAN; 3];
+        converter.mix(&mut output);
+        assert_eq!(output, [0.0, 0.5, 1.0]);
+        converter.mix(&mut output);
+        assert_eq!(output, [1.5, 2.0, 2.5]);
+
+        // Far more frames than are mixed at a time.
+        let mut output = vec![f32::NAN; FormatConverter::<fn(&mut [f32])>::MIX_FRAMES * 3];
+        converter.mix(&mut output);
+        assert_eq!(output[0], 3.0);
+        assert_eq!(
+            output[output.len() - 1],
+            3.0 + (output.len() - 1) as f32 / 2.0
+        );
+    }
+
+    #[test]
+    fn device_names_prefer_exact_matches() {
+        let names = [
+            Some("Speakers (USB Headset)".to_string()),
+            None,
+            Some("USB Headset".to_string()),
+        ];
+        assert_eq!(match_device_name(&names, "USB Headset"), Some(2));
+        assert_eq!(match_device_name(&names, "usb"), Some(0));
+        assert_eq!(match_device_name(&names, "speakers"), Some(0));
+        assert_eq!(match_device_name(&names, "HDMI"), None);
+    }
+
+    #[test]
+    fn interruptions_are_reported_once() {
+        let interruptions = AudioInterruptions::default();
+        let player = interruptions.clone();
+        assert!(!player.take());
+
+        interruptions.raise();
+        interruptions.raise();
+        assert!(player.take());
+        assert!(!player.take());
     }
 }
@@ -0,0 +1,393 @@
+//! JSON-RPC automation interface for external test harnesses
+//!
+//! When enabled, the player listens on a local TCP socket for JSON-RPC 2.0
+//! requests, one per line. Each request is forwarded to the event loop so
+//! that it runs between frames, and its response is written back as a single
+//! line.
+//!
+//! Supported methods:
+//!
+//! * `getStage` - the movie size, viewport size, current frame, frame rate
+//!   and playing state.
+//! * `setPlaying` - `{ "playing": bool }`, pause or resume the movie.
+//! * `runFrame` - `{ "count": number }`, run one or more frames immediately.
+//! * `mouseMove`, `mouseDown`, `mouseUp` - `{ "x": number, "y": number }`, in
+//!   viewport pixels.
+//! * `keyDown`, `keyUp` - `{ "keyCode": number }`, a Flash key code.
+//! * `textInput` - `{ "text": string }`, type each character of a string.
+//! * `getTrace` - the lines traced by the movie since the last call.
+
+use crate::custom_event::RuffleEvent;
+use ruffle_core::backend::log::LogBackend;
+use ruffle_core::events::KeyCode;
+use ruffle_core::{Player, PlayerEvent};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use winit::event_loop::EventLoopProxy;
+
+/// JSON-RPC error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+type RpcResult = Result<Value, (i64, String)>;
+
+/// A request received by the automation server, waiting to be handled on
+/// the event loop.
+pub struct AutomationRequest {
+    /// The request, as it was received.
+    body: String,
+
+    /// Where to send the response.
+    reply: Sender<String>,
+}
+
+impl AutomationRequest {
+    /// Handle this request and send its response back to the client.
+    pub fn respond(self, player: &mut Player, traces: &TraceBuffer) {
+        let response = handle_request(player, traces, &self.body);
+        let _ = self.reply.send(response);
+    }
+}
+
+/// Trace output collected for `getTrace`.
+#[derive(Clone, Default)]
+pub struct TraceBuffer(Arc<Mutex<Vec<String>>>);
+
+impl TraceBuffer {
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+/// Log backend that records traces for automation clients, in addition to
+/// logging them.
+pub struct AutomationLogBackend {
+    traces: TraceBuffer,
+}
+
+impl AutomationLogBackend {
+    pub fn new(traces: TraceBuffer) -> Self {
+        Self { traces }
+    }
+}
+
+impl LogBackend for AutomationLogBackend {
+    fn avm_trace(&self, message: &str) {
+        log::info!(target: "avm_trace", "{}", message);
+        self.traces.0.lock().unwrap().push(message.to_string());
+    }
+}
+
+/// Start listening for automation clients on the given address.
+pub fn start_server(
+    address: SocketAddr,
+    event_loop: EventLoopProxy<RuffleEvent>,
+) -> std::io::Result<()> {
+    if !address.ip().is_loopback() {
+        log::warn!(
+            "Automation server is listening on {}, which is not a loopback address",
+            address
+        );
+    }
+
+    let listener = TcpListener::bind(address)?;
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let event_loop = event_loop.clone();
+                    thread::spawn(move || {
+                        if let Err(e) = serve_client(stream, event_loop) {
+                            log::warn!("Automation client disconnected: {}", e);
+                        }
+                    });
+                }
+                Err(e) => log::warn!("Unable to accept automation client: {}", e),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Forward each request from a client to the event loop, and write back the
+/// responses in order.
+fn serve_client(stream: TcpStream, event_loop: EventLoopProxy<RuffleEvent>) -> std::io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let body = line?;
+        if body.trim().is_empty() {
+            continue;
+        }
+
+        let (reply, response) = channel();
+        if event_loop
+            .send_event(RuffleEvent::Automation(AutomationRequest { body, reply }))
+            .is_err()
+        {
+            // The event loop has shut down.
+            break;
+        }
+
+        match response.recv() {
+            Ok(response) => writeln!(writer, "{}", response)?,
+            Err(_) => break,
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_request(player: &mut Player, traces: &TraceBuffer, body: &str) -> String {
+    let (id, result) = match serde_json::from_str::<Value>(body) {
+        Ok(request) => {
+            let id = request.get("id").cloned().unwrap_or(Value::Null);
+            let params = request.get("params").cloned().unwrap_or(Value::Null);
+            let result = match request.get("method").and_then(Value::as_str) {
+                Some(method) => call(player, traces, method, &params),
+                None => Err((INVALID_REQUEST, "Missing method".to_string())),
+            };
+            (id, result)
+        }
+        Err(e) => (Value::Null, Err((PARSE_ERROR, e.to_string()))),
+    };
+
+    let response = match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        }),
+    };
+    response.to_string()
+}
+
+fn call(player: &mut Player, traces: &TraceBuffer, method: &str, params: &Value) -> RpcResult {
+    match method {
+        "getStage" => {
+            let (viewport_width, viewport_height) = player.viewport_dimensions();
+            Ok(json!({
+                "width": player.movie_width(),
+                "height": player.movie_height(),
+                "viewportWidth": viewport_width,
+                "viewportHeight": viewport_height,
+                "frame": player.current_frame(),
+                "frameRate": player.frame_rate(),
+                "isPlaying": player.is_playing(),
+            }))
+        }
+        "setPlaying" => {
+            let playing = params
+                .get("playing")
+                .and_then(Value::as_bool)
+                .ok_or_else(|| invalid_params("Expected a boolean `playing`"))?;
+            player.set_is_playing(playing);
+            Ok(Value::Null)
+        }
+        "runFrame" => {
+            let count = match params.get("count") {
+                Some(count) => count
+                    .as_u64()
+                    .ok_or_else(|| invalid_params("Expected a non-negative `count`"))?,
+                None => 1,
+            };
+            for _ in 0..count {
+                player.run_frame();
+            }
+            Ok(json!({ "frame": player.current_frame() }))
+        }
+        "mouseMove" => {
+            let (x, y) = position(params)?;
+            player.handle_event(PlayerEvent::MouseMove { x, y });
+            Ok(Value::Null)
+        }
+        "mouseDown" => {
+            let (x, y) = position(params)?;
+            player.handle_event(PlayerEvent::MouseMove { x, y });
+            player.handle_event(PlayerEvent::MouseDown { x, y });
+            Ok(Value::Null)
+        }
+        "mouseUp" => {
+            let (x, y) = position(params)?;
+            player.handle_event(PlayerEvent::MouseMove { x, y });
+            player.handle_event(PlayerEvent::MouseUp { x, y });
+            Ok(Value::Null)
+        }
+        "keyDown" => {
+            let key_code = key_code(params)?;
+            player.handle_event(PlayerEvent::KeyDown { key_code });
+            Ok(Value::Null)
+        }
+        "keyUp" => {
+            let key_code = key_code(params)?;
+            player.handle_event(PlayerEvent::KeyUp { key_code });
+            Ok(Value::Null)
+        }
+        "textInput" => {
+            let text = params
+                .get("text")
+                .and_then(Value::as_str)
+                .ok_or_else(|| invalid_params("Expected a string `text`"))?;
+            for codepoint in text.chars() {
+                player.handle_event(PlayerEvent::TextInput { codepoint });
+            }
+            Ok(Value::Null)
+        }
+        "getTrace" => Ok(json!({ "lines": traces.take() })),
+        _ => Err((METHOD_NOT_FOUND, format!("Unknown method {}", method))),
+    }
+}
+
+fn invalid_params(message: &str) -> (i64, String) {
+    (INVALID_PARAMS, message.to_string())
+}
+
+fn position(params: &Value) -> Result<(f64, f64), (i64, String)> {
+    let x = params.get("x").and_then(Value::as_f64);
+    let y = params.get("y").and_then(Value::as_f64);
+    match (x, y) {
+        (Some(x), Some(y)) => Ok((x, y)),
+        _ => Err(invalid_params("Expected numbers `x` and `y`")),
+    }
+}
+
+fn key_code(params: &Value) -> Result<KeyCode, (i64, String)> {
+    params
+        .get("keyCode")
+        .and_then(Value::as_u64)
+        .filter(|&key_code| key_code <= u8::MAX.into())
+        .and_then(|key_code| KeyCode::from_u8(key_code as u8))
+        .ok_or_else(|| invalid_params("Expected a valid `keyCode`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ruffle_core::backend::audio::NullAudioBackend;
+    use ruffle_core::backend::locale::NullLocaleBackend;
+    use ruffle_core::backend::log::NullLogBackend;
+    use ruffle_core::backend::navigator::NullNavigatorBackend;
+    use ruffle_core::backend::render::NullRenderer;
+    use ruffle_core::backend::storage::MemoryStorageBackend;
+    use ruffle_core::backend::ui::NullUiBackend;
+    use ruffle_core::backend::video::NullVideoBackend;
+    use ruffle_core::tag_utils::SwfMovie;
+
+    fn player() -> Arc<Mutex<Player>> {
+        let player = Player::new(
+            Box::new(NullRenderer),
+            Box::new(NullAudioBackend::new()),
+            Box::new(NullNavigatorBackend::new()),
+            Box::new(MemoryStorageBackend::default()),
+            Box::new(NullLocaleBackend::new()),
+            Box::new(NullVideoBackend::new()),
+            Box::new(NullLogBackend::new()),
+            Box::new(NullUiBackend::new()),
+        )
+        .unwrap();
+        player
+            .lock()
+            .unwrap()
+            .set_root_movie(Arc::new(SwfMovie::empty(10)));
+        player
+    }
+
+    fn request(player: &Arc<Mutex<Player>>, traces: &TraceBuffer, body: &str) -> Value {
+        let response = handle_request(&mut player.lock().unwrap(), traces, body);
+        serde_json::from_str(&response).unwrap()
+    }
+
+    fn error_code(response: &Value) -> Option<i64> {
+        response["error"]["code"].as_i64()
+    }
+
+    #[test]
+    fn responses_echo_the_request_id() {
+        let player = player();
+        let traces = TraceBuffer::default();
+        let response = request(
+            &player,
+            &traces,
+            r#"{"jsonrpc":"2.0","id":"a","method":"getTrace"}"#,
+        );
+        assert_eq!(response["jsonrpc"], "2.0");
+        assert_eq!(response["id"], "a");
+        assert!(response.get("error").is_none());
+    }
+
+    #[test]
+    fn bad_requests_are_errors() {
+        let player = player();
+        let traces = TraceBuffer::default();
+
+        let response = request(&player, &traces, "{");
+        assert_eq!(error_code(&response), Some(PARSE_ERROR));
+        assert_eq!(response["id"], Value::Null);
+
+        let response = request(&player, &traces, r#"{"id":1}"#);
+        assert_eq!(error_code(&response), Some(INVALID_REQUEST));
+        assert_eq!(response["id"], 1);
+
+        let response = request(&player, &traces, r#"{"id":2,"method":"screenshot"}"#);
+        assert_eq!(error_code(&response), Some(METHOD_NOT_FOUND));
+
+        for params in &[
+            r#"{"method":"setPlaying","params":{"playing":"yes"}}"#,
+            r#"{"method":"runFrame","params":{"count":-1}}"#,
+            r#"{"method":"mouseDown","params":{"x":1}}"#,
+            r#"{"method":"keyDown","params":{"keyCode":256}}"#,
+            r#"{"method":"textInput","params":{}}"#,
+        ] {
+            let response = request(&player, &traces, params);
+            assert_eq!(error_code(&response), Some(INVALID_PARAMS), "{}", params);
+        }
+    }
+
+    #[test]
+    fn set_playing_is_reported_by_get_stage() {
+        let player = player();
+        let traces = TraceBuffer::default();
+
+        for playing in &[true, false] {
+            let body = json!({ "method": "setPlaying", "params": { "playing": playing } });
+            request(&player, &traces, &body.to_string());
+            let stage = request(&player, &traces, r#"{"method":"getStage"}"#);
+            assert_eq!(stage["result"]["isPlaying"], *playing);
+        }
+    }
+
+    #[test]
+    fn run_frame_reports_the_current_frame() {
+        let player = player();
+        let traces = TraceBuffer::default();
+        let response = request(
+            &player,
+            &traces,
+            r#"{"method":"runFrame","params":{"count":2}}"#,
+        );
+        let stage = request(&player, &traces, r#"{"method":"getStage"}"#);
+        assert_eq!(response["result"]["frame"], stage["result"]["frame"]);
+    }
+
+    #[test]
+    fn get_trace_returns_new_lines_once() {
+        let player = player();
+        let traces = TraceBuffer::default();
+        let log = AutomationLogBackend::new(traces.clone());
+        log.avm_trace("first");
+        log.avm_trace("second");
+
+        let response = request(&player, &traces, r#"{"method":"getTrace"}"#);
+        assert_eq!(response["result"]["lines"], json!(["first", "second"]));
+
+        let response = request(&player, &traces, r#"{"method":"getTrace"}"#);
+        assert_eq!(response["result"]["lines"], json!([]));
+    }
+}
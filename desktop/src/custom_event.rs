@@ -1,7 +1,13 @@
 //! Custom event type for desktop ruffle
 
+use crate::automation::AutomationRequest;
+
 /// User-defined events.
 pub enum RuffleEvent {
     /// Indicates that one or more tasks are ready to poll on our executor.
     TaskPoll,
+
+    /// A request from an automation client that should be handled between
+    /// frames.
+    Automation(AutomationRequest),
 }
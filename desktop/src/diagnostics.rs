@@ -0,0 +1,204 @@
+//! A report of what this system supports, for troubleshooting.
+
+use cpal::traits::{DeviceTrait, HostTrait};
+use ruffle_core::bug_report::{BugReport, RecentWarnings};
+use ruffle_core::tag_utils::SwfMovie;
+use ruffle_render_wgpu::target::TextureTarget;
+use ruffle_render_wgpu::{wgpu, WgpuRenderBackend};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use tinyfiledialogs::save_file_dialog_with_filter;
+
+/// Probe the graphics, audio and storage capabilities of this system and
+/// build a report of them.
+///
+/// Every probe is independent, so a failure is recorded in the report rather
+/// than stopping the others from running.
+pub fn report(backend: wgpu::BackendBit, power_preference: wgpu::PowerPreference) -> Value {
+    json!({
+        "ruffle": {
+            "version": env!("CARGO_PKG_VERSION"),
+            "os": std::env::consts::OS,
+            "arch": std::env::consts::ARCH,
+        },
+        "graphics": graphics(backend, power_preference),
+        "audio": audio(),
+        "storage": storage(),
+    })
+}
+
+/// Ask the user where to save a bug report, and save one there.
+///
+/// The report holds the diagnostics of this system, the URL of the movie
+/// that is playing and the warnings that were logged while it played.
+pub fn save_bug_report(
+    backend: wgpu::BackendBit,
+    power_preference: wgpu::PowerPreference,
+    movie: Option<&SwfMovie>,
+    warnings: &RecentWarnings,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let path = match save_file_dialog_with_filter(
+        "Save bug report",
+        "ruffle-bug-report.zip",
+        &["*.zip"],
+        "Zip archive",
+    ) {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+
+    let diagnostics = serde_json::to_string_pretty(&report(backend, power_preference))?;
+    let mut bug_report = BugReport::new(diagnostics);
+    if let Some(url) = movie.and_then(|movie| movie.url()) {
+        bug_report = bug_report.with_movie_url(url);
+    }
+    fs::write(path, bug_report.with_warnings(warnings).to_zip()?)?;
+
+    Ok(())
+}
+
+fn graphics(backend: wgpu::BackendBit, power_preference: wgpu::PowerPreference) -> Value {
+    let instance = wgpu::Instance::new(wgpu::BackendBit::all());
+    let adapters: Vec<Value> = instance
+        .enumerate_adapters(wgpu::BackendBit::all())
+        .map(|adapter| adapter_info(&adapter.get_info()))
+        .collect();
+
+    // Check that the adapter Ruffle would pick can create a device, which is
+    // where most driver problems show up.
+    let selected = match WgpuRenderBackend::<TextureTarget>::build_descriptors(
+        backend,
+        wgpu::Instance::new(backend),
+        None,
+        power_preference,
+        None,
+    ) {
+        Ok(descriptors) => json!({
+            "ok": true,
+            "adapter": adapter_info(&descriptors.info),
+        }),
+        Err(e) => json!({ "ok": false, "error": e.to_string() }),
+    };
+
+    json!({
+        "adapters": adapters,
+        "selected": selected,
+    })
+}
+
+fn adapter_info(info: &wgpu::AdapterInfo) -> Value {
+    json!({
+        "name": info.name,
+        "vendor": format!("{:#06x}", info.vendor),
+        "device": format!("{:#06x}", info.device),
+        "type": format!("{:?}", info.device_type),
+        "backend": format!("{:?}", info.backend),
+    })
+}
+
+fn audio() -> Value {
+    let host = cpal::default_host();
+    let default_device = host
+        .default_output_device()
+        .and_then(|device| device.name().ok());
+
+    let devices = match host.output_devices() {
+        Ok(devices) => devices
+            .map(|device| {
+                let name = device.name().unwrap_or_default();
+                match device.default_output_config() {
+                    Ok(config) => json!({
+                        "name": name,
+                        "channels": config.channels(),
+                        "sample_rate": config.sample_rate().0,
+                        "sample_format": format!("{:?}", config.sample_format()),
+                    }),
+                    Err(e) => json!({ "name": name, "error": e.to_string() }),
+                }
+            })
+            .collect(),
+        Err(e) => json!({ "error": e.to_string() }),
+    };
+
+    json!({
+        "host": host.id().name(),
+        "default_device": default_device,
+        "devices": devices,
+    })
+}
+
+fn storage() -> Value {
+    match dirs::data_local_dir() {
+        Some(dir) => probe_storage(&dir.join("ruffle")),
+        None => json!({ "ok": false, "error": "No local data directory" }),
+    }
+}
+
+/// Check that shared objects can be saved in the given directory.
+fn probe_storage(path: &Path) -> Value {
+    // Write and remove a file, as the directory existing doesn't mean that
+    // shared objects can be saved in it.
+    let probe = path.join(".diagnostics");
+    let result = fs::create_dir_all(path)
+        .and_then(|_| fs::write(&probe, b"ruffle"))
+        .and_then(|_| fs::remove_file(&probe));
+
+    match result {
+        Ok(()) => json!({ "ok": true, "path": path }),
+        Err(e) => json!({ "ok": false, "path": path, "error": e.to_string() }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adapter_ids_are_hex() {
+        let info = wgpu::AdapterInfo {
+            name: "Test GPU".to_string(),
+            vendor: 0x10de,
+            device: 0x1f,
+            device_type: wgpu::DeviceType::DiscreteGpu,
+            backend: wgpu::Backend::Vulkan,
+        };
+        assert_eq!(
+            adapter_info(&info),
+            json!({
+                "name": "Test GPU",
+                "vendor": "0x10de",
+                "device": "0x001f",
+                "type": "DiscreteGpu",
+                "backend": "Vulkan",
+            })
+        );
+    }
+
+    #[test]
+    fn storage_probe_cleans_up() {
+        let dir = std::env::temp_dir().join(format!("ruffle-diag-{}", std::process::id()));
+        let path = dir.join("nested");
+
+        let report = probe_storage(&path);
+        assert_eq!(report["ok"], true);
+        assert_eq!(report["path"], json!(path));
+        assert!(path.is_dir());
+        assert!(!path.join(".diagnostics").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn storage_probe_reports_errors() {
+        // A directory can't be created inside a regular file.
+        let file = std::env::temp_dir().join(format!("ruffle-diag-file-{}", std::process::id()));
+        fs::write(&file, b"").unwrap();
+
+        let report = probe_storage(&file.join("ruffle"));
+        assert_eq!(report["ok"], false);
+        assert!(report["error"].is_string());
+
+        fs::remove_file(file).unwrap();
+    }
+}
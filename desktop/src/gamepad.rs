@@ -0,0 +1,129 @@
+//! Game controller support, through gilrs.
+
+use gilrs::{Axis, Button, EventType, GamepadId, Gilrs};
+use ruffle_core::events::GamepadControl;
+use ruffle_core::PlayerEvent;
+
+/// The game controllers connected to the computer.
+pub struct Gamepads {
+    /// The gilrs context, if game controllers are supported on this platform.
+    gilrs: Option<Gilrs>,
+
+    /// Whether the controllers that were connected before startup have been
+    /// reported yet.
+    reported_initial: bool,
+}
+
+impl Gamepads {
+    pub fn new() -> Self {
+        let gilrs = match Gilrs::new() {
+            Ok(gilrs) => Some(gilrs),
+            Err(e) => {
+                log::warn!("Couldn't start game controller support: {}", e);
+                None
+            }
+        };
+
+        Self {
+            gilrs,
+            reported_initial: false,
+        }
+    }
+
+    /// Collect what has happened to the controllers since this was last
+    /// called, as player events.
+    pub fn poll(&mut self) -> Vec<PlayerEvent> {
+        let gilrs = match &mut self.gilrs {
+            Some(gilrs) => gilrs,
+            None => return Vec::new(),
+        };
+
+        let mut events = Vec::new();
+        if !self.reported_initial {
+            self.reported_initial = true;
+            for (id, gamepad) in gilrs.gamepads() {
+                events.push(PlayerEvent::GamepadConnected {
+                    id: player_id(id),
+                    name: gamepad.name().to_string(),
+                });
+            }
+        }
+
+        while let Some(gilrs::Event { id, event, .. }) = gilrs.next_event() {
+            match event {
+                EventType::Connected => events.push(PlayerEvent::GamepadConnected {
+                    id: player_id(id),
+                    name: gilrs.gamepad(id).name().to_string(),
+                }),
+                EventType::Disconnected => {
+                    events.push(PlayerEvent::GamepadDisconnected { id: player_id(id) })
+                }
+                EventType::ButtonChanged(button, value, _) => {
+                    if let Some(control) = button_control(button) {
+                        events.push(PlayerEvent::GamepadInput {
+                            id: player_id(id),
+                            control,
+                            value: value.into(),
+                        });
+                    }
+                }
+                EventType::AxisChanged(axis, value, _) => {
+                    if let Some((control, sign)) = axis_control(axis) {
+                        events.push(PlayerEvent::GamepadInput {
+                            id: player_id(id),
+                            control,
+                            value: f64::from(value) * sign,
+                        });
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        events
+    }
+}
+
+fn player_id(id: GamepadId) -> u32 {
+    usize::from(id) as u32
+}
+
+/// Find where a button is in the standard gamepad layout.
+fn button_control(button: Button) -> Option<GamepadControl> {
+    let index = match button {
+        Button::South => 0,
+        Button::East => 1,
+        Button::West => 2,
+        Button::North => 3,
+        Button::LeftTrigger => 4,
+        Button::RightTrigger => 5,
+        Button::LeftTrigger2 => 6,
+        Button::RightTrigger2 => 7,
+        Button::Select => 8,
+        Button::Start => 9,
+        Button::LeftThumb => 10,
+        Button::RightThumb => 11,
+        Button::DPadUp => 12,
+        Button::DPadDown => 13,
+        Button::DPadLeft => 14,
+        Button::DPadRight => 15,
+        Button::Mode => 16,
+        _ => return None,
+    };
+
+    Some(GamepadControl::Button(index))
+}
+
+/// Find where an axis is in the standard gamepad layout, and the sign that
+/// its values need.
+///
+/// gilrs points the Y axes up, while the standard layout points them down.
+fn axis_control(axis: Axis) -> Option<(GamepadControl, f64)> {
+    match axis {
+        Axis::LeftStickX => Some((GamepadControl::Axis(0), 1.0)),
+        Axis::LeftStickY => Some((GamepadControl::Axis(1), -1.0)),
+        Axis::RightStickX => Some((GamepadControl::Axis(2), 1.0)),
+        Axis::RightStickY => Some((GamepadControl::Axis(3), -1.0)),
+        _ => None,
+    }
+}
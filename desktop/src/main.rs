@@ -6,40 +6,49 @@
 #![windows_subsystem = "windows"]
 
 mod audio;
+mod automation;
 mod custom_event;
+mod diagnostics;
 mod executor;
+mod gamepad;
 mod locale;
 mod navigator;
 mod storage;
 mod task;
 mod ui;
 
+use crate::automation::{AutomationLogBackend, TraceBuffer};
 use crate::custom_event::RuffleEvent;
 use crate::executor::GlutinAsyncExecutor;
+use crate::gamepad::Gamepads;
 use clap::Clap;
 use isahc::{config::RedirectPolicy, prelude::*, HttpClient};
 use ruffle_core::{
     backend::{
         audio::{AudioBackend, NullAudioBackend},
-        log as log_backend,
+        log::{self as log_backend, LogBackend},
         navigator::NullNavigatorBackend,
         storage::MemoryStorageBackend,
         ui::NullUiBackend,
         video,
     },
-    config::Letterbox,
+    bug_report::{RecentWarnings, WarningLogger},
+    config::{FontScript, Letterbox, NotdefStrategy, SpoofedCapabilities},
     Player,
 };
 use ruffle_render_wgpu::WgpuRenderBackend;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
-use tinyfiledialogs::open_file_dialog;
+use tinyfiledialogs::{input_box, open_file_dialog};
 use url::Url;
 
+use ruffle_core::bundle::Bundle;
+use ruffle_core::events::{KeyCode, KeyRemap};
 use ruffle_core::tag_utils::SwfMovie;
 use ruffle_render_wgpu::clap::{GraphicsBackend, PowerPreference};
 use std::io::Read;
+use std::net::SocketAddr;
 use std::rc::Rc;
 use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize, Size};
 use winit::event::{
@@ -55,7 +64,8 @@ use winit::window::{Fullscreen, Icon, Window, WindowBuilder};
     version = include_str!(concat!(env!("OUT_DIR"), "/version-info.txt")),
 )]
 struct Opt {
-    /// Path to a Flash movie (SWF) to play.
+    /// Path to a Flash movie (SWF) to play, or a .zip bundle containing a
+    /// movie and the files it loads.
     #[clap(name = "FILE", parse(from_os_str))]
     input_path: Option<PathBuf>,
 
@@ -100,6 +110,93 @@ struct Opt {
 
     #[clap(long, takes_value = false)]
     dont_warn_on_unsupported_content: bool,
+
+    /// A font to use for device text in a given script (cjk, cyrillic or arabic)
+    /// when the default device font lacks glyphs for it, as a TrueType or OpenType font file.
+    /// This can be repeated multiple times, for example --fallback-font cjk=NotoSansCJK.otf.
+    #[clap(long, number_of_values = 1, multiple_occurrences = true)]
+    fallback_font: Vec<String>,
+
+    /// Render characters that no device font has a glyph for as boxes.
+    #[clap(long, takes_value = false)]
+    show_missing_glyphs: bool,
+
+    /// Run movies in the security sandbox that a browser would put them in.
+    /// By default, movies are trusted like in the standalone Flash Player, and may load data from anywhere.
+    #[clap(long, takes_value = false)]
+    sandboxed: bool,
+
+    /// Pause movies while the window is in the background, and resume them when it comes back.
+    /// Movies that listen for deactivate events are left to pause themselves.
+    #[clap(long, takes_value = false)]
+    pause_in_background: bool,
+
+    /// Pause movies when audio is interrupted, such as by unplugging headphones.
+    /// They stay paused until the play/pause media key is pressed.
+    #[clap(long, takes_value = false)]
+    pause_on_audio_interruption: bool,
+
+    /// Make pressing one key look like pressing another to the movie, for games hardcoded to an awkward layout.
+    /// Keys are letters, digits, names such as left or space, or key codes.
+    /// This can be repeated multiple times, for example --remap-key w=up --remap-key up=w.
+    #[clap(
+        long,
+        number_of_values = 1,
+        multiple_occurrences = true,
+        value_name = "FROM=TO"
+    )]
+    remap_key: Vec<String>,
+
+    /// Report this Flash Player version to movies, for example --spoof-version "WIN 11,2,202,235".
+    /// Some movies refuse to run unless they find a particular version or operating system.
+    #[clap(long, value_name = "VERSION")]
+    spoof_version: Option<String>,
+
+    /// Report this operating system to movies, for example --spoof-os "Windows 7".
+    #[clap(long, value_name = "OS")]
+    spoof_os: Option<String>,
+
+    /// Report this player type to movies: StandAlone, External, PlugIn or ActiveX.
+    #[clap(long, value_name = "TYPE")]
+    spoof_player_type: Option<String>,
+
+    /// Report this language to movies, for example --spoof-language ja.
+    #[clap(long, value_name = "CODE")]
+    spoof_language: Option<String>,
+
+    /// Tell movies that this is a debugger version of Flash Player.
+    #[clap(long, takes_value = false)]
+    spoof_debugger: bool,
+
+    /// Listen for JSON-RPC automation requests on a local address, for example 127.0.0.1:9000.
+    /// This allows external tools to inspect the stage, inject input and read trace output.
+    #[clap(long)]
+    automation: Option<SocketAddr>,
+
+    /// Copy shared objects (.sol files) from a directory into Ruffle's storage, then exit.
+    /// The directory should be laid out like Flash Player's #SharedObjects/<id> directory.
+    #[clap(long, parse(from_os_str), value_name = "DIR")]
+    import_shared_objects: Option<PathBuf>,
+
+    /// Copy Ruffle's shared objects (.sol files) into a directory, then exit.
+    /// The directory is laid out like Flash Player's #SharedObjects/<id> directory.
+    #[clap(long, parse(from_os_str), value_name = "DIR")]
+    export_shared_objects: Option<PathBuf>,
+
+    /// Name of the audio output device to use, instead of the system default.
+    /// A case-insensitive part of the name is enough, for example --audio-device headset.
+    /// If the device is unplugged, audio moves to the default device until it returns.
+    #[clap(long, value_name = "NAME")]
+    audio_device: Option<String>,
+
+    /// List the available audio output devices, then exit.
+    #[clap(long, takes_value = false)]
+    list_audio_devices: bool,
+
+    /// Print a report of the graphics, audio and storage capabilities of this system, then exit.
+    /// The graphics report uses the --graphics and --power options to pick an adapter.
+    #[clap(long, takes_value = false)]
+    diag: bool,
 }
 
 #[cfg(feature = "render_trace")]
@@ -117,11 +214,36 @@ fn trace_path(_opt: &Opt) -> Option<&Path> {
     None
 }
 
+/// Register a device font fallback given as `<script>=<path>`.
+fn register_fallback_font(
+    player: &mut Player,
+    fallback_font: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (script, path) = fallback_font
+        .split_once('=')
+        .ok_or("Expected <script>=<path>")?;
+    let script = script
+        .parse::<FontScript>()
+        .map_err(|_| format!("Unknown script {}", script))?;
+    let data = std::fs::read(path)?;
+    player.register_device_font_fallback(script, &data)
+}
+
+/// Add a key remapping given as `<from>=<to>`.
+fn add_key_remap(key_remap: &mut KeyRemap, remap: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let (from, to) = remap.split_once('=').ok_or("Expected <from>=<to>")?;
+    let from = KeyCode::from_name(from).ok_or_else(|| format!("Unknown key {}", from))?;
+    let to = KeyCode::from_name(to).ok_or_else(|| format!("Unknown key {}", to))?;
+    key_remap.insert(from, to);
+    Ok(())
+}
+
 // TODO: Return just `SwfMovie` by making it hold `Url`?
+/// Load a movie, along with the bundle it is in if the path is a bundle.
 fn load_movie_from_path(
     path: &Path,
     opt: &Opt,
-) -> Result<(SwfMovie, Url), Box<dyn std::error::Error>> {
+) -> Result<(SwfMovie, Url, Option<Bundle>), Box<dyn std::error::Error>> {
     let movie_url = if path.exists() {
         let absolute_path = path.canonicalize().unwrap_or_else(|_| path.to_owned());
         Url::from_file_path(absolute_path)
@@ -131,8 +253,8 @@ fn load_movie_from_path(
             .map_err(|_| "Input path is not a file and could not be parsed as a URL.")?
     };
 
-    let mut movie = if movie_url.scheme() == "file" {
-        SwfMovie::from_path(movie_url.to_file_path().unwrap(), None)?
+    let data = if movie_url.scheme() == "file" {
+        std::fs::read(movie_url.to_file_path().unwrap())?
     } else {
         let proxy = opt.proxy.as_ref().and_then(|url| url.as_str().parse().ok());
         let builder = HttpClient::builder()
@@ -142,8 +264,15 @@ fn load_movie_from_path(
         let response = client.get(movie_url.to_string())?;
         let mut buffer: Vec<u8> = Vec::new();
         response.into_body().read_to_end(&mut buffer)?;
+        buffer
+    };
 
-        SwfMovie::from_data(&buffer, Some(movie_url.to_string()), None)?
+    let (mut movie, movie_url, bundle) = if Bundle::is_bundle(&data) {
+        let bundle = Bundle::from_zip(&data, movie_url.as_str())?;
+        (bundle.movie()?, bundle.movie_url(), Some(bundle))
+    } else {
+        let movie = SwfMovie::from_data(&data, Some(movie_url.to_string()), None)?;
+        (movie, movie_url, None)
     };
 
     let parameters = opt.parameters.iter().map(|parameter| {
@@ -156,11 +285,17 @@ fn load_movie_from_path(
     });
     movie.append_parameters(parameters);
 
-    Ok((movie, movie_url))
+    Ok((movie, movie_url, bundle))
 }
 
-fn load_from_file_dialog(opt: &Opt) -> Result<Option<(SwfMovie, Url)>, Box<dyn std::error::Error>> {
-    let result = open_file_dialog("Load a Flash File", "", Some((&["*.swf"], ".swf")));
+fn load_from_file_dialog(
+    opt: &Opt,
+) -> Result<Option<(SwfMovie, Url, Option<Bundle>)>, Box<dyn std::error::Error>> {
+    let result = open_file_dialog(
+        "Load a Flash File",
+        "",
+        Some((&["*.swf", "*.spl", "*.zip"], "Flash Files")),
+    );
 
     let selected: PathBuf = match result {
         Some(file_path) => file_path.into(),
@@ -175,19 +310,21 @@ fn load_from_file_dialog(opt: &Opt) -> Result<Option<(SwfMovie, Url)>, Box<dyn s
 }
 
 struct App {
-    #[allow(dead_code)]
     opt: Opt,
     window: Rc<Window>,
     event_loop: EventLoop<RuffleEvent>,
     executor: Arc<Mutex<GlutinAsyncExecutor>>,
     player: Arc<Mutex<Player>>,
     movie: Option<Arc<SwfMovie>>,
+    traces: TraceBuffer,
+    warnings: Arc<RecentWarnings>,
+    audio_interruptions: Option<audio::AudioInterruptions>,
 }
 
 impl App {
     const DEFAULT_WINDOW_SIZE: LogicalSize<f64> = LogicalSize::new(1280.0, 720.0);
 
-    fn new(opt: Opt) -> Result<Self, Box<dyn std::error::Error>> {
+    fn new(opt: Opt, warnings: Arc<RecentWarnings>) -> Result<Self, Box<dyn std::error::Error>> {
         let movie = if let Some(path) = opt.input_path.to_owned() {
             Some(load_movie_from_path(&path, &opt)?)
         } else {
@@ -205,7 +342,7 @@ impl App {
 
         let event_loop: EventLoop<RuffleEvent> = EventLoop::with_user_event();
 
-        let (title, movie_size) = if let Some((movie, movie_url)) = &movie {
+        let (title, movie_size) = if let Some((movie, movie_url, _)) = &movie {
             let filename = movie_url
                 .path_segments()
                 .and_then(|segments| segments.last())
@@ -249,20 +386,27 @@ impl App {
         let viewport_scale_factor = window.scale_factor();
 
         let window = Rc::new(window);
-        let renderer = Box::new(WgpuRenderBackend::for_window(
+        let mut renderer = Box::new(WgpuRenderBackend::for_window(
             window.as_ref(),
             (viewport_size.width, viewport_size.height),
             opt.graphics.into(),
             opt.power.into(),
             trace_path(&opt),
         )?);
-        let audio: Box<dyn AudioBackend> = match audio::CpalAudioBackend::new() {
-            Ok(audio) => Box::new(audio),
-            Err(e) => {
-                log::error!("Unable to create audio device: {}", e);
-                Box::new(NullAudioBackend::new())
-            }
-        };
+        if let Err(e) = renderer.warm_up() {
+            log::warn!("Couldn't build pipelines ahead of time: {}", e);
+        }
+        let (audio, audio_interruptions): (Box<dyn AudioBackend>, _) =
+            match audio::CpalAudioBackend::new(opt.audio_device.as_deref()) {
+                Ok(audio) => {
+                    let interruptions = audio.interruptions();
+                    (Box::new(audio), Some(interruptions))
+                }
+                Err(e) => {
+                    log::error!("Unable to create audio device: {}", e);
+                    (Box::new(NullAudioBackend::new()), None)
+                }
+            };
         let (executor, channel) = GlutinAsyncExecutor::new(event_loop.create_proxy());
         let navigator = Box::new(navigator::ExternalNavigatorBackend::new(
             movie.as_ref().unwrap().1.clone(), // TODO: Get rid of this parameter.
@@ -274,15 +418,56 @@ impl App {
         let storage = Box::new(storage::DiskStorageBackend::new());
         let locale = Box::new(locale::DesktopLocaleBackend::new());
         let video = Box::new(video::SoftwareVideoBackend::new());
-        let log = Box::new(log_backend::NullLogBackend::new());
-        let ui = Box::new(ui::DesktopUiBackend::new(window.clone()));
+        let traces = TraceBuffer::default();
+        let log: Box<dyn LogBackend> = match opt.automation {
+            Some(address) => {
+                automation::start_server(address, event_loop.create_proxy())?;
+                Box::new(AutomationLogBackend::new(traces.clone()))
+            }
+            None => Box::new(log_backend::NullLogBackend::new()),
+        };
+        let mut ui = Box::new(ui::DesktopUiBackend::new(window.clone()));
+        let mut key_remap = KeyRemap::new();
+        for remap in &opt.remap_key {
+            if let Err(e) = add_key_remap(&mut key_remap, remap) {
+                log::error!("Unable to remap key {}: {}", remap, e);
+            }
+        }
+        ui.set_key_remap(key_remap);
         let player = Player::new(renderer, audio, navigator, storage, locale, video, log, ui)?;
 
-        let movie = movie.map(|(movie, _)| Arc::new(movie));
+        let (movie, bundle) = match movie {
+            Some((movie, _, bundle)) => (Some(Arc::new(movie)), bundle),
+            None => (None, None),
+        };
 
         {
             let mut player_lock = player.lock().unwrap();
             player_lock.set_warn_on_unsupported_content(!opt.dont_warn_on_unsupported_content);
+            player_lock.set_trusted(!opt.sandboxed);
+            for fallback_font in &opt.fallback_font {
+                if let Err(e) = register_fallback_font(&mut player_lock, fallback_font) {
+                    log::error!("Unable to load fallback font {}: {}", fallback_font, e);
+                }
+            }
+            if opt.show_missing_glyphs {
+                player_lock.set_notdef_strategy(NotdefStrategy::Box);
+            }
+            if let Some(monitor) = window.current_monitor() {
+                let size = monitor.size();
+                player_lock.set_screen_resolution(size.width, size.height);
+            }
+            player_lock.set_spoofed_capabilities(SpoofedCapabilities {
+                version: opt.spoof_version.clone(),
+                os: opt.spoof_os.clone(),
+                player_type: opt.spoof_player_type.clone(),
+                language: opt.spoof_language.clone(),
+                is_debugger: opt.spoof_debugger.then(|| true),
+                ..Default::default()
+            });
+            if let Some(bundle) = bundle {
+                player_lock.mount_bundle(bundle);
+            }
             if let Some(movie) = &movie {
                 player_lock.set_root_movie(movie.to_owned());
                 player_lock.set_is_playing(true); // Desktop player will auto-play.
@@ -302,6 +487,9 @@ impl App {
             executor,
             player,
             movie,
+            traces,
+            warnings,
+            audio_interruptions,
         })
     }
 
@@ -311,12 +499,25 @@ impl App {
         let player = self.player;
         let executor = self.executor;
         let movie = self.movie;
+        let traces = self.traces;
+        let warnings = self.warnings;
+        let pause_in_background = self.opt.pause_in_background;
+        let audio_interruptions = if self.opt.pause_on_audio_interruption {
+            self.audio_interruptions
+        } else {
+            None
+        };
+        let graphics = self.opt.graphics;
+        let power = self.opt.power;
 
         let mut mouse_pos = PhysicalPosition::new(0.0, 0.0);
         let mut time = Instant::now();
         let mut next_frame_time = Instant::now();
         let mut minimized = false;
         let mut fullscreen_down = false;
+        let mut paused_in_background = false;
+        let mut find_query: Option<String> = None;
+        let mut gamepads = Gamepads::new();
         loop {
             // Poll UI events
             self.event_loop
@@ -389,6 +590,25 @@ impl App {
                     match event {
                         // Core loop
                         winit::event::Event::MainEventsCleared => {
+                            if let Some(interruptions) = &audio_interruptions {
+                                if interruptions.take() {
+                                    let mut player_lock = player.lock().unwrap();
+                                    if player_lock.is_playing() {
+                                        log::info!("Audio was interrupted, pausing");
+                                        player_lock.set_is_playing(false);
+                                        paused_in_background = false;
+                                    }
+                                }
+                            }
+
+                            let gamepad_events = gamepads.poll();
+                            if !gamepad_events.is_empty() {
+                                let mut player_lock = player.lock().unwrap();
+                                for event in gamepad_events {
+                                    player_lock.handle_event(event);
+                                }
+                            }
+
                             let new_time = Instant::now();
                             let dt = new_time.duration_since(time).as_micros();
                             if dt > 0 {
@@ -461,6 +681,26 @@ impl App {
                                     window.request_redraw();
                                 }
                             }
+                            WindowEvent::Touch(touch) => {
+                                use ruffle_core::events::TouchPhase;
+                                let mut player_lock = player.lock().unwrap();
+                                let phase = match touch.phase {
+                                    winit::event::TouchPhase::Started => TouchPhase::Began,
+                                    winit::event::TouchPhase::Moved => TouchPhase::Moved,
+                                    winit::event::TouchPhase::Ended => TouchPhase::Ended,
+                                    winit::event::TouchPhase::Cancelled => TouchPhase::Cancelled,
+                                };
+                                let event = ruffle_core::PlayerEvent::Touch {
+                                    id: touch.id as i32,
+                                    phase,
+                                    x: touch.location.x,
+                                    y: touch.location.y,
+                                };
+                                player_lock.handle_event(event);
+                                if player_lock.needs_render() {
+                                    window.request_redraw();
+                                }
+                            }
                             WindowEvent::MouseWheel { delta, .. } => {
                                 use ruffle_core::events::MouseWheelDelta;
                                 let mut player_lock = player.lock().unwrap();
@@ -485,6 +725,96 @@ impl App {
                                     window.request_redraw();
                                 }
                             }
+                            WindowEvent::Focused(focused) => {
+                                let mut player_lock = player.lock().unwrap();
+                                player_lock.set_focused(focused);
+                                if !focused
+                                    && pause_in_background
+                                    && player_lock.is_playing()
+                                    && !player_lock.handles_deactivate()
+                                {
+                                    player_lock.set_is_playing(false);
+                                    paused_in_background = true;
+                                } else if focused && paused_in_background {
+                                    player_lock.set_is_playing(true);
+                                    paused_in_background = false;
+                                }
+                                if player_lock.needs_render() {
+                                    window.request_redraw();
+                                }
+                            }
+                            WindowEvent::KeyboardInput {
+                                input:
+                                    KeyboardInput {
+                                        state: ElementState::Pressed,
+                                        virtual_keycode:
+                                            Some(
+                                                key @ (VirtualKeyCode::PlayPause
+                                                | VirtualKeyCode::MediaStop),
+                                            ),
+                                        ..
+                                    },
+                                ..
+                            } => {
+                                // Media keys control the player, rather than
+                                // being passed on to the movie.
+                                let mut player_lock = player.lock().unwrap();
+                                let playing =
+                                    key == VirtualKeyCode::PlayPause && !player_lock.is_playing();
+                                player_lock.set_is_playing(playing);
+                                paused_in_background = false;
+                            }
+                            WindowEvent::KeyboardInput {
+                                input:
+                                    KeyboardInput {
+                                        state: ElementState::Pressed,
+                                        virtual_keycode: Some(VirtualKeyCode::B),
+                                        modifiers,
+                                        ..
+                                    },
+                                ..
+                            } if modifiers.ctrl() && modifiers.shift() => {
+                                // Report a bug.
+                                if let Err(e) = diagnostics::save_bug_report(
+                                    graphics.into(),
+                                    power.into(),
+                                    movie.as_deref(),
+                                    &warnings,
+                                ) {
+                                    log::error!("Couldn't save bug report: {}", e);
+                                }
+                            }
+                            WindowEvent::KeyboardInput {
+                                input:
+                                    KeyboardInput {
+                                        state: ElementState::Pressed,
+                                        virtual_keycode:
+                                            Some(key @ (VirtualKeyCode::F | VirtualKeyCode::G)),
+                                        modifiers,
+                                        ..
+                                    },
+                                ..
+                            } if modifiers.ctrl() && modifiers.shift() => {
+                                // Find text in the movie. Ctrl+Shift+G repeats
+                                // the last search without asking again.
+                                if key == VirtualKeyCode::F || find_query.is_none() {
+                                    find_query = input_box(
+                                        "Find",
+                                        "Find text in the movie:",
+                                        find_query.as_deref().unwrap_or_default(),
+                                    )
+                                    .filter(|query| !query.is_empty());
+                                }
+                                if let Some(query) = &find_query {
+                                    let mut player_lock = player.lock().unwrap();
+                                    if !player_lock.find_text(query) {
+                                        log::info!("No matches for {:?}", query);
+                                    }
+                                    if player_lock.needs_render() {
+                                        window.request_redraw();
+                                    }
+                                }
+                            }
                             WindowEvent::KeyboardInput { .. }
                             | WindowEvent::ReceivedCharacter(_) => {
                                 let mut player_lock = player.lock().unwrap();
@@ -506,6 +836,13 @@ impl App {
                             .lock()
                             .expect("active executor reference")
                             .poll_all(),
+                        winit::event::Event::UserEvent(RuffleEvent::Automation(request)) => {
+                            let mut player_lock = player.lock().unwrap();
+                            request.respond(&mut player_lock, &traces);
+                            if player_lock.needs_render() {
+                                window.request_redraw();
+                            }
+                        }
                         _ => (),
                     }
 
@@ -523,7 +860,7 @@ fn run_timedemo(opt: Opt) -> Result<(), Box<dyn std::error::Error>> {
         .input_path
         .as_ref()
         .ok_or("Input file necessary for timedemo")?;
-    let (movie, _) = load_movie_from_path(path, &opt)?;
+    let (movie, _, bundle) = load_movie_from_path(path, &opt)?;
     let movie_frames = Some(movie.num_frames());
 
     let viewport_width = 1920;
@@ -546,6 +883,9 @@ fn run_timedemo(opt: Opt) -> Result<(), Box<dyn std::error::Error>> {
     let player = Player::new(renderer, audio, navigator, storage, locale, video, log, ui)?;
 
     let mut player_lock = player.lock().unwrap();
+    if let Some(bundle) = bundle {
+        player_lock.mount_bundle(bundle);
+    }
     player_lock.set_root_movie(Arc::new(movie));
     player_lock.set_is_playing(true);
     player_lock.set_viewport_dimensions(viewport_width, viewport_height, viewport_scale_factor);
@@ -568,7 +908,46 @@ fn run_timedemo(opt: Opt) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn init() {
+fn run_shared_objects_transfer(opt: Opt) -> Result<(), Box<dyn std::error::Error>> {
+    let storage = storage::DiskStorageBackend::new();
+
+    if let Some(from) = &opt.import_shared_objects {
+        let count = storage.import_shared_objects(from)?;
+        println!(
+            "Imported {} shared objects from {}",
+            count,
+            from.to_string_lossy()
+        );
+    }
+
+    if let Some(to) = &opt.export_shared_objects {
+        let count = storage.export_shared_objects(to)?;
+        println!(
+            "Exported {} shared objects to {}",
+            count,
+            to.to_string_lossy()
+        );
+    }
+
+    Ok(())
+}
+
+fn list_audio_devices() -> Result<(), Box<dyn std::error::Error>> {
+    for name in audio::output_device_names()? {
+        println!("{}", name);
+    }
+
+    Ok(())
+}
+
+fn print_diagnostics(opt: &Opt) -> Result<(), Box<dyn std::error::Error>> {
+    let report = diagnostics::report(opt.graphics.into(), opt.power.into());
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    Ok(())
+}
+
+fn init() -> Arc<RecentWarnings> {
     // When linked with the windows subsystem windows won't automatically attach
     // to the console of the parent process, so we do it explicitly. This fails
     // silently if the parent has no console.
@@ -578,7 +957,14 @@ fn init() {
         AttachConsole(ATTACH_PARENT_PROCESS);
     }
 
-    env_logger::init();
+    // Warnings are kept for bug reports, even when they aren't printed.
+    let warnings = Arc::new(RecentWarnings::new());
+    let logger = env_logger::Logger::from_default_env();
+    let max_level = logger.filter().max(log::LevelFilter::Warn);
+    if log::set_boxed_logger(Box::new(WarningLogger::new(logger, warnings.clone()))).is_ok() {
+        log::set_max_level(max_level);
+    }
+    warnings
 }
 
 fn shutdown(result: &Result<(), Box<dyn std::error::Error>>) {
@@ -594,12 +980,18 @@ fn shutdown(result: &Result<(), Box<dyn std::error::Error>>) {
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    init();
+    let warnings = init();
     let opt = Opt::parse();
-    let result = if opt.timedemo {
+    let result = if opt.diag {
+        print_diagnostics(&opt)
+    } else if opt.list_audio_devices {
+        list_audio_devices()
+    } else if opt.timedemo {
         run_timedemo(opt)
+    } else if opt.import_shared_objects.is_some() || opt.export_shared_objects.is_some() {
+        run_shared_objects_transfer(opt)
     } else {
-        App::new(opt).map(|app| app.run())
+        App::new(opt, warnings).map(|app| app.run())
     };
     shutdown(&result);
     result
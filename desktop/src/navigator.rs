@@ -3,14 +3,18 @@
 use crate::custom_event::RuffleEvent;
 use isahc::{config::RedirectPolicy, prelude::*, AsyncReadResponseExt, HttpClient, Request};
 use ruffle_core::backend::navigator::{
-    NavigationMethod, NavigatorBackend, OwnedFuture, RequestOptions,
+    NavigationMethod, NavigatorBackend, OwnedFuture, RequestOptions, SocketEvent, SocketHandle,
 };
 use ruffle_core::indexmap::IndexMap;
 use ruffle_core::loader::Error;
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fs;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
 use std::rc::Rc;
-use std::sync::mpsc::Sender;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
 use std::time::{Duration, Instant};
 use url::Url;
 use winit::event_loop::EventLoopProxy;
@@ -34,6 +38,18 @@ pub struct ExternalNavigatorBackend {
     client: Option<Rc<HttpClient>>,
 
     upgrade_to_https: bool,
+
+    /// Sockets opened by the movie, with the channel that sends them data.
+    sockets: HashMap<SocketHandle, Sender<Vec<u8>>>,
+
+    /// The handle of the next socket to be opened.
+    next_socket_handle: SocketHandle,
+
+    /// Sink for socket threads to report what happens to their sockets.
+    socket_event_sender: Sender<(SocketHandle, SocketEvent)>,
+
+    /// Events reported by socket threads, waiting to be polled.
+    socket_event_receiver: Receiver<(SocketHandle, SocketEvent)>,
 }
 
 impl ExternalNavigatorBackend {
@@ -52,6 +68,7 @@ impl ExternalNavigatorBackend {
             .redirect_policy(RedirectPolicy::Follow);
 
         let client = builder.build().ok().map(Rc::new);
+        let (socket_event_sender, socket_event_receiver) = std::sync::mpsc::channel();
 
         Self {
             channel,
@@ -60,6 +77,10 @@ impl ExternalNavigatorBackend {
             movie_url,
             start_time: Instant::now(),
             upgrade_to_https,
+            sockets: HashMap::new(),
+            next_socket_handle: 0,
+            socket_event_sender,
+            socket_event_receiver,
         }
     }
 }
@@ -75,7 +96,9 @@ impl NavigatorBackend for ExternalNavigatorBackend {
 
         //NOTE: Flash desktop players / projectors ignore the window parameter,
         //      unless it's a `_layer`, and we shouldn't handle that anyway.
-        let mut parsed_url = match Url::parse(&url) {
+
+        // Relative URLs are resolved against the movie, as they are for fetches.
+        let mut parsed_url = match self.movie_url.join(&url) {
             Ok(parsed_url) => parsed_url,
             Err(e) => {
                 log::error!(
@@ -88,7 +111,13 @@ impl NavigatorBackend for ExternalNavigatorBackend {
         };
 
         let modified_url = match vars_method {
-            Some((_, query_pairs)) => {
+            Some((method, query_pairs)) => {
+                if let NavigationMethod::Post = method {
+                    // The system browser can only be asked to open a URL, so
+                    // form data is always sent in the query string.
+                    log::warn!("Sending POST variables as a GET request to {}", url);
+                }
+
                 {
                     //lifetime limiter because we don't have NLL yet
                     let mut modifier = parsed_url.query_pairs_mut();
@@ -133,12 +162,19 @@ impl NavigatorBackend for ExternalNavigatorBackend {
             _ => Box::pin(async move {
                 let client = client.ok_or(Error::NetworkUnavailable)?;
 
-                let request = match options.method() {
+                let mut request = match options.method() {
                     NavigationMethod::Get => Request::get(processed_url.to_string()),
                     NavigationMethod::Post => Request::post(processed_url.to_string()),
                 };
 
-                let (body_data, _) = options.body().clone().unwrap_or_default();
+                let (body_data, mime) = options.body().clone().unwrap_or_default();
+                if !mime.is_empty() {
+                    request = request.header("Content-Type", mime);
+                }
+                for (name, value) in options.headers() {
+                    request = request.header(name.as_str(), value.as_str());
+                }
+
                 let body = request
                     .body(body_data)
                     .map_err(|e| Error::FetchError(e.to_string()))?;
@@ -194,4 +230,209 @@ impl NavigatorBackend for ExternalNavigatorBackend {
         }
         url
     }
+
+    fn enforces_cors(&self) -> bool {
+        false
+    }
+
+    fn connect_socket(&mut self, host: &str, port: u16, timeout: Duration) -> SocketHandle {
+        let handle = self.next_socket_handle;
+        self.next_socket_handle = self.next_socket_handle.wrapping_add(1);
+
+        let (sender, receiver) = channel();
+        self.sockets.insert(handle, sender);
+
+        let host = host.to_string();
+        let events = self.socket_event_sender.clone();
+        thread::spawn(move || run_socket(handle, &host, port, timeout, events, receiver));
+
+        handle
+    }
+
+    fn send_socket(&mut self, handle: SocketHandle, data: Vec<u8>) {
+        if let Some(sender) = self.sockets.get(&handle) {
+            let _ = sender.send(data);
+        }
+    }
+
+    fn close_socket(&mut self, handle: SocketHandle) {
+        // Dropping the sender makes the socket's thread shut it down.
+        self.sockets.remove(&handle);
+    }
+
+    fn poll_sockets(&mut self) -> Vec<(SocketHandle, SocketEvent)> {
+        let events: Vec<_> = self.socket_event_receiver.try_iter().collect();
+        for (handle, event) in &events {
+            if matches!(
+                event,
+                SocketEvent::Closed | SocketEvent::IoError | SocketEvent::SecurityError
+            ) {
+                self.sockets.remove(handle);
+            }
+        }
+        events
+    }
+}
+
+/// Connect a socket, then send it the data received on `outgoing` until the
+/// movie closes it.
+///
+/// Everything that happens to the socket is reported on `events`.
+fn run_socket(
+    handle: SocketHandle,
+    host: &str,
+    port: u16,
+    timeout: Duration,
+    events: Sender<(SocketHandle, SocketEvent)>,
+    outgoing: Receiver<Vec<u8>>,
+) {
+    let mut stream = match connect_socket(host, port, timeout) {
+        Ok(stream) => stream,
+        Err(e) => {
+            log::warn!("Unable to connect to {}:{}: {}", host, port, e);
+            // Flash Player reports a connection that timed out as a security
+            // error, as that's what happens when no policy file is served.
+            let event = if e.kind() == io::ErrorKind::TimedOut {
+                SocketEvent::SecurityError
+            } else {
+                SocketEvent::IoError
+            };
+            let _ = events.send((handle, event));
+            return;
+        }
+    };
+
+    let reader = match stream.try_clone() {
+        Ok(reader) => reader,
+        Err(e) => {
+            log::warn!("Unable to read from socket {}:{}: {}", host, port, e);
+            let _ = events.send((handle, SocketEvent::IoError));
+            return;
+        }
+    };
+    let _ = events.send((handle, SocketEvent::Connected));
+    thread::spawn(move || read_socket(handle, reader, events));
+
+    for data in outgoing {
+        if let Err(e) = stream.write_all(&data) {
+            // The reading thread reports the broken connection.
+            log::warn!("Unable to write to socket {}:{}: {}", host, port, e);
+            break;
+        }
+    }
+
+    let _ = stream.shutdown(Shutdown::Both);
+}
+
+/// Connect to the first address of a host that accepts the connection.
+fn connect_socket(host: &str, port: u16, timeout: Duration) -> io::Result<TcpStream> {
+    let mut error = io::Error::new(io::ErrorKind::NotFound, "Host has no addresses");
+    for address in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&address, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => error = e,
+        }
+    }
+    Err(error)
+}
+
+/// Report the data received on a socket until it's closed.
+fn read_socket(
+    handle: SocketHandle,
+    mut stream: TcpStream,
+    events: Sender<(SocketHandle, SocketEvent)>,
+) {
+    let mut buffer = [0; 4096];
+    loop {
+        let event = match stream.read(&mut buffer) {
+            Ok(0) => SocketEvent::Closed,
+            Ok(length) => SocketEvent::Data(buffer[..length].to_vec()),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(_) => SocketEvent::IoError,
+        };
+        let is_data = matches!(event, SocketEvent::Data(_));
+        if events.send((handle, event)).is_err() || !is_data {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Connect a socket to a local port on a thread of its own, returning the
+    /// sender for its outgoing data and the receiver for its events.
+    fn start_socket(port: u16) -> (Sender<Vec<u8>>, Receiver<(SocketHandle, SocketEvent)>) {
+        let (events, event_receiver) = channel();
+        let (outgoing, outgoing_receiver) = channel();
+        thread::spawn(move || run_socket(1, "127.0.0.1", port, TIMEOUT, events, outgoing_receiver));
+        (outgoing, event_receiver)
+    }
+
+    fn listen() -> (TcpListener, u16) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        (listener, port)
+    }
+
+    #[test]
+    fn socket_exchanges_data_until_the_server_hangs_up() {
+        let (listener, port) = listen();
+        let (outgoing, events) = start_socket(port);
+        let (mut server, _) = listener.accept().unwrap();
+        assert_eq!(
+            events.recv_timeout(TIMEOUT).unwrap(),
+            (1, SocketEvent::Connected)
+        );
+
+        outgoing.send(b"ping".to_vec()).unwrap();
+        let mut received = [0; 4];
+        server.read_exact(&mut received).unwrap();
+        assert_eq!(&received, b"ping");
+
+        server.write_all(b"pong").unwrap();
+        assert_eq!(
+            events.recv_timeout(TIMEOUT).unwrap(),
+            (1, SocketEvent::Data(b"pong".to_vec()))
+        );
+
+        drop(server);
+        assert_eq!(
+            events.recv_timeout(TIMEOUT).unwrap(),
+            (1, SocketEvent::Closed)
+        );
+    }
+
+    #[test]
+    fn closing_a_socket_shuts_down_the_connection() {
+        let (listener, port) = listen();
+        let (outgoing, events) = start_socket(port);
+        let (mut server, _) = listener.accept().unwrap();
+        assert_eq!(
+            events.recv_timeout(TIMEOUT).unwrap(),
+            (1, SocketEvent::Connected)
+        );
+
+        // Dropping the sender is how `close_socket` closes a socket.
+        drop(outgoing);
+        server.set_read_timeout(Some(TIMEOUT)).unwrap();
+        let mut received = Vec::new();
+        server.read_to_end(&mut received).unwrap();
+        assert!(received.is_empty());
+    }
+
+    #[test]
+    fn refused_connection_is_an_io_error() {
+        // Nothing is listening on the port once the listener is dropped.
+        let (_, port) = listen();
+        let (_outgoing, events) = start_socket(port);
+        assert_eq!(
+            events.recv_timeout(TIMEOUT).unwrap(),
+            (1, SocketEvent::IoError)
+        );
+    }
 }
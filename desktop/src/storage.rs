@@ -1,7 +1,7 @@
 use ruffle_core::backend::storage::StorageBackend;
 use std::fs;
 use std::fs::File;
-use std::io::Write;
+use std::io::{self, Write};
 use std::path::{Component, Path, PathBuf};
 
 pub struct DiskStorageBackend {
@@ -11,7 +11,12 @@ pub struct DiskStorageBackend {
 
 impl DiskStorageBackend {
     pub fn new() -> Self {
-        let base_path = dirs::data_local_dir().unwrap().join("ruffle");
+        Self::with_base_path(dirs::data_local_dir().unwrap().join("ruffle"))
+    }
+
+    /// Store shared objects beneath a given directory, instead of the
+    /// platform's local data directory.
+    pub fn with_base_path(base_path: PathBuf) -> Self {
         let shared_objects_path = base_path.join("SharedObjects");
 
         // Create a base dir if one doesn't exist yet
@@ -43,6 +48,85 @@ impl DiskStorageBackend {
         // Remove this code eventually.
         self.base_path.join(name.replacen("/#", "/", 1))
     }
+
+    /// Read a shared object saved by the original Flash Player, if it exists.
+    ///
+    /// This allows saves to carry over to Ruffle until the movie first
+    /// flushes its own copy.
+    fn get_flash_player_shared_object(&self, name: &str) -> Option<Vec<u8>> {
+        flash_player_shared_objects_dirs()
+            .into_iter()
+            .map(|dir| dir.join(format!("{}.sol", name)))
+            .filter(|path| Self::is_path_allowed(path))
+            .find_map(|path| std::fs::read(path).ok())
+    }
+
+    /// Copy every shared object in a directory into Ruffle's storage.
+    ///
+    /// The directory should be laid out like Flash Player's storage, such as
+    /// a `#SharedObjects/<id>` directory, with each shared object stored as
+    /// `<host>/<path>/<name>.sol`. Returns the number of files copied.
+    pub fn import_shared_objects(&self, from: &Path) -> io::Result<usize> {
+        copy_sol_files(from, &self.shared_objects_path)
+    }
+
+    /// Copy every shared object in Ruffle's storage into a directory, laid
+    /// out like Flash Player's storage. Returns the number of files copied.
+    pub fn export_shared_objects(&self, to: &Path) -> io::Result<usize> {
+        copy_sol_files(&self.shared_objects_path, to)
+    }
+}
+
+/// The directory the original Flash Player keeps shared objects in on this
+/// platform.
+fn flash_player_shared_objects_root() -> Option<PathBuf> {
+    if cfg!(windows) {
+        dirs::data_dir().map(|dir| dir.join("Macromedia/Flash Player/#SharedObjects"))
+    } else if cfg!(target_os = "macos") {
+        dirs::home_dir()
+            .map(|dir| dir.join("Library/Preferences/Macromedia/Flash Player/#SharedObjects"))
+    } else {
+        dirs::home_dir().map(|dir| dir.join(".macromedia/Flash_Player/#SharedObjects"))
+    }
+}
+
+/// The directories the original Flash Player stores shared objects in.
+///
+/// Flash Player keeps its shared objects beneath a randomly named directory
+/// for each user profile, so there may be more than one.
+fn flash_player_shared_objects_dirs() -> Vec<PathBuf> {
+    let root = match flash_player_shared_objects_root() {
+        Some(root) => root,
+        None => return Vec::new(),
+    };
+
+    match fs::read_dir(root) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Recursively copy all `.sol` files from one directory to another,
+/// preserving their relative paths.
+fn copy_sol_files(from: &Path, to: &Path) -> io::Result<usize> {
+    let mut copied = 0;
+    for entry in fs::read_dir(from)? {
+        let path = entry?.path();
+        let destination = to.join(path.file_name().unwrap_or_default());
+        if path.is_dir() {
+            copied += copy_sol_files(&path, &destination)?;
+        } else if path.extension().map_or(false, |ext| ext == "sol") {
+            fs::create_dir_all(to)?;
+            fs::copy(&path, &destination)?;
+            copied += 1;
+        }
+    }
+
+    Ok(copied)
 }
 
 impl StorageBackend for DiskStorageBackend {
@@ -57,6 +141,9 @@ impl StorageBackend for DiskStorageBackend {
                 let path = self.get_back_compat_shared_object_path(name);
                 match std::fs::read(path) {
                     Ok(data) => Some(data),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                        self.get_flash_player_shared_object(name)
+                    }
                     Err(e) => {
                         log::warn!("Unable to read file {:?}", e);
                         None
@@ -108,3 +195,78 @@ impl StorageBackend for DiskStorageBackend {
         let _ = fs::remove_file(path);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory for a test to store files in.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("ruffle_storage_{}_{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn shared_objects_are_stored_as_sol_files() {
+        let dir = test_dir("sol_files");
+        let mut storage = DiskStorageBackend::with_base_path(dir.clone());
+
+        assert!(storage.put("localhost/game.swf/#save", b"data"));
+        assert_eq!(
+            fs::read(dir.join("SharedObjects/localhost/game.swf/#save.sol")).unwrap(),
+            b"data"
+        );
+        assert_eq!(
+            storage.get("localhost/game.swf/#save"),
+            Some(b"data".to_vec())
+        );
+
+        storage.remove_key("localhost/game.swf/#save");
+        assert!(!dir
+            .join("SharedObjects/localhost/game.swf/#save.sol")
+            .exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn paths_outside_storage_are_rejected() {
+        let dir = test_dir("outside");
+        let mut storage = DiskStorageBackend::with_base_path(dir.clone());
+
+        assert!(!storage.put("../escaped/#save", b"data"));
+        assert!(!dir.join("escaped").exists());
+        assert_eq!(storage.get("../escaped/#save"), None);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn shared_objects_round_trip_through_export_and_import() {
+        let dir = test_dir("round_trip");
+        let mut storage = DiskStorageBackend::with_base_path(dir.join("from"));
+        assert!(storage.put("localhost/a.swf/#first", b"1"));
+        assert!(storage.put("example.com/games/b.swf/#second", b"2"));
+        fs::write(dir.join("from/SharedObjects/notes.txt"), b"not a save").unwrap();
+
+        let exported = dir.join("#SharedObjects/ABCDEFGH");
+        assert_eq!(storage.export_shared_objects(&exported).unwrap(), 2);
+        assert!(exported.join("localhost/a.swf/#first.sol").is_file());
+        assert!(exported
+            .join("example.com/games/b.swf/#second.sol")
+            .is_file());
+        assert!(!exported.join("notes.txt").exists());
+
+        let imported = DiskStorageBackend::with_base_path(dir.join("to"));
+        assert_eq!(imported.import_shared_objects(&exported).unwrap(), 2);
+        assert_eq!(imported.get("localhost/a.swf/#first"), Some(b"1".to_vec()));
+        assert_eq!(
+            imported.get("example.com/games/b.swf/#second"),
+            Some(b"2".to_vec())
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
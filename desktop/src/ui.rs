@@ -1,67 +1,119 @@
 use clipboard::{ClipboardContext, ClipboardProvider};
-use ruffle_core::backend::ui::{MouseCursor, UiBackend};
-use ruffle_core::events::{KeyCode, PlayerEvent};
-use std::collections::HashSet;
+use ruffle_core::backend::ui::{CustomMouseCursor, MouseCursor, UiBackend};
+use ruffle_core::events::{KeyCode, KeyLocation, KeyRemap, PlayerEvent};
+use std::collections::HashMap;
 use std::rc::Rc;
 use tinyfiledialogs::{message_box_ok, MessageBoxIcon};
-use winit::event::{ElementState, ModifiersState, VirtualKeyCode, WindowEvent};
-use winit::window::Window;
+use winit::dpi::PhysicalPosition;
+use winit::event::{ElementState, ModifiersState, ScanCode, VirtualKeyCode, WindowEvent};
+use winit::window::{Fullscreen, Window};
 
 pub struct DesktopUiBackend {
     window: Rc<Window>,
-    keys_down: HashSet<VirtualKeyCode>,
+
+    /// The keys that are held down, and the key codes that they produced
+    /// before being remapped.
+    keys_down: HashMap<ScanCode, KeyCode>,
     cursor_visible: bool,
+
+    /// Whether the player is drawing a custom cursor in place of ours.
+    custom_cursor: bool,
     last_key: KeyCode,
     last_char: Option<char>,
+    last_location: KeyLocation,
+
+    /// The characters that each key has typed, with and without Shift held,
+    /// as learned from the text input that followed it.
+    key_chars: HashMap<(ScanCode, bool), char>,
+
+    /// The key whose character is expected in the next text input.
+    pending_char_key: Option<(ScanCode, bool)>,
     clipboard: ClipboardContext,
+    key_remap: KeyRemap,
 }
 
 impl DesktopUiBackend {
     pub fn new(window: Rc<Window>) -> Self {
         Self {
             window,
-            keys_down: HashSet::new(),
+            keys_down: HashMap::new(),
             cursor_visible: true,
+            custom_cursor: false,
             last_key: KeyCode::Unknown,
             last_char: None,
+            last_location: KeyLocation::Standard,
+            key_chars: HashMap::new(),
+            pending_char_key: None,
             clipboard: ClipboardProvider::new().unwrap(),
+            key_remap: KeyRemap::new(),
         }
     }
 
+    /// Rewrite the keys that the user presses before the movie sees them.
+    pub fn set_key_remap(&mut self, key_remap: KeyRemap) {
+        self.key_remap = key_remap;
+    }
+
+    /// Whether the key is held down, before it is remapped.
+    fn is_physical_key_down(&self, key: KeyCode) -> bool {
+        key != KeyCode::Unknown && self.keys_down.values().any(|down| *down == key)
+    }
+
     /// Process an input event, and return an event that should be forward to the player, if any.
     pub fn handle_event(&mut self, event: WindowEvent) -> Option<PlayerEvent> {
         // Allow KeyboardInput.modifiers (ModifiersChanged event not functional yet).
         #[allow(deprecated)]
         match event {
-            WindowEvent::KeyboardInput { input, .. } => match input.state {
-                ElementState::Pressed => {
-                    if let Some(key) = input.virtual_keycode {
-                        self.keys_down.insert(key);
-                        self.last_char =
-                            winit_key_to_char(key, input.modifiers.contains(ModifiersState::SHIFT));
-                        if let Some(key_code) = winit_to_ruffle_key_code(key) {
-                            self.last_key = key_code;
-                            return Some(PlayerEvent::KeyDown { key_code });
-                        } else {
-                            self.last_key = KeyCode::Unknown;
-                        }
+            WindowEvent::KeyboardInput { input, .. } => {
+                let is_shift_down = input.modifiers.contains(ModifiersState::SHIFT);
+                let char_key = (input.scancode, is_shift_down);
+                self.last_char = self.key_chars.get(&char_key).copied().or_else(|| {
+                    input
+                        .virtual_keycode
+                        .and_then(|key| winit_key_to_char(key, is_shift_down))
+                });
+                self.last_location = input
+                    .virtual_keycode
+                    .map_or(KeyLocation::Standard, winit_key_location);
+
+                let key_code = scancode_to_ruffle_key_code(input.scancode)
+                    .or_else(|| input.virtual_keycode.and_then(winit_to_ruffle_key_code));
+                let key_code = match key_code {
+                    Some(key_code) => key_code,
+                    None => {
+                        self.keys_down.remove(&input.scancode);
+                        self.last_key = KeyCode::Unknown;
+                        return None;
                     }
-                }
-                ElementState::Released => {
-                    if let Some(key) = input.virtual_keycode {
-                        self.keys_down.remove(&key);
-                        self.last_char =
-                            winit_key_to_char(key, input.modifiers.contains(ModifiersState::SHIFT));
-                        if let Some(key_code) = winit_to_ruffle_key_code(key) {
-                            self.last_key = key_code;
-                            return Some(PlayerEvent::KeyUp { key_code });
-                        } else {
-                            self.last_key = KeyCode::Unknown;
-                        }
+                };
+                self.last_key = self.key_remap.remap(key_code);
+
+                match input.state {
+                    ElementState::Pressed => {
+                        self.keys_down.insert(input.scancode, key_code);
+                        // Shortcuts such as Ctrl+A type control characters,
+                        // which aren't what the key types on its own.
+                        let is_shortcut = input.modifiers.contains(ModifiersState::CTRL)
+                            || input.modifiers.contains(ModifiersState::ALT)
+                            || input.modifiers.contains(ModifiersState::LOGO);
+                        self.pending_char_key = Some(char_key).filter(|_| !is_shortcut);
+                        return Some(PlayerEvent::KeyDown {
+                            key_code: self.last_key,
+                        });
+                    }
+                    ElementState::Released => {
+                        self.keys_down.remove(&input.scancode);
+                        return Some(PlayerEvent::KeyUp {
+                            key_code: self.last_key,
+                        });
                     }
                 }
-            },
+            }
             WindowEvent::ReceivedCharacter(codepoint) => {
+                if let Some(char_key) = self.pending_char_key.take() {
+                    self.key_chars.insert(char_key, codepoint);
+                }
+                self.last_char = Some(codepoint);
                 return Some(PlayerEvent::TextInput { codepoint });
             }
             _ => (),
@@ -81,113 +133,9 @@ const DOWNLOAD_FAILED_MESSAGE: &str = "Ruffle failed to open or download this fi
 
 impl UiBackend for DesktopUiBackend {
     fn is_key_down(&self, key: KeyCode) -> bool {
-        match key {
-            KeyCode::Unknown => false,
-            KeyCode::Backspace => self.keys_down.contains(&VirtualKeyCode::Back),
-            KeyCode::Tab => self.keys_down.contains(&VirtualKeyCode::Tab),
-            KeyCode::Return => self.keys_down.contains(&VirtualKeyCode::Return),
-            KeyCode::Shift => {
-                self.keys_down.contains(&VirtualKeyCode::LShift)
-                    || self.keys_down.contains(&VirtualKeyCode::RShift)
-            }
-            KeyCode::Control => {
-                self.keys_down.contains(&VirtualKeyCode::LControl)
-                    || self.keys_down.contains(&VirtualKeyCode::RControl)
-            }
-            KeyCode::Alt => {
-                self.keys_down.contains(&VirtualKeyCode::LAlt)
-                    || self.keys_down.contains(&VirtualKeyCode::RAlt)
-            }
-            KeyCode::CapsLock => self.keys_down.contains(&VirtualKeyCode::Capital),
-            KeyCode::Escape => self.keys_down.contains(&VirtualKeyCode::Escape),
-            KeyCode::Space => self.keys_down.contains(&VirtualKeyCode::Space),
-            KeyCode::Key0 => self.keys_down.contains(&VirtualKeyCode::Key0),
-            KeyCode::Key1 => self.keys_down.contains(&VirtualKeyCode::Key1),
-            KeyCode::Key2 => self.keys_down.contains(&VirtualKeyCode::Key2),
-            KeyCode::Key3 => self.keys_down.contains(&VirtualKeyCode::Key3),
-            KeyCode::Key4 => self.keys_down.contains(&VirtualKeyCode::Key4),
-            KeyCode::Key5 => self.keys_down.contains(&VirtualKeyCode::Key5),
-            KeyCode::Key6 => self.keys_down.contains(&VirtualKeyCode::Key6),
-            KeyCode::Key7 => self.keys_down.contains(&VirtualKeyCode::Key7),
-            KeyCode::Key8 => self.keys_down.contains(&VirtualKeyCode::Key8),
-            KeyCode::Key9 => self.keys_down.contains(&VirtualKeyCode::Key9),
-            KeyCode::A => self.keys_down.contains(&VirtualKeyCode::A),
-            KeyCode::B => self.keys_down.contains(&VirtualKeyCode::B),
-            KeyCode::C => self.keys_down.contains(&VirtualKeyCode::C),
-            KeyCode::D => self.keys_down.contains(&VirtualKeyCode::D),
-            KeyCode::E => self.keys_down.contains(&VirtualKeyCode::E),
-            KeyCode::F => self.keys_down.contains(&VirtualKeyCode::F),
-            KeyCode::G => self.keys_down.contains(&VirtualKeyCode::G),
-            KeyCode::H => self.keys_down.contains(&VirtualKeyCode::H),
-            KeyCode::I => self.keys_down.contains(&VirtualKeyCode::I),
-            KeyCode::J => self.keys_down.contains(&VirtualKeyCode::J),
-            KeyCode::K => self.keys_down.contains(&VirtualKeyCode::K),
-            KeyCode::L => self.keys_down.contains(&VirtualKeyCode::L),
-            KeyCode::M => self.keys_down.contains(&VirtualKeyCode::M),
-            KeyCode::N => self.keys_down.contains(&VirtualKeyCode::N),
-            KeyCode::O => self.keys_down.contains(&VirtualKeyCode::O),
-            KeyCode::P => self.keys_down.contains(&VirtualKeyCode::P),
-            KeyCode::Q => self.keys_down.contains(&VirtualKeyCode::Q),
-            KeyCode::R => self.keys_down.contains(&VirtualKeyCode::R),
-            KeyCode::S => self.keys_down.contains(&VirtualKeyCode::S),
-            KeyCode::T => self.keys_down.contains(&VirtualKeyCode::T),
-            KeyCode::U => self.keys_down.contains(&VirtualKeyCode::U),
-            KeyCode::V => self.keys_down.contains(&VirtualKeyCode::V),
-            KeyCode::W => self.keys_down.contains(&VirtualKeyCode::W),
-            KeyCode::X => self.keys_down.contains(&VirtualKeyCode::X),
-            KeyCode::Y => self.keys_down.contains(&VirtualKeyCode::Y),
-            KeyCode::Z => self.keys_down.contains(&VirtualKeyCode::Z),
-            KeyCode::Semicolon => self.keys_down.contains(&VirtualKeyCode::Semicolon),
-            KeyCode::Equals => self.keys_down.contains(&VirtualKeyCode::Equals),
-            KeyCode::Comma => self.keys_down.contains(&VirtualKeyCode::Comma),
-            KeyCode::Minus => self.keys_down.contains(&VirtualKeyCode::Minus),
-            KeyCode::Period => self.keys_down.contains(&VirtualKeyCode::Period),
-            KeyCode::Slash => self.keys_down.contains(&VirtualKeyCode::Slash),
-            KeyCode::Grave => self.keys_down.contains(&VirtualKeyCode::Grave),
-            KeyCode::LBracket => self.keys_down.contains(&VirtualKeyCode::LBracket),
-            KeyCode::Backslash => self.keys_down.contains(&VirtualKeyCode::Backslash),
-            KeyCode::RBracket => self.keys_down.contains(&VirtualKeyCode::RBracket),
-            KeyCode::Apostrophe => self.keys_down.contains(&VirtualKeyCode::Apostrophe),
-            KeyCode::Numpad0 => self.keys_down.contains(&VirtualKeyCode::Numpad0),
-            KeyCode::Numpad1 => self.keys_down.contains(&VirtualKeyCode::Numpad1),
-            KeyCode::Numpad2 => self.keys_down.contains(&VirtualKeyCode::Numpad2),
-            KeyCode::Numpad3 => self.keys_down.contains(&VirtualKeyCode::Numpad3),
-            KeyCode::Numpad4 => self.keys_down.contains(&VirtualKeyCode::Numpad4),
-            KeyCode::Numpad5 => self.keys_down.contains(&VirtualKeyCode::Numpad5),
-            KeyCode::Numpad6 => self.keys_down.contains(&VirtualKeyCode::Numpad6),
-            KeyCode::Numpad7 => self.keys_down.contains(&VirtualKeyCode::Numpad7),
-            KeyCode::Numpad8 => self.keys_down.contains(&VirtualKeyCode::Numpad8),
-            KeyCode::Numpad9 => self.keys_down.contains(&VirtualKeyCode::Numpad9),
-            KeyCode::Multiply => self.keys_down.contains(&VirtualKeyCode::NumpadMultiply),
-            KeyCode::Plus => self.keys_down.contains(&VirtualKeyCode::NumpadAdd),
-            KeyCode::NumpadMinus => self.keys_down.contains(&VirtualKeyCode::NumpadSubtract),
-            KeyCode::NumpadPeriod => self.keys_down.contains(&VirtualKeyCode::NumpadDecimal),
-            KeyCode::NumpadSlash => self.keys_down.contains(&VirtualKeyCode::NumpadDivide),
-            KeyCode::PgUp => self.keys_down.contains(&VirtualKeyCode::PageUp),
-            KeyCode::PgDown => self.keys_down.contains(&VirtualKeyCode::PageDown),
-            KeyCode::End => self.keys_down.contains(&VirtualKeyCode::End),
-            KeyCode::Home => self.keys_down.contains(&VirtualKeyCode::Home),
-            KeyCode::Left => self.keys_down.contains(&VirtualKeyCode::Left),
-            KeyCode::Up => self.keys_down.contains(&VirtualKeyCode::Up),
-            KeyCode::Right => self.keys_down.contains(&VirtualKeyCode::Right),
-            KeyCode::Down => self.keys_down.contains(&VirtualKeyCode::Down),
-            KeyCode::Insert => self.keys_down.contains(&VirtualKeyCode::Insert),
-            KeyCode::Delete => self.keys_down.contains(&VirtualKeyCode::Delete),
-            KeyCode::Pause => self.keys_down.contains(&VirtualKeyCode::Pause),
-            KeyCode::ScrollLock => self.keys_down.contains(&VirtualKeyCode::Scroll),
-            KeyCode::F1 => self.keys_down.contains(&VirtualKeyCode::F1),
-            KeyCode::F2 => self.keys_down.contains(&VirtualKeyCode::F2),
-            KeyCode::F3 => self.keys_down.contains(&VirtualKeyCode::F3),
-            KeyCode::F4 => self.keys_down.contains(&VirtualKeyCode::F4),
-            KeyCode::F5 => self.keys_down.contains(&VirtualKeyCode::F5),
-            KeyCode::F6 => self.keys_down.contains(&VirtualKeyCode::F6),
-            KeyCode::F7 => self.keys_down.contains(&VirtualKeyCode::F7),
-            KeyCode::F8 => self.keys_down.contains(&VirtualKeyCode::F8),
-            KeyCode::F9 => self.keys_down.contains(&VirtualKeyCode::F9),
-            KeyCode::F10 => self.keys_down.contains(&VirtualKeyCode::F10),
-            KeyCode::F11 => self.keys_down.contains(&VirtualKeyCode::F11),
-            KeyCode::F12 => self.keys_down.contains(&VirtualKeyCode::F12),
-        }
+        self.key_remap
+            .sources(key)
+            .any(|source| self.is_physical_key_down(source))
     }
 
     fn last_key_code(&self) -> KeyCode {
@@ -198,12 +146,17 @@ impl UiBackend for DesktopUiBackend {
         self.last_char
     }
 
+    fn last_key_location(&self) -> KeyLocation {
+        self.last_location
+    }
+
     fn mouse_visible(&self) -> bool {
         self.cursor_visible
     }
 
     fn set_mouse_visible(&mut self, visible: bool) {
-        self.window.set_cursor_visible(visible);
+        self.window
+            .set_cursor_visible(visible && !self.custom_cursor);
         self.cursor_visible = visible;
     }
 
@@ -218,14 +171,41 @@ impl UiBackend for DesktopUiBackend {
         self.window.set_cursor_icon(icon);
     }
 
+    fn set_custom_mouse_cursor(&mut self, cursor: Option<&CustomMouseCursor>) -> bool {
+        // winit can't show bitmap cursors, so the player draws them instead.
+        self.custom_cursor = cursor.is_some();
+        self.window
+            .set_cursor_visible(self.cursor_visible && !self.custom_cursor);
+        false
+    }
+
     fn set_clipboard_content(&mut self, content: String) {
-        self.clipboard.set_contents(content).unwrap();
+        if let Err(e) = self.clipboard.set_contents(content) {
+            log::error!("Couldn't set clipboard contents: {}", e);
+        }
+    }
+
+    fn clipboard_content(&mut self) -> Option<String> {
+        self.clipboard.get_contents().ok()
     }
 
     fn is_fullscreen(&self) -> bool {
         self.window.fullscreen().is_some()
     }
 
+    fn set_fullscreen(&mut self, is_full: bool) {
+        self.window
+            .set_fullscreen(is_full.then(|| Fullscreen::Borderless(None)));
+    }
+
+    fn set_ime_position(&mut self, position: Option<(f64, f64)>) {
+        // The input method is always enabled, and its text arrives as
+        // ordinary text input, so it only needs to know where to show up.
+        if let Some((x, y)) = position {
+            self.window.set_ime_position(PhysicalPosition::new(x, y));
+        }
+    }
+
     fn display_unsupported_message(&self) {
         message_box_ok(
             "Ruffle - Unsupported content",
@@ -253,7 +233,8 @@ fn winit_to_ruffle_key_code(key_code: VirtualKeyCode) -> Option<KeyCode> {
     Some(match key_code {
         VirtualKeyCode::Back => KeyCode::Backspace,
         VirtualKeyCode::Tab => KeyCode::Tab,
-        VirtualKeyCode::Return => KeyCode::Return,
+        VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => KeyCode::Return,
+        VirtualKeyCode::LWin | VirtualKeyCode::RWin => KeyCode::Command,
         VirtualKeyCode::LShift | VirtualKeyCode::RShift => KeyCode::Shift,
         VirtualKeyCode::LControl | VirtualKeyCode::RControl => KeyCode::Control,
         VirtualKeyCode::LAlt | VirtualKeyCode::RAlt => KeyCode::Alt,
@@ -322,6 +303,7 @@ fn winit_to_ruffle_key_code(key_code: VirtualKeyCode) -> Option<KeyCode> {
         VirtualKeyCode::NumpadSubtract => KeyCode::NumpadMinus,
         VirtualKeyCode::NumpadDecimal => KeyCode::NumpadPeriod,
         VirtualKeyCode::NumpadDivide => KeyCode::NumpadSlash,
+        VirtualKeyCode::Numlock => KeyCode::NumLock,
         VirtualKeyCode::PageUp => KeyCode::PgUp,
         VirtualKeyCode::PageDown => KeyCode::PgDown,
         VirtualKeyCode::End => KeyCode::End,
@@ -346,6 +328,163 @@ fn winit_to_ruffle_key_code(key_code: VirtualKeyCode) -> Option<KeyCode> {
         VirtualKeyCode::F10 => KeyCode::F10,
         VirtualKeyCode::F11 => KeyCode::F11,
         VirtualKeyCode::F12 => KeyCode::F12,
+        VirtualKeyCode::F13 => KeyCode::F13,
+        VirtualKeyCode::F14 => KeyCode::F14,
+        VirtualKeyCode::F15 => KeyCode::F15,
+        _ => return None,
+    })
+}
+
+/// Return where a winit `VirtualKeyCode` is on the keyboard.
+fn winit_key_location(key_code: VirtualKeyCode) -> KeyLocation {
+    match key_code {
+        VirtualKeyCode::LShift
+        | VirtualKeyCode::LControl
+        | VirtualKeyCode::LAlt
+        | VirtualKeyCode::LWin => KeyLocation::Left,
+        VirtualKeyCode::RShift
+        | VirtualKeyCode::RControl
+        | VirtualKeyCode::RAlt
+        | VirtualKeyCode::RWin => KeyLocation::Right,
+        VirtualKeyCode::Numpad0
+        | VirtualKeyCode::Numpad1
+        | VirtualKeyCode::Numpad2
+        | VirtualKeyCode::Numpad3
+        | VirtualKeyCode::Numpad4
+        | VirtualKeyCode::Numpad5
+        | VirtualKeyCode::Numpad6
+        | VirtualKeyCode::Numpad7
+        | VirtualKeyCode::Numpad8
+        | VirtualKeyCode::Numpad9
+        | VirtualKeyCode::NumpadAdd
+        | VirtualKeyCode::NumpadComma
+        | VirtualKeyCode::NumpadDecimal
+        | VirtualKeyCode::NumpadDivide
+        | VirtualKeyCode::NumpadEnter
+        | VirtualKeyCode::NumpadEquals
+        | VirtualKeyCode::NumpadMultiply
+        | VirtualKeyCode::NumpadSubtract => KeyLocation::NumPad,
+        _ => KeyLocation::Standard,
+    }
+}
+
+/// Convert the scancode of a key in the main block of the keyboard into a
+/// Ruffle `KeyCode`, by where the key is rather than what is printed on it.
+///
+/// This keeps movies that use WASD or similar working on every layout, as
+/// winit's `VirtualKeyCode` follows the layout on some platforms. Keys
+/// outside the main block are left to `winit_to_ruffle_key_code`, as they
+/// are the same on every layout.
+#[cfg(not(target_os = "macos"))]
+fn scancode_to_ruffle_key_code(scancode: ScanCode) -> Option<KeyCode> {
+    // Windows gives PC set 1 scancodes, and Linux gives evdev codes, which
+    // are the same for these keys.
+    Some(match scancode {
+        0x02 => KeyCode::Key1,
+        0x03 => KeyCode::Key2,
+        0x04 => KeyCode::Key3,
+        0x05 => KeyCode::Key4,
+        0x06 => KeyCode::Key5,
+        0x07 => KeyCode::Key6,
+        0x08 => KeyCode::Key7,
+        0x09 => KeyCode::Key8,
+        0x0A => KeyCode::Key9,
+        0x0B => KeyCode::Key0,
+        0x0C => KeyCode::Minus,
+        0x0D => KeyCode::Equals,
+        0x10 => KeyCode::Q,
+        0x11 => KeyCode::W,
+        0x12 => KeyCode::E,
+        0x13 => KeyCode::R,
+        0x14 => KeyCode::T,
+        0x15 => KeyCode::Y,
+        0x16 => KeyCode::U,
+        0x17 => KeyCode::I,
+        0x18 => KeyCode::O,
+        0x19 => KeyCode::P,
+        0x1A => KeyCode::LBracket,
+        0x1B => KeyCode::RBracket,
+        0x1E => KeyCode::A,
+        0x1F => KeyCode::S,
+        0x20 => KeyCode::D,
+        0x21 => KeyCode::F,
+        0x22 => KeyCode::G,
+        0x23 => KeyCode::H,
+        0x24 => KeyCode::J,
+        0x25 => KeyCode::K,
+        0x26 => KeyCode::L,
+        0x27 => KeyCode::Semicolon,
+        0x28 => KeyCode::Apostrophe,
+        0x29 => KeyCode::Grave,
+        0x2B => KeyCode::Backslash,
+        0x2C => KeyCode::Z,
+        0x2D => KeyCode::X,
+        0x2E => KeyCode::C,
+        0x2F => KeyCode::V,
+        0x30 => KeyCode::B,
+        0x31 => KeyCode::N,
+        0x32 => KeyCode::M,
+        0x33 => KeyCode::Comma,
+        0x34 => KeyCode::Period,
+        0x35 => KeyCode::Slash,
+        _ => return None,
+    })
+}
+
+/// Convert the scancode of a key in the main block of the keyboard into a
+/// Ruffle `KeyCode`, by where the key is rather than what is printed on it.
+#[cfg(target_os = "macos")]
+fn scancode_to_ruffle_key_code(scancode: ScanCode) -> Option<KeyCode> {
+    // macOS gives its own virtual key codes, named `kVK_ANSI_*` after the
+    // US layout.
+    Some(match scancode {
+        0x00 => KeyCode::A,
+        0x01 => KeyCode::S,
+        0x02 => KeyCode::D,
+        0x03 => KeyCode::F,
+        0x04 => KeyCode::H,
+        0x05 => KeyCode::G,
+        0x06 => KeyCode::Z,
+        0x07 => KeyCode::X,
+        0x08 => KeyCode::C,
+        0x09 => KeyCode::V,
+        0x0B => KeyCode::B,
+        0x0C => KeyCode::Q,
+        0x0D => KeyCode::W,
+        0x0E => KeyCode::E,
+        0x0F => KeyCode::R,
+        0x10 => KeyCode::Y,
+        0x11 => KeyCode::T,
+        0x12 => KeyCode::Key1,
+        0x13 => KeyCode::Key2,
+        0x14 => KeyCode::Key3,
+        0x15 => KeyCode::Key4,
+        0x16 => KeyCode::Key6,
+        0x17 => KeyCode::Key5,
+        0x18 => KeyCode::Equals,
+        0x19 => KeyCode::Key9,
+        0x1A => KeyCode::Key7,
+        0x1B => KeyCode::Minus,
+        0x1C => KeyCode::Key8,
+        0x1D => KeyCode::Key0,
+        0x1E => KeyCode::RBracket,
+        0x1F => KeyCode::O,
+        0x20 => KeyCode::U,
+        0x21 => KeyCode::LBracket,
+        0x22 => KeyCode::I,
+        0x23 => KeyCode::P,
+        0x25 => KeyCode::L,
+        0x26 => KeyCode::J,
+        0x27 => KeyCode::Apostrophe,
+        0x28 => KeyCode::K,
+        0x29 => KeyCode::Semicolon,
+        0x2A => KeyCode::Backslash,
+        0x2B => KeyCode::Comma,
+        0x2C => KeyCode::Slash,
+        0x2D => KeyCode::N,
+        0x2E => KeyCode::M,
+        0x2F => KeyCode::Period,
+        0x32 => KeyCode::Grave,
         _ => return None,
     })
 }
@@ -0,0 +1,122 @@
+use ruffle_core::backend::audio::{
+    swf, AudioBackend, AudioMixer, SoundHandle, SoundInstanceHandle, SoundTransform,
+};
+use ruffle_core::impl_audio_mixer_backend;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+type Error = Box<dyn std::error::Error>;
+
+/// The number of channels in captured audio.
+pub const NUM_CHANNELS: u8 = 2;
+
+/// The sample rate of captured audio.
+pub const SAMPLE_RATE: u32 = 44100;
+
+/// The size in bytes of one sample on every channel.
+pub const BLOCK_ALIGN: u16 = NUM_CHANNELS as u16 * 2;
+
+/// An audio backend that mixes audio on demand, so that it can be recorded
+/// in step with the captured frames instead of being played out loud.
+pub struct CaptureAudioBackend {
+    mixer: AudioMixer,
+
+    /// The fraction of a sample that was owed by previous frames.
+    ///
+    /// Sample rates rarely divide evenly by frame rates, so this is carried
+    /// over to keep the audio from drifting away from the frames.
+    sample_remainder: f64,
+}
+
+impl CaptureAudioBackend {
+    pub fn new() -> Self {
+        Self {
+            mixer: AudioMixer::new(NUM_CHANNELS, SAMPLE_RATE),
+            sample_remainder: 0.0,
+        }
+    }
+
+    /// Mix the audio that plays during one frame of the movie, in
+    /// interleaved stereo.
+    pub fn mix_frame(&mut self, frame_rate: f64) -> Vec<i16> {
+        let samples = f64::from(SAMPLE_RATE) / frame_rate + self.sample_remainder;
+        self.sample_remainder = samples.fract();
+
+        let mut buffer = vec![0; samples as usize * usize::from(NUM_CHANNELS)];
+        self.mixer.mix::<i16>(&mut buffer);
+        buffer
+    }
+}
+
+impl AudioBackend for CaptureAudioBackend {
+    impl_audio_mixer_backend!(mixer);
+
+    fn play(&mut self) {}
+
+    fn pause(&mut self) {}
+}
+
+/// Write the `WAVEFORMAT` structure that describes captured audio, as used
+/// by both WAV and AVI files.
+pub fn write_wave_format(writer: &mut impl Write) -> Result<(), Error> {
+    writer.write_all(&1u16.to_le_bytes())?; // PCM
+    writer.write_all(&u16::from(NUM_CHANNELS).to_le_bytes())?;
+    writer.write_all(&SAMPLE_RATE.to_le_bytes())?;
+    writer.write_all(&(SAMPLE_RATE * u32::from(BLOCK_ALIGN)).to_le_bytes())?;
+    writer.write_all(&BLOCK_ALIGN.to_le_bytes())?;
+    writer.write_all(&16u16.to_le_bytes())?;
+    Ok(())
+}
+
+/// Write samples to `writer` as little-endian 16-bit PCM.
+pub fn write_samples(writer: &mut impl Write, samples: &[i16]) -> Result<(), Error> {
+    for sample in samples {
+        writer.write_all(&sample.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// Write the audio of each captured frame, in interleaved stereo, to a
+/// 16-bit PCM WAV file.
+pub fn write_wav(path: &Path, frames: &[Vec<i16>]) -> Result<(), Error> {
+    let num_samples: usize = frames.iter().map(Vec::len).sum();
+    let data_size = u32::try_from(num_samples * 2)
+        .ok()
+        .filter(|size| *size <= u32::MAX - 36)
+        .ok_or("Captured audio is too long for a WAV file")?;
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(b"RIFF")?;
+    writer.write_all(&(36 + data_size).to_le_bytes())?;
+    writer.write_all(b"WAVE")?;
+
+    writer.write_all(b"fmt ")?;
+    writer.write_all(&16u32.to_le_bytes())?;
+    write_wave_format(&mut writer)?;
+
+    writer.write_all(b"data")?;
+    writer.write_all(&data_size.to_le_bytes())?;
+    for samples in frames {
+        write_samples(&mut writer, samples)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_mix_whole_seconds_of_audio() {
+        let mut audio = CaptureAudioBackend::new();
+        let samples: usize = (0..24).map(|_| audio.mix_frame(24.0).len()).sum();
+        assert_eq!(samples, SAMPLE_RATE as usize * usize::from(NUM_CHANNELS));
+
+        let lengths: Vec<usize> = (0..2).map(|_| audio.mix_frame(24.0).len()).collect();
+        assert_eq!(lengths, vec![1837 * 2, 1838 * 2]);
+    }
+}
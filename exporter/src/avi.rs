@@ -0,0 +1,306 @@
+use crate::audio::{write_samples, write_wave_format, BLOCK_ALIGN, SAMPLE_RATE};
+use image::RgbaImage;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+type Error = Box<dyn std::error::Error>;
+
+/// The AVI file has an `idx1` index.
+const AVIF_HASINDEX: u32 = 0x10;
+
+/// The audio and video chunks of the AVI file are interleaved.
+const AVIF_ISINTERLEAVED: u32 = 0x100;
+
+/// An index entry refers to a chunk that can be decoded on its own.
+const AVIIF_KEYFRAME: u32 = 0x10;
+
+/// The size of the `avih` and `strh` headers.
+const HEADER_SIZE: u32 = 56;
+
+/// The size of a `BITMAPINFOHEADER`.
+const BITMAP_INFO_SIZE: u32 = 40;
+
+/// The size of a `WAVEFORMAT`.
+const WAVE_FORMAT_SIZE: u32 = 16;
+
+/// Write captured frames, and the audio that played during each of them, to
+/// a single uncompressed AVI file.
+///
+/// The video is stored as 32-bit RGB and the audio as 16-bit PCM. Each
+/// frame's audio is written right after the frame, so that the two streams
+/// stay in sync.
+pub fn write_avi(
+    path: &Path,
+    frames: &[RgbaImage],
+    audio: Option<&[Vec<i16>]>,
+    frame_rate: f64,
+) -> Result<(), Error> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_avi_to(&mut writer, frames, audio, frame_rate)?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_avi_to(
+    writer: &mut impl Write,
+    frames: &[RgbaImage],
+    audio: Option<&[Vec<i16>]>,
+    frame_rate: f64,
+) -> Result<(), Error> {
+    let (width, height) = frames
+        .first()
+        .map(|frame| frame.dimensions())
+        .ok_or("No frames were captured")?;
+    let frame_size = u64::from(width) * u64::from(height) * 4;
+    let num_frames = frames.len() as u64;
+    let audio_sizes: Vec<u64> = audio
+        .unwrap_or_default()
+        .iter()
+        .map(|samples| samples.len() as u64 * 2)
+        .collect();
+    let audio_size: u64 = audio_sizes.iter().sum();
+    let num_chunks = num_frames + audio_sizes.iter().filter(|size| **size > 0).count() as u64;
+
+    let video_list_size = 4 + (8 + HEADER_SIZE) + (8 + BITMAP_INFO_SIZE);
+    let audio_list_size = 4 + (8 + HEADER_SIZE) + (8 + WAVE_FORMAT_SIZE);
+    let mut header_list_size = 4 + (8 + HEADER_SIZE) + (8 + video_list_size);
+    if audio.is_some() {
+        header_list_size += 8 + audio_list_size;
+    }
+    let movie_list_size =
+        4 + num_frames * (8 + frame_size) + 8 * (num_chunks - num_frames) + audio_size;
+    let riff_size =
+        4 + (8 + u64::from(header_list_size)) + (8 + movie_list_size) + 8 + 16 * num_chunks;
+    let too_large = || "Captured frames are too large for an AVI file";
+    let riff_size = u32::try_from(riff_size).map_err(|_| too_large())?;
+    let movie_list_size = u32::try_from(movie_list_size).map_err(|_| too_large())?;
+    let frame_size = u32::try_from(frame_size).map_err(|_| too_large())?;
+
+    // Flash frame rates are 8.8 fixed point, so this is exact.
+    let rate = (frame_rate * 256.0).round() as u32;
+    let scale = 256;
+    let streams: u32 = if audio.is_some() { 2 } else { 1 };
+
+    writer.write_all(b"RIFF")?;
+    writer.write_all(&riff_size.to_le_bytes())?;
+    writer.write_all(b"AVI ")?;
+
+    writer.write_all(b"LIST")?;
+    writer.write_all(&header_list_size.to_le_bytes())?;
+    writer.write_all(b"hdrl")?;
+
+    writer.write_all(b"avih")?;
+    writer.write_all(&HEADER_SIZE.to_le_bytes())?;
+    writer.write_all(&((1_000_000.0 / frame_rate).round() as u32).to_le_bytes())?;
+    writer.write_all(&0u32.to_le_bytes())?; // dwMaxBytesPerSec
+    writer.write_all(&0u32.to_le_bytes())?; // dwPaddingGranularity
+    writer.write_all(&(AVIF_HASINDEX | AVIF_ISINTERLEAVED).to_le_bytes())?;
+    writer.write_all(&(num_frames as u32).to_le_bytes())?;
+    writer.write_all(&0u32.to_le_bytes())?; // dwInitialFrames
+    writer.write_all(&streams.to_le_bytes())?;
+    writer.write_all(&frame_size.to_le_bytes())?; // dwSuggestedBufferSize
+    writer.write_all(&width.to_le_bytes())?;
+    writer.write_all(&height.to_le_bytes())?;
+    writer.write_all(&[0; 16])?; // dwReserved
+
+    writer.write_all(b"LIST")?;
+    writer.write_all(&video_list_size.to_le_bytes())?;
+    writer.write_all(b"strl")?;
+    write_stream_header(
+        writer,
+        StreamHeader {
+            kind: b"vids",
+            scale,
+            rate,
+            length: num_frames as u32,
+            buffer_size: frame_size,
+            sample_size: 0,
+            width: width as u16,
+            height: height as u16,
+        },
+    )?;
+    writer.write_all(b"strf")?;
+    writer.write_all(&BITMAP_INFO_SIZE.to_le_bytes())?;
+    writer.write_all(&BITMAP_INFO_SIZE.to_le_bytes())?;
+    writer.write_all(&width.to_le_bytes())?;
+    // A positive height means the rows are stored bottom-up.
+    writer.write_all(&height.to_le_bytes())?;
+    writer.write_all(&1u16.to_le_bytes())?; // biPlanes
+    writer.write_all(&32u16.to_le_bytes())?; // biBitCount
+    writer.write_all(&0u32.to_le_bytes())?; // biCompression (BI_RGB)
+    writer.write_all(&frame_size.to_le_bytes())?;
+    writer.write_all(&[0; 16])?; // Resolution and palette
+
+    if audio.is_some() {
+        writer.write_all(b"LIST")?;
+        writer.write_all(&audio_list_size.to_le_bytes())?;
+        writer.write_all(b"strl")?;
+        write_stream_header(
+            writer,
+            StreamHeader {
+                kind: b"auds",
+                scale: u32::from(BLOCK_ALIGN),
+                rate: SAMPLE_RATE * u32::from(BLOCK_ALIGN),
+                length: (audio_size / u64::from(BLOCK_ALIGN)) as u32,
+                buffer_size: audio_sizes.iter().copied().max().unwrap_or(0) as u32,
+                sample_size: u32::from(BLOCK_ALIGN),
+                width: 0,
+                height: 0,
+            },
+        )?;
+        writer.write_all(b"strf")?;
+        writer.write_all(&WAVE_FORMAT_SIZE.to_le_bytes())?;
+        write_wave_format(writer)?;
+    }
+
+    writer.write_all(b"LIST")?;
+    writer.write_all(&movie_list_size.to_le_bytes())?;
+    writer.write_all(b"movi")?;
+
+    // Index offsets are relative to the `movi` fourcc.
+    let mut index = Vec::with_capacity(num_chunks as usize);
+    let mut offset = 4;
+    for (i, frame) in frames.iter().enumerate() {
+        writer.write_all(b"00db")?;
+        writer.write_all(&frame_size.to_le_bytes())?;
+        for row in frame.rows().rev() {
+            for pixel in row {
+                let [r, g, b, a] = pixel.0;
+                writer.write_all(&[b, g, r, a])?;
+            }
+        }
+        index.push((b"00db", offset, frame_size));
+        offset += 8 + frame_size;
+
+        if let Some(samples) = audio.and_then(|audio| audio.get(i)) {
+            if !samples.is_empty() {
+                let size = samples.len() as u32 * 2;
+                writer.write_all(b"01wb")?;
+                writer.write_all(&size.to_le_bytes())?;
+                write_samples(writer, samples)?;
+                index.push((b"01wb", offset, size));
+                offset += 8 + size;
+            }
+        }
+    }
+
+    writer.write_all(b"idx1")?;
+    writer.write_all(&(index.len() as u32 * 16).to_le_bytes())?;
+    for (id, offset, size) in index {
+        writer.write_all(id)?;
+        writer.write_all(&AVIIF_KEYFRAME.to_le_bytes())?;
+        writer.write_all(&offset.to_le_bytes())?;
+        writer.write_all(&size.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+struct StreamHeader {
+    kind: &'static [u8; 4],
+    scale: u32,
+    rate: u32,
+    length: u32,
+    buffer_size: u32,
+    sample_size: u32,
+    width: u16,
+    height: u16,
+}
+
+fn write_stream_header(writer: &mut impl Write, header: StreamHeader) -> Result<(), Error> {
+    writer.write_all(b"strh")?;
+    writer.write_all(&HEADER_SIZE.to_le_bytes())?;
+    writer.write_all(header.kind)?;
+    writer.write_all(&[0; 4])?; // fccHandler
+    writer.write_all(&0u32.to_le_bytes())?; // dwFlags
+    writer.write_all(&0u16.to_le_bytes())?; // wPriority
+    writer.write_all(&0u16.to_le_bytes())?; // wLanguage
+    writer.write_all(&0u32.to_le_bytes())?; // dwInitialFrames
+    writer.write_all(&header.scale.to_le_bytes())?;
+    writer.write_all(&header.rate.to_le_bytes())?;
+    writer.write_all(&0u32.to_le_bytes())?; // dwStart
+    writer.write_all(&header.length.to_le_bytes())?;
+    writer.write_all(&header.buffer_size.to_le_bytes())?;
+    writer.write_all(&u32::MAX.to_le_bytes())?; // dwQuality
+    writer.write_all(&header.sample_size.to_le_bytes())?;
+    writer.write_all(&0u16.to_le_bytes())?; // rcFrame
+    writer.write_all(&0u16.to_le_bytes())?;
+    writer.write_all(&header.width.to_le_bytes())?;
+    writer.write_all(&header.height.to_le_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn read_u32(data: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes([
+            data[offset],
+            data[offset + 1],
+            data[offset + 2],
+            data[offset + 3],
+        ])
+    }
+
+    fn write(audio: Option<&[Vec<i16>]>) -> Vec<u8> {
+        let frames = vec![
+            RgbaImage::from_pixel(2, 2, Rgba([1, 2, 3, 255])),
+            RgbaImage::from_pixel(2, 2, Rgba([4, 5, 6, 255])),
+        ];
+        let mut data = Vec::new();
+        write_avi_to(&mut data, &frames, audio, 24.0).unwrap();
+        data
+    }
+
+    fn find(data: &[u8], id: &[u8]) -> usize {
+        data.windows(id.len()).position(|w| w == id).unwrap()
+    }
+
+    #[test]
+    fn riff_size_covers_the_file() {
+        for audio in &[None, Some(&[vec![1, 2], vec![3, 4, 5, 6]][..])] {
+            let data = write(*audio);
+            assert_eq!(&data[0..4], b"RIFF");
+            assert_eq!(read_u32(&data, 4) as usize, data.len() - 8);
+            let hdrl = find(&data, b"hdrl");
+            let movi = find(&data, b"movi");
+            assert_eq!(read_u32(&data, hdrl - 4) as usize, movi - 8 - hdrl);
+            let idx1 = find(&data, b"idx1");
+            assert_eq!(read_u32(&data, movi - 4) as usize, idx1 - movi);
+        }
+    }
+
+    #[test]
+    fn audio_follows_its_frame() {
+        let data = write(Some(&[vec![1, 2], vec![3, 4, 5, 6]][..]));
+        let movi = find(&data, b"movi");
+        let idx1 = find(&data, b"idx1");
+        let entries: Vec<(&[u8], u32, u32)> = data[idx1 + 8..]
+            .chunks(16)
+            .map(|e| (&e[0..4], read_u32(e, 8), read_u32(e, 12)))
+            .collect();
+        let ids: Vec<&[u8]> = entries.iter().map(|(id, _, _)| *id).collect();
+        let expected: Vec<&[u8]> = vec![b"00db", b"01wb", b"00db", b"01wb"];
+        assert_eq!(ids, expected);
+
+        for (id, offset, size) in entries {
+            let chunk = movi + offset as usize;
+            assert_eq!(&data[chunk..chunk + 4], id);
+            assert_eq!(read_u32(&data, chunk + 4), size);
+        }
+
+        // The second frame is stored as BGRA, followed by its audio.
+        let second_audio = movi + 4 + (8 + 16) + (8 + 4) + (8 + 16);
+        assert_eq!(&data[second_audio - 16..second_audio - 12], &[6, 5, 4, 255]);
+        assert_eq!(&data[second_audio + 8..second_audio + 10], &[3, 0]);
+    }
+
+    #[test]
+    fn no_frames_is_an_error() {
+        assert!(write_avi_to(&mut Vec::new(), &[], None, 24.0).is_err());
+    }
+}
@@ -1,7 +1,12 @@
+mod audio;
+mod avi;
+
+use crate::audio::{write_wav, CaptureAudioBackend};
+use crate::avi::write_avi;
 use clap::Clap;
 use image::RgbaImage;
 use indicatif::{ProgressBar, ProgressStyle};
-use ruffle_core::backend::audio::NullAudioBackend;
+use ruffle_core::backend::audio::{AudioBackend, NullAudioBackend};
 use ruffle_core::backend::locale::NullLocaleBackend;
 use ruffle_core::backend::log::NullLogBackend;
 use ruffle_core::backend::navigator::NullNavigatorBackend;
@@ -61,6 +66,18 @@ struct Opt {
     #[clap(short, long)]
     silent: bool,
 
+    /// Also record the audio of the captured frames to a WAV file.
+    /// The audio is mixed one frame at a time, so it stays in sync with the frames.
+    #[clap(long)]
+    audio: bool,
+
+    /// Save the captured frames, and the audio if `--audio` is given, as a single
+    /// uncompressed AVI video instead of PNG and WAV files.
+    /// This is the only video format the exporter writes. For MP4 or WebM, re-encode
+    /// the AVI with an external tool, such as `ffmpeg -i movie.avi movie.mp4`.
+    #[clap(long)]
+    avi: bool,
+
     #[clap(flatten)]
     size: SizeOpt,
 
@@ -86,6 +103,23 @@ struct Opt {
     trace_path: Option<PathBuf>,
 }
 
+/// The frames and audio captured from a movie.
+struct Capture {
+    frames: Vec<RgbaImage>,
+
+    /// The audio that played during each captured frame, if it was recorded.
+    audio: Option<Vec<Vec<i16>>>,
+
+    frame_rate: f64,
+}
+
+impl Capture {
+    /// Save the capture as a single AVI video.
+    fn save_avi(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        write_avi(path, &self.frames, self.audio.as_deref(), self.frame_rate)
+    }
+}
+
 fn take_screenshot(
     descriptors: Descriptors,
     swf_path: &Path,
@@ -93,7 +127,8 @@ fn take_screenshot(
     skipframes: u32,
     progress: &Option<ProgressBar>,
     size: SizeOpt,
-) -> Result<(Descriptors, Vec<RgbaImage>), Box<dyn std::error::Error>> {
+    capture_audio: bool,
+) -> Result<(Descriptors, Capture), Box<dyn std::error::Error>> {
     let movie = SwfMovie::from_path(&swf_path, None)?;
 
     let width = size
@@ -109,9 +144,14 @@ fn take_screenshot(
     let height = (height * size.scale).round() as u32;
 
     let target = TextureTarget::new(&descriptors.device, (width, height));
+    let audio: Box<dyn AudioBackend> = if capture_audio {
+        Box::new(CaptureAudioBackend::new())
+    } else {
+        Box::new(NullAudioBackend::new())
+    };
     let player = Player::new(
         Box::new(WgpuRenderBackend::new(descriptors, target)?),
-        Box::new(NullAudioBackend::new()),
+        audio,
         Box::new(NullNavigatorBackend::new()),
         Box::new(MemoryStorageBackend::default()),
         Box::new(NullLocaleBackend::new()),
@@ -127,6 +167,11 @@ fn take_screenshot(
     player.lock().unwrap().set_root_movie(Arc::new(movie));
 
     let mut result = Vec::new();
+    let mut audio_result = if capture_audio {
+        Some(Vec::new())
+    } else {
+        None
+    };
     let totalframes = frames + skipframes;
 
     for i in 0..totalframes {
@@ -138,6 +183,18 @@ fn take_screenshot(
             ));
         }
         player.lock().unwrap().run_frame();
+        if let Some(audio_result) = &mut audio_result {
+            let mut player = player.lock().unwrap();
+            let frame_rate = player.frame_rate();
+            let samples = player
+                .audio_mut()
+                .downcast_mut::<CaptureAudioBackend>()
+                .unwrap()
+                .mix_frame(frame_rate);
+            if i >= skipframes {
+                audio_result.push(samples);
+            }
+        }
         if i >= skipframes {
             player.lock().unwrap().render();
             let mut player = player.lock().unwrap();
@@ -158,6 +215,7 @@ fn take_screenshot(
         }
     }
 
+    let frame_rate = player.lock().unwrap().frame_rate();
     let descriptors = Arc::try_unwrap(player)
         .ok()
         .unwrap()
@@ -167,7 +225,14 @@ fn take_screenshot(
         .ok()
         .unwrap()
         .descriptors();
-    Ok((descriptors, result))
+    Ok((
+        descriptors,
+        Capture {
+            frames: result,
+            audio: audio_result,
+            frame_rate,
+        },
+    ))
 }
 
 fn find_files(root: &Path, with_progress: bool) -> Vec<DirEntry> {
@@ -204,13 +269,15 @@ fn capture_single_swf(descriptors: Descriptors, opt: &Opt) -> Result<(), Box<dyn
     let output = opt.output_path.clone().unwrap_or_else(|| {
         let mut result = PathBuf::new();
         result.set_file_name(opt.swf.file_stem().unwrap());
-        if opt.frames == 1 {
+        if opt.avi {
+            result.set_extension("avi");
+        } else if opt.frames == 1 {
             result.set_extension("png");
         }
         result
     });
 
-    if opt.frames > 1 {
+    if opt.frames > 1 && !opt.avi {
         let _ = create_dir_all(&output);
     }
 
@@ -228,26 +295,41 @@ fn capture_single_swf(descriptors: Descriptors, opt: &Opt) -> Result<(), Box<dyn
         None
     };
 
-    let (_, frames) = take_screenshot(
+    let (_, capture) = take_screenshot(
         descriptors,
         &opt.swf,
         opt.frames,
         opt.skipframes,
         &progress,
         opt.size,
+        opt.audio,
     )?;
 
     if let Some(progress) = &progress {
         progress.set_message(opt.swf.file_stem().unwrap().to_string_lossy().into_owned());
     }
 
-    if frames.len() == 1 {
-        frames.get(0).unwrap().save(&output)?;
+    let frames = &capture.frames;
+    if opt.avi {
+        capture.save_avi(&output)?;
     } else {
-        for (frame, image) in frames.iter().enumerate() {
-            let mut path: PathBuf = (&output).into();
-            path.push(format!("{}.png", frame));
-            image.save(&path)?;
+        if frames.len() == 1 {
+            frames.get(0).unwrap().save(&output)?;
+        } else {
+            for (frame, image) in frames.iter().enumerate() {
+                let mut path: PathBuf = (&output).into();
+                path.push(format!("{}.png", frame));
+                image.save(&path)?;
+            }
+        }
+
+        if let Some(audio) = &capture.audio {
+            let path = if frames.len() == 1 {
+                output.with_extension("wav")
+            } else {
+                output.join("audio.wav")
+            };
+            write_wav(&path, audio)?;
         }
     }
 
@@ -295,13 +377,14 @@ fn capture_multiple_swfs(mut descriptors: Descriptors, opt: &Opt) -> Result<(),
     };
 
     for file in &files {
-        let (new_descriptors, frames) = take_screenshot(
+        let (new_descriptors, capture) = take_screenshot(
             descriptors,
             file.path(),
             opt.frames,
             opt.skipframes,
             &progress,
             opt.size,
+            opt.audio,
         )?;
         descriptors = new_descriptors;
 
@@ -321,7 +404,16 @@ fn capture_multiple_swfs(mut descriptors: Descriptors, opt: &Opt) -> Result<(),
             .unwrap_or_else(|_| file.path())
             .to_path_buf();
 
-        if frames.len() == 1 {
+        let frames = &capture.frames;
+        if opt.avi {
+            let mut destination: PathBuf = (&output).into();
+            relative_path.set_extension("avi");
+            destination.push(relative_path);
+            if let Some(parent) = destination.parent() {
+                let _ = create_dir_all(parent);
+            }
+            capture.save_avi(&destination)?;
+        } else if frames.len() == 1 {
             let mut destination: PathBuf = (&output).into();
             relative_path.set_extension("png");
             destination.push(relative_path);
@@ -329,6 +421,9 @@ fn capture_multiple_swfs(mut descriptors: Descriptors, opt: &Opt) -> Result<(),
                 let _ = create_dir_all(parent);
             }
             frames.get(0).unwrap().save(&destination)?;
+            if let Some(audio) = &capture.audio {
+                write_wav(&destination.with_extension("wav"), audio)?;
+            }
         } else {
             let mut parent: PathBuf = (&output).into();
             relative_path.set_extension("");
@@ -339,6 +434,9 @@ fn capture_multiple_swfs(mut descriptors: Descriptors, opt: &Opt) -> Result<(),
                 destination.push(format!("{}.png", frame));
                 image.save(&destination)?;
             }
+            if let Some(audio) = &capture.audio {
+                write_wav(&parent.join("audio.wav"), audio)?;
+            }
         }
     }
 
@@ -0,0 +1,72 @@
+use std::string::FromUtf8Error;
+use thiserror::Error;
+
+/// An error from fetching or loading a resource.
+///
+/// This is shared by navigator backends and the loaders in `ruffle_core`.
+/// Backends generally only need `FetchError`, `NetworkError` and
+/// `NetworkUnavailable`.
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Load cancelled")]
+    Cancelled,
+
+    #[error("Non-root-movie loader spawned as root movie loader")]
+    NotRootMovieLoader,
+
+    #[error("Non-movie loader spawned as movie loader")]
+    NotMovieLoader,
+
+    #[error("Non-form loader spawned as form loader")]
+    NotFormLoader,
+
+    #[error("Non-load vars loader spawned as load vars loader")]
+    NotLoadVarsLoader,
+
+    #[error("Non-XML loader spawned as XML loader")]
+    NotXmlLoader,
+
+    #[error("Non-URLLoader loader spawned as URLLoader loader")]
+    NotUrlLoader,
+
+    #[error("Non-URLStream loader spawned as URLStream loader")]
+    NotUrlStream,
+
+    #[error("Non-NetConnection call loader spawned as NetConnection call loader")]
+    NotNetConnectionCall,
+
+    #[error("Non-Loader loader spawned as Loader loader")]
+    NotAvm2Loader,
+
+    #[error("Could not fetch movie {0}")]
+    FetchError(String),
+
+    #[error("Security sandbox violation: {0}")]
+    SecurityError(String),
+
+    #[error("Invalid SWF")]
+    InvalidSwf(#[from] Box<dyn std::error::Error>),
+
+    #[error("Invalid image: {0}")]
+    InvalidImage(String),
+
+    #[error("Invalid bundle: {0}")]
+    InvalidBundle(String),
+
+    #[error("Invalid XML encoding")]
+    InvalidXmlEncoding(#[from] FromUtf8Error),
+
+    #[error("Network error")]
+    NetworkError(#[from] std::io::Error),
+
+    #[error("Network unavailable.")]
+    NetworkUnavailable,
+
+    // TODO: We can't support lifetimes on this error object yet (or we'll need some backends inside
+    // the GC arena). We're losing info here. How do we fix that?
+    #[error("Error running avm1 script: {0}")]
+    Avm1Error(String),
+
+    #[error("Error running avm2 script: {0}")]
+    Avm2Error(String),
+}
@@ -0,0 +1,419 @@
+pub mod context3d;
+
+use crate::backend::context3d::Context3D;
+use crate::matrix::Matrix;
+pub use crate::quality::StageQuality;
+use crate::shape_utils::DistilledShape;
+pub use crate::transform::Transform;
+use downcast_rs::{impl_downcast, Downcast};
+pub use swf;
+pub use swf::Color;
+
+pub trait RenderBackend: Downcast {
+    fn set_viewport_dimensions(&mut self, width: u32, height: u32);
+    fn register_shape(
+        &mut self,
+        shape: DistilledShape,
+        bitmap_source: &dyn BitmapSource,
+    ) -> ShapeHandle;
+    fn replace_shape(
+        &mut self,
+        shape: DistilledShape,
+        bitmap_source: &dyn BitmapSource,
+        handle: ShapeHandle,
+    );
+    fn register_glyph_shape(&mut self, shape: &swf::Glyph) -> ShapeHandle;
+    fn register_bitmap_jpeg(
+        &mut self,
+        data: &[u8],
+        jpeg_tables: Option<&[u8]>,
+    ) -> Result<BitmapInfo, Error>;
+    fn register_bitmap_jpeg_2(&mut self, data: &[u8]) -> Result<BitmapInfo, Error>;
+    fn register_bitmap_jpeg_3(
+        &mut self,
+        jpeg_data: &[u8],
+        alpha_data: &[u8],
+    ) -> Result<BitmapInfo, Error>;
+    fn register_bitmap_png(
+        &mut self,
+        swf_tag: &swf::DefineBitsLossless,
+    ) -> Result<BitmapInfo, Error>;
+
+    /// Sets the stage quality used for the frames that follow.
+    ///
+    /// This controls the amount of anti-aliasing. Backends that can't change
+    /// it at runtime ignore it.
+    fn set_quality(&mut self, _quality: StageQuality) {}
+
+    fn begin_frame(&mut self, clear: Color);
+    fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform, smoothing: bool);
+    fn render_shape(&mut self, shape: ShapeHandle, transform: &Transform);
+    fn draw_rect(&mut self, color: Color, matrix: &Matrix);
+    fn end_frame(&mut self);
+    fn push_mask(&mut self);
+    fn activate_mask(&mut self);
+    fn deactivate_mask(&mut self);
+    fn pop_mask(&mut self);
+
+    fn get_bitmap_pixels(&mut self, bitmap: BitmapHandle) -> Option<Bitmap>;
+    fn register_bitmap_raw(
+        &mut self,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<BitmapHandle, Error>;
+    fn update_texture(
+        &mut self,
+        bitmap: BitmapHandle,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<BitmapHandle, Error>;
+
+    /// Whether this backend can upload YUV video frames with
+    /// `register_bitmap_yuv420` and `update_texture_yuv420`.
+    ///
+    /// Video backends convert frames to RGBA themselves for backends that
+    /// can't.
+    fn supports_yuv420(&self) -> bool {
+        false
+    }
+
+    /// Register a bitmap holding a YUV video frame, converted to RGBA by the
+    /// backend.
+    fn register_bitmap_yuv420(&mut self, _frame: Yuv420Frame<'_>) -> Result<BitmapHandle, Error> {
+        Err("YUV frames are not supported by this renderer".into())
+    }
+
+    /// Replace the contents of a bitmap with a YUV video frame of the same
+    /// size.
+    fn update_texture_yuv420(
+        &mut self,
+        _bitmap: BitmapHandle,
+        _frame: Yuv420Frame<'_>,
+    ) -> Result<BitmapHandle, Error> {
+        Err("YUV frames are not supported by this renderer".into())
+    }
+
+    /// Start drawing into a transparent offscreen bitmap of the given size,
+    /// instead of the frame. Draw calls go to the bitmap until
+    /// `end_offscreen` is called.
+    ///
+    /// Returns `false` if this backend can't draw offscreen, in which case
+    /// draw calls still go to the frame.
+    fn begin_offscreen(&mut self, _width: u32, _height: u32) -> bool {
+        false
+    }
+
+    /// Stop drawing offscreen, and read back what was drawn as premultiplied
+    /// RGBA.
+    fn end_offscreen(&mut self) -> Option<Bitmap> {
+        None
+    }
+
+    /// Start drawing a display object that's blended onto what's behind it
+    /// with the given blend mode. Everything drawn until the matching
+    /// `pop_blend_mode` is grouped together, then blended as one.
+    ///
+    /// Backends that can't blend draw everything normally.
+    fn push_blend_mode(&mut self, _blend_mode: swf::BlendMode) {}
+
+    /// Blend everything drawn since the matching `push_blend_mode` onto
+    /// what's behind it.
+    fn pop_blend_mode(&mut self) {}
+
+    /// Start drawing content that's masked by the alpha of its mask, rather
+    /// than by the mask's shape. The masked content is drawn first, then
+    /// `activate_alpha_mask` is called and the mask is drawn over it, and
+    /// `pop_alpha_mask` applies the mask.
+    ///
+    /// Returns `false` if the backend can't draw alpha masks right now, in
+    /// which case nothing changes and a regular mask should be used instead.
+    fn push_alpha_mask(&mut self) -> bool {
+        false
+    }
+
+    /// Stop drawing the masked content, and start drawing the alpha mask.
+    fn activate_alpha_mask(&mut self) {}
+
+    /// Draw the masked content through the alpha of its mask.
+    fn pop_alpha_mask(&mut self) {}
+
+    /// Create a Stage3D context that draws with the same device as this
+    /// backend. Backends without Stage3D support return an error.
+    fn create_context3d(&mut self) -> Result<Box<dyn Context3D>, Error> {
+        Err("Stage3D is not supported by this renderer".into())
+    }
+
+    /// Draw what a Stage3D context last presented, at its own size.
+    fn render_context3d(&mut self, _context: &dyn Context3D, _transform: &Transform) {}
+}
+impl_downcast!(RenderBackend);
+
+type Error = Box<dyn std::error::Error>;
+
+#[derive(Copy, Clone, Debug)]
+pub struct ShapeHandle(pub usize);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct BitmapHandle(pub usize);
+
+/// Info returned by the `register_bitmap` methods.
+#[derive(Copy, Clone, Debug)]
+pub struct BitmapInfo {
+    pub handle: BitmapHandle,
+    pub width: u16,
+    pub height: u16,
+}
+
+/// An object that returns a bitmap given an ID.
+///
+/// This is used by render backends to get the bitmap used in a bitmap fill.
+/// For movie libraries, this will return the bitmap with the given character ID.
+pub trait BitmapSource {
+    fn bitmap(&self, id: u16) -> Option<BitmapInfo>;
+}
+
+pub struct NullBitmapSource;
+impl BitmapSource for NullBitmapSource {
+    fn bitmap(&self, _id: u16) -> Option<BitmapInfo> {
+        None
+    }
+}
+
+pub struct NullRenderer;
+
+impl NullRenderer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for NullRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RenderBackend for NullRenderer {
+    fn set_viewport_dimensions(&mut self, _width: u32, _height: u32) {}
+    fn register_shape(
+        &mut self,
+        _shape: DistilledShape,
+        _bitmap_source: &dyn BitmapSource,
+    ) -> ShapeHandle {
+        ShapeHandle(0)
+    }
+    fn replace_shape(
+        &mut self,
+        _shape: DistilledShape,
+        _bitmap_source: &dyn BitmapSource,
+        _handle: ShapeHandle,
+    ) {
+    }
+    fn register_glyph_shape(&mut self, _shape: &swf::Glyph) -> ShapeHandle {
+        ShapeHandle(0)
+    }
+    fn register_bitmap_jpeg(
+        &mut self,
+        _data: &[u8],
+        _jpeg_tables: Option<&[u8]>,
+    ) -> Result<BitmapInfo, Error> {
+        Ok(BitmapInfo {
+            handle: BitmapHandle(0),
+            width: 0,
+            height: 0,
+        })
+    }
+    fn register_bitmap_jpeg_2(&mut self, _data: &[u8]) -> Result<BitmapInfo, Error> {
+        Ok(BitmapInfo {
+            handle: BitmapHandle(0),
+            width: 0,
+            height: 0,
+        })
+    }
+    fn register_bitmap_jpeg_3(
+        &mut self,
+        _data: &[u8],
+        _alpha_data: &[u8],
+    ) -> Result<BitmapInfo, Error> {
+        Ok(BitmapInfo {
+            handle: BitmapHandle(0),
+            width: 0,
+            height: 0,
+        })
+    }
+    fn register_bitmap_png(
+        &mut self,
+        _swf_tag: &swf::DefineBitsLossless,
+    ) -> Result<BitmapInfo, Error> {
+        Ok(BitmapInfo {
+            handle: BitmapHandle(0),
+            width: 0,
+            height: 0,
+        })
+    }
+    fn begin_frame(&mut self, _clear: Color) {}
+    fn end_frame(&mut self) {}
+    fn render_bitmap(&mut self, _bitmap: BitmapHandle, _transform: &Transform, _smoothing: bool) {}
+    fn render_shape(&mut self, _shape: ShapeHandle, _transform: &Transform) {}
+    fn draw_rect(&mut self, _color: Color, _matrix: &Matrix) {}
+    fn push_mask(&mut self) {}
+    fn activate_mask(&mut self) {}
+    fn deactivate_mask(&mut self) {}
+    fn pop_mask(&mut self) {}
+
+    fn get_bitmap_pixels(&mut self, _bitmap: BitmapHandle) -> Option<Bitmap> {
+        None
+    }
+    fn register_bitmap_raw(
+        &mut self,
+        _width: u32,
+        _height: u32,
+        _rgba: Vec<u8>,
+    ) -> Result<BitmapHandle, Error> {
+        Ok(BitmapHandle(0))
+    }
+
+    fn update_texture(
+        &mut self,
+        _bitmap: BitmapHandle,
+        _width: u32,
+        _height: u32,
+        _rgba: Vec<u8>,
+    ) -> Result<BitmapHandle, Error> {
+        Ok(BitmapHandle(0))
+    }
+}
+
+/// A decoded video frame in planar YUV 4:2:0 format, with BT.601
+/// limited-range samples.
+///
+/// The planes are borrowed from the decoder, so that backends which convert
+/// on the GPU can upload them without an intermediate copy.
+#[derive(Debug, Copy, Clone)]
+pub struct Yuv420Frame<'a> {
+    pub width: u32,
+    pub height: u32,
+
+    /// Luma samples, `width` per row.
+    pub y: &'a [u8],
+
+    /// Blue-difference chroma samples, `chroma_width` per row.
+    pub u: &'a [u8],
+
+    /// Red-difference chroma samples, `chroma_width` per row.
+    pub v: &'a [u8],
+
+    /// The number of chroma samples in each row, which may include padding
+    /// beyond the `(width + 1) / 2` samples that are visible.
+    pub chroma_width: u32,
+}
+
+impl Yuv420Frame<'_> {
+    /// The number of rows in each chroma plane, which may also include
+    /// padding.
+    pub fn chroma_height(&self) -> u32 {
+        match self.chroma_width {
+            0 => 0,
+            chroma_width => self.u.len() as u32 / chroma_width,
+        }
+    }
+}
+
+/// Decoded bitmap data from an SWF tag.
+#[derive(Debug, Clone)]
+pub struct Bitmap {
+    pub width: u32,
+    pub height: u32,
+    pub data: BitmapFormat,
+}
+
+/// Decoded bitmap data from an SWF tag.
+/// The image data will have pre-multiplied alpha.
+#[derive(Debug, Clone)]
+pub enum BitmapFormat {
+    Rgb(Vec<u8>),
+    Rgba(Vec<u8>),
+}
+
+impl From<BitmapFormat> for Vec<i32> {
+    fn from(format: BitmapFormat) -> Self {
+        match format {
+            BitmapFormat::Rgb(x) => x
+                .chunks_exact(3)
+                .map(|chunk| {
+                    let red = chunk[0];
+                    let green = chunk[1];
+                    let blue = chunk[2];
+                    i32::from_le_bytes([blue, green, red, 0xFF])
+                })
+                .collect(),
+            BitmapFormat::Rgba(x) => x
+                .chunks_exact(4)
+                .map(|chunk| {
+                    let red = chunk[0];
+                    let green = chunk[1];
+                    let blue = chunk[2];
+                    let alpha = chunk[3];
+                    i32::from_le_bytes([blue, green, red, alpha])
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Images in SWFs are stored with premultiplied alpha.
+/// Converts RGBA premultiplied alpha to standard RBGA.
+pub fn unmultiply_alpha_rgba(rgba: &mut [u8]) {
+    rgba.chunks_exact_mut(4).for_each(|rgba| {
+        if rgba[3] > 0 {
+            let a = f32::from(rgba[3]) / 255.0;
+            rgba[0] = f32::min(f32::from(rgba[0]) / a, 255.0) as u8;
+            rgba[1] = f32::min(f32::from(rgba[1]) / a, 255.0) as u8;
+            rgba[2] = f32::min(f32::from(rgba[2]) / a, 255.0) as u8;
+        }
+    })
+}
+
+/// Converts an RGBA color from sRGB space to linear color space.
+pub fn srgb_to_linear(color: [f32; 4]) -> [f32; 4] {
+    fn to_linear_channel(n: f32) -> f32 {
+        if n <= 0.04045 {
+            n / 12.92
+        } else {
+            f32::powf((n + 0.055) / 1.055, 2.4)
+        }
+    }
+    [
+        to_linear_channel(color[0]),
+        to_linear_channel(color[1]),
+        to_linear_channel(color[2]),
+        color[3],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chroma_height_includes_padding_rows() {
+        let planes = [0; 12];
+        let frame = Yuv420Frame {
+            width: 5,
+            height: 5,
+            y: &planes,
+            u: &planes,
+            v: &planes,
+            chroma_width: 4,
+        };
+        assert_eq!(frame.chroma_height(), 3);
+
+        let frame = Yuv420Frame {
+            chroma_width: 0,
+            ..frame
+        };
+        assert_eq!(frame.chroma_height(), 0);
+    }
+}
@@ -0,0 +1,380 @@
+//! The interface between Stage3D and rendering backends.
+//!
+//! A `Context3D` is created by a `RenderBackend`, and draws into a back buffer
+//! of its own. Whatever it last presented is drawn by the backend underneath
+//! the display list with `RenderBackend::render_context3d`.
+
+pub mod atf;
+
+use downcast_rs::{impl_downcast, Downcast};
+
+type Error = Box<dyn std::error::Error>;
+
+/// The number of vertex attributes that a program can read.
+pub const MAX_VERTEX_ATTRIBUTES: usize = 8;
+
+/// The number of textures that a program can sample.
+pub const MAX_SAMPLERS: usize = 8;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct VertexBuffer3DHandle(pub usize);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct IndexBuffer3DHandle(pub usize);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Program3DHandle(pub usize);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Texture3DHandle(pub usize);
+
+/// How the data of a vertex attribute is laid out in its vertex buffer.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Context3DVertexBufferFormat {
+    Float1,
+    Float2,
+    Float3,
+    Float4,
+    /// Four bytes, each read as a number from 0 to 1.
+    Bytes4,
+}
+
+impl Context3DVertexBufferFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "float1" => Self::Float1,
+            "float2" => Self::Float2,
+            "float3" => Self::Float3,
+            "float4" => Self::Float4,
+            "bytes4" => Self::Bytes4,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Context3DProgramType {
+    Vertex,
+    Fragment,
+}
+
+impl Context3DProgramType {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "vertex" => Self::Vertex,
+            "fragment" => Self::Fragment,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Context3DBlendFactor {
+    DestinationAlpha,
+    DestinationColor,
+    One,
+    OneMinusDestinationAlpha,
+    OneMinusDestinationColor,
+    OneMinusSourceAlpha,
+    OneMinusSourceColor,
+    SourceAlpha,
+    SourceColor,
+    Zero,
+}
+
+impl Context3DBlendFactor {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "destinationAlpha" => Self::DestinationAlpha,
+            "destinationColor" => Self::DestinationColor,
+            "one" => Self::One,
+            "oneMinusDestinationAlpha" => Self::OneMinusDestinationAlpha,
+            "oneMinusDestinationColor" => Self::OneMinusDestinationColor,
+            "oneMinusSourceAlpha" => Self::OneMinusSourceAlpha,
+            "oneMinusSourceColor" => Self::OneMinusSourceColor,
+            "sourceAlpha" => Self::SourceAlpha,
+            "sourceColor" => Self::SourceColor,
+            "zero" => Self::Zero,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Context3DCompareMode {
+    Always,
+    Equal,
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+    Never,
+    NotEqual,
+}
+
+impl Context3DCompareMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "always" => Self::Always,
+            "equal" => Self::Equal,
+            "greater" => Self::Greater,
+            "greaterEqual" => Self::GreaterEqual,
+            "less" => Self::Less,
+            "lessEqual" => Self::LessEqual,
+            "never" => Self::Never,
+            "notEqual" => Self::NotEqual,
+            _ => return None,
+        })
+    }
+}
+
+/// Which faces of triangles are culled.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Context3DTriangleFace {
+    Back,
+    Front,
+    FrontAndBack,
+    None,
+}
+
+impl Context3DTriangleFace {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "back" => Self::Back,
+            "front" => Self::Front,
+            "frontAndBack" => Self::FrontAndBack,
+            "none" => Self::None,
+            _ => return None,
+        })
+    }
+}
+
+/// What happens to a value in the stencil buffer when it's drawn over.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Context3DStencilAction {
+    DecrementSaturate,
+    DecrementWrap,
+    IncrementSaturate,
+    IncrementWrap,
+    Invert,
+    Keep,
+    Set,
+    Zero,
+}
+
+impl Context3DStencilAction {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "decrementSaturate" => Self::DecrementSaturate,
+            "decrementWrap" => Self::DecrementWrap,
+            "incrementSaturate" => Self::IncrementSaturate,
+            "incrementWrap" => Self::IncrementWrap,
+            "invert" => Self::Invert,
+            "keep" => Self::Keep,
+            "set" => Self::Set,
+            "zero" => Self::Zero,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Context3DTextureFormat {
+    Bgra,
+    BgraPacked,
+    BgrPacked,
+    Compressed,
+    CompressedAlpha,
+    RgbaHalfFloat,
+}
+
+impl Context3DTextureFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "bgra" => Self::Bgra,
+            "bgraPacked4444" => Self::BgraPacked,
+            "bgrPacked565" => Self::BgrPacked,
+            "compressed" => Self::Compressed,
+            "compressedAlpha" => Self::CompressedAlpha,
+            "rgbaHalfFloat" => Self::RgbaHalfFloat,
+            _ => return None,
+        })
+    }
+}
+
+/// The blend factors that what's drawn is blended with, as given to
+/// `Context3D.setBlendFactors`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct BlendFactors {
+    pub source: Context3DBlendFactor,
+    pub destination: Context3DBlendFactor,
+}
+
+impl Default for BlendFactors {
+    fn default() -> Self {
+        Self {
+            source: Context3DBlendFactor::One,
+            destination: Context3DBlendFactor::Zero,
+        }
+    }
+}
+
+/// How the stencil buffer is tested and updated, as given to
+/// `Context3D.setStencilActions`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct StencilActions {
+    /// The faces of triangles that the actions apply to.
+    pub triangle_face: Context3DTriangleFace,
+
+    /// How the reference value is compared with the stencil buffer.
+    pub compare_mode: Context3DCompareMode,
+
+    pub action_on_both_pass: Context3DStencilAction,
+    pub action_on_depth_fail: Context3DStencilAction,
+    pub action_on_depth_pass_stencil_fail: Context3DStencilAction,
+}
+
+impl Default for StencilActions {
+    fn default() -> Self {
+        Self {
+            triangle_face: Context3DTriangleFace::FrontAndBack,
+            compare_mode: Context3DCompareMode::Always,
+            action_on_both_pass: Context3DStencilAction::Keep,
+            action_on_depth_fail: Context3DStencilAction::Keep,
+            action_on_depth_pass_stencil_fail: Context3DStencilAction::Keep,
+        }
+    }
+}
+
+/// A Stage3D rendering context.
+///
+/// Resources are referred to by handles given out by the context. Draw calls
+/// go into the back buffer, which is shown once it's presented.
+pub trait Context3D: Downcast {
+    /// The `driverInfo` reported to ActionScript.
+    fn driver_info(&self) -> String;
+
+    /// Resize the back buffer, which clears it. The back buffer is
+    /// multisampled if `anti_alias` is above zero.
+    fn configure_back_buffer(
+        &mut self,
+        width: u32,
+        height: u32,
+        anti_alias: u32,
+        enable_depth_and_stencil: bool,
+    );
+
+    /// Clear the parts of what's being drawn into that are selected by
+    /// `mask`, a combination of the `Context3DClearMask` flags.
+    fn clear(&mut self, color: [f32; 4], depth: f32, stencil: u32, mask: u32);
+
+    fn create_vertex_buffer(
+        &mut self,
+        num_vertices: u32,
+        data32_per_vertex: u32,
+    ) -> VertexBuffer3DHandle;
+
+    /// Replace vertices from `start_vertex` onwards with `data`, which holds
+    /// whole vertices of 32-bit little-endian values.
+    fn upload_vertex_buffer(
+        &mut self,
+        buffer: VertexBuffer3DHandle,
+        start_vertex: u32,
+        data: &[u8],
+    );
+
+    fn create_index_buffer(&mut self, num_indices: u32) -> IndexBuffer3DHandle;
+
+    /// Replace indices from `start_offset` onwards.
+    fn upload_index_buffer(&mut self, buffer: IndexBuffer3DHandle, start_offset: u32, data: &[u16]);
+
+    fn create_program(&mut self) -> Program3DHandle;
+
+    /// Compile a program from AGAL bytecode.
+    fn upload_program(
+        &mut self,
+        program: Program3DHandle,
+        vertex_program: &[u8],
+        fragment_program: &[u8],
+    ) -> Result<(), Error>;
+
+    fn create_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: Context3DTextureFormat,
+        optimize_for_render_to_texture: bool,
+    ) -> Result<Texture3DHandle, Error>;
+
+    /// Replace a mip level of a texture with RGBA pixels.
+    fn upload_texture(&mut self, texture: Texture3DHandle, mip_level: u32, rgba: &[u8]);
+
+    fn dispose_vertex_buffer(&mut self, buffer: VertexBuffer3DHandle);
+    fn dispose_index_buffer(&mut self, buffer: IndexBuffer3DHandle);
+    fn dispose_program(&mut self, program: Program3DHandle);
+    fn dispose_texture(&mut self, texture: Texture3DHandle);
+
+    fn set_program(&mut self, program: Option<Program3DHandle>);
+
+    /// Set where a vertex attribute is read from, as an offset in 32-bit
+    /// values into each vertex of a buffer.
+    fn set_vertex_buffer_at(
+        &mut self,
+        index: usize,
+        buffer: Option<(VertexBuffer3DHandle, u32, Context3DVertexBufferFormat)>,
+    );
+
+    /// Set constant registers of a program type, four values per register.
+    fn set_program_constants(
+        &mut self,
+        program_type: Context3DProgramType,
+        first_register: u32,
+        values: &[f32],
+    );
+
+    fn set_texture_at(&mut self, sampler: usize, texture: Option<Texture3DHandle>);
+
+    fn set_blend_factors(&mut self, blend_factors: BlendFactors);
+
+    fn set_culling(&mut self, face: Context3DTriangleFace);
+
+    fn set_depth_test(&mut self, depth_mask: bool, pass_compare_mode: Context3DCompareMode);
+
+    fn set_color_mask(&mut self, red: bool, green: bool, blue: bool, alpha: bool);
+
+    /// Set how the stencil buffer is tested and updated by draws.
+    fn set_stencil_actions(&mut self, actions: StencilActions);
+
+    /// Set the value that the stencil buffer is compared with, and which of
+    /// its bits are read and written.
+    fn set_stencil_reference_value(&mut self, reference: u32, read_mask: u32, write_mask: u32);
+
+    /// Draw into a texture instead of the back buffer, until the back buffer
+    /// is drawn into again.
+    fn set_render_to_texture(
+        &mut self,
+        texture: Texture3DHandle,
+        enable_depth_and_stencil: bool,
+        anti_alias: u32,
+    ) -> Result<(), Error>;
+
+    fn set_render_to_back_buffer(&mut self);
+
+    /// Limit drawing to a rectangle of what's drawn into, given in pixels as
+    /// `(x, y, width, height)`.
+    fn set_scissor_rectangle(&mut self, rectangle: Option<(u32, u32, u32, u32)>);
+
+    /// Draw triangles with the current program, starting at `first_index` in
+    /// the index buffer. All of the remaining indices are drawn if no number
+    /// of triangles is given.
+    fn draw_triangles(
+        &mut self,
+        index_buffer: IndexBuffer3DHandle,
+        first_index: u32,
+        num_triangles: Option<u32>,
+    ) -> Result<(), Error>;
+
+    /// Show what's been drawn into the back buffer.
+    fn present(&mut self);
+}
+impl_downcast!(Context3D);
@@ -0,0 +1,293 @@
+//! Decoding of ATF, the texture format that compressed Stage3D textures are
+//! uploaded in.
+//!
+//! An ATF file holds each mip level of a texture in several block compressed
+//! formats, one for each kind of GPU. Only the DXT data is read, and it's
+//! decoded to RGBA so that backends don't need to support block compression.
+
+use super::Context3DTextureFormat;
+
+type Error = Box<dyn std::error::Error>;
+
+/// The ATF formats that hold block compressed data without any further
+/// compression.
+const FORMAT_RAW_COMPRESSED: u8 = 3;
+const FORMAT_RAW_COMPRESSED_ALPHA: u8 = 5;
+
+/// A decoded ATF texture.
+#[derive(Clone, Debug)]
+pub struct AtfTexture {
+    /// `Compressed` for DXT1 data, or `CompressedAlpha` for DXT5 data.
+    pub format: Context3DTextureFormat,
+    pub width: u32,
+    pub height: u32,
+
+    /// The RGBA pixels of each mip level that the file holds, starting with
+    /// the largest.
+    pub mip_levels: Vec<Vec<u8>>,
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+
+    /// Whether lengths are 32-bit, rather than the 24-bit ones of the first
+    /// version of the format.
+    long_lengths: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() < len {
+            return Err("Unexpected end of ATF data".into());
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    /// Read a big-endian length.
+    fn read_length(&mut self) -> Result<usize, Error> {
+        let bytes = self.read_bytes(if self.long_lengths { 4 } else { 3 })?;
+        Ok(bytes
+            .iter()
+            .fold(0, |len, &byte| len << 8 | usize::from(byte)))
+    }
+
+    /// Read a block of data that's prefixed with its length.
+    fn read_block(&mut self) -> Result<&'a [u8], Error> {
+        let len = self.read_length()?;
+        self.read_bytes(len)
+    }
+}
+
+/// Decode an ATF file.
+pub fn decode(data: &[u8]) -> Result<AtfTexture, Error> {
+    if !data.starts_with(b"ATF") {
+        return Err("Not an ATF file".into());
+    }
+
+    // Files from the second version onwards mark their header with 0xFF, in
+    // place of the first version's format byte.
+    let (version, header_len) = match data.get(6) {
+        Some(0xff) => (*data.get(7).ok_or("Unexpected end of ATF data")?, 12),
+        _ => (0, 6),
+    };
+    let mut reader = Reader {
+        data: &data[header_len.min(data.len())..],
+        long_lengths: header_len == 12,
+    };
+
+    let format_byte = reader.read_u8()?;
+    if format_byte & 0x80 != 0 {
+        return Err("ATF cube maps are not supported".into());
+    }
+    let (format, other_blocks) = match format_byte & 0x7f {
+        // DXT1, followed by PVRTC and ETC1.
+        FORMAT_RAW_COMPRESSED => (Context3DTextureFormat::Compressed, 2),
+        // DXT5, followed by PVRTC and ETC1 for colour and alpha.
+        FORMAT_RAW_COMPRESSED_ALPHA => (Context3DTextureFormat::CompressedAlpha, 3),
+        format => return Err(format!("ATF format {} is not supported", format).into()),
+    };
+    // ETC2 data was added in the third version.
+    let other_blocks = if version >= 3 {
+        other_blocks + 1
+    } else {
+        other_blocks
+    };
+
+    let log2_width = reader.read_u8()?;
+    let log2_height = reader.read_u8()?;
+    if log2_width > 12 || log2_height > 12 {
+        return Err("ATF texture is too big".into());
+    }
+    let width = 1 << log2_width;
+    let height = 1 << log2_height;
+    let count = reader.read_u8()?;
+
+    let mut mip_levels = Vec::with_capacity(count.into());
+    for level in 0..u32::from(count).min(u32::from(log2_width.max(log2_height)) + 1) {
+        let dxt = reader.read_block()?;
+        for _ in 0..other_blocks {
+            reader.read_block()?;
+        }
+        if dxt.is_empty() {
+            return Err("ATF file has no DXT data".into());
+        }
+
+        let level_width = (width >> level).max(1);
+        let level_height = (height >> level).max(1);
+        mip_levels.push(decode_dxt(
+            dxt,
+            level_width,
+            level_height,
+            format == Context3DTextureFormat::CompressedAlpha,
+        )?);
+    }
+
+    Ok(AtfTexture {
+        format,
+        width,
+        height,
+        mip_levels,
+    })
+}
+
+/// Decode DXT1 data, or DXT5 data if `alpha` is set, to RGBA.
+pub fn decode_dxt(data: &[u8], width: u32, height: u32, alpha: bool) -> Result<Vec<u8>, Error> {
+    let (width, height) = (width as usize, height as usize);
+    let block_size = if alpha { 16 } else { 8 };
+    let blocks_wide = (width + 3) / 4;
+    let num_blocks = blocks_wide * ((height + 3) / 4);
+    if data.len() < num_blocks * block_size {
+        return Err("Not enough DXT data".into());
+    }
+
+    let mut rgba = vec![0; width * height * 4];
+    for (i, block) in data.chunks_exact(block_size).take(num_blocks).enumerate() {
+        let pixels = if alpha {
+            let mut pixels = decode_color_block(&block[8..], false);
+            for (pixel, alpha) in pixels.iter_mut().zip(&decode_alpha_block(&block[..8])) {
+                pixel[3] = *alpha;
+            }
+            pixels
+        } else {
+            decode_color_block(block, true)
+        };
+
+        let block_x = i % blocks_wide * 4;
+        let block_y = i / blocks_wide * 4;
+        for (j, pixel) in pixels.iter().enumerate() {
+            let x = block_x + j % 4;
+            let y = block_y + j / 4;
+            if x < width && y < height {
+                let offset = (y * width + x) * 4;
+                rgba[offset..offset + 4].copy_from_slice(pixel);
+            }
+        }
+    }
+    Ok(rgba)
+}
+
+fn rgb565(color: u16) -> [u16; 3] {
+    let r = color >> 11 & 0x1f;
+    let g = color >> 5 & 0x3f;
+    let b = color & 0x1f;
+    [r << 3 | r >> 2, g << 2 | g >> 4, b << 3 | b >> 2]
+}
+
+/// Decode the colours of a block of 4x4 pixels. DXT1 blocks can have
+/// transparent pixels, but the colour blocks of DXT5 can't.
+fn decode_color_block(block: &[u8], allow_transparent: bool) -> [[u8; 4]; 16] {
+    let color0 = u16::from_le_bytes([block[0], block[1]]);
+    let color1 = u16::from_le_bytes([block[2], block[3]]);
+    let a = rgb565(color0);
+    let b = rgb565(color1);
+    let mix = |weight_a: u16, weight_b: u16| {
+        let total = weight_a + weight_b;
+        let channel = |i: usize| ((a[i] * weight_a + b[i] * weight_b) / total) as u8;
+        [channel(0), channel(1), channel(2), 255]
+    };
+
+    let palette = if color0 > color1 || !allow_transparent {
+        [mix(1, 0), mix(0, 1), mix(2, 1), mix(1, 2)]
+    } else {
+        [mix(1, 0), mix(0, 1), mix(1, 1), [0, 0, 0, 0]]
+    };
+
+    let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+    let mut pixels = [[0; 4]; 16];
+    for (i, pixel) in pixels.iter_mut().enumerate() {
+        *pixel = palette[(indices >> (i * 2) & 3) as usize];
+    }
+    pixels
+}
+
+/// Decode the alpha values of a block of 4x4 pixels in DXT5 data.
+fn decode_alpha_block(block: &[u8]) -> [u8; 16] {
+    let alpha0 = u16::from(block[0]);
+    let alpha1 = u16::from(block[1]);
+    let mut palette = [0; 8];
+    palette[0] = alpha0 as u8;
+    palette[1] = alpha1 as u8;
+    if alpha0 > alpha1 {
+        for (i, alpha) in (2..).zip(&mut palette[2..8]) {
+            *alpha = (((8 - i) * alpha0 + (i - 1) * alpha1) / 7) as u8;
+        }
+    } else {
+        for (i, alpha) in (2..).zip(&mut palette[2..6]) {
+            *alpha = (((6 - i) * alpha0 + (i - 1) * alpha1) / 5) as u8;
+        }
+        palette[6] = 0;
+        palette[7] = 255;
+    }
+
+    let indices = block[2..8]
+        .iter()
+        .rev()
+        .fold(0u64, |indices, &byte| indices << 8 | u64::from(byte));
+    let mut alphas = [0; 16];
+    for (i, alpha) in alphas.iter_mut().enumerate() {
+        *alpha = palette[(indices >> (i * 3) & 7) as usize];
+    }
+    alphas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A DXT1 block with red and blue as its colours, with the top row red,
+    /// the second row blue and the other rows between the two.
+    const DXT1_BLOCK: [u8; 8] = [0x00, 0xf8, 0x1f, 0x00, 0x00, 0x55, 0xaa, 0xff];
+
+    #[test]
+    fn decode_dxt1() {
+        let rgba = decode_dxt(&DXT1_BLOCK, 4, 4, false).unwrap();
+        assert_eq!(&rgba[0..4], &[255, 0, 0, 255]);
+        assert_eq!(&rgba[16..20], &[0, 0, 255, 255]);
+        assert_eq!(&rgba[32..36], &[170, 0, 85, 255]);
+        assert_eq!(&rgba[48..52], &[85, 0, 170, 255]);
+    }
+
+    #[test]
+    fn decode_dxt5_alpha() {
+        let mut block = [0; 16];
+        block[0] = 255;
+        block[1] = 0;
+        // The first pixel uses the first alpha, and the second the second.
+        block[2] = 0b0000_1000;
+        block[8..].copy_from_slice(&DXT1_BLOCK);
+        let rgba = decode_dxt(&block, 4, 4, true).unwrap();
+        assert_eq!(rgba[3], 255);
+        assert_eq!(rgba[7], 0);
+    }
+
+    #[test]
+    fn decode_atf() {
+        let mut data = b"ATF\0\0\0\xff\x03".to_vec();
+        data.extend_from_slice(&0u32.to_be_bytes());
+        data.extend_from_slice(&[FORMAT_RAW_COMPRESSED, 2, 2, 1]);
+        data.extend_from_slice(&8u32.to_be_bytes());
+        data.extend_from_slice(&DXT1_BLOCK);
+        // Empty PVRTC, ETC1 and ETC2 data.
+        for _ in 0..3 {
+            data.extend_from_slice(&0u32.to_be_bytes());
+        }
+
+        let texture = decode(&data).unwrap();
+        assert_eq!(texture.format, Context3DTextureFormat::Compressed);
+        assert_eq!((texture.width, texture.height), (4, 4));
+        assert_eq!(texture.mip_levels.len(), 1);
+        assert_eq!(&texture.mip_levels[0][0..4], &[255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn reject_truncated_atf() {
+        assert!(decode(b"ATF\0\0\0\xff\x03\0\0\0\0\x03\x02\x02\x01").is_err());
+        assert!(decode(b"PNG").is_err());
+    }
+}
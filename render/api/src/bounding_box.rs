@@ -1,9 +1,7 @@
 use crate::matrix::Matrix;
-use gc_arena::Collect;
 use swf::Twips;
 
-#[derive(Clone, Debug, PartialEq, Collect, Default)]
-#[collect(require_static)]
+#[derive(Clone, Debug, PartialEq, Default)]
 pub struct BoundingBox {
     pub x_min: Twips,
     pub y_min: Twips,
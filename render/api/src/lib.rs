@@ -0,0 +1,16 @@
+//! The interface between Ruffle and its rendering backends.
+//!
+//! This crate holds the `RenderBackend` trait along with the geometry and
+//! bitmap types it uses, so that a renderer can be written without depending
+//! on the rest of `ruffle_core`. `ruffle_core` re-exports all of it under the
+//! same paths as before.
+
+pub mod backend;
+pub mod bounding_box;
+pub mod color_transform;
+pub mod matrix;
+pub mod quality;
+pub mod shape_utils;
+pub mod transform;
+
+pub use swf;
@@ -135,6 +135,47 @@ impl Matrix {
             ty: Twips::new(out_ty),
         };
     }
+
+    /// Transforms a point by the inverse of this matrix without rounding.
+    ///
+    /// Unlike inverting the matrix and multiplying, this keeps the translation
+    /// at full precision, so that nested transforms can be undone before
+    /// rounding to the nearest twip once at the end.
+    pub fn inverse_transform_point(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let (a, b, c, d) = (
+            f64::from(self.a),
+            f64::from(self.b),
+            f64::from(self.c),
+            f64::from(self.d),
+        );
+        let det = a * d - b * c;
+        if det == 0.0 {
+            return (0.0, 0.0);
+        }
+        let x = x - self.tx.get() as f64;
+        let y = y - self.ty.get() as f64;
+        ((d * x - c * y) / det, (a * y - b * x) / det)
+    }
+}
+
+/// Rounds a point in fractional twips to the nearest twip, using the same
+/// ties-to-even rule as `round_to_i32`.
+pub fn round_to_twips((x, y): (f64, f64)) -> (Twips, Twips) {
+    let round = |f: f64| {
+        if f.is_finite() {
+            let a = f.abs();
+            if a < 2_147_483_648.0_f64 {
+                let k = 1.0 / f64::EPSILON;
+                let out = if a < k { ((a + k) - k).copysign(f) } else { f };
+                Twips::new(out as i32)
+            } else {
+                Twips::new(i32::MIN)
+            }
+        } else {
+            Twips::new(0)
+        }
+    };
+    (round(x), round(y))
 }
 
 impl std::ops::Mul for Matrix {
@@ -753,6 +794,31 @@ mod tests {
             (Twips::new(141), Twips::ZERO)
         )
     );
+
+    #[test]
+    fn inverse_transform_point() {
+        let matrix = Matrix {
+            a: 2.0,
+            b: 0.0,
+            c: 0.0,
+            d: 2.0,
+            tx: Twips::new(15),
+            ty: Twips::new(-5),
+        };
+        assert_eq!(matrix.inverse_transform_point((16.0, -4.0)), (0.5, 0.5));
+        assert_eq!(
+            round_to_twips(matrix.inverse_transform_point((16.0, -4.0))),
+            (Twips::new(0), Twips::new(0))
+        );
+        assert_eq!(
+            round_to_twips(matrix.inverse_transform_point((18.0, -2.0))),
+            (Twips::new(2), Twips::new(2))
+        );
+        assert_eq!(
+            Matrix::scale(0.0, 0.0).inverse_transform_point((10.0, 10.0)),
+            (0.0, 0.0)
+        );
+    }
 }
 
 impl From<swf::Matrix> for Matrix {
@@ -0,0 +1,180 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// The quality setting of the `Stage`.
+///
+/// In the Flash Player, this settings affects anti-aliasing and bitmap smoothing.
+/// Render backends are told about the active setting through `RenderBackend::set_quality`.
+/// [StageQuality in the AS3 Reference](https://help.adobe.com/en_US/FlashPlatform/reference/actionscript/3/flash/display/StageQuality.html)
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StageQuality {
+    /// No anti-aliasing, and bitmaps are never smoothed.
+    Low,
+
+    /// 2x anti-aliasing.
+    Medium,
+
+    /// 4x anti-aliasing.
+    High,
+
+    /// 4x anti-aliasing with high quality downsampling.
+    /// Bitmaps will use high quality downsampling when scaled down.
+    /// Despite the name, this is not the best quality setting as 8x8 and 16x16 modes were added to
+    /// Flash Player 11.3.
+    Best,
+
+    /// 8x anti-aliasing.
+    /// Bitmaps will use high quality downsampling when scaled down.
+    High8x8,
+
+    /// 8x anti-aliasing done in linear sRGB space.
+    /// Bitmaps will use high quality downsampling when scaled down.
+    High8x8Linear,
+
+    /// 16x anti-aliasing.
+    /// Bitmaps will use high quality downsampling when scaled down.
+    High16x16,
+
+    /// 16x anti-aliasing done in linear sRGB space.
+    /// Bitmaps will use high quality downsampling when scaled down.
+    High16x16Linear,
+}
+
+impl StageQuality {
+    /// Returns the string representing the quality setting as returned by AVM1 `_quality` and
+    /// AVM2 `Stage.quality`.
+    pub fn into_avm_str(self) -> &'static str {
+        // Flash Player always returns quality in uppercase, despite the AVM2 `StageQuality` being
+        // lowercase.
+        match self {
+            StageQuality::Low => "LOW",
+            StageQuality::Medium => "MEDIUM",
+            StageQuality::High => "HIGH",
+            StageQuality::Best => "BEST",
+            // The linear sRGB quality settings are not returned even if they are active.
+            StageQuality::High8x8 | StageQuality::High8x8Linear => "8X8",
+            StageQuality::High16x16 | StageQuality::High16x16Linear => "16X16",
+        }
+    }
+
+    /// Returns the number of anti-aliasing samples per pixel for this quality.
+    ///
+    /// Backends may clamp this to whatever their hardware supports.
+    pub fn sample_count(self) -> u32 {
+        match self {
+            StageQuality::Low => 1,
+            StageQuality::Medium => 2,
+            StageQuality::High | StageQuality::Best => 4,
+            StageQuality::High8x8 | StageQuality::High8x8Linear => 8,
+            StageQuality::High16x16 | StageQuality::High16x16Linear => 16,
+        }
+    }
+
+    /// Returns whether bitmaps with smoothing enabled are actually smoothed.
+    ///
+    /// Flash Player ignores bitmap smoothing below `High` quality.
+    pub fn smooth_bitmaps(self) -> bool {
+        !matches!(self, StageQuality::Low | StageQuality::Medium)
+    }
+}
+
+impl Default for StageQuality {
+    fn default() -> StageQuality {
+        StageQuality::High
+    }
+}
+
+impl Display for StageQuality {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Match string values returned by AS.
+        let s = match *self {
+            StageQuality::Low => "low",
+            StageQuality::Medium => "medium",
+            StageQuality::High => "high",
+            StageQuality::Best => "best",
+            StageQuality::High8x8 => "8x8",
+            StageQuality::High8x8Linear => "8x8linear",
+            StageQuality::High16x16 => "16x16",
+            StageQuality::High16x16Linear => "16x16linear",
+        };
+        f.write_str(s)
+    }
+}
+
+/// The error returned when a string isn't a valid quality setting.
+#[derive(Debug)]
+pub struct ParseQualityError;
+
+impl FromStr for StageQuality {
+    type Err = ParseQualityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let quality = match s.to_ascii_lowercase().as_str() {
+            "low" => StageQuality::Low,
+            "medium" => StageQuality::Medium,
+            "high" => StageQuality::High,
+            "best" => StageQuality::Best,
+            "8x8" => StageQuality::High8x8,
+            "8x8linear" => StageQuality::High8x8Linear,
+            "16x16" => StageQuality::High16x16,
+            "16x16linear" => StageQuality::High16x16Linear,
+            _ => return Err(ParseQualityError),
+        };
+        Ok(quality)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [StageQuality; 8] = [
+        StageQuality::Low,
+        StageQuality::Medium,
+        StageQuality::High,
+        StageQuality::Best,
+        StageQuality::High8x8,
+        StageQuality::High8x8Linear,
+        StageQuality::High16x16,
+        StageQuality::High16x16Linear,
+    ];
+
+    #[test]
+    fn quality_changes_rendering() {
+        let expected = [
+            (1, false),
+            (2, false),
+            (4, true),
+            (4, true),
+            (8, true),
+            (8, true),
+            (16, true),
+            (16, true),
+        ];
+        for (quality, (sample_count, smooth_bitmaps)) in ALL.iter().zip(expected.iter()) {
+            assert_eq!(quality.sample_count(), *sample_count, "{}", quality);
+            assert_eq!(quality.smooth_bitmaps(), *smooth_bitmaps, "{}", quality);
+        }
+    }
+
+    #[test]
+    fn quality_strings_round_trip() {
+        for quality in ALL.iter() {
+            assert_eq!(
+                quality.to_string().parse::<StageQuality>().ok(),
+                Some(*quality)
+            );
+            assert_eq!(
+                quality.into_avm_str().parse::<StageQuality>().ok(),
+                Some(match quality {
+                    StageQuality::High8x8Linear => StageQuality::High8x8,
+                    StageQuality::High16x16Linear => StageQuality::High16x16,
+                    quality => *quality,
+                })
+            );
+        }
+        assert!("bogus".parse::<StageQuality>().is_err());
+        assert!("".parse::<StageQuality>().is_err());
+        assert_eq!(StageQuality::default(), StageQuality::High);
+    }
+}
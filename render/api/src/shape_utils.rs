@@ -73,9 +73,23 @@ pub enum DrawPath<'a> {
     Fill {
         style: &'a FillStyle,
         commands: Vec<DrawCommand>,
+        winding_rule: FillRule,
     },
 }
 
+/// How a fill decides which of the areas enclosed by its path are inside it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FillRule {
+    /// Areas that are enclosed an odd number of times are filled.
+    ///
+    /// Shapes from SWFs are always filled this way.
+    EvenOdd,
+
+    /// Areas that the path winds around more times in one direction than the
+    /// other are filled.
+    NonZero,
+}
+
 /// `DistilledShape` represents a ready-to-be-consumed collection of paths (both fills and strokes)
 /// that has been converted down from another source (such as SWF's `swf::Shape` format).
 #[derive(Debug, PartialEq, Clone)]
@@ -542,6 +556,7 @@ impl<'a> ShapeConverter<'a> {
             self.commands.push(DrawPath::Fill {
                 style,
                 commands: path.into_draw_commands().collect(),
+                winding_rule: FillRule::EvenOdd,
             });
         }
 
@@ -648,6 +663,7 @@ mod tests {
                     y: Twips::from_pixels(100.0),
                 },
             ],
+            winding_rule: FillRule::EvenOdd,
         }];
         assert_eq!(commands, expected);
     }
@@ -712,6 +728,7 @@ mod tests {
                     y: Twips::from_pixels(200.0),
                 },
             ],
+            winding_rule: FillRule::EvenOdd,
         }];
         assert_eq!(commands, expected);
     }
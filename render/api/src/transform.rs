@@ -1,33 +1,15 @@
-use crate::html::Position;
-use crate::prelude::*;
-use gc_arena::Collect;
+use crate::color_transform::ColorTransform;
+use crate::matrix::Matrix;
 
 /// Represents the transform for a DisplayObject.
 /// This includes both the transformation matrix and the color transform.
 ///
-#[derive(Clone, Collect, Debug, Default)]
-#[collect(require_static)]
+#[derive(Clone, Debug, Default)]
 pub struct Transform {
     pub matrix: Matrix,
     pub color_transform: ColorTransform,
 }
 
-impl From<Position<Twips>> for Transform {
-    fn from(pos: Position<Twips>) -> Self {
-        Self {
-            matrix: Matrix {
-                a: 1.0,
-                b: 0.0,
-                c: 0.0,
-                d: 1.0,
-                tx: pos.x(),
-                ty: pos.y(),
-            },
-            color_transform: Default::default(),
-        }
-    }
-}
-
 pub struct TransformStack(Vec<Transform>);
 
 impl TransformStack {
@@ -3,15 +3,16 @@ use ruffle_core::backend::render::{
     Bitmap, BitmapFormat, BitmapHandle, BitmapInfo, BitmapSource, Color, JpegTagFormat,
     NullBitmapSource, RenderBackend, ShapeHandle, Transform,
 };
+use ruffle_core::bitmap::bitmap_data::Color as PixelColor;
 use ruffle_core::color_transform::ColorTransform;
 use ruffle_core::matrix::Matrix;
-use ruffle_core::shape_utils::{DistilledShape, DrawCommand};
+use ruffle_core::shape_utils::{DistilledShape, DrawCommand, FillRule};
 use ruffle_web_common::JsResult;
 use std::convert::TryInto;
-use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen::{Clamped, JsCast, JsValue};
 use web_sys::{
-    CanvasGradient, CanvasPattern, CanvasRenderingContext2d, Element, HtmlCanvasElement,
-    HtmlImageElement, Path2d, SvgsvgElement,
+    CanvasGradient, CanvasPattern, CanvasRenderingContext2d, CanvasWindingRule, Element,
+    HtmlCanvasElement, HtmlImageElement, ImageData, Path2d, SvgsvgElement,
 };
 
 type Error = Box<dyn std::error::Error>;
@@ -30,6 +31,14 @@ pub struct WebCanvasRenderBackend {
     use_color_transform_hack: bool,
     pixelated_property_value: &'static str,
     deactivating_mask: bool,
+
+    /// The viewport size of the frame while drawing into an offscreen
+    /// canvas, which is restored when the offscreen drawing is done.
+    offscreen_frame_size: Option<(u32, u32)>,
+
+    /// The blend modes of the display objects being drawn into render
+    /// targets of their own, to be blended onto the target below when done.
+    blend_modes: Vec<swf::BlendMode>,
 }
 
 /// Canvas-drawable shape data extracted from an SWF file.
@@ -67,7 +76,7 @@ enum CanvasDrawCommand {
     Stroke {
         path: Path2d,
         line_width: f64,
-        stroke_style: CanvasColor,
+        stroke_style: CanvasFillStyle,
         line_cap: String,
         line_join: String,
         miter_limit: f64,
@@ -77,6 +86,7 @@ enum CanvasDrawCommand {
     Fill {
         path: Path2d,
         fill_style: CanvasFillStyle,
+        winding_rule: CanvasWindingRule,
     },
 
     /// A command to draw a particular image (such as an SVG)
@@ -212,6 +222,8 @@ impl WebCanvasRenderBackend {
             viewport_height: 0,
             use_color_transform_hack: is_firefox,
             deactivating_mask: false,
+            offscreen_frame_size: None,
+            blend_modes: vec![],
 
             // For rendering non-smoothed bitmaps.
             // crisp-edges works in Firefox, pixelated works in Chrome (and others)?
@@ -363,6 +375,44 @@ impl WebCanvasRenderBackend {
         self.context.set_global_alpha(1.0);
     }
 
+    /// Blend the pixels of a render target onto the current one, for the
+    /// blend modes that canvas composite operations can't do.
+    fn blend_pixels(&self, source: &CanvasRenderingContext2d, blend_mode: swf::BlendMode) {
+        let (width, height) = (self.viewport_width, self.viewport_height);
+        let (source, mut dest) = match (
+            source.get_image_data(0.0, 0.0, width.into(), height.into()),
+            self.context
+                .get_image_data(0.0, 0.0, width.into(), height.into()),
+        ) {
+            (Ok(source), Ok(dest)) => (source.data().0, dest.data().0),
+            _ => return,
+        };
+
+        // Canvas pixels aren't premultiplied, but the blend formulas expect
+        // them to be.
+        for (source, dest) in source.chunks_exact(4).zip(dest.chunks_exact_mut(4)) {
+            let source = PixelColor::argb(source[3], source[0], source[1], source[2])
+                .to_premultiplied_alpha(true);
+            let color = PixelColor::argb(dest[3], dest[0], dest[1], dest[2])
+                .to_premultiplied_alpha(true)
+                .blend(&source, blend_mode);
+            let color = if color.alpha() > 0 {
+                color.to_un_multiplied_alpha()
+            } else {
+                color
+            };
+            dest.copy_from_slice(&[color.red(), color.green(), color.blue(), color.alpha()]);
+        }
+
+        if let Ok(image_data) =
+            ImageData::new_with_u8_clamped_array_and_sh(Clamped(&dest[..]), width, height)
+        {
+            self.context
+                .put_image_data(&image_data, 0.0, 0.0)
+                .warn_on_error();
+        }
+    }
+
     fn register_bitmap_pure_jpeg(&mut self, data: &[u8]) -> Result<BitmapInfo, Error> {
         let data = ruffle_core::backend::render::remove_invalid_jpeg_data(data);
         let mut decoder = jpeg_decoder::Decoder::new(&data[..]);
@@ -569,7 +619,11 @@ impl RenderBackend for WebCanvasRenderBackend {
         if let Some(shape) = self.shapes.get(shape.0) {
             for command in shape.0.iter() {
                 match command {
-                    CanvasDrawCommand::Fill { path, fill_style } => {
+                    CanvasDrawCommand::Fill {
+                        path,
+                        fill_style,
+                        winding_rule,
+                    } => {
                         let xformed_fill_style =
                             fill_style.color_transform(&transform.color_transform);
                         if xformed_fill_style.is_none() {
@@ -584,7 +638,8 @@ impl RenderBackend for WebCanvasRenderBackend {
                             CanvasFillStyle::Pattern(patt) => self.context.set_fill_style(patt),
                         };
 
-                        self.context.fill_with_path_2d(path);
+                        self.context
+                            .fill_with_path_2d_and_winding(path, *winding_rule);
 
                         if xformed_fill_style.is_none() {
                             self.clear_color_filter();
@@ -600,13 +655,26 @@ impl RenderBackend for WebCanvasRenderBackend {
                     } => {
                         let xformed_stroke_style =
                             stroke_style.color_transform(&transform.color_transform);
+                        if xformed_stroke_style.is_none() {
+                            self.set_color_filter(transform);
+                        }
+
                         self.context.set_line_width(*line_width);
                         self.context.set_line_cap(line_cap);
                         self.context.set_line_join(line_join);
                         self.context.set_miter_limit(*miter_limit);
-                        self.context
-                            .set_stroke_style(&JsValue::from_str(&xformed_stroke_style.0));
+                        match xformed_stroke_style.as_ref().unwrap_or(stroke_style) {
+                            CanvasFillStyle::Color(CanvasColor(color, ..)) => {
+                                self.context.set_stroke_style(&JsValue::from_str(color))
+                            }
+                            CanvasFillStyle::Gradient(grad) => self.context.set_stroke_style(grad),
+                            CanvasFillStyle::Pattern(patt) => self.context.set_stroke_style(patt),
+                        };
                         self.context.stroke_with_path(path);
+
+                        if xformed_stroke_style.is_none() {
+                            self.clear_color_filter();
+                        }
                     }
                     CanvasDrawCommand::DrawImage {
                         image,
@@ -647,6 +715,49 @@ impl RenderBackend for WebCanvasRenderBackend {
         self.clear_color_filter();
     }
 
+    fn begin_offscreen(&mut self, width: u32, height: u32) -> bool {
+        if self.offscreen_frame_size.is_some() || width == 0 || height == 0 {
+            return false;
+        }
+
+        // Render targets are the size of the viewport, so the offscreen canvas
+        // and any masks drawn into it get the size of the bitmap.
+        self.offscreen_frame_size = Some((self.viewport_width, self.viewport_height));
+        self.viewport_width = width;
+        self.viewport_height = height;
+        self.push_render_target();
+        self.context.reset_transform().warn_on_error();
+        true
+    }
+
+    fn end_offscreen(&mut self) -> Option<Bitmap> {
+        let (frame_width, frame_height) = self.offscreen_frame_size.take()?;
+        let (width, height) = (self.viewport_width, self.viewport_height);
+        self.viewport_width = frame_width;
+        self.viewport_height = frame_height;
+
+        let (_canvas, context) = self.pop_render_target();
+        context.reset_transform().warn_on_error();
+        let image_data = context
+            .get_image_data(0.0, 0.0, width.into(), height.into())
+            .ok()?;
+
+        // Canvas pixels aren't premultiplied.
+        let mut data = image_data.data().to_vec();
+        for pixel in data.chunks_exact_mut(4) {
+            let alpha = u16::from(pixel[3]);
+            for channel in &mut pixel[..3] {
+                *channel = (u16::from(*channel) * alpha / 255) as u8;
+            }
+        }
+
+        Some(Bitmap {
+            width,
+            height,
+            data: BitmapFormat::Rgba(data),
+        })
+    }
+
     fn push_mask(&mut self) {
         // In the canvas backend, masks are implemented using two render targets.
         // We render the masker clips to the first render target.
@@ -702,6 +813,54 @@ impl RenderBackend for WebCanvasRenderBackend {
             .unwrap();
     }
 
+    fn push_blend_mode(&mut self, blend_mode: swf::BlendMode) {
+        // Objects with blend modes are drawn into a render target of their
+        // own, which is blended onto the one below it when they're done.
+        self.blend_modes.push(blend_mode);
+        self.push_render_target();
+    }
+
+    fn pop_blend_mode(&mut self) {
+        let blend_mode = match self.blend_modes.pop() {
+            Some(blend_mode) => blend_mode,
+            None => return,
+        };
+        let (canvas, context) = self.pop_render_target();
+        context.reset_transform().warn_on_error();
+        self.context.reset_transform().warn_on_error();
+
+        if let Some(operation) = composite_operation(blend_mode) {
+            self.context
+                .set_global_composite_operation(operation)
+                .warn_on_error();
+            self.context
+                .draw_image_with_html_canvas_element(&canvas, 0.0, 0.0)
+                .warn_on_error();
+            self.context
+                .set_global_composite_operation("source-over")
+                .warn_on_error();
+        } else {
+            self.blend_pixels(&context, blend_mode);
+        }
+    }
+
+    fn push_alpha_mask(&mut self) -> bool {
+        // The masked content is grouped in a layer, and the mask is drawn
+        // over it with the alpha blend mode, which keeps the content only
+        // as much as the mask is opaque.
+        self.push_blend_mode(swf::BlendMode::Layer);
+        true
+    }
+
+    fn activate_alpha_mask(&mut self) {
+        self.push_blend_mode(swf::BlendMode::Alpha);
+    }
+
+    fn pop_alpha_mask(&mut self) {
+        self.pop_blend_mode();
+        self.pop_blend_mode();
+    }
+
     fn get_bitmap_pixels(&mut self, bitmap: BitmapHandle) -> Option<Bitmap> {
         let window = web_sys::window().unwrap();
         let document = window.document().unwrap();
@@ -784,6 +943,26 @@ impl RenderBackend for WebCanvasRenderBackend {
 }
 
 #[allow(clippy::cognitive_complexity)]
+/// The canvas composite operation that blends like the given blend mode, if
+/// there is one.
+fn composite_operation(blend_mode: swf::BlendMode) -> Option<&'static str> {
+    use swf::BlendMode;
+    Some(match blend_mode {
+        BlendMode::Normal | BlendMode::Layer => "source-over",
+        BlendMode::Multiply => "multiply",
+        BlendMode::Screen => "screen",
+        BlendMode::Lighten => "lighten",
+        BlendMode::Darken => "darken",
+        BlendMode::Difference => "difference",
+        BlendMode::Add => "lighter",
+        BlendMode::Alpha => "destination-in",
+        BlendMode::Erase => "destination-out",
+        BlendMode::Overlay => "overlay",
+        BlendMode::HardLight => "hard-light",
+        BlendMode::Subtract | BlendMode::Invert => return None,
+    })
+}
+
 fn swf_shape_to_svg(
     shape: DistilledShape,
     bitmap_source: &dyn BitmapSource,
@@ -840,7 +1019,11 @@ fn swf_shape_to_svg(
     let mut svg_paths = Vec::with_capacity(shape.paths.len());
     for path in shape.paths {
         match path {
-            DrawPath::Fill { style, commands } => {
+            DrawPath::Fill {
+                style,
+                commands,
+                winding_rule,
+            } => {
                 let mut svg_path = SvgPath::new();
 
                 let fill = match style {
@@ -1107,6 +1290,13 @@ fn swf_shape_to_svg(
                     }
                 };
                 svg_path = svg_path.set("fill", fill);
+                svg_path = svg_path.set(
+                    "fill-rule",
+                    match winding_rule {
+                        FillRule::EvenOdd => "evenodd",
+                        FillRule::NonZero => "nonzero",
+                    },
+                );
 
                 let mut data = Data::new();
                 for command in commands {
@@ -1304,9 +1494,66 @@ fn swf_shape_to_canvas_commands(
     bounds_viewbox_matrix.set_a(1.0 / 20.0);
     bounds_viewbox_matrix.set_d(1.0 / 20.0);
 
+    // Creates the pattern used to fill or stroke with a bitmap.
+    let bitmap_fill_style = |id: CharacterId,
+                             matrix: &swf::Matrix,
+                             is_smoothed: bool,
+                             is_repeating: bool|
+     -> Option<CanvasFillStyle> {
+        if let Some(bitmap) = bitmap_source
+            .bitmap(id)
+            .and_then(|bitmap| bitmaps.get(bitmap.handle.0))
+        {
+            let image = HtmlImageElement::new_with_width_and_height(bitmap.width, bitmap.height)
+                .expect("html image element");
+
+            if !is_smoothed {
+                //image = image.set("image-rendering", pixelated_property_value);
+            }
+
+            let repeat = if !is_repeating { "no-repeat" } else { "repeat" };
+
+            let bitmap_pattern = context
+                .create_pattern_with_html_image_element(&image, repeat)
+                .expect("pattern creation success")?;
+
+            // Set source below the pattern creation because otherwise the bitmap gets screwed up
+            // when cached? (Issue #412)
+            image.set_src(&bitmap.data);
+
+            let a = matrix;
+
+            let matrix = matrix_factory.create_svg_matrix();
+
+            matrix.set_a(a.a.to_f32());
+            matrix.set_b(a.b.to_f32());
+            matrix.set_c(a.c.to_f32());
+            matrix.set_d(a.d.to_f32());
+            matrix.set_e(a.tx.get() as f32);
+            matrix.set_f(a.ty.get() as f32);
+
+            bitmap_pattern.set_transform(&matrix);
+
+            Some(CanvasFillStyle::Pattern(bitmap_pattern))
+        } else {
+            log::error!("Couldn't fill shape with unknown bitmap {}", id);
+            Some(CanvasFillStyle::Color(CanvasColor(
+                "rgba(0,0,0,0)".to_string(),
+                0,
+                0,
+                0,
+                0,
+            )))
+        }
+    };
+
     for path in &shape.paths {
         match path {
-            DrawPath::Fill { style, commands } => {
+            DrawPath::Fill {
+                style,
+                commands,
+                winding_rule,
+            } => {
                 let fill_style = match style {
                     FillStyle::Color(Color { r, g, b, a }) => CanvasFillStyle::Color(CanvasColor(
                         format!("rgba({},{},{},{})", r, g, b, f32::from(*a) / 255.0),
@@ -1323,60 +1570,7 @@ fn swf_shape_to_canvas_commands(
                         matrix,
                         is_smoothed,
                         is_repeating,
-                    } => {
-                        if let Some(bitmap) = bitmap_source
-                            .bitmap(*id)
-                            .and_then(|bitmap| bitmaps.get(bitmap.handle.0))
-                        {
-                            let image = HtmlImageElement::new_with_width_and_height(
-                                bitmap.width,
-                                bitmap.height,
-                            )
-                            .expect("html image element");
-
-                            if !*is_smoothed {
-                                //image = image.set("image-rendering", pixelated_property_value);
-                            }
-
-                            let repeat = if !*is_repeating {
-                                "no-repeat"
-                            } else {
-                                "repeat"
-                            };
-
-                            let bitmap_pattern = context
-                                .create_pattern_with_html_image_element(&image, repeat)
-                                .expect("pattern creation success")?;
-
-                            // Set source below the pattern creation because otherwise the bitmap gets screwed up
-                            // when cached? (Issue #412)
-                            image.set_src(&bitmap.data);
-
-                            let a = *matrix;
-
-                            let matrix = matrix_factory.create_svg_matrix();
-
-                            matrix.set_a(a.a.to_f32());
-                            matrix.set_b(a.b.to_f32());
-                            matrix.set_c(a.c.to_f32());
-                            matrix.set_d(a.d.to_f32());
-                            matrix.set_e(a.tx.get() as f32);
-                            matrix.set_f(a.ty.get() as f32);
-
-                            bitmap_pattern.set_transform(&matrix);
-
-                            CanvasFillStyle::Pattern(bitmap_pattern)
-                        } else {
-                            log::error!("Couldn't fill shape with unknown bitmap {}", id);
-                            CanvasFillStyle::Color(CanvasColor(
-                                "rgba(0,0,0,0)".to_string(),
-                                0,
-                                0,
-                                0,
-                                0,
-                            ))
-                        }
-                    }
+                    } => bitmap_fill_style(*id, matrix, *is_smoothed, *is_repeating)?,
                 };
 
                 let path = Path2d::new().unwrap();
@@ -1385,9 +1579,15 @@ fn swf_shape_to_canvas_commands(
                     &bounds_viewbox_matrix,
                 );
 
-                canvas_data
-                    .0
-                    .push(CanvasDrawCommand::Fill { path, fill_style });
+                let winding_rule = match winding_rule {
+                    FillRule::EvenOdd => CanvasWindingRule::Evenodd,
+                    FillRule::NonZero => CanvasWindingRule::Nonzero,
+                };
+                canvas_data.0.push(CanvasDrawCommand::Fill {
+                    path,
+                    fill_style,
+                    winding_rule,
+                });
             }
             DrawPath::Stroke {
                 style,
@@ -1403,16 +1603,27 @@ fn swf_shape_to_canvas_commands(
                 // Therefore, we clamp the stroke width to 1 pixel (20 twips). This won't be 100% accurate
                 // if the shape is scaled, but it looks much closer to the Flash Player.
                 let line_width = std::cmp::max(style.width.get(), 20);
-                let stroke_style = CanvasColor(
-                    format!(
-                        "rgba({},{},{},{})",
-                        style.color.r, style.color.g, style.color.b, style.color.a
-                    ),
-                    style.color.r,
-                    style.color.g,
-                    style.color.b,
-                    style.color.a,
-                );
+                let stroke_style = match &style.fill_style {
+                    Some(FillStyle::Bitmap {
+                        id,
+                        matrix,
+                        is_smoothed,
+                        is_repeating,
+                    }) => bitmap_fill_style(*id, matrix, *is_smoothed, *is_repeating)?,
+                    Some(FillStyle::LinearGradient(_))
+                    | Some(FillStyle::RadialGradient(_))
+                    | Some(FillStyle::FocalGradient { .. }) => return None,
+                    Some(FillStyle::Color(_)) | None => CanvasFillStyle::Color(CanvasColor(
+                        format!(
+                            "rgba({},{},{},{})",
+                            style.color.r, style.color.g, style.color.b, style.color.a
+                        ),
+                        style.color.r,
+                        style.color.g,
+                        style.color.b,
+                        style.color.a,
+                    )),
+                };
                 let line_cap = match style.start_cap {
                     LineCapStyle::Round => "round",
                     LineCapStyle::Square => "square",
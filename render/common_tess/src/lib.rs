@@ -5,8 +5,8 @@ use lyon::tessellation::{
     FillTessellator, FillVertex, StrokeTessellator, StrokeVertex, StrokeVertexConstructor,
 };
 use lyon::tessellation::{FillOptions, StrokeOptions};
-use ruffle_core::backend::render::{srgb_to_linear, swf, BitmapHandle, BitmapSource};
-use ruffle_core::shape_utils::{DistilledShape, DrawCommand, DrawPath};
+use ruffle_render_api::backend::{srgb_to_linear, swf, BitmapHandle, BitmapSource};
+use ruffle_render_api::shape_utils::{DistilledShape, DrawCommand, DrawPath, FillRule};
 
 pub struct ShapeTessellator {
     fill_tess: FillTessellator,
@@ -44,174 +44,47 @@ impl ShapeTessellator {
         }
 
         for path in shape.paths {
-            match path {
-                DrawPath::Fill { style, commands } => match style {
-                    swf::FillStyle::Color(color) => {
-                        let mut buffers_builder = BuffersBuilder::new(
-                            &mut lyon_mesh,
-                            RuffleVertexCtor {
-                                color: color.clone(),
-                            },
-                        );
-
-                        if let Err(e) = self.fill_tess.tessellate_path(
-                            &ruffle_path_to_lyon_path(commands, true),
-                            &FillOptions::even_odd(),
-                            &mut buffers_builder,
-                        ) {
-                            // This may just be a degenerate path; skip it.
-                            log::error!("Tessellation failure: {:?}", e);
-                            continue;
-                        }
-                    }
-                    swf::FillStyle::LinearGradient(gradient) => {
-                        flush_draw(DrawType::Color, &mut mesh, &mut lyon_mesh);
-
-                        let mut buffers_builder = BuffersBuilder::new(
-                            &mut lyon_mesh,
-                            RuffleVertexCtor {
-                                color: swf::Color::from_rgb(0xffffff, 255),
-                            },
-                        );
-
-                        if let Err(e) = self.fill_tess.tessellate_path(
-                            &ruffle_path_to_lyon_path(commands, true),
-                            &FillOptions::even_odd(),
-                            &mut buffers_builder,
-                        ) {
-                            // This may just be a degenerate path; skip it.
-                            log::error!("Tessellation failure: {:?}", e);
-                            continue;
-                        }
-
-                        flush_draw(
-                            DrawType::Gradient(swf_gradient_to_uniforms(
-                                GradientType::Linear,
-                                gradient,
-                                swf::Fixed8::ZERO,
-                            )),
-                            &mut mesh,
-                            &mut lyon_mesh,
-                        );
-                    }
-                    swf::FillStyle::RadialGradient(gradient) => {
-                        flush_draw(DrawType::Color, &mut mesh, &mut lyon_mesh);
-
-                        let mut buffers_builder = BuffersBuilder::new(
-                            &mut lyon_mesh,
-                            RuffleVertexCtor {
-                                color: swf::Color::from_rgb(0xffffff, 255),
-                            },
-                        );
-
-                        if let Err(e) = self.fill_tess.tessellate_path(
-                            &ruffle_path_to_lyon_path(commands, true),
-                            &FillOptions::even_odd(),
-                            &mut buffers_builder,
-                        ) {
-                            // This may just be a degenerate path; skip it.
-                            log::error!("Tessellation failure: {:?}", e);
-                            continue;
-                        }
-
-                        flush_draw(
-                            DrawType::Gradient(swf_gradient_to_uniforms(
-                                GradientType::Radial,
-                                gradient,
-                                swf::Fixed8::ZERO,
-                            )),
-                            &mut mesh,
-                            &mut lyon_mesh,
-                        );
+            let fill_style = match &path {
+                DrawPath::Fill { style, .. } => Some(*style),
+                DrawPath::Stroke { style, .. } => style.fill_style.as_ref(),
+            };
+
+            // Solid colors are batched together, while gradients and bitmaps are
+            // drawn on their own with white vertices.
+            let (color, draw_type) = match (fill_style, &path) {
+                (Some(swf::FillStyle::Color(color)), _) => (color.clone(), None),
+                (None, DrawPath::Stroke { style, .. }) => (style.color.clone(), None),
+                (Some(style), _) => {
+                    flush_draw(DrawType::Color, &mut mesh, &mut lyon_mesh);
+
+                    if let Some(draw_type) = fill_draw_type(style, bitmap_source) {
+                        (swf::Color::from_rgb(0xffffff, 255), Some(draw_type))
+                    } else {
+                        log::error!("Couldn't fill shape with unknown bitmap");
+                        continue;
                     }
-                    swf::FillStyle::FocalGradient {
-                        gradient,
-                        focal_point,
-                    } => {
-                        flush_draw(DrawType::Color, &mut mesh, &mut lyon_mesh);
-
-                        let mut buffers_builder = BuffersBuilder::new(
-                            &mut lyon_mesh,
-                            RuffleVertexCtor {
-                                color: swf::Color::from_rgb(0xffffff, 255),
-                            },
-                        );
-
-                        if let Err(e) = self.fill_tess.tessellate_path(
-                            &ruffle_path_to_lyon_path(commands, true),
-                            &FillOptions::even_odd(),
-                            &mut buffers_builder,
-                        ) {
-                            // This may just be a degenerate path; skip it.
-                            log::error!("Tessellation failure: {:?}", e);
-                            continue;
-                        }
+                }
+                (None, DrawPath::Fill { .. }) => continue,
+            };
 
-                        flush_draw(
-                            DrawType::Gradient(swf_gradient_to_uniforms(
-                                GradientType::Focal,
-                                gradient,
-                                *focal_point,
-                            )),
-                            &mut mesh,
-                            &mut lyon_mesh,
-                        );
-                    }
-                    swf::FillStyle::Bitmap {
-                        id,
-                        matrix,
-                        is_smoothed,
-                        is_repeating,
-                    } => {
-                        flush_draw(DrawType::Color, &mut mesh, &mut lyon_mesh);
-
-                        let mut buffers_builder = BuffersBuilder::new(
-                            &mut lyon_mesh,
-                            RuffleVertexCtor {
-                                color: swf::Color::from_rgb(0xffffff, 255),
-                            },
-                        );
-
-                        if let Err(e) = self.fill_tess.tessellate_path(
-                            &ruffle_path_to_lyon_path(commands, true),
-                            &FillOptions::even_odd(),
-                            &mut buffers_builder,
-                        ) {
-                            // This may just be a degenerate path; skip it.
-                            log::error!("Tessellation failure: {:?}", e);
-                            continue;
-                        }
+            let mut buffers_builder =
+                BuffersBuilder::new(&mut lyon_mesh, RuffleVertexCtor { color });
 
-                        if let Some(bitmap) = bitmap_source.bitmap(*id) {
-                            flush_draw(
-                                DrawType::Bitmap(Bitmap {
-                                    matrix: swf_bitmap_to_gl_matrix(
-                                        (*matrix).into(),
-                                        bitmap.width.into(),
-                                        bitmap.height.into(),
-                                    ),
-                                    bitmap: bitmap.handle,
-                                    is_smoothed: *is_smoothed,
-                                    is_repeating: *is_repeating,
-                                }),
-                                &mut mesh,
-                                &mut lyon_mesh,
-                            );
-                        }
-                    }
-                },
+            let result = match path {
+                DrawPath::Fill {
+                    commands,
+                    winding_rule,
+                    ..
+                } => self.fill_tess.tessellate_path(
+                    &ruffle_path_to_lyon_path(commands, true),
+                    &fill_options(winding_rule),
+                    &mut buffers_builder,
+                ),
                 DrawPath::Stroke {
                     style,
                     commands,
                     is_closed,
                 } => {
-                    let mut buffers_builder = BuffersBuilder::new(
-                        &mut lyon_mesh,
-                        RuffleVertexCtor {
-                            color: style.color.clone(),
-                        },
-                    );
-
                     // TODO(Herschel): 0 width indicates "hairline".
                     let width = (style.width.to_pixels() as f32).max(1.0);
 
@@ -244,16 +117,22 @@ impl ShapeTessellator {
                     };
                     options = options.with_line_join(line_join);
 
-                    if let Err(e) = self.stroke_tess.tessellate_path(
+                    self.stroke_tess.tessellate_path(
                         &ruffle_path_to_lyon_path(commands, is_closed),
                         &options,
                         &mut buffers_builder,
-                    ) {
-                        // This may just be a degenerate path; skip it.
-                        log::error!("Tessellation failure: {:?}", e);
-                        continue;
-                    }
+                    )
                 }
+            };
+
+            if let Err(e) = result {
+                // This may just be a degenerate path; skip it.
+                log::error!("Tessellation failure: {:?}", e);
+                continue;
+            }
+
+            if let Some(draw_type) = draw_type {
+                flush_draw(draw_type, &mut mesh, &mut lyon_mesh);
             }
         }
 
@@ -321,7 +200,7 @@ pub struct Bitmap {
 }
 
 #[allow(clippy::many_single_char_names)]
-fn swf_to_gl_matrix(m: ruffle_core::matrix::Matrix) -> [[f32; 3]; 3] {
+fn swf_to_gl_matrix(m: ruffle_render_api::matrix::Matrix) -> [[f32; 3]; 3] {
     let tx = m.tx.get() as f32;
     let ty = m.ty.get() as f32;
     let det = m.a * m.d - m.c * m.b;
@@ -346,7 +225,7 @@ fn swf_to_gl_matrix(m: ruffle_core::matrix::Matrix) -> [[f32; 3]; 3] {
 
 #[allow(clippy::many_single_char_names)]
 fn swf_bitmap_to_gl_matrix(
-    m: ruffle_core::matrix::Matrix,
+    m: ruffle_render_api::matrix::Matrix,
     bitmap_width: u32,
     bitmap_height: u32,
 ) -> [[f32; 3]; 3] {
@@ -374,6 +253,55 @@ fn swf_bitmap_to_gl_matrix(
     [[a, d, 0.0], [b, e, 0.0], [c, f, 1.0]]
 }
 
+/// The draw type used to fill with a gradient or bitmap.
+///
+/// Returns `None` for solid colors, which are drawn with vertex colors
+/// instead, and for bitmaps that the bitmap source doesn't have.
+fn fill_draw_type(style: &swf::FillStyle, bitmap_source: &dyn BitmapSource) -> Option<DrawType> {
+    match style {
+        swf::FillStyle::Color(_) => None,
+        swf::FillStyle::LinearGradient(gradient) => Some(DrawType::Gradient(
+            swf_gradient_to_uniforms(GradientType::Linear, gradient, swf::Fixed8::ZERO),
+        )),
+        swf::FillStyle::RadialGradient(gradient) => Some(DrawType::Gradient(
+            swf_gradient_to_uniforms(GradientType::Radial, gradient, swf::Fixed8::ZERO),
+        )),
+        swf::FillStyle::FocalGradient {
+            gradient,
+            focal_point,
+        } => Some(DrawType::Gradient(swf_gradient_to_uniforms(
+            GradientType::Focal,
+            gradient,
+            *focal_point,
+        ))),
+        swf::FillStyle::Bitmap {
+            id,
+            matrix,
+            is_smoothed,
+            is_repeating,
+        } => {
+            let bitmap = bitmap_source.bitmap(*id)?;
+            Some(DrawType::Bitmap(Bitmap {
+                matrix: swf_bitmap_to_gl_matrix(
+                    (*matrix).into(),
+                    bitmap.width.into(),
+                    bitmap.height.into(),
+                ),
+                bitmap: bitmap.handle,
+                is_smoothed: *is_smoothed,
+                is_repeating: *is_repeating,
+            }))
+        }
+    }
+}
+
+fn fill_options(winding_rule: FillRule) -> FillOptions {
+    match winding_rule {
+        FillRule::EvenOdd => FillOptions::even_odd(),
+        FillRule::NonZero => FillOptions::non_zero(),
+    }
+}
+
 fn ruffle_path_to_lyon_path(commands: Vec<DrawCommand>, is_closed: bool) -> Path {
     fn point(x: swf::Twips, y: swf::Twips) -> lyon::math::Point {
         lyon::math::Point::new(x.to_pixels() as f32, y.to_pixels() as f32)
@@ -486,3 +414,149 @@ pub enum GradientType {
     Radial,
     Focal,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ruffle_render_api::backend::BitmapInfo;
+    use ruffle_render_api::bounding_box::BoundingBox;
+    use swf::Twips;
+
+    struct OneBitmap;
+
+    impl BitmapSource for OneBitmap {
+        fn bitmap(&self, id: u16) -> Option<BitmapInfo> {
+            if id == 0 {
+                Some(BitmapInfo {
+                    handle: BitmapHandle(7),
+                    width: 10,
+                    height: 10,
+                })
+            } else {
+                None
+            }
+        }
+    }
+
+    fn bitmap_fill(id: u16) -> swf::FillStyle {
+        swf::FillStyle::Bitmap {
+            id,
+            matrix: swf::Matrix::IDENTITY,
+            is_smoothed: true,
+            is_repeating: false,
+        }
+    }
+
+    fn square() -> Vec<DrawCommand> {
+        let point = |x, y| (Twips::from_pixels(x), Twips::from_pixels(y));
+        let corners = [point(10.0, 0.0), point(10.0, 10.0), point(0.0, 10.0)];
+        let mut commands = vec![DrawCommand::MoveTo {
+            x: Twips::ZERO,
+            y: Twips::ZERO,
+        }];
+        commands.extend(
+            corners
+                .iter()
+                .map(|&(x, y)| DrawCommand::LineTo { x, y })
+                .chain(std::iter::once(DrawCommand::LineTo {
+                    x: Twips::ZERO,
+                    y: Twips::ZERO,
+                })),
+        );
+        commands
+    }
+
+    fn tessellate(paths: Vec<DrawPath>) -> Mesh {
+        ShapeTessellator::new().tessellate_shape(
+            DistilledShape {
+                paths,
+                shape_bounds: BoundingBox::default(),
+                edge_bounds: BoundingBox::default(),
+                id: 0,
+            },
+            &OneBitmap,
+        )
+    }
+
+    #[test]
+    fn solid_fills_and_strokes_are_batched() {
+        let fill = swf::FillStyle::Color(swf::Color::from_rgb(0xFF0000, 255));
+        let line = swf::LineStyle::new_v1(Twips::from_pixels(2.0), swf::Color::from_rgb(0, 255));
+        let mesh = tessellate(vec![
+            DrawPath::Fill {
+                style: &fill,
+                commands: square(),
+                winding_rule: FillRule::EvenOdd,
+            },
+            DrawPath::Stroke {
+                style: &line,
+                is_closed: true,
+                commands: square(),
+            },
+        ]);
+
+        assert_eq!(mesh.len(), 1);
+        assert!(matches!(mesh[0].draw_type, DrawType::Color));
+        let has_color = |color| mesh[0].vertices.iter().any(|vertex| vertex.color == color);
+        assert!(has_color(swf::Color::from_rgb(0xFF0000, 255)));
+        assert!(has_color(swf::Color::from_rgb(0, 255)));
+    }
+
+    #[test]
+    fn strokes_can_be_filled_with_bitmaps() {
+        let solid = swf::FillStyle::Color(swf::Color::from_rgb(0xFF0000, 255));
+        let mut line =
+            swf::LineStyle::new_v1(Twips::from_pixels(2.0), swf::Color::from_rgb(0, 255));
+        line.fill_style = Some(bitmap_fill(0));
+        let mesh = tessellate(vec![
+            DrawPath::Fill {
+                style: &solid,
+                commands: square(),
+                winding_rule: FillRule::EvenOdd,
+            },
+            DrawPath::Stroke {
+                style: &line,
+                is_closed: true,
+                commands: square(),
+            },
+        ]);
+
+        // The solid fill is drawn first, then the bitmap stroke with white vertices.
+        assert_eq!(mesh.len(), 2);
+        assert!(matches!(mesh[0].draw_type, DrawType::Color));
+        match &mesh[1].draw_type {
+            DrawType::Bitmap(bitmap) => {
+                assert_eq!(bitmap.bitmap, BitmapHandle(7));
+                assert!(bitmap.is_smoothed);
+                assert!(!bitmap.is_repeating);
+            }
+            _ => panic!("stroke should be drawn with its bitmap"),
+        }
+        assert!(mesh[1]
+            .vertices
+            .iter()
+            .all(|vertex| vertex.color == swf::Color::from_rgb(0xFFFFFF, 255)));
+    }
+
+    #[test]
+    fn unknown_bitmaps_are_skipped() {
+        let fill = bitmap_fill(1);
+        let mut line =
+            swf::LineStyle::new_v1(Twips::from_pixels(2.0), swf::Color::from_rgb(0, 255));
+        line.fill_style = Some(bitmap_fill(1));
+        let mesh = tessellate(vec![
+            DrawPath::Fill {
+                style: &fill,
+                commands: square(),
+                winding_rule: FillRule::EvenOdd,
+            },
+            DrawPath::Stroke {
+                style: &line,
+                is_closed: true,
+                commands: square(),
+            },
+        ]);
+
+        assert!(mesh.is_empty());
+    }
+}
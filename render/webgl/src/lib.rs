@@ -1,7 +1,7 @@
 use bytemuck::{Pod, Zeroable};
 use ruffle_core::backend::render::{
     Bitmap, BitmapFormat, BitmapHandle, BitmapInfo, BitmapSource, Color, NullBitmapSource,
-    RenderBackend, ShapeHandle, Transform,
+    RenderBackend, ShapeHandle, StageQuality, Transform,
 };
 use ruffle_core::shape_utils::DistilledShape;
 use ruffle_core::swf;
@@ -69,6 +69,9 @@ pub struct WebGlRenderBackend {
     msaa_buffers: Option<MsaaBuffers>,
     msaa_sample_count: u32,
 
+    /// The most MSAA samples this device supports, lowered on mobile devices.
+    max_msaa_sample_count: u32,
+
     color_program: ShaderProgram,
     bitmap_program: ShaderProgram,
     gradient_program: ShaderProgram,
@@ -96,6 +99,9 @@ pub struct WebGlRenderBackend {
     view_height: i32,
     view_matrix: [[f32; 4]; 4],
 
+    /// The framebuffer being drawn into instead of the frame, if any.
+    offscreen: Option<Offscreen>,
+
     bitmap_registry: HashMap<BitmapHandle, Bitmap>,
 }
 
@@ -204,7 +210,12 @@ impl WebGlRenderBackend {
         let gradient_program = ShaderProgram::new(&gl, &texture_vertex, &gradient_fragment)?;
 
         gl.enable(Gl::BLEND);
-        gl.blend_func(Gl::SRC_ALPHA, Gl::ONE_MINUS_SRC_ALPHA);
+        gl.blend_func_separate(
+            Gl::SRC_ALPHA,
+            Gl::ONE_MINUS_SRC_ALPHA,
+            Gl::ONE,
+            Gl::ONE_MINUS_SRC_ALPHA,
+        );
 
         // Necessary to load RGB textures (alignment defaults to 4).
         gl.pixel_storei(Gl::UNPACK_ALIGNMENT, 1);
@@ -216,6 +227,7 @@ impl WebGlRenderBackend {
 
             msaa_buffers: None,
             msaa_sample_count,
+            max_msaa_sample_count: msaa_sample_count,
 
             color_program,
             gradient_program,
@@ -232,6 +244,7 @@ impl WebGlRenderBackend {
             view_width: 1,
             view_height: 1,
             view_matrix: [[0.0; 4]; 4],
+            offscreen: None,
 
             mask_state: MaskState::NoMask,
             num_masks: 0,
@@ -253,6 +266,35 @@ impl WebGlRenderBackend {
         Ok(renderer)
     }
 
+    /// Draws once with every shader program, so that the browser finishes
+    /// preparing them now rather than the first time a movie uses them.
+    ///
+    /// Browsers often translate shaders and build the driver's version of a
+    /// program lazily, on its first draw call. Nothing is drawn, as every
+    /// fragment is scissored away.
+    pub fn warm_up(&self) {
+        self.gl.enable(Gl::SCISSOR_TEST);
+        self.gl.scissor(0, 0, 0, 0);
+
+        let color_quad = &self.meshes[self.color_quad_shape.0].draws[0];
+        let bitmap_quad = &self.meshes[self.bitmap_quad_shape.0].draws[0];
+        let programs = [
+            (&self.color_program, color_quad),
+            (&self.gradient_program, bitmap_quad),
+            (&self.bitmap_program, bitmap_quad),
+        ];
+        for (program, quad) in programs.iter() {
+            self.gl.use_program(Some(&program.program));
+            self.bind_vertex_array(Some(&quad.vao));
+            self.gl
+                .draw_elements_with_i32(Gl::TRIANGLES, quad.num_indices, Gl::UNSIGNED_INT, 0);
+        }
+
+        self.bind_vertex_array(None);
+        self.gl.use_program(None);
+        self.gl.disable(Gl::SCISSOR_TEST);
+    }
+
     fn build_quad_mesh(&self, program: &ShaderProgram) -> Result<Mesh, Error> {
         let vao = self.create_vertex_array()?;
 
@@ -358,16 +400,8 @@ impl WebGlRenderBackend {
     }
 
     fn build_msaa_buffers(&mut self, width: i32, height: i32) -> Result<(), Error> {
-        if self.gl2.is_none() || self.msaa_sample_count <= 1 {
-            self.gl.bind_framebuffer(Gl::FRAMEBUFFER, None);
-            self.gl.bind_renderbuffer(Gl::RENDERBUFFER, None);
-            return Ok(());
-        }
-
-        let gl = self.gl2.as_ref().unwrap();
-
         // Delete previous buffers, if they exist.
-        if let Some(msaa_buffers) = self.msaa_buffers.take() {
+        if let (Some(gl), Some(msaa_buffers)) = (&self.gl2, self.msaa_buffers.take()) {
             gl.delete_renderbuffer(Some(&msaa_buffers.color_renderbuffer));
             gl.delete_renderbuffer(Some(&msaa_buffers.stencil_renderbuffer));
             gl.delete_framebuffer(Some(&msaa_buffers.render_framebuffer));
@@ -375,6 +409,14 @@ impl WebGlRenderBackend {
             gl.delete_texture(Some(&msaa_buffers.framebuffer_texture));
         }
 
+        if self.gl2.is_none() || self.msaa_sample_count <= 1 {
+            self.gl.bind_framebuffer(Gl::FRAMEBUFFER, None);
+            self.gl.bind_renderbuffer(Gl::RENDERBUFFER, None);
+            return Ok(());
+        }
+
+        let gl = self.gl2.as_ref().unwrap();
+
         // Create frame and render buffers.
         let render_framebuffer = gl
             .create_framebuffer()
@@ -595,6 +637,15 @@ impl WebGlRenderBackend {
         ];
     }
 
+    /// Bind the framebuffer that frames are drawn into.
+    fn bind_frame_framebuffer(&self) {
+        let framebuffer = self
+            .msaa_buffers
+            .as_ref()
+            .map(|msaa_buffers| &msaa_buffers.render_framebuffer);
+        self.gl.bind_framebuffer(Gl::FRAMEBUFFER, framebuffer);
+    }
+
     /// Creates and binds a new VAO.
     fn create_vertex_array(&self) -> Result<WebGlVertexArrayObject, Error> {
         let vao = if let Some(gl2) = &self.gl2 {
@@ -621,6 +672,73 @@ impl WebGlRenderBackend {
         };
     }
 
+    /// Set how colors are blended. Alpha is always blended as premultiplied,
+    /// so that what's drawn into a transparent offscreen bitmap has the right
+    /// alpha.
+    fn set_blend_func(&self, src_blend: u32, dst_blend: u32) {
+        self.gl
+            .blend_func_separate(src_blend, dst_blend, Gl::ONE, Gl::ONE_MINUS_SRC_ALPHA);
+    }
+
+    fn create_offscreen(&self, width: i32, height: i32) -> Result<Offscreen, Error> {
+        let gl = &self.gl;
+
+        let texture = gl.create_texture().ok_or("Unable to create texture")?;
+        gl.bind_texture(Gl::TEXTURE_2D, Some(&texture));
+        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+            Gl::TEXTURE_2D,
+            0,
+            Gl::RGBA as i32,
+            width,
+            height,
+            0,
+            Gl::RGBA,
+            Gl::UNSIGNED_BYTE,
+            None,
+        )
+        .into_js_result()?;
+        gl.bind_texture(Gl::TEXTURE_2D, None);
+
+        let stencil_renderbuffer = gl
+            .create_renderbuffer()
+            .ok_or("Unable to create renderbuffer")?;
+        gl.bind_renderbuffer(Gl::RENDERBUFFER, Some(&stencil_renderbuffer));
+        gl.renderbuffer_storage(Gl::RENDERBUFFER, Gl::STENCIL_INDEX8, width, height);
+        gl.check_error("renderbuffer_storage (offscreen stencil)")?;
+        gl.bind_renderbuffer(Gl::RENDERBUFFER, None);
+
+        let framebuffer = gl
+            .create_framebuffer()
+            .ok_or("Unable to create framebuffer")?;
+        gl.bind_framebuffer(Gl::FRAMEBUFFER, Some(&framebuffer));
+        gl.framebuffer_texture_2d(
+            Gl::FRAMEBUFFER,
+            Gl::COLOR_ATTACHMENT0,
+            Gl::TEXTURE_2D,
+            Some(&texture),
+            0,
+        );
+        gl.framebuffer_renderbuffer(
+            Gl::FRAMEBUFFER,
+            Gl::STENCIL_ATTACHMENT,
+            Gl::RENDERBUFFER,
+            Some(&stencil_renderbuffer),
+        );
+
+        Ok(Offscreen {
+            framebuffer,
+            texture,
+            stencil_renderbuffer,
+            width,
+            height,
+            frame_mask_state: self.mask_state,
+            frame_num_masks: self.num_masks,
+            frame_view_width: self.view_width,
+            frame_view_height: self.view_height,
+            frame_view_matrix: self.view_matrix,
+        })
+    }
+
     fn set_stencil_state(&mut self) {
         // Set stencil state for masking, if necessary.
         if self.mask_state_dirty {
@@ -733,6 +851,18 @@ impl RenderBackend for WebGlRenderBackend {
             .viewport(0, 0, self.renderbuffer_width, self.renderbuffer_height);
     }
 
+    fn set_quality(&mut self, quality: StageQuality) {
+        // WebGL1 has no MSAA, and the context's own anti-aliasing can't be changed.
+        let msaa_sample_count = quality.sample_count().min(self.max_msaa_sample_count);
+        if msaa_sample_count == self.msaa_sample_count {
+            return;
+        }
+        self.msaa_sample_count = msaa_sample_count;
+        if let Err(e) = self.build_msaa_buffers(self.renderbuffer_width, self.renderbuffer_height) {
+            log::error!("Couldn't rebuild MSAA buffers: {}", e);
+        }
+    }
+
     fn register_shape(
         &mut self,
         shape: DistilledShape,
@@ -967,7 +1097,7 @@ impl RenderBackend for WebGlRenderBackend {
                 self.add_color = None;
 
                 if (src_blend, dst_blend) != self.blend_func {
-                    self.gl.blend_func(src_blend, dst_blend);
+                    self.set_blend_func(src_blend, dst_blend);
                     self.blend_func = (src_blend, dst_blend);
                 }
             }
@@ -1058,7 +1188,7 @@ impl RenderBackend for WebGlRenderBackend {
                 self.add_color = None;
 
                 if (src_blend, dst_blend) != self.blend_func {
-                    self.gl.blend_func(src_blend, dst_blend);
+                    self.set_blend_func(src_blend, dst_blend);
                     self.blend_func = (src_blend, dst_blend);
                 }
             }
@@ -1202,7 +1332,7 @@ impl RenderBackend for WebGlRenderBackend {
             self.add_color = None;
 
             if (src_blend, dst_blend) != self.blend_func {
-                self.gl.blend_func(src_blend, dst_blend);
+                self.set_blend_func(src_blend, dst_blend);
                 self.blend_func = (src_blend, dst_blend);
             }
         };
@@ -1231,6 +1361,91 @@ impl RenderBackend for WebGlRenderBackend {
         );
     }
 
+    fn begin_offscreen(&mut self, width: u32, height: u32) -> bool {
+        if self.offscreen.is_some() || width == 0 || height == 0 {
+            return false;
+        }
+
+        let offscreen = match self.create_offscreen(width as i32, height as i32) {
+            Ok(offscreen) => offscreen,
+            Err(e) => {
+                log::warn!("Couldn't begin offscreen drawing: {}", e);
+                self.bind_frame_framebuffer();
+                return false;
+            }
+        };
+
+        self.gl.viewport(0, 0, offscreen.width, offscreen.height);
+        self.offscreen = Some(offscreen);
+
+        self.view_width = width as i32;
+        self.view_height = height as i32;
+        self.build_matrices();
+        // Make the next draw upload the new view matrix.
+        self.active_program = std::ptr::null();
+
+        self.mask_state = MaskState::NoMask;
+        self.num_masks = 0;
+        self.mask_state_dirty = true;
+        self.set_stencil_state();
+
+        self.gl.clear_color(0.0, 0.0, 0.0, 0.0);
+        self.gl.stencil_mask(0xff);
+        self.gl.clear(Gl::COLOR_BUFFER_BIT | Gl::STENCIL_BUFFER_BIT);
+        true
+    }
+
+    fn end_offscreen(&mut self) -> Option<Bitmap> {
+        let offscreen = self.offscreen.take()?;
+
+        let row_len = offscreen.width as usize * 4;
+        let mut rgba = vec![0; row_len * offscreen.height as usize];
+        let result = self.gl.read_pixels_with_opt_u8_array(
+            0,
+            0,
+            offscreen.width,
+            offscreen.height,
+            Gl::RGBA,
+            Gl::UNSIGNED_BYTE,
+            Some(&mut rgba),
+        );
+
+        self.bind_frame_framebuffer();
+        self.gl
+            .viewport(0, 0, self.renderbuffer_width, self.renderbuffer_height);
+        self.gl.delete_framebuffer(Some(&offscreen.framebuffer));
+        self.gl.delete_texture(Some(&offscreen.texture));
+        self.gl
+            .delete_renderbuffer(Some(&offscreen.stencil_renderbuffer));
+
+        self.view_width = offscreen.frame_view_width;
+        self.view_height = offscreen.frame_view_height;
+        self.view_matrix = offscreen.frame_view_matrix;
+        self.active_program = std::ptr::null();
+
+        self.mask_state = offscreen.frame_mask_state;
+        self.num_masks = offscreen.frame_num_masks;
+        self.mask_state_dirty = true;
+
+        if let Err(e) = result {
+            log::warn!("Couldn't read offscreen bitmap: {:?}", e);
+            return None;
+        }
+
+        // GL reads rows from the bottom up.
+        let data = rgba
+            .chunks_exact(row_len)
+            .rev()
+            .flatten()
+            .copied()
+            .collect();
+        Some(Bitmap {
+            width: offscreen.width as u32,
+            height: offscreen.height as u32,
+            data: BitmapFormat::Rgba(data),
+        })
+    }
+
     fn push_mask(&mut self) {
         debug_assert!(
             self.mask_state == MaskState::NoMask || self.mask_state == MaskState::DrawMaskedContent
@@ -1315,6 +1530,23 @@ impl RenderBackend for WebGlRenderBackend {
     }
 }
 
+/// A framebuffer being drawn into between `begin_offscreen` and
+/// `end_offscreen`.
+struct Offscreen {
+    framebuffer: WebGlFramebuffer,
+    texture: WebGlTexture,
+    stencil_renderbuffer: WebGlRenderbuffer,
+    width: i32,
+    height: i32,
+
+    /// The state of the frame, restored when the offscreen drawing is done.
+    frame_mask_state: MaskState,
+    frame_num_masks: u32,
+    frame_view_width: i32,
+    frame_view_height: i32,
+    frame_view_matrix: [[f32; 4]; 4],
+}
+
 struct Texture {
     width: u32,
     height: u32,
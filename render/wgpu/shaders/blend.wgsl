@@ -0,0 +1,121 @@
+// Blends a layer drawn with a blend mode onto a copy of what it was drawn
+// over, using the same formulas as `BitmapData.draw`.
+//
+// Both are multisampled and premultiplied. They're read sample by sample so
+// that each blended sample is written back where it came from.
+
+[[block]]
+struct Blend {
+    // The blend mode, numbered as in SWF files.
+    mode: u32;
+};
+
+[[group(0), binding(0)]]
+var t_layer: texture_multisampled_2d<f32>;
+[[group(0), binding(1)]]
+var t_backdrop: texture_multisampled_2d<f32>;
+[[group(0), binding(2)]]
+var<uniform> blend: Blend;
+
+// A single triangle covering the whole target.
+[[stage(vertex)]]
+fn main_vertex([[builtin(vertex_index)]] index: u32) -> [[builtin(position)]] vec4<f32> {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+}
+
+fn hard_light(s: f32, sa: f32, d: f32, da: f32) -> f32 {
+    let uncovered = s * (1.0 - da) + d * (1.0 - sa);
+    if (2.0 * s <= sa) {
+        return 2.0 * s * d + uncovered;
+    }
+    return sa * da - 2.0 * (da - d) * (sa - s) + uncovered;
+}
+
+[[stage(fragment)]]
+fn main_fragment(
+    [[builtin(position)]] position: vec4<f32>,
+    [[builtin(sample_index)]] sample_index: u32
+) -> [[location(0)]] vec4<f32> {
+    let coords = vec2<i32>(position.xy);
+    let source = textureLoad(t_layer, coords, i32(sample_index));
+    let dest = textureLoad(t_backdrop, coords, i32(sample_index));
+
+    let s = source.rgb;
+    let sa = source.a;
+    let d = dest.rgb;
+    let da = dest.a;
+    let uncovered = s * (1.0 - da) + d * (1.0 - sa);
+
+    var alpha: f32 = sa + da - sa * da;
+    var color: vec3<f32>;
+    switch (blend.mode) {
+        // MULTIPLY
+        case 3u: {
+            color = s * d + uncovered;
+        }
+        // SCREEN
+        case 4u: {
+            color = s + d - s * d;
+        }
+        // LIGHTEN
+        case 5u: {
+            color = max(s * da, d * sa) + uncovered;
+        }
+        // DARKEN
+        case 6u: {
+            color = min(s * da, d * sa) + uncovered;
+        }
+        // DIFFERENCE
+        case 7u: {
+            color = s + d - 2.0 * min(s * da, d * sa);
+        }
+        // ADD
+        case 8u: {
+            color = s + d;
+        }
+        // SUBTRACT
+        case 9u: {
+            color = d - s;
+        }
+        // INVERT
+        case 10u: {
+            alpha = da;
+            color = (vec3<f32>(da, da, da) - d) * sa + d * (1.0 - sa);
+        }
+        // ALPHA
+        case 11u: {
+            alpha = da * sa;
+            color = d * sa;
+        }
+        // ERASE
+        case 12u: {
+            alpha = da * (1.0 - sa);
+            color = d * (1.0 - sa);
+        }
+        // OVERLAY
+        case 13u: {
+            color = vec3<f32>(
+                hard_light(d.r, da, s.r, sa),
+                hard_light(d.g, da, s.g, sa),
+                hard_light(d.b, da, s.b, sa)
+            );
+        }
+        // HARDLIGHT
+        case 14u: {
+            color = vec3<f32>(
+                hard_light(s.r, sa, d.r, da),
+                hard_light(s.g, sa, d.g, da),
+                hard_light(s.b, sa, d.b, da)
+            );
+        }
+        // NORMAL and LAYER
+        default: {
+            color = s + d * (1.0 - sa);
+        }
+    }
+
+    // A premultiplied channel can't be brighter than its alpha.
+    let max_color = vec3<f32>(alpha, alpha, alpha);
+    return vec4<f32>(clamp(color, vec3<f32>(0.0, 0.0, 0.0), max_color), alpha);
+}
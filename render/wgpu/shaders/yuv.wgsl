@@ -0,0 +1,52 @@
+// Converts a planar YUV 4:2:0 video frame to RGBA.
+
+struct VertexOutput {
+    [[builtin(position)]] position: vec4<f32>;
+    [[location(0)]] uv: vec2<f32>;
+};
+
+[[group(0), binding(0)]]
+var t_y: texture_2d<f32>;
+[[group(0), binding(1)]]
+var t_u: texture_2d<f32>;
+[[group(0), binding(2)]]
+var t_v: texture_2d<f32>;
+[[group(0), binding(3)]]
+var s_plane: sampler;
+
+// A single triangle covering the whole target.
+[[stage(vertex)]]
+fn main_vertex([[builtin(vertex_index)]] index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var output: VertexOutput;
+    output.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    output.uv = uv;
+    return output;
+}
+
+[[stage(fragment)]]
+fn main_fragment(input: VertexOutput) -> [[location(0)]] vec4<f32> {
+    // Each chroma sample covers 2x2 luma samples. The chroma planes may be
+    // padded, so scale by texels rather than by the whole texture.
+    let luma_size = textureDimensions(t_y);
+    let chroma_size = textureDimensions(t_u);
+    let chroma_scale = vec2<f32>(
+        f32(luma_size.x) * 0.5 / f32(chroma_size.x),
+        f32(luma_size.y) * 0.5 / f32(chroma_size.y)
+    );
+    let chroma_uv = input.uv * chroma_scale;
+
+    let y = textureSample(t_y, s_plane, input.uv).r;
+    let u = textureSample(t_u, s_plane, chroma_uv).r;
+    let v = textureSample(t_v, s_plane, chroma_uv).r;
+
+    // BT.601, limited range.
+    let c = (y - 0.0627451) * 1.164383;
+    let d = u - 0.5019608;
+    let e = v - 0.5019608;
+    let r = clamp(c + 1.596027 * e, 0.0, 1.0);
+    let g = clamp(c - 0.391762 * d - 0.812968 * e, 0.0, 1.0);
+    let b = clamp(c + 2.017232 * d, 0.0, 1.0);
+
+    return vec4<f32>(r, g, b, 1.0);
+}
@@ -0,0 +1,159 @@
+//! Blending of display objects drawn with blend modes.
+//!
+//! An object with a blend mode is drawn into a multisampled layer of its own.
+//! When it's done, what it was drawn over is copied, and a render pass blends
+//! the layer onto the copy, writing the result back over the original.
+
+use crate::pipelines::mask_render_state;
+use crate::MaskState;
+use std::borrow::Cow;
+
+/// The pipelines that blend layers onto what they were drawn over.
+#[derive(Debug)]
+pub struct BlendCompositor {
+    layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+    masked_pipeline: wgpu::RenderPipeline,
+}
+
+impl BlendCompositor {
+    pub fn new(device: &wgpu::Device, msaa_sample_count: u32) -> Self {
+        let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: create_debug_label!("Blend shader").as_deref(),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("../shaders/blend.wgsl"))),
+            flags: wgpu::ShaderFlags::all(),
+        });
+
+        let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStage::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                multisampled: true,
+                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                view_dimension: wgpu::TextureViewDimension::D2,
+            },
+            count: None,
+        };
+        let layout_label = create_debug_label!("Blend bind group layout");
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                texture_entry(0),
+                texture_entry(1),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+            label: layout_label.as_deref(),
+        });
+
+        let pipeline_layout_label = create_debug_label!("Blend pipeline layout");
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: pipeline_layout_label.as_deref(),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+
+        // The blended result replaces what's there, except outside of any
+        // mask that the layer was drawn inside.
+        let create_pipeline = |mask_state, label: Option<String>| {
+            let (stencil, write_mask) = mask_render_state(mask_state);
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: label.as_deref(),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "main_vertex",
+                    buffers: &[],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: "main_fragment",
+                    targets: &[wgpu::ColorTargetState {
+                        format: wgpu::TextureFormat::Bgra8Unorm,
+                        blend: None,
+                        write_mask,
+                    }],
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::default(),
+                    clamp_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: wgpu::TextureFormat::Depth24PlusStencil8,
+                    depth_write_enabled: false,
+                    depth_compare: wgpu::CompareFunction::Always,
+                    stencil,
+                    bias: Default::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: msaa_sample_count,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+            })
+        };
+        let pipeline = create_pipeline(MaskState::NoMask, create_debug_label!("Blend pipeline"));
+        let masked_pipeline = create_pipeline(
+            MaskState::DrawMaskedContent,
+            create_debug_label!("Blend pipeline draw masked content"),
+        );
+
+        Self {
+            layout,
+            pipeline,
+            masked_pipeline,
+        }
+    }
+
+    /// The pipeline that blends onto a target in the given mask state.
+    ///
+    /// Layers are never blended while masks are being drawn.
+    pub fn pipeline_for(&self, mask_state: MaskState) -> &wgpu::RenderPipeline {
+        if mask_state == MaskState::DrawMaskedContent {
+            &self.masked_pipeline
+        } else {
+            &self.pipeline
+        }
+    }
+
+    /// Make the bind group that blends a layer onto a copy of what it was
+    /// drawn over.
+    pub fn bind_group(
+        &self,
+        device: &wgpu::Device,
+        layer: &wgpu::TextureView,
+        backdrop: &wgpu::TextureView,
+        uniforms: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(layer),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(backdrop),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: uniforms.as_entire_binding(),
+                },
+            ],
+            label: create_debug_label!("Blend bind group").as_deref(),
+        })
+    }
+}
@@ -0,0 +1,1239 @@
+//! Stage3D contexts, drawn with the same device as the rest of the stage.
+//!
+//! Each draw call is submitted as soon as it's made, so that buffers written
+//! through the queue between draw calls are seen by the right ones.
+
+mod agal;
+
+use crate::utils::create_buffer_with_data;
+use crate::Error;
+use agal::{Filter, Mipmap, SamplerConfig, TranslatedProgram, Wrapping};
+use ruffle_core::backend::render::context3d::{
+    BlendFactors, Context3D, Context3DBlendFactor, Context3DCompareMode, Context3DProgramType,
+    Context3DStencilAction, Context3DTextureFormat, Context3DTriangleFace,
+    Context3DVertexBufferFormat, IndexBuffer3DHandle, Program3DHandle, StencilActions,
+    Texture3DHandle, VertexBuffer3DHandle, MAX_SAMPLERS, MAX_VERTEX_ATTRIBUTES,
+};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The texture format of back buffers and textures.
+const TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth24PlusStencil8;
+
+/// The flags of `Context3DClearMask`.
+const CLEAR_COLOR: u32 = 1;
+const CLEAR_DEPTH: u32 = 2;
+const CLEAR_STENCIL: u32 = 4;
+
+/// The number of samples taken of each pixel when anti-aliasing.
+const MSAA_SAMPLE_COUNT: u32 = 4;
+
+/// What's drawn into along with a texture: a multisampled texture that's
+/// resolved into it, if anti-aliasing, and a depth and stencil buffer, if
+/// enabled.
+struct Attachments {
+    sample_count: u32,
+    msaa_view: Option<wgpu::TextureView>,
+    depth_view: Option<wgpu::TextureView>,
+}
+
+impl Attachments {
+    fn new(
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        anti_alias: u32,
+        enable_depth_and_stencil: bool,
+    ) -> Self {
+        let sample_count = if anti_alias > 0 { MSAA_SAMPLE_COUNT } else { 1 };
+        let create_view = |label: Option<String>, format| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: label.as_deref(),
+                    size: wgpu::Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage: wgpu::TextureUsage::RENDER_ATTACHMENT,
+                })
+                .create_view(&Default::default())
+        };
+
+        Self {
+            sample_count,
+            msaa_view: (sample_count > 1).then(|| {
+                create_view(
+                    create_debug_label!("Context3D multisampled buffer"),
+                    TEXTURE_FORMAT,
+                )
+            }),
+            depth_view: enable_depth_and_stencil
+                .then(|| create_view(create_debug_label!("Context3D depth buffer"), DEPTH_FORMAT)),
+        }
+    }
+}
+
+/// What's currently drawn into, which is either the back buffer or a texture.
+struct Target<'a> {
+    view: &'a wgpu::TextureView,
+    attachments: &'a Attachments,
+    width: u32,
+    height: u32,
+}
+
+impl<'a> Target<'a> {
+    fn color_attachment(
+        &self,
+        load: wgpu::LoadOp<wgpu::Color>,
+    ) -> wgpu::RenderPassColorAttachment<'a> {
+        let attachments: &'a Attachments = self.attachments;
+        let ops = wgpu::Operations { load, store: true };
+        match &attachments.msaa_view {
+            Some(msaa_view) => wgpu::RenderPassColorAttachment {
+                view: msaa_view,
+                resolve_target: Some(self.view),
+                ops,
+            },
+            None => wgpu::RenderPassColorAttachment {
+                view: self.view,
+                resolve_target: None,
+                ops,
+            },
+        }
+    }
+
+    fn depth_stencil_attachment(
+        &self,
+        depth_load: wgpu::LoadOp<f32>,
+        stencil_load: wgpu::LoadOp<u32>,
+    ) -> Option<wgpu::RenderPassDepthStencilAttachment<'a>> {
+        let attachments: &'a Attachments = self.attachments;
+        attachments
+            .depth_view
+            .as_ref()
+            .map(|view| wgpu::RenderPassDepthStencilAttachment {
+                view,
+                depth_ops: Some(wgpu::Operations {
+                    load: depth_load,
+                    store: true,
+                }),
+                stencil_ops: Some(wgpu::Operations {
+                    load: stencil_load,
+                    store: true,
+                }),
+            })
+    }
+}
+
+struct BackBuffer {
+    width: u32,
+    height: u32,
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    attachments: Attachments,
+
+    /// What was last presented, which is what's shown on the stage.
+    front_texture: wgpu::Texture,
+    front_view: wgpu::TextureView,
+}
+
+struct VertexBuffer {
+    buffer: wgpu::Buffer,
+    size: u64,
+    data32_per_vertex: u32,
+}
+
+struct IndexBuffer {
+    buffer: wgpu::Buffer,
+
+    /// A copy of the indices, so that uploads of an odd number of indices can
+    /// be padded to a whole number of 32-bit values.
+    indices: Vec<u16>,
+}
+
+struct Program {
+    translated: TranslatedProgram,
+    vertex_module: wgpu::ShaderModule,
+    fragment_module: wgpu::ShaderModule,
+    texture_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
+}
+
+struct Texture {
+    width: u32,
+    height: u32,
+    mip_level_count: u32,
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+
+    /// A view of the largest mip level, which is what's drawn into when
+    /// rendering to the texture.
+    render_view: wgpu::TextureView,
+
+    /// What's drawn into along with the texture, once it's been rendered to.
+    attachments: Option<Attachments>,
+}
+
+/// Everything a render pipeline depends on besides the program.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+struct PipelineKey {
+    program: usize,
+    attributes: [Option<(u32, u32, Context3DVertexBufferFormat)>; MAX_VERTEX_ATTRIBUTES],
+    blend_factors: BlendFactors,
+    culling: Context3DTriangleFace,
+    depth_mask: bool,
+    depth_compare: Context3DCompareMode,
+    color_mask: wgpu::ColorWrite,
+    stencil_actions: StencilActions,
+    stencil_read_mask: u32,
+    stencil_write_mask: u32,
+    has_depth: bool,
+    sample_count: u32,
+}
+
+pub struct WgpuContext3D {
+    device: Arc<wgpu::Device>,
+    queue: Arc<wgpu::Queue>,
+    driver_info: String,
+    constants_layout: wgpu::BindGroupLayout,
+
+    back_buffer: Option<BackBuffer>,
+    vertex_buffers: Vec<Option<VertexBuffer>>,
+    index_buffers: Vec<Option<IndexBuffer>>,
+    programs: Vec<Option<Program>>,
+    textures: Vec<Option<Texture>>,
+    pipelines: HashMap<PipelineKey, wgpu::RenderPipeline>,
+    samplers: HashMap<SamplerConfig, wgpu::Sampler>,
+
+    program: Option<Program3DHandle>,
+    attributes:
+        [Option<(VertexBuffer3DHandle, u32, Context3DVertexBufferFormat)>; MAX_VERTEX_ATTRIBUTES],
+    sampler_textures: [Option<Texture3DHandle>; MAX_SAMPLERS],
+    vertex_constants: Vec<f32>,
+    fragment_constants: Vec<f32>,
+    blend_factors: BlendFactors,
+    culling: Context3DTriangleFace,
+    depth_mask: bool,
+    depth_compare: Context3DCompareMode,
+    color_mask: wgpu::ColorWrite,
+    stencil_actions: StencilActions,
+    stencil_reference: u32,
+    stencil_read_mask: u32,
+    stencil_write_mask: u32,
+    scissor_rectangle: Option<(u32, u32, u32, u32)>,
+
+    /// The texture that's drawn into instead of the back buffer, if any.
+    render_to_texture: Option<Texture3DHandle>,
+}
+
+impl WgpuContext3D {
+    pub fn new(
+        device: Arc<wgpu::Device>,
+        queue: Arc<wgpu::Queue>,
+        info: &wgpu::AdapterInfo,
+    ) -> Self {
+        let constants_entry =
+            |binding, visibility, num_constants: usize| wgpu::BindGroupLayoutEntry {
+                binding,
+                visibility,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: wgpu::BufferSize::new(num_constants as u64 * 16),
+                },
+                count: None,
+            };
+        let constants_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: create_debug_label!("Context3D constants bind group layout").as_deref(),
+            entries: &[
+                constants_entry(0, wgpu::ShaderStage::VERTEX, agal::VERTEX_CONSTANTS),
+                constants_entry(1, wgpu::ShaderStage::FRAGMENT, agal::FRAGMENT_CONSTANTS),
+            ],
+        });
+
+        Self {
+            device,
+            queue,
+            driver_info: format!("{} ({:?})", info.name, info.backend),
+            constants_layout,
+            back_buffer: None,
+            vertex_buffers: Vec::new(),
+            index_buffers: Vec::new(),
+            programs: Vec::new(),
+            textures: Vec::new(),
+            pipelines: HashMap::new(),
+            samplers: HashMap::new(),
+            program: None,
+            attributes: [None; MAX_VERTEX_ATTRIBUTES],
+            sampler_textures: [None; MAX_SAMPLERS],
+            vertex_constants: vec![0.0; agal::VERTEX_CONSTANTS * 4],
+            fragment_constants: vec![0.0; agal::FRAGMENT_CONSTANTS * 4],
+            blend_factors: BlendFactors::default(),
+            culling: Context3DTriangleFace::None,
+            depth_mask: true,
+            depth_compare: Context3DCompareMode::Less,
+            color_mask: wgpu::ColorWrite::ALL,
+            stencil_actions: StencilActions::default(),
+            stencil_reference: 0,
+            stencil_read_mask: 0xff,
+            stencil_write_mask: 0xff,
+            scissor_rectangle: None,
+            render_to_texture: None,
+        }
+    }
+
+    /// What was last presented, and its size.
+    pub fn front_buffer(&self) -> Option<(&wgpu::TextureView, u32, u32)> {
+        self.back_buffer.as_ref().map(|back_buffer| {
+            (
+                &back_buffer.front_view,
+                back_buffer.width,
+                back_buffer.height,
+            )
+        })
+    }
+
+    fn back_buffer_target(&self) -> Option<Target<'_>> {
+        self.back_buffer.as_ref().map(|back_buffer| Target {
+            view: &back_buffer.view,
+            attachments: &back_buffer.attachments,
+            width: back_buffer.width,
+            height: back_buffer.height,
+        })
+    }
+
+    /// What's drawn into, unless it's a texture that has been disposed.
+    fn target(&self) -> Option<Target<'_>> {
+        match self.render_to_texture {
+            Some(handle) => {
+                let texture = self.textures.get(handle.0)?.as_ref()?;
+                Some(Target {
+                    view: &texture.render_view,
+                    attachments: texture.attachments.as_ref()?,
+                    width: texture.width,
+                    height: texture.height,
+                })
+            }
+            None => self.back_buffer_target(),
+        }
+    }
+
+    fn clear_target(
+        &self,
+        target: &Target<'_>,
+        color: [f32; 4],
+        depth: f32,
+        stencil: u32,
+        mask: u32,
+    ) {
+        let color = wgpu::Color {
+            r: color[0].into(),
+            g: color[1].into(),
+            b: color[2].into(),
+            a: color[3].into(),
+        };
+        let load = |clear: bool, value| {
+            if clear {
+                wgpu::LoadOp::Clear(value)
+            } else {
+                wgpu::LoadOp::Load
+            }
+        };
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: create_debug_label!("Context3D clear").as_deref(),
+            });
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[target.color_attachment(load(mask & CLEAR_COLOR != 0, color))],
+            depth_stencil_attachment: target.depth_stencil_attachment(
+                load(mask & CLEAR_DEPTH != 0, depth),
+                load(mask & CLEAR_STENCIL != 0, stencil),
+            ),
+        });
+        self.queue.submit(Some(encoder.finish()));
+    }
+
+    fn prepare_pipeline(&mut self, key: &PipelineKey) -> Result<(), Error> {
+        if !self.pipelines.contains_key(key) {
+            let pipeline = self.create_pipeline(key)?;
+            self.pipelines.insert(key.clone(), pipeline);
+        }
+        Ok(())
+    }
+
+    fn create_pipeline(&self, key: &PipelineKey) -> Result<wgpu::RenderPipeline, Error> {
+        let program = self.programs[key.program]
+            .as_ref()
+            .ok_or("The program has been disposed")?;
+
+        let attributes: Vec<[wgpu::VertexAttribute; 1]> = key
+            .attributes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, attribute)| {
+                attribute.map(|(_, offset, format)| {
+                    [wgpu::VertexAttribute {
+                        format: vertex_format(format),
+                        offset: u64::from(offset) * 4,
+                        shader_location: i as u32,
+                    }]
+                })
+            })
+            .collect();
+        let buffers: Vec<wgpu::VertexBufferLayout> = key
+            .attributes
+            .iter()
+            .flatten()
+            .zip(&attributes)
+            .map(|((stride, _, _), attributes)| wgpu::VertexBufferLayout {
+                array_stride: u64::from(*stride) * 4,
+                step_mode: wgpu::InputStepMode::Vertex,
+                attributes,
+            })
+            .collect();
+
+        let blend_component = wgpu::BlendComponent {
+            src_factor: blend_factor(key.blend_factors.source),
+            dst_factor: blend_factor(key.blend_factors.destination),
+            operation: wgpu::BlendOperation::Add,
+        };
+        let cull_mode = match key.culling {
+            Context3DTriangleFace::Back => Some(wgpu::Face::Back),
+            Context3DTriangleFace::Front => Some(wgpu::Face::Front),
+            // Drawing nothing at all is handled by `draw_triangles`.
+            Context3DTriangleFace::FrontAndBack | Context3DTriangleFace::None => None,
+        };
+        let stencil_actions = key.stencil_actions;
+        let stencil_face = |selected: bool| {
+            if selected {
+                wgpu::StencilFaceState {
+                    compare: compare_function(stencil_actions.compare_mode),
+                    fail_op: stencil_operation(stencil_actions.action_on_depth_pass_stencil_fail),
+                    depth_fail_op: stencil_operation(stencil_actions.action_on_depth_fail),
+                    pass_op: stencil_operation(stencil_actions.action_on_both_pass),
+                }
+            } else {
+                wgpu::StencilFaceState::IGNORE
+            }
+        };
+        let depth_stencil = key.has_depth.then(|| wgpu::DepthStencilState {
+            format: DEPTH_FORMAT,
+            depth_write_enabled: key.depth_mask,
+            depth_compare: compare_function(key.depth_compare),
+            stencil: wgpu::StencilState {
+                front: stencil_face(matches!(
+                    stencil_actions.triangle_face,
+                    Context3DTriangleFace::Front | Context3DTriangleFace::FrontAndBack
+                )),
+                back: stencil_face(matches!(
+                    stencil_actions.triangle_face,
+                    Context3DTriangleFace::Back | Context3DTriangleFace::FrontAndBack
+                )),
+                read_mask: key.stencil_read_mask,
+                write_mask: key.stencil_write_mask,
+            },
+            bias: Default::default(),
+        });
+
+        let label = create_debug_label!("Context3D program {} pipeline", key.program);
+        Ok(self
+            .device
+            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: label.as_deref(),
+                layout: Some(&program.pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &program.vertex_module,
+                    entry_point: "main",
+                    buffers: &buffers,
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &program.fragment_module,
+                    entry_point: "main",
+                    targets: &[wgpu::ColorTargetState {
+                        format: TEXTURE_FORMAT,
+                        blend: Some(wgpu::BlendState {
+                            color: blend_component,
+                            alpha: blend_component,
+                        }),
+                        write_mask: key.color_mask,
+                    }],
+                }),
+                // Stage3D's front faces are wound clockwise.
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Cw,
+                    cull_mode,
+                    polygon_mode: wgpu::PolygonMode::default(),
+                    clamp_depth: false,
+                    conservative: false,
+                },
+                depth_stencil,
+                multisample: wgpu::MultisampleState {
+                    count: key.sample_count,
+                    ..Default::default()
+                },
+            }))
+    }
+
+    fn prepare_sampler(&mut self, config: SamplerConfig) {
+        let device = &self.device;
+        self.samplers.entry(config).or_insert_with(|| {
+            let filter = match config.filter {
+                Filter::Nearest => wgpu::FilterMode::Nearest,
+                Filter::Linear => wgpu::FilterMode::Linear,
+            };
+            let (mipmap_filter, lod_max_clamp) = match config.mipmap {
+                Mipmap::Disable => (wgpu::FilterMode::Nearest, 0.0),
+                Mipmap::Nearest => (wgpu::FilterMode::Nearest, 100.0),
+                Mipmap::Linear => (wgpu::FilterMode::Linear, 100.0),
+            };
+            let (address_mode_u, address_mode_v) = match config.wrapping {
+                Wrapping::Clamp => (
+                    wgpu::AddressMode::ClampToEdge,
+                    wgpu::AddressMode::ClampToEdge,
+                ),
+                Wrapping::Repeat => (wgpu::AddressMode::Repeat, wgpu::AddressMode::Repeat),
+                Wrapping::ClampURepeatV => {
+                    (wgpu::AddressMode::ClampToEdge, wgpu::AddressMode::Repeat)
+                }
+                Wrapping::RepeatUClampV => {
+                    (wgpu::AddressMode::Repeat, wgpu::AddressMode::ClampToEdge)
+                }
+            };
+            device.create_sampler(&wgpu::SamplerDescriptor {
+                label: create_debug_label!("Context3D sampler {:?}", config).as_deref(),
+                address_mode_u,
+                address_mode_v,
+                address_mode_w: wgpu::AddressMode::ClampToEdge,
+                mag_filter: filter,
+                min_filter: filter,
+                mipmap_filter,
+                lod_min_clamp: 0.0,
+                lod_max_clamp,
+                compare: None,
+                anisotropy_clamp: None,
+                border_color: None,
+            })
+        });
+    }
+}
+
+impl Context3D for WgpuContext3D {
+    fn driver_info(&self) -> String {
+        self.driver_info.clone()
+    }
+
+    fn configure_back_buffer(
+        &mut self,
+        width: u32,
+        height: u32,
+        anti_alias: u32,
+        enable_depth_and_stencil: bool,
+    ) {
+        let size = wgpu::Extent3d {
+            width: width.max(1),
+            height: height.max(1),
+            depth_or_array_layers: 1,
+        };
+        let create_texture = |label: Option<String>, format, usage| {
+            self.device.create_texture(&wgpu::TextureDescriptor {
+                label: label.as_deref(),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage,
+            })
+        };
+
+        let texture = create_texture(
+            create_debug_label!("Context3D back buffer"),
+            TEXTURE_FORMAT,
+            wgpu::TextureUsage::RENDER_ATTACHMENT | wgpu::TextureUsage::COPY_SRC,
+        );
+        let front_texture = create_texture(
+            create_debug_label!("Context3D front buffer"),
+            TEXTURE_FORMAT,
+            wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+        );
+
+        self.back_buffer = Some(BackBuffer {
+            width: size.width,
+            height: size.height,
+            view: texture.create_view(&Default::default()),
+            texture,
+            attachments: Attachments::new(
+                &self.device,
+                size.width,
+                size.height,
+                anti_alias,
+                enable_depth_and_stencil,
+            ),
+            front_view: front_texture.create_view(&Default::default()),
+            front_texture,
+        });
+        if let Some(target) = self.back_buffer_target() {
+            self.clear_target(
+                &target,
+                [0.0; 4],
+                1.0,
+                0,
+                CLEAR_COLOR | CLEAR_DEPTH | CLEAR_STENCIL,
+            );
+        }
+    }
+
+    fn clear(&mut self, color: [f32; 4], depth: f32, stencil: u32, mask: u32) {
+        if let Some(target) = self.target() {
+            self.clear_target(&target, color, depth, stencil, mask);
+        }
+    }
+
+    fn create_vertex_buffer(
+        &mut self,
+        num_vertices: u32,
+        data32_per_vertex: u32,
+    ) -> VertexBuffer3DHandle {
+        let size = u64::from(num_vertices) * u64::from(data32_per_vertex) * 4;
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: create_debug_label!("Context3D vertex buffer").as_deref(),
+            size,
+            usage: wgpu::BufferUsage::VERTEX | wgpu::BufferUsage::COPY_DST,
+            mapped_at_creation: false,
+        });
+        self.vertex_buffers.push(Some(VertexBuffer {
+            buffer,
+            size,
+            data32_per_vertex,
+        }));
+        VertexBuffer3DHandle(self.vertex_buffers.len() - 1)
+    }
+
+    fn upload_vertex_buffer(
+        &mut self,
+        buffer: VertexBuffer3DHandle,
+        start_vertex: u32,
+        data: &[u8],
+    ) {
+        if let Some(Some(buffer)) = self.vertex_buffers.get(buffer.0) {
+            let offset = u64::from(start_vertex) * u64::from(buffer.data32_per_vertex) * 4;
+            let len = (data.len() as u64).min(buffer.size.saturating_sub(offset)) & !3;
+            if len > 0 {
+                self.queue
+                    .write_buffer(&buffer.buffer, offset, &data[..len as usize]);
+            }
+        }
+    }
+
+    fn create_index_buffer(&mut self, num_indices: u32) -> IndexBuffer3DHandle {
+        // Keep the size a whole number of 32-bit values.
+        let indices = vec![0; (num_indices as usize + 1) & !1];
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: create_debug_label!("Context3D index buffer").as_deref(),
+            size: indices.len() as u64 * 2,
+            usage: wgpu::BufferUsage::INDEX | wgpu::BufferUsage::COPY_DST,
+            mapped_at_creation: false,
+        });
+        self.index_buffers
+            .push(Some(IndexBuffer { buffer, indices }));
+        IndexBuffer3DHandle(self.index_buffers.len() - 1)
+    }
+
+    fn upload_index_buffer(
+        &mut self,
+        buffer: IndexBuffer3DHandle,
+        start_offset: u32,
+        data: &[u16],
+    ) {
+        if let Some(Some(buffer)) = self.index_buffers.get_mut(buffer.0) {
+            let start = start_offset as usize;
+            if start >= buffer.indices.len() {
+                return;
+            }
+            let end = (start + data.len()).min(buffer.indices.len());
+            buffer.indices[start..end].copy_from_slice(&data[..end - start]);
+
+            // Writes must start and end on 32-bit boundaries.
+            let start = start & !1;
+            let end = (end + 1) & !1;
+            self.queue.write_buffer(
+                &buffer.buffer,
+                start as u64 * 2,
+                bytemuck::cast_slice(&buffer.indices[start..end]),
+            );
+        }
+    }
+
+    fn create_program(&mut self) -> Program3DHandle {
+        self.programs.push(None);
+        Program3DHandle(self.programs.len() - 1)
+    }
+
+    fn upload_program(
+        &mut self,
+        program: Program3DHandle,
+        vertex_program: &[u8],
+        fragment_program: &[u8],
+    ) -> Result<(), Error> {
+        if program.0 >= self.programs.len() {
+            return Err("The program has been disposed".into());
+        }
+        let translated = agal::translate(vertex_program, fragment_program)?;
+
+        let create_module = |label: Option<String>, source: &str| {
+            self.device
+                .create_shader_module(&wgpu::ShaderModuleDescriptor {
+                    label: label.as_deref(),
+                    source: wgpu::ShaderSource::Wgsl(Cow::Owned(source.to_string())),
+                    flags: wgpu::ShaderFlags::all(),
+                })
+        };
+        let vertex_module = create_module(
+            create_debug_label!("Context3D program {} vertex shader", program.0),
+            &translated.vertex_shader,
+        );
+        let fragment_module = create_module(
+            create_debug_label!("Context3D program {} fragment shader", program.0),
+            &translated.fragment_shader,
+        );
+
+        let texture_entries: Vec<wgpu::BindGroupLayoutEntry> = translated
+            .samplers
+            .iter()
+            .enumerate()
+            .filter(|(_, config)| config.is_some())
+            .flat_map(|(i, _)| {
+                let binding = i as u32 * 2;
+                vec![
+                    wgpu::BindGroupLayoutEntry {
+                        binding,
+                        visibility: wgpu::ShaderStage::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: binding + 1,
+                        visibility: wgpu::ShaderStage::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler {
+                            filtering: true,
+                            comparison: false,
+                        },
+                        count: None,
+                    },
+                ]
+            })
+            .collect();
+        let texture_layout =
+            self.device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: create_debug_label!("Context3D program {} textures layout", program.0)
+                        .as_deref(),
+                    entries: &texture_entries,
+                });
+        let pipeline_layout = self
+            .device
+            .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: create_debug_label!("Context3D program {} pipeline layout", program.0)
+                    .as_deref(),
+                bind_group_layouts: &[&self.constants_layout, &texture_layout],
+                push_constant_ranges: &[],
+            });
+
+        // Pipelines made with the old program are stale.
+        self.pipelines.retain(|key, _| key.program != program.0);
+        self.programs[program.0] = Some(Program {
+            translated,
+            vertex_module,
+            fragment_module,
+            texture_layout,
+            pipeline_layout,
+        });
+        Ok(())
+    }
+
+    fn create_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: Context3DTextureFormat,
+        _optimize_for_render_to_texture: bool,
+    ) -> Result<Texture3DHandle, Error> {
+        // Compressed textures are uploaded after they've been decoded.
+        if format == Context3DTextureFormat::RgbaHalfFloat {
+            return Err(format!("Texture format {:?} is not supported", format).into());
+        }
+
+        let mip_level_count = 32 - width.max(height).max(1).leading_zeros();
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: create_debug_label!("Context3D texture {}", self.textures.len()).as_deref(),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: TEXTURE_FORMAT,
+            usage: wgpu::TextureUsage::SAMPLED
+                | wgpu::TextureUsage::COPY_DST
+                | wgpu::TextureUsage::RENDER_ATTACHMENT,
+        });
+        self.textures.push(Some(Texture {
+            width,
+            height,
+            mip_level_count,
+            view: texture.create_view(&Default::default()),
+            render_view: texture.create_view(&wgpu::TextureViewDescriptor {
+                mip_level_count: std::num::NonZeroU32::new(1),
+                ..Default::default()
+            }),
+            texture,
+            attachments: None,
+        }));
+        Ok(Texture3DHandle(self.textures.len() - 1))
+    }
+
+    fn upload_texture(&mut self, texture: Texture3DHandle, mip_level: u32, rgba: &[u8]) {
+        if let Some(Some(texture)) = self.textures.get(texture.0) {
+            let width = (texture.width >> mip_level).max(1);
+            let height = (texture.height >> mip_level).max(1);
+            if mip_level >= texture.mip_level_count || rgba.len() < (width * height * 4) as usize {
+                return;
+            }
+            self.queue.write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &texture.texture,
+                    mip_level,
+                    origin: wgpu::Origin3d::ZERO,
+                },
+                rgba,
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(width * 4),
+                    rows_per_image: None,
+                },
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+    }
+
+    fn dispose_vertex_buffer(&mut self, buffer: VertexBuffer3DHandle) {
+        if let Some(buffer) = self.vertex_buffers.get_mut(buffer.0) {
+            *buffer = None;
+        }
+    }
+
+    fn dispose_index_buffer(&mut self, buffer: IndexBuffer3DHandle) {
+        if let Some(buffer) = self.index_buffers.get_mut(buffer.0) {
+            *buffer = None;
+        }
+    }
+
+    fn dispose_program(&mut self, program: Program3DHandle) {
+        if let Some(slot) = self.programs.get_mut(program.0) {
+            *slot = None;
+            self.pipelines.retain(|key, _| key.program != program.0);
+        }
+    }
+
+    fn dispose_texture(&mut self, texture: Texture3DHandle) {
+        if let Some(texture) = self.textures.get_mut(texture.0) {
+            *texture = None;
+        }
+    }
+
+    fn set_program(&mut self, program: Option<Program3DHandle>) {
+        self.program = program;
+    }
+
+    fn set_vertex_buffer_at(
+        &mut self,
+        index: usize,
+        buffer: Option<(VertexBuffer3DHandle, u32, Context3DVertexBufferFormat)>,
+    ) {
+        if let Some(attribute) = self.attributes.get_mut(index) {
+            *attribute = buffer;
+        }
+    }
+
+    fn set_program_constants(
+        &mut self,
+        program_type: Context3DProgramType,
+        first_register: u32,
+        values: &[f32],
+    ) {
+        let constants = match program_type {
+            Context3DProgramType::Vertex => &mut self.vertex_constants,
+            Context3DProgramType::Fragment => &mut self.fragment_constants,
+        };
+        let start = (first_register as usize * 4).min(constants.len());
+        let end = (start + values.len()).min(constants.len());
+        constants[start..end].copy_from_slice(&values[..end - start]);
+    }
+
+    fn set_texture_at(&mut self, sampler: usize, texture: Option<Texture3DHandle>) {
+        if let Some(slot) = self.sampler_textures.get_mut(sampler) {
+            *slot = texture;
+        }
+    }
+
+    fn set_blend_factors(&mut self, blend_factors: BlendFactors) {
+        self.blend_factors = blend_factors;
+    }
+
+    fn set_culling(&mut self, face: Context3DTriangleFace) {
+        self.culling = face;
+    }
+
+    fn set_depth_test(&mut self, depth_mask: bool, pass_compare_mode: Context3DCompareMode) {
+        self.depth_mask = depth_mask;
+        self.depth_compare = pass_compare_mode;
+    }
+
+    fn set_color_mask(&mut self, red: bool, green: bool, blue: bool, alpha: bool) {
+        let mut color_mask = wgpu::ColorWrite::empty();
+        color_mask.set(wgpu::ColorWrite::RED, red);
+        color_mask.set(wgpu::ColorWrite::GREEN, green);
+        color_mask.set(wgpu::ColorWrite::BLUE, blue);
+        color_mask.set(wgpu::ColorWrite::ALPHA, alpha);
+        self.color_mask = color_mask;
+    }
+
+    fn set_stencil_actions(&mut self, actions: StencilActions) {
+        self.stencil_actions = actions;
+    }
+
+    fn set_stencil_reference_value(&mut self, reference: u32, read_mask: u32, write_mask: u32) {
+        self.stencil_reference = reference;
+        self.stencil_read_mask = read_mask;
+        self.stencil_write_mask = write_mask;
+    }
+
+    fn set_render_to_texture(
+        &mut self,
+        texture: Texture3DHandle,
+        enable_depth_and_stencil: bool,
+        anti_alias: u32,
+    ) -> Result<(), Error> {
+        let device = &self.device;
+        let target = self
+            .textures
+            .get_mut(texture.0)
+            .and_then(Option::as_mut)
+            .ok_or("The texture has been disposed")?;
+
+        // The attachments are kept between uses of the texture, as long as
+        // they're still what's asked for.
+        let sample_count = if anti_alias > 0 { MSAA_SAMPLE_COUNT } else { 1 };
+        let reusable = target.attachments.as_ref().map_or(false, |attachments| {
+            attachments.sample_count == sample_count
+                && attachments.depth_view.is_some() == enable_depth_and_stencil
+        });
+        if !reusable {
+            target.attachments = Some(Attachments::new(
+                device,
+                target.width,
+                target.height,
+                anti_alias,
+                enable_depth_and_stencil,
+            ));
+        }
+
+        self.render_to_texture = Some(texture);
+        Ok(())
+    }
+
+    fn set_render_to_back_buffer(&mut self) {
+        self.render_to_texture = None;
+    }
+
+    fn set_scissor_rectangle(&mut self, rectangle: Option<(u32, u32, u32, u32)>) {
+        self.scissor_rectangle = rectangle;
+    }
+
+    fn draw_triangles(
+        &mut self,
+        index_buffer: IndexBuffer3DHandle,
+        first_index: u32,
+        num_triangles: Option<u32>,
+    ) -> Result<(), Error> {
+        let (width, height, has_depth, sample_count) = match self.target() {
+            Some(target) => (
+                target.width,
+                target.height,
+                target.attachments.depth_view.is_some(),
+                target.attachments.sample_count,
+            ),
+            None if self.render_to_texture.is_some() => {
+                return Err("The texture being rendered to has been disposed".into())
+            }
+            None => return Err("The back buffer has not been configured".into()),
+        };
+        let program_handle = self.program.ok_or("No program has been set")?;
+        let program = self
+            .programs
+            .get(program_handle.0)
+            .and_then(Option::as_ref)
+            .ok_or("The program has not been uploaded")?;
+
+        let num_indices = self
+            .index_buffers
+            .get(index_buffer.0)
+            .and_then(Option::as_ref)
+            .ok_or("The index buffer has been disposed")?
+            .indices
+            .len() as u32;
+        let count = match num_triangles {
+            Some(num_triangles) => num_triangles.saturating_mul(3),
+            None => num_indices.saturating_sub(first_index),
+        };
+        if first_index.saturating_add(count) > num_indices {
+            return Err("Drawing past the end of the index buffer".into());
+        }
+        if count == 0 || self.culling == Context3DTriangleFace::FrontAndBack {
+            return Ok(());
+        }
+
+        let mut attributes = [None; MAX_VERTEX_ATTRIBUTES];
+        let mut vertex_buffers = Vec::new();
+        for (i, used) in program.translated.attributes.iter().enumerate() {
+            if !used {
+                continue;
+            }
+            let (handle, offset, format) = self.attributes[i]
+                .ok_or_else(|| format!("No vertex buffer has been set for attribute {}", i))?;
+            let buffer = self
+                .vertex_buffers
+                .get(handle.0)
+                .and_then(Option::as_ref)
+                .ok_or("The vertex buffer has been disposed")?;
+            attributes[i] = Some((buffer.data32_per_vertex, offset, format));
+            vertex_buffers.push(handle);
+        }
+
+        let mut textures = Vec::new();
+        for (i, config) in program.translated.samplers.iter().enumerate() {
+            if let Some(config) = config {
+                let handle = self.sampler_textures[i]
+                    .filter(|handle| matches!(self.textures.get(handle.0), Some(Some(_))))
+                    .ok_or_else(|| format!("No texture has been set for sampler {}", i))?;
+                if Some(handle) == self.render_to_texture {
+                    return Err(
+                        format!("The texture of sampler {} is also being rendered to", i).into(),
+                    );
+                }
+                textures.push((i as u32, handle, *config));
+            }
+        }
+
+        let vertex_constants = create_buffer_with_data(
+            &self.device,
+            bytemuck::cast_slice(&self.vertex_constants),
+            wgpu::BufferUsage::UNIFORM,
+            create_debug_label!("Context3D vertex constants"),
+        );
+        let fragment_constants = create_buffer_with_data(
+            &self.device,
+            bytemuck::cast_slice(&self.fragment_constants),
+            wgpu::BufferUsage::UNIFORM,
+            create_debug_label!("Context3D fragment constants"),
+        );
+        let constants_bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: create_debug_label!("Context3D constants bind group").as_deref(),
+            layout: &self.constants_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: vertex_constants.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: fragment_constants.as_entire_binding(),
+                },
+            ],
+        });
+
+        let key = PipelineKey {
+            program: program_handle.0,
+            attributes,
+            blend_factors: self.blend_factors,
+            culling: self.culling,
+            depth_mask: self.depth_mask,
+            depth_compare: self.depth_compare,
+            color_mask: self.color_mask,
+            stencil_actions: self.stencil_actions,
+            stencil_read_mask: self.stencil_read_mask,
+            stencil_write_mask: self.stencil_write_mask,
+            has_depth,
+            sample_count,
+        };
+        self.prepare_pipeline(&key)?;
+        for (_, _, config) in &textures {
+            self.prepare_sampler(*config);
+        }
+
+        let program = self.programs[program_handle.0].as_ref().unwrap();
+        let texture_entries: Vec<wgpu::BindGroupEntry> = textures
+            .iter()
+            .flat_map(|(i, handle, config)| {
+                let texture = self.textures[handle.0].as_ref().unwrap();
+                vec![
+                    wgpu::BindGroupEntry {
+                        binding: i * 2,
+                        resource: wgpu::BindingResource::TextureView(&texture.view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: i * 2 + 1,
+                        resource: wgpu::BindingResource::Sampler(&self.samplers[config]),
+                    },
+                ]
+            })
+            .collect();
+        let texture_bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: create_debug_label!("Context3D textures bind group").as_deref(),
+            layout: &program.texture_layout,
+            entries: &texture_entries,
+        });
+
+        let target = self.target().unwrap();
+        let index_buffer = self.index_buffers[index_buffer.0].as_ref().unwrap();
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: create_debug_label!("Context3D draw").as_deref(),
+            });
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[target.color_attachment(wgpu::LoadOp::Load)],
+                depth_stencil_attachment: target
+                    .depth_stencil_attachment(wgpu::LoadOp::Load, wgpu::LoadOp::Load),
+            });
+
+            render_pass.set_pipeline(&self.pipelines[&key]);
+            if has_depth {
+                render_pass.set_stencil_reference(self.stencil_reference);
+            }
+            render_pass.set_bind_group(0, &constants_bind_group, &[]);
+            render_pass.set_bind_group(1, &texture_bind_group, &[]);
+            for (slot, handle) in vertex_buffers.iter().enumerate() {
+                let buffer = self.vertex_buffers[handle.0].as_ref().unwrap();
+                render_pass.set_vertex_buffer(slot as u32, buffer.buffer.slice(..));
+            }
+            render_pass.set_index_buffer(index_buffer.buffer.slice(..), wgpu::IndexFormat::Uint16);
+            if let Some((x, y, scissor_width, scissor_height)) = self.scissor_rectangle {
+                let x = x.min(width);
+                let y = y.min(height);
+                render_pass.set_scissor_rect(
+                    x,
+                    y,
+                    scissor_width.min(width - x),
+                    scissor_height.min(height - y),
+                );
+            }
+            render_pass.draw_indexed(first_index..first_index + count, 0, 0..1);
+        }
+        self.queue.submit(Some(encoder.finish()));
+        Ok(())
+    }
+
+    fn present(&mut self) {
+        if let Some(back_buffer) = &self.back_buffer {
+            let mut encoder = self
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: create_debug_label!("Context3D present").as_deref(),
+                });
+            encoder.copy_texture_to_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &back_buffer.texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                },
+                wgpu::ImageCopyTexture {
+                    texture: &back_buffer.front_texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                },
+                wgpu::Extent3d {
+                    width: back_buffer.width,
+                    height: back_buffer.height,
+                    depth_or_array_layers: 1,
+                },
+            );
+            self.queue.submit(Some(encoder.finish()));
+        }
+    }
+}
+
+fn vertex_format(format: Context3DVertexBufferFormat) -> wgpu::VertexFormat {
+    match format {
+        Context3DVertexBufferFormat::Float1 => wgpu::VertexFormat::Float32,
+        Context3DVertexBufferFormat::Float2 => wgpu::VertexFormat::Float32x2,
+        Context3DVertexBufferFormat::Float3 => wgpu::VertexFormat::Float32x3,
+        Context3DVertexBufferFormat::Float4 => wgpu::VertexFormat::Float32x4,
+        Context3DVertexBufferFormat::Bytes4 => wgpu::VertexFormat::Unorm8x4,
+    }
+}
+
+fn blend_factor(factor: Context3DBlendFactor) -> wgpu::BlendFactor {
+    match factor {
+        Context3DBlendFactor::DestinationAlpha => wgpu::BlendFactor::DstAlpha,
+        Context3DBlendFactor::DestinationColor => wgpu::BlendFactor::Dst,
+        Context3DBlendFactor::One => wgpu::BlendFactor::One,
+        Context3DBlendFactor::OneMinusDestinationAlpha => wgpu::BlendFactor::OneMinusDstAlpha,
+        Context3DBlendFactor::OneMinusDestinationColor => wgpu::BlendFactor::OneMinusDst,
+        Context3DBlendFactor::OneMinusSourceAlpha => wgpu::BlendFactor::OneMinusSrcAlpha,
+        Context3DBlendFactor::OneMinusSourceColor => wgpu::BlendFactor::OneMinusSrc,
+        Context3DBlendFactor::SourceAlpha => wgpu::BlendFactor::SrcAlpha,
+        Context3DBlendFactor::SourceColor => wgpu::BlendFactor::Src,
+        Context3DBlendFactor::Zero => wgpu::BlendFactor::Zero,
+    }
+}
+
+fn stencil_operation(action: Context3DStencilAction) -> wgpu::StencilOperation {
+    match action {
+        Context3DStencilAction::DecrementSaturate => wgpu::StencilOperation::DecrementClamp,
+        Context3DStencilAction::DecrementWrap => wgpu::StencilOperation::DecrementWrap,
+        Context3DStencilAction::IncrementSaturate => wgpu::StencilOperation::IncrementClamp,
+        Context3DStencilAction::IncrementWrap => wgpu::StencilOperation::IncrementWrap,
+        Context3DStencilAction::Invert => wgpu::StencilOperation::Invert,
+        Context3DStencilAction::Keep => wgpu::StencilOperation::Keep,
+        Context3DStencilAction::Set => wgpu::StencilOperation::Replace,
+        Context3DStencilAction::Zero => wgpu::StencilOperation::Zero,
+    }
+}
+
+fn compare_function(mode: Context3DCompareMode) -> wgpu::CompareFunction {
+    match mode {
+        Context3DCompareMode::Always => wgpu::CompareFunction::Always,
+        Context3DCompareMode::Equal => wgpu::CompareFunction::Equal,
+        Context3DCompareMode::Greater => wgpu::CompareFunction::Greater,
+        Context3DCompareMode::GreaterEqual => wgpu::CompareFunction::GreaterEqual,
+        Context3DCompareMode::Less => wgpu::CompareFunction::Less,
+        Context3DCompareMode::LessEqual => wgpu::CompareFunction::LessEqual,
+        Context3DCompareMode::Never => wgpu::CompareFunction::Never,
+        Context3DCompareMode::NotEqual => wgpu::CompareFunction::NotEqual,
+    }
+}
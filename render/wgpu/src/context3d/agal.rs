@@ -0,0 +1,788 @@
+//! Translation of AGAL, the bytecode of Stage3D programs, into WGSL.
+//!
+//! Every AGAL register is a `vec4<f32>`. Each instruction becomes a statement
+//! computing all four components of its result, which are then written to the
+//! components of its destination that are in its write mask.
+
+use crate::Error;
+use std::convert::TryInto;
+use std::fmt::Write;
+
+/// The number of registers that vertex programs pass to fragment programs.
+pub const MAX_VARYINGS: usize = 8;
+
+/// The number of constant registers of each program type, which is the most
+/// that any AGAL version allows.
+pub const VERTEX_CONSTANTS: usize = 250;
+pub const FRAGMENT_CONSTANTS: usize = 64;
+
+/// The number of vertex attributes and texture samplers.
+const MAX_ATTRIBUTES: usize = 8;
+const MAX_SAMPLERS: usize = 8;
+
+/// The number of temporary registers, which is the most that any AGAL
+/// version allows.
+const MAX_TEMPORARIES: usize = 26;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum ShaderType {
+    Vertex,
+    Fragment,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum RegisterType {
+    Attribute,
+    Constant,
+    Temporary,
+    Output,
+    Varying,
+    Sampler,
+}
+
+impl RegisterType {
+    fn from_u8(value: u8) -> Result<Self, Error> {
+        Ok(match value {
+            0 => Self::Attribute,
+            1 => Self::Constant,
+            2 => Self::Temporary,
+            3 => Self::Output,
+            4 => Self::Varying,
+            5 => Self::Sampler,
+            _ => return Err(format!("Unsupported AGAL register type {}", value).into()),
+        })
+    }
+}
+
+/// How a texture is filtered when it's sampled.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Filter {
+    Nearest,
+    Linear,
+}
+
+/// How a texture's mip levels are sampled.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Mipmap {
+    Disable,
+    Nearest,
+    Linear,
+}
+
+/// What happens to texture coordinates outside of the texture.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Wrapping {
+    Clamp,
+    Repeat,
+    ClampURepeatV,
+    RepeatUClampV,
+}
+
+/// How a program samples one of its textures, as given by the first `tex`
+/// instruction that samples it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SamplerConfig {
+    pub filter: Filter,
+    pub mipmap: Mipmap,
+    pub wrapping: Wrapping,
+}
+
+/// A pair of AGAL programs translated into WGSL.
+#[derive(Debug)]
+pub struct TranslatedProgram {
+    pub vertex_shader: String,
+    pub fragment_shader: String,
+
+    /// Which vertex attributes the vertex program reads.
+    pub attributes: [bool; MAX_ATTRIBUTES],
+
+    /// The textures that the fragment program samples.
+    pub samplers: [Option<SamplerConfig>; MAX_SAMPLERS],
+}
+
+/// Translate a vertex program and a fragment program into WGSL.
+pub fn translate(
+    vertex_program: &[u8],
+    fragment_program: &[u8],
+) -> Result<TranslatedProgram, Error> {
+    let mut vertex = Translator::new(ShaderType::Vertex);
+    vertex.translate(vertex_program)?;
+    let mut fragment = Translator::new(ShaderType::Fragment);
+    fragment.translate(fragment_program)?;
+
+    Ok(TranslatedProgram {
+        vertex_shader: vertex.finish_vertex(),
+        fragment_shader: fragment.finish_fragment(),
+        attributes: vertex.attributes,
+        samplers: fragment.samplers,
+    })
+}
+
+/// A register read by an instruction.
+#[derive(Debug)]
+struct Source {
+    register_type: RegisterType,
+    number: u16,
+    swizzle: u8,
+
+    /// For indirectly addressed registers, the register and component that's
+    /// added to the offset to give the register number.
+    indirect: Option<(RegisterType, u16, u8)>,
+}
+
+impl Source {
+    fn parse(value: u64) -> Result<Self, Error> {
+        let number = value as u16;
+        let offset = (value >> 16) as u8;
+        let swizzle = (value >> 24) as u8;
+        let register_type = RegisterType::from_u8((value >> 32) as u8 & 0xf)?;
+        let indirect = if value >> 63 != 0 {
+            let index_type = RegisterType::from_u8((value >> 40) as u8 & 0xf)?;
+            let component = (value >> 48) as u8 & 0x3;
+            Some((index_type, number, component))
+        } else {
+            None
+        };
+        Ok(Self {
+            register_type,
+            number: if indirect.is_some() {
+                offset.into()
+            } else {
+                number
+            },
+            swizzle,
+            indirect,
+        })
+    }
+}
+
+/// The texture sampler read by a `tex` instruction.
+#[derive(Debug)]
+struct SamplerSource {
+    number: u16,
+    lod_bias: i8,
+    config: SamplerConfig,
+}
+
+impl SamplerSource {
+    fn parse(value: u64) -> Result<Self, Error> {
+        let dimension = (value >> 44) & 0xf;
+        if dimension != 0 {
+            return Err("Only 2D textures can be sampled".into());
+        }
+        let wrapping = match (value >> 52) & 0xf {
+            0 => Wrapping::Clamp,
+            1 => Wrapping::Repeat,
+            2 => Wrapping::ClampURepeatV,
+            3 => Wrapping::RepeatUClampV,
+            wrapping => return Err(format!("Unknown AGAL texture wrapping {}", wrapping).into()),
+        };
+        let mipmap = match (value >> 56) & 0xf {
+            0 => Mipmap::Disable,
+            1 => Mipmap::Nearest,
+            2 => Mipmap::Linear,
+            mipmap => return Err(format!("Unknown AGAL mipmap mode {}", mipmap).into()),
+        };
+        // Anisotropic filtering is treated as linear.
+        let filter = match value >> 60 {
+            0 => Filter::Nearest,
+            _ => Filter::Linear,
+        };
+        Ok(Self {
+            number: value as u16,
+            lod_bias: (value >> 16) as u8 as i8,
+            config: SamplerConfig {
+                filter,
+                mipmap,
+                wrapping,
+            },
+        })
+    }
+}
+
+struct Translator {
+    shader_type: ShaderType,
+    body: String,
+    indent: usize,
+    num_instructions: usize,
+    temporaries: [bool; MAX_TEMPORARIES],
+    attributes: [bool; MAX_ATTRIBUTES],
+    samplers: [Option<SamplerConfig>; MAX_SAMPLERS],
+}
+
+impl Translator {
+    fn new(shader_type: ShaderType) -> Self {
+        Self {
+            shader_type,
+            body: String::new(),
+            indent: 1,
+            num_instructions: 0,
+            temporaries: [false; MAX_TEMPORARIES],
+            attributes: [false; MAX_ATTRIBUTES],
+            samplers: [None; MAX_SAMPLERS],
+        }
+    }
+
+    fn translate(&mut self, bytecode: &[u8]) -> Result<(), Error> {
+        if bytecode.len() < 7 || bytecode[0] != 0xa0 || bytecode[5] != 0xa1 {
+            return Err("AGAL program has no valid header".into());
+        }
+        let shader_type = match bytecode[6] {
+            0 => ShaderType::Vertex,
+            1 => ShaderType::Fragment,
+            _ => return Err("AGAL program has an unknown program type".into()),
+        };
+        if shader_type != self.shader_type {
+            return Err("AGAL program is of the wrong program type".into());
+        }
+
+        let tokens = &bytecode[7..];
+        if tokens.len() % 24 != 0 {
+            return Err("AGAL program ends partway through an instruction".into());
+        }
+        for token in tokens.chunks_exact(24) {
+            let opcode = u32::from_le_bytes(token[0..4].try_into().unwrap());
+            let destination = u32::from_le_bytes(token[4..8].try_into().unwrap());
+            let source1 = u64::from_le_bytes(token[8..16].try_into().unwrap());
+            let source2 = u64::from_le_bytes(token[16..24].try_into().unwrap());
+            self.instruction(opcode, destination, source1, source2)?;
+        }
+
+        if self.indent != 1 {
+            return Err("AGAL program has an unterminated conditional".into());
+        }
+        Ok(())
+    }
+
+    fn instruction(
+        &mut self,
+        opcode: u32,
+        destination: u32,
+        source1: u64,
+        source2: u64,
+    ) -> Result<(), Error> {
+        let s1 = || Source::parse(source1);
+        let s2 = || Source::parse(source2);
+
+        let value = match opcode {
+            0x00 => self.source(&s1()?)?,
+            0x01 => format!("{} + {}", self.source(&s1()?)?, self.source(&s2()?)?),
+            0x02 => format!("{} - {}", self.source(&s1()?)?, self.source(&s2()?)?),
+            0x03 => format!("{} * {}", self.source(&s1()?)?, self.source(&s2()?)?),
+            0x04 => format!("{} / {}", self.source(&s1()?)?, self.source(&s2()?)?),
+            0x05 => format!("{} / {}", splat("1.0"), self.source(&s1()?)?),
+            0x06 => self.call("min", &[s1()?, s2()?])?,
+            0x07 => self.call("max", &[s1()?, s2()?])?,
+            0x08 => self.call("fract", &[s1()?])?,
+            0x09 => self.call("sqrt", &[s1()?])?,
+            0x0a => self.call("inverseSqrt", &[s1()?])?,
+            0x0b => self.call("pow", &[s1()?, s2()?])?,
+            0x0c => self.call("log2", &[s1()?])?,
+            0x0d => self.call("exp2", &[s1()?])?,
+            0x0e => format!("vec4<f32>(normalize(({}).xyz), 0.0)", self.source(&s1()?)?),
+            0x0f => self.call("sin", &[s1()?])?,
+            0x10 => self.call("cos", &[s1()?])?,
+            0x11 => format!(
+                "vec4<f32>(cross(({}).xyz, ({}).xyz), 0.0)",
+                self.source(&s1()?)?,
+                self.source(&s2()?)?
+            ),
+            0x12 => {
+                let dot = format!(
+                    "dot(({}).xyz, ({}).xyz)",
+                    self.source(&s1()?)?,
+                    self.source(&s2()?)?
+                );
+                splat(&dot)
+            }
+            0x13 => {
+                let dot = format!("dot({}, {})", self.source(&s1()?)?, self.source(&s2()?)?);
+                splat(&dot)
+            }
+            0x14 => self.call("abs", &[s1()?])?,
+            0x15 => format!("-({})", self.source(&s1()?)?),
+            0x16 => format!(
+                "clamp({}, {}, {})",
+                self.source(&s1()?)?,
+                splat("0.0"),
+                splat("1.0")
+            ),
+            0x17 => self.matrix(&s1()?, &s2()?, 3, 3)?,
+            0x18 => self.matrix(&s1()?, &s2()?, 4, 4)?,
+            0x19 => self.matrix(&s1()?, &s2()?, 4, 3)?,
+            0x1a | 0x1b => {
+                if self.shader_type != ShaderType::Fragment {
+                    return Err("Derivatives can only be taken in fragment programs".into());
+                }
+                let function = if opcode == 0x1a { "dpdx" } else { "dpdy" };
+                self.call(function, &[s1()?])?
+            }
+            0x1c..=0x1f => {
+                let operator = match opcode {
+                    0x1c => "==",
+                    0x1d => "!=",
+                    0x1e => ">",
+                    _ => "<",
+                };
+                let condition = format!(
+                    "({}).x {} ({}).x",
+                    self.source(&s1()?)?,
+                    operator,
+                    self.source(&s2()?)?
+                );
+                self.line(&format!("if ({}) {{", condition));
+                self.indent += 1;
+                return Ok(());
+            }
+            0x20 => {
+                if self.indent <= 1 {
+                    return Err("AGAL program has an else without an if".into());
+                }
+                self.indent -= 1;
+                self.line("} else {");
+                self.indent += 1;
+                return Ok(());
+            }
+            0x21 => {
+                if self.indent <= 1 {
+                    return Err("AGAL program has an endif without an if".into());
+                }
+                self.indent -= 1;
+                self.line("}");
+                return Ok(());
+            }
+            0x27 => {
+                if self.shader_type != ShaderType::Fragment {
+                    return Err("Only fragment programs can kill fragments".into());
+                }
+                let condition = format!("({}).x < 0.0", self.source(&s1()?)?);
+                self.line(&format!("if ({}) {{", condition));
+                self.line("    discard;");
+                self.line("}");
+                return Ok(());
+            }
+            0x28 => {
+                if self.shader_type != ShaderType::Fragment {
+                    return Err("Only fragment programs can sample textures".into());
+                }
+                let coordinates = self.source(&s1()?)?;
+                let sampler = SamplerSource::parse(source2)?;
+                let number = usize::from(sampler.number);
+                let config = self
+                    .samplers
+                    .get_mut(number)
+                    .ok_or("AGAL program samples an unknown texture")?;
+                config.get_or_insert(sampler.config);
+                if sampler.lod_bias != 0 {
+                    format!(
+                        "textureSampleBias(texture{0}, sampler{0}, ({1}).xy, {2:?})",
+                        number,
+                        coordinates,
+                        f32::from(sampler.lod_bias) / 8.0
+                    )
+                } else {
+                    format!(
+                        "textureSample(texture{0}, sampler{0}, ({1}).xy)",
+                        number, coordinates
+                    )
+                }
+            }
+            0x29 => self.compare(">=", &s1()?, &s2()?)?,
+            0x2a => self.compare("<", &s1()?, &s2()?)?,
+            0x2b => self.call("sign", &[s1()?])?,
+            0x2c => self.compare("==", &s1()?, &s2()?)?,
+            0x2d => self.compare("!=", &s1()?, &s2()?)?,
+            _ => return Err(format!("Unsupported AGAL opcode {:#x}", opcode).into()),
+        };
+
+        self.write_destination(destination, &value)
+    }
+
+    /// Assign the components of a value that are in the destination's write
+    /// mask to the destination register.
+    fn write_destination(&mut self, destination: u32, value: &str) -> Result<(), Error> {
+        let number = destination as u16;
+        let write_mask = (destination >> 16) as u8 & 0xf;
+        let register_type = RegisterType::from_u8((destination >> 24) as u8 & 0xf)?;
+        let register = match register_type {
+            RegisterType::Temporary => self.temporary(number)?,
+            RegisterType::Output if number == 0 => "o".to_string(),
+            RegisterType::Varying if self.shader_type == ShaderType::Vertex => varying(number)?,
+            _ => return Err("AGAL program writes to a register it can't write to".into()),
+        };
+
+        let result = format!("r{}", self.num_instructions);
+        self.num_instructions += 1;
+        self.line(&format!("let {} = {};", result, value));
+        if write_mask == 0xf || write_mask == 0 {
+            self.line(&format!("{} = {};", register, result));
+        } else {
+            let components: Vec<String> = ["x", "y", "z", "w"]
+                .iter()
+                .enumerate()
+                .map(|(i, component)| {
+                    let from = if write_mask & (1 << i) != 0 {
+                        &result
+                    } else {
+                        &register
+                    };
+                    format!("{}.{}", from, component)
+                })
+                .collect();
+            self.line(&format!(
+                "{} = vec4<f32>({});",
+                register,
+                components.join(", ")
+            ));
+        }
+        Ok(())
+    }
+
+    /// The expression reading a source register, swizzled.
+    fn source(&mut self, source: &Source) -> Result<String, Error> {
+        let register = self.register(source, 0)?;
+        Ok(format!("{}.{}", register, swizzle(source.swizzle)))
+    }
+
+    /// The expression for a whole source register, or one of the registers
+    /// after it.
+    fn register(&mut self, source: &Source, row: u16) -> Result<String, Error> {
+        if let Some((index_type, index_number, component)) = source.indirect {
+            if source.register_type != RegisterType::Constant {
+                return Err("Only constants can be indirectly addressed".into());
+            }
+            let index = self.register(
+                &Source {
+                    register_type: index_type,
+                    number: index_number,
+                    swizzle: 0,
+                    indirect: None,
+                },
+                0,
+            )?;
+            let component = ["x", "y", "z", "w"][usize::from(component)];
+            return Ok(format!(
+                "constants.c[i32({}.{}) + {}]",
+                index,
+                component,
+                source.number + row
+            ));
+        }
+
+        let number = source.number + row;
+        Ok(match source.register_type {
+            RegisterType::Attribute if self.shader_type == ShaderType::Vertex => {
+                let attribute = self
+                    .attributes
+                    .get_mut(usize::from(number))
+                    .ok_or("AGAL program reads an unknown vertex attribute")?;
+                *attribute = true;
+                format!("va{}", number)
+            }
+            RegisterType::Constant => {
+                let num_constants = match self.shader_type {
+                    ShaderType::Vertex => VERTEX_CONSTANTS,
+                    ShaderType::Fragment => FRAGMENT_CONSTANTS,
+                };
+                if usize::from(number) >= num_constants {
+                    return Err("AGAL program reads an unknown constant".into());
+                }
+                format!("constants.c[{}]", number)
+            }
+            RegisterType::Temporary => self.temporary(number)?,
+            RegisterType::Varying => varying(number)?,
+            RegisterType::Output if number == 0 => "o".to_string(),
+            _ => return Err("AGAL program reads a register it can't read".into()),
+        })
+    }
+
+    fn temporary(&mut self, number: u16) -> Result<String, Error> {
+        let temporary = self
+            .temporaries
+            .get_mut(usize::from(number))
+            .ok_or("AGAL program uses an unknown temporary register")?;
+        *temporary = true;
+        Ok(format!("t{}", number))
+    }
+
+    fn call(&mut self, function: &str, sources: &[Source]) -> Result<String, Error> {
+        let arguments = sources
+            .iter()
+            .map(|source| self.source(source))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(format!("{}({})", function, arguments.join(", ")))
+    }
+
+    /// Set components to 1 where the comparison holds, and 0 elsewhere.
+    fn compare(&mut self, operator: &str, s1: &Source, s2: &Source) -> Result<String, Error> {
+        Ok(format!(
+            "select({}, {}, {} {} {})",
+            splat("0.0"),
+            splat("1.0"),
+            self.source(s1)?,
+            operator,
+            self.source(s2)?
+        ))
+    }
+
+    /// Multiply a vector by the matrix in the registers starting at `s2`,
+    /// giving the dot products with each of its rows.
+    fn matrix(
+        &mut self,
+        s1: &Source,
+        s2: &Source,
+        size: usize,
+        rows: u16,
+    ) -> Result<String, Error> {
+        let components = if size == 3 { ".xyz" } else { "" };
+        let vector = self.source(s1)?;
+        let mut dots = Vec::with_capacity(4);
+        for row in 0..rows {
+            let row = self.register(s2, row)?;
+            dots.push(format!(
+                "dot(({}){}, {}{})",
+                vector, components, row, components
+            ));
+        }
+        if rows == 3 {
+            dots.push("0.0".to_string());
+        }
+        Ok(format!("vec4<f32>({})", dots.join(", ")))
+    }
+
+    fn line(&mut self, line: &str) {
+        for _ in 0..self.indent {
+            self.body.push_str("    ");
+        }
+        self.body.push_str(line);
+        self.body.push('\n');
+    }
+
+    fn declarations(&self, shader: &mut String, num_constants: usize, binding: u32) {
+        let _ = writeln!(shader, "[[block]]");
+        let _ = writeln!(shader, "struct Constants {{");
+        let _ = writeln!(
+            shader,
+            "    c: [[stride(16)]] array<vec4<f32>, {}>;",
+            num_constants
+        );
+        let _ = writeln!(shader, "}};");
+        let _ = writeln!(shader);
+        let _ = writeln!(shader, "[[group(0), binding({})]]", binding);
+        let _ = writeln!(shader, "var<uniform> constants: Constants;");
+        let _ = writeln!(shader);
+    }
+
+    fn locals(&self, shader: &mut String) {
+        let zero = splat("0.0");
+        for (i, _) in self
+            .temporaries
+            .iter()
+            .enumerate()
+            .filter(|(_, used)| **used)
+        {
+            let _ = writeln!(shader, "    var t{}: vec4<f32> = {};", i, zero);
+        }
+        let _ = writeln!(shader, "    var o: vec4<f32> = {};", zero);
+    }
+
+    fn finish_vertex(&self) -> String {
+        let mut shader = String::new();
+        self.declarations(&mut shader, VERTEX_CONSTANTS, 0);
+
+        let _ = writeln!(shader, "struct VertexOutput {{");
+        let _ = writeln!(shader, "    [[builtin(position)]] position: vec4<f32>;");
+        for i in 0..MAX_VARYINGS {
+            let _ = writeln!(shader, "    [[location({0})]] v{0}: vec4<f32>;", i);
+        }
+        let _ = writeln!(shader, "}};");
+        let _ = writeln!(shader);
+
+        let attributes: Vec<String> = self
+            .attributes
+            .iter()
+            .enumerate()
+            .filter(|(_, used)| **used)
+            .map(|(i, _)| format!("[[location({0})]] va{0}: vec4<f32>", i))
+            .collect();
+        let _ = writeln!(shader, "[[stage(vertex)]]");
+        let _ = writeln!(
+            shader,
+            "fn main({}) -> VertexOutput {{",
+            attributes.join(", ")
+        );
+        self.locals(&mut shader);
+        for i in 0..MAX_VARYINGS {
+            let _ = writeln!(shader, "    var v{}: vec4<f32> = {};", i, splat("0.0"));
+        }
+        shader.push_str(&self.body);
+        let varyings: Vec<String> = (0..MAX_VARYINGS).map(|i| format!("v{}", i)).collect();
+        let _ = writeln!(
+            shader,
+            "    return VertexOutput(o, {});",
+            varyings.join(", ")
+        );
+        let _ = writeln!(shader, "}}");
+        shader
+    }
+
+    fn finish_fragment(&self) -> String {
+        let mut shader = String::new();
+        self.declarations(&mut shader, FRAGMENT_CONSTANTS, 1);
+
+        for (i, _) in self
+            .samplers
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.is_some())
+        {
+            let _ = writeln!(shader, "[[group(1), binding({})]]", i * 2);
+            let _ = writeln!(shader, "var texture{}: texture_2d<f32>;", i);
+            let _ = writeln!(shader, "[[group(1), binding({})]]", i * 2 + 1);
+            let _ = writeln!(shader, "var sampler{}: sampler;", i);
+        }
+        let _ = writeln!(shader);
+
+        let varyings: Vec<String> = (0..MAX_VARYINGS)
+            .map(|i| format!("[[location({0})]] v{0}: vec4<f32>", i))
+            .collect();
+        let _ = writeln!(shader, "[[stage(fragment)]]");
+        let _ = writeln!(
+            shader,
+            "fn main({}) -> [[location(0)]] vec4<f32> {{",
+            varyings.join(", ")
+        );
+        self.locals(&mut shader);
+        shader.push_str(&self.body);
+        let _ = writeln!(shader, "    return o;");
+        let _ = writeln!(shader, "}}");
+        shader
+    }
+}
+
+fn varying(number: u16) -> Result<String, Error> {
+    if usize::from(number) >= MAX_VARYINGS {
+        return Err("AGAL program uses an unknown varying register".into());
+    }
+    Ok(format!("v{}", number))
+}
+
+/// A vector with the same value in every component.
+fn splat(value: &str) -> String {
+    format!("vec4<f32>({0}, {0}, {0}, {0})", value)
+}
+
+/// The components selected by an AGAL swizzle, two bits per component.
+fn swizzle(swizzle: u8) -> String {
+    (0..4)
+        .map(|i| ['x', 'y', 'z', 'w'][usize::from((swizzle >> (i * 2)) & 3)])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XYZW: u8 = 0b11_10_01_00;
+
+    fn program(shader_type: u8, instructions: &[(u32, u32, u64, u64)]) -> Vec<u8> {
+        let mut bytecode = vec![0xa0, 1, 0, 0, 0, 0xa1, shader_type];
+        for (opcode, destination, source1, source2) in instructions {
+            bytecode.extend_from_slice(&opcode.to_le_bytes());
+            bytecode.extend_from_slice(&destination.to_le_bytes());
+            bytecode.extend_from_slice(&source1.to_le_bytes());
+            bytecode.extend_from_slice(&source2.to_le_bytes());
+        }
+        bytecode
+    }
+
+    fn destination(register_type: u32, number: u32, write_mask: u32) -> u32 {
+        register_type << 24 | write_mask << 16 | number
+    }
+
+    fn source(register_type: u64, number: u64, swizzle: u8) -> u64 {
+        register_type << 32 | u64::from(swizzle) << 24 | number
+    }
+
+    #[test]
+    fn translates_textured_program() {
+        // m44 op, va0, vc0
+        // mov v0, va1
+        let vertex = program(
+            0,
+            &[
+                (
+                    0x18,
+                    destination(3, 0, 0xf),
+                    source(0, 0, XYZW),
+                    source(1, 0, XYZW),
+                ),
+                (0x00, destination(4, 0, 0xf), source(0, 1, XYZW), 0),
+            ],
+        );
+        // tex ft0, v0, fs0 <2d, linear, nomip, repeat>
+        // mov oc, ft0
+        let sampler = 1 << 60 | 1 << 52;
+        let fragment = program(
+            1,
+            &[
+                (0x28, destination(2, 0, 0xf), source(4, 0, XYZW), sampler),
+                (0x00, destination(3, 0, 0xf), source(2, 0, XYZW), 0),
+            ],
+        );
+
+        let translated = translate(&vertex, &fragment).unwrap();
+        assert_eq!(
+            translated.attributes,
+            [true, true, false, false, false, false, false, false]
+        );
+        assert_eq!(
+            translated.samplers[0],
+            Some(SamplerConfig {
+                filter: Filter::Linear,
+                mipmap: Mipmap::Disable,
+                wrapping: Wrapping::Repeat,
+            })
+        );
+        assert!(translated
+            .vertex_shader
+            .contains("dot((va0.xyzw), constants.c[3])"));
+        assert!(translated
+            .fragment_shader
+            .contains("textureSample(texture0, sampler0, (v0.xyzw).xy)"));
+    }
+
+    #[test]
+    fn writes_masked_components() {
+        // add vt0.xz, va0.yyyy, vc1
+        let vertex = program(
+            0,
+            &[(
+                0x01,
+                destination(2, 0, 0b0101),
+                source(0, 0, 0b01_01_01_01),
+                source(1, 1, XYZW),
+            )],
+        );
+        let fragment = program(1, &[]);
+
+        let translated = translate(&vertex, &fragment).unwrap();
+        assert!(translated
+            .vertex_shader
+            .contains("let r0 = va0.yyyy + constants.c[1].xyzw;"));
+        assert!(translated
+            .vertex_shader
+            .contains("t0 = vec4<f32>(r0.x, t0.y, r0.z, t0.w);"));
+    }
+
+    #[test]
+    fn rejects_invalid_programs() {
+        let vertex = program(0, &[]);
+        let fragment = program(1, &[]);
+        assert!(translate(&fragment, &vertex).is_err());
+        assert!(translate(&vertex[..3], &fragment).is_err());
+        // An unknown opcode.
+        let bad = program(0, &[(0xff, 0, 0, 0)]);
+        assert!(translate(&bad, &fragment).is_err());
+    }
+}
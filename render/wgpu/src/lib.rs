@@ -1,6 +1,7 @@
+use ruffle_core::backend::render::context3d::Context3D;
 use ruffle_core::backend::render::{
     Bitmap, BitmapFormat, BitmapHandle, BitmapInfo, BitmapSource, Color, RenderBackend,
-    ShapeHandle, Transform,
+    ShapeHandle, StageQuality, Transform, Yuv420Frame,
 };
 use ruffle_core::shape_utils::DistilledShape;
 use ruffle_core::swf;
@@ -27,27 +28,46 @@ type Error = Box<dyn std::error::Error>;
 mod utils;
 
 mod bitmaps;
+mod blend;
+mod context3d;
 mod globals;
 mod pipelines;
+mod sample_count_cache;
 pub mod target;
+mod yuv;
 
 #[cfg(feature = "clap")]
 pub mod clap;
 
 use crate::bitmaps::BitmapSamplers;
+use crate::blend::BlendCompositor;
+use crate::context3d::WgpuContext3D;
 use crate::globals::Globals;
+use crate::sample_count_cache::{sample_count_for, SampleCountCache};
+use crate::yuv::{YuvConverter, YuvTextureRing};
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::Arc;
 pub use wgpu;
 
 pub struct Descriptors {
-    pub device: wgpu::Device,
+    /// The device and queue are shared with Stage3D contexts.
+    pub device: Arc<wgpu::Device>,
     pub info: wgpu::AdapterInfo,
-    queue: wgpu::Queue,
+    queue: Arc<wgpu::Queue>,
     globals: Globals,
     pipelines: Pipelines,
     bitmap_samplers: BitmapSamplers,
+    yuv_converter: YuvConverter,
+
+    /// Blending reads back what's been drawn from the multisampled
+    /// framebuffer, so blend modes are only supported with MSAA.
+    blend_compositor: Option<BlendCompositor>,
     msaa_sample_count: u32,
+
+    /// The pipelines and blend compositors built for other MSAA sample
+    /// counts.
+    pipeline_cache: SampleCountCache<(Pipelines, Option<BlendCompositor>)>,
 }
 
 impl Descriptors {
@@ -61,31 +81,83 @@ impl Descriptors {
 
         let bitmap_samplers = BitmapSamplers::new(&device);
         let globals = Globals::new(&device);
-        let pipelines = Pipelines::new(
-            &device,
-            msaa_sample_count,
-            bitmap_samplers.layout(),
-            globals.layout(),
-        )?;
+        let yuv_converter = YuvConverter::new(&device);
+        let (pipelines, blend_compositor) =
+            build_pipelines(&device, msaa_sample_count, &bitmap_samplers, &globals)?;
 
         Ok(Self {
-            device,
+            device: Arc::new(device),
             info,
-            queue,
+            queue: Arc::new(queue),
             globals,
             pipelines,
             bitmap_samplers,
+            yuv_converter,
+            blend_compositor,
             msaa_sample_count,
+            pipeline_cache: SampleCountCache::default(),
         })
     }
+
+    /// Switches to everything that depends on the number of MSAA samples,
+    /// building it if it wasn't built before.
+    fn set_msaa_sample_count(&mut self, msaa_sample_count: u32) -> Result<(), Error> {
+        let device = &self.device;
+        let bitmap_samplers = &self.bitmap_samplers;
+        let globals = &self.globals;
+        let (pipelines, blend_compositor) =
+            self.pipeline_cache
+                .take_or_build(msaa_sample_count, |msaa_sample_count| {
+                    build_pipelines(device, msaa_sample_count, bitmap_samplers, globals)
+                })?;
+        let pipelines = std::mem::replace(&mut self.pipelines, pipelines);
+        let blend_compositor = std::mem::replace(&mut self.blend_compositor, blend_compositor);
+        self.pipeline_cache
+            .insert(self.msaa_sample_count, (pipelines, blend_compositor));
+        self.msaa_sample_count = msaa_sample_count;
+        Ok(())
+    }
+
+    /// Builds the pipelines for every stage quality ahead of time, so that
+    /// the first change of quality doesn't stall a frame.
+    pub fn warm_up(&mut self) -> Result<(), Error> {
+        let device = &self.device;
+        let bitmap_samplers = &self.bitmap_samplers;
+        let globals = &self.globals;
+        self.pipeline_cache
+            .warm_up(self.msaa_sample_count, |msaa_sample_count| {
+                build_pipelines(device, msaa_sample_count, bitmap_samplers, globals)
+            })
+    }
+}
+
+fn build_pipelines(
+    device: &wgpu::Device,
+    msaa_sample_count: u32,
+    bitmap_samplers: &BitmapSamplers,
+    globals: &Globals,
+) -> Result<(Pipelines, Option<BlendCompositor>), Error> {
+    let pipelines = Pipelines::new(
+        device,
+        msaa_sample_count,
+        bitmap_samplers.layout(),
+        globals.layout(),
+    )?;
+    let blend_compositor =
+        (msaa_sample_count >= 2).then(|| BlendCompositor::new(device, msaa_sample_count));
+    Ok((pipelines, blend_compositor))
 }
 
 pub struct WgpuRenderBackend<T: RenderTarget> {
     descriptors: Descriptors,
     target: T,
+    frame_buffer: wgpu::Texture,
     frame_buffer_view: wgpu::TextureView,
     depth_texture_view: wgpu::TextureView,
     current_frame: Option<Frame<'static, T>>,
+
+    /// The bitmap being drawn into instead of the frame, if any.
+    offscreen: Option<Offscreen>,
     meshes: Vec<Mesh>,
     mask_state: MaskState,
     shape_tessellator: ShapeTessellator,
@@ -95,6 +167,22 @@ pub struct WgpuRenderBackend<T: RenderTarget> {
     quad_ibo: wgpu::Buffer,
     quad_tex_transforms: wgpu::Buffer,
     bitmap_registry: HashMap<BitmapHandle, Bitmap>,
+
+    /// The plane textures of bitmaps that show YUV video frames.
+    yuv_rings: HashMap<BitmapHandle, YuvTextureRing>,
+
+    /// The layers that display objects with blend modes are being drawn
+    /// into, from the bottom up. `None` for objects being drawn normally
+    /// because they can't be blended.
+    blend_layers: Vec<Option<BlendLayer>>,
+
+    /// Layers that have been blended, kept until the frame is submitted
+    /// because its render pass still refers to them.
+    blended_layers: Vec<BlendedLayer>,
+
+    /// The bind groups of Stage3D contexts drawn this frame, kept for the
+    /// same reason.
+    context3d_bind_groups: Vec<wgpu::BindGroup>,
 }
 
 #[allow(dead_code)]
@@ -117,6 +205,230 @@ impl<'a, T: RenderTarget> Frame<'static, T> {
     }
 }
 
+/// A bitmap being drawn into between `begin_offscreen` and `end_offscreen`.
+#[allow(dead_code)]
+struct Offscreen {
+    // Declared first so the render pass is dropped before what it draws to.
+    frame: Frame<'static, TextureTarget>,
+    target: TextureTarget,
+    frame_buffer_view: wgpu::TextureView,
+    depth_texture_view: wgpu::TextureView,
+
+    /// The masks that were in use by the frame, restored when the offscreen
+    /// drawing is done.
+    frame_mask_state: MaskState,
+    frame_num_masks: u32,
+}
+
+/// A multisampled layer that a display object with a blend mode is drawn
+/// into, between `push_blend_mode` and `pop_blend_mode`.
+struct BlendLayer {
+    blend_mode: swf::BlendMode,
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    depth_texture_view: wgpu::TextureView,
+
+    /// The masks that were in use by what the layer is drawn over, restored
+    /// when the layer is blended onto it.
+    parent_mask_state: MaskState,
+    parent_num_masks: u32,
+}
+
+impl BlendLayer {
+    fn new(
+        descriptors: &Descriptors,
+        width: u32,
+        height: u32,
+        blend_mode: swf::BlendMode,
+        parent_mask_state: MaskState,
+        parent_num_masks: u32,
+    ) -> Self {
+        let extent = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+
+        let label = create_debug_label!("Blend layer texture");
+        let texture = descriptors.device.create_texture(&wgpu::TextureDescriptor {
+            label: label.as_deref(),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: descriptors.msaa_sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Bgra8Unorm,
+            usage: wgpu::TextureUsage::RENDER_ATTACHMENT
+                | wgpu::TextureUsage::SAMPLED
+                | wgpu::TextureUsage::COPY_SRC,
+        });
+        let view = texture.create_view(&Default::default());
+
+        let label = create_debug_label!("Blend layer depth texture");
+        let depth_texture = descriptors.device.create_texture(&wgpu::TextureDescriptor {
+            label: label.as_deref(),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: descriptors.msaa_sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth24PlusStencil8,
+            usage: wgpu::TextureUsage::RENDER_ATTACHMENT,
+        });
+        let depth_texture_view = depth_texture.create_view(&Default::default());
+
+        Self {
+            blend_mode,
+            texture,
+            view,
+            depth_texture_view,
+            parent_mask_state,
+            parent_num_masks,
+        }
+    }
+}
+
+/// A layer that's been blended onto what it was drawn over, along with
+/// what it was blended with.
+#[allow(dead_code)]
+struct BlendedLayer {
+    layer: BlendLayer,
+    backdrop: wgpu::Texture,
+    backdrop_view: wgpu::TextureView,
+    uniforms: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+/// Start a render pass drawing to the given attachments, either clearing
+/// them first or carrying on from what's already been drawn.
+fn begin_render_pass<'a>(
+    encoder: &'a mut wgpu::CommandEncoder,
+    color_view: &'a wgpu::TextureView,
+    resolve_target: Option<&'a wgpu::TextureView>,
+    depth_texture_view: &'a wgpu::TextureView,
+    clear: Option<wgpu::Color>,
+) -> wgpu::RenderPass<'a> {
+    let (color_load, depth_load, stencil_load) = match clear {
+        Some(color) => (
+            wgpu::LoadOp::Clear(color),
+            wgpu::LoadOp::Clear(0.0),
+            wgpu::LoadOp::Clear(0),
+        ),
+        None => (wgpu::LoadOp::Load, wgpu::LoadOp::Load, wgpu::LoadOp::Load),
+    };
+
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        color_attachments: &[wgpu::RenderPassColorAttachment {
+            view: color_view,
+            ops: wgpu::Operations {
+                load: color_load,
+                store: true,
+            },
+            resolve_target,
+        }],
+        depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+            view: depth_texture_view,
+            depth_ops: Some(wgpu::Operations {
+                load: depth_load,
+                store: true,
+            }),
+            stencil_ops: Some(wgpu::Operations {
+                load: stencil_load,
+                store: true,
+            }),
+        }),
+        label: None,
+    })
+}
+
+/// Get the render pass that draw calls go to: the offscreen one if there is
+/// one, or else the frame's.
+fn active_render_pass<'a, 'b, T: RenderTarget>(
+    current_frame: &'b mut Option<Frame<'static, T>>,
+    offscreen: &'b mut Option<Offscreen>,
+) -> Option<&'b mut wgpu::RenderPass<'a>> {
+    if let Some(offscreen) = offscreen {
+        Some(&mut offscreen.frame.get().render_pass)
+    } else {
+        current_frame
+            .as_mut()
+            .map(|frame| &mut frame.get().render_pass)
+    }
+}
+
+/// Draw a texture, given by a bind group in the bitmap layout, as a quad of
+/// its size in pixels.
+#[allow(clippy::too_many_arguments)]
+fn draw_quad<'a>(
+    render_pass: &mut wgpu::RenderPass<'a>,
+    descriptors: &'a Descriptors,
+    quad_vbo: &'a wgpu::Buffer,
+    quad_ibo: &'a wgpu::Buffer,
+    bind_group: &'a wgpu::BindGroup,
+    (width, height): (u32, u32),
+    transform: &Transform,
+    smoothing: bool,
+    mask_state: MaskState,
+    num_masks: u32,
+) {
+    let matrix = transform.matrix
+        * ruffle_core::matrix::Matrix {
+            a: width as f32,
+            d: height as f32,
+            ..Default::default()
+        };
+
+    let world_matrix = [
+        [matrix.a, matrix.b, 0.0, 0.0],
+        [matrix.c, matrix.d, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [
+            matrix.tx.to_pixels() as f32,
+            matrix.ty.to_pixels() as f32,
+            0.0,
+            1.0,
+        ],
+    ];
+
+    render_pass.set_pipeline(
+        descriptors
+            .pipelines
+            .bitmap_pipelines
+            .pipeline_for(mask_state),
+    );
+    render_pass.set_push_constants(
+        wgpu::ShaderStage::VERTEX,
+        0,
+        bytemuck::cast_slice(&[Transforms { world_matrix }]),
+    );
+    render_pass.set_push_constants(
+        wgpu::ShaderStage::FRAGMENT,
+        std::mem::size_of::<Transforms>() as u32,
+        bytemuck::cast_slice(&[ColorAdjustments::from(transform.color_transform)]),
+    );
+    render_pass.set_bind_group(0, descriptors.globals.bind_group(), &[]);
+    render_pass.set_bind_group(1, bind_group, &[]);
+    render_pass.set_bind_group(
+        2,
+        descriptors.bitmap_samplers.get_bind_group(false, smoothing),
+        &[],
+    );
+    render_pass.set_vertex_buffer(0, quad_vbo.slice(..));
+    render_pass.set_index_buffer(quad_ibo.slice(..), wgpu::IndexFormat::Uint32);
+
+    match mask_state {
+        MaskState::NoMask => (),
+        MaskState::DrawMaskStencil => {
+            debug_assert!(num_masks > 0);
+            render_pass.set_stencil_reference(num_masks - 1);
+        }
+        MaskState::DrawMaskedContent | MaskState::ClearMaskStencil => {
+            debug_assert!(num_masks > 0);
+            render_pass.set_stencil_reference(num_masks);
+        }
+    };
+
+    render_pass.draw_indexed(0..6, 0, 0..1);
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
 pub enum MaskState {
     NoMask,
@@ -309,7 +621,7 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             sample_count: descriptors.msaa_sample_count,
             dimension: wgpu::TextureDimension::D2,
             format: target.format(),
-            usage: wgpu::TextureUsage::RENDER_ATTACHMENT,
+            usage: wgpu::TextureUsage::RENDER_ATTACHMENT | wgpu::TextureUsage::COPY_SRC,
         });
         let frame_buffer_view = frame_buffer.create_view(&Default::default());
 
@@ -335,9 +647,11 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
         Ok(Self {
             descriptors,
             target,
+            frame_buffer,
             frame_buffer_view,
             depth_texture_view,
             current_frame: None,
+            offscreen: None,
             meshes: Vec::new(),
             shape_tessellator: ShapeTessellator::new(),
             textures: Vec::new(),
@@ -349,6 +663,10 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             quad_ibo,
             quad_tex_transforms,
             bitmap_registry: HashMap::new(),
+            yuv_rings: HashMap::new(),
+            blend_layers: Vec::new(),
+            blended_layers: Vec::new(),
+            context3d_bind_groups: Vec::new(),
         })
     }
 
@@ -637,9 +955,26 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             extent,
         );
 
-        let handle = BitmapHandle(self.textures.len());
         let width = bitmap.width;
         let height = bitmap.height;
+        let handle = self.register_texture(texture, width, height);
+        self.bitmap_registry.insert(handle, bitmap);
+
+        BitmapInfo {
+            handle,
+            width: width as u16,
+            height: height as u16,
+        }
+    }
+
+    /// Add a bitmap texture, and make the bind group to draw it with.
+    fn register_texture(
+        &mut self,
+        texture: wgpu::Texture,
+        width: u32,
+        height: u32,
+    ) -> BitmapHandle {
+        let handle = BitmapHandle(self.textures.len());
 
         // Make bind group for bitmap quad.
         let texture_view = texture.create_view(&Default::default());
@@ -667,7 +1002,6 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
                 label: create_debug_label!("Bitmap {} bind group", handle.0).as_deref(),
             });
 
-        self.bitmap_registry.insert(handle, bitmap);
         self.textures.push(Texture {
             width,
             height,
@@ -675,11 +1009,7 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             bind_group,
         });
 
-        BitmapInfo {
-            handle,
-            width: width as u16,
-            height: height as u16,
-        }
+        handle
     }
 
     pub fn target(&self) -> &T {
@@ -689,6 +1019,25 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
     pub fn device(&self) -> &wgpu::Device {
         &self.descriptors.device
     }
+
+    /// Builds the pipelines for every stage quality now, rather than the
+    /// first time the movie changes quality.
+    pub fn warm_up(&mut self) -> Result<(), Error> {
+        self.descriptors.warm_up()
+    }
+
+    /// Whether anything drawn now can be drawn into a layer and blended.
+    fn can_blend(&self) -> bool {
+        // Layers are drawn within the frame's command encoder, so nothing can
+        // be blended offscreen. Masks ignore blend modes.
+        self.descriptors.blend_compositor.is_some()
+            && self.current_frame.is_some()
+            && self.offscreen.is_none()
+            && matches!(
+                self.mask_state,
+                MaskState::NoMask | MaskState::DrawMaskedContent
+            )
+    }
 }
 
 impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
@@ -714,9 +1063,10 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
                 sample_count: self.descriptors.msaa_sample_count,
                 dimension: wgpu::TextureDimension::D2,
                 format: self.target.format(),
-                usage: wgpu::TextureUsage::RENDER_ATTACHMENT,
+                usage: wgpu::TextureUsage::RENDER_ATTACHMENT | wgpu::TextureUsage::COPY_SRC,
             });
         self.frame_buffer_view = frame_buffer.create_view(&Default::default());
+        self.frame_buffer = frame_buffer;
 
         let label = create_debug_label!("Depth texture");
         let depth_texture = self
@@ -739,6 +1089,19 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         self.descriptors.globals.set_resolution(width, height);
     }
 
+    fn set_quality(&mut self, quality: StageQuality) {
+        let msaa_sample_count = sample_count_for(quality);
+        if msaa_sample_count == self.descriptors.msaa_sample_count {
+            return;
+        }
+        if let Err(e) = self.descriptors.set_msaa_sample_count(msaa_sample_count) {
+            log::error!("Couldn't change MSAA sample count: {}", e);
+            return;
+        }
+        // The framebuffers have to be recreated with the new sample count.
+        self.set_viewport_dimensions(self.target.width(), self.target.height());
+    }
+
     fn register_shape(
         &mut self,
         shape: DistilledShape,
@@ -806,6 +1169,9 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
     fn begin_frame(&mut self, clear: Color) {
         self.mask_state = MaskState::NoMask;
         self.num_masks = 0;
+        self.blend_layers.clear();
+        self.blended_layers.clear();
+        self.context3d_bind_groups.clear();
 
         let frame_output = match self.target.get_next_texture() {
             Ok(frame) => frame,
@@ -881,89 +1247,34 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
 
     fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform, smoothing: bool) {
         if let Some(texture) = self.textures.get(bitmap.0) {
-            let frame = if let Some(frame) = &mut self.current_frame {
-                frame.get()
+            let render_pass = if let Some(render_pass) =
+                active_render_pass(&mut self.current_frame, &mut self.offscreen)
+            {
+                render_pass
             } else {
                 return;
             };
 
-            let transform = Transform {
-                matrix: transform.matrix
-                    * ruffle_core::matrix::Matrix {
-                        a: texture.width as f32,
-                        d: texture.height as f32,
-                        ..Default::default()
-                    },
-                ..*transform
-            };
-
-            let world_matrix = [
-                [transform.matrix.a, transform.matrix.b, 0.0, 0.0],
-                [transform.matrix.c, transform.matrix.d, 0.0, 0.0],
-                [0.0, 0.0, 1.0, 0.0],
-                [
-                    transform.matrix.tx.to_pixels() as f32,
-                    transform.matrix.ty.to_pixels() as f32,
-                    0.0,
-                    1.0,
-                ],
-            ];
-
-            frame.render_pass.set_pipeline(
-                self.descriptors
-                    .pipelines
-                    .bitmap_pipelines
-                    .pipeline_for(self.mask_state),
+            draw_quad(
+                render_pass,
+                &self.descriptors,
+                &self.quad_vbo,
+                &self.quad_ibo,
+                &texture.bind_group,
+                (texture.width, texture.height),
+                transform,
+                smoothing,
+                self.mask_state,
+                self.num_masks,
             );
-            frame.render_pass.set_push_constants(
-                wgpu::ShaderStage::VERTEX,
-                0,
-                bytemuck::cast_slice(&[Transforms { world_matrix }]),
-            );
-            frame.render_pass.set_push_constants(
-                wgpu::ShaderStage::FRAGMENT,
-                std::mem::size_of::<Transforms>() as u32,
-                bytemuck::cast_slice(&[ColorAdjustments::from(transform.color_transform)]),
-            );
-            frame
-                .render_pass
-                .set_bind_group(0, self.descriptors.globals.bind_group(), &[]);
-            frame
-                .render_pass
-                .set_bind_group(1, &texture.bind_group, &[]);
-            frame.render_pass.set_bind_group(
-                2,
-                self.descriptors
-                    .bitmap_samplers
-                    .get_bind_group(false, smoothing),
-                &[],
-            );
-            frame
-                .render_pass
-                .set_vertex_buffer(0, self.quad_vbo.slice(..));
-            frame
-                .render_pass
-                .set_index_buffer(self.quad_ibo.slice(..), wgpu::IndexFormat::Uint32);
-
-            match self.mask_state {
-                MaskState::NoMask => (),
-                MaskState::DrawMaskStencil => {
-                    debug_assert!(self.num_masks > 0);
-                    frame.render_pass.set_stencil_reference(self.num_masks - 1);
-                }
-                MaskState::DrawMaskedContent | MaskState::ClearMaskStencil => {
-                    debug_assert!(self.num_masks > 0);
-                    frame.render_pass.set_stencil_reference(self.num_masks);
-                }
-            };
-
-            frame.render_pass.draw_indexed(0..6, 0, 0..1);
         }
     }
 
     fn render_shape(&mut self, shape: ShapeHandle, transform: &Transform) {
-        let frame = if let Some(frame) = &mut self.current_frame {
-            frame.get()
+        let render_pass = if let Some(render_pass) =
+            active_render_pass(&mut self.current_frame, &mut self.offscreen)
+        {
+            render_pass
         } else {
             return;
         };
@@ -982,14 +1293,12 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
             ],
         ];
 
-        frame
-            .render_pass
-            .set_bind_group(0, self.descriptors.globals.bind_group(), &[]);
+        render_pass.set_bind_group(0, self.descriptors.globals.bind_group(), &[]);
 
         for draw in &mesh.draws {
             match &draw.draw_type {
                 DrawType::Color => {
-                    frame.render_pass.set_pipeline(
+                    render_pass.set_pipeline(
                         self.descriptors
                             .pipelines
                             .color_pipelines
@@ -997,13 +1306,13 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
                     );
                 }
                 DrawType::Gradient { bind_group, .. } => {
-                    frame.render_pass.set_pipeline(
+                    render_pass.set_pipeline(
                         self.descriptors
                             .pipelines
                             .gradient_pipelines
                             .pipeline_for(self.mask_state),
                     );
-                    frame.render_pass.set_bind_group(1, bind_group, &[]);
+                    render_pass.set_bind_group(1, bind_group, &[]);
                 }
                 DrawType::Bitmap {
                     is_repeating,
@@ -1011,14 +1320,14 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
                     bind_group,
                     ..
                 } => {
-                    frame.render_pass.set_pipeline(
+                    render_pass.set_pipeline(
                         self.descriptors
                             .pipelines
                             .bitmap_pipelines
                             .pipeline_for(self.mask_state),
                     );
-                    frame.render_pass.set_bind_group(1, bind_group, &[]);
-                    frame.render_pass.set_bind_group(
+                    render_pass.set_bind_group(1, bind_group, &[]);
+                    render_pass.set_bind_group(
                         2,
                         self.descriptors
                             .bitmap_samplers
@@ -1028,42 +1337,40 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
                 }
             }
 
-            frame.render_pass.set_push_constants(
+            render_pass.set_push_constants(
                 wgpu::ShaderStage::VERTEX,
                 0,
                 bytemuck::cast_slice(&[Transforms { world_matrix }]),
             );
-            frame.render_pass.set_push_constants(
+            render_pass.set_push_constants(
                 wgpu::ShaderStage::FRAGMENT,
                 std::mem::size_of::<Transforms>() as u32,
                 bytemuck::cast_slice(&[ColorAdjustments::from(transform.color_transform)]),
             );
-            frame
-                .render_pass
-                .set_vertex_buffer(0, draw.vertex_buffer.slice(..));
-            frame
-                .render_pass
-                .set_index_buffer(draw.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            render_pass.set_vertex_buffer(0, draw.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(draw.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
 
             match self.mask_state {
                 MaskState::NoMask => (),
                 MaskState::DrawMaskStencil => {
                     debug_assert!(self.num_masks > 0);
-                    frame.render_pass.set_stencil_reference(self.num_masks - 1);
+                    render_pass.set_stencil_reference(self.num_masks - 1);
                 }
                 MaskState::DrawMaskedContent | MaskState::ClearMaskStencil => {
                     debug_assert!(self.num_masks > 0);
-                    frame.render_pass.set_stencil_reference(self.num_masks);
+                    render_pass.set_stencil_reference(self.num_masks);
                 }
             };
 
-            frame.render_pass.draw_indexed(0..draw.index_count, 0, 0..1);
+            render_pass.draw_indexed(0..draw.index_count, 0, 0..1);
         }
     }
 
     fn draw_rect(&mut self, color: Color, matrix: &ruffle_core::matrix::Matrix) {
-        let frame = if let Some(frame) = &mut self.current_frame {
-            frame.get()
+        let render_pass = if let Some(render_pass) =
+            active_render_pass(&mut self.current_frame, &mut self.offscreen)
+        {
+            render_pass
         } else {
             return;
         };
@@ -1088,19 +1395,19 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         ];
 
         let add_color = [0.0, 0.0, 0.0, 0.0];
-        frame.render_pass.set_pipeline(
+        render_pass.set_pipeline(
             self.descriptors
                 .pipelines
                 .color_pipelines
                 .pipeline_for(self.mask_state),
         );
 
-        frame.render_pass.set_push_constants(
+        render_pass.set_push_constants(
             wgpu::ShaderStage::VERTEX,
             0,
             bytemuck::cast_slice(&[Transforms { world_matrix }]),
         );
-        frame.render_pass.set_push_constants(
+        render_pass.set_push_constants(
             wgpu::ShaderStage::FRAGMENT,
             std::mem::size_of::<Transforms>() as u32,
             bytemuck::cast_slice(&[ColorAdjustments {
@@ -1109,29 +1416,23 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
             }]),
         );
 
-        frame
-            .render_pass
-            .set_bind_group(0, self.descriptors.globals.bind_group(), &[]);
-        frame
-            .render_pass
-            .set_vertex_buffer(0, self.quad_vbo.slice(..));
-        frame
-            .render_pass
-            .set_index_buffer(self.quad_ibo.slice(..), wgpu::IndexFormat::Uint32);
+        render_pass.set_bind_group(0, self.descriptors.globals.bind_group(), &[]);
+        render_pass.set_vertex_buffer(0, self.quad_vbo.slice(..));
+        render_pass.set_index_buffer(self.quad_ibo.slice(..), wgpu::IndexFormat::Uint32);
 
         match self.mask_state {
             MaskState::NoMask => (),
             MaskState::DrawMaskStencil => {
                 debug_assert!(self.num_masks > 0);
-                frame.render_pass.set_stencil_reference(self.num_masks - 1);
+                render_pass.set_stencil_reference(self.num_masks - 1);
             }
             MaskState::DrawMaskedContent | MaskState::ClearMaskStencil => {
                 debug_assert!(self.num_masks > 0);
-                frame.render_pass.set_stencil_reference(self.num_masks);
+                render_pass.set_stencil_reference(self.num_masks);
             }
         };
 
-        frame.render_pass.draw_indexed(0..6, 0, 0..1);
+        render_pass.draw_indexed(0..6, 0, 0..1);
     }
 
     fn end_frame(&mut self) {
@@ -1146,6 +1447,155 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
                 vec![draw_encoder.finish()],
             );
         }
+        self.blend_layers.clear();
+        self.blended_layers.clear();
+        self.context3d_bind_groups.clear();
+    }
+
+    fn begin_offscreen(&mut self, width: u32, height: u32) -> bool {
+        if self.offscreen.is_some() || width == 0 || height == 0 {
+            return false;
+        }
+
+        let device = &self.descriptors.device;
+        let mut target = TextureTarget::new(device, (width, height));
+        let extent = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+
+        let label = create_debug_label!("Offscreen framebuffer texture");
+        let frame_buffer = device.create_texture(&wgpu::TextureDescriptor {
+            label: label.as_deref(),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: self.descriptors.msaa_sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: target.format(),
+            usage: wgpu::TextureUsage::RENDER_ATTACHMENT,
+        });
+        let frame_buffer_view = frame_buffer.create_view(&Default::default());
+
+        let label = create_debug_label!("Offscreen depth texture");
+        let depth_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: label.as_deref(),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: self.descriptors.msaa_sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth24PlusStencil8,
+            usage: wgpu::TextureUsage::RENDER_ATTACHMENT,
+        });
+        let depth_texture_view = depth_texture.create_view(&Default::default());
+
+        let frame_output = match target.get_next_texture() {
+            Ok(frame) => frame,
+            Err(e) => {
+                log::warn!("Couldn't begin offscreen drawing: {}", e);
+                return false;
+            }
+        };
+
+        let label = create_debug_label!("Offscreen draw encoder");
+        let draw_encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: label.as_deref(),
+        });
+        let mut frame_data = Box::new((draw_encoder, frame_output));
+
+        self.descriptors.globals.set_resolution(width, height);
+        self.descriptors
+            .globals
+            .update_uniform(&self.descriptors.device, &mut frame_data.0);
+
+        let (color_view, resolve_target) = if self.descriptors.msaa_sample_count >= 2 {
+            (&frame_buffer_view, Some(frame_data.1.view()))
+        } else {
+            (frame_data.1.view(), None)
+        };
+
+        let render_pass = frame_data.0.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[wgpu::RenderPassColorAttachment {
+                view: color_view,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: true,
+                },
+                resolve_target,
+            }],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &depth_texture_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(0.0),
+                    store: true,
+                }),
+                stencil_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(0),
+                    store: true,
+                }),
+            }),
+            label: None,
+        });
+
+        // See `begin_frame` for why the lifetime is cast away.
+        let frame = Frame {
+            render_pass: unsafe {
+                std::mem::transmute::<_, wgpu::RenderPass<'static>>(render_pass)
+            },
+            frame_data,
+        };
+
+        self.offscreen = Some(Offscreen {
+            frame,
+            target,
+            frame_buffer_view,
+            depth_texture_view,
+            frame_mask_state: self.mask_state,
+            frame_num_masks: self.num_masks,
+        });
+        self.mask_state = MaskState::NoMask;
+        self.num_masks = 0;
+        true
+    }
+
+    fn end_offscreen(&mut self) -> Option<Bitmap> {
+        let offscreen = self.offscreen.take()?;
+        self.mask_state = offscreen.frame_mask_state;
+        self.num_masks = offscreen.frame_num_masks;
+
+        // Finalize render pass.
+        drop(offscreen.frame.render_pass);
+
+        let draw_encoder = offscreen.frame.frame_data.0;
+        offscreen.target.submit(
+            &self.descriptors.device,
+            &self.descriptors.queue,
+            vec![draw_encoder.finish()],
+        );
+
+        // The offscreen drawing changed the view matrix, so put it back before
+        // anything else is drawn.
+        self.descriptors
+            .globals
+            .set_resolution(self.target.width(), self.target.height());
+        let label = create_debug_label!("Globals restore encoder");
+        let mut encoder =
+            self.descriptors
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: label.as_deref(),
+                });
+        self.descriptors
+            .globals
+            .update_uniform(&self.descriptors.device, &mut encoder);
+        self.descriptors.queue.submit(Some(encoder.finish()));
+
+        let image = offscreen.target.capture(&self.descriptors.device)?;
+        Some(Bitmap {
+            width: image.width(),
+            height: image.height(),
+            data: BitmapFormat::Rgba(image.into_raw()),
+        })
     }
 
     fn push_mask(&mut self) {
@@ -1176,6 +1626,246 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         };
     }
 
+    fn push_blend_mode(&mut self, blend_mode: swf::BlendMode) {
+        let can_blend = self.can_blend();
+        let frame = match self.current_frame.take() {
+            Some(frame) if can_blend => frame,
+            frame => {
+                self.current_frame = frame;
+                self.blend_layers.push(None);
+                return;
+            }
+        };
+
+        let layer = BlendLayer::new(
+            &self.descriptors,
+            self.target.width(),
+            self.target.height(),
+            blend_mode,
+            self.mask_state,
+            self.num_masks,
+        );
+
+        // Carry on drawing the frame into the layer.
+        drop(frame.render_pass);
+        let mut frame_data = frame.frame_data;
+        let render_pass = begin_render_pass(
+            &mut frame_data.0,
+            &layer.view,
+            None,
+            &layer.depth_texture_view,
+            Some(wgpu::Color::TRANSPARENT),
+        );
+        // See `begin_frame` for why the lifetime is cast away. The layer's
+        // views are kept until the frame is submitted.
+        self.current_frame = Some(Frame {
+            render_pass: unsafe {
+                std::mem::transmute::<_, wgpu::RenderPass<'static>>(render_pass)
+            },
+            frame_data,
+        });
+
+        self.blend_layers.push(Some(layer));
+        self.mask_state = MaskState::NoMask;
+        self.num_masks = 0;
+    }
+
+    fn pop_blend_mode(&mut self) {
+        let layer = match self.blend_layers.pop() {
+            Some(Some(layer)) => layer,
+            _ => return,
+        };
+        self.mask_state = layer.parent_mask_state;
+        self.num_masks = layer.parent_num_masks;
+
+        let (frame, compositor) = match (
+            self.current_frame.take(),
+            &self.descriptors.blend_compositor,
+        ) {
+            (Some(frame), Some(compositor)) => (frame, compositor),
+            _ => return,
+        };
+        drop(frame.render_pass);
+        let mut frame_data = frame.frame_data;
+        let (encoder, frame_output) = &mut *frame_data;
+
+        // The layer is blended onto the layer below it, if there is one, or
+        // else onto the frame.
+        let parent = self.blend_layers.iter().rev().find_map(Option::as_ref);
+        let (parent_texture, parent_view, resolve_target, depth_texture_view) = match parent {
+            Some(parent) => (
+                &parent.texture,
+                &parent.view,
+                None,
+                &parent.depth_texture_view,
+            ),
+            None => (
+                &self.frame_buffer,
+                &self.frame_buffer_view,
+                Some(frame_output.view()),
+                &self.depth_texture_view,
+            ),
+        };
+
+        // A render pass can't read what it draws to, so blend with a copy.
+        let extent = wgpu::Extent3d {
+            width: self.target.width(),
+            height: self.target.height(),
+            depth_or_array_layers: 1,
+        };
+        let label = create_debug_label!("Blend backdrop texture");
+        let backdrop = self
+            .descriptors
+            .device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: label.as_deref(),
+                size: extent,
+                mip_level_count: 1,
+                sample_count: self.descriptors.msaa_sample_count,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Bgra8Unorm,
+                usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+            });
+        encoder.copy_texture_to_texture(
+            wgpu::ImageCopyTexture {
+                texture: parent_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            wgpu::ImageCopyTexture {
+                texture: &backdrop,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            extent,
+        );
+        let backdrop_view = backdrop.create_view(&Default::default());
+
+        let uniforms = create_buffer_with_data(
+            &self.descriptors.device,
+            bytemuck::cast_slice(&[layer.blend_mode as u32, 0, 0, 0]),
+            wgpu::BufferUsage::UNIFORM,
+            create_debug_label!("Blend uniforms"),
+        );
+        let bind_group = compositor.bind_group(
+            &self.descriptors.device,
+            &layer.view,
+            &backdrop_view,
+            &uniforms,
+        );
+
+        let mut render_pass = begin_render_pass(
+            encoder,
+            parent_view,
+            resolve_target,
+            depth_texture_view,
+            None,
+        );
+        render_pass.set_pipeline(compositor.pipeline_for(self.mask_state));
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        if self.mask_state == MaskState::DrawMaskedContent {
+            render_pass.set_stencil_reference(self.num_masks);
+        }
+        render_pass.draw(0..3, 0..1);
+
+        // See `begin_frame` for why the lifetime is cast away.
+        self.current_frame = Some(Frame {
+            render_pass: unsafe {
+                std::mem::transmute::<_, wgpu::RenderPass<'static>>(render_pass)
+            },
+            frame_data,
+        });
+        self.blended_layers.push(BlendedLayer {
+            layer,
+            backdrop,
+            backdrop_view,
+            uniforms,
+            bind_group,
+        });
+    }
+
+    fn push_alpha_mask(&mut self) -> bool {
+        // The masked content is grouped in a layer, and the mask is blended
+        // over it with the alpha blend mode.
+        if !self.can_blend() {
+            return false;
+        }
+        self.push_blend_mode(swf::BlendMode::Layer);
+        true
+    }
+
+    fn activate_alpha_mask(&mut self) {
+        self.push_blend_mode(swf::BlendMode::Alpha);
+    }
+
+    fn pop_alpha_mask(&mut self) {
+        self.pop_blend_mode();
+        self.pop_blend_mode();
+    }
+
+    fn create_context3d(&mut self) -> Result<Box<dyn Context3D>, Error> {
+        Ok(Box::new(WgpuContext3D::new(
+            self.descriptors.device.clone(),
+            self.descriptors.queue.clone(),
+            &self.descriptors.info,
+        )))
+    }
+
+    fn render_context3d(&mut self, context: &dyn Context3D, transform: &Transform) {
+        let (view, width, height) = match context
+            .downcast_ref::<WgpuContext3D>()
+            .and_then(|context| context.front_buffer())
+        {
+            Some(front_buffer) => front_buffer,
+            None => return,
+        };
+
+        let bind_group = self
+            .descriptors
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &self.descriptors.pipelines.bitmap_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                            buffer: &self.quad_tex_transforms,
+                            offset: 0,
+                            size: wgpu::BufferSize::new(
+                                std::mem::size_of::<TextureTransforms>() as u64
+                            ),
+                        }),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(view),
+                    },
+                ],
+                label: create_debug_label!("Context3D front buffer bind group").as_deref(),
+            });
+        self.context3d_bind_groups.push(bind_group);
+
+        let render_pass = if let Some(render_pass) =
+            active_render_pass(&mut self.current_frame, &mut self.offscreen)
+        {
+            render_pass
+        } else {
+            return;
+        };
+        draw_quad(
+            render_pass,
+            &self.descriptors,
+            &self.quad_vbo,
+            &self.quad_ibo,
+            self.context3d_bind_groups.last().unwrap(),
+            (width, height),
+            transform,
+            true,
+            self.mask_state,
+            self.num_masks,
+        );
+    }
+
     fn get_bitmap_pixels(&mut self, bitmap: BitmapHandle) -> Option<Bitmap> {
         self.bitmap_registry.get(&bitmap).cloned()
     }
@@ -1234,6 +1924,83 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
 
         Ok(handle)
     }
+
+    fn supports_yuv420(&self) -> bool {
+        true
+    }
+
+    fn register_bitmap_yuv420(&mut self, frame: Yuv420Frame<'_>) -> Result<BitmapHandle, Error> {
+        let texture_label = create_debug_label!("YUV video Texture");
+        let texture = self
+            .descriptors
+            .device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: texture_label.as_deref(),
+                size: wgpu::Extent3d {
+                    width: frame.width,
+                    height: frame.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsage::SAMPLED
+                    | wgpu::TextureUsage::COPY_DST
+                    | wgpu::TextureUsage::RENDER_ATTACHMENT,
+            });
+
+        let mut ring = YuvTextureRing::new(
+            &self.descriptors.device,
+            &self.descriptors.yuv_converter,
+            &frame,
+        );
+        ring.upload(
+            &self.descriptors.device,
+            &self.descriptors.queue,
+            &self.descriptors.yuv_converter,
+            &frame,
+            &texture,
+        );
+
+        let handle = self.register_texture(texture, frame.width, frame.height);
+        self.yuv_rings.insert(handle, ring);
+
+        Ok(handle)
+    }
+
+    fn update_texture_yuv420(
+        &mut self,
+        handle: BitmapHandle,
+        frame: Yuv420Frame<'_>,
+    ) -> Result<BitmapHandle, Error> {
+        let texture = match self.textures.get(handle.0) {
+            Some(texture) if texture.width == frame.width && texture.height == frame.height => {
+                &texture.texture
+            }
+            Some(_) => return Err("update_texture_yuv420: Frame size changed".into()),
+            None => return Err("update_texture_yuv420: Bitmap not registered".into()),
+        };
+
+        // Only bitmaps registered from YUV frames can be rendered to.
+        let descriptors = &self.descriptors;
+        let ring = self
+            .yuv_rings
+            .get_mut(&handle)
+            .ok_or("update_texture_yuv420: Bitmap is not a YUV video frame")?;
+        if !ring.fits(&frame) {
+            *ring = YuvTextureRing::new(&descriptors.device, &descriptors.yuv_converter, &frame);
+        }
+        ring.upload(
+            &descriptors.device,
+            &descriptors.queue,
+            &descriptors.yuv_converter,
+            &frame,
+            texture,
+        );
+
+        Ok(handle)
+    }
 }
 
 fn create_quad_buffers(device: &wgpu::Device) -> (wgpu::Buffer, wgpu::Buffer, wgpu::Buffer) {
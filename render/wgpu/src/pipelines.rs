@@ -717,7 +717,7 @@ fn create_gradient_pipeline(
     ShapePipeline { mask_pipelines }
 }
 
-fn mask_render_state(state: MaskState) -> (wgpu::StencilState, wgpu::ColorWrite) {
+pub fn mask_render_state(state: MaskState) -> (wgpu::StencilState, wgpu::ColorWrite) {
     let (stencil_state, color_write) = match state {
         MaskState::NoMask => (
             wgpu::StencilFaceState {
@@ -0,0 +1,128 @@
+use ruffle_core::backend::render::StageQuality;
+use std::collections::HashMap;
+
+type Error = Box<dyn std::error::Error>;
+
+/// The qualities whose sample counts are built ahead of time. Every quality
+/// above `High` uses as many samples as it does.
+const WARM_UP_QUALITIES: [StageQuality; 3] =
+    [StageQuality::Low, StageQuality::Medium, StageQuality::High];
+
+/// The number of MSAA samples used for a stage quality.
+pub fn sample_count_for(quality: StageQuality) -> u32 {
+    // wgpu only guarantees support for up to 4 samples.
+    quality.sample_count().min(4)
+}
+
+/// Values built for MSAA sample counts other than the one in use, such as
+/// pipelines, so that changing the stage quality back and forth doesn't
+/// rebuild them and stall a frame while their shaders compile.
+pub struct SampleCountCache<T>(HashMap<u32, T>);
+
+impl<T> Default for SampleCountCache<T> {
+    fn default() -> Self {
+        Self(HashMap::new())
+    }
+}
+
+impl<T> SampleCountCache<T> {
+    /// Take the value for a sample count out of the cache, building it if it
+    /// wasn't built before.
+    pub fn take_or_build(
+        &mut self,
+        sample_count: u32,
+        build: impl FnOnce(u32) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        match self.0.remove(&sample_count) {
+            Some(value) => Ok(value),
+            None => build(sample_count),
+        }
+    }
+
+    /// Keep the value for a sample count that's no longer in use.
+    pub fn insert(&mut self, sample_count: u32, value: T) {
+        self.0.insert(sample_count, value);
+    }
+
+    /// Build the values for every stage quality ahead of time, other than
+    /// the one for the sample count in use.
+    pub fn warm_up(
+        &mut self,
+        current_sample_count: u32,
+        mut build: impl FnMut(u32) -> Result<T, Error>,
+    ) -> Result<(), Error> {
+        for &quality in &WARM_UP_QUALITIES {
+            let sample_count = sample_count_for(quality);
+            if sample_count != current_sample_count && !self.0.contains_key(&sample_count) {
+                let value = build(sample_count)?;
+                self.0.insert(sample_count, value);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn sample_counts_are_capped_at_four() {
+        assert_eq!(sample_count_for(StageQuality::Low), 1);
+        assert_eq!(sample_count_for(StageQuality::Medium), 2);
+        assert_eq!(sample_count_for(StageQuality::High), 4);
+        assert_eq!(sample_count_for(StageQuality::High16x16Linear), 4);
+    }
+
+    #[test]
+    fn switching_back_reuses_the_old_value() {
+        let mut cache = SampleCountCache::default();
+        let builds = RefCell::new(vec![]);
+        let mut build = |sample_count| {
+            builds.borrow_mut().push(sample_count);
+            Ok(sample_count * 10)
+        };
+
+        // Switch from 4 samples to 2, and back again.
+        let current = cache.take_or_build(2, &mut build).unwrap();
+        cache.insert(4, 40);
+        assert_eq!(current, 20);
+        let current = cache.take_or_build(4, &mut build).unwrap();
+        cache.insert(2, 20);
+        assert_eq!(current, 40);
+
+        assert_eq!(*builds.borrow(), vec![2]);
+    }
+
+    #[test]
+    fn warm_up_builds_each_other_sample_count_once() {
+        let mut cache = SampleCountCache::default();
+        let builds = RefCell::new(vec![]);
+        let mut build = |sample_count| {
+            builds.borrow_mut().push(sample_count);
+            Ok(sample_count * 10)
+        };
+
+        cache.warm_up(4, &mut build).unwrap();
+        cache.warm_up(4, &mut build).unwrap();
+        assert_eq!(*builds.borrow(), vec![1, 2]);
+
+        // What was built is used when switching, instead of building again.
+        assert_eq!(cache.take_or_build(1, &mut build).unwrap(), 10);
+        assert_eq!(cache.take_or_build(2, &mut build).unwrap(), 20);
+        assert_eq!(*builds.borrow(), vec![1, 2]);
+    }
+
+    #[test]
+    fn warm_up_stops_at_the_first_error() {
+        let mut cache: SampleCountCache<u32> = SampleCountCache::default();
+        let mut builds = vec![];
+        let result = cache.warm_up(4, |sample_count| {
+            builds.push(sample_count);
+            Err("no pipelines".into())
+        });
+        assert!(result.is_err());
+        assert_eq!(builds, vec![1]);
+    }
+}
@@ -0,0 +1,433 @@
+//! Conversion of YUV video frames to RGBA textures on the GPU.
+//!
+//! Decoded planes are uploaded as they are, and a render pass converts them
+//! into the video's bitmap texture. This uploads less than half as much data
+//! as an RGBA frame, and leaves the color conversion to the GPU.
+
+use ruffle_core::backend::render::Yuv420Frame;
+use std::borrow::Cow;
+use std::num::NonZeroU32;
+
+/// How many sets of plane textures each video cycles through.
+///
+/// The GPU may still be converting the previous frame when the next one is
+/// decoded, so uploads go to the least recently used set instead of waiting
+/// for the set in flight.
+const RING_SIZE: usize = 3;
+
+/// The pipeline that converts YUV planes to RGBA.
+#[derive(Debug)]
+pub struct YuvConverter {
+    layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+    sampler: wgpu::Sampler,
+}
+
+impl YuvConverter {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: create_debug_label!("YUV conversion shader").as_deref(),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("../shaders/yuv.wgsl"))),
+            flags: wgpu::ShaderFlags::all(),
+        });
+
+        let plane_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStage::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                multisampled: false,
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+            },
+            count: None,
+        };
+        let layout_label = create_debug_label!("YUV plane bind group layout");
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                plane_entry(0),
+                plane_entry(1),
+                plane_entry(2),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler {
+                        comparison: false,
+                        filtering: true,
+                    },
+                    count: None,
+                },
+            ],
+            label: layout_label.as_deref(),
+        });
+
+        let pipeline_layout_label = create_debug_label!("YUV conversion pipeline layout");
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: pipeline_layout_label.as_deref(),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: create_debug_label!("YUV conversion pipeline").as_deref(),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "main_vertex",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "main_fragment",
+                targets: &[wgpu::ColorTargetState {
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    blend: None,
+                    write_mask: wgpu::ColorWrite::ALL,
+                }],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::default(),
+                clamp_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+        });
+
+        // Chroma is upsampled by the sampler, so it must filter linearly.
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: create_debug_label!("YUV plane sampler").as_deref(),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: 100.0,
+            compare: None,
+            anisotropy_clamp: None,
+            border_color: None,
+        });
+
+        Self {
+            layout,
+            pipeline,
+            sampler,
+        }
+    }
+}
+
+/// One set of textures holding the planes of a frame.
+#[derive(Debug)]
+struct PlaneSet {
+    y: wgpu::Texture,
+    u: wgpu::Texture,
+    v: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+}
+
+/// The plane textures of a video, used in turn for successive frames.
+#[derive(Debug)]
+pub struct YuvTextureRing {
+    width: u32,
+    height: u32,
+    chroma_width: u32,
+    chroma_height: u32,
+    sets: Vec<PlaneSet>,
+    next: usize,
+}
+
+impl YuvTextureRing {
+    /// Create plane textures sized for the given frame.
+    pub fn new(device: &wgpu::Device, converter: &YuvConverter, frame: &Yuv420Frame<'_>) -> Self {
+        let chroma_width = frame.chroma_width;
+        let chroma_height = frame.chroma_height();
+
+        let sets = (0..RING_SIZE)
+            .map(|index| {
+                let y = create_plane(device, frame.width, frame.height, "Y", index);
+                let u = create_plane(device, chroma_width, chroma_height, "U", index);
+                let v = create_plane(device, chroma_width, chroma_height, "V", index);
+
+                let y_view = y.create_view(&Default::default());
+                let u_view = u.create_view(&Default::default());
+                let v_view = v.create_view(&Default::default());
+                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    layout: &converter.layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(&y_view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::TextureView(&u_view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 2,
+                            resource: wgpu::BindingResource::TextureView(&v_view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 3,
+                            resource: wgpu::BindingResource::Sampler(&converter.sampler),
+                        },
+                    ],
+                    label: create_debug_label!("YUV planes {} bind group", index).as_deref(),
+                });
+
+                PlaneSet {
+                    y,
+                    u,
+                    v,
+                    bind_group,
+                }
+            })
+            .collect();
+
+        Self {
+            width: frame.width,
+            height: frame.height,
+            chroma_width,
+            chroma_height,
+            sets,
+            next: 0,
+        }
+    }
+
+    /// Whether the planes of a frame fit this ring's textures.
+    pub fn fits(&self, frame: &Yuv420Frame<'_>) -> bool {
+        self.width == frame.width
+            && self.height == frame.height
+            && self.chroma_width == frame.chroma_width
+            && self.chroma_height == frame.chroma_height()
+    }
+
+    /// Upload the planes of a frame, and convert them into `target`.
+    ///
+    /// The conversion is submitted right away, so it is ordered before the
+    /// draw commands of the frame that displays it.
+    pub fn upload(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        converter: &YuvConverter,
+        frame: &Yuv420Frame<'_>,
+        target: &wgpu::Texture,
+    ) {
+        let set = &self.sets[self.next];
+        self.next = (self.next + 1) % self.sets.len();
+
+        write_plane(queue, &set.y, frame.y, self.width, self.height);
+        write_plane(
+            queue,
+            &set.u,
+            frame.u,
+            self.chroma_width,
+            self.chroma_height,
+        );
+        write_plane(
+            queue,
+            &set.v,
+            frame.v,
+            self.chroma_width,
+            self.chroma_height,
+        );
+
+        let target_view = target.create_view(&Default::default());
+        let label = create_debug_label!("YUV conversion encoder");
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: label.as_deref(),
+        });
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                color_attachments: &[wgpu::RenderPassColorAttachment {
+                    view: &target_view,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: true,
+                    },
+                    resolve_target: None,
+                }],
+                depth_stencil_attachment: None,
+                label: None,
+            });
+            render_pass.set_pipeline(&converter.pipeline);
+            render_pass.set_bind_group(0, &set.bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+        queue.submit(Some(encoder.finish()));
+    }
+}
+
+fn create_plane(
+    device: &wgpu::Device,
+    width: u32,
+    height: u32,
+    plane: &str,
+    index: usize,
+) -> wgpu::Texture {
+    let label = create_debug_label!("YUV {} plane {}", plane, index);
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: label.as_deref(),
+        size: wgpu::Extent3d {
+            width: width.max(1),
+            height: height.max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::R8Unorm,
+        usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+    })
+}
+
+fn write_plane(queue: &wgpu::Queue, texture: &wgpu::Texture, data: &[u8], width: u32, height: u32) {
+    let length = width as usize * height as usize;
+    if width == 0 || height == 0 || data.len() < length {
+        log::warn!(
+            "YUV plane is smaller than {}x{}, skipping it",
+            width,
+            height
+        );
+        return;
+    }
+
+    queue.write_texture(
+        wgpu::ImageCopyTexture {
+            texture,
+            mip_level: 0,
+            origin: Default::default(),
+        },
+        &data[..length],
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: NonZeroU32::new(width),
+            rows_per_image: None,
+        },
+        wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    /// Open a device to convert frames with, or `None` if there is no GPU.
+    fn request_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+        let instance = wgpu::Instance::new(wgpu::BackendBit::PRIMARY);
+        let adapter = block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::default(),
+            compatible_surface: None,
+        }))?;
+        block_on(adapter.request_device(&Default::default(), None)).ok()
+    }
+
+    /// Convert a one pixel frame, and read back the pixel.
+    fn convert(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        converter: &YuvConverter,
+        (y, u, v): (u8, u8, u8),
+    ) -> [u8; 4] {
+        let frame = Yuv420Frame {
+            width: 1,
+            height: 1,
+            y: &[y],
+            u: &[u],
+            v: &[v],
+            chroma_width: 1,
+        };
+        let extent = wgpu::Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        };
+        let target = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsage::RENDER_ATTACHMENT | wgpu::TextureUsage::COPY_SRC,
+        });
+        let mut ring = YuvTextureRing::new(device, converter, &frame);
+        ring.upload(device, queue, converter, &frame, &target);
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: wgpu::COPY_BYTES_PER_ROW_ALIGNMENT.into(),
+            usage: wgpu::BufferUsage::COPY_DST | wgpu::BufferUsage::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&Default::default());
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &target,
+                mip_level: 0,
+                origin: Default::default(),
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: NonZeroU32::new(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                    rows_per_image: None,
+                },
+            },
+            extent,
+        );
+        queue.submit(Some(encoder.finish()));
+
+        let mapped = buffer.slice(..).map_async(wgpu::MapMode::Read);
+        device.poll(wgpu::Maintain::Wait);
+        block_on(mapped).unwrap();
+        let pixel = buffer.slice(..).get_mapped_range();
+        [pixel[0], pixel[1], pixel[2], pixel[3]]
+    }
+
+    #[test]
+    fn converts_bt601_limited_range() {
+        let (device, queue) = match request_device() {
+            Some(device) => device,
+            None => return,
+        };
+        let converter = YuvConverter::new(&device);
+
+        let cases = [
+            ((16, 128, 128), [0, 0, 0, 255]),
+            ((235, 128, 128), [255, 255, 255, 255]),
+            ((81, 90, 240), [255, 0, 0, 255]),
+            ((145, 54, 34), [0, 255, 0, 255]),
+            ((41, 240, 110), [0, 0, 255, 255]),
+            // Out of gamut colors are clamped.
+            ((255, 255, 255), [255, 125, 255, 255]),
+        ];
+        for (yuv, expected) in cases.iter() {
+            let rgba = convert(&device, &queue, &converter, *yuv);
+            for (actual, expected) in rgba.iter().zip(expected) {
+                assert!(
+                    (i16::from(*actual) - i16::from(*expected)).abs() <= 2,
+                    "{:?} converted to {:?}, expected {:?}",
+                    yuv,
+                    rgba,
+                    expected
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! The interface between Ruffle and its storage backends.
+//!
+//! This crate holds the `StorageBackend` trait that shared objects are saved
+//! through, so that a frontend can provide its own storage without depending
+//! on the rest of `ruffle_core`. `ruffle_core` re-exports it under
+//! `ruffle_core::backend::storage`.
+
+use downcast_rs::{impl_downcast, Downcast};
+use std::collections::HashMap;
+
+pub trait StorageBackend: Downcast {
+    fn get(&self, name: &str) -> Option<Vec<u8>>;
+
+    fn put(&mut self, name: &str, value: &[u8]) -> bool;
+
+    fn get_size(&self, name: &str) -> Option<usize> {
+        self.get(name).map(|x| x.len())
+    }
+
+    fn remove_key(&mut self, name: &str);
+
+    /// The largest shared object, in bytes, that can be stored.
+    fn max_size(&self) -> usize {
+        usize::MAX
+    }
+}
+impl_downcast!(StorageBackend);
+
+#[derive(Default)]
+pub struct MemoryStorageBackend {
+    map: HashMap<String, Vec<u8>>,
+}
+
+impl StorageBackend for MemoryStorageBackend {
+    fn get(&self, name: &str) -> Option<Vec<u8>> {
+        self.map.get(name).cloned()
+    }
+
+    fn put(&mut self, name: &str, value: &[u8]) -> bool {
+        self.map.insert(name.into(), value.to_vec());
+        true
+    }
+
+    fn remove_key(&mut self, name: &str) {
+        self.map.remove(name);
+    }
+}
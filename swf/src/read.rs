@@ -856,15 +856,18 @@ impl<'a> Reader<'a> {
         } else {
             ColorTransform::new()
         };
+        // Filters and blend modes were added to DefineButton2 in SWF8. Older
+        // exporters left garbage in these reserved bits, which Flash ignores.
+        let has_swf8_flags = version >= 2 && self.version >= 8;
         let mut filters = vec![];
-        if (flags & 0b1_0000) != 0 {
+        if has_swf8_flags && (flags & 0b1_0000) != 0 {
             let num_filters = self.read_u8()?;
             filters.reserve(num_filters as usize);
             for _ in 0..num_filters {
                 filters.push(self.read_filter()?);
             }
         }
-        let blend_mode = if (flags & 0b10_0000) != 0 {
+        let blend_mode = if has_swf8_flags && (flags & 0b10_0000) != 0 {
             self.read_blend_mode()?
         } else {
             BlendMode::Normal
@@ -1179,7 +1182,7 @@ impl<'a> Reader<'a> {
         Ok(zone)
     }
 
-    fn read_define_font_info(&mut self, version: u8) -> Result<Tag<'a>> {
+    pub fn read_define_font_info(&mut self, version: u8) -> Result<Tag<'a>> {
         let id = self.read_u16()?;
 
         let font_name_len = self.read_u8()?;
@@ -1717,7 +1720,15 @@ impl<'a> Reader<'a> {
 
     fn read_gradient(&mut self, shape_version: u8) -> Result<Gradient> {
         let matrix = self.read_matrix()?;
-        let (num_records, spread, interpolation) = self.read_gradient_flags()?;
+        let (num_records, spread, interpolation) = if shape_version >= 4 || self.version >= 8 {
+            self.read_gradient_flags()?
+        } else {
+            // Spread and interpolation modes were added in SWF8. Exporters
+            // for older versions left garbage in these bits, which Flash
+            // ignores.
+            let num_records = (self.read_u8()? & 0b1111).into();
+            (num_records, GradientSpread::Pad, GradientInterpolation::Rgb)
+        };
         let mut records = Vec::with_capacity(num_records);
         for _ in 0..num_records {
             records.push(GradientRecord {
@@ -2986,7 +2997,73 @@ pub mod tests {
 
     #[test]
     fn read_gradient() {
-        // TODO
+        // Identity matrix, 1 record, ratio 0, red.
+        let gradient = Gradient {
+            matrix: Matrix::IDENTITY,
+            spread: GradientSpread::Reflect,
+            interpolation: GradientInterpolation::LinearRgb,
+            records: vec![GradientRecord {
+                ratio: 0,
+                color: Color::from_rgb(0xFF0000, 255),
+            }],
+        };
+        assert_eq!(
+            reader(&[0, 0b0101_0001, 0, 255, 0, 0, 255])
+                .read_gradient(4)
+                .unwrap(),
+            gradient
+        );
+
+        // SWF8 and later movies use them in every version of DefineShape.
+        assert_eq!(
+            Reader::new(&[0, 0b0101_0001, 0, 255, 0, 0], 8)
+                .read_gradient(1)
+                .unwrap(),
+            gradient
+        );
+
+        // Pre-SWF8 movies ignore the spread and interpolation bits, even if they are invalid.
+        let gradient = Gradient {
+            spread: GradientSpread::Pad,
+            interpolation: GradientInterpolation::Rgb,
+            ..gradient
+        };
+        assert_eq!(
+            Reader::new(&[0, 0b1111_0001, 0, 255, 0, 0], 7)
+                .read_gradient(1)
+                .unwrap(),
+            gradient
+        );
+    }
+
+    #[test]
+    fn read_define_button_1_ignores_swf8_flags() {
+        // One up state record with the filter list and blend mode bits set,
+        // followed by an empty action list.
+        let button = Button {
+            id: 1,
+            is_track_as_menu: false,
+            records: vec![ButtonRecord {
+                states: ButtonState::UP,
+                id: 2,
+                depth: 1,
+                matrix: Matrix::IDENTITY,
+                color_transform: ColorTransform::new(),
+                filters: vec![],
+                blend_mode: BlendMode::Normal,
+            }],
+            actions: vec![ButtonAction {
+                conditions: ButtonActionCondition::OVER_DOWN_TO_OVER_UP,
+                key_code: None,
+                action_data: &[0],
+            }],
+        };
+        assert_eq!(
+            reader(&[1, 0, 0b0011_0001, 2, 0, 1, 0, 0, 0, 0])
+                .read_define_button_1()
+                .unwrap(),
+            button
+        );
     }
 
     #[test]
@@ -1,6 +1,36 @@
 //! Tests running SWFs in a headless Ruffle instance.
 //!
 //! Trace output can be compared with correct output from the official Flash Player.
+//!
+//! A test folder may also contain an `input.txt` sidecar, which scripts mouse
+//! and keyboard input to send to the movie at given frames:
+//!
+//! ```text
+//! # <frame> <command> <arguments...>
+//! 1 move 100 50
+//! 2 click 100 50
+//! 3 down 10 10
+//! 4 move 200 10
+//! 4 up 200 10
+//! 5 key 65
+//! 6 text "Hello, world!"
+//! 7 paste "one\r\ntwo"
+//! ```
+//!
+//! Input for a frame is sent just before that frame runs, in the order it is
+//! listed. Coordinates are in viewport pixels, and key codes are Flash key codes.
+//! The commands are `move`, `down`, `up` and `click` (a press and release at
+//! a point), `key_down`, `key_up` and `key` (a press and release), `wheel`
+//! (a number of lines to scroll), `text` (a quoted string to type), and
+//! `paste` (a quoted string pasted through the platform, in which `\r`, `\n`
+//! and `\\` are escapes).
+//!
+//! Interactive tests can also call assertion helpers through
+//! `ExternalInterface.call`:
+//!
+//! * `assert(condition, message)` fails the test if `condition` is falsy.
+//! * `assertEquals(expected, actual, message)` fails the test if the values
+//!   differ.
 
 use approx::assert_relative_eq;
 use ruffle_core::backend::render::RenderBackend;
@@ -10,26 +40,35 @@ use ruffle_core::backend::{
     audio::NullAudioBackend,
     locale::NullLocaleBackend,
     log::LogBackend,
-    navigator::{NullExecutor, NullNavigatorBackend},
+    navigator::{
+        NavigationMethod, NavigatorBackend, NullExecutor, NullNavigatorBackend, OwnedFuture,
+        RequestOptions, SocketEvent, SocketHandle,
+    },
     render::NullRenderer,
     storage::{MemoryStorageBackend, StorageBackend},
-    ui::NullUiBackend,
+    ui::{CustomMouseCursor, MouseCursor, UiBackend},
     video::NullVideoBackend,
 };
+use ruffle_core::config::SpoofedCapabilities;
 use ruffle_core::context::UpdateContext;
+use ruffle_core::events::{KeyCode, KeyLocation, MouseWheelDelta, PlayerEvent};
 use ruffle_core::external::Value as ExternalValue;
 use ruffle_core::external::{ExternalInterfaceMethod, ExternalInterfaceProvider};
+use ruffle_core::indexmap::IndexMap;
+use ruffle_core::loader::Error as LoaderError;
 use ruffle_core::tag_utils::SwfMovie;
 use ruffle_core::Player;
 use ruffle_render_wgpu::target::TextureTarget;
 use ruffle_render_wgpu::wgpu;
 use ruffle_render_wgpu::WgpuRenderBackend;
+use std::borrow::Cow;
 use std::cell::RefCell;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use url::Url;
 
 fn get_img_platform_suffix(info: &wgpu::AdapterInfo) -> String {
     format!("{}-{}", std::env::consts::OS, info.name)
@@ -95,6 +134,24 @@ macro_rules! swf_tests_approx {
     };
 }
 
+// This macro generates test cases for a given list of SWFs using `test_swf_interactive`.
+macro_rules! swf_tests_interactive {
+    ($($(#[$attr:meta])* ($name:ident, $path:expr, $num_frames:literal),)*) => {
+        $(
+        #[test]
+        $(#[$attr])*
+        fn $name() -> Result<(), Error> {
+            set_logger();
+            test_swf_interactive(
+                concat!("tests/swfs/", $path, "/test.swf"),
+                $num_frames,
+                concat!("tests/swfs/", $path, "/output.txt"),
+            )
+        }
+        )*
+    };
+}
+
 // List of SWFs to test.
 // Format: (test_name, test_folder, number_of_frames_to_run)
 // The test folder is a relative to core/tests/swfs
@@ -174,6 +231,13 @@ swf_tests! {
     (movieclip_prototype_extension, "avm1/movieclip_prototype_extension", 1),
     (movieclip_hittest, "avm1/movieclip_hittest", 1),
     (movieclip_hittest_shapeflag, "avm1/movieclip_hittest_shapeflag", 10),
+    (movieclip_hittest_matrix, "avm1/movieclip_hittest_matrix", 1),
+    (classic_tween, "avm1/classic_tween", 5),
+    (blend_mode, "avm1/blend_mode", 1),
+    (scroll_rect, "avm1/scroll_rect", 1),
+    (cache_as_bitmap, "avm1/cache_as_bitmap", 1),
+    (scale9grid, "avm1/scale9grid", 1),
+    (stage_quality, "avm1/stage_quality", 1),
     (movieclip_lockroot, "avm1/movieclip_lockroot", 10),
     #[ignore] (textfield_text, "avm1/textfield_text", 1),
     (recursive_prototypes, "avm1/recursive_prototypes", 2),
@@ -278,6 +342,7 @@ swf_tests! {
     (cross_movie_root, "avm1/cross_movie_root", 5),
     (roots_and_levels, "avm1/roots_and_levels", 1),
     (swf5_encoding, "avm1/swf5_encoding", 1),
+    (string_ops_swf5, "avm1/string_ops_swf5", 1),
     (swf6_case_insensitive, "avm1/swf6_case_insensitive", 1),
     (swf7_case_sensitive, "avm1/swf7_case_sensitive", 1),
     (prototype_enumerate, "avm1/prototype_enumerate", 1),
@@ -383,6 +448,7 @@ swf_tests! {
     (as3_hello_world, "avm2/hello_world", 1),
     (as3_function_call, "avm2/function_call", 1),
     (as3_function_call_via_call, "avm2/function_call_via_call", 1),
+    (as3_function_receivers, "avm2/function_receivers", 1),
     (as3_constructor_call, "avm2/constructor_call", 1),
     (as3_class_methods, "avm2/class_methods", 1),
     (as3_es3_inheritance, "avm2/es3_inheritance", 1),
@@ -469,6 +535,8 @@ swf_tests! {
     (as3_urshift, "avm2/urshift", 1),
     (as3_in, "avm2/in", 1),
     (as3_bytearray, "avm2/bytearray", 1),
+    (as3_date, "avm2/date", 1),
+    (as3_amf3_encoding, "avm2/amf3_encoding", 1),
     (as3_generate_random_bytes, "avm2/generate_random_bytes", 1),
     (as3_get_definition_by_name, "avm2/get_definition_by_name", 1),
     (as3_get_qualified_class_name, "avm2/get_qualified_class_name", 1),
@@ -521,6 +589,14 @@ swf_tests! {
     (as3_movieclip_constr, "avm2/movieclip_constr", 1),
     (as3_lazyinit, "avm2/lazyinit", 1),
     (as3_trace, "avm2/trace", 1),
+    (as3_display_object_filters, "avm2/display_object_filters", 3),
+    (as3_display_object_shadow_filters, "avm2/display_object_shadow_filters", 1),
+    (as3_display_object_gradient_filters, "avm2/display_object_gradient_filters", 3),
+    (as3_blend_mode, "avm2/blend_mode", 4),
+    (as3_scroll_rect, "avm2/scroll_rect", 1),
+    (as3_cache_as_bitmap, "avm2/cache_as_bitmap", 1),
+    (as3_scale9grid, "avm2/scale9grid", 1),
+    (as3_stage_quality, "avm2/stage_quality", 1),
     (as3_displayobjectcontainer_getchildat, "avm2/displayobjectcontainer_getchildat", 1),
     (as3_displayobjectcontainer_getchildbyname, "avm2/displayobjectcontainer_getchildbyname", 1),
     (as3_displayobjectcontainer_addchild, "avm2/displayobjectcontainer_addchild", 1),
@@ -567,6 +643,8 @@ swf_tests! {
     (as3_movieclip_willtrigger, "avm2/movieclip_willtrigger", 3),
     (as3_eventdispatcher_dispatchevent, "avm2/eventdispatcher_dispatchevent", 1),
     (as3_eventdispatcher_dispatchevent_handlerorder, "avm2/eventdispatcher_dispatchevent_handlerorder", 1),
+    (as3_eventdispatcher_dispatchevent_redispatch, "avm2/eventdispatcher_dispatchevent_redispatch", 1),
+    (as3_eventdispatcher_weak_listener, "avm2/eventdispatcher_weak_listener", 1),
     (as3_eventdispatcher_dispatchevent_cancel, "avm2/eventdispatcher_dispatchevent_cancel", 1),
     (as3_eventdispatcher_dispatchevent_this, "avm2/eventdispatcher_dispatchevent_this", 1),
     (as3_movieclip_dispatchevent, "avm2/movieclip_dispatchevent", 1),
@@ -594,12 +672,40 @@ swf_tests! {
     #[ignore] (as3_edittext_html_roundtrip, "avm2/edittext_html_roundtrip", 1),
     (as3_edittext_newline_stripping, "avm2/edittext_newline_stripping", 1),
     (as3_edittext_width_height, "avm2/edittext_width_height", 1),
+    (as3_textfield_selection, "avm2/textfield_selection", 1),
     (as3_shape_drawrect, "avm2/shape_drawrect", 1),
+    (as3_graphics_data, "avm2/graphics_data", 4),
+    (as3_graphics_bitmap_fill, "avm2/graphics_bitmap_fill", 6),
+    (as3_graphics_copy_from, "avm2/graphics_copy_from", 5),
+    (as3_graphics_draw_triangles, "avm2/graphics_draw_triangles", 3),
     (as3_movieclip_drawrect, "avm2/movieclip_drawrect", 1),
     (as3_get_timer, "avm2/get_timer", 1),
+    (as3_as_type_primitives, "avm2/as_type_primitives", 1),
+    (as3_number_to_string_small, "avm2/number_to_string_small", 1),
+    (as3_scope_with_activation, "avm2/scope_with_activation", 1),
+    (as3_uncaught_error_events, "avm2/uncaught_error_events", 3),
+    (as3_for_in_mutation, "avm2/for_in_mutation", 1),
+    (as3_object_prototype_queries, "avm2/object_prototype_queries", 1),
+    (as3_urlloader_events, "avm2/urlloader_events", 1),
+    (as3_urlstream_progressive, "avm2/urlstream_progressive", 1),
+    #[ignore] (as3_number_arithmetic_benchmark, "avm2/number_arithmetic_benchmark", 1),
+    (as3_timers, "avm2/timers", 4),
+    (as3_worker_message_channel, "avm2/worker_message_channel", 3),
+    (as3_worker_timers, "avm2/worker_timers", 5),
+    (as3_property_cache, "avm2/property_cache", 1),
+    (as3_lazy_builtin_classes, "avm2/lazy_builtin_classes", 1),
+    #[ignore] (as3_property_cache_benchmark, "avm2/property_cache_benchmark", 1),
+    (as3_mouseevent_keyboardevent_fields, "avm2/mouseevent_keyboardevent_fields", 2),
+    (as3_keyboardevent_char_code, "avm2/keyboardevent_char_code", 2),
     (as3_op_escxattr, "avm2/op_escxattr", 1),
     (as3_op_escxelem, "avm2/op_escxelem", 1),
     (as3_op_lookupswitch, "avm2/op_lookupswitch", 1),
+    (as3_loader_load_bytes_events, "avm2/loader_load_bytes_events", 2),
+    (as3_loader, "avm2/loader", 9),
+    (as3_loaderinfo_shared_events, "avm2/loaderinfo_shared_events", 1),
+    (as3_avm1movie, "avm2/avm1movie", 3),
+    (as3_morphshape, "avm2/morphshape", 2),
+    (as3_text_snapshot, "avm2/text_snapshot", 1),
     (as3_loaderinfo_properties, "avm2/loaderinfo_properties", 2),
     (as3_loaderinfo_quine, "avm2/loaderinfo_quine", 2),
     (nan_scale, "avm1/nan_scale", 1),
@@ -673,7 +779,13 @@ swf_tests! {
     (as3_bitmap_timeline, "avm2/bitmap_timeline", 1),
     #[ignore] (as3_bitmapdata_embedded, "avm2/bitmapdata_embedded", 1),
     (as3_bitmapdata_constr, "avm2/bitmapdata_constr", 1),
+    (as3_bitmapdata_pixel_batch, "avm2/bitmapdata_pixel_batch", 5),
+    (as3_bitmapdata_copypixels_alpha, "avm2/bitmapdata_copypixels_alpha", 1),
+    (as3_bitmapdata_hittest, "avm2/bitmapdata_hittest", 2),
+    (as3_bitmapdata_apply_filter, "avm2/bitmapdata_apply_filter", 4),
+    (as3_displacement_map_filter, "avm2/displacement_map_filter", 1),
     (as3_bitmap_constr, "avm2/bitmap_constr", 1),
+    (as3_duplicate_definitions, "avm2/duplicate_definitions", 1),
     #[ignore] (as3_bitmap_properties, "avm2/bitmap_properties", 1),
 }
 
@@ -711,6 +823,18 @@ swf_tests_approx! {
     (as3_edittext_font_size, "avm2/edittext_font_size", 1, epsilon = 0.1),
 }
 
+// List of SWFs to test with scripted input and assertion helpers.
+// Format: (test_name, test_folder, number_of_frames_to_run)
+// The input to send is read from "input.txt" in the test folder.
+swf_tests_interactive! {
+    (input_script, "avm1/input_script", 3),
+    (selection_shortcuts, "avm1/selection_shortcuts", 3),
+    (textfield_restrict_maxchars, "avm1/textfield_restrict_maxchars", 2),
+    (ime_composition, "avm1/ime_composition", 4),
+    (textfield_undo_redo, "avm1/textfield_undo_redo", 4),
+    (as3_textfield_paste, "avm2/textfield_paste", 4),
+}
+
 #[test]
 fn external_interface_avm1() -> Result<(), Error> {
     set_logger();
@@ -855,6 +979,255 @@ fn stage_scale_mode() -> Result<(), Error> {
     )
 }
 
+#[test]
+fn as3_stage_resize() -> Result<(), Error> {
+    set_logger();
+    test_swf_with_hooks(
+        "tests/swfs/avm2/stage_resize/test.swf",
+        1,
+        "tests/swfs/avm2/stage_resize/output.txt",
+        |player| {
+            player
+                .lock()
+                .unwrap()
+                .set_viewport_dimensions(900, 900, 1.0);
+            Ok(())
+        },
+        |player| {
+            // Entering and leaving fullscreen resizes the viewport.
+            let mut player = player.lock().unwrap();
+            let ui = player.ui_mut().downcast_mut::<TestUiBackend>().unwrap();
+            ui.fullscreen = true;
+            player.set_viewport_dimensions(1000, 800, 1.0);
+            let ui = player.ui_mut().downcast_mut::<TestUiBackend>().unwrap();
+            ui.fullscreen = false;
+            player.set_viewport_dimensions(900, 900, 1.0);
+            Ok(())
+        },
+        false,
+    )
+}
+
+#[test]
+fn as3_stage_display_state() -> Result<(), Error> {
+    set_logger();
+    test_swf_with_hooks(
+        "tests/swfs/avm2/stage_display_state/test.swf",
+        3,
+        "tests/swfs/avm2/stage_display_state/output.txt",
+        |_| Ok(()),
+        |player| {
+            let mut player = player.lock().unwrap();
+            let ui = player.ui_mut().downcast_mut::<TestUiBackend>().unwrap();
+            std::assert_eq!(ui.fullscreen_requests, vec![true]);
+            ui.fullscreen = true;
+            player.set_viewport_dimensions(1000, 1000, 1.0);
+
+            // Plain fullscreen only lets through keys that can't enter text.
+            player.handle_event(PlayerEvent::KeyDown {
+                key_code: KeyCode::A,
+            });
+            player.handle_event(PlayerEvent::KeyDown {
+                key_code: KeyCode::Space,
+            });
+
+            let ui = player.ui_mut().downcast_mut::<TestUiBackend>().unwrap();
+            std::assert_eq!(ui.fullscreen_requests, vec![true, false]);
+            ui.fullscreen = false;
+            player.set_viewport_dimensions(1000, 1000, 1.0);
+            player.handle_event(PlayerEvent::KeyDown {
+                key_code: KeyCode::A,
+            });
+            Ok(())
+        },
+        false,
+    )
+}
+
+#[test]
+fn frame_budget() -> Result<(), Error> {
+    set_logger();
+    test_swf_with_hooks(
+        "tests/swfs/avm1/frame_budget/test.swf",
+        1,
+        "tests/swfs/avm1/frame_budget/output.txt",
+        |_| Ok(()),
+        |player| {
+            let mut player = player.lock().unwrap();
+            let frame_time = 1000.0 / player.frame_rate();
+            player.set_is_playing(true);
+
+            // A spent budget still runs one frame, and drops the rest.
+            player.set_frame_budget(Some(Duration::from_secs(0)));
+            player.tick(frame_time * 3.5);
+
+            // Without a budget, the player catches up on every frame.
+            player.set_frame_budget(None);
+            player.tick(frame_time * 3.5);
+            Ok(())
+        },
+        false,
+    )
+}
+
+/// `Capabilities` reports spoofed capabilities in place of the real ones.
+#[test]
+fn capabilities_spoofed_avm2() -> Result<(), Error> {
+    set_logger();
+    test_swf_with_hooks(
+        "tests/swfs/avm2/capabilities_spoofed/test.swf",
+        1,
+        "tests/swfs/avm2/capabilities_spoofed/output.txt",
+        |player| {
+            let mut player = player.lock().unwrap();
+            player.set_screen_resolution(800, 600);
+            player.set_spoofed_capabilities(SpoofedCapabilities {
+                version: Some("WIN 11,2,202,235".to_string()),
+                os: Some("Windows 7".to_string()),
+                manufacturer: Some("Adobe Windows".to_string()),
+                player_type: Some("PlugIn".to_string()),
+                language: Some("zh-CN".to_string()),
+                screen_resolution: Some((1920, 1080)),
+                is_debugger: Some(true),
+            });
+            Ok(())
+        },
+        |_| Ok(()),
+        false,
+    )
+}
+
+/// `System.setClipboard` and the general clipboard write text to the
+/// clipboard.
+#[test]
+fn clipboard_avm2() -> Result<(), Error> {
+    set_logger();
+    test_swf_with_hooks(
+        "tests/swfs/avm2/clipboard/test.swf",
+        1,
+        "tests/swfs/avm2/clipboard/output.txt",
+        |_| Ok(()),
+        |player| {
+            let mut player = player.lock().unwrap();
+            let ui = player.ui_mut().downcast_mut::<TestUiBackend>().unwrap();
+            std::assert_eq!(
+                ui.clipboard_writes,
+                vec!["from setClipboard", "from setData", "", "from handler"]
+            );
+            Ok(())
+        },
+        false,
+    )
+}
+
+/// `flash.ui.Mouse` hides the mouse and shows bitmap cursors through the UI
+/// backend.
+#[test]
+fn mouse_cursor_avm2() -> Result<(), Error> {
+    set_logger();
+    test_swf_with_hooks(
+        "tests/swfs/avm2/mouse_cursor/test.swf",
+        1,
+        "tests/swfs/avm2/mouse_cursor/output.txt",
+        |_| Ok(()),
+        |player| {
+            let mut player = player.lock().unwrap();
+            let ui = player.ui_mut().downcast_mut::<TestUiBackend>().unwrap();
+            std::assert!(ui.mouse_hidden);
+            std::assert_eq!(ui.custom_cursors.len(), 1);
+            let cursor = ui.custom_cursors[0].as_ref().unwrap();
+            std::assert_eq!((cursor.width, cursor.height), (2, 3));
+            // The hot spot is clamped to the image.
+            std::assert_eq!(cursor.hot_spot, (1, 1));
+            std::assert_eq!(cursor.unmultiplied_rgba()[..4], [255, 0, 0, 128]);
+            Ok(())
+        },
+        false,
+    )
+}
+
+/// Opening an AVM2 context menu shows the visible custom items without the
+/// built-in ones, and picking one dispatches `menuItemSelect` on it.
+#[test]
+fn context_menu_avm2() -> Result<(), Error> {
+    set_logger();
+    test_swf_with_hooks(
+        "tests/swfs/avm2/context_menu/test.swf",
+        1,
+        "tests/swfs/avm2/context_menu/output.txt",
+        |_| Ok(()),
+        |player| {
+            let mut player = player.lock().unwrap();
+            let items = player.prepare_context_menu();
+            let captions: Vec<_> = items.iter().map(|item| item.caption.as_str()).collect();
+            std::assert_eq!(captions, ["Credits", "Quality"]);
+            std::assert!(items.iter().all(|item| item.separator_before));
+            player.run_context_menu_callback(1);
+            player.clear_custom_menu_items();
+            Ok(())
+        },
+        false,
+    )
+}
+
+/// Requests made with `navigateToURL` and `sendToURL` reach the navigator
+/// with the right window, method and data.
+#[test]
+fn navigate_to_url() -> Result<(), Error> {
+    set_logger();
+    test_swf_with_test_navigator("tests/swfs/avm2/navigate_to_url", 1)
+}
+
+/// A `Socket` exchanges data with `TestNavigatorBackend`'s echo server.
+#[test]
+fn socket_echo() -> Result<(), Error> {
+    set_logger();
+    test_swf_with_test_navigator("tests/swfs/avm2/socket_echo", 4)
+}
+
+/// Runs the `test.swf` in a folder with a `TestNavigatorBackend`, and tests
+/// that its trace output, including the requests traced by the navigator,
+/// matches `output.txt`.
+fn test_swf_with_test_navigator(path: &str, num_frames: u32) -> Result<(), Error> {
+    let base_path = Path::new(path);
+    let (mut executor, channel) = NullExecutor::new();
+    let movie = SwfMovie::from_path(base_path.join("test.swf"), None)?;
+    let trace_output = Rc::new(RefCell::new(Vec::new()));
+
+    let player = Player::new(
+        Box::new(NullRenderer),
+        Box::new(NullAudioBackend::new()),
+        Box::new(TestNavigatorBackend::new(
+            NullNavigatorBackend::with_base_path(base_path, channel),
+            trace_output.clone(),
+        )),
+        Box::new(MemoryStorageBackend::default()),
+        Box::new(NullLocaleBackend::new()),
+        Box::new(NullVideoBackend::new()),
+        Box::new(TestLogBackend::new(trace_output.clone())),
+        Box::new(TestUiBackend::default()),
+    )?;
+    player.lock().unwrap().set_trusted(true);
+    player.lock().unwrap().set_root_movie(Arc::new(movie));
+
+    for _ in 0..num_frames {
+        player.lock().unwrap().run_frame();
+        player.lock().unwrap().update_sockets();
+        executor.poll_all().unwrap();
+    }
+    executor.block_all().unwrap();
+
+    let expected_output = std::fs::read_to_string(base_path.join("output.txt"))?;
+    let trace = trace_output.borrow().join("\n");
+    assert_eq!(
+        trace,
+        expected_output.replace("\r\n", "\n").trim_end(),
+        "ruffle output != flash player output"
+    );
+
+    Ok(())
+}
+
 /// Wrapper around string slice that makes debug output `{:?}` to print string same way as `{}`.
 /// Used in different `assert*!` macros in combination with `pretty_assertions` crate to make
 /// test failures to show nice diffs.
@@ -927,6 +1300,41 @@ fn test_swf_with_hooks(
     Ok(())
 }
 
+/// Loads an SWF and runs it through the Ruffle core for a number of frames,
+/// with the assertion helpers available to it.
+/// Tests that the trace output matches the given expected output, and that
+/// no assertions failed.
+fn test_swf_interactive(
+    swf_path: &str,
+    num_frames: u32,
+    expected_output_path: &str,
+) -> Result<(), Error> {
+    let failures = Rc::new(RefCell::new(Vec::new()));
+    let provider_failures = failures.clone();
+
+    test_swf_with_hooks(
+        swf_path,
+        num_frames,
+        expected_output_path,
+        |player| {
+            player
+                .lock()
+                .unwrap()
+                .add_external_interface(Box::new(AssertionProvider::new(provider_failures)));
+            Ok(())
+        },
+        |_| Ok(()),
+        false,
+    )?;
+
+    let failures = failures.borrow();
+    if !failures.is_empty() {
+        return Err(format!("Assertions failed:\n{}", failures.join("\n")).into());
+    }
+
+    Ok(())
+}
+
 /// Loads an SWF and runs it through the Ruffle core for a number of frames.
 /// Tests that the trace output matches the given expected output.
 /// If a line has a floating point value, it will be compared approxinmately using the given epsilon.
@@ -989,6 +1397,7 @@ fn run_swf(
     check_img &= RUN_IMG_TESTS;
 
     let base_path = Path::new(swf_path).parent().unwrap();
+    let input_script = InputScript::from_path(&base_path.join("input.txt"))?;
     let (mut executor, channel) = NullExecutor::new();
     let movie = SwfMovie::from_path(swf_path, None)?;
     let frame_time = 1000.0 / movie.frame_rate().to_f64();
@@ -1034,8 +1443,9 @@ fn run_swf(
         Box::new(NullLocaleBackend::new()),
         video_backend,
         Box::new(TestLogBackend::new(trace_output.clone())),
-        Box::new(NullUiBackend::new()),
+        Box::new(TestUiBackend::default()),
     )?;
+    player.lock().unwrap().set_trusted(true);
     player.lock().unwrap().set_root_movie(Arc::new(movie));
     player
         .lock()
@@ -1044,7 +1454,8 @@ fn run_swf(
 
     before_start(player.clone())?;
 
-    for _ in 0..num_frames {
+    for frame in 1..=num_frames {
+        input_script.send_frame_input(&mut player.lock().unwrap(), frame);
         player.lock().unwrap().run_frame();
         player.lock().unwrap().update_timers(frame_time);
         executor.poll_all().unwrap();
@@ -1130,6 +1541,543 @@ impl LogBackend for TestLogBackend {
     }
 }
 
+/// A `NavigatorBackend` that traces the pages it is asked to open and the
+/// requests it is asked to fetch, then behaves like `NullNavigatorBackend`.
+///
+/// Sockets can connect to an echo server at `echo.example`, port 7, which
+/// sends back everything it receives, and hangs up after sending back `bye`.
+/// Connecting anywhere else fails with an I/O error.
+struct TestNavigatorBackend {
+    inner: NullNavigatorBackend,
+    trace_output: Rc<RefCell<Vec<String>>>,
+    open_sockets: HashSet<SocketHandle>,
+    socket_events: Vec<(SocketHandle, SocketEvent)>,
+    next_socket_handle: SocketHandle,
+}
+
+impl TestNavigatorBackend {
+    fn new(inner: NullNavigatorBackend, trace_output: Rc<RefCell<Vec<String>>>) -> Self {
+        Self {
+            inner,
+            trace_output,
+            open_sockets: HashSet::new(),
+            socket_events: Vec::new(),
+            next_socket_handle: 0,
+        }
+    }
+
+    fn method_name(method: NavigationMethod) -> &'static str {
+        match method {
+            NavigationMethod::Get => "GET",
+            NavigationMethod::Post => "POST",
+        }
+    }
+}
+
+impl NavigatorBackend for TestNavigatorBackend {
+    fn navigate_to_url(
+        &self,
+        url: String,
+        window: Option<String>,
+        vars_method: Option<(NavigationMethod, IndexMap<String, String>)>,
+    ) {
+        let mut line = format!("navigate {} ({})", url, window.unwrap_or_default());
+        if let Some((method, vars)) = vars_method {
+            let vars: Vec<_> = vars
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect();
+            line += &format!(" {} {}", Self::method_name(method), vars.join("&"));
+        }
+        self.trace_output.borrow_mut().push(line);
+    }
+
+    fn fetch(
+        &self,
+        url: &str,
+        request_options: RequestOptions,
+    ) -> OwnedFuture<Vec<u8>, LoaderError> {
+        let mut line = format!(
+            "fetch {} {}",
+            url,
+            Self::method_name(request_options.method())
+        );
+        if let Some((body, content_type)) = request_options.body() {
+            line += &format!(" {} {}", content_type, String::from_utf8_lossy(body));
+        }
+        for (name, value) in request_options.headers() {
+            line += &format!(" [{}: {}]", name, value);
+        }
+        self.trace_output.borrow_mut().push(line);
+        Box::pin(async { Ok(Vec::new()) })
+    }
+
+    fn time_since_launch(&mut self) -> Duration {
+        self.inner.time_since_launch()
+    }
+
+    fn spawn_future(&mut self, future: OwnedFuture<(), LoaderError>) {
+        self.inner.spawn_future(future)
+    }
+
+    fn resolve_relative_url<'a>(&self, url: &'a str) -> Cow<'a, str> {
+        self.inner.resolve_relative_url(url)
+    }
+
+    fn pre_process_url(&self, url: Url) -> Url {
+        self.inner.pre_process_url(url)
+    }
+
+    fn enforces_cors(&self) -> bool {
+        self.inner.enforces_cors()
+    }
+
+    fn connect_socket(&mut self, host: &str, port: u16, _timeout: Duration) -> SocketHandle {
+        let handle = self.next_socket_handle;
+        self.next_socket_handle += 1;
+        if (host, port) == ("echo.example", 7) {
+            self.open_sockets.insert(handle);
+            self.socket_events.push((handle, SocketEvent::Connected));
+        } else {
+            self.socket_events.push((handle, SocketEvent::IoError));
+        }
+        handle
+    }
+
+    fn send_socket(&mut self, handle: SocketHandle, data: Vec<u8>) {
+        if !self.open_sockets.contains(&handle) {
+            return;
+        }
+        let hang_up = data == b"bye";
+        self.socket_events.push((handle, SocketEvent::Data(data)));
+        if hang_up {
+            self.open_sockets.remove(&handle);
+            self.socket_events.push((handle, SocketEvent::Closed));
+        }
+    }
+
+    fn close_socket(&mut self, handle: SocketHandle) {
+        self.open_sockets.remove(&handle);
+        self.socket_events.retain(|(h, _)| *h != handle);
+    }
+
+    fn poll_sockets(&mut self) -> Vec<(SocketHandle, SocketEvent)> {
+        std::mem::take(&mut self.socket_events)
+    }
+}
+
+/// A `UiBackend` that tracks the keys held down by an input script, and keeps
+/// a clipboard and the mouse cursor, so that they can be tested.
+#[derive(Default)]
+struct TestUiBackend {
+    keys_down: HashSet<KeyCode>,
+    last_key: Option<KeyCode>,
+    clipboard: String,
+
+    /// Everything written to the clipboard, in order.
+    clipboard_writes: Vec<String>,
+
+    mouse_hidden: bool,
+
+    /// Every custom cursor shown, in order, with `None` when one is cleared.
+    custom_cursors: Vec<Option<CustomMouseCursor>>,
+
+    /// Whether the player is fullscreen, as set by the test.
+    fullscreen: bool,
+
+    /// Every request to enter or leave fullscreen, in order.
+    fullscreen_requests: Vec<bool>,
+}
+
+impl TestUiBackend {
+    fn handle_event(&mut self, event: &PlayerEvent) {
+        match *event {
+            PlayerEvent::KeyDown { key_code } => {
+                self.keys_down.insert(key_code);
+                self.last_key = Some(key_code);
+            }
+            PlayerEvent::KeyUp { key_code } => {
+                self.keys_down.remove(&key_code);
+            }
+            _ => (),
+        }
+    }
+}
+
+impl UiBackend for TestUiBackend {
+    fn is_key_down(&self, key: KeyCode) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    fn last_key_code(&self) -> KeyCode {
+        self.last_key.unwrap_or(KeyCode::Unknown)
+    }
+
+    /// The character typed by the last key on a US layout, for letters and
+    /// digits.
+    fn last_key_char(&self) -> Option<char> {
+        let code = self.last_key? as u8;
+        match code {
+            b'A'..=b'Z' if self.is_key_down(KeyCode::Shift) => Some(char::from(code)),
+            b'A'..=b'Z' => Some(char::from(code).to_ascii_lowercase()),
+            b'0'..=b'9' => Some(char::from(code)),
+            96..=105 => Some(char::from(b'0' + code - 96)),
+            _ => None,
+        }
+    }
+
+    /// Where the last key is, taking modifiers to be the ones on the left.
+    fn last_key_location(&self) -> KeyLocation {
+        match self.last_key {
+            Some(KeyCode::Shift) | Some(KeyCode::Control) | Some(KeyCode::Alt) => KeyLocation::Left,
+            Some(key)
+                if (KeyCode::Numpad0 as u8..=KeyCode::NumpadSlash as u8).contains(&(key as u8)) =>
+            {
+                KeyLocation::NumPad
+            }
+            _ => KeyLocation::Standard,
+        }
+    }
+
+    fn mouse_visible(&self) -> bool {
+        !self.mouse_hidden
+    }
+
+    fn set_mouse_visible(&mut self, visible: bool) {
+        self.mouse_hidden = !visible;
+    }
+
+    fn set_mouse_cursor(&mut self, _cursor: MouseCursor) {}
+
+    fn set_custom_mouse_cursor(&mut self, cursor: Option<&CustomMouseCursor>) -> bool {
+        self.custom_cursors.push(cursor.cloned());
+        false
+    }
+
+    fn set_clipboard_content(&mut self, content: String) {
+        self.clipboard_writes.push(content.clone());
+        self.clipboard = content;
+    }
+
+    fn clipboard_content(&mut self) -> Option<String> {
+        Some(self.clipboard.clone())
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        self.fullscreen
+    }
+
+    fn set_fullscreen(&mut self, is_full: bool) {
+        self.fullscreen_requests.push(is_full);
+    }
+
+    fn set_ime_position(&mut self, _position: Option<(f64, f64)>) {}
+
+    fn display_unsupported_message(&self) {}
+
+    fn display_root_movie_download_failed_message(&self) {}
+
+    fn message(&self, _message: &str) {}
+}
+
+/// Input to send to a movie, read from an `input.txt` sidecar.
+#[derive(Debug, Default, PartialEq)]
+struct InputScript {
+    /// Each event, with the frame it's sent before.
+    events: Vec<(u32, PlayerEvent)>,
+}
+
+impl InputScript {
+    /// Read an input script, or an empty one if the file doesn't exist.
+    fn from_path(path: &Path) -> Result<Self, Error> {
+        match std::fs::read_to_string(path) {
+            Ok(script) => Self::parse(&script)
+                .map_err(|e| format!("Invalid input script {:?}: {}", path, e).into()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn parse(script: &str) -> Result<Self, String> {
+        let mut events = Vec::new();
+
+        for (index, line) in script.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |message: &str| format!("line {}: {}", index + 1, message);
+            let mut parts = line.splitn(3, char::is_whitespace);
+            let frame = parts
+                .next()
+                .and_then(|frame| frame.parse::<u32>().ok())
+                .filter(|&frame| frame > 0)
+                .ok_or_else(|| error("expected a frame number starting from 1"))?;
+            let command = parts.next().ok_or_else(|| error("expected a command"))?;
+            let arguments = parts.next().unwrap_or("").trim();
+
+            let position = || -> Result<(f64, f64), String> {
+                let mut coordinates = arguments.split_whitespace().map(str::parse::<f64>);
+                match (coordinates.next(), coordinates.next(), coordinates.next()) {
+                    (Some(Ok(x)), Some(Ok(y)), None) => Ok((x, y)),
+                    _ => Err(error("expected x and y coordinates")),
+                }
+            };
+            let quoted = || -> Result<String, String> {
+                arguments
+                    .strip_prefix('"')
+                    .and_then(|text| text.strip_suffix('"'))
+                    .map(str::to_string)
+                    .ok_or_else(|| error("expected a quoted string"))
+            };
+            let key_code = || -> Result<KeyCode, String> {
+                arguments
+                    .parse::<u8>()
+                    .ok()
+                    .and_then(KeyCode::from_u8)
+                    .ok_or_else(|| error("expected a key code"))
+            };
+
+            match command {
+                "move" => {
+                    let (x, y) = position()?;
+                    events.push((frame, PlayerEvent::MouseMove { x, y }));
+                }
+                "down" => {
+                    let (x, y) = position()?;
+                    events.push((frame, PlayerEvent::MouseMove { x, y }));
+                    events.push((frame, PlayerEvent::MouseDown { x, y }));
+                }
+                "up" => {
+                    let (x, y) = position()?;
+                    events.push((frame, PlayerEvent::MouseMove { x, y }));
+                    events.push((frame, PlayerEvent::MouseUp { x, y }));
+                }
+                "click" => {
+                    let (x, y) = position()?;
+                    events.push((frame, PlayerEvent::MouseMove { x, y }));
+                    events.push((frame, PlayerEvent::MouseDown { x, y }));
+                    events.push((frame, PlayerEvent::MouseUp { x, y }));
+                }
+                "key_down" => {
+                    let key_code = key_code()?;
+                    events.push((frame, PlayerEvent::KeyDown { key_code }));
+                }
+                "key_up" => {
+                    let key_code = key_code()?;
+                    events.push((frame, PlayerEvent::KeyUp { key_code }));
+                }
+                "key" => {
+                    let key_code = key_code()?;
+                    events.push((frame, PlayerEvent::KeyDown { key_code }));
+                    events.push((frame, PlayerEvent::KeyUp { key_code }));
+                }
+                "wheel" => {
+                    let lines = arguments
+                        .parse::<f64>()
+                        .map_err(|_| error("expected a number of lines"))?;
+                    let delta = MouseWheelDelta::Lines(lines);
+                    events.push((frame, PlayerEvent::MouseWheel { delta }));
+                }
+                "text" => {
+                    for codepoint in quoted()?.chars() {
+                        events.push((frame, PlayerEvent::TextInput { codepoint }));
+                    }
+                }
+                "paste" => {
+                    let text = unescape(&quoted()?).ok_or_else(|| error("invalid escape"))?;
+                    events.push((frame, PlayerEvent::Paste { text }));
+                }
+                "ime_start" => events.push((frame, PlayerEvent::ImeStart)),
+                "ime_update" => {
+                    let text = quoted()?;
+                    events.push((frame, PlayerEvent::ImeUpdate { text }));
+                }
+                "ime_commit" => {
+                    let text = quoted()?;
+                    events.push((frame, PlayerEvent::ImeCommit { text }));
+                }
+                _ => return Err(error(&format!("unknown command {}", command))),
+            }
+        }
+
+        // Events are sent in frame order, keeping the order of each frame's
+        // events as written.
+        events.sort_by_key(|(frame, _)| *frame);
+
+        Ok(Self { events })
+    }
+
+    /// Send the input scripted for a frame.
+    fn send_frame_input(&self, player: &mut Player, frame: u32) {
+        for (_, event) in self.events.iter().filter(|(f, _)| *f == frame) {
+            if let Some(ui) = player.ui_mut().downcast_mut::<TestUiBackend>() {
+                ui.handle_event(event);
+            }
+            player.handle_event(event.clone());
+        }
+    }
+}
+
+/// Replace the `\r`, `\n` and `\\` escapes of an input script string.
+fn unescape(text: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        unescaped.push(match c {
+            '\\' => match chars.next()? {
+                'r' => '\r',
+                'n' => '\n',
+                '\\' => '\\',
+                _ => return None,
+            },
+            c => c,
+        });
+    }
+    Some(unescaped)
+}
+
+#[test]
+fn input_script_parsing() -> Result<(), Error> {
+    let script =
+        InputScript::parse("# A comment\n\n2 key 65\n1 click 10 20.5\n2 text \"a b\"\n3 wheel -1")?;
+    let key_code = KeyCode::A;
+    std::assert_eq!(
+        script.events,
+        vec![
+            (1, PlayerEvent::MouseMove { x: 10.0, y: 20.5 }),
+            (1, PlayerEvent::MouseDown { x: 10.0, y: 20.5 }),
+            (1, PlayerEvent::MouseUp { x: 10.0, y: 20.5 }),
+            (2, PlayerEvent::KeyDown { key_code }),
+            (2, PlayerEvent::KeyUp { key_code }),
+            (2, PlayerEvent::TextInput { codepoint: 'a' }),
+            (2, PlayerEvent::TextInput { codepoint: ' ' }),
+            (2, PlayerEvent::TextInput { codepoint: 'b' }),
+            (
+                3,
+                PlayerEvent::MouseWheel {
+                    delta: MouseWheelDelta::Lines(-1.0)
+                }
+            ),
+        ]
+    );
+
+    let script = InputScript::parse("1 ime_start\n1 ime_update \"ni\"\n1 ime_commit \"\"")?;
+    std::assert_eq!(
+        script.events,
+        vec![
+            (1, PlayerEvent::ImeStart),
+            (
+                1,
+                PlayerEvent::ImeUpdate {
+                    text: "ni".to_string()
+                }
+            ),
+            (
+                1,
+                PlayerEvent::ImeCommit {
+                    text: "".to_string()
+                }
+            ),
+        ]
+    );
+
+    let script = InputScript::parse(r#"1 paste "a\r\nb\\n""#)?;
+    std::assert_eq!(
+        script.events,
+        vec![(
+            1,
+            PlayerEvent::Paste {
+                text: "a\r\nb\\n".to_string()
+            }
+        )]
+    );
+
+    assert!(InputScript::parse("0 click 1 2").is_err());
+    assert!(InputScript::parse("1 click 1").is_err());
+    assert!(InputScript::parse("1 key 999").is_err());
+    assert!(InputScript::parse("1 text unquoted").is_err());
+    assert!(InputScript::parse("1 ime_update unquoted").is_err());
+    assert!(InputScript::parse(r#"1 paste "\t""#).is_err());
+    assert!(InputScript::parse("1 jump").is_err());
+
+    Ok(())
+}
+
+/// Provides the assertion helpers of interactive tests, and records the
+/// assertions that failed.
+struct AssertionProvider {
+    failures: Rc<RefCell<Vec<String>>>,
+}
+
+impl AssertionProvider {
+    fn new(failures: Rc<RefCell<Vec<String>>>) -> Self {
+        Self { failures }
+    }
+}
+
+/// Whether an external value counts as true for `assert`.
+fn is_truthy(value: &ExternalValue) -> bool {
+    match value {
+        ExternalValue::Null => false,
+        ExternalValue::Bool(value) => *value,
+        ExternalValue::Number(value) => *value != 0.0 && !value.is_nan(),
+        ExternalValue::String(value) => !value.is_empty(),
+        ExternalValue::Object(_) | ExternalValue::List(_) => true,
+    }
+}
+
+/// The message passed as the argument at `index` of an assertion helper.
+fn assertion_message(args: &[ExternalValue], index: usize) -> String {
+    match args.get(index) {
+        Some(ExternalValue::String(message)) => message.clone(),
+        _ => "(no message)".to_string(),
+    }
+}
+
+impl ExternalInterfaceProvider for AssertionProvider {
+    fn get_method(&self, name: &str) -> Option<Box<dyn ExternalInterfaceMethod>> {
+        let failures = self.failures.clone();
+        match name {
+            "assert" => Some(Box::new(
+                move |_context: &mut UpdateContext<'_, '_, '_>, args: &[ExternalValue]| {
+                    let passed = args.get(0).map(is_truthy).unwrap_or(false);
+                    if !passed {
+                        failures
+                            .borrow_mut()
+                            .push(format!("assert: {}", assertion_message(args, 1)));
+                    }
+                    ExternalValue::Bool(passed)
+                },
+            )),
+            "assertEquals" => Some(Box::new(
+                move |_context: &mut UpdateContext<'_, '_, '_>, args: &[ExternalValue]| {
+                    let expected = args.get(0).unwrap_or(&ExternalValue::Null);
+                    let actual = args.get(1).unwrap_or(&ExternalValue::Null);
+                    let passed = expected == actual;
+                    if !passed {
+                        failures.borrow_mut().push(format!(
+                            "assertEquals: {}: expected {:?}, got {:?}",
+                            assertion_message(args, 2),
+                            expected,
+                            actual
+                        ));
+                    }
+                    ExternalValue::Bool(passed)
+                },
+            )),
+            _ => None,
+        }
+    }
+
+    fn on_callback_available(&self, _name: &str) {}
+
+    fn on_fs_command(&self, _command: &str, _args: &str) -> bool {
+        false
+    }
+}
+
 #[derive(Default)]
 pub struct ExternalInterfaceTestProvider {}
 
@@ -0,0 +1,18 @@
+// Compile with:
+//  mtasc -main -version 8 -header 200:150:30 Test.as -swf test.swf 
+class Test {
+    static function main(current) {
+        // Sets an empty clip's `blendMode` by number and by name, tracing it
+        // after each change. Unknown numbers and names, including names in
+        // the wrong case, leave the blend mode as it was.
+        var clip:Object = current.createEmptyMovieClip("clip", 1);
+        trace("// new clip");
+        trace(clip.blendMode);
+        var values = [3, "screen", "bogus", 14, "SCREEN", 1, 15, "layer"];
+        for (var i = 0; i < values.length; i++) {
+            trace("// set to " + values[i]);
+            clip.blendMode = values[i];
+            trace(clip.blendMode);
+        }
+    }
+}
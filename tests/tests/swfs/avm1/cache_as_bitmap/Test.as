@@ -0,0 +1,32 @@
+// Compile with:
+//  mtasc -main -version 8 -header 200:150:30 Test.as -swf test.swf 
+class Test {
+    static function main(current) {
+        // Traces an empty clip's `cacheAsBitmap` as it's set and cleared. A
+        // clip with filters reads as cached whatever it's set to, until its
+        // filters are removed.
+        var clip:Object = current.createEmptyMovieClip("clip", 1);
+        trace("// new clip");
+        trace(clip.cacheAsBitmap);
+
+        clip.cacheAsBitmap = true;
+        trace("// set to true");
+        trace(clip.cacheAsBitmap);
+
+        clip.cacheAsBitmap = 0;
+        trace("// set to 0");
+        trace(clip.cacheAsBitmap);
+
+        clip.filters = [new flash.filters.BlurFilter()];
+        trace("// with a blur filter");
+        trace(clip.cacheAsBitmap);
+
+        clip.cacheAsBitmap = false;
+        trace("// set to false with a blur filter");
+        trace(clip.cacheAsBitmap);
+
+        clip.filters = [];
+        trace("// without filters");
+        trace(clip.cacheAsBitmap);
+    }
+}
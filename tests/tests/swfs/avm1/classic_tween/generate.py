@@ -0,0 +1,139 @@
+#!/usr/bin/env python3
+"""Generates test.swf for the classic_tween test.
+
+Flash bakes classic motion tweens into the timeline: every frame of the
+tween moves the clip with its own matrix and color transform, with easing
+and rotation direction already applied. The player only has to apply
+them as they are.
+
+The movie tweens two empty clips over five frames:
+
+* `cw` eases in along x (`_x` goes 0, 10, 40, 90, 160), rotates clockwise
+  by a full turn and fades its red channel from a multiplier of 1 and an
+  offset of 0 to a multiplier of 0 and an offset of 128.
+* `ccw` stays put and rotates counterclockwise by a full turn.
+
+Each frame traces the properties the tween changed.
+
+This is assembled rather than compiled from ActionScript because a classic
+tween only exists as the PlaceObject2 tags an authoring tool bakes into each
+frame, which scripts can't create.
+"""
+
+import math
+import os
+import struct
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", ".."))
+
+from swfgen import (
+    END,
+    SHOW_FRAME,
+    Actions,
+    BitWriter,
+    do_action,
+    signed_bits,
+    tag,
+    write_movie,
+)
+
+FRAMES = 5
+
+
+def matrix(degrees, x, y):
+    """A rotation by `degrees` followed by a translation, in twips."""
+    radians = math.radians(degrees)
+    a = round(math.cos(radians) * 65536)
+    b = round(math.sin(radians) * 65536)
+
+    writer = BitWriter()
+    writer.ub(1, 1)  # HasScale
+    n = signed_bits(a, a)
+    writer.ub(n, 5)
+    writer.sb(a, n)
+    writer.sb(a, n)
+    writer.ub(1, 1)  # HasRotate
+    n = signed_bits(b, -b)
+    writer.ub(n, 5)
+    writer.sb(b, n)
+    writer.sb(-b, n)
+    n = signed_bits(x, y)
+    writer.ub(n, 5)
+    writer.sb(x, n)
+    writer.sb(y, n)
+    return writer.to_bytes()
+
+
+def color_transform(red_multiplier, red_offset):
+    """A CXFORMWITHALPHA that only changes the red channel."""
+    mult = [red_multiplier, 256, 256, 256]
+    add = [red_offset, 0, 0, 0]
+    writer = BitWriter()
+    writer.ub(1, 1)  # HasAddTerms
+    writer.ub(1, 1)  # HasMultTerms
+    n = signed_bits(*mult, *add)
+    writer.ub(n, 4)
+    for value in mult + add:
+        writer.sb(value, n)
+    return writer.to_bytes()
+
+
+def place_object_2(depth, transform, cxform=None, character_id=None, name=None):
+    flags = 0x04  # HasMatrix
+    if cxform is not None:
+        flags |= 0x08
+    if character_id is None:
+        flags |= 0x01  # Move
+    else:
+        flags |= 0x02
+    if name is not None:
+        flags |= 0x20
+    body = struct.pack("<BH", flags, depth)
+    if character_id is not None:
+        body += struct.pack("<H", character_id)
+    body += transform
+    if cxform is not None:
+        body += cxform
+    if name is not None:
+        body += name.encode() + b"\0"
+    return tag(26, body)
+
+
+def tween_frame(k):
+    """Place the clips on the first frame, and move them after that."""
+    t = k / (FRAMES - 1)
+    x = round(3200 * t * t)
+    first = k == 0
+    cw = place_object_2(
+        1,
+        matrix(360 * t, x, 2000),
+        color_transform(round(256 * (1 - t)), round(128 * t)),
+        character_id=1 if first else None,
+        name="cw" if first else None,
+    )
+    ccw = place_object_2(
+        2,
+        matrix(-360 * t, 4000, 4000),
+        character_id=1 if first else None,
+        name="ccw" if first else None,
+    )
+    return cw + ccw
+
+
+def trace_frame(k):
+    actions = Actions()
+    actions.push("// frame {}".format(k + 1)).trace()
+    paths = ["cw._x", "cw._rotation", "cw._xscale", "cw.transform.colorTransform", "ccw._rotation"]
+    for path in paths:
+        actions.push(path + ":").trace()
+        actions.trace_value(path)
+    return do_action(actions)
+
+
+empty_sprite = tag(39, struct.pack("<HH", 1, 1) + SHOW_FRAME + END)
+
+frames = [tween_frame(k) + trace_frame(k) for k in range(FRAMES)]
+frames[0] = empty_sprite + frames[0]
+
+write_movie(os.path.join(os.path.dirname(__file__), "test.swf"), frames, version=8)
@@ -0,0 +1,29 @@
+#!/usr/bin/env python3
+"""Generates test.swf for the frame_budget test.
+
+Each frame traces its own number. The test runs the first frame, then ticks
+the player with enough time for several frames, first with a zero frame
+budget and then without one.
+
+This is assembled rather than compiled from ActionScript because the test
+counts frames, and needs actions on each of eight frames of the root
+timeline, which a script-only compiler can't place.
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", ".."))
+
+from swfgen import Actions, do_action, write_movie
+
+
+def trace_frame(frame):
+    return do_action(Actions().push("frame {}".format(frame)).trace())
+
+
+write_movie(
+    os.path.join(os.path.dirname(__file__), "test.swf"),
+    [trace_frame(frame) for frame in range(1, 9)],
+    version=8,
+)
@@ -0,0 +1,53 @@
+#!/usr/bin/env python3
+"""Generates test.swf for the ime_composition test.
+
+The movie has an empty input text field `a`, and a `System.IME` listener
+that traces the text it's told about. `input.txt` focuses `a`, composes
+text with an input method and commits it, then cancels a second
+composition. The text of `a` is traced after each step.
+
+This is assembled rather than compiled from ActionScript because the field
+is a DefineEditText tag placed on the timeline, and it's traced by actions
+on each frame, between the steps of `input.txt`. A script-only compiler
+can't place either.
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", ".."))
+
+from swfgen import Actions, define_edit_text, do_action, place_object, write_movie
+
+
+def trace_text(label):
+    actions = Actions()
+    actions.push(label).trace()
+    actions.trace_value("a.text")
+    return do_action(actions)
+
+
+on_composition = Actions()
+on_composition.push("onIMEComposition: ").get("s").add2().trace()
+
+listener = Actions()
+listener.push("listener", 0).init_object().set_variable()
+listener.get("listener").push("onIMEComposition").function(["s"], on_composition)
+listener.set_member()
+listener.get("listener").push(1).get("System.IME").push("addListener").call_method()
+listener.pop()
+
+frame_1 = define_edit_text(1, 200, 40, text="")
+frame_1 += place_object(1, 1, name="a")
+frame_1 += do_action(listener)
+
+write_movie(
+    os.path.join(os.path.dirname(__file__), "test.swf"),
+    [
+        frame_1 + trace_text("// new field"),
+        trace_text("// composing"),
+        trace_text("// committed"),
+        trace_text("// cancelled"),
+    ],
+    version=8,
+)
@@ -0,0 +1,16 @@
+// Frame 1
+var listener = new Object();
+listener.onMouseDown = function() {
+	trace("onMouseDown");
+};
+listener.onKeyDown = function() {
+	trace("onKeyDown " + Key.getCode());
+};
+Mouse.addListener(listener);
+Key.addListener(listener);
+
+// Frame 2 is empty; `input.txt` sends a click and a key press before it runs.
+
+// Frame 3
+flash.external.ExternalInterface.call("assertEquals", 65, Key.getCode(), "last key code");
+trace("done");
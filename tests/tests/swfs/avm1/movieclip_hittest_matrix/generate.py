@@ -0,0 +1,232 @@
+#!/usr/bin/env python3
+"""Generates test.swf for the movieclip_hittest_matrix test.
+
+The movie places a handful of clips on the root timeline and traces the
+result of `hitTest` in each of its modes:
+
+* `tri`, `tri2` and `hidden` contain a right triangle with its right angle
+  at the top left, so the bottom right half of their bounding box is empty.
+* `hidden` has `_visible` set to false.
+* `masked` is a 100x100 square masked by `maskclip`, a 20x20 square in its
+  top left corner.
+* `container` holds another triangle clip, `inner`, at its origin.
+
+This is assembled rather than compiled from ActionScript because the clips
+are DefineShape and DefineSprite characters placed on the root timeline,
+which only an authoring tool can make, and scripts can't create.
+"""
+
+import struct
+
+
+class BitWriter:
+    def __init__(self):
+        self.bits = []
+
+    def ub(self, value, n):
+        for i in reversed(range(n)):
+            self.bits.append((value >> i) & 1)
+
+    def sb(self, value, n):
+        self.ub(value & ((1 << n) - 1), n)
+
+    def to_bytes(self):
+        bits = self.bits + [0] * (-len(self.bits) % 8)
+        return bytes(
+            int("".join(map(str, bits[i : i + 8])), 2) for i in range(0, len(bits), 8)
+        )
+
+
+def signed_bits(*values):
+    return max(max(abs(v) for v in values).bit_length() + 1, 2)
+
+
+def rect(x_min, x_max, y_min, y_max):
+    writer = BitWriter()
+    n = signed_bits(x_min, x_max, y_min, y_max)
+    writer.ub(n, 5)
+    for v in (x_min, x_max, y_min, y_max):
+        writer.sb(v, n)
+    return writer.to_bytes()
+
+
+def tag(code, body):
+    if len(body) < 0x3F:
+        return struct.pack("<H", (code << 6) | len(body)) + body
+    return struct.pack("<HI", (code << 6) | 0x3F, len(body)) + body
+
+
+def define_shape(shape_id, points):
+    """A solid red polygon through `points`, in twips."""
+    xs = [x for x, _ in points]
+    ys = [y for _, y in points]
+    body = struct.pack("<H", shape_id) + rect(min(xs), max(xs), min(ys), max(ys))
+    body += bytes([1, 0x00, 0xFF, 0x00, 0x00])  # One solid red fill style.
+    body += bytes([0])  # No line styles.
+
+    writer = BitWriter()
+    writer.ub(1, 4)  # NumFillBits
+    writer.ub(0, 4)  # NumLineBits
+
+    # Style change: move to the first point, select fill style 1.
+    x, y = points[0]
+    writer.ub(0, 1)  # TypeFlag
+    writer.ub(0b00101, 5)  # StateFillStyle1 | StateMoveTo
+    n = signed_bits(x, y)
+    writer.ub(n, 5)
+    writer.sb(x, n)
+    writer.sb(y, n)
+    writer.ub(1, 1)  # FillStyle1
+
+    for (x1, y1), (x2, y2) in zip(points, points[1:] + points[:1]):
+        dx, dy = x2 - x1, y2 - y1
+        n = signed_bits(dx, dy)
+        writer.ub(1, 1)  # TypeFlag
+        writer.ub(1, 1)  # StraightFlag
+        writer.ub(n - 2, 4)
+        writer.ub(1, 1)  # GeneralLineFlag
+        writer.sb(dx, n)
+        writer.sb(dy, n)
+
+    writer.ub(0, 6)  # EndShapeRecord
+    return tag(2, body + writer.to_bytes())
+
+
+def place_object(depth, character_id, name=None, x=0, y=0):
+    flags = 0x02 | 0x04  # HasCharacter | HasMatrix
+    if name is not None:
+        flags |= 0x20
+    body = struct.pack("<BHH", flags, depth, character_id)
+
+    writer = BitWriter()
+    writer.ub(0, 1)  # HasScale
+    writer.ub(0, 1)  # HasRotate
+    n = signed_bits(x * 20, y * 20)
+    writer.ub(n, 5)
+    writer.sb(x * 20, n)
+    writer.sb(y * 20, n)
+    body += writer.to_bytes()
+
+    if name is not None:
+        body += name.encode() + b"\0"
+    return tag(26, body)
+
+
+def define_sprite(sprite_id, shape_id, name=None):
+    tags = place_object(1, shape_id, name) + tag(1, b"") + tag(0, b"")
+    return tag(39, struct.pack("<HH", sprite_id, 1) + tags)
+
+
+def push(*values):
+    body = b""
+    for v in values:
+        if isinstance(v, bool):
+            body += bytes([5, int(v)])
+        elif isinstance(v, int):
+            body += bytes([7]) + struct.pack("<i", v)
+        else:
+            body += bytes([0]) + v.encode() + b"\0"
+    return bytes([0x96]) + struct.pack("<H", len(body)) + body
+
+
+GET_VARIABLE = bytes([0x1C])
+SET_MEMBER = bytes([0x4F])
+CALL_METHOD = bytes([0x52])
+POP = bytes([0x17])
+TRACE = bytes([0x26])
+
+
+def call(clip, method, *args):
+    code = push(*reversed(args)) if args else b""
+    return code + push(len(args), clip) + GET_VARIABLE + push(method) + CALL_METHOD
+
+
+def trace_hit_test(clip, *args):
+    label = "{}.hitTest({})".format(clip, ", ".join(str(a).lower() for a in args))
+    code = push("// " + label) + TRACE
+    if len(args) == 1:
+        # Clip-vs-clip: the argument is the other clip, not its name.
+        code += push(args[0]) + GET_VARIABLE + push(1, clip) + GET_VARIABLE
+        code += push("hitTest") + CALL_METHOD
+    else:
+        code += call(clip, "hitTest", *args)
+    return code + TRACE + push("") + TRACE
+
+
+def actions():
+    code = push("hidden") + GET_VARIABLE + push("_visible", False) + SET_MEMBER
+    code += push("maskclip") + GET_VARIABLE + push(1, "masked") + GET_VARIABLE
+    code += push("setMask") + CALL_METHOD + POP
+
+    checks = [
+        # Inside the triangle.
+        ("tri", 110, 110),
+        ("tri", 110, 110, True),
+        # Inside the bounding box, outside the triangle.
+        ("tri", 190, 190),
+        ("tri", 190, 190, True),
+        # Outside the bounding box.
+        ("tri", 300, 300),
+        ("tri", 300, 300, True),
+        # Invisible clips are still hit in both modes.
+        ("hidden", 310, 110),
+        ("hidden", 310, 110, True),
+        ("hidden", 390, 190, True),
+        # The bounding box ignores masks, but the shape test respects them.
+        ("masked", 150, 300),
+        ("masked", 150, 300, True),
+        ("masked", 110, 260, True),
+        # Masks can be hit-tested directly.
+        ("maskclip", 110, 260),
+        ("maskclip", 110, 260, True),
+        ("maskclip", 150, 300, True),
+        # Points are in stage coordinates, even for nested clips.
+        ("container.inner", 410, 260, True),
+        ("container.inner", 490, 340),
+        ("container.inner", 490, 340, True),
+        ("container.inner", 110, 110, True),
+        # Clip-vs-clip always compares bounding boxes.
+        ("tri", "tri2"),
+        ("tri2", "tri"),
+        ("tri", "hidden"),
+        ("masked", "tri2"),
+        ("maskclip", "tri2"),
+    ]
+    for check in checks:
+        code += trace_hit_test(*check)
+    return tag(12, code + b"\0")
+
+
+def main():
+    triangle = [(0, 0), (2000, 0), (0, 2000)]
+    square = [(0, 0), (2000, 0), (2000, 2000), (0, 2000)]
+    small_square = [(0, 0), (400, 0), (400, 400), (0, 400)]
+
+    tags = tag(9, bytes([0xFF, 0xFF, 0xFF]))
+    tags += define_shape(1, triangle)
+    tags += define_shape(2, square)
+    tags += define_shape(3, small_square)
+    tags += define_sprite(4, 1)
+    tags += define_sprite(5, 2)
+    tags += define_sprite(6, 3)
+    tags += define_sprite(7, 4, "inner")
+    tags += place_object(1, 4, "tri", 100, 100)
+    tags += place_object(2, 4, "tri2", 180, 180)
+    tags += place_object(3, 4, "hidden", 300, 100)
+    tags += place_object(4, 5, "masked", 100, 250)
+    tags += place_object(5, 6, "maskclip", 100, 250)
+    tags += place_object(6, 7, "container", 400, 250)
+    tags += actions()
+    tags += tag(1, b"")
+    tags += tag(0, b"")
+
+    header = rect(0, 550 * 20, 0, 400 * 20) + struct.pack("<HH", 24 << 8, 1)
+    body = header + tags
+    swf = b"FWS" + bytes([8]) + struct.pack("<I", 8 + len(body)) + body
+
+    with open("test.swf", "wb") as f:
+        f.write(swf)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,41 @@
+// Compile with:
+//  mtasc -main -version 8 -header 200:150:30 Test.as -swf test.swf 
+class Test {
+    static function main(current) {
+        // Draws a 100x100 square in a clip, gives it a `scale9Grid` and
+        // traces the grid it gives back, which doesn't change when the
+        // returned rectangle does. It then scales the clip, which is sliced
+        // by the grid but keeps the size its scale gives it, and removes the
+        // grid again.
+        var clip:Object = current.createEmptyMovieClip("clip", 1);
+        clip.beginFill(0xFF0000);
+        clip.moveTo(0, 0);
+        clip.lineTo(100, 0);
+        clip.lineTo(100, 100);
+        clip.lineTo(0, 100);
+        clip.lineTo(0, 0);
+        clip.endFill();
+
+        trace("// no grid");
+        trace(clip.scale9Grid);
+
+        trace("// grid");
+        clip.scale9Grid = new flash.geom.Rectangle(10, 15, 80, 70);
+        trace(clip.scale9Grid);
+        var grid:Object = clip.scale9Grid;
+        grid.width = 0;
+        trace(clip.scale9Grid);
+
+        trace("// scaled");
+        clip._xscale = 300;
+        clip._yscale = 50;
+        trace(clip._width);
+        trace(clip._height);
+
+        trace("// removed");
+        clip.scale9Grid = null;
+        trace(clip.scale9Grid);
+        trace(clip._width);
+        trace(clip._height);
+    }
+}
@@ -0,0 +1,56 @@
+// Compile with:
+//  mtasc -main -version 8 -header 200:150:30 Test.as -swf test.swf 
+class Test {
+    static function main(current) {
+        // Draws a 100x100 square in a clip at (5, 5) and scrolls it to the
+        // rectangle at (10, 20) that's 30x40. Changing the returned
+        // rectangle doesn't scroll the clip, and setting `scrollRect` to
+        // `null` removes it.
+        var clip:Object = current.createEmptyMovieClip("clip", 1);
+        clip.beginFill(0xFF0000);
+        clip.moveTo(0, 0);
+        clip.lineTo(100, 0);
+        clip.lineTo(100, 100);
+        clip.lineTo(0, 100);
+        clip.lineTo(0, 0);
+        clip.endFill();
+        clip._x = 5;
+        clip._y = 5;
+
+        trace("// not scrolled");
+        traceClip(clip);
+
+        trace("// scrolled");
+        clip.scrollRect = {x: 10, y: 20, width: 30, height: 40};
+        traceClip(clip);
+
+        trace("// contents on the stage");
+        var point:Object = {x: 10, y: 20};
+        clip.localToGlobal(point);
+        trace(point.x);
+        trace(point.y);
+
+        trace("// returned rectangle changed");
+        var rect:Object = clip.scrollRect;
+        rect.x = 0;
+        trace(clip.scrollRect);
+
+        trace("// removed");
+        clip.scrollRect = null;
+        traceClip(clip);
+    }
+
+    // Traces the clip's `scrollRect`, its size, and hit tests on either side
+    // of the rectangle's right edge.
+    static function traceClip(clip:Object) {
+        trace("scrollRect:");
+        trace(clip.scrollRect);
+        trace("size:");
+        trace(clip._width);
+        trace(clip._height);
+        trace("hit inside the rectangle:");
+        trace(clip.hitTest(34, 10, true));
+        trace("hit past its right edge:");
+        trace(clip.hitTest(36, 10, true));
+    }
+}
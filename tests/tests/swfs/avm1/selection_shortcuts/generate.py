@@ -0,0 +1,56 @@
+#!/usr/bin/env python3
+"""Generates test.swf for the selection_shortcuts test.
+
+The movie has a read-only text field `a` containing "Hello world", and an
+empty input text field `b` below it. `input.txt` clicks `a`, selects all of
+its text with Ctrl+A and copies it with Ctrl+C, then clicks `b` and pastes
+into it with Cmd+V. The `Selection` APIs are traced after each step.
+
+This is assembled rather than compiled from ActionScript because the fields
+are DefineEditText tags placed on the timeline, and they're traced by
+actions on each frame, between the steps of `input.txt`. A script-only
+compiler can't place either.
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", ".."))
+
+from swfgen import Actions, define_edit_text, do_action, place_object, write_movie
+
+
+def trace_selection(actions):
+    actions.trace_call("Selection.getFocus")
+    actions.trace_call("Selection.getBeginIndex")
+    actions.trace_call("Selection.getEndIndex")
+    actions.trace_call("Selection.getCaretIndex")
+
+
+frame_1 = define_edit_text(1, 200, 40, text="Hello world", read_only=True)
+frame_1 += define_edit_text(2, 200, 40, text="")
+frame_1 += place_object(1, 1, name="a")
+frame_1 += place_object(2, 2, name="b", y=100)
+actions = Actions()
+trace_selection(actions)
+frame_1 += do_action(actions)
+
+actions = Actions()
+actions.push("// a: Ctrl+A, Ctrl+C").trace()
+trace_selection(actions)
+frame_2 = do_action(actions)
+
+actions = Actions()
+actions.push("// b: Cmd+V").trace()
+trace_selection(actions)
+actions.trace_value("b.text")
+actions.push("// Selection.setSelection(1, 3)").trace()
+actions.call("Selection.setSelection", 1, 3).pop()
+trace_selection(actions)
+frame_3 = do_action(actions)
+
+write_movie(
+    os.path.join(os.path.dirname(__file__), "test.swf"),
+    [frame_1, frame_2, frame_3],
+    version=8,
+)
@@ -0,0 +1,25 @@
+// Compile with:
+//  mtasc -main -version 8 -header 200:150:30 Test.as -swf test.swf 
+class Test {
+    static function main(current) {
+        // Sets `_quality` to each quality level and traces what it and
+        // `_highquality` read back, then sets `_highquality` to 0, 1 and 2.
+        // Unknown `_quality` values leave the quality unchanged.
+        traceQuality("default:");
+        var qualities = ["LOW", "medium", "High", "BEST", "16x16", "bogus"];
+        for (var i = 0; i < qualities.length; i++) {
+            _quality = qualities[i];
+            traceQuality("_quality = \"" + qualities[i] + "\":");
+        }
+        for (var level = 0; level <= 2; level++) {
+            _highquality = level;
+            traceQuality("_highquality = " + level + ":");
+        }
+    }
+
+    static function traceQuality(label:String) {
+        trace(label);
+        trace(_quality);
+        trace(_highquality);
+    }
+}
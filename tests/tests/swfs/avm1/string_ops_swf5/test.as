@@ -0,0 +1,14 @@
+// Published as SWF5 from a Western locale, where the euro sign is byte 0x80
+// in Windows-1252. In SWF5 and below, the SWF4 string functions work on the
+// bytes of a string in the locale encoding rather than on its characters.
+
+trace(chr(128));
+trace(ord(chr(128)));
+trace(ord("€"));
+
+// Only the low byte of the character code is used, so this is chr(200).
+trace(chr(456));
+
+trace(length("a€b"));
+trace(substring("a€b", 2, 1));
+trace(substring("a€b", 2, -1));
@@ -0,0 +1,50 @@
+#!/usr/bin/env python3
+"""Generates test.swf for the textfield_restrict_maxchars test.
+
+The movie has an empty input text field `a`. Its `maxChars` and `restrict`
+are traced before and after setting them to 5 and "a-z". Text set from
+script isn't limited by either. `input.txt` then clicks the field and types
+"Hi, you all!", which enters "hiyou": upper case letters are lowered,
+other characters are dropped, and typing stops after five characters.
+
+This is assembled rather than compiled from ActionScript because the field
+is a DefineEditText tag placed on the timeline, and the typed text is traced
+by actions on a second frame. A script-only compiler can't place either.
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", ".."))
+
+from swfgen import Actions, define_edit_text, do_action, place_object, write_movie
+
+
+def set_member(actions, obj, name, value):
+    actions.get(obj).push(name, value).set_member()
+
+
+frame_1 = define_edit_text(1, 200, 40, text="")
+frame_1 += place_object(1, 1, name="a")
+actions = Actions()
+actions.trace_value("a.maxChars")
+actions.trace_value("a.restrict")
+set_member(actions, "a", "maxChars", 5)
+set_member(actions, "a", "restrict", "a-z")
+actions.trace_value("a.maxChars")
+actions.trace_value("a.restrict")
+set_member(actions, "a", "text", "Set From Script")
+actions.trace_value("a.text")
+set_member(actions, "a", "text", "")
+frame_1 += do_action(actions)
+
+actions = Actions()
+actions.push("// typed").trace()
+actions.trace_value("a.text")
+frame_2 = do_action(actions)
+
+write_movie(
+    os.path.join(os.path.dirname(__file__), "test.swf"),
+    [frame_1, frame_2],
+    version=8,
+)
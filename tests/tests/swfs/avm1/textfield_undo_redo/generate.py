@@ -0,0 +1,41 @@
+#!/usr/bin/env python3
+"""Generates test.swf for the textfield_undo_redo test.
+
+The movie has an empty input text field `a`. `input.txt` types "abc" into
+it, which is undone as one edit with Ctrl+Z and redone with Ctrl+Y. The
+text is traced after each step.
+
+This is assembled rather than compiled from ActionScript because the field
+is a DefineEditText tag placed on the timeline, and it's traced by actions
+on each frame, between the steps of `input.txt`. A script-only compiler
+can't place either.
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", ".."))
+
+from swfgen import Actions, define_edit_text, do_action, place_object, write_movie
+
+
+def trace_text(label):
+    actions = Actions()
+    actions.push(label).trace()
+    actions.trace_value("a.text")
+    return do_action(actions)
+
+
+frame_1 = define_edit_text(1, 200, 40, text="")
+frame_1 += place_object(1, 1, name="a")
+
+write_movie(
+    os.path.join(os.path.dirname(__file__), "test.swf"),
+    [
+        frame_1 + trace_text("// new field"),
+        trace_text("// typed abc"),
+        trace_text("// Ctrl+Z"),
+        trace_text("// Ctrl+Y"),
+    ],
+    version=8,
+)
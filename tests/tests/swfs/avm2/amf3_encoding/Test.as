@@ -0,0 +1,159 @@
+package {
+	import flash.display.MovieClip;
+	import flash.net.registerClassAlias;
+	import flash.utils.ByteArray;
+
+	// Values are written with `ByteArray.writeObject` and traced as hex, to
+	// be compared against the bytes Flash Player writes for them. Known Flash
+	// bytes are also read back with `ByteArray.readObject`.
+	public class Test extends MovieClip {
+		public function Test() {
+			registerClassAlias("Point", Point);
+
+			// U29 limits, and integers outside of them that are written as doubles.
+			var ints = [
+				0,
+				0x7F,
+				0x80,
+				0x3FFF,
+				0x4000,
+				0x1FFFFF,
+				0x200000,
+				0x0FFFFFFF,
+				0x10000000,
+				-1,
+				-0x10000000,
+				-0x10000001
+			];
+			for each (var value:int in ints) {
+				dump("int " + value, value);
+			}
+
+			dump("string refs", ["abc", "abc", ""]);
+
+			var object = {a: 1};
+			dump("object refs", [object, object]);
+
+			dump("anonymous traits", [{a: 1}, {a: 2}]);
+
+			dump("class traits", [{a: 1}, newPoint(1, 2), newPoint(3, 4)]);
+
+			var sparse = [];
+			sparse[0] = 1;
+			sparse[2] = 3;
+			dump("sparse array", sparse);
+
+			var associative = [1, 2];
+			associative.key = "v";
+			dump("associative array", associative);
+
+			var bytes = new ByteArray();
+			bytes.writeByte(1);
+			bytes.writeByte(2);
+			bytes.writeByte(3);
+			dump("bytearray", bytes);
+
+			dump("date", new Date(1000));
+			var date = new Date(1000);
+			dump("date refs", [date, date]);
+
+			// Bytes written by Flash Player.
+			var result = read([0x04, 0xBF, 0xFF, 0xFF, 0xFF]);
+			trace("read int 268435455:", result);
+			result = read([0x04, 0xFF, 0xFF, 0xFF, 0xFF]);
+			trace("read int -1:", result);
+
+			result = read([0x0A, 0x0B, 0x01, 0x03, 0x61, 0x04, 0x01, 0x01]);
+			trace("read object a:", result.a);
+
+			result = read([0x09, 0x05, 0x01, 0x0A, 0x0B, 0x01, 0x03, 0x61, 0x04, 0x01, 0x01, 0x0A, 0x02]);
+			trace("read object refs:", result[0] === result[1]);
+
+			result = read([
+				0x09, 0x05, 0x01,
+				0x0A, 0x0B, 0x01, 0x03, 0x61, 0x04, 0x01, 0x01,
+				0x0A, 0x01, 0x00, 0x04, 0x02, 0x01
+			]);
+			trace("read traits refs:", result[1].a);
+
+			// "Point" is 0x50 0x6F 0x69 0x6E 0x74.
+			result = read([
+				0x0A, 0x23, 0x0B, 0x50, 0x6F, 0x69, 0x6E, 0x74,
+				0x03, 0x78, 0x03, 0x79, 0x04, 0x01, 0x04, 0x02
+			]);
+			trace("read class is Point:", result is Point);
+			trace("read class x:", result.x);
+			trace("read class y:", result.y);
+
+			result = read([0x09, 0x03, 0x03, 0x32, 0x04, 0x03, 0x01, 0x04, 0x01]);
+			trace("read sparse length:", result.length);
+			trace("read sparse 2:", result[2]);
+
+			// "key" is 0x6B 0x65 0x79.
+			result = read([
+				0x09, 0x05, 0x07, 0x6B, 0x65, 0x79,
+				0x06, 0x03, 0x76, 0x01, 0x04, 0x01, 0x04, 0x02
+			]);
+			trace("read associative length:", result.length);
+			trace("read associative key:", result.key);
+			trace("read associative 1:", result[1]);
+
+			result = read([0x0C, 0x07, 0x01, 0x02, 0x03]);
+			trace("read bytearray:", hex(result));
+
+			result = read([0x08, 0x01, 0x40, 0x8F, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00]);
+			trace("read date:", result.getTime());
+
+			result = roundtrip(new Date(1000));
+			trace("roundtrip date is Date:", result is Date);
+			trace("roundtrip date:", result.getTime());
+		}
+
+		function newPoint(x, y) {
+			var point = new Point();
+			point.x = x;
+			point.y = y;
+			return point;
+		}
+
+		function hex(bytes:ByteArray):String {
+			var digits = "0123456789abcdef";
+			var result = "";
+			bytes.position = 0;
+			while (bytes.bytesAvailable > 0) {
+				var byte = bytes.readUnsignedByte();
+				result = result + " " + digits.charAt(byte >> 4) + digits.charAt(byte & 15);
+			}
+			return result;
+		}
+
+		function dump(label:String, value:*) {
+			var bytes = new ByteArray();
+			bytes.writeObject(value);
+			trace(label + ":" + hex(bytes));
+		}
+
+		// Reads a value out of the given bytes.
+		function read(data:Array) {
+			var bytes = new ByteArray();
+			while (data.length > 0) {
+				bytes.writeByte(data.shift());
+			}
+			bytes.position = 0;
+			return bytes.readObject();
+		}
+
+		// Writes a value and reads it back.
+		function roundtrip(value) {
+			var bytes = new ByteArray();
+			bytes.writeObject(value);
+			bytes.position = 0;
+			return bytes.readObject();
+		}
+	}
+}
+
+class Point {
+	public var x;
+	public var y;
+}
@@ -0,0 +1,36 @@
+#!/usr/bin/env python3
+"""Generates test.swf for the as_type_primitives test.
+
+Primitives that pass an `as` check or a `coerce` are left as they were,
+rather than being boxed into objects.
+
+This is assembled rather than compiled from ActionScript because it tests the
+`astype` and `coerce` opcodes, which compilers don't emit for `as` casts and
+`Object()` calls.
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", ".."))
+
+from swfgen import Abc, write_avm2_movie
+
+abc = Abc()
+doc = abc.document_class("Test")
+
+ctor = doc.constructor
+ctor.pushbyte(5).astype("Number").typeof().trace_top("typeof (5 as Number):")
+ctor.pushbyte(5).getlex("Number").astypelate().typeof()
+ctor.trace_top("typeof (5 as late Number):")
+ctor.pushdouble(2.5).astype("Number").pushbyte(1).add()
+ctor.trace_top("(2.5 as Number) + 1:")
+ctor.pushstring("a").astype("Number").trace_top("'a' as Number:")
+ctor.pushnull().astype("Object").trace_top("null as Object:")
+ctor.pushtrue().astype("Boolean").typeof().trace_top("typeof (true as Boolean):")
+ctor.pushbyte(-1).astype("uint").trace_top("-1 as uint:")
+ctor.pushbyte(3).astype("uint").trace_top("3 as uint:")
+ctor.pushdouble(1.5).astype("int").trace_top("1.5 as int:")
+ctor.pushbyte(5).coerce("Object").typeof().trace_top("typeof Object(5):")
+
+write_avm2_movie(os.path.join(os.path.dirname(__file__), "test.swf"), abc, doc)
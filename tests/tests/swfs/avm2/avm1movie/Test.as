@@ -0,0 +1,38 @@
+package {
+	import flash.display.AVM1Movie;
+	import flash.display.DisplayObjectContainer;
+	import flash.display.Loader;
+	import flash.display.MovieClip;
+	import flash.events.Event;
+	import flash.net.URLRequest;
+
+	// Loads `child.swf`, an AVM1 movie made by `generate.py`, into a
+	// `Loader`. Once the load is complete it checks that the content is an
+	// `AVM1Movie`, which AVM2 can't look inside, and what
+	// `contentLoaderInfo` says about it. The child keeps running its own
+	// timeline, and still sees itself as `_root`.
+	public class Test extends MovieClip {
+		var loader;
+
+		public function Test() {
+			loader = new Loader();
+			addChild(loader);
+			loader.contentLoaderInfo.addEventListener("complete", onComplete);
+			trace("// load child.swf");
+			loader.load(new URLRequest("child.swf"));
+		}
+
+		function onComplete(event:Event) {
+			trace("// complete");
+			var content = loader.content;
+			trace("content is AVM1Movie:", content is AVM1Movie);
+			trace("content is DisplayObjectContainer:", content is DisplayObjectContainer);
+			trace("content's parent is the Loader:", content.parent === loader);
+			trace("Loader numChildren:", loader.numChildren);
+			var info = loader.contentLoaderInfo;
+			trace("contentLoaderInfo.content is the content:", info.content === content);
+			trace("actionScriptVersion:", info.actionScriptVersion);
+			trace("swfVersion:", info.swfVersion);
+		}
+	}
+}
@@ -0,0 +1,28 @@
+#!/usr/bin/env python3
+"""Generates child.swf for the avm1movie test.
+
+`child.swf` is the AVM1 movie that `Test.as` loads. It traces from the
+actions on each of its two frames, and checks that it still sees itself as
+`_root`.
+
+This is assembled rather than compiled from ActionScript because the child
+needs actions on two frames of its own timeline, which a script-only
+compiler can't place.
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", ".."))
+
+from swfgen import Actions, do_action, write_movie
+
+frame1 = Actions().push("avm1: frame 1").trace()
+frame1.push("avm1: this == _root:").trace()
+frame1.get("this").get("_root").equals2().trace()
+frame2 = Actions().push("avm1: frame 2").trace().stop()
+write_movie(
+    os.path.join(os.path.dirname(__file__), "child.swf"),
+    [do_action(frame1), do_action(frame2)],
+    version=8,
+)
@@ -0,0 +1,135 @@
+package {
+	import flash.display.BitmapData;
+	import flash.display.MovieClip;
+	import flash.display.Shape;
+	import flash.events.Event;
+	import flash.events.UncaughtErrorEvent;
+	import flash.filters.ColorMatrixFilter;
+	import flash.filters.ConvolutionFilter;
+	import flash.geom.Point;
+	import flash.geom.Rectangle;
+
+	// Traces the defaults of `ColorMatrixFilter` and `ConvolutionFilter`, then
+	// filters a three pixel wide bitmap with `applyFilter`: a color matrix
+	// swapping red and blue, convolutions that move pixels with and without
+	// clamping, one with a divisor, bias and preserved alpha on part of the
+	// bitmap, and a copy to another point. It also sets both filters as the
+	// `filters` of a shape and traces what the shape gives back. The
+	// `enterFrame` listener then calls `applyFilter` with a null filter and
+	// with a shape as the source, tracing the errors that reach
+	// `uncaughtError`.
+	public class Test extends MovieClip {
+		static const SWAP_RED_AND_BLUE = [
+			0, 0, 1, 0, 0,
+			0, 1, 0, 0, 0,
+			1, 0, 0, 0, 0,
+			0, 0, 0, 1, 0
+		];
+		static const CONVOLUTION_PROPERTIES = [
+			"matrixX",
+			"matrixY",
+			"matrix",
+			"divisor",
+			"bias",
+			"preserveAlpha",
+			"clamp",
+			"color",
+			"alpha"
+		];
+
+		var bitmap;
+		var step:int = 0;
+
+		public function Test() {
+			loaderInfo.uncaughtErrorEvents.addEventListener("uncaughtError", onUncaughtError);
+
+			trace("// defaults");
+			trace("ColorMatrixFilter matrix:", new ColorMatrixFilter().matrix);
+			traceFilter(new ConvolutionFilter(), "ConvolutionFilter");
+
+			trace("// applyFilter");
+			bitmap = new BitmapData(3, 1, true, 0xFF102030);
+			bitmap.setPixel32(1, 0, 0xFF405060);
+			bitmap.setPixel32(2, 0, 0xFF708090);
+			tracePixels("original");
+
+			applyFilter(new Rectangle(0, 0, 3, 1), new Point(0, 0), new ColorMatrixFilter(SWAP_RED_AND_BLUE));
+			tracePixels("red and blue swapped");
+
+			applyFilter(new Rectangle(0, 0, 3, 1), new Point(0, 0), new ConvolutionFilter(3, 1, [0, 0, 1]));
+			tracePixels("moved left and clamped");
+
+			applyFilter(
+				new Rectangle(0, 0, 3, 1),
+				new Point(0, 0),
+				new ConvolutionFilter(3, 1, [1, 0, 0], 1, 0, false, false, 0xFF0000, 1)
+			);
+			tracePixels("moved right onto red");
+
+			applyFilter(new Rectangle(1, 0, 1, 1), new Point(1, 0), new ConvolutionFilter(1, 1, [2], 4, 16, true));
+			tracePixels("halved with a bias");
+
+			applyFilter(new Rectangle(0, 0, 1, 1), new Point(2, 0), new ColorMatrixFilter());
+			tracePixels("copied to the right");
+
+			trace("// filters");
+			var shape = new Shape();
+			shape.filters = [
+				new ColorMatrixFilter(SWAP_RED_AND_BLUE),
+				new ConvolutionFilter(3, 3, [0, 1, 0, 1, 4, 1, 0, 1, 0], 8, 2, false, false, 0x00FF00, 0.5)
+			];
+			var filters = shape.filters;
+			trace("length:", filters.length);
+			var item = filters.shift();
+			trace("is ColorMatrixFilter:", item is ColorMatrixFilter);
+			trace("color matrix matrix:", item.matrix);
+			item = filters.shift();
+			trace("is ConvolutionFilter:", item is ConvolutionFilter);
+			traceFilter(item, "convolution");
+
+			addEventListener("enterFrame", onEnterFrame);
+		}
+
+		function traceFilter(filter, prefix) {
+			for each (var name in CONVOLUTION_PROPERTIES) {
+				trace(prefix + " " + name + ":", filter[name]);
+			}
+		}
+
+		function tracePixels(label) {
+			for (var x = 0; x < 3; x++) {
+				trace(label + " " + x + ":", bitmap.getPixel32(x, 0));
+			}
+		}
+
+		// Filter `rect` of the bitmap into itself at `point`.
+		function applyFilter(rect, point, filter) {
+			bitmap.applyFilter(bitmap, rect, point, filter);
+		}
+
+		function onUncaughtError(event:UncaughtErrorEvent) {
+			trace("error:", event.error);
+			event.preventDefault();
+		}
+
+		function onEnterFrame(event:Event) {
+			switch (step++) {
+				case 0:
+					trace("// applyFilter with a null filter");
+					bitmap.applyFilter(bitmap, new Rectangle(0, 0, 3, 1), new Point(0, 0), null);
+					trace("not reached");
+					break;
+				case 1:
+					trace("// applyFilter with a shape as the source");
+					var source:* = new Shape();
+					bitmap.applyFilter(source, new Rectangle(0, 0, 3, 1), new Point(0, 0), new ColorMatrixFilter());
+					trace("not reached");
+					break;
+				case 2:
+					tracePixels("after the errors");
+					removeEventListener("enterFrame", onEnterFrame);
+					break;
+			}
+		}
+	}
+}
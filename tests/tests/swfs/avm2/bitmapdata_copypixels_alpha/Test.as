@@ -0,0 +1,36 @@
+package {
+	import flash.display.BitmapData;
+	import flash.display.MovieClip;
+	import flash.geom.Point;
+	import flash.geom.Rectangle;
+
+	// Copies pixels with `BitmapData.copyPixels`, taking their alpha from
+	// another bitmap at an `alphaPoint`, merging a translucent source over
+	// the destination with `mergeAlpha`, and copying a bitmap onto itself
+	// where the source and destination overlap.
+	public class Test extends MovieClip {
+		public function Test() {
+			trace("// alphaBitmapData and alphaPoint");
+			var dest = new BitmapData(3, 1, true, 0);
+			var source = new BitmapData(3, 1, false, 0xFF0000);
+			var alpha = new BitmapData(3, 1, true, 0x80000000);
+			alpha.fillRect(new Rectangle(2, 0, 1, 1), 0xFF000000);
+			dest.copyPixels(source, new Rectangle(0, 0, 2, 1), new Point(0, 0), alpha, new Point(1, 0));
+			trace("pixels:", dest.getVector(new Rectangle(0, 0, 3, 1)));
+
+			trace("// mergeAlpha");
+			dest = new BitmapData(2, 1, true, 0xFF0000FF);
+			source = new BitmapData(1, 1, true, 0x80FF0000);
+			dest.copyPixels(source, new Rectangle(0, 0, 1, 1), new Point(0, 0), null, null, true);
+			dest.copyPixels(source, new Rectangle(0, 0, 1, 1), new Point(1, 0), null, null, false);
+			trace("pixels:", dest.getVector(new Rectangle(0, 0, 2, 1)));
+
+			trace("// copying a bitmap onto itself");
+			dest = new BitmapData(3, 1, false, 0xFF0000);
+			dest.fillRect(new Rectangle(1, 0, 1, 1), 0x00FF00);
+			dest.fillRect(new Rectangle(2, 0, 1, 1), 0x0000FF);
+			dest.copyPixels(dest, new Rectangle(0, 0, 2, 1), new Point(1, 0));
+			trace("pixels:", dest.getVector(new Rectangle(0, 0, 3, 1)));
+		}
+	}
+}
@@ -0,0 +1,65 @@
+package {
+	import flash.display.Bitmap;
+	import flash.display.BitmapData;
+	import flash.display.MovieClip;
+	import flash.events.Event;
+	import flash.events.UncaughtErrorEvent;
+	import flash.geom.Point;
+	import flash.geom.Rectangle;
+
+	// A 4x4 bitmap has an opaque 2x2 square in its bottom right corner and a
+	// faint pixel in its top left. It's hit tested against points,
+	// rectangles, another BitmapData and a Bitmap, at different positions and
+	// alpha thresholds. The `enterFrame` listener then passes an object of
+	// the wrong type and traces the error that reaches `uncaughtError`.
+	public class Test extends MovieClip {
+		var bitmap;
+
+		public function Test() {
+			loaderInfo.uncaughtErrorEvents.addEventListener("uncaughtError", onUncaughtError);
+
+			bitmap = new BitmapData(4, 4, true, 0);
+			bitmap.fillRect(new Rectangle(2, 2, 2, 2), 0xFF000000);
+			bitmap.fillRect(new Rectangle(0, 0, 1, 1), 0x40000000);
+
+			trace("// points");
+			trace("opaque pixel:", bitmap.hitTest(new Point(0, 0), 255, new Point(3, 3)));
+			trace("transparent pixel:", bitmap.hitTest(new Point(0, 0), 255, new Point(1, 1)));
+			trace("outside:", bitmap.hitTest(new Point(0, 0), 1, new Point(4, 4)));
+			trace("moved bitmap:", bitmap.hitTest(new Point(10, 10), 255, new Point(12, 12)));
+			trace("faint pixel at its alpha:", bitmap.hitTest(new Point(0, 0), 64, new Point(0, 0)));
+			trace("faint pixel above its alpha:", bitmap.hitTest(new Point(0, 0), 65, new Point(0, 0)));
+
+			trace("// rectangles");
+			trace("transparent area:", bitmap.hitTest(new Point(0, 0), 255, new Rectangle(0, 0, 2, 2)));
+			trace("overlapping the square:", bitmap.hitTest(new Point(0, 0), 255, new Rectangle(1, 1, 2, 2)));
+			trace("outside:", bitmap.hitTest(new Point(0, 0), 1, new Rectangle(4, 0, 4, 4)));
+			trace("moved bitmap:", bitmap.hitTest(new Point(-2, -2), 255, new Rectangle(0, 0, 1, 1)));
+
+			trace("// bitmaps");
+			var other = new BitmapData(2, 2, true, 0xFF000000);
+			trace("over the faint pixel:", bitmap.hitTest(new Point(0, 0), 255, other, new Point(0, 0)));
+			trace("over the faint pixel, low threshold:", bitmap.hitTest(new Point(0, 0), 1, other, new Point(0, 0)));
+			trace("over the square:", bitmap.hitTest(new Point(0, 0), 255, other, new Point(3, 3)));
+			trace("past the square:", bitmap.hitTest(new Point(0, 0), 255, other, new Point(4, 4)));
+			trace("both moved:", bitmap.hitTest(new Point(5, 5), 255, other, new Point(8, 8)));
+			trace("itself:", bitmap.hitTest(new Point(0, 0), 255, bitmap, new Point(1, 1)));
+			var otherBitmap = new Bitmap(other);
+			trace("Bitmap:", bitmap.hitTest(new Point(0, 0), 255, otherBitmap, new Point(2, 2)));
+
+			addEventListener("enterFrame", onEnterFrame);
+		}
+
+		function onUncaughtError(event:UncaughtErrorEvent) {
+			trace("error:", event.error);
+			event.preventDefault();
+		}
+
+		function onEnterFrame(event:Event) {
+			removeEventListener("enterFrame", onEnterFrame);
+			trace("// wrong type");
+			bitmap.hitTest(new Point(0, 0), 1, new Object());
+			trace("not reached");
+		}
+	}
+}
@@ -0,0 +1,88 @@
+package {
+	import flash.display.BitmapData;
+	import flash.display.MovieClip;
+	import flash.events.Event;
+	import flash.events.UncaughtErrorEvent;
+	import flash.geom.Rectangle;
+
+	// Reads and writes the pixels of a 2x2 bitmap with `getPixels`,
+	// `copyPixelsToByteArray`, `setPixels`, `getVector` and `setVector`,
+	// including rectangles that are partly or wholly outside of it. The
+	// `enterFrame` listener then gives `setPixels` and `setVector` too little
+	// data, one frame each, and traces the errors that reach `uncaughtError`
+	// along with the pixels that were set before the data ran out.
+	public class Test extends MovieClip {
+		static const RED = 0x80FF0000;
+		static const GREEN = 0xFF00FF00;
+		static const BLUE = 0xFF0000FF;
+
+		var bitmap;
+		var step:int = 0;
+
+		public function Test() {
+			loaderInfo.uncaughtErrorEvents.addEventListener("uncaughtError", onUncaughtError);
+
+			bitmap = new BitmapData(2, 2, true, RED);
+			bitmap.fillRect(new Rectangle(1, 0, 1, 1), GREEN);
+			trace("getVector:", bitmap.getVector(new Rectangle(0, 0, 2, 2)));
+
+			trace("// getPixels");
+			var bytes = bitmap.getPixels(new Rectangle(0, 0, 2, 1));
+			trace("length:", bytes.length);
+			trace("position:", bytes.position);
+			bytes.position = 0;
+			trace("first byte:", bytes.readUnsignedByte());
+			trace("second byte:", bytes.readUnsignedByte());
+			trace("clipped length:", bitmap.getPixels(new Rectangle(1, 1, 5, 5)).length);
+			trace("outside length:", bitmap.getPixels(new Rectangle(-4, -4, 2, 2)).length);
+
+			trace("// copyPixelsToByteArray");
+			bytes.position = 8;
+			bitmap.copyPixelsToByteArray(new Rectangle(1, 1, 1, 1), bytes);
+			trace("length:", bytes.length);
+			trace("position:", bytes.position);
+
+			trace("// setPixels");
+			bytes.position = 0;
+			bitmap.setPixels(new Rectangle(0, 1, 2, 1), bytes);
+			trace("position:", bytes.position);
+			trace("pixels:", bitmap.getVector(new Rectangle(0, 0, 2, 2)));
+
+			trace("// setVector");
+			bitmap.setVector(new Rectangle(0, 0, 1, 2), bitmap.getVector(new Rectangle(1, 0, 1, 2)));
+			trace("pixels:", bitmap.getVector(new Rectangle(0, 0, 2, 2)));
+
+			addEventListener("enterFrame", onEnterFrame);
+		}
+
+		function onUncaughtError(event:UncaughtErrorEvent) {
+			trace("error:", event.error);
+			event.preventDefault();
+		}
+
+		function onEnterFrame(event:Event) {
+			switch (step++) {
+				case 0:
+					trace("// setPixels past the end of the ByteArray");
+					var bytes = bitmap.getPixels(new Rectangle(1, 0, 1, 1));
+					bitmap.fillRect(new Rectangle(0, 0, 2, 2), RED);
+					bytes.position = 0;
+					bitmap.setPixels(new Rectangle(0, 0, 2, 2), bytes);
+					trace("not reached");
+					break;
+				case 1:
+					trace("pixels:", bitmap.getVector(new Rectangle(0, 0, 2, 2)));
+					trace("// setVector past the end of the Vector");
+					var vector = bitmap.getVector(new Rectangle(1, 1, 1, 1));
+					bitmap.fillRect(new Rectangle(0, 0, 2, 2), BLUE);
+					bitmap.setVector(new Rectangle(0, 0, 2, 2), vector);
+					trace("not reached");
+					break;
+				case 2:
+					trace("pixels:", bitmap.getVector(new Rectangle(0, 0, 2, 2)));
+					removeEventListener("enterFrame", onEnterFrame);
+					break;
+			}
+		}
+	}
+}
@@ -0,0 +1,70 @@
+package {
+	import flash.display.MovieClip;
+	import flash.display.Shape;
+	import flash.events.Event;
+	import flash.events.UncaughtErrorEvent;
+
+	// Traces the `blendMode` of a new shape, then sets it to every blend mode
+	// by name and traces it back. The `enterFrame` listener then sets an
+	// unknown name and a name in the wrong case, traces the errors that
+	// reach `uncaughtError`, and checks that the blend mode didn't change.
+	public class Test extends MovieClip {
+		static const BLEND_MODES = [
+			"layer",
+			"multiply",
+			"screen",
+			"lighten",
+			"darken",
+			"difference",
+			"add",
+			"subtract",
+			"invert",
+			"alpha",
+			"erase",
+			"overlay",
+			"hardlight",
+			"normal"
+		];
+
+		var shape;
+		var step:int = 0;
+
+		public function Test() {
+			loaderInfo.uncaughtErrorEvents.addEventListener("uncaughtError", onUncaughtError);
+
+			shape = new Shape();
+			trace("new shape:", shape.blendMode);
+			for each (var name in BLEND_MODES) {
+				shape.blendMode = name;
+				trace(name + ":", shape.blendMode);
+			}
+			shape.blendMode = "multiply";
+
+			addEventListener("enterFrame", onEnterFrame);
+		}
+
+		function onUncaughtError(event:UncaughtErrorEvent) {
+			trace("error:", event.error);
+			event.preventDefault();
+		}
+
+		function onEnterFrame(event:Event) {
+			switch (step++) {
+				case 0:
+					trace("// set to bogus");
+					shape.blendMode = "bogus";
+					trace("not reached");
+					break;
+				case 1:
+					trace("// set to SCREEN");
+					shape.blendMode = "SCREEN";
+					trace("not reached");
+					break;
+				case 2:
+					trace("after the errors:", shape.blendMode);
+					removeEventListener("enterFrame", onEnterFrame);
+					break;
+			}
+		}
+	}
+}
@@ -0,0 +1,37 @@
+package {
+	import flash.display.MovieClip;
+	import flash.display.Sprite;
+	import flash.filters.BlurFilter;
+
+	// Creates a sprite and traces its `cacheAsBitmap` as it's set and
+	// cleared. A sprite with filters reads as cached whatever it's set to,
+	// until its filters are removed.
+	public class Test extends MovieClip {
+		var sprite;
+
+		public function Test() {
+			sprite = new Sprite();
+			step("// new sprite");
+
+			sprite.cacheAsBitmap = true;
+			step("// set to true");
+
+			sprite.cacheAsBitmap = 0;
+			step("// set to 0");
+
+			sprite.filters = [new BlurFilter()];
+			step("// with a blur filter");
+
+			sprite.cacheAsBitmap = false;
+			step("// set to false with a blur filter");
+
+			sprite.filters = [];
+			step("// without filters");
+		}
+
+		function step(label) {
+			trace(label);
+			trace("cacheAsBitmap:", sprite.cacheAsBitmap);
+		}
+	}
+}
@@ -0,0 +1,19 @@
+package {
+	import flash.display.MovieClip;
+	import flash.system.Capabilities;
+
+	// The test runs this with spoofed capabilities, which `Capabilities`
+	// reports in place of the real ones.
+	public class Test extends MovieClip {
+		public function Test() {
+			trace("version:", Capabilities.version);
+			trace("os:", Capabilities.os);
+			trace("manufacturer:", Capabilities.manufacturer);
+			trace("playerType:", Capabilities.playerType);
+			trace("language:", Capabilities.language);
+			trace("screenResolutionX:", Capabilities.screenResolutionX);
+			trace("screenResolutionY:", Capabilities.screenResolutionY);
+			trace("isDebugger:", Capabilities.isDebugger);
+		}
+	}
+}
@@ -0,0 +1,30 @@
+package {
+	import flash.desktop.Clipboard;
+	import flash.display.MovieClip;
+	import flash.system.System;
+
+	// Text is written to the clipboard by `System.setClipboard` and by the
+	// general clipboard's `setData`, `clear` and `setDataHandler`. Formats
+	// other than text are refused, and the clipboard's formats can't be seen
+	// outside of a paste. The test checks what reached the clipboard once the
+	// movie has run.
+	public class Test extends MovieClip {
+		public function Test() {
+			System.setClipboard("from setClipboard");
+
+			trace("generalClipboard is shared:", Clipboard.generalClipboard === Clipboard.generalClipboard);
+
+			trace("setData text:", Clipboard.generalClipboard.setData("air:text", "from setData"));
+			trace("setData html:", Clipboard.generalClipboard.setData("air:html", "<b>html</b>"));
+
+			trace("hasFormat:", Clipboard.generalClipboard.hasFormat("air:text"));
+			trace("formats.length:", Clipboard.generalClipboard.formats.length);
+
+			Clipboard.generalClipboard.clear();
+
+			trace("setDataHandler:", Clipboard.generalClipboard.setDataHandler("air:text", function() {
+				return "from handler";
+			}));
+		}
+	}
+}
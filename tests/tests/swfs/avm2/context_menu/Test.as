@@ -0,0 +1,33 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.ContextMenuEvent;
+	import flash.ui.ContextMenu;
+	import flash.ui.ContextMenuItem;
+
+	// Sets the movie's `contextMenu` to a menu with the built-in items hidden
+	// and three custom items, one of which is invisible. The menu and each
+	// item trace the events they receive. The test opens the menu and picks
+	// the last item.
+	public class Test extends MovieClip {
+		public function Test() {
+			var menu = new ContextMenu();
+			menu.hideBuiltInItems();
+			menu.addEventListener("menuSelect", function(event:ContextMenuEvent) {
+				trace("menu:", event.type);
+			});
+
+			var items = [["Credits", false, true], ["Hidden", false, false], ["Quality", true, true]];
+			for each (var item in items) {
+				var menuItem = new ContextMenuItem(item[0], item[1], true, item[2]);
+				menuItem.addEventListener("menuItemSelect", function(event:ContextMenuEvent) {
+					trace("item:", event.type);
+					trace("caption:", event.target.caption);
+				});
+				menu.customItems.push(menuItem);
+			}
+
+			contextMenu = menu;
+			trace("customItems.length:", contextMenu.customItems.length);
+		}
+	}
+}
@@ -0,0 +1,74 @@
+package {
+	import flash.display.MovieClip;
+
+	// Expected values come from Flash Player running in the test harness's
+	// timezone (GMT+0545, with "now" at 2001-02-03 04:05:06 local time).
+	public class Test extends MovieClip {
+		public function Test() {
+			var date = new Date(0);
+			trace("new Date(0):", date.toString());
+			trace("new Date(0).getTime():", date.getTime());
+			trace("new Date(0).getTimezoneOffset():", date.getTimezoneOffset());
+			trace("new Date(0).getHours():", date.getHours());
+			trace("new Date(0).getMinutes():", date.getMinutes());
+			trace("new Date(0).getUTCHours():", date.getUTCHours());
+			trace("new Date(0).getDay():", date.getDay());
+			trace("new Date(0).fullYear:", date.fullYear);
+			trace("new Date(0).month:", date.month);
+
+			date = new Date();
+			trace("new Date():", date.toString());
+			trace("new Date().getTime():", date.getTime());
+			trace("new Date().getUTCDate():", date.getUTCDate());
+			trace("new Date().getUTCHours():", date.getUTCHours());
+			trace("new Date().getUTCMinutes():", date.getUTCMinutes());
+			trace("new Date().getUTCDay():", date.getUTCDay());
+
+			date = new Date(2000, 11);
+			trace("new Date(2000, 11):", date.toString());
+			trace("new Date(2000, 11).getTime():", date.getTime());
+			date = new Date(2000, 12);
+			trace("new Date(2000, 12):", date.toString());
+			trace("new Date(2000, 12).getTime():", date.getTime());
+			date = new Date(2000, -1);
+			trace("new Date(2000, -1):", date.toString());
+			trace("new Date(2000, -1).getTime():", date.getTime());
+			date = new Date(2000, 11, 31);
+			trace("new Date(2000, 11, 31):", date.toString());
+			trace("new Date(2000, 11, 31).getTime():", date.getTime());
+			date = new Date(2000, 12, 31);
+			trace("new Date(2000, 12, 31):", date.toString());
+			trace("new Date(2000, 12, 31).getTime():", date.getTime());
+			date = new Date(2000, 11, -1);
+			trace("new Date(2000, 11, -1):", date.toString());
+			trace("new Date(2000, 11, -1).getTime():", date.getTime());
+			date = new Date(2000, 12, -1);
+			trace("new Date(2000, 12, -1):", date.toString());
+			trace("new Date(2000, 12, -1).getTime():", date.getTime());
+
+			trace("new Date(2000, NaN):", new Date(2000, NaN));
+
+			trace("Date.UTC(2000, 11):", Date.UTC(2000, 11));
+			trace("Date.UTC(2000, 12):", Date.UTC(2000, 12));
+			trace("Date.UTC(2000, 11, 31):", Date.UTC(2000, 11, 31));
+
+			date = new Date(1609237353000);
+			trace("new Date(1609237353000).setUTCMonth(11):", date.setUTCMonth(11));
+			trace("new Date(1609237353000).setUTCMonth(11) ->", date.toString());
+			date = new Date(1609237353000);
+			trace("new Date(1609237353000).setUTCMonth(12):", date.setUTCMonth(12));
+			trace("new Date(1609237353000).setUTCMonth(12) ->", date.toString());
+			date = new Date(1609237353000);
+			trace("new Date(1609237353000).setUTCMonth(-1):", date.setUTCMonth(-1));
+			trace("new Date(1609237353000).setUTCMonth(-1) ->", date.toString());
+			date = new Date(1609237353000);
+			trace("new Date(1609237353000).setMonth(12):", date.setMonth(12));
+			trace("new Date(1609237353000).setMonth(12) ->", date.toString());
+
+			date = new Date(0);
+			date.fullYear = 2020;
+			date.hoursUTC = 6;
+			trace("fullYear = 2020, hoursUTC = 6:", date.toString());
+		}
+	}
+}
@@ -0,0 +1,101 @@
+package {
+	import flash.display.BitmapData;
+	import flash.display.MovieClip;
+	import flash.display.Shape;
+	import flash.filters.DisplacementMapFilter;
+	import flash.filters.DisplacementMapFilterMode;
+	import flash.geom.Point;
+	import flash.geom.Rectangle;
+
+	// Traces the `DisplacementMapFilterMode` constants and the defaults of
+	// `DisplacementMapFilter`. It then displaces a three pixel wide bitmap by
+	// two pixels to the left with `applyFilter` in every mode, and with a one
+	// pixel map placed over the middle pixel. Finally it sets filters on a
+	// shape, one of them without a map, and traces what the shape gives back.
+	public class Test extends MovieClip {
+		static const PIXELS = [0xFF102030, 0xFF405060, 0xFF708090];
+		static const PROPERTIES = [
+			"mapBitmap",
+			"mapPoint",
+			"componentX",
+			"componentY",
+			"scaleX",
+			"scaleY",
+			"mode",
+			"color",
+			"alpha"
+		];
+
+		var bitmap;
+		var map;
+		var smallMap;
+
+		public function Test() {
+			trace("// DisplacementMapFilterMode");
+			trace("CLAMP:", DisplacementMapFilterMode.CLAMP);
+			trace("COLOR:", DisplacementMapFilterMode.COLOR);
+			trace("IGNORE:", DisplacementMapFilterMode.IGNORE);
+			trace("WRAP:", DisplacementMapFilterMode.WRAP);
+
+			trace("// defaults");
+			var item = new DisplacementMapFilter();
+			for each (var name in PROPERTIES) {
+				trace(name + ":", item[name]);
+			}
+
+			trace("// applyFilter");
+			bitmap = new BitmapData(3, 1, true, 0);
+			// A red channel of 0 with a scale of 4 moves pixels by two to the left.
+			map = new BitmapData(3, 1, true, 0xFF008080);
+			smallMap = new BitmapData(1, 1, true, 0xFF008080);
+			resetPixels();
+			tracePixels("original");
+			displace(map, new Point(0, 0), "wrap");
+			displace(map, new Point(0, 0), "clamp");
+			displace(map, new Point(0, 0), "ignore");
+			displace(map, new Point(0, 0), "color");
+			displace(smallMap, new Point(1, 0), "wrap");
+
+			trace("// filters");
+			var shape = new Shape();
+			shape.filters = [
+				newFilter(map, new Point(2, 3), "clamp"),
+				newFilter(null, new Point(0, 0), "wrap")
+			];
+			var filters = shape.filters;
+			trace("length:", filters.length);
+			item = filters.shift();
+			trace("is DisplacementMapFilter:", item is DisplacementMapFilter);
+			for each (name in PROPERTIES.slice(2)) {
+				trace(name + ":", item[name]);
+			}
+			trace("mapPoint x:", item.mapPoint.x);
+			trace("mapPoint y:", item.mapPoint.y);
+			trace("mapBitmap is the same object:", item.mapBitmap === map);
+			trace("mapBitmap width:", item.mapBitmap.width);
+			trace("mapBitmap pixel:", item.mapBitmap.getPixel32(2, 0));
+		}
+
+		function resetPixels() {
+			for (var x = 0; x < PIXELS.length; x++) {
+				bitmap.setPixel32(x, 0, PIXELS[x]);
+			}
+		}
+
+		function tracePixels(label) {
+			for (var x = 0; x < 3; x++) {
+				trace(label + " " + x + ":", bitmap.getPixel32(x, 0));
+			}
+		}
+
+		function newFilter(mapBitmap, mapPoint, mode) {
+			return new DisplacementMapFilter(mapBitmap, mapPoint, 1, 2, 4, 0, mode, 0x00FF00, 1);
+		}
+
+		function displace(mapBitmap, mapPoint, mode) {
+			resetPixels();
+			bitmap.applyFilter(bitmap, new Rectangle(0, 0, 3, 1), new Point(0, 0), newFilter(mapBitmap, mapPoint, mode));
+			tracePixels(mode + " at " + mapPoint.x + "," + mapPoint.y);
+		}
+	}
+}
@@ -0,0 +1,73 @@
+package {
+	import flash.display.MovieClip;
+	import flash.display.Shape;
+	import flash.events.Event;
+	import flash.events.UncaughtErrorEvent;
+	import flash.filters.BitmapFilter;
+	import flash.filters.BlurFilter;
+
+	// Constructs `BlurFilter`s with and without arguments, clones one, and
+	// sets it as the `filters` of a shape, tracing the filters that the shape
+	// gives back. Changing the filter afterwards must not change the shape's
+	// copy of it. The `enterFrame` listener then sets `filters` to an array
+	// holding something other than a filter and traces the error that
+	// reaches `uncaughtError`.
+	public class Test extends MovieClip {
+		var step:int = 0;
+
+		public function Test() {
+			loaderInfo.uncaughtErrorEvents.addEventListener("uncaughtError", onUncaughtError);
+
+			trace("// BlurFilter");
+			var item = new BlurFilter();
+			traceBlur(item, "default");
+			trace("is BitmapFilter:", item is BitmapFilter);
+			var filter = new BlurFilter(8, 2, 3);
+			traceBlur(filter, "constructed");
+			item = filter.clone();
+			traceBlur(item, "clone");
+			trace("clone is the filter:", item === filter);
+
+			trace("// filters");
+			var shape = new Shape();
+			trace("new shape length:", shape.filters.length);
+			shape.filters = [filter];
+			trace("set length:", shape.filters.length);
+			item = shape.filters.pop();
+			trace("is BlurFilter:", item is BlurFilter);
+			traceBlur(item, "read back");
+			trace("read back is the filter:", item === filter);
+			filter.blurX = 16;
+			item = shape.filters.pop();
+			trace("after changing the filter blurX:", item.blurX);
+			shape.filters = null;
+			trace("null length:", shape.filters.length);
+
+			addEventListener("enterFrame", onEnterFrame);
+		}
+
+		function traceBlur(filter, prefix) {
+			trace(prefix + " blurX:", filter.blurX);
+			trace(prefix + " blurY:", filter.blurY);
+			trace(prefix + " quality:", filter.quality);
+		}
+
+		function onUncaughtError(event:UncaughtErrorEvent) {
+			trace("error:", event.error);
+			event.preventDefault();
+		}
+
+		function onEnterFrame(event:Event) {
+			switch (step++) {
+				case 0:
+					trace("// filters holding a number");
+					new Shape().filters = [1];
+					trace("not reached");
+					break;
+				case 1:
+					removeEventListener("enterFrame", onEnterFrame);
+					break;
+			}
+		}
+	}
+}
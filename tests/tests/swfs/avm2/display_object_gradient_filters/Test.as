@@ -0,0 +1,134 @@
+package {
+	import flash.display.MovieClip;
+	import flash.display.Shape;
+	import flash.events.Event;
+	import flash.events.UncaughtErrorEvent;
+	import flash.filters.BevelFilter;
+	import flash.filters.BitmapFilterType;
+	import flash.filters.GradientBevelFilter;
+	import flash.filters.GradientGlowFilter;
+
+	// Traces the `BitmapFilterType` constants and the defaults of
+	// `BevelFilter` and `GradientGlowFilter`. It sets a bevel, a gradient glow
+	// and a gradient bevel as the `filters` of a shape, and traces every
+	// property of the filters that the shape gives back, including a gradient
+	// whose arrays have different lengths and an unknown filter type. The
+	// `enterFrame` listener then sets `filters` to an array holding a string
+	// as well as a filter, traces the error that reaches `uncaughtError`, and
+	// checks that the shape kept its filters.
+	public class Test extends MovieClip {
+		static const BEVEL_PROPERTIES = [
+			"distance",
+			"angle",
+			"highlightColor",
+			"highlightAlpha",
+			"shadowColor",
+			"shadowAlpha",
+			"blurX",
+			"blurY",
+			"strength",
+			"quality",
+			"type",
+			"knockout"
+		];
+		static const GRADIENT_PROPERTIES = [
+			"distance",
+			"angle",
+			"colors",
+			"alphas",
+			"ratios",
+			"blurX",
+			"blurY",
+			"strength",
+			"quality",
+			"type",
+			"knockout"
+		];
+
+		var shape;
+		var step:int = 0;
+
+		public function Test() {
+			loaderInfo.uncaughtErrorEvents.addEventListener("uncaughtError", onUncaughtError);
+
+			trace("// BitmapFilterType");
+			trace("FULL:", BitmapFilterType.FULL);
+			trace("INNER:", BitmapFilterType.INNER);
+			trace("OUTER:", BitmapFilterType.OUTER);
+
+			trace("// defaults");
+			traceFilter(new BevelFilter(), BEVEL_PROPERTIES, "BevelFilter");
+			traceFilter(new GradientGlowFilter(), GRADIENT_PROPERTIES, "GradientGlowFilter");
+
+			trace("// filters");
+			shape = new Shape();
+			shape.filters = [
+				new BevelFilter(5, 90, 0xFFFF00, 0.5, 0x0000FF, 0.25, 2, 3, 2, 2, "outer", true),
+				new GradientGlowFilter(
+					2,
+					0,
+					[0xFF0000, 0x00FF00, 0x0000FF],
+					[0, 0.5, 1],
+					[0, 128, 255],
+					8,
+					6,
+					1.5,
+					1,
+					"full",
+					false
+				),
+				new GradientBevelFilter(
+					1,
+					180,
+					[0xFFFFFF, 0x000000],
+					[1, 1, 1],
+					[0, 255],
+					3,
+					3,
+					1,
+					3,
+					"sideways",
+					false
+				)
+			];
+			var filters = shape.filters;
+			trace("length:", filters.length);
+			var item = filters.shift();
+			trace("is BevelFilter:", item is BevelFilter);
+			traceFilter(item, BEVEL_PROPERTIES, "bevel");
+			item = filters.shift();
+			trace("is GradientGlowFilter:", item is GradientGlowFilter);
+			traceFilter(item, GRADIENT_PROPERTIES, "gradient glow");
+			item = filters.shift();
+			trace("is GradientBevelFilter:", item is GradientBevelFilter);
+			traceFilter(item, GRADIENT_PROPERTIES, "gradient bevel");
+
+			addEventListener("enterFrame", onEnterFrame);
+		}
+
+		function traceFilter(filter, names, prefix) {
+			for each (var name in names) {
+				trace(prefix + " " + name + ":", filter[name]);
+			}
+		}
+
+		function onUncaughtError(event:UncaughtErrorEvent) {
+			trace("error:", event.error);
+			event.preventDefault();
+		}
+
+		function onEnterFrame(event:Event) {
+			switch (step++) {
+				case 0:
+					trace("// filters holding a string");
+					shape.filters = [new BevelFilter(), "glow"];
+					trace("not reached");
+					break;
+				case 1:
+					trace("length after the error:", shape.filters.length);
+					removeEventListener("enterFrame", onEnterFrame);
+					break;
+			}
+		}
+	}
+}
@@ -0,0 +1,69 @@
+package {
+	import flash.display.MovieClip;
+	import flash.display.Shape;
+	import flash.filters.DropShadowFilter;
+	import flash.filters.GlowFilter;
+
+	// Constructs `DropShadowFilter`s and `GlowFilter`s with and without
+	// arguments, sets one of each as the `filters` of a shape, and traces
+	// every property of the filters that the shape gives back, in order.
+	public class Test extends MovieClip {
+		static const SHADOW_PROPERTIES = [
+			"distance",
+			"angle",
+			"color",
+			"alpha",
+			"blurX",
+			"blurY",
+			"strength",
+			"quality",
+			"inner",
+			"knockout",
+			"hideObject"
+		];
+		static const GLOW_PROPERTIES = [
+			"color",
+			"alpha",
+			"blurX",
+			"blurY",
+			"strength",
+			"quality",
+			"inner",
+			"knockout"
+		];
+
+		public function Test() {
+			trace("// DropShadowFilter");
+			traceFilter(new DropShadowFilter(), SHADOW_PROPERTIES, "default");
+			var shadow = new DropShadowFilter(8, 90, 0x0000FF, 0.5, 2, 3, 2, 3, true, true, true);
+			traceFilter(shadow, SHADOW_PROPERTIES, "constructed");
+
+			trace("// GlowFilter");
+			traceFilter(new GlowFilter(), GLOW_PROPERTIES, "default");
+			var glow = new GlowFilter(0x00FF00, 0.25, 10, 12, 3, 2, true, false);
+			traceFilter(glow, GLOW_PROPERTIES, "constructed");
+			var item = glow.clone();
+			trace("is GlowFilter:", item is GlowFilter);
+			traceFilter(item, GLOW_PROPERTIES, "clone");
+
+			trace("// filters");
+			var shape = new Shape();
+			shape.filters = [shadow, glow];
+			var filters = shape.filters;
+			trace("length:", filters.length);
+			item = filters.shift();
+			trace("is DropShadowFilter:", item is DropShadowFilter);
+			traceFilter(item, SHADOW_PROPERTIES, "read back");
+			item = filters.shift();
+			trace("is GlowFilter:", item is GlowFilter);
+			trace("is DropShadowFilter:", item is DropShadowFilter);
+			traceFilter(item, GLOW_PROPERTIES, "read back");
+		}
+
+		function traceFilter(filter, names, prefix) {
+			for each (var name in names) {
+				trace(prefix + " " + name + ":", filter[name]);
+			}
+		}
+	}
+}
@@ -0,0 +1,43 @@
+#!/usr/bin/env python3
+"""Generates test.swf for the duplicate_definitions test.
+
+The ABC file has three scripts. The first two both define a class named
+`Dup`, and the last one, the entry point, defines the document class.
+Flash Player keeps the first definition of a name: the document class
+sees the first `Dup`, and the second script never runs.
+
+The first script also defines its own `flash.geom.Point`. It shadows the
+builtin class of the same name in the parent domain, rather than being
+dropped as a duplicate of it.
+
+This is assembled rather than compiled from ActionScript because a compiler
+rejects a second definition of `Dup` in the same ABC file, and won't let a
+movie define a class in the `flash.geom` package.
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", ".."))
+
+from swfgen import Abc, write_avm2_movie
+
+abc = Abc()
+
+for which in ("first", "second"):
+    dup = abc.define_class("Dup")
+    dup.static_method("which").pushstring(which).returnvalue()
+    classes = [dup]
+    if which == "first":
+        point = abc.define_class("flash.geom::Point")
+        point.static_method("which").pushstring("user").returnvalue()
+        classes.append(point)
+    abc.script(classes).trace(which + " script ran")
+
+doc = abc.document_class("Test")
+doc.constructor.trace("document class constructed")
+doc.constructor.getlex("Dup").callproperty("which", 0).trace_top("Dup.which():")
+doc.constructor.getlex("flash.geom::Point").callproperty("which", 0)
+doc.constructor.trace_top("Point.which():")
+
+write_avm2_movie(os.path.join(os.path.dirname(__file__), "test.swf"), abc, doc)
@@ -0,0 +1,46 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.Event;
+
+	// The movie listens to its own `custom` event three times:
+	//
+	// * `onFirst`, at priority 5, removes itself and `onSecond`,
+	// * `onSecond`, at priority 0, is removed by `onFirst` while the event is
+	//   being dispatched, so it is still called for that dispatch only,
+	// * `onThird`, at priority 0, reports whether it received the original
+	//   event.
+	//
+	// The same event is then dispatched again, which dispatches a clone of
+	// it, and `onFirst` is added back at a lower priority than the others.
+	public class Test extends MovieClip {
+		var saved;
+
+		public function Test() {
+			addEventListener("custom", onFirst, false, 5);
+			addEventListener("custom", onSecond, false, 0);
+			addEventListener("custom", onThird, false, 0);
+			saved = new Event("custom");
+			trace("// dispatch");
+			trace(dispatchEvent(saved));
+			trace("// redispatch");
+			trace(dispatchEvent(saved));
+			trace("// onFirst added back at priority -1");
+			addEventListener("custom", onFirst, false, -1);
+			trace(dispatchEvent(new Event("custom")));
+		}
+
+		function onFirst(event:Event) {
+			trace("onFirst");
+			removeEventListener("custom", onFirst);
+			removeEventListener("custom", onSecond);
+		}
+
+		function onSecond(event:Event) {
+			trace("onSecond");
+		}
+
+		function onThird(event:Event) {
+			trace("onThird, original event:", event === saved);
+		}
+	}
+}
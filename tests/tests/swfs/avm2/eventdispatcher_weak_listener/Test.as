@@ -0,0 +1,33 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.Event;
+
+	// `listener` holds a function that's added as a weak listener for the
+	// movie's own `custom` event. While something else holds the function,
+	// the weak listener is called and reported by `hasEventListener`. Adding
+	// the same function again with a strong reference doesn't add a second
+	// listener, and removing it removes the one registration.
+	public class Test extends MovieClip {
+		var listener;
+
+		public function Test() {
+			listener = function(event:Event) {
+				trace("listener called");
+			};
+
+			trace("// weak listener");
+			addEventListener("custom", listener, false, 0, true);
+			trace("hasEventListener:", hasEventListener("custom"));
+			trace("dispatchEvent:", dispatchEvent(new Event("custom")));
+
+			trace("// added again with a strong reference");
+			addEventListener("custom", listener, false, 0, false);
+			trace("dispatchEvent:", dispatchEvent(new Event("custom")));
+
+			trace("// removed");
+			removeEventListener("custom", listener);
+			trace("hasEventListener:", hasEventListener("custom"));
+			trace("dispatchEvent:", dispatchEvent(new Event("custom")));
+		}
+	}
+}
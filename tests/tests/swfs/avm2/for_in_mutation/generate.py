@@ -0,0 +1,143 @@
+#!/usr/bin/env python3
+"""Generates test.swf for the for_in_mutation test.
+
+Each case enumerates an object with `hasnext2` and `nextname`, tracing each
+name, while the loop body changes the object:
+
+* Deleting the current property doesn't skip the next one, deleting a
+  property that hasn't been reached yet means it isn't visited, and adding
+  a property means it is.
+* Properties made non-enumerable aren't visited.
+* Elements pushed onto an array while its elements are being enumerated are
+  visited, and elements cut off by shortening it are not. Elements pushed
+  once enumeration has moved on to its dynamic properties are not visited.
+
+It also enumerates an array with a hole using `hasnext`, its values with
+`nextvalue`, and `null`.
+
+This is assembled rather than compiled from ActionScript because compilers
+only emit `hasnext2`, never `hasnext`, and because the "array grows after its
+elements" case checks the object register after `hasnext2` has moved it
+along the prototype chain.
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", ".."))
+
+from swfgen import Abc, write_avm2_movie
+
+OBJECT = 1
+INDEX = 2
+NAME = 3
+ARRAY = 4
+
+abc = Abc()
+doc = abc.document_class("Test")
+ctor = doc.constructor
+
+
+def for_in(label, body=None):
+    """Enumerate the object in the `OBJECT` register, tracing each name."""
+    ctor.pushbyte(0).setlocal(INDEX)
+    ctor.label(label)
+    ctor.hasnext2(OBJECT, INDEX).iffalse(label + "_end")
+    ctor.getlocal(OBJECT).getlocal(INDEX).nextname().setlocal(NAME)
+    ctor.getlocal(NAME).trace_top()
+    if body is not None:
+        body()
+    ctor.jump(label)
+    ctor.label(label + "_end")
+
+
+def when_name_is(name, label, action):
+    ctor.getlocal(NAME).pushstring(name).ifne(label)
+    action()
+    ctor.label(label)
+
+
+def new_object(*names):
+    ctor.newobject(0).setlocal(OBJECT)
+    for value, name in enumerate(names):
+        ctor.getlocal(OBJECT).pushbyte(value).setproperty(name)
+
+
+def new_array(*values, dynamic=None):
+    for value in values:
+        ctor.push(value)
+    ctor.newarray(len(values)).setlocal(OBJECT)
+    if dynamic is not None:
+        ctor.getlocal(OBJECT).pushtrue().setproperty(dynamic)
+
+
+def push(value):
+    ctor.getlocal(OBJECT).pushbyte(value).callpropvoid("push", 1)
+
+
+ctor.trace("// delete and add during enumeration")
+new_object("a", "b", "c")
+
+
+def delete_and_add():
+    ctor.getlocal(OBJECT).deleteproperty("a").pop()
+    ctor.getlocal(OBJECT).deleteproperty("c").pop()
+    ctor.getlocal(OBJECT).pushbyte(3).setproperty("d")
+
+
+for_in("delete", lambda: when_name_is("a", "delete_skip", delete_and_add))
+
+ctor.trace("// setPropertyIsEnumerable")
+new_object("x", "y", "z")
+ctor.getlocal(OBJECT).pushstring("y").pushfalse()
+ctor.callpropvoid("setPropertyIsEnumerable", 2)
+for_in("enumerable")
+
+ctor.trace("// array grows")
+new_array(10, 20, 30, dynamic="p")
+for_in("grow", lambda: when_name_is("0", "grow_skip", lambda: push(40)))
+
+ctor.trace("// array shrinks")
+new_array(10, 20, 30, 40, dynamic="p")
+
+
+def shrink():
+    ctor.getlocal(OBJECT).pushbyte(2).setproperty("length")
+
+
+for_in("shrink", lambda: when_name_is("1", "shrink_skip", shrink))
+
+ctor.trace("// array grows after its elements")
+new_array(10, 20, dynamic="p")
+# `hasnext2` moves the object register along the prototype chain.
+ctor.getlocal(OBJECT).setlocal(ARRAY)
+for_in("late", lambda: when_name_is("p", "late_skip", lambda: push(30)))
+ctor.getlocal(ARRAY).getproperty("length").trace_top("length:")
+
+ctor.trace("// hasnext skips holes")
+ctor.newarray(0).setlocal(OBJECT)
+ctor.getlocal(OBJECT).pushstring("first").setproperty("0")
+ctor.getlocal(OBJECT).pushstring("third").setproperty("2")
+ctor.pushbyte(0).setlocal(INDEX)
+ctor.label("hasnext")
+ctor.getlocal(OBJECT).getlocal(INDEX).hasnext().dup().setlocal(INDEX)
+ctor.iffalse("hasnext_end")
+ctor.getlocal(OBJECT).getlocal(INDEX).nextname().trace_top()
+ctor.jump("hasnext")
+ctor.label("hasnext_end")
+
+ctor.trace("// nextvalue")
+new_array(10, 20, 30)
+ctor.pushbyte(0).setlocal(INDEX)
+ctor.label("values")
+ctor.hasnext2(OBJECT, INDEX).iffalse("values_end")
+ctor.getlocal(OBJECT).getlocal(INDEX).nextvalue().trace_top()
+ctor.jump("values")
+ctor.label("values_end")
+
+ctor.trace("// null")
+ctor.pushnull().setlocal(OBJECT)
+for_in("null")
+ctor.trace("done")
+
+write_avm2_movie(os.path.join(os.path.dirname(__file__), "test.swf"), abc, doc)
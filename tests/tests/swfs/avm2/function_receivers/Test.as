@@ -0,0 +1,63 @@
+package {
+	import flash.display.MovieClip;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			var three = function(a, b, c) {};
+			var rest = function(a, ...args) {};
+			var receiver = function() { return this; };
+			var plusOne = function() { return this + 1; };
+			var join = function(...args) { return args.join(","); };
+
+			trace("///three.length");
+			trace(three.length);
+			trace("///rest.length");
+			trace(rest.length);
+			trace("///this.method.length");
+			trace(this.method.length);
+
+			trace("///receiver.call(null) != null");
+			trace(receiver.call(null) != null);
+			trace("///receiver.call(null) === receiver.call(undefined)");
+			trace(receiver.call(null) === receiver.call(undefined));
+			trace("///receiver.call(null) === this");
+			trace(receiver.call(null) === this);
+			trace("///receiver.call(this) === this");
+			trace(receiver.call(this) === this);
+
+			trace("///plusOne.call(5)");
+			trace(plusOne.call(5));
+			trace("///plusOne.apply(2)");
+			trace(plusOne.apply(2));
+
+			trace("///join.apply(null, [\"x\", \"y\"])");
+			trace(join.apply(null, ["x", "y"]));
+			trace("///join.apply(null, {length: 2, 0: \"a\", 1: \"b\"})");
+			trace(join.apply(null, {length: 2, 0: "a", 1: "b"}));
+			trace("///join.apply(null, null)");
+			trace(join.apply(null, null));
+			trace("///join.apply(null)");
+			trace(join.apply(null));
+
+			trace("///var f:* = join;");
+			var f:* = join;
+			trace("///f[\"call\"](null, \"p\", \"q\")");
+			trace(f["call"](null, "p", "q"));
+			trace("///f[\"apply\"](null, [\"r\"])");
+			trace(f["apply"](null, ["r"]));
+
+			trace("///this.method == this.method");
+			trace(this.method == this.method);
+			trace("///var m = this.method;");
+			var m = this.method;
+			trace("///m.call(null) === this");
+			trace(m.call(null) === this);
+			trace("///m.apply({}) === this");
+			trace(m.apply({}) === this);
+		}
+
+		public function method(a, b) {
+			return this;
+		}
+	}
+}
@@ -0,0 +1,83 @@
+package {
+	import flash.display.BitmapData;
+	import flash.display.MovieClip;
+	import flash.display.Shape;
+	import flash.events.Event;
+	import flash.events.UncaughtErrorEvent;
+	import flash.geom.Matrix;
+
+	// Fills a rectangle with `Graphics.beginBitmapFill`, with and without a
+	// matrix, strokes one with `lineBitmapStyle`, and traces the size of each
+	// shape. The `enterFrame` listener then passes `null` and an object that
+	// isn't a `BitmapData` to `beginBitmapFill` and `lineBitmapStyle`, one
+	// frame each, and traces the errors that reach `uncaughtError`.
+	public class Test extends MovieClip {
+		var bitmap;
+		var step:int = 0;
+
+		public function Test() {
+			loaderInfo.uncaughtErrorEvents.addEventListener("uncaughtError", onUncaughtError);
+
+			bitmap = new BitmapData(4, 4, false, 0xFF0000FF);
+
+			var shape = new Shape();
+			shape.graphics.beginBitmapFill(bitmap);
+			shape.graphics.drawRect(0, 0, 10, 10);
+			shape.graphics.endFill();
+			traceSize(shape, "fill");
+
+			shape = new Shape();
+			shape.graphics.beginBitmapFill(bitmap, new Matrix(2, 0, 0, 2, 5, 5), false, true);
+			shape.graphics.drawRect(0, 0, 10, 10);
+			shape.graphics.endFill();
+			traceSize(shape, "matrix fill");
+
+			shape = new Shape();
+			shape.graphics.lineStyle(2);
+			shape.graphics.lineBitmapStyle(bitmap);
+			shape.graphics.drawRect(0, 0, 10, 10);
+			traceSize(shape, "stroke");
+
+			addEventListener("enterFrame", onEnterFrame);
+		}
+
+		function traceSize(shape, prefix) {
+			trace(prefix + " width:", shape.width);
+			trace(prefix + " height:", shape.height);
+		}
+
+		function onUncaughtError(event:UncaughtErrorEvent) {
+			trace("error:", event.error);
+			event.preventDefault();
+		}
+
+		function onEnterFrame(event:Event) {
+			var shape:* = new Shape();
+			switch (step++) {
+				case 0:
+					trace("// beginBitmapFill with null");
+					shape.graphics.beginBitmapFill(null);
+					trace("not reached");
+					break;
+				case 1:
+					trace("// beginBitmapFill with shape");
+					shape.graphics.beginBitmapFill(shape);
+					trace("not reached");
+					break;
+				case 2:
+					trace("// lineBitmapStyle with null");
+					shape.graphics.lineBitmapStyle(null);
+					trace("not reached");
+					break;
+				case 3:
+					trace("// lineBitmapStyle with shape");
+					shape.graphics.lineBitmapStyle(shape);
+					trace("not reached");
+					break;
+				case 4:
+					removeEventListener("enterFrame", onEnterFrame);
+					break;
+			}
+		}
+	}
+}
@@ -0,0 +1,96 @@
+package {
+	import flash.display.MovieClip;
+	import flash.display.Shape;
+	import flash.events.Event;
+	import flash.events.UncaughtErrorEvent;
+
+	// Draws a cubic curve with `Graphics.cubicCurveTo` and traces the
+	// quadratic curves it is read back as. Copies a filled rectangle over a
+	// stroked line with `copyFrom`, copies a drawing onto itself, and strokes
+	// with `lineGradientStyle`, tracing the size of each shape. The
+	// `enterFrame` listener then passes `null` to `copyFrom` and an unknown
+	// gradient type and spread method to `lineGradientStyle`, one frame each,
+	// and traces the errors that reach `uncaughtError`.
+	public class Test extends MovieClip {
+		var step:int = 0;
+
+		public function Test() {
+			loaderInfo.uncaughtErrorEvents.addEventListener("uncaughtError", onUncaughtError);
+
+			trace("// cubicCurveTo");
+			var shape = new Shape();
+			shape.graphics.beginFill(0xFF0000);
+			shape.graphics.cubicCurveTo(0, 100, 100, 100, 100, 0);
+			shape.graphics.endFill();
+			var data = shape.graphics.readGraphicsData();
+			data.shift();
+			trace("commands:", data.shift().commands);
+			shape = new Shape();
+			shape.graphics.cubicCurveTo(10, 10, 20, 20, 30, 30);
+			trace("straight commands:", shape.graphics.readGraphicsData().shift().commands);
+
+			trace("// copyFrom");
+			var source = new Shape();
+			source.graphics.beginFill(0xFF0000);
+			source.graphics.drawRect(0, 0, 10, 20);
+			source.graphics.endFill();
+			shape = new Shape();
+			shape.graphics.lineStyle(2);
+			shape.graphics.lineTo(50, 50);
+			shape.graphics.copyFrom(source.graphics);
+			traceSize(shape, "copy");
+			trace("copy items:", shape.graphics.readGraphicsData().length);
+			source.graphics.clear();
+			traceSize(shape, "copy after clearing the source");
+			shape.graphics.copyFrom(shape.graphics);
+			traceSize(shape, "copy of itself");
+
+			trace("// lineGradientStyle");
+			shape = new Shape();
+			shape.graphics.lineStyle(4);
+			lineGradientStyle(shape, "linear", "pad");
+			shape.graphics.drawRect(0, 0, 20, 10);
+			traceSize(shape, "gradient stroke");
+
+			addEventListener("enterFrame", onEnterFrame);
+		}
+
+		function traceSize(shape, prefix) {
+			trace(prefix + " width:", shape.width);
+			trace(prefix + " height:", shape.height);
+		}
+
+		function lineGradientStyle(shape, type, spreadMethod) {
+			shape.graphics.lineGradientStyle(type, [0xFF0000, 0x0000FF], [1, 1], [0, 255], null, spreadMethod);
+		}
+
+		function onUncaughtError(event:UncaughtErrorEvent) {
+			trace("error:", event.error);
+			event.preventDefault();
+		}
+
+		function onEnterFrame(event:Event) {
+			var shape = new Shape();
+			switch (step++) {
+				case 0:
+					trace("// copyFrom with null");
+					shape.graphics.copyFrom(null);
+					trace("not reached");
+					break;
+				case 1:
+					trace("// lineGradientStyle with an unknown type");
+					lineGradientStyle(shape, "conic", "pad");
+					trace("not reached");
+					break;
+				case 2:
+					trace("// lineGradientStyle with an unknown spread method");
+					lineGradientStyle(shape, "linear", "sideways");
+					trace("not reached");
+					break;
+				case 3:
+					removeEventListener("enterFrame", onEnterFrame);
+					break;
+			}
+		}
+	}
+}
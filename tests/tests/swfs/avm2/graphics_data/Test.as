@@ -0,0 +1,129 @@
+package {
+	import flash.display.GraphicsEndFill;
+	import flash.display.GraphicsPath;
+	import flash.display.GraphicsSolidFill;
+	import flash.display.GraphicsStroke;
+	import flash.display.IGraphicsData;
+	import flash.display.IGraphicsFill;
+	import flash.display.IGraphicsStroke;
+	import flash.display.MovieClip;
+	import flash.display.Shape;
+	import flash.events.Event;
+	import flash.events.UncaughtErrorEvent;
+
+	// Builds a `GraphicsPath` with its drawing methods, draws it with
+	// `Graphics.drawPath` under a fill and a stroke, and reads the drawing
+	// back with `readGraphicsData`, tracing every `IGraphicsData` object it
+	// gets. It copies that drawing to another shape with `drawGraphicsData`,
+	// and draws a path whose data runs out early. The `enterFrame` listener
+	// then passes an unknown winding to `drawPath` and `drawGraphicsData`,
+	// one frame each, and traces the errors that reach `uncaughtError`.
+	public class Test extends MovieClip {
+		var path;
+		var step:int = 0;
+
+		public function Test() {
+			loaderInfo.uncaughtErrorEvents.addEventListener("uncaughtError", onUncaughtError);
+
+			trace("// GraphicsPath");
+			var path = new GraphicsPath();
+			path.moveTo(0, 0);
+			path.lineTo(10, 0);
+			path.wideLineTo(10, 10);
+			path.lineTo(0, 10);
+			trace("commands:", path.commands);
+			trace("data:", path.data);
+			trace("winding:", path.winding);
+			this.path = path;
+
+			trace("// drawPath and readGraphicsData");
+			var shape = new Shape();
+			shape.graphics.beginFill(0xFF0000);
+			shape.graphics.lineStyle(2, 0x00FF00);
+			shape.graphics.drawPath(path.commands, path.data, "nonZero");
+			shape.graphics.endFill();
+
+			var data = shape.graphics.readGraphicsData();
+			trace("length:", data.length);
+			var item = data.shift();
+			trace("is GraphicsSolidFill:", item is GraphicsSolidFill);
+			trace("is IGraphicsFill:", item is IGraphicsFill);
+			trace("is IGraphicsData:", item is IGraphicsData);
+			trace("color:", item.color);
+			trace("alpha:", item.alpha);
+			item = data.shift();
+			trace("is GraphicsStroke:", item is GraphicsStroke);
+			trace("is IGraphicsStroke:", item is IGraphicsStroke);
+			trace("thickness:", item.thickness);
+			trace("pixelHinting:", item.pixelHinting);
+			trace("scaleMode:", item.scaleMode);
+			trace("caps:", item.caps);
+			trace("joints:", item.joints);
+			trace("miterLimit:", item.miterLimit);
+			trace("fill.color:", item.fill.color);
+			tracePath(data.shift());
+			item = data.shift();
+			trace("is GraphicsEndFill:", item is GraphicsEndFill);
+			trace("left:", data.length);
+
+			trace("// drawGraphicsData");
+			var copy = new Shape();
+			copy.graphics.drawGraphicsData(shape.graphics.readGraphicsData());
+			data = copy.graphics.readGraphicsData();
+			trace("length:", data.length);
+			data.shift();
+			data.shift();
+			tracePath(data.shift());
+
+			trace("// drawPath with too little data");
+			path = new GraphicsPath();
+			path.moveTo(0, 0);
+			path.commands.push(0);
+			path.lineTo(20, 0);
+			path.lineTo(20, 20);
+			path.data.pop();
+			trace("commands:", path.commands);
+			trace("data:", path.data);
+			shape = new Shape();
+			shape.graphics.drawPath(path.commands, path.data);
+			data = shape.graphics.readGraphicsData();
+			trace("length:", data.length);
+			tracePath(data.shift());
+
+			addEventListener("enterFrame", onEnterFrame);
+		}
+
+		function tracePath(item) {
+			trace("is GraphicsPath:", item is GraphicsPath);
+			trace("commands:", item.commands);
+			trace("data:", item.data);
+			trace("winding:", item.winding);
+		}
+
+		function onUncaughtError(event:UncaughtErrorEvent) {
+			trace("error:", event.error);
+			event.preventDefault();
+		}
+
+		function onEnterFrame(event:Event) {
+			switch (step++) {
+				case 0:
+					trace("// drawPath with an unknown winding");
+					new Shape().graphics.drawPath(path.commands, path.data, "sideways");
+					trace("not reached");
+					break;
+				case 1:
+					trace("// drawGraphicsData with an unknown winding");
+					path.winding = "sideways";
+					var data = new Vector.<IGraphicsData>();
+					data.push(path);
+					new Shape().graphics.drawGraphicsData(data);
+					trace("not reached");
+					break;
+				case 2:
+					removeEventListener("enterFrame", onEnterFrame);
+					break;
+			}
+		}
+	}
+}
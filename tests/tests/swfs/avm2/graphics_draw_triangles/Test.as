@@ -0,0 +1,78 @@
+package {
+	import flash.display.MovieClip;
+	import flash.display.Shape;
+	import flash.events.Event;
+	import flash.events.UncaughtErrorEvent;
+
+	// Fills triangles with `Graphics.drawTriangles`, with and without indices
+	// and with each kind of culling, and traces the size of each shape to
+	// show which triangles were drawn. The `enterFrame` listener then passes
+	// an unknown culling and traces the error that reaches `uncaughtError`.
+	public class Test extends MovieClip {
+		// One triangle wound clockwise on screen, and one wound
+		// counter-clockwise.
+		static const TWO_TRIANGLES = [0, 0, 10, 0, 0, 10, 20, 20, 20, 40, 40, 20];
+		// A triangle and a vertex that only the indices use.
+		static const SPARE_VERTEX = [0, 0, 10, 0, 0, 10, 50, 50];
+
+		var shape;
+		var step:int = 0;
+
+		public function Test() {
+			loaderInfo.uncaughtErrorEvents.addEventListener("uncaughtError", onUncaughtError);
+
+			drawTriangles(TWO_TRIANGLES);
+			traceSize("no culling");
+			drawTriangles(TWO_TRIANGLES, null, "none");
+			traceSize("none");
+			drawTriangles(TWO_TRIANGLES, null, "positive");
+			traceSize("positive");
+			drawTriangles(TWO_TRIANGLES, null, "negative");
+			traceSize("negative");
+			drawTriangles(SPARE_VERTEX);
+			traceSize("no indices");
+			drawTriangles(SPARE_VERTEX, [1, 3, 2]);
+			traceSize("indices");
+			drawTriangles(SPARE_VERTEX, [0, 1, 2, 3]);
+			traceSize("incomplete indices");
+			drawTriangles(SPARE_VERTEX, [0, 1, 9]);
+			traceSize("missing vertex");
+
+			addEventListener("enterFrame", onEnterFrame);
+		}
+
+		function drawTriangles(vertices:Array, indices:Array = null, culling:String = null) {
+			shape = new Shape();
+			shape.graphics.beginFill(0xFF0000);
+			var indexVector = indices ? Vector.<int>(indices) : null;
+			if (culling) {
+				shape.graphics.drawTriangles(Vector.<Number>(vertices), indexVector, null, culling);
+			} else {
+				shape.graphics.drawTriangles(Vector.<Number>(vertices), indexVector, null);
+			}
+		}
+
+		function traceSize(prefix) {
+			trace(prefix + " width:", shape.width);
+			trace(prefix + " height:", shape.height);
+		}
+
+		function onUncaughtError(event:UncaughtErrorEvent) {
+			trace("error:", event.error);
+			event.preventDefault();
+		}
+
+		function onEnterFrame(event:Event) {
+			switch (step++) {
+				case 0:
+					trace("// drawTriangles with an unknown culling");
+					drawTriangles(TWO_TRIANGLES, null, "sideways");
+					trace("not reached");
+					break;
+				case 1:
+					removeEventListener("enterFrame", onEnterFrame);
+					break;
+			}
+		}
+	}
+}
@@ -0,0 +1,27 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.KeyboardEvent;
+	import flash.ui.KeyLocation;
+	import flash.ui.Keyboard;
+
+	// `input.txt` presses A, then Shift+B, then 1 on the numpad. The test's
+	// UI backend types characters as a US layout would, and takes modifiers
+	// to be the ones on the left.
+	public class Test extends MovieClip {
+		public function Test() {
+			trace("Keyboard.A:", Keyboard.A);
+			trace("Keyboard.NUMPAD_1:", Keyboard.NUMPAD_1);
+			trace("KeyLocation.NUM_PAD:", KeyLocation.NUM_PAD);
+			stage.addEventListener("keyDown", onKeyDown);
+		}
+
+		function onKeyDown(event:KeyboardEvent) {
+			trace("// keyDown");
+			trace("keyCode:", event.keyCode);
+			trace("charCode:", event.charCode);
+			trace("keyLocation:", event.keyLocation);
+			trace("shiftKey:", event.shiftKey);
+			trace("ctrlKey:", event.ctrlKey);
+		}
+	}
+}
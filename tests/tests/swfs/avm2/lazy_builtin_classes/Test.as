@@ -0,0 +1,39 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.ErrorEvent;
+	import flash.events.Event;
+	import flash.system.ImageDecodingPolicy;
+	import flash.utils.getDefinitionByName;
+
+	// `ErrorEvent`, `ImageDecodingPolicy` and `Math` are builtin classes that
+	// are only constructed the first time they are looked up. `MyError`
+	// extends `ErrorEvent` before anything else has touched it, and the other
+	// two are first reached through `getDefinitionByName`.
+	public class Test extends MovieClip {
+		public function Test() {
+			var error = new MyError("custom");
+			trace("type:", error.type);
+			trace("errorID:", error.errorID);
+			trace("is ErrorEvent:", error is ErrorEvent);
+			trace("instanceof ErrorEvent:", error instanceof ErrorEvent);
+			trace("instanceof Event:", error instanceof Event);
+			trace("instanceof MyError:", error instanceof MyError);
+			trace("ErrorEvent.ERROR:", ErrorEvent.ERROR);
+			trace("getDefinitionByName is ErrorEvent:", getDefinitionByName("flash.events.ErrorEvent") === ErrorEvent);
+
+			var policy = getDefinitionByName("flash.system.ImageDecodingPolicy");
+			trace("ImageDecodingPolicy.ON_LOAD:", policy.ON_LOAD);
+			trace("getDefinitionByName is ImageDecodingPolicy:", policy === ImageDecodingPolicy);
+
+			trace("Math.max(1, 2):", getDefinitionByName("Math").max(1, 2));
+		}
+	}
+}
+
+import flash.events.ErrorEvent;
+
+class MyError extends ErrorEvent {
+	public function MyError(type:String) {
+		super(type);
+	}
+}
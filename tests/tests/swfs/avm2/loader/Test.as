@@ -0,0 +1,130 @@
+package {
+	import flash.display.Loader;
+	import flash.display.MovieClip;
+	import flash.events.Event;
+	import flash.events.IOErrorEvent;
+	import flash.events.ProgressEvent;
+	import flash.events.UncaughtErrorEvent;
+	import flash.net.URLRequest;
+	import flash.system.ApplicationDomain;
+	import flash.system.LoaderContext;
+	import flash.utils.getQualifiedClassName;
+
+	// Loads `child.swf` (built from `Child.as`) into a `Loader`, tracing the
+	// events its `contentLoaderInfo` dispatches. The child gets its own
+	// application domain, so `Child` isn't visible to the parent until the
+	// child is loaded again into `ApplicationDomain.currentDomain`. Each load
+	// unloads the content before it.
+	//
+	// Then, one step per frame, it loads `image.png` (a 4x3 red image),
+	// unloads it with `unloadAndStop`, loads a missing file, starts a load and
+	// cancels it with `close`, and calls `load` and `loadBytes` with null,
+	// tracing the errors that reach `uncaughtError`. Loaded content is
+	// constructed by the time `init` fires, and shares the Loader's
+	// `contentLoaderInfo`.
+	public class Test extends MovieClip {
+		var loader;
+		var step:int = 0;
+
+		public function Test() {
+			loaderInfo.uncaughtErrorEvents.addEventListener("uncaughtError", onUncaughtError);
+
+			loader = new Loader();
+			addChild(loader);
+			loader.contentLoaderInfo.addEventListener("open", onEvent);
+			loader.contentLoaderInfo.addEventListener("progress", onProgress);
+			loader.contentLoaderInfo.addEventListener("init", onInit);
+			loader.contentLoaderInfo.addEventListener("complete", onComplete);
+			loader.contentLoaderInfo.addEventListener("unload", onEvent);
+			loader.contentLoaderInfo.addEventListener("ioError", onIoError);
+			addEventListener("enterFrame", onEnterFrame);
+
+			trace("// load child.swf");
+			traceContent();
+			loader.load(new URLRequest("child.swf"));
+			trace("// load returned");
+			traceContent();
+		}
+
+		function traceContent() {
+			trace("content:", loader.content);
+			trace("numChildren:", loader.numChildren);
+		}
+
+		function onEvent(event:Event) {
+			trace(event.type);
+		}
+
+		function onProgress(event:ProgressEvent) {
+			trace("progress, all loaded:", event.bytesLoaded === event.bytesTotal);
+		}
+
+		function onInit(event:Event) {
+			trace("init");
+			trace("numChildren:", loader.numChildren);
+			trace("content is the child:", loader.getChildAt(0) === loader.content);
+		}
+
+		function onComplete(event:Event) {
+			trace("complete");
+			trace("content class:", getQualifiedClassName(loader.content));
+			trace("width:", loader.content.width);
+			trace("height:", loader.content.height);
+			trace("shares contentLoaderInfo:", loader.content.loaderInfo === loader.contentLoaderInfo);
+			trace("Child visible to the parent:", ApplicationDomain.currentDomain.hasDefinition("Child"));
+		}
+
+		function onIoError(event:IOErrorEvent) {
+			trace(event.type);
+			trace("errorID:", event.errorID);
+		}
+
+		function onUncaughtError(event:UncaughtErrorEvent) {
+			trace("error:", event.error);
+			event.preventDefault();
+		}
+
+		function onEnterFrame(event:Event) {
+			switch (step++) {
+				case 0:
+					trace("// load child.swf into the current domain");
+					loader.load(new URLRequest("child.swf"), new LoaderContext(false, ApplicationDomain.currentDomain));
+					break;
+				case 1:
+					trace("// load image.png");
+					loader.load(new URLRequest("image.png"));
+					break;
+				case 2:
+					trace("// unloadAndStop");
+					loader.unloadAndStop();
+					traceContent();
+					break;
+				case 3:
+					trace("// load missing.png");
+					loader.load(new URLRequest("missing.png"));
+					traceContent();
+					break;
+				case 4:
+					trace("// load image.png, then close");
+					loader.load(new URLRequest("image.png"));
+					loader.close();
+					break;
+				case 5:
+					trace("// after close");
+					traceContent();
+					trace("// load(null)");
+					loader.load(null);
+					trace("not reached");
+					break;
+				case 6:
+					trace("// loadBytes(null)");
+					loader.loadBytes(null);
+					trace("not reached");
+					break;
+				case 7:
+					removeEventListener("enterFrame", onEnterFrame);
+					break;
+			}
+		}
+	}
+}
@@ -0,0 +1,68 @@
+package {
+	import flash.display.Loader;
+	import flash.display.MovieClip;
+	import flash.events.Event;
+	import flash.events.ProgressEvent;
+	import flash.system.LoaderContext;
+	import flash.utils.ByteArray;
+
+	// Loads a 3x2 PNG into a `Loader` with `loadBytes`, tracing each event
+	// its `contentLoaderInfo` dispatches. Once the first load is complete,
+	// the same bytes are loaded again with an `imageDecodingPolicy` of
+	// `onLoad`, which decodes the image in the background. That load unloads
+	// the first image, and should then dispatch the same events as the first.
+	public class Test extends MovieClip {
+		// An opaque red 3x2 RGB PNG.
+		static const PNG = [
+			0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D,
+			0x49, 0x48, 0x44, 0x52, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x02,
+			0x08, 0x02, 0x00, 0x00, 0x00, 0x12, 0x16, 0xF1, 0x4D, 0x00, 0x00, 0x00,
+			0x10, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9C, 0x63, 0xF8, 0xCF, 0xC0, 0x00,
+			0x41, 0x0C, 0x70, 0x16, 0x00, 0x41, 0xD2, 0x05, 0xFB, 0x87, 0xF0, 0xB9,
+			0x48, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4E, 0x44, 0xAE, 0x42, 0x60,
+			0x82
+		];
+
+		var loader;
+		var bytes;
+		var reloaded:Boolean = false;
+
+		public function Test() {
+			loader = new Loader();
+			bytes = new ByteArray();
+			for each (var byte in PNG) {
+				bytes.writeByte(byte);
+			}
+
+			loader.contentLoaderInfo.addEventListener("open", onEvent);
+			loader.contentLoaderInfo.addEventListener("progress", onProgress);
+			loader.contentLoaderInfo.addEventListener("init", onEvent);
+			loader.contentLoaderInfo.addEventListener("complete", onComplete);
+			loader.contentLoaderInfo.addEventListener("unload", onEvent);
+
+			trace("// loadBytes");
+			loader.loadBytes(bytes);
+			trace("// loadBytes returned");
+		}
+
+		function onEvent(event:Event) {
+			trace(event.type);
+		}
+
+		function onProgress(event:ProgressEvent) {
+			trace(event.type, event.bytesLoaded, event.bytesTotal);
+		}
+
+		function onComplete(event:Event) {
+			trace(event.type, loader.content.width, loader.content.height);
+			if (reloaded) {
+				return;
+			}
+			reloaded = true;
+			var context = new LoaderContext();
+			context.imageDecodingPolicy = "onLoad";
+			trace("// loadBytes with imageDecodingPolicy onLoad");
+			loader.loadBytes(bytes, context);
+		}
+	}
+}
@@ -0,0 +1,23 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.Event;
+
+	public class Child extends MovieClip {
+		public function Child() {
+			trace("child: constructed");
+			var info = loaderInfo;
+			trace("child: url:", info.url.substr(info.url.lastIndexOf("/") + 1));
+			trace("child: loaderURL:", info.loaderURL.substr(info.loaderURL.lastIndexOf("/") + 1));
+			trace("child: parameters.foo:", info.parameters.foo);
+			trace("child: parameters.bar:", info.parameters.bar);
+			trace("child: swfVersion:", info.swfVersion);
+			trace("child: frameRate:", info.frameRate);
+			info.sharedEvents.addEventListener("fromParent", onFromParent);
+			info.sharedEvents.dispatchEvent(new Event("fromChild"));
+		}
+
+		function onFromParent(event:Event) {
+			trace("child: got fromParent");
+		}
+	}
+}
@@ -0,0 +1,52 @@
+package {
+	import flash.display.Loader;
+	import flash.display.MovieClip;
+	import flash.events.Event;
+	import flash.net.URLRequest;
+	import flash.system.LoaderContext;
+
+	// Traces what the movie's own `loaderInfo` says about how it was loaded,
+	// then loads `child.swf` (built from `Child.as`, as SWF 11 at 30fps) with
+	// `LoaderContext.parameters` of foo=1 and bar=2. The child traces its
+	// `loaderInfo` as it's constructed, then dispatches `fromChild` through
+	// `sharedEvents`. Once the load is complete, the parent checks the
+	// child's `loaderInfo` from its side and dispatches `fromParent` back.
+	public class Test extends MovieClip {
+		var loader;
+
+		public function Test() {
+			trace("// root movie");
+			trace("loader:", loaderInfo.loader);
+			trace("stage loader:", stage.loaderInfo.loader);
+			trace("bytes are the whole movie:", loaderInfo.bytes.length === loaderInfo.bytesTotal);
+			trace("same loaderInfo each time:", loaderInfo === this.loaderInfo);
+			trace("same sharedEvents each time:", loaderInfo.sharedEvents === loaderInfo.sharedEvents);
+
+			loader = new Loader();
+			addChild(loader);
+			loader.contentLoaderInfo.sharedEvents.addEventListener("fromChild", onFromChild);
+			loader.contentLoaderInfo.addEventListener("complete", onComplete);
+
+			trace("// load child.swf");
+			var context = new LoaderContext();
+			context.parameters = {foo: "1", bar: "2"};
+			loader.load(new URLRequest("child.swf"), context);
+		}
+
+		function onFromChild(event:Event) {
+			trace("parent: got fromChild");
+		}
+
+		function onComplete(event:Event) {
+			trace("// complete");
+			var info = loader.contentLoaderInfo;
+			trace("loader is the Loader:", info.loader === loader);
+			trace("content is the Loader's content:", info.content === loader.content);
+			trace("bytesTotal:", info.bytesTotal);
+			trace("bytesLoaded:", info.bytesLoaded);
+			trace("bytes.length:", info.bytes.length);
+			trace("url:", info.url.substr(info.url.lastIndexOf("/") + 1));
+			info.sharedEvents.dispatchEvent(new Event("fromParent"));
+		}
+	}
+}
@@ -0,0 +1,130 @@
+#!/usr/bin/env python3
+"""Generates test.swf for the morphshape test.
+
+The root timeline places a shape tween, a red square growing from 100x100
+to 200x200 pixels, halfway through at ratio 32768. The document class
+checks that it's a `MorphShape` with the size of the tweened square. On the
+next frame it tries to construct a `MorphShape` itself, which isn't
+allowed.
+
+This is assembled rather than compiled from ActionScript because the shape
+tween has to be a DefineMorphShape placed on the root timeline, which only
+an authoring tool can make, and scripts can't create.
+"""
+
+import os
+import struct
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", ".."))
+
+from swfgen import (
+    END,
+    SHOW_FRAME,
+    Abc,
+    BitWriter,
+    do_abc,
+    file_attributes,
+    rect,
+    set_background_color,
+    signed_bits,
+    swf,
+    symbol_class,
+    tag,
+)
+
+MORPH_SHAPE = "flash.display::MorphShape"
+
+
+def square_edges(size, fill_style):
+    """The edges of a square with its top left corner at the origin, `size`
+    twips across, filled with `fill_style` if it isn't None."""
+    writer = BitWriter()
+    # The end edges of a morph shape can't select styles, so they have no
+    # style bits.
+    writer.ub(1 if fill_style is not None else 0, 4)  # NumFillBits
+    writer.ub(0, 4)  # NumLineBits
+
+    # Style change: move to the origin, and select the fill style.
+    writer.ub(0, 1)  # TypeFlag
+    writer.ub(0b00101 if fill_style is not None else 0b00001, 5)
+    writer.ub(2, 5)
+    writer.sb(0, 2)
+    writer.sb(0, 2)
+    if fill_style is not None:
+        writer.ub(fill_style, 1)
+
+    for dx, dy in [(size, 0), (0, size), (-size, 0), (0, -size)]:
+        n = signed_bits(dx, dy)
+        writer.ub(1, 1)  # TypeFlag
+        writer.ub(1, 1)  # StraightFlag
+        writer.ub(n - 2, 4)
+        writer.ub(1, 1)  # GeneralLineFlag
+        writer.sb(dx, n)
+        writer.sb(dy, n)
+
+    writer.ub(0, 6)  # EndShapeRecord
+    return writer.to_bytes()
+
+
+def define_morph_shape(character_id, start_size, end_size):
+    """A red square tweening from `start_size` to `end_size` pixels across."""
+    start, end = start_size * 20, end_size * 20
+    body = struct.pack("<H", character_id) + rect(0, start, 0, start) + rect(0, end, 0, end)
+    styles = bytes([1, 0x00]) + bytes([0xFF, 0, 0, 0xFF]) * 2  # One solid red fill.
+    styles += bytes([0])  # No line styles.
+    start_edges = square_edges(start, 1)
+    body += struct.pack("<I", len(styles) + len(start_edges)) + styles + start_edges
+    body += square_edges(end, None)
+    return tag(46, body)
+
+
+def place_object_with_ratio(depth, character_id, ratio):
+    flags = 0x02 | 0x10  # HasCharacter | HasRatio
+    return tag(26, struct.pack("<BHHH", flags, depth, character_id, ratio))
+
+
+abc = Abc()
+doc = abc.document_class("Test")
+
+ctor = doc.constructor
+ctor.getlocal_0().getproperty("loaderInfo").getproperty("uncaughtErrorEvents")
+ctor.pushstring("uncaughtError").getlocal_0().getproperty("onUncaughtError")
+ctor.callpropvoid("addEventListener", 2)
+ctor.getlocal_0().pushstring("enterFrame").getlocal_0().getproperty("onEnterFrame")
+ctor.callpropvoid("addEventListener", 2)
+
+ctor.trace("// timeline morph shape")
+ctor.getlocal_0().push(0).callproperty("getChildAt", 1).setlocal_1()
+ctor.getlocal_1().getlex(MORPH_SHAPE).istypelate().trace_top("is MorphShape:")
+ctor.getlocal_1().getlex("flash.display::Shape").istypelate().trace_top("is Shape:")
+ctor.getlocal_1().getlex("flash.display::DisplayObject").istypelate()
+ctor.trace_top("is DisplayObject:")
+ctor.findpropstrict("flash.utils::getQualifiedClassName").getlocal_1()
+ctor.callproperty("flash.utils::getQualifiedClassName", 1).trace_top("class:")
+ctor.getlocal_1().getproperty("parent").getlocal_0().strictequals()
+ctor.trace_top("parent is the root:")
+ctor.getlocal_1().getproperty("width").trace_top("width:")
+ctor.getlocal_1().getproperty("height").trace_top("height:")
+
+on_uncaught_error = doc.method("onUncaughtError", "flash.events::UncaughtErrorEvent")
+on_uncaught_error.getlocal_1().getproperty("error").trace_top("error:")
+on_uncaught_error.getlocal_1().callpropvoid("preventDefault", 0)
+
+on_enter_frame = doc.method("onEnterFrame", "flash.events::Event")
+on_enter_frame.getlocal_0().pushstring("enterFrame").getlocal_0().getproperty("onEnterFrame")
+on_enter_frame.callpropvoid("removeEventListener", 2)
+on_enter_frame.trace("// new MorphShape()")
+on_enter_frame.getlex(MORPH_SHAPE).construct(0).pop()
+on_enter_frame.trace("not reached")
+
+abc.document_script(doc)
+tags = file_attributes()
+tags += set_background_color(0xFFFFFF)
+tags += define_morph_shape(1, 100, 200)
+tags += do_abc(abc.serialize())
+tags += symbol_class([(0, doc.name)])
+tags += place_object_with_ratio(1, 1, 32768)
+tags += SHOW_FRAME + END
+with open(os.path.join(os.path.dirname(__file__), "test.swf"), "wb") as f:
+    f.write(swf(tags))
@@ -0,0 +1,34 @@
+package {
+	import flash.display.BitmapData;
+	import flash.display.MovieClip;
+	import flash.geom.Point;
+	import flash.ui.Mouse;
+	import flash.ui.MouseCursorData;
+
+	// Picks a built-in cursor with `Mouse.cursor`, registers a 2x3 bitmap
+	// cursor whose hot spot lies outside of it, shows that cursor and hides
+	// the mouse. The test checks what reached the UI backend once the movie
+	// has run.
+	public class Test extends MovieClip {
+		public function Test() {
+			trace("supportsCursor:", Mouse.supportsCursor);
+			trace("cursor:", Mouse.cursor);
+
+			Mouse.cursor = "ibeam";
+			trace("cursor:", Mouse.cursor);
+
+			var cursor = new MouseCursorData();
+			var data = new Vector.<BitmapData>();
+			data.push(new BitmapData(2, 3, true, 0x80FF0000));
+			cursor.data = data;
+			cursor.hotSpot = new Point(5, 1);
+			Mouse.registerCursor("dot", cursor);
+			trace("cursor after registering:", Mouse.cursor);
+
+			Mouse.cursor = "dot";
+			trace("cursor:", Mouse.cursor);
+
+			Mouse.hide();
+		}
+	}
+}
@@ -0,0 +1,48 @@
+package {
+	import flash.display.MovieClip;
+	import flash.display.Sprite;
+	import flash.events.KeyboardEvent;
+	import flash.events.MouseEvent;
+
+	// Adds a 100x100 sprite at (50, 50). The sprite doesn't have
+	// `buttonMode` set, as AVM2 sends mouse events to every interactive
+	// object. `input.txt` moves the mouse over the sprite, clicks it and
+	// presses a key, and the fields of each event are traced.
+	public class Test extends MovieClip {
+		var sprite;
+
+		public function Test() {
+			sprite = new Sprite();
+			sprite.graphics.beginFill(0xFF0000);
+			sprite.graphics.drawRect(0, 0, 100, 100);
+			sprite.x = 50;
+			sprite.y = 50;
+			addChild(sprite);
+			sprite.addEventListener("mouseOver", onMouse);
+			sprite.addEventListener("mouseDown", onMouse);
+			sprite.addEventListener("mouseUp", onMouse);
+			sprite.addEventListener("click", onMouse);
+			stage.addEventListener("keyDown", onKey);
+			stage.addEventListener("keyUp", onKey);
+		}
+
+		function onMouse(event:MouseEvent) {
+			trace("//", event.type);
+			trace("target is sprite:", event.target === sprite);
+			trace("bubbles:", event.bubbles);
+			trace("localX:", event.localX);
+			trace("localY:", event.localY);
+			trace("stageX:", event.stageX);
+			trace("stageY:", event.stageY);
+			trace("buttonDown:", event.buttonDown);
+			trace("relatedObject:", event.relatedObject);
+		}
+
+		function onKey(event:KeyboardEvent) {
+			trace("//", event.type);
+			trace("target is stage:", event.target === stage);
+			trace("bubbles:", event.bubbles);
+			trace("keyCode:", event.keyCode);
+		}
+	}
+}
@@ -0,0 +1,44 @@
+package {
+	import flash.display.MovieClip;
+	import flash.net.URLRequest;
+	import flash.net.URLVariables;
+	import flash.net.navigateToURL;
+	import flash.net.sendToURL;
+
+	// Opens `URLRequest`s with `navigateToURL` and sends them with
+	// `sendToURL`, with and without a window, `URLVariables` data, and both
+	// methods. The test harness traces the requests that reach the navigator.
+	public class Test extends MovieClip {
+		public function Test() {
+			var variables = new URLVariables();
+			variables.a = "1";
+			variables.b = "x y";
+
+			var request = new URLRequest("http://example.com/a");
+
+			trace("// navigateToURL, default window");
+			navigateToURL(request);
+
+			request.url = "http://example.com/b";
+			request.data = variables;
+			trace("// navigateToURL, GET variables");
+			navigateToURL(request, "_self");
+
+			request.method = "POST";
+			trace("// navigateToURL, POST variables");
+			navigateToURL(request, "_top");
+
+			trace("// sendToURL, POST variables");
+			sendToURL(request);
+
+			request.method = "GET";
+			request.url = "http://example.com/c?x=1";
+			trace("// sendToURL, GET variables");
+			sendToURL(request);
+
+			request.data = "a=1&b=2";
+			trace("// sendToURL, GET string data");
+			sendToURL(request);
+		}
+	}
+}
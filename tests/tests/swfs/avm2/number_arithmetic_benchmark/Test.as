@@ -0,0 +1,19 @@
+package {
+	import flash.display.MovieClip;
+
+	// A tight loop of mixed `int` and `Number` arithmetic, with its operands
+	// passed through `as Number`. The test is ignored by default; time it with
+	//
+	//     cargo test --release --test regression_tests as3_number_arithmetic_benchmark -- --ignored
+	//
+	// to measure changes to how numbers are represented and operated on.
+	public class Test extends MovieClip {
+		public function Test() {
+			var total:Number = 0;
+			for (var i:int = 0; i < 200000; i++) {
+				total += (i as Number) * 0.5 + (i + 3) % 7;
+			}
+			trace("total:", total);
+		}
+	}
+}
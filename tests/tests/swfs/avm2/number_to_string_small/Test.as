@@ -0,0 +1,34 @@
+package {
+	import flash.display.MovieClip;
+
+	// Small integers and single characters are handed out from a table of
+	// preallocated strings. This checks the values either side of the
+	// table's limits, and that the strings it produces behave like any other.
+	public class Test extends MovieClip {
+		public function Test() {
+			trace("// Number, int and uint to String");
+			trace("0:", String(0));
+			trace("-0:", String(-0.0));
+			trace("-1:", String(-1));
+			trace("255:", String(255));
+			trace("256:", String(256));
+			trace("255.0:", String(255.0));
+			trace("255.5:", String(255.5));
+			trace("uint 4294967295:", String(uint(4294967295)));
+			trace("4294967296:", String(4294967296));
+
+			trace("// charAt");
+			for (var i = 0; i < 4; i++) {
+				trace("charAt(" + i + "):", "aé€z".charAt(i));
+			}
+
+			trace("// split and join");
+			trace("split:", "aé€z".split("").join("|"));
+			trace("join:", [1, null, undefined, 300].join(","));
+
+			trace("// concatenated small strings");
+			trace("1 + 2:", String(1) + String(2));
+			trace("1 === 1:", String(1) === String(1));
+		}
+	}
+}
@@ -0,0 +1,50 @@
+package {
+	import flash.display.MovieClip;
+
+	// Calls `hasOwnProperty`, `propertyIsEnumerable`, `setPropertyIsEnumerable`
+	// and `isPrototypeOf` on an instance of a sealed class, a dynamic object,
+	// an array and primitives.
+	public class Test extends MovieClip {
+		var field = "value";
+
+		public function Test() {
+			trace("// sealed class instance");
+			trace("hasOwnProperty(field):", this.hasOwnProperty("field"));
+			trace("hasOwnProperty(missing):", this.hasOwnProperty("missing"));
+			trace("propertyIsEnumerable(field):", this.propertyIsEnumerable("field"));
+
+			trace("// dynamic object");
+			var obj:* = {a: 1};
+			trace("hasOwnProperty(a):", obj.hasOwnProperty("a"));
+			trace("hasOwnProperty(toString):", obj.hasOwnProperty("toString"));
+			trace("propertyIsEnumerable(a):", obj.propertyIsEnumerable("a"));
+			trace("setPropertyIsEnumerable(a, false):", obj.setPropertyIsEnumerable("a", false));
+			trace("propertyIsEnumerable(a):", obj.propertyIsEnumerable("a"));
+			trace("setPropertyIsEnumerable(a, 1):", obj.setPropertyIsEnumerable("a", 1));
+			trace("propertyIsEnumerable(a):", obj.propertyIsEnumerable("a"));
+			trace("hasOwnProperty():", obj.hasOwnProperty());
+			obj.undefined = 1;
+			trace("hasOwnProperty() after setting undefined:", obj.hasOwnProperty());
+
+			trace("// array");
+			var arr:* = [10, 20];
+			trace("hasOwnProperty(0):", arr.hasOwnProperty("0"));
+			trace("hasOwnProperty(2):", arr.hasOwnProperty("2"));
+			trace("propertyIsEnumerable(1):", arr.propertyIsEnumerable("1"));
+			trace("propertyIsEnumerable(push):", arr.propertyIsEnumerable("push"));
+
+			trace("// primitives");
+			trace("(5).hasOwnProperty(x):", (5).hasOwnProperty("x"));
+			trace("'abc'.propertyIsEnumerable(0):", "abc".propertyIsEnumerable("0"));
+
+			trace("// isPrototypeOf");
+			trace("Object.prototype of object:", Object.prototype.isPrototypeOf(obj));
+			trace("Object.prototype of array:", Object.prototype.isPrototypeOf(arr));
+			trace("Array.prototype of array:", Array.prototype.isPrototypeOf(arr));
+			trace("Array.prototype of object:", Array.prototype.isPrototypeOf(obj));
+			trace("object of itself:", obj.isPrototypeOf(obj));
+			trace("Object.prototype of 5:", Object.prototype.isPrototypeOf(5));
+			trace("Object.prototype of nothing:", Object.prototype.isPrototypeOf());
+		}
+	}
+}
@@ -0,0 +1,76 @@
+package {
+	import flash.display.MovieClip;
+
+	// The same property reads and writes in `readX`, `writeX` and `readK` are
+	// run against receivers of several classes, so that their inline caches
+	// are filled, hit, missed, and refilled. `C` declares `x` in a different
+	// slot than `A`, so a stale cache entry would read or write the wrong slot.
+	public class Test extends MovieClip {
+		public function Test() {
+			var a = new A();
+			var b = new B();
+			var c = new C();
+			var d = new D();
+			var e = new E();
+
+			writeX(a, 1);
+			writeX(c, 3);
+			writeX(b, 2);
+			trace("a.x:", readX(a));
+			trace("c.x:", readX(c));
+			trace("b.x:", readX(b));
+			trace("a.x:", readX(a));
+			trace("e.x:", readX(e));
+			trace("c.x:", readX(c));
+			trace("a.x:", readX(a));
+
+			trace("c.pad:", c.pad);
+
+			// Dynamic receivers are never cached, so `x` is found once it is added.
+			trace("d.x:", readX(d));
+			writeX(d, 4);
+			trace("d.x:", readX(d));
+			trace("a.x:", readX(a));
+
+			trace("a.k:", readK(a));
+			trace("b.k:", readK(b));
+			trace("a.k:", readK(a));
+		}
+
+		function readX(o) {
+			return o.x;
+		}
+
+		function writeX(o, value) {
+			o.x = value;
+		}
+
+		function readK(o) {
+			return o.k;
+		}
+	}
+}
+
+class A {
+	var x;
+	const k = 7;
+}
+
+class B extends A {
+	var y;
+}
+
+class C {
+	var pad;
+	var x;
+}
+
+// Dynamic, with no `x` trait.
+dynamic class D {
+}
+
+class E {
+	function get x() {
+		return 5;
+	}
+}
@@ -0,0 +1,24 @@
+package {
+	import flash.display.MovieClip;
+
+	// A tight loop of cached slot reads and writes. The test is ignored by
+	// default; time it with
+	//
+	//     cargo test --release --test regression_tests as3_property_cache_benchmark -- --ignored
+	//
+	// to measure the inline caches against a build without them.
+	public class Test extends MovieClip {
+		public function Test() {
+			var a = new A();
+			for (var i:int = 0; i < 200000; i++) {
+				a.x = a.x + a.k;
+			}
+			trace("x:", a.x);
+		}
+	}
+}
+
+class A {
+	public var x = 0;
+	public const k = 7;
+}
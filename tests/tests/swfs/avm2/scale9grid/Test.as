@@ -0,0 +1,54 @@
+package {
+	import flash.display.MovieClip;
+	import flash.display.Sprite;
+	import flash.geom.Rectangle;
+
+	// Draws a 100x100 square in a sprite, gives it a `scale9Grid` and traces
+	// the grid it gives back, which is a new rectangle on every read. It then
+	// scales the sprite, which is sliced by the grid but keeps the size its
+	// scale gives it, and removes the grid again.
+	public class Test extends MovieClip {
+		var sprite;
+
+		public function Test() {
+			sprite = new Sprite();
+			sprite.graphics.beginFill(0xFF0000);
+			sprite.graphics.drawRect(0, 0, 100, 100);
+			addChild(sprite);
+
+			trace("// no grid");
+			trace("scale9Grid:", sprite.scale9Grid);
+
+			trace("// grid");
+			sprite.scale9Grid = new Rectangle(10, 15, 80, 70);
+			var grid = traceGrid("scale9Grid");
+			trace("same object each time:", sprite.scale9Grid === grid);
+			grid.width = 0;
+			traceGrid("after changing it");
+
+			trace("// scaled");
+			sprite.scaleX = 3;
+			sprite.scaleY = 0.5;
+			traceSize();
+
+			trace("// removed");
+			sprite.scale9Grid = null;
+			trace("scale9Grid:", sprite.scale9Grid);
+			traceSize();
+		}
+
+		function traceGrid(prefix) {
+			var grid = sprite.scale9Grid;
+			trace(prefix + " x:", grid.x);
+			trace(prefix + " y:", grid.y);
+			trace(prefix + " width:", grid.width);
+			trace(prefix + " height:", grid.height);
+			return grid;
+		}
+
+		function traceSize() {
+			trace("width:", sprite.width);
+			trace("height:", sprite.height);
+		}
+	}
+}
@@ -0,0 +1,81 @@
+#!/usr/bin/env python3
+"""Generates test.swf for the scope_with_activation test.
+
+A `with` scope resolves names along its object's whole prototype chain, so
+`x` two prototypes up shadows the `x` of an outer scope, including from a
+closure created inside the `with` block. `getscopeobject` counts from the
+bottom of the method's own scopes, so the scopes it finds don't change when
+a `with` scope is pushed on top.
+
+A function with an activation object keeps its local `count` in a slot of
+the activation, which a closure updates, and which `getscopeobject(0)` finds
+from under a `with` scope.
+
+This is assembled rather than compiled from ActionScript because compilers
+never emit `getscopeobject` to reach a `with` scope, or the scopes under one,
+so the indices it counts from can't be tested from source.
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", ".."))
+
+from swfgen import Abc, write_avm2_movie
+
+abc = Abc()
+doc = abc.document_class("Test")
+ctor = doc.constructor
+
+ctor.trace("// with scope")
+ctor.pushstring("x").pushstring("outer").newobject(1).pushscope()
+
+# `obj` is an `M`, whose prototype is a `G`, whose prototype has `x`.
+g = abc.function()
+m = abc.function()
+ctor.newfunction(g).setlocal_1()
+ctor.getlocal_1().pushstring("x").pushstring("deep").newobject(1).setproperty("prototype")
+ctor.newfunction(m).setlocal_2()
+ctor.getlocal_2().getlocal_1().construct(0).setproperty("prototype")
+ctor.getlocal_2().construct(0).setlocal_3()
+
+get_x = abc.function()
+get_x.findpropstrict("x").getproperty("x").returnvalue()
+
+ctor.getlocal_3().pushwith()
+ctor.findpropstrict("x").getproperty("x").trace_top("x inside with:")
+ctor.newfunction(get_x).setlocal(4)
+ctor.getscopeobject(0).getlocal_0().strictequals().trace_top("getscopeobject(0) is this:")
+ctor.getscopeobject(1).getproperty("x").trace_top("getscopeobject(1).x:")
+ctor.getscopeobject(2).getlocal_3().strictequals().trace_top(
+    "getscopeobject(2) is the with object:"
+)
+ctor.popscope()
+ctor.findpropstrict("x").getproperty("x").trace_top("x after popscope:")
+ctor.getlocal(4).pushnull().call(0).trace_top("x from closure:")
+ctor.popscope()
+
+ctor.trace("// activation")
+inc = abc.function()
+inc.findpropstrict("count").findpropstrict("count").getproperty("count")
+inc.increment().setproperty("count")
+inc.findpropstrict("count").getproperty("count").returnvalue()
+
+f = abc.function()
+f.activation_var("count")
+f.newactivation().dup().pushscope().setlocal_1()
+f.getlocal_1().pushbyte(0).setslot(1)
+f.newfunction(inc).setlocal_2()
+f.getlocal_2().pushnull().call(0).trace_top("inc():")
+f.getlocal_2().pushnull().call(0).trace_top("inc():")
+f.pushstring("count").pushbyte(99).newobject(1).pushwith()
+f.getscopeobject(0).getslot(1).trace_top("getscopeobject(0).count:")
+f.findpropstrict("count").getproperty("count").trace_top("count inside with:")
+f.getscopeobject(0).getlocal_1().strictequals().trace_top(
+    "getscopeobject(0) is the activation:"
+)
+f.popscope()
+
+ctor.newfunction(f).pushnull().call(0).pop()
+
+write_avm2_movie(os.path.join(os.path.dirname(__file__), "test.swf"), abc, doc)
@@ -0,0 +1,59 @@
+package {
+	import flash.display.MovieClip;
+	import flash.display.Sprite;
+	import flash.geom.Rectangle;
+
+	// Draws a 100x100 square in a sprite at (5, 5) and scrolls it to the
+	// rectangle at (10, 20) that's 30x40. It traces the sprite's size and hit
+	// tests on either side of the rectangle's right edge before scrolling,
+	// while scrolled and after `scrollRect` is set back to null. It also
+	// checks that each read of `scrollRect` gives a new rectangle, and that
+	// changing one doesn't scroll the sprite.
+	public class Test extends MovieClip {
+		var sprite;
+
+		public function Test() {
+			sprite = new Sprite();
+			sprite.graphics.beginFill(0xFF0000);
+			sprite.graphics.drawRect(0, 0, 100, 100);
+			sprite.x = 5;
+			sprite.y = 5;
+			addChild(sprite);
+
+			trace("// not scrolled");
+			trace("scrollRect:", sprite.scrollRect);
+			traceSprite();
+
+			trace("// scrolled");
+			sprite.scrollRect = new Rectangle(10, 20, 30, 40);
+			var rect = traceScrollRect("scrollRect");
+			traceSprite();
+
+			trace("// returned rectangle");
+			trace("same object each time:", sprite.scrollRect === rect);
+			rect.x = 0;
+			traceScrollRect("after changing it");
+
+			trace("// removed");
+			sprite.scrollRect = null;
+			trace("scrollRect:", sprite.scrollRect);
+			traceSprite();
+		}
+
+		function traceScrollRect(prefix) {
+			var rect = sprite.scrollRect;
+			trace(prefix + " x:", rect.x);
+			trace(prefix + " y:", rect.y);
+			trace(prefix + " width:", rect.width);
+			trace(prefix + " height:", rect.height);
+			return rect;
+		}
+
+		function traceSprite() {
+			trace("width:", sprite.width);
+			trace("height:", sprite.height);
+			trace("hit inside the rectangle:", sprite.hitTestPoint(34, 10, true));
+			trace("hit past its right edge:", sprite.hitTestPoint(36, 10, true));
+		}
+	}
+}
@@ -0,0 +1,64 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.Event;
+	import flash.events.IOErrorEvent;
+	import flash.events.ProgressEvent;
+	import flash.net.Socket;
+
+	// Connects a `Socket` to the test navigator's echo server, sends it
+	// `hello` and then `bye`, after which the server hangs up. A second
+	// socket connects to a port nothing listens on.
+	public class Test extends MovieClip {
+		public function Test() {
+			trace("connected before connect:", new Socket().connected);
+
+			var socket = new Socket();
+			socket.addEventListener("connect", onConnect);
+			socket.addEventListener("socketData", onSocketData);
+			socket.addEventListener("close", onClose);
+			socket.addEventListener("ioError", onIoError);
+			socket.connect("echo.example", 7);
+			trace("connected while connecting:", socket.connected);
+
+			socket = new Socket();
+			socket.addEventListener("connect", onConnect);
+			socket.addEventListener("ioError", onIoError);
+			socket.connect("echo.example", 8);
+		}
+
+		// Writes a string to the socket, and flushes it.
+		function send(socket, text:String) {
+			socket.writeUTFBytes(text);
+			trace("bytesPending:", socket.bytesPending);
+			socket.flush();
+			trace("bytesPending after flush:", socket.bytesPending);
+		}
+
+		function onConnect(event:Event) {
+			trace("connect");
+			trace("connected:", event.target.connected);
+			send(event.target, "hello");
+		}
+
+		function onSocketData(event:ProgressEvent) {
+			trace("socketData", event.bytesLoaded);
+			trace("bytesAvailable:", event.target.bytesAvailable);
+			var received = event.target.readUTFBytes(event.target.bytesAvailable);
+			trace("received:", received);
+			trace("bytesAvailable after reading:", event.target.bytesAvailable);
+			if (received == "hello") {
+				send(event.target, "bye");
+			}
+		}
+
+		function onClose(event:Event) {
+			trace("close");
+			trace("connected:", event.target.connected);
+		}
+
+		function onIoError(event:IOErrorEvent) {
+			trace("ioError", event.errorID, event.text);
+			trace("connected:", event.target.connected);
+		}
+	}
+}
@@ -0,0 +1,92 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.Event;
+	import flash.events.FullScreenEvent;
+	import flash.events.KeyboardEvent;
+	import flash.events.UncaughtErrorEvent;
+	import flash.geom.Rectangle;
+
+	// Sets `fullScreenSourceRect` to the 100x50 rectangle at (10, 20) and
+	// asks for `FULL_SCREEN`. The display state stays `normal` until the
+	// player actually goes fullscreen. Setting `displayState` to an unknown
+	// value throws error #2008.
+	//
+	// After the last frame, the test goes fullscreen. The `fullScreen`
+	// handler sees the source rectangle as the stage size, then turns
+	// hardware scaling off with an empty rectangle. While fullscreen, the
+	// test presses A and Space; only Space gets through. Its handler asks to
+	// leave fullscreen, which the display state shows straight away. Once the
+	// test leaves fullscreen, A gets through again.
+	public class Test extends MovieClip {
+		var step:int = 0;
+
+		public function Test() {
+			loaderInfo.uncaughtErrorEvents.addEventListener("uncaughtError", onUncaughtError);
+			stage.addEventListener("fullScreen", onFullScreen);
+			stage.addEventListener("keyDown", onKeyDown);
+			addEventListener("enterFrame", onEnterFrame);
+
+			trace("// defaults");
+			traceState();
+			trace("allowsFullScreen:", stage.allowsFullScreen);
+			trace("allowsFullScreenInteractive:", stage.allowsFullScreenInteractive);
+			trace("fullScreenSourceRect:", stage.fullScreenSourceRect);
+
+			trace("// fullScreenSourceRect = (10, 20, 100, 50)");
+			stage.fullScreenSourceRect = new Rectangle(10, 20, 100, 50);
+			var rect = stage.fullScreenSourceRect;
+			trace("fullScreenSourceRect:", rect);
+			// The getter returns a copy.
+			rect.x = 0;
+			trace("fullScreenSourceRect:", stage.fullScreenSourceRect);
+			traceState();
+
+			trace("// displayState = fullScreen");
+			stage.displayState = "fullScreen";
+			traceState();
+		}
+
+		function traceState() {
+			trace("displayState:", stage.displayState);
+			trace("stageWidth:", stage.stageWidth);
+			trace("stageHeight:", stage.stageHeight);
+		}
+
+		function onUncaughtError(event:UncaughtErrorEvent) {
+			trace("error:", event.error);
+			event.preventDefault();
+		}
+
+		function onEnterFrame(event:Event) {
+			switch (step++) {
+				case 0:
+					trace("// displayState = bogus");
+					stage.displayState = "bogus";
+					trace("not reached");
+					break;
+				case 1:
+					traceState();
+					removeEventListener("enterFrame", onEnterFrame);
+					break;
+			}
+		}
+
+		function onFullScreen(event:FullScreenEvent) {
+			trace("// fullScreen", event.fullScreen);
+			traceState();
+			trace("// fullScreenSourceRect = empty rectangle");
+			stage.fullScreenSourceRect = new Rectangle();
+			trace("fullScreenSourceRect:", stage.fullScreenSourceRect);
+			traceState();
+		}
+
+		function onKeyDown(event:KeyboardEvent) {
+			trace("keyDown:", event.keyCode);
+			if (event.keyCode == 32) {
+				trace("// displayState = normal");
+				stage.displayState = "normal";
+				traceState();
+			}
+		}
+	}
+}
@@ -0,0 +1,18 @@
+package {
+	import flash.display.MovieClip;
+
+	// Sets `stage.quality` to each quality level and traces what it reads
+	// back. Values are case-insensitive and always read back in uppercase,
+	// the linear modes read back as their plain counterparts, and unknown
+	// values leave the quality unchanged.
+	public class Test extends MovieClip {
+		public function Test() {
+			trace("default:", stage.quality);
+			var values = ["low", "medium", "high", "best", "8x8", "8x8linear", "16x16", "16x16linear", "Low", "bogus", ""];
+			for each (var value in values) {
+				stage.quality = value;
+				trace('"' + value + '":', stage.quality);
+			}
+		}
+	}
+}
@@ -0,0 +1,77 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.Event;
+	import flash.events.FullScreenEvent;
+
+	// The test runs in a 900x900 viewport. The movie listens for `resize`
+	// and `fullScreen` on the stage, then sets `stageWidth` and `stageHeight`
+	// in the default scale mode, where they're ignored, and in `NO_SCALE`
+	// mode, where they resize the stage. Setting the size the stage already
+	// has doesn't fire `resize`, and negative sizes become 0. It also traces
+	// the `FullScreenEvent` constants and what its constructor stores.
+	//
+	// After the last frame, the test makes the player fullscreen and resizes
+	// its viewport, then leaves fullscreen again. Each change resizes the
+	// stage back to the viewport and fires `fullScreen`.
+	public class Test extends MovieClip {
+		public function Test() {
+			stage.addEventListener("resize", onResize);
+			stage.addEventListener("fullScreen", onFullScreen);
+
+			trace("// showAll");
+			traceSize();
+			trace("// stageWidth = 100, stageHeight = 50 in showAll");
+			stage.stageWidth = 100;
+			stage.stageHeight = 50;
+			traceSize();
+
+			trace("// scaleMode = noScale");
+			stage.scaleMode = "noScale";
+			traceSize();
+			trace("// stageWidth = 100");
+			stage.stageWidth = 100;
+			trace("// stageHeight = 50.5");
+			var height:* = 50.5;
+			stage.stageHeight = height;
+			trace("// stageWidth = 100 again");
+			stage.stageWidth = 100;
+			traceSize();
+			trace("// stageWidth = -5");
+			stage.stageWidth = -5;
+			traceSize();
+
+			trace("// FullScreenEvent");
+			trace("FULL_SCREEN:", FullScreenEvent.FULL_SCREEN);
+			trace("FULL_SCREEN_INTERACTIVE_ACCEPTED:", FullScreenEvent.FULL_SCREEN_INTERACTIVE_ACCEPTED);
+			var event = new FullScreenEvent("fullScreen");
+			traceFullScreenEvent(event);
+			event = new FullScreenEvent("custom", true, true, true, true);
+			traceFullScreenEvent(event);
+			trace("is an Event:", event is Event);
+		}
+
+		function traceSize() {
+			trace("stageWidth:", stage.stageWidth);
+			trace("stageHeight:", stage.stageHeight);
+		}
+
+		function traceFullScreenEvent(event:FullScreenEvent) {
+			trace("type:", event.type);
+			trace("bubbles:", event.bubbles);
+			trace("cancelable:", event.cancelable);
+			trace("fullScreen:", event.fullScreen);
+			trace("interactive:", event.interactive);
+		}
+
+		function onResize(event:Event) {
+			trace("// resize");
+			traceSize();
+		}
+
+		function onFullScreen(event:FullScreenEvent) {
+			trace("// fullScreen");
+			traceFullScreenEvent(event);
+			traceSize();
+		}
+	}
+}
@@ -0,0 +1,135 @@
+#!/usr/bin/env python3
+"""Generates test.swf for the text_snapshot test.
+
+This is built with swfgen rather than from a Test.as, because the test needs
+static text on the root timeline: two DefineText tags, "Hello" and "World",
+drawn with an embedded DefineFont2. The document class reads and selects
+them through the root's `TextSnapshot`.
+"""
+
+import os
+import struct
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", ".."))
+
+from swfgen import (
+    END,
+    SHOW_FRAME,
+    Abc,
+    BitWriter,
+    do_abc,
+    file_attributes,
+    place_object,
+    rect,
+    set_background_color,
+    swf,
+    symbol_class,
+    tag,
+)
+
+CHARACTERS = "HeloWrd"
+GLYPH_BITS = 3
+ADVANCE_BITS = 10
+ADVANCE = 400
+
+
+def define_font(font_id):
+    """A DefineFont2 with an empty glyph for each of `CHARACTERS`."""
+    # Each glyph has one fill style bit, no line style bits, and nothing but
+    # an end record.
+    glyph = bytes([0x10, 0x00])
+    num_glyphs = len(CHARACTERS)
+    offsets_size = 2 * (num_glyphs + 1)
+    offsets = b"".join(
+        struct.pack("<H", offsets_size + i * len(glyph)) for i in range(num_glyphs)
+    )
+    code_table_offset = struct.pack("<H", offsets_size + num_glyphs * len(glyph))
+    codes = b"".join(struct.pack("<H", ord(c)) for c in CHARACTERS)
+
+    name = b"Test"
+    body = struct.pack("<HBB", font_id, 0x04, 0)  # FontFlagsWideCodes
+    body += bytes([len(name)]) + name + struct.pack("<H", num_glyphs)
+    body += offsets + code_table_offset + glyph * num_glyphs + codes
+    return tag(48, body)
+
+
+def define_text(character_id, font_id, text):
+    width = len(text) * ADVANCE
+    body = struct.pack("<H", character_id) + rect(0, width, 0, 400)
+    body += bytes([0x00])  # An identity matrix.
+    body += bytes([GLYPH_BITS, ADVANCE_BITS])
+
+    # HasFont | HasColor | HasYOffset
+    body += bytes([0x80 | 0x08 | 0x04 | 0x02]) + struct.pack("<H", font_id)
+    body += bytes([0, 0, 0]) + struct.pack("<hH", 400, 400) + bytes([len(text)])
+    glyphs = BitWriter()
+    for c in text:
+        glyphs.ub(CHARACTERS.index(c), GLYPH_BITS)
+        glyphs.sb(ADVANCE, ADVANCE_BITS)
+    body += glyphs.to_bytes()
+
+    body += bytes([0])  # EndOfRecordsFlag
+    return tag(11, body)
+
+
+abc = Abc()
+doc = abc.document_class("Test")
+
+ctor = doc.constructor
+ctor.getlocal_0().getproperty("textSnapshot").setlocal_1()
+ctor.getlocal_1().getlex("flash.text::TextSnapshot").istypelate()
+ctor.trace_top("is TextSnapshot:")
+ctor.getlocal_1().getproperty("charCount").trace_top("charCount:")
+
+ctor.trace("// getText")
+ctor.getlocal_1().push(0).push(10).callproperty("getText", 2).trace_top("(0, 10):")
+ctor.getlocal_1().push(0).push(10).push(True).callproperty("getText", 3)
+ctor.trace_top("(0, 10, true):")
+ctor.getlocal_1().push(3).push(7).callproperty("getText", 2).trace_top("(3, 7):")
+
+ctor.trace("// findText")
+for begin, pattern, case_sensitive in [
+    (0, "world", False),
+    (0, "world", True),
+    (0, "World", True),
+    (0, "o", False),
+    (5, "o", False),
+    (7, "o", False),
+    (0, "", False),
+]:
+    ctor.getlocal_1().push(begin).push(pattern).push(case_sensitive)
+    ctor.callproperty("findText", 3)
+    ctor.trace_top("(%d, %r, %s):" % (begin, pattern, "true" if case_sensitive else "false"))
+
+ctor.trace("// setSelected(3, 7, true)")
+ctor.getlocal_1().push(3).push(7).push(True).callpropvoid("setSelected", 3)
+ctor.getlocal_1().push(0).push(3).callproperty("getSelected", 2).trace_top("getSelected(0, 3):")
+ctor.getlocal_1().push(0).push(4).callproperty("getSelected", 2).trace_top("getSelected(0, 4):")
+ctor.getlocal_1().push(False).callproperty("getSelectedText", 1).trace_top("getSelectedText():")
+ctor.getlocal_1().push(True).callproperty("getSelectedText", 1)
+ctor.trace_top("getSelectedText(true):")
+
+ctor.trace("// setSelected(4, 6, false)")
+ctor.getlocal_1().push(4).push(6).push(False).callpropvoid("setSelected", 3)
+ctor.getlocal_1().push(False).callproperty("getSelectedText", 1).trace_top("getSelectedText():")
+ctor.getlocal_1().push(True).callproperty("getSelectedText", 1)
+ctor.trace_top("getSelectedText(true):")
+
+ctor.getlocal_1().pushint(0xFF0000).callpropvoid("setSelectColor", 1)
+ctor.getlocal_1().push(False).callproperty("getSelectedText", 1)
+ctor.trace_top("after setSelectColor:")
+
+abc.document_script(doc)
+tags = file_attributes()
+tags += set_background_color(0xFFFFFF)
+tags += define_font(1)
+tags += define_text(2, 1, "Hello")
+tags += define_text(3, 1, "World")
+tags += do_abc(abc.serialize())
+tags += symbol_class([(0, doc.name)])
+tags += place_object(1, 2, x=50, y=50)
+tags += place_object(2, 3, x=50, y=100)
+tags += SHOW_FRAME + END
+with open(os.path.join(os.path.dirname(__file__), "test.swf"), "wb") as f:
+    f.write(swf(tags))
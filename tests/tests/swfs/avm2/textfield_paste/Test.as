@@ -0,0 +1,69 @@
+package {
+	import flash.desktop.Clipboard;
+	import flash.desktop.ClipboardFormats;
+	import flash.display.MovieClip;
+	import flash.display.Sprite;
+	import flash.events.Event;
+	import flash.events.TextEvent;
+	import flash.text.TextField;
+	import flash.text.TextFieldType;
+
+	// `input.txt` pastes into a single-line field, a multiline field, and
+	// a sprite, moving the focus between them one frame at a time.
+	public class Test extends MovieClip {
+		var single:TextField;
+		var multi:TextField;
+		var box:Sprite;
+		var frame:int = 0;
+
+		public function Test() {
+			single = new TextField();
+			single.type = TextFieldType.INPUT;
+			single.restrict = "a-z";
+			single.maxChars = 5;
+			single.text = "xy";
+			addChild(single);
+			single.addEventListener(TextEvent.TEXT_INPUT, onTextInput);
+
+			multi = new TextField();
+			multi.type = TextFieldType.INPUT;
+			multi.multiline = true;
+			addChild(multi);
+			multi.addEventListener(TextEvent.TEXT_INPUT, onTextInput);
+
+			box = new Sprite();
+			addChild(box);
+			box.addEventListener(Event.PASTE, onPaste);
+
+			stage.focus = single;
+			single.setSelection(1, 1);
+			addEventListener(Event.ENTER_FRAME, onEnterFrame);
+		}
+
+		function linesJoined(text:String):String {
+			return text.split("\r").join("|").split("\n").join("|");
+		}
+
+		function onTextInput(event:TextEvent) {
+			trace("textInput:", linesJoined(event.text));
+			if (event.text === "blocked") {
+				event.preventDefault();
+			}
+		}
+
+		function onPaste(event:Event) {
+			trace("paste:", Clipboard.generalClipboard.getData(ClipboardFormats.TEXT_FORMAT));
+		}
+
+		function onEnterFrame(event:Event) {
+			frame++;
+			if (frame == 1) {
+				trace("single:", single.text);
+				stage.focus = multi;
+			} else if (frame == 2) {
+				trace("multi:", linesJoined(multi.text));
+				stage.focus = box;
+			}
+		}
+	}
+}
@@ -0,0 +1,29 @@
+package {
+	import flash.display.MovieClip;
+	import flash.text.TextField;
+
+	// A new text field has an empty selection at index 0. `setSelection`
+	// selects a range, clamped to the length of the text, and the selection
+	// properties report it.
+	public class Test extends MovieClip {
+		public function Test() {
+			var field = new TextField();
+			field.text = "Hello world";
+			traceSelection("// new field", field);
+
+			field.setSelection(0, 5);
+			traceSelection("// setSelection(0, 5)", field);
+
+			field.setSelection(6, 100);
+			traceSelection("// setSelection(6, 100)", field);
+		}
+
+		function traceSelection(label:String, field:TextField) {
+			trace(label);
+			trace("selectionBeginIndex:", field.selectionBeginIndex);
+			trace("selectionEndIndex:", field.selectionEndIndex);
+			trace("caretIndex:", field.caretIndex);
+			trace("selectedText:", field.selectedText);
+		}
+	}
+}
@@ -0,0 +1,48 @@
+package {
+	import flash.display.MovieClip;
+	import flash.utils.clearInterval;
+	import flash.utils.clearTimeout;
+	import flash.utils.setInterval;
+	import flash.utils.setTimeout;
+
+	// At 24 frames per second, the test harness advances the timers by
+	// about 42ms after each frame, so the callbacks fire over the first
+	// three frames.
+	public class Test extends MovieClip {
+		var count;
+		var intervalId;
+
+		public function Test() {
+			trace("constructor");
+			setTimeout(onTimeout, 50, "a", 1);
+			count = 0;
+			intervalId = setInterval(onInterval, 30);
+			var id = setTimeout(onCleared, 10);
+			trace("typeof id:", typeof id);
+			clearTimeout(id);
+			trace("constructor end");
+		}
+
+		function onTimeout(a, b) {
+			trace("onTimeout", a, b);
+			setTimeout(onNested, 10);
+		}
+
+		function onNested() {
+			trace("onNested");
+		}
+
+		function onInterval() {
+			count++;
+			trace("onInterval", count);
+			if (count == 3) {
+				clearInterval(intervalId);
+				trace("cleared interval");
+			}
+		}
+
+		function onCleared() {
+			trace("onCleared");
+		}
+	}
+}
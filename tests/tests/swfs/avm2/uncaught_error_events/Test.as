@@ -0,0 +1,56 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.Event;
+	import flash.events.EventDispatcher;
+	import flash.events.UncaughtErrorEvent;
+	import flash.utils.setTimeout;
+
+	// Listens for `uncaughtError` on `loaderInfo.uncaughtErrorEvents`. The
+	// first `enterFrame` listener call schedules a timeout and then throws,
+	// and the timeout throws too. Both errors reach the listener as an
+	// `UncaughtErrorEvent`, which it cancels.
+	public class Test extends MovieClip {
+		var thrown;
+
+		public function Test() {
+			trace("// uncaughtErrorEvents");
+			var events = loaderInfo.uncaughtErrorEvents;
+			trace("same object each time:", events === loaderInfo.uncaughtErrorEvents);
+			trace("is EventDispatcher:", events is EventDispatcher);
+			events.addEventListener("uncaughtError", onUncaughtError);
+			addEventListener("enterFrame", onEnterFrame);
+		}
+
+		function onUncaughtError(event:UncaughtErrorEvent) {
+			trace("event:", event.type);
+			trace("is UncaughtErrorEvent:", event is UncaughtErrorEvent);
+			trace("bubbles:", event.bubbles);
+			trace("cancelable:", event.cancelable);
+			trace("error == null:", event.error == null);
+			event.preventDefault();
+			trace("isDefaultPrevented:", event.isDefaultPrevented());
+		}
+
+		function onEnterFrame(event:Event) {
+			if (thrown) {
+				return;
+			}
+			thrown = true;
+			trace("// enterFrame listener throws");
+			setTimeout(onTimeout, 0);
+			throwError();
+			trace("not reached");
+		}
+
+		function onTimeout() {
+			trace("// timeout throws");
+			throwError();
+			trace("not reached");
+		}
+
+		function throwError() {
+			var object:* = null;
+			object.missing;
+		}
+	}
+}
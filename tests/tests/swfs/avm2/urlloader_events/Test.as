@@ -0,0 +1,73 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.Event;
+	import flash.events.HTTPStatusEvent;
+	import flash.events.IOErrorEvent;
+	import flash.events.ProgressEvent;
+	import flash.net.URLLoader;
+	import flash.net.URLRequest;
+	import flash.net.URLVariables;
+	import flash.utils.ByteArray;
+
+	// Loads a file as text, as binary and as variables, and a missing file,
+	// tracing the events each `URLLoader` dispatches and the data it ends up
+	// with.
+	public class Test extends MovieClip {
+		public function Test() {
+			load("data.txt", "text", onTextComplete);
+			load("data.txt", "binary", onBinaryComplete);
+			load("vars.txt", "variables", onVariablesComplete);
+			load("missing.txt", "text", null);
+		}
+
+		function load(url:String, dataFormat:String, onComplete:Function) {
+			var loader = new URLLoader();
+			loader.dataFormat = dataFormat;
+			loader.addEventListener("open", onOpen);
+			loader.addEventListener("progress", onProgress);
+			loader.addEventListener("httpStatus", onHttpStatus);
+			loader.addEventListener("ioError", onIoError);
+			if (onComplete != null) {
+				loader.addEventListener("complete", onComplete);
+			}
+			loader.load(new URLRequest(url));
+		}
+
+		function onOpen(event:Event) {
+			trace("open", event.target.dataFormat);
+		}
+
+		function onProgress(event:ProgressEvent) {
+			trace("progress", event.bytesLoaded, event.bytesTotal);
+			trace("loader bytes", event.target.bytesLoaded, event.target.bytesTotal);
+		}
+
+		function onHttpStatus(event:HTTPStatusEvent) {
+			trace("httpStatus", event.status);
+		}
+
+		function onIoError(event:IOErrorEvent) {
+			trace("ioError", event.errorID, event.text);
+		}
+
+		function onTextComplete(event:Event) {
+			trace("complete");
+			trace("is String:", event.target.data is String);
+			trace("data:", event.target.data);
+		}
+
+		function onBinaryComplete(event:Event) {
+			trace("complete");
+			trace("is ByteArray:", event.target.data is ByteArray);
+			trace("length:", event.target.data.length);
+			trace("readUTFBytes(5):", event.target.data.readUTFBytes(5));
+		}
+
+		function onVariablesComplete(event:Event) {
+			trace("complete");
+			trace("is URLVariables:", event.target.data is URLVariables);
+			trace("a:", event.target.data.a);
+			trace("b:", event.target.data.b);
+		}
+	}
+}
@@ -0,0 +1,69 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.Event;
+	import flash.events.HTTPStatusEvent;
+	import flash.events.IOErrorEvent;
+	import flash.events.ProgressEvent;
+	import flash.net.URLRequest;
+	import flash.net.URLStream;
+
+	// Streams `data.bin`, "Hello" followed by 64 KiB of "x", reading from the
+	// stream as each chunk arrives. Loads are reported in 64 KiB chunks, so
+	// it arrives in two. A second stream is closed by its first `progress`
+	// event, and a third loads a missing file.
+	public class Test extends MovieClip {
+		public function Test() {
+			trace("connected before load:", new URLStream().connected);
+
+			var stream = new URLStream();
+			stream.addEventListener("open", onOpen);
+			stream.addEventListener("progress", onProgress);
+			stream.addEventListener("httpStatus", onHttpStatus);
+			stream.addEventListener("complete", onComplete);
+			stream.load(new URLRequest("data.bin"));
+			trace("connected after load:", stream.connected);
+
+			stream = new URLStream();
+			stream.addEventListener("progress", onProgressClose);
+			stream.addEventListener("complete", onComplete);
+			stream.load(new URLRequest("data.bin"));
+
+			stream = new URLStream();
+			stream.addEventListener("httpStatus", onHttpStatus);
+			stream.addEventListener("ioError", onIoError);
+			stream.load(new URLRequest("missing.bin"));
+		}
+
+		function onOpen(event:Event) {
+			trace("open");
+			trace("bytesAvailable:", event.target.bytesAvailable);
+		}
+
+		function onProgress(event:ProgressEvent) {
+			trace("progress", event.bytesLoaded, event.bytesTotal);
+			trace("bytesAvailable:", event.target.bytesAvailable);
+			trace("readUTFBytes(5):", event.target.readUTFBytes(5));
+			trace("bytesAvailable after reading:", event.target.bytesAvailable);
+		}
+
+		function onProgressClose(event:ProgressEvent) {
+			trace("progress before close", event.bytesLoaded);
+			event.target.close();
+			trace("connected after close:", event.target.connected);
+		}
+
+		function onHttpStatus(event:HTTPStatusEvent) {
+			trace("httpStatus", event.status);
+			trace("connected:", event.target.connected);
+		}
+
+		function onComplete(event:Event) {
+			trace("complete");
+			trace("bytesAvailable:", event.target.bytesAvailable);
+		}
+
+		function onIoError(event:IOErrorEvent) {
+			trace("ioError", event.text);
+		}
+	}
+}
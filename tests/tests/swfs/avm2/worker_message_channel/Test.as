@@ -0,0 +1,70 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.Event;
+	import flash.system.Worker;
+	import flash.system.WorkerDomain;
+
+	// The movie starts itself as a background worker, from
+	// `loaderInfo.bytes`, and the constructor checks
+	// `Worker.current.isPrimordial` to decide which side it is running on.
+	//
+	// The primordial worker shares two channels and a plain object with the
+	// background worker, and sends it a message before it has started. The
+	// background worker echoes every message back, and the primordial worker
+	// terminates it once the echo arrives.
+	public class Test extends MovieClip {
+		var worker;
+		var toWorker;
+		var fromWorker;
+
+		public function Test() {
+			if (Worker.current.isPrimordial) {
+				trace("Worker.isSupported:", Worker.isSupported);
+				worker = WorkerDomain.current.createWorker(loaderInfo.bytes);
+				trace("new worker state:", worker.state);
+
+				toWorker = Worker.current.createMessageChannel(worker);
+				fromWorker = worker.createMessageChannel(Worker.current);
+
+				worker.setSharedProperty("toWorker", toWorker);
+				worker.setSharedProperty("fromWorker", fromWorker);
+				worker.setSharedProperty("config", {name: "shared object"});
+
+				fromWorker.addEventListener("channelMessage", onPrimordialMessage);
+				worker.addEventListener("workerState", onWorkerState);
+
+				worker.start();
+				toWorker.send("hello");
+				trace("primordial constructor end");
+				return;
+			}
+
+			trace("background constructor");
+			trace("Worker.current.isPrimordial:", Worker.current.isPrimordial);
+			trace("Worker.current.state:", Worker.current.state);
+			toWorker = Worker.current.getSharedProperty("toWorker");
+			fromWorker = Worker.current.getSharedProperty("fromWorker");
+			trace("config.name:", Worker.current.getSharedProperty("config").name);
+			toWorker.addEventListener("channelMessage", onBackgroundMessage);
+			fromWorker.send("started");
+		}
+
+		function onBackgroundMessage(event:Event) {
+			var message = toWorker.receive();
+			trace("background received:", message);
+			fromWorker.send(message + " back");
+		}
+
+		function onPrimordialMessage(event:Event) {
+			var message = fromWorker.receive();
+			trace("primordial received:", message);
+			if (message === "hello back") {
+				trace("terminate:", worker.terminate());
+			}
+		}
+
+		function onWorkerState(event:Event) {
+			trace("workerState:", worker.state);
+		}
+	}
+}
@@ -0,0 +1,60 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.Event;
+	import flash.system.Worker;
+	import flash.system.WorkerDomain;
+	import flash.utils.setTimeout;
+
+	// The movie starts itself as a background worker, like
+	// worker_message_channel. Both workers call `setTimeout`, and each
+	// callback checks which worker it is running in. The background worker
+	// then sets a second timeout and tells the primordial worker, which
+	// terminates it. That second timeout must never fire, even though a
+	// later primordial timeout does.
+	public class Test extends MovieClip {
+		var worker;
+		var fromWorker;
+
+		public function Test() {
+			if (Worker.current.isPrimordial) {
+				worker = WorkerDomain.current.createWorker(loaderInfo.bytes);
+				fromWorker = worker.createMessageChannel(Worker.current);
+				worker.setSharedProperty("fromWorker", fromWorker);
+				fromWorker.addEventListener("channelMessage", onPrimordialMessage);
+				worker.start();
+				setTimeout(onPrimordialTimeout, 10);
+				trace("primordial constructor end");
+				return;
+			}
+
+			trace("background constructor");
+			fromWorker = Worker.current.getSharedProperty("fromWorker");
+			setTimeout(onBackgroundTimeout, 20, "from background");
+		}
+
+		function onPrimordialTimeout() {
+			trace("primordial timeout, isPrimordial:", Worker.current.isPrimordial);
+		}
+
+		function onBackgroundTimeout(message:String) {
+			trace("background timeout:", message);
+			trace("background timeout, isPrimordial:", Worker.current.isPrimordial);
+			setTimeout(onTerminatedTimeout, 100);
+			fromWorker.send("timeout");
+		}
+
+		function onTerminatedTimeout() {
+			trace("not reached");
+		}
+
+		function onPrimordialMessage(event:Event) {
+			trace("primordial received:", fromWorker.receive());
+			trace("terminate:", worker.terminate());
+			setTimeout(onLateTimeout, 150);
+		}
+
+		function onLateTimeout() {
+			trace("primordial late timeout");
+		}
+	}
+}
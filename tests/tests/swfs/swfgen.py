@@ -0,0 +1,947 @@
+"""A small SWF and ABC assembler for generating test movies.
+
+Tests are normally written in ActionScript, as a `Test.as` next to
+`test.swf`. This module is only for the few tests that can't be: ones that
+need bytecode a compiler won't emit, or timeline content that scripts can't
+create. Those folders contain a `generate.py` script that builds `test.swf`
+with this module, and says why it can't be compiled from source.
+
+AVM2 movies are written as ABC assembly, usually as a document class whose
+constructor runs the test:
+
+    abc = Abc()
+    doc = abc.document_class("Test")
+    doc.constructor.trace("Hello, world!")
+    write_avm2_movie("test.swf", abc, doc)
+
+AVM1 movies are written frame by frame with `write_movie`, using `Actions`
+to assemble `DoAction` tags.
+
+In ABC, names are given as strings. `"trace"` is a public name, and
+`"flash.utils::getTimer"` is a name in the `flash.utils` package.
+"""
+
+import struct
+
+
+# SWF structure
+
+
+class BitWriter:
+    def __init__(self):
+        self.bits = []
+
+    def ub(self, value, n):
+        for i in reversed(range(n)):
+            self.bits.append((value >> i) & 1)
+
+    def sb(self, value, n):
+        self.ub(value & ((1 << n) - 1), n)
+
+    def to_bytes(self):
+        bits = self.bits + [0] * (-len(self.bits) % 8)
+        return bytes(
+            int("".join(map(str, bits[i : i + 8])), 2) for i in range(0, len(bits), 8)
+        )
+
+
+def signed_bits(*values):
+    return max(max(abs(v) for v in values).bit_length() + 1, 2)
+
+
+def rect(x_min, x_max, y_min, y_max):
+    writer = BitWriter()
+    n = signed_bits(x_min, x_max, y_min, y_max)
+    writer.ub(n, 5)
+    for v in (x_min, x_max, y_min, y_max):
+        writer.sb(v, n)
+    return writer.to_bytes()
+
+
+def tag(code, body):
+    if len(body) < 0x3F:
+        return struct.pack("<H", (code << 6) | len(body)) + body
+    return struct.pack("<HI", (code << 6) | 0x3F, len(body)) + body
+
+
+def swf(tags, version=10, width=550, height=400, frame_rate=24, num_frames=1):
+    header = rect(0, width * 20, 0, height * 20)
+    header += struct.pack("<HH", int(frame_rate * 256), num_frames)
+    body = header + tags
+    return b"FWS" + bytes([version]) + struct.pack("<I", 8 + len(body)) + body
+
+
+def file_attributes(as3=True):
+    return tag(69, struct.pack("<I", 0x08 if as3 else 0))
+
+
+def set_background_color(rgb):
+    return tag(9, bytes([(rgb >> 16) & 0xFF, (rgb >> 8) & 0xFF, rgb & 0xFF]))
+
+
+def do_abc(abc_bytes, name="", lazy=True):
+    return tag(82, struct.pack("<I", 1 if lazy else 0) + name.encode() + b"\0" + abc_bytes)
+
+
+def symbol_class(symbols):
+    body = struct.pack("<H", len(symbols))
+    for character_id, name in symbols:
+        body += struct.pack("<H", character_id) + name.encode() + b"\0"
+    return tag(76, body)
+
+
+SHOW_FRAME = tag(1, b"")
+END = tag(0, b"")
+
+
+def define_edit_text(
+    character_id,
+    width,
+    height,
+    text=None,
+    variable="",
+    read_only=False,
+    selectable=True,
+    border=True,
+):
+    """A text field using the default device font, `width` by `height` pixels."""
+    body = struct.pack("<H", character_id) + rect(0, width * 20, 0, height * 20)
+    flags = 0
+    if text is not None:
+        flags |= 0x8000
+    if read_only:
+        flags |= 0x0800
+    if not selectable:
+        flags |= 0x0010
+    if border:
+        flags |= 0x0008
+    body += struct.pack(">H", flags)
+    body += variable.encode() + b"\0"
+    if text is not None:
+        body += text.encode() + b"\0"
+    return tag(37, body)
+
+
+def place_object(depth, character_id, name=None, x=0, y=0):
+    """Place a character at a position given in pixels."""
+    flags = 0x02 | 0x04
+    if name is not None:
+        flags |= 0x20
+    body = bytes([flags]) + struct.pack("<HH", depth, character_id)
+    matrix = BitWriter()
+    matrix.ub(0, 1)  # HasScale
+    matrix.ub(0, 1)  # HasRotate
+    n = signed_bits(x * 20, y * 20)
+    matrix.ub(n, 5)
+    matrix.sb(x * 20, n)
+    matrix.sb(y * 20, n)
+    body += matrix.to_bytes()
+    if name is not None:
+        body += name.encode() + b"\0"
+    return tag(26, body)
+
+
+def do_action(actions):
+    return tag(12, actions.assemble())
+
+
+def write_movie(path, frames, version=10, as3=False, **kwargs):
+    """Write a movie with the given tags in each frame."""
+    tags = file_attributes(as3) if version >= 8 else b""
+    tags += set_background_color(0xFFFFFF)
+    for frame in frames:
+        tags += frame + SHOW_FRAME
+    tags += END
+    with open(path, "wb") as f:
+        f.write(swf(tags, version=version, num_frames=len(frames), **kwargs))
+
+
+def write_avm2_movie(path, abc, doc, version=10, **kwargs):
+    """Write a one-frame movie whose document class is `doc`."""
+    abc.document_script(doc)
+    tags = file_attributes()
+    tags += set_background_color(0xFFFFFF)
+    tags += do_abc(abc.serialize())
+    tags += symbol_class([(0, doc.name)])
+    tags += SHOW_FRAME + END
+    with open(path, "wb") as f:
+        f.write(swf(tags, version=version, **kwargs))
+
+
+# AVM1 actions
+
+
+class Actions:
+    """An assembler for AVM1 action bytecode.
+
+    Each action without operands is a method named after it, and `push`
+    pushes constants of any type. Strings are written as UTF-8, so movies
+    using them should be SWF6 or later.
+    """
+
+    OPS = {
+        "stop": 0x07,
+        "pop": 0x17,
+        "get_variable": 0x1C,
+        "set_variable": 0x1D,
+        "trace": 0x26,
+        "call_function": 0x3D,
+        "init_array": 0x42,
+        "init_object": 0x43,
+        "add2": 0x47,
+        "equals2": 0x49,
+        "get_member": 0x4E,
+        "set_member": 0x4F,
+        "call_method": 0x52,
+        "new_method": 0x53,
+    }
+
+    def __init__(self):
+        self.code = bytearray()
+
+    def __getattr__(self, op):
+        if op not in self.OPS:
+            raise AttributeError(op)
+
+        def emit():
+            self.code.append(self.OPS[op])
+            return self
+
+        return emit
+
+    def push(self, *values):
+        body = b""
+        for value in values:
+            if value is None:
+                body += bytes([2])
+            elif isinstance(value, bool):
+                body += bytes([5, int(value)])
+            elif isinstance(value, int):
+                body += bytes([7]) + struct.pack("<i", value)
+            elif isinstance(value, float):
+                body += bytes([6]) + struct.pack("<d", value)[4:] + struct.pack("<d", value)[:4]
+            else:
+                body += bytes([0]) + value.encode() + b"\0"
+        self.code += bytes([0x96]) + struct.pack("<H", len(body)) + body
+        return self
+
+    def function(self, params, body):
+        """Push an anonymous function taking the named parameters, with
+        another `Actions` as its body."""
+        code = bytes(body.code)
+        data = b"\0" + struct.pack("<H", len(params))
+        data += b"".join(param.encode() + b"\0" for param in params)
+        data += struct.pack("<H", len(code))
+        self.code += bytes([0x9B]) + struct.pack("<H", len(data)) + data + code
+        return self
+
+    def get(self, path):
+        """Push the value of a variable, following `.` to get members."""
+        name, *members = path.split(".")
+        self.push(name).get_variable()
+        for member in members:
+            self.push(member).get_member()
+        return self
+
+    def call(self, path, *args):
+        """Call a function or method with constant arguments, leaving its
+        result on the stack."""
+        self.push(*reversed(args), len(args))
+        if "." in path:
+            obj, method = path.rsplit(".", 1)
+            return self.get(obj).push(method).call_method()
+        return self.push(path).call_function()
+
+    def trace_value(self, path):
+        return self.get(path).trace()
+
+    def trace_call(self, path, *args):
+        return self.call(path, *args).trace()
+
+    def assemble(self):
+        return bytes(self.code) + b"\0"
+
+
+# ABC primitives
+
+
+def u30(value):
+    value &= 0xFFFFFFFF
+    out = bytearray()
+    while True:
+        byte = value & 0x7F
+        value >>= 7
+        if value:
+            out.append(byte | 0x80)
+        else:
+            out.append(byte)
+            return bytes(out)
+
+
+def s24(value):
+    return struct.pack("<i", value)[:3]
+
+
+NS_PRIVATE = 0x05
+NS_NAMESPACE = 0x08
+NS_PACKAGE = 0x16
+NS_PACKAGE_INTERNAL = 0x17
+NS_PROTECTED = 0x18
+
+MN_QNAME = 0x07
+MN_MULTINAME = 0x09
+MN_MULTINAME_L = 0x1B
+
+TRAIT_SLOT = 0
+TRAIT_METHOD = 1
+TRAIT_GETTER = 2
+TRAIT_SETTER = 3
+TRAIT_CLASS = 4
+TRAIT_CONST = 6
+
+CLASS_SEALED = 0x01
+CLASS_FINAL = 0x02
+CLASS_INTERFACE = 0x04
+CLASS_PROTECTED_NS = 0x08
+
+
+def split_name(name):
+    if "::" in name:
+        package, local = name.rsplit("::", 1)
+        return package, local
+    return "", name
+
+
+class Code:
+    """An assembler for one method body.
+
+    Each opcode is a method named after it. Operands that refer to the
+    constant pool are given as values or names and interned automatically.
+    Branch targets are label names, placed with `label`.
+    """
+
+    OPS = {
+        "nop": 0x02,
+        "throw": 0x03,
+        "getsuper": 0x04,
+        "setsuper": 0x05,
+        "kill": 0x08,
+        "label_op": 0x09,
+        "ifnlt": 0x0C,
+        "ifnle": 0x0D,
+        "ifngt": 0x0E,
+        "ifnge": 0x0F,
+        "jump": 0x10,
+        "iftrue": 0x11,
+        "iffalse": 0x12,
+        "ifeq": 0x13,
+        "ifne": 0x14,
+        "iflt": 0x15,
+        "ifle": 0x16,
+        "ifgt": 0x17,
+        "ifge": 0x18,
+        "ifstricteq": 0x19,
+        "ifstrictne": 0x1A,
+        "lookupswitch": 0x1B,
+        "pushwith": 0x1C,
+        "popscope": 0x1D,
+        "nextname": 0x1E,
+        "hasnext": 0x1F,
+        "pushnull": 0x20,
+        "pushundefined": 0x21,
+        "nextvalue": 0x23,
+        "pushbyte": 0x24,
+        "pushshort": 0x25,
+        "pushtrue": 0x26,
+        "pushfalse": 0x27,
+        "pushnan": 0x28,
+        "pop": 0x29,
+        "dup": 0x2A,
+        "swap": 0x2B,
+        "pushstring": 0x2C,
+        "pushint": 0x2D,
+        "pushuint": 0x2E,
+        "pushdouble": 0x2F,
+        "pushscope": 0x30,
+        "hasnext2": 0x32,
+        "newfunction": 0x40,
+        "call": 0x41,
+        "construct": 0x42,
+        "callsuper": 0x45,
+        "callproperty": 0x46,
+        "returnvoid": 0x47,
+        "returnvalue": 0x48,
+        "constructsuper": 0x49,
+        "constructprop": 0x4A,
+        "callproplex": 0x4C,
+        "callsupervoid": 0x4E,
+        "callpropvoid": 0x4F,
+        "applytype": 0x53,
+        "newobject": 0x55,
+        "newarray": 0x56,
+        "newactivation": 0x57,
+        "newclass": 0x58,
+        "findpropstrict": 0x5D,
+        "findproperty": 0x5E,
+        "getlex": 0x60,
+        "setproperty": 0x61,
+        "getlocal": 0x62,
+        "setlocal": 0x63,
+        "getglobalscope": 0x64,
+        "getscopeobject": 0x65,
+        "getproperty": 0x66,
+        "initproperty": 0x68,
+        "deleteproperty": 0x6A,
+        "getslot": 0x6C,
+        "setslot": 0x6D,
+        "convert_s": 0x70,
+        "convert_i": 0x73,
+        "convert_u": 0x74,
+        "convert_d": 0x75,
+        "convert_b": 0x76,
+        "coerce": 0x80,
+        "coerce_a": 0x82,
+        "coerce_s": 0x85,
+        "astype": 0x86,
+        "astypelate": 0x87,
+        "negate": 0x90,
+        "increment": 0x91,
+        "decrement": 0x93,
+        "typeof": 0x95,
+        "not": 0x96,
+        "add": 0xA0,
+        "subtract": 0xA1,
+        "multiply": 0xA2,
+        "divide": 0xA3,
+        "modulo": 0xA4,
+        "lshift": 0xA5,
+        "rshift": 0xA6,
+        "urshift": 0xA7,
+        "bitand": 0xA8,
+        "bitor": 0xA9,
+        "bitxor": 0xAA,
+        "equals": 0xAB,
+        "strictequals": 0xAC,
+        "lessthan": 0xAD,
+        "lessequals": 0xAE,
+        "greaterthan": 0xAF,
+        "greaterequals": 0xB0,
+        "instanceof": 0xB1,
+        "istype": 0xB2,
+        "istypelate": 0xB3,
+        "in_op": 0xB4,
+        "increment_i": 0xC0,
+        "decrement_i": 0xC1,
+        "inclocal_i": 0xC2,
+        "getlocal_0": 0xD0,
+        "getlocal_1": 0xD1,
+        "getlocal_2": 0xD2,
+        "getlocal_3": 0xD3,
+        "setlocal_0": 0xD4,
+        "setlocal_1": 0xD5,
+        "setlocal_2": 0xD6,
+        "setlocal_3": 0xD7,
+    }
+
+    BRANCHES = {
+        "ifnlt",
+        "ifnle",
+        "ifngt",
+        "ifnge",
+        "jump",
+        "iftrue",
+        "iffalse",
+        "ifeq",
+        "ifne",
+        "iflt",
+        "ifle",
+        "ifgt",
+        "ifge",
+        "ifstricteq",
+        "ifstrictne",
+    }
+
+    # Opcodes with a multiname operand, optionally followed by an argument count.
+    MULTINAME_OPS = {
+        "getsuper",
+        "setsuper",
+        "findpropstrict",
+        "findproperty",
+        "getlex",
+        "setproperty",
+        "getproperty",
+        "initproperty",
+        "deleteproperty",
+        "coerce",
+        "astype",
+        "istype",
+    }
+    MULTINAME_ARGC_OPS = {
+        "callsuper",
+        "callproperty",
+        "constructprop",
+        "callproplex",
+        "callsupervoid",
+        "callpropvoid",
+    }
+    # Opcodes with a single u30 operand.
+    U30_OPS = {
+        "kill",
+        "pushshort",
+        "newfunction",
+        "call",
+        "construct",
+        "constructsuper",
+        "applytype",
+        "newobject",
+        "newarray",
+        "newclass",
+        "getlocal",
+        "setlocal",
+        "getslot",
+        "setslot",
+        "inclocal_i",
+    }
+
+    def __init__(self, abc):
+        self.abc = abc
+        self.parts = []
+        self.labels = {}
+        self.local_count = 1
+        self.max_stack = 32
+        self.max_scope_depth = 16
+        self.need_activation = False
+        self.activation_traits = []
+
+    def __getattr__(self, op):
+        if op not in self.OPS:
+            raise AttributeError(op)
+
+        def emit(*operands):
+            self._emit(op, operands)
+            return self
+
+        return emit
+
+    def _emit(self, op, operands):
+        code = bytes([self.OPS[op]])
+        pool = self.abc
+        if op in self.BRANCHES:
+            self.parts.append(("branch", code, operands[0]))
+            return
+        if op == "lookupswitch":
+            default, cases = operands
+            self.parts.append(("switch", default, cases))
+            return
+        if op in self.MULTINAME_OPS:
+            code += u30(pool.multiname(operands[0]))
+        elif op in self.MULTINAME_ARGC_OPS:
+            code += u30(pool.multiname(operands[0])) + u30(operands[1])
+        elif op == "pushstring":
+            code += u30(pool.string(operands[0]))
+        elif op == "pushint":
+            code += u30(pool.int(operands[0]))
+        elif op == "pushuint":
+            code += u30(pool.uint(operands[0]))
+        elif op == "pushdouble":
+            code += u30(pool.double(operands[0]))
+        elif op in ("pushbyte", "getscopeobject"):
+            code += bytes([operands[0] & 0xFF])
+        elif op == "hasnext2":
+            code += u30(operands[0]) + u30(operands[1])
+        elif op in self.U30_OPS:
+            operand = operands[0]
+            if op == "newfunction":
+                operand = operand.index
+            elif op == "newclass":
+                operand = operand.index
+            code += u30(operand)
+            if op in ("getlocal", "setlocal", "kill"):
+                self.local_count = max(self.local_count, operand + 1)
+        elif op.startswith(("getlocal_", "setlocal_")):
+            self.local_count = max(self.local_count, int(op[-1]) + 1)
+        self.parts.append(("bytes", code))
+
+    def label(self, name):
+        self.parts.append(("label", name))
+        return self
+
+    def activation_var(self, name, type_name="*"):
+        """Declare a slot on the object that `newactivation` creates."""
+        self.need_activation = True
+        self.activation_traits.append(Trait(name, TRAIT_SLOT, (type_name, None)))
+        return self
+
+    def raw(self, data):
+        """Emit raw bytes, such as invalid opcodes."""
+        self.parts.append(("bytes", bytes(data)))
+        return self
+
+    def push(self, value):
+        """Push a constant of any type."""
+        if value is None:
+            return self.pushnull()
+        if value is True:
+            return self.pushtrue()
+        if value is False:
+            return self.pushfalse()
+        if isinstance(value, int):
+            if -128 <= value <= 127:
+                return self.pushbyte(value)
+            return self.pushint(value)
+        if isinstance(value, float):
+            return self.pushdouble(value)
+        return self.pushstring(value)
+
+    def trace(self, *values):
+        """Trace constants, concatenated with spaces like `trace` does."""
+        self.findpropstrict("trace")
+        for value in values:
+            self.push(value)
+        return self.callpropvoid("trace", len(values))
+
+    def trace_top(self, prefix=None):
+        """Trace the value on top of the stack, with an optional prefix."""
+        if prefix is None:
+            self.findpropstrict("trace").swap()
+            return self.callpropvoid("trace", 1)
+        self.setlocal(self.scratch_local())
+        self.findpropstrict("trace").pushstring(prefix).getlocal(self.scratch_local())
+        return self.callpropvoid("trace", 2)
+
+    def scratch_local(self):
+        """A local register reserved for helper sequences."""
+        return 15
+
+    def assemble(self):
+        # Instruction sizes don't depend on branch offsets, so one pass
+        # finds every label and a second fills in the offsets.
+        positions = {}
+        offset = 0
+        for part in self.parts:
+            if part[0] == "label":
+                positions[part[1]] = offset
+            elif part[0] == "bytes":
+                offset += len(part[1])
+            elif part[0] == "branch":
+                offset += 4
+            elif part[0] == "switch":
+                offset += 1 + 3 + len(u30(len(part[2]) - 1)) + 3 * len(part[2])
+
+        out = bytearray()
+        for part in self.parts:
+            if part[0] == "bytes":
+                out += part[1]
+            elif part[0] == "branch":
+                end = len(out) + 4
+                out += part[1] + s24(positions[part[2]] - end)
+            elif part[0] == "switch":
+                start = len(out)
+                _, default, cases = part
+                out += bytes([0x1B]) + s24(positions[default] - start)
+                out += u30(len(cases) - 1)
+                for case in cases:
+                    out += s24(positions[case] - start)
+        # Every body ends by returning, in case its last path falls through.
+        out += bytes([self.OPS["returnvoid"]])
+        return bytes(out)
+
+
+class Method:
+    def __init__(self, abc, index, param_types, return_type, name, flags, optional):
+        self.abc = abc
+        self.index = index
+        self.param_types = param_types
+        self.return_type = return_type
+        self.name = name
+        self.flags = flags
+        self.optional = optional
+        self.code = Code(abc)
+        self.code.local_count = len(param_types) + 1
+
+    def __getattr__(self, attr):
+        # Let methods be used as their own assemblers.
+        return getattr(self.__dict__["code"], attr)
+
+
+class Trait:
+    def __init__(self, name, kind, data, attrs=0):
+        self.name = name
+        self.kind = kind
+        self.data = data
+        self.attrs = attrs
+
+
+class Class:
+    def __init__(self, abc, index, name, super_name, flags):
+        self.abc = abc
+        self.index = index
+        self.name = name
+        self.super_name = super_name
+        self.flags = flags | CLASS_PROTECTED_NS
+        self.interfaces = []
+        self.instance_traits = []
+        self.class_traits = []
+        self.constructor = abc.method(name=name)
+        self.constructor.getlocal_0().pushscope()
+        self.constructor.getlocal_0().constructsuper(0)
+        self.static_init = abc.method(name=name + "$cinit")
+        self.static_init.getlocal_0().pushscope()
+        self.extra_scopes = []
+
+    def constructor_with_params(self, *param_types):
+        """Replace the constructor with one taking parameters."""
+        self.constructor = self.abc.method(*param_types, name=self.name)
+        self.constructor.getlocal_0().pushscope()
+        return self.constructor
+
+    def var(self, name, type_name="*", value=None):
+        self.instance_traits.append(Trait(name, TRAIT_SLOT, (type_name, value)))
+
+    def const(self, name, type_name="*", value=None):
+        self.instance_traits.append(Trait(name, TRAIT_CONST, (type_name, value)))
+
+    def method(self, name, *param_types, return_type="*", kind=TRAIT_METHOD, attrs=0):
+        method = self.abc.method(*param_types, return_type=return_type, name=name)
+        method.getlocal_0().pushscope()
+        self.instance_traits.append(Trait(name, kind, method, attrs))
+        return method
+
+    def getter(self, name, return_type="*"):
+        return self.method(name, return_type=return_type, kind=TRAIT_GETTER)
+
+    def setter(self, name, param_type="*"):
+        return self.method(name, param_type, return_type="void", kind=TRAIT_SETTER)
+
+    def static_method(self, name, *param_types, return_type="*"):
+        method = self.abc.method(*param_types, return_type=return_type, name=name)
+        method.getlocal_0().pushscope()
+        self.class_traits.append(Trait(name, TRAIT_METHOD, method))
+        return method
+
+
+class Abc:
+    def __init__(self):
+        self.ints = []
+        self.uints = []
+        self.doubles = []
+        self.strings = []
+        self.namespaces = []
+        self.ns_sets = []
+        self.multinames = []
+        self.methods = []
+        self.classes = []
+        self.scripts = []
+
+    # Constant pool
+
+    def _intern(self, pool, value):
+        if value not in pool:
+            pool.append(value)
+        return pool.index(value) + 1
+
+    def int(self, value):
+        return self._intern(self.ints, value)
+
+    def uint(self, value):
+        return self._intern(self.uints, value)
+
+    def double(self, value):
+        return self._intern(self.doubles, value)
+
+    def string(self, value):
+        return self._intern(self.strings, value)
+
+    def namespace(self, kind, name):
+        return self._intern(self.namespaces, (kind, self.string(name)))
+
+    def ns_set(self, namespaces):
+        return self._intern(self.ns_sets, tuple(namespaces))
+
+    def qname(self, name, ns_kind=NS_PACKAGE):
+        package, local = split_name(name)
+        ns = self.namespace(ns_kind, package)
+        return self._intern(self.multinames, (MN_QNAME, ns, self.string(local)))
+
+    def multiname(self, name):
+        """Intern a multiname given as a string, a pool index or `"*"`."""
+        if isinstance(name, int):
+            return name
+        if name == "*":
+            return 0
+        return self.qname(name)
+
+    def multiname_l(self, packages):
+        """A runtime multiname searching the given public packages."""
+        ns_set = self.ns_set(self.namespace(NS_PACKAGE, p) for p in packages)
+        return self._intern(self.multinames, (MN_MULTINAME_L, ns_set))
+
+    # Definitions
+
+    def method(self, *param_types, return_type="*", name="", flags=0, optional=None):
+        method = Method(
+            self, len(self.methods), param_types, return_type, name, flags, optional
+        )
+        self.methods.append(method)
+        return method
+
+    def function(self, *param_types, return_type="*", name=""):
+        """A method body for use with `newfunction`."""
+        return self.method(*param_types, return_type=return_type, name=name)
+
+    def define_class(self, name, super_name="Object", flags=CLASS_SEALED):
+        cls = Class(self, len(self.classes), name, super_name, flags)
+        self.classes.append(cls)
+        return cls
+
+    def document_class(self, name="Test", super_name="flash.display::MovieClip"):
+        return self.define_class(name, super_name)
+
+    def script(self, classes):
+        """Add a script that defines the given classes, in order, as globals.
+
+        Returns the script's initializer, so that more code can be run after
+        the classes are defined.
+        """
+        script = self.method(name="script$init")
+        script.getlocal_0().pushscope()
+        traits = []
+        for cls in classes:
+            script.getscopeobject(0)
+            if cls.super_name is None:
+                script.pushnull()
+            else:
+                script.getlex(cls.super_name)
+            script.newclass(cls)
+            script.initproperty(cls.name)
+            traits.append(Trait(cls.name, TRAIT_CLASS, cls))
+        self.scripts.append((script, traits))
+        return script
+
+    def document_script(self, doc):
+        """Add the entry script, which defines every class that no other
+        script does."""
+        defined = [t.data for _, traits in self.scripts for t in traits]
+        self.script([cls for cls in self.classes if cls not in defined])
+
+    # Serialization
+
+    def _trait(self, trait, slot_id):
+        out = u30(self.multiname(trait.name)) + bytes([trait.kind | (trait.attrs << 4)])
+        if trait.kind in (TRAIT_SLOT, TRAIT_CONST):
+            type_name, value = trait.data
+            out += u30(slot_id) + u30(self.multiname(type_name))
+            out += self._default_value(value)
+        elif trait.kind == TRAIT_CLASS:
+            out += u30(slot_id) + u30(trait.data.index)
+        else:
+            out += u30(0) + u30(trait.data.index)
+        return out
+
+    def _default_value(self, value):
+        if value is None:
+            return u30(0)
+        if value is True:
+            return u30(0x0B) + bytes([0x0B])
+        if value is False:
+            return u30(0x0A) + bytes([0x0A])
+        if isinstance(value, int):
+            return u30(self.int(value)) + bytes([0x03])
+        if isinstance(value, float):
+            return u30(self.double(value)) + bytes([0x06])
+        return u30(self.string(value)) + bytes([0x01])
+
+    def _traits(self, traits):
+        out = u30(len(traits))
+        slot_id = 0
+        for trait in traits:
+            if trait.kind in (TRAIT_SLOT, TRAIT_CONST, TRAIT_CLASS):
+                slot_id += 1
+                out += self._trait(trait, slot_id)
+            else:
+                out += self._trait(trait, 0)
+        return out
+
+    def serialize(self):
+        # Assemble bodies and traits first, so that everything they reference
+        # is interned into the constant pool.
+        bodies = [(m, m.code.assemble()) for m in self.methods]
+        instances = b""
+        statics = b""
+        for cls in self.classes:
+            instance = u30(self.qname(cls.name))
+            instance += u30(self.multiname(cls.super_name or "*"))
+            instance += bytes([cls.flags])
+            instance += u30(self.namespace(NS_PROTECTED, cls.name))
+            instance += u30(len(cls.interfaces))
+            for interface in cls.interfaces:
+                instance += u30(self.multiname(interface))
+            instance += u30(cls.constructor.index)
+            instance += self._traits(cls.instance_traits)
+            instances += instance
+            statics += u30(cls.static_init.index) + self._traits(cls.class_traits)
+        scripts = b""
+        for script, traits in self.scripts:
+            scripts += u30(script.index) + self._traits(traits)
+        method_infos = b""
+        for method in self.methods:
+            info = u30(len(method.param_types)) + u30(self.multiname(method.return_type))
+            for param_type in method.param_types:
+                info += u30(self.multiname(param_type))
+            info += u30(self.string(method.name))
+            flags = method.flags
+            if method.optional:
+                flags |= 0x08
+            if method.code.need_activation:
+                flags |= 0x02
+            info += bytes([flags])
+            if method.optional:
+                info += u30(len(method.optional))
+                for value in method.optional:
+                    if value is None:
+                        info += u30(0x0C) + bytes([0x0C])
+                    else:
+                        info += self._default_value(value)
+            method_infos += info
+        method_bodies = b""
+        for method, code in bodies:
+            body = u30(method.index)
+            body += u30(method.code.max_stack)
+            body += u30(method.code.local_count)
+            body += u30(0) + u30(method.code.max_scope_depth)
+            body += u30(len(code)) + code
+            body += u30(0)  # Exceptions
+            body += self._traits(method.code.activation_traits)
+            method_bodies += body
+
+        out = struct.pack("<HH", 16, 46)
+        out += self._pool(self.ints, lambda v: u30(v))
+        out += self._pool(self.uints, lambda v: u30(v))
+        out += self._pool(self.doubles, lambda v: struct.pack("<d", v))
+        out += self._pool(self.strings, lambda v: u30(len(v.encode())) + v.encode())
+        out += self._pool(self.namespaces, lambda v: bytes([v[0]]) + u30(v[1]))
+        out += self._pool(
+            self.ns_sets, lambda v: u30(len(v)) + b"".join(u30(ns) for ns in v)
+        )
+        out += self._pool(self.multinames, self._multiname_bytes)
+        out += u30(len(self.methods)) + method_infos
+        out += u30(0)  # Metadata
+        out += u30(len(self.classes)) + instances + statics
+        out += u30(len(self.scripts)) + scripts
+        out += u30(len(self.methods)) + method_bodies
+        return out
+
+    @staticmethod
+    def _pool(pool, write):
+        if not pool:
+            return u30(0)
+        return u30(len(pool) + 1) + b"".join(write(v) for v in pool)
+
+    @staticmethod
+    def _multiname_bytes(multiname):
+        kind = multiname[0]
+        if kind == MN_QNAME:
+            return bytes([kind]) + u30(multiname[1]) + u30(multiname[2])
+        if kind == MN_MULTINAME_L:
+            return bytes([kind]) + u30(multiname[1])
+        raise ValueError(multiname)
@@ -0,0 +1,209 @@
+use crate::keyboard::{KeyCode, KeyLocation};
+use downcast_rs::{impl_downcast, Downcast};
+
+pub trait UiBackend: Downcast {
+    fn is_key_down(&self, key: KeyCode) -> bool;
+
+    fn last_key_code(&self) -> KeyCode;
+
+    fn last_key_char(&self) -> Option<char>;
+
+    /// Where the last key pressed or released is on the keyboard.
+    fn last_key_location(&self) -> KeyLocation;
+
+    fn mouse_visible(&self) -> bool;
+
+    fn set_mouse_visible(&mut self, visible: bool);
+
+    /// Changes the mouse cursor image.
+    fn set_mouse_cursor(&mut self, cursor: MouseCursor);
+
+    /// Changes the mouse cursor to a bitmap registered by the movie, or back
+    /// to the cursor last set with `set_mouse_cursor` when `None`.
+    ///
+    /// Returns `false` if the bitmap can't be shown natively. The native
+    /// cursor must then stay hidden until the custom cursor is cleared, and
+    /// the player draws the bitmap over the stage instead.
+    fn set_custom_mouse_cursor(&mut self, cursor: Option<&CustomMouseCursor>) -> bool;
+
+    /// Set the clipboard to the given content
+    fn set_clipboard_content(&mut self, content: String);
+
+    /// Get the text on the clipboard, if the platform lets it be read.
+    fn clipboard_content(&mut self) -> Option<String>;
+
+    fn is_fullscreen(&self) -> bool;
+
+    /// Asks the platform to enter or leave fullscreen.
+    ///
+    /// This may not happen straight away, or at all. The player is told
+    /// about it through a change in viewport size.
+    fn set_fullscreen(&mut self, is_full: bool);
+
+    /// Tells the platform's input method where text is being entered, in
+    /// window pixels, so that it can show its candidate window there.
+    /// `None` means that no text field is being edited.
+    ///
+    /// Text composed with the input method is sent to the player with the
+    /// `ImeStart`, `ImeUpdate` and `ImeCommit` events.
+    fn set_ime_position(&mut self, position: Option<(f64, f64)>);
+
+    /// Displays a warning about unsupported content in Ruffle.
+    /// The user can still click an "OK" or "run anyway" message to dismiss the warning.
+    fn display_unsupported_message(&self);
+
+    /// Displays a message about an error during root movie download.
+    /// In particular, on web this can be a CORS error, which we can sidestep
+    /// by providing a direct .swf link instead.
+    fn display_root_movie_download_failed_message(&self);
+
+    // Unused, but kept in case we need it later
+    fn message(&self, message: &str);
+}
+impl_downcast!(UiBackend);
+
+/// A mouse cursor icon displayed by the Flash Player.
+/// Communicated from the core to the UI backend via `UiBackend::set_mouse_cursor`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MouseCursor {
+    /// The default arrow icon.
+    /// Equivalent to AS3 `MouseCursor.ARROW`.
+    Arrow,
+
+    /// The hand icon incdicating a button or link.
+    /// Equivalent to AS3 `MouseCursor.BUTTON`.
+    Hand,
+
+    /// The text I-beam.
+    /// Equivalent to AS3 `MouseCursor.IBEAM`.
+    IBeam,
+
+    /// The grabby-dragging hand icon.
+    /// Equivalent to AS3 `MouseCursor.HAND`.
+    Grab,
+}
+
+/// A bitmap mouse cursor, registered by a movie with `Mouse.registerCursor`.
+#[derive(Clone, Debug)]
+pub struct CustomMouseCursor {
+    /// The width of the image, in pixels.
+    pub width: u32,
+
+    /// The height of the image, in pixels.
+    pub height: u32,
+
+    /// The pixels of the image, as premultiplied RGBA.
+    pub rgba: Vec<u8>,
+
+    /// The point of the image that the mouse position is at.
+    pub hot_spot: (u32, u32),
+}
+
+impl CustomMouseCursor {
+    /// The pixels of the image, as RGBA without premultiplied alpha.
+    pub fn unmultiplied_rgba(&self) -> Vec<u8> {
+        self.rgba
+            .chunks_exact(4)
+            .flat_map(|pixel| {
+                let alpha = pixel[3];
+                let unmultiply = |c: u8| {
+                    if alpha == 0 {
+                        0
+                    } else {
+                        (u16::from(c) * 255 / u16::from(alpha)).min(255) as u8
+                    }
+                };
+                [
+                    unmultiply(pixel[0]),
+                    unmultiply(pixel[1]),
+                    unmultiply(pixel[2]),
+                    alpha,
+                ]
+            })
+            .collect()
+    }
+}
+
+/// UiBackend that does nothing.
+pub struct NullUiBackend {}
+
+impl NullUiBackend {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl UiBackend for NullUiBackend {
+    fn is_key_down(&self, _key: KeyCode) -> bool {
+        false
+    }
+
+    fn last_key_code(&self) -> KeyCode {
+        KeyCode::Unknown
+    }
+
+    fn last_key_char(&self) -> Option<char> {
+        None
+    }
+
+    fn last_key_location(&self) -> KeyLocation {
+        KeyLocation::Standard
+    }
+
+    fn mouse_visible(&self) -> bool {
+        true
+    }
+
+    fn set_mouse_visible(&mut self, _visible: bool) {}
+
+    fn set_mouse_cursor(&mut self, _cursor: MouseCursor) {}
+
+    fn set_custom_mouse_cursor(&mut self, _cursor: Option<&CustomMouseCursor>) -> bool {
+        false
+    }
+
+    fn set_clipboard_content(&mut self, _content: String) {}
+
+    fn clipboard_content(&mut self) -> Option<String> {
+        None
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        false
+    }
+
+    fn set_fullscreen(&mut self, _is_full: bool) {}
+
+    fn set_ime_position(&mut self, _position: Option<(f64, f64)>) {}
+
+    fn display_unsupported_message(&self) {}
+
+    fn display_root_movie_download_failed_message(&self) {}
+
+    fn message(&self, _message: &str) {}
+}
+
+impl Default for NullUiBackend {
+    fn default() -> Self {
+        NullUiBackend::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unmultiplied_rgba() {
+        let cursor = CustomMouseCursor {
+            width: 3,
+            height: 1,
+            rgba: vec![128, 64, 0, 128, 255, 255, 255, 255, 10, 20, 30, 0],
+            hot_spot: (0, 0),
+        };
+        assert_eq!(
+            cursor.unmultiplied_rgba(),
+            [255, 127, 0, 128, 255, 255, 255, 255, 0, 0, 0, 0]
+        );
+    }
+}
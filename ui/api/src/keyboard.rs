@@ -0,0 +1,198 @@
+//! Keyboard key codes, as seen by movies.
+
+use num_derive::FromPrimitive;
+
+/// Flash virtual keycode.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, FromPrimitive)]
+pub enum KeyCode {
+    Unknown = 0,
+    Backspace = 8,
+    Tab = 9,
+    Return = 13,
+    Command = 15,
+    Shift = 16,
+    Control = 17,
+    Alt = 18,
+    Pause = 19,
+    CapsLock = 20,
+    Escape = 27,
+    Space = 32,
+    PgUp = 33,
+    PgDown = 34,
+    End = 35,
+    Home = 36,
+    Left = 37,
+    Up = 38,
+    Right = 39,
+    Down = 40,
+    Insert = 45,
+    Delete = 46,
+    Key0 = 48,
+    Key1 = 49,
+    Key2 = 50,
+    Key3 = 51,
+    Key4 = 52,
+    Key5 = 53,
+    Key6 = 54,
+    Key7 = 55,
+    Key8 = 56,
+    Key9 = 57,
+    A = 65,
+    B = 66,
+    C = 67,
+    D = 68,
+    E = 69,
+    F = 70,
+    G = 71,
+    H = 72,
+    I = 73,
+    J = 74,
+    K = 75,
+    L = 76,
+    M = 77,
+    N = 78,
+    O = 79,
+    P = 80,
+    Q = 81,
+    R = 82,
+    S = 83,
+    T = 84,
+    U = 85,
+    V = 86,
+    W = 87,
+    X = 88,
+    Y = 89,
+    Z = 90,
+    Numpad0 = 96,
+    Numpad1 = 97,
+    Numpad2 = 98,
+    Numpad3 = 99,
+    Numpad4 = 100,
+    Numpad5 = 101,
+    Numpad6 = 102,
+    Numpad7 = 103,
+    Numpad8 = 104,
+    Numpad9 = 105,
+    Multiply = 106,
+    Plus = 107,
+    NumpadMinus = 109,
+    NumpadPeriod = 110,
+    NumpadSlash = 111,
+    F1 = 112,
+    F2 = 113,
+    F3 = 114,
+    F4 = 115,
+    F5 = 116,
+    F6 = 117,
+    F7 = 118,
+    F8 = 119,
+    F9 = 120,
+    F10 = 121,
+    F11 = 122,
+    F12 = 123,
+    F13 = 124,
+    F14 = 125,
+    F15 = 126,
+    NumLock = 144,
+    ScrollLock = 145,
+    Semicolon = 186,
+    Equals = 187,
+    Comma = 188,
+    Minus = 189,
+    Period = 190,
+    Slash = 191,
+    Grave = 192,
+    LBracket = 219,
+    Backslash = 220,
+    RBracket = 221,
+    Apostrophe = 222,
+}
+
+impl KeyCode {
+    pub fn from_u8(n: u8) -> Option<Self> {
+        num_traits::FromPrimitive::from_u8(n)
+    }
+
+    /// Find a key by a name that a user might type, such as `a`, `7`, `f5`
+    /// or `left`, or by its key code.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        if let Ok(code) = name.parse::<u8>() {
+            if name.len() > 1 {
+                return Self::from_u8(code);
+            }
+        }
+
+        let mut chars = name.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            if c.is_ascii_alphanumeric() {
+                return Self::from_u8(c.to_ascii_uppercase() as u8);
+            }
+        }
+
+        if let Some(n) = name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+            if (1..=15).contains(&n) {
+                return Self::from_u8(111 + n);
+            }
+        }
+
+        Some(match name.as_str() {
+            "backspace" => KeyCode::Backspace,
+            "tab" => KeyCode::Tab,
+            "enter" | "return" => KeyCode::Return,
+            "shift" => KeyCode::Shift,
+            "control" | "ctrl" => KeyCode::Control,
+            "alt" => KeyCode::Alt,
+            "pause" => KeyCode::Pause,
+            "capslock" => KeyCode::CapsLock,
+            "escape" | "esc" => KeyCode::Escape,
+            "space" => KeyCode::Space,
+            "pageup" => KeyCode::PgUp,
+            "pagedown" => KeyCode::PgDown,
+            "end" => KeyCode::End,
+            "home" => KeyCode::Home,
+            "left" => KeyCode::Left,
+            "up" => KeyCode::Up,
+            "right" => KeyCode::Right,
+            "down" => KeyCode::Down,
+            "insert" => KeyCode::Insert,
+            "delete" => KeyCode::Delete,
+            "command" | "cmd" => KeyCode::Command,
+            "numlock" => KeyCode::NumLock,
+            _ => return None,
+        })
+    }
+}
+
+/// Where a key is on the keyboard, for keys such as Shift that appear more
+/// than once. The values match Flash's `KeyLocation` constants.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum KeyLocation {
+    Standard = 0,
+    Left = 1,
+    Right = 2,
+    NumPad = 3,
+}
+
+impl Default for KeyLocation {
+    fn default() -> Self {
+        KeyLocation::Standard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_names() {
+        assert_eq!(KeyCode::from_name("a"), Some(KeyCode::A));
+        assert_eq!(KeyCode::from_name("7"), Some(KeyCode::Key7));
+        assert_eq!(KeyCode::from_name("F5"), Some(KeyCode::F5));
+        assert_eq!(KeyCode::from_name("Left"), Some(KeyCode::Left));
+        assert_eq!(KeyCode::from_name("32"), Some(KeyCode::Space));
+        assert_eq!(KeyCode::from_name("f13"), Some(KeyCode::F13));
+        assert_eq!(KeyCode::from_name("f16"), None);
+        assert_eq!(KeyCode::from_name("hyper"), None);
+    }
+}
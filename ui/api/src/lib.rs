@@ -0,0 +1,9 @@
+//! The interface between Ruffle and its user interface backends.
+//!
+//! This crate holds the `UiBackend` trait along with the cursor and keyboard
+//! types it uses, so that a frontend can be written without depending on the
+//! rest of `ruffle_core`. `ruffle_core` re-exports all of it under the same
+//! paths as before.
+
+pub mod backend;
+pub mod keyboard;
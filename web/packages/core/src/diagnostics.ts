@@ -0,0 +1,185 @@
+/**
+ * A report of what the current browser supports, for troubleshooting.
+ */
+export interface DiagnosticsReport {
+    version: string;
+    userAgent: string;
+    wasm: {
+        supported: boolean;
+        simd: boolean;
+        threads: boolean;
+        sharedArrayBuffer: boolean;
+        crossOriginIsolated: boolean;
+    };
+    graphics: {
+        webgl: boolean;
+        webgl2: boolean;
+        renderer: string | null;
+        vendor: string | null;
+    };
+    audio: {
+        supported: boolean;
+        sampleRate: number | null;
+        error: string | null;
+    };
+    storage: {
+        localStorage: boolean;
+        quota: number | null;
+        usage: number | null;
+    };
+}
+
+// A module containing a function that uses a SIMD instruction.
+const SIMD_MODULE = new Uint8Array([
+    0, 97, 115, 109, 1, 0, 0, 0, 1, 5, 1, 96, 0, 1, 123, 3, 2, 1, 0, 10, 10, 1,
+    8, 0, 65, 0, 253, 15, 253, 98, 11,
+]);
+
+// A module containing a function that uses an atomic instruction on a shared memory.
+const THREADS_MODULE = new Uint8Array([
+    0, 97, 115, 109, 1, 0, 0, 0, 1, 4, 1, 96, 0, 0, 3, 2, 1, 0, 5, 4, 1, 3, 1,
+    1, 10, 11, 1, 9, 0, 65, 0, 254, 16, 2, 0, 26, 11,
+]);
+
+/**
+ * Check whether WebAssembly accepts a module.
+ *
+ * @param module The module to validate.
+ * @returns True if the module is valid in this browser.
+ */
+function validates(module: Uint8Array): boolean {
+    try {
+        return WebAssembly.validate(module);
+    } catch (e) {
+        return false;
+    }
+}
+
+/**
+ * Probe the WebAssembly features Ruffle can make use of.
+ *
+ * @returns The WebAssembly section of the report.
+ */
+function probeWasm(): DiagnosticsReport["wasm"] {
+    const supported = typeof WebAssembly === "object";
+    const sharedArrayBuffer = typeof SharedArrayBuffer === "function";
+    return {
+        supported,
+        simd: supported && validates(SIMD_MODULE),
+        // Threads also need the page to be cross-origin isolated, so that
+        // memory can be shared between workers.
+        threads: supported && sharedArrayBuffer && validates(THREADS_MODULE),
+        sharedArrayBuffer,
+        crossOriginIsolated: window.crossOriginIsolated === true,
+    };
+}
+
+/**
+ * Probe the WebGL support of this browser and the GPU it would use.
+ *
+ * @returns The graphics section of the report.
+ */
+function probeGraphics(): DiagnosticsReport["graphics"] {
+    const canvas = document.createElement("canvas");
+    const webgl2 = canvas.getContext("webgl2");
+    const gl =
+        webgl2 ??
+        canvas.getContext("webgl") ??
+        canvas.getContext("experimental-webgl");
+
+    let renderer = null;
+    let vendor = null;
+    if (gl) {
+        const context = <WebGLRenderingContext>gl;
+        const info = context.getExtension("WEBGL_debug_renderer_info");
+        if (info) {
+            renderer = context.getParameter(info.UNMASKED_RENDERER_WEBGL);
+            vendor = context.getParameter(info.UNMASKED_VENDOR_WEBGL);
+        } else {
+            renderer = context.getParameter(context.RENDERER);
+            vendor = context.getParameter(context.VENDOR);
+        }
+    }
+
+    return {
+        webgl: gl !== null,
+        webgl2: webgl2 !== null,
+        renderer,
+        vendor,
+    };
+}
+
+/**
+ * Probe whether audio can be played.
+ *
+ * @returns The audio section of the report.
+ */
+async function probeAudio(): Promise<DiagnosticsReport["audio"]> {
+    if (typeof AudioContext !== "function") {
+        return { supported: false, sampleRate: null, error: null };
+    }
+
+    try {
+        const context = new AudioContext();
+        const sampleRate = context.sampleRate;
+        await context.close();
+        return { supported: true, sampleRate, error: null };
+    } catch (e) {
+        return { supported: false, sampleRate: null, error: String(e) };
+    }
+}
+
+/**
+ * Probe whether shared objects can be saved.
+ *
+ * @returns The storage section of the report.
+ */
+async function probeStorage(): Promise<DiagnosticsReport["storage"]> {
+    let localStorageWorks = false;
+    try {
+        // Accessing `localStorage` throws when storage is blocked, and
+        // writing throws when it is full.
+        const key = "ruffle-diagnostics";
+        window.localStorage.setItem(key, "ruffle");
+        localStorageWorks = window.localStorage.getItem(key) === "ruffle";
+        window.localStorage.removeItem(key);
+    } catch (e) {
+        localStorageWorks = false;
+    }
+
+    let quota = null;
+    let usage = null;
+    if (navigator.storage && navigator.storage.estimate) {
+        try {
+            const estimate = await navigator.storage.estimate();
+            quota = estimate.quota ?? null;
+            usage = estimate.usage ?? null;
+        } catch (e) {
+            // Leave the estimate unknown.
+        }
+    }
+
+    return { localStorage: localStorageWorks, quota, usage };
+}
+
+/**
+ * Probe the capabilities of the current browser that Ruffle depends on.
+ *
+ * Every probe is independent, so a failure is recorded in the report
+ * rather than stopping the others from running.
+ *
+ * @param version The version of Ruffle making the report.
+ * @returns A report suitable for attaching to a bug report.
+ */
+export async function runDiagnostics(
+    version: string
+): Promise<DiagnosticsReport> {
+    return {
+        version,
+        userAgent: navigator.userAgent,
+        wasm: probeWasm(),
+        graphics: probeGraphics(),
+        audio: await probeAudio(),
+        storage: await probeStorage(),
+    };
+}
@@ -1,3 +1,4 @@
+export * from "./diagnostics";
 export * from "./load-ruffle";
 export * from "./plugin-polyfill";
 export * from "./polyfills";
@@ -76,6 +76,83 @@ export enum LogLevel {
     Trace = "trace",
 }
 
+/**
+ * A WebSocket server that relays socket connections to a TCP host and port.
+ */
+export interface SocketProxy {
+    /**
+     * The host that the movie connects to.
+     */
+    host: string;
+
+    /**
+     * The port that the movie connects to.
+     */
+    port: number;
+
+    /**
+     * The URL of the WebSocket server that relays the connection.
+     */
+    proxyUrl: string;
+}
+
+/**
+ * A font to use for device text in a script that the default device font
+ * lacks glyphs for.
+ */
+export interface FallbackFont {
+    /**
+     * The script the font is used for: `"cjk"`, `"cyrillic"` or `"arabic"`.
+     */
+    script: "cjk" | "cyrillic" | "arabic";
+
+    /**
+     * The URL of a TrueType or OpenType font file.
+     */
+    url: string;
+}
+
+/**
+ * Capabilities reported to a movie through `$version` and
+ * `System.capabilities`. Anything left out is reported as it really is.
+ */
+export interface SpoofedCapabilities {
+    /**
+     * The version string, such as `WIN 11,2,202,235`.
+     */
+    version?: string;
+
+    /**
+     * The name of the operating system, such as `Windows 7`.
+     */
+    os?: string;
+
+    /**
+     * The manufacturer of the player, such as `Adobe Windows`.
+     */
+    manufacturer?: string;
+
+    /**
+     * The type of the player, such as `PlugIn` or `ActiveX`.
+     */
+    playerType?: string;
+
+    /**
+     * The language code, such as `en` or `zh-CN`.
+     */
+    language?: string;
+
+    /**
+     * The width and height of the screen.
+     */
+    screenResolution?: [number, number];
+
+    /**
+     * Whether the player is a debugger version.
+     */
+    isDebugger?: boolean;
+}
+
 /**
  * Any options used for loading a movie.
  */
@@ -153,6 +230,19 @@ export interface BaseLoadOptions {
      */
     warnOnUnsupportedContent?: boolean;
 
+    /**
+     * Whether movies are trusted, and may load data from any site.
+     *
+     * By default, movies run in the security sandbox of their site, like
+     * in Flash Player. They may then only load data from other sites
+     * that allow it with CORS headers, as the browser requires anyway.
+     * Sites without CORS headers can't be read, even with a
+     * crossdomain.xml policy file.
+     *
+     * @default false
+     */
+    trusted?: boolean;
+
     /**
      * Console logging level.
      *
@@ -179,6 +269,25 @@ export interface BaseLoadOptions {
         nanos: number;
     };
 
+    /**
+     * Maximum amount of time to spend running frames in a single
+     * browser animation frame.
+     *
+     * When content falls behind, Ruffle normally runs several frames
+     * at once to catch up. With a budget set, it stops catching up once
+     * the budget is spent and returns control to the browser. This keeps
+     * the page responsive, at the cost of the content running slower than
+     * its frame rate. Only these catch-up frames are skipped: a single
+     * frame is never split or resumed later, so one very long frame script
+     * can still exceed the budget.
+     *
+     * @default null
+     */
+    frameBudget?: {
+        secs: number;
+        nanos: number;
+    } | null;
+
     /**
      * Specifies the base directory or URL used to resolve all relative path statements in the SWF file.
      * null means the current directory.
@@ -219,6 +328,38 @@ export interface BaseLoadOptions {
      * @default "showAll"
      */
     scale?: string;
+
+    /**
+     * WebSocket proxies to connect `flash.net.Socket`s through.
+     *
+     * Browsers can't open TCP connections, so a movie can only connect to
+     * a host and port that has a proxy listed here. Data is relayed as
+     * binary WebSocket messages.
+     *
+     * @default []
+     */
+    socketProxy?: SocketProxy[];
+
+    /**
+     * Capabilities to report to the movie in place of the real ones.
+     *
+     * Some movies refuse to run, or hide features, unless they find a
+     * particular Flash Player version or operating system.
+     *
+     * @default {}
+     */
+    spoofCapabilities?: SpoofedCapabilities;
+
+    /**
+     * Fonts to use for device text in scripts that the default device
+     * font lacks glyphs for, such as CJK text.
+     *
+     * Several fonts may be given for the same script, and are tried in
+     * order. They are downloaded before the movie starts.
+     *
+     * @default []
+     */
+    fallbackFont?: FallbackFont[];
 }
 
 /**
@@ -13,6 +13,7 @@ import {
 } from "./load-options";
 import { MovieMetadata } from "./movie-metadata";
 import { InternalContextMenuItem } from "./context-menu";
+import { runDiagnostics } from "./diagnostics";
 
 export const FLASH_MIMETYPE = "application/x-shockwave-flash";
 export const FUTURESPLASH_MIMETYPE = "application/futuresplash";
@@ -414,6 +415,21 @@ export class RufflePlayer extends HTMLElement {
         this.instance = new ruffleConstructor(this.container, this, config);
         console.log("New Ruffle instance created.");
 
+        for (const fallbackFont of config.fallbackFont ?? []) {
+            try {
+                const response = await fetch(fallbackFont.url);
+                if (!response.ok) {
+                    throw new Error(`HTTP ${response.status}`);
+                }
+                const data = new Uint8Array(await response.arrayBuffer());
+                this.instance?.register_fallback_font(fallbackFont.script, data);
+            } catch (e) {
+                console.error(
+                    `Unable to load fallback font ${fallbackFont.url}: ${e}`
+                );
+            }
+        }
+
         // In Firefox, AudioContext.state is always "suspended" when the object has just been created.
         // It may change by itself to "running" some milliseconds later. So we need to wait a little
         // bit before checking if autoplay is supported and applying the instance config.
@@ -903,6 +919,22 @@ export class RufflePlayer extends HTMLElement {
         };
     }
 
+    /**
+     * Calls a callback of the movie with a request in the XML format of
+     * Flash Player's `CallFunction`, such as
+     * `<invoke name="callback" returntype="xml"><arguments><string>hello</string></arguments></invoke>`.
+     *
+     * Pages written for Flash Player may use this instead of calling the
+     * callbacks directly.
+     *
+     * @param request The XML request naming the callback and its arguments.
+     * @returns The result of the callback in the same XML format, or
+     * `undefined` if the request is invalid.
+     */
+    CallFunction(request: string): string | undefined {
+        return this.instance?.call_function(request);
+    }
+
     /**
      * Sets a trace observer on this flash player.
      *
@@ -914,6 +946,28 @@ export class RufflePlayer extends HTMLElement {
         this.instance?.set_trace_observer(observer);
     }
 
+    /**
+     * Packages a bug report about the current movie, for attaching to an issue.
+     *
+     * The report holds a diagnostics report of this browser, the URL of the
+     * movie, the warnings logged while it played and optionally a screenshot
+     * of it.
+     *
+     * @param includeScreenshot Whether to include a screenshot of the movie.
+     * @returns The report, as a `.zip` file.
+     */
+    async bugReport(includeScreenshot = true): Promise<Blob> {
+        const diagnostics = await runDiagnostics("%VERSION_NUMBER%");
+        if (!this.instance) {
+            throw new Error("No movie has been loaded");
+        }
+        const data = this.instance.bug_report(
+            JSON.stringify(diagnostics, null, 2),
+            includeScreenshot
+        );
+        return new Blob([data], { type: "application/zip" });
+    }
+
     /**
      * Panics this specific player, forcefully destroying all resources and displays an error message to the user.
      *
@@ -1243,6 +1297,23 @@ export class RufflePlayer extends HTMLElement {
         this.dispatchEvent(new Event(RufflePlayer.LOADED_DATA));
     }
 
+    /**
+     * Called by Ruffle when content writes to the clipboard.
+     *
+     * Browsers may refuse the write if it isn't in response to user input.
+     *
+     * @param content The text to place on the clipboard.
+     */
+    private setClipboard(content: string): void {
+        if (!navigator.clipboard) {
+            console.warn("The Clipboard API is not available");
+            return;
+        }
+        navigator.clipboard.writeText(content).catch((e) => {
+            console.warn("Couldn't write to the clipboard", e);
+        });
+    }
+
     setIsExtension(isExtension: boolean): void {
         this.isExtension = isExtension;
     }
@@ -1,3 +1,4 @@
+import { DiagnosticsReport, runDiagnostics } from "./diagnostics";
 import { pluginPolyfill, polyfill } from "./polyfills";
 import { registerElement } from "./register-element";
 import { RufflePlayer } from "./ruffle-player";
@@ -63,4 +64,14 @@ export class SourceAPI {
         const name = registerElement("ruffle-player", RufflePlayer);
         return <RufflePlayer>document.createElement(name);
     }
+
+    /**
+     * Probe the capabilities of the current browser that Ruffle depends on,
+     * such as WebAssembly features, WebGL, audio and storage.
+     *
+     * @returns A report suitable for attaching to a bug report.
+     */
+    diagnostics(): Promise<DiagnosticsReport> {
+        return runDiagnostics(this.version);
+    }
 }
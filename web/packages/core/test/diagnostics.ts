@@ -0,0 +1,148 @@
+import { strict as assert } from "assert";
+import { runDiagnostics } from "../src/diagnostics";
+
+/**
+ * Replace a global for the rest of a test, returning a function that
+ * restores it.
+ *
+ * @param name The name of the global.
+ * @param value The value to give it.
+ * @returns A function that puts the original value back.
+ */
+function stubGlobal(name: string, value: unknown): () => void {
+    const original = Object.getOwnPropertyDescriptor(globalThis, name);
+    Object.defineProperty(globalThis, name, {
+        value,
+        configurable: true,
+        writable: true,
+    });
+    return () => {
+        if (original) {
+            Object.defineProperty(globalThis, name, original);
+        } else {
+            delete (globalThis as Record<string, unknown>)[name];
+        }
+    };
+}
+
+/**
+ * A canvas whose contexts all fail to be created, as when WebGL is disabled.
+ */
+const noWebGl = {
+    createElement: () => ({ getContext: () => null }),
+};
+
+describe("runDiagnostics", function () {
+    let restore: (() => void)[] = [];
+
+    afterEach(function () {
+        restore.forEach((restoreGlobal) => restoreGlobal());
+        restore = [];
+    });
+
+    it("reports missing features without failing", async function () {
+        restore = [
+            stubGlobal("window", {
+                get localStorage() {
+                    throw new Error("Storage is blocked");
+                },
+            }),
+            stubGlobal("document", noWebGl),
+            stubGlobal("navigator", { userAgent: "Test" }),
+            stubGlobal("AudioContext", undefined),
+        ];
+
+        const report = await runDiagnostics("1.2.3");
+        assert.equal(report.version, "1.2.3");
+        assert.equal(report.userAgent, "Test");
+        assert.equal(report.wasm.supported, true);
+        assert.equal(report.wasm.crossOriginIsolated, false);
+        assert.deepEqual(report.graphics, {
+            webgl: false,
+            webgl2: false,
+            renderer: null,
+            vendor: null,
+        });
+        assert.deepEqual(report.audio, {
+            supported: false,
+            sampleRate: null,
+            error: null,
+        });
+        assert.deepEqual(report.storage, {
+            localStorage: false,
+            quota: null,
+            usage: null,
+        });
+    });
+
+    it("reports available audio and storage", async function () {
+        const items = new Map<string, string>();
+        let closed = false;
+        restore = [
+            stubGlobal("window", {
+                crossOriginIsolated: true,
+                localStorage: {
+                    setItem: (key: string, value: string) =>
+                        items.set(key, value),
+                    getItem: (key: string) => items.get(key) ?? null,
+                    removeItem: (key: string) => items.delete(key),
+                },
+            }),
+            stubGlobal("document", noWebGl),
+            stubGlobal("navigator", {
+                userAgent: "Test",
+                storage: {
+                    estimate: async () => ({ quota: 1000, usage: 10 }),
+                },
+            }),
+            stubGlobal(
+                "AudioContext",
+                class {
+                    sampleRate = 48000;
+                    async close() {
+                        closed = true;
+                    }
+                }
+            ),
+        ];
+
+        const report = await runDiagnostics("1.2.3");
+        assert.equal(report.wasm.crossOriginIsolated, true);
+        assert.deepEqual(report.audio, {
+            supported: true,
+            sampleRate: 48000,
+            error: null,
+        });
+        assert.equal(closed, true);
+        assert.deepEqual(report.storage, {
+            localStorage: true,
+            quota: 1000,
+            usage: 10,
+        });
+        // The probe cleans up after itself.
+        assert.equal(items.size, 0);
+    });
+
+    it("reports an audio context that can't be created", async function () {
+        restore = [
+            stubGlobal("window", {}),
+            stubGlobal("document", noWebGl),
+            stubGlobal("navigator", { userAgent: "Test" }),
+            stubGlobal(
+                "AudioContext",
+                class {
+                    constructor() {
+                        throw new Error("Not allowed");
+                    }
+                }
+            ),
+        ];
+
+        const report = await runDiagnostics("1.2.3");
+        assert.deepEqual(report.audio, {
+            supported: false,
+            sampleRate: null,
+            error: "Error: Not allowed",
+        });
+    });
+});
@@ -25,10 +25,13 @@ function isSwf(
     if (mime === "application/octet-stream") {
         const url = new URL(details.url);
         const extension = url.pathname.substring(url.pathname.lastIndexOf("."));
-        return extension.toLowerCase() === ".swf";
+        return [".swf", ".spl"].includes(extension.toLowerCase());
     }
 
-    return mime === "application/x-shockwave-flash";
+    return (
+        mime === "application/x-shockwave-flash" ||
+        mime === "application/futuresplash"
+    );
 }
 
 function onHeadersReceived(
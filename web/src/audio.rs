@@ -941,7 +941,7 @@ impl AudioBackend for WebAudioBackend {
         &mut self,
         stream_handle: Option<SoundHandle>,
         clip_frame: u16,
-        _clip_data: ruffle_core::tag_utils::SwfSlice,
+        _clip_data: ruffle_core::backend::audio::StreamData,
         _stream_info: &swf::SoundStreamHead,
     ) -> Result<SoundInstanceHandle, Error> {
         if let Some(stream) = stream_handle {
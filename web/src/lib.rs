@@ -21,11 +21,12 @@ use ruffle_core::backend::{
     ui::UiBackend,
     video::SoftwareVideoBackend,
 };
-use ruffle_core::config::Letterbox;
+use ruffle_core::bug_report::{BugReport, RecentWarnings, WarningLogger};
+use ruffle_core::config::{FontScript, Letterbox, SpoofedCapabilities};
 use ruffle_core::context::UpdateContext;
-use ruffle_core::events::{KeyCode, MouseWheelDelta};
+use ruffle_core::events::{GamepadControl, KeyCode, MouseWheelDelta, TouchPhase};
 use ruffle_core::external::{
-    ExternalInterfaceMethod, ExternalInterfaceProvider, Value as ExternalValue, Value,
+    self, ExternalInterfaceMethod, ExternalInterfaceProvider, Value as ExternalValue, Value,
 };
 use ruffle_core::tag_utils::SwfMovie;
 use ruffle_core::{Color, Player, PlayerEvent};
@@ -38,12 +39,16 @@ use std::time::Duration;
 use std::{cell::RefCell, error::Error, num::NonZeroI32};
 use wasm_bindgen::{prelude::*, JsCast, JsValue};
 use web_sys::{
-    AddEventListenerOptions, Element, Event, EventTarget, HtmlCanvasElement, HtmlElement,
-    KeyboardEvent, PointerEvent, WheelEvent, Window,
+    AddEventListenerOptions, ClipboardEvent, CompositionEvent, Element, Event, EventTarget,
+    Gamepad, GamepadButton, HtmlCanvasElement, HtmlElement, HtmlTextAreaElement, KeyboardEvent,
+    PointerEvent, WheelEvent, Window,
 };
 
 static RUFFLE_GLOBAL_PANIC: Once = Once::new();
 
+/// The events that an input method fires on the element it composes into.
+const COMPOSITION_EVENTS: [&str; 3] = ["compositionstart", "compositionupdate", "compositionend"];
+
 thread_local! {
     /// We store the actual instances of the ruffle core in a static pool.
     /// This gives us a clear boundary between the JS side and Rust side, avoiding
@@ -51,6 +56,9 @@ thread_local! {
     static INSTANCES: RefCell<Arena<RefCell<RuffleInstance>>> = RefCell::new(Arena::new());
 
     static CURRENT_CONTEXT: RefCell<Option<*mut UpdateContext<'static, 'static, 'static>>> = RefCell::new(None);
+
+    /// The warnings logged by every player on the page, for bug reports.
+    static RECENT_WARNINGS: Arc<RecentWarnings> = Arc::new(RecentWarnings::new());
 }
 
 type AnimationHandler = Closure<dyn FnMut(f64)>;
@@ -59,6 +67,9 @@ struct RuffleInstance {
     core: Arc<Mutex<Player>>,
     js_player: JavascriptPlayer,
     canvas: HtmlCanvasElement,
+
+    /// A hidden element that input methods compose text into.
+    ime_input: HtmlTextAreaElement,
     canvas_width: i32,
     canvas_height: i32,
     device_pixel_ratio: f64,
@@ -72,11 +83,17 @@ struct RuffleInstance {
     player_mouse_down_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
     window_mouse_down_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
     mouse_up_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
+    pointer_cancel_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
     mouse_wheel_callback: Option<Closure<dyn FnMut(WheelEvent)>>,
     key_down_callback: Option<Closure<dyn FnMut(KeyboardEvent)>>,
     key_up_callback: Option<Closure<dyn FnMut(KeyboardEvent)>>,
+    paste_callback: Option<Closure<dyn FnMut(ClipboardEvent)>>,
+    composition_callback: Option<Closure<dyn FnMut(CompositionEvent)>>,
     unload_callback: Option<Closure<dyn FnMut(Event)>>,
     has_focus: bool,
+
+    /// The values of each gamepad's axes and buttons when it was last polled.
+    gamepads: BTreeMap<u32, Vec<f64>>,
     trace_observer: Arc<RefCell<JsValue>>,
 }
 
@@ -116,8 +133,17 @@ extern "C" {
     #[wasm_bindgen(method, getter, js_name = "isFullscreen")]
     fn is_fullscreen(this: &JavascriptPlayer) -> bool;
 
+    #[wasm_bindgen(method, js_name = "enterFullscreen")]
+    fn enter_fullscreen(this: &JavascriptPlayer);
+
+    #[wasm_bindgen(method, js_name = "exitFullscreen")]
+    fn exit_fullscreen(this: &JavascriptPlayer);
+
     #[wasm_bindgen(method, js_name = "setMetadata")]
     fn set_metadata(this: &JavascriptPlayer, metadata: JsValue);
+
+    #[wasm_bindgen(method, js_name = "setClipboard")]
+    fn set_clipboard(this: &JavascriptPlayer, content: &str);
 }
 
 struct JavascriptInterface {
@@ -153,11 +179,22 @@ pub struct Config {
     #[serde(rename = "warnOnUnsupportedContent")]
     warn_on_unsupported_content: bool,
 
+    trusted: bool,
+
     #[serde(rename = "logLevel")]
     log_level: log::Level,
 
     #[serde(rename = "maxExecutionDuration")]
     max_execution_duration: Duration,
+
+    #[serde(rename = "frameBudget")]
+    frame_budget: Option<Duration>,
+
+    #[serde(rename = "socketProxy")]
+    socket_proxy: Vec<navigator::SocketProxy>,
+
+    #[serde(rename = "spoofCapabilities")]
+    spoof_capabilities: SpoofedCapabilities,
 }
 
 impl Default for Config {
@@ -173,8 +210,12 @@ impl Default for Config {
             upgrade_to_https: true,
             base_url: None,
             warn_on_unsupported_content: true,
+            trusted: false,
             log_level: log::Level::Error,
             max_execution_duration: Duration::from_secs(15),
+            frame_budget: None,
+            socket_proxy: Vec::new(),
+            spoof_capabilities: Default::default(),
         }
     }
 }
@@ -262,6 +303,26 @@ impl Ruffle {
         Ok(())
     }
 
+    /// Register a font to fall back to for device text in a given script
+    /// (cjk, cyrillic or arabic), as a TrueType or OpenType font file.
+    ///
+    /// Fonts should be registered before a movie is loaded, so that its text
+    /// is laid out with them.
+    pub fn register_fallback_font(
+        &mut self,
+        script: &str,
+        data: Uint8Array,
+    ) -> Result<(), JsValue> {
+        let script = script
+            .parse::<FontScript>()
+            .map_err(|_| format!("Unknown script {}", script))?;
+        let data = data.to_vec();
+        self.with_core_mut(|core| core.register_device_font_fallback(script, &data))
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("Error loading fallback font: {}", e))?;
+        Ok(())
+    }
+
     pub fn play(&mut self) {
         let _ = self.with_core_mut(|core| {
             core.set_is_playing(true);
@@ -278,6 +339,50 @@ impl Ruffle {
         self.with_core(|core| core.is_playing()).unwrap_or_default()
     }
 
+    /// Package a bug report as the contents of a `.zip`, from a diagnostics
+    /// report of the browser as JSON.
+    pub fn bug_report(
+        &mut self,
+        diagnostics: String,
+        include_screenshot: bool,
+    ) -> Result<Uint8Array, JsValue> {
+        let canvas = self
+            .with_instance(|instance| instance.canvas.clone())
+            .map_err(|e| e.to_string())?;
+        let (movie_url, screenshot) = self
+            .with_core_mut(|core| {
+                let movie_url = core.swf().url().map(str::to_owned);
+                let screenshot = if include_screenshot {
+                    // WebGL only keeps what it drew until the browser shows
+                    // it, so draw the frame again before reading it back.
+                    core.render();
+                    canvas
+                        .to_data_url_with_type("image/png")
+                        .ok()
+                        .and_then(|url| {
+                            url.strip_prefix("data:image/png;base64,")
+                                .and_then(|data| base64::decode(data).ok())
+                        })
+                } else {
+                    None
+                };
+                (movie_url, screenshot)
+            })
+            .map_err(|e| e.to_string())?;
+
+        let mut bug_report = BugReport::new(diagnostics);
+        if let Some(url) = &movie_url {
+            bug_report = bug_report.with_movie_url(url);
+        }
+        bug_report = RECENT_WARNINGS.with(|warnings| bug_report.with_warnings(warnings));
+        if let Some(png) = screenshot {
+            bug_report = bug_report.with_screenshot(png);
+        }
+        let data = bug_report.to_zip().map_err(|e| e.to_string())?;
+
+        Ok(Uint8Array::from(&data[..]))
+    }
+
     // after the context menu is closed, remember to call `clear_custom_menu_items`!
     pub fn prepare_context_menu(&mut self) -> JsValue {
         self.with_core_mut(|core| {
@@ -358,6 +463,16 @@ impl Ruffle {
                     .warn_on_error();
                 instance.mouse_up_callback = None;
             }
+            if let Some(pointer_cancel_callback) = &instance.pointer_cancel_callback {
+                let canvas_events: &EventTarget = instance.canvas.as_ref();
+                canvas_events
+                    .remove_event_listener_with_callback(
+                        "pointercancel",
+                        pointer_cancel_callback.as_ref().unchecked_ref(),
+                    )
+                    .warn_on_error();
+                instance.pointer_cancel_callback = None;
+            }
             if let Some(mouse_wheel_callback) = &instance.mouse_wheel_callback {
                 let canvas_events: &EventTarget = instance.canvas.as_ref();
                 canvas_events
@@ -388,6 +503,28 @@ impl Ruffle {
                     .warn_on_error();
                 instance.key_up_callback = None;
             }
+            if let Some(paste_callback) = &instance.paste_callback {
+                instance
+                    .window
+                    .remove_event_listener_with_callback(
+                        "paste",
+                        paste_callback.as_ref().unchecked_ref(),
+                    )
+                    .warn_on_error();
+                instance.paste_callback = None;
+            }
+            if let Some(composition_callback) = &instance.composition_callback {
+                for event_type in &COMPOSITION_EVENTS {
+                    instance
+                        .ime_input
+                        .remove_event_listener_with_callback(
+                            event_type,
+                            composition_callback.as_ref().unchecked_ref(),
+                        )
+                        .warn_on_error();
+                }
+                instance.composition_callback = None;
+            }
             if let Some(unload_callback) = &instance.unload_callback {
                 instance
                     .window
@@ -414,23 +551,19 @@ impl Ruffle {
     #[allow(clippy::boxed_local)] // for js_bind
     pub fn call_exposed_callback(&self, name: &str, args: Box<[JsValue]>) -> JsValue {
         let args: Vec<ExternalValue> = args.iter().map(js_to_external_value).collect();
-
-        // Re-entrant callbacks need to return through the hole that was punched through for them
-        // We record the context of external functions, and then if we get an internal callback
-        // during the same call we'll reuse that.
-        // This is unsafe by nature. I don't know any safe way to do this.
-        if let Some(context) = CURRENT_CONTEXT.with(|v| *v.borrow()) {
-            unsafe {
-                if let Some(callback) = (*context).external_interface.get_callback(name) {
-                    return external_to_js_value(callback.call(&mut *context, name, args));
-                }
-            }
-        }
-
-        self.with_core_mut(|core| external_to_js_value(core.call_internal_interface(name, args)))
+        self.call_callback(name, args)
+            .map(external_to_js_value)
             .unwrap_or(JsValue::NULL)
     }
 
+    /// Call a callback with a request in the XML format of Flash Player's
+    /// `CallFunction`, returning the result in the same format.
+    pub fn call_function(&self, request: &str) -> Option<String> {
+        let (name, args) = external::parse_invoke(request)?;
+        self.call_callback(&name, args)
+            .map(|result| result.to_xml())
+    }
+
     pub fn set_trace_observer(&self, observer: JsValue) {
         let _ = self.with_instance(|instance| {
             *instance.trace_observer.borrow_mut() = observer;
@@ -450,12 +583,41 @@ impl Ruffle {
 }
 
 impl Ruffle {
+    fn call_callback(&self, name: &str, args: Vec<ExternalValue>) -> Option<ExternalValue> {
+        // Re-entrant callbacks need to return through the hole that was punched through for them
+        // We record the context of external functions, and then if we get an internal callback
+        // during the same call we'll reuse that.
+        // This is unsafe by nature. I don't know any safe way to do this.
+        if let Some(context) = CURRENT_CONTEXT.with(|v| *v.borrow()) {
+            unsafe {
+                if let Some(callback) = (*context).external_interface.get_callback(name) {
+                    return Some(callback.call(&mut *context, name, args));
+                }
+            }
+        }
+
+        self.with_core_mut(|core| core.call_internal_interface(name, args))
+            .ok()
+    }
+
     fn new_internal(
         parent: HtmlElement,
         js_player: JavascriptPlayer,
         config: Config,
     ) -> Result<Ruffle, Box<dyn Error>> {
-        let _ = console_log::init_with_level(config.log_level);
+        // Warnings are kept for bug reports, even when they aren't logged.
+        let logger = WarningLogger::new(
+            log_adapter::ConsoleLogger::new(config.log_level),
+            RECENT_WARNINGS.with(Arc::clone),
+        );
+        if log::set_boxed_logger(Box::new(logger)).is_ok() {
+            log::set_max_level(
+                config
+                    .log_level
+                    .to_level_filter()
+                    .max(log::LevelFilter::Warn),
+            );
+        }
         let allow_script_access = config.allow_script_access;
 
         let window = web_sys::window().ok_or("Expected window")?;
@@ -475,6 +637,7 @@ impl Ruffle {
             allow_script_access,
             config.upgrade_to_https,
             config.base_url,
+            config.socket_proxy,
         ));
         let storage = match window.local_storage() {
             Ok(Some(s)) => {
@@ -489,7 +652,38 @@ impl Ruffle {
         let trace_observer = Arc::new(RefCell::new(JsValue::UNDEFINED));
         let video = Box::new(SoftwareVideoBackend::new());
         let log = Box::new(log_adapter::WebLogBackend::new(trace_observer.clone()));
-        let ui = Box::new(ui::WebUiBackend::new(js_player.clone(), &canvas));
+
+        // Input methods only compose text into a focused editable element, so
+        // a hidden one is focused while a text field is being edited.
+        let ime_input: HtmlTextAreaElement = document
+            .create_element("textarea")
+            .into_js_result()?
+            .unchecked_into();
+        ime_input
+            .set_attribute("aria-hidden", "true")
+            .warn_on_error();
+        let ime_input_style = ime_input.style();
+        for (name, value) in &[
+            ("position", "absolute"),
+            ("opacity", "0"),
+            ("width", "1px"),
+            ("height", "1px"),
+            ("padding", "0"),
+            ("border", "0"),
+            ("resize", "none"),
+            ("pointer-events", "none"),
+        ] {
+            ime_input_style.set_property(name, value).warn_on_error();
+        }
+        parent
+            .append_child(&ime_input.clone().into())
+            .into_js_result()?;
+
+        let ui = Box::new(ui::WebUiBackend::new(
+            js_player.clone(),
+            &canvas,
+            &ime_input,
+        ));
 
         let core =
             ruffle_core::Player::new(renderer, audio, navigator, storage, locale, video, log, ui)?;
@@ -500,11 +694,19 @@ impl Ruffle {
             }
             core.set_letterbox(config.letterbox);
             core.set_warn_on_unsupported_content(config.warn_on_unsupported_content);
+            core.set_trusted(config.trusted);
             core.set_max_execution_duration(config.max_execution_duration);
+            core.set_frame_budget(config.frame_budget);
             core.set_show_menu(config.show_menu);
             core.set_stage_align(config.salign.as_deref().unwrap_or(""));
             core.set_quality(config.quality.as_deref().unwrap_or("high"));
             core.set_scale_mode(config.scale.as_deref().unwrap_or("showAll"));
+            core.set_spoofed_capabilities(config.spoof_capabilities);
+            if let Ok(screen) = window.screen() {
+                if let (Ok(width), Ok(height)) = (screen.width(), screen.height()) {
+                    core.set_screen_resolution(width as u32, height as u32);
+                }
+            }
 
             // Create the external interface.
             if allow_script_access {
@@ -517,6 +719,7 @@ impl Ruffle {
             core,
             js_player: js_player.clone(),
             canvas: canvas.clone(),
+            ime_input,
             canvas_width: 0, // Initialize canvas width and height to 0 to force an initial canvas resize.
             canvas_height: 0,
             device_pixel_ratio: window.device_pixel_ratio(),
@@ -528,12 +731,16 @@ impl Ruffle {
             player_mouse_down_callback: None,
             window_mouse_down_callback: None,
             mouse_up_callback: None,
+            pointer_cancel_callback: None,
             mouse_wheel_callback: None,
             key_down_callback: None,
             key_up_callback: None,
+            paste_callback: None,
+            composition_callback: None,
             unload_callback: None,
             timestamp: None,
             has_focus: false,
+            gamepads: BTreeMap::new(),
             trace_observer,
         };
 
@@ -555,10 +762,12 @@ impl Ruffle {
             // Create mouse move handler.
             let mouse_move_callback = Closure::wrap(Box::new(move |js_event: PointerEvent| {
                 let _ = ruffle.with_instance(move |instance| {
-                    let event = PlayerEvent::MouseMove {
-                        x: f64::from(js_event.offset_x()) * instance.device_pixel_ratio,
-                        y: f64::from(js_event.offset_y()) * instance.device_pixel_ratio,
-                    };
+                    let event =
+                        touch_event(&js_event, TouchPhase::Moved, instance.device_pixel_ratio)
+                            .unwrap_or_else(|| PlayerEvent::MouseMove {
+                                x: f64::from(js_event.offset_x()) * instance.device_pixel_ratio,
+                                y: f64::from(js_event.offset_y()) * instance.device_pixel_ratio,
+                            });
                     let _ = instance.with_core_mut(|core| {
                         core.handle_event(event);
                     });
@@ -589,10 +798,11 @@ impl Ruffle {
                                 .set_pointer_capture(js_event.pointer_id());
                         }
                         let device_pixel_ratio = instance.device_pixel_ratio;
-                        let event = PlayerEvent::MouseDown {
-                            x: f64::from(js_event.offset_x()) * device_pixel_ratio,
-                            y: f64::from(js_event.offset_y()) * device_pixel_ratio,
-                        };
+                        let event = touch_event(&js_event, TouchPhase::Began, device_pixel_ratio)
+                            .unwrap_or_else(|| PlayerEvent::MouseDown {
+                                x: f64::from(js_event.offset_x()) * device_pixel_ratio,
+                                y: f64::from(js_event.offset_y()) * device_pixel_ratio,
+                            });
                         let _ = instance.with_core_mut(|core| {
                             core.handle_event(event);
                         });
@@ -660,10 +870,12 @@ impl Ruffle {
                                 .unchecked_ref::<Element>()
                                 .release_pointer_capture(js_event.pointer_id());
                         }
-                        let event = PlayerEvent::MouseUp {
-                            x: f64::from(js_event.offset_x()) * instance.device_pixel_ratio,
-                            y: f64::from(js_event.offset_y()) * instance.device_pixel_ratio,
-                        };
+                        let event =
+                            touch_event(&js_event, TouchPhase::Ended, instance.device_pixel_ratio)
+                                .unwrap_or_else(|| PlayerEvent::MouseUp {
+                                    x: f64::from(js_event.offset_x()) * instance.device_pixel_ratio,
+                                    y: f64::from(js_event.offset_y()) * instance.device_pixel_ratio,
+                                });
                         let _ = instance.with_core_mut(|core| {
                             core.handle_event(event);
                         });
@@ -684,6 +896,31 @@ impl Ruffle {
                 .warn_on_error();
             instance.mouse_up_callback = Some(mouse_up_callback);
 
+            // Create pointer cancel handler, for touches the browser takes over.
+            let pointer_cancel_callback = Closure::wrap(Box::new(move |js_event: PointerEvent| {
+                let _ = ruffle.with_instance(|instance| {
+                    if let Some(event) = touch_event(
+                        &js_event,
+                        TouchPhase::Cancelled,
+                        instance.device_pixel_ratio,
+                    ) {
+                        let _ = instance.with_core_mut(|core| {
+                            core.handle_event(event);
+                        });
+                    }
+                });
+            })
+                as Box<dyn FnMut(PointerEvent)>);
+
+            let canvas_events: &EventTarget = canvas.as_ref();
+            canvas_events
+                .add_event_listener_with_callback(
+                    "pointercancel",
+                    pointer_cancel_callback.as_ref().unchecked_ref(),
+                )
+                .warn_on_error();
+            instance.pointer_cancel_callback = Some(pointer_cancel_callback);
+
             // Create mouse wheel handler.
             let mouse_wheel_callback = Closure::wrap(Box::new(move |js_event: WheelEvent| {
                 let _ = ruffle.with_instance(|instance| {
@@ -716,7 +953,8 @@ impl Ruffle {
             // Create keydown event handler.
             let key_down_callback = Closure::wrap(Box::new(move |js_event: KeyboardEvent| {
                 let _ = ruffle.with_instance(|instance| {
-                    if instance.has_focus {
+                    // Keys pressed while composing belong to the input method.
+                    if instance.has_focus && !is_composing(&js_event) {
                         let _ = instance.with_core_mut(|core| {
                             let ui = if let Some(ui) =
                                 core.ui_mut().downcast_mut::<ui::WebUiBackend>()
@@ -739,7 +977,12 @@ impl Ruffle {
                             }
                         });
 
-                        js_event.prevent_default();
+                        // The browser only hands out the clipboard through its own paste event.
+                        let is_paste = (js_event.ctrl_key() || js_event.meta_key())
+                            && js_event.key().eq_ignore_ascii_case("v");
+                        if !is_paste {
+                            js_event.prevent_default();
+                        }
                     }
                 });
             }) as Box<dyn FnMut(KeyboardEvent)>);
@@ -755,7 +998,7 @@ impl Ruffle {
             // Create keyup event handler.
             let key_up_callback = Closure::wrap(Box::new(move |js_event: KeyboardEvent| {
                 let _ = ruffle.with_instance(|instance| {
-                    if instance.has_focus {
+                    if instance.has_focus && !is_composing(&js_event) {
                         let _ = instance.with_core_mut(|core| {
                             let ui = if let Some(ui) =
                                 core.ui_mut().downcast_mut::<ui::WebUiBackend>()
@@ -781,6 +1024,58 @@ impl Ruffle {
                 .warn_on_error();
             instance.key_up_callback = Some(key_up_callback);
 
+            // Create paste event handler.
+            let paste_callback = Closure::wrap(Box::new(move |js_event: ClipboardEvent| {
+                let _ = ruffle.with_instance(|instance| {
+                    if instance.has_focus {
+                        let text = js_event
+                            .clipboard_data()
+                            .and_then(|data| data.get_data("text/plain").ok());
+                        if let Some(text) = text {
+                            let _ = instance.with_core_mut(|core| {
+                                core.handle_event(PlayerEvent::Paste { text });
+                            });
+                        }
+                        js_event.prevent_default();
+                    }
+                });
+            }) as Box<dyn FnMut(ClipboardEvent)>);
+
+            window
+                .add_event_listener_with_callback("paste", paste_callback.as_ref().unchecked_ref())
+                .warn_on_error();
+            instance.paste_callback = Some(paste_callback);
+
+            // Create input method composition handler.
+            let composition_callback = Closure::wrap(Box::new(move |js_event: CompositionEvent| {
+                let _ = ruffle.with_instance(|instance| {
+                    let text = js_event.data().unwrap_or_default();
+                    let event = match js_event.type_().as_str() {
+                        "compositionstart" => PlayerEvent::ImeStart,
+                        "compositionupdate" => PlayerEvent::ImeUpdate { text },
+                        _ => {
+                            instance.ime_input.set_value("");
+                            PlayerEvent::ImeCommit { text }
+                        }
+                    };
+                    let _ = instance.with_core_mut(|core| {
+                        core.handle_event(event);
+                    });
+                });
+            })
+                as Box<dyn FnMut(CompositionEvent)>);
+
+            for event_type in &COMPOSITION_EVENTS {
+                instance
+                    .ime_input
+                    .add_event_listener_with_callback(
+                        event_type,
+                        composition_callback.as_ref().unchecked_ref(),
+                    )
+                    .warn_on_error();
+            }
+            instance.composition_callback = Some(composition_callback);
+
             let unload_callback = Closure::wrap(Box::new(move |_| {
                 let _ = ruffle.with_core_mut(|core| {
                     core.flush_shared_objects();
@@ -930,6 +1225,7 @@ impl Ruffle {
     fn tick(&mut self, timestamp: f64) {
         let mut dt = 0.0;
         let mut new_dimensions = None;
+        let mut gamepad_events = Vec::new();
         let _ = self.with_instance_mut(|instance| {
             // Check for canvas resize.
             let canvas_width = instance.canvas.client_width();
@@ -976,6 +1272,8 @@ impl Ruffle {
 
             // Store the timestamp of the last tick.
             instance.timestamp = Some(timestamp);
+
+            gamepad_events = instance.poll_gamepads();
         });
 
         // Tick the Ruffle core.
@@ -991,6 +1289,10 @@ impl Ruffle {
                     .set_viewport_dimensions(viewport_width, viewport_height);
             }
 
+            for event in gamepad_events {
+                core.handle_event(event);
+            }
+
             core.tick(dt);
 
             // Render if the core signals a new frame, or if we resized.
@@ -1026,6 +1328,73 @@ impl Ruffle {
 }
 
 impl RuffleInstance {
+    /// Compare the gamepads with how they were when last polled, describing
+    /// what changed as player events.
+    ///
+    /// The Gamepad API has no events for input, so it has to be polled.
+    fn poll_gamepads(&mut self) -> Vec<PlayerEvent> {
+        let mut events = Vec::new();
+        let gamepads = match self.window.navigator().get_gamepads() {
+            Ok(gamepads) => gamepads,
+            Err(_) => return events,
+        };
+
+        let mut previous = std::mem::take(&mut self.gamepads);
+        for gamepad in gamepads.iter() {
+            let gamepad: Gamepad = match gamepad.dyn_into() {
+                Ok(gamepad) => gamepad,
+                Err(_) => continue,
+            };
+            if !gamepad.connected() {
+                continue;
+            }
+
+            let id = gamepad.index();
+            let axes = gamepad.axes();
+            let num_axes = axes.length() as usize;
+            let values: Vec<f64> = axes
+                .iter()
+                .map(|axis| axis.as_f64().unwrap_or_default())
+                .chain(gamepad.buttons().iter().map(|button| {
+                    button
+                        .dyn_into::<GamepadButton>()
+                        .map(|button| button.value())
+                        .unwrap_or_default()
+                }))
+                .collect();
+
+            let old_values = previous.remove(&id);
+            if old_values.is_none() {
+                events.push(PlayerEvent::GamepadConnected {
+                    id,
+                    name: gamepad.id(),
+                });
+            }
+            for (index, value) in values.iter().enumerate() {
+                if old_values.as_ref().and_then(|old| old.get(index)) != Some(value) {
+                    let control = if index < num_axes {
+                        GamepadControl::Axis(index)
+                    } else {
+                        GamepadControl::Button(index - num_axes)
+                    };
+                    events.push(PlayerEvent::GamepadInput {
+                        id,
+                        control,
+                        value: *value,
+                    });
+                }
+            }
+
+            self.gamepads.insert(id, values);
+        }
+
+        for id in previous.keys() {
+            events.push(PlayerEvent::GamepadDisconnected { id: *id });
+        }
+
+        events
+    }
+
     #[allow(dead_code)]
     fn with_core<F, O>(&self, f: F) -> Result<O, RuffleInstanceError>
     where
@@ -1138,9 +1507,21 @@ impl ExternalInterfaceProvider for JavascriptInterface {
             return Some(Box::new(method));
         }
         if let Some(window) = web_sys::window() {
-            if let Some(method) = self.find_method(window.into(), name) {
+            if let Some(method) = self.find_method(window.clone().into(), name) {
                 return Some(Box::new(method));
             }
+
+            // Like in Flash Player, the name may also be an expression that
+            // evaluates to a function, such as `function() { ... }`.
+            let function = Function::new_no_args(&format!("return ({});", name))
+                .call0(&JsValue::UNDEFINED)
+                .ok()?;
+            if function.is_function() {
+                return Some(Box::new(JavascriptMethod {
+                    this: window.into(),
+                    function,
+                }));
+            }
         }
         None
     }
@@ -1234,7 +1615,10 @@ fn create_renderer(
             .dyn_into()
             .map_err(|_| "Expected HtmlCanvasElement")?;
         match ruffle_render_webgl::WebGlRenderBackend::new(&canvas) {
-            Ok(renderer) => return Ok((canvas, Box::new(renderer))),
+            Ok(renderer) => {
+                renderer.warm_up();
+                return Ok((canvas, Box::new(renderer)));
+            }
             Err(error) => log::error!("Error creating WebGL renderer: {}", error),
         }
     }
@@ -1288,6 +1672,29 @@ pub fn set_panic_handler() {
     });
 }
 
+/// Whether a key event is part of composing text with an input method.
+fn is_composing(event: &KeyboardEvent) -> bool {
+    event.is_composing() || event.key() == "Process"
+}
+
+/// Convert a pointer event into a touch event, if it came from a touch screen.
+fn touch_event(
+    js_event: &PointerEvent,
+    phase: TouchPhase,
+    device_pixel_ratio: f64,
+) -> Option<PlayerEvent> {
+    if js_event.pointer_type() != "touch" {
+        return None;
+    }
+
+    Some(PlayerEvent::Touch {
+        id: js_event.pointer_id(),
+        phase,
+        x: f64::from(js_event.offset_x()) * device_pixel_ratio,
+        y: f64::from(js_event.offset_y()) * device_pixel_ratio,
+    })
+}
+
 fn parse_movie_parameters(input: &JsValue) -> Vec<(String, String)> {
     let mut params = Vec::new();
     if let Ok(keys) = js_sys::Reflect::own_keys(input) {
@@ -22,3 +22,28 @@ impl LogBackend for WebLogBackend {
         }
     }
 }
+
+/// Logs messages to the browser console, up to a maximum level.
+pub struct ConsoleLogger {
+    level: log::Level,
+}
+
+impl ConsoleLogger {
+    pub fn new(level: log::Level) -> Self {
+        Self { level }
+    }
+}
+
+impl log::Log for ConsoleLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            console_log::log(record);
+        }
+    }
+
+    fn flush(&self) {}
+}
@@ -2,24 +2,79 @@
 use js_sys::{Array, ArrayBuffer, Uint8Array};
 use ruffle_core::backend::navigator::{
     url_from_relative_url, NavigationMethod, NavigatorBackend, OwnedFuture, RequestOptions,
+    SocketEvent, SocketHandle,
 };
 use ruffle_core::indexmap::IndexMap;
 use ruffle_core::loader::Error;
+use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
 use std::time::Duration;
 use url::Url;
-use wasm_bindgen::JsCast;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
 use wasm_bindgen_futures::{spawn_local, JsFuture};
 use web_sys::{
-    window, Blob, BlobPropertyBag, Document, Performance, Request, RequestInit, Response,
+    window, BinaryType, Blob, BlobPropertyBag, Document, Event, Headers, MessageEvent, Performance,
+    Request, RequestInit, Response, WebSocket,
 };
 
+/// A WebSocket server that relays connections to a TCP host and port.
+///
+/// Browsers can't open TCP sockets, so `flash.net.Socket` connections are
+/// made through a proxy like this instead.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SocketProxy {
+    host: String,
+
+    port: u16,
+
+    #[serde(rename = "proxyUrl")]
+    proxy_url: String,
+}
+
+/// Socket events waiting to be polled, shared with the WebSocket callbacks.
+type SocketEvents = Rc<RefCell<Vec<(SocketHandle, SocketEvent)>>>;
+
+/// A socket connected through a WebSocket proxy.
+struct ProxiedSocket {
+    websocket: WebSocket,
+
+    /// The WebSocket callbacks, which must live as long as the socket.
+    _on_open: Closure<dyn FnMut(Event)>,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_close: Closure<dyn FnMut(Event)>,
+    _on_error: Closure<dyn FnMut(Event)>,
+    _on_timeout: Closure<dyn FnMut()>,
+
+    /// The timer that fails the connection if it takes too long.
+    timeout_id: i32,
+}
+
+impl Drop for ProxiedSocket {
+    fn drop(&mut self) {
+        self.websocket.set_onopen(None);
+        self.websocket.set_onmessage(None);
+        self.websocket.set_onclose(None);
+        self.websocket.set_onerror(None);
+        if let Some(window) = window() {
+            window.clear_timeout_with_handle(self.timeout_id);
+        }
+        let _ = self.websocket.close();
+    }
+}
+
 pub struct WebNavigatorBackend {
     performance: Performance,
     start_time: f64,
     allow_script_access: bool,
     upgrade_to_https: bool,
     base_url: Option<String>,
+    socket_proxies: Vec<SocketProxy>,
+    sockets: HashMap<SocketHandle, ProxiedSocket>,
+    socket_events: SocketEvents,
+    next_socket_handle: SocketHandle,
 }
 
 impl WebNavigatorBackend {
@@ -27,6 +82,7 @@ impl WebNavigatorBackend {
         allow_script_access: bool,
         upgrade_to_https: bool,
         mut base_url: Option<String>,
+        socket_proxies: Vec<SocketProxy>,
     ) -> Self {
         let window = web_sys::window().expect("window()");
         let performance = window.performance().expect("window.performance()");
@@ -66,6 +122,10 @@ impl WebNavigatorBackend {
             allow_script_access,
             upgrade_to_https,
             base_url,
+            socket_proxies,
+            sockets: HashMap::new(),
+            socket_events: Rc::new(RefCell::new(Vec::new())),
+            next_socket_handle: 0,
         }
     }
 
@@ -208,6 +268,16 @@ impl NavigatorBackend for WebNavigatorBackend {
                 init.body(Some(&datablob));
             }
 
+            if !options.headers().is_empty() {
+                let headers = Headers::new().unwrap();
+                for (name, value) in options.headers() {
+                    headers.append(name, value).map_err(|_| {
+                        Error::FetchError(format!("Invalid request header {}", name))
+                    })?;
+                }
+                init.headers(&headers);
+            }
+
             let request = Request::new_with_str_and_init(&url, &init)
                 .map_err(|_| Error::FetchError(format!("Unable to create request for {}", url)))?;
 
@@ -272,4 +342,177 @@ impl NavigatorBackend for WebNavigatorBackend {
         }
         url
     }
+
+    fn enforces_cors(&self) -> bool {
+        true
+    }
+
+    fn connect_socket(&mut self, host: &str, port: u16, timeout: Duration) -> SocketHandle {
+        let handle = self.next_socket_handle;
+        self.next_socket_handle = self.next_socket_handle.wrapping_add(1);
+
+        let proxy = self
+            .socket_proxies
+            .iter()
+            .find(|proxy| proxy.host == host && proxy.port == port);
+        let event = match proxy {
+            Some(proxy) => match connect_proxied_socket(
+                handle,
+                &proxy.proxy_url,
+                timeout,
+                self.socket_events.clone(),
+            ) {
+                Ok(socket) => {
+                    self.sockets.insert(handle, socket);
+                    return handle;
+                }
+                Err(e) => {
+                    log::warn!("Unable to connect to {}: {:?}", proxy.proxy_url, e);
+                    SocketEvent::IoError
+                }
+            },
+            None => {
+                log::warn!(
+                    "Can't connect to {}:{}, as no socket proxy is configured for it",
+                    host,
+                    port
+                );
+                SocketEvent::SecurityError
+            }
+        };
+
+        self.socket_events.borrow_mut().push((handle, event));
+        handle
+    }
+
+    fn send_socket(&mut self, handle: SocketHandle, data: Vec<u8>) {
+        if let Some(socket) = self.sockets.get(&handle) {
+            if let Err(e) = socket.websocket.send_with_u8_array(&data) {
+                log::warn!("Unable to send to socket: {:?}", e);
+            }
+        }
+    }
+
+    fn close_socket(&mut self, handle: SocketHandle) {
+        self.sockets.remove(&handle);
+        self.socket_events
+            .borrow_mut()
+            .retain(|(event_handle, _)| *event_handle != handle);
+    }
+
+    fn poll_sockets(&mut self) -> Vec<(SocketHandle, SocketEvent)> {
+        let events = std::mem::take(&mut *self.socket_events.borrow_mut());
+        for (handle, event) in &events {
+            if matches!(
+                event,
+                SocketEvent::Closed | SocketEvent::IoError | SocketEvent::SecurityError
+            ) {
+                self.sockets.remove(handle);
+            }
+        }
+        events
+    }
+}
+
+/// Open a WebSocket to a socket proxy, reporting what happens to it in
+/// `events`.
+fn connect_proxied_socket(
+    handle: SocketHandle,
+    proxy_url: &str,
+    timeout: Duration,
+    events: SocketEvents,
+) -> Result<ProxiedSocket, JsValue> {
+    let websocket = WebSocket::new(proxy_url)?;
+    websocket.set_binary_type(BinaryType::Arraybuffer);
+
+    // Once the connection has failed or closed, it reports nothing more.
+    let is_open = Rc::new(Cell::new(false));
+    let is_finished = Rc::new(Cell::new(false));
+
+    let on_open = {
+        let events = events.clone();
+        let is_open = is_open.clone();
+        let is_finished = is_finished.clone();
+        Closure::wrap(Box::new(move |_: Event| {
+            if !is_finished.get() {
+                is_open.set(true);
+                events.borrow_mut().push((handle, SocketEvent::Connected));
+            }
+        }) as Box<dyn FnMut(Event)>)
+    };
+
+    let on_message = {
+        let events = events.clone();
+        let is_finished = is_finished.clone();
+        Closure::wrap(Box::new(move |event: MessageEvent| {
+            if is_finished.get() {
+                return;
+            }
+            let data = event.data();
+            let data = if let Some(buffer) = data.dyn_ref::<ArrayBuffer>() {
+                Uint8Array::new(buffer).to_vec()
+            } else if let Some(text) = data.as_string() {
+                text.into_bytes()
+            } else {
+                return;
+            };
+            events.borrow_mut().push((handle, SocketEvent::Data(data)));
+        }) as Box<dyn FnMut(MessageEvent)>)
+    };
+
+    let finish = {
+        let events = events.clone();
+        let is_finished = is_finished.clone();
+        move |event: SocketEvent| {
+            if !is_finished.replace(true) {
+                events.borrow_mut().push((handle, event));
+            }
+        }
+    };
+
+    let on_close = {
+        let finish = finish.clone();
+        Closure::wrap(Box::new(move |_: Event| finish(SocketEvent::Closed)) as Box<dyn FnMut(Event)>)
+    };
+
+    let on_error = {
+        let finish = finish.clone();
+        Closure::wrap(
+            Box::new(move |_: Event| finish(SocketEvent::IoError)) as Box<dyn FnMut(Event)>
+        )
+    };
+
+    // As in Flash Player, a connection that takes too long is reported as a
+    // security error.
+    let on_timeout = {
+        let websocket = websocket.clone();
+        Closure::wrap(Box::new(move || {
+            if !is_open.get() {
+                finish(SocketEvent::SecurityError);
+                let _ = websocket.close();
+            }
+        }) as Box<dyn FnMut()>)
+    };
+
+    websocket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+    websocket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    websocket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+    websocket.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+    let timeout_id = window()
+        .ok_or("Expected window")?
+        .set_timeout_with_callback_and_timeout_and_arguments_0(
+            on_timeout.as_ref().unchecked_ref(),
+            timeout.as_millis() as i32,
+        )?;
+
+    Ok(ProxiedSocket {
+        websocket,
+        _on_open: on_open,
+        _on_message: on_message,
+        _on_close: on_close,
+        _on_error: on_error,
+        _on_timeout: on_timeout,
+        timeout_id,
+    })
 }
@@ -1,6 +1,10 @@
 use ruffle_core::backend::storage::StorageBackend;
 use web_sys::Storage;
 
+/// The number of characters most browsers allow an origin to keep in
+/// `localStorage`.
+const LOCAL_STORAGE_QUOTA: usize = 5 * 1024 * 1024;
+
 pub struct LocalStorageBackend {
     storage: Storage,
 }
@@ -29,4 +33,10 @@ impl StorageBackend for LocalStorageBackend {
     fn remove_key(&mut self, name: &str) {
         let _ = self.storage.delete(name);
     }
+
+    fn max_size(&self) -> usize {
+        // Data is stored as base64, which takes four characters for every
+        // three bytes.
+        LOCAL_STORAGE_QUOTA / 4 * 3
+    }
 }
@@ -1,32 +1,49 @@
 use super::JavascriptPlayer;
-use ruffle_core::backend::ui::{MouseCursor, UiBackend};
-use ruffle_core::events::KeyCode;
+use ruffle_core::backend::ui::{CustomMouseCursor, MouseCursor, UiBackend};
+use ruffle_core::events::{KeyCode, KeyLocation};
 use ruffle_web_common::JsResult;
 use std::collections::HashSet;
-use web_sys::{HtmlCanvasElement, KeyboardEvent};
+use wasm_bindgen::{Clamped, JsCast, JsValue};
+use web_sys::{
+    CanvasRenderingContext2d, HtmlCanvasElement, HtmlTextAreaElement, ImageData, KeyboardEvent,
+};
 
 /// An implementation of `UiBackend` utilizing `web_sys` bindings to input
 /// APIs.
 pub struct WebUiBackend {
     js_player: JavascriptPlayer,
     canvas: HtmlCanvasElement,
+
+    /// The hidden element that input methods compose text into.
+    ime_input: HtmlTextAreaElement,
     keys_down: HashSet<String>,
     cursor_visible: bool,
     cursor: MouseCursor,
+
+    /// The CSS value for a custom cursor chosen by the movie.
+    custom_cursor: Option<String>,
     last_key: KeyCode,
     last_char: Option<char>,
+    last_location: KeyLocation,
 }
 
 impl WebUiBackend {
-    pub fn new(js_player: JavascriptPlayer, canvas: &HtmlCanvasElement) -> Self {
+    pub fn new(
+        js_player: JavascriptPlayer,
+        canvas: &HtmlCanvasElement,
+        ime_input: &HtmlTextAreaElement,
+    ) -> Self {
         Self {
             js_player,
             canvas: canvas.clone(),
+            ime_input: ime_input.clone(),
             keys_down: HashSet::new(),
             cursor_visible: true,
             cursor: MouseCursor::Arrow,
+            custom_cursor: None,
             last_key: KeyCode::Unknown,
             last_char: None,
+            last_location: KeyLocation::Standard,
         }
     }
 
@@ -36,6 +53,7 @@ impl WebUiBackend {
         self.last_key = web_to_ruffle_key_code(&code).unwrap_or(KeyCode::Unknown);
         self.keys_down.insert(code);
         self.last_char = web_key_to_codepoint(&event.key());
+        self.last_location = web_to_ruffle_key_location(event.location());
     }
 
     /// Register a key release for a given code string.
@@ -44,132 +62,66 @@ impl WebUiBackend {
         self.last_key = web_to_ruffle_key_code(&code).unwrap_or(KeyCode::Unknown);
         self.keys_down.remove(&code);
         self.last_char = web_key_to_codepoint(&event.key());
+        self.last_location = web_to_ruffle_key_location(event.location());
     }
 
     fn update_mouse_cursor(&self) {
-        let cursor = if self.cursor_visible {
+        let cursor = if !self.cursor_visible {
+            "none"
+        } else if let Some(custom_cursor) = &self.custom_cursor {
+            custom_cursor
+        } else {
             match self.cursor {
                 MouseCursor::Arrow => "auto",
                 MouseCursor::Hand => "pointer",
                 MouseCursor::IBeam => "text",
                 MouseCursor::Grab => "grab",
             }
-        } else {
-            "none"
         };
         self.canvas
             .style()
             .set_property("cursor", cursor)
             .warn_on_error();
     }
+
+    /// Encode a custom cursor as a CSS `cursor` value.
+    fn custom_cursor_css(cursor: &CustomMouseCursor) -> Result<String, JsValue> {
+        let document = web_sys::window()
+            .and_then(|window| window.document())
+            .ok_or_else(|| JsValue::from_str("No document"))?;
+        let canvas: HtmlCanvasElement = document.create_element("canvas")?.unchecked_into();
+        canvas.set_width(cursor.width);
+        canvas.set_height(cursor.height);
+        let context: CanvasRenderingContext2d = canvas
+            .get_context("2d")?
+            .ok_or_else(|| JsValue::from_str("No 2d context"))?
+            .unchecked_into();
+
+        let rgba = cursor.unmultiplied_rgba();
+        let image_data = ImageData::new_with_u8_clamped_array_and_sh(
+            Clamped(&rgba[..]),
+            cursor.width,
+            cursor.height,
+        )?;
+        context.put_image_data(&image_data, 0.0, 0.0)?;
+
+        let (hot_x, hot_y) = cursor.hot_spot;
+        Ok(format!(
+            "url({}) {} {}, auto",
+            canvas.to_data_url()?,
+            hot_x,
+            hot_y
+        ))
+    }
 }
 
 impl UiBackend for WebUiBackend {
     fn is_key_down(&self, key: KeyCode) -> bool {
-        match key {
-            KeyCode::Unknown => false,
-            KeyCode::Backspace => self.keys_down.contains("Backspace"),
-            KeyCode::Tab => self.keys_down.contains("Tab"),
-            KeyCode::Return => self.keys_down.contains("Enter"),
-            KeyCode::Shift => {
-                self.keys_down.contains("ShiftLeft") || self.keys_down.contains("ShiftRight")
-            }
-            KeyCode::Control => {
-                self.keys_down.contains("ControlLeft") || self.keys_down.contains("ControlRight")
-            }
-            KeyCode::Alt => {
-                self.keys_down.contains("AltLeft") || self.keys_down.contains("AltRight")
-            }
-            KeyCode::CapsLock => self.keys_down.contains("CapsLock"),
-            KeyCode::Escape => self.keys_down.contains("Escape"),
-            KeyCode::Space => self.keys_down.contains("Space"),
-            KeyCode::Key0 => self.keys_down.contains("Digit0"),
-            KeyCode::Key1 => self.keys_down.contains("Digit1"),
-            KeyCode::Key2 => self.keys_down.contains("Digit2"),
-            KeyCode::Key3 => self.keys_down.contains("Digit3"),
-            KeyCode::Key4 => self.keys_down.contains("Digit4"),
-            KeyCode::Key5 => self.keys_down.contains("Digit5"),
-            KeyCode::Key6 => self.keys_down.contains("Digit6"),
-            KeyCode::Key7 => self.keys_down.contains("Digit7"),
-            KeyCode::Key8 => self.keys_down.contains("Digit8"),
-            KeyCode::Key9 => self.keys_down.contains("Digit9"),
-            KeyCode::A => self.keys_down.contains("KeyA"),
-            KeyCode::B => self.keys_down.contains("KeyB"),
-            KeyCode::C => self.keys_down.contains("KeyC"),
-            KeyCode::D => self.keys_down.contains("KeyD"),
-            KeyCode::E => self.keys_down.contains("KeyE"),
-            KeyCode::F => self.keys_down.contains("KeyF"),
-            KeyCode::G => self.keys_down.contains("KeyG"),
-            KeyCode::H => self.keys_down.contains("KeyH"),
-            KeyCode::I => self.keys_down.contains("KeyI"),
-            KeyCode::J => self.keys_down.contains("KeyJ"),
-            KeyCode::K => self.keys_down.contains("KeyK"),
-            KeyCode::L => self.keys_down.contains("KeyL"),
-            KeyCode::M => self.keys_down.contains("KeyM"),
-            KeyCode::N => self.keys_down.contains("KeyN"),
-            KeyCode::O => self.keys_down.contains("KeyO"),
-            KeyCode::P => self.keys_down.contains("KeyP"),
-            KeyCode::Q => self.keys_down.contains("KeyQ"),
-            KeyCode::R => self.keys_down.contains("KeyR"),
-            KeyCode::S => self.keys_down.contains("KeyS"),
-            KeyCode::T => self.keys_down.contains("KeyT"),
-            KeyCode::U => self.keys_down.contains("KeyU"),
-            KeyCode::V => self.keys_down.contains("KeyV"),
-            KeyCode::W => self.keys_down.contains("KeyW"),
-            KeyCode::X => self.keys_down.contains("KeyX"),
-            KeyCode::Y => self.keys_down.contains("KeyY"),
-            KeyCode::Z => self.keys_down.contains("KeyZ"),
-            KeyCode::Semicolon => self.keys_down.contains("Semicolon"),
-            KeyCode::Equals => self.keys_down.contains("Equal"),
-            KeyCode::Comma => self.keys_down.contains("Comma"),
-            KeyCode::Minus => self.keys_down.contains("Minus"),
-            KeyCode::Period => self.keys_down.contains("Period"),
-            KeyCode::Slash => self.keys_down.contains("Slash"),
-            KeyCode::Grave => self.keys_down.contains("Backquote"),
-            KeyCode::LBracket => self.keys_down.contains("BracketLeft"),
-            KeyCode::Backslash => self.keys_down.contains("Backslash"),
-            KeyCode::RBracket => self.keys_down.contains("BracketRight"),
-            KeyCode::Apostrophe => self.keys_down.contains("Quote"),
-            KeyCode::Numpad0 => self.keys_down.contains("Numpad0"),
-            KeyCode::Numpad1 => self.keys_down.contains("Numpad1"),
-            KeyCode::Numpad2 => self.keys_down.contains("Numpad2"),
-            KeyCode::Numpad3 => self.keys_down.contains("Numpad3"),
-            KeyCode::Numpad4 => self.keys_down.contains("Numpad4"),
-            KeyCode::Numpad5 => self.keys_down.contains("Numpad5"),
-            KeyCode::Numpad6 => self.keys_down.contains("Numpad6"),
-            KeyCode::Numpad7 => self.keys_down.contains("Numpad7"),
-            KeyCode::Numpad8 => self.keys_down.contains("Numpad8"),
-            KeyCode::Numpad9 => self.keys_down.contains("Numpad9"),
-            KeyCode::Multiply => self.keys_down.contains("NumpadMultiply"),
-            KeyCode::Plus => self.keys_down.contains("NumpadAdd"),
-            KeyCode::NumpadMinus => self.keys_down.contains("NumpadSubtract"),
-            KeyCode::NumpadPeriod => self.keys_down.contains("NumpadDecimal"),
-            KeyCode::NumpadSlash => self.keys_down.contains("NumpadDivide"),
-            KeyCode::PgUp => self.keys_down.contains("PageUp"),
-            KeyCode::PgDown => self.keys_down.contains("PageDown"),
-            KeyCode::End => self.keys_down.contains("End"),
-            KeyCode::Home => self.keys_down.contains("Home"),
-            KeyCode::Left => self.keys_down.contains("ArrowLeft"),
-            KeyCode::Up => self.keys_down.contains("ArrowUp"),
-            KeyCode::Right => self.keys_down.contains("ArrowRight"),
-            KeyCode::Down => self.keys_down.contains("ArrowDown"),
-            KeyCode::Insert => self.keys_down.contains("Insert"),
-            KeyCode::Delete => self.keys_down.contains("Delete"),
-            KeyCode::Pause => self.keys_down.contains("Pause"),
-            KeyCode::ScrollLock => self.keys_down.contains("ScrollLock"),
-            KeyCode::F1 => self.keys_down.contains("F1"),
-            KeyCode::F2 => self.keys_down.contains("F2"),
-            KeyCode::F3 => self.keys_down.contains("F3"),
-            KeyCode::F4 => self.keys_down.contains("F4"),
-            KeyCode::F5 => self.keys_down.contains("F5"),
-            KeyCode::F6 => self.keys_down.contains("F6"),
-            KeyCode::F7 => self.keys_down.contains("F7"),
-            KeyCode::F8 => self.keys_down.contains("F8"),
-            KeyCode::F9 => self.keys_down.contains("F9"),
-            KeyCode::F10 => self.keys_down.contains("F10"),
-            KeyCode::F11 => self.keys_down.contains("F11"),
-            KeyCode::F12 => self.keys_down.contains("F12"),
-        }
+        key != KeyCode::Unknown
+            && self
+                .keys_down
+                .iter()
+                .any(|code| web_to_ruffle_key_code(code) == Some(key))
     }
 
     fn last_key_code(&self) -> KeyCode {
@@ -180,6 +132,10 @@ impl UiBackend for WebUiBackend {
         self.last_char
     }
 
+    fn last_key_location(&self) -> KeyLocation {
+        self.last_location
+    }
+
     fn mouse_visible(&self) -> bool {
         self.cursor_visible
     }
@@ -194,14 +150,59 @@ impl UiBackend for WebUiBackend {
         self.update_mouse_cursor();
     }
 
-    fn set_clipboard_content(&mut self, _content: String) {
-        log::warn!("set clipboard not implemented");
+    fn set_custom_mouse_cursor(&mut self, cursor: Option<&CustomMouseCursor>) -> bool {
+        self.custom_cursor = match cursor.map(Self::custom_cursor_css) {
+            Some(Ok(css)) => Some(css),
+            Some(Err(e)) => {
+                log::warn!("Unable to show custom mouse cursor: {:?}", e);
+                None
+            }
+            None => None,
+        };
+        self.update_mouse_cursor();
+        cursor.is_none() || self.custom_cursor.is_some()
+    }
+
+    fn set_clipboard_content(&mut self, content: String) {
+        self.js_player.set_clipboard(&content);
+    }
+
+    fn clipboard_content(&mut self) -> Option<String> {
+        // Browsers only hand out the clipboard asynchronously, or to paste events.
+        None
     }
 
     fn is_fullscreen(&self) -> bool {
         self.js_player.is_fullscreen()
     }
 
+    fn set_fullscreen(&mut self, is_full: bool) {
+        // Browsers only allow this in response to user input, and it happens asynchronously.
+        if is_full {
+            self.js_player.enter_fullscreen();
+        } else {
+            self.js_player.exit_fullscreen();
+        }
+    }
+
+    fn set_ime_position(&mut self, position: Option<(f64, f64)>) {
+        match position {
+            Some((x, y)) => {
+                // The position is in canvas pixels rather than CSS pixels.
+                let scale = web_sys::window().map_or(1.0, |window| window.device_pixel_ratio());
+                let style = self.ime_input.style();
+                style
+                    .set_property("left", &format!("{}px", x / scale))
+                    .warn_on_error();
+                style
+                    .set_property("top", &format!("{}px", y / scale))
+                    .warn_on_error();
+                self.ime_input.focus().warn_on_error();
+            }
+            None => self.ime_input.blur().warn_on_error(),
+        }
+    }
+
     fn display_unsupported_message(&self) {
         self.js_player.display_unsupported_message()
     }
@@ -221,7 +222,8 @@ pub fn web_to_ruffle_key_code(key_code: &str) -> Option<KeyCode> {
     Some(match key_code {
         "Backspace" => KeyCode::Backspace,
         "Tab" => KeyCode::Tab,
-        "Enter" => KeyCode::Return,
+        "Enter" | "NumpadEnter" => KeyCode::Return,
+        "MetaLeft" | "MetaRight" | "OSLeft" | "OSRight" => KeyCode::Command,
         "ShiftLeft" | "ShiftRight" => KeyCode::Shift,
         "ControlLeft" | "ControlRight" => KeyCode::Control,
         "AltLeft" | "AltRight" => KeyCode::Alt,
@@ -290,6 +292,7 @@ pub fn web_to_ruffle_key_code(key_code: &str) -> Option<KeyCode> {
         "NumpadSubtract" => KeyCode::NumpadMinus,
         "NumpadDecimal" => KeyCode::NumpadPeriod,
         "NumpadDivide" => KeyCode::NumpadSlash,
+        "NumLock" => KeyCode::NumLock,
         "PageUp" => KeyCode::PgUp,
         "PageDown" => KeyCode::PgDown,
         "End" => KeyCode::End,
@@ -314,10 +317,23 @@ pub fn web_to_ruffle_key_code(key_code: &str) -> Option<KeyCode> {
         "F10" => KeyCode::F10,
         "F11" => KeyCode::F11,
         "F12" => KeyCode::F12,
+        "F13" => KeyCode::F13,
+        "F14" => KeyCode::F14,
+        "F15" => KeyCode::F15,
         _ => return None,
     })
 }
 
+/// Convert a web `KeyboardEvent.location` value into a Ruffle `KeyLocation`.
+pub fn web_to_ruffle_key_location(location: u32) -> KeyLocation {
+    match location {
+        KeyboardEvent::DOM_KEY_LOCATION_LEFT => KeyLocation::Left,
+        KeyboardEvent::DOM_KEY_LOCATION_RIGHT => KeyLocation::Right,
+        KeyboardEvent::DOM_KEY_LOCATION_NUMPAD => KeyLocation::NumPad,
+        _ => KeyLocation::Standard,
+    }
+}
+
 /// Convert a web `KeyboardEvent.key` value into a character codepoint.
 /// Return `None` if they input was not a printable character.
 pub fn web_key_to_codepoint(key: &str) -> Option<char> {
@@ -334,6 +350,9 @@ pub fn web_key_to_codepoint(key: &str) -> Option<char> {
         // Check for special characters.
         match key {
             "Backspace" => Some(8 as char),
+            "Tab" => Some(9 as char),
+            "Enter" => Some(13 as char),
+            "Escape" => Some(27 as char),
             "Delete" => Some(127 as char),
             _ => None,
         }